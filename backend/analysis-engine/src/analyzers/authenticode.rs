@@ -0,0 +1,1272 @@
+use std::collections::HashSet;
+use std::ops::Range;
+use std::path::Path;
+
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use goblin::pe::PE;
+use ring::signature as ring_signature;
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use sha2::{Sha256, Sha384, Sha512};
+use shared::types::CertificateInfo;
+use tracing::{debug, warn};
+
+/// WIN_CERTIFICATE type for PKCS#7 SignedData (the only type Authenticode uses)
+const WIN_CERT_TYPE_PKCS_SIGNED_DATA: u16 = 0x0002;
+
+// DER tags used while walking PKCS#7 / X.509 structures
+const TAG_INTEGER: u8 = 0x02;
+const TAG_BIT_STRING: u8 = 0x03;
+const TAG_OCTET_STRING: u8 = 0x04;
+const TAG_OID: u8 = 0x06;
+const TAG_UTC_TIME: u8 = 0x17;
+const TAG_GENERALIZED_TIME: u8 = 0x18;
+const TAG_SEQUENCE: u8 = 0x30;
+const TAG_SET: u8 = 0x31;
+const TAG_CONTEXT_0: u8 = 0xA0;
+const TAG_CONTEXT_1: u8 = 0xA1;
+
+/// 1.2.840.113549.1.7.2 (pkcs7-signedData)
+const OID_SIGNED_DATA: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x07, 0x02];
+/// 1.3.6.1.4.1.311.2.1.4 (SPC_INDIRECT_DATA_OBJID)
+const OID_SPC_INDIRECT_DATA: &[u8] = &[0x2B, 0x06, 0x01, 0x04, 0x01, 0x82, 0x37, 0x02, 0x01, 0x04];
+/// 1.2.840.113549.1.9.4 (pkcs9-messageDigest)
+const OID_MESSAGE_DIGEST: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x09, 0x04];
+
+// Public key algorithms
+const OID_RSA_ENCRYPTION: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x01];
+const OID_EC_PUBLIC_KEY: &[u8] = &[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x02, 0x01];
+const OID_P256: &[u8] = &[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x03, 0x01, 0x07];
+const OID_P384: &[u8] = &[0x2B, 0x81, 0x04, 0x00, 0x22];
+
+// Digest algorithms, bare and combined with RSA or ECDSA
+const OID_SHA1: &[u8] = &[0x2B, 0x0E, 0x03, 0x02, 0x1A];
+const OID_SHA256: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01];
+const OID_SHA384: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x02];
+const OID_SHA512: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x03];
+const OID_SHA1_RSA: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x05];
+const OID_SHA256_RSA: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x0B];
+const OID_SHA384_RSA: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x0C];
+const OID_SHA512_RSA: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x0D];
+const OID_ECDSA_SHA256: &[u8] = &[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x04, 0x03, 0x02];
+const OID_ECDSA_SHA384: &[u8] = &[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x04, 0x03, 0x03];
+const OID_ECDSA_SHA512: &[u8] = &[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x04, 0x03, 0x04];
+
+/// Problems found while validating an Authenticode signature
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SignatureIssue {
+    /// Certificate table present but could not be parsed
+    Malformed,
+    /// A certificate in the chain is past its `not_after` date
+    Expired,
+    /// A certificate in the chain is not yet valid
+    NotYetValid,
+    /// The signing certificate is its own issuer
+    SelfSigned,
+    /// The chain is missing an intermediate between signer and root
+    BrokenChain,
+    /// The chain does not terminate at a trusted root
+    UntrustedRoot,
+    /// A certificate appears on the configured revocation lists or on a CRL
+    /// embedded in the signature
+    Revoked,
+    /// A certificate is known to have been leaked or abused by malware
+    KnownAbused,
+    /// A certificate or the signer's signature does not verify with the issuer's key
+    BadSignature,
+    /// The signed image digest does not match the file
+    DigestMismatch,
+}
+
+impl SignatureIssue {
+    /// Threat score contribution for this issue
+    pub fn weight(&self) -> f64 {
+        match self {
+            SignatureIssue::KnownAbused => 0.50,
+            SignatureIssue::DigestMismatch => 0.40,
+            SignatureIssue::Revoked => 0.40,
+            SignatureIssue::BadSignature => 0.30,
+            SignatureIssue::Malformed => 0.20,
+            SignatureIssue::SelfSigned => 0.15,
+            SignatureIssue::BrokenChain => 0.15,
+            SignatureIssue::UntrustedRoot => 0.10,
+            SignatureIssue::Expired => 0.10,
+            SignatureIssue::NotYetValid => 0.10,
+        }
+    }
+}
+
+/// Result of Authenticode signature validation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthenticodeAnalysis {
+    pub is_signed: bool,
+    pub chain_valid: bool,
+    pub signer: Option<String>,
+    pub certificates: Vec<CertificateInfo>,
+    pub issues: Vec<SignatureIssue>,
+}
+
+impl AuthenticodeAnalysis {
+    fn unsigned() -> Self {
+        Self {
+            is_signed: false,
+            chain_valid: false,
+            signer: None,
+            certificates: Vec::new(),
+            issues: Vec::new(),
+        }
+    }
+}
+
+/// Configuration for Authenticode validation
+#[derive(Debug, Clone)]
+pub struct AuthenticodeConfig {
+    /// DER certificates of the root CAs accepted as chain anchors
+    pub trusted_roots: Vec<Vec<u8>>,
+    /// Serial numbers (lowercase hex) from the local CRL snapshot
+    pub revoked_serials: Vec<String>,
+    /// SHA-1 thumbprints (lowercase hex) of revoked certificates
+    pub revoked_thumbprints: Vec<String>,
+    /// Serial numbers (lowercase hex) of leaked or abused code-signing certificates
+    pub abused_serials: Vec<String>,
+    /// Check the chain against the revocation lists above and the CRLs
+    /// embedded in the signature. Revocation is list-based: no OCSP
+    /// responder or CRL distribution point is contacted.
+    pub check_revocation: bool,
+}
+
+impl Default for AuthenticodeConfig {
+    fn default() -> Self {
+        Self {
+            trusted_roots: Vec::new(),
+            revoked_serials: Vec::new(),
+            revoked_thumbprints: Vec::new(),
+            abused_serials: vec![
+                // NVIDIA code-signing certificates leaked in 2022
+                "43bb437d609866286dd839e1d00309f5".to_string(),
+                "14781bc862e8dc503a559346f5dcc518".to_string(),
+            ],
+            check_revocation: true,
+        }
+    }
+}
+
+impl AuthenticodeConfig {
+    /// Replace the trust anchors with the certificates in a PEM bundle,
+    /// returning how many were read
+    pub fn load_trust_store(&mut self, path: &Path) -> std::io::Result<usize> {
+        let pem = std::fs::read_to_string(path)?;
+        self.trusted_roots = pem_certificates(&pem);
+        Ok(self.trusted_roots.len())
+    }
+}
+
+/// DER bodies of the CERTIFICATE blocks in a PEM bundle
+fn pem_certificates(pem: &str) -> Vec<Vec<u8>> {
+    use base64::Engine;
+
+    let mut certs = Vec::new();
+    let mut body: Option<String> = None;
+    for line in pem.lines().map(str::trim) {
+        match line {
+            "-----BEGIN CERTIFICATE-----" => body = Some(String::new()),
+            "-----END CERTIFICATE-----" => {
+                if let Some(encoded) = body.take() {
+                    match base64::engine::general_purpose::STANDARD.decode(encoded) {
+                        Ok(der) => certs.push(der),
+                        Err(e) => warn!("Skipping undecodable trust store certificate: {}", e),
+                    }
+                }
+            }
+            _ => {
+                if let Some(encoded) = body.as_mut() {
+                    encoded.push_str(line);
+                }
+            }
+        }
+    }
+    certs
+}
+
+/// Digest algorithms Authenticode signatures use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DigestAlgorithm {
+    Sha1,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl DigestAlgorithm {
+    /// Accepts both bare digest OIDs and the RSA / ECDSA signature OIDs
+    fn from_oid(oid: &[u8]) -> Option<Self> {
+        match oid {
+            OID_SHA1 | OID_SHA1_RSA => Some(Self::Sha1),
+            OID_SHA256 | OID_SHA256_RSA | OID_ECDSA_SHA256 => Some(Self::Sha256),
+            OID_SHA384 | OID_SHA384_RSA | OID_ECDSA_SHA384 => Some(Self::Sha384),
+            OID_SHA512 | OID_SHA512_RSA | OID_ECDSA_SHA512 => Some(Self::Sha512),
+            _ => None,
+        }
+    }
+
+    /// Hash the concatenation of `parts`
+    fn digest(&self, parts: &[&[u8]]) -> Vec<u8> {
+        fn run<D: Digest>(parts: &[&[u8]]) -> Vec<u8> {
+            let mut hasher = D::new();
+            for part in parts {
+                hasher.update(part);
+            }
+            hasher.finalize().to_vec()
+        }
+
+        match self {
+            Self::Sha1 => run::<Sha1>(parts),
+            Self::Sha256 => run::<Sha256>(parts),
+            Self::Sha384 => run::<Sha384>(parts),
+            Self::Sha512 => run::<Sha512>(parts),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyKind {
+    Rsa,
+    EcP256,
+    EcP384,
+}
+
+/// SubjectPublicKeyInfo reduced to what ring needs
+#[derive(Debug, Clone)]
+struct PublicKey {
+    kind: KeyKind,
+    /// RSAPublicKey DER or the uncompressed EC point
+    key: Vec<u8>,
+}
+
+impl PublicKey {
+    fn verify(&self, digest: DigestAlgorithm, message: &[u8], signature: &[u8]) -> bool {
+        let algorithm: &'static dyn ring_signature::VerificationAlgorithm = match (self.kind, digest) {
+            (KeyKind::Rsa, DigestAlgorithm::Sha1) => &ring_signature::RSA_PKCS1_2048_8192_SHA1_FOR_LEGACY_USE_ONLY,
+            (KeyKind::Rsa, DigestAlgorithm::Sha256) => &ring_signature::RSA_PKCS1_2048_8192_SHA256,
+            (KeyKind::Rsa, DigestAlgorithm::Sha384) => &ring_signature::RSA_PKCS1_2048_8192_SHA384,
+            (KeyKind::Rsa, DigestAlgorithm::Sha512) => &ring_signature::RSA_PKCS1_2048_8192_SHA512,
+            (KeyKind::EcP256, DigestAlgorithm::Sha256) => &ring_signature::ECDSA_P256_SHA256_ASN1,
+            (KeyKind::EcP256, DigestAlgorithm::Sha384) => &ring_signature::ECDSA_P256_SHA384_ASN1,
+            (KeyKind::EcP384, DigestAlgorithm::Sha256) => &ring_signature::ECDSA_P384_SHA256_ASN1,
+            (KeyKind::EcP384, DigestAlgorithm::Sha384) => &ring_signature::ECDSA_P384_SHA384_ASN1,
+            _ => return false,
+        };
+        ring_signature::UnparsedPublicKey::new(algorithm, &self.key)
+            .verify(message, signature)
+            .is_ok()
+    }
+}
+
+/// Parsed X.509 certificate fields relevant to chain validation
+#[derive(Debug, Clone)]
+struct ParsedCertificate {
+    subject: String,
+    issuer: String,
+    serial_number: String,
+    not_before: DateTime<Utc>,
+    not_after: DateTime<Utc>,
+    thumbprint: String,
+    /// Issuer Name DER and serial INTEGER contents, as a SignerInfo names them
+    issuer_der: Vec<u8>,
+    serial_der: Vec<u8>,
+    /// DER TBSCertificate, the bytes the issuer signed
+    tbs: Vec<u8>,
+    signature_digest: Option<DigestAlgorithm>,
+    signature: Vec<u8>,
+    public_key: Option<PublicKey>,
+}
+
+impl ParsedCertificate {
+    fn is_self_signed(&self) -> bool {
+        self.subject == self.issuer
+    }
+
+    /// Whether this certificate's key verifies `cert`'s signature
+    fn issued(&self, cert: &ParsedCertificate) -> bool {
+        match (&self.public_key, cert.signature_digest) {
+            (Some(key), Some(digest)) => key.verify(digest, &cert.tbs, &cert.signature),
+            _ => false,
+        }
+    }
+
+    /// Whether this certificate's key verifies `crl`'s signature
+    fn signed_list(&self, crl: &RevocationList) -> bool {
+        match (&self.public_key, crl.signature_digest) {
+            (Some(key), Some(digest)) => key.verify(digest, &crl.tbs, &crl.signature),
+            _ => false,
+        }
+    }
+}
+
+/// A CRL embedded in the SignedData
+#[derive(Debug, Clone)]
+struct RevocationList {
+    issuer: String,
+    /// DER TBSCertList, the bytes the issuer signed
+    tbs: Vec<u8>,
+    signature_digest: Option<DigestAlgorithm>,
+    signature: Vec<u8>,
+    /// Serial numbers (lowercase hex) of the certificates it revokes
+    revoked_serials: Vec<String>,
+}
+
+/// The SignerInfo of an Authenticode SignedData
+#[derive(Debug, Clone)]
+struct SignerInfo {
+    issuer_der: Vec<u8>,
+    serial_der: Vec<u8>,
+    digest: DigestAlgorithm,
+    /// Authenticated attributes re-tagged as a SET OF, the bytes actually signed
+    signed_attributes: Option<Vec<u8>>,
+    /// The messageDigest attribute
+    message_digest: Option<Vec<u8>>,
+    signature: Vec<u8>,
+}
+
+/// The parts of a PKCS#7 SignedData needed to validate an Authenticode signature
+#[derive(Debug, Clone)]
+struct SignedData {
+    certificates: Vec<ParsedCertificate>,
+    /// SpcIndirectDataContent contents, covered by the messageDigest attribute
+    indirect_data: Vec<u8>,
+    /// Algorithm and digest of the PE image the publisher signed
+    image_digest: (DigestAlgorithm, Vec<u8>),
+    signer: SignerInfo,
+    crls: Vec<RevocationList>,
+}
+
+/// Validates Authenticode signatures embedded in PE files
+pub struct AuthenticodeVerifier {
+    config: AuthenticodeConfig,
+    roots: Vec<ParsedCertificate>,
+}
+
+impl AuthenticodeVerifier {
+    pub fn new(config: AuthenticodeConfig) -> Self {
+        let roots = config.trusted_roots.iter()
+            .filter_map(|der| {
+                let parsed = der::expect(der, TAG_SEQUENCE).and_then(|(cert, _)| Self::parse_certificate(&cert));
+                if parsed.is_none() {
+                    warn!("Skipping unparseable Authenticode trust anchor");
+                }
+                parsed
+            })
+            .collect();
+        Self { config, roots }
+    }
+
+    /// Locate the PE certificate table and validate every embedded signature
+    pub fn verify(&self, data: &[u8], pe: &PE) -> AuthenticodeAnalysis {
+        let table = match pe.header.optional_header
+            .and_then(|oh| *oh.data_directories.get_certificate_table())
+        {
+            Some(dir) if dir.size > 0 => dir,
+            _ => return AuthenticodeAnalysis::unsigned(),
+        };
+
+        // The certificate table address is a file offset, not an RVA
+        let start = table.virtual_address as usize;
+        let end = start.saturating_add(table.size as usize);
+        if end > data.len() {
+            warn!("Certificate table extends past end of file");
+            return self.malformed();
+        }
+
+        let mut signatures = Vec::new();
+        for blob in Self::pkcs7_blobs(&data[start..end]) {
+            match Self::parse_signed_data(blob) {
+                Some(signed) => signatures.push(signed),
+                None => return self.malformed(),
+            }
+        }
+
+        let Some(primary) = signatures.first() else {
+            return self.malformed();
+        };
+        let certs: Vec<ParsedCertificate> = signatures.iter()
+            .flat_map(|signed| signed.certificates.iter().cloned())
+            .collect();
+        if certs.is_empty() {
+            return self.malformed();
+        }
+
+        let signer = certs.iter().position(|c| {
+            c.issuer_der == primary.signer.issuer_der && c.serial_der == primary.signer.serial_der
+        });
+        let integrity = Self::integrity_issues(data, start..end, primary, signer.map(|i| &certs[i]));
+        let crls: Vec<RevocationList> = signatures.iter()
+            .flat_map(|signed| signed.crls.iter().cloned())
+            .collect();
+
+        self.validate_chain(certs, signer, integrity, &crls, Utc::now())
+    }
+
+    /// Check that the signature covers this file: the signed image digest
+    /// must match the file, and the signer's key must verify the signed data
+    fn integrity_issues(
+        data: &[u8],
+        table: Range<usize>,
+        signed: &SignedData,
+        signer: Option<&ParsedCertificate>,
+    ) -> Vec<SignatureIssue> {
+        let mut issues = Vec::new();
+
+        let (algorithm, expected) = &signed.image_digest;
+        if image_hash(data, *algorithm, table).as_deref() != Some(expected.as_slice()) {
+            issues.push(SignatureIssue::DigestMismatch);
+        }
+
+        let info = &signed.signer;
+        let signature_valid = signer.and_then(|c| c.public_key.as_ref()).map_or(false, |key| {
+            match &info.signed_attributes {
+                Some(attributes) => {
+                    let content_digest = info.digest.digest(&[&signed.indirect_data]);
+                    info.message_digest.as_deref() == Some(content_digest.as_slice())
+                        && key.verify(info.digest, attributes, &info.signature)
+                }
+                None => key.verify(info.digest, &signed.indirect_data, &info.signature),
+            }
+        });
+        if !signature_valid {
+            issues.push(SignatureIssue::BadSignature);
+        }
+
+        issues
+    }
+
+    fn malformed(&self) -> AuthenticodeAnalysis {
+        AuthenticodeAnalysis {
+            is_signed: true,
+            issues: vec![SignatureIssue::Malformed],
+            ..AuthenticodeAnalysis::unsigned()
+        }
+    }
+
+    /// Split the certificate table into its WIN_CERTIFICATE PKCS#7 payloads
+    fn pkcs7_blobs(table: &[u8]) -> Vec<&[u8]> {
+        let mut blobs = Vec::new();
+        let mut offset = 0usize;
+
+        while offset + 8 <= table.len() {
+            let length = u32::from_le_bytes([table[offset], table[offset + 1], table[offset + 2], table[offset + 3]]) as usize;
+            let cert_type = u16::from_le_bytes([table[offset + 6], table[offset + 7]]);
+
+            if length < 8 || offset + length > table.len() {
+                break;
+            }
+
+            if cert_type == WIN_CERT_TYPE_PKCS_SIGNED_DATA {
+                blobs.push(&table[offset + 8..offset + length]);
+            }
+
+            // Entries are aligned on 8-byte boundaries
+            offset += (length + 7) & !7;
+        }
+
+        blobs
+    }
+
+    /// Walk a PKCS#7 ContentInfo and return its certificates, CRLs, signed
+    /// image digest and SignerInfo
+    fn parse_signed_data(blob: &[u8]) -> Option<SignedData> {
+        let (content_info, _) = der::expect(blob, TAG_SEQUENCE)?;
+        let (oid, rest) = der::expect(content_info.value, TAG_OID)?;
+        if oid.value != OID_SIGNED_DATA {
+            return None;
+        }
+
+        let (explicit, _) = der::expect(rest, TAG_CONTEXT_0)?;
+        let (signed_data, _) = der::expect(explicit.value, TAG_SEQUENCE)?;
+
+        // version, digestAlgorithms, encapContentInfo
+        let (_, rest) = der::expect(signed_data.value, TAG_INTEGER)?;
+        let (_, rest) = der::expect(rest, TAG_SET)?;
+        let (encap, rest) = der::expect(rest, TAG_SEQUENCE)?;
+
+        // SpcIndirectDataContent { data, messageDigest DigestInfo }
+        let (content_type, content) = der::expect(encap.value, TAG_OID)?;
+        if content_type.value != OID_SPC_INDIRECT_DATA {
+            return None;
+        }
+        let (content, _) = der::expect(content, TAG_CONTEXT_0)?;
+        let (indirect, _) = der::expect(content.value, TAG_SEQUENCE)?;
+        let (_, digest_info) = der::expect(indirect.value, TAG_SEQUENCE)?;
+        let (digest_info, _) = der::expect(digest_info, TAG_SEQUENCE)?;
+        let (algorithm, digest) = der::expect(digest_info.value, TAG_SEQUENCE)?;
+        let (digest, _) = der::expect(digest, TAG_OCTET_STRING)?;
+        let image_digest = (
+            DigestAlgorithm::from_oid(der::algorithm_oid(algorithm.value)?)?,
+            digest.value.to_vec(),
+        );
+
+        // certificates [0] IMPLICIT SET OF Certificate
+        let (certs, mut rest) = der::expect(rest, TAG_CONTEXT_0)?;
+        let mut certificates = Vec::new();
+        let mut remaining = certs.value;
+        while !remaining.is_empty() {
+            let (cert, cert_rest) = der::read(remaining)?;
+            if cert.tag == TAG_SEQUENCE {
+                certificates.push(Self::parse_certificate(&cert)?);
+            }
+            remaining = cert_rest;
+        }
+
+        // crls [1] IMPLICIT SET OF CertificateList, then signerInfos;
+        // Authenticode allows one signer
+        let mut crls = Vec::new();
+        if rest.first() == Some(&TAG_CONTEXT_1) {
+            let (lists, lists_rest) = der::read(rest)?;
+            let mut remaining = lists.value;
+            while !remaining.is_empty() {
+                let (list, list_rest) = der::read(remaining)?;
+                if list.tag == TAG_SEQUENCE {
+                    crls.push(Self::parse_crl(&list)?);
+                }
+                remaining = list_rest;
+            }
+            rest = lists_rest;
+        }
+        let (signer_infos, _) = der::expect(rest, TAG_SET)?;
+        let (signer_info, _) = der::expect(signer_infos.value, TAG_SEQUENCE)?;
+
+        Some(SignedData {
+            certificates,
+            indirect_data: indirect.value.to_vec(),
+            image_digest,
+            signer: Self::parse_signer_info(signer_info.value)?,
+            crls,
+        })
+    }
+
+    fn parse_crl(crl: &der::Tlv) -> Option<RevocationList> {
+        let (tbs, rest) = der::expect(crl.value, TAG_SEQUENCE)?;
+        let (signature_algorithm, rest) = der::expect(rest, TAG_SEQUENCE)?;
+        let (signature, _) = der::expect(rest, TAG_BIT_STRING)?;
+
+        // Optional version, signature algorithm, issuer, thisUpdate and
+        // optional nextUpdate
+        let mut rest = tbs.value;
+        if rest.first() == Some(&TAG_INTEGER) {
+            rest = der::read(rest)?.1;
+        }
+        let (_signature_alg, rest) = der::expect(rest, TAG_SEQUENCE)?;
+        let (issuer, rest) = der::expect(rest, TAG_SEQUENCE)?;
+        let (_this_update, mut rest) = der::read(rest)?;
+        if matches!(rest.first(), Some(&(TAG_UTC_TIME | TAG_GENERALIZED_TIME))) {
+            rest = der::read(rest)?.1;
+        }
+
+        // revokedCertificates is left out when nothing is revoked
+        let mut revoked_serials = Vec::new();
+        if let Some((entries, _)) = der::expect(rest, TAG_SEQUENCE) {
+            let mut remaining = entries.value;
+            while !remaining.is_empty() {
+                let (entry, entry_rest) = der::expect(remaining, TAG_SEQUENCE)?;
+                let (serial, _) = der::expect(entry.value, TAG_INTEGER)?;
+                revoked_serials.push(hex::encode(der::strip_leading_zeros(serial.value)));
+                remaining = entry_rest;
+            }
+        }
+
+        Some(RevocationList {
+            issuer: der::format_name(issuer.value),
+            tbs: tbs.raw.to_vec(),
+            signature_digest: der::algorithm_oid(signature_algorithm.value).and_then(DigestAlgorithm::from_oid),
+            signature: der::bit_string(&signature)?.to_vec(),
+            revoked_serials,
+        })
+    }
+
+    fn parse_signer_info(info: &[u8]) -> Option<SignerInfo> {
+        // version, sid IssuerAndSerialNumber, digestAlgorithm
+        let (_, rest) = der::expect(info, TAG_INTEGER)?;
+        let (sid, rest) = der::expect(rest, TAG_SEQUENCE)?;
+        let (issuer, sid_rest) = der::expect(sid.value, TAG_SEQUENCE)?;
+        let (serial, _) = der::expect(sid_rest, TAG_INTEGER)?;
+        let (digest_algorithm, mut rest) = der::expect(rest, TAG_SEQUENCE)?;
+        let digest = DigestAlgorithm::from_oid(der::algorithm_oid(digest_algorithm.value)?)?;
+
+        // authenticatedAttributes [0] IMPLICIT; the signature covers them
+        // encoded as a SET OF
+        let mut signed_attributes = None;
+        let mut message_digest = None;
+        if rest.first() == Some(&TAG_CONTEXT_0) {
+            let (attributes, attributes_rest) = der::read(rest)?;
+            let mut encoded = attributes.raw.to_vec();
+            encoded[0] = TAG_SET;
+            message_digest = Self::message_digest(attributes.value);
+            signed_attributes = Some(encoded);
+            rest = attributes_rest;
+        }
+
+        let (_signature_algorithm, rest) = der::expect(rest, TAG_SEQUENCE)?;
+        let (signature, _) = der::expect(rest, TAG_OCTET_STRING)?;
+
+        Some(SignerInfo {
+            issuer_der: issuer.raw.to_vec(),
+            serial_der: serial.value.to_vec(),
+            digest,
+            signed_attributes,
+            message_digest,
+            signature: signature.value.to_vec(),
+        })
+    }
+
+    fn message_digest(attributes: &[u8]) -> Option<Vec<u8>> {
+        let mut remaining = attributes;
+        while let Some((attribute, rest)) = der::expect(remaining, TAG_SEQUENCE) {
+            if let Some((oid, values)) = der::expect(attribute.value, TAG_OID) {
+                if oid.value == OID_MESSAGE_DIGEST {
+                    let (values, _) = der::expect(values, TAG_SET)?;
+                    let (digest, _) = der::expect(values.value, TAG_OCTET_STRING)?;
+                    return Some(digest.value.to_vec());
+                }
+            }
+            remaining = rest;
+        }
+        None
+    }
+
+    fn parse_certificate(cert: &der::Tlv) -> Option<ParsedCertificate> {
+        let (tbs, rest) = der::expect(cert.value, TAG_SEQUENCE)?;
+        let (signature_algorithm, rest) = der::expect(rest, TAG_SEQUENCE)?;
+        let (signature, _) = der::expect(rest, TAG_BIT_STRING)?;
+
+        // Optional explicit version
+        let mut rest = tbs.value;
+        if rest.first() == Some(&TAG_CONTEXT_0) {
+            rest = der::read(rest)?.1;
+        }
+
+        let (serial, rest) = der::expect(rest, TAG_INTEGER)?;
+        let (_signature_alg, rest) = der::expect(rest, TAG_SEQUENCE)?;
+        let (issuer, rest) = der::expect(rest, TAG_SEQUENCE)?;
+        let (validity, rest) = der::expect(rest, TAG_SEQUENCE)?;
+        let (subject, rest) = der::expect(rest, TAG_SEQUENCE)?;
+        let (public_key_info, _) = der::expect(rest, TAG_SEQUENCE)?;
+
+        let (not_before, validity_rest) = der::read(validity.value)?;
+        let (not_after, _) = der::read(validity_rest)?;
+
+        let mut hasher = Sha1::new();
+        hasher.update(cert.raw);
+
+        Some(ParsedCertificate {
+            subject: der::format_name(subject.value),
+            issuer: der::format_name(issuer.value),
+            serial_number: hex::encode(der::strip_leading_zeros(serial.value)),
+            not_before: der::parse_time(&not_before)?,
+            not_after: der::parse_time(&not_after)?,
+            thumbprint: hex::encode(hasher.finalize()),
+            issuer_der: issuer.raw.to_vec(),
+            serial_der: serial.value.to_vec(),
+            tbs: tbs.raw.to_vec(),
+            signature_digest: der::algorithm_oid(signature_algorithm.value).and_then(DigestAlgorithm::from_oid),
+            signature: der::bit_string(&signature)?.to_vec(),
+            public_key: Self::parse_public_key(public_key_info.value),
+        })
+    }
+
+    fn parse_public_key(info: &[u8]) -> Option<PublicKey> {
+        let (algorithm, rest) = der::expect(info, TAG_SEQUENCE)?;
+        let (key, _) = der::expect(rest, TAG_BIT_STRING)?;
+        let (oid, parameters) = der::expect(algorithm.value, TAG_OID)?;
+
+        let kind = match oid.value {
+            OID_RSA_ENCRYPTION => KeyKind::Rsa,
+            OID_EC_PUBLIC_KEY => match der::expect(parameters, TAG_OID)?.0.value {
+                OID_P256 => KeyKind::EcP256,
+                OID_P384 => KeyKind::EcP384,
+                _ => return None,
+            },
+            _ => return None,
+        };
+
+        Some(PublicKey { kind, key: der::bit_string(&key)?.to_vec() })
+    }
+
+    /// Order the certificates from signer to root and collect validation
+    /// issues. Each link must carry a signature its issuer's key verifies,
+    /// and the chain is only trusted when it ends at a configured root.
+    fn validate_chain(
+        &self,
+        certs: Vec<ParsedCertificate>,
+        signer: Option<usize>,
+        integrity: Vec<SignatureIssue>,
+        crls: &[RevocationList],
+        now: DateTime<Utc>,
+    ) -> AuthenticodeAnalysis {
+        let mut issues: HashSet<SignatureIssue> = integrity.into_iter().collect();
+
+        // Without a SignerInfo match, the signer is the certificate that
+        // issued nothing else in the bag
+        let signer = match signer {
+            Some(index) => certs[index].clone(),
+            None => {
+                let issuers: HashSet<&str> = certs.iter()
+                    .filter(|c| !c.is_self_signed())
+                    .map(|c| c.issuer.as_str())
+                    .collect();
+                certs.iter()
+                    .find(|c| !issuers.contains(c.subject.as_str()))
+                    .unwrap_or(&certs[0])
+                    .clone()
+            }
+        };
+
+        let mut chain = vec![signer.clone()];
+        let mut current = signer.clone();
+        while !current.is_self_signed() {
+            let mut candidates = certs.iter()
+                .filter(|c| c.subject == current.issuer && c.thumbprint != current.thumbprint)
+                .filter(|c| !chain.iter().any(|link| link.thumbprint == c.thumbprint))
+                .peekable();
+            if candidates.peek().is_none() {
+                break;
+            }
+            // A certificate that only shares the issuer's name is not its issuer
+            match candidates.find(|c| c.issued(&current)) {
+                Some(next) => {
+                    chain.push(next.clone());
+                    current = next.clone();
+                }
+                None => {
+                    issues.insert(SignatureIssue::BadSignature);
+                    break;
+                }
+            }
+        }
+
+        if signer.is_self_signed() {
+            issues.insert(SignatureIssue::SelfSigned);
+        }
+
+        // Authenticode blobs usually omit the root, so the top of the chain
+        // must either be a trusted root or be signed by one
+        let top = chain.last().unwrap_or(&signer);
+        if top.is_self_signed() && !top.issued(top) {
+            issues.insert(SignatureIssue::BadSignature);
+        }
+        let anchored = self.roots.iter().any(|root| root.thumbprint == top.thumbprint)
+            || self.roots.iter().any(|root| root.subject == top.issuer && root.issued(top));
+        if !anchored && self.roots.iter().any(|root| root.subject == top.issuer) {
+            // Names a trusted root that did not sign it
+            issues.insert(SignatureIssue::BadSignature);
+        }
+        if !anchored && !signer.is_self_signed() {
+            if top.is_self_signed() {
+                issues.insert(SignatureIssue::UntrustedRoot);
+            } else {
+                issues.insert(SignatureIssue::BrokenChain);
+            }
+        }
+
+        // An embedded CRL only counts when its issuer's key verifies it
+        let crls: Vec<&RevocationList> = crls.iter()
+            .filter(|crl| {
+                certs.iter().chain(&self.roots).any(|c| c.subject == crl.issuer && c.signed_list(crl))
+            })
+            .collect();
+        let revoked = |cert: &ParsedCertificate| {
+            self.config.check_revocation
+                && (self.config.revoked_serials.contains(&cert.serial_number)
+                    || self.config.revoked_thumbprints.contains(&cert.thumbprint)
+                    || crls.iter().any(|crl| {
+                        crl.issuer == cert.issuer && crl.revoked_serials.contains(&cert.serial_number)
+                    }))
+        };
+
+        for cert in &chain {
+            if now > cert.not_after {
+                issues.insert(SignatureIssue::Expired);
+            }
+            if now < cert.not_before {
+                issues.insert(SignatureIssue::NotYetValid);
+            }
+            if revoked(cert) {
+                issues.insert(SignatureIssue::Revoked);
+            }
+            if self.config.abused_serials.contains(&cert.serial_number) {
+                issues.insert(SignatureIssue::KnownAbused);
+            }
+        }
+
+        let mut issues: Vec<SignatureIssue> = issues.into_iter().collect();
+        issues.sort_by(|a, b| b.weight().partial_cmp(&a.weight()).unwrap_or(std::cmp::Ordering::Equal));
+        let chain_valid = issues.is_empty();
+
+        debug!("Authenticode chain of {} certificates, signer={}, issues={:?}",
+               chain.len(), signer.subject, issues);
+
+        let certificates = chain.iter()
+            .map(|c| {
+                let cert_valid = now >= c.not_before && now <= c.not_after
+                    && !revoked(c)
+                    && !self.config.abused_serials.contains(&c.serial_number);
+                CertificateInfo {
+                    subject: c.subject.clone(),
+                    issuer: c.issuer.clone(),
+                    serial_number: c.serial_number.clone(),
+                    not_before: c.not_before,
+                    not_after: c.not_after,
+                    thumbprint: c.thumbprint.clone(),
+                    is_valid: chain_valid && cert_valid,
+                }
+            })
+            .collect();
+
+        AuthenticodeAnalysis {
+            is_signed: true,
+            chain_valid,
+            signer: Some(signer.subject),
+            certificates,
+            issues,
+        }
+    }
+}
+
+/// Authenticode image hash: the file without its checksum, its certificate
+/// table directory entry and the certificate table itself
+fn image_hash(data: &[u8], algorithm: DigestAlgorithm, table: Range<usize>) -> Option<Vec<u8>> {
+    let pe_offset = u32::from_le_bytes(data.get(0x3C..0x40)?.try_into().ok()?) as usize;
+    // PE signature (4 bytes) and COFF file header (20 bytes)
+    let optional_header = pe_offset.checked_add(24)?;
+    let magic = u16::from_le_bytes(data.get(optional_header..optional_header + 2)?.try_into().ok()?);
+    let certificate_entry = match magic {
+        0x10b => optional_header + 128,
+        0x20b => optional_header + 144,
+        _ => return None,
+    };
+    let checksum = optional_header + 64;
+
+    if certificate_entry + 8 > table.start || table.end > data.len() {
+        return None;
+    }
+
+    Some(algorithm.digest(&[
+        &data[..checksum],
+        &data[checksum + 4..certificate_entry],
+        &data[certificate_entry + 8..table.start],
+        &data[table.end..],
+    ]))
+}
+
+impl Default for AuthenticodeVerifier {
+    fn default() -> Self {
+        Self::new(AuthenticodeConfig::default())
+    }
+}
+
+/// Minimal DER reader covering the subset of ASN.1 used by PKCS#7 and X.509
+mod der {
+    use super::*;
+
+    pub struct Tlv<'a> {
+        pub tag: u8,
+        pub value: &'a [u8],
+        /// Full encoding including tag and length, used for thumbprints
+        pub raw: &'a [u8],
+    }
+
+    /// Read one TLV, returning it and the remaining input
+    pub fn read(input: &[u8]) -> Option<(Tlv<'_>, &[u8])> {
+        let tag = *input.first()?;
+        let first = *input.get(1)? as usize;
+
+        let (len, header) = if first & 0x80 == 0 {
+            (first, 2)
+        } else {
+            let count = first & 0x7F;
+            if count == 0 || count > 4 {
+                return None;
+            }
+            let bytes = input.get(2..2 + count)?;
+            let len = bytes.iter().fold(0usize, |acc, &b| (acc << 8) | b as usize);
+            (len, 2 + count)
+        };
+
+        let end = header.checked_add(len)?;
+        let value = input.get(header..end)?;
+        Some((Tlv { tag, value, raw: &input[..end] }, &input[end..]))
+    }
+
+    /// Read one TLV and require a specific tag
+    pub fn expect(input: &[u8], tag: u8) -> Option<(Tlv<'_>, &[u8])> {
+        let (tlv, rest) = read(input)?;
+        if tlv.tag == tag { Some((tlv, rest)) } else { None }
+    }
+
+    /// OID of an AlgorithmIdentifier's contents
+    pub fn algorithm_oid(algorithm: &[u8]) -> Option<&[u8]> {
+        expect(algorithm, TAG_OID).map(|(oid, _)| oid.value)
+    }
+
+    /// BIT STRING contents without the unused-bits octet
+    pub fn bit_string<'a>(tlv: &Tlv<'a>) -> Option<&'a [u8]> {
+        match tlv.value.split_first() {
+            Some((0, bits)) => Some(bits),
+            _ => None,
+        }
+    }
+
+    pub fn strip_leading_zeros(bytes: &[u8]) -> &[u8] {
+        let first = bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len().saturating_sub(1));
+        &bytes[first..]
+    }
+
+    pub fn parse_time(tlv: &Tlv) -> Option<DateTime<Utc>> {
+        let text = std::str::from_utf8(tlv.value).ok()?.trim_end_matches('Z');
+        let naive = match tlv.tag {
+            TAG_UTC_TIME => {
+                // Two-digit years: 50-99 => 19xx, 00-49 => 20xx (RFC 5280)
+                let year: u32 = text.get(0..2)?.parse().ok()?;
+                let century = if year >= 50 { "19" } else { "20" };
+                NaiveDateTime::parse_from_str(&format!("{}{}", century, text), "%Y%m%d%H%M%S").ok()?
+            }
+            TAG_GENERALIZED_TIME => NaiveDateTime::parse_from_str(text, "%Y%m%d%H%M%S").ok()?,
+            _ => return None,
+        };
+        Some(Utc.from_utc_datetime(&naive))
+    }
+
+    fn attribute_name(oid: &[u8]) -> Option<&'static str> {
+        match oid {
+            [0x55, 0x04, 0x03] => Some("CN"),
+            [0x55, 0x04, 0x06] => Some("C"),
+            [0x55, 0x04, 0x07] => Some("L"),
+            [0x55, 0x04, 0x08] => Some("ST"),
+            [0x55, 0x04, 0x0A] => Some("O"),
+            [0x55, 0x04, 0x0B] => Some("OU"),
+            _ => None,
+        }
+    }
+
+    /// Render an X.509 Name as "CN=..., O=..., C=..." (most specific first)
+    pub fn format_name(name: &[u8]) -> String {
+        let mut parts = Vec::new();
+        let mut rdns = name;
+
+        while let Some((rdn, rest)) = read(rdns) {
+            let mut attrs = rdn.value;
+            while let Some((attr, attr_rest)) = expect(attrs, TAG_SEQUENCE) {
+                if let Some((oid, value_rest)) = expect(attr.value, TAG_OID) {
+                    if let (Some(label), Some((value, _))) = (attribute_name(oid.value), read(value_rest)) {
+                        parts.push(format!("{}={}", label, String::from_utf8_lossy(value.value)));
+                    }
+                }
+                attrs = attr_rest;
+            }
+            rdns = rest;
+        }
+
+        parts.reverse();
+        parts.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use ring::rand::SystemRandom;
+    use ring::signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_ASN1_SIGNING};
+
+    fn key() -> EcdsaKeyPair {
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, &rng).unwrap();
+        EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, pkcs8.as_ref(), &rng).unwrap()
+    }
+
+    fn sign(key: &EcdsaKeyPair, message: &[u8]) -> Vec<u8> {
+        key.sign(&SystemRandom::new(), message).unwrap().as_ref().to_vec()
+    }
+
+    /// A certificate for `key`, signed by `issuer_key`
+    fn cert(
+        subject: &str,
+        issuer: &str,
+        serial: &str,
+        expired: bool,
+        key: &EcdsaKeyPair,
+        issuer_key: &EcdsaKeyPair,
+    ) -> ParsedCertificate {
+        let now = Utc::now();
+        let tbs = format!("{}|{}|{}", subject, issuer, serial).into_bytes();
+        ParsedCertificate {
+            subject: subject.to_string(),
+            issuer: issuer.to_string(),
+            serial_number: serial.to_string(),
+            not_before: now - Duration::days(365),
+            not_after: if expired { now - Duration::days(1) } else { now + Duration::days(365) },
+            thumbprint: format!("thumb-{}", serial),
+            issuer_der: issuer.as_bytes().to_vec(),
+            serial_der: serial.as_bytes().to_vec(),
+            signature: sign(issuer_key, &tbs),
+            tbs,
+            signature_digest: Some(DigestAlgorithm::Sha256),
+            public_key: Some(PublicKey { kind: KeyKind::EcP256, key: key.public_key().as_ref().to_vec() }),
+        }
+    }
+
+    const ROOT: &str = "CN=DigiCert Assured ID Root CA, OU=www.digicert.com, O=DigiCert Inc, C=US";
+
+    fn verifier_trusting(root: ParsedCertificate) -> AuthenticodeVerifier {
+        AuthenticodeVerifier { config: AuthenticodeConfig::default(), roots: vec![root] }
+    }
+
+    #[test]
+    fn test_valid_chain_to_trusted_root() {
+        let (root_key, intermediate_key, leaf_key) = (key(), key(), key());
+        let verifier = verifier_trusting(cert(ROOT, ROOT, "00", false, &root_key, &root_key));
+        let certs = vec![
+            cert("CN=Intermediate", ROOT, "02", false, &intermediate_key, &root_key),
+            cert("CN=Acme Software", "CN=Intermediate", "01", false, &leaf_key, &intermediate_key),
+        ];
+
+        let analysis = verifier.validate_chain(certs, None, Vec::new(), &[], Utc::now());
+        assert!(analysis.chain_valid, "issues: {:?}", analysis.issues);
+        assert_eq!(analysis.signer.as_deref(), Some("CN=Acme Software"));
+        assert_eq!(analysis.certificates.len(), 2);
+        assert!(analysis.certificates.iter().all(|c| c.is_valid));
+    }
+
+    #[test]
+    fn test_matching_names_without_signatures_are_not_trusted() {
+        let (root_key, forger_key, leaf_key) = (key(), key(), key());
+        let verifier = verifier_trusting(cert(ROOT, ROOT, "00", false, &root_key, &root_key));
+
+        // Claims the trusted root as issuer but was signed by someone else
+        let certs = vec![
+            cert("CN=Intermediate", ROOT, "02", false, &forger_key, &forger_key),
+            cert("CN=Acme Software", "CN=Intermediate", "01", false, &leaf_key, &forger_key),
+        ];
+        let analysis = verifier.validate_chain(certs, None, Vec::new(), &[], Utc::now());
+        assert!(!analysis.chain_valid);
+        assert!(analysis.issues.contains(&SignatureIssue::BadSignature));
+        assert!(analysis.certificates.iter().all(|c| !c.is_valid));
+    }
+
+    #[test]
+    fn test_integrity_issues_block_trust() {
+        let (root_key, leaf_key) = (key(), key());
+        let verifier = verifier_trusting(cert(ROOT, ROOT, "00", false, &root_key, &root_key));
+        let certs = vec![cert("CN=Acme Software", ROOT, "01", false, &leaf_key, &root_key)];
+
+        let analysis = verifier.validate_chain(certs, Some(0), vec![SignatureIssue::DigestMismatch], &[], Utc::now());
+        assert!(!analysis.chain_valid);
+        assert_eq!(analysis.issues, vec![SignatureIssue::DigestMismatch]);
+    }
+
+    #[test]
+    fn test_self_signed_and_expired() {
+        let own_key = key();
+        let verifier = AuthenticodeVerifier::default();
+        let certs = vec![cert("CN=Totally Legit", "CN=Totally Legit", "05", true, &own_key, &own_key)];
+
+        let analysis = verifier.validate_chain(certs, None, Vec::new(), &[], Utc::now());
+        assert!(!analysis.chain_valid);
+        assert!(analysis.issues.contains(&SignatureIssue::SelfSigned));
+        assert!(analysis.issues.contains(&SignatureIssue::Expired));
+        assert!(!analysis.certificates[0].is_valid);
+    }
+
+    #[test]
+    fn test_revoked_and_abused_certificates() {
+        let (root_key, nvidia_key, other_key) = (key(), key(), key());
+        let config = AuthenticodeConfig {
+            revoked_serials: vec!["01".to_string()],
+            ..AuthenticodeConfig::default()
+        };
+        let verifier = AuthenticodeVerifier::new(config);
+        let certs = vec![
+            cert("CN=NVIDIA Corporation", ROOT, "43bb437d609866286dd839e1d00309f5", false, &nvidia_key, &root_key),
+            cert("CN=Other", ROOT, "01", false, &other_key, &root_key),
+        ];
+
+        let analysis = verifier.validate_chain(certs, None, Vec::new(), &[], Utc::now());
+        assert!(analysis.issues.contains(&SignatureIssue::KnownAbused));
+        assert_eq!(analysis.issues[0], SignatureIssue::KnownAbused, "highest weight first");
+    }
+
+    /// One DER TLV; lengths up to 255 bytes
+    fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+        let header = match content.len() {
+            len @ 0..=0x7F => vec![tag, len as u8],
+            len => vec![tag, 0x81, u8::try_from(len).unwrap()],
+        };
+        [header, content.to_vec()].concat()
+    }
+
+    /// A CRL from "CN=CA" revoking `serial`, signed with `key`
+    fn crl_der(key: &EcdsaKeyPair, serial: u8) -> Vec<u8> {
+        let common_name = [tlv(TAG_OID, &[0x55, 0x04, 0x03]), tlv(0x13, b"CA")].concat();
+        let name = tlv(TAG_SEQUENCE, &tlv(TAG_SET, &tlv(TAG_SEQUENCE, &common_name)));
+        let algorithm = tlv(TAG_SEQUENCE, &tlv(TAG_OID, OID_ECDSA_SHA256));
+        let entry = [tlv(TAG_INTEGER, &[0x00, serial]), tlv(TAG_UTC_TIME, b"240101120000Z")].concat();
+        let tbs = tlv(TAG_SEQUENCE, &[
+            tlv(TAG_INTEGER, &[0x01]),
+            algorithm.clone(),
+            name,
+            tlv(TAG_UTC_TIME, b"240101120000Z"),
+            tlv(TAG_UTC_TIME, b"240201120000Z"),
+            tlv(TAG_SEQUENCE, &tlv(TAG_SEQUENCE, &entry)),
+        ].concat());
+        let signature = [vec![0u8], sign(key, &tbs)].concat();
+        tlv(TAG_SEQUENCE, &[tbs, algorithm, tlv(TAG_BIT_STRING, &signature)].concat())
+    }
+
+    #[test]
+    fn test_embedded_crls_revoke_when_the_issuer_signed_them() {
+        let (ca_key, leaf_key, forger_key) = (key(), key(), key());
+        let verifier = verifier_trusting(cert("CN=CA", "CN=CA", "00", false, &ca_key, &ca_key));
+        let certs = || vec![cert("CN=Acme Software", "CN=CA", "81", false, &leaf_key, &ca_key)];
+
+        let der = crl_der(&ca_key, 0x81);
+        let crl = AuthenticodeVerifier::parse_crl(&der::read(&der).unwrap().0).unwrap();
+        assert_eq!(crl.issuer, "CN=CA");
+        assert_eq!(crl.revoked_serials, vec!["81".to_string()]);
+
+        let analysis = verifier.validate_chain(certs(), None, Vec::new(), std::slice::from_ref(&crl), Utc::now());
+        assert_eq!(analysis.issues, vec![SignatureIssue::Revoked]);
+        assert!(!analysis.certificates[0].is_valid);
+
+        // A CRL the issuer did not sign is ignored
+        let der = crl_der(&forger_key, 0x81);
+        let forged = AuthenticodeVerifier::parse_crl(&der::read(&der).unwrap().0).unwrap();
+        let analysis = verifier.validate_chain(certs(), None, Vec::new(), &[forged], Utc::now());
+        assert!(analysis.chain_valid, "issues: {:?}", analysis.issues);
+
+        // As is every list when revocation checking is off
+        let verifier = AuthenticodeVerifier {
+            config: AuthenticodeConfig {
+                revoked_serials: vec!["81".to_string()],
+                check_revocation: false,
+                ..AuthenticodeConfig::default()
+            },
+            ..verifier
+        };
+        let analysis = verifier.validate_chain(certs(), None, Vec::new(), &[crl], Utc::now());
+        assert!(analysis.chain_valid, "issues: {:?}", analysis.issues);
+        assert!(analysis.certificates[0].is_valid);
+    }
+
+    #[test]
+    fn test_broken_chain() {
+        let (leaf_key, issuer_key) = (key(), key());
+        let verifier = AuthenticodeVerifier::default();
+        let certs = vec![cert("CN=Acme Software", "CN=Missing Intermediate", "01", false, &leaf_key, &issuer_key)];
+
+        let analysis = verifier.validate_chain(certs, None, Vec::new(), &[], Utc::now());
+        assert!(analysis.issues.contains(&SignatureIssue::BrokenChain));
+    }
+
+    /// PE32+ headers, a body and a certificate table, returning the table range
+    fn pe_image() -> (Vec<u8>, Range<usize>) {
+        let mut data = vec![0u8; 0x200];
+        data[0x3C..0x40].copy_from_slice(&0x40u32.to_le_bytes());
+        data[0x40..0x44].copy_from_slice(b"PE\0\0");
+        data[0x58..0x5A].copy_from_slice(&0x20bu16.to_le_bytes());
+        data[0x100..0x110].copy_from_slice(b"section contents");
+        let table = data.len()..data.len() + 16;
+        data.extend_from_slice(&[0xAA; 16]);
+        (data, table)
+    }
+
+    #[test]
+    fn test_image_hash_skips_checksum_and_certificate_table() {
+        let (mut data, table) = pe_image();
+        let original = image_hash(&data, DigestAlgorithm::Sha256, table.clone()).unwrap();
+
+        // Checksum, certificate directory entry and the table itself are excluded
+        data[0x58 + 64] = 0x7F;
+        data[0x58 + 144] = 0x7F;
+        data[table.start] = 0x00;
+        assert_eq!(image_hash(&data, DigestAlgorithm::Sha256, table.clone()).unwrap(), original);
+
+        data[0x100] = b'S';
+        assert_ne!(image_hash(&data, DigestAlgorithm::Sha256, table).unwrap(), original);
+    }
+
+    #[test]
+    fn test_integrity_checks_signer_and_image_digest() {
+        let signer_key = key();
+        let signer = cert("CN=Acme Software", ROOT, "01", false, &signer_key, &signer_key);
+        let (mut data, table) = pe_image();
+
+        let indirect_data = b"spc indirect data".to_vec();
+        let attributes = b"signed attributes".to_vec();
+        let signed = SignedData {
+            certificates: vec![signer.clone()],
+            image_digest: (DigestAlgorithm::Sha256, image_hash(&data, DigestAlgorithm::Sha256, table.clone()).unwrap()),
+            signer: SignerInfo {
+                issuer_der: signer.issuer_der.clone(),
+                serial_der: signer.serial_der.clone(),
+                digest: DigestAlgorithm::Sha256,
+                message_digest: Some(DigestAlgorithm::Sha256.digest(&[&indirect_data])),
+                signature: sign(&signer_key, &attributes),
+                signed_attributes: Some(attributes),
+            },
+            indirect_data,
+            crls: Vec::new(),
+        };
+
+        assert!(AuthenticodeVerifier::integrity_issues(&data, table.clone(), &signed, Some(&signer)).is_empty());
+
+        let mut tampered = signed.clone();
+        tampered.indirect_data = b"other indirect data".to_vec();
+        assert_eq!(
+            AuthenticodeVerifier::integrity_issues(&data, table.clone(), &tampered, Some(&signer)),
+            vec![SignatureIssue::BadSignature]
+        );
+
+        let impostor = cert("CN=Acme Software", ROOT, "01", false, &key(), &signer_key);
+        assert_eq!(
+            AuthenticodeVerifier::integrity_issues(&data, table.clone(), &signed, Some(&impostor)),
+            vec![SignatureIssue::BadSignature]
+        );
+
+        data[0x100] = b'S';
+        assert_eq!(
+            AuthenticodeVerifier::integrity_issues(&data, table, &signed, Some(&signer)),
+            vec![SignatureIssue::DigestMismatch]
+        );
+    }
+
+    #[test]
+    fn test_win_certificate_parsing() {
+        let mut table = Vec::new();
+        // 12-byte entry: 8-byte header + 4-byte payload, padded to 16
+        table.extend_from_slice(&12u32.to_le_bytes());
+        table.extend_from_slice(&0x0200u16.to_le_bytes());
+        table.extend_from_slice(&WIN_CERT_TYPE_PKCS_SIGNED_DATA.to_le_bytes());
+        table.extend_from_slice(&[0x30, 0x02, 0x05, 0x00]);
+        table.extend_from_slice(&[0u8; 4]);
+
+        let blobs = AuthenticodeVerifier::pkcs7_blobs(&table);
+        assert_eq!(blobs.len(), 1);
+        assert_eq!(blobs[0], &[0x30, 0x02, 0x05, 0x00]);
+        assert!(AuthenticodeVerifier::parse_signed_data(blobs[0]).is_none());
+    }
+
+    #[test]
+    fn test_pem_trust_store() {
+        let pem = "junk\n-----BEGIN CERTIFICATE-----\nMAMCAQE=\n-----END CERTIFICATE-----\n";
+        assert_eq!(pem_certificates(pem), vec![vec![0x30, 0x03, 0x02, 0x01, 0x01]]);
+    }
+
+    #[test]
+    fn test_der_time_and_name() {
+        let utc = der::Tlv { tag: TAG_UTC_TIME, value: b"240101120000Z", raw: &[] };
+        let parsed = der::parse_time(&utc).unwrap();
+        assert_eq!(parsed.to_rfc3339(), "2024-01-01T12:00:00+00:00");
+
+        // SEQUENCE { SET { SEQUENCE { OID 2.5.4.3, PrintableString "Test" } } }
+        let name = [0x31, 0x0B, 0x30, 0x09, 0x06, 0x03, 0x55, 0x04, 0x03, 0x13, 0x02, b'O', b'K'];
+        assert_eq!(der::format_name(&name), "CN=OK");
+    }
+}
@@ -0,0 +1,253 @@
+//! Cross-analyzer detection deduplication
+//!
+//! YARA, ClamAV and hash reputation sources frequently report the same
+//! family (`Win.Trojan.Emotet-9953`, `Emotet`, `emotet_loader`). Left as-is,
+//! each copy is another vote in `AnalysisResult::update_consensus`, so one
+//! piece of evidence inflates the consensus. This pass folds detections that
+//! share an indicator and category into a single detection that keeps the
+//! strongest verdict/confidence and records every supporting engine.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::models::analysis_result::{DetectionResult, ThreatCategory, ThreatVerdict};
+
+/// Metadata keys that name the indicator, most specific first
+const INDICATOR_KEYS: &[&str] = &["malware_family", "family", "signature", "rule_name", "threat_name"];
+
+/// Tokens that describe platform or threat class rather than the family
+const GENERIC_TOKENS: &[&str] = &[
+    "win", "win32", "win64", "linux", "unix", "osx", "macos", "android", "multi", "doc", "xls",
+    "pdf", "js", "html", "trojan", "ransomware", "worm", "virus", "malware", "backdoor", "rootkit",
+    "spyware", "adware", "exploit", "phishing", "generic", "heur", "heuristic", "suspicious",
+    "downloader", "dropper", "loader", "packed", "variant",
+];
+
+/// Metadata key recording how many detections were folded together
+pub const EVIDENCE_COUNT_KEY: &str = "evidence_count";
+/// Metadata key listing the engines that reported the merged indicator
+pub const SUPPORTING_ENGINES_KEY: &str = "supporting_engines";
+/// Metadata key recording the normalized indicator a detection was merged on
+pub const INDICATOR_KEY: &str = "indicator";
+
+/// Normalized family name for a detection, if it names one
+pub fn indicator_key(detection: &DetectionResult) -> Option<String> {
+    INDICATOR_KEYS.iter().find_map(|key| {
+        detection
+            .metadata
+            .get(*key)
+            .and_then(Value::as_str)
+            .and_then(normalize_indicator)
+    })
+}
+
+/// Reduce a signature/family name to its family token
+/// (`Win.Trojan.Emotet-9953` → `emotet`)
+pub fn normalize_indicator(raw: &str) -> Option<String> {
+    raw.split(|c: char| !c.is_ascii_alphanumeric())
+        .map(str::to_ascii_lowercase)
+        .find(|token| {
+            token.len() > 2
+                && !token.chars().all(|c| c.is_ascii_digit())
+                && !GENERIC_TOKENS.contains(&token.as_str())
+        })
+}
+
+fn verdict_rank(verdict: &ThreatVerdict) -> u8 {
+    match verdict {
+        ThreatVerdict::Malicious => 3,
+        ThreatVerdict::Suspicious => 2,
+        ThreatVerdict::Benign => 1,
+        ThreatVerdict::Unknown => 0,
+    }
+}
+
+/// Detections without categories corroborate any category for their indicator
+fn categories_overlap(a: &[ThreatCategory], b: &[ThreatCategory]) -> bool {
+    a.is_empty() || b.is_empty() || a.iter().any(|c| b.contains(c))
+}
+
+fn evidence_count(detection: &DetectionResult) -> u64 {
+    detection
+        .metadata
+        .get(EVIDENCE_COUNT_KEY)
+        .and_then(Value::as_u64)
+        .unwrap_or(1)
+}
+
+fn supporting_engines(detection: &DetectionResult) -> Vec<String> {
+    match detection.metadata.get(SUPPORTING_ENGINES_KEY).and_then(Value::as_array) {
+        Some(engines) => engines
+            .iter()
+            .filter_map(|e| e.as_str().map(str::to_string))
+            .collect(),
+        None => vec![detection.engine_name.clone()],
+    }
+}
+
+/// Fold `other` into `merged`. Confidence is the maximum, not a sum or
+/// average, so corroboration never counts the same indicator twice.
+fn absorb(merged: &mut DetectionResult, other: DetectionResult) {
+    let evidence = evidence_count(merged) + evidence_count(&other);
+
+    let mut engines = supporting_engines(merged);
+    for engine in supporting_engines(&other) {
+        if !engines.contains(&engine) {
+            engines.push(engine);
+        }
+    }
+
+    for category in &other.categories {
+        if !merged.categories.contains(category) {
+            merged.categories.push(category.clone());
+        }
+    }
+    merged.severity = merged.severity.clone().max(other.severity.clone());
+    merged.processing_time_ms = merged.processing_time_ms.max(other.processing_time_ms);
+
+    // The strongest detection becomes the representative one
+    let stronger = (verdict_rank(&other.verdict), other.confidence)
+        > (verdict_rank(&merged.verdict), merged.confidence);
+    if stronger {
+        merged.detection_id = other.detection_id;
+        merged.engine_name = other.engine_name;
+        merged.engine_version = other.engine_version;
+        merged.engine_type = other.engine_type;
+        merged.verdict = other.verdict;
+        merged.confidence = other.confidence;
+        merged.detected_at = other.detected_at;
+    }
+    for (key, value) in other.metadata {
+        merged.metadata.entry(key).or_insert(value);
+    }
+
+    merged.metadata.insert(EVIDENCE_COUNT_KEY.to_string(), Value::from(evidence));
+    merged
+        .metadata
+        .insert(SUPPORTING_ENGINES_KEY.to_string(), Value::from(engines));
+}
+
+/// Merge detections that share an indicator and category; detections with
+/// no recognizable indicator are passed through untouched. Order follows the
+/// first occurrence of each indicator.
+pub fn merge_detections(detections: Vec<DetectionResult>) -> Vec<DetectionResult> {
+    let mut merged: Vec<DetectionResult> = Vec::with_capacity(detections.len());
+    let mut by_indicator: HashMap<String, Vec<usize>> = HashMap::new();
+
+    for mut detection in detections {
+        let Some(indicator) = indicator_key(&detection) else {
+            merged.push(detection);
+            continue;
+        };
+
+        let slots = by_indicator.entry(indicator.clone()).or_default();
+        match slots
+            .iter()
+            .copied()
+            .find(|&i| categories_overlap(&merged[i].categories, &detection.categories))
+        {
+            Some(i) => absorb(&mut merged[i], detection),
+            None => {
+                detection
+                    .metadata
+                    .insert(INDICATOR_KEY.to_string(), Value::from(indicator));
+                slots.push(merged.len());
+                merged.push(detection);
+            }
+        }
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::analysis_result::{EngineType, SeverityLevel};
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn detection(
+        engine: &str,
+        key: &str,
+        name: &str,
+        verdict: ThreatVerdict,
+        confidence: f32,
+        categories: Vec<ThreatCategory>,
+    ) -> DetectionResult {
+        DetectionResult {
+            detection_id: Uuid::new_v4(),
+            engine_name: engine.to_string(),
+            engine_version: "1.0.0".to_string(),
+            engine_type: EngineType::Yara,
+            verdict,
+            confidence,
+            severity: SeverityLevel::Medium,
+            categories,
+            metadata: HashMap::from([(key.to_string(), Value::from(name))]),
+            detected_at: Utc::now(),
+            processing_time_ms: 10,
+            error_message: None,
+        }
+    }
+
+    #[test]
+    fn test_normalize_indicator() {
+        assert_eq!(normalize_indicator("Win.Trojan.Emotet-9953").as_deref(), Some("emotet"));
+        assert_eq!(normalize_indicator("Emotet").as_deref(), Some("emotet"));
+        assert_eq!(normalize_indicator("emotet_loader").as_deref(), Some("emotet"));
+        assert_eq!(normalize_indicator("Win.Trojan.Generic-1"), None);
+    }
+
+    #[test]
+    fn test_merges_same_indicator_across_engines() {
+        let merged = merge_detections(vec![
+            detection("ClamAV", "signature", "Win.Trojan.Emotet-9953", ThreatVerdict::Malicious, 0.98, vec![ThreatCategory::Trojan]),
+            detection("MalwareBazaar", "malware_family", "Emotet", ThreatVerdict::Malicious, 0.9, vec![]),
+            detection("YARA", "rule_name", "emotet_loader", ThreatVerdict::Suspicious, 0.6, vec![ThreatCategory::Trojan]),
+        ]);
+
+        assert_eq!(merged.len(), 1);
+        let d = &merged[0];
+        assert_eq!(d.engine_name, "ClamAV");
+        assert_eq!(d.confidence, 0.98);
+        assert_eq!(d.verdict, ThreatVerdict::Malicious);
+        assert_eq!(d.metadata[EVIDENCE_COUNT_KEY], Value::from(3));
+        assert_eq!(d.metadata[SUPPORTING_ENGINES_KEY].as_array().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_keeps_distinct_indicators_and_categories() {
+        let merged = merge_detections(vec![
+            detection("ClamAV", "signature", "Win.Trojan.Emotet-1", ThreatVerdict::Malicious, 0.9, vec![ThreatCategory::Trojan]),
+            detection("YARA", "rule_name", "Emotet", ThreatVerdict::Malicious, 0.8, vec![ThreatCategory::Ransomware]),
+            detection("YARA", "rule_name", "Qakbot", ThreatVerdict::Malicious, 0.8, vec![]),
+            detection("Static", "other", "x", ThreatVerdict::Suspicious, 0.5, vec![]),
+        ]);
+
+        assert_eq!(merged.len(), 4);
+    }
+
+    #[test]
+    fn test_merging_prevents_double_counted_confidence() {
+        use crate::models::analysis_result::AnalysisResult;
+
+        let detections = vec![
+            detection("ClamAV", "signature", "Win.Trojan.Emotet-1", ThreatVerdict::Malicious, 0.9, vec![]),
+            detection("MalwareBazaar", "malware_family", "Emotet", ThreatVerdict::Malicious, 0.9, vec![]),
+            detection("Static", "other", "x", ThreatVerdict::Benign, 0.3, vec![]),
+            detection("Heuristics", "other", "y", ThreatVerdict::Benign, 0.3, vec![]),
+        ];
+
+        let mut result = AnalysisResult::default();
+        for d in merge_detections(detections) {
+            result.add_detection(d);
+        }
+
+        // One Emotet vote against two benign votes
+        assert_eq!(result.detections.len(), 3);
+        assert_eq!(result.consensus_verdict, ThreatVerdict::Benign);
+        assert!((result.consensus_confidence - 0.5).abs() < 1e-6);
+    }
+}
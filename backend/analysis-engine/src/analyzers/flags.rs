@@ -0,0 +1,123 @@
+//! Runtime on/off switches for individual analyzers
+//!
+//! Each analyzer has a flag in the shared feature-flag store
+//! (`analysis-engine.analyzer.<name>`), settable globally or per tenant through
+//! `PUT /admin/feature-flags/:flag`. The tenant is the user behind the API key
+//! or the submitter of a queued submission. Flags can only switch an analyzer
+//! off for a request; they never enable one the request or build left out.
+
+use serde::Serialize;
+use shared::feature_flags::FeatureFlagClient;
+
+use super::AnalysisOptions;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Analyzer {
+    Hash,
+    Static,
+    Yara,
+    Clamav,
+    Sandbox,
+    Ml,
+}
+
+impl Analyzer {
+    pub const ALL: [Analyzer; 6] = [
+        Analyzer::Hash,
+        Analyzer::Static,
+        Analyzer::Yara,
+        Analyzer::Clamav,
+        Analyzer::Sandbox,
+        Analyzer::Ml,
+    ];
+
+    pub fn flag(&self) -> &'static str {
+        match self {
+            Analyzer::Hash => "analysis-engine.analyzer.hash",
+            Analyzer::Static => "analysis-engine.analyzer.static",
+            Analyzer::Yara => "analysis-engine.analyzer.yara",
+            Analyzer::Clamav => "analysis-engine.analyzer.clamav",
+            Analyzer::Sandbox => "analysis-engine.analyzer.sandbox",
+            Analyzer::Ml => "analysis-engine.analyzer.ml",
+        }
+    }
+
+    /// Whether this build runs the analyzer at all. The sandbox and ML
+    /// analyzers are not part of the file pipeline yet; their flags can
+    /// still be set ahead of time.
+    pub fn available(&self) -> bool {
+        match self {
+            Analyzer::Hash | Analyzer::Static => true,
+            Analyzer::Yara => cfg!(feature = "yara-engine"),
+            Analyzer::Clamav => cfg!(feature = "clamav"),
+            Analyzer::Sandbox | Analyzer::Ml => false,
+        }
+    }
+}
+
+/// One analyzer as reported by `/engines/status`
+#[derive(Debug, Clone, Serialize)]
+pub struct AnalyzerStatus {
+    pub analyzer: Analyzer,
+    pub available: bool,
+    /// Flag setting for the tenant asking, or the global setting
+    pub enabled: bool,
+    /// Whether the analyzer will actually run
+    pub active: bool,
+}
+
+#[derive(Clone)]
+pub struct AnalyzerFlags {
+    client: FeatureFlagClient,
+}
+
+impl AnalyzerFlags {
+    pub fn new(client: FeatureFlagClient) -> Self {
+        Self { client }
+    }
+
+    pub fn client(&self) -> &FeatureFlagClient {
+        &self.client
+    }
+
+    pub async fn is_enabled(&self, analyzer: Analyzer, tenant: Option<&str>) -> bool {
+        self.client.is_enabled(analyzer.flag(), tenant, true).await
+    }
+
+    /// Switch off the analyzers disabled for `tenant`
+    pub async fn apply(&self, tenant: Option<&str>, options: &mut AnalysisOptions) {
+        let switches = [
+            (Analyzer::Hash, &mut options.enable_hash_analysis),
+            (Analyzer::Static, &mut options.enable_static_analysis),
+            (Analyzer::Yara, &mut options.enable_yara_analysis),
+            (Analyzer::Clamav, &mut options.enable_clamav_analysis),
+        ];
+        for (analyzer, enabled) in switches {
+            if *enabled && !self.is_enabled(analyzer, tenant).await {
+                tracing::debug!("Analyzer {:?} disabled by feature flag", analyzer);
+                *enabled = false;
+            }
+        }
+    }
+
+    /// Current state of every analyzer, read straight from the flag store so
+    /// a change shows up on the next call. Falls back to the cached view if
+    /// the store is unreachable.
+    pub async fn status(&self, tenant: Option<&str>) -> Vec<AnalyzerStatus> {
+        let mut statuses = Vec::with_capacity(Analyzer::ALL.len());
+        for analyzer in Analyzer::ALL {
+            let enabled = match self.client.state(analyzer.flag()).await {
+                Ok(state) => state.evaluate(tenant, true),
+                Err(_) => self.is_enabled(analyzer, tenant).await,
+            };
+            statuses.push(AnalyzerStatus {
+                analyzer,
+                available: analyzer.available(),
+                enabled,
+                active: analyzer.available() && enabled,
+            });
+        }
+        statuses
+    }
+}
@@ -9,7 +9,10 @@ use uuid::Uuid;
 // Re-export all analyzer modules
 pub mod hash_analyzer;
 pub mod static_analyzer;
+pub mod authenticode;
 pub mod dynamic_analyzer;
+pub mod dedup;
+pub mod flags;
 
 #[cfg(feature = "yara-engine")]
 pub mod yara_engine;
@@ -19,6 +22,7 @@ pub mod clamav_analyzer;
 // Re-export commonly used types
 pub use hash_analyzer::{HashAnalyzer, HashAnalyzerConfig, HashInfo, HashType};
 pub use static_analyzer::{StaticAnalyzer, StaticAnalyzerConfig, FileType, PEAnalysis, StringAnalysis, EntropyAnalysis};
+pub use authenticode::{AuthenticodeAnalysis, AuthenticodeConfig, AuthenticodeVerifier, SignatureIssue};
 
 #[cfg(feature = "yara-engine")]
 pub use yara_engine::{YaraEngine, YaraEngineConfig, YaraMatch, YaraRule, YaraEngineError};
@@ -281,6 +285,10 @@ impl AnalysisEngine {
             }
         }
 
+        // Fold duplicate indicators so corroborating engines don't stack votes
+        let detections = dedup::merge_detections(detections);
+        debug!("{} detections after deduplication", detections.len());
+
         // Add detections to result
         for det in detections {
             result.add_detection(det);
@@ -13,6 +13,7 @@ use chrono::Utc;
 use uuid::Uuid;
 
 use crate::models::analysis_result::{DetectionResult, ThreatVerdict, ConfidenceLevel, EngineType, SeverityLevel, ThreatCategory};
+use super::authenticode::{AuthenticodeAnalysis, AuthenticodeConfig, AuthenticodeVerifier};
 
 /// File type enumeration based on magic bytes and headers
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -43,6 +44,7 @@ pub struct PEAnalysis {
     pub entropy: f64,
     pub suspicious_imports: Vec<String>,
     pub packer_signatures: Vec<String>,
+    pub authenticode: Option<AuthenticodeAnalysis>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -120,6 +122,8 @@ pub struct StaticAnalyzerConfig {
     pub enable_pe_analysis: bool,
     pub enable_string_analysis: bool,
     pub enable_entropy_analysis: bool,
+    pub enable_signature_verification: bool,
+    pub authenticode: AuthenticodeConfig,
     pub suspicious_string_threshold: f64,
     pub min_string_length: usize,
     pub entropy_window_size: usize,
@@ -134,6 +138,8 @@ impl Default for StaticAnalyzerConfig {
             enable_pe_analysis: true,
             enable_string_analysis: true,
             enable_entropy_analysis: true,
+            enable_signature_verification: true,
+            authenticode: AuthenticodeConfig::default(),
             suspicious_string_threshold: 0.7,
             min_string_length: 8,
             entropy_window_size: 1024,
@@ -218,12 +224,14 @@ lazy_static! {
 /// Main static analyzer implementation
 pub struct StaticAnalyzer {
     config: StaticAnalyzerConfig,
+    authenticode: AuthenticodeVerifier,
 }
 
 impl StaticAnalyzer {
     /// Create a new static analyzer instance
     pub fn new(config: StaticAnalyzerConfig) -> Self {
-        Self { config }
+        let authenticode = AuthenticodeVerifier::new(config.authenticode.clone());
+        Self { config, authenticode }
     }
 
     /// Perform comprehensive static analysis on file data
@@ -336,6 +344,14 @@ impl StaticAnalyzer {
                         threat_score += 0.15;
                         threat_details.push(format!("{} suspicious sections", suspicious_sections.len()));
                     }
+
+                    // Code-signing problems
+                    if let Some(signature) = &analysis.authenticode {
+                        for issue in &signature.issues {
+                            threat_score += issue.weight();
+                            threat_details.push(format!("Authenticode signature issue: {:?}", issue));
+                        }
+                    }
                     
                     metadata.insert("pe_analysis".to_string(), serde_json::to_value(&analysis)?);
                     Some(analysis)
@@ -574,7 +590,7 @@ impl StaticAnalyzer {
                 let section_data = &data[offset..offset + size];
                 let ent = self.calculate_entropy(section_data);
                 let suspicious = ent > self.config.entropy_threshold 
-                    || section_name.starts_with('.')
+                    || !section_name.starts_with('.')
                     || section_name.len() < 2
                     || !section_name.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_');
                 (ent, suspicious)
@@ -629,6 +645,13 @@ impl StaticAnalyzer {
         // Extract resource names (simplified)
         let resources: Vec<String> = Vec::new(); // TODO: Implement full resource parsing
 
+        let authenticode = if self.config.enable_signature_verification {
+            Some(self.authenticode.verify(data, &pe))
+        } else {
+            None
+        };
+        let is_signed = authenticode.as_ref().map(|a| a.is_signed).unwrap_or(false);
+
         Ok(PEAnalysis {
             machine_type: format!("{:?}", pe.header.coff_header.machine),
             timestamp: Some(pe.header.coff_header.time_date_stamp),
@@ -638,10 +661,11 @@ impl StaticAnalyzer {
             exports,
             resources,
             is_packed,
-            is_signed: false, // goblin doesn't expose is_signed directly
+            is_signed,
             entropy: overall_entropy,
             suspicious_imports,
             packer_signatures,
+            authenticode,
         })
     }
 
@@ -723,6 +747,12 @@ impl StaticAnalyzer {
                     categories.push(cat);
                 }
             }
+            if threat.contains("Authenticode") {
+                let cat = ThreatCategory::Other("Invalid Code Signature".to_string());
+                if !categories.contains(&cat) {
+                    categories.push(cat);
+                }
+            }
         }
 
         // If no specific categories, mark as general suspicious
@@ -0,0 +1,279 @@
+//! Completion callbacks
+//!
+//! Submitters can pass a `callback_url` with an analysis. When the analysis
+//! finishes, the final `AnalysisResult` is POSTed there as JSON, signed with
+//! HMAC-SHA256 over `"<timestamp>.<body>"` using `CALLBACK_SIGNING_SECRET`:
+//!
+//! ```text
+//! X-Nexus-Event: analysis.completed
+//! X-Nexus-Delivery: <uuid>
+//! X-Nexus-Signature: t=<unix timestamp>,v1=<hex digest>
+//! ```
+//!
+//! Failed deliveries (network errors, 5xx, 429) are retried with exponential
+//! backoff; other 4xx responses are treated as permanent. Deliveries go
+//! through the `callbacks` fault-injection client (see `shared::chaos`).
+
+use std::net::IpAddr;
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use shared::chaos::{FaultInjector, InjectedFault};
+use shared::webhooks::is_private;
+use thiserror::Error;
+use tracing::{info, warn};
+use url::Url;
+use uuid::Uuid;
+
+use crate::models::analysis_result::AnalysisResult;
+
+pub const SIGNATURE_HEADER: &str = "X-Nexus-Signature";
+pub const EVENT_HEADER: &str = "X-Nexus-Event";
+pub const DELIVERY_HEADER: &str = "X-Nexus-Delivery";
+const EVENT_ANALYSIS_COMPLETED: &str = "analysis.completed";
+/// Name of the outbound client in fault-injection rules
+const CHAOS_CLIENT: &str = "callbacks";
+
+#[derive(Debug, Error)]
+pub enum CallbackError {
+    #[error("Invalid callback URL: {0}")]
+    InvalidUrl(String),
+    #[error("Callback host is not publicly routable: {0}")]
+    PrivateAddress(String),
+    #[error("Callback delivery failed: {0}")]
+    Delivery(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct CallbackConfig {
+    pub signing_secret: String,
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub request_timeout: Duration,
+    /// Allow loopback/private targets (local development only)
+    pub allow_private_targets: bool,
+}
+
+impl CallbackConfig {
+    /// Read configuration from the environment; callbacks are disabled
+    /// unless `CALLBACK_SIGNING_SECRET` is set
+    pub fn from_env() -> Option<Self> {
+        let signing_secret = std::env::var("CALLBACK_SIGNING_SECRET")
+            .ok()
+            .filter(|s| !s.is_empty())?;
+        let env_u64 = |name: &str, default: u64| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(default)
+        };
+
+        Some(Self {
+            signing_secret,
+            max_attempts: env_u64("CALLBACK_MAX_ATTEMPTS", 5) as u32,
+            initial_backoff: Duration::from_secs(env_u64("CALLBACK_INITIAL_BACKOFF_SECS", 2)),
+            request_timeout: Duration::from_secs(env_u64("CALLBACK_TIMEOUT_SECS", 10)),
+            allow_private_targets: std::env::var("CALLBACK_ALLOW_PRIVATE_TARGETS")
+                .map(|v| v == "true")
+                .unwrap_or(false),
+        })
+    }
+}
+
+/// Compute the `X-Nexus-Signature` value for a payload
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("t={},v1={}", timestamp, hex::encode(mac.finalize().into_bytes()))
+}
+
+pub struct CallbackDispatcher {
+    client: reqwest::Client,
+    config: CallbackConfig,
+    faults: FaultInjector,
+}
+
+impl CallbackDispatcher {
+    pub fn new(config: CallbackConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(config.request_timeout)
+            // Redirects could bounce the request to an internal address
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .expect("Failed to build callback HTTP client");
+        Self {
+            client,
+            config,
+            faults: FaultInjector::disabled(),
+        }
+    }
+
+    pub fn with_fault_injector(mut self, faults: FaultInjector) -> Self {
+        self.faults = faults;
+        self
+    }
+
+    /// Check a submitter-provided URL before accepting the analysis
+    pub fn validate_url(&self, raw: &str) -> Result<Url, CallbackError> {
+        let url = Url::parse(raw).map_err(|e| CallbackError::InvalidUrl(e.to_string()))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(CallbackError::InvalidUrl("scheme must be http or https".to_string()));
+        }
+        let host = url
+            .host_str()
+            .ok_or_else(|| CallbackError::InvalidUrl("missing host".to_string()))?;
+
+        if !self.config.allow_private_targets {
+            let literal = host.trim_start_matches('[').trim_end_matches(']');
+            if host.eq_ignore_ascii_case("localhost")
+                || literal.parse::<IpAddr>().is_ok_and(is_private)
+            {
+                return Err(CallbackError::PrivateAddress(host.to_string()));
+            }
+        }
+
+        Ok(url)
+    }
+
+    /// Re-check the resolved addresses at delivery time (DNS may have changed)
+    async fn check_resolved(&self, url: &Url) -> Result<(), CallbackError> {
+        if self.config.allow_private_targets {
+            return Ok(());
+        }
+        let host = url.host_str().unwrap_or_default();
+        let port = url.port_or_known_default().unwrap_or(443);
+        let addrs = tokio::net::lookup_host((host, port))
+            .await
+            .map_err(|e| CallbackError::Delivery(format!("DNS lookup failed: {}", e)))?;
+        for addr in addrs {
+            if is_private(addr.ip()) {
+                return Err(CallbackError::PrivateAddress(addr.ip().to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    /// POST the result to `url`, retrying transient failures
+    pub async fn deliver(&self, url: &Url, result: &AnalysisResult) -> Result<(), CallbackError> {
+        let body = serde_json::to_vec(result).map_err(|e| CallbackError::Delivery(e.to_string()))?;
+        let delivery_id = Uuid::new_v4();
+        let mut backoff = self.config.initial_backoff;
+
+        for attempt in 1..=self.config.max_attempts {
+            let outcome = match self.check_resolved(url).await {
+                Ok(()) => self.attempt(url, delivery_id, &body).await,
+                // Never retry a target that resolves to an internal address
+                Err(e @ CallbackError::PrivateAddress(_)) => return Err(e),
+                Err(e) => Err((e.to_string(), true)),
+            };
+
+            match outcome {
+                Ok(()) => {
+                    info!(
+                        analysis_id = %result.analysis_id,
+                        delivery_id = %delivery_id,
+                        attempt,
+                        "Delivered analysis callback"
+                    );
+                    return Ok(());
+                }
+                Err((reason, retryable)) => {
+                    warn!(
+                        analysis_id = %result.analysis_id,
+                        delivery_id = %delivery_id,
+                        attempt,
+                        "Analysis callback failed: {}",
+                        reason
+                    );
+                    if !retryable || attempt == self.config.max_attempts {
+                        return Err(CallbackError::Delivery(reason));
+                    }
+                }
+            }
+
+            tokio::time::sleep(backoff).await;
+            backoff = backoff.saturating_mul(2);
+        }
+
+        Err(CallbackError::Delivery("no delivery attempts configured".to_string()))
+    }
+
+    /// One delivery attempt; the error carries whether it is worth retrying
+    async fn attempt(&self, url: &Url, delivery_id: Uuid, body: &[u8]) -> Result<(), (String, bool)> {
+        let timestamp = chrono::Utc::now().timestamp();
+        let request = self
+            .client
+            .post(url.clone())
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, EVENT_ANALYSIS_COMPLETED)
+            .header(DELIVERY_HEADER, delivery_id.to_string())
+            .header(SIGNATURE_HEADER, sign(&self.config.signing_secret, timestamp, body))
+            .body(body.to_vec())
+            .send();
+        let response = match self.faults.intercept(CHAOS_CLIENT, request).await {
+            Ok(sent) => sent.map_err(|e| (e.to_string(), true))?,
+            Err(fault @ InjectedFault::Status(status)) => {
+                return Err((fault.to_string(), status >= 500 || status == 429))
+            }
+            Err(fault @ InjectedFault::Dropped) => return Err((fault.to_string(), true)),
+        };
+
+        let status = response.status();
+        if status.is_success() {
+            Ok(())
+        } else {
+            let retryable = status.is_server_error() || status.as_u16() == 429;
+            Err((format!("HTTP {}", status), retryable))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dispatcher(allow_private_targets: bool) -> CallbackDispatcher {
+        CallbackDispatcher::new(CallbackConfig {
+            signing_secret: "secret".to_string(),
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(1),
+            request_timeout: Duration::from_secs(1),
+            allow_private_targets,
+        })
+    }
+
+    #[test]
+    fn test_signature_format() {
+        let signature = sign("secret", 1700000000, b"{}");
+        let (timestamp, digest) = signature.split_once(",v1=").unwrap();
+        assert_eq!(timestamp, "t=1700000000");
+        assert_eq!(digest.len(), 64);
+        assert_eq!(signature, sign("secret", 1700000000, b"{}"));
+        assert_ne!(signature, sign("other", 1700000000, b"{}"));
+    }
+
+    #[test]
+    fn test_validate_url_rejects_internal_targets() {
+        let d = dispatcher(false);
+        assert!(d.validate_url("https://hooks.example.com/nexus").is_ok());
+        assert!(d.validate_url("ftp://example.com").is_err());
+        for url in [
+            "http://localhost:8080/cb",
+            "http://127.0.0.1/cb",
+            "http://10.0.0.5/cb",
+            "http://169.254.169.254/latest/meta-data",
+            "http://[::1]/cb",
+        ] {
+            assert!(
+                matches!(d.validate_url(url), Err(CallbackError::PrivateAddress(_))),
+                "{} should be rejected",
+                url
+            );
+        }
+        assert!(dispatcher(true).validate_url("http://localhost:8080/cb").is_ok());
+    }
+}
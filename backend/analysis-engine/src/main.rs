@@ -2,19 +2,19 @@ use std::env;
 use std::sync::Arc;
 
 use axum::{
-    extract::{Multipart, Path, State},
-    response::Json,
-    http::StatusCode,
+    extract::{Extension, Multipart, Path, Query, State},
+    response::{IntoResponse, Json, Response},
+    http::{header, StatusCode},
     routing::{get, post},
     Router,
 };
 use serde::{Deserialize, Serialize};
 use tower::ServiceBuilder;
-use tower_http::{cors::CorsLayer, trace::TraceLayer};
+use tower_http::trace::TraceLayer;
 use tracing::error;
 use uuid::Uuid;
 use tokio::net::TcpListener;
-use tracing::info;
+use tracing::{info, warn};
 
 mod analyzers;
 mod models;
@@ -23,15 +23,26 @@ mod storage;
 mod scanners;
 mod sandbox;
 mod queue;
+mod middleware;
+mod reports;
+mod callbacks;
+#[cfg(test)]
+mod testing;
 
 use crate::scanners::Scanner;
 use crate::analyzers::{AnalysisEngine, AnalysisEngineConfig, FileAnalysisRequest, AnalysisOptions, AnalysisPriority};
+use crate::analyzers::flags::{Analyzer, AnalyzerFlags, AnalyzerStatus};
 use crate::analyzers::hash_analyzer::{HashInfo, HashType};
 use crate::models::analysis_result::{AnalysisResult, ThreatVerdict, FileMetadata};
 use crate::utils::file_handler::FileHandler;
-use crate::storage::S3Client;
+use crate::storage::{Database, S3Client};
+use crate::reports::{AnalysisReport, ReportFormat};
+use crate::callbacks::{CallbackConfig, CallbackDispatcher};
+use crate::queue::shutdown::{self, ShutdownCoordinator};
+use crate::middleware::{ApiKeyContext, QuotaManager};
 use crate::scanners::file_scanner::{FileScanner, FileScannerConfig};
-use crate::scanners::url_scanner::{UrlScanner, UrlScannerConfig};
+use crate::scanners::url_scanner::{UrlScanner, UrlScannerConfig, UrlScanResult};
+use crate::scanners::{url_cache, ScanVerdict, UrlCacheConfig, UrlScanCache};
 use chrono::Utc;
 use std::time::Duration;
 use tokio::sync::Mutex;
@@ -43,6 +54,12 @@ pub struct AppState {
     s3_client: Arc<S3Client>,
     file_scanner: Arc<FileScanner>,
     url_scanner: Arc<UrlScanner>,
+    url_cache: Arc<UrlScanCache>,
+    shutdown: Arc<ShutdownCoordinator>,
+    quota_manager: Arc<QuotaManager>,
+    database: Arc<Database>,
+    callbacks: Option<Arc<CallbackDispatcher>>,
+    analyzer_flags: AnalyzerFlags,
     database_url: String,
     redis_url: String,
 }
@@ -52,6 +69,18 @@ struct AnalysisRequest {
     priority: Option<u8>,
     bounty_id: Option<String>,
     metadata: Option<serde_json::Value>,
+    /// Receives the final `AnalysisResult` when set (see `callbacks`)
+    callback_url: Option<String>,
+}
+#[derive(Deserialize)]
+struct UrlAnalysisRequest {
+    url: String,
+    bounty_id: Option<String>,
+    /// Rescan even when a fresh verdict is cached; only honored for bounty
+    /// submissions, which pay for the scan, from the bounty's creator or an
+    /// engine owner taking part in it
+    #[serde(default)]
+    force_refresh: bool,
 }
 #[derive(Serialize)]
 struct AnalysisResponse {
@@ -59,6 +88,19 @@ struct AnalysisResponse {
     status: String,
     message: String,
 }
+/// Verdict served from the URL cache instead of a new scan
+#[derive(Serialize)]
+struct CachedUrlAnalysisResponse {
+    /// Analysis that produced the cached result
+    analysis_id: String,
+    status: String,
+    message: String,
+    verdict: ScanVerdict,
+    confidence_score: f32,
+    /// Seconds since the cached scan ran
+    age: u64,
+    result: UrlScanResult,
+}
 #[derive(Serialize)]
 struct HealthResponse {
     status: String,
@@ -71,15 +113,27 @@ struct EngineStatus {
     static_analyzer: bool,
     hash_analyzer: bool,
     yara_engine: bool,
+    analyzers: Vec<AnalyzerStatus>,
+}
+
+impl EngineStatus {
+    async fn current(flags: &AnalyzerFlags, tenant: Option<&str>) -> Self {
+        let analyzers = flags.status(tenant).await;
+        let active = |analyzer: Analyzer| analyzers.iter().any(|s| s.analyzer == analyzer && s.active);
+
+        Self {
+            static_analyzer: active(Analyzer::Static),
+            hash_analyzer: active(Analyzer::Hash),
+            yara_engine: active(Analyzer::Yara),
+            analyzers,
+        }
+    }
 }
 
 #[tokio::main]
 async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
     // Initialize tracing
-    tracing_subscriber::fmt()
-        .with_target(false)
-        .with_timer(tracing_subscriber::fmt::time::time())
-        .init();
+    shared::observability::init_service_logging(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))?;
 
     info!("Starting Nexus-Security Analysis Engine");
 
@@ -94,8 +148,18 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
         .unwrap_or(8002);
     let yara_rule_path = env::var("YARA_RULE_PATH")
         .unwrap_or_else(|_| "./rules".to_string());
+    let authenticode_trust_store = env::var("AUTHENTICODE_TRUST_STORE")
+        .unwrap_or_else(|_| "/etc/ssl/certs/ca-certificates.crt".to_string());
     let upload_dir = env::var("UPLOAD_DIR")
         .unwrap_or_else(|_| "./temp/nexus-uploads".to_string());
+    let flag_cache_ttl_secs = env::var("FEATURE_FLAG_CACHE_TTL_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(shared::feature_flags::DEFAULT_CACHE_TTL.as_secs());
+    let drain_timeout_secs = env::var("SHUTDOWN_DRAIN_TIMEOUT_SECS")
+        .unwrap_or_else(|_| "30".to_string())
+        .parse::<u64>()
+        .unwrap_or(30);
 
     // Initialize database connection pool
     info!("Connecting to database...");
@@ -103,16 +167,21 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
         .await
         .expect("Failed to connect to database");
     info!("Database connection established");
+    let database = Arc::new(Database::from_pool(db_pool.clone()).await?);
 
     // Initialize Redis client
     info!("Connecting to Redis...");
     let redis_client = redis::Client::open(redis_url.clone())
         .expect("Failed to create Redis client");
-    redis_client
+    let redis_conn = redis_client
         .get_multiplexed_async_connection()
         .await
         .expect("Failed to connect to Redis");
     info!("Redis connection established");
+    let analyzer_flags = AnalyzerFlags::new(shared::feature_flags::FeatureFlagClient::new(
+        redis_conn.clone(),
+        Duration::from_secs(flag_cache_ttl_secs),
+    ));
 
     // Initialize S3 client
     info!("Initializing S3 client...");
@@ -123,6 +192,15 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
     info!("Initializing analysis engines...");
     let mut config = AnalysisEngineConfig::default();
     config.yara_engine.rules_directory = std::path::PathBuf::from(yara_rule_path);
+    match config.static_analyzer.authenticode
+        .load_trust_store(std::path::Path::new(&authenticode_trust_store))
+    {
+        Ok(count) => info!("Loaded {} Authenticode trust anchors from {}", count, authenticode_trust_store),
+        Err(e) => warn!(
+            "Cannot read Authenticode trust store {}: {}; no signature will be reported as trusted",
+            authenticode_trust_store, e
+        ),
+    }
     let analysis_engine = Arc::new(Mutex::new(AnalysisEngine::new(config)?));
     let file_handler = Arc::new(FileHandler::new(&upload_dir)?);
 
@@ -130,6 +208,18 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
     info!("Initializing scanners...");
     let file_scanner = Arc::new(<FileScanner as Scanner>::new(FileScannerConfig::default())?);
     let url_scanner = Arc::new(<UrlScanner as Scanner>::new(UrlScannerConfig::default())?);
+    let url_cache = Arc::new(UrlScanCache::new(redis_conn.clone(), UrlCacheConfig::from_env()));
+
+    let shutdown_coordinator = Arc::new(ShutdownCoordinator::new());
+
+    // Shared by inbound routes and outbound callbacks so one seed drives both
+    let faults = shared::chaos::FaultInjector::from_env();
+
+    let callbacks = CallbackConfig::from_env()
+        .map(|c| Arc::new(CallbackDispatcher::new(c).with_fault_injector(faults.clone())));
+    if callbacks.is_none() {
+        warn!("CALLBACK_SIGNING_SECRET not set; analysis callbacks are disabled");
+    }
 
     // Create application state
     let app_state = AppState {
@@ -138,6 +228,12 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
         s3_client: s3_client.clone(),
         file_scanner,
         url_scanner,
+        url_cache,
+        shutdown: shutdown_coordinator.clone(),
+        quota_manager: Arc::new(QuotaManager::new(db_pool.clone(), redis_conn)),
+        database,
+        callbacks,
+        analyzer_flags,
         database_url,
         redis_url,
     };
@@ -150,6 +246,8 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
     let consumer_db_pool = db_pool.clone();
     let consumer_s3_client = s3_client.clone();
     let consumer_analysis_engine = app_state.analysis_engine.clone();
+    let consumer_shutdown = shutdown_coordinator.clone();
+    let consumer_flags = app_state.analyzer_flags.clone();
 
     tokio::spawn(async move {
         if let Err(e) = crate::queue::consumer::start_analysis_worker(
@@ -157,6 +255,8 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
             consumer_db_pool,
             consumer_s3_client,
             consumer_analysis_engine,
+            consumer_flags,
+            consumer_shutdown,
         )
         .await
         {
@@ -166,43 +266,85 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
     info!("Queue consumer worker started");
 
     // Build the application router
-    let app = Router::new()
-        .route("/health", get(health_check))
+    // Everything except the health check requires an API key and counts against its quota
+    let metered_routes = Router::new()
         .route("/analyze/file", post(analyze_file))
         .route("/analyze/url", post(analyze_url))
         .route("/analyze/hash", post(analyze_hash))
         .route("/analysis/:id", get(get_analysis_result))
         .route("/analysis/:id/detailed", get(get_detailed_analysis))
         .route("/engines/status", get(engines_status))
+        .route_layer(axum::middleware::from_fn_with_state(
+            app_state.clone(),
+            crate::middleware::api_key::require_api_key,
+        ));
+
+    let app = Router::new()
+        .route("/health", get(health_check))
+        .merge(metered_routes)
+        .merge(shared::observability::log_level_routes())
+        .merge(shared::feature_flags::feature_flag_routes(app_state.analyzer_flags.client().clone()))
         .with_state(app_state)
-        .layer(CorsLayer::permissive())
-        .layer(TraceLayer::new_for_http());
+        .layer(axum::middleware::from_fn_with_state(
+            shared::request_signing::SignatureVerifier::from_env()?
+                .allow_unsigned_payload("/analyze/file"),
+            shared::request_signing::verify_signature_middleware,
+        ))
+        .layer(axum::middleware::from_fn_with_state(faults, shared::chaos::chaos_middleware))
+        .layer(shared::http_security::CorsPolicy::from_env().layer())
+        .layer(axum::middleware::from_fn(shared::http_security::security_headers_middleware))
+        .layer(TraceLayer::new_for_http())
+        .layer(axum::middleware::from_fn(shared::observability::log_context_middleware));
 
     // Start the server
     let addr = format!("0.0.0.0:{}", port);
     info!("Analysis Engine listening on {}", addr);
 
     let listener = TcpListener::bind(&addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown::shutdown_signal(shutdown_coordinator.clone()))
+        .await?;
+
+    // Give running analyses a chance to finish, then hand the rest to other replicas
+    let unfinished = shutdown_coordinator
+        .wait_for_drain(Duration::from_secs(drain_timeout_secs))
+        .await;
+    if unfinished.is_empty() {
+        info!("All in-flight analyses drained");
+    } else {
+        warn!(
+            "{} analyses still running after {}s, re-queueing",
+            unfinished.len(),
+            drain_timeout_secs
+        );
+        if let Err(e) = shutdown::requeue_unfinished(&redis_client, &db_pool, &unfinished).await {
+            error!("Failed to re-queue unfinished analyses: {}", e);
+        }
+    }
 
+    info!("Analysis Engine shut down");
     Ok(())
 }
 
-async fn health_check() -> Json<HealthResponse> {
-    Json(HealthResponse { 
-        status: "healthy".to_string(), 
+async fn health_check(State(state): State<AppState>) -> (StatusCode, Json<HealthResponse>) {
+    // Report draining so load balancers stop routing to this replica
+    let (code, status) = if state.shutdown.is_draining() {
+        (StatusCode::SERVICE_UNAVAILABLE, "draining")
+    } else {
+        (StatusCode::OK, "healthy")
+    };
+
+    (code, Json(HealthResponse {
+        status: status.to_string(), 
         service: "analysis-engine".to_string(), 
         version: env!("CARGO_PKG_VERSION").to_string(), 
-        engines: EngineStatus { 
-            static_analyzer: true, 
-            hash_analyzer: true, 
-            yara_engine: true, 
-        }, 
-    })
+        engines: EngineStatus::current(&state.analyzer_flags, None).await,
+    }))
 }
 
 async fn analyze_file(
     State(state): State<AppState>,
+    Extension(api_key): Extension<ApiKeyContext>,
     mut multipart: Multipart,
 ) -> Result<Json<AnalysisResponse>, StatusCode> {
     info!("Received file analysis request");
@@ -244,15 +386,57 @@ async fn analyze_file(
         analysis_options: AnalysisOptions::default(),
     };
 
-    let mut engine_guard = state.analysis_engine.lock().await;
-    let analysis_result = engine_guard.analyze_file(request).await.map_err(|e| {
-        error!("Analysis failed: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    let callback_url = _analysis_req.and_then(|r| r.callback_url);
+    if let Some(raw_url) = callback_url {
+        let dispatcher = state.callbacks.clone().ok_or_else(|| {
+            warn!("Callback requested but callbacks are not configured");
+            StatusCode::BAD_REQUEST
+        })?;
+        let url = dispatcher.validate_url(&raw_url).map_err(|e| {
+            warn!("Rejected callback URL: {}", e);
+            StatusCode::BAD_REQUEST
+        })?;
+        if state.shutdown.is_draining() {
+            return Err(StatusCode::SERVICE_UNAVAILABLE);
+        }
 
-    // TODO: Store analysis result in database via proper persistence layer
+        let analysis_id = Uuid::parse_str(&analysis_id).expect("generated UUID");
+        tokio::spawn(async move {
+            let mut analysis_result = match run_file_analysis(&state, &api_key, request).await {
+                Ok(result) => result,
+                Err(e) => {
+                    error!("Analysis {} failed: {}", analysis_id, e);
+                    return;
+                }
+            };
+            // Keep the id the submitter was given
+            analysis_result.analysis_id = analysis_id;
+            persist_analysis(&state, &analysis_result).await;
+
+            if let Err(e) = dispatcher.deliver(&url, &analysis_result).await {
+                error!("Giving up on callback for analysis {}: {}", analysis_id, e);
+            }
+        });
+
+        return Ok(Json(AnalysisResponse {
+            analysis_id: analysis_id.to_string(),
+            status: "submitted".to_string(),
+            message: "File Analysis started; result will be sent to the callback URL".to_string(),
+        }));
+    }
 
-    info!("Analysis completed: {:?}", analysis_result.analysis_id);
+    let analysis_result = run_file_analysis(&state, &api_key, request)
+        .await
+        .map_err(|e| {
+            error!("Analysis failed: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    persist_analysis(&state, &analysis_result).await;
+
+    info!(
+        "Analysis completed: {:?} (user {})",
+        analysis_result.analysis_id, api_key.user_id
+    );
 
     Ok(Json(AnalysisResponse {
         analysis_id: analysis_result.analysis_id.to_string(),
@@ -261,30 +445,107 @@ async fn analyze_file(
     }))
 }
 
+/// Run the engine and charge sandbox time to the caller's key
+async fn run_file_analysis(
+    state: &AppState,
+    api_key: &ApiKeyContext,
+    mut request: FileAnalysisRequest,
+) -> anyhow::Result<AnalysisResult> {
+    let tenant = api_key.user_id.to_string();
+    state
+        .analyzer_flags
+        .apply(Some(&tenant), &mut request.analysis_options)
+        .await;
+
+    let mut engine_guard = state.analysis_engine.lock().await;
+    let analysis_result = engine_guard.analyze_file(request).await?;
+    drop(engine_guard);
+
+    if analysis_result.behavioral_analysis.is_some() {
+        state
+            .quota_manager
+            .record_sandbox_usage(
+                api_key.key_id,
+                analysis_result.total_processing_time_ms.unwrap_or(0),
+            )
+            .await;
+    }
+
+    Ok(analysis_result)
+}
+
+async fn persist_analysis(state: &AppState, analysis_result: &AnalysisResult) {
+    if let Err(e) = state.database.save_analysis_result(analysis_result).await {
+        error!("Failed to persist analysis {}: {}", analysis_result.analysis_id, e);
+    }
+}
+
 async fn analyze_url(
-    State(state): State<AppState>, 
+    State(state): State<AppState>,
+    Extension(api_key): Extension<ApiKeyContext>,
     Json(request): Json<serde_json::Value>,
-) -> Result<Json<AnalysisResponse>, StatusCode> {
-    let url = request.get("url")
-        .and_then(|v| v.as_str())
-        .ok_or(StatusCode::BAD_REQUEST)?.to_string();
+) -> Result<Response, StatusCode> {
+    let request: UrlAnalysisRequest =
+        serde_json::from_value(request).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let canonical_url = url_cache::canonicalize_url(&request.url).map_err(|e| {
+        warn!("Rejected URL submission: {}", e);
+        StatusCode::BAD_REQUEST
+    })?;
 
-    let analysis_id = Uuid::new_v4().to_string();
+    let force_refresh = match (request.force_refresh, request.bounty_id.as_deref()) {
+        (false, _) => false,
+        (true, None) => {
+            info!("Ignoring force_refresh for {} outside a bounty submission", canonical_url);
+            false
+        }
+        // Only someone the rescan is paid for may bypass the cache
+        (true, Some(bounty_id)) => {
+            let bounty_id = Uuid::parse_str(bounty_id).map_err(|_| StatusCode::BAD_REQUEST)?;
+            match state.database.is_bounty_party(&bounty_id, &api_key.user_id).await {
+                Ok(Some(true)) => true,
+                Ok(Some(false)) => return Err(StatusCode::FORBIDDEN),
+                Ok(None) => return Err(StatusCode::NOT_FOUND),
+                Err(e) => {
+                    error!("Failed to check bounty {} for a URL rescan: {}", bounty_id, e);
+                    return Err(StatusCode::INTERNAL_SERVER_ERROR);
+                }
+            }
+        }
+    };
+
+    if !force_refresh {
+        if let Some(cached) = state.url_cache.get(&canonical_url).await {
+            let age = cached.age(Utc::now());
+            info!("Serving cached verdict for {} ({}s old)", canonical_url, age);
+            return Ok(Json(CachedUrlAnalysisResponse {
+                analysis_id: cached.analysis_id.to_string(),
+                status: "cached".to_string(),
+                message: "URL verdict served from cache".to_string(),
+                verdict: cached.result.base.verdict.clone(),
+                confidence_score: cached.result.base.confidence_score,
+                age,
+                result: cached.result,
+            })
+            .into_response());
+        }
+    }
+
+    let analysis_id = Uuid::new_v4();
 
     let state_clone = state.clone();
-    let analysis_id_clone = analysis_id.clone();
 
     tokio::spawn(async move {
-        if let Err(e) = perform_url_analysis(state_clone, &analysis_id_clone, &url).await {
-            error!("URL analysis failed for {}: {}", analysis_id_clone, e);
+        if let Err(e) = perform_url_analysis(state_clone, analysis_id, &request.url, &canonical_url).await {
+            error!("URL analysis failed for {}: {}", analysis_id, e);
         }
     });
 
     Ok(Json(AnalysisResponse {
-        analysis_id,
+        analysis_id: analysis_id.to_string(),
         status: "submitted".to_string(),
         message: "URL Analysis started successfully".to_string(),
-    }))
+    })
+    .into_response())
 }
 
 async fn analyze_hash(
@@ -332,27 +593,62 @@ async fn get_analysis_result(
     })))
 }
 
+#[derive(Deserialize)]
+struct ReportQuery {
+    format: Option<ReportFormat>,
+}
+
 async fn get_detailed_analysis(
     Path(id): Path<String>,
-    State(_state): State<AppState>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
+    Query(query): Query<ReportQuery>,
+    State(state): State<AppState>,
+) -> Result<Response, StatusCode> {
     info!("Fetching detailed analysis for: {}", id);
 
-    // TODO: Implement detailed analysis retrieval
-    Ok(Json(serde_json::json!({
-        "analysis_id": id,
-        "detailed_result": "Detailed analysis data here"
-    })))
+    let analysis_id = Uuid::parse_str(&id).map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let result = state
+        .database
+        .get_analysis_result(&analysis_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load analysis {}: {}", analysis_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let report = AnalysisReport::from_result(&result);
+
+    let response = match query.format.unwrap_or_default() {
+        ReportFormat::Json => Json(report).into_response(),
+        ReportFormat::Html => (
+            [(header::CONTENT_TYPE, "text/html; charset=utf-8".to_string())],
+            reports::html::render(&report),
+        )
+            .into_response(),
+        ReportFormat::Pdf => (
+            [
+                (header::CONTENT_TYPE, "application/pdf".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"analysis-{}.pdf\"", analysis_id),
+                ),
+            ],
+            reports::pdf::render(&report),
+        )
+            .into_response(),
+    };
+
+    Ok(response)
 }
 
+/// Analyzers as seen by the caller, including per-tenant flag overrides
 async fn engines_status(
-    State(_state): State<AppState>
+    State(state): State<AppState>,
+    Extension(api_key): Extension<ApiKeyContext>,
 ) -> Json<EngineStatus> {
-    Json(EngineStatus {
-        static_analyzer: true,
-        hash_analyzer: true,
-        yara_engine: true,
-    })
+    let tenant = api_key.user_id.to_string();
+    Json(EngineStatus::current(&state.analyzer_flags, Some(&tenant)).await)
 }
 
 async fn perform_file_analysis(
@@ -362,12 +658,13 @@ async fn perform_file_analysis(
     _request: Option<AnalysisRequest>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let file_data = state.file_handler.get_file(file_path).await?;
-    let req = FileAnalysisRequest {
+    let mut req = FileAnalysisRequest {
         filename: file_path.to_string(),
         file_data,
         file_hashes: None,
         analysis_options: AnalysisOptions::default(),
     };
+    state.analyzer_flags.apply(None, &mut req.analysis_options).await;
 
     let mut engine_guard = state.analysis_engine.lock().await;
     engine_guard.analyze_file(req).await?;
@@ -377,8 +674,9 @@ async fn perform_file_analysis(
 
 async fn perform_url_analysis(
     state: AppState,
-    analysis_id: &str,
+    analysis_id: Uuid,
     url: &str,
+    canonical_url: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
     info!("Starting URL analysis for: {} ({})", analysis_id, url);
 
@@ -387,6 +685,8 @@ async fn perform_url_analysis(
 
     info!("URL analysis completed for: {} - Verdict: {:?}", analysis_id, scan_result.base.verdict);
 
+    state.url_cache.put(canonical_url, analysis_id, &scan_result).await;
+
     // TODO: Store URL scan results in database with proper AnalysisResult structure
 
     Ok(())
@@ -0,0 +1,329 @@
+//! API key authentication and per-key daily quotas
+//!
+//! Every analysis request must carry an `X-API-Key` header matching an active
+//! row in `api_keys`. Usage is metered per key and UTC day in Redis:
+//! - submissions: one per analysis request
+//! - bytes scanned: taken from the request `Content-Length`
+//! - sandbox minutes: recorded after an analysis that ran behavioral analysis
+//!
+//! Responses carry `X-RateLimit-*` headers describing the submission quota.
+
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use redis::aio::MultiplexedConnection;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{PgPool, Row};
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use crate::AppState;
+
+const API_KEY_HEADER: &str = "x-api-key";
+
+/// Counters are kept a little past the end of the day they cover
+const COUNTER_TTL_SECS: i64 = 2 * 24 * 60 * 60;
+
+/// Metered resources
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaMetric {
+    Submissions,
+    BytesScanned,
+    SandboxMinutes,
+}
+
+impl QuotaMetric {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuotaMetric::Submissions => "submissions",
+            QuotaMetric::BytesScanned => "bytes",
+            QuotaMetric::SandboxMinutes => "sandbox_minutes",
+        }
+    }
+}
+
+/// Daily limits attached to an API key
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DailyQuota {
+    pub submissions: i64,
+    pub bytes_scanned: i64,
+    pub sandbox_minutes: i64,
+}
+
+impl DailyQuota {
+    pub fn limit_for(&self, metric: QuotaMetric) -> i64 {
+        match metric {
+            QuotaMetric::Submissions => self.submissions,
+            QuotaMetric::BytesScanned => self.bytes_scanned,
+            QuotaMetric::SandboxMinutes => self.sandbox_minutes,
+        }
+    }
+}
+
+/// Authenticated caller, inserted into request extensions by the middleware
+#[derive(Debug, Clone)]
+pub struct ApiKeyContext {
+    pub key_id: Uuid,
+    pub user_id: Uuid,
+    pub quota: DailyQuota,
+}
+
+/// Rate limit state reported back to the caller
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitInfo {
+    pub limit: i64,
+    pub remaining: i64,
+    pub reset_at: DateTime<Utc>,
+}
+
+impl RateLimitInfo {
+    pub fn new(limit: i64, used: i64, reset_at: DateTime<Utc>) -> Self {
+        Self {
+            limit,
+            remaining: (limit - used).max(0),
+            reset_at,
+        }
+    }
+
+    pub fn apply_headers(&self, headers: &mut HeaderMap) {
+        headers.insert("X-RateLimit-Limit", HeaderValue::from(self.limit));
+        headers.insert("X-RateLimit-Remaining", HeaderValue::from(self.remaining));
+        headers.insert("X-RateLimit-Reset", HeaderValue::from(self.reset_at.timestamp()));
+    }
+}
+
+/// Start of the next UTC day, when all daily counters reset
+pub fn next_reset(now: DateTime<Utc>) -> DateTime<Utc> {
+    (now.date_naive() + Duration::days(1))
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+        .and_utc()
+}
+
+fn counter_key(key_id: Uuid, metric: QuotaMetric, now: DateTime<Utc>) -> String {
+    format!("quota:{}:{}:{}", key_id, now.format("%Y%m%d"), metric.as_str())
+}
+
+/// Looks up API keys and meters their usage
+pub struct QuotaManager {
+    db_pool: PgPool,
+    redis_conn: MultiplexedConnection,
+}
+
+impl QuotaManager {
+    pub fn new(db_pool: PgPool, redis_conn: MultiplexedConnection) -> Self {
+        Self { db_pool, redis_conn }
+    }
+
+    /// Resolve a raw API key to its context, or `None` if unknown/inactive/expired
+    pub async fn authenticate(&self, api_key: &str) -> Result<Option<ApiKeyContext>, sqlx::Error> {
+        let key_hash = hex::encode(Sha256::digest(api_key.as_bytes()));
+
+        let row = sqlx::query(
+            r#"
+            UPDATE api_keys
+            SET last_used_at = NOW()
+            WHERE key_hash = $1
+              AND is_active = TRUE
+              AND (expires_at IS NULL OR expires_at > NOW())
+            RETURNING id, user_id, daily_submission_quota, daily_bytes_quota,
+                      daily_sandbox_minutes_quota
+            "#,
+        )
+        .bind(key_hash)
+        .fetch_optional(&self.db_pool)
+        .await?;
+
+        Ok(row.map(|row| ApiKeyContext {
+            key_id: row.get("id"),
+            user_id: row.get("user_id"),
+            quota: DailyQuota {
+                submissions: row.get::<i32, _>("daily_submission_quota") as i64,
+                bytes_scanned: row.get("daily_bytes_quota"),
+                sandbox_minutes: row.get::<i32, _>("daily_sandbox_minutes_quota") as i64,
+            },
+        }))
+    }
+
+    /// Current usage of a metric for today
+    pub async fn usage(&self, key_id: Uuid, metric: QuotaMetric) -> redis::RedisResult<i64> {
+        let mut conn = self.redis_conn.clone();
+        let used: Option<i64> = conn.get(counter_key(key_id, metric, Utc::now())).await?;
+        Ok(used.unwrap_or(0))
+    }
+
+    /// Add `amount` to today's counter and return the new total
+    pub async fn consume(
+        &self,
+        key_id: Uuid,
+        metric: QuotaMetric,
+        amount: i64,
+    ) -> redis::RedisResult<i64> {
+        let key = counter_key(key_id, metric, Utc::now());
+        let mut conn = self.redis_conn.clone();
+        let (total,): (i64,) = redis::pipe()
+            .atomic()
+            .incr(&key, amount)
+            .expire(&key, COUNTER_TTL_SECS)
+            .ignore()
+            .query_async(&mut conn)
+            .await?;
+        Ok(total)
+    }
+
+    /// Record sandbox time used by a completed analysis (rounded up to whole minutes)
+    pub async fn record_sandbox_usage(&self, key_id: Uuid, processing_time_ms: u64) {
+        let minutes = processing_time_ms.div_ceil(60_000).max(1) as i64;
+        if let Err(e) = self.consume(key_id, QuotaMetric::SandboxMinutes, minutes).await {
+            warn!("Failed to record sandbox usage for key {}: {}", key_id, e);
+        }
+    }
+}
+
+fn quota_exceeded(metric: QuotaMetric, info: &RateLimitInfo) -> Response {
+    let mut response = (
+        StatusCode::TOO_MANY_REQUESTS,
+        Json(serde_json::json!({
+            "error": "quota_exceeded",
+            "message": format!("Daily {} quota exceeded", metric.as_str()),
+            "reset_at": info.reset_at,
+        })),
+    )
+        .into_response();
+
+    let retry_after = (info.reset_at - Utc::now()).num_seconds().max(0);
+    response
+        .headers_mut()
+        .insert("Retry-After", HeaderValue::from(retry_after));
+    info.apply_headers(response.headers_mut());
+    response
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response {
+    (status, Json(serde_json::json!({ "error": message }))).into_response()
+}
+
+/// Authenticate the caller and enforce daily quotas before the handler runs
+pub async fn require_api_key(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let Some(api_key) = request
+        .headers()
+        .get(API_KEY_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_owned)
+    else {
+        return error_response(StatusCode::UNAUTHORIZED, "Missing X-API-Key header");
+    };
+
+    let context = match state.quota_manager.authenticate(&api_key).await {
+        Ok(Some(context)) => context,
+        Ok(None) => return error_response(StatusCode::UNAUTHORIZED, "Invalid or expired API key"),
+        Err(e) => {
+            error!("API key lookup failed: {}", e);
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, "Internal server error");
+        }
+    };
+
+    let quotas = &state.quota_manager;
+    let reset_at = next_reset(Utc::now());
+    let quota = context.quota;
+
+    // Only analysis submissions are metered; reads just report the current state
+    let metered = request.method() == Method::POST;
+    let usage = if metered {
+        quotas.consume(context.key_id, QuotaMetric::Submissions, 1).await
+    } else {
+        quotas.usage(context.key_id, QuotaMetric::Submissions).await
+    };
+    let submissions_used = match usage {
+        Ok(used) => used,
+        Err(e) => {
+            error!("Quota counter unavailable: {}", e);
+            return error_response(StatusCode::SERVICE_UNAVAILABLE, "Quota service unavailable");
+        }
+    };
+
+    let info = RateLimitInfo::new(quota.submissions, submissions_used, reset_at);
+    if metered && submissions_used > quota.submissions {
+        return quota_exceeded(QuotaMetric::Submissions, &info);
+    }
+
+    if metered {
+        let content_length = request
+            .headers()
+            .get(axum::http::header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<i64>().ok())
+            .unwrap_or(0);
+
+        let checks = [
+            (QuotaMetric::BytesScanned, quotas.consume(context.key_id, QuotaMetric::BytesScanned, content_length).await),
+            (QuotaMetric::SandboxMinutes, quotas.usage(context.key_id, QuotaMetric::SandboxMinutes).await),
+        ];
+        for (metric, used) in checks {
+            match used {
+                Ok(used) if used > quota.limit_for(metric) => {
+                    let info = RateLimitInfo::new(quota.limit_for(metric), used, reset_at);
+                    return quota_exceeded(metric, &info);
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    error!("Quota counter unavailable: {}", e);
+                    return error_response(StatusCode::SERVICE_UNAVAILABLE, "Quota service unavailable");
+                }
+            }
+        }
+    }
+
+    shared::observability::set_log_user_id(context.user_id);
+    request.extensions_mut().insert(context);
+
+    let mut response = next.run(request).await;
+    info.apply_headers(response.headers_mut());
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_next_reset_is_utc_midnight() {
+        let now = Utc.with_ymd_and_hms(2024, 5, 10, 17, 45, 0).unwrap();
+        assert_eq!(next_reset(now), Utc.with_ymd_and_hms(2024, 5, 11, 0, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_rate_limit_headers() {
+        let reset_at = Utc.with_ymd_and_hms(2024, 5, 11, 0, 0, 0).unwrap();
+        let info = RateLimitInfo::new(100, 130, reset_at);
+        assert_eq!(info.remaining, 0);
+
+        let mut headers = HeaderMap::new();
+        info.apply_headers(&mut headers);
+        assert_eq!(headers["X-RateLimit-Limit"], "100");
+        assert_eq!(headers["X-RateLimit-Remaining"], "0");
+        assert_eq!(headers["X-RateLimit-Reset"], reset_at.timestamp().to_string());
+    }
+
+    #[test]
+    fn test_counter_keys_are_per_day_and_metric() {
+        let key_id = Uuid::nil();
+        let now = Utc.with_ymd_and_hms(2024, 5, 10, 12, 0, 0).unwrap();
+        assert_eq!(
+            counter_key(key_id, QuotaMetric::BytesScanned, now),
+            format!("quota:{}:20240510:bytes", key_id)
+        );
+    }
+}
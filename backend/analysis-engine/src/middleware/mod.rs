@@ -0,0 +1,3 @@
+pub mod api_key;
+
+pub use api_key::{ApiKeyContext, QuotaManager};
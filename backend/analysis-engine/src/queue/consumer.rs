@@ -7,10 +7,12 @@ use tokio::sync::Mutex;
 use tracing::{info, warn, error};
 
 use crate::analyzers::{AnalysisEngine, FileAnalysisRequest, AnalysisOptions};
+use crate::analyzers::flags::AnalyzerFlags;
 use crate::storage::S3Client;
+use super::shutdown::{AnalysisStage, InFlightGuard, ShutdownCoordinator};
 
 /// Redis queue key for analysis tasks
-const ANALYSIS_QUEUE_KEY: &str = "analysis_queue";
+pub(crate) const ANALYSIS_QUEUE_KEY: &str = "analysis_queue";
 
 /// WebSocket event channel for real-time updates
 const WS_CHANNEL_ANALYSIS_UPDATED: &str = "events:analysis_updated";
@@ -36,10 +38,17 @@ pub async fn start_analysis_worker(
     db_pool: PgPool,
     s3_client: Arc<S3Client>,
     analysis_engine: Arc<Mutex<AnalysisEngine>>,
+    analyzer_flags: AnalyzerFlags,
+    shutdown: Arc<ShutdownCoordinator>,
 ) -> Result<()> {
     info!("Starting analysis queue consumer worker");
 
     loop {
+        if shutdown.is_draining() {
+            info!("Shutdown in progress, analysis queue consumer stopped accepting work");
+            return Ok(());
+        }
+
         // Step 1: Listen to Redis analysis queue (blocking pop)
         let submission_id = match pop_from_queue(&redis_client).await {
             Ok(Some(id)) => id,
@@ -55,8 +64,21 @@ pub async fn start_analysis_worker(
             }
         };
 
+        // Shutdown may have started while blocked on BRPOP; hand the item back
+        if shutdown.is_draining() {
+            super::shutdown::requeue_unfinished(
+                &redis_client,
+                &db_pool,
+                &[(submission_id, AnalysisStage::Fetching)],
+            )
+            .await?;
+            return Ok(());
+        }
+
         info!("Received submission for analysis: {}", submission_id);
 
+        let guard = shutdown.track(submission_id);
+
         // Process the submission
         if let Err(e) = process_submission(
             submission_id,
@@ -64,6 +86,8 @@ pub async fn start_analysis_worker(
             &db_pool,
             &s3_client,
             &analysis_engine,
+            &analyzer_flags,
+            &guard,
         )
         .await
         {
@@ -112,6 +136,8 @@ async fn process_submission(
     db_pool: &PgPool,
     s3_client: &S3Client,
     analysis_engine: &Arc<Mutex<AnalysisEngine>>,
+    analyzer_flags: &AnalyzerFlags,
+    guard: &InFlightGuard,
 ) -> Result<()> {
     // Step 2: Fetch submission from database
     let submission = fetch_submission_from_db(db_pool, submission_id).await?;
@@ -136,6 +162,7 @@ async fn process_submission(
     .await?;
 
     // Step 3: Download file from S3
+    guard.set_stage(AnalysisStage::Downloading);
     let file_path = submission
         .file_path
         .as_ref()
@@ -158,13 +185,18 @@ async fn process_submission(
         .clone()
         .unwrap_or_else(|| "unknown".to_string());
 
-    let analysis_request = FileAnalysisRequest {
+    let mut analysis_request = FileAnalysisRequest {
         filename: filename.clone(),
         file_data,
         file_hashes: None,
         analysis_options: AnalysisOptions::default(), // Enable all analyzers
     };
+    let tenant = submission.submitter_id.map(|id| id.to_string());
+    analyzer_flags
+        .apply(tenant.as_deref(), &mut analysis_request.analysis_options)
+        .await;
 
+    guard.set_stage(AnalysisStage::Analyzing);
     let mut engine = analysis_engine.lock().await;
     let analysis_result = engine
         .analyze_file(analysis_request)
@@ -181,6 +213,7 @@ async fn process_submission(
     );
 
     // Step 5: Store results in database
+    guard.set_stage(AnalysisStage::Storing);
     let (is_malicious, confidence_score) = calculate_verdict(&analysis_result);
 
     store_analysis_results(
@@ -340,7 +373,7 @@ async fn publish_ws_event(
     let message = serde_json::to_string(payload)
         .map_err(|e| anyhow!("Failed to serialize WebSocket event: {}", e))?;
 
-    conn.publish(channel, message)
+    conn.publish::<_, _, ()>(channel, message)
         .await
         .map_err(|e| anyhow!("Failed to publish WebSocket event: {}", e))?;
 
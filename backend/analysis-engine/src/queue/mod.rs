@@ -1,4 +1,5 @@
 pub mod consumer;
+pub mod shutdown;
 // NOTE: scheduler is temporarily disabled — it depends on the `shared` crate
 // (KafkaProducer, RedisClient, etc.) which is not a dependency of analysis-engine.
 // pub mod scheduler;
\ No newline at end of file
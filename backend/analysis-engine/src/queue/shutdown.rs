@@ -0,0 +1,258 @@
+//! Graceful shutdown coordination for the analysis worker.
+//!
+//! On SIGTERM the coordinator flips into draining mode: the queue consumer
+//! stops popping new submissions, `/health` reports the replica as draining,
+//! and `main` waits for in-flight analyses up to a deadline. Anything still
+//! running after that is marked `resumable` and pushed back onto the queue so
+//! another replica picks it up.
+
+use anyhow::{anyhow, Result};
+use redis::AsyncCommands;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::consumer::ANALYSIS_QUEUE_KEY;
+
+/// Submission status for work interrupted by shutdown
+pub const RESUMABLE_STATUS: &str = "resumable";
+
+/// Stage an in-flight analysis has reached, persisted when it is interrupted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnalysisStage {
+    Fetching,
+    Downloading,
+    Analyzing,
+    Storing,
+}
+
+impl AnalysisStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AnalysisStage::Fetching => "fetching",
+            AnalysisStage::Downloading => "downloading",
+            AnalysisStage::Analyzing => "analyzing",
+            AnalysisStage::Storing => "storing",
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ShutdownCoordinator {
+    draining: AtomicBool,
+    in_flight: Mutex<HashMap<Uuid, AnalysisStage>>,
+    drained: Notify,
+}
+
+impl ShutdownCoordinator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop accepting new work
+    pub fn begin_shutdown(&self) {
+        if !self.draining.swap(true, Ordering::SeqCst) {
+            info!(
+                "Shutdown requested, draining {} in-flight analyses",
+                self.in_flight_count()
+            );
+        }
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+
+    pub fn in_flight_count(&self) -> usize {
+        self.in_flight.lock().unwrap().len()
+    }
+
+    /// Register a submission as in flight until the returned guard is dropped
+    pub fn track(self: &Arc<Self>, submission_id: Uuid) -> InFlightGuard {
+        self.in_flight
+            .lock()
+            .unwrap()
+            .insert(submission_id, AnalysisStage::Fetching);
+        InFlightGuard {
+            coordinator: self.clone(),
+            submission_id,
+        }
+    }
+
+    /// Wait until all in-flight analyses finish or `timeout` elapses.
+    ///
+    /// Returns the analyses that were still running at the deadline.
+    pub async fn wait_for_drain(&self, timeout: Duration) -> Vec<(Uuid, AnalysisStage)> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let remaining: Vec<_> = self
+                .in_flight
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(id, stage)| (*id, *stage))
+                .collect();
+            if remaining.is_empty() {
+                return remaining;
+            }
+            if tokio::time::timeout_at(deadline, self.drained.notified())
+                .await
+                .is_err()
+            {
+                return remaining;
+            }
+        }
+    }
+
+    fn set_stage(&self, submission_id: Uuid, stage: AnalysisStage) {
+        if let Some(entry) = self.in_flight.lock().unwrap().get_mut(&submission_id) {
+            *entry = stage;
+        }
+    }
+
+    fn finish(&self, submission_id: Uuid) {
+        self.in_flight.lock().unwrap().remove(&submission_id);
+        self.drained.notify_one();
+    }
+}
+
+/// Keeps a submission registered as in flight for as long as it is alive
+pub struct InFlightGuard {
+    coordinator: Arc<ShutdownCoordinator>,
+    submission_id: Uuid,
+}
+
+impl InFlightGuard {
+    pub fn set_stage(&self, stage: AnalysisStage) {
+        self.coordinator.set_stage(self.submission_id, stage);
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.coordinator.finish(self.submission_id);
+    }
+}
+
+/// Persist interrupted analyses as resumable and put them back on the queue
+pub async fn requeue_unfinished(
+    redis_client: &redis::Client,
+    db_pool: &PgPool,
+    unfinished: &[(Uuid, AnalysisStage)],
+) -> Result<()> {
+    if unfinished.is_empty() {
+        return Ok(());
+    }
+
+    let mut conn = redis_client
+        .get_multiplexed_async_connection()
+        .await
+        .map_err(|e| anyhow!("Failed to connect to Redis: {}", e))?;
+
+    for (submission_id, stage) in unfinished {
+        let resume = serde_json::json!({
+            "resume": {
+                "interrupted_stage": stage.as_str(),
+                "interrupted_at": chrono::Utc::now(),
+            }
+        });
+
+        if let Err(e) = sqlx::query(
+            r#"
+            UPDATE submissions
+            SET analysis_status = $1,
+                metadata = COALESCE(metadata, '{}'::jsonb) || $2::jsonb,
+                updated_at = NOW()
+            WHERE id = $3
+            "#,
+        )
+        .bind(RESUMABLE_STATUS)
+        .bind(resume.to_string())
+        .bind(submission_id)
+        .execute(db_pool)
+        .await
+        {
+            warn!("Failed to mark submission {} resumable: {}", submission_id, e);
+        }
+
+        // RPUSH so the next BRPOP on any replica picks it up first
+        conn.rpush::<_, _, ()>(ANALYSIS_QUEUE_KEY, submission_id.to_string())
+            .await
+            .map_err(|e| anyhow!("Failed to re-queue submission {}: {}", submission_id, e))?;
+
+        info!(
+            "Re-queued submission {} interrupted while {}",
+            submission_id,
+            stage.as_str()
+        );
+    }
+
+    Ok(())
+}
+
+/// Resolve on SIGTERM or Ctrl-C and put the coordinator into draining mode
+pub async fn shutdown_signal(coordinator: Arc<ShutdownCoordinator>) {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Failed to listen for shutdown signal");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    coordinator.begin_shutdown();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_drain_completes_when_guards_drop() {
+        let coordinator = Arc::new(ShutdownCoordinator::new());
+        let guard = coordinator.track(Uuid::new_v4());
+        coordinator.begin_shutdown();
+        assert!(coordinator.is_draining());
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(guard);
+        });
+
+        let remaining = coordinator.wait_for_drain(Duration::from_secs(5)).await;
+        assert!(remaining.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_drain_reports_unfinished_at_deadline() {
+        let coordinator = Arc::new(ShutdownCoordinator::new());
+        let submission_id = Uuid::new_v4();
+        let guard = coordinator.track(submission_id);
+        guard.set_stage(AnalysisStage::Analyzing);
+
+        let remaining = coordinator.wait_for_drain(Duration::from_millis(20)).await;
+        assert_eq!(remaining, vec![(submission_id, AnalysisStage::Analyzing)]);
+
+        drop(guard);
+        assert_eq!(coordinator.in_flight_count(), 0);
+    }
+}
@@ -0,0 +1,173 @@
+//! Self-contained HTML rendering (inline CSS, no external assets)
+
+use std::fmt::Write;
+
+use super::{AnalysisReport, Finding};
+use crate::models::analysis_result::ThreatVerdict;
+
+const STYLE: &str = "body{font-family:-apple-system,Segoe UI,Helvetica,Arial,sans-serif;margin:2rem;color:#1f2937}\
+h1{font-size:1.5rem}h2{font-size:1.1rem;border-bottom:1px solid #e5e7eb;padding-bottom:.25rem;margin-top:2rem}\
+table{border-collapse:collapse;width:100%;font-size:.9rem}th,td{text-align:left;padding:.35rem .5rem;border-bottom:1px solid #f3f4f6}\
+code{font-family:Menlo,Consolas,monospace;font-size:.85rem}.verdict{display:inline-block;padding:.2rem .6rem;border-radius:4px;color:#fff;font-weight:600}\
+.malicious{background:#dc2626}.suspicious{background:#d97706}.benign{background:#16a34a}.unknown{background:#6b7280}.muted{color:#6b7280}";
+
+/// Escape text for safe inclusion in HTML element content and attributes
+pub fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn verdict_class(verdict: &ThreatVerdict) -> &'static str {
+    match verdict {
+        ThreatVerdict::Malicious => "malicious",
+        ThreatVerdict::Suspicious => "suspicious",
+        ThreatVerdict::Benign => "benign",
+        ThreatVerdict::Unknown => "unknown",
+    }
+}
+
+fn findings_table(out: &mut String, title: &str, findings: &[Finding]) {
+    let _ = write!(out, "<h2>{}</h2>", escape(title));
+    if findings.is_empty() {
+        out.push_str("<p class=\"muted\">No findings</p>");
+        return;
+    }
+    out.push_str("<table><tr><th>Engine</th><th>Verdict</th><th>Confidence</th><th>Severity</th><th>Categories</th></tr>");
+    for f in findings {
+        let _ = write!(
+            out,
+            "<tr><td>{}</td><td>{:?}</td><td>{:.0}%</td><td>{:?}</td><td>{}</td></tr>",
+            escape(&f.engine),
+            f.verdict,
+            f.confidence * 100.0,
+            f.severity,
+            escape(&f.categories.join(", "))
+        );
+    }
+    out.push_str("</table>");
+}
+
+pub fn render(report: &AnalysisReport) -> String {
+    let mut out = String::new();
+    let summary = &report.summary;
+
+    let _ = write!(
+        out,
+        "<!DOCTYPE html><html lang=\"en\"><head><meta charset=\"utf-8\"><title>Analysis Report {id}</title><style>{STYLE}</style></head><body>\
+<h1>Nexus-Security Analysis Report</h1>\
+<p class=\"muted\">Analysis <code>{id}</code> &middot; generated {generated}</p>\
+<p><span class=\"verdict {class}\">{verdict:?}</span> &nbsp; {confidence:.1}% confidence &middot; {severity:?} severity &middot; {malicious} of {total} engines flagged malicious</p>",
+        id = report.analysis_id,
+        generated = report.generated_at.to_rfc3339(),
+        class = verdict_class(&summary.verdict),
+        verdict = summary.verdict,
+        confidence = summary.confidence * 100.0,
+        severity = summary.severity,
+        malicious = summary.malicious_detections,
+        total = summary.total_detections,
+    );
+
+    let file = &report.file;
+    let _ = write!(
+        out,
+        "<h2>File</h2><table>\
+<tr><th>Name</th><td>{}</td></tr><tr><th>Size</th><td>{} bytes</td></tr><tr><th>Type</th><td>{}</td></tr>\
+<tr><th>MD5</th><td><code>{}</code></td></tr><tr><th>SHA1</th><td><code>{}</code></td></tr><tr><th>SHA256</th><td><code>{}</code></td></tr>",
+        escape(file.filename.as_deref().unwrap_or("-")),
+        file.file_size,
+        escape(&file.mime_type),
+        escape(&file.md5),
+        escape(&file.sha1),
+        escape(&file.sha256),
+    );
+    if let Some(entropy) = file.entropy {
+        let _ = write!(out, "<tr><th>Entropy</th><td>{:.2}</td></tr>", entropy);
+    }
+    out.push_str("</table>");
+
+    findings_table(&mut out, "Static Analysis", &report.static_findings);
+    findings_table(&mut out, "Scanners", &report.scanner_findings);
+
+    out.push_str("<h2>YARA Matches</h2>");
+    if report.yara_matches.is_empty() {
+        out.push_str("<p class=\"muted\">No matches</p>");
+    } else {
+        out.push_str("<table><tr><th>Rule</th><th>Namespace</th><th>Tags</th><th>Strings</th></tr>");
+        for m in &report.yara_matches {
+            let _ = write!(
+                out,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape(&m.rule_name),
+                escape(m.namespace.as_deref().unwrap_or("-")),
+                escape(&m.tags.join(", ")),
+                m.matched_strings
+            );
+        }
+        out.push_str("</table>");
+    }
+
+    if let Some(sandbox) = &report.sandbox {
+        findings_table(&mut out, "Sandbox", &sandbox.findings);
+        let _ = write!(
+            out,
+            "<p>{} process, {} file, {} registry and {} network operations observed.</p>",
+            sandbox.process_operations,
+            sandbox.file_operations,
+            sandbox.registry_operations,
+            sandbox.network_operations
+        );
+        if !sandbox.network_destinations.is_empty() {
+            out.push_str("<ul>");
+            for destination in &sandbox.network_destinations {
+                let _ = write!(out, "<li><code>{}</code></li>", escape(destination));
+            }
+            out.push_str("</ul>");
+        }
+    }
+
+    if let Some(indicators) = &report.network_indicators {
+        out.push_str("<h2>Network Indicators</h2><ul>");
+        for value in indicators
+            .urls
+            .iter()
+            .chain(&indicators.domains)
+            .chain(&indicators.ips)
+        {
+            let _ = write!(out, "<li><code>{}</code></li>", escape(value));
+        }
+        out.push_str("</ul>");
+    }
+
+    out.push_str("</body></html>");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("<a href=\"x\">&'"), "&lt;a href=&quot;x&quot;&gt;&amp;&#39;");
+    }
+
+    #[test]
+    fn test_render_escapes_file_name() {
+        let report = AnalysisReport::from_result(&super::super::tests::sample_result());
+        let html = render(&report);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("invoice&lt;1&gt;.exe"));
+        assert!(!html.contains("invoice<1>.exe"));
+        assert!(html.contains("verdict malicious"));
+    }
+}
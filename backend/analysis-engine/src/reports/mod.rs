@@ -0,0 +1,344 @@
+//! Detailed analysis reports
+//!
+//! Aggregates static, signature/scanner, YARA and sandbox findings from an
+//! `AnalysisResult` into a single structured report that can be rendered as
+//! JSON, self-contained HTML, or PDF for attaching to bounty submissions.
+
+pub mod html;
+pub mod pdf;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::analysis_result::{
+    AnalysisResult, DetectionResult, EngineType, NetworkIndicators, SeverityLevel, ThreatVerdict,
+};
+
+/// Output format requested by the caller
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    #[default]
+    Json,
+    Html,
+    Pdf,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalysisReport {
+    pub report_id: Uuid,
+    pub generated_at: DateTime<Utc>,
+    pub analysis_id: Uuid,
+    pub submission_id: Uuid,
+    pub bounty_id: Option<Uuid>,
+    pub summary: ReportSummary,
+    pub file: FileSection,
+    pub static_findings: Vec<Finding>,
+    pub scanner_findings: Vec<Finding>,
+    pub yara_matches: Vec<YaraFinding>,
+    pub sandbox: Option<SandboxSection>,
+    pub network_indicators: Option<NetworkIndicators>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportSummary {
+    pub verdict: ThreatVerdict,
+    pub confidence: f32,
+    pub severity: SeverityLevel,
+    pub malicious_detections: usize,
+    pub total_detections: usize,
+    pub tags: Vec<String>,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub processing_time_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileSection {
+    pub filename: Option<String>,
+    pub file_size: u64,
+    pub mime_type: String,
+    pub md5: String,
+    pub sha1: String,
+    pub sha256: String,
+    pub entropy: Option<f64>,
+}
+
+/// A single engine detection, flattened for display
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Finding {
+    pub engine: String,
+    pub verdict: ThreatVerdict,
+    pub confidence: f32,
+    pub severity: SeverityLevel,
+    pub categories: Vec<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct YaraFinding {
+    pub rule_name: String,
+    pub namespace: Option<String>,
+    pub tags: Vec<String>,
+    pub matched_strings: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SandboxSection {
+    pub findings: Vec<Finding>,
+    pub process_operations: usize,
+    pub file_operations: usize,
+    pub registry_operations: usize,
+    pub network_operations: usize,
+    pub network_destinations: Vec<String>,
+}
+
+impl Finding {
+    fn from_detection(detection: &DetectionResult) -> Self {
+        Self {
+            engine: format!("{} {}", detection.engine_name, detection.engine_version)
+                .trim()
+                .to_string(),
+            verdict: detection.verdict.clone(),
+            confidence: detection.confidence,
+            severity: detection.severity.clone(),
+            categories: detection
+                .categories
+                .iter()
+                .map(|c| format!("{:?}", c))
+                .collect(),
+            error: detection.error_message.clone(),
+        }
+    }
+}
+
+impl AnalysisReport {
+    /// Build a report from a completed (or failed) analysis
+    pub fn from_result(result: &AnalysisResult) -> Self {
+        let mut static_findings = Vec::new();
+        let mut scanner_findings = Vec::new();
+        let mut sandbox_findings = Vec::new();
+
+        for detection in &result.detections {
+            let finding = Finding::from_detection(detection);
+            match detection.engine_type {
+                EngineType::Static | EngineType::Ml => static_findings.push(finding),
+                EngineType::Dynamic | EngineType::Behavioral | EngineType::Sandbox => {
+                    sandbox_findings.push(finding)
+                }
+                EngineType::Yara | EngineType::Hash | EngineType::Human => {
+                    scanner_findings.push(finding)
+                }
+            }
+        }
+
+        let sandbox = if result.behavioral_analysis.is_some() || !sandbox_findings.is_empty() {
+            let behavior = result.behavioral_analysis.as_ref();
+            let mut network_destinations: Vec<String> = behavior
+                .map(|b| {
+                    b.network_operations
+                        .iter()
+                        .map(|op| format!("{} ({})", op.destination, op.protocol))
+                        .collect()
+                })
+                .unwrap_or_default();
+            network_destinations.sort();
+            network_destinations.dedup();
+
+            Some(SandboxSection {
+                findings: sandbox_findings,
+                process_operations: behavior.map_or(0, |b| b.process_operations.len()),
+                file_operations: behavior.map_or(0, |b| b.file_operations.len()),
+                registry_operations: behavior.map_or(0, |b| b.registry_operations.len()),
+                network_operations: behavior.map_or(0, |b| b.network_operations.len()),
+                network_destinations,
+            })
+        } else {
+            None
+        };
+
+        let metadata = &result.file_metadata;
+
+        Self {
+            report_id: Uuid::new_v4(),
+            generated_at: Utc::now(),
+            analysis_id: result.analysis_id,
+            submission_id: result.submission_id,
+            bounty_id: result.bounty_id,
+            summary: ReportSummary {
+                verdict: result.consensus_verdict.clone(),
+                confidence: result.consensus_confidence,
+                severity: result.consensus_severity.clone(),
+                malicious_detections: result
+                    .detections
+                    .iter()
+                    .filter(|d| d.verdict == ThreatVerdict::Malicious)
+                    .count(),
+                total_detections: result.detections.len(),
+                tags: result.tags.clone(),
+                started_at: result.started_at,
+                completed_at: result.completed_at,
+                processing_time_ms: result.total_processing_time_ms,
+            },
+            file: FileSection {
+                filename: metadata.filename.clone(),
+                file_size: metadata.file_size,
+                mime_type: metadata.mime_type.clone(),
+                md5: metadata.md5.clone(),
+                sha1: metadata.sha1.clone(),
+                sha256: metadata.sha256.clone(),
+                entropy: metadata.entropy,
+            },
+            static_findings,
+            scanner_findings,
+            yara_matches: result
+                .yara_matches
+                .iter()
+                .map(|m| YaraFinding {
+                    rule_name: m.rule_name.clone(),
+                    namespace: m.namespace.clone(),
+                    tags: m.tags.clone(),
+                    matched_strings: m.strings.iter().map(|s| s.instances.len()).sum(),
+                })
+                .collect(),
+            sandbox,
+            network_indicators: result.network_indicators.clone(),
+        }
+    }
+
+    /// Plain-text lines shared by the PDF renderer
+    pub fn text_lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            "Nexus-Security Analysis Report".to_string(),
+            String::new(),
+            format!("Analysis ID: {}", self.analysis_id),
+            format!("Submission ID: {}", self.submission_id),
+            format!("Generated: {}", self.generated_at.to_rfc3339()),
+            String::new(),
+            "Summary".to_string(),
+            format!("  Verdict: {:?}", self.summary.verdict),
+            format!("  Confidence: {:.1}%", self.summary.confidence * 100.0),
+            format!("  Severity: {:?}", self.summary.severity),
+            format!(
+                "  Detections: {} malicious of {}",
+                self.summary.malicious_detections, self.summary.total_detections
+            ),
+            String::new(),
+            "File".to_string(),
+            format!("  Name: {}", self.file.filename.as_deref().unwrap_or("-")),
+            format!("  Size: {} bytes", self.file.file_size),
+            format!("  Type: {}", self.file.mime_type),
+            format!("  MD5: {}", self.file.md5),
+            format!("  SHA1: {}", self.file.sha1),
+            format!("  SHA256: {}", self.file.sha256),
+        ];
+
+        for (title, findings) in [
+            ("Static Analysis", &self.static_findings),
+            ("Scanners", &self.scanner_findings),
+        ] {
+            lines.push(String::new());
+            lines.push(title.to_string());
+            if findings.is_empty() {
+                lines.push("  No findings".to_string());
+            }
+            for f in findings {
+                lines.push(format!(
+                    "  {}: {:?} ({:.0}%, {:?})",
+                    f.engine,
+                    f.verdict,
+                    f.confidence * 100.0,
+                    f.severity
+                ));
+            }
+        }
+
+        lines.push(String::new());
+        lines.push("YARA Matches".to_string());
+        if self.yara_matches.is_empty() {
+            lines.push("  No matches".to_string());
+        }
+        for m in &self.yara_matches {
+            lines.push(format!("  {} [{}]", m.rule_name, m.tags.join(", ")));
+        }
+
+        if let Some(sandbox) = &self.sandbox {
+            lines.push(String::new());
+            lines.push("Sandbox".to_string());
+            lines.push(format!(
+                "  Operations: {} process, {} file, {} registry, {} network",
+                sandbox.process_operations,
+                sandbox.file_operations,
+                sandbox.registry_operations,
+                sandbox.network_operations
+            ));
+            for destination in &sandbox.network_destinations {
+                lines.push(format!("  Contacted: {}", destination));
+            }
+        }
+
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::analysis_result::{AnalysisStatus, FileMetadata};
+    use std::collections::HashMap;
+
+    pub(crate) fn sample_result() -> AnalysisResult {
+        let mut result = AnalysisResult::new(
+            Uuid::new_v4(),
+            FileMetadata {
+                filename: Some("invoice<1>.exe".to_string()),
+                file_size: 2048,
+                mime_type: "application/x-msdownload".to_string(),
+                md5: "d41d8cd98f00b204e9800998ecf8427e".to_string(),
+                sha1: "da39a3ee5e6b4b0d3255bfef95601890afd80709".to_string(),
+                sha256: "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+                    .to_string(),
+                sha512: None,
+                entropy: Some(7.2),
+                magic_bytes: None,
+                executable_info: None,
+            },
+        );
+        result.consensus_verdict = ThreatVerdict::Malicious;
+        result.status = AnalysisStatus::Completed;
+        for (engine_type, name) in [(EngineType::Static, "static"), (EngineType::Yara, "clamav")] {
+            result.detections.push(DetectionResult {
+                detection_id: Uuid::new_v4(),
+                engine_name: name.to_string(),
+                engine_version: "1.0".to_string(),
+                engine_type,
+                verdict: ThreatVerdict::Malicious,
+                confidence: 0.9,
+                severity: SeverityLevel::High,
+                categories: vec![],
+                metadata: HashMap::new(),
+                detected_at: Utc::now(),
+                processing_time_ms: 10,
+                error_message: None,
+            });
+        }
+        result
+    }
+
+    #[test]
+    fn test_report_groups_findings_by_engine_type() {
+        let report = AnalysisReport::from_result(&sample_result());
+        assert_eq!(report.static_findings.len(), 1);
+        assert_eq!(report.scanner_findings.len(), 1);
+        assert!(report.sandbox.is_none());
+        assert_eq!(report.summary.malicious_detections, 2);
+    }
+
+    #[test]
+    fn test_text_lines_include_hashes() {
+        let report = AnalysisReport::from_result(&sample_result());
+        let lines = report.text_lines();
+        assert!(lines.iter().any(|l| l.contains(&report.file.sha256)));
+    }
+}
@@ -0,0 +1,133 @@
+//! Minimal PDF 1.4 rendering
+//!
+//! Writes the report's text lines onto A4 pages using the built-in Helvetica
+//! font, so no font embedding or external PDF library is needed.
+
+use std::fmt::Write;
+
+use super::AnalysisReport;
+
+const PAGE_WIDTH: u32 = 595;
+const PAGE_HEIGHT: u32 = 842;
+const MARGIN: u32 = 50;
+const FONT_SIZE: u32 = 10;
+const LINE_HEIGHT: u32 = 14;
+const LINES_PER_PAGE: usize = ((PAGE_HEIGHT - 2 * MARGIN) / LINE_HEIGHT) as usize;
+
+/// Escape a line for a PDF literal string. Helvetica only covers
+/// Latin-1 in WinAnsiEncoding, so anything outside ASCII is replaced.
+fn escape(line: &str) -> String {
+    let mut escaped = String::with_capacity(line.len());
+    for c in line.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            ' '..='~' => escaped.push(c),
+            _ => escaped.push('?'),
+        }
+    }
+    escaped
+}
+
+fn content_stream(lines: &[String]) -> String {
+    let mut stream = String::new();
+    let _ = write!(
+        stream,
+        "BT\n/F1 {} Tf\n{} TL\n{} {} Td\n",
+        FONT_SIZE,
+        LINE_HEIGHT,
+        MARGIN,
+        PAGE_HEIGHT - MARGIN
+    );
+    for line in lines {
+        let _ = writeln!(stream, "({}) Tj T*", escape(line));
+    }
+    stream.push_str("ET");
+    stream
+}
+
+pub fn render(report: &AnalysisReport) -> Vec<u8> {
+    let lines = report.text_lines();
+    let pages: Vec<&[String]> = lines.chunks(LINES_PER_PAGE).collect();
+
+    // Object layout: 1 catalog, 2 page tree, 3 font, then a page + content pair per page
+    let page_ids: Vec<usize> = (0..pages.len()).map(|i| 4 + i * 2).collect();
+    let mut objects: Vec<String> = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            page_ids
+                .iter()
+                .map(|id| format!("{} 0 R", id))
+                .collect::<Vec<_>>()
+                .join(" "),
+            pages.len()
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+    ];
+
+    for (page, page_id) in pages.iter().zip(&page_ids) {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+            PAGE_WIDTH,
+            PAGE_HEIGHT,
+            page_id + 1
+        ));
+        let stream = content_stream(page);
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{}\nendstream",
+            stream.len(),
+            stream
+        ));
+    }
+
+    let mut out = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(out.len());
+        let _ = write!(out, "{} 0 obj\n{}\nendobj\n", i + 1, object);
+    }
+
+    let xref_offset = out.len();
+    let _ = write!(out, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        let _ = write!(out, "{:010} 00000 n \n", offset);
+    }
+    let _ = write!(
+        out,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref_offset
+    );
+
+    out.into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a (b) \\ é"), "a \\(b\\) \\\\ ?");
+    }
+
+    #[test]
+    fn test_render_produces_valid_structure() {
+        let report = AnalysisReport::from_result(&super::super::tests::sample_result());
+        let pdf = String::from_utf8(render(&report)).unwrap();
+        assert!(pdf.starts_with("%PDF-1.4"));
+        assert!(pdf.trim_end().ends_with("%%EOF"));
+
+        let startxref: usize = pdf
+            .rsplit("startxref\n")
+            .next()
+            .and_then(|tail| tail.lines().next())
+            .and_then(|n| n.parse().ok())
+            .unwrap();
+        assert!(pdf[startxref..].starts_with("xref"));
+    }
+}
@@ -8,11 +8,13 @@
 
 pub mod file_scanner;
 pub mod url_scanner;
+pub mod url_cache;
 pub mod email_scanner;
 pub mod archive_scanner;
 
 pub use file_scanner::{FileScanner, FileScannerConfig, FileScanResult};
 pub use url_scanner::{UrlScanner, UrlScannerConfig, UrlScanResult};
+pub use url_cache::{UrlCacheConfig, UrlScanCache};
 pub use email_scanner::{EmailScanner, EmailScannerConfig, EmailScanResult};
 pub use archive_scanner::{ArchiveScanner, ArchiveScannerConfig, ArchiveScanResult};
 
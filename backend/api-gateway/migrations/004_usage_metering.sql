@@ -0,0 +1,51 @@
+-- Migration 004: Per-organization usage metering and billing exports
+-- Counters are accumulated in Redis by the gateway and flushed here periodically.
+
+-- ============================================
+-- Organizations
+-- ============================================
+CREATE TABLE IF NOT EXISTS organizations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(255) NOT NULL,
+    billing_email VARCHAR(255),
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS organization_members (
+    organization_id UUID REFERENCES organizations(id) ON DELETE CASCADE NOT NULL,
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE NOT NULL,
+    role VARCHAR(20) NOT NULL DEFAULT 'member', -- 'owner', 'admin', 'member'
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (organization_id, user_id)
+);
+
+-- Usage is attributed to a single organization per user
+CREATE UNIQUE INDEX IF NOT EXISTS idx_org_members_user ON organization_members(user_id);
+
+-- ============================================
+-- Daily usage rollups
+-- ============================================
+CREATE TABLE IF NOT EXISTS organization_usage_daily (
+    organization_id UUID REFERENCES organizations(id) ON DELETE CASCADE NOT NULL,
+    usage_date DATE NOT NULL,
+    api_calls BIGINT NOT NULL DEFAULT 0,
+    analysis_seconds BIGINT NOT NULL DEFAULT 0,
+    storage_bytes BIGINT NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (organization_id, usage_date)
+);
+
+CREATE INDEX IF NOT EXISTS idx_org_usage_date ON organization_usage_daily(usage_date);
+
+-- ============================================
+-- Monthly billing exports
+-- ============================================
+CREATE TABLE IF NOT EXISTS billing_exports (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    period VARCHAR(7) NOT NULL UNIQUE, -- 'YYYY-MM'
+    file_path TEXT NOT NULL,
+    organization_count INTEGER NOT NULL DEFAULT 0,
+    generated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,55 @@
+-- Migration 005: Role-based access control
+-- A caller's permissions are those of the role in their access token plus
+-- those of every role assigned to them here.
+
+-- ============================================
+-- Roles and their permissions
+-- ============================================
+CREATE TABLE IF NOT EXISTS roles (
+    name VARCHAR(50) PRIMARY KEY,
+    description TEXT,
+    is_system BOOLEAN NOT NULL DEFAULT FALSE, -- built-in roles cannot be deleted
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS role_permissions (
+    role VARCHAR(50) REFERENCES roles(name) ON DELETE CASCADE NOT NULL,
+    permission VARCHAR(100) NOT NULL, -- e.g. 'bounty:create'; 'admin' grants everything
+    PRIMARY KEY (role, permission)
+);
+
+-- ============================================
+-- Additional roles granted to users
+-- ============================================
+CREATE TABLE IF NOT EXISTS user_roles (
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE NOT NULL,
+    role VARCHAR(50) REFERENCES roles(name) ON DELETE CASCADE NOT NULL,
+    granted_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    granted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, role)
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_roles_role ON user_roles(role);
+
+-- ============================================
+-- Built-in roles, matching the gateway's fallback defaults
+-- ============================================
+INSERT INTO roles (name, description, is_system) VALUES
+    ('admin', 'Full access to every API', TRUE),
+    ('moderator', 'Verifies submissions and moderates bounties', TRUE),
+    ('user', 'Registered analyst or bounty creator', TRUE)
+ON CONFLICT (name) DO NOTHING;
+
+INSERT INTO role_permissions (role, permission) VALUES
+    ('admin', 'admin'),
+    ('moderator', 'admin:console'),
+    ('moderator', 'analysis:submit'),
+    ('moderator', 'bounty:create'),
+    ('moderator', 'bounty:manage'),
+    ('moderator', 'submissions:verify'),
+    ('moderator', 'webhooks:manage'),
+    ('user', 'analysis:submit'),
+    ('user', 'bounty:create'),
+    ('user', 'webhooks:manage')
+ON CONFLICT DO NOTHING;
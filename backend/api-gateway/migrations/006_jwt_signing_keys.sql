@@ -0,0 +1,17 @@
+-- Migration 006: Rotating JWT signing keys
+-- Access and refresh tokens are signed with Ed25519 key pairs. Every key is
+-- published in the JWKS before it starts signing and keeps verifying after
+-- its successor takes over, until the last token it signed has expired.
+
+CREATE TABLE IF NOT EXISTS jwt_signing_keys (
+    kid VARCHAR(64) PRIMARY KEY,             -- RFC 7638 thumbprint of the public key
+    algorithm VARCHAR(16) NOT NULL DEFAULT 'EdDSA',
+    public_key BYTEA NOT NULL,               -- raw 32-byte Ed25519 public key
+    encrypted_private_key BYTEA NOT NULL,    -- PKCS#8, AES-256-GCM under a key derived from JWT_SECRET
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    activates_at TIMESTAMPTZ NOT NULL,       -- starts signing
+    retires_at TIMESTAMPTZ,                  -- stops signing (successor activates)
+    expires_at TIMESTAMPTZ                   -- stops verifying and leaves the JWKS
+);
+
+CREATE INDEX IF NOT EXISTS idx_jwt_signing_keys_activates_at ON jwt_signing_keys(activates_at DESC);
@@ -0,0 +1,12 @@
+-- Migration 007: Organization quotas
+-- Limits pooled across every member and API key of an organization. NULL
+-- means unlimited. Consumption is tracked by the gateway in Redis
+-- (quota:<organization_id>:<YYYYMMDD>:<budget>) and resets at 00:00 UTC.
+
+CREATE TABLE IF NOT EXISTS organization_quotas (
+    organization_id UUID PRIMARY KEY REFERENCES organizations(id) ON DELETE CASCADE,
+    requests_per_minute INTEGER CHECK (requests_per_minute > 0),
+    daily_submissions INTEGER CHECK (daily_submissions >= 0),
+    daily_analyses INTEGER CHECK (daily_analyses >= 0),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
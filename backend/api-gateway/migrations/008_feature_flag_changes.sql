@@ -0,0 +1,16 @@
+-- Migration 008: Feature flag audit trail
+-- Flags themselves live in Redis (`feature_flag:<name>` hashes) so changes
+-- take effect on every instance immediately; this table records who
+-- changed what, when and why.
+
+CREATE TABLE IF NOT EXISTS feature_flag_changes (
+    id BIGSERIAL PRIMARY KEY,
+    flag VARCHAR(128) NOT NULL,
+    enabled BOOLEAN,                          -- NULL: setting removed, default applies
+    reason TEXT,
+    retry_after_seconds BIGINT,
+    changed_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_feature_flag_changes_flag ON feature_flag_changes(flag, changed_at DESC);
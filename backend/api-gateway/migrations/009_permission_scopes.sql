@@ -0,0 +1,26 @@
+-- Migration 009: Permission scopes enforced by the services
+-- Access tokens carry a caller's permissions as scopes and the gateway
+-- forwards them to services, which gate their admin routes on them. Roles
+-- can now also be granted to an organization, applying to all its members.
+
+-- ============================================
+-- Roles granted to every member of an organization
+-- ============================================
+CREATE TABLE IF NOT EXISTS organization_roles (
+    organization_id UUID REFERENCES organizations(id) ON DELETE CASCADE NOT NULL,
+    role VARCHAR(50) REFERENCES roles(name) ON DELETE CASCADE NOT NULL,
+    granted_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    granted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (organization_id, role)
+);
+
+CREATE INDEX IF NOT EXISTS idx_organization_roles_role ON organization_roles(role);
+
+-- ============================================
+-- Service permissions of the built-in roles, matching the gateway's
+-- fallback defaults; admins hold all of them through 'admin'
+-- ============================================
+INSERT INTO role_permissions (role, permission) VALUES
+    ('moderator', 'bounty:moderate')
+ON CONFLICT DO NOTHING;
+
@@ -0,0 +1,17 @@
+-- Migration 010: Devices and countries users have signed in from
+-- Active sessions live in Redis and disappear when they end; this table
+-- remembers where a user has signed in from so a login from a new device
+-- or country can be reported to them. One row per device and country.
+
+CREATE TABLE IF NOT EXISTS user_known_devices (
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE NOT NULL,
+    fingerprint VARCHAR(64) NOT NULL,
+    country VARCHAR(2) NOT NULL DEFAULT '',   -- '' when the edge proxy did not report one
+    label VARCHAR(255) NOT NULL,
+    last_ip_address VARCHAR(45),
+    first_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, fingerprint, country)
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_known_devices_last_seen ON user_known_devices(user_id, last_seen_at DESC);
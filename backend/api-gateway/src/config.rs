@@ -1,4 +1,6 @@
+use chrono::{DateTime, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 use thiserror::Error;
@@ -30,6 +32,14 @@ pub struct AppConfig {
     pub services: ServicesConfig,
     pub features: FeaturesConfig,
     pub monitoring: MonitoringConfig,
+    #[serde(default)]
+    pub usage: UsageConfig,
+    #[serde(default)]
+    pub idempotency: IdempotencyConfig,
+    #[serde(default)]
+    pub quotas: QuotaConfig,
+    #[serde(default)]
+    pub api_versions: ApiVersionsConfig,
 }
 
 /// Server configuration
@@ -99,6 +109,8 @@ pub struct ContractsConfig {
 /// Security configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SecurityConfig {
+    /// Encrypts the JWT signing keys at rest; tokens are signed with the
+    /// rotating key pairs in `services::jwt_keys`
     pub jwt_secret: String,
     pub jwt_expiry_hours: i64,
     pub refresh_token_expiry_days: i64,
@@ -110,8 +122,68 @@ pub struct SecurityConfig {
     pub max_login_attempts: u32,
     pub lockout_duration_minutes: u64,
     pub session_timeout_minutes: u64,
+    /// Days a signing key is used before its successor takes over
+    #[serde(default = "default_jwt_key_rotation_days")]
+    pub jwt_key_rotation_days: i64,
+    /// Keep accepting HS256 tokens signed with `jwt_secret`, issued before
+    /// the switch to key pairs. Only needed until those tokens expire.
+    #[serde(default)]
+    pub accept_legacy_hs256_tokens: bool,
+    /// Session cookies for the web frontend
+    #[serde(default)]
+    pub cookies: CookieConfig,
     pub cors: CorsConfig,
     pub rate_limiting: RateLimitingConfig,
+    /// Addresses or CIDR ranges of the load balancers in front of the
+    /// gateway. Only their X-Forwarded-For / X-Real-IP is believed; every
+    /// other client is known by its socket address.
+    #[serde(default)]
+    pub trusted_proxies: Vec<String>,
+}
+
+fn default_jwt_key_rotation_days() -> i64 {
+    30
+}
+
+/// Session cookies issued next to the tokens in login responses, so
+/// browsers need not keep tokens in script-readable storage. Requests
+/// authenticated by cookie must pass the CSRF check (`middleware::csrf`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CookieConfig {
+    pub enabled: bool,
+    /// Set `Secure` on every cookie the gateway sends, including proxied ones
+    pub secure: bool,
+    pub same_site: SameSite,
+    /// Domain attribute, e.g. `.nexus-security.com`; host-only when unset
+    pub domain: Option<String>,
+}
+
+/// `SameSite` cookie attribute
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SameSite {
+    Strict,
+    Lax,
+    None,
+}
+
+impl SameSite {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SameSite::Strict => "Strict",
+            SameSite::Lax => "Lax",
+            SameSite::None => "None",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "strict" => Some(SameSite::Strict),
+            "lax" => Some(SameSite::Lax),
+            "none" => Some(SameSite::None),
+            _ => None,
+        }
+    }
 }
 
 /// CORS configuration
@@ -134,6 +206,33 @@ pub struct RateLimitingConfig {
     pub auth_requests_per_15min: u32,
     pub api_requests_per_hour: u32,
     pub whitelist_ips: Vec<String>,
+    /// Limits for authenticated callers, keyed by `AuthContext.rate_limit_tier`.
+    /// Anonymous callers use `requests_per_minute`, auth endpoints use
+    /// `auth_requests_per_15min`.
+    #[serde(default = "default_rate_limit_tiers")]
+    pub tiers: HashMap<String, RateLimitTier>,
+}
+
+/// Sliding-window limit for one tier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimitTier {
+    pub requests: u32,
+    pub window_seconds: u64,
+}
+
+fn default_rate_limit_tiers() -> HashMap<String, RateLimitTier> {
+    [("standard", 300), ("premium", 1200), ("admin", 6000)]
+        .into_iter()
+        .map(|(tier, requests)| {
+            (
+                tier.to_string(),
+                RateLimitTier {
+                    requests,
+                    window_seconds: 60,
+                },
+            )
+        })
+        .collect()
 }
 
 /// External services configuration
@@ -144,10 +243,83 @@ pub struct ServicesConfig {
     pub notification_service_url: String,
     pub storage_service_url: String,
     pub ml_service_url: Option<String>,
+    /// Cap on file uploads, which are streamed to the upstream
     pub max_file_size_mb: usize,
+    /// Cap on every other request body
+    #[serde(default = "default_max_json_body_kb")]
+    pub max_json_body_kb: usize,
     pub supported_file_types: Vec<String>,
     pub analysis_timeout_seconds: u64,
     pub upload_path: String,
+    #[serde(default = "default_submission_service_url")]
+    pub submission_service_url: String,
+    #[serde(default = "default_payment_service_url")]
+    pub payment_service_url: String,
+    #[serde(default = "default_reputation_service_url")]
+    pub reputation_service_url: String,
+    #[serde(default = "default_consensus_service_url")]
+    pub consensus_service_url: String,
+    /// Key the gateway presents to the analysis engine on proxied requests
+    #[serde(default)]
+    pub analysis_engine_api_key: Option<String>,
+    /// Connection pool and timeout overrides, keyed by upstream service name
+    #[serde(default)]
+    pub upstreams: HashMap<String, UpstreamSettings>,
+}
+
+/// Connection pool and timeouts for one proxied upstream
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpstreamSettings {
+    pub connect_timeout_ms: u64,
+    /// Whole-exchange timeout, including streaming the request and response bodies
+    pub request_timeout_seconds: u64,
+    pub pool_max_idle_per_host: usize,
+    pub pool_idle_timeout_seconds: u64,
+}
+
+impl Default for UpstreamSettings {
+    fn default() -> Self {
+        Self {
+            connect_timeout_ms: 2_000,
+            request_timeout_seconds: 30,
+            pool_max_idle_per_host: 32,
+            pool_idle_timeout_seconds: 90,
+        }
+    }
+}
+
+fn default_max_json_body_kb() -> usize {
+    1024
+}
+
+fn default_submission_service_url() -> String {
+    "http://localhost:8085".to_string()
+}
+
+fn default_reputation_service_url() -> String {
+    "http://localhost:8086".to_string()
+}
+
+fn default_consensus_service_url() -> String {
+    "http://localhost:8087".to_string()
+}
+
+fn default_payment_service_url() -> String {
+    "http://localhost:8088".to_string()
+}
+
+impl ServicesConfig {
+    /// Settings for an upstream; services that receive file uploads default
+    /// to the analysis timeout instead of the short API timeout
+    pub fn upstream_settings(&self, service: &str) -> UpstreamSettings {
+        self.upstreams.get(service).copied().unwrap_or_else(|| match service {
+            "analysis-engine" | "submission-service" => UpstreamSettings {
+                request_timeout_seconds: self.analysis_timeout_seconds,
+                ..UpstreamSettings::default()
+            },
+            _ => UpstreamSettings::default(),
+        })
+    }
 }
 
 /// Feature flags configuration
@@ -179,6 +351,42 @@ pub struct MonitoringConfig {
     pub jaeger_endpoint: Option<String>,
 }
 
+/// Usage metering and billing export configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageConfig {
+    pub enabled: bool,
+    pub flush_interval_seconds: u64,
+    pub billing_export_dir: String,
+}
+
+/// Replay of retried writes that carry an `Idempotency-Key`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdempotencyConfig {
+    pub enabled: bool,
+    /// How long a stored response is replayed for
+    pub ttl_seconds: u64,
+    /// Responses with larger bodies are not stored (the key is released)
+    pub max_response_bytes: usize,
+}
+
+/// Organization quotas: pooled rate limits and daily budgets shared by
+/// every member and API key of an organization
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuotaConfig {
+    pub enabled: bool,
+    /// How long quota definitions are cached in Redis
+    pub cache_seconds: u64,
+}
+
+/// Lifecycle of the API versions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiVersionsConfig {
+    /// When v1 routes with a v2 successor were deprecated (`Deprecation` header)
+    pub v1_deprecated_at: DateTime<Utc>,
+    /// When those routes stop being served (`Sunset` header), once decided
+    pub v1_sunset_at: Option<DateTime<Utc>>,
+}
+
 /// Environment type
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -223,6 +431,10 @@ impl Default for AppConfig {
             services: ServicesConfig::default(),
             features: FeaturesConfig::default(),
             monitoring: MonitoringConfig::default(),
+            usage: UsageConfig::default(),
+            idempotency: IdempotencyConfig::default(),
+            quotas: QuotaConfig::default(),
+            api_versions: ApiVersionsConfig::default(),
         }
     }
 }
@@ -316,8 +528,12 @@ impl Default for SecurityConfig {
             max_login_attempts: 5,
             lockout_duration_minutes: 15,
             session_timeout_minutes: 60,
+            jwt_key_rotation_days: default_jwt_key_rotation_days(),
+            accept_legacy_hs256_tokens: false,
+            cookies: CookieConfig::default(),
             cors: CorsConfig::default(),
             rate_limiting: RateLimitingConfig::default(),
+            trusted_proxies: vec![],
         }
     }
 }
@@ -350,6 +566,7 @@ impl Default for RateLimitingConfig {
             auth_requests_per_15min: 5,
             api_requests_per_hour: 1000,
             whitelist_ips: vec![],
+            tiers: default_rate_limit_tiers(),
         }
     }
 }
@@ -363,6 +580,7 @@ impl Default for ServicesConfig {
             storage_service_url: "http://localhost:8084".to_string(),
             ml_service_url: None,
             max_file_size_mb: 100,
+            max_json_body_kb: default_max_json_body_kb(),
             supported_file_types: vec![
                 "exe".to_string(),
                 "dll".to_string(),
@@ -372,6 +590,12 @@ impl Default for ServicesConfig {
             ],
             analysis_timeout_seconds: 300,
             upload_path: "./uploads".to_string(),
+            submission_service_url: default_submission_service_url(),
+            payment_service_url: default_payment_service_url(),
+            reputation_service_url: default_reputation_service_url(),
+            consensus_service_url: default_consensus_service_url(),
+            analysis_engine_api_key: None,
+            upstreams: HashMap::new(),
         }
     }
 }
@@ -409,6 +633,62 @@ impl Default for MonitoringConfig {
     }
 }
 
+impl Default for UsageConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            flush_interval_seconds: 60,
+            billing_export_dir: "./billing-exports".to_string(),
+        }
+    }
+}
+
+impl Default for IdempotencyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ttl_seconds: 86_400,
+            max_response_bytes: 1024 * 1024,
+        }
+    }
+}
+
+impl Default for CookieConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            secure: true,
+            same_site: SameSite::Lax,
+            domain: None,
+        }
+    }
+}
+
+impl Default for QuotaConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            cache_seconds: 60,
+        }
+    }
+}
+
+impl Default for ApiVersionsConfig {
+    fn default() -> Self {
+        Self {
+            // Release of API v2
+            v1_deprecated_at: Utc.with_ymd_and_hms(2026, 10, 18, 0, 0, 0).unwrap(),
+            v1_sunset_at: None,
+        }
+    }
+}
+
+fn parse_timestamp(name: &str, value: &str) -> ConfigResult<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|_| ConfigError::InvalidValue(format!("{} must be an RFC 3339 timestamp", name)))
+}
+
 impl AppConfig {
     /// Load configuration from a TOML file
     pub fn from_file<P: AsRef<Path>>(path: P) -> ConfigResult<Self> {
@@ -504,6 +784,38 @@ impl AppConfig {
             ));
         }
 
+        if let Some(minutes) = std::env::var("SESSION_TIMEOUT_MINUTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+        {
+            config.security.session_timeout_minutes = minutes;
+        }
+        if let Some(days) = std::env::var("JWT_KEY_ROTATION_DAYS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+        {
+            config.security.jwt_key_rotation_days = days;
+        }
+        if let Ok(value) = std::env::var("JWT_ACCEPT_LEGACY_HS256") {
+            config.security.accept_legacy_hs256_tokens = value == "true";
+        }
+
+        // Session cookies
+        if let Ok(value) = std::env::var("SESSION_COOKIES_ENABLED") {
+            config.security.cookies.enabled = value == "true";
+        }
+        if let Ok(value) = std::env::var("SESSION_COOKIE_SECURE") {
+            config.security.cookies.secure = value != "false";
+        }
+        if let Ok(value) = std::env::var("SESSION_COOKIE_SAMESITE") {
+            config.security.cookies.same_site = SameSite::parse(&value).ok_or_else(|| {
+                ConfigError::InvalidValue("Invalid SESSION_COOKIE_SAMESITE".to_string())
+            })?;
+        }
+        if let Ok(domain) = std::env::var("SESSION_COOKIE_DOMAIN") {
+            config.security.cookies.domain = Some(domain).filter(|d| !d.is_empty());
+        }
+
         // CORS origins
         if let Ok(origins) = std::env::var("CORS_ALLOWED_ORIGINS") {
             config.security.cors.allowed_origins =
@@ -517,6 +829,30 @@ impl AppConfig {
         if let Ok(url) = std::env::var("BOUNTY_MANAGER_URL") {
             config.services.bounty_manager_url = url;
         }
+        if let Ok(url) = std::env::var("NOTIFICATION_SERVICE_URL") {
+            config.services.notification_service_url = url;
+        }
+        if let Ok(url) = std::env::var("SUBMISSION_SERVICE_URL") {
+            config.services.submission_service_url = url;
+        }
+        if let Ok(url) = std::env::var("PAYMENT_SERVICE_URL") {
+            config.services.payment_service_url = url;
+        }
+        if let Ok(url) = std::env::var("REPUTATION_SERVICE_URL") {
+            config.services.reputation_service_url = url;
+        }
+        if let Ok(url) = std::env::var("CONSENSUS_SERVICE_URL") {
+            config.services.consensus_service_url = url;
+        }
+        if let Ok(key) = std::env::var("ANALYSIS_ENGINE_API_KEY") {
+            config.services.analysis_engine_api_key = Some(key);
+        }
+        if let Ok(mb) = std::env::var("MAX_FILE_SIZE_MB") {
+            config.services.max_file_size_mb = mb.parse().unwrap_or(100);
+        }
+        if let Ok(kb) = std::env::var("MAX_JSON_BODY_KB") {
+            config.services.max_json_body_kb = kb.parse().unwrap_or(default_max_json_body_kb());
+        }
 
         // Feature flags
         if let Ok(val) = std::env::var("ENABLE_MFA") {
@@ -534,6 +870,66 @@ impl AppConfig {
             config.monitoring.sentry_dsn = Some(dsn);
         }
 
+        // Rate limiting
+        if let Ok(val) = std::env::var("RATE_LIMIT_ENABLED") {
+            config.security.rate_limiting.enabled = val.parse().unwrap_or(true);
+        }
+        if let Ok(val) = std::env::var("RATE_LIMIT_REQUESTS_PER_MINUTE") {
+            config.security.rate_limiting.requests_per_minute = val.parse().map_err(|_| {
+                ConfigError::InvalidValue("Invalid RATE_LIMIT_REQUESTS_PER_MINUTE".to_string())
+            })?;
+        }
+        if let Ok(ips) = std::env::var("RATE_LIMIT_WHITELIST_IPS") {
+            config.security.rate_limiting.whitelist_ips =
+                ips.split(',').map(|s| s.trim().to_string()).collect();
+        }
+        if let Ok(proxies) = std::env::var("TRUSTED_PROXIES") {
+            config.security.trusted_proxies = proxies
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+        }
+
+        // Usage metering
+        if let Ok(val) = std::env::var("USAGE_METERING_ENABLED") {
+            config.usage.enabled = val.parse().unwrap_or(true);
+        }
+        if let Ok(val) = std::env::var("USAGE_FLUSH_INTERVAL_SECONDS") {
+            config.usage.flush_interval_seconds = val.parse().map_err(|_| {
+                ConfigError::InvalidValue("Invalid USAGE_FLUSH_INTERVAL_SECONDS".to_string())
+            })?;
+        }
+        if let Ok(dir) = std::env::var("BILLING_EXPORT_DIR") {
+            config.usage.billing_export_dir = dir;
+        }
+
+        // Idempotency keys
+        if let Ok(val) = std::env::var("IDEMPOTENCY_ENABLED") {
+            config.idempotency.enabled = val.parse().unwrap_or(true);
+        }
+        if let Ok(val) = std::env::var("IDEMPOTENCY_TTL_SECONDS") {
+            config.idempotency.ttl_seconds = val.parse().map_err(|_| {
+                ConfigError::InvalidValue("Invalid IDEMPOTENCY_TTL_SECONDS".to_string())
+            })?;
+        }
+        // Organization quotas
+        if let Ok(val) = std::env::var("ORG_QUOTAS_ENABLED") {
+            config.quotas.enabled = val.parse().unwrap_or(true);
+        }
+        if let Ok(val) = std::env::var("ORG_QUOTA_CACHE_SECONDS") {
+            config.quotas.cache_seconds = val.parse().map_err(|_| {
+                ConfigError::InvalidValue("Invalid ORG_QUOTA_CACHE_SECONDS".to_string())
+            })?;
+        }
+
+        if let Ok(val) = std::env::var("API_V1_DEPRECATED_AT") {
+            config.api_versions.v1_deprecated_at = parse_timestamp("API_V1_DEPRECATED_AT", &val)?;
+        }
+        if let Ok(val) = std::env::var("API_V1_SUNSET_AT") {
+            config.api_versions.v1_sunset_at = Some(parse_timestamp("API_V1_SUNSET_AT", &val)?);
+        }
+
         config.validate()?;
         Ok(config)
     }
@@ -590,6 +986,60 @@ impl AppConfig {
         if let Ok(jwt_secret) = std::env::var("JWT_SECRET") {
             self.security.jwt_secret = jwt_secret;
         }
+        if let Some(minutes) = std::env::var("SESSION_TIMEOUT_MINUTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+        {
+            self.security.session_timeout_minutes = minutes;
+        }
+        if let Some(days) = std::env::var("JWT_KEY_ROTATION_DAYS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+        {
+            self.security.jwt_key_rotation_days = days;
+        }
+        if let Ok(value) = std::env::var("JWT_ACCEPT_LEGACY_HS256") {
+            self.security.accept_legacy_hs256_tokens = value == "true";
+        }
+        if let Ok(value) = std::env::var("SESSION_COOKIES_ENABLED") {
+            self.security.cookies.enabled = value == "true";
+        }
+        if let Ok(value) = std::env::var("SESSION_COOKIE_SECURE") {
+            self.security.cookies.secure = value != "false";
+        }
+        if let Some(same_site) = std::env::var("SESSION_COOKIE_SAMESITE")
+            .ok()
+            .and_then(|v| SameSite::parse(&v))
+        {
+            self.security.cookies.same_site = same_site;
+        }
+        if let Ok(domain) = std::env::var("SESSION_COOKIE_DOMAIN") {
+            self.security.cookies.domain = Some(domain).filter(|d| !d.is_empty());
+        }
+        if let Ok(key) = std::env::var("ANALYSIS_ENGINE_API_KEY") {
+            self.services.analysis_engine_api_key = Some(key);
+        }
+        if let Ok(dir) = std::env::var("BILLING_EXPORT_DIR") {
+            self.usage.billing_export_dir = dir;
+        }
+        if let Some(seconds) = std::env::var("IDEMPOTENCY_TTL_SECONDS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+        {
+            self.idempotency.ttl_seconds = seconds;
+        }
+        if let Some(at) = std::env::var("API_V1_DEPRECATED_AT")
+            .ok()
+            .and_then(|v| parse_timestamp("API_V1_DEPRECATED_AT", &v).ok())
+        {
+            self.api_versions.v1_deprecated_at = at;
+        }
+        if let Some(at) = std::env::var("API_V1_SUNSET_AT")
+            .ok()
+            .and_then(|v| parse_timestamp("API_V1_SUNSET_AT", &v).ok())
+        {
+            self.api_versions.v1_sunset_at = Some(at);
+        }
     }
 
     /// Validate configuration
@@ -611,6 +1061,18 @@ impl AppConfig {
             return Err(ConfigError::MissingField("redis.url".to_string()));
         }
 
+        if let Some(proxy) = self
+            .security
+            .trusted_proxies
+            .iter()
+            .find(|proxy| crate::middleware::rate_limiter::parse_ip_range(proxy).is_none())
+        {
+            return Err(ConfigError::InvalidValue(format!(
+                "TRUSTED_PROXIES entry {} is not an address or CIDR range",
+                proxy
+            )));
+        }
+
         // Validate JWT secret in production
         if self.server.environment.is_production() {
             if self.security.jwt_secret == "change-me-in-production" {
@@ -625,6 +1087,26 @@ impl AppConfig {
             }
         }
 
+        // Browsers drop SameSite=None cookies that are not Secure
+        let cookies = &self.security.cookies;
+        if cookies.same_site == SameSite::None && !cookies.secure {
+            return Err(ConfigError::InvalidValue(
+                "SameSite=None session cookies must be Secure".to_string(),
+            ));
+        }
+        if cookies.enabled && !cookies.secure && self.server.environment.is_production() {
+            return Err(ConfigError::InvalidValue(
+                "Session cookies must be Secure in production".to_string(),
+            ));
+        }
+        if self.server.environment.is_production()
+            && self.security.cors.allowed_origins.iter().any(|o| o == "*")
+        {
+            return Err(ConfigError::InvalidValue(
+                "CORS origins must be listed explicitly in production".to_string(),
+            ));
+        }
+
         // Validate blockchain configuration
         if self.features.enable_blockchain_integration {
             if self.blockchain.rpc_url.is_empty() {
@@ -632,6 +1114,18 @@ impl AppConfig {
             }
         }
 
+        if self.usage.enabled && self.usage.flush_interval_seconds == 0 {
+            return Err(ConfigError::InvalidValue(
+                "usage.flush_interval_seconds cannot be 0".to_string(),
+            ));
+        }
+
+        if self.idempotency.enabled && self.idempotency.ttl_seconds == 0 {
+            return Err(ConfigError::InvalidValue(
+                "idempotency.ttl_seconds cannot be 0".to_string(),
+            ));
+        }
+
         // Validate rate limiting
         if self.security.rate_limiting.enabled {
             if self.security.rate_limiting.requests_per_minute == 0 {
@@ -639,6 +1133,18 @@ impl AppConfig {
                     "Rate limit requests_per_minute cannot be 0".to_string(),
                 ));
             }
+            if let Some((tier, _)) = self
+                .security
+                .rate_limiting
+                .tiers
+                .iter()
+                .find(|(_, t)| t.requests == 0 || t.window_seconds == 0)
+            {
+                return Err(ConfigError::InvalidValue(format!(
+                    "Rate limit tier '{}' must allow at least one request per non-empty window",
+                    tier
+                )));
+            }
         }
 
         Ok(())
@@ -654,6 +1160,11 @@ impl AppConfig {
         self.services.max_file_size_mb * 1024 * 1024
     }
 
+    /// Get the body limit of non-upload routes in bytes
+    pub fn max_json_body_bytes(&self) -> usize {
+        self.services.max_json_body_kb * 1024
+    }
+
     /// Check if a feature is enabled
     pub fn is_feature_enabled(&self, feature: &str) -> bool {
         match feature {
@@ -685,6 +1196,7 @@ impl AppConfig {
         println!("Redis: {}", self.mask_credentials(&self.redis.url));
         println!("Blockchain RPC: {}", self.blockchain.rpc_url);
         println!("Max file size: {} MB", self.services.max_file_size_mb);
+        println!("Max JSON body: {} KB", self.services.max_json_body_kb);
         println!(
             "Rate limiting: {}",
             if self.security.rate_limiting.enabled {
@@ -758,6 +1270,33 @@ mod tests {
         assert!(config.validate().is_err()); // Should fail with default JWT secret
     }
 
+    #[test]
+    fn test_cookie_validation() {
+        let mut config = AppConfig::default();
+        config.security.cookies.same_site = SameSite::None;
+        config.security.cookies.secure = false;
+        assert!(config.validate().is_err());
+
+        config.security.cookies.same_site = SameSite::Lax;
+        assert!(config.validate().is_ok());
+        assert_eq!(SameSite::parse("STRICT"), Some(SameSite::Strict));
+        assert_eq!(SameSite::parse("sometimes"), None);
+    }
+
+    #[test]
+    fn test_cors_wildcard_rejected_in_production() {
+        let mut config = AppConfig::default();
+        config.security.cors.allowed_origins = vec!["*".to_string()];
+        assert!(config.validate().is_ok());
+
+        config.server.environment = Environment::Production;
+        config.security.jwt_secret = "x".repeat(32);
+        assert!(config.validate().is_err());
+
+        config.security.cors.allowed_origins = vec!["https://app.nexus-security.com".to_string()];
+        assert!(config.validate().is_ok());
+    }
+
     #[test]
     fn test_feature_flags() {
         let config = AppConfig::default();
@@ -770,6 +1309,7 @@ mod tests {
     fn test_max_file_size_bytes() {
         let config = AppConfig::default();
         assert_eq!(config.max_file_size_bytes(), 100 * 1024 * 1024);
+        assert_eq!(config.max_json_body_bytes(), 1024 * 1024);
     }
 
     #[test]
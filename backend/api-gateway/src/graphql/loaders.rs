@@ -0,0 +1,205 @@
+//! Per-request dataloaders
+//!
+//! Each loader answers every key requested while resolving one level of a
+//! query with a single `= ANY($1)` query. Loaders are created per request,
+//! so their cache never outlives it. One-to-many loaders return at most
+//! [`MAX_CHILDREN`] rows per key, newest first.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::Arc;
+
+use async_graphql::dataloader::{DataLoader, Loader};
+use async_graphql::{Context, Request};
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use super::types::{
+    AnalysisNode, BountyNode, ReputationNode, SubmissionNode, UserNode, ANALYSIS_COLUMNS,
+    BOUNTY_COLUMNS, REPUTATION_COLUMNS, SUBMISSION_COLUMNS, USER_COLUMNS,
+};
+
+/// Cap on the items of a nested list
+pub const MAX_CHILDREN: i64 = 50;
+
+type LoadResult<V> = Result<HashMap<Uuid, V>, Arc<sqlx::Error>>;
+
+/// Log the cause and hand the client a generic error
+pub(crate) fn internal_error(e: impl Display) -> async_graphql::Error {
+    tracing::error!("GraphQL resolver failed: {}", e);
+    async_graphql::Error::new("Internal server error")
+}
+
+/// Registers and looks up the request's loaders
+pub struct Loaders;
+
+impl Loaders {
+    pub fn register(request: Request, pool: &PgPool) -> Request {
+        fn loader<L>(loader: L) -> DataLoader<L> {
+            DataLoader::new(loader, tokio::spawn)
+        }
+
+        request
+            .data(loader(UserLoader(pool.clone())))
+            .data(loader(BountyLoader(pool.clone())))
+            .data(loader(SubmissionLoader(pool.clone())))
+            .data(loader(ReputationLoader(pool.clone())))
+            .data(loader(BountyAnalysesLoader(pool.clone())))
+            .data(loader(SubmissionAnalysesLoader(pool.clone())))
+            .data(loader(CreatorBountiesLoader(pool.clone())))
+            .data(loader(SubmitterSubmissionsLoader(pool.clone())))
+    }
+
+    pub fn get<'a, L: Send + Sync + 'static>(
+        ctx: &Context<'a>,
+    ) -> async_graphql::Result<&'a DataLoader<L>> {
+        ctx.data::<DataLoader<L>>()
+    }
+}
+
+async fn fetch<T>(pool: &PgPool, sql: &str, keys: &[Uuid]) -> Result<Vec<T>, Arc<sqlx::Error>>
+where
+    T: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+{
+    sqlx::query_as::<_, T>(sql)
+        .bind(keys)
+        .fetch_all(pool)
+        .await
+        .map_err(Arc::new)
+}
+
+/// Rows of `table` whose `key` is among the keys, newest first, at most
+/// [`MAX_CHILDREN`] per key
+fn children_sql(columns: &str, table: &str, key: &str) -> String {
+    format!(
+        "SELECT * FROM (
+             SELECT {columns}, ROW_NUMBER() OVER (PARTITION BY {key} ORDER BY created_at DESC) AS row_number
+             FROM {table} WHERE {key} = ANY($1)
+         ) ranked
+         WHERE row_number <= {MAX_CHILDREN}
+         ORDER BY row_number"
+    )
+}
+
+fn group<V>(rows: Vec<V>, key: impl Fn(&V) -> Option<Uuid>) -> HashMap<Uuid, Vec<V>> {
+    let mut grouped: HashMap<Uuid, Vec<V>> = HashMap::new();
+    for row in rows {
+        if let Some(k) = key(&row) {
+            grouped.entry(k).or_default().push(row);
+        }
+    }
+    grouped
+}
+
+pub struct UserLoader(PgPool);
+
+impl Loader<Uuid> for UserLoader {
+    type Value = UserNode;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(&self, keys: &[Uuid]) -> LoadResult<UserNode> {
+        let sql = format!("SELECT {} FROM users WHERE id = ANY($1)", USER_COLUMNS);
+        let rows: Vec<UserNode> = fetch(&self.0, &sql, keys).await?;
+        Ok(rows.into_iter().map(|r| (r.id, r)).collect())
+    }
+}
+
+pub struct BountyLoader(PgPool);
+
+impl Loader<Uuid> for BountyLoader {
+    type Value = BountyNode;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(&self, keys: &[Uuid]) -> LoadResult<BountyNode> {
+        let sql = format!("SELECT {} FROM bounties WHERE id = ANY($1)", BOUNTY_COLUMNS);
+        let rows: Vec<BountyNode> = fetch(&self.0, &sql, keys).await?;
+        Ok(rows.into_iter().map(|r| (r.id, r)).collect())
+    }
+}
+
+pub struct SubmissionLoader(PgPool);
+
+impl Loader<Uuid> for SubmissionLoader {
+    type Value = SubmissionNode;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(&self, keys: &[Uuid]) -> LoadResult<SubmissionNode> {
+        let sql = format!("SELECT {} FROM submissions WHERE id = ANY($1)", SUBMISSION_COLUMNS);
+        let rows: Vec<SubmissionNode> = fetch(&self.0, &sql, keys).await?;
+        Ok(rows.into_iter().map(|r| (r.id, r)).collect())
+    }
+}
+
+/// Reputation breakdown, keyed by user id
+pub struct ReputationLoader(PgPool);
+
+impl Loader<Uuid> for ReputationLoader {
+    type Value = ReputationNode;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(&self, keys: &[Uuid]) -> LoadResult<ReputationNode> {
+        let sql = format!(
+            "SELECT {} FROM reputation_scores WHERE user_id = ANY($1)",
+            REPUTATION_COLUMNS
+        );
+        let rows: Vec<ReputationNode> = fetch(&self.0, &sql, keys).await?;
+        Ok(rows.into_iter().map(|r| (r.user_id, r)).collect())
+    }
+}
+
+/// Analyses, keyed by bounty id
+pub struct BountyAnalysesLoader(PgPool);
+
+impl Loader<Uuid> for BountyAnalysesLoader {
+    type Value = Vec<AnalysisNode>;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(&self, keys: &[Uuid]) -> LoadResult<Vec<AnalysisNode>> {
+        let sql = children_sql(ANALYSIS_COLUMNS, "analysis_results", "bounty_id");
+        let rows: Vec<AnalysisNode> = fetch(&self.0, &sql, keys).await?;
+        Ok(group(rows, |r| r.bounty_id))
+    }
+}
+
+/// Analyses, keyed by submission id
+pub struct SubmissionAnalysesLoader(PgPool);
+
+impl Loader<Uuid> for SubmissionAnalysesLoader {
+    type Value = Vec<AnalysisNode>;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(&self, keys: &[Uuid]) -> LoadResult<Vec<AnalysisNode>> {
+        let sql = children_sql(ANALYSIS_COLUMNS, "analysis_results", "submission_id");
+        let rows: Vec<AnalysisNode> = fetch(&self.0, &sql, keys).await?;
+        Ok(group(rows, |r| Some(r.submission_id)))
+    }
+}
+
+/// Bounties, keyed by creator id
+pub struct CreatorBountiesLoader(PgPool);
+
+impl Loader<Uuid> for CreatorBountiesLoader {
+    type Value = Vec<BountyNode>;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(&self, keys: &[Uuid]) -> LoadResult<Vec<BountyNode>> {
+        let sql = children_sql(BOUNTY_COLUMNS, "bounties", "creator_id");
+        let rows: Vec<BountyNode> = fetch(&self.0, &sql, keys).await?;
+        Ok(group(rows, |r| Some(r.creator_id)))
+    }
+}
+
+/// Submissions, keyed by submitter id
+pub struct SubmitterSubmissionsLoader(PgPool);
+
+impl Loader<Uuid> for SubmitterSubmissionsLoader {
+    type Value = Vec<SubmissionNode>;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(&self, keys: &[Uuid]) -> LoadResult<Vec<SubmissionNode>> {
+        let sql = children_sql(SUBMISSION_COLUMNS, "submissions", "submitter_id");
+        let rows: Vec<SubmissionNode> = fetch(&self.0, &sql, keys).await?;
+        Ok(group(rows, |r| Some(r.submitter_id)))
+    }
+}
@@ -0,0 +1,211 @@
+//! GraphQL schema for dashboard queries
+//!
+//! One query composes bounties, submissions, analyses, reputation and
+//! users, so a dashboard page is a single round trip instead of one REST
+//! call per panel. Nested fields are resolved through per-request
+//! dataloaders (`loaders`), which batch every lookup of a level into one
+//! `= ANY($1)` query, so a page of bounties with their creators and
+//! analyses costs three queries however many bounties it holds.
+//!
+//! The schema is read-only. It is served behind the same auth, rate
+//! limiting and metering layers as the REST routes, and query depth and
+//! complexity are capped so one request cannot fan out without bound.
+//! Users and submissions looked up by id are visible only to their owner
+//! and admins, and embargoed verdicts are left out of `analyses`.
+
+pub mod loaders;
+pub mod types;
+
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Error, Object, Result, Schema};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::middleware::auth::Claims;
+use crate::services::embargo::Viewer;
+use loaders::{internal_error, BountyLoader, Loaders, SubmissionLoader, UserLoader};
+use types::{BountyNode, SubmissionNode, UserNode, BOUNTY_COLUMNS, USER_COLUMNS};
+
+pub type DashboardSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+const MAX_DEPTH: usize = 8;
+const MAX_COMPLEXITY: usize = 1_000;
+
+/// Largest page a list field returns
+pub const MAX_PAGE_SIZE: i32 = 100;
+const DEFAULT_PAGE_SIZE: i32 = 20;
+
+pub fn build_schema(pool: PgPool) -> DashboardSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(pool)
+        .limit_depth(MAX_DEPTH)
+        .limit_complexity(MAX_COMPLEXITY)
+        .finish()
+}
+
+/// Clamp a client-supplied page size
+pub(crate) fn page_size(limit: Option<i32>) -> i64 {
+    limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE) as i64
+}
+
+/// The caller, as registered by the handler
+pub(crate) fn viewer(ctx: &Context<'_>) -> Option<Viewer> {
+    ctx.data_opt::<Viewer>().copied()
+}
+
+/// Only `owner` and admins may read the object
+fn ensure_owner_or_admin(ctx: &Context<'_>, owner: Uuid) -> Result<()> {
+    match viewer(ctx) {
+        Some(v) if v.is_admin || v.user_id == owner => Ok(()),
+        _ => Err(Error::new("Forbidden")),
+    }
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// The authenticated caller
+    async fn me(&self, ctx: &Context<'_>) -> Result<Option<UserNode>> {
+        let claims = ctx.data::<Claims>()?;
+        Loaders::get::<UserLoader>(ctx)?.load_one(claims.sub).await.map_err(internal_error)
+    }
+
+    /// A user's profile; only the user themself and admins may look it up
+    async fn user(&self, ctx: &Context<'_>, id: Uuid) -> Result<Option<UserNode>> {
+        ensure_owner_or_admin(ctx, id)?;
+        Loaders::get::<UserLoader>(ctx)?.load_one(id).await.map_err(internal_error)
+    }
+
+    async fn bounty(&self, ctx: &Context<'_>, id: Uuid) -> Result<Option<BountyNode>> {
+        Loaders::get::<BountyLoader>(ctx)?.load_one(id).await.map_err(internal_error)
+    }
+
+    /// A sample; only its submitter and admins may look it up
+    async fn submission(&self, ctx: &Context<'_>, id: Uuid) -> Result<Option<SubmissionNode>> {
+        let submission =
+            Loaders::get::<SubmissionLoader>(ctx)?.load_one(id).await.map_err(internal_error)?;
+        if let Some(submission) = &submission {
+            ensure_owner_or_admin(ctx, submission.submitter_id)?;
+        }
+        Ok(submission)
+    }
+
+    /// Bounties, newest first, optionally filtered by status
+    #[graphql(complexity = "page_size(limit) as usize * child_complexity")]
+    async fn bounties(
+        &self,
+        ctx: &Context<'_>,
+        status: Option<String>,
+        limit: Option<i32>,
+        #[graphql(default)] offset: i32,
+    ) -> Result<Vec<BountyNode>> {
+        let sql = format!(
+            "SELECT {} FROM bounties
+             WHERE $1::TEXT IS NULL OR COALESCE(status, bounty_status) = $1
+             ORDER BY created_at DESC
+             LIMIT $2 OFFSET $3",
+            BOUNTY_COLUMNS
+        );
+        sqlx::query_as::<_, BountyNode>(&sql)
+            .bind(status)
+            .bind(page_size(limit))
+            .bind(offset.max(0) as i64)
+            .fetch_all(ctx.data::<PgPool>()?)
+            .await
+            .map_err(internal_error)
+    }
+
+    /// Active users by reputation, highest first
+    #[graphql(complexity = "page_size(limit) as usize * child_complexity")]
+    async fn leaderboard(&self, ctx: &Context<'_>, limit: Option<i32>) -> Result<Vec<UserNode>> {
+        let sql = format!(
+            "SELECT {} FROM users
+             WHERE is_active = true
+             ORDER BY reputation_score DESC NULLS LAST
+             LIMIT $1",
+            USER_COLUMNS
+        );
+        sqlx::query_as::<_, UserNode>(&sql)
+            .bind(page_size(limit))
+            .fetch_all(ctx.data::<PgPool>()?)
+            .await
+            .map_err(internal_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> DashboardSchema {
+        // Never connects; resolvers that reach the database fail fast
+        let pool = sqlx::postgres::PgPoolOptions::new()
+            .acquire_timeout(std::time::Duration::from_millis(50))
+            .connect_lazy("postgres://localhost/nexus_test")
+            .unwrap();
+        build_schema(pool)
+    }
+
+    #[test]
+    fn test_page_size_is_clamped() {
+        assert_eq!(page_size(None), DEFAULT_PAGE_SIZE as i64);
+        assert_eq!(page_size(Some(0)), 1);
+        assert_eq!(page_size(Some(10_000)), MAX_PAGE_SIZE as i64);
+    }
+
+    #[tokio::test]
+    async fn test_schema_exposes_dashboard_types() {
+        let sdl = schema().sdl();
+        for ty in ["type Bounty", "type Submission", "type Analysis", "type Reputation", "type User"] {
+            assert!(sdl.contains(ty), "schema is missing {}", ty);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deep_queries_are_rejected() {
+        // Mostly single-object hops, so the query stays under the complexity cap
+        let query = r#"{ bounty(id: "00000000-0000-0000-0000-000000000000") {
+            submission { analyses { bounty { submission { analyses {
+                bounty { creator { reputation { totalScore } } }
+            } } } } }
+        } }"#;
+        let response = schema().execute(query).await;
+        assert!(
+            response.errors.iter().any(|e| e.message.contains("nested too deep")),
+            "{:?}",
+            response.errors
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dashboard_query_is_within_limits() {
+        let query = r#"{
+            me { username reputation { totalScore rankPosition }
+                 submissions { originalFilename analysisStatus analyses { verdict } } }
+            bounties(limit: 20) { title rewardAmount creator { username }
+                                  analyses { verdict confidenceScore } }
+            leaderboard(limit: 10) { username reputationScore }
+        }"#;
+        let response = schema().execute(query).await;
+        assert!(
+            !response
+                .errors
+                .iter()
+                .any(|e| e.message.contains("too complex") || e.message.contains("nested too deep")),
+            "{:?}",
+            response.errors
+        );
+    }
+
+    #[tokio::test]
+    async fn test_oversized_pages_are_rejected() {
+        // 100 bounties, each with its analyses and their bounties
+        let query = "{ bounties(limit: 100) { analyses { bounty { id } } } }";
+        let response = schema().execute(query).await;
+        assert!(
+            response.errors.iter().any(|e| e.message.contains("too complex")),
+            "{:?}",
+            response.errors
+        );
+    }
+}
@@ -0,0 +1,215 @@
+//! GraphQL object types
+//!
+//! Rows are read straight from the platform tables; foreign keys are kept
+//! off the schema and exposed as nested objects resolved by the loaders.
+
+use async_graphql::{ComplexObject, Context, Result, SimpleObject};
+use chrono::{DateTime, Utc};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use super::loaders::{
+    internal_error, BountyAnalysesLoader, BountyLoader, CreatorBountiesLoader, Loaders,
+    ReputationLoader, SubmissionAnalysesLoader, SubmissionLoader, SubmitterSubmissionsLoader,
+    UserLoader,
+};
+use crate::services::embargo;
+
+/// Drop analyses of bounties whose verdicts are embargoed from the caller
+async fn visible(ctx: &Context<'_>, analyses: Option<Vec<AnalysisNode>>) -> Result<Vec<AnalysisNode>> {
+    let mut analyses = analyses.unwrap_or_default();
+    let bounty_ids: Vec<Uuid> = analyses.iter().filter_map(|a| a.bounty_id).collect();
+    let withheld = embargo::withheld(ctx.data::<sqlx::PgPool>()?, &bounty_ids, super::viewer(ctx))
+        .await
+        .map_err(internal_error)?;
+    analyses.retain(|a| a.bounty_id.map_or(true, |id| !withheld.contains(&id)));
+    Ok(analyses)
+}
+
+/// Estimated length of a nested list, for query complexity; the loaders
+/// return at most `loaders::MAX_CHILDREN` items
+const CHILD_LIST_COST: usize = 10;
+
+pub const USER_COLUMNS: &str = "id, username, \
+    COALESCE(reputation_score, 0) AS reputation_score, \
+    COALESCE(total_submissions, 0) AS total_submissions, \
+    COALESCE(successful_submissions, 0) AS successful_submissions, \
+    created_at";
+
+pub const BOUNTY_COLUMNS: &str = "id, creator_id, submission_id, title, description, \
+    reward_amount::TEXT AS reward_amount, \
+    COALESCE(status, bounty_status, 'active') AS status, \
+    deadline, COALESCE(participant_count, 0) AS participant_count, \
+    created_at, completed_at";
+
+pub const SUBMISSION_COLUMNS: &str = "id, submitter_id, file_hash, url, original_filename, \
+    file_size, mime_type, submission_type, is_malicious, \
+    confidence_score::FLOAT8 AS confidence_score, \
+    COALESCE(analysis_status, 'pending') AS analysis_status, created_at";
+
+pub const ANALYSIS_COLUMNS: &str = "id, bounty_id, submission_id, engine_id, verdict, \
+    confidence_score::FLOAT8 AS confidence_score, \
+    COALESCE(threat_types, '{}') AS threat_types, \
+    COALESCE(analysis_status, 'completed') AS status, created_at, completed_at";
+
+pub const REPUTATION_COLUMNS: &str = "user_id, \
+    COALESCE(accuracy_score, 0) AS accuracy_score, \
+    COALESCE(speed_score, 0) AS speed_score, \
+    COALESCE(consistency_score, 0) AS consistency_score, \
+    COALESCE(expertise_score, 0) AS expertise_score, \
+    COALESCE(community_score, 0) AS community_score, \
+    COALESCE(total_score, 0) AS total_score, \
+    rank_position, percentile::FLOAT8 AS percentile, last_calculated";
+
+#[derive(Debug, Clone, FromRow, SimpleObject)]
+#[graphql(name = "User", complex)]
+pub struct UserNode {
+    pub id: Uuid,
+    pub username: String,
+    pub reputation_score: i32,
+    pub total_submissions: i32,
+    pub successful_submissions: i32,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[ComplexObject]
+impl UserNode {
+    async fn reputation(&self, ctx: &Context<'_>) -> Result<Option<ReputationNode>> {
+        Loaders::get::<ReputationLoader>(ctx)?.load_one(self.id).await.map_err(internal_error)
+    }
+
+    /// Bounties the user created, newest first
+    #[graphql(complexity = "CHILD_LIST_COST * child_complexity")]
+    async fn bounties(&self, ctx: &Context<'_>) -> Result<Vec<BountyNode>> {
+        let loader = Loaders::get::<CreatorBountiesLoader>(ctx)?;
+        Ok(loader.load_one(self.id).await.map_err(internal_error)?.unwrap_or_default())
+    }
+
+    /// Samples the user submitted, newest first
+    #[graphql(complexity = "CHILD_LIST_COST * child_complexity")]
+    async fn submissions(&self, ctx: &Context<'_>) -> Result<Vec<SubmissionNode>> {
+        let loader = Loaders::get::<SubmitterSubmissionsLoader>(ctx)?;
+        Ok(loader.load_one(self.id).await.map_err(internal_error)?.unwrap_or_default())
+    }
+}
+
+#[derive(Debug, Clone, FromRow, SimpleObject)]
+#[graphql(name = "Bounty", complex)]
+pub struct BountyNode {
+    pub id: Uuid,
+    #[graphql(skip)]
+    pub creator_id: Uuid,
+    #[graphql(skip)]
+    pub submission_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    /// Decimal amount in wei
+    pub reward_amount: String,
+    pub status: String,
+    pub deadline: Option<DateTime<Utc>>,
+    pub participant_count: i32,
+    pub created_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[ComplexObject]
+impl BountyNode {
+    async fn creator(&self, ctx: &Context<'_>) -> Result<Option<UserNode>> {
+        Loaders::get::<UserLoader>(ctx)?.load_one(self.creator_id).await.map_err(internal_error)
+    }
+
+    /// The sample the bounty is for
+    async fn submission(&self, ctx: &Context<'_>) -> Result<Option<SubmissionNode>> {
+        let loader = Loaders::get::<SubmissionLoader>(ctx)?;
+        loader.load_one(self.submission_id).await.map_err(internal_error)
+    }
+
+    /// Engine verdicts on the bounty, newest first
+    #[graphql(complexity = "CHILD_LIST_COST * child_complexity")]
+    async fn analyses(&self, ctx: &Context<'_>) -> Result<Vec<AnalysisNode>> {
+        let loader = Loaders::get::<BountyAnalysesLoader>(ctx)?;
+        visible(ctx, loader.load_one(self.id).await.map_err(internal_error)?).await
+    }
+}
+
+#[derive(Debug, Clone, FromRow, SimpleObject)]
+#[graphql(name = "Submission", complex)]
+pub struct SubmissionNode {
+    pub id: Uuid,
+    #[graphql(skip)]
+    pub submitter_id: Uuid,
+    pub file_hash: Option<String>,
+    pub url: Option<String>,
+    pub original_filename: Option<String>,
+    pub file_size: Option<i64>,
+    pub mime_type: Option<String>,
+    /// `file` or `url`
+    pub submission_type: String,
+    /// Final consensus, once reached
+    pub is_malicious: Option<bool>,
+    pub confidence_score: Option<f64>,
+    pub analysis_status: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[ComplexObject]
+impl SubmissionNode {
+    async fn submitter(&self, ctx: &Context<'_>) -> Result<Option<UserNode>> {
+        Loaders::get::<UserLoader>(ctx)?.load_one(self.submitter_id).await.map_err(internal_error)
+    }
+
+    /// Engine verdicts on the sample, newest first
+    #[graphql(complexity = "CHILD_LIST_COST * child_complexity")]
+    async fn analyses(&self, ctx: &Context<'_>) -> Result<Vec<AnalysisNode>> {
+        let loader = Loaders::get::<SubmissionAnalysesLoader>(ctx)?;
+        visible(ctx, loader.load_one(self.id).await.map_err(internal_error)?).await
+    }
+}
+
+#[derive(Debug, Clone, FromRow, SimpleObject)]
+#[graphql(name = "Analysis", complex)]
+pub struct AnalysisNode {
+    pub id: Uuid,
+    #[graphql(skip)]
+    pub bounty_id: Option<Uuid>,
+    #[graphql(skip)]
+    pub submission_id: Uuid,
+    pub engine_id: Uuid,
+    pub verdict: String,
+    pub confidence_score: f64,
+    pub threat_types: Vec<String>,
+    pub status: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[ComplexObject]
+impl AnalysisNode {
+    async fn bounty(&self, ctx: &Context<'_>) -> Result<Option<BountyNode>> {
+        let Some(bounty_id) = self.bounty_id else {
+            return Ok(None);
+        };
+        Loaders::get::<BountyLoader>(ctx)?.load_one(bounty_id).await.map_err(internal_error)
+    }
+
+    async fn submission(&self, ctx: &Context<'_>) -> Result<Option<SubmissionNode>> {
+        let loader = Loaders::get::<SubmissionLoader>(ctx)?;
+        loader.load_one(self.submission_id).await.map_err(internal_error)
+    }
+}
+
+#[derive(Debug, Clone, FromRow, SimpleObject)]
+#[graphql(name = "Reputation")]
+pub struct ReputationNode {
+    #[graphql(skip)]
+    pub user_id: Uuid,
+    pub accuracy_score: i32,
+    pub speed_score: i32,
+    pub consistency_score: i32,
+    pub expertise_score: i32,
+    pub community_score: i32,
+    pub total_score: i32,
+    pub rank_position: Option<i32>,
+    pub percentile: Option<f64>,
+    pub last_calculated: Option<DateTime<Utc>>,
+}
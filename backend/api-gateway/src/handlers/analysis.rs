@@ -2,15 +2,20 @@ use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
     response::Json,
+    Extension,
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
+use crate::services::embargo::{self, Viewer};
+use crate::utils::AuthContext;
 use crate::AppState;
 
 /// Query parameters for listing analyses
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct ListAnalysesQuery {
     pub page: Option<u32>,
     pub limit: Option<u32>,
@@ -21,7 +26,7 @@ pub struct ListAnalysesQuery {
 }
 
 /// Response for analysis list
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct AnalysisListResponse {
     pub analyses: Vec<AnalysisSummary>,
     pub total: i64,
@@ -30,7 +35,7 @@ pub struct AnalysisListResponse {
 }
 
 /// Summary of an analysis
-#[derive(Debug, Serialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
 pub struct AnalysisSummary {
     pub id: Uuid,
     pub file_hash: Option<String>,
@@ -39,10 +44,47 @@ pub struct AnalysisSummary {
     pub confidence: Option<f64>,
     pub created_at: DateTime<Utc>,
     pub completed_at: Option<DateTime<Utc>>,
+    #[serde(skip)]
+    pub bounty_id: Option<Uuid>,
+    /// The verdict is hidden by a verdict embargo on the bounty
+    #[sqlx(default)]
+    pub verdict_withheld: bool,
+}
+
+impl AnalysisSummary {
+    fn withhold_verdict(&mut self) {
+        self.verdict = None;
+        self.confidence = None;
+        self.verdict_withheld = true;
+    }
+}
+
+/// Blank the verdicts of analyses whose bounty is under a verdict embargo
+/// the caller is not exempt from
+pub(crate) async fn apply_embargoes(
+    state: &AppState,
+    caller: Option<&AuthContext>,
+    analyses: &mut [AnalysisSummary],
+) -> Result<(), StatusCode> {
+    let bounty_ids: Vec<Uuid> = analyses.iter().filter_map(|a| a.bounty_id).collect();
+    let withheld = embargo::withheld(state.db.pool(), &bounty_ids, Viewer::from_context(caller))
+        .await
+        .map_err(|e| {
+            tracing::error!("{:#}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    for analysis in analyses
+        .iter_mut()
+        .filter(|a| a.bounty_id.is_some_and(|id| withheld.contains(&id)))
+    {
+        analysis.withhold_verdict();
+    }
+    Ok(())
 }
 
 /// Analysis stats
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct AnalysisStats {
     pub total_analyses: i64,
     pub pending: i64,
@@ -53,12 +95,26 @@ pub struct AnalysisStats {
 }
 
 /// Get analysis by ID
+#[utoipa::path(
+    get,
+    path = "/api/v1/analysis/{analysis_id}",
+    tag = "analysis",
+    params(
+        ("analysis_id" = Uuid, Path, description = "Analysis id"),
+    ),
+    responses(
+        (status = 200, description = "Analysis", body = AnalysisSummary),
+        (status = 404, description = "Analysis not found"),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn get_analysis(
     State(state): State<AppState>,
+    caller: Option<Extension<AuthContext>>,
     Path(analysis_id): Path<Uuid>,
 ) -> Result<Json<AnalysisSummary>, StatusCode> {
     let row = sqlx::query_as::<_, AnalysisSummary>(
-        "SELECT id, file_hash, status, verdict, confidence::float8 as confidence, created_at, completed_at FROM analyses WHERE id = $1"
+        "SELECT id, file_hash, status, verdict, confidence::float8 as confidence, created_at, completed_at, bounty_id FROM analyses WHERE id = $1"
     )
     .bind(analysis_id)
     .fetch_optional(state.db.pool())
@@ -68,23 +124,50 @@ pub async fn get_analysis(
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
 
-    match row {
-        Some(analysis) => Ok(Json(analysis)),
-        None => Err(StatusCode::NOT_FOUND),
-    }
+    let mut analysis = row.ok_or(StatusCode::NOT_FOUND)?;
+    apply_embargoes(&state, caller.as_deref(), std::slice::from_mut(&mut analysis)).await?;
+
+    Ok(Json(analysis))
 }
 
 /// Get analysis details (same as get_analysis for now)
+#[utoipa::path(
+    get,
+    path = "/api/v1/analysis/{analysis_id}/details",
+    tag = "analysis",
+    params(
+        ("analysis_id" = Uuid, Path, description = "Analysis id"),
+    ),
+    responses(
+        (status = 200, description = "Analysis", body = AnalysisSummary),
+        (status = 404, description = "Analysis not found"),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn get_analysis_details(
     state: State<AppState>,
+    caller: Option<Extension<AuthContext>>,
     path: Path<Uuid>,
 ) -> Result<Json<AnalysisSummary>, StatusCode> {
-    get_analysis(state, path).await
+    get_analysis(state, caller, path).await
 }
 
 /// List all analyses with filters
+#[utoipa::path(
+    get,
+    path = "/api/v1/analysis",
+    tag = "analysis",
+    params(
+        ListAnalysesQuery,
+    ),
+    responses(
+        (status = 200, description = "Page of analyses", body = AnalysisListResponse),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn list_analyses(
     State(state): State<AppState>,
+    caller: Option<Extension<AuthContext>>,
     Query(params): Query<ListAnalysesQuery>,
 ) -> Result<Json<AnalysisListResponse>, StatusCode> {
     let page = params.page.unwrap_or(1);
@@ -99,8 +182,8 @@ pub async fn list_analyses(
             StatusCode::INTERNAL_SERVER_ERROR
         })?;
 
-    let analyses = sqlx::query_as::<_, AnalysisSummary>(
-        "SELECT id, file_hash, status, verdict, confidence::float8 as confidence, created_at, completed_at
+    let mut analyses = sqlx::query_as::<_, AnalysisSummary>(
+        "SELECT id, file_hash, status, verdict, confidence::float8 as confidence, created_at, completed_at, bounty_id
          FROM analyses ORDER BY created_at DESC LIMIT $1 OFFSET $2"
     )
     .bind(limit as i64)
@@ -111,6 +194,7 @@ pub async fn list_analyses(
         tracing::error!("DB error listing analyses: {}", e);
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
+    apply_embargoes(&state, caller.as_deref(), &mut analyses).await?;
 
     Ok(Json(AnalysisListResponse {
         analyses,
@@ -121,9 +205,21 @@ pub async fn list_analyses(
 }
 
 /// Get analysis statistics
+#[utoipa::path(
+    get,
+    path = "/api/v1/analysis/stats",
+    tag = "analysis",
+    responses(
+        (status = 200, description = "Verdict counts", body = AnalysisStats),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn get_analysis_stats(
     State(state): State<AppState>,
 ) -> Result<Json<AnalysisStats>, StatusCode> {
+    // Verdict counts leave out embargoed bounties
+    let embargoed = embargo::active_embargo("analyses.bounty_id");
+
     let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM analyses")
         .fetch_one(state.db.pool())
         .await
@@ -139,17 +235,17 @@ pub async fn get_analysis_stats(
         .await
         .unwrap_or(0);
 
-    let malicious: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM analyses WHERE verdict = 'malicious'")
+    let malicious: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM analyses WHERE verdict = 'malicious' AND NOT {}", embargoed))
         .fetch_one(state.db.pool())
         .await
         .unwrap_or(0);
 
-    let benign: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM analyses WHERE verdict = 'benign'")
+    let benign: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM analyses WHERE verdict = 'benign' AND NOT {}", embargoed))
         .fetch_one(state.db.pool())
         .await
         .unwrap_or(0);
 
-    let suspicious: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM analyses WHERE verdict = 'suspicious'")
+    let suspicious: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM analyses WHERE verdict = 'suspicious' AND NOT {}", embargoed))
         .fetch_one(state.db.pool())
         .await
         .unwrap_or(0);
@@ -165,12 +261,25 @@ pub async fn get_analysis_stats(
 }
 
 /// Get analyses by bounty
+#[utoipa::path(
+    get,
+    path = "/api/v1/analysis/by-bounty/{bounty_id}",
+    tag = "analysis",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+    ),
+    responses(
+        (status = 200, description = "Analyses of the bounty", body = Vec<AnalysisSummary>),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn get_analyses_by_bounty(
     State(state): State<AppState>,
+    caller: Option<Extension<AuthContext>>,
     Path(bounty_id): Path<Uuid>,
 ) -> Result<Json<Vec<AnalysisSummary>>, StatusCode> {
-    let analyses = sqlx::query_as::<_, AnalysisSummary>(
-        "SELECT id, file_hash, status, verdict, confidence::float8 as confidence, created_at, completed_at
+    let mut analyses = sqlx::query_as::<_, AnalysisSummary>(
+        "SELECT id, file_hash, status, verdict, confidence::float8 as confidence, created_at, completed_at, bounty_id
          FROM analyses WHERE bounty_id = $1 ORDER BY created_at DESC"
     )
     .bind(bounty_id)
@@ -180,17 +289,31 @@ pub async fn get_analyses_by_bounty(
         tracing::error!("DB error: {}", e);
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
+    apply_embargoes(&state, caller.as_deref(), &mut analyses).await?;
 
     Ok(Json(analyses))
 }
 
 /// Get analyses by file hash
+#[utoipa::path(
+    get,
+    path = "/api/v1/analysis/by-hash/{file_hash}",
+    tag = "analysis",
+    params(
+        ("file_hash" = String, Path, description = "SHA-256 of the file"),
+    ),
+    responses(
+        (status = 200, description = "Analyses of the file", body = Vec<AnalysisSummary>),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn get_analyses_by_hash(
     State(state): State<AppState>,
+    caller: Option<Extension<AuthContext>>,
     Path(file_hash): Path<String>,
 ) -> Result<Json<Vec<AnalysisSummary>>, StatusCode> {
-    let analyses = sqlx::query_as::<_, AnalysisSummary>(
-        "SELECT id, file_hash, status, verdict, confidence::float8 as confidence, created_at, completed_at
+    let mut analyses = sqlx::query_as::<_, AnalysisSummary>(
+        "SELECT id, file_hash, status, verdict, confidence::float8 as confidence, created_at, completed_at, bounty_id
          FROM analyses WHERE file_hash = $1 ORDER BY created_at DESC"
     )
     .bind(&file_hash)
@@ -200,11 +323,23 @@ pub async fn get_analyses_by_hash(
         tracing::error!("DB error: {}", e);
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
+    apply_embargoes(&state, caller.as_deref(), &mut analyses).await?;
 
     Ok(Json(analyses))
 }
 
 /// Submit analysis (standalone, not via bounty route)
+#[utoipa::path(
+    post,
+    path = "/api/v1/analysis/submit",
+    tag = "analysis",
+    request_body(content = serde_json::Value, description = "`bounty_id`, `file_hash`, `verdict` (malicious, benign or suspicious) and optional `confidence`"),
+    responses(
+        (status = 200, description = "Analysis recorded", body = serde_json::Value),
+        (status = 400, description = "Missing bounty_id or invalid verdict"),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn submit_analysis(
     State(state): State<AppState>,
     claims: crate::middleware::auth::Claims,
@@ -265,6 +400,21 @@ pub async fn submit_analysis(
 }
 
 /// Dispute an analysis result
+#[utoipa::path(
+    post,
+    path = "/api/v1/analysis/{analysis_id}/dispute",
+    tag = "analysis",
+    params(
+        ("analysis_id" = Uuid, Path, description = "Analysis id"),
+    ),
+    request_body(content = serde_json::Value, description = "`reason` for the dispute"),
+    responses(
+        (status = 200, description = "Dispute opened", body = serde_json::Value),
+        (status = 404, description = "Analysis not found"),
+        (status = 409, description = "Analysis already disputed"),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn dispute_analysis(
     State(state): State<AppState>,
     claims: crate::middleware::auth::Claims,
@@ -273,7 +423,7 @@ pub async fn dispute_analysis(
 ) -> Result<Json<serde_json::Value>, StatusCode> {
     // Verify analysis exists
     let analysis = sqlx::query_as::<_, AnalysisSummary>(
-        "SELECT id, file_hash, status, verdict, confidence::float8 as confidence, created_at, completed_at FROM analyses WHERE id = $1"
+        "SELECT id, file_hash, status, verdict, confidence::float8 as confidence, created_at, completed_at, bounty_id FROM analyses WHERE id = $1"
     )
     .bind(analysis_id)
     .fetch_optional(state.db.pool())
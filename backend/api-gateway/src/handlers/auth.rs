@@ -1,22 +1,30 @@
 use axum::{
-    extract::{Query, State},
-    http::{HeaderMap, StatusCode},
-    response::Json,
+    extract::{ConnectInfo, Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Json},
     routing::{get, post},
     Router,
 };
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
-use chrono::{DateTime, Utc, Duration};
-use jsonwebtoken::{encode, decode, Header, Validation, EncodingKey, DecodingKey};
+use chrono::{DateTime, Utc};
 use ethers::core::types::Signature;
+use std::net::SocketAddr;
 
+use crate::middleware::auth::{session_is_live, Claims, JwtService, REFRESH_TOKEN_ROLE};
+use crate::middleware::csrf::{self, ACCESS_COOKIE, REFRESH_COOKIE};
+use crate::middleware::rate_limiter::client_ip_from;
+use shared::login_guard::LoginDecision;
+use shared::messaging::event_types::{AccountLockedEvent, NewDeviceLoginEvent, NexusEvent};
 use crate::models::user::User;
 use crate::services::database::DatabaseService;
+use crate::services::session::{DeviceInfo, Session};
 use crate::utils::crypto::{hash_password, verify_password};
 use crate::utils::{ApiError, ApiResult};
+use crate::openapi::ErrorResponse;
 use crate::{AppState, ApiResponse};
 
 pub fn auth_routes() -> Router<AppState> {
@@ -32,16 +40,7 @@ pub fn auth_routes() -> Router<AppState> {
 }
 
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct Claims {
-    pub sub: String,          // user_id
-    pub wallet_address: Option<String>,
-    pub exp: usize,
-    pub iat: usize,
-    pub role: String,
-}
-
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct RegisterRequest {
     pub username: String,
     pub email: String,
@@ -49,25 +48,30 @@ pub struct RegisterRequest {
     pub wallet_address: Option<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct LoginRequest {
     pub identifier: String, // username or email
     pub password: String,
+    /// Solved CAPTCHA, required after repeated failures
+    #[serde(default)]
+    pub captcha_token: Option<String>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct WalletConnectRequest {
     pub wallet_address: String,
     pub signature: String,
     pub message: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct RefreshTokenRequest {
-    pub refresh_token: String,
+    /// Omit to use the refresh cookie; the request then needs `X-CSRF-Token`
+    #[serde(default)]
+    pub refresh_token: Option<String>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct AuthResponse {
     pub user: UserResponse,
     pub access_token: String,
@@ -75,7 +79,30 @@ pub struct AuthResponse {
     pub expires_in: i64,
 }
 
-#[derive(Serialize)]
+/// A login session of the caller
+#[derive(Serialize, ToSchema)]
+pub struct SessionResponse {
+    #[serde(flatten)]
+    pub session: Session,
+    /// Whether the request was made with this session's token
+    pub current: bool,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RevokeAllSessionsParams {
+    /// Also end the session making the request
+    #[serde(default)]
+    pub include_current: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RevokeAllSessionsResponse {
+    /// Number of sessions ended
+    pub revoked: usize,
+}
+
+#[derive(Serialize, ToSchema)]
 pub struct UserResponse {
     pub id: Uuid,
     pub username: String,
@@ -102,10 +129,23 @@ impl From<User> for UserResponse {
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/register",
+    tag = "auth",
+    request_body = RegisterRequest,
+    responses(
+        (status = 200, description = "Account created", body = ApiResponse<AuthResponse>),
+        (status = 400, description = "Username or email taken", body = ErrorResponse),
+        (status = 422, description = "Invalid input", body = ErrorResponse),
+    )
+)]
 pub async fn register(
     State(state): State<AppState>,
+    headers: HeaderMap,
+    peer: Option<ConnectInfo<SocketAddr>>,
     Json(payload): Json<RegisterRequest>,
-) -> ApiResult<Json<ApiResponse<AuthResponse>>> {
+) -> ApiResult<(HeaderMap, Json<ApiResponse<AuthResponse>>)> {
     // Validate input
 
     if payload.username.is_empty() || payload.email.is_empty() || payload.password.len() < 8 {
@@ -150,8 +190,11 @@ pub async fn register(
     .fetch_one(state.db.pool())
     .await?;
 
-    // Generate Tokens
-    let (access_token, refresh_token) = generate_tokens(&user, &state.config.security.jwt_secret)?;
+    // Open a session and issue its tokens
+    let session = open_session(&state, &user, &headers, peer).await?;
+    let (access_token, refresh_token) =
+        generate_tokens(&state, &user, session.id).await?;
+    let cookies = session_cookie_headers(&state, session.id, &access_token, &refresh_token).await?;
 
     let response = AuthResponse {
         user: user.into(),
@@ -160,25 +203,51 @@ pub async fn register(
         expires_in: 3600      // 1 hour
     };
 
-    Ok(Json(ApiResponse::success(response)))
+    Ok((cookies, Json(ApiResponse::success(response))))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/login",
+    tag = "auth",
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "Logged in; also sets the session and CSRF cookies when cookie sessions are enabled", body = ApiResponse<AuthResponse>),
+        (status = 401, description = "Wrong credentials, or `captcha_required` after repeated failures", body = ErrorResponse),
+        (status = 429, description = "Account or client locked out; see `Retry-After`", body = ErrorResponse),
+    )
+)]
 pub async fn login(
-    State(state): State<AppState>, 
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    peer: Option<ConnectInfo<SocketAddr>>,
     Json(payload): Json<LoginRequest>,
-) -> ApiResult<Json<ApiResponse<AuthResponse>>> {
+) -> ApiResult<(HeaderMap, Json<ApiResponse<AuthResponse>>)> {
+    // Locked out accounts and IPs are turned away before any password check
+    let ip_address = client_ip_from(
+        &headers,
+        peer.map(|ConnectInfo(addr)| addr),
+        &state.config.security.trusted_proxies,
+    );
+    check_login_guard(&state, &payload, ip_address.as_deref()).await?;
+
     // Find user by username or email
     let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE username = $1 OR email = $1")
         .bind(&payload.identifier)
         .fetch_optional(state.db.pool())
-        .await?
-        .ok_or(ApiError::Unauthorized)?;
+        .await?;
+    let Some(user) = user else {
+        return Err(login_failed(&state, &payload.identifier, ip_address.as_deref(), None).await);
+    };
 
     // Verify Password
     if !verify_password(&payload.password, &user.password_hash)
         .map_err(|e| ApiError::Internal(format!("Password verification failed: {}", e)))? 
     {
-        return Err(ApiError::Unauthorized);
+        return Err(login_failed(&state, &payload.identifier, ip_address.as_deref(), Some(&user)).await);
+    }
+    if let Err(e) = state.login_guard.record_success(&payload.identifier).await {
+        tracing::warn!("Failed to clear login failures: {}", e);
     }
 
     // Update last login
@@ -188,8 +257,11 @@ pub async fn login(
         .execute(state.db.pool())
         .await?;
 
-    // Generate Tokens
-    let (access_token, refresh_token) = generate_tokens(&user, &state.config.security.jwt_secret)?;
+    // Open a session and issue its tokens
+    let session = open_session(&state, &user, &headers, peer).await?;
+    let (access_token, refresh_token) =
+        generate_tokens(&state, &user, session.id).await?;
+    let cookies = session_cookie_headers(&state, session.id, &access_token, &refresh_token).await?;
 
     let response = AuthResponse {
         user: user.into(),
@@ -198,44 +270,107 @@ pub async fn login(
         expires_in: 3600
     };
 
-    Ok(Json(ApiResponse::success(response)))
+    Ok((cookies, Json(ApiResponse::success(response))))
 } 
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/logout",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Logged out; the session's tokens stop working and its cookies are cleared", body = ApiResponse<serde_json::Value>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    )
+)]
 pub async fn logout(
     headers: HeaderMap,
     State(state): State<AppState>,
-) -> ApiResult<Json<ApiResponse<()>>> {
+) -> ApiResult<(HeaderMap, Json<ApiResponse<()>>)> {
     // Extract token from header
     let token = extract_token_from_header(&headers)?;
+    let claims = decode_token(&token, &state.jwt)?;
+
+    // Ending the session invalidates both of its tokens
+    if let Some(session_id) = claims.sid {
+        state
+            .sessions
+            .revoke(claims.sub, session_id)
+            .await
+            .map_err(|e| ApiError::Internal(format!("Failed to end session: {}", e)))?;
+    }
 
-    // Add token to blacklist (you might want to implement a Redis-based blacklist)
-    // For now, we'll just return success
+    let cookies = if state.config.security.cookies.enabled {
+        csrf::clear_session_cookies(&state.config.security.cookies)
+    } else {
+        HeaderMap::new()
+    };
 
-    Ok(Json(ApiResponse::success_with_message(
-        (),
-        "Successfully logged out".to_string(),
-    )))
+    Ok((
+        cookies,
+        Json(ApiResponse::success_with_message(
+            (),
+            "Successfully logged out".to_string(),
+        )),
+    ))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/refresh",
+    tag = "auth",
+    request_body = RefreshTokenRequest,
+    responses(
+        (status = 200, description = "New token pair", body = ApiResponse<AuthResponse>),
+        (status = 401, description = "Invalid refresh token", body = ErrorResponse),
+        (status = 403, description = "Refresh cookie sent without a valid CSRF token", body = ErrorResponse),
+    )
+)]
 pub async fn refresh_token(
     State(state): State<AppState>,
-    Json(payload): Json<RefreshTokenRequest>,
-) -> ApiResult<Json<ApiResponse<AuthResponse>>> {
+    headers: HeaderMap,
+    payload: Option<Json<RefreshTokenRequest>>,
+) -> ApiResult<(HeaderMap, Json<ApiResponse<AuthResponse>>)> {
+    // Refresh token from the body, or from the cookie on browser sessions
+    let body_token = payload.and_then(|Json(payload)| payload.refresh_token);
+    let from_cookie = body_token.is_none();
+    let token = body_token
+        .or_else(|| csrf::cookie_value(&headers, REFRESH_COOKIE))
+        .ok_or(ApiError::Unauthorized)?;
+
     // Decode refresh token
-    let claims = decode_token(&payload.refresh_token, &state.config.security.jwt_secret)?;
-    
-    // Get user from database
-    let user_id = Uuid::parse_str(&claims.sub)
-        .map_err(|_| ApiError::BadRequest("Invalid user ID in token".to_string()))?;
+    let claims = decode_token(&token, &state.jwt)?;
+    if claims.role != REFRESH_TOKEN_ROLE {
+        return Err(ApiError::Unauthorized);
+    }
 
+    // The session must still be open; refreshing counts as activity
+    let session_id = claims.sid.ok_or(ApiError::Unauthorized)?;
+    if !session_is_live(&state.sessions, &claims).await {
+        return Err(ApiError::Unauthorized);
+    }
+
+    // The access cookie may have expired, so the CSRF layer cannot be
+    // relied on to have seen this request
+    if from_cookie {
+        csrf::verify_csrf(&state, &headers, Some(session_id))
+            .await
+            .map_err(|e| {
+                tracing::warn!("Refresh rejected: {}", e);
+                ApiError::Forbidden
+            })?;
+    }
+
+    // Get user from database
     let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
-        .bind(user_id)
+        .bind(claims.sub)
         .fetch_optional(state.db.pool())
         .await?
         .ok_or(ApiError::Unauthorized)?;
 
-    // Generate new tokens
-    let (access_token, refresh_token) = generate_tokens(&user, &state.config.security.jwt_secret)?;
+    // Generate new tokens for the same session
+    let (access_token, refresh_token) =
+        generate_tokens(&state, &user, session_id).await?;
+    let cookies = session_cookie_headers(&state, session_id, &access_token, &refresh_token).await?;
 
     let response = AuthResponse {
         user: user.into(),
@@ -244,25 +379,118 @@ pub async fn refresh_token(
         expires_in: 3600,
     };
 
-    Ok(Json(ApiResponse::success(response)))
+    Ok((cookies, Json(ApiResponse::success(response))))
+}
+
+/// The caller's open sessions, most recently active first
+#[utoipa::path(
+    get,
+    path = "/api/v1/sessions",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Open sessions", body = ApiResponse<Vec<SessionResponse>>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    )
+)]
+pub async fn list_sessions(
+    State(state): State<AppState>,
+    claims: Claims,
+) -> ApiResult<Json<ApiResponse<Vec<SessionResponse>>>> {
+    let sessions = state
+        .sessions
+        .list(claims.sub)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to list sessions: {}", e)))?
+        .into_iter()
+        .map(|session| SessionResponse {
+            current: claims.sid == Some(session.id),
+            session,
+        })
+        .collect();
+
+    Ok(Json(ApiResponse::success(sessions)))
+}
+
+/// End one of the caller's sessions, e.g. on a lost device
+#[utoipa::path(
+    delete,
+    path = "/api/v1/sessions/{session_id}",
+    tag = "auth",
+    params(
+        ("session_id" = Uuid, Path, description = "Session id"),
+    ),
+    responses(
+        (status = 204, description = "Session ended; its tokens stop working"),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+        (status = 404, description = "No such session for the caller", body = ErrorResponse),
+    )
+)]
+pub async fn revoke_session(
+    State(state): State<AppState>,
+    claims: Claims,
+    Path(session_id): Path<Uuid>,
+) -> ApiResult<StatusCode> {
+    let revoked = state
+        .sessions
+        .revoke(claims.sub, session_id)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to end session: {}", e)))?;
+    if !revoked {
+        return Err(ApiError::NotFound(format!("Session {} not found", session_id)));
+    }
+
+    tracing::info!("User {} ended session {}", claims.sub, session_id);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Log out everywhere: end all of the caller's sessions, by default except
+/// the one making the request
+#[utoipa::path(
+    delete,
+    path = "/api/v1/sessions",
+    tag = "auth",
+    params(RevokeAllSessionsParams),
+    responses(
+        (status = 200, description = "Sessions ended; their tokens stop working", body = ApiResponse<RevokeAllSessionsResponse>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    )
+)]
+pub async fn revoke_all_sessions(
+    State(state): State<AppState>,
+    claims: Claims,
+    Query(params): Query<RevokeAllSessionsParams>,
+) -> ApiResult<(HeaderMap, Json<ApiResponse<RevokeAllSessionsResponse>>)> {
+    let keep = if params.include_current { None } else { claims.sid };
+    let revoked = state
+        .sessions
+        .revoke_all(claims.sub, keep)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to end sessions: {}", e)))?;
+
+    let cookies = if keep.is_none() && state.config.security.cookies.enabled {
+        csrf::clear_session_cookies(&state.config.security.cookies)
+    } else {
+        HeaderMap::new()
+    };
+
+    tracing::info!("User {} ended {} sessions", claims.sub, revoked);
+    Ok((cookies, Json(ApiResponse::success(RevokeAllSessionsResponse { revoked }))))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/verify",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Token is valid", body = ApiResponse<UserResponse>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    )
+)]
 pub async fn verify_token(
     headers: HeaderMap, 
     State(state): State<AppState>, 
 ) -> ApiResult<Json<ApiResponse<UserResponse>>> {
-    let token = extract_token_from_header(&headers)?;
-    let claims = decode_token(&token, &state.config.security.jwt_secret)?;
-
-    let user_id = Uuid::parse_str(&claims.sub)
-        .map_err(|_| ApiError::BadRequest("Invalid user ID in token".to_string()))?;
-
-    let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
-        .bind(user_id)
-        .fetch_optional(state.db.pool())
-        .await?
-        .ok_or(ApiError::Unauthorized)?;
-
+    let user = authenticate_user(&headers, &state).await?;
     Ok(Json(ApiResponse::success(user.into())))
 }
 
@@ -274,6 +502,17 @@ pub async fn get_profile(
     Ok(Json(ApiResponse::success(user.into())))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/wallet/connect",
+    tag = "auth",
+    request_body = WalletConnectRequest,
+    responses(
+        (status = 200, description = "Wallet linked", body = ApiResponse<UserResponse>),
+        (status = 400, description = "Invalid wallet signature", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    )
+)]
 pub async fn collect_wallet(
     headers: HeaderMap,
     State(state): State<AppState>,
@@ -298,6 +537,15 @@ pub async fn collect_wallet(
     Ok(Json(ApiResponse::success(user.into())))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/wallet/disconnect",
+    tag = "auth",
+    responses(
+        (status = 200, description = "Wallet unlinked", body = ApiResponse<UserResponse>),
+        (status = 401, description = "Missing or invalid token", body = ErrorResponse),
+    )
+)]
 pub async fn disconnect_wallet(
     headers: HeaderMap,
     State(state): State<AppState>,
@@ -313,53 +561,202 @@ pub async fn disconnect_wallet(
     Ok(Json(ApiResponse::success(user.into())))
 }
 
-// Helper function
-fn generate_tokens(user: &User, secret: &str) -> ApiResult<(String, String)> {
-    let now = Utc::now();
-    let exp_access = (now + Duration::hours(1)).timestamp() as usize;
-    let exp_refresh = (now + Duration::days(30)).timestamp() as usize;
-
-    let claims_access = Claims {
-        sub: user.id.to_string(),
-        wallet_address: user.wallet_address.clone(),
-        exp: exp_access,
-        iat: now.timestamp() as usize,
-        role: "user".to_string(),
-    };
+/// Public keys that verify gateway-issued tokens. Services validating
+/// tokens should cache this set and refetch it when they meet an unknown `kid`.
+#[utoipa::path(
+    get,
+    path = "/.well-known/jwks.json",
+    tag = "auth",
+    responses(
+        (status = 200, description = "JSON Web Key Set (RFC 7517)", body = serde_json::Value),
+    )
+)]
+pub async fn jwks(State(state): State<AppState>) -> impl IntoResponse {
+    (
+        [(header::CACHE_CONTROL, "public, max-age=300")],
+        Json(state.jwt.jwks()),
+    )
+}
 
-    let claims_refresh = Claims {
-        sub: user.id.to_string(),
-        wallet_address: user.wallet_address.clone(),
-        exp: exp_refresh,
-        iat: now.timestamp() as usize,
-        role: "refresh".to_string(),
-    };
+/// Account role of access tokens issued at login; admins and moderators get
+/// their permissions through role grants
+const ACCESS_TOKEN_ROLE: &str = "user";
+
+/// Access and refresh tokens for a session. The access token carries the
+/// user's current permissions as scopes.
+async fn generate_tokens(state: &AppState, user: &User, session_id: Uuid) -> ApiResult<(String, String)> {
+    let jwt = &state.jwt;
+    let scopes = state.rbac.permissions_for(user.id, ACCESS_TOKEN_ROLE).await;
+    let claims_access = Claims::new(user.id, user.email.clone(), ACCESS_TOKEN_ROLE.to_string(), 1)
+        .with_session(session_id)
+        .with_scopes(scopes);
+    let claims_refresh = Claims::new(
+        user.id,
+        user.email.clone(),
+        REFRESH_TOKEN_ROLE.to_string(),
+        30 * 24,
+    )
+    .with_session(session_id);
 
-    let encoding_key = EncodingKey::from_secret(secret.as_ref());
-    let access_token = encode(&Header::default(), &claims_access, &encoding_key)
+    let access_token = jwt
+        .generate_token(&claims_access)
         .map_err(|e| ApiError::Internal(format!("Token generation failed: {}", e)))?;
 
-    let refresh_token = encode(&Header::default(), &claims_refresh, &encoding_key)
+    let refresh_token = jwt
+        .generate_token(&claims_refresh)
         .map_err(|e| ApiError::Internal(format!("Token generation failed: {}", e)))?;
 
     Ok((access_token, refresh_token))
 }
 
-fn decode_token(token: &str, secret: &str) -> ApiResult<Claims> {
-    let decoding_key = DecodingKey::from_secret(secret.as_ref());
-    let validation = Validation::default();
-    
-    decode::<Claims>(token, &decoding_key, &validation)
-        .map(|data| data.claims)
-        .map_err(|_| ApiError::Unauthorized)
+/// Apply the brute-force guard (`shared::login_guard`) before a login.
+/// Redis being down lets logins through, like the rate limiter.
+async fn check_login_guard(state: &AppState, payload: &LoginRequest, ip: Option<&str>) -> ApiResult<()> {
+    let decision = state
+        .login_guard
+        .check(&payload.identifier, ip, payload.captcha_token.as_deref())
+        .await;
+    match decision {
+        Ok(LoginDecision::Allow) => Ok(()),
+        Ok(LoginDecision::CaptchaRequired) => Err(ApiError::CaptchaRequired),
+        Ok(LoginDecision::Locked { retry_after }) => Err(ApiError::TooManyRequests {
+            message: "Too many failed sign-in attempts, try again later".to_string(),
+            retry_after_secs: retry_after.as_secs().max(1),
+        }),
+        Err(e) => {
+            tracing::warn!("Login guard unavailable, allowing attempt: {}", e);
+            Ok(())
+        }
+    }
+}
+
+/// Count a failed login and tell the owner when it locks their account
+async fn login_failed(state: &AppState, identifier: &str, ip: Option<&str>, user: Option<&User>) -> ApiError {
+    let outcome = match state.login_guard.record_failure(identifier, ip).await {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            tracing::warn!("Failed to record login failure: {}", e);
+            return ApiError::Unauthorized;
+        }
+    };
+
+    if let (Some(locked_for), Some(user)) = (outcome.account_locked_for, user) {
+        tracing::warn!("Account {} locked for {:?} after failed logins", user.id, locked_for);
+        let event = NexusEvent::AccountLocked(AccountLockedEvent {
+            user_id: user.id,
+            email: user.email.clone(),
+            failed_attempts: outcome.account_failures,
+            ip_address: ip.map(str::to_string),
+            locked_until: Utc::now()
+                + chrono::Duration::from_std(locked_for).unwrap_or_else(|_| chrono::Duration::zero()),
+        });
+        if let Err(e) = publish_notice(state, &event).await {
+            tracing::warn!("Failed to queue lockout notice for user {}: {}", user.id, e);
+        }
+    }
+    ApiError::Unauthorized
+}
+
+/// Session and CSRF cookies for a browser login; none unless cookie sessions
+/// are enabled
+async fn session_cookie_headers(
+    state: &AppState,
+    session_id: Uuid,
+    access_token: &str,
+    refresh_token: &str,
+) -> ApiResult<HeaderMap> {
+    let config = &state.config.security.cookies;
+    if !config.enabled {
+        return Ok(HeaderMap::new());
+    }
+
+    let secret = state
+        .sessions
+        .csrf_secret(session_id)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to load session: {}", e)))?
+        .ok_or_else(|| ApiError::Internal("Session has no CSRF secret".to_string()))?;
+
+    Ok(csrf::session_cookies(
+        config,
+        access_token,
+        refresh_token,
+        &csrf::csrf_token(&secret, session_id),
+        3600,
+        30 * 24 * 3600,
+    ))
+}
+
+/// Queue an account notice for the notification service
+async fn publish_notice(state: &AppState, event: &NexusEvent) -> anyhow::Result<()> {
+    let mut conn = state.redis.connection_pool.clone();
+    shared::messaging::publish_event_on(&mut conn, event).await
+}
+
+/// Open a login session, recording the device it came from, and tell the
+/// user when that device or its country is new to their account
+async fn open_session(
+    state: &AppState,
+    user: &User,
+    headers: &HeaderMap,
+    peer: Option<ConnectInfo<SocketAddr>>,
+) -> ApiResult<Session> {
+    let ip_address = client_ip_from(
+        headers,
+        peer.map(|ConnectInfo(addr)| addr),
+        &state.config.security.trusted_proxies,
+    );
+    let session = state
+        .sessions
+        .create(user.id, DeviceInfo::from_request(headers, ip_address))
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to open session: {}", e)))?;
+
+    // Device history is best effort; it must not keep anyone from logging in
+    let novelty = match state.known_devices.record_login(user.id, &session.device).await {
+        Ok(novelty) => novelty,
+        Err(e) => {
+            tracing::warn!("Failed to record device of session {}: {}", session.id, e);
+            return Ok(session);
+        }
+    };
+    if novelty.is_news() {
+        let device = &session.device;
+        tracing::info!(
+            "User {} logged in from a new {} ({}, {:?})",
+            user.id,
+            if novelty.new_device { "device" } else { "country" },
+            device.label,
+            device.country
+        );
+        let event = NexusEvent::NewDeviceLogin(NewDeviceLoginEvent {
+            user_id: user.id,
+            email: user.email.clone(),
+            session_id: session.id,
+            device: device.label.clone(),
+            ip_address: device.ip_address.clone(),
+            country: device.country.clone(),
+            new_device: novelty.new_device,
+            new_country: novelty.new_country,
+            logged_in_at: session.created_at,
+        });
+        if let Err(e) = publish_notice(state, &event).await {
+            tracing::warn!("Failed to queue new sign-in notice for user {}: {}", user.id, e);
+        }
+    }
+    Ok(session)
+}
+
+fn decode_token(token: &str, jwt: &JwtService) -> ApiResult<Claims> {
+    jwt.validate_token(token).map_err(|_| ApiError::Unauthorized)
 }
 
 fn extract_token_from_header(headers: &HeaderMap) -> ApiResult<String> {
-    let auth_header = headers
-        .get("Authorization")
-        .ok_or(ApiError::Unauthorized)?
-        .to_str()
-        .map_err(|_| ApiError::Unauthorized)?;
+    // Browser sessions carry the access token in a cookie
+    let Some(auth_header) = headers.get("Authorization") else {
+        return csrf::cookie_value(headers, ACCESS_COOKIE).ok_or(ApiError::Unauthorized);
+    };
+    let auth_header = auth_header.to_str().map_err(|_| ApiError::Unauthorized)?;
 
     if !auth_header.starts_with("Bearer ") {
         return Err(ApiError::Unauthorized);
@@ -370,13 +767,13 @@ fn extract_token_from_header(headers: &HeaderMap) -> ApiResult<String> {
 
 async fn authenticate_user(headers: &HeaderMap, state: &AppState) -> ApiResult<User> {
     let token = extract_token_from_header(headers)?;
-    let claims = decode_token(&token, &state.config.security.jwt_secret)?;
-
-    let user_id = Uuid::parse_str(&claims.sub)
-        .map_err(|_| ApiError::BadRequest("Invalid user ID in token".to_string()))?;
+    let claims = decode_token(&token, &state.jwt)?;
+    if claims.role == REFRESH_TOKEN_ROLE || !session_is_live(&state.sessions, &claims).await {
+        return Err(ApiError::Unauthorized);
+    }
 
     sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
-        .bind(user_id)
+        .bind(claims.sub)
         .fetch_optional(state.db.pool())
         .await?
         .ok_or(ApiError::Unauthorized)
@@ -402,6 +799,13 @@ fn verify_wallet_signature(address: &str, signature: &str, message: &str) -> boo
 }
 
 /// Verify email address
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/verify-email",
+    tag = "auth",
+    request_body = serde_json::Value,
+    responses((status = 501, description = "Not implemented yet"))
+)]
 pub async fn verify_email(
     State(_state): State<AppState>,
     Json(_payload): Json<serde_json::Value>,
@@ -410,6 +814,13 @@ pub async fn verify_email(
 }
 
 /// Forgot password — send reset link
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/forgot-password",
+    tag = "auth",
+    request_body = serde_json::Value,
+    responses((status = 501, description = "Not implemented yet"))
+)]
 pub async fn forgot_password(
     State(_state): State<AppState>,
     Json(_payload): Json<serde_json::Value>,
@@ -418,6 +829,13 @@ pub async fn forgot_password(
 }
 
 /// Reset password
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/reset-password",
+    tag = "auth",
+    request_body = serde_json::Value,
+    responses((status = 501, description = "Not implemented yet"))
+)]
 pub async fn reset_password(
     State(_state): State<AppState>,
     Json(_payload): Json<serde_json::Value>,
@@ -426,6 +844,12 @@ pub async fn reset_password(
 }
 
 /// Generate API key
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/api-key",
+    tag = "auth",
+    responses((status = 501, description = "Not implemented yet"))
+)]
 pub async fn generate_api_key(
     State(_state): State<AppState>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
@@ -1,5 +1,5 @@
 use axum::{
-    extract::{Path, Query, State},
+    extract::{Extension, Path, Query, State},
     http::StatusCode,
     response::Json,
     routing::{get, post, put},
@@ -8,18 +8,22 @@ use axum::{
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::models::{
     bounty::{Bounty, BountyStatus},
     user::User,
 };
+use crate::middleware::route_policy::SCOPE_BOUNTY_MANAGE;
+use crate::services::embargo::{self, Viewer};
+use crate::utils::AuthContext;
 use crate::AppState;
 // Import CreateBountyRequest from models if available, otherwise define here matching the service
 // Re-using existing structs if they match, or updating them.
 
 // Request/Response DTOs
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct CreateBountyRequest {
     pub title: String,
     pub description: String,
@@ -30,7 +34,7 @@ pub struct CreateBountyRequest {
     pub deadline: DateTime<Utc>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct SubmitAnalysisRequest {
     pub engine_id: String,
     pub verdict: String, // "malicious", "benign", "suspicious"
@@ -38,7 +42,8 @@ pub struct SubmitAnalysisRequest {
     pub analysis_details: serde_json::Value,
     pub stake_amount: u64,
 }
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct BountyFilters {
     pub status: Option<String>,
     pub min_reward: Option<u64>,
@@ -62,6 +67,21 @@ pub struct BountyResponse {
     pub consensus_reached: bool,
     pub final_verdict: Option<String>,
     pub confidence_score: Option<f32>,
+    /// The verdict is hidden by a verdict embargo on the bounty
+    pub verdict_embargoed: bool,
+}
+
+impl BountyResponse {
+    /// Hide the verdict while the bounty is under a verdict embargo the
+    /// viewer is not exempt from
+    pub async fn apply_embargo(&mut self, db: &sqlx::PgPool, viewer: Option<Viewer>) -> anyhow::Result<()> {
+        if embargo::is_withheld(db, self.id, viewer).await? {
+            self.final_verdict = None;
+            self.confidence_score = None;
+            self.verdict_embargoed = true;
+        }
+        Ok(())
+    }
 }
 #[derive(Serialize)]
 pub struct BountListResponse {
@@ -71,7 +91,8 @@ pub struct BountListResponse {
     pub limit: u32,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
+#[schema(as = BountySubmissionResponse)]
 pub struct SubmissionResponse {
     pub id: Uuid,
     pub bounty_id: Uuid,
@@ -90,6 +111,18 @@ pub struct BountyDetailsResponse {
     pub file_info: Option<FileInfo>,
 }
 
+impl BountyDetailsResponse {
+    /// Like [`BountyResponse::apply_embargo`], also dropping the engines'
+    /// verdicts
+    pub async fn apply_embargo(&mut self, db: &sqlx::PgPool, viewer: Option<Viewer>) -> anyhow::Result<()> {
+        self.bounty.apply_embargo(db, viewer).await?;
+        if self.bounty.verdict_embargoed {
+            self.submissions.clear();
+        }
+        Ok(())
+    }
+}
+
 #[derive(Serialize)]
 pub struct FileInfo {
     pub hash: String,
@@ -101,6 +134,20 @@ pub struct FileInfo {
 // handler Implementation
 // TODO: Rewrite to match actual Bounty model structure from models/bounty.rs
 // handler Implementation
+/// Create a bounty
+///
+/// Requires the `bounty:create` permission.
+#[utoipa::path(
+    post,
+    path = "/api/v1/bounties",
+    tag = "bounties",
+    request_body = CreateBountyRequest,
+    responses(
+        (status = 200, description = "Bounty created", body = Bounty),
+        (status = 403, description = "Missing the `bounty:create` permission", body = crate::openapi::ErrorResponse),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn create_bounty(
     State(state): State<AppState>,
     claims: crate::middleware::auth::Claims,
@@ -201,6 +248,18 @@ pub async fn create_bounty(
 }
 
 // TODO: Rewrite to match actual Bounty model
+#[utoipa::path(
+    get,
+    path = "/api/v1/bounties",
+    tag = "bounties",
+    params(
+        BountyFilters,
+    ),
+    responses(
+        (status = 200, description = "Open bounties, newest first", body = Vec<Bounty>),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn list_bounties(
     State(state): State<AppState>,
     Query(filters): Query<BountyFilters>,
@@ -221,6 +280,19 @@ pub async fn list_bounties(
 }
 
 // TODO: Rewrite to match actual Bounty model
+#[utoipa::path(
+    get,
+    path = "/api/v1/bounties/{bounty_id}",
+    tag = "bounties",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+    ),
+    responses(
+        (status = 200, description = "Bounty", body = Bounty),
+        (status = 404, description = "Bounty not found"),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn get_bounty(
     State(state): State<AppState>,
     Path(bounty_id): Path<Uuid>,
@@ -238,6 +310,22 @@ pub async fn get_bounty(
     Ok(Json(bounty))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/bounties/{bounty_id}/submit",
+    operation_id = "submit_bounty_analysis",
+    tag = "bounties",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+    ),
+    request_body = SubmitAnalysisRequest,
+    responses(
+        (status = 200, description = "Verdict submitted on chain", body = SubmissionResponse),
+        (status = 400, description = "Unknown verdict"),
+        (status = 412, description = "Bounty not yet confirmed on chain"),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn submit_analysis(
     State(state): State<crate::AppState>,
     Path(bounty_id): Path<Uuid>,
@@ -292,6 +380,23 @@ pub async fn submit_analysis(
     }))
 }
 
+/// Resolve a bounty on chain
+///
+/// Requires the `bounty:manage` permission.
+#[utoipa::path(
+    put,
+    path = "/api/v1/bounties/{bounty_id}/finalize",
+    tag = "bounties",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+    ),
+    responses(
+        (status = 200, description = "Bounty finalized on chain"),
+        (status = 403, description = "Missing the `bounty:manage` permission", body = crate::openapi::ErrorResponse),
+        (status = 412, description = "Bounty not yet confirmed on chain"),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn finalize_bounty(
     State(state): State<crate::AppState>,
     Path(bounty_id): Path<Uuid>,
@@ -320,10 +425,27 @@ pub async fn finalize_bounty(
     Ok(StatusCode::OK)
 }
 
-/// Update a bounty (owner only)
+/// Update a bounty (owner, or callers with `bounty:manage`)
+#[utoipa::path(
+    put,
+    path = "/api/v1/bounties/{bounty_id}",
+    tag = "bounties",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+    ),
+    request_body(content = serde_json::Value, description = "Fields to change: `title`, `description`"),
+    responses(
+        (status = 200, description = "Bounty updated", body = serde_json::Value),
+        (status = 403, description = "Neither the creator nor allowed to manage bounties"),
+        (status = 404, description = "Bounty not found"),
+        (status = 409, description = "Bounty is no longer open"),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn update_bounty(
     State(state): State<crate::AppState>,
     claims: crate::middleware::auth::Claims,
+    Extension(caller): Extension<AuthContext>,
     Path(bounty_id): Path<Uuid>,
     Json(payload): Json<serde_json::Value>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
@@ -332,7 +454,7 @@ pub async fn update_bounty(
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
         .ok_or(StatusCode::NOT_FOUND)?;
 
-    if bounty.creator != claims.sub {
+    if bounty.creator != claims.sub && !caller.has_permission(SCOPE_BOUNTY_MANAGE) {
         return Err(StatusCode::FORBIDDEN);
     }
 
@@ -363,17 +485,33 @@ pub async fn update_bounty(
     })))
 }
 
-/// Cancel a bounty (owner only, must be draft/active)
+/// Cancel a bounty (owner or `bounty:manage`, must be draft/active)
+#[utoipa::path(
+    post,
+    path = "/api/v1/bounties/{bounty_id}/cancel",
+    tag = "bounties",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+    ),
+    responses(
+        (status = 200, description = "Bounty cancelled"),
+        (status = 403, description = "Neither the creator nor allowed to manage bounties"),
+        (status = 404, description = "Bounty not found"),
+        (status = 409, description = "Bounty is no longer open"),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn cancel_bounty(
     State(state): State<crate::AppState>,
     claims: crate::middleware::auth::Claims,
+    Extension(caller): Extension<AuthContext>,
     Path(bounty_id): Path<Uuid>,
 ) -> Result<StatusCode, StatusCode> {
     let bounty = state.db.get_bounty_by_id(bounty_id).await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
         .ok_or(StatusCode::NOT_FOUND)?;
 
-    if bounty.creator != claims.sub {
+    if bounty.creator != claims.sub && !caller.has_permission(SCOPE_BOUNTY_MANAGE) {
         return Err(StatusCode::FORBIDDEN);
     }
 
@@ -390,10 +528,27 @@ pub async fn cancel_bounty(
     Ok(StatusCode::OK)
 }
 
-/// Extend bounty deadline (owner only)
+/// Extend bounty deadline (owner or `bounty:manage`)
+#[utoipa::path(
+    post,
+    path = "/api/v1/bounties/{bounty_id}/extend",
+    tag = "bounties",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+    ),
+    request_body(content = serde_json::Value, description = "`deadline`: new RFC 3339 deadline, later than the current one"),
+    responses(
+        (status = 200, description = "Deadline extended", body = serde_json::Value),
+        (status = 400, description = "Missing or earlier deadline"),
+        (status = 403, description = "Neither the creator nor allowed to manage bounties"),
+        (status = 404, description = "Bounty not found"),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn extend_bounty(
     State(state): State<crate::AppState>,
     claims: crate::middleware::auth::Claims,
+    Extension(caller): Extension<AuthContext>,
     Path(bounty_id): Path<Uuid>,
     Json(payload): Json<serde_json::Value>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
@@ -401,7 +556,7 @@ pub async fn extend_bounty(
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
         .ok_or(StatusCode::NOT_FOUND)?;
 
-    if bounty.creator != claims.sub {
+    if bounty.creator != claims.sub && !caller.has_permission(SCOPE_BOUNTY_MANAGE) {
         return Err(StatusCode::FORBIDDEN);
     }
 
@@ -434,6 +589,21 @@ pub async fn extend_bounty(
 }
 
 /// Claim bounty reward (participant only, bounty must be completed)
+#[utoipa::path(
+    post,
+    path = "/api/v1/bounties/{bounty_id}/claim",
+    tag = "bounties",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+    ),
+    responses(
+        (status = 200, description = "Reward claimed", body = serde_json::Value),
+        (status = 404, description = "Bounty not found"),
+        (status = 409, description = "Bounty is not completed"),
+        (status = 412, description = "Caller has no winning submission"),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn claim_reward(
     State(state): State<crate::AppState>,
     claims: crate::middleware::auth::Claims,
@@ -464,6 +634,19 @@ pub async fn claim_reward(
 }
 
 /// Get bounty statistics
+#[utoipa::path(
+    get,
+    path = "/api/v1/bounties/{bounty_id}/stats",
+    tag = "bounties",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+    ),
+    responses(
+        (status = 200, description = "Submission statistics", body = serde_json::Value),
+        (status = 404, description = "Bounty not found"),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn get_bounty_stats(
     State(state): State<crate::AppState>,
     Path(bounty_id): Path<Uuid>,
@@ -498,6 +681,15 @@ pub async fn get_bounty_stats(
 }
 
 /// List active bounties
+#[utoipa::path(
+    get,
+    path = "/api/v1/bounties/active",
+    tag = "bounties",
+    responses(
+        (status = 200, description = "Bounties accepting submissions", body = Vec<Bounty>),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn list_active_bounties(
     State(state): State<crate::AppState>,
 ) -> Result<Json<Vec<Bounty>>, StatusCode> {
@@ -510,6 +702,15 @@ pub async fn list_active_bounties(
 }
 
 /// List completed bounties
+#[utoipa::path(
+    get,
+    path = "/api/v1/bounties/completed",
+    tag = "bounties",
+    responses(
+        (status = 200, description = "Finalized bounties", body = Vec<Bounty>),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn list_completed_bounties(
     State(state): State<crate::AppState>,
 ) -> Result<Json<Vec<Bounty>>, StatusCode> {
@@ -0,0 +1,155 @@
+use axum::{
+    extract::{Path, State},
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::middleware::auth::Claims;
+use crate::models::error::ApiError;
+use crate::services::feature_flags::{
+    flag_default, is_valid_flag_name, route_group_flag, FlagChange, FlagChangeRecord, FlagNotice,
+    MAINTENANCE_FLAG, ROUTE_GROUPS,
+};
+use crate::AppState;
+
+/// Changes returned with a single flag
+const HISTORY_LIMIT: i64 = 20;
+
+/// Current value of a flag
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FlagStatus {
+    pub flag: String,
+    /// Effective value
+    pub enabled: bool,
+    /// Whether the value was set rather than the default
+    pub overridden: bool,
+    /// Reason and Retry-After while the flag keeps routes off
+    pub notice: Option<FlagNotice>,
+}
+
+/// A flag with its recent changes
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FlagDetails {
+    #[serde(flatten)]
+    pub status: FlagStatus,
+    /// Newest first
+    pub history: Vec<FlagChangeRecord>,
+}
+
+/// Body of a flag change
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetFlagRequest {
+    /// `null` removes the setting, restoring the default
+    pub enabled: Option<bool>,
+    /// Shown to clients while routes are off
+    pub reason: Option<String>,
+    /// Sent as `Retry-After` while routes are off (default 300)
+    pub retry_after_seconds: Option<u64>,
+}
+
+async fn flag_status(state: &AppState, flag: &str) -> Result<FlagStatus, ApiError> {
+    let unavailable = |e: anyhow::Error| ApiError::ServiceUnavailable(format!("{:#}", e));
+    let stored = state.flags.state(flag).await.map_err(unavailable)?;
+    Ok(FlagStatus {
+        flag: flag.to_string(),
+        enabled: stored.enabled.unwrap_or(flag_default(flag)),
+        overridden: stored.enabled.is_some(),
+        notice: state.flags.notice(flag).await.map_err(unavailable)?,
+    })
+}
+
+fn validate_flag(flag: &str) -> Result<(), ApiError> {
+    if is_valid_flag_name(flag) {
+        Ok(())
+    } else {
+        Err(ApiError::Validation(format!("Invalid flag name: {}", flag)))
+    }
+}
+
+/// Maintenance mode and the route group switches
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/flags",
+    tag = "admin",
+    responses(
+        (status = 200, description = "`gateway.maintenance` and every `gateway.routes.<group>` flag", body = Vec<FlagStatus>),
+        (status = 503, description = "Feature flags unavailable", body = crate::openapi::ErrorResponse),
+    )
+)]
+pub async fn list_flags(State(state): State<AppState>) -> Result<Json<Vec<FlagStatus>>, ApiError> {
+    let flags = std::iter::once(MAINTENANCE_FLAG.to_string())
+        .chain(ROUTE_GROUPS.iter().map(|(group, _)| route_group_flag(group)));
+
+    let mut statuses = Vec::with_capacity(ROUTE_GROUPS.len() + 1);
+    for flag in flags {
+        statuses.push(flag_status(&state, &flag).await?);
+    }
+    Ok(Json(statuses))
+}
+
+/// A flag and its recent changes
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/flags/{flag}",
+    tag = "admin",
+    params(
+        ("flag" = String, Path, description = "Flag name, e.g. `gateway.routes.wallet`"),
+    ),
+    responses(
+        (status = 200, description = "Current value and change history", body = FlagDetails),
+        (status = 422, description = "Invalid flag name", body = crate::openapi::ErrorResponse),
+        (status = 503, description = "Feature flags unavailable", body = crate::openapi::ErrorResponse),
+    )
+)]
+pub async fn get_flag(
+    State(state): State<AppState>,
+    Path(flag): Path<String>,
+) -> Result<Json<FlagDetails>, ApiError> {
+    validate_flag(&flag)?;
+    let status = flag_status(&state, &flag).await?;
+    let history = state
+        .flags
+        .history(&flag, HISTORY_LIMIT)
+        .await
+        .map_err(|e| ApiError::Internal(format!("{:#}", e)))?;
+    Ok(Json(FlagDetails { status, history }))
+}
+
+/// Set a flag on every gateway instance
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/flags/{flag}",
+    tag = "admin",
+    params(
+        ("flag" = String, Path, description = "Flag name, e.g. `gateway.maintenance`"),
+    ),
+    request_body = SetFlagRequest,
+    responses(
+        (status = 200, description = "Flag updated; instances apply it within seconds", body = FlagStatus),
+        (status = 422, description = "Invalid flag name", body = crate::openapi::ErrorResponse),
+        (status = 503, description = "Feature flags unavailable", body = crate::openapi::ErrorResponse),
+    )
+)]
+pub async fn put_flag(
+    State(state): State<AppState>,
+    claims: Claims,
+    Path(flag): Path<String>,
+    Json(request): Json<SetFlagRequest>,
+) -> Result<Json<FlagStatus>, ApiError> {
+    validate_flag(&flag)?;
+    let change = FlagChange {
+        enabled: request.enabled,
+        reason: request.reason.filter(|r| !r.trim().is_empty()),
+        retry_after_seconds: request.retry_after_seconds,
+        changed_by: Some(claims.sub).filter(|id| !id.is_nil()),
+    };
+    state
+        .flags
+        .set(&flag, change)
+        .await
+        .map_err(|e| ApiError::ServiceUnavailable(format!("{:#}", e)))?;
+
+    tracing::warn!("Feature flag {} set to {:?} by {}", flag, request.enabled, claims.sub);
+    Ok(Json(flag_status(&state, &flag).await?))
+}
@@ -0,0 +1,53 @@
+use axum::{extract::State, Extension, Json};
+
+use crate::graphql::loaders::Loaders;
+use crate::middleware::auth::Claims;
+use crate::services::embargo::Viewer;
+use crate::utils::AuthContext;
+use crate::AppState;
+
+/// Run a dashboard query
+///
+/// POST /api/v1/graphql
+#[utoipa::path(
+    post,
+    path = "/api/v1/graphql",
+    tag = "dashboard",
+    summary = "Query dashboard data with GraphQL",
+    description = "Standard GraphQL-over-HTTP request (`query`, `variables`, `operationName`). \
+                   The schema is served in SDL form by `GET /api/v1/graphql`.",
+    request_body = serde_json::Value,
+    responses(
+        (status = 200, description = "GraphQL response; errors are reported in `errors`", body = serde_json::Value),
+        (status = 401, description = "Missing or invalid token"),
+    )
+)]
+pub async fn graphql(
+    State(state): State<AppState>,
+    claims: Claims,
+    caller: Option<Extension<AuthContext>>,
+    Json(request): Json<async_graphql::Request>,
+) -> Json<async_graphql::Response> {
+    let mut request = request.data(claims);
+    if let Some(viewer) = Viewer::from_context(caller.as_deref()) {
+        request = request.data(viewer);
+    }
+    let request = Loaders::register(request, state.db.pool());
+    Json(state.graphql.execute(request).await)
+}
+
+/// Schema of the dashboard endpoint
+///
+/// GET /api/v1/graphql
+#[utoipa::path(
+    get,
+    path = "/api/v1/graphql",
+    tag = "dashboard",
+    summary = "GraphQL schema in SDL form",
+    responses(
+        (status = 200, description = "Schema definition", body = String, content_type = "text/plain"),
+    )
+)]
+pub async fn graphql_schema(State(state): State<AppState>) -> String {
+    state.graphql.sdl()
+}
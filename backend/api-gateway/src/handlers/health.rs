@@ -1,96 +1,144 @@
 use axum::{extract::State, http::StatusCode, response::Json};
-use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use chrono::Utc;
+use futures_util::future::join_all;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::future::Future;
+use std::time::{Duration, Instant};
 
+use crate::services::proxy_service::CircuitBreakerState;
+use crate::utils::{HealthCheck, ServiceHealth};
 use crate::AppState;
 
-/// Service status enum
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
-#[serde(rename_all = "lowercase")]
-pub enum ServiceStatus {
-    Healthy,
-    Degraded,
-    Unhealthy,
-}
-
-/// Health check response structure
-#[derive(Debug, Serialize, Deserialize)]
-pub struct HealthResponse {
-    pub status: ServiceStatus,
-    pub version: String,
-    pub timestamp: DateTime<Utc>,
-    pub uptime: u64,
-    pub services: ServiceHealth,
-}
-
-/// Individual service health status
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ServiceHealth {
-    pub database: ServiceStatus,
-    pub redis: ServiceStatus,
-    pub blockchain: ServiceStatus,
+/// Longest a single dependency may take to answer the health check
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Dependencies the gateway cannot serve any request without
+const CRITICAL_CHECKS: &[&str] = &["database", "redis"];
+
+/// Time a check, failing it if it does not finish within `PROBE_TIMEOUT`
+async fn probe<F>(check: F) -> ServiceHealth
+where
+    F: Future<Output = Result<(), String>>,
+{
+    let start = Instant::now();
+    let outcome = tokio::time::timeout(PROBE_TIMEOUT, check).await;
+    let elapsed_ms = start.elapsed().as_millis() as u64;
+
+    match outcome {
+        Ok(Ok(())) => ServiceHealth::healthy(elapsed_ms),
+        Ok(Err(error)) => ServiceHealth {
+            response_time_ms: Some(elapsed_ms),
+            ..ServiceHealth::unhealthy(error)
+        },
+        Err(_) => ServiceHealth::unhealthy(format!("No response within {}s", PROBE_TIMEOUT.as_secs())),
+    }
 }
 
 /// Main health check endpoint
 ///
 /// GET /api/v1/health
-pub async fn health_check(
-    State(state): State<AppState>,
-) -> Result<Json<HealthResponse>, StatusCode> {
-    let start_time = std::time::Instant::now();
-
-    // Check services
-    let db_ready = state.db.health_check().await.is_ok();
-    let redis_ready = state.redis.health_check().await.unwrap_or(false);
-    let blockchain_ready = state.blockchain.health_check().await;
-
-    // Determine overall status
-    let overall_status = if db_ready && redis_ready && blockchain_ready {
-        ServiceStatus::Healthy
-    } else {
-        ServiceStatus::Degraded
-    };
-
-    let uptime = start_time.elapsed().as_secs();
-
-    let response = HealthResponse {
-        status: overall_status,
-        version: env!("CARGO_PKG_VERSION").to_string(),
-        timestamp: Utc::now(),
-        uptime,
-        services: ServiceHealth {
-            database: if db_ready {
-                ServiceStatus::Healthy
-            } else {
-                ServiceStatus::Unhealthy
-            },
-            redis: if redis_ready {
-                ServiceStatus::Healthy
-            } else {
-                ServiceStatus::Unhealthy
-            },
-            blockchain: if blockchain_ready {
-                ServiceStatus::Healthy
+///
+/// Checks the gateway's own dependencies and every backend service in
+/// parallel. The gateway is unhealthy (503) when a critical dependency is
+/// down and degraded when anything else is.
+#[utoipa::path(
+    get,
+    path = "/api/v1/health",
+    tag = "health",
+    responses(
+        (status = 200, description = "Healthy or degraded", body = HealthCheck),
+        (status = 503, description = "A critical dependency is down", body = HealthCheck),
+    )
+)]
+pub async fn health_check(State(state): State<AppState>) -> (StatusCode, Json<HealthCheck>) {
+    let upstream_names: Vec<String> = state.proxy.service_names().map(str::to_string).collect();
+    let upstream_probes = upstream_names.iter().map(|name| {
+        let proxy = state.proxy.clone();
+        probe(async move {
+            match proxy.health_check(name).await {
+                Ok(true) => Ok(()),
+                Ok(false) => Err("Health check returned an error status".to_string()),
+                Err(e) => Err(format!("{:#}", e)),
+            }
+        })
+    });
+
+    let (database, redis, blockchain, upstreams) = tokio::join!(
+        probe(async { state.db.health_check().await.map_err(|e| format!("{:#}", e)) }),
+        probe(async {
+            match state.redis.health_check().await {
+                Ok(true) => Ok(()),
+                Ok(false) => Err("PING failed".to_string()),
+                Err(e) => Err(format!("{:#}", e)),
+            }
+        }),
+        probe(async {
+            if state.blockchain.health_check().await {
+                Ok(())
             } else {
-                ServiceStatus::Unhealthy
-            },
-        },
-    };
+                Err("RPC node unreachable".to_string())
+            }
+        }),
+        join_all(upstream_probes),
+    );
+
+    let mut checks = HashMap::from([
+        ("database".to_string(), database),
+        ("redis".to_string(), redis),
+        ("blockchain".to_string(), blockchain),
+    ]);
+
+    let mut breakers = state.proxy.circuit_breaker_snapshots().await;
+    for (name, mut health) in upstream_names.into_iter().zip(upstreams) {
+        health.circuit_breaker = breakers.remove(&name);
+        // Answering again while the breaker is still open means recovering, not healthy
+        let breaker_open = health
+            .circuit_breaker
+            .as_ref()
+            .is_some_and(|b| b.state != CircuitBreakerState::Closed);
+        if breaker_open && health.status == "healthy" {
+            health.status = "degraded".to_string();
+        }
+        checks.insert(name, health);
+    }
+
+    let critical_down = CRITICAL_CHECKS
+        .iter()
+        .any(|name| checks.get(*name).is_none_or(|h| h.status == "unhealthy"));
+    let all_healthy = checks.values().all(|h| h.status == "healthy");
 
-    let status_code = if response.status == ServiceStatus::Healthy {
-        StatusCode::OK
+    let (status_code, status) = if critical_down {
+        (StatusCode::SERVICE_UNAVAILABLE, "unhealthy")
+    } else if all_healthy {
+        (StatusCode::OK, "healthy")
     } else {
-        StatusCode::SERVICE_UNAVAILABLE
+        (StatusCode::OK, "degraded")
     };
 
-    Ok(Json(response))
+    (
+        status_code,
+        Json(HealthCheck {
+            status: status.to_string(),
+            timestamp: Utc::now(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            uptime_seconds: state.started_at.elapsed().as_secs(),
+            checks,
+        }),
+    )
 }
 
 /// Readiness check endpoint
 ///
 /// GET /api/v1/ready
+#[utoipa::path(
+    get,
+    path = "/api/v1/health/ready",
+    tag = "health",
+    responses(
+        (status = 200, description = "Ready to serve traffic", body = serde_json::Value),
+        (status = 503, description = "Not ready"),
+    )
+)]
 pub async fn readiness_check(
     State(state): State<AppState>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
@@ -111,6 +159,12 @@ pub async fn readiness_check(
 /// Liveness check endpoint
 ///
 /// GET /api/v1/alive
+#[utoipa::path(
+    get,
+    path = "/api/v1/health/live",
+    tag = "health",
+    responses((status = 200, description = "Process is alive", body = serde_json::Value))
+)]
 pub async fn liveness_check() -> Json<serde_json::Value> {
     Json(serde_json::json!({
         "alive": true,
@@ -121,6 +175,15 @@ pub async fn liveness_check() -> Json<serde_json::Value> {
 /// Detailed metrics endpoint (for monitoring systems)
 ///
 /// GET /api/v1/metrics
+#[utoipa::path(
+    get,
+    path = "/api/v1/health/metrics",
+    tag = "health",
+    responses(
+        (status = 200, description = "Request and dependency metrics", body = serde_json::Value),
+        (status = 500, description = "Metrics unavailable"),
+    )
+)]
 pub async fn metrics(State(state): State<AppState>) -> Result<Json<serde_json::Value>, StatusCode> {
     let snapshot = state.metrics.get_snapshot().await;
     let endpoint_metrics = state.metrics.get_endpoint_metrics().await;
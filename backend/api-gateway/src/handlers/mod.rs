@@ -10,10 +10,17 @@ use thiserror::Error;
 pub mod analysis;
 pub mod auth;
 pub mod bounty;
+pub mod flags;
+pub mod graphql;
 pub mod health;
+pub mod proxy;
+pub mod rbac;
+pub mod realtime;
 pub mod reputation;
 pub mod submission;
+pub mod usage;
 pub mod user;
+pub mod v2;
 pub mod wallet;
 pub mod webhook;
 
@@ -64,40 +71,6 @@ impl IntoResponse for ApiError {
 
 pub type ApiResult<T> = Result<T, ApiError>;
 
-// Common response types
-#[derive(serde::Serialize)]
-pub struct ApiResponse<T> {
-    pub success: bool,
-    pub data: Option<T>,
-    pub message: Option<String>,
-}
-
-impl <T> ApiResponse<T> {
-    pub fn success(data: T) -> Self {
-        Self {
-            success: true,
-            data: Some(data),
-            message: None,
-        }
-    }
-
-    pub fn success_with_message(data: T, message: String) -> Self {
-        Self {
-            success: true,
-            data: Some(data),
-            message: Some(message),
-        }
-    }
-
-    pub fn error(message: String) -> ApiResponse<()> {
-        ApiResponse {
-            success: false,
-            data: None,
-            message: Some(message),
-        }
-    }
-}
-
 // Pagination helper
 #[derive(serde::Deserialize)]
 pub struct PaginationQuery {
@@ -0,0 +1,1514 @@
+//! Routes served by backend services through the gateway's reverse proxy.
+//!
+//! Authentication, rate limiting and usage metering run in the gateway as
+//! for any other route; the request is then streamed to the upstream with
+//! the caller's identity in `X-User-Id` / `X-User-Role`.
+//!
+//! Bodies are capped per route: file uploads may be up to
+//! `max_file_size_mb` and are streamed without being buffered, everything
+//! else is held to `max_json_body_kb`.
+
+use axum::{
+    extract::{Path, Request, State},
+    response::{IntoResponse, Response},
+};
+use uuid::Uuid;
+
+use crate::openapi::{FileUpload, ProxyErrorResponse};
+use crate::services::proxy_service::{ANALYSIS_ENGINE, BOUNTY_MANAGER, REPUTATION_SERVICE, SUBMISSION_SERVICE};
+use crate::AppState;
+
+async fn forward(state: &AppState, service: &str, path: &str, request: Request) -> Response {
+    let max_body_bytes = state.config.max_json_body_bytes() as u64;
+    forward_with_limit(state, service, path, request, max_body_bytes).await
+}
+
+async fn forward_upload(state: &AppState, service: &str, path: &str, request: Request) -> Response {
+    let max_body_bytes = state.config.max_file_size_bytes() as u64;
+    forward_with_limit(state, service, path, request, max_body_bytes).await
+}
+
+async fn forward_with_limit(
+    state: &AppState,
+    service: &str,
+    path: &str,
+    request: Request,
+    max_body_bytes: u64,
+) -> Response {
+    state
+        .proxy
+        .forward(service, path, request, max_body_bytes)
+        .await
+        .unwrap_or_else(IntoResponse::into_response)
+}
+
+// ─── Analysis engine ────────────────────────────────────────────
+
+/// POST /api/v1/analysis/file — multipart upload, streamed to the engine
+#[utoipa::path(
+    post,
+    path = "/api/v1/analysis/file",
+    tag = "analysis",
+    summary = "Analyze a file with the analysis engine",
+    request_body(content = FileUpload, content_type = "multipart/form-data", description = "`file` to analyze"),
+    responses(
+        (status = 200, description = "Analysis queued", body = serde_json::Value),
+        (status = 413, description = "File exceeds the upload limit", body = ProxyErrorResponse),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn analyze_file(State(state): State<AppState>, request: Request) -> Response {
+    forward_upload(&state, ANALYSIS_ENGINE, "/analyze/file", request).await
+}
+
+/// POST /api/v1/analysis/url
+#[utoipa::path(
+    post,
+    path = "/api/v1/analysis/url",
+    tag = "analysis",
+    summary = "Analyze a URL with the analysis engine",
+    request_body(content = serde_json::Value, description = "`url` to analyze"),
+    responses(
+        (status = 200, description = "Analysis queued", body = serde_json::Value),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn analyze_url(State(state): State<AppState>, request: Request) -> Response {
+    forward(&state, ANALYSIS_ENGINE, "/analyze/url", request).await
+}
+
+/// POST /api/v1/analysis/hash
+#[utoipa::path(
+    post,
+    path = "/api/v1/analysis/hash",
+    tag = "analysis",
+    summary = "Look up a file hash with the analysis engine",
+    request_body(content = serde_json::Value, description = "`hash` to look up"),
+    responses(
+        (status = 200, description = "Analysis queued", body = serde_json::Value),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn analyze_hash(State(state): State<AppState>, request: Request) -> Response {
+    forward(&state, ANALYSIS_ENGINE, "/analyze/hash", request).await
+}
+
+/// GET /api/v1/analysis/results/:analysis_id
+#[utoipa::path(
+    get,
+    path = "/api/v1/analysis/results/{analysis_id}",
+    tag = "analysis",
+    summary = "Engine result of an analysis",
+    params(
+        ("analysis_id" = Uuid, Path, description = "Analysis id"),
+    ),
+    responses(
+        (status = 200, description = "Analysis result", body = serde_json::Value),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn get_engine_result(
+    State(state): State<AppState>,
+    Path(analysis_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    forward(&state, ANALYSIS_ENGINE, &format!("/analysis/{}", analysis_id), request).await
+}
+
+/// GET /api/v1/analysis/results/:analysis_id/detailed
+#[utoipa::path(
+    get,
+    path = "/api/v1/analysis/results/{analysis_id}/detailed",
+    tag = "analysis",
+    summary = "Engine result with per-engine details",
+    params(
+        ("analysis_id" = Uuid, Path, description = "Analysis id"),
+    ),
+    responses(
+        (status = 200, description = "Detailed analysis result", body = serde_json::Value),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn get_engine_result_detailed(
+    State(state): State<AppState>,
+    Path(analysis_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/analysis/{}/detailed", analysis_id);
+    forward(&state, ANALYSIS_ENGINE, &path, request).await
+}
+
+/// GET /api/v1/analysis/engines/status
+#[utoipa::path(
+    get,
+    path = "/api/v1/analysis/engines/status",
+    tag = "analysis",
+    summary = "Status of the analysis engines",
+    responses(
+        (status = 200, description = "Engine status", body = serde_json::Value),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn get_engines_status(State(state): State<AppState>, request: Request) -> Response {
+    forward(&state, ANALYSIS_ENGINE, "/engines/status", request).await
+}
+
+// ─── Submission service ─────────────────────────────────────────
+
+/// POST /api/v1/submissions/file — multipart upload, streamed to storage
+#[utoipa::path(
+    post,
+    path = "/api/v1/submissions/file",
+    tag = "submissions",
+    summary = "Submit a file for analysis",
+    request_body(content = FileUpload, content_type = "multipart/form-data", description = "`file` to submit"),
+    responses(
+        (status = 200, description = "File stored and queued", body = serde_json::Value),
+        (status = 413, description = "File exceeds the upload limit", body = ProxyErrorResponse),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn submit_file(State(state): State<AppState>, request: Request) -> Response {
+    forward_upload(&state, SUBMISSION_SERVICE, "/submit/file", request).await
+}
+
+/// POST /api/v1/submissions/url
+#[utoipa::path(
+    post,
+    path = "/api/v1/submissions/url",
+    tag = "submissions",
+    summary = "Submit a URL for analysis",
+    request_body(content = serde_json::Value, description = "`url` to submit"),
+    responses(
+        (status = 200, description = "URL queued", body = serde_json::Value),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn submit_url(State(state): State<AppState>, request: Request) -> Response {
+    forward(&state, SUBMISSION_SERVICE, "/submit/url", request).await
+}
+
+// ─── Bounty manager ─────────────────────────────────────────────
+
+/// GET /api/v1/bounties/archived
+#[utoipa::path(
+    get,
+    path = "/api/v1/bounties/archived",
+    tag = "bounties",
+    summary = "List archived bounties",
+    responses(
+        (status = 200, description = "Page of archived bounty summaries", body = serde_json::Value),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn list_archived_bounties(State(state): State<AppState>, request: Request) -> Response {
+    forward(&state, BOUNTY_MANAGER, "/bounties/archived", request).await
+}
+
+/// POST /api/v1/bounties/:bounty_id/rehydrate
+#[utoipa::path(
+    post,
+    path = "/api/v1/bounties/{bounty_id}/rehydrate",
+    tag = "bounties",
+    summary = "Restore an archived bounty",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+    ),
+    responses(
+        (status = 200, description = "Bounty restored", body = serde_json::Value),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn rehydrate_bounty(
+    State(state): State<AppState>,
+    Path(bounty_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/bounties/{}/rehydrate", bounty_id);
+    forward(&state, BOUNTY_MANAGER, &path, request).await
+}
+
+/// GET /api/v1/bounties/:bounty_id/embargo
+#[utoipa::path(
+    get,
+    path = "/api/v1/bounties/{bounty_id}/embargo",
+    tag = "bounties",
+    summary = "Verdict embargo of a bounty",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+    ),
+    responses(
+        (status = 200, description = "Embargo of the bounty", body = serde_json::Value),
+        (status = 403, description = "Caller is not the creator, a member of their organization or an admin"),
+        (status = 404, description = "Bounty or embargo not found"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn get_bounty_embargo(
+    State(state): State<AppState>,
+    Path(bounty_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/bounties/{}/embargo", bounty_id);
+    forward(&state, BOUNTY_MANAGER, &path, request).await
+}
+
+/// PUT /api/v1/bounties/:bounty_id/embargo
+#[utoipa::path(
+    put,
+    path = "/api/v1/bounties/{bounty_id}/embargo",
+    tag = "bounties",
+    summary = "Withhold the verdicts of a bounty",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+    ),
+    request_body(content = serde_json::Value, description = "`days` (configured default when omitted), `until_released` and `reason`"),
+    responses(
+        (status = 200, description = "Embargo placed", body = serde_json::Value),
+        (status = 400, description = "Embargo length out of range"),
+        (status = 403, description = "Caller is not the creator, a member of their organization or an admin"),
+        (status = 404, description = "Bounty not found"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn place_bounty_embargo(
+    State(state): State<AppState>,
+    Path(bounty_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/bounties/{}/embargo", bounty_id);
+    forward(&state, BOUNTY_MANAGER, &path, request).await
+}
+
+/// POST /api/v1/bounties/:bounty_id/embargo/release
+#[utoipa::path(
+    post,
+    path = "/api/v1/bounties/{bounty_id}/embargo/release",
+    tag = "bounties",
+    summary = "Lift the verdict embargo of a bounty early",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+    ),
+    responses(
+        (status = 200, description = "Embargo released", body = serde_json::Value),
+        (status = 403, description = "Caller is not the creator, a member of their organization or an admin"),
+        (status = 409, description = "No embargo in force"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn release_bounty_embargo(
+    State(state): State<AppState>,
+    Path(bounty_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/bounties/{}/embargo/release", bounty_id);
+    forward(&state, BOUNTY_MANAGER, &path, request).await
+}
+
+/// POST /api/v1/bounties/:bounty_id/funding
+#[utoipa::path(
+    post,
+    path = "/api/v1/bounties/{bounty_id}/funding",
+    tag = "bounties",
+    summary = "Attach the creator's reward deposit to a bounty",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+    ),
+    request_body(content = serde_json::Value, description = "`deposit_tx_hash` of the on-chain deposit"),
+    responses(
+        (status = 200, description = "Deposit attached; the bounty activates once it confirms", body = serde_json::Value),
+        (status = 403, description = "Caller is not the creator"),
+        (status = 409, description = "Bounty is not awaiting funding, or the deposit funds another bounty"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn fund_bounty(
+    State(state): State<AppState>,
+    Path(bounty_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/bounties/{}/funding", bounty_id);
+    forward(&state, BOUNTY_MANAGER, &path, request).await
+}
+
+/// GET /api/v1/bounties/:bounty_id/deposit-verification
+#[utoipa::path(
+    get,
+    path = "/api/v1/bounties/{bounty_id}/deposit-verification",
+    tag = "bounties",
+    summary = "Where verification of a bounty's reward deposit stands",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+    ),
+    responses(
+        (status = 200, description = "Deposit verification", body = serde_json::Value),
+        (status = 403, description = "Caller is not the creator"),
+        (status = 404, description = "No deposit recorded"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn get_deposit_verification(
+    State(state): State<AppState>,
+    Path(bounty_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/bounties/{}/deposit-verification", bounty_id);
+    forward(&state, BOUNTY_MANAGER, &path, request).await
+}
+
+/// GET /api/v1/bounties/:bounty_id/participants
+#[utoipa::path(
+    get,
+    path = "/api/v1/bounties/{bounty_id}/participants",
+    tag = "bounties",
+    summary = "Engines taking part in a bounty",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+    ),
+    responses(
+        (status = 200, description = "Participants with their stake and submission status", body = serde_json::Value),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn list_bounty_participants(
+    State(state): State<AppState>,
+    Path(bounty_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/bounties/{}/participants", bounty_id);
+    forward(&state, BOUNTY_MANAGER, &path, request).await
+}
+
+/// POST /api/v1/bounties/:bounty_id/participants
+#[utoipa::path(
+    post,
+    path = "/api/v1/bounties/{bounty_id}/participants",
+    tag = "bounties",
+    summary = "Join a bounty ahead of submitting",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+    ),
+    request_body(content = serde_json::Value, description = "`stake_amount` to lock"),
+    responses(
+        (status = 200, description = "Joined; the stake is locked", body = serde_json::Value),
+        (status = 400, description = "Stake or reputation below the bounty's requirements"),
+        (status = 409, description = "Already joined, or the bounty is full"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn join_bounty(
+    State(state): State<AppState>,
+    Path(bounty_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/bounties/{}/participants", bounty_id);
+    forward(&state, BOUNTY_MANAGER, &path, request).await
+}
+
+/// POST /api/v1/bounties/:bounty_id/participants/withdraw
+#[utoipa::path(
+    post,
+    path = "/api/v1/bounties/{bounty_id}/participants/withdraw",
+    tag = "bounties",
+    summary = "Withdraw from a bounty before submitting",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+    ),
+    responses(
+        (status = 200, description = "Withdrawn; the stake is released", body = serde_json::Value),
+        (status = 409, description = "Already submitted"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn withdraw_from_bounty(
+    State(state): State<AppState>,
+    Path(bounty_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/bounties/{}/participants/withdraw", bounty_id);
+    forward(&state, BOUNTY_MANAGER, &path, request).await
+}
+
+/// GET /api/v1/bounties/:bounty_id/webhooks
+#[utoipa::path(
+    get,
+    path = "/api/v1/bounties/{bounty_id}/webhooks",
+    tag = "bounties",
+    summary = "Webhooks on one of the caller's bounties",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+    ),
+    responses(
+        (status = 200, description = "The bounty's webhooks", body = serde_json::Value),
+        (status = 403, description = "Caller is not the creator"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn list_bounty_webhooks(
+    State(state): State<AppState>,
+    Path(bounty_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/bounties/{}/webhooks", bounty_id);
+    forward(&state, BOUNTY_MANAGER, &path, request).await
+}
+
+/// POST /api/v1/bounties/:bounty_id/webhooks
+#[utoipa::path(
+    post,
+    path = "/api/v1/bounties/{bounty_id}/webhooks",
+    tag = "bounties",
+    summary = "Subscribe a URL to events of one of the caller's bounties",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+    ),
+    request_body(content = serde_json::Value, description = "HTTPS `url` and `events`"),
+    responses(
+        (status = 201, description = "Webhook registered, with its signing secret", body = serde_json::Value),
+        (status = 400, description = "URL is not an allowed HTTPS address, or no events"),
+        (status = 403, description = "Caller is not the creator"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn register_bounty_webhook(
+    State(state): State<AppState>,
+    Path(bounty_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/bounties/{}/webhooks", bounty_id);
+    forward(&state, BOUNTY_MANAGER, &path, request).await
+}
+
+/// DELETE /api/v1/bounties/:bounty_id/webhooks/:webhook_id
+#[utoipa::path(
+    delete,
+    path = "/api/v1/bounties/{bounty_id}/webhooks/{webhook_id}",
+    tag = "bounties",
+    summary = "Remove a bounty webhook",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+        ("webhook_id" = Uuid, Path, description = "Webhook id"),
+    ),
+    responses(
+        (status = 200, description = "Webhook removed", body = serde_json::Value),
+        (status = 404, description = "No such webhook on the caller's bounty"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn delete_bounty_webhook(
+    State(state): State<AppState>,
+    Path((bounty_id, webhook_id)): Path<(Uuid, Uuid)>,
+    request: Request,
+) -> Response {
+    let path = format!("/bounties/{}/webhooks/{}", bounty_id, webhook_id);
+    forward(&state, BOUNTY_MANAGER, &path, request).await
+}
+
+/// GET /api/v1/bounties/:bounty_id/webhooks/:webhook_id/deliveries
+#[utoipa::path(
+    get,
+    path = "/api/v1/bounties/{bounty_id}/webhooks/{webhook_id}/deliveries",
+    tag = "bounties",
+    summary = "Deliveries of a bounty webhook",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+        ("webhook_id" = Uuid, Path, description = "Webhook id"),
+    ),
+    responses(
+        (status = 200, description = "Deliveries, newest first", body = serde_json::Value),
+        (status = 404, description = "No such webhook on the caller's bounty"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn list_bounty_webhook_deliveries(
+    State(state): State<AppState>,
+    Path((bounty_id, webhook_id)): Path<(Uuid, Uuid)>,
+    request: Request,
+) -> Response {
+    let path = format!("/bounties/{}/webhooks/{}/deliveries", bounty_id, webhook_id);
+    forward(&state, BOUNTY_MANAGER, &path, request).await
+}
+
+/// POST /api/v1/bounties/:bounty_id/report
+#[utoipa::path(
+    post,
+    path = "/api/v1/bounties/{bounty_id}/report",
+    tag = "bounties",
+    summary = "Report a bounty for abuse",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+    ),
+    request_body(content = serde_json::Value, description = "`reason` and optional `details`"),
+    responses(
+        (status = 200, description = "Report recorded", body = serde_json::Value),
+        (status = 404, description = "Bounty not found"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn report_bounty(
+    State(state): State<AppState>,
+    Path(bounty_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/bounties/{}/report", bounty_id);
+    forward(&state, BOUNTY_MANAGER, &path, request).await
+}
+
+/// POST /api/v1/bounties/:bounty_id/submissions
+#[utoipa::path(
+    post,
+    path = "/api/v1/bounties/{bounty_id}/submissions",
+    tag = "bounties",
+    summary = "Submit the calling engine's verdict on a bounty",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+    ),
+    request_body(content = serde_json::Value, description = "`verdict`, `confidence`, `stake_amount` and optional `prediction`"),
+    responses(
+        (status = 200, description = "Submission recorded", body = serde_json::Value),
+        (status = 400, description = "Invalid verdict, confidence or stake"),
+        (status = 409, description = "Bounty is not open to the engine"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn submit_bounty_verdict(
+    State(state): State<AppState>,
+    Path(bounty_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/bounties/{}/submit", bounty_id);
+    forward(&state, BOUNTY_MANAGER, &path, request).await
+}
+
+/// POST /api/v1/bounties/:bounty_id/commitments
+#[utoipa::path(
+    post,
+    path = "/api/v1/bounties/{bounty_id}/commitments",
+    tag = "bounties",
+    summary = "Commit to a verdict on a commit-reveal bounty",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+    ),
+    request_body(content = serde_json::Value, description = "`commitment` hash and `stake_amount`"),
+    responses(
+        (status = 200, description = "Commitment recorded", body = serde_json::Value),
+        (status = 409, description = "Bounty is not taking commitments"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn commit_bounty_verdict(
+    State(state): State<AppState>,
+    Path(bounty_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/bounties/{}/commitments", bounty_id);
+    forward(&state, BOUNTY_MANAGER, &path, request).await
+}
+
+/// POST /api/v1/bounties/:bounty_id/reveal
+#[utoipa::path(
+    post,
+    path = "/api/v1/bounties/{bounty_id}/reveal",
+    tag = "bounties",
+    summary = "Reveal a committed verdict",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+    ),
+    request_body(content = serde_json::Value, description = "`verdict`, `confidence` and the commitment's `nonce`"),
+    responses(
+        (status = 200, description = "Verdict revealed", body = serde_json::Value),
+        (status = 400, description = "Verdict and nonce do not open the commitment"),
+        (status = 409, description = "Reveal window is not open"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn reveal_bounty_verdict(
+    State(state): State<AppState>,
+    Path(bounty_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/bounties/{}/reveal", bounty_id);
+    forward(&state, BOUNTY_MANAGER, &path, request).await
+}
+
+/// GET /api/v1/bounties/export
+#[utoipa::path(
+    get,
+    path = "/api/v1/bounties/export",
+    tag = "bounties",
+    summary = "Export the caller's bounties",
+    params(
+        ("format" = Option<String>, Query, description = "`csv` or `ndjson`"),
+        ("from" = Option<String>, Query, description = "Start of the creation range"),
+        ("to" = Option<String>, Query, description = "End of the creation range"),
+    ),
+    responses(
+        (status = 200, description = "Export rows", body = serde_json::Value),
+        (status = 202, description = "Export queued in the background", body = serde_json::Value),
+        (status = 400, description = "Range too long or invalid"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn export_bounties(State(state): State<AppState>, request: Request) -> Response {
+    forward(&state, BOUNTY_MANAGER, "/bounties/export", request).await
+}
+
+/// GET /api/v1/bounties/exports
+#[utoipa::path(
+    get,
+    path = "/api/v1/bounties/exports",
+    tag = "bounties",
+    summary = "The caller's background exports",
+    responses(
+        (status = 200, description = "Exports, newest first", body = serde_json::Value),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn list_bounty_exports(State(state): State<AppState>, request: Request) -> Response {
+    forward(&state, BOUNTY_MANAGER, "/bounties/exports", request).await
+}
+
+/// GET /api/v1/bounties/exports/:export_id
+#[utoipa::path(
+    get,
+    path = "/api/v1/bounties/exports/{export_id}",
+    tag = "bounties",
+    summary = "One of the caller's background exports",
+    params(
+        ("export_id" = Uuid, Path, description = "Export id"),
+    ),
+    responses(
+        (status = 200, description = "Export with its download link once ready", body = serde_json::Value),
+        (status = 404, description = "No such export of the caller's"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn get_bounty_export(
+    State(state): State<AppState>,
+    Path(export_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/bounties/exports/{}", export_id);
+    forward(&state, BOUNTY_MANAGER, &path, request).await
+}
+
+/// GET /api/v1/bounties/exports/:export_id/download
+#[utoipa::path(
+    get,
+    path = "/api/v1/bounties/exports/{export_id}/download",
+    tag = "bounties",
+    summary = "Download a finished export through its signed link",
+    params(
+        ("export_id" = Uuid, Path, description = "Export id"),
+    ),
+    responses(
+        (status = 200, description = "Export file", body = serde_json::Value),
+        (status = 403, description = "Link signature invalid or expired"),
+        (status = 404, description = "Export not found or deleted"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn download_bounty_export(
+    State(state): State<AppState>,
+    Path(export_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/bounties/exports/{}/download", export_id);
+    forward(&state, BOUNTY_MANAGER, &path, request).await
+}
+
+/// GET /api/v1/bounties/stats
+#[utoipa::path(
+    get,
+    path = "/api/v1/bounties/stats",
+    tag = "bounties",
+    summary = "Bounty totals across the platform",
+    responses(
+        (status = 200, description = "Bounty statistics", body = serde_json::Value),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn get_bounty_manager_stats(State(state): State<AppState>, request: Request) -> Response {
+    forward(&state, BOUNTY_MANAGER, "/bounties/stats", request).await
+}
+
+/// GET /api/v1/bounties/stats/creators
+#[utoipa::path(
+    get,
+    path = "/api/v1/bounties/stats/creators",
+    tag = "bounties",
+    summary = "Per-creator bounty statistics",
+    responses(
+        (status = 200, description = "Creators with their bounty statistics", body = serde_json::Value),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn list_creator_stats(State(state): State<AppState>, request: Request) -> Response {
+    forward(&state, BOUNTY_MANAGER, "/bounties/stats/creators", request).await
+}
+
+/// GET /api/v1/bounties/stats/creators/:creator
+#[utoipa::path(
+    get,
+    path = "/api/v1/bounties/stats/creators/{creator}",
+    tag = "bounties",
+    summary = "Bounty statistics of one creator",
+    params(
+        ("creator" = String, Path, description = "Creator address"),
+    ),
+    responses(
+        (status = 200, description = "Creator statistics", body = serde_json::Value),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn get_creator_stats(
+    State(state): State<AppState>,
+    Path(creator): Path<String>,
+    request: Request,
+) -> Response {
+    let path = format!("/bounties/stats/creators/{}", creator);
+    forward(&state, BOUNTY_MANAGER, &path, request).await
+}
+
+/// GET /api/v1/standing-bounties
+#[utoipa::path(
+    get,
+    path = "/api/v1/standing-bounties",
+    tag = "bounties",
+    summary = "The caller's standing bounties",
+    responses(
+        (status = 200, description = "Standing bounties", body = serde_json::Value),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn list_standing_bounties(State(state): State<AppState>, request: Request) -> Response {
+    forward(&state, BOUNTY_MANAGER, "/standing-bounties", request).await
+}
+
+/// POST /api/v1/standing-bounties
+#[utoipa::path(
+    post,
+    path = "/api/v1/standing-bounties",
+    tag = "bounties",
+    summary = "Create a standing bounty",
+    request_body(content = serde_json::Value, description = "Trigger, reward and terms of the child bounties"),
+    responses(
+        (status = 200, description = "Standing bounty created", body = serde_json::Value),
+        (status = 400, description = "Invalid trigger or terms"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn create_standing_bounty(State(state): State<AppState>, request: Request) -> Response {
+    forward(&state, BOUNTY_MANAGER, "/standing-bounties", request).await
+}
+
+/// GET /api/v1/standing-bounties/:standing_id
+#[utoipa::path(
+    get,
+    path = "/api/v1/standing-bounties/{standing_id}",
+    tag = "bounties",
+    summary = "A standing bounty and the children it has opened",
+    params(
+        ("standing_id" = Uuid, Path, description = "Standing bounty id"),
+    ),
+    responses(
+        (status = 200, description = "Standing bounty", body = serde_json::Value),
+        (status = 404, description = "No such standing bounty of the caller's"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn get_standing_bounty(
+    State(state): State<AppState>,
+    Path(standing_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/standing-bounties/{}", standing_id);
+    forward(&state, BOUNTY_MANAGER, &path, request).await
+}
+
+/// PUT /api/v1/standing-bounties/:standing_id/enabled
+#[utoipa::path(
+    put,
+    path = "/api/v1/standing-bounties/{standing_id}/enabled",
+    tag = "bounties",
+    summary = "Pause or resume a standing bounty",
+    params(
+        ("standing_id" = Uuid, Path, description = "Standing bounty id"),
+    ),
+    request_body(content = serde_json::Value, description = "`enabled`"),
+    responses(
+        (status = 200, description = "Standing bounty updated", body = serde_json::Value),
+        (status = 404, description = "No such standing bounty of the caller's"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn set_standing_bounty_enabled(
+    State(state): State<AppState>,
+    Path(standing_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/standing-bounties/{}/enabled", standing_id);
+    forward(&state, BOUNTY_MANAGER, &path, request).await
+}
+
+/// GET /api/v1/tags
+#[utoipa::path(
+    get,
+    path = "/api/v1/tags",
+    tag = "bounties",
+    summary = "The bounty tag taxonomy",
+    params(
+        ("category" = Option<String>, Query, description = "Only tags of this category"),
+    ),
+    responses(
+        (status = 200, description = "Tags", body = serde_json::Value),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn list_tags(State(state): State<AppState>, request: Request) -> Response {
+    forward(&state, BOUNTY_MANAGER, "/tags", request).await
+}
+
+/// GET /api/v1/tags/stats
+#[utoipa::path(
+    get,
+    path = "/api/v1/tags/stats",
+    tag = "bounties",
+    summary = "Bounty and submission activity per tag",
+    responses(
+        (status = 200, description = "Activity per tag", body = serde_json::Value),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn get_tag_stats(State(state): State<AppState>, request: Request) -> Response {
+    forward(&state, BOUNTY_MANAGER, "/tags/stats", request).await
+}
+
+/// PUT /api/v1/tags/:slug
+#[utoipa::path(
+    put,
+    path = "/api/v1/tags/{slug}",
+    tag = "bounties",
+    summary = "Add or update a tag",
+    params(
+        ("slug" = String, Path, description = "Tag slug"),
+    ),
+    request_body(content = serde_json::Value, description = "`name` and `category`"),
+    responses(
+        (status = 200, description = "Tag saved", body = serde_json::Value),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn upsert_tag(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    request: Request,
+) -> Response {
+    let path = format!("/tags/{}", slug);
+    forward(&state, BOUNTY_MANAGER, &path, request).await
+}
+
+/// DELETE /api/v1/tags/:slug
+#[utoipa::path(
+    delete,
+    path = "/api/v1/tags/{slug}",
+    tag = "bounties",
+    summary = "Remove a tag no bounty carries",
+    params(
+        ("slug" = String, Path, description = "Tag slug"),
+    ),
+    responses(
+        (status = 200, description = "Tag removed", body = serde_json::Value),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 409, description = "Tag is still in use"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn delete_tag(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    request: Request,
+) -> Response {
+    let path = format!("/tags/{}", slug);
+    forward(&state, BOUNTY_MANAGER, &path, request).await
+}
+
+/// GET /api/v1/tags/:slug/bounties
+#[utoipa::path(
+    get,
+    path = "/api/v1/tags/{slug}/bounties",
+    tag = "bounties",
+    summary = "Bounties carrying a tag",
+    params(
+        ("slug" = String, Path, description = "Tag slug"),
+    ),
+    responses(
+        (status = 200, description = "Bounties, newest first", body = serde_json::Value),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn list_tagged_bounties(
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    request: Request,
+) -> Response {
+    let path = format!("/tags/{}/bounties", slug);
+    forward(&state, BOUNTY_MANAGER, &path, request).await
+}
+
+/// GET /api/v1/admin/moderation/queue
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/moderation/queue",
+    tag = "admin",
+    summary = "Open moderation cases",
+    responses(
+        (status = 200, description = "Cases, severe ones and then the oldest first", body = serde_json::Value),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn list_moderation_queue(State(state): State<AppState>, request: Request) -> Response {
+    forward(&state, BOUNTY_MANAGER, "/admin/moderation/queue", request).await
+}
+
+/// GET /api/v1/admin/moderation/bounties/:bounty_id
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/moderation/bounties/{bounty_id}",
+    tag = "admin",
+    summary = "A bounty's moderation case",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+    ),
+    responses(
+        (status = 200, description = "Case with its reports and audit trail", body = serde_json::Value),
+        (status = 404, description = "No case for the bounty"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn get_moderation_case(
+    State(state): State<AppState>,
+    Path(bounty_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/admin/moderation/bounties/{}", bounty_id);
+    forward(&state, BOUNTY_MANAGER, &path, request).await
+}
+
+/// POST /api/v1/admin/moderation/bounties/:bounty_id/hold
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/moderation/bounties/{bounty_id}/hold",
+    tag = "admin",
+    summary = "Hold a bounty for review",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+    ),
+    request_body(content = serde_json::Value, description = "`reason`"),
+    responses(
+        (status = 200, description = "Bounty held", body = serde_json::Value),
+        (status = 409, description = "Bounty cannot be held from its status"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn hold_bounty(
+    State(state): State<AppState>,
+    Path(bounty_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/admin/moderation/bounties/{}/hold", bounty_id);
+    forward(&state, BOUNTY_MANAGER, &path, request).await
+}
+
+/// POST /api/v1/admin/moderation/bounties/:bounty_id/approve
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/moderation/bounties/{bounty_id}/approve",
+    tag = "admin",
+    summary = "Clear a bounty under review",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+    ),
+    request_body(content = serde_json::Value, description = "`reason`"),
+    responses(
+        (status = 200, description = "Case closed", body = serde_json::Value),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn approve_bounty(
+    State(state): State<AppState>,
+    Path(bounty_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/admin/moderation/bounties/{}/approve", bounty_id);
+    forward(&state, BOUNTY_MANAGER, &path, request).await
+}
+
+/// POST /api/v1/admin/moderation/bounties/:bounty_id/takedown
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/moderation/bounties/{bounty_id}/takedown",
+    tag = "admin",
+    summary = "Take a bounty down",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+    ),
+    request_body(content = serde_json::Value, description = "`reason`"),
+    responses(
+        (status = 200, description = "Bounty cancelled and its artifact blocked", body = serde_json::Value),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn takedown_bounty(
+    State(state): State<AppState>,
+    Path(bounty_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/admin/moderation/bounties/{}/takedown", bounty_id);
+    forward(&state, BOUNTY_MANAGER, &path, request).await
+}
+
+/// GET /api/v1/admin/calibration/bounties
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/calibration/bounties",
+    tag = "admin",
+    summary = "Calibration bounties",
+    responses(
+        (status = 200, description = "Calibration bounties, newest first", body = serde_json::Value),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn list_calibration_bounties(State(state): State<AppState>, request: Request) -> Response {
+    forward(&state, BOUNTY_MANAGER, "/admin/calibration/bounties", request).await
+}
+
+/// PUT /api/v1/admin/calibration/bounties/:bounty_id
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/calibration/bounties/{bounty_id}",
+    tag = "admin",
+    summary = "Make a bounty a calibration bounty",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+    ),
+    request_body(content = serde_json::Value, description = "`verdict` the artifact is known to have"),
+    responses(
+        (status = 200, description = "Bounty marked", body = serde_json::Value),
+        (status = 409, description = "Engines have already submitted"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn mark_calibration_bounty(
+    State(state): State<AppState>,
+    Path(bounty_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/admin/calibration/bounties/{}", bounty_id);
+    forward(&state, BOUNTY_MANAGER, &path, request).await
+}
+
+/// GET /api/v1/admin/calibration/artifacts
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/calibration/artifacts",
+    tag = "admin",
+    summary = "The calibration artifact pool",
+    responses(
+        (status = 200, description = "Artifacts, newest first", body = serde_json::Value),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn list_calibration_artifacts(State(state): State<AppState>, request: Request) -> Response {
+    forward(&state, BOUNTY_MANAGER, "/admin/calibration/artifacts", request).await
+}
+
+/// POST /api/v1/admin/calibration/artifacts
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/calibration/artifacts",
+    tag = "admin",
+    summary = "Add an artifact to the calibration pool",
+    request_body(content = serde_json::Value, description = "Artifact and its known `verdict`"),
+    responses(
+        (status = 200, description = "Artifact added", body = serde_json::Value),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn add_calibration_artifact(State(state): State<AppState>, request: Request) -> Response {
+    forward(&state, BOUNTY_MANAGER, "/admin/calibration/artifacts", request).await
+}
+
+/// PUT /api/v1/admin/calibration/artifacts/:artifact_id/active
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/calibration/artifacts/{artifact_id}/active",
+    tag = "admin",
+    summary = "Take an artifact out of the calibration pool or put it back",
+    params(
+        ("artifact_id" = Uuid, Path, description = "Artifact id"),
+    ),
+    request_body(content = serde_json::Value, description = "`active`"),
+    responses(
+        (status = 200, description = "Artifact updated", body = serde_json::Value),
+        (status = 404, description = "Artifact not found"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn set_calibration_artifact_active(
+    State(state): State<AppState>,
+    Path(artifact_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/admin/calibration/artifacts/{}/active", artifact_id);
+    forward(&state, BOUNTY_MANAGER, &path, request).await
+}
+
+// ─── Reputation service ─────────────────────────────────────────
+
+/// GET /api/v1/reputation/webhooks
+#[utoipa::path(
+    get,
+    path = "/api/v1/reputation/webhooks",
+    tag = "reputation",
+    summary = "Webhooks following the caller's reputation",
+    responses(
+        (status = 200, description = "The caller's reputation webhooks", body = serde_json::Value),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn list_reputation_webhooks(State(state): State<AppState>, request: Request) -> Response {
+    forward(&state, REPUTATION_SERVICE, "/api/v1/reputation/webhooks", request).await
+}
+
+/// POST /api/v1/reputation/webhooks
+#[utoipa::path(
+    post,
+    path = "/api/v1/reputation/webhooks",
+    tag = "reputation",
+    summary = "Follow changes in the caller's reputation",
+    request_body(
+        content = serde_json::Value,
+        description = "HTTPS `url` and `events`: `score_changed`, `rank_changed`, `badge_awarded`, `streak_milestone`"
+    ),
+    responses(
+        (status = 201, description = "Webhook registered, with its signing secret", body = serde_json::Value),
+        (status = 400, description = "URL is not HTTPS, no events, or too many webhooks"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn register_reputation_webhook(State(state): State<AppState>, request: Request) -> Response {
+    forward(&state, REPUTATION_SERVICE, "/api/v1/reputation/webhooks", request).await
+}
+
+/// DELETE /api/v1/reputation/webhooks/:webhook_id
+#[utoipa::path(
+    delete,
+    path = "/api/v1/reputation/webhooks/{webhook_id}",
+    tag = "reputation",
+    summary = "Remove a reputation webhook",
+    params(
+        ("webhook_id" = Uuid, Path, description = "Webhook id"),
+    ),
+    responses(
+        (status = 200, description = "Webhook removed", body = serde_json::Value),
+        (status = 404, description = "No such webhook of the caller's"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn delete_reputation_webhook(
+    State(state): State<AppState>,
+    Path(webhook_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/api/v1/reputation/webhooks/{}", webhook_id);
+    forward(&state, REPUTATION_SERVICE, &path, request).await
+}
+
+/// GET /api/v1/reputation/webhooks/:webhook_id/deliveries
+#[utoipa::path(
+    get,
+    path = "/api/v1/reputation/webhooks/{webhook_id}/deliveries",
+    tag = "reputation",
+    summary = "Deliveries of a reputation webhook",
+    params(
+        ("webhook_id" = Uuid, Path, description = "Webhook id"),
+        ("page" = Option<i64>, Query, description = "Page, from 1"),
+        ("limit" = Option<i64>, Query, description = "Deliveries per page, at most 100"),
+    ),
+    responses(
+        (status = 200, description = "Page of deliveries, newest first", body = serde_json::Value),
+        (status = 404, description = "No such webhook of the caller's"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn list_reputation_webhook_deliveries(
+    State(state): State<AppState>,
+    Path(webhook_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/api/v1/reputation/webhooks/{}/deliveries", webhook_id);
+    forward(&state, REPUTATION_SERVICE, &path, request).await
+}
+
+/// GET /api/v1/reputation/appeals
+#[utoipa::path(
+    get,
+    path = "/api/v1/reputation/appeals",
+    tag = "reputation",
+    summary = "The caller's appeals, and those awaiting their vote",
+    responses(
+        (status = 200, description = "`appeals` filed by the caller and `awaiting_vote` on their panels", body = serde_json::Value),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn list_reputation_appeals(State(state): State<AppState>, request: Request) -> Response {
+    forward(&state, REPUTATION_SERVICE, "/api/v1/reputation/appeals", request).await
+}
+
+/// POST /api/v1/reputation/appeals
+#[utoipa::path(
+    post,
+    path = "/api/v1/reputation/appeals",
+    tag = "reputation",
+    summary = "Appeal a reputation penalty",
+    request_body(
+        content = serde_json::Value,
+        description = "`history_id` of the penalty, `reason` and optional `evidence`: `description` and HTTPS `url`"
+    ),
+    responses(
+        (status = 201, description = "Appeal filed", body = serde_json::Value),
+        (status = 400, description = "Not an appealable penalty, too old, or already appealed"),
+        (status = 404, description = "No such history entry of the caller's"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn file_reputation_appeal(State(state): State<AppState>, request: Request) -> Response {
+    forward(&state, REPUTATION_SERVICE, "/api/v1/reputation/appeals", request).await
+}
+
+/// GET /api/v1/reputation/appeals/:appeal_id
+#[utoipa::path(
+    get,
+    path = "/api/v1/reputation/appeals/{appeal_id}",
+    tag = "reputation",
+    summary = "An appeal with its evidence, panel and audit trail",
+    params(
+        ("appeal_id" = Uuid, Path, description = "Appeal id"),
+    ),
+    responses(
+        (status = 200, description = "The appeal", body = serde_json::Value),
+        (status = 404, description = "No such appeal by or before the caller"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn get_reputation_appeal(
+    State(state): State<AppState>,
+    Path(appeal_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/api/v1/reputation/appeals/{}", appeal_id);
+    forward(&state, REPUTATION_SERVICE, &path, request).await
+}
+
+/// POST /api/v1/reputation/appeals/:appeal_id/evidence
+#[utoipa::path(
+    post,
+    path = "/api/v1/reputation/appeals/{appeal_id}/evidence",
+    tag = "reputation",
+    summary = "Add evidence to an undecided appeal",
+    params(
+        ("appeal_id" = Uuid, Path, description = "Appeal id"),
+    ),
+    request_body(content = serde_json::Value, description = "`evidence`: `description` and HTTPS `url` of each item"),
+    responses(
+        (status = 200, description = "All of the appeal's evidence", body = serde_json::Value),
+        (status = 400, description = "Appeal already decided, or too much evidence"),
+        (status = 404, description = "No such appeal of the caller's"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn add_reputation_appeal_evidence(
+    State(state): State<AppState>,
+    Path(appeal_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/api/v1/reputation/appeals/{}/evidence", appeal_id);
+    forward(&state, REPUTATION_SERVICE, &path, request).await
+}
+
+/// POST /api/v1/reputation/appeals/:appeal_id/withdraw
+#[utoipa::path(
+    post,
+    path = "/api/v1/reputation/appeals/{appeal_id}/withdraw",
+    tag = "reputation",
+    summary = "Withdraw an undecided appeal",
+    params(
+        ("appeal_id" = Uuid, Path, description = "Appeal id"),
+    ),
+    responses(
+        (status = 200, description = "Appeal withdrawn; the penalty stands", body = serde_json::Value),
+        (status = 400, description = "Appeal already decided"),
+        (status = 404, description = "No such appeal of the caller's"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn withdraw_reputation_appeal(
+    State(state): State<AppState>,
+    Path(appeal_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/api/v1/reputation/appeals/{}/withdraw", appeal_id);
+    forward(&state, REPUTATION_SERVICE, &path, request).await
+}
+
+/// POST /api/v1/reputation/appeals/:appeal_id/votes
+#[utoipa::path(
+    post,
+    path = "/api/v1/reputation/appeals/{appeal_id}/votes",
+    tag = "reputation",
+    summary = "Vote on an appeal as one of its arbitrators",
+    params(
+        ("appeal_id" = Uuid, Path, description = "Appeal id"),
+    ),
+    request_body(content = serde_json::Value, description = "`uphold` to reverse the penalty, optional `notes`"),
+    responses(
+        (status = 200, description = "The appeal, decided if this vote gave either side a majority", body = serde_json::Value),
+        (status = 400, description = "Already voted, or voting closed"),
+        (status = 404, description = "No such appeal with the caller on its panel"),
+        (status = 502, description = "Upstream failed", body = ProxyErrorResponse),
+        (status = 503, description = "Upstream circuit open", body = ProxyErrorResponse),
+        (status = 504, description = "Upstream timed out", body = ProxyErrorResponse),
+    )
+)]
+pub async fn vote_on_reputation_appeal(
+    State(state): State<AppState>,
+    Path(appeal_id): Path<Uuid>,
+    request: Request,
+) -> Response {
+    let path = format!("/api/v1/reputation/appeals/{}/votes", appeal_id);
+    forward(&state, REPUTATION_SERVICE, &path, request).await
+}
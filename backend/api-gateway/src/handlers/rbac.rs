@@ -0,0 +1,266 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::middleware::auth::Claims;
+use crate::models::error::ApiError;
+use crate::services::rbac::{OrganizationRoleGrant, RbacError, Role, RoleDefinition, RoleGrant, PERMISSIONS};
+use crate::AppState;
+
+impl From<RbacError> for ApiError {
+    fn from(err: RbacError) -> Self {
+        match err {
+            RbacError::UnknownPermission(_) | RbacError::InvalidRoleName(_) => {
+                ApiError::Validation(err.to_string())
+            }
+            RbacError::RoleNotFound(_) | RbacError::UserNotFound(_) | RbacError::OrganizationNotFound(_) => {
+                ApiError::NotFound(err.to_string())
+            }
+            RbacError::SystemRole(_) => ApiError::Conflict(err.to_string()),
+            RbacError::Database(e) => {
+                tracing::error!("RBAC query failed: {}", e);
+                ApiError::Database("Failed to access roles".to_string())
+            }
+        }
+    }
+}
+
+/// A permission roles can grant
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PermissionInfo {
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+/// Body of a role assignment
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AssignRoleRequest {
+    pub role: String,
+}
+
+/// Permissions that can be granted to roles
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/permissions",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Permission catalog", body = Vec<PermissionInfo>),
+    )
+)]
+pub async fn list_permissions() -> Json<Vec<PermissionInfo>> {
+    Json(
+        PERMISSIONS
+            .iter()
+            .map(|&(name, description)| PermissionInfo { name, description })
+            .collect(),
+    )
+}
+
+/// Roles and the permissions they grant
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/roles",
+    tag = "admin",
+    responses(
+        (status = 200, description = "All roles", body = Vec<Role>),
+        (status = 500, description = "Internal error", body = crate::openapi::ErrorResponse),
+    )
+)]
+pub async fn list_roles(State(state): State<AppState>) -> Result<Json<Vec<Role>>, ApiError> {
+    Ok(Json(state.rbac.list_roles().await?))
+}
+
+/// Create a role or replace its permissions
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/roles/{role}",
+    tag = "admin",
+    params(
+        ("role" = String, Path, description = "Role name: lowercase letters, digits, `-` and `_`"),
+    ),
+    request_body = RoleDefinition,
+    responses(
+        (status = 200, description = "Role saved", body = Role),
+        (status = 422, description = "Invalid role name or unknown permission", body = crate::openapi::ErrorResponse),
+    )
+)]
+pub async fn put_role(
+    State(state): State<AppState>,
+    Path(role): Path<String>,
+    Json(definition): Json<RoleDefinition>,
+) -> Result<Json<Role>, ApiError> {
+    let saved = state.rbac.upsert_role(&role, definition).await?;
+    tracing::info!("Role {} now grants {:?}", saved.name, saved.permissions);
+    Ok(Json(saved))
+}
+
+/// Delete a custom role and every assignment of it
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/roles/{role}",
+    tag = "admin",
+    params(
+        ("role" = String, Path, description = "Role name"),
+    ),
+    responses(
+        (status = 204, description = "Role deleted"),
+        (status = 404, description = "Role not found", body = crate::openapi::ErrorResponse),
+        (status = 409, description = "Built-in roles cannot be deleted", body = crate::openapi::ErrorResponse),
+    )
+)]
+pub async fn delete_role(
+    State(state): State<AppState>,
+    Path(role): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    state.rbac.delete_role(&role).await?;
+    tracing::info!("Role {} deleted", role);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Roles granted to a user in addition to their account role
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/users/{user_id}/roles",
+    tag = "admin",
+    params(
+        ("user_id" = Uuid, Path, description = "User id"),
+    ),
+    responses(
+        (status = 200, description = "Granted roles", body = Vec<RoleGrant>),
+    )
+)]
+pub async fn list_user_roles(
+    State(state): State<AppState>,
+    Path(user_id): Path<Uuid>,
+) -> Result<Json<Vec<RoleGrant>>, ApiError> {
+    Ok(Json(state.rbac.grants_for(user_id).await?))
+}
+
+/// Grant a role to a user
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/users/{user_id}/roles",
+    tag = "admin",
+    params(
+        ("user_id" = Uuid, Path, description = "User id"),
+    ),
+    request_body = AssignRoleRequest,
+    responses(
+        (status = 201, description = "Role granted (or already held)"),
+        (status = 404, description = "Unknown user or role", body = crate::openapi::ErrorResponse),
+    )
+)]
+pub async fn assign_user_role(
+    State(state): State<AppState>,
+    claims: Claims,
+    Path(user_id): Path<Uuid>,
+    Json(request): Json<AssignRoleRequest>,
+) -> Result<StatusCode, ApiError> {
+    state.rbac.assign_role(user_id, &request.role, claims.sub).await?;
+    tracing::info!("{} granted role {} to {}", claims.sub, request.role, user_id);
+    Ok(StatusCode::CREATED)
+}
+
+/// Revoke a role from a user
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/users/{user_id}/roles/{role}",
+    tag = "admin",
+    params(
+        ("user_id" = Uuid, Path, description = "User id"),
+        ("role" = String, Path, description = "Role name"),
+    ),
+    responses(
+        (status = 204, description = "Role revoked"),
+        (status = 404, description = "The user does not hold the role", body = crate::openapi::ErrorResponse),
+    )
+)]
+pub async fn revoke_user_role(
+    State(state): State<AppState>,
+    claims: Claims,
+    Path((user_id, role)): Path<(Uuid, String)>,
+) -> Result<StatusCode, ApiError> {
+    if !state.rbac.revoke_role(user_id, &role).await? {
+        return Err(RbacError::RoleNotFound(role).into());
+    }
+    tracing::info!("{} revoked role {} from {}", claims.sub, role, user_id);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Roles granted to every member of an organization
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/organizations/{organization_id}/roles",
+    tag = "admin",
+    params(
+        ("organization_id" = Uuid, Path, description = "Organization id"),
+    ),
+    responses(
+        (status = 200, description = "Granted roles", body = Vec<OrganizationRoleGrant>),
+    )
+)]
+pub async fn list_organization_roles(
+    State(state): State<AppState>,
+    Path(organization_id): Path<Uuid>,
+) -> Result<Json<Vec<OrganizationRoleGrant>>, ApiError> {
+    Ok(Json(state.rbac.organization_grants(organization_id).await?))
+}
+
+/// Grant a role to every member of an organization
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/organizations/{organization_id}/roles",
+    tag = "admin",
+    params(
+        ("organization_id" = Uuid, Path, description = "Organization id"),
+    ),
+    request_body = AssignRoleRequest,
+    responses(
+        (status = 201, description = "Role granted (or already held)"),
+        (status = 404, description = "Unknown organization or role", body = crate::openapi::ErrorResponse),
+    )
+)]
+pub async fn assign_organization_role(
+    State(state): State<AppState>,
+    claims: Claims,
+    Path(organization_id): Path<Uuid>,
+    Json(request): Json<AssignRoleRequest>,
+) -> Result<StatusCode, ApiError> {
+    state
+        .rbac
+        .assign_organization_role(organization_id, &request.role, claims.sub)
+        .await?;
+    tracing::info!("{} granted role {} to organization {}", claims.sub, request.role, organization_id);
+    Ok(StatusCode::CREATED)
+}
+
+/// Revoke a role from an organization
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/organizations/{organization_id}/roles/{role}",
+    tag = "admin",
+    params(
+        ("organization_id" = Uuid, Path, description = "Organization id"),
+        ("role" = String, Path, description = "Role name"),
+    ),
+    responses(
+        (status = 204, description = "Role revoked"),
+        (status = 404, description = "The organization does not hold the role", body = crate::openapi::ErrorResponse),
+    )
+)]
+pub async fn revoke_organization_role(
+    State(state): State<AppState>,
+    claims: Claims,
+    Path((organization_id, role)): Path<(Uuid, String)>,
+) -> Result<StatusCode, ApiError> {
+    if !state.rbac.revoke_organization_role(organization_id, &role).await? {
+        return Err(RbacError::RoleNotFound(role).into());
+    }
+    tracing::info!("{} revoked role {} from organization {}", claims.sub, role, organization_id);
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,216 @@
+use axum::{
+    extract::{
+        ws::{CloseFrame, Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use serde::Deserialize;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, info, warn};
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::middleware::auth::{authorize, session_is_live, Claims, REFRESH_TOKEN_ROLE};
+use crate::middleware::route_policy::RoutePolicy;
+use crate::models::error::ApiError;
+use crate::services::realtime::{ClientMessage, Topic, TopicFilter, WebSocketMessage};
+use crate::AppState;
+
+/// Close code sent when the access token expires mid-connection
+const CLOSE_POLICY_VIOLATION: u16 = 1008;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct WebSocketQuery {
+    /// Access token, for clients that cannot set the `Authorization` header
+    pub token: Option<String>,
+    /// Comma-separated initial topics; all topics when omitted
+    pub topics: Option<String>,
+}
+
+fn parse_topics(topics: Option<&str>) -> Result<Vec<Topic>, String> {
+    match topics {
+        None => Ok(Topic::ALL.to_vec()),
+        Some(list) => list
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::parse)
+            .collect(),
+    }
+}
+
+/// Real-time event stream
+///
+/// GET /api/v1/ws
+///
+/// Upgrades to a WebSocket that pushes `WebSocketMessage`s for the
+/// subscribed topics. The access token comes from the `Authorization` header
+/// or, for browsers, the `token` query parameter. Clients change their
+/// topics with `{"type": "subscribe" | "unsubscribe", "topics": [...]}`.
+/// Payment events only reach their recipient, and verdicts the bounty's
+/// creator and the sample's submitter unless embargoed (admins get all).
+/// The socket is closed when the token expires.
+#[utoipa::path(
+    get,
+    path = "/api/v1/ws",
+    tag = "realtime",
+    params(WebSocketQuery),
+    responses(
+        (status = 101, description = "Switching to the WebSocket protocol", body = WebSocketMessage),
+        (status = 400, description = "Unknown topic", body = crate::openapi::ErrorResponse),
+        (status = 401, description = "Missing or invalid access token", body = crate::openapi::ErrorResponse),
+    )
+)]
+pub async fn websocket(
+    State(state): State<AppState>,
+    Query(query): Query<WebSocketQuery>,
+    header_claims: Option<Claims>,
+    upgrade: WebSocketUpgrade,
+) -> Result<Response, ApiError> {
+    // The auth layer treats this route as public and only reads the header
+    let claims = match header_claims {
+        Some(claims) => claims,
+        None => {
+            let token = query
+                .token
+                .as_deref()
+                .ok_or_else(|| ApiError::Unauthorized("Missing access token".to_string()))?;
+            let claims = state.jwt.validate_token(token)?;
+            if !session_is_live(&state.sessions, &claims).await {
+                return Err(ApiError::Unauthorized("Session has ended".to_string()));
+            }
+            claims
+        }
+    };
+    if claims.role == REFRESH_TOKEN_ROLE {
+        return Err(ApiError::Unauthorized("Refresh tokens cannot open a stream".to_string()));
+    }
+
+    let topics = parse_topics(query.topics.as_deref()).map_err(ApiError::BadRequest)?;
+    let caller = state.rbac.auth_context(&claims).await;
+    let is_admin = authorize(RoutePolicy::Admin, Some(&caller)).is_ok();
+    let filter = TopicFilter::new(claims.sub, is_admin, topics);
+
+    Ok(upgrade
+        .on_upgrade(move |socket| run_connection(socket, state, claims, filter))
+        .into_response())
+}
+
+async fn send(socket: &mut WebSocket, message: &WebSocketMessage) -> bool {
+    match serde_json::to_string(message) {
+        Ok(text) => socket.send(Message::Text(text)).await.is_ok(),
+        Err(e) => {
+            warn!("Failed to serialize WebSocket message: {}", e);
+            true
+        }
+    }
+}
+
+/// Apply one client message and build the reply
+fn handle_client_message(filter: &mut TopicFilter, text: &str) -> WebSocketMessage {
+    match serde_json::from_str::<ClientMessage>(text) {
+        Ok(ClientMessage::Subscribe { topics }) => {
+            filter.subscribe(&topics);
+            WebSocketMessage::Subscriptions { topics: filter.topics() }
+        }
+        Ok(ClientMessage::Unsubscribe { topics }) => {
+            filter.unsubscribe(&topics);
+            WebSocketMessage::Subscriptions { topics: filter.topics() }
+        }
+        Ok(ClientMessage::Ping) => WebSocketMessage::Pong,
+        Err(e) => WebSocketMessage::Error {
+            message: format!("Invalid message: {}", e),
+        },
+    }
+}
+
+async fn run_connection(mut socket: WebSocket, state: AppState, claims: Claims, mut filter: TopicFilter) {
+    let connection_id = Uuid::new_v4();
+    let mut events = state.realtime.subscribe();
+    info!("WebSocket {} opened for user {}", connection_id, claims.sub);
+
+    let connected = WebSocketMessage::Connected {
+        connection_id,
+        user_id: claims.sub,
+        topics: filter.topics(),
+    };
+    if !send(&mut socket, &connected).await {
+        return;
+    }
+
+    let ttl = Duration::from_secs((claims.exp - Utc::now().timestamp()).max(0) as u64);
+    let expiry = tokio::time::sleep(ttl);
+    tokio::pin!(expiry);
+
+    loop {
+        tokio::select! {
+            event = events.recv() => {
+                let message = match event {
+                    Ok(event) if filter.accepts(&event) => WebSocketMessage::from(event),
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(skipped)) => WebSocketMessage::Lagged { skipped },
+                    Err(RecvError::Closed) => break,
+                };
+                if !send(&mut socket, &message).await {
+                    break;
+                }
+            }
+            incoming = socket.recv() => {
+                let reply = match incoming {
+                    Some(Ok(Message::Text(text))) => handle_client_message(&mut filter, &text),
+                    Some(Ok(Message::Close(_))) | None | Some(Err(_)) => break,
+                    // Pings are answered by the protocol layer
+                    Some(Ok(_)) => continue,
+                };
+                if !send(&mut socket, &reply).await {
+                    break;
+                }
+            }
+            _ = &mut expiry => {
+                debug!("Access token of WebSocket {} expired", connection_id);
+                let _ = socket
+                    .send(Message::Close(Some(CloseFrame {
+                        code: CLOSE_POLICY_VIOLATION,
+                        reason: "Access token expired".into(),
+                    })))
+                    .await;
+                break;
+            }
+        }
+    }
+
+    info!("WebSocket {} closed", connection_id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_topics() {
+        assert_eq!(parse_topics(None).unwrap(), Topic::ALL.to_vec());
+        assert_eq!(
+            parse_topics(Some("bounty_updated, payment_settled,")).unwrap(),
+            vec![Topic::BountyUpdated, Topic::PaymentSettled]
+        );
+        assert!(parse_topics(Some("bounty_updated,everything")).is_err());
+    }
+
+    #[test]
+    fn test_client_messages_update_filter() {
+        let mut filter = TopicFilter::new(Uuid::new_v4(), false, []);
+
+        let reply = handle_client_message(&mut filter, r#"{"type":"subscribe","topics":["consensus_reached"]}"#);
+        assert!(matches!(reply, WebSocketMessage::Subscriptions { ref topics } if topics == &[Topic::ConsensusReached]));
+
+        let reply = handle_client_message(&mut filter, r#"{"type":"unsubscribe","topics":["consensus_reached"]}"#);
+        assert!(matches!(reply, WebSocketMessage::Subscriptions { ref topics } if topics.is_empty()));
+
+        assert!(matches!(handle_client_message(&mut filter, r#"{"type":"ping"}"#), WebSocketMessage::Pong));
+        assert!(matches!(handle_client_message(&mut filter, "hello"), WebSocketMessage::Error { .. }));
+    }
+}
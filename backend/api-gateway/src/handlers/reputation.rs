@@ -1,16 +1,18 @@
 use axum::{
-    extract::{Path, Query, State},
+    extract::{Path, Query, Request, State},
     http::StatusCode,
-    response::Json,
+    response::{IntoResponse, Json, Response},
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
+use crate::services::proxy_service::REPUTATION_SERVICE;
 use crate::AppState;
 
 /// Leaderboard entry
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct LeaderboardEntry {
     pub rank: u32,
     pub user_id: Uuid,
@@ -19,14 +21,26 @@ pub struct LeaderboardEntry {
 }
 
 /// Leaderboard query parameters
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct LeaderboardQuery {
     pub page: Option<u32>,
     pub limit: Option<u32>,
+    /// Reputation season like `2026-Q3`, or `current`; served by the
+    /// reputation service
+    pub season: Option<String>,
+    /// `24h`, `7d` or `30d` to rank by reputation gained in the window, or
+    /// `all`; served by the reputation service
+    pub window: Option<String>,
+    /// `engine` or `human`; served by the reputation service
+    pub participant: Option<String>,
+    /// Threat category slug to rank by category score; served by the
+    /// reputation service
+    pub specialization: Option<String>,
 }
 
 /// Leaderboard response
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct LeaderboardResponse {
     pub entries: Vec<LeaderboardEntry>,
     pub total: i64,
@@ -35,7 +49,7 @@ pub struct LeaderboardResponse {
 }
 
 /// User reputation response
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct UserReputation {
     pub user_id: Uuid,
     pub username: Option<String>,
@@ -44,7 +58,7 @@ pub struct UserReputation {
 }
 
 /// Reputation history entry
-#[derive(Debug, Serialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
 pub struct ReputationHistoryEntry {
     pub id: Uuid,
     pub event_type: String,
@@ -55,7 +69,7 @@ pub struct ReputationHistoryEntry {
 }
 
 /// Reputation history response
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ReputationHistoryResponse {
     pub entries: Vec<ReputationHistoryEntry>,
     pub total: i64,
@@ -73,9 +87,48 @@ struct LeaderboardRow {
 }
 
 /// Get global leaderboard
+#[utoipa::path(
+    get,
+    path = "/api/v1/reputation/leaderboard",
+    tag = "reputation",
+    params(
+        LeaderboardQuery,
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Page of the leaderboard; with `season`, the season and a page of its leaderboard; with \
+                           `window`, `participant` or `specialization`, a page of that board and its `total`",
+            body = LeaderboardResponse
+        ),
+        (status = 400, description = "Unknown window or participant, or season combined with them"),
+        (status = 404, description = "Season or category not found"),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn get_leaderboard(
     State(state): State<AppState>,
     Query(params): Query<LeaderboardQuery>,
+    request: Request,
+) -> Response {
+    let served_upstream = params.season.is_some()
+        || params.window.is_some()
+        || params.participant.is_some()
+        || params.specialization.is_some();
+    if served_upstream {
+        let max_body_bytes = state.config.max_json_body_bytes() as u64;
+        return state
+            .proxy
+            .forward(REPUTATION_SERVICE, "/api/v1/reputation/leaderboard", request, max_body_bytes)
+            .await
+            .unwrap_or_else(IntoResponse::into_response);
+    }
+    global_leaderboard(&state, &params).await.into_response()
+}
+
+async fn global_leaderboard(
+    state: &AppState,
+    params: &LeaderboardQuery,
 ) -> Result<Json<LeaderboardResponse>, StatusCode> {
     let page = params.page.unwrap_or(1);
     let limit = params.limit.unwrap_or(50).min(100);
@@ -120,6 +173,15 @@ pub async fn get_leaderboard(
 }
 
 /// Get top analysts (top 10)
+#[utoipa::path(
+    get,
+    path = "/api/v1/reputation/leaderboard/top",
+    tag = "reputation",
+    responses(
+        (status = 200, description = "Ten highest-ranked analysts", body = Vec<LeaderboardEntry>),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn get_top_analysts(
     State(state): State<AppState>,
 ) -> Result<Json<Vec<LeaderboardEntry>>, StatusCode> {
@@ -149,6 +211,19 @@ pub async fn get_top_analysts(
 }
 
 /// Get user reputation by ID
+#[utoipa::path(
+    get,
+    path = "/api/v1/reputation/user/{user_id}",
+    tag = "reputation",
+    params(
+        ("user_id" = Uuid, Path, description = "User id"),
+    ),
+    responses(
+        (status = 200, description = "Reputation of the user", body = UserReputation),
+        (status = 404, description = "User not found"),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn get_user_reputation(
     State(state): State<AppState>,
     Path(user_id): Path<Uuid>,
@@ -183,6 +258,19 @@ pub async fn get_user_reputation(
 }
 
 /// Get reputation history for a user
+#[utoipa::path(
+    get,
+    path = "/api/v1/reputation/history/{user_id}",
+    tag = "reputation",
+    params(
+        ("user_id" = Uuid, Path, description = "User id"),
+        LeaderboardQuery,
+    ),
+    responses(
+        (status = 200, description = "Page of reputation changes", body = ReputationHistoryResponse),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn get_reputation_history(
     State(state): State<AppState>,
     Path(user_id): Path<Uuid>,
@@ -238,6 +326,12 @@ pub async fn get_reputation_history(
 }
 
 /// List available badges (static)
+#[utoipa::path(
+    get,
+    path = "/api/v1/reputation/badges",
+    tag = "reputation",
+    responses((status = 200, description = "Badges that can be earned", body = Vec<serde_json::Value>))
+)]
 pub async fn list_available_badges(
     State(_state): State<AppState>,
 ) -> Result<Json<Vec<serde_json::Value>>, StatusCode> {
@@ -251,6 +345,17 @@ pub async fn list_available_badges(
 }
 
 /// Claim badge — check eligibility and award
+#[utoipa::path(
+    post,
+    path = "/api/v1/reputation/claim-badge",
+    tag = "reputation",
+    request_body(content = serde_json::Value, description = "`badge_id` of the badge to claim"),
+    responses(
+        (status = 200, description = "Badge claimed", body = serde_json::Value),
+        (status = 400, description = "Unknown badge"),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn claim_badge(
     State(state): State<AppState>,
     claims: crate::middleware::auth::Claims,
@@ -10,6 +10,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::models::{
@@ -20,7 +21,7 @@ use crate::models::{
 use crate::utils::{crypto::calculate_file_hash, validation::FileValidator};
 
 // Request/Response DTOs
-#[derive(Deserialize, Clone, Serialize)]
+#[derive(Deserialize, Clone, Serialize, ToSchema)]
 pub struct CreateSubmissionRequest {
     pub bounty_id: Uuid,
     pub engine_name: String,
@@ -42,7 +43,8 @@ pub struct UpdateSubmissionRequest {
     pub additional_signatures: Option<Vec<String>>,
 }
 
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct SubmissionFilters {
     pub bounty_id: Option<Uuid>,
     pub engine_id: Option<String>,
@@ -56,7 +58,7 @@ pub struct SubmissionFilters {
     pub limit: Option<u32>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct SubmissionResponse {
     pub id: Uuid,
     pub bounty_id: Uuid,
@@ -77,7 +79,7 @@ pub struct SubmissionResponse {
     pub reputation_change: Option<i32>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct DetailedSubmissionResponse {
     pub submission: SubmissionResponse,
     pub technical_details: serde_json::Value,
@@ -86,7 +88,7 @@ pub struct DetailedSubmissionResponse {
     pub file_info: Option<FileInfo>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct SubmissionListResponse {
     pub submissions: Vec<SubmissionResponse>,
     pub total_count: u32,
@@ -119,7 +121,7 @@ pub struct SubmissionError {
     pub request_data: serde_json::Value,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct AnalysisMetrics {
     pub processing_time_ms: u64,
     pub signatures_matched: u32,
@@ -128,14 +130,15 @@ pub struct AnalysisMetrics {
     pub resource_usage: ResourceUsage,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct ResourceUsage {
     pub cpu_time_ms: u64,
     pub memory_usage_mb: u64,
     pub disk_io_mb: u64,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
+#[schema(as = SubmissionFileInfo)]
 pub struct FileInfo {
     pub hash: String,
     pub size: u64,
@@ -146,7 +149,7 @@ pub struct FileInfo {
     pub last_analysis: Option<DateTime<Utc>>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, ToSchema)]
 pub enum SubmissionStatus {
     Pending,
     Processing,
@@ -243,6 +246,16 @@ pub async fn upload_file(
     Err(StatusCode::BAD_REQUEST)
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/submissions",
+    tag = "submissions",
+    request_body = CreateSubmissionRequest,
+    responses(
+        (status = 200, description = "Submission created", body = SubmissionResponse),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn create_submission(
     State(state): State<AppState>,
     Json(request): Json<CreateSubmissionRequest>,
@@ -591,6 +604,18 @@ pub fn create_submission_router() -> Router<AppState> {
 // Aliases / stubs for v1 routes
 
 /// List submissions (alias for get_submissions)
+#[utoipa::path(
+    get,
+    path = "/api/v1/submissions",
+    tag = "submissions",
+    params(
+        SubmissionFilters,
+    ),
+    responses(
+        (status = 200, description = "Page of submissions", body = SubmissionListResponse),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn list_submissions(
     state: State<AppState>,
     query: Query<SubmissionFilters>,
@@ -599,6 +624,19 @@ pub async fn list_submissions(
 }
 
 /// Get single submission (alias for get_submission_details)
+#[utoipa::path(
+    get,
+    path = "/api/v1/submissions/{submission_id}",
+    tag = "submissions",
+    params(
+        ("submission_id" = Uuid, Path, description = "Submission id"),
+    ),
+    responses(
+        (status = 200, description = "Submission with technical details", body = DetailedSubmissionResponse),
+        (status = 404, description = "Submission not found"),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn get_submission(
     state: State<AppState>,
     path: Path<Uuid>,
@@ -607,6 +645,16 @@ pub async fn get_submission(
 }
 
 /// Vote on a submission
+#[utoipa::path(
+    post,
+    path = "/api/v1/submissions/{submission_id}/vote",
+    tag = "submissions",
+    params(
+        ("submission_id" = Uuid, Path, description = "Submission id"),
+    ),
+    request_body = serde_json::Value,
+    responses((status = 501, description = "Not implemented yet"))
+)]
 pub async fn vote_on_submission(
     State(_state): State<AppState>,
     Path(_submission_id): Path<Uuid>,
@@ -616,6 +664,15 @@ pub async fn vote_on_submission(
 }
 
 /// Verify a submission
+#[utoipa::path(
+    post,
+    path = "/api/v1/submissions/{submission_id}/verify",
+    tag = "submissions",
+    params(
+        ("submission_id" = Uuid, Path, description = "Submission id"),
+    ),
+    responses((status = 501, description = "Not implemented yet"))
+)]
 pub async fn verify_submission(
     State(_state): State<AppState>,
     Path(_submission_id): Path<Uuid>,
@@ -624,6 +681,12 @@ pub async fn verify_submission(
 }
 
 /// Get current user's submissions
+#[utoipa::path(
+    get,
+    path = "/api/v1/submissions/my-submissions",
+    tag = "submissions",
+    responses((status = 200, description = "Submissions of the caller", body = SubmissionListResponse))
+)]
 pub async fn get_my_submissions(
     State(_state): State<AppState>,
 ) -> Result<Json<SubmissionListResponse>, StatusCode> {
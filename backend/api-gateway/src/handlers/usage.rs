@@ -0,0 +1,321 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::middleware::auth::Claims;
+use crate::services::quota::{quota_reset_at, OrganizationQuota, QuotaBudget};
+use crate::services::usage::{billing_csv, BillingPeriod, DailyUsage};
+use crate::AppState;
+
+/// Longest range served by the usage API
+const MAX_RANGE_DAYS: i64 = 366;
+
+/// Usage query (inclusive UTC dates, defaults to the last 30 days)
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct UsageQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+/// Totals over the requested range
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct UsageTotals {
+    pub api_calls: i64,
+    pub analysis_minutes: i64,
+    pub storage_bytes: i64,
+}
+
+/// Usage response
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UsageResponse {
+    pub organization_id: Uuid,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub totals: UsageTotals,
+    pub days: Vec<DailyUsage>,
+}
+
+/// Billing export response
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BillingExportResponse {
+    pub period: String,
+    pub file_path: String,
+    pub organization_count: usize,
+}
+
+/// Consumption of one daily budget
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BudgetStatus {
+    pub budget: QuotaBudget,
+    /// `null` when unlimited
+    pub limit: Option<u32>,
+    pub used: u32,
+    pub remaining: Option<u32>,
+}
+
+/// Quotas of the caller's organization and how much of them is used today
+#[derive(Debug, Serialize, ToSchema)]
+pub struct QuotaStatusResponse {
+    pub organization_id: Uuid,
+    pub quota: OrganizationQuota,
+    pub budgets: Vec<BudgetStatus>,
+    /// When the daily budgets reset (00:00 UTC)
+    pub reset_at: DateTime<Utc>,
+}
+
+// ─── Handlers ───
+
+/// Daily usage breakdown for the caller's organization
+#[utoipa::path(
+    get,
+    path = "/api/v1/usage",
+    tag = "usage",
+    params(
+        UsageQuery,
+    ),
+    responses(
+        (status = 200, description = "Usage per day and in total", body = UsageResponse),
+        (status = 400, description = "Invalid or too long date range"),
+        (status = 404, description = "Caller belongs to no organization"),
+        (status = 500, description = "Internal error"),
+    )
+)]
+pub async fn get_usage(
+    State(state): State<AppState>,
+    claims: Claims,
+    Query(query): Query<UsageQuery>,
+) -> Result<Json<UsageResponse>, StatusCode> {
+    let to = query.to.unwrap_or_else(|| Utc::now().date_naive());
+    let from = query.from.unwrap_or(to - Duration::days(29));
+    if from > to || (to - from).num_days() >= MAX_RANGE_DAYS {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let organization_id = state
+        .usage
+        .organization_for_user(claims.sub)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to resolve organization: {:#}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let days = state
+        .usage
+        .daily_usage(organization_id, from, to)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to load usage: {:#}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let seconds: i64 = days.iter().map(|d| d.analysis_seconds).sum();
+    let totals = UsageTotals {
+        api_calls: days.iter().map(|d| d.api_calls).sum(),
+        analysis_minutes: (seconds + 59) / 60,
+        storage_bytes: days.iter().map(|d| d.storage_bytes).sum(),
+    };
+
+    Ok(Json(UsageResponse {
+        organization_id,
+        from,
+        to,
+        totals,
+        days,
+    }))
+}
+
+/// Download the billing CSV for a period (admin only)
+#[utoipa::path(
+    get,
+    path = "/api/v1/usage/billing/{period}",
+    tag = "usage",
+    params(
+        ("period" = String, Path, description = "Billing month as YYYY-MM", example = "2024-05"),
+    ),
+    responses(
+        (status = 200, description = "Billing CSV, one row per organization", content_type = "text/csv", body = String),
+        (status = 400, description = "Invalid period"),
+        (status = 500, description = "Internal error"),
+    )
+)]
+pub async fn get_billing_report(
+    State(state): State<AppState>,
+    Path(period): Path<String>,
+) -> Result<Response, StatusCode> {
+    let period: BillingPeriod = period.parse().map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let lines = state.usage.billing_lines(period).await.map_err(|e| {
+        tracing::error!("Failed to build billing report: {:#}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"billing-{}.csv\"", period),
+            ),
+        ],
+        billing_csv(period, &lines),
+    )
+        .into_response())
+}
+
+/// Regenerate the billing export file for a period (admin only)
+#[utoipa::path(
+    post,
+    path = "/api/v1/usage/billing/{period}/export",
+    tag = "usage",
+    params(
+        ("period" = String, Path, description = "Billing month as YYYY-MM", example = "2024-05"),
+    ),
+    responses(
+        (status = 200, description = "Export file written", body = BillingExportResponse),
+        (status = 400, description = "Invalid period"),
+        (status = 500, description = "Internal error"),
+    )
+)]
+pub async fn export_billing(
+    State(state): State<AppState>,
+    Path(period): Path<String>,
+) -> Result<Json<BillingExportResponse>, StatusCode> {
+    let period: BillingPeriod = period.parse().map_err(|_| StatusCode::BAD_REQUEST)?;
+    let dir = PathBuf::from(&state.config.usage.billing_export_dir);
+
+    let (path, organization_count) =
+        state.usage.export_billing(period, &dir).await.map_err(|e| {
+            tracing::error!("Billing export for {} failed: {:#}", period, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(BillingExportResponse {
+        period: period.to_string(),
+        file_path: path.to_string_lossy().to_string(),
+        organization_count,
+    }))
+}
+
+/// Quotas of the caller's organization and today's consumption
+#[utoipa::path(
+    get,
+    path = "/api/v1/usage/quota",
+    tag = "usage",
+    responses(
+        (status = 200, description = "Limits and what is left of them today", body = QuotaStatusResponse),
+        (status = 404, description = "Caller belongs to no organization"),
+        (status = 500, description = "Internal error"),
+    )
+)]
+pub async fn get_quota(
+    State(state): State<AppState>,
+    claims: Claims,
+) -> Result<Json<QuotaStatusResponse>, StatusCode> {
+    let internal = |e: anyhow::Error| {
+        tracing::error!("Failed to load quota: {:#}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    };
+
+    let organization_id = state
+        .usage
+        .organization_for_user(claims.sub)
+        .await
+        .map_err(internal)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let quota = state.quotas.quota(organization_id).await.map_err(internal)?;
+
+    let mut budgets = Vec::with_capacity(QuotaBudget::ALL.len());
+    for budget in QuotaBudget::ALL {
+        let limit = budget.limit(&quota);
+        let used = state.quotas.used_today(organization_id, budget).await.map_err(internal)?;
+        budgets.push(BudgetStatus {
+            budget,
+            limit,
+            used,
+            remaining: limit.map(|l| l.saturating_sub(used)),
+        });
+    }
+
+    Ok(Json(QuotaStatusResponse {
+        organization_id,
+        quota,
+        budgets,
+        reset_at: quota_reset_at(Utc::now()),
+    }))
+}
+
+/// Quotas of an organization (admin only)
+#[utoipa::path(
+    get,
+    path = "/api/v1/usage/organizations/{organization_id}/quota",
+    tag = "usage",
+    params(
+        ("organization_id" = Uuid, Path, description = "Organization id"),
+    ),
+    responses(
+        (status = 200, description = "Limits of the organization; `null` means unlimited", body = OrganizationQuota),
+        (status = 500, description = "Internal error"),
+    )
+)]
+pub async fn get_organization_quota(
+    State(state): State<AppState>,
+    Path(organization_id): Path<Uuid>,
+) -> Result<Json<OrganizationQuota>, StatusCode> {
+    let quota = state.quotas.quota(organization_id).await.map_err(|e| {
+        tracing::error!("Failed to load quota of {}: {:#}", organization_id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(quota))
+}
+
+/// Replace the quotas of an organization (admin only)
+#[utoipa::path(
+    put,
+    path = "/api/v1/usage/organizations/{organization_id}/quota",
+    tag = "usage",
+    params(
+        ("organization_id" = Uuid, Path, description = "Organization id"),
+    ),
+    request_body = OrganizationQuota,
+    responses(
+        (status = 200, description = "Stored limits", body = OrganizationQuota),
+        (status = 400, description = "Negative or zero limit"),
+        (status = 404, description = "Organization not found"),
+        (status = 500, description = "Internal error"),
+    )
+)]
+pub async fn put_organization_quota(
+    State(state): State<AppState>,
+    Path(organization_id): Path<Uuid>,
+    Json(quota): Json<OrganizationQuota>,
+) -> Result<Json<OrganizationQuota>, StatusCode> {
+    let valid = quota.requests_per_minute.is_none_or(|r| r > 0)
+        && quota.daily_submissions.is_none_or(|d| d >= 0)
+        && quota.daily_analyses.is_none_or(|d| d >= 0);
+    if !valid {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let stored = state
+        .quotas
+        .set_quota(organization_id, &quota)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to store quota of {}: {:#}", organization_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    tracing::info!("Quota of organization {} set to {:?}", organization_id, stored);
+    Ok(Json(stored))
+}
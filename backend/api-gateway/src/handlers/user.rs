@@ -6,13 +6,14 @@ use axum::{
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::models::user::User;
 use crate::AppState;
 
 /// User profile response
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct UserProfile {
     pub id: Uuid,
     pub username: String,
@@ -29,7 +30,7 @@ pub struct UserProfile {
 }
 
 /// Update user profile request
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateProfileRequest {
     pub username: Option<String>,
     pub email: Option<String>,
@@ -39,7 +40,7 @@ pub struct UpdateProfileRequest {
 }
 
 /// User statistics
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct UserStats {
     pub total_analyses: u64,
     pub total_bounties_created: u64,
@@ -53,6 +54,16 @@ pub struct UserStats {
 /// Get current user profile
 ///
 /// GET /api/v1/users/me
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me",
+    tag = "users",
+    responses(
+        (status = 200, description = "Profile of the caller", body = UserProfile),
+        (status = 404, description = "User not found"),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn get_current_user(
     State(state): State<AppState>,
     claims: crate::middleware::auth::Claims,
@@ -86,6 +97,19 @@ pub async fn get_current_user(
 /// Get user profile by ID
 ///
 /// GET /api/v1/users/:id
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/{user_id}",
+    tag = "users",
+    params(
+        ("user_id" = Uuid, Path, description = "User id"),
+    ),
+    responses(
+        (status = 200, description = "Public profile", body = UserProfile),
+        (status = 404, description = "User not found"),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn get_user_by_id(
     State(state): State<AppState>,
     Path(user_id): Path<Uuid>,
@@ -119,6 +143,17 @@ pub async fn get_user_by_id(
 /// Update current user profile
 ///
 /// PUT /api/v1/users/me
+#[utoipa::path(
+    put,
+    path = "/api/v1/users/me",
+    tag = "users",
+    request_body = UpdateProfileRequest,
+    responses(
+        (status = 200, description = "Profile of the caller; changes are not persisted yet", body = UserProfile),
+        (status = 404, description = "User not found"),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn update_profile(
     State(state): State<AppState>,
     claims: crate::middleware::auth::Claims,
@@ -142,6 +177,15 @@ pub async fn update_profile(
 /// Get user statistics
 ///
 /// GET /api/v1/users/me/stats
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me/stats",
+    tag = "users",
+    responses(
+        (status = 200, description = "Statistics of the caller", body = UserStats),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn get_user_stats(
     State(state): State<AppState>,
     claims: crate::middleware::auth::Claims,
@@ -221,6 +265,18 @@ pub async fn delete_account(State(state): State<AppState>) -> Result<StatusCode,
 }
 
 /// Get another user's stats by ID
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/{user_id}/stats",
+    tag = "users",
+    params(
+        ("user_id" = Uuid, Path, description = "User id"),
+    ),
+    responses(
+        (status = 200, description = "Statistics of the user", body = UserStats),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn get_user_stats_by_id(
     State(state): State<AppState>,
     Path(user_id): Path<Uuid>,
@@ -252,6 +308,12 @@ pub async fn get_user_stats_by_id(
 }
 
 /// List current user's API keys
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/me/api-keys",
+    tag = "users",
+    responses((status = 200, description = "API keys of the caller", body = Vec<serde_json::Value>))
+)]
 pub async fn list_api_keys(
     State(_state): State<AppState>,
 ) -> Result<Json<Vec<serde_json::Value>>, StatusCode> {
@@ -259,6 +321,15 @@ pub async fn list_api_keys(
 }
 
 /// Revoke an API key
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/me/api-keys/{key_id}",
+    tag = "users",
+    params(
+        ("key_id" = Uuid, Path, description = "API key id"),
+    ),
+    responses((status = 501, description = "Not implemented yet"))
+)]
 pub async fn revoke_api_key(
     State(_state): State<AppState>,
     Path(_key_id): Path<Uuid>,
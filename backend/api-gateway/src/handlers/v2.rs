@@ -0,0 +1,284 @@
+//! API v2 handlers
+//!
+//! Every response uses the `models::v2::ApiResponse` envelope, lists are
+//! cursor-paged newest first and errors are problem details.
+
+use axum::{
+    extract::{OriginalUri, Path, Query, State},
+    Extension, Json,
+};
+use serde::Deserialize;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::middleware::auth::Claims;
+use crate::models::v2::{
+    Analysis, ApiResponse, Bounty, Cursor, CursorParams, Problem, Submission, User,
+    ANALYSIS_COLUMNS, BOUNTY_COLUMNS, SUBMISSION_COLUMNS, USER_COLUMNS,
+};
+use crate::services::embargo::{self, Viewer};
+use crate::utils::AuthContext;
+use crate::AppState;
+
+type V2Result<T> = Result<Json<ApiResponse<T>>, Problem>;
+
+/// Keyset condition selecting the rows after `($n, $n+1)`, newest first.
+/// `created_at` is nullable in the schema; rows without one cannot be paged.
+fn after_cursor(first_param: usize) -> String {
+    format!(
+        "created_at IS NOT NULL AND (${a}::TIMESTAMPTZ IS NULL OR (created_at, id) < (${a}, ${b}))",
+        a = first_param,
+        b = first_param + 1
+    )
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BountyFilter {
+    /// `active`, `completed`, `expired` or `cancelled`
+    pub status: Option<String>,
+}
+
+/// List bounties
+///
+/// GET /api/v2/bounties
+#[utoipa::path(
+    get,
+    path = "/api/v2/bounties",
+    tag = "v2",
+    operation_id = "v2_list_bounties",
+    params(BountyFilter, CursorParams),
+    responses(
+        (status = 200, description = "Page of bounties, newest first", body = ApiResponse<Vec<Bounty>>),
+        (status = 400, description = "Invalid cursor", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn list_bounties(
+    State(state): State<AppState>,
+    Query(filter): Query<BountyFilter>,
+    Query(page): Query<CursorParams>,
+) -> V2Result<Vec<Bounty>> {
+    let cursor = page.position()?;
+    let sql = format!(
+        "SELECT {} FROM bounties
+         WHERE ($1::TEXT IS NULL OR COALESCE(status, bounty_status) = $1) AND {}
+         ORDER BY created_at DESC, id DESC
+         LIMIT $4",
+        BOUNTY_COLUMNS,
+        after_cursor(2)
+    );
+    let rows = sqlx::query_as::<_, Bounty>(&sql)
+        .bind(filter.status)
+        .bind(cursor.map(|c| c.created_at))
+        .bind(cursor.map(|c| c.id))
+        .bind(page.limit() + 1)
+        .fetch_all(state.db.pool())
+        .await?;
+
+    Ok(Json(ApiResponse::page(rows, page.limit(), |b| Cursor { created_at: b.created_at, id: b.id })))
+}
+
+/// Get a bounty
+///
+/// GET /api/v2/bounties/:bounty_id
+#[utoipa::path(
+    get,
+    path = "/api/v2/bounties/{bounty_id}",
+    tag = "v2",
+    operation_id = "v2_get_bounty",
+    params(("bounty_id" = Uuid, Path, description = "Bounty id")),
+    responses(
+        (status = 200, description = "The bounty", body = ApiResponse<Bounty>),
+        (status = 404, description = "Bounty not found", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_bounty(
+    State(state): State<AppState>,
+    OriginalUri(uri): OriginalUri,
+    Path(bounty_id): Path<Uuid>,
+) -> V2Result<Bounty> {
+    let sql = format!("SELECT {} FROM bounties WHERE id = $1", BOUNTY_COLUMNS);
+    let bounty = sqlx::query_as::<_, Bounty>(&sql)
+        .bind(bounty_id)
+        .fetch_optional(state.db.pool())
+        .await?
+        .ok_or_else(|| Problem::not_found("Bounty not found").with_instance(uri.path()))?;
+
+    Ok(Json(ApiResponse::new(bounty)))
+}
+
+/// List the analyses of a bounty
+///
+/// GET /api/v2/bounties/:bounty_id/analyses
+#[utoipa::path(
+    get,
+    path = "/api/v2/bounties/{bounty_id}/analyses",
+    tag = "v2",
+    operation_id = "v2_list_bounty_analyses",
+    params(("bounty_id" = Uuid, Path, description = "Bounty id"), CursorParams),
+    responses(
+        (status = 200, description = "Page of analyses, newest first; empty while the bounty's verdicts are embargoed from the caller", body = ApiResponse<Vec<Analysis>>),
+        (status = 400, description = "Invalid cursor", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn list_bounty_analyses(
+    State(state): State<AppState>,
+    Path(bounty_id): Path<Uuid>,
+    caller: Option<Extension<AuthContext>>,
+    Query(page): Query<CursorParams>,
+) -> V2Result<Vec<Analysis>> {
+    let cursor = page.position()?;
+    let viewer = Viewer::from_context(caller.as_deref());
+    let withheld = embargo::is_withheld(state.db.pool(), bounty_id, viewer).await.map_err(|e| {
+        tracing::error!("{:#}", e);
+        Problem::internal()
+    })?;
+    if withheld {
+        return Ok(Json(ApiResponse::page(Vec::new(), page.limit(), |a: &Analysis| Cursor {
+            created_at: a.created_at,
+            id: a.id,
+        })));
+    }
+
+    let sql = format!(
+        "SELECT {} FROM analysis_results
+         WHERE bounty_id = $1 AND {}
+         ORDER BY created_at DESC, id DESC
+         LIMIT $4",
+        ANALYSIS_COLUMNS,
+        after_cursor(2)
+    );
+    let rows = sqlx::query_as::<_, Analysis>(&sql)
+        .bind(bounty_id)
+        .bind(cursor.map(|c| c.created_at))
+        .bind(cursor.map(|c| c.id))
+        .bind(page.limit() + 1)
+        .fetch_all(state.db.pool())
+        .await?;
+
+    Ok(Json(ApiResponse::page(rows, page.limit(), |a| Cursor { created_at: a.created_at, id: a.id })))
+}
+
+/// List the caller's submissions
+///
+/// GET /api/v2/submissions
+#[utoipa::path(
+    get,
+    path = "/api/v2/submissions",
+    tag = "v2",
+    operation_id = "v2_list_submissions",
+    params(CursorParams),
+    responses(
+        (status = 200, description = "Page of the caller's submissions, newest first", body = ApiResponse<Vec<Submission>>),
+        (status = 400, description = "Invalid cursor", body = Problem, content_type = "application/problem+json"),
+        (status = 401, description = "Missing or invalid token", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn list_submissions(
+    State(state): State<AppState>,
+    claims: Claims,
+    Query(page): Query<CursorParams>,
+) -> V2Result<Vec<Submission>> {
+    let cursor = page.position()?;
+    let sql = format!(
+        "SELECT {} FROM submissions
+         WHERE submitter_id = $1 AND {}
+         ORDER BY created_at DESC, id DESC
+         LIMIT $4",
+        SUBMISSION_COLUMNS,
+        after_cursor(2)
+    );
+    let rows = sqlx::query_as::<_, Submission>(&sql)
+        .bind(claims.sub)
+        .bind(cursor.map(|c| c.created_at))
+        .bind(cursor.map(|c| c.id))
+        .bind(page.limit() + 1)
+        .fetch_all(state.db.pool())
+        .await?;
+
+    Ok(Json(ApiResponse::page(rows, page.limit(), |s| Cursor { created_at: s.created_at, id: s.id })))
+}
+
+/// Get a submission
+///
+/// GET /api/v2/submissions/:submission_id
+#[utoipa::path(
+    get,
+    path = "/api/v2/submissions/{submission_id}",
+    tag = "v2",
+    operation_id = "v2_get_submission",
+    params(("submission_id" = Uuid, Path, description = "Submission id")),
+    responses(
+        (status = 200, description = "The submission", body = ApiResponse<Submission>),
+        (status = 404, description = "Submission not found", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_submission(
+    State(state): State<AppState>,
+    OriginalUri(uri): OriginalUri,
+    Path(submission_id): Path<Uuid>,
+) -> V2Result<Submission> {
+    let sql = format!("SELECT {} FROM submissions WHERE id = $1", SUBMISSION_COLUMNS);
+    let submission = sqlx::query_as::<_, Submission>(&sql)
+        .bind(submission_id)
+        .fetch_optional(state.db.pool())
+        .await?
+        .ok_or_else(|| Problem::not_found("Submission not found").with_instance(uri.path()))?;
+
+    Ok(Json(ApiResponse::new(submission)))
+}
+
+/// Get the caller's profile
+///
+/// GET /api/v2/users/me
+#[utoipa::path(
+    get,
+    path = "/api/v2/users/me",
+    tag = "v2",
+    operation_id = "v2_get_current_user",
+    responses(
+        (status = 200, description = "Profile of the caller", body = ApiResponse<User>),
+        (status = 401, description = "Missing or invalid token", body = Problem, content_type = "application/problem+json"),
+        (status = 404, description = "User not found", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_current_user(
+    State(state): State<AppState>,
+    OriginalUri(uri): OriginalUri,
+    claims: Claims,
+) -> V2Result<User> {
+    fetch_user(&state, claims.sub, uri.path()).await
+}
+
+/// Get a user's public profile
+///
+/// GET /api/v2/users/:user_id
+#[utoipa::path(
+    get,
+    path = "/api/v2/users/{user_id}",
+    tag = "v2",
+    operation_id = "v2_get_user",
+    params(("user_id" = Uuid, Path, description = "User id")),
+    responses(
+        (status = 200, description = "The user", body = ApiResponse<User>),
+        (status = 404, description = "User not found", body = Problem, content_type = "application/problem+json"),
+    )
+)]
+pub async fn get_user(
+    State(state): State<AppState>,
+    OriginalUri(uri): OriginalUri,
+    Path(user_id): Path<Uuid>,
+) -> V2Result<User> {
+    fetch_user(&state, user_id, uri.path()).await
+}
+
+async fn fetch_user(state: &AppState, user_id: Uuid, instance: &str) -> V2Result<User> {
+    let sql = format!("SELECT {} FROM users WHERE id = $1", USER_COLUMNS);
+    let user = sqlx::query_as::<_, User>(&sql)
+        .bind(user_id)
+        .fetch_optional(state.db.pool())
+        .await?
+        .ok_or_else(|| Problem::not_found("User not found").with_instance(instance))?;
+
+    Ok(Json(ApiResponse::new(user)))
+}
@@ -6,12 +6,13 @@ use axum::{
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::AppState;
 
 /// Wallet balance response
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct WalletBalance {
     pub address: String,
     pub balance: String,
@@ -23,7 +24,8 @@ pub struct WalletBalance {
 }
 
 /// Transaction history query
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct TransactionQuery {
     pub page: Option<u32>,
     pub limit: Option<u32>,
@@ -33,7 +35,7 @@ pub struct TransactionQuery {
 }
 
 /// Transaction history response
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct TransactionListResponse {
     pub transactions: Vec<Transaction>,
     pub total: u64,
@@ -42,7 +44,7 @@ pub struct TransactionListResponse {
 }
 
 /// Individual transaction
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct Transaction {
     pub id: Uuid,
     pub transaction_hash: Option<String>,
@@ -63,14 +65,14 @@ pub struct WithdrawRequest {
 }
 
 /// Stake request
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct StakeRequest {
     pub amount: String,
     pub bounty_id: Uuid,
 }
 
 /// Connect wallet request
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct ConnectWalletRequest {
     pub address: String,
     pub signature: String,
@@ -80,6 +82,12 @@ pub struct ConnectWalletRequest {
 /// Get wallet balance
 ///
 /// GET /api/v1/wallet/balance
+#[utoipa::path(
+    get,
+    path = "/api/v1/wallet/balance",
+    tag = "wallet",
+    responses((status = 200, description = "Token balances of the caller", body = WalletBalance))
+)]
 pub async fn get_balance(
     State(state): State<AppState>,
 ) -> Result<Json<WalletBalance>, StatusCode> {
@@ -100,6 +108,15 @@ pub async fn get_balance(
 /// Get wallet balance by address
 ///
 /// GET /api/v1/wallet/balance/:address
+#[utoipa::path(
+    get,
+    path = "/api/v1/wallet/balance/{address}",
+    tag = "wallet",
+    params(
+        ("address" = String, Path, description = "Ethereum address"),
+    ),
+    responses((status = 200, description = "Token balances of the address", body = WalletBalance))
+)]
 pub async fn get_balance_by_address(
     State(state): State<AppState>,
     Path(address): Path<String>,
@@ -127,6 +144,15 @@ pub async fn get_balance_by_address(
 /// Get transaction history
 ///
 /// GET /api/v1/wallet/transactions
+#[utoipa::path(
+    get,
+    path = "/api/v1/wallet/transactions",
+    tag = "wallet",
+    params(
+        TransactionQuery,
+    ),
+    responses((status = 200, description = "Page of token transactions", body = TransactionListResponse))
+)]
 pub async fn get_transactions(
     State(_state): State<AppState>,
     Query(params): Query<TransactionQuery>,
@@ -146,6 +172,18 @@ pub async fn get_transactions(
 /// Connect wallet — verify signature and link address to user
 ///
 /// POST /api/v1/wallet/connect
+#[utoipa::path(
+    post,
+    path = "/api/v1/wallet/connect",
+    tag = "wallet",
+    request_body = ConnectWalletRequest,
+    responses(
+        (status = 200, description = "Wallet linked to the caller", body = serde_json::Value),
+        (status = 400, description = "Malformed signature or address"),
+        (status = 401, description = "Signature does not match the address"),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn connect_wallet(
     State(state): State<AppState>,
     claims: crate::middleware::auth::Claims,
@@ -190,6 +228,16 @@ pub async fn connect_wallet(
 /// Disconnect wallet — remove address from user record
 ///
 /// POST /api/v1/wallet/disconnect
+#[utoipa::path(
+    post,
+    path = "/api/v1/wallet/disconnect",
+    operation_id = "disconnect_user_wallet",
+    tag = "wallet",
+    responses(
+        (status = 200, description = "Wallet unlinked"),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn disconnect_wallet(
     State(state): State<AppState>,
     claims: crate::middleware::auth::Claims,
@@ -226,6 +274,13 @@ pub async fn withdraw(
 /// from the frontend before calling submitAnalysis. This endpoint records the intent.
 ///
 /// POST /api/v1/wallet/stake
+#[utoipa::path(
+    post,
+    path = "/api/v1/wallet/stake",
+    tag = "wallet",
+    request_body = StakeRequest,
+    responses((status = 200, description = "How to stake on a bounty", body = serde_json::Value))
+)]
 pub async fn stake_tokens(
     State(_state): State<AppState>,
     Json(payload): Json<StakeRequest>,
@@ -247,6 +302,15 @@ pub async fn stake_tokens(
 /// NOTE: Stakes are returned automatically during bounty resolution via resolveBounty
 ///
 /// POST /api/v1/wallet/unstake/:bounty_id
+#[utoipa::path(
+    post,
+    path = "/api/v1/wallet/unstake/{bounty_id}",
+    tag = "wallet",
+    params(
+        ("bounty_id" = Uuid, Path, description = "Bounty id"),
+    ),
+    responses((status = 200, description = "Stakes are returned when the bounty resolves", body = serde_json::Value))
+)]
 pub async fn unstake_tokens(
     State(_state): State<AppState>,
     Path(bounty_id): Path<Uuid>,
@@ -262,6 +326,12 @@ pub async fn unstake_tokens(
 /// NOTE: Rewards are distributed during resolveBounty
 ///
 /// POST /api/v1/wallet/claim-rewards
+#[utoipa::path(
+    post,
+    path = "/api/v1/wallet/claim-rewards",
+    tag = "wallet",
+    responses((status = 200, description = "Rewards are paid out when bounties resolve", body = serde_json::Value))
+)]
 pub async fn claim_rewards(
     State(_state): State<AppState>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
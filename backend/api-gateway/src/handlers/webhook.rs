@@ -5,12 +5,13 @@ use axum::{
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::AppState;
 
 /// Webhook configuration
-#[derive(Debug, Serialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
 pub struct Webhook {
     pub id: Uuid,
     pub user_id: Uuid,
@@ -29,7 +30,7 @@ pub struct Webhook {
 }
 
 /// Register webhook request
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct RegisterWebhookRequest {
     pub url: String,
     pub events: Vec<String>,
@@ -38,7 +39,7 @@ pub struct RegisterWebhookRequest {
 }
 
 /// Update webhook request
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateWebhookRequest {
     pub url: Option<String>,
     pub events: Option<Vec<String>>,
@@ -48,7 +49,7 @@ pub struct UpdateWebhookRequest {
 }
 
 /// Webhook delivery log
-#[derive(Debug, Serialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
 pub struct WebhookDelivery {
     pub id: Uuid,
     pub webhook_id: Uuid,
@@ -64,14 +65,15 @@ pub struct WebhookDelivery {
 }
 
 /// Webhook list query
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct WebhookQuery {
     pub page: Option<u32>,
     pub limit: Option<u32>,
 }
 
 /// Webhook list response
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct WebhookListResponse {
     pub webhooks: Vec<Webhook>,
     pub total: i64,
@@ -80,7 +82,7 @@ pub struct WebhookListResponse {
 }
 
 /// Delivery list response
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct DeliveryListResponse {
     pub deliveries: Vec<WebhookDelivery>,
     pub total: i64,
@@ -89,21 +91,22 @@ pub struct DeliveryListResponse {
 }
 
 /// Delivery query
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct DeliveryQuery {
     pub page: Option<u32>,
     pub limit: Option<u32>,
 }
 
 /// Test webhook request
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct TestWebhookRequest {
     pub event_type: String,
     pub sample_payload: Option<serde_json::Value>,
 }
 
 /// Available webhook events
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct WebhookEvent {
     pub name: String,
     pub description: String,
@@ -113,6 +116,16 @@ pub struct WebhookEvent {
 // ─── Handlers ───
 
 /// Create a webhook
+#[utoipa::path(
+    post,
+    path = "/api/v1/webhooks",
+    tag = "webhooks",
+    request_body = RegisterWebhookRequest,
+    responses(
+        (status = 200, description = "Webhook registered, including its signing secret", body = Webhook),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn create_webhook(
     State(state): State<AppState>,
     Json(payload): Json<RegisterWebhookRequest>,
@@ -146,6 +159,18 @@ pub async fn create_webhook(
 }
 
 /// List user's webhooks
+#[utoipa::path(
+    get,
+    path = "/api/v1/webhooks",
+    tag = "webhooks",
+    params(
+        WebhookQuery,
+    ),
+    responses(
+        (status = 200, description = "Page of webhooks", body = WebhookListResponse),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn list_webhooks(
     State(state): State<AppState>,
     Query(params): Query<WebhookQuery>,
@@ -180,6 +205,19 @@ pub async fn list_webhooks(
 }
 
 /// Get webhook by ID
+#[utoipa::path(
+    get,
+    path = "/api/v1/webhooks/{webhook_id}",
+    tag = "webhooks",
+    params(
+        ("webhook_id" = Uuid, Path, description = "Webhook id"),
+    ),
+    responses(
+        (status = 200, description = "Webhook", body = Webhook),
+        (status = 404, description = "Webhook not found"),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn get_webhook(
     State(state): State<AppState>,
     Path(webhook_id): Path<Uuid>,
@@ -200,6 +238,20 @@ pub async fn get_webhook(
 }
 
 /// Update webhook
+#[utoipa::path(
+    put,
+    path = "/api/v1/webhooks/{webhook_id}",
+    tag = "webhooks",
+    params(
+        ("webhook_id" = Uuid, Path, description = "Webhook id"),
+    ),
+    request_body = UpdateWebhookRequest,
+    responses(
+        (status = 200, description = "Updated webhook", body = Webhook),
+        (status = 404, description = "Webhook not found"),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn update_webhook(
     State(state): State<AppState>,
     Path(webhook_id): Path<Uuid>,
@@ -235,6 +287,19 @@ pub async fn update_webhook(
 }
 
 /// Delete webhook
+#[utoipa::path(
+    delete,
+    path = "/api/v1/webhooks/{webhook_id}",
+    tag = "webhooks",
+    params(
+        ("webhook_id" = Uuid, Path, description = "Webhook id"),
+    ),
+    responses(
+        (status = 204, description = "Webhook deleted"),
+        (status = 404, description = "Webhook not found"),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn delete_webhook(
     State(state): State<AppState>,
     Path(webhook_id): Path<Uuid>,
@@ -256,6 +321,20 @@ pub async fn delete_webhook(
 }
 
 /// Test webhook by sending a sample event
+#[utoipa::path(
+    post,
+    path = "/api/v1/webhooks/{webhook_id}/test",
+    tag = "webhooks",
+    params(
+        ("webhook_id" = Uuid, Path, description = "Webhook id"),
+    ),
+    request_body = TestWebhookRequest,
+    responses(
+        (status = 200, description = "Result of the test delivery", body = WebhookDelivery),
+        (status = 404, description = "Webhook not found"),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn test_webhook(
     State(state): State<AppState>,
     Path(webhook_id): Path<Uuid>,
@@ -328,6 +407,19 @@ pub async fn test_webhook(
 }
 
 /// Get webhook deliveries
+#[utoipa::path(
+    get,
+    path = "/api/v1/webhooks/{webhook_id}/deliveries",
+    tag = "webhooks",
+    params(
+        ("webhook_id" = Uuid, Path, description = "Webhook id"),
+        DeliveryQuery,
+    ),
+    responses(
+        (status = 200, description = "Page of deliveries, newest first", body = DeliveryListResponse),
+        (status = 500, description = "Internal error"),
+    )
+)]
 pub async fn get_webhook_deliveries(
     State(state): State<AppState>,
     Path(webhook_id): Path<Uuid>,
@@ -368,6 +460,12 @@ pub async fn get_webhook_deliveries(
 }
 
 /// List available webhook events
+#[utoipa::path(
+    get,
+    path = "/api/v1/webhooks/events",
+    tag = "webhooks",
+    responses((status = 200, description = "Events a webhook can subscribe to", body = Vec<WebhookEvent>))
+)]
 pub async fn list_available_events(
     State(_state): State<AppState>,
 ) -> Result<Json<Vec<WebhookEvent>>, StatusCode> {
@@ -9,26 +9,26 @@ use axum::{
 };
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
     net::SocketAddr,
     sync::Arc,
     time::{SystemTime, UNIX_EPOCH},
 };
-use tokio::{net::TcpListener, sync::RwLock};
+use tokio::net::TcpListener;
 use tower::ServiceBuilder;
 use tower_http::{
-    cors::{Any, CorsLayer},
     limit::RequestBodyLimitLayer,
     trace::TraceLayer,
 };
 use tracing::{debug, error, info, warn};
-use uuid::Uuid;
 
 mod config;
+mod graphql;
 mod handlers;
 mod middleware;
+use middleware::auth::JwtService;
 use middleware::metrics::{metrics_middleware, MetricsCollector};
 mod models;
+mod openapi;
 mod routes;
 mod services;
 mod utils;
@@ -36,10 +36,18 @@ mod utils;
 use config::AppConfig;
 use handlers::{auth, health, reputation, user};
 use models::{analysis::AnalysisResult, bounty::Bounty, user::User};
-use services::{blockchain::BlockchainService, database::DatabaseService, redis::RedisService};
+use services::{
+    blockchain::BlockchainService, database::DatabaseService,
+    jwt_keys::{self, JwtKeyStore},
+    proxy_service::{ProxyConfig, ProxyService, ServiceRegistry},
+    redis::RedisService,
+    usage::{self as usage_service, UsageMeter},
+    FeatureFlagService, KnownDeviceStore, QuotaService, RbacService, RealtimeHub, SessionStore,
+};
 use utils::{crypto::JwtClaims, validation::ValidationError};
 
 use crate::models::response::ApiResponse;
+use shared::login_guard::{LoginGuard, LoginGuardConfig, SiteVerifyCaptcha};
 
 use crate::utils::helpers::current_timestamp;
 
@@ -50,67 +58,23 @@ pub struct AppState {
     pub redis: Arc<RedisService>,
     pub blockchain: Arc<BlockchainService>,
     pub config: Arc<AppConfig>,
-    pub active_sessions: Arc<RwLock<HashMap<String, SessionInfo>>>,
+    pub sessions: Arc<SessionStore>,
+    pub known_devices: Arc<KnownDeviceStore>,
+    pub login_guard: Arc<LoginGuard>,
+    pub jwt: Arc<JwtService>,
     pub metrics: Arc<MetricsCollector>,
-}
-
-// Session information for active users
-#[derive(Debug, Clone)]
-pub struct SessionInfo {
-    pub user_id: Uuid,
-    pub wallet_address: Option<String>,
-    pub reputation_score: i32,
-    pub last_activity: u64,
-    pub permissions: Vec<String>,
+    pub usage: Arc<UsageMeter>,
+    pub quotas: Arc<QuotaService>,
+    pub flags: Arc<FeatureFlagService>,
+    pub proxy: Arc<ProxyService>,
+    pub realtime: Arc<RealtimeHub>,
+    pub rbac: Arc<RbacService>,
+    pub graphql: graphql::DashboardSchema,
+    pub started_at: std::time::Instant,
 }
 
 // ApiResponse moved to models::response
 
-// Middleware for authentication
-async fn auth_middleware(
-    State(state): State<AppState>,
-    mut request: axum::extract::Request,
-    next: Next,
-) -> Result<Response, StatusCode> {
-    // Extract authorization header
-    let auth_header = request
-        .headers()
-        .get(header::AUTHORIZATION)
-        .and_then(|header| header.to_str().ok());
-
-    if let Some(auth_header) = auth_header {
-        if let Some(token) = auth_header.strip_prefix("Bearer ") {
-            // Validate JWT token
-            match utils::crypto::validate_jwt(token, &state.config.security.jwt_secret) {
-                Ok(claims) => {
-                    // Check if session is still active
-                    let sessions = state.active_sessions.read().await;
-                    if let Some(session_info) = sessions.get(&claims.sub) {
-                        // Add user info to request extensions
-                        request.extensions_mut().insert(claims);
-                        request.extensions_mut().insert(session_info.clone());
-                        return Ok(next.run(request).await);
-                    }
-                }
-                Err(e) => {
-                    warn!("JWT validation failed: {}", e);
-                }
-            }
-        }
-    }
-
-    // For public endpoints, continue without authentication
-    let path = request.uri().path();
-    if path.starts_with("/api/v1/health")
-        || path.starts_with("/api/v1/auth/login")
-        || path.starts_with("api/v1/auth/register")
-    {
-        return Ok(next.run(request).await);
-    }
-
-    Err(StatusCode::UNAUTHORIZED)
-}
-
 // Middleware for request logging
 async fn logging_middleware(request: axum::extract::Request, next: Next) -> Response {
     let start_time = SystemTime::now();
@@ -194,12 +158,8 @@ async fn shutdown_signal() {
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize tracing for logging
-    tracing_subscriber::fmt()
-        .with_max_level(tracing::Level::INFO)
-        .with_target(false)
-        .compact()
-        .init();
+    // Structured logs, plus span export when OTEL_EXPORTER_OTLP_ENDPOINT is set
+    shared::observability::init_service_logging(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))?;
 
     info!(
         "Starting Nexus-Security API Gateway v{}",
@@ -216,33 +176,120 @@ async fn main() -> Result<()> {
     // Initialize services
     let (db, redis, blockchain) = initialize_services(&config).await?;
 
+    // Initialize usage metering and the periodic flush/billing export worker
+    let usage = Arc::new(UsageMeter::new(
+        db.pool().clone(),
+        redis.connection_pool.clone(),
+    ));
+    if config.usage.enabled {
+        usage_service::spawn_usage_worker(usage.clone(), config.usage.clone());
+    }
+
+    // Organization quotas, pooled across members and API keys
+    let quotas = Arc::new(QuotaService::new(
+        db.pool().clone(),
+        redis.connection_pool.clone(),
+        config.quotas.cache_seconds,
+    ));
+
+    // Maintenance mode and route group switches, flipped at runtime
+    let flags = Arc::new(FeatureFlagService::new(
+        db.pool().clone(),
+        redis.connection_pool.clone(),
+    ));
+
+    // Reverse proxy with a connection pool per backend service
+    let proxy = Arc::new(
+        ProxyService::with_registry(
+            ProxyConfig::from_env(),
+            ServiceRegistry::from_config(&config.services),
+        )
+        .context("Failed to initialize proxy service")?,
+    );
+
+    // Redis events pushed to WebSocket clients
+    let realtime = Arc::new(RealtimeHub::new());
+    realtime.clone().spawn_listener(config.redis.url.clone(), db.pool().clone());
+
+    // Role to permission mapping resolved on every authenticated request
+    let rbac = Arc::new(RbacService::new(db.pool().clone()));
+
+    // Login sessions live in Redis so they survive restarts and are shared
+    // by every gateway instance
+    let sessions = Arc::new(SessionStore::new(
+        redis.connection_pool.clone(),
+        std::time::Duration::from_secs(config.security.session_timeout_minutes * 60),
+    ));
+    // Devices and countries users signed in from, kept after sessions end
+    let known_devices = Arc::new(KnownDeviceStore::new(db.pool().clone()));
+
+    // Failed password counters and lockouts, shared by every instance
+    let mut login_guard = LoginGuard::new(redis.connection_pool.clone(), LoginGuardConfig::from_env());
+    if let Some(captcha) = SiteVerifyCaptcha::from_env() {
+        login_guard = login_guard.with_captcha(Arc::new(captcha));
+    }
+    let login_guard = Arc::new(login_guard);
+
+    // Token signing keys are shared through Postgres and rotated by
+    // whichever instance notices first
+    let key_store = JwtKeyStore::new(db.pool().clone(), &config.security);
+    let signing_keys = key_store
+        .load()
+        .await
+        .context("Failed to load JWT signing keys")?;
+    let mut jwt = JwtService::new(&signing_keys);
+    if config.security.accept_legacy_hs256_tokens {
+        jwt = jwt.with_legacy_secret(&config.security.jwt_secret);
+    }
+    let jwt = Arc::new(jwt);
+    jwt_keys::spawn_key_refresh(key_store, jwt.clone());
+
+    let graphql = graphql::build_schema(db.pool().clone());
+
     // Create application state
     let state = AppState {
         db: Arc::new(db),
         redis: Arc::new(redis),
         blockchain: Arc::new(blockchain),
         config: Arc::new(config.clone()),
-        active_sessions: Arc::new(RwLock::new(HashMap::new())),
+        sessions,
+        known_devices,
+        login_guard,
+        jwt,
         metrics: metrics_collector.clone(),
+        usage,
+        quotas,
+        flags,
+        proxy,
+        realtime,
+        rbac,
+        graphql,
+        started_at: std::time::Instant::now(),
     };
 
-    // Create router with all routes and middleware
-    let cors = CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods([
-            axum::http::Method::GET,
-            axum::http::Method::POST,
-            axum::http::Method::PUT,
-            axum::http::Method::DELETE,
-            axum::http::Method::PATCH,
-            axum::http::Method::OPTIONS,
-        ])
-        .allow_headers(Any);
+    // Create router with all routes and middleware; browser origins come
+    // from `security.cors` (CORS_ALLOWED_ORIGINS)
+    let cors = shared::http_security::CorsPolicy::new(config.security.cors.allowed_origins.clone())
+        .with_credentials(config.security.cors.allow_credentials)
+        .with_max_age(std::time::Duration::from_secs(config.security.cors.max_age_seconds))
+        .layer();
 
     let app = routes::create_router(state)
         .layer(TraceLayer::new_for_http())
+        // Request ID and trace context for every log line, response and
+        // upstream call
+        .layer(axum_middleware::from_fn(shared::observability::log_context_middleware))
         .layer(cors)
-        .layer(DefaultBodyLimit::max(10 * 1024 * 1024)); // 10MB
+        // HSTS, nosniff, frame-ancestors, and a CSP on HTML such as the API docs
+        .layer(axum_middleware::from_fn(shared::http_security::security_headers_middleware))
+        // Secure/SameSite on every cookie we send, proxied ones included
+        .layer(axum_middleware::from_fn_with_state(
+            config.security.cookies.clone(),
+            middleware::csrf::cookie_attributes_middleware,
+        ))
+        // Extractor-read bodies; proxied routes set their own cap and file
+        // uploads stream past this one (`handlers::proxy`)
+        .layer(DefaultBodyLimit::max(config.max_json_body_bytes()));
 
     // Create server address
     let addr = SocketAddr::from(([0, 0, 0, 0], config.server.port));
@@ -260,11 +307,12 @@ async fn main() -> Result<()> {
     info!("🔍 Health check available at http://{}/api/v1/health", addr);
 
     // Start server with graceful shutdown
-    axum::serve(listener, app)
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
         .with_graceful_shutdown(shutdown_signal())
         .await
         .context("Server error")?;
 
+    shared::observability::shutdown_tracing();
     info!("Nexus-Security API Gateway shut down gracefully");
     Ok(())
 }
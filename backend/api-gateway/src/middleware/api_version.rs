@@ -0,0 +1,199 @@
+//! API versioning middleware
+//!
+//! v1 routes that have a v2 successor announce their deprecation with the
+//! `Deprecation` header (RFC 9745), the `Sunset` header (RFC 8594) once a
+//! date is set, and a `Link` to the successor. v1 routes without a
+//! successor are left alone until v2 covers them.
+//!
+//! v2 errors are problem details (RFC 7807). Handlers return them directly;
+//! `problem_details_middleware` converts the errors produced by the shared
+//! layers (auth, rate limiting, metering) so every v2 error has one shape.
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::State,
+    http::{header, HeaderValue, Method, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::middleware::route_policy::{api_relative, pattern_matches};
+use crate::models::v2::{Problem, PROBLEM_CONTENT_TYPE};
+use crate::AppState;
+
+/// Largest error body read back when converting it to a problem
+const MAX_ERROR_BODY: usize = 64 * 1024;
+
+/// v1 routes and the v2 route replacing them, `None` when v2 has no
+/// equivalent yet. Parameters of the v1 pattern are substituted into the
+/// successor. More specific patterns come first.
+const V1_SUCCESSORS: &[(Method, &str, Option<&str>)] = &[
+    (Method::GET, "/bounties/active", Some("/bounties?status=active")),
+    (Method::GET, "/bounties/completed", Some("/bounties?status=completed")),
+    (Method::GET, "/bounties/archived", None),
+    (Method::GET, "/bounties", Some("/bounties")),
+    (Method::GET, "/bounties/:bounty_id", Some("/bounties/:bounty_id")),
+    (Method::GET, "/analysis/by-bounty/:bounty_id", Some("/bounties/:bounty_id/analyses")),
+    (Method::GET, "/submissions/my-submissions", Some("/submissions")),
+    (Method::GET, "/submissions/:submission_id", Some("/submissions/:submission_id")),
+    (Method::GET, "/users/me", Some("/users/me")),
+    (Method::GET, "/users/:user_id", Some("/users/:user_id")),
+];
+
+/// Path of the v2 route replacing a v1 request, if there is one
+pub fn v1_successor(method: &Method, path: &str) -> Option<String> {
+    let path = api_relative(path);
+    let (_, pattern, successor) = V1_SUCCESSORS
+        .iter()
+        .find(|(m, pattern, _)| m == method && pattern_matches(pattern, path))?;
+
+    let params: Vec<(&str, &str)> = pattern
+        .split('/')
+        .zip(path.split('/'))
+        .filter(|(p, _)| p.starts_with(':'))
+        .collect();
+
+    let mut successor = successor.map(str::to_string)?;
+    for (name, value) in params {
+        successor = successor.replace(name, value);
+    }
+    Some(format!("/api/v2{}", successor))
+}
+
+/// HTTP date (IMF-fixdate), as `Sunset` requires
+fn http_date(at: chrono::DateTime<chrono::Utc>) -> String {
+    at.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// Deprecation headers on v1 responses whose route has a v2 successor
+pub async fn deprecation_middleware(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let successor = v1_successor(request.method(), request.uri().path());
+    let mut response = next.run(request).await;
+    let Some(successor) = successor else {
+        return response;
+    };
+
+    let versions = &state.config.api_versions;
+    let headers = response.headers_mut();
+    let deprecation = format!("@{}", versions.v1_deprecated_at.timestamp());
+    if let Ok(value) = HeaderValue::from_str(&deprecation) {
+        headers.insert("deprecation", value);
+    }
+    if let Some(sunset) = versions.v1_sunset_at {
+        if let Ok(value) = HeaderValue::from_str(&http_date(sunset)) {
+            headers.insert("sunset", value);
+        }
+    }
+    if let Ok(value) = HeaderValue::from_str(&format!("<{}>; rel=\"successor-version\"", successor)) {
+        headers.append(header::LINK, value);
+    }
+    response
+}
+
+/// Convert error responses that are not problem details yet
+pub async fn problem_details_middleware(request: Request<Body>, next: Next) -> Response {
+    let instance = request.uri().path().to_string();
+    let response = next.run(request).await;
+
+    let status = response.status();
+    let is_problem = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|v| v.as_bytes().starts_with(PROBLEM_CONTENT_TYPE.as_bytes()));
+    if !(status.is_client_error() || status.is_server_error()) || is_problem {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let body = to_bytes(body, MAX_ERROR_BODY).await.unwrap_or_default();
+    let mut problem = to_problem(status, &body).with_instance(instance).into_response();
+
+    // Keep the headers clients act on, such as Retry-After
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.remove(header::CONTENT_TYPE);
+    for (name, value) in parts.headers.iter() {
+        problem.headers_mut().append(name, value.clone());
+    }
+    problem
+}
+
+/// Problem for an error response, its detail taken from the usual JSON
+/// error fields or the plain-text body
+fn to_problem(status: StatusCode, body: &[u8]) -> Problem {
+    let detail = match serde_json::from_slice::<serde_json::Value>(body) {
+        Ok(json) => ["error", "details", "message"]
+            .iter()
+            .find_map(|field| json.get(field)?.as_str().map(str::to_string)),
+        Err(_) => std::str::from_utf8(body)
+            .ok()
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+            .map(str::to_string),
+    };
+    // Server errors never leak their cause
+    let detail = if status.is_server_error() { None } else { detail };
+    Problem::new(status, detail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_v1_successor_substitutes_parameters() {
+        let id = "6f1c2c0e-5a35-4a1c-9a41-8d1f4b7f2a10";
+        assert_eq!(
+            v1_successor(&Method::GET, &format!("/api/v1/analysis/by-bounty/{}", id)),
+            Some(format!("/api/v2/bounties/{}/analyses", id))
+        );
+        assert_eq!(
+            v1_successor(&Method::GET, &format!("/bounties/{}", id)),
+            Some(format!("/api/v2/bounties/{}", id))
+        );
+    }
+
+    #[test]
+    fn test_v1_successor_prefers_specific_routes() {
+        assert_eq!(
+            v1_successor(&Method::GET, "/api/bounties/active"),
+            Some("/api/v2/bounties?status=active".to_string())
+        );
+        assert_eq!(
+            v1_successor(&Method::GET, "/api/v1/users/me"),
+            Some("/api/v2/users/me".to_string())
+        );
+        assert_eq!(v1_successor(&Method::GET, "/api/v1/bounties/archived"), None);
+    }
+
+    #[test]
+    fn test_routes_without_successor_are_not_deprecated() {
+        assert_eq!(v1_successor(&Method::POST, "/api/v1/bounties"), None);
+        assert_eq!(v1_successor(&Method::GET, "/api/v1/wallet/balance"), None);
+        assert_eq!(v1_successor(&Method::GET, "/api/v1/bounties/x/stats"), None);
+    }
+
+    #[test]
+    fn test_http_date_is_imf_fixdate() {
+        use chrono::TimeZone;
+        let at = chrono::Utc.with_ymd_and_hms(2027, 4, 1, 0, 0, 0).unwrap();
+        assert_eq!(http_date(at), "Thu, 01 Apr 2027 00:00:00 GMT");
+    }
+
+    #[test]
+    fn test_error_bodies_become_problems() {
+        let problem = to_problem(StatusCode::TOO_MANY_REQUESTS, br#"{"error":"Rate limit exceeded"}"#);
+        assert_eq!(problem.status, 429);
+        assert_eq!(problem.title, "Too Many Requests");
+        assert_eq!(problem.detail.as_deref(), Some("Rate limit exceeded"));
+
+        let problem = to_problem(StatusCode::UNAUTHORIZED, b"Missing token\n");
+        assert_eq!(problem.detail.as_deref(), Some("Missing token"));
+
+        let problem = to_problem(StatusCode::BAD_GATEWAY, br#"{"error":"upstream at 10.0.0.3 down"}"#);
+        assert_eq!(problem.detail, None);
+    }
+}
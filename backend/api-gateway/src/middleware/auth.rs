@@ -8,12 +8,22 @@ use axum::{
     Json,
 };
 use chrono::{DateTime, Duration, Utc};
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use futures_util::future::BoxFuture;
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{decode, decode_header, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use tracing::{info, warn};
 use uuid::Uuid;
 
+use crate::middleware::csrf::{cookie_value, CookieSession, ACCESS_COOKIE};
+use crate::middleware::route_policy::{policy_for, RoutePolicy, SCOPE_ADMIN_CONSOLE};
 use crate::models::error::ApiError;
+use crate::services::jwt_keys::KeySet;
+use crate::services::rbac::default_permissions;
+use crate::services::session::SessionStore;
+use crate::utils::AuthContext;
 use crate::AppState;
 
 /// JWT Claims structure
@@ -26,6 +36,12 @@ pub struct Claims {
     pub iat: i64,      // Issued at
     pub nbf: i64,      // Not before
     pub jti: String,   // JWT ID (unique token identifier)
+    /// Login session the token belongs to (`services::session`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<Uuid>,
+    /// Permissions held when the token was issued (`services::rbac`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<String>,
 }
 
 impl Claims {
@@ -41,9 +57,23 @@ impl Claims {
             iat: now.timestamp(),
             nbf: now.timestamp(),
             jti: Uuid::new_v4().to_string(),
+            sid: None,
+            scopes: Vec::new(),
         }
     }
 
+    /// Bind the token to a login session
+    pub fn with_session(mut self, session_id: Uuid) -> Self {
+        self.sid = Some(session_id);
+        self
+    }
+
+    /// Embed the caller's permissions
+    pub fn with_scopes(mut self, scopes: Vec<String>) -> Self {
+        self.scopes = scopes;
+        self
+    }
+
     pub fn is_expired(&self) -> bool {
         Utc::now().timestamp() > self.exp
     }
@@ -54,33 +84,115 @@ impl Claims {
     }
 }
 
-/// JWT token generation and validation
+impl Claims {
+    /// Caller context with the built-in permissions of the token's role.
+    /// Requests resolve assigned roles through `RbacService::auth_context`.
+    pub fn auth_context(&self) -> AuthContext {
+        self.auth_context_with(default_permissions(&self.role))
+    }
+
+    /// Caller context for downstream middleware (rate limiting tier, permissions)
+    pub fn auth_context_with(&self, permissions: Vec<String>) -> AuthContext {
+        let tier = match self.role.as_str() {
+            "admin" | "moderator" => "admin",
+            _ => "standard",
+        };
+        AuthContext::new(self.sub.to_string(), String::new())
+            .with_permissions(permissions)
+            .with_rate_limit_tier(tier)
+    }
+}
+
+/// JWT signing and validation with the gateway's key set
+/// (`services::jwt_keys`). Tokens are signed with the current key and
+/// accepted under any published one, so tokens signed before a rotation keep
+/// working until their key expires.
 pub struct JwtService {
+    keys: RwLock<ActiveKeys>,
+    /// HS256 secret of tokens issued before the switch to key pairs
+    legacy_key: Option<DecodingKey>,
+}
+
+struct ActiveKeys {
+    kid: String,
     encoding_key: EncodingKey,
-    decoding_key: DecodingKey,
-    validation: Validation,
+    decoding_keys: HashMap<String, DecodingKey>,
+    jwks: JwkSet,
 }
 
-impl JwtService {
-    pub fn new(secret: &str) -> Self {
-        let encoding_key = EncodingKey::from_secret(secret.as_bytes());
-        let decoding_key = DecodingKey::from_secret(secret.as_bytes());
-        let validation = Validation::default();
+impl ActiveKeys {
+    fn new(keys: &KeySet) -> Self {
+        Self {
+            kid: keys.signing.public.kid.clone(),
+            encoding_key: keys.signing.encoding_key(),
+            decoding_keys: keys
+                .verifying
+                .iter()
+                .map(|key| (key.kid.clone(), key.decoding_key()))
+                .collect(),
+            jwks: keys.jwks(),
+        }
+    }
+}
 
+impl JwtService {
+    pub fn new(keys: &KeySet) -> Self {
         Self {
-            encoding_key,
-            decoding_key,
-            validation,
+            keys: RwLock::new(ActiveKeys::new(keys)),
+            legacy_key: None,
+        }
+    }
+
+    /// Also accept HS256 tokens signed with `secret`
+    pub fn with_legacy_secret(mut self, secret: &str) -> Self {
+        self.legacy_key = Some(DecodingKey::from_secret(secret.as_bytes()));
+        self
+    }
+
+    /// Switch to a reloaded key set
+    pub fn install(&self, keys: &KeySet) {
+        let active = ActiveKeys::new(keys);
+        let mut current = self.keys.write().unwrap_or_else(|e| e.into_inner());
+        if current.kid != active.kid {
+            info!("Signing tokens with JWT key {}", active.kid);
         }
+        *current = active;
+    }
+
+    /// Public keys for `/.well-known/jwks.json`
+    pub fn jwks(&self) -> JwkSet {
+        self.keys.read().unwrap_or_else(|e| e.into_inner()).jwks.clone()
     }
 
     pub fn generate_token(&self, claims: &Claims) -> Result<String, ApiError> {
-        encode(&Header::default(), claims, &self.encoding_key)
+        let keys = self.keys.read().unwrap_or_else(|e| e.into_inner());
+        let mut header = Header::new(Algorithm::EdDSA);
+        header.kid = Some(keys.kid.clone());
+        encode(&header, claims, &keys.encoding_key)
             .map_err(|e| ApiError::Internal(format!("Failed to generate token: {}", e)))
     }
 
     pub fn validate_token(&self, token: &str) -> Result<Claims, ApiError> {
-        decode::<Claims>(token, &self.decoding_key, &self.validation)
+        let header = decode_header(token)
+            .map_err(|e| ApiError::Unauthorized(format!("Invalid token: {}", e)))?;
+
+        let result = match (header.alg, header.kid) {
+            (Algorithm::EdDSA, Some(kid)) => {
+                let keys = self.keys.read().unwrap_or_else(|e| e.into_inner());
+                let key = keys
+                    .decoding_keys
+                    .get(&kid)
+                    .ok_or_else(|| ApiError::Unauthorized("Unknown signing key".to_string()))?;
+                decode::<Claims>(token, key, &Validation::new(Algorithm::EdDSA))
+            }
+            (Algorithm::HS256, None) => match &self.legacy_key {
+                Some(key) => decode::<Claims>(token, key, &Validation::new(Algorithm::HS256)),
+                None => return Err(ApiError::Unauthorized("Unsupported token".to_string())),
+            },
+            _ => return Err(ApiError::Unauthorized("Unsupported token".to_string())),
+        };
+
+        result
             .map(|data| data.claims)
             .map_err(|e| ApiError::Unauthorized(format!("Invalid token: {}", e)))
     }
@@ -98,112 +210,151 @@ impl JwtService {
     }
 }
 
-/// Authentication middleware
-pub async fn auth_middleware(
-    State(state): State<AppState>,
-    mut request: Request<Body>,
-    next: Next,
-) -> Result<Response, StatusCode> {
-    // Extract authorization header
-    let auth_header = request
-        .headers()
-        .get(header::AUTHORIZATION)
-        .and_then(|h| h.to_str().ok());
-
-    match auth_header {
-        Some(header) if header.starts_with("Bearer ") => {
-            let token = &header[7..];
-
-            // Recreate JwtService here (in production, this should ideally be in AppState but for now we create it on fly or cached)
-            // However, JwtService construction is cheap (key derivation), or we can move it to AppState.
-            // Since AppState doesn't have it, we'll verify using the secret from config.
-
-            // NOTE: Ideally JwtService should be in AppState to avoid re-hashing key.
-            // But for this refactor, we instantiate it.
-            let jwt_service = JwtService::new(&state.config.security.jwt_secret);
+/// Role carried by refresh tokens; they may only be exchanged at `/auth/refresh`
+pub const REFRESH_TOKEN_ROLE: &str = "refresh";
+
+/// Decide whether a caller satisfies a route policy.
+/// `caller` must come from an already validated access token.
+pub fn authorize(policy: RoutePolicy, caller: Option<&AuthContext>) -> Result<(), StatusCode> {
+    match (policy, caller) {
+        (RoutePolicy::Public, _) => Ok(()),
+        (_, None) => Err(StatusCode::UNAUTHORIZED),
+        (RoutePolicy::Authenticated, Some(_)) => Ok(()),
+        (RoutePolicy::Admin, Some(caller)) if caller.has_permission(SCOPE_ADMIN_CONSOLE) => Ok(()),
+        (RoutePolicy::Scope(scope), Some(caller)) if caller.has_permission(scope) => Ok(()),
+        _ => Err(StatusCode::FORBIDDEN),
+    }
+}
 
-            match jwt_service.validate_token(token) {
-                Ok(claims) => {
-                    request.extensions_mut().insert(claims);
-                    Ok(next.run(request).await)
-                }
-                Err(_) => Err(StatusCode::UNAUTHORIZED),
+/// Middleware requiring a permission on top of the route policy, for routes
+/// whose path alone does not say what they need:
+///
+/// ```ignore
+/// .route("/", post(create).route_layer(middleware::from_fn(require_permission("bounty:create"))))
+/// ```
+///
+/// Must run inside `auth_middleware`, which resolves the caller's permissions.
+pub fn require_permission(
+    permission: &'static str,
+) -> impl Fn(Request<Body>, Next) -> BoxFuture<'static, Response> + Clone + Send + Sync + 'static {
+    move |request, next| {
+        Box::pin(async move {
+            let allowed = request
+                .extensions()
+                .get::<AuthContext>()
+                .map(|caller| caller.has_permission(permission));
+            match allowed {
+                Some(true) => next.run(request).await,
+                Some(false) => ApiError::Forbidden(format!("Missing permission {}", permission))
+                    .into_response(),
+                None => StatusCode::UNAUTHORIZED.into_response(),
             }
+        })
+    }
+}
+
+/// Whether the login session behind a token is still open, refreshing its
+/// idle expiry. Tokens not bound to a session are judged by their signature
+/// alone, as are all tokens while Redis is unreachable.
+pub async fn session_is_live(sessions: &SessionStore, claims: &Claims) -> bool {
+    let Some(session_id) = claims.sid else {
+        return true;
+    };
+    match sessions.touch(session_id, claims.sub).await {
+        Ok(session) => session.is_some(),
+        Err(e) => {
+            warn!("Session lookup for {} failed: {:#}", session_id, e);
+            true
         }
-        _ => Err(StatusCode::UNAUTHORIZED),
     }
 }
 
-/// Optional authentication middleware (doesn't fail on missing token)
-pub async fn optional_auth_middleware(
+/// Authentication middleware.
+///
+/// The route policy is resolved first; only then is the bearer token
+/// inspected. Public routes pass even with a missing or bad token (a valid
+/// one is still attached for handlers that personalize responses). Tokens
+/// whose session was revoked or timed out count as missing. Callers with
+/// neither a token nor an access cookie may present an organization API key
+/// in `X-API-Key`.
+pub async fn auth_middleware(
     State(state): State<AppState>,
     mut request: Request<Body>,
     next: Next,
-) -> Response {
-    // Extract authorization header
-    let auth_header = request
+) -> Result<Response, StatusCode> {
+    let policy = policy_for(request.method(), request.uri().path());
+
+    // Bearer header first; browsers on cookie sessions send the access
+    // cookie instead and are held to the CSRF check (`middleware::csrf`)
+    let bearer = request
         .headers()
         .get(header::AUTHORIZATION)
-        .and_then(|h| h.to_str().ok());
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .map(str::to_string);
+    let from_cookie = bearer.is_none();
+    let token = bearer.or_else(|| cookie_value(request.headers(), ACCESS_COOKIE));
+    let claims = token
+        .as_deref()
+        .and_then(|token| state.jwt.validate_token(token).ok())
+        .filter(|claims| claims.role != REFRESH_TOKEN_ROLE);
+    let claims = match claims {
+        Some(claims) if session_is_live(&state.sessions, &claims).await => Some(claims),
+        _ => None,
+    };
 
-    if let Some(header) = auth_header {
-        if header.starts_with("Bearer ") {
-            let token = &header[7..];
-            let jwt_service = JwtService::new(&state.config.security.jwt_secret);
+    let caller = match claims {
+        Some(claims) => Some((state.rbac.auth_context(&claims).await, claims)),
+        None => match (&token, api_key(&request)) {
+            (None, Some(api_key)) => api_key_caller(&state, &api_key).await,
+            _ => None,
+        },
+    };
+    authorize(policy, caller.as_ref().map(|(context, _)| context))?;
 
-            if let Ok(claims) = jwt_service.validate_token(token) {
-                request.extensions_mut().insert(claims);
-            }
+    if let Some((context, claims)) = caller {
+        if from_cookie {
+            request.extensions_mut().insert(CookieSession(claims.sid));
         }
+        request.extensions_mut().insert(context);
+        request.extensions_mut().insert(claims);
     }
 
-    next.run(request).await
+    Ok(next.run(request).await)
 }
 
-/// Admin role middleware (must be used after auth_middleware)
-pub async fn require_admin(mut request: Request<Body>, next: Next) -> Result<Response, StatusCode> {
-    let claims = request
-        .extensions()
-        .get::<Claims>()
-        .ok_or(StatusCode::UNAUTHORIZED)?;
+/// The organization API key a request presents
+fn api_key(request: &Request<Body>) -> Option<String> {
+    request
+        .headers()
+        .get("X-API-Key")
+        .and_then(|h| h.to_str().ok())
+        .map(str::to_string)
+}
 
-    if claims.role != "admin" && claims.role != "moderator" {
-        return Err(StatusCode::FORBIDDEN);
+/// The caller of an organization API key, if it is valid
+async fn api_key_caller(state: &AppState, api_key: &str) -> Option<(AuthContext, Claims)> {
+    match state.rbac.api_key_context(api_key).await {
+        Ok(caller) => caller.map(|(claims, context)| (context, claims)),
+        Err(e) => {
+            warn!("API key lookup failed: {}", e);
+            None
+        }
     }
-
-    Ok(next.run(request).await)
 }
 
-/// API key authentication middleware
+/// API key authentication middleware, for routes only open to organization
+/// API keys
 pub async fn api_key_middleware(
     State(state): State<AppState>,
     mut request: Request<Body>,
     next: Next,
 ) -> Result<Response, StatusCode> {
-    // Check for API key in header
-    let api_key = request
-        .headers()
-        .get("X-API-Key")
-        .and_then(|h| h.to_str().ok())
+    let api_key = api_key(&request).ok_or(StatusCode::UNAUTHORIZED)?;
+    let (context, claims) = api_key_caller(&state, &api_key)
+        .await
         .ok_or(StatusCode::UNAUTHORIZED)?;
-
-    // TODO: Validate API key against database
-    // For now, just check if it starts with "nxs_"
-    if !api_key.starts_with("nxs_") {
-        return Err(StatusCode::UNAUTHORIZED);
-    }
-
-    // TODO: Add user context from API key lookup
-    let claims = Claims {
-        sub: Uuid::new_v4(),
-        email: "api@example.com".to_string(),
-        role: "user".to_string(),
-        exp: (Utc::now() + Duration::hours(24)).timestamp(),
-        iat: Utc::now().timestamp(),
-        nbf: Utc::now().timestamp(),
-        jti: Uuid::new_v4().to_string(),
-    };
-
+    request.extensions_mut().insert(context);
     request.extensions_mut().insert(claims);
 
     Ok(next.run(request).await)
@@ -232,6 +383,8 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::middleware::csrf::{cookie_value, CookieSession, ACCESS_COOKIE};
+use crate::middleware::route_policy::{SCOPE_ANALYSIS_SUBMIT, SCOPE_SUBMISSIONS_VERIFY};
 
     #[test]
     fn test_claims_creation() {
@@ -248,7 +401,7 @@ mod tests {
 
     #[test]
     fn test_jwt_service() {
-        let jwt_service = JwtService::new("test_secret_key_at_least_32_chars");
+        let jwt_service = JwtService::new(&KeySet::generate().unwrap());
         let claims = Claims::new(
             Uuid::new_v4(),
             "test@example.com".to_string(),
@@ -262,4 +415,116 @@ mod tests {
         let validated_claims = jwt_service.validate_token(&token).unwrap();
         assert_eq!(validated_claims.email, "test@example.com");
     }
+
+    #[test]
+    fn test_jwt_key_rotation() {
+        let claims = Claims::new(Uuid::new_v4(), "test@example.com".to_string(), "user".to_string(), 1);
+        let old_keys = KeySet::generate().unwrap();
+        let jwt_service = JwtService::new(&old_keys);
+        let old_token = jwt_service.generate_token(&claims).unwrap();
+
+        // During the overlap the retired key still verifies
+        let mut new_keys = KeySet::generate().unwrap();
+        new_keys.verifying.push(old_keys.signing.public.clone());
+        jwt_service.install(&new_keys);
+        let new_token = jwt_service.generate_token(&claims).unwrap();
+        assert_eq!(
+            decode_header(&new_token).unwrap().kid.as_deref(),
+            Some(new_keys.signing.public.kid.as_str())
+        );
+        assert!(jwt_service.validate_token(&old_token).is_ok());
+        assert!(jwt_service.validate_token(&new_token).is_ok());
+        assert_eq!(jwt_service.jwks().keys.len(), 2);
+
+        // Once it expires, its tokens are rejected
+        new_keys.verifying.pop();
+        jwt_service.install(&new_keys);
+        assert!(jwt_service.validate_token(&old_token).is_err());
+        assert!(jwt_service.validate_token(&new_token).is_ok());
+    }
+
+    #[test]
+    fn test_legacy_hs256_tokens() {
+        let secret = "test_secret_key_at_least_32_chars";
+        let claims = Claims::new(Uuid::new_v4(), "test@example.com".to_string(), "user".to_string(), 1);
+        let legacy_token =
+            encode(&Header::default(), &claims, &EncodingKey::from_secret(secret.as_bytes())).unwrap();
+
+        let keys = KeySet::generate().unwrap();
+        assert!(JwtService::new(&keys).validate_token(&legacy_token).is_err());
+        assert!(JwtService::new(&keys)
+            .with_legacy_secret(secret)
+            .validate_token(&legacy_token)
+            .is_ok());
+    }
+
+    fn claims_with_role(role: &str) -> AuthContext {
+        Claims::new(Uuid::new_v4(), "test@example.com".to_string(), role.to_string(), 1)
+            .auth_context()
+    }
+
+    #[test]
+    fn test_authorize_public() {
+        assert_eq!(authorize(RoutePolicy::Public, None), Ok(()));
+        assert_eq!(authorize(RoutePolicy::Public, Some(&claims_with_role("user"))), Ok(()));
+    }
+
+    #[test]
+    fn test_authorize_authenticated() {
+        assert_eq!(authorize(RoutePolicy::Authenticated, None), Err(StatusCode::UNAUTHORIZED));
+        assert_eq!(authorize(RoutePolicy::Authenticated, Some(&claims_with_role("user"))), Ok(()));
+    }
+
+    #[test]
+    fn test_authorize_admin() {
+        assert_eq!(authorize(RoutePolicy::Admin, None), Err(StatusCode::UNAUTHORIZED));
+        assert_eq!(
+            authorize(RoutePolicy::Admin, Some(&claims_with_role("user"))),
+            Err(StatusCode::FORBIDDEN)
+        );
+        assert_eq!(authorize(RoutePolicy::Admin, Some(&claims_with_role("moderator"))), Ok(()));
+        assert_eq!(authorize(RoutePolicy::Admin, Some(&claims_with_role("admin"))), Ok(()));
+    }
+
+    #[test]
+    fn test_authorize_scope() {
+        let verify = RoutePolicy::Scope(SCOPE_SUBMISSIONS_VERIFY);
+        assert_eq!(authorize(verify, None), Err(StatusCode::UNAUTHORIZED));
+        assert_eq!(authorize(verify, Some(&claims_with_role("user"))), Err(StatusCode::FORBIDDEN));
+        assert_eq!(authorize(verify, Some(&claims_with_role("moderator"))), Ok(()));
+        assert_eq!(authorize(verify, Some(&claims_with_role("admin"))), Ok(()));
+
+        let submit = RoutePolicy::Scope(SCOPE_ANALYSIS_SUBMIT);
+        assert_eq!(authorize(submit, Some(&claims_with_role("user"))), Ok(()));
+        assert_eq!(
+            authorize(submit, Some(&claims_with_role(REFRESH_TOKEN_ROLE))),
+            Err(StatusCode::FORBIDDEN)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_require_permission() {
+        use axum::{middleware::from_fn, routing::post, Router};
+        use tower::Service;
+
+        let app: Router = Router::new().route(
+            "/bounties",
+            post(|| async { "created" }).route_layer(from_fn(require_permission("bounty:create"))),
+        );
+        let call = |caller: Option<AuthContext>| {
+            let mut app = app.clone();
+            async move {
+                let mut request = Request::post("/bounties").body(Body::empty()).unwrap();
+                if let Some(caller) = caller {
+                    request.extensions_mut().insert(caller);
+                }
+                app.call(request).await.unwrap().status()
+            }
+        };
+
+        assert_eq!(call(None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(call(Some(claims_with_role("user"))).await, StatusCode::OK);
+        assert_eq!(call(Some(claims_with_role("admin"))).await, StatusCode::OK);
+        assert_eq!(call(Some(claims_with_role("guest"))).await, StatusCode::FORBIDDEN);
+    }
 }
@@ -0,0 +1,330 @@
+//! CSRF protection for cookie-based sessions.
+//!
+//! Browsers attach cookies to cross-site requests, so a session carried in
+//! a cookie needs a second proof that the request came from our own
+//! frontend. We use the double-submit pattern: login sets a `nexus_csrf`
+//! cookie the frontend can read, and every unsafe request must echo it in
+//! `X-CSRF-Token`. The token is an HMAC over the session id keyed with a
+//! per-session secret (`SessionStore::csrf_secret`), so a token planted by
+//! a sibling subdomain or lifted from another session does not verify.
+//!
+//! Bearer clients are exempt: an `Authorization` header is never sent by a
+//! browser on its own. Only requests the auth middleware authenticated from
+//! the access cookie carry [`CookieSession`] and are checked.
+//!
+//! Cookie attributes (`Secure`, `SameSite`, `Domain`) come from
+//! `security.cookies` and are applied to every `Set-Cookie` leaving the
+//! gateway, including ones from proxied services.
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, HeaderMap, HeaderValue, Method, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use rand::RngCore;
+use ring::hmac;
+use uuid::Uuid;
+
+use crate::config::CookieConfig;
+use crate::models::error::ApiError;
+use crate::AppState;
+
+/// HttpOnly cookie holding the access token
+pub const ACCESS_COOKIE: &str = "nexus_access";
+/// HttpOnly cookie holding the refresh token, only sent to the refresh endpoint
+pub const REFRESH_COOKIE: &str = "nexus_refresh";
+/// CSRF token readable by the frontend
+pub const CSRF_COOKIE: &str = "nexus_csrf";
+/// Header the frontend echoes the CSRF token in
+pub const CSRF_HEADER: &str = "x-csrf-token";
+
+const REFRESH_COOKIE_PATH: &str = "/api/v1/auth/refresh";
+
+/// Marks a request authenticated from the access cookie rather than a
+/// Bearer header. Holds the token's session.
+#[derive(Debug, Clone, Copy)]
+pub struct CookieSession(pub Option<Uuid>);
+
+/// Value of the named cookie in the request's `Cookie` headers
+pub fn cookie_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn signature(secret: &str, session_id: Uuid, nonce: &str) -> hmac::Tag {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    hmac::sign(&key, format!("{}.{}", session_id, nonce).as_bytes())
+}
+
+/// New CSRF token for a session: `<nonce>.<hmac(session.nonce)>`
+pub fn csrf_token(secret: &str, session_id: Uuid) -> String {
+    let mut nonce = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let nonce = hex::encode(nonce);
+    let tag = signature(secret, session_id, &nonce);
+    format!("{}.{}", nonce, hex::encode(tag.as_ref()))
+}
+
+/// Whether `token` was issued for this session
+pub fn csrf_token_valid(secret: &str, session_id: Uuid, token: &str) -> bool {
+    let Some((nonce, tag)) = token.split_once('.') else {
+        return false;
+    };
+    let Ok(tag) = hex::decode(tag) else {
+        return false;
+    };
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    hmac::verify(&key, format!("{}.{}", session_id, nonce).as_bytes(), &tag).is_ok()
+}
+
+/// Check the double-submitted token: the header must match the cookie and
+/// verify against the session's secret. Fails closed.
+pub async fn verify_csrf(
+    state: &AppState,
+    headers: &HeaderMap,
+    session_id: Option<Uuid>,
+) -> Result<(), ApiError> {
+    let forbidden = |reason: &str| ApiError::Forbidden(format!("CSRF check failed: {}", reason));
+
+    let header_token = headers
+        .get(CSRF_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .ok_or_else(|| forbidden("missing X-CSRF-Token header"))?;
+    let cookie_token =
+        cookie_value(headers, CSRF_COOKIE).ok_or_else(|| forbidden("missing CSRF cookie"))?;
+    // Both copies come from the caller, so this comparison leaks nothing;
+    // the HMAC check below is constant time
+    if header_token != cookie_token {
+        return Err(forbidden("token mismatch"));
+    }
+
+    let session_id = session_id.ok_or_else(|| forbidden("no session"))?;
+    let secret = state
+        .sessions
+        .csrf_secret(session_id)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to load session: {}", e)))?
+        .ok_or_else(|| forbidden("no session secret"))?;
+    if !csrf_token_valid(&secret, session_id, header_token) {
+        return Err(forbidden("invalid token"));
+    }
+    Ok(())
+}
+
+/// Require a valid CSRF token on unsafe requests authenticated by cookie.
+///
+/// Sits inside auth so it can see how the request was authenticated.
+pub async fn csrf_middleware(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let safe = matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE
+    );
+    if let (false, Some(CookieSession(session_id))) =
+        (safe, request.extensions().get::<CookieSession>().copied())
+    {
+        if let Err(error) = verify_csrf(&state, request.headers(), session_id).await {
+            tracing::warn!(
+                "{} {} rejected: {}",
+                request.method(),
+                request.uri().path(),
+                error
+            );
+            return error.into_response();
+        }
+    }
+    next.run(request).await
+}
+
+fn build_cookie(
+    config: &CookieConfig,
+    name: &str,
+    value: &str,
+    path: &str,
+    http_only: bool,
+    max_age: i64,
+) -> Option<HeaderValue> {
+    let mut cookie = format!("{}={}; Path={}; Max-Age={}", name, value, path, max_age);
+    if http_only {
+        cookie.push_str("; HttpOnly");
+    }
+    if let Some(domain) = &config.domain {
+        cookie.push_str(&format!("; Domain={}", domain));
+    }
+    HeaderValue::from_str(&harden_set_cookie(config, &cookie)).ok()
+}
+
+/// `Set-Cookie` headers for a freshly issued token pair
+pub fn session_cookies(
+    config: &CookieConfig,
+    access_token: &str,
+    refresh_token: &str,
+    csrf_token: &str,
+    access_max_age: i64,
+    refresh_max_age: i64,
+) -> HeaderMap {
+    let cookies = [
+        build_cookie(config, ACCESS_COOKIE, access_token, "/", true, access_max_age),
+        build_cookie(
+            config,
+            REFRESH_COOKIE,
+            refresh_token,
+            REFRESH_COOKIE_PATH,
+            true,
+            refresh_max_age,
+        ),
+        // Lives as long as the refresh token so the refresh call can be checked
+        build_cookie(config, CSRF_COOKIE, csrf_token, "/", false, refresh_max_age),
+    ];
+
+    let mut headers = HeaderMap::new();
+    for cookie in cookies.into_iter().flatten() {
+        headers.append(header::SET_COOKIE, cookie);
+    }
+    headers
+}
+
+/// `Set-Cookie` headers that remove the session cookies
+pub fn clear_session_cookies(config: &CookieConfig) -> HeaderMap {
+    let cookies = [
+        build_cookie(config, ACCESS_COOKIE, "", "/", true, 0),
+        build_cookie(config, REFRESH_COOKIE, "", REFRESH_COOKIE_PATH, true, 0),
+        build_cookie(config, CSRF_COOKIE, "", "/", false, 0),
+    ];
+
+    let mut headers = HeaderMap::new();
+    for cookie in cookies.into_iter().flatten() {
+        headers.append(header::SET_COOKIE, cookie);
+    }
+    headers
+}
+
+/// Add the configured `Secure` and `SameSite` attributes to a `Set-Cookie`
+/// value that lacks them
+pub fn harden_set_cookie(config: &CookieConfig, cookie: &str) -> String {
+    let has = |attribute: &str| {
+        cookie
+            .split(';')
+            .skip(1)
+            .any(|part| part.trim().to_ascii_lowercase().starts_with(attribute))
+    };
+
+    let mut hardened = cookie.to_string();
+    if config.secure && !has("secure") {
+        hardened.push_str("; Secure");
+    }
+    if !has("samesite") {
+        hardened.push_str(&format!("; SameSite={}", config.same_site.as_str()));
+    }
+    hardened
+}
+
+/// Apply the cookie policy to every `Set-Cookie` the gateway sends
+pub async fn cookie_attributes_middleware(
+    State(config): State<CookieConfig>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let mut response = next.run(request).await;
+
+    let headers = response.headers_mut();
+    let cookies: Vec<HeaderValue> = headers.get_all(header::SET_COOKIE).iter().cloned().collect();
+    if cookies.is_empty() {
+        return response;
+    }
+    headers.remove(header::SET_COOKIE);
+    for cookie in cookies {
+        let hardened = cookie
+            .to_str()
+            .ok()
+            .and_then(|value| HeaderValue::from_str(&harden_set_cookie(&config, value)).ok())
+            .unwrap_or(cookie);
+        headers.append(header::SET_COOKIE, hardened);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SameSite;
+
+    #[test]
+    fn test_csrf_token_bound_to_session() {
+        let session_id = Uuid::new_v4();
+        let token = csrf_token("secret", session_id);
+
+        assert!(csrf_token_valid("secret", session_id, &token));
+        assert!(!csrf_token_valid("other-secret", session_id, &token));
+        assert!(!csrf_token_valid("secret", Uuid::new_v4(), &token));
+        assert!(!csrf_token_valid("secret", session_id, "not-a-token"));
+        assert_ne!(token, csrf_token("secret", session_id));
+    }
+
+    #[test]
+    fn test_cookie_value() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::COOKIE,
+            HeaderValue::from_static("theme=dark; nexus_csrf=abc.def; nexus_access="),
+        );
+
+        assert_eq!(cookie_value(&headers, CSRF_COOKIE).as_deref(), Some("abc.def"));
+        assert_eq!(cookie_value(&headers, ACCESS_COOKIE), None);
+        assert_eq!(cookie_value(&headers, "missing"), None);
+    }
+
+    #[test]
+    fn test_harden_set_cookie() {
+        let config = CookieConfig {
+            enabled: true,
+            secure: true,
+            same_site: SameSite::Strict,
+            domain: None,
+        };
+
+        assert_eq!(
+            harden_set_cookie(&config, "id=1; Path=/"),
+            "id=1; Path=/; Secure; SameSite=Strict"
+        );
+        // Attributes set by the upstream are kept
+        assert_eq!(
+            harden_set_cookie(&config, "id=1; secure; SameSite=Lax"),
+            "id=1; secure; SameSite=Lax"
+        );
+    }
+
+    #[test]
+    fn test_session_cookies() {
+        let config = CookieConfig {
+            domain: Some(".nexus-security.com".to_string()),
+            ..CookieConfig::default()
+        };
+        let headers = session_cookies(&config, "access", "refresh", "csrf", 3600, 86400);
+        let cookies: Vec<&str> = headers
+            .get_all(header::SET_COOKIE)
+            .iter()
+            .map(|value| value.to_str().unwrap())
+            .collect();
+
+        assert_eq!(cookies.len(), 3);
+        assert!(cookies[0].starts_with("nexus_access=access; Path=/; Max-Age=3600; HttpOnly"));
+        assert!(cookies[1].contains("Path=/api/v1/auth/refresh"));
+        assert!(!cookies[2].contains("HttpOnly"));
+        assert!(cookies
+            .iter()
+            .all(|c| c.contains("Domain=.nexus-security.com") && c.ends_with("; Secure; SameSite=Lax")));
+    }
+}
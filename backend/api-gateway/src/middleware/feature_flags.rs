@@ -0,0 +1,113 @@
+use axum::{
+    body::Body,
+    extract::State,
+    http::{HeaderValue, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::services::feature_flags::Unavailable;
+use crate::AppState;
+
+/// Body of 503 responses for switched-off routes
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ServiceUnavailableError {
+    /// `MAINTENANCE` or `ROUTE_GROUP_DISABLED`
+    pub error: String,
+    pub message: String,
+    pub route_group: Option<String>,
+    pub retry_after_seconds: u64,
+    pub timestamp: DateTime<Utc>,
+}
+
+pub fn unavailable_response(unavailable: &Unavailable) -> Response {
+    let retry_after = unavailable.retry_after_seconds();
+    let reason = unavailable.notice.as_ref().and_then(|n| n.reason.clone());
+    let (error, message) = match unavailable.route_group {
+        None => (
+            "MAINTENANCE",
+            reason.unwrap_or_else(|| "The API is down for maintenance".to_string()),
+        ),
+        Some(group) => (
+            "ROUTE_GROUP_DISABLED",
+            reason.unwrap_or_else(|| format!("The {} API is temporarily disabled", group)),
+        ),
+    };
+
+    let body = ServiceUnavailableError {
+        error: error.to_string(),
+        message,
+        route_group: unavailable.route_group.map(str::to_string),
+        retry_after_seconds: retry_after,
+        timestamp: Utc::now(),
+    };
+    let mut response = (StatusCode::SERVICE_UNAVAILABLE, Json(body)).into_response();
+    if let Ok(value) = HeaderValue::from_str(&retry_after.to_string()) {
+        response.headers_mut().insert("Retry-After", value);
+    }
+    response
+}
+
+/// Turn away requests to routes switched off by a feature flag
+/// (`services::feature_flags`) with 503 and `Retry-After`.
+///
+/// Runs before auth so maintenance mode sheds load before any token or
+/// database work is done.
+pub async fn feature_flag_middleware(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    if let Some(unavailable) = state.flags.unavailable(request.uri().path()).await {
+        tracing::debug!("{} rejected: {} is off", request.uri().path(), unavailable.flag);
+        return unavailable_response(&unavailable);
+    }
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::feature_flags::{
+        route_group_flag, FlagNotice, DEFAULT_RETRY_AFTER_SECS, MAINTENANCE_FLAG,
+    };
+
+    #[test]
+    fn test_route_group_response_uses_notice() {
+        let unavailable = Unavailable {
+            flag: route_group_flag("wallet"),
+            route_group: Some("wallet"),
+            notice: Some(FlagNotice {
+                reason: Some("Payment provider incident".to_string()),
+                retry_after_seconds: 900,
+                changed_by: None,
+                changed_at: Utc::now(),
+            }),
+        };
+        let response = unavailable_response(&unavailable);
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.headers()["Retry-After"], "900");
+    }
+
+    #[tokio::test]
+    async fn test_maintenance_response_defaults() {
+        let unavailable = Unavailable {
+            flag: MAINTENANCE_FLAG.to_string(),
+            route_group: None,
+            notice: None,
+        };
+        let response = unavailable_response(&unavailable);
+        assert_eq!(
+            response.headers()["Retry-After"],
+            DEFAULT_RETRY_AFTER_SECS.to_string().as_str()
+        );
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: ServiceUnavailableError = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.error, "MAINTENANCE");
+        assert_eq!(body.route_group, None);
+    }
+}
@@ -0,0 +1,412 @@
+use axum::{
+    body::{self, Body, Bytes, HttpBody},
+    extract::State,
+    http::{header, HeaderName, HeaderValue, Method, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use futures_util::{stream, StreamExt};
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use crate::models::error::ApiError;
+use crate::utils::AuthContext;
+use crate::AppState;
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+
+/// Longest accepted `Idempotency-Key`
+const MAX_KEY_LENGTH: usize = 255;
+
+/// How long a request may hold its key before a retry may take over. Bounds
+/// the damage of a request that never finishes (crash, dropped connection).
+const IN_FLIGHT_TTL_SECONDS: u64 = 300;
+
+/// JSON bodies up to this size are fingerprinted so a key cannot be reused
+/// for a different request
+const MAX_FINGERPRINT_BYTES: usize = 1024 * 1024;
+
+/// What a key maps to in Redis
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+enum StoredRequest {
+    /// The first request is still running
+    InFlight { fingerprint: Option<String> },
+    /// The first request finished; its response is replayed
+    Completed {
+        fingerprint: Option<String>,
+        response: StoredResponse,
+    },
+}
+
+impl StoredRequest {
+    fn fingerprint(&self) -> Option<&str> {
+        match self {
+            StoredRequest::InFlight { fingerprint } | StoredRequest::Completed { fingerprint, .. } => {
+                fingerprint.as_deref()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct StoredResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    /// Base64 body
+    body: String,
+}
+
+impl StoredResponse {
+    fn capture(status: StatusCode, headers: &header::HeaderMap, body: &[u8]) -> Self {
+        let headers = headers
+            .iter()
+            .filter(|(name, _)| {
+                *name != header::CONTENT_LENGTH && *name != header::SET_COOKIE
+            })
+            .filter_map(|(name, value)| {
+                Some((name.as_str().to_string(), value.to_str().ok()?.to_string()))
+            })
+            .collect();
+
+        Self {
+            status: status.as_u16(),
+            headers,
+            body: BASE64.encode(body),
+        }
+    }
+
+    fn replay(&self) -> Response {
+        let mut response = Response::new(Body::from(BASE64.decode(&self.body).unwrap_or_default()));
+        *response.status_mut() =
+            StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let headers = response.headers_mut();
+        for (name, value) in &self.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                headers.append(name, value);
+            }
+        }
+        headers.insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+        response
+    }
+}
+
+/// Redis key for a caller's key on one route. The route is hashed so long
+/// paths do not bloat the key, and scoping by caller means one user cannot
+/// replay (or block) another's response by guessing their key.
+fn storage_key(prefix: &str, subject: &str, method: &Method, path: &str, key: &str) -> String {
+    let route = hex::encode(Sha256::digest(format!("{} {}", method, path)));
+    format!("{}idempotency:{}:{}:{}", prefix, subject, &route[..16], key)
+}
+
+fn is_valid_key(key: &str) -> bool {
+    !key.is_empty()
+        && key.len() <= MAX_KEY_LENGTH
+        && key.bytes().all(|b| b.is_ascii_graphic())
+}
+
+/// Whether a response is the request's outcome. Server errors, and
+/// rejections that depend on the caller's state rather than the request
+/// (permissions, rate limits), are not stored: the key is released so the
+/// client's retry runs the request again.
+fn is_storable(status: StatusCode) -> bool {
+    !status.is_server_error()
+        && !matches!(
+            status,
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS
+        )
+}
+
+/// A response body read for storing
+enum BufferedBody {
+    Complete(Bytes),
+    /// Longer than the limit; the whole body, still streaming
+    TooLarge(Body),
+    Failed(axum::Error),
+}
+
+/// Read `body` into memory if it is at most `limit` bytes. Handlers rarely
+/// set `Content-Length` (JSON responses do not), so the size is found by
+/// reading; a body that turns out longer is handed back intact.
+async fn buffer_body(body: Body, limit: usize) -> BufferedBody {
+    if body.size_hint().lower() > limit as u64 {
+        return BufferedBody::TooLarge(body);
+    }
+
+    let mut data = body.into_data_stream();
+    let mut chunks: Vec<Bytes> = Vec::new();
+    let mut len = 0usize;
+    while let Some(chunk) = data.next().await {
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(e) => return BufferedBody::Failed(e),
+        };
+        len += chunk.len();
+        chunks.push(chunk);
+        if len > limit {
+            let read = stream::iter(chunks.into_iter().map(Ok::<_, axum::Error>));
+            return BufferedBody::TooLarge(Body::from_stream(read.chain(data)));
+        }
+    }
+
+    BufferedBody::Complete(match chunks.len() {
+        0 => Bytes::new(),
+        1 => chunks.pop().unwrap_or_default(),
+        _ => chunks.concat().into(),
+    })
+}
+
+fn is_json(request: &Request<Body>) -> bool {
+    request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"))
+}
+
+/// Idempotency middleware (must be used after an auth middleware).
+///
+/// A POST carrying an `Idempotency-Key` header is recorded per caller, key
+/// and route. The first request runs and its response is stored for
+/// `idempotency.ttl_seconds`; retries with the same key get that response
+/// back with `Idempotent-Replayed: true` instead of running again, so a
+/// client retrying a timed-out bounty creation or payment cannot fund it
+/// twice. A retry that arrives while the first request is still running is
+/// rejected with 409, and reusing a key for a different JSON body with 422.
+///
+/// Redis errors fail open, like the rate limiter.
+pub async fn idempotency_middleware(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let config = &state.config.idempotency;
+    if !config.enabled || request.method() != Method::POST {
+        return next.run(request).await;
+    }
+    let Some(key) = request.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return next.run(request).await;
+    };
+    let Some(subject) = request.extensions().get::<AuthContext>().map(|c| c.user_id.clone()) else {
+        return next.run(request).await;
+    };
+    let key = match key.to_str() {
+        Ok(key) if is_valid_key(key) => key.to_string(),
+        _ => {
+            return ApiError::BadRequest(format!(
+                "Idempotency-Key must be 1-{} printable ASCII characters",
+                MAX_KEY_LENGTH
+            ))
+            .into_response()
+        }
+    };
+
+    let storage_key = storage_key(
+        &state.config.redis.key_prefix,
+        &subject,
+        request.method(),
+        request.uri().path(),
+        &key,
+    );
+
+    let (request, fingerprint) = if is_json(&request) {
+        let (parts, body) = request.into_parts();
+        let bytes = match body::to_bytes(body, MAX_FINGERPRINT_BYTES).await {
+            Ok(bytes) => bytes,
+            Err(_) => {
+                return ApiError::FileTooLarge(format!(
+                    "JSON bodies are limited to {} bytes",
+                    MAX_FINGERPRINT_BYTES
+                ))
+                .into_response()
+            }
+        };
+        let fingerprint = hex::encode(Sha256::digest(&bytes));
+        (Request::from_parts(parts, Body::from(bytes)), Some(fingerprint))
+    } else {
+        (request, None)
+    };
+
+    let mut conn = state.redis.connection_pool.clone();
+    let marker = StoredRequest::InFlight { fingerprint: fingerprint.clone() };
+    let claimed: redis::RedisResult<Option<String>> = redis::cmd("SET")
+        .arg(&storage_key)
+        .arg(serde_json::to_string(&marker).unwrap_or_default())
+        .arg("NX")
+        .arg("EX")
+        .arg(IN_FLIGHT_TTL_SECONDS)
+        .query_async(&mut conn)
+        .await;
+
+    match claimed {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            let existing: redis::RedisResult<Option<String>> = conn.get(&storage_key).await;
+            match existing.map(|v| v.and_then(|v| serde_json::from_str::<StoredRequest>(&v).ok())) {
+                Ok(Some(stored)) if stored.fingerprint() != fingerprint.as_deref() => {
+                    return ApiError::Validation(
+                        "Idempotency-Key was already used for a different request".to_string(),
+                    )
+                    .into_response();
+                }
+                Ok(Some(StoredRequest::Completed { response, .. })) => return response.replay(),
+                Ok(Some(StoredRequest::InFlight { .. })) => {
+                    let mut response = ApiError::Conflict(
+                        "A request with this Idempotency-Key is still being processed".to_string(),
+                    )
+                    .into_response();
+                    response
+                        .headers_mut()
+                        .insert(header::RETRY_AFTER, HeaderValue::from_static("1"));
+                    return response;
+                }
+                // Expired in between: nothing left to replay
+                Ok(None) => return next.run(request).await,
+                Err(e) => {
+                    warn!("Idempotency store unavailable, running request: {}", e);
+                    return next.run(request).await;
+                }
+            }
+        }
+        Err(e) => {
+            warn!("Idempotency store unavailable, running request: {}", e);
+            return next.run(request).await;
+        }
+    }
+
+    let response = next.run(request).await;
+
+    if !is_storable(response.status()) {
+        let released: redis::RedisResult<()> = conn.del(&storage_key).await;
+        if let Err(e) = released {
+            warn!("Failed to release idempotency key {}: {}", storage_key, e);
+        }
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let bytes = match buffer_body(body, config.max_response_bytes).await {
+        BufferedBody::Complete(bytes) => bytes,
+        BufferedBody::TooLarge(body) => {
+            let released: redis::RedisResult<()> = conn.del(&storage_key).await;
+            if let Err(e) = released {
+                warn!("Failed to release idempotency key {}: {}", storage_key, e);
+            }
+            return Response::from_parts(parts, body);
+        }
+        BufferedBody::Failed(e) => {
+            let _: redis::RedisResult<()> = conn.del(&storage_key).await;
+            warn!("Failed to buffer response for idempotency key: {}", e);
+            return ApiError::Internal("Failed to read response".to_string()).into_response();
+        }
+    };
+
+    let stored = StoredRequest::Completed {
+        fingerprint,
+        response: StoredResponse::capture(parts.status, &parts.headers, &bytes),
+    };
+    let saved: redis::RedisResult<()> = conn
+        .set_ex(
+            &storage_key,
+            serde_json::to_string(&stored).unwrap_or_default(),
+            config.ttl_seconds,
+        )
+        .await;
+    if let Err(e) = saved {
+        warn!("Failed to store response for idempotency key {}: {}", storage_key, e);
+    }
+
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_storage_key_is_scoped_by_caller_and_route() {
+        let key = storage_key("nexus:", "user-1", &Method::POST, "/bounties", "abc");
+        assert!(key.starts_with("nexus:idempotency:user-1:"));
+        assert!(key.ends_with(":abc"));
+
+        assert_ne!(key, storage_key("nexus:", "user-2", &Method::POST, "/bounties", "abc"));
+        assert_ne!(key, storage_key("nexus:", "user-1", &Method::POST, "/wallet/stake", "abc"));
+        assert_eq!(key, storage_key("nexus:", "user-1", &Method::POST, "/bounties", "abc"));
+    }
+
+    #[test]
+    fn test_key_validation() {
+        assert!(is_valid_key("8e03978e-40d5-43e8-bc93-6894a57f9324"));
+        assert!(!is_valid_key(""));
+        assert!(!is_valid_key("has space"));
+        assert!(!is_valid_key(&"k".repeat(MAX_KEY_LENGTH + 1)));
+    }
+
+    #[test]
+    fn test_only_request_outcomes_are_stored() {
+        assert!(is_storable(StatusCode::CREATED));
+        assert!(is_storable(StatusCode::BAD_REQUEST));
+        assert!(!is_storable(StatusCode::FORBIDDEN));
+        assert!(!is_storable(StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(!is_storable(StatusCode::BAD_GATEWAY));
+    }
+
+    #[tokio::test]
+    async fn test_json_responses_are_buffered_without_content_length() {
+        let response = axum::Json(serde_json::json!({"id": "1"})).into_response();
+        assert!(response.headers().get(header::CONTENT_LENGTH).is_none());
+
+        let BufferedBody::Complete(bytes) = buffer_body(response.into_body(), 1024).await else {
+            panic!("expected the body to be buffered");
+        };
+        assert_eq!(&bytes[..], br#"{"id":"1"}"#);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_bodies_are_passed_on_whole() {
+        let chunks = (0..4).map(|_| Ok::<_, std::io::Error>(Bytes::from(vec![b'x'; 100])));
+        let body = Body::from_stream(stream::iter(chunks));
+
+        let BufferedBody::TooLarge(body) = buffer_body(body, 250).await else {
+            panic!("expected the body to exceed the limit");
+        };
+        let bytes = body::to_bytes(body, usize::MAX).await.unwrap();
+        assert_eq!(bytes.len(), 400);
+
+        let BufferedBody::TooLarge(_) = buffer_body(Body::from(vec![0u8; 300]), 250).await else {
+            panic!("expected the body to exceed the limit");
+        };
+    }
+
+    #[tokio::test]
+    async fn test_stored_response_round_trip() {
+        let mut headers = header::HeaderMap::new();
+        headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        headers.insert(header::CONTENT_LENGTH, HeaderValue::from_static("11"));
+        headers.insert(header::LOCATION, HeaderValue::from_static("/bounties/1"));
+
+        let stored = StoredRequest::Completed {
+            fingerprint: Some("f".to_string()),
+            response: StoredResponse::capture(StatusCode::CREATED, &headers, br#"{"id":"1"}"#),
+        };
+        let json = serde_json::to_string(&stored).unwrap();
+        let StoredRequest::Completed { response, .. } = serde_json::from_str(&json).unwrap() else {
+            panic!("expected a completed request");
+        };
+
+        let replayed = response.replay();
+        assert_eq!(replayed.status(), StatusCode::CREATED);
+        assert_eq!(replayed.headers()[header::LOCATION], "/bounties/1");
+        assert_eq!(replayed.headers()[IDEMPOTENT_REPLAYED_HEADER], "true");
+        assert!(replayed.headers().get(header::CONTENT_LENGTH).is_none());
+        let body = body::to_bytes(replayed.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], br#"{"id":"1"}"#);
+    }
+}
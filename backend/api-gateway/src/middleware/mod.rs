@@ -1,13 +1,20 @@
 // Middleware modules for the API Gateway
+pub mod api_version;
 pub mod auth;
-pub mod cors;
+pub mod csrf;
+pub mod feature_flags;
+pub mod idempotency;
 pub mod logging;
 pub mod metrics;
+pub mod quota;
 pub mod rate_limiter;
+pub mod route_policy;
+pub mod usage;
 
 // Re-export commonly used middleware
 pub use auth::*;
-pub use cors::*;
+pub use idempotency::*;
 pub use logging::*;
 pub use metrics::*;
 pub use rate_limiter::*;
+pub use usage::*;
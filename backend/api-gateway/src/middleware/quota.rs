@@ -0,0 +1,207 @@
+use axum::{
+    body::Body,
+    extract::State,
+    http::{HeaderMap, HeaderValue, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::middleware::auth::Claims;
+use crate::middleware::rate_limiter::apply_rate_limit_headers;
+use crate::services::quota::{OrganizationQuota, QuotaBudget, QuotaDecision};
+use crate::utils::RateLimitInfo;
+use crate::utils::AuthContext;
+use crate::AppState;
+
+/// Body of 429 responses once an organization limit is exhausted
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuotaExceededError {
+    pub error: String,
+    pub message: String,
+    /// `requests_per_minute`, `submissions` or `analyses`
+    pub quota: String,
+    pub limit: u32,
+    pub used: u32,
+    /// When the quota frees up again
+    pub reset_at: DateTime<Utc>,
+    pub retry_after_seconds: u64,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl QuotaExceededError {
+    fn new(quota: &str, limit: u32, used: u32, reset_at: DateTime<Utc>) -> Self {
+        let now = Utc::now();
+        Self {
+            error: "QUOTA_EXCEEDED".to_string(),
+            message: format!("Organization {} quota of {} exhausted", quota, limit),
+            quota: quota.to_string(),
+            limit,
+            used,
+            reset_at,
+            retry_after_seconds: (reset_at - now).num_seconds().max(1) as u64,
+            timestamp: now,
+        }
+    }
+}
+
+/// Write `X-Quota-*` headers for a daily budget
+pub fn apply_quota_headers(headers: &mut HeaderMap, budget: QuotaBudget, decision: &QuotaDecision) {
+    let mut set = |name: &'static str, value: String| {
+        if let Ok(value) = HeaderValue::from_str(&value) {
+            headers.insert(name, value);
+        }
+    };
+    set("X-Quota-Name", budget.as_str().to_string());
+    set("X-Quota-Limit", decision.limit.to_string());
+    set("X-Quota-Remaining", decision.remaining().to_string());
+    set("X-Quota-Reset", decision.reset_at.timestamp().to_string());
+    if !decision.allowed {
+        let retry_after = (decision.reset_at - Utc::now()).num_seconds().max(1);
+        set("Retry-After", retry_after.to_string());
+    }
+}
+
+/// Organization quota middleware (must be used after an auth middleware).
+///
+/// Members of an organization with quotas share a requests-per-minute
+/// window and daily submission and analysis budgets, whichever key or
+/// account they call with. A request that exhausts one is rejected with
+/// 429 and the time the quota resets. Budget units drawn by requests that
+/// then fail are given back.
+///
+/// Runs inside the per-caller rate limiter, whose `X-RateLimit-*` headers
+/// successful responses carry. Like it, this fails open: if Redis or Postgres
+/// is unavailable the request goes through.
+pub async fn organization_quota_middleware(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some(user_id) = request.extensions().get::<Claims>().map(|c| c.sub) else {
+        return next.run(request).await;
+    };
+    // API key callers carry their organization; users are looked up
+    let known_organization = request.extensions().get::<AuthContext>().and_then(AuthContext::organization);
+    if !state.config.quotas.enabled {
+        return next.run(request).await;
+    }
+
+    let resolved = match known_organization {
+        Some(id) => Ok(Some(id)),
+        None => state.usage.organization_for_user(user_id).await,
+    };
+    let organization_id = match resolved {
+        Ok(Some(id)) => id,
+        Ok(None) => return next.run(request).await,
+        Err(e) => {
+            warn!("Failed to resolve organization for {}: {:#}", user_id, e);
+            return next.run(request).await;
+        }
+    };
+    let quota = match state.quotas.quota(organization_id).await {
+        Ok(quota) => quota,
+        Err(e) => {
+            warn!("Quotas unavailable for {}, allowing request: {:#}", organization_id, e);
+            return next.run(request).await;
+        }
+    };
+    if quota == OrganizationQuota::default() {
+        return next.run(request).await;
+    }
+
+    // Pooled window, on top of each caller's own rate limit
+    if let Some(requests) = quota.requests_per_minute {
+        let requests = requests.max(1) as u32;
+        let key = format!("{}rate_limit:org:{}", state.config.redis.key_prefix, organization_id);
+        match state.redis.sliding_window_hit(&key, requests, 60_000).await {
+            Ok((allowed, count, oldest_ms)) => {
+                let reset_at = Utc
+                    .timestamp_millis_opt(oldest_ms + 60_000)
+                    .single()
+                    .unwrap_or_else(Utc::now);
+                if !allowed {
+                    let info = RateLimitInfo::new(requests, 0, reset_at);
+                    let body = QuotaExceededError::new("requests_per_minute", requests, count, reset_at);
+                    let mut response = (StatusCode::TOO_MANY_REQUESTS, Json(body)).into_response();
+                    apply_rate_limit_headers(response.headers_mut(), &info);
+                    return response;
+                }
+            }
+            Err(e) => warn!("Organization rate limit unavailable, allowing request: {:#}", e),
+        }
+    }
+
+    let budget = QuotaBudget::for_request(request.method(), request.uri().path())
+        .and_then(|budget| Some((budget, budget.limit(&quota)?)));
+    let mut drawn = None;
+    if let Some((budget, limit)) = budget {
+        match state.quotas.consume(organization_id, budget, limit).await {
+            Ok(decision) if !decision.allowed => {
+                let body = QuotaExceededError::new(budget.as_str(), limit, decision.used, decision.reset_at);
+                let mut response = (StatusCode::TOO_MANY_REQUESTS, Json(body)).into_response();
+                apply_quota_headers(response.headers_mut(), budget, &decision);
+                return response;
+            }
+            Ok(decision) => drawn = Some((budget, decision, Utc::now())),
+            Err(e) => warn!("Quota unavailable for {}, allowing request: {:#}", organization_id, e),
+        }
+    }
+
+    let mut response = next.run(request).await;
+
+    if let Some((budget, decision, drawn_at)) = drawn {
+        if response.status().is_success() {
+            apply_quota_headers(response.headers_mut(), budget, &decision);
+        } else {
+            let quotas = state.quotas.clone();
+            tokio::spawn(async move {
+                if let Err(e) = quotas.refund(organization_id, budget, drawn_at).await {
+                    warn!("Failed to refund {} quota for {}: {:#}", budget.as_str(), organization_id, e);
+                }
+            });
+        }
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quota_headers() {
+        let reset_at = Utc::now() + chrono::Duration::hours(2);
+        let mut decision = QuotaDecision { allowed: true, limit: 100, used: 40, reset_at };
+
+        let mut headers = HeaderMap::new();
+        apply_quota_headers(&mut headers, QuotaBudget::Submissions, &decision);
+        assert_eq!(headers["X-Quota-Name"], "submissions");
+        assert_eq!(headers["X-Quota-Limit"], "100");
+        assert_eq!(headers["X-Quota-Remaining"], "60");
+        assert_eq!(headers["X-Quota-Reset"], reset_at.timestamp().to_string().as_str());
+        assert!(headers.get("Retry-After").is_none());
+
+        decision.allowed = false;
+        decision.used = 100;
+        let mut headers = HeaderMap::new();
+        apply_quota_headers(&mut headers, QuotaBudget::Analyses, &decision);
+        assert_eq!(headers["X-Quota-Remaining"], "0");
+        let retry_after: i64 = headers["Retry-After"].to_str().unwrap().parse().unwrap();
+        assert!((7199..=7200).contains(&retry_after));
+    }
+
+    #[test]
+    fn test_quota_exceeded_body() {
+        let reset_at = Utc::now() + chrono::Duration::minutes(10);
+        let body = QuotaExceededError::new("analyses", 500, 500, reset_at);
+        let json = serde_json::to_value(&body).unwrap();
+        assert_eq!(json["error"], "QUOTA_EXCEEDED");
+        assert_eq!(json["quota"], "analyses");
+        assert_eq!(json["limit"], 500);
+        assert!((599..=600).contains(&body.retry_after_seconds));
+    }
+}
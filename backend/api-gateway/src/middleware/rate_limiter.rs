@@ -1,18 +1,23 @@
 use axum::{
     body::Body,
     extract::{ConnectInfo, State},
-    http::{Request, StatusCode},
+    http::{HeaderMap, HeaderValue, Request, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
     Json,
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
-use chrono::Utc;
+use chrono::{TimeZone, Utc};
+
+use crate::config::{RateLimitTier, RateLimitingConfig};
+use crate::middleware::logging::log_rate_limit_exceeded;
+use crate::utils::{AuthContext, RateLimitInfo};
+use crate::AppState;
 
 /// Rate limit configuration
 #[derive(Debug, Clone)]
@@ -358,10 +363,273 @@ impl AdaptiveRateLimiter {
     }
 }
 
+// ─── Distributed (Redis) rate limiting ───
+
+/// Which budget a request draws from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitScope {
+    /// Regular API traffic, limited per caller tier
+    Api,
+    /// Credential endpoints (login, register, reset), limited per IP
+    Auth,
+}
+
+impl RateLimitScope {
+    fn as_str(&self) -> &'static str {
+        match self {
+            RateLimitScope::Api => "api",
+            RateLimitScope::Auth => "auth",
+        }
+    }
+}
+
+/// Client IP of a request, as `client_ip_from` sees it
+fn client_ip(request: &Request<Body>, trusted_proxies: &[String]) -> String {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| *addr);
+    client_ip_from(request.headers(), peer, trusted_proxies).unwrap_or_else(|| "unknown".to_string())
+}
+
+/// An address or CIDR range ("10.0.0.0/8", "2001:db8::/32") as its network
+/// address and prefix length
+pub(crate) fn parse_ip_range(range: &str) -> Option<(IpAddr, u32)> {
+    let (addr, prefix) = match range.trim().split_once('/') {
+        Some((addr, prefix)) => (addr.parse::<IpAddr>().ok()?, Some(prefix.parse::<u32>().ok()?)),
+        None => (range.trim().parse::<IpAddr>().ok()?, None),
+    };
+    let bits = if addr.is_ipv4() { 32 } else { 128 };
+    let prefix = prefix.unwrap_or(bits);
+    (prefix <= bits).then_some((addr, prefix))
+}
+
+fn in_range(ip: IpAddr, range: &str) -> bool {
+    let Some((network, prefix)) = parse_ip_range(range) else {
+        return false;
+    };
+    let (ip, network, bits) = match (ip.to_canonical(), network) {
+        (IpAddr::V4(ip), IpAddr::V4(network)) => (u32::from(ip) as u128, u32::from(network) as u128, 32),
+        (IpAddr::V6(ip), IpAddr::V6(network)) => (u128::from(ip), u128::from(network), 128),
+        _ => return false,
+    };
+    if prefix == 0 {
+        return true;
+    }
+    let shift = bits - prefix;
+    (ip >> shift) == (network >> shift)
+}
+
+fn is_trusted_proxy(ip: IpAddr, trusted_proxies: &[String]) -> bool {
+    trusted_proxies.iter().any(|range| in_range(ip, range))
+}
+
+/// Client IP of a connection from `peer`. Forwarding headers are only
+/// believed when the peer is a trusted proxy; the client is then the
+/// rightmost X-Forwarded-For hop that is not itself a trusted proxy, since
+/// everything left of it was written by the client.
+pub(crate) fn client_ip_from(
+    headers: &HeaderMap,
+    peer: Option<SocketAddr>,
+    trusted_proxies: &[String],
+) -> Option<String> {
+    let peer = peer?.ip().to_canonical();
+    if !is_trusted_proxy(peer, trusted_proxies) {
+        return Some(peer.to_string());
+    }
+
+    let forwarded: Vec<IpAddr> = headers
+        .get_all("X-Forwarded-For")
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .filter_map(|hop| hop.trim().parse::<IpAddr>().ok())
+        .collect();
+    if let Some(client) = forwarded
+        .iter()
+        .rev()
+        .find(|hop| !is_trusted_proxy(**hop, trusted_proxies))
+        .or(forwarded.first())
+    {
+        return Some(client.to_canonical().to_string());
+    }
+
+    headers
+        .get("X-Real-IP")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<IpAddr>().ok())
+        .map(|ip| ip.to_canonical().to_string())
+        .or_else(|| Some(peer.to_string()))
+}
+
+/// Identity the window is counted against: API key, then user, then IP
+pub fn rate_limit_subject(context: Option<&AuthContext>, ip: &str) -> String {
+    match context {
+        Some(AuthContext { api_key_id: Some(key_id), .. }) => format!("key:{}", key_id),
+        Some(context) => format!("user:{}", context.user_id),
+        None => format!("ip:{}", ip),
+    }
+}
+
+/// Limit for a caller; unknown tiers fall back to `standard`
+pub fn resolve_rate_limit(
+    config: &RateLimitingConfig,
+    context: Option<&AuthContext>,
+    scope: RateLimitScope,
+) -> RateLimitTier {
+    let anonymous = RateLimitTier {
+        requests: config.requests_per_minute,
+        window_seconds: 60,
+    };
+
+    match (scope, context) {
+        (RateLimitScope::Auth, _) => RateLimitTier {
+            requests: config.auth_requests_per_15min,
+            window_seconds: 900,
+        },
+        (RateLimitScope::Api, Some(context)) => config
+            .tiers
+            .get(&context.rate_limit_tier)
+            .or_else(|| config.tiers.get("standard"))
+            .copied()
+            .unwrap_or(anonymous),
+        (RateLimitScope::Api, None) => anonymous,
+    }
+}
+
+/// Write `X-RateLimit-*` (and `Retry-After` when exhausted) headers
+pub fn apply_rate_limit_headers(headers: &mut HeaderMap, info: &RateLimitInfo) {
+    let mut set = |name: &'static str, value: String| {
+        if let Ok(value) = HeaderValue::from_str(&value) {
+            headers.insert(name, value);
+        }
+    };
+    set("X-RateLimit-Limit", info.limit.to_string());
+    set("X-RateLimit-Remaining", info.remaining.to_string());
+    set("X-RateLimit-Reset", info.reset_at.timestamp().to_string());
+    if let Some(retry_after) = info.retry_after {
+        set("Retry-After", retry_after.max(1).to_string());
+    }
+}
+
+async fn enforce_rate_limit(
+    state: &AppState,
+    scope: RateLimitScope,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let config = &state.config.security.rate_limiting;
+    let ip = client_ip(&request, &state.config.security.trusted_proxies);
+    if !config.enabled || config.whitelist_ips.contains(&ip) {
+        return next.run(request).await;
+    }
+
+    let context = request.extensions().get::<AuthContext>().cloned();
+    let limit = resolve_rate_limit(config, context.as_ref(), scope);
+    let subject = match scope {
+        RateLimitScope::Auth => format!("ip:{}", ip),
+        RateLimitScope::Api => rate_limit_subject(context.as_ref(), &ip),
+    };
+    let key = format!("{}rate_limit:{}:{}", state.config.redis.key_prefix, scope.as_str(), subject);
+    let window_ms = limit.window_seconds * 1000;
+
+    let (allowed, count, oldest_ms) =
+        match state.redis.sliding_window_hit(&key, limit.requests, window_ms).await {
+            Ok(hit) => hit,
+            Err(e) => {
+                // Fail open: a Redis outage must not take the API down with it
+                tracing::warn!("Rate limiter unavailable, allowing request: {:#}", e);
+                return next.run(request).await;
+            }
+        };
+
+    // The window frees a slot when its oldest hit expires
+    let reset_at = Utc
+        .timestamp_millis_opt(oldest_ms + window_ms as i64)
+        .single()
+        .unwrap_or_else(Utc::now);
+    let info = RateLimitInfo::new(limit.requests, limit.requests.saturating_sub(count), reset_at);
+
+    if !allowed {
+        log_rate_limit_exceeded(&subject, request.uri().path(), limit.requests, &ip);
+        let body = RateLimitError {
+            error: "RATE_LIMIT_EXCEEDED".to_string(),
+            message: format!(
+                "Rate limit of {} requests per {}s exceeded",
+                limit.requests, limit.window_seconds
+            ),
+            retry_after_seconds: info.retry_after.unwrap_or(0).max(1) as u64,
+            limit: limit.requests,
+            timestamp: Utc::now(),
+        };
+        let mut response = (StatusCode::TOO_MANY_REQUESTS, Json(body)).into_response();
+        apply_rate_limit_headers(response.headers_mut(), &info);
+        return response;
+    }
+
+    let mut response = next.run(request).await;
+    apply_rate_limit_headers(response.headers_mut(), &info);
+    response
+}
+
+/// Redis-backed sliding-window limit shared by every gateway instance.
+/// Runs after auth so the caller's tier is known from `AuthContext`.
+pub async fn distributed_rate_limit_middleware(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    enforce_rate_limit(&state, RateLimitScope::Api, request, next).await
+}
+
+/// Stricter per-IP limit for credential endpoints
+pub async fn auth_rate_limit_middleware(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    enforce_rate_limit(&state, RateLimitScope::Auth, request, next).await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_forwarded_headers_only_from_trusted_proxies() {
+        let proxies = vec!["10.0.0.0/8".to_string(), "192.0.2.1".to_string()];
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Forwarded-For", HeaderValue::from_static("1.2.3.4, 203.0.113.7, 10.1.2.3"));
+        headers.insert("X-Real-IP", HeaderValue::from_static("1.2.3.4"));
+
+        // A client talking to the gateway directly is its socket address
+        let direct: SocketAddr = "198.51.100.9:5000".parse().unwrap();
+        assert_eq!(client_ip_from(&headers, Some(direct), &proxies).as_deref(), Some("198.51.100.9"));
+
+        // Behind the proxies, the first untrusted hop from the right; the
+        // spoofed 1.2.3.4 on the left is ignored
+        let proxy: SocketAddr = "10.0.0.5:5000".parse().unwrap();
+        assert_eq!(client_ip_from(&headers, Some(proxy), &proxies).as_deref(), Some("203.0.113.7"));
+
+        let proxy: SocketAddr = "192.0.2.1:5000".parse().unwrap();
+        headers.remove("X-Forwarded-For");
+        assert_eq!(client_ip_from(&headers, Some(proxy), &proxies).as_deref(), Some("1.2.3.4"));
+        assert_eq!(client_ip_from(&headers, Some(proxy), &[]).as_deref(), Some("192.0.2.1"));
+        assert_eq!(client_ip_from(&headers, None, &proxies), None);
+    }
+
+    #[test]
+    fn test_ip_ranges() {
+        assert!(in_range("10.200.0.1".parse().unwrap(), "10.0.0.0/8"));
+        assert!(!in_range("11.0.0.1".parse().unwrap(), "10.0.0.0/8"));
+        assert!(in_range("::ffff:10.0.0.1".parse().unwrap(), "10.0.0.0/8"));
+        assert!(in_range("2001:db8::1".parse().unwrap(), "2001:db8::/32"));
+        assert!(!in_range("2001:db9::1".parse().unwrap(), "2001:db8::/32"));
+        assert!(in_range("8.8.8.8".parse().unwrap(), "0.0.0.0/0"));
+        assert!(parse_ip_range("10.0.0.0/33").is_none());
+        assert!(parse_ip_range("proxy.internal").is_none());
+    }
+
     #[tokio::test]
     async fn test_rate_limiter_allows_within_limit() {
         let limiter = RateLimiter::new(RateLimitConfig {
@@ -409,4 +677,52 @@ mod tests {
         let result = limiter.check_rate_limit("test_user").await;
         assert!(result.is_allowed());
     }
+
+    fn context(tier: &str) -> AuthContext {
+        AuthContext::new("user-1".to_string(), String::new()).with_rate_limit_tier(tier)
+    }
+
+    #[test]
+    fn test_resolve_rate_limit_tiers() {
+        let config = RateLimitingConfig::default();
+
+        let anonymous = resolve_rate_limit(&config, None, RateLimitScope::Api);
+        assert_eq!(anonymous.requests, config.requests_per_minute);
+
+        let premium = resolve_rate_limit(&config, Some(&context("premium")), RateLimitScope::Api);
+        assert_eq!(premium, config.tiers["premium"]);
+
+        // Unknown tiers get the standard budget
+        let unknown = resolve_rate_limit(&config, Some(&context("gold")), RateLimitScope::Api);
+        assert_eq!(unknown, config.tiers["standard"]);
+
+        let auth = resolve_rate_limit(&config, Some(&context("admin")), RateLimitScope::Auth);
+        assert_eq!(auth.requests, config.auth_requests_per_15min);
+        assert_eq!(auth.window_seconds, 900);
+    }
+
+    #[test]
+    fn test_rate_limit_subject() {
+        assert_eq!(rate_limit_subject(None, "10.0.0.1"), "ip:10.0.0.1");
+        assert_eq!(rate_limit_subject(Some(&context("standard")), "10.0.0.1"), "user:user-1");
+        let keyed = context("standard").with_api_key("abc".to_string());
+        assert_eq!(rate_limit_subject(Some(&keyed), "10.0.0.1"), "key:abc");
+    }
+
+    #[test]
+    fn test_apply_rate_limit_headers() {
+        let reset_at = Utc::now() + chrono::Duration::seconds(30);
+
+        let mut headers = HeaderMap::new();
+        apply_rate_limit_headers(&mut headers, &RateLimitInfo::new(10, 4, reset_at));
+        assert_eq!(headers["X-RateLimit-Limit"], "10");
+        assert_eq!(headers["X-RateLimit-Remaining"], "4");
+        assert_eq!(headers["X-RateLimit-Reset"], reset_at.timestamp().to_string().as_str());
+        assert!(headers.get("Retry-After").is_none());
+
+        let mut headers = HeaderMap::new();
+        apply_rate_limit_headers(&mut headers, &RateLimitInfo::new(10, 0, reset_at));
+        let retry_after: u32 = headers["Retry-After"].to_str().unwrap().parse().unwrap();
+        assert!((29..=30).contains(&retry_after));
+    }
 }
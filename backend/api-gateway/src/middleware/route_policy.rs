@@ -0,0 +1,268 @@
+//! Route access policies
+//!
+//! Every API route is classified here before any token is looked at, so a
+//! public route never depends on how JWT validation fails and a route that is
+//! missing from the table is denied (authenticated) by default. Patterns are
+//! relative to the API root (`/api/v1`, `/api/v2` or `/api`), so both API
+//! versions share one table; `:name` matches one path segment and a trailing
+//! `*` matches the rest of the path. The first matching rule wins, so
+//! specific rules must precede broader ones.
+
+use axum::http::Method;
+
+/// Access requirement for a route
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoutePolicy {
+    /// No credentials needed; a valid token is still attached if present
+    Public,
+    /// Any valid access token
+    Authenticated,
+    /// Access token whose `AuthContext` grants `SCOPE_ADMIN_CONSOLE`
+    /// (admins and moderators by default)
+    Admin,
+    /// Access token whose `AuthContext` grants the scope
+    Scope(&'static str),
+}
+
+/// One row of the policy table; `method: None` matches every method
+#[derive(Debug, Clone)]
+pub struct PolicyRule {
+    pub method: Option<Method>,
+    pub pattern: &'static str,
+    pub policy: RoutePolicy,
+}
+
+const fn rule(method: Option<Method>, pattern: &'static str, policy: RoutePolicy) -> PolicyRule {
+    PolicyRule { method, pattern, policy }
+}
+
+const ANY: Option<Method> = None;
+const GET: Option<Method> = Some(Method::GET);
+const POST: Option<Method> = Some(Method::POST);
+
+pub const SCOPE_ADMIN_CONSOLE: &str = "admin:console";
+pub const SCOPE_ANALYSIS_SUBMIT: &str = "analysis:submit";
+pub const SCOPE_BOUNTY_CREATE: &str = "bounty:create";
+pub const SCOPE_BOUNTY_MANAGE: &str = "bounty:manage";
+pub const SCOPE_ROLES_MANAGE: &str = "roles:manage";
+pub const SCOPE_SUBMISSIONS_VERIFY: &str = "submissions:verify";
+pub const SCOPE_WEBHOOKS_MANAGE: &str = "webhooks:manage";
+
+/// Route policy table, evaluated top to bottom
+pub static ROUTE_POLICIES: &[PolicyRule] = &[
+    // Health and credential endpoints. The auth handlers that act on an
+    // existing session (logout, wallet) validate the token themselves;
+    // session management goes through the auth layer.
+    rule(GET, "/health/*", RoutePolicy::Public),
+    // Served outside the API prefix; listed so the spec marks it public
+    rule(GET, "/.well-known/*", RoutePolicy::Public),
+    rule(POST, "/auth/api-key", RoutePolicy::Authenticated),
+    rule(ANY, "/auth/sessions/*", RoutePolicy::Authenticated),
+    rule(ANY, "/sessions/*", RoutePolicy::Authenticated),
+    rule(ANY, "/auth/*", RoutePolicy::Public),
+    // Browsers cannot set headers on a WebSocket handshake, so the stream
+    // handler also accepts the token as a query parameter and checks it itself
+    rule(GET, "/ws", RoutePolicy::Public),
+    // Public reads of bounties, analyses and reputation; writes need a token.
+    // Embargo details are limited to the creator's organization and admins,
+    // which the bounty manager checks against the forwarded identity.
+    rule(GET, "/bounties/:bounty_id/embargo", RoutePolicy::Authenticated),
+    // Deposits, webhooks and exports belong to the bounty's creator; a
+    // finished export's download link is signed and works without a session
+    rule(GET, "/bounties/:bounty_id/deposit-verification", RoutePolicy::Authenticated),
+    rule(ANY, "/bounties/:bounty_id/webhooks/*", RoutePolicy::Scope(SCOPE_WEBHOOKS_MANAGE)),
+    rule(GET, "/bounties/exports/:export_id/download", RoutePolicy::Public),
+    rule(GET, "/bounties/export", RoutePolicy::Authenticated),
+    rule(GET, "/bounties/exports/*", RoutePolicy::Authenticated),
+    rule(GET, "/bounties/*", RoutePolicy::Public),
+    // Anyone may browse the tag taxonomy; only admins edit it
+    rule(GET, "/tags/*", RoutePolicy::Public),
+    rule(ANY, "/tags/*", RoutePolicy::Admin),
+    rule(POST, "/analysis/submit", RoutePolicy::Scope(SCOPE_ANALYSIS_SUBMIT)),
+    // Engine calls are proxied with the gateway's engine key, so they are
+    // never anonymous
+    rule(POST, "/analysis/file", RoutePolicy::Scope(SCOPE_ANALYSIS_SUBMIT)),
+    rule(POST, "/analysis/url", RoutePolicy::Scope(SCOPE_ANALYSIS_SUBMIT)),
+    rule(POST, "/analysis/hash", RoutePolicy::Scope(SCOPE_ANALYSIS_SUBMIT)),
+    rule(GET, "/analysis/results/*", RoutePolicy::Authenticated),
+    rule(GET, "/analysis/engines/*", RoutePolicy::Authenticated),
+    rule(GET, "/analysis/*", RoutePolicy::Public),
+    // A user's webhooks watch their own standing
+    rule(ANY, "/reputation/webhooks/*", RoutePolicy::Scope(SCOPE_WEBHOOKS_MANAGE)),
+    // Appeals are seen only by the appellant and the arbitrators on its panel
+    rule(ANY, "/reputation/appeals/*", RoutePolicy::Authenticated),
+    rule(GET, "/reputation/*", RoutePolicy::Public),
+    // Dashboard queries can reach the caller's own data (`me`)
+    rule(ANY, "/graphql", RoutePolicy::Authenticated),
+    // Scope-gated actions
+    rule(POST, "/submissions/:submission_id/verify", RoutePolicy::Scope(SCOPE_SUBMISSIONS_VERIFY)),
+    rule(ANY, "/webhooks/*", RoutePolicy::Scope(SCOPE_WEBHOOKS_MANAGE)),
+    // Administration
+    rule(ANY, "/usage/billing/*", RoutePolicy::Admin),
+    rule(ANY, "/usage/organizations/*", RoutePolicy::Admin),
+    rule(ANY, "/admin/*", RoutePolicy::Admin),
+    rule(POST, "/bounties/:bounty_id/rehydrate", RoutePolicy::Admin),
+];
+
+/// Drop the API mount prefix so the same table serves every version
+pub(crate) fn api_relative(path: &str) -> &str {
+    ["/api/v1", "/api/v2", "/api"]
+        .iter()
+        .find_map(|prefix| {
+            let rest = path.strip_prefix(prefix)?;
+            (rest.is_empty() || rest.starts_with('/')).then_some(rest)
+        })
+        .unwrap_or(path)
+}
+
+pub(crate) fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let mut pattern_segments = pattern.split('/').filter(|s| !s.is_empty());
+    let mut path_segments = path.split('/').filter(|s| !s.is_empty());
+
+    loop {
+        match (pattern_segments.next(), path_segments.next()) {
+            (Some("*"), _) => return true,
+            (Some(p), Some(s)) if p.starts_with(':') || p == s => continue,
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
+
+/// Policy for a request; routes missing from the table require authentication
+pub fn policy_for(method: &Method, path: &str) -> RoutePolicy {
+    let path = api_relative(path);
+    ROUTE_POLICIES
+        .iter()
+        .find(|rule| {
+            rule.method.as_ref().is_none_or(|m| m == method) && pattern_matches(rule.pattern, path)
+        })
+        .map(|rule| rule.policy)
+        .unwrap_or(RoutePolicy::Authenticated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_public_routes() {
+        for (method, path) in [
+            (Method::GET, "/api/v1/health"),
+            (Method::GET, "/api/v1/health/ready"),
+            (Method::POST, "/api/v1/auth/login"),
+            (Method::POST, "/api/v1/auth/register"),
+            (Method::POST, "/api/auth/register"),
+            (Method::POST, "/auth/refresh"),
+            (Method::GET, "/api/v1/bounties"),
+            (Method::GET, "/api/v1/bounties/123/stats"),
+            (Method::GET, "/api/v1/analysis/by-hash/abc"),
+            (Method::GET, "/api/v1/reputation/leaderboard"),
+            (Method::GET, "/api/v2/bounties"),
+            (Method::GET, "/api/v2/bounties/123/analyses"),
+            (Method::GET, "/api/v1/ws"),
+            (Method::GET, "/api/v1/bounties/42/participants"),
+            (Method::GET, "/api/v1/bounties/exports/42/download"),
+            (Method::GET, "/api/v1/tags"),
+            (Method::GET, "/api/v1/tags/ransomware/bounties"),
+            (Method::GET, "/.well-known/jwks.json"),
+        ] {
+            assert_eq!(policy_for(&method, path), RoutePolicy::Public, "{} {}", method, path);
+        }
+    }
+
+    #[test]
+    fn test_authenticated_routes() {
+        for (method, path) in [
+            (Method::POST, "/api/v1/auth/api-key"),
+            (Method::GET, "/api/v1/auth/sessions"),
+            (Method::DELETE, "/api/v1/auth/sessions/123"),
+            (Method::GET, "/api/v1/sessions"),
+            (Method::DELETE, "/api/v1/sessions"),
+            (Method::DELETE, "/api/v1/sessions/123"),
+            (Method::POST, "/api/v1/bounties"),
+            (Method::PUT, "/api/v1/bounties/123"),
+            (Method::GET, "/api/v1/bounties/123/embargo"),
+            (Method::POST, "/api/v1/bounties/123/embargo/release"),
+            (Method::GET, "/api/v1/bounties/123/deposit-verification"),
+            (Method::POST, "/api/v1/bounties/123/submissions"),
+            (Method::GET, "/api/v1/bounties/export"),
+            (Method::GET, "/api/v1/bounties/exports"),
+            (Method::GET, "/api/v1/bounties/exports/42"),
+            (Method::GET, "/api/v1/standing-bounties"),
+            (Method::POST, "/api/v1/analysis/123/dispute"),
+            (Method::GET, "/api/v1/users/me"),
+            (Method::GET, "/api/v1/submissions/my-submissions"),
+            (Method::GET, "/api/v1/usage"),
+            (Method::GET, "/api/v1/usage/quota"),
+            (Method::GET, "/api/v1/analysis/results/42"),
+            (Method::GET, "/api/v1/analysis/engines/status"),
+            (Method::GET, "/api/v1/reputation/appeals"),
+            (Method::GET, "/api/v1/reputation/appeals/42"),
+            (Method::POST, "/api/v1/graphql"),
+            (Method::POST, "/api/v1/submissions/file"),
+            (Method::GET, "/api/v2/users/me"),
+            (Method::GET, "/api/v2/submissions"),
+            // Not in the table at all
+            (Method::GET, "/api/v1/unknown/route"),
+        ] {
+            assert_eq!(policy_for(&method, path), RoutePolicy::Authenticated, "{} {}", method, path);
+        }
+    }
+
+    #[test]
+    fn test_admin_routes() {
+        assert_eq!(policy_for(&Method::GET, "/api/v1/usage/billing/2026-01"), RoutePolicy::Admin);
+        assert_eq!(
+            policy_for(&Method::POST, "/api/v1/usage/billing/2026-01/export"),
+            RoutePolicy::Admin
+        );
+        assert_eq!(
+            policy_for(&Method::POST, "/api/v1/bounties/42/rehydrate"),
+            RoutePolicy::Admin
+        );
+        assert_eq!(policy_for(&Method::PUT, "/api/v1/admin/roles/triager"), RoutePolicy::Admin);
+        assert_eq!(
+            policy_for(&Method::POST, "/api/v1/admin/moderation/bounties/42/takedown"),
+            RoutePolicy::Admin
+        );
+        assert_eq!(policy_for(&Method::PUT, "/api/v1/tags/ransomware"), RoutePolicy::Admin);
+        assert_eq!(
+            policy_for(&Method::PUT, "/api/v1/usage/organizations/42/quota"),
+            RoutePolicy::Admin
+        );
+    }
+
+    #[test]
+    fn test_scoped_routes() {
+        assert_eq!(
+            policy_for(&Method::POST, "/api/v1/analysis/submit"),
+            RoutePolicy::Scope(SCOPE_ANALYSIS_SUBMIT)
+        );
+        assert_eq!(
+            policy_for(&Method::POST, "/api/v1/analysis/file"),
+            RoutePolicy::Scope(SCOPE_ANALYSIS_SUBMIT)
+        );
+        assert_eq!(
+            policy_for(&Method::POST, "/api/v1/submissions/42/verify"),
+            RoutePolicy::Scope(SCOPE_SUBMISSIONS_VERIFY)
+        );
+        assert_eq!(
+            policy_for(&Method::DELETE, "/api/v1/webhooks/7"),
+            RoutePolicy::Scope(SCOPE_WEBHOOKS_MANAGE)
+        );
+        assert_eq!(
+            policy_for(&Method::GET, "/api/v1/reputation/webhooks"),
+            RoutePolicy::Scope(SCOPE_WEBHOOKS_MANAGE)
+        );
+        assert_eq!(
+            policy_for(&Method::GET, "/api/v1/bounties/42/webhooks"),
+            RoutePolicy::Scope(SCOPE_WEBHOOKS_MANAGE)
+        );
+        // A parameter only matches a single segment
+        assert_eq!(
+            policy_for(&Method::POST, "/api/v1/submissions/42/extra/verify"),
+            RoutePolicy::Authenticated
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, Method, Request},
+    middleware::Next,
+    response::Response,
+};
+use std::time::Instant;
+use tracing::warn;
+
+use crate::middleware::auth::Claims;
+use crate::services::usage::UsageMetric;
+use crate::utils::AuthContext;
+use crate::AppState;
+
+/// Route prefixes whose write requests front analysis work
+const ANALYSIS_PREFIXES: [&str; 2] = ["/analysis", "/submissions"];
+
+/// Usage metering middleware (must be used after an auth middleware).
+///
+/// Every authenticated request counts as one API call. Successful uploads
+/// add their body size to storage, and successful writes to analysis routes
+/// add their wall-clock time to analysis usage. Counters are recorded off the
+/// request path so metering never adds latency or fails a request.
+pub async fn usage_metering_middleware(
+    State(state): State<AppState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some(user_id) = request.extensions().get::<Claims>().map(|c| c.sub) else {
+        return next.run(request).await;
+    };
+    // API key callers carry their organization; users are looked up
+    let known_organization = request.extensions().get::<AuthContext>().and_then(AuthContext::organization);
+    if !state.config.usage.enabled {
+        return next.run(request).await;
+    }
+
+    let is_write = matches!(*request.method(), Method::POST | Method::PUT);
+    let is_analysis = is_write
+        && ANALYSIS_PREFIXES
+            .iter()
+            .any(|prefix| request.uri().path().starts_with(prefix));
+    let upload_bytes = if is_write {
+        request
+            .headers()
+            .get(header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<i64>().ok())
+            .unwrap_or(0)
+    } else {
+        0
+    };
+
+    let started = Instant::now();
+    let response = next.run(request).await;
+    let elapsed = started.elapsed();
+    let succeeded = response.status().is_success();
+
+    let usage = state.usage.clone();
+    tokio::spawn(async move {
+        let resolved = match known_organization {
+            Some(id) => Ok(Some(id)),
+            None => usage.organization_for_user(user_id).await,
+        };
+        let organization_id = match resolved {
+            Ok(Some(id)) => id,
+            Ok(None) => return,
+            Err(e) => {
+                warn!("Failed to resolve organization for {}: {:#}", user_id, e);
+                return;
+            }
+        };
+
+        let mut records = vec![(UsageMetric::ApiCalls, 1)];
+        if succeeded {
+            records.push((UsageMetric::StorageBytes, upload_bytes));
+            if is_analysis {
+                records.push((UsageMetric::AnalysisSeconds, elapsed.as_secs().max(1) as i64));
+            }
+        }
+
+        for (metric, amount) in records {
+            if let Err(e) = usage.record(organization_id, metric, amount).await {
+                warn!("Failed to meter {} for {}: {:#}", metric.as_str(), organization_id, e);
+            }
+        }
+    });
+
+    response
+}
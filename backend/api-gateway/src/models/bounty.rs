@@ -4,7 +4,7 @@ use sqlx::FromRow;
 use uuid::Uuid;
 
 // Bounty status enum
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, utoipa::ToSchema)]
 #[sqlx(type_name = "bounty_status", rename_all = "lowercase")]
 pub enum BountyStatus {
     Draft,
@@ -17,7 +17,7 @@ pub enum BountyStatus {
 }
 
 // Bounty priority levels
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, utoipa::ToSchema)]
 #[sqlx(type_name = "bounty_priority", rename_all = "lowercase")]
 pub enum BountyPriority {
     Low,
@@ -28,7 +28,7 @@ pub enum BountyPriority {
 }
 
 // Bounty type categories
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, utoipa::ToSchema)]
 #[sqlx(type_name = "bounty_type", rename_all = "lowercase")]
 pub enum BountyType {
     FileAnalysis,
@@ -42,7 +42,7 @@ pub enum BountyType {
 }
 
 // Payment distribution method
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, utoipa::ToSchema)]
 #[sqlx(type_name = "distribution_method", rename_all = "lowercase")]
 pub enum DistributionMethod {
     WinnerTakeAll,
@@ -52,7 +52,7 @@ pub enum DistributionMethod {
 }
 
 // Main bounty record
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, utoipa::ToSchema)]
 pub struct Bounty {
     pub id: Uuid,
     pub creator: Uuid,
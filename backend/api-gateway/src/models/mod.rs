@@ -16,65 +16,13 @@ pub use response::*;
 
 pub mod bounty;
 pub mod user;
+pub mod v2;
 
 // Re-export commonly used types for convenience
 pub use analysis::*;
 pub use bounty::*;
 pub use user::*;
 
-/// Common response wrapper for API endpoints
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ApiResponse<T> {
-    pub success: bool,
-    pub data: Option<T>,
-    pub message: Option<String>,
-    pub timestamp: DateTime<Utc>,
-}
-
-impl<T> ApiResponse<T> {
-    /// Create a successful response with data
-    pub fn success(data: T) -> Self {
-        Self {
-            success: true,
-            data: Some(data),
-            message: None,
-            timestamp: Utc::now(),
-        }
-    }
-
-    /// Create a successful response with data and message
-    pub fn success_with_message(data: T, message: String) -> Self {
-        Self {
-            success: true,
-            data: Some(data),
-            message: Some(message),
-            timestamp: Utc::now(),
-        }
-    }
-}
-
-impl ApiResponse<()> {
-    /// Create an error response
-    pub fn error(message: String) -> Self {
-        Self {
-            success: false,
-            data: None,
-            message: Some(message),
-            timestamp: Utc::now(),
-        }
-    }
-
-    /// Create a simple success response without data
-    pub fn success_empty() -> Self {
-        Self {
-            success: true,
-            data: None,
-            message: None,
-            timestamp: Utc::now(),
-        }
-    }
-}
-
 /// Pagination parameters for list endpoints
 #[derive(Debug, Deserialize)]
 pub struct PaginationParams {
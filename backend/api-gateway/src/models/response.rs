@@ -1,13 +1,17 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use shared::observability::current_request_id;
 use uuid::Uuid;
 
 /// Generic API response wrapper
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct ApiResponse<T> {
     pub success: bool,
     pub data: Option<T>,
     pub message: Option<String>,
+    /// `X-Request-Id` of the request; quote it when reporting a problem
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
     pub timestamp: DateTime<Utc>,
 }
 
@@ -18,6 +22,7 @@ impl<T> ApiResponse<T> {
             success: true,
             data: Some(data),
             message: None,
+            request_id: current_request_id(),
             timestamp: Utc::now(),
         }
     }
@@ -28,6 +33,7 @@ impl<T> ApiResponse<T> {
             success: true,
             data: Some(data),
             message: Some(message.into()),
+            request_id: current_request_id(),
             timestamp: Utc::now(),
         }
     }
@@ -40,6 +46,7 @@ impl ApiResponse<()> {
             success: false,
             data: None,
             message: Some(message.into()),
+            request_id: current_request_id(),
             timestamp: Utc::now(),
         }
     }
@@ -50,6 +57,7 @@ impl ApiResponse<()> {
             success: true,
             data: None,
             message: None,
+            request_id: current_request_id(),
             timestamp: Utc::now(),
         }
     }
@@ -60,6 +68,7 @@ impl ApiResponse<()> {
             success: true,
             data: None,
             message: Some(message.into()),
+            request_id: current_request_id(),
             timestamp: Utc::now(),
         }
     }
@@ -461,6 +470,18 @@ mod tests {
         assert_eq!(response.data, Some("test data"));
     }
 
+    #[tokio::test]
+    async fn test_api_response_records_request_id() {
+        use shared::observability::{with_log_context, LogContext};
+
+        let json = serde_json::to_value(ApiResponse::success_empty()).unwrap();
+        assert!(json.get("request_id").is_none());
+
+        let context = LogContext::from_headers(Some("req-42"), None);
+        let response = with_log_context(context, async { ApiResponse::error("Payout failed") }).await;
+        assert_eq!(response.request_id.as_deref(), Some("req-42"));
+    }
+
     #[test]
     fn test_paginated_response() {
         let response = PaginatedResponse::new(
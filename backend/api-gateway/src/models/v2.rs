@@ -0,0 +1,345 @@
+//! Models of API v2
+//!
+//! Every v2 response uses one envelope, [`ApiResponse`]: the payload under
+//! `data` and, on lists, a `pagination` block. Lists are paged with opaque
+//! keyset cursors (newest first), so pages stay stable while rows are
+//! inserted. Errors are RFC 7807 problem details ([`Problem`]) served as
+//! `application/problem+json`.
+//!
+//! Resources are read from the platform tables directly; the `*_COLUMNS`
+//! constants are the projection each model is decoded from.
+
+use axum::{
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use shared::observability::current_request_id;
+use sqlx::FromRow;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+pub const PROBLEM_CONTENT_TYPE: &str = "application/problem+json";
+
+const DEFAULT_PAGE_SIZE: u32 = 20;
+const MAX_PAGE_SIZE: u32 = 100;
+
+// ─── Envelope and pagination ────────────────────────────────────
+
+/// Envelope of every successful v2 response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ApiResponse<T> {
+    pub data: T,
+    /// Present on list responses
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pagination: Option<Pagination>,
+    /// `X-Request-Id` of the request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+impl<T> ApiResponse<T> {
+    pub fn new(data: T) -> Self {
+        Self { data, pagination: None, request_id: current_request_id() }
+    }
+}
+
+impl<T> ApiResponse<Vec<T>> {
+    /// Page of at most `limit` rows out of `rows`, which holds one extra
+    /// row when there is a next page
+    pub fn page(mut rows: Vec<T>, limit: i64, cursor: impl Fn(&T) -> Cursor) -> Self {
+        let has_more = rows.len() as i64 > limit;
+        rows.truncate(limit as usize);
+        let next_cursor = has_more
+            .then(|| rows.last().map(|row| cursor(row).encode()))
+            .flatten();
+        Self {
+            data: rows,
+            pagination: Some(Pagination { next_cursor, has_more, limit: limit as u32 }),
+            request_id: current_request_id(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Pagination {
+    /// Pass as `cursor` to fetch the next page; absent on the last page
+    pub next_cursor: Option<String>,
+    pub has_more: bool,
+    pub limit: u32,
+}
+
+/// Query parameters of paged lists
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CursorParams {
+    /// `next_cursor` of the previous page
+    pub cursor: Option<String>,
+    /// Page size, 1 to 100 (default 20)
+    pub limit: Option<u32>,
+}
+
+impl CursorParams {
+    pub fn limit(&self) -> i64 {
+        self.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE) as i64
+    }
+
+    pub fn position(&self) -> Result<Option<Cursor>, Problem> {
+        self.cursor.as_deref().map(Cursor::decode).transpose()
+    }
+}
+
+/// Position after the last row of a page: its `(created_at, id)` key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl Cursor {
+    pub fn encode(&self) -> String {
+        // Microseconds match Postgres timestamp precision, so the key
+        // round-trips exactly
+        let created_at = self.created_at.to_rfc3339_opts(SecondsFormat::Micros, true);
+        URL_SAFE_NO_PAD.encode(format!("{}|{}", created_at, self.id))
+    }
+
+    pub fn decode(cursor: &str) -> Result<Self, Problem> {
+        let invalid = || Problem::bad_request("Invalid pagination cursor");
+        let raw = URL_SAFE_NO_PAD.decode(cursor).map_err(|_| invalid())?;
+        let raw = String::from_utf8(raw).map_err(|_| invalid())?;
+        let (created_at, id) = raw.split_once('|').ok_or_else(invalid)?;
+        Ok(Self {
+            created_at: DateTime::parse_from_rfc3339(created_at)
+                .map_err(|_| invalid())?
+                .with_timezone(&Utc),
+            id: id.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+// ─── Problem details ────────────────────────────────────────────
+
+/// RFC 7807 problem details, the body of every v2 error
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct Problem {
+    /// `about:blank`: the status code says everything there is to say
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    /// Extension member: `X-Request-Id` of the failed request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+impl Problem {
+    pub fn new(status: StatusCode, detail: Option<String>) -> Self {
+        Self {
+            problem_type: "about:blank".to_string(),
+            title: status.canonical_reason().unwrap_or("Error").to_string(),
+            status: status.as_u16(),
+            detail,
+            instance: None,
+            request_id: current_request_id(),
+        }
+    }
+
+    pub fn bad_request(detail: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, Some(detail.into()))
+    }
+
+    pub fn not_found(detail: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, Some(detail.into()))
+    }
+
+    pub fn internal() -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, None)
+    }
+
+    pub fn with_instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+}
+
+impl From<sqlx::Error> for Problem {
+    fn from(err: sqlx::Error) -> Self {
+        tracing::error!("Database error: {}", err);
+        Self::internal()
+    }
+}
+
+impl IntoResponse for Problem {
+    fn into_response(self) -> Response {
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let mut response = (status, Json(self)).into_response();
+        response
+            .headers_mut()
+            .insert(header::CONTENT_TYPE, HeaderValue::from_static(PROBLEM_CONTENT_TYPE));
+        response
+    }
+}
+
+// ─── Resources ──────────────────────────────────────────────────
+
+pub const BOUNTY_COLUMNS: &str = "id, creator_id, submission_id, title, description, \
+    reward_amount::TEXT AS reward_amount, \
+    COALESCE(status, bounty_status, 'active') AS status, \
+    deadline, COALESCE(participant_count, 0) AS participant_count, \
+    created_at, completed_at";
+
+pub const SUBMISSION_COLUMNS: &str = "id, submitter_id, submission_type, file_hash, url, \
+    original_filename, file_size, mime_type, is_malicious, \
+    confidence_score::FLOAT8 AS confidence_score, \
+    COALESCE(analysis_status, 'pending') AS analysis_status, created_at";
+
+pub const ANALYSIS_COLUMNS: &str = "id, bounty_id, submission_id, engine_id, verdict, \
+    confidence_score::FLOAT8 AS confidence_score, \
+    COALESCE(threat_types, '{}') AS threat_types, \
+    COALESCE(analysis_status, 'completed') AS status, created_at, completed_at";
+
+pub const USER_COLUMNS: &str = "id, username, \
+    COALESCE(reputation_score, 0) AS reputation_score, \
+    COALESCE(total_submissions, 0) AS total_submissions, \
+    COALESCE(successful_submissions, 0) AS successful_submissions, \
+    created_at";
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[schema(as = v2::Bounty)]
+pub struct Bounty {
+    pub id: Uuid,
+    pub creator_id: Uuid,
+    /// The sample the bounty is for
+    pub submission_id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    /// Decimal amount in wei
+    pub reward_amount: String,
+    /// `active`, `completed`, `expired` or `cancelled`
+    pub status: String,
+    pub deadline: Option<DateTime<Utc>>,
+    pub participant_count: i32,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[schema(as = v2::Submission)]
+pub struct Submission {
+    pub id: Uuid,
+    pub submitter_id: Uuid,
+    /// `file` or `url`
+    pub submission_type: String,
+    pub file_hash: Option<String>,
+    pub url: Option<String>,
+    pub original_filename: Option<String>,
+    pub file_size: Option<i64>,
+    pub mime_type: Option<String>,
+    /// Final consensus, once reached
+    pub is_malicious: Option<bool>,
+    pub confidence_score: Option<f64>,
+    /// `pending`, `analyzing`, `resumable`, `completed` or `failed`
+    pub analysis_status: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[schema(as = v2::Analysis)]
+pub struct Analysis {
+    pub id: Uuid,
+    pub bounty_id: Option<Uuid>,
+    pub submission_id: Uuid,
+    pub engine_id: Uuid,
+    /// `malicious`, `benign`, `suspicious` or `unknown`
+    pub verdict: String,
+    pub confidence_score: f64,
+    pub threat_types: Vec<String>,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+#[schema(as = v2::User)]
+pub struct User {
+    pub id: Uuid,
+    pub username: String,
+    pub reputation_score: i32,
+    pub total_submissions: i32,
+    pub successful_submissions: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cursor() -> Cursor {
+        Cursor {
+            created_at: DateTime::parse_from_rfc3339("2026-10-18T09:30:00.123456Z")
+                .unwrap()
+                .with_timezone(&Utc),
+            id: Uuid::new_v4(),
+        }
+    }
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let cursor = cursor();
+        assert_eq!(Cursor::decode(&cursor.encode()).unwrap(), cursor);
+    }
+
+    #[test]
+    fn test_invalid_cursor_is_bad_request() {
+        for raw in ["not base64!", "bm8gc2VwYXJhdG9y", ""] {
+            let problem = Cursor::decode(raw).unwrap_err();
+            assert_eq!(problem.status, 400, "{}", raw);
+        }
+    }
+
+    #[test]
+    fn test_page_sets_next_cursor_only_when_more_rows() {
+        let rows: Vec<Cursor> = (0..3).map(|_| cursor()).collect();
+
+        let page = ApiResponse::page(rows.clone(), 2, |c| *c);
+        let pagination = page.pagination.unwrap();
+        assert_eq!(page.data.len(), 2);
+        assert!(pagination.has_more);
+        assert_eq!(pagination.next_cursor, Some(rows[1].encode()));
+
+        let page = ApiResponse::page(rows, 3, |c| *c);
+        let pagination = page.pagination.unwrap();
+        assert!(!pagination.has_more);
+        assert!(pagination.next_cursor.is_none());
+    }
+
+    #[test]
+    fn test_problem_serializes_as_rfc7807() {
+        let problem = Problem::not_found("Bounty not found").with_instance("/api/v2/bounties/1");
+        let json = serde_json::to_value(&problem).unwrap();
+        assert_eq!(json["type"], "about:blank");
+        assert_eq!(json["title"], "Not Found");
+        assert_eq!(json["status"], 404);
+        assert_eq!(json["detail"], "Bounty not found");
+        assert_eq!(json["instance"], "/api/v2/bounties/1");
+
+        let response = problem.into_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], PROBLEM_CONTENT_TYPE);
+    }
+
+    #[test]
+    fn test_page_size_is_clamped() {
+        assert_eq!(CursorParams::default().limit(), 20);
+        assert_eq!(CursorParams { limit: Some(0), ..Default::default() }.limit(), 1);
+        assert_eq!(CursorParams { limit: Some(500), ..Default::default() }.limit(), 100);
+    }
+}
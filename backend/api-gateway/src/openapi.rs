@@ -0,0 +1,447 @@
+//! OpenAPI specification of the gateway API
+//!
+//! Handlers carry `#[utoipa::path]` annotations and their DTOs derive
+//! `ToSchema`; [`ApiDoc`] collects them. Security requirements are not
+//! written on the handlers but derived from the route policy table, so the
+//! spec cannot drift from what the auth middleware enforces. The spec and a
+//! Swagger UI are served at `/api/v1/openapi.json` and `/api/v1/docs`.
+
+use axum::http::Method;
+use axum::Router;
+use serde::Serialize;
+use utoipa::openapi::path::Operation;
+use utoipa::openapi::Deprecated;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityRequirement, SecurityScheme};
+use utoipa::{Modify, OpenApi, ToSchema};
+use utoipa_swagger_ui::SwaggerUi;
+
+use crate::handlers::{
+    analysis, auth, bounty, flags, graphql, health, proxy, rbac, realtime, reputation, submission, usage, user,
+    v2, wallet, webhook,
+};
+use crate::middleware::api_version::v1_successor;
+use crate::middleware::route_policy::{policy_for, RoutePolicy};
+use crate::services::realtime::ClientMessage;
+
+pub const DOCS_PATH: &str = "/api/v1/docs";
+pub const SPEC_PATH: &str = "/api/v1/openapi.json";
+
+const BEARER_SCHEME: &str = "bearer";
+
+/// Body of every error response produced by `ApiError`
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorResponse {
+    /// Always `false`
+    pub success: bool,
+    pub error: String,
+    /// Always `null`
+    pub data: Option<serde_json::Value>,
+}
+
+/// Body of errors raised by the gateway while proxying to a backend service
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProxyErrorResponse {
+    pub error: String,
+    pub details: String,
+}
+
+/// Multipart body of file uploads; documentation only, uploads are streamed
+/// to the upstream untouched
+#[derive(ToSchema)]
+#[allow(dead_code)]
+pub struct FileUpload {
+    #[schema(value_type = String, format = Binary)]
+    pub file: Vec<u8>,
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Nexus Security API",
+        description = "Public API of the Nexus Security gateway. Routes are also mounted under `/api`."
+    ),
+    paths(
+        health::health_check,
+        health::readiness_check,
+        health::liveness_check,
+        health::metrics,
+        auth::register,
+        auth::login,
+        auth::logout,
+        auth::refresh_token,
+        auth::verify_token,
+        auth::verify_email,
+        auth::forgot_password,
+        auth::reset_password,
+        auth::generate_api_key,
+        auth::collect_wallet,
+        auth::disconnect_wallet,
+        auth::list_sessions,
+        auth::revoke_session,
+        auth::revoke_all_sessions,
+        auth::jwks,
+        bounty::list_bounties,
+        bounty::create_bounty,
+        bounty::get_bounty,
+        bounty::update_bounty,
+        bounty::get_bounty_stats,
+        bounty::list_active_bounties,
+        bounty::list_completed_bounties,
+        bounty::cancel_bounty,
+        bounty::extend_bounty,
+        bounty::claim_reward,
+        bounty::submit_analysis,
+        bounty::finalize_bounty,
+        analysis::list_analyses,
+        analysis::get_analysis,
+        analysis::get_analysis_details,
+        analysis::get_analysis_stats,
+        analysis::get_analyses_by_bounty,
+        analysis::get_analyses_by_hash,
+        analysis::submit_analysis,
+        analysis::dispute_analysis,
+        proxy::analyze_file,
+        proxy::analyze_url,
+        proxy::analyze_hash,
+        proxy::get_engine_result,
+        proxy::get_engine_result_detailed,
+        proxy::get_engines_status,
+        proxy::list_archived_bounties,
+        proxy::rehydrate_bounty,
+        proxy::get_bounty_embargo,
+        proxy::place_bounty_embargo,
+        proxy::release_bounty_embargo,
+        proxy::fund_bounty,
+        proxy::get_deposit_verification,
+        proxy::list_bounty_participants,
+        proxy::join_bounty,
+        proxy::withdraw_from_bounty,
+        proxy::list_bounty_webhooks,
+        proxy::register_bounty_webhook,
+        proxy::delete_bounty_webhook,
+        proxy::list_bounty_webhook_deliveries,
+        proxy::report_bounty,
+        proxy::submit_bounty_verdict,
+        proxy::commit_bounty_verdict,
+        proxy::reveal_bounty_verdict,
+        proxy::export_bounties,
+        proxy::list_bounty_exports,
+        proxy::get_bounty_export,
+        proxy::download_bounty_export,
+        proxy::get_bounty_manager_stats,
+        proxy::list_creator_stats,
+        proxy::get_creator_stats,
+        proxy::list_standing_bounties,
+        proxy::create_standing_bounty,
+        proxy::get_standing_bounty,
+        proxy::set_standing_bounty_enabled,
+        proxy::list_tags,
+        proxy::get_tag_stats,
+        proxy::upsert_tag,
+        proxy::delete_tag,
+        proxy::list_tagged_bounties,
+        proxy::list_moderation_queue,
+        proxy::get_moderation_case,
+        proxy::hold_bounty,
+        proxy::approve_bounty,
+        proxy::takedown_bounty,
+        proxy::list_calibration_bounties,
+        proxy::mark_calibration_bounty,
+        proxy::list_calibration_artifacts,
+        proxy::add_calibration_artifact,
+        proxy::set_calibration_artifact_active,
+        proxy::submit_file,
+        proxy::submit_url,
+        proxy::list_reputation_webhooks,
+        proxy::register_reputation_webhook,
+        proxy::delete_reputation_webhook,
+        proxy::list_reputation_webhook_deliveries,
+        proxy::list_reputation_appeals,
+        proxy::file_reputation_appeal,
+        proxy::get_reputation_appeal,
+        proxy::add_reputation_appeal_evidence,
+        proxy::withdraw_reputation_appeal,
+        proxy::vote_on_reputation_appeal,
+        reputation::get_leaderboard,
+        reputation::get_top_analysts,
+        reputation::get_user_reputation,
+        reputation::get_reputation_history,
+        reputation::list_available_badges,
+        reputation::claim_badge,
+        submission::list_submissions,
+        submission::create_submission,
+        submission::get_submission,
+        submission::vote_on_submission,
+        submission::verify_submission,
+        submission::get_my_submissions,
+        user::get_current_user,
+        user::update_profile,
+        user::get_user_stats,
+        user::get_user_by_id,
+        user::get_user_stats_by_id,
+        user::list_api_keys,
+        user::revoke_api_key,
+        wallet::connect_wallet,
+        wallet::disconnect_wallet,
+        wallet::get_balance,
+        wallet::get_balance_by_address,
+        wallet::stake_tokens,
+        wallet::unstake_tokens,
+        wallet::get_transactions,
+        wallet::claim_rewards,
+        webhook::list_webhooks,
+        webhook::create_webhook,
+        webhook::get_webhook,
+        webhook::update_webhook,
+        webhook::delete_webhook,
+        webhook::test_webhook,
+        webhook::get_webhook_deliveries,
+        webhook::list_available_events,
+        usage::get_usage,
+        usage::get_billing_report,
+        usage::export_billing,
+        usage::get_quota,
+        usage::get_organization_quota,
+        usage::put_organization_quota,
+        realtime::websocket,
+        rbac::list_permissions,
+        rbac::list_roles,
+        rbac::put_role,
+        rbac::delete_role,
+        rbac::list_user_roles,
+        rbac::assign_user_role,
+        rbac::revoke_user_role,
+        rbac::list_organization_roles,
+        rbac::assign_organization_role,
+        rbac::revoke_organization_role,
+        flags::list_flags,
+        flags::get_flag,
+        flags::put_flag,
+        graphql::graphql,
+        graphql::graphql_schema,
+        v2::list_bounties,
+        v2::get_bounty,
+        v2::list_bounty_analyses,
+        v2::list_submissions,
+        v2::get_submission,
+        v2::get_current_user,
+        v2::get_user,
+    ),
+    components(schemas(ErrorResponse, ProxyErrorResponse, ClientMessage)),
+    modifiers(&SecurityFromRoutePolicy, &DeprecateSupersededV1),
+    tags(
+        (name = "health", description = "Liveness, readiness and dependency health"),
+        (name = "auth", description = "Accounts, sessions and wallet linking"),
+        (name = "bounties", description = "Malware analysis bounties"),
+        (name = "analysis", description = "Analyses and engine results"),
+        (name = "submissions", description = "Engine verdicts and file submissions"),
+        (name = "reputation", description = "Leaderboard, reputation history and badges"),
+        (name = "users", description = "Profiles, statistics and API keys"),
+        (name = "wallet", description = "Token balances, staking and linked wallets"),
+        (name = "webhooks", description = "Webhook subscriptions and deliveries"),
+        (name = "usage", description = "Metered usage, organization quotas and billing"),
+        (name = "realtime", description = "WebSocket stream of platform events"),
+        (name = "admin", description = "Roles, permissions, feature flags and maintenance mode"),
+        (name = "dashboard", description = "GraphQL queries composing dashboard data"),
+        (name = "v2", description = "API v2: one response envelope, cursor pagination and problem details errors"),
+    )
+)]
+pub struct ApiDoc;
+
+/// Registers the bearer scheme and sets each operation's security from
+/// [`policy_for`]. Public routes accept a token but do not need one.
+struct SecurityFromRoutePolicy;
+
+impl Modify for SecurityFromRoutePolicy {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            BEARER_SCHEME,
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .description(Some("Access token from `/api/v1/auth/login`"))
+                    .build(),
+            ),
+        );
+
+        for (path, item) in openapi.paths.paths.iter_mut() {
+            let operations = [
+                (Method::GET, &mut item.get),
+                (Method::POST, &mut item.post),
+                (Method::PUT, &mut item.put),
+                (Method::DELETE, &mut item.delete),
+                (Method::PATCH, &mut item.patch),
+            ];
+            for (method, operation) in operations {
+                if let Some(operation) = operation {
+                    apply_policy(operation, policy_for(&method, path));
+                }
+            }
+        }
+    }
+}
+
+fn apply_policy(operation: &mut Operation, policy: RoutePolicy) {
+    let bearer = SecurityRequirement::new(BEARER_SCHEME, Vec::<String>::new());
+    let requirement = match policy {
+        RoutePolicy::Public => vec![SecurityRequirement::default(), bearer],
+        RoutePolicy::Authenticated => vec![bearer],
+        RoutePolicy::Admin => {
+            append_description(operation, "Requires the `admin:console` permission.");
+            vec![bearer]
+        }
+        RoutePolicy::Scope(scope) => {
+            append_description(operation, &format!("Requires the `{}` scope.", scope));
+            vec![bearer]
+        }
+    };
+    operation.security = Some(requirement);
+}
+
+/// Marks v1 operations that have a v2 successor as deprecated, matching the
+/// headers `api_version::deprecation_middleware` sends
+struct DeprecateSupersededV1;
+
+impl Modify for DeprecateSupersededV1 {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        for (path, item) in openapi.paths.paths.iter_mut() {
+            if !path.starts_with("/api/v1/") {
+                continue;
+            }
+            let operations = [
+                (Method::GET, &mut item.get),
+                (Method::POST, &mut item.post),
+                (Method::PUT, &mut item.put),
+                (Method::DELETE, &mut item.delete),
+                (Method::PATCH, &mut item.patch),
+            ];
+            for (method, operation) in operations {
+                let Some(operation) = operation else { continue };
+                if let Some(successor) = v1_successor(&method, path) {
+                    operation.deprecated = Some(Deprecated::True);
+                    append_description(operation, &format!("Deprecated: use `{} {}`.", method, successor));
+                }
+            }
+        }
+    }
+}
+
+fn append_description(operation: &mut Operation, note: &str) {
+    operation.description = Some(match operation.description.take() {
+        Some(description) if !description.is_empty() => format!("{}\n\n{}", description, note),
+        _ => note.to_string(),
+    });
+}
+
+/// Swagger UI at [`DOCS_PATH`] backed by the spec at [`SPEC_PATH`]
+pub fn docs_router<S>() -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    Router::new().merge(SwaggerUi::new(DOCS_PATH).url(SPEC_PATH, ApiDoc::openapi()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use utoipa::openapi::path::Operation;
+
+    fn operations(spec: &utoipa::openapi::OpenApi) -> Vec<(Method, String, Operation)> {
+        let mut operations = Vec::new();
+        for (path, item) in &spec.paths.paths {
+            for (method, operation) in [
+                (Method::GET, &item.get),
+                (Method::POST, &item.post),
+                (Method::PUT, &item.put),
+                (Method::DELETE, &item.delete),
+                (Method::PATCH, &item.patch),
+            ] {
+                if let Some(operation) = operation {
+                    operations.push((method, path.clone(), operation.clone()));
+                }
+            }
+        }
+        operations
+    }
+
+    #[test]
+    fn test_spec_covers_core_routes() {
+        let spec = ApiDoc::openapi();
+        for path in [
+            "/api/v1/health",
+            "/api/v1/auth/login",
+            "/api/v1/bounties",
+            "/api/v1/bounties/{bounty_id}",
+            "/api/v1/analysis/{analysis_id}",
+            "/api/v2/bounties",
+            "/api/v2/bounties/{bounty_id}/analyses",
+        ] {
+            assert!(spec.paths.paths.contains_key(path), "missing {}", path);
+        }
+
+        let schemas = &spec.components.as_ref().unwrap().schemas;
+        for schema in ["ErrorResponse", "Bounty", "AuthResponse", "AnalysisSummary", "v2.Bounty", "Problem"] {
+            assert!(schemas.contains_key(schema), "missing schema {}", schema);
+        }
+
+        serde_json::to_string(&spec).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_spec_served_next_to_api() {
+        use axum::{body::Body, http::Request, http::StatusCode, routing::get};
+        use tower::Service;
+
+        let app: Router = Router::new()
+            .merge(docs_router())
+            .nest("/api/v1", Router::new().route("/health", get(|| async { "ok" })));
+
+        for path in [SPEC_PATH, "/api/v1/docs/", "/api/v1/health"] {
+            let request = Request::get(path).body(Body::empty()).unwrap();
+            let response = app.clone().call(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK, "{}", path);
+        }
+    }
+
+    #[test]
+    fn test_superseded_v1_operations_are_deprecated() {
+        let spec = ApiDoc::openapi();
+        let deprecated = |path: &str| {
+            let operation = spec.paths.paths[path].get.as_ref().unwrap();
+            operation.deprecated == Some(Deprecated::True)
+        };
+        assert!(deprecated("/api/v1/bounties/{bounty_id}"));
+        assert!(deprecated("/api/v1/users/me"));
+        assert!(!deprecated("/api/v1/bounties/{bounty_id}/stats"));
+        assert!(!deprecated("/api/v2/bounties/{bounty_id}"));
+    }
+
+    #[test]
+    fn test_operation_ids_are_unique() {
+        let spec = ApiDoc::openapi();
+        let mut seen = HashSet::new();
+        for (method, path, operation) in operations(&spec) {
+            let id = operation.operation_id.expect("operation id");
+            assert!(seen.insert(id.clone()), "duplicate operation id {} ({} {})", id, method, path);
+        }
+    }
+
+    #[test]
+    fn test_security_follows_route_policy() {
+        let spec = ApiDoc::openapi();
+        for (method, path, operation) in operations(&spec) {
+            let security = operation.security.expect("security requirement");
+            let anonymous = security.contains(&SecurityRequirement::default());
+            assert_eq!(
+                anonymous,
+                policy_for(&method, &path) == RoutePolicy::Public,
+                "{} {}",
+                method,
+                path
+            );
+        }
+    }
+}
@@ -1,12 +1,30 @@
 pub mod v1;
+pub mod v2;
 
-use axum::Router;
+use axum::{middleware, routing::get, Router};
 
-use crate::AppState;
+use crate::handlers::auth;
+use crate::middleware::api_version;
+use crate::{openapi, AppState};
 
-/// Create the main router with API v1
+/// Create the main router with APIs v1 and v2, their documentation and the
+/// JWKS. v1 responses carry deprecation headers where v2 has a successor.
 pub fn create_router(state: AppState) -> Router {
+    let well_known = Router::new()
+        .route("/.well-known/jwks.json", get(auth::jwks))
+        .with_state(state.clone());
+
+    let v1_routes = || {
+        v1::create_routes(state.clone()).layer(middleware::from_fn_with_state(
+            state.clone(),
+            api_version::deprecation_middleware,
+        ))
+    };
+
     Router::new()
-        .nest("/api/v1", v1::create_routes(state.clone()))
-        .nest("/api", v1::create_routes(state))
+        .merge(openapi::docs_router())
+        .merge(well_known)
+        .nest("/api/v1", v1_routes())
+        .nest("/api/v2", v2::create_routes(state.clone()))
+        .nest("/api", v1_routes())
 }
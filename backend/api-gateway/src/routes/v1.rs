@@ -6,52 +6,109 @@ use axum::{
 
 use crate::{
     handlers::{
-        analysis, auth, bounty, health, reputation, submission, user, wallet, webhook,
+        analysis, auth, bounty, flags, graphql, health, proxy, rbac, realtime, reputation, submission, usage,
+        user, wallet, webhook,
+    },
+    middleware::{
+        auth::{self as auth_mw, require_permission},
+        csrf as csrf_mw,
+        feature_flags as flags_mw,
+        idempotency as idempotency_mw,
+        quota as quota_mw,
+        rate_limiter as rate_limit_mw,
+        route_policy::{SCOPE_BOUNTY_CREATE, SCOPE_BOUNTY_MANAGE, SCOPE_ROLES_MANAGE},
+        usage as usage_mw,
     },
-    middleware::auth as auth_mw,
     AppState,
 };
 
 /// Create all routes for API v1
 ///
-/// Auth strategy:
-///   - Public groups (health, auth): no auth layer
-///   - Mixed groups (bounties, analysis, reputation): optional_auth — GETs work
-///     anonymously, POSTs that extract `Claims` still return 401 if no token
-///   - Protected groups (users, wallet, submissions, webhooks): strict auth —
-///     all requests without a valid JWT are rejected with 401
+/// Auth strategy: a single auth layer wraps every route and classifies the
+/// request against the policy table in `middleware::route_policy` (public,
+/// authenticated, admin or scope-based) *before* looking at the token.
+/// Routes missing from the table require authentication.
+///
+/// Authenticated requests outside health/auth are metered per organization;
+/// the usage layer sits inside auth so it sees the claims.
+///
+/// Rate limiting is a Redis sliding window shared across gateway instances.
+/// It also sits inside auth so the caller's tier (`AuthContext`) is known,
+/// and outside usage so rejected requests are not billed. Credential
+/// endpoints get a stricter per-IP budget.
+///
+/// Organizations with quotas (`services::quota`) also share a pooled
+/// window and daily submission and analysis budgets across their members
+/// and API keys; that layer sits between rate limiting and usage.
+///
+/// POSTs carrying an `Idempotency-Key` replay their first response to
+/// retries (`middleware::idempotency`). That layer is innermost so a replay
+/// is still rate limited and metered like any other call.
+///
+/// Permissions come from the caller's roles (`services::rbac`). Routes whose
+/// path does not say what they need add `require_permission` on top.
+///
+/// `/graphql` composes the dashboard's data in one query (`crate::graphql`)
+/// and is metered like the REST routes it replaces.
+///
+/// Routes backed by another service (`handlers::proxy`) go through the same
+/// layers and are then streamed to the upstream.
+///
+/// Browsers may authenticate with the session cookies instead of a Bearer
+/// token; unsafe requests made that way must pass the CSRF check
+/// (`middleware::csrf`), which sits just inside auth.
+///
+/// Maintenance mode and per-group switches (`services::feature_flags`) are
+/// checked before anything else and answer 503 with `Retry-After`.
 pub fn create_routes(state: AppState) -> Router {
-    // ── Public routes (no auth) ──────────────────────────
-    let public_routes = Router::new()
-        .nest("/health", health_routes())
-        .nest("/auth", auth_routes());
-
-    // ── Mixed routes (optional auth) ─────────────────────
-    let mixed_routes = Router::new()
+    let metered_routes = Router::new()
         .nest("/bounties", bounty_routes())
+        .nest("/standing-bounties", standing_bounty_routes())
+        .nest("/tags", tag_routes())
         .nest("/analysis", analysis_routes())
         .nest("/reputation", reputation_routes())
-        .route_layer(middleware::from_fn_with_state(
-            state.clone(),
-            auth_mw::optional_auth_middleware,
-        ));
-
-    // ── Protected routes (strict auth) ───────────────────
-    let protected_routes = Router::new()
         .nest("/users", user_routes())
         .nest("/wallet", wallet_routes())
         .nest("/submissions", submission_routes())
         .nest("/webhooks", webhook_routes())
+        .nest("/usage", usage_routes())
+        .nest("/admin", admin_routes())
+        .route("/graphql", get(graphql::graphql_schema).post(graphql::graphql))
         .route_layer(middleware::from_fn_with_state(
             state.clone(),
-            auth_mw::auth_middleware,
+            idempotency_mw::idempotency_middleware,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            usage_mw::usage_metering_middleware,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            quota_mw::organization_quota_middleware,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            rate_limit_mw::distributed_rate_limit_middleware,
         ));
 
-    // Merge all route groups and attach state
     Router::new()
-        .merge(public_routes)
-        .merge(mixed_routes)
-        .merge(protected_routes)
+        .nest("/health", health_routes())
+        .route("/ws", get(realtime::websocket))
+        .nest("/auth", auth_routes(&state))
+        .nest("/sessions", session_routes(&state))
+        .merge(metered_routes)
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            csrf_mw::csrf_middleware,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth_mw::auth_middleware,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            flags_mw::feature_flag_middleware,
+        ))
         .with_state(state)
 }
 
@@ -65,39 +122,124 @@ fn health_routes() -> Router<AppState> {
         .route("/metrics", get(health::metrics))
 }
 
-fn auth_routes() -> Router<AppState> {
-    Router::new()
+fn auth_routes(state: &AppState) -> Router<AppState> {
+    // Endpoints that accept credentials — brute-force targets
+    let credential_routes = Router::new()
         .route("/register", post(auth::register))
         .route("/login", post(auth::login))
+        .route("/forgot-password", post(auth::forgot_password))
+        .route("/reset-password", post(auth::reset_password))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            rate_limit_mw::auth_rate_limit_middleware,
+        ));
+
+    Router::new()
         .route("/logout", post(auth::logout))
         .route("/refresh", post(auth::refresh_token))
         .route("/verify", post(auth::verify_token))
         .route("/verify-email", post(auth::verify_email))
-        .route("/forgot-password", post(auth::forgot_password))
-        .route("/reset-password", post(auth::reset_password))
         .route("/api-key", post(auth::generate_api_key))
         .route("/wallet/connect", post(auth::collect_wallet))
         .route("/wallet/disconnect", post(auth::disconnect_wallet))
+        // Also served at `/sessions`
+        .route("/sessions", get(auth::list_sessions))
+        .route("/sessions/:session_id", delete(auth::revoke_session))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            rate_limit_mw::distributed_rate_limit_middleware,
+        ))
+        .merge(credential_routes)
+}
+
+/// The caller's login sessions and devices; not metered, like `/auth`
+fn session_routes(state: &AppState) -> Router<AppState> {
+    Router::new()
+        .route("/", get(auth::list_sessions).delete(auth::revoke_all_sessions))
+        .route("/:session_id", delete(auth::revoke_session))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            rate_limit_mw::distributed_rate_limit_middleware,
+        ))
 }
 
-// ─── Mixed route groups (optional auth) ─────────────────────────
+// ─── Resource route groups ──────────────────────────────────────
 
 fn bounty_routes() -> Router<AppState> {
     Router::new()
-        // Public reads
         .route("/", get(bounty::list_bounties))
         .route("/:bounty_id", get(bounty::get_bounty))
         .route("/:bounty_id/stats", get(bounty::get_bounty_stats))
         .route("/active", get(bounty::list_active_bounties))
         .route("/completed", get(bounty::list_completed_bounties))
-        // Writes — Claims extractor returns 401 if missing from extensions
-        .route("/", post(bounty::create_bounty))
+        .route(
+            "/",
+            post(bounty::create_bounty)
+                .route_layer(middleware::from_fn(require_permission(SCOPE_BOUNTY_CREATE))),
+        )
         .route("/:bounty_id", put(bounty::update_bounty))
         .route("/:bounty_id/cancel", post(bounty::cancel_bounty))
         .route("/:bounty_id/extend", post(bounty::extend_bounty))
         .route("/:bounty_id/claim", post(bounty::claim_reward))
         .route("/:bounty_id/submit", post(bounty::submit_analysis))
-        .route("/:bounty_id/finalize", put(bounty::finalize_bounty))
+        .route(
+            "/:bounty_id/finalize",
+            put(bounty::finalize_bounty)
+                .route_layer(middleware::from_fn(require_permission(SCOPE_BOUNTY_MANAGE))),
+        )
+        // Served by the bounty manager
+        .route("/archived", get(proxy::list_archived_bounties))
+        .route("/:bounty_id/rehydrate", post(proxy::rehydrate_bounty))
+        .route("/:bounty_id/embargo", get(proxy::get_bounty_embargo).put(proxy::place_bounty_embargo))
+        .route("/:bounty_id/embargo/release", post(proxy::release_bounty_embargo))
+        .route("/:bounty_id/funding", post(proxy::fund_bounty))
+        .route("/:bounty_id/deposit-verification", get(proxy::get_deposit_verification))
+        .route(
+            "/:bounty_id/participants",
+            get(proxy::list_bounty_participants).post(proxy::join_bounty),
+        )
+        .route("/:bounty_id/participants/withdraw", post(proxy::withdraw_from_bounty))
+        .route(
+            "/:bounty_id/webhooks",
+            get(proxy::list_bounty_webhooks).post(proxy::register_bounty_webhook),
+        )
+        .route("/:bounty_id/webhooks/:webhook_id", delete(proxy::delete_bounty_webhook))
+        .route(
+            "/:bounty_id/webhooks/:webhook_id/deliveries",
+            get(proxy::list_bounty_webhook_deliveries),
+        )
+        .route("/:bounty_id/report", post(proxy::report_bounty))
+        // The bounty manager's intake; `/submit` above is the on-chain one
+        .route("/:bounty_id/submissions", post(proxy::submit_bounty_verdict))
+        .route("/:bounty_id/commitments", post(proxy::commit_bounty_verdict))
+        .route("/:bounty_id/reveal", post(proxy::reveal_bounty_verdict))
+        .route("/export", get(proxy::export_bounties))
+        .route("/exports", get(proxy::list_bounty_exports))
+        .route("/exports/:export_id", get(proxy::get_bounty_export))
+        .route("/exports/:export_id/download", get(proxy::download_bounty_export))
+        .route("/stats", get(proxy::get_bounty_manager_stats))
+        .route("/stats/creators", get(proxy::list_creator_stats))
+        .route("/stats/creators/:creator", get(proxy::get_creator_stats))
+}
+
+/// Served by the bounty manager
+fn standing_bounty_routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/",
+            get(proxy::list_standing_bounties).post(proxy::create_standing_bounty),
+        )
+        .route("/:standing_id", get(proxy::get_standing_bounty))
+        .route("/:standing_id/enabled", put(proxy::set_standing_bounty_enabled))
+}
+
+/// Served by the bounty manager, which keeps tag edits to admins
+fn tag_routes() -> Router<AppState> {
+    Router::new()
+        .route("/", get(proxy::list_tags))
+        .route("/stats", get(proxy::get_tag_stats))
+        .route("/:slug", put(proxy::upsert_tag).delete(proxy::delete_tag))
+        .route("/:slug/bounties", get(proxy::list_tagged_bounties))
 }
 
 fn analysis_routes() -> Router<AppState> {
@@ -110,6 +252,13 @@ fn analysis_routes() -> Router<AppState> {
         .route("/by-hash/:file_hash", get(analysis::get_analyses_by_hash))
         .route("/submit", post(analysis::submit_analysis))
         .route("/:analysis_id/dispute", post(analysis::dispute_analysis))
+        // Served by the analysis engine
+        .route("/file", post(proxy::analyze_file))
+        .route("/url", post(proxy::analyze_url))
+        .route("/hash", post(proxy::analyze_hash))
+        .route("/results/:analysis_id", get(proxy::get_engine_result))
+        .route("/results/:analysis_id/detailed", get(proxy::get_engine_result_detailed))
+        .route("/engines/status", get(proxy::get_engines_status))
 }
 
 fn reputation_routes() -> Router<AppState> {
@@ -120,10 +269,22 @@ fn reputation_routes() -> Router<AppState> {
         .route("/badges", get(reputation::list_available_badges))
         .route("/history/:user_id", get(reputation::get_reputation_history))
         .route("/claim-badge", post(reputation::claim_badge))
+        .route(
+            "/webhooks",
+            get(proxy::list_reputation_webhooks).post(proxy::register_reputation_webhook),
+        )
+        .route("/webhooks/:webhook_id", delete(proxy::delete_reputation_webhook))
+        .route("/webhooks/:webhook_id/deliveries", get(proxy::list_reputation_webhook_deliveries))
+        .route(
+            "/appeals",
+            get(proxy::list_reputation_appeals).post(proxy::file_reputation_appeal),
+        )
+        .route("/appeals/:appeal_id", get(proxy::get_reputation_appeal))
+        .route("/appeals/:appeal_id/evidence", post(proxy::add_reputation_appeal_evidence))
+        .route("/appeals/:appeal_id/withdraw", post(proxy::withdraw_reputation_appeal))
+        .route("/appeals/:appeal_id/votes", post(proxy::vote_on_reputation_appeal))
 }
 
-// ─── Protected route groups (strict auth) ───────────────────────
-
 fn user_routes() -> Router<AppState> {
     Router::new()
         .route("/me", get(user::get_current_user))
@@ -155,6 +316,9 @@ fn submission_routes() -> Router<AppState> {
         .route("/:submission_id/vote", post(submission::vote_on_submission))
         .route("/:submission_id/verify", post(submission::verify_submission))
         .route("/my-submissions", get(submission::get_my_submissions))
+        // Served by the submission service
+        .route("/file", post(proxy::submit_file))
+        .route("/url", post(proxy::submit_url))
 }
 
 fn webhook_routes() -> Router<AppState> {
@@ -169,3 +333,63 @@ fn webhook_routes() -> Router<AppState> {
         .route("/events", get(webhook::list_available_events))
 }
 
+fn usage_routes() -> Router<AppState> {
+    // Billing and organization quota routes are admin-only via the route
+    // policy table
+    Router::new()
+        .route("/", get(usage::get_usage))
+        .route("/quota", get(usage::get_quota))
+        .route(
+            "/organizations/:organization_id/quota",
+            get(usage::get_organization_quota).put(usage::put_organization_quota),
+        )
+        .route("/billing/:period", get(usage::get_billing_report))
+        .route("/billing/:period/export", post(usage::export_billing))
+}
+
+fn admin_routes() -> Router<AppState> {
+    // Admin-only via the route policy table; role management also needs
+    // `roles:manage`, which moderators do not have
+    let flag_routes = Router::new()
+        .route("/flags", get(flags::list_flags))
+        .route("/flags/:flag", get(flags::get_flag).put(flags::put_flag));
+
+    // Served by the bounty manager
+    let moderation_routes = Router::new()
+        .route("/moderation/queue", get(proxy::list_moderation_queue))
+        .route("/moderation/bounties/:bounty_id", get(proxy::get_moderation_case))
+        .route("/moderation/bounties/:bounty_id/hold", post(proxy::hold_bounty))
+        .route("/moderation/bounties/:bounty_id/approve", post(proxy::approve_bounty))
+        .route("/moderation/bounties/:bounty_id/takedown", post(proxy::takedown_bounty))
+        .route("/calibration/bounties", get(proxy::list_calibration_bounties))
+        .route("/calibration/bounties/:bounty_id", put(proxy::mark_calibration_bounty))
+        .route(
+            "/calibration/artifacts",
+            get(proxy::list_calibration_artifacts).post(proxy::add_calibration_artifact),
+        )
+        .route(
+            "/calibration/artifacts/:artifact_id/active",
+            put(proxy::set_calibration_artifact_active),
+        );
+
+    Router::new()
+        .route("/permissions", get(rbac::list_permissions))
+        .route("/roles", get(rbac::list_roles))
+        .route("/roles/:role", put(rbac::put_role).delete(rbac::delete_role))
+        .route(
+            "/users/:user_id/roles",
+            get(rbac::list_user_roles).post(rbac::assign_user_role),
+        )
+        .route("/users/:user_id/roles/:role", delete(rbac::revoke_user_role))
+        .route(
+            "/organizations/:organization_id/roles",
+            get(rbac::list_organization_roles).post(rbac::assign_organization_role),
+        )
+        .route(
+            "/organizations/:organization_id/roles/:role",
+            delete(rbac::revoke_organization_role),
+        )
+        .route_layer(middleware::from_fn(require_permission(SCOPE_ROLES_MANAGE)))
+        .merge(flag_routes)
+        .merge(moderation_routes)
+}
@@ -0,0 +1,57 @@
+use axum::{middleware, routing::get, Router};
+
+use crate::{
+    handlers::v2,
+    middleware::{
+        api_version, auth as auth_mw, csrf as csrf_mw, feature_flags as flags_mw, quota as quota_mw, rate_limiter as rate_limit_mw,
+        usage as usage_mw,
+    },
+    AppState,
+};
+
+/// Create all routes for API v2
+///
+/// v2 is read-only for now and covers bounties, submissions, analyses and
+/// users; everything else is still served by v1. Requests go through the
+/// same auth, rate limiting, quota and metering layers as v1 (see
+/// `routes::v1::create_routes`), with the same policy table.
+///
+/// Responses use the `models::v2::ApiResponse` envelope, lists are cursor
+/// paged, and every error, including those raised by the shared layers, is
+/// a problem details document (`middleware::api_version`).
+pub fn create_routes(state: AppState) -> Router {
+    Router::new()
+        .route("/bounties", get(v2::list_bounties))
+        .route("/bounties/:bounty_id", get(v2::get_bounty))
+        .route("/bounties/:bounty_id/analyses", get(v2::list_bounty_analyses))
+        .route("/submissions", get(v2::list_submissions))
+        .route("/submissions/:submission_id", get(v2::get_submission))
+        .route("/users/me", get(v2::get_current_user))
+        .route("/users/:user_id", get(v2::get_user))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            usage_mw::usage_metering_middleware,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            quota_mw::organization_quota_middleware,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            rate_limit_mw::distributed_rate_limit_middleware,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            csrf_mw::csrf_middleware,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth_mw::auth_middleware,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            flags_mw::feature_flag_middleware,
+        ))
+        .layer(middleware::from_fn(api_version::problem_details_middleware))
+        .with_state(state)
+}
@@ -0,0 +1,77 @@
+//! Verdict embargoes on the gateway's own reads
+//!
+//! The bounty manager keeps `bounty_verdict_embargoes`. While an embargo is
+//! in force, verdicts of the bounty are shown only to admins, the bounty's
+//! creator and members of the creator's organization. Handlers that read
+//! verdicts straight from the database ask [`withheld`] which of the
+//! bounties they return the caller may not see, and blank those verdicts.
+
+use std::collections::HashSet;
+
+use anyhow::{Context, Result};
+use shared::permissions;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::utils::AuthContext;
+
+/// SQL condition that holds while the verdicts of the bounty in
+/// `bounty_column` are embargoed, for aggregates that cannot be personalized
+pub fn active_embargo(bounty_column: &str) -> String {
+    format!(
+        "EXISTS (SELECT 1 FROM bounty_verdict_embargoes e \
+         WHERE e.bounty_id = {} \
+           AND e.released_at IS NULL \
+           AND (e.embargo_until IS NULL OR e.embargo_until > NOW()))",
+        bounty_column
+    )
+}
+
+/// A caller reading verdicts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Viewer {
+    pub user_id: Uuid,
+    /// Holds bounty:moderate
+    pub is_admin: bool,
+}
+
+impl Viewer {
+    /// `None` for anonymous callers
+    pub fn from_context(caller: Option<&AuthContext>) -> Option<Self> {
+        let caller = caller?;
+        Some(Self {
+            user_id: Uuid::parse_str(&caller.user_id).ok()?,
+            is_admin: caller.has_permission(permissions::BOUNTY_MODERATE),
+        })
+    }
+}
+
+/// Bounties among `bounty_ids` whose verdicts `viewer` may not see
+pub async fn withheld(pool: &PgPool, bounty_ids: &[Uuid], viewer: Option<Viewer>) -> Result<HashSet<Uuid>> {
+    if bounty_ids.is_empty() || viewer.is_some_and(|v| v.is_admin) {
+        return Ok(HashSet::new());
+    }
+
+    let withheld: Vec<Uuid> = sqlx::query_scalar(
+        r#"
+        SELECT e.bounty_id FROM bounty_verdict_embargoes e
+        WHERE e.bounty_id = ANY($1)
+          AND e.released_at IS NULL
+          AND (e.embargo_until IS NULL OR e.embargo_until > NOW())
+          AND (e.creator_user_id = $2) IS NOT TRUE
+          AND (e.organization_id = (SELECT organization_id FROM organization_members WHERE user_id = $2)) IS NOT TRUE
+        "#,
+    )
+    .bind(bounty_ids)
+    .bind(viewer.map(|v| v.user_id))
+    .fetch_all(pool)
+    .await
+    .context("Failed to load verdict embargoes")?;
+
+    Ok(withheld.into_iter().collect())
+}
+
+/// Whether `viewer` may see the verdicts of one bounty
+pub async fn is_withheld(pool: &PgPool, bounty_id: Uuid, viewer: Option<Viewer>) -> Result<bool> {
+    Ok(withheld(pool, &[bounty_id], viewer).await?.contains(&bounty_id))
+}
@@ -0,0 +1,276 @@
+//! Feature flags and maintenance switches of the gateway
+//!
+//! Flags are the shared Redis-backed flags (`shared::feature_flags`), so a
+//! change reaches every gateway instance within the flag cache TTL without a
+//! redeploy. Two kinds matter to the gateway itself:
+//!
+//! - `gateway.maintenance` puts the whole API in maintenance mode; health,
+//!   auth and admin routes stay up so operators can turn it off again, and
+//!   session routes so users can still log out a lost device.
+//! - `gateway.routes.<group>` switches off one route group, e.g. `wallet`
+//!   while a payment incident is handled. Groups are on unless set.
+//!
+//! Disabling a flag can carry a reason and a Retry-After, kept in Redis next
+//! to the flag and sent with the 503 responses. Every change is also
+//! recorded in Postgres (`feature_flag_changes`) for the audit trail.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use redis::aio::MultiplexedConnection;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use shared::feature_flags::{FeatureFlagClient, FlagState, DEFAULT_CACHE_TTL};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::middleware::route_policy::{api_relative, pattern_matches};
+
+pub use shared::feature_flags::is_valid_flag_name;
+
+pub const MAINTENANCE_FLAG: &str = "gateway.maintenance";
+const ROUTE_GROUP_FLAG_PREFIX: &str = "gateway.routes.";
+const NOTICE_KEY_PREFIX: &str = "feature_flag_notice:";
+
+/// Retry-After sent when a flag was disabled without one
+pub const DEFAULT_RETRY_AFTER_SECS: u64 = 300;
+
+/// Route groups that can be switched off, by path pattern
+pub const ROUTE_GROUPS: &[(&str, &str)] = &[
+    ("auth", "/auth/*"),
+    ("bounties", "/bounties/*"),
+    ("analysis", "/analysis/*"),
+    ("submissions", "/submissions/*"),
+    ("reputation", "/reputation/*"),
+    ("users", "/users/*"),
+    ("wallet", "/wallet/*"),
+    ("webhooks", "/webhooks/*"),
+    ("usage", "/usage/*"),
+    ("graphql", "/graphql"),
+    ("realtime", "/ws"),
+];
+
+/// Routes served during maintenance
+const MAINTENANCE_EXEMPT: &[&str] = &["/health/*", "/auth/*", "/sessions/*", "/admin/*"];
+
+pub fn route_group_flag(group: &str) -> String {
+    format!("{}{}", ROUTE_GROUP_FLAG_PREFIX, group)
+}
+
+/// Route group a request path belongs to
+pub fn route_group(path: &str) -> Option<&'static str> {
+    let path = api_relative(path);
+    ROUTE_GROUPS
+        .iter()
+        .find(|(_, pattern)| pattern_matches(pattern, path))
+        .map(|(group, _)| *group)
+}
+
+fn maintenance_exempt(path: &str) -> bool {
+    let path = api_relative(path);
+    MAINTENANCE_EXEMPT.iter().any(|pattern| pattern_matches(pattern, path))
+}
+
+/// Value of a flag nobody has set: maintenance is off, everything else on
+pub fn flag_default(flag: &str) -> bool {
+    flag != MAINTENANCE_FLAG
+}
+
+/// Why a disabled flag is off and when to come back
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct FlagNotice {
+    pub reason: Option<String>,
+    pub retry_after_seconds: u64,
+    pub changed_by: Option<Uuid>,
+    pub changed_at: DateTime<Utc>,
+}
+
+/// A route that is switched off
+#[derive(Debug, Clone)]
+pub struct Unavailable {
+    pub flag: String,
+    /// `None` in maintenance mode
+    pub route_group: Option<&'static str>,
+    pub notice: Option<FlagNotice>,
+}
+
+impl Unavailable {
+    pub fn retry_after_seconds(&self) -> u64 {
+        self.notice
+            .as_ref()
+            .map_or(DEFAULT_RETRY_AFTER_SECS, |n| n.retry_after_seconds)
+    }
+}
+
+/// A requested change of a flag
+#[derive(Debug, Clone, Default)]
+pub struct FlagChange {
+    /// `None` removes the setting, restoring the default
+    pub enabled: Option<bool>,
+    pub reason: Option<String>,
+    pub retry_after_seconds: Option<u64>,
+    pub changed_by: Option<Uuid>,
+}
+
+/// Audit record of a flag change
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct FlagChangeRecord {
+    pub flag: String,
+    pub enabled: Option<bool>,
+    pub reason: Option<String>,
+    pub retry_after_seconds: Option<i64>,
+    pub changed_by: Option<Uuid>,
+    pub changed_at: DateTime<Utc>,
+}
+
+fn notice_key(flag: &str) -> String {
+    format!("{}{}", NOTICE_KEY_PREFIX, flag)
+}
+
+pub struct FeatureFlagService {
+    flags: FeatureFlagClient,
+    redis: MultiplexedConnection,
+    pool: PgPool,
+}
+
+impl FeatureFlagService {
+    pub fn new(pool: PgPool, redis: MultiplexedConnection) -> Self {
+        Self {
+            flags: FeatureFlagClient::new(redis.clone(), DEFAULT_CACHE_TTL),
+            redis,
+            pool,
+        }
+    }
+
+    /// Effective value of a flag (cached; the last known value or the
+    /// default applies while Redis is unreachable)
+    pub async fn is_enabled(&self, flag: &str) -> bool {
+        self.flags.is_enabled(flag, None, flag_default(flag)).await
+    }
+
+    /// Whether a request must be turned away, and why
+    pub async fn unavailable(&self, path: &str) -> Option<Unavailable> {
+        let (flag, route_group) = if !maintenance_exempt(path) && self.is_enabled(MAINTENANCE_FLAG).await {
+            (MAINTENANCE_FLAG.to_string(), None)
+        } else {
+            let group = route_group(path)?;
+            let flag = route_group_flag(group);
+            if self.is_enabled(&flag).await {
+                return None;
+            }
+            (flag, Some(group))
+        };
+
+        let notice = self.notice(&flag).await.unwrap_or_else(|e| {
+            tracing::warn!("Failed to read notice of {}: {:#}", flag, e);
+            None
+        });
+        Some(Unavailable { flag, route_group, notice })
+    }
+
+    /// Stored settings of a flag, read from Redis
+    pub async fn state(&self, flag: &str) -> Result<FlagState> {
+        self.flags.state(flag).await.context("Feature flags unavailable")
+    }
+
+    pub async fn notice(&self, flag: &str) -> Result<Option<FlagNotice>> {
+        let mut conn = self.redis.clone();
+        let notice: Option<String> = conn.get(notice_key(flag)).await?;
+        Ok(notice.and_then(|n| serde_json::from_str(&n).ok()))
+    }
+
+    /// Apply a change and record it
+    pub async fn set(&self, flag: &str, change: FlagChange) -> Result<FlagState> {
+        let state = self
+            .flags
+            .set(flag, None, change.enabled)
+            .await
+            .context("Failed to update feature flag")?;
+
+        // A notice only makes sense while the flag keeps routes off
+        let disables = change.enabled == Some(flag == MAINTENANCE_FLAG);
+        let mut conn = self.redis.clone();
+        if disables {
+            let notice = FlagNotice {
+                reason: change.reason.clone(),
+                retry_after_seconds: change.retry_after_seconds.unwrap_or(DEFAULT_RETRY_AFTER_SECS),
+                changed_by: change.changed_by,
+                changed_at: Utc::now(),
+            };
+            let _: () = conn.set(notice_key(flag), serde_json::to_string(&notice)?).await?;
+        } else {
+            let _: () = conn.del(notice_key(flag)).await?;
+        }
+
+        // The flag is already live; a failed audit insert is logged, not fatal
+        let recorded = sqlx::query(
+            r#"
+            INSERT INTO feature_flag_changes (flag, enabled, reason, retry_after_seconds, changed_by)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(flag)
+        .bind(change.enabled)
+        .bind(&change.reason)
+        .bind(change.retry_after_seconds.map(|s| s as i64))
+        .bind(change.changed_by)
+        .execute(&self.pool)
+        .await;
+        if let Err(e) = recorded {
+            tracing::error!("Failed to record change of feature flag {}: {}", flag, e);
+        }
+
+        Ok(state)
+    }
+
+    /// Latest changes of a flag, newest first
+    pub async fn history(&self, flag: &str, limit: i64) -> Result<Vec<FlagChangeRecord>> {
+        let changes = sqlx::query_as::<_, FlagChangeRecord>(
+            r#"
+            SELECT flag, enabled, reason, retry_after_seconds, changed_by, changed_at
+            FROM feature_flag_changes
+            WHERE flag = $1
+            ORDER BY changed_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(flag)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(changes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route_groups() {
+        assert_eq!(route_group("/api/v1/wallet/balance"), Some("wallet"));
+        assert_eq!(route_group("/wallet"), Some("wallet"));
+        assert_eq!(route_group("/api/v2/bounties/42/analyses"), Some("bounties"));
+        assert_eq!(route_group("/api/v1/graphql"), Some("graphql"));
+        assert_eq!(route_group("/api/v1/health"), None);
+        assert_eq!(route_group("/api/v1/admin/flags"), None);
+    }
+
+    #[test]
+    fn test_maintenance_keeps_operator_routes_up() {
+        assert!(maintenance_exempt("/api/v1/health/ready"));
+        assert!(maintenance_exempt("/api/v1/auth/login"));
+        assert!(maintenance_exempt("/api/v1/sessions"));
+        assert!(maintenance_exempt("/api/admin/flags/gateway.maintenance"));
+        assert!(!maintenance_exempt("/api/v1/bounties"));
+        assert!(!maintenance_exempt("/api/v2/users/me"));
+    }
+
+    #[test]
+    fn test_flag_defaults() {
+        assert!(!flag_default(MAINTENANCE_FLAG));
+        assert!(flag_default(&route_group_flag("wallet")));
+        assert_eq!(route_group_flag("wallet"), "gateway.routes.wallet");
+        assert!(is_valid_flag_name(&route_group_flag("wallet")));
+    }
+}
@@ -0,0 +1,339 @@
+//! JWT signing keys
+//!
+//! Tokens are signed with Ed25519 key pairs kept in Postgres, so every
+//! gateway instance signs with the same key and publishes the same JWKS at
+//! `/.well-known/jwks.json`, where clients can verify tokens without a secret.
+//! Internal services never see the token; the gateway forwards the caller's
+//! identity in signed headers (`shared::request_signing`).
+//!
+//! A key signs for `security.jwt_key_rotation_days`. Its successor is created
+//! [`PUBLISH_AHEAD`] before it activates so every instance, and every client
+//! caching the JWKS, knows it before the first token carries its `kid`. The
+//! retired key keeps verifying for `security.refresh_token_expiry_days`, the
+//! lifetime of the longest token it signed. Private keys are stored encrypted
+//! under a key derived from `security.jwt_secret`.
+
+use anyhow::{anyhow, Context, Result};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::jwk::{
+    AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm,
+    OctetKeyPairParameters, OctetKeyPairType, PublicKeyUse,
+};
+use jsonwebtoken::{DecodingKey, EncodingKey};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::signature::{Ed25519KeyPair, KeyPair};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::config::SecurityConfig;
+use crate::middleware::auth::JwtService;
+
+/// How long a new key is published before it starts signing
+pub const PUBLISH_AHEAD: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+/// How often each instance reloads the key set and checks for a due rotation
+pub const REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Ed25519 public key as published in the JWKS
+#[derive(Debug, Clone)]
+pub struct PublicKey {
+    pub kid: String,
+    pub key: Vec<u8>,
+}
+
+impl PublicKey {
+    fn new(key: Vec<u8>) -> Self {
+        Self {
+            kid: thumbprint(&key),
+            key,
+        }
+    }
+
+    pub fn decoding_key(&self) -> DecodingKey {
+        DecodingKey::from_ed_der(&self.key)
+    }
+
+    pub fn jwk(&self) -> Jwk {
+        Jwk {
+            common: CommonParameters {
+                public_key_use: Some(PublicKeyUse::Signature),
+                key_algorithm: Some(KeyAlgorithm::EdDSA),
+                key_id: Some(self.kid.clone()),
+                ..Default::default()
+            },
+            algorithm: AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                key_type: OctetKeyPairType::OctetKeyPair,
+                curve: EllipticCurve::Ed25519,
+                x: URL_SAFE_NO_PAD.encode(&self.key),
+            }),
+        }
+    }
+}
+
+/// RFC 7638 thumbprint of an Ed25519 public key, used as its `kid`
+fn thumbprint(public_key: &[u8]) -> String {
+    let canonical = format!(
+        r#"{{"crv":"Ed25519","kty":"OKP","x":"{}"}}"#,
+        URL_SAFE_NO_PAD.encode(public_key)
+    );
+    URL_SAFE_NO_PAD.encode(Sha256::digest(canonical.as_bytes()))
+}
+
+/// The key currently signing tokens
+pub struct SigningKey {
+    pub public: PublicKey,
+    pkcs8: Vec<u8>,
+}
+
+impl SigningKey {
+    pub fn generate() -> Result<Self> {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+            .map_err(|_| anyhow!("Failed to generate Ed25519 key pair"))?;
+        Self::from_pkcs8(pkcs8.as_ref().to_vec())
+    }
+
+    fn from_pkcs8(pkcs8: Vec<u8>) -> Result<Self> {
+        let pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(&pkcs8)
+            .map_err(|_| anyhow!("Invalid Ed25519 private key"))?;
+        Ok(Self {
+            public: PublicKey::new(pair.public_key().as_ref().to_vec()),
+            pkcs8,
+        })
+    }
+
+    pub fn encoding_key(&self) -> EncodingKey {
+        EncodingKey::from_ed_der(&self.pkcs8)
+    }
+}
+
+/// Keys a gateway instance works with: one to sign, all published ones to verify
+pub struct KeySet {
+    pub signing: SigningKey,
+    /// Every published key, the signing key included
+    pub verifying: Vec<PublicKey>,
+}
+
+impl KeySet {
+    /// A single fresh key, for tests and tools that run without a database
+    pub fn generate() -> Result<Self> {
+        let signing = SigningKey::generate()?;
+        let verifying = vec![signing.public.clone()];
+        Ok(Self { signing, verifying })
+    }
+
+    pub fn jwks(&self) -> JwkSet {
+        JwkSet {
+            keys: self.verifying.iter().map(PublicKey::jwk).collect(),
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct KeyRow {
+    public_key: Vec<u8>,
+    encrypted_private_key: Vec<u8>,
+    activates_at: DateTime<Utc>,
+}
+
+/// Encrypts private keys at rest with AES-256-GCM; the nonce is stored in
+/// front of the ciphertext
+struct KeyWrapper {
+    key: LessSafeKey,
+    rng: SystemRandom,
+}
+
+impl KeyWrapper {
+    fn new(secret: &str) -> Self {
+        let digest = Sha256::digest(format!("nexus-jwt-signing-keys:{}", secret).as_bytes());
+        let key = UnboundKey::new(&AES_256_GCM, &digest).expect("SHA-256 output is a valid AES-256 key");
+        Self {
+            key: LessSafeKey::new(key),
+            rng: SystemRandom::new(),
+        }
+    }
+
+    fn seal(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let mut nonce = [0u8; NONCE_LEN];
+        self.rng
+            .fill(&mut nonce)
+            .map_err(|_| anyhow!("Failed to generate nonce"))?;
+        let mut sealed = plaintext.to_vec();
+        self.key
+            .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut sealed)
+            .map_err(|_| anyhow!("Failed to encrypt signing key"))?;
+        Ok([nonce.as_slice(), &sealed].concat())
+    }
+
+    fn open(&self, sealed: &[u8]) -> Result<Vec<u8>> {
+        if sealed.len() < NONCE_LEN {
+            return Err(anyhow!("Encrypted signing key is truncated"));
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce)
+            .map_err(|_| anyhow!("Invalid signing key nonce"))?;
+        let mut buffer = ciphertext.to_vec();
+        let plaintext = self
+            .key
+            .open_in_place(nonce, Aad::empty(), &mut buffer)
+            .map_err(|_| anyhow!("Failed to decrypt signing key; was JWT_SECRET changed?"))?;
+        Ok(plaintext.to_vec())
+    }
+}
+
+/// Postgres-backed key store shared by all gateway instances
+pub struct JwtKeyStore {
+    db: PgPool,
+    wrapper: KeyWrapper,
+    rotation: Duration,
+    verify_for: Duration,
+}
+
+impl JwtKeyStore {
+    pub fn new(db: PgPool, security: &SecurityConfig) -> Self {
+        Self {
+            db,
+            wrapper: KeyWrapper::new(&security.jwt_secret),
+            rotation: Duration::days(security.jwt_key_rotation_days.max(1)),
+            verify_for: Duration::days(security.refresh_token_expiry_days.max(1)),
+        }
+    }
+
+    /// Create the first key, or the successor of a key that has signed for
+    /// a full rotation period. Returns whether a key was created. Instances
+    /// race for this, so the check runs under an advisory lock.
+    pub async fn rotate_if_due(&self) -> Result<bool> {
+        let mut tx = self.db.begin().await?;
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext('jwt_signing_keys'))")
+            .execute(&mut *tx)
+            .await?;
+
+        let latest: Option<(String, DateTime<Utc>)> = sqlx::query_as(
+            "SELECT kid, activates_at FROM jwt_signing_keys ORDER BY activates_at DESC LIMIT 1",
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let now = Utc::now();
+        let activates_at = match &latest {
+            // Nothing has been signed yet, so there is nobody to warn
+            None => now,
+            Some((_, activated)) if *activated + self.rotation <= now => {
+                now + Duration::from_std(PUBLISH_AHEAD)?
+            }
+            Some(_) => return Ok(false),
+        };
+
+        let key = SigningKey::generate()?;
+        sqlx::query(
+            r#"
+            INSERT INTO jwt_signing_keys (kid, public_key, encrypted_private_key, activates_at)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(&key.public.kid)
+        .bind(&key.public.key)
+        .bind(self.wrapper.seal(&key.pkcs8)?)
+        .bind(activates_at)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to store signing key")?;
+
+        if let Some((kid, _)) = &latest {
+            sqlx::query(
+                "UPDATE jwt_signing_keys SET retires_at = $2, expires_at = $3 WHERE kid = $1",
+            )
+            .bind(kid)
+            .bind(activates_at)
+            .bind(activates_at + self.verify_for)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        info!("Created JWT signing key {} (active from {})", key.public.kid, activates_at);
+        Ok(true)
+    }
+
+    /// The key signing right now and every key still verifying, including
+    /// one published ahead of its activation
+    pub async fn load(&self) -> Result<KeySet> {
+        self.rotate_if_due().await?;
+
+        let rows = sqlx::query_as::<_, KeyRow>(
+            r#"
+            SELECT public_key, encrypted_private_key, activates_at
+            FROM jwt_signing_keys
+            WHERE expires_at IS NULL OR expires_at > NOW()
+            ORDER BY activates_at DESC
+            "#,
+        )
+        .fetch_all(&self.db)
+        .await
+        .context("Failed to load signing keys")?;
+
+        let now = Utc::now();
+        let current = rows
+            .iter()
+            .find(|row| row.activates_at <= now)
+            .ok_or_else(|| anyhow!("No active JWT signing key"))?;
+        let signing = SigningKey::from_pkcs8(self.wrapper.open(&current.encrypted_private_key)?)?;
+        let verifying = rows
+            .into_iter()
+            .map(|row| PublicKey::new(row.public_key))
+            .collect();
+
+        Ok(KeySet { signing, verifying })
+    }
+}
+
+/// Reload the key set every [`REFRESH_INTERVAL`], rotating when due, so
+/// instances pick up keys created by their peers
+pub fn spawn_key_refresh(store: JwtKeyStore, jwt: Arc<JwtService>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            match store.load().await {
+                Ok(keys) => jwt.install(&keys),
+                Err(e) => warn!("Failed to refresh JWT signing keys: {:#}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_private_keys_round_trip_through_the_wrapper() {
+        let key = SigningKey::generate().unwrap();
+        let wrapper = KeyWrapper::new("test_secret_key_at_least_32_chars");
+
+        let sealed = wrapper.seal(&key.pkcs8).unwrap();
+        assert_ne!(sealed[NONCE_LEN..], key.pkcs8[..]);
+        let opened = SigningKey::from_pkcs8(wrapper.open(&sealed).unwrap()).unwrap();
+        assert_eq!(opened.public.kid, key.public.kid);
+
+        let other = KeyWrapper::new("a_different_secret_of_32_chars_xx");
+        assert!(other.open(&sealed).is_err());
+    }
+
+    #[test]
+    fn test_jwks_publishes_public_keys_only() {
+        let keys = KeySet::generate().unwrap();
+        let jwks = serde_json::to_value(keys.jwks()).unwrap();
+        let jwk = &jwks["keys"][0];
+
+        assert_eq!(jwk["kty"], "OKP");
+        assert_eq!(jwk["crv"], "Ed25519");
+        assert_eq!(jwk["alg"], "EdDSA");
+        assert_eq!(jwk["use"], "sig");
+        assert_eq!(jwk["kid"], keys.signing.public.kid.as_str());
+        assert!(jwk.get("d").is_none());
+    }
+}
@@ -0,0 +1,137 @@
+//! Devices and countries users have signed in from
+//!
+//! Every login is recorded against the session's device fingerprint and
+//! country (`services::session::DeviceInfo`) in `user_known_devices`. A
+//! login is news to its owner when the account has been used before but
+//! never from that device, or never from that country; the auth handlers
+//! then send them a notice. An account's first login is never news.
+
+use anyhow::{Context, Result};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::services::session::DeviceInfo;
+
+/// What a user's earlier logins say about a new one
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, sqlx::FromRow)]
+pub struct LoginHistory {
+    /// The user has logged in before
+    pub has_logins: bool,
+    pub known_device: bool,
+    /// Some earlier login reported a country
+    pub has_countries: bool,
+    pub known_country: bool,
+}
+
+/// Whether a login came from somewhere its owner should hear about
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LoginNovelty {
+    pub new_device: bool,
+    pub new_country: bool,
+}
+
+impl LoginNovelty {
+    pub fn assess(history: &LoginHistory, country_reported: bool) -> Self {
+        Self {
+            new_device: history.has_logins && !history.known_device,
+            new_country: country_reported && history.has_countries && !history.known_country,
+        }
+    }
+
+    pub fn is_news(&self) -> bool {
+        self.new_device || self.new_country
+    }
+}
+
+pub struct KnownDeviceStore {
+    pool: PgPool,
+}
+
+impl KnownDeviceStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Remember the device of a login and say whether it is new to the user
+    pub async fn record_login(&self, user_id: Uuid, device: &DeviceInfo) -> Result<LoginNovelty> {
+        let country = device.country.as_deref().unwrap_or("");
+
+        let history: LoginHistory = sqlx::query_as(
+            r#"
+            SELECT
+                COUNT(*) > 0 AS has_logins,
+                COUNT(*) FILTER (WHERE fingerprint = $2) > 0 AS known_device,
+                COUNT(*) FILTER (WHERE country <> '') > 0 AS has_countries,
+                COUNT(*) FILTER (WHERE country = $3) > 0 AS known_country
+            FROM user_known_devices
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .bind(&device.fingerprint)
+        .bind(country)
+        .fetch_one(&self.pool)
+        .await
+        .context("Failed to load known devices")?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO user_known_devices (user_id, fingerprint, country, label, last_ip_address)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (user_id, fingerprint, country) DO UPDATE
+            SET label = EXCLUDED.label,
+                last_ip_address = EXCLUDED.last_ip_address,
+                last_seen_at = NOW()
+            "#,
+        )
+        .bind(user_id)
+        .bind(&device.fingerprint)
+        .bind(country)
+        .bind(&device.label)
+        .bind(&device.ip_address)
+        .execute(&self.pool)
+        .await
+        .context("Failed to record device")?;
+
+        Ok(LoginNovelty::assess(&history, !country.is_empty()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_login_is_not_news() {
+        let novelty = LoginNovelty::assess(&LoginHistory::default(), true);
+        assert!(!novelty.is_news());
+    }
+
+    #[test]
+    fn test_new_device_and_country() {
+        let seen = LoginHistory {
+            has_logins: true,
+            known_device: true,
+            has_countries: true,
+            known_country: true,
+        };
+        assert!(!LoginNovelty::assess(&seen, true).is_news());
+
+        let new_device = LoginHistory { known_device: false, ..seen };
+        assert_eq!(
+            LoginNovelty::assess(&new_device, true),
+            LoginNovelty { new_device: true, new_country: false }
+        );
+
+        let new_country = LoginHistory { known_country: false, ..seen };
+        assert_eq!(
+            LoginNovelty::assess(&new_country, true),
+            LoginNovelty { new_device: false, new_country: true }
+        );
+        // Without a country header nothing can be said about the country
+        assert!(!LoginNovelty::assess(&new_country, false).is_news());
+        // Nor when no earlier login reported one
+        let no_countries = LoginHistory { has_countries: false, known_country: false, ..seen };
+        assert!(!LoginNovelty::assess(&no_countries, true).is_news());
+    }
+}
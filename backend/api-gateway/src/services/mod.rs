@@ -3,14 +3,30 @@ pub mod auth_service;
 pub mod blockchain;
 pub mod cache_service;
 pub mod database;
+pub mod embargo;
 pub mod event_bus;
+pub mod feature_flags;
+pub mod jwt_keys;
+pub mod known_devices;
 pub mod proxy_service;
+pub mod quota;
+pub mod rbac;
+pub mod realtime;
 pub mod redis;
+pub mod session;
+pub mod usage;
 
 pub use auth_service::AuthService;
 pub use blockchain::BlockchainService;
 pub use cache_service::CacheService;
 pub use database::DatabaseService;
 pub use event_bus::EventBus;
+pub use feature_flags::FeatureFlagService;
+pub use known_devices::KnownDeviceStore;
 pub use proxy_service::ProxyService;
-pub use redis::RedisService;
\ No newline at end of file
+pub use quota::QuotaService;
+pub use rbac::RbacService;
+pub use realtime::RealtimeHub;
+pub use redis::RedisService;
+pub use session::SessionStore;
+pub use usage::UsageMeter;
\ No newline at end of file
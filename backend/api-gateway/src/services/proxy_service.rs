@@ -1,18 +1,127 @@
 use anyhow::{Context, Result};
-use reqwest::{Client, Method, Request, Response, StatusCode};
+use axum::{
+    body::{Body, Bytes},
+    extract::ConnectInfo,
+    response::IntoResponse,
+    Json,
+};
+use futures_util::StreamExt;
+use rand::Rng;
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    Client, Method, Response,
+};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::RwLock;
+use thiserror::Error;
+use tokio::sync::{mpsc, RwLock};
 use tracing::{debug, error, info, warn};
-use uuid::Uuid;
+use shared::observability;
+use shared::permissions::{self, Scopes};
+use shared::request_signing::{self, RequestSigner, SignedIdentity, MAX_SIGNED_BODY_BYTES};
+
+use crate::config::{ServicesConfig, UpstreamSettings};
+use crate::middleware::auth::Claims;
+use crate::utils::AuthContext;
+
+pub const ANALYSIS_ENGINE: &str = "analysis-engine";
+pub const BOUNTY_MANAGER: &str = "bounty-manager";
+pub const NOTIFICATION_SERVICE: &str = "notification-service";
+pub const SUBMISSION_SERVICE: &str = "submission-service";
+pub const PAYMENT_SERVICE: &str = "payment-service";
+pub const REPUTATION_SERVICE: &str = "reputation-service";
+pub const CONSENSUS_SERVICE: &str = "consensus-service";
+
+/// Connection-level headers (RFC 9110 §7.6.1) that never cross the proxy
+const HOP_BY_HOP_HEADERS: &[&str] = &[
+    "connection",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+];
+
+/// Client headers the gateway drops or replaces with its own values.
+/// Upstreams trust the identity headers, so they must never come from
+/// the caller.
+const GATEWAY_OWNED_HEADERS: &[&str] = &[
+    "host",
+    request_signing::KEY_ID_HEADER,
+    request_signing::TIMESTAMP_HEADER,
+    request_signing::CONTENT_SHA256_HEADER,
+    request_signing::SIGNATURE_HEADER,
+    "authorization",
+    "cookie",
+    "x-api-key",
+    "x-user-id",
+    "x-user-role",
+    "x-org-id",
+    "x-org-role",
+    permissions::SCOPES_HEADER,
+    "x-forwarded-for",
+    "x-forwarded-host",
+    observability::REQUEST_ID_HEADER,
+    observability::TRACEPARENT_HEADER,
+];
+
+/// Errors from forwarding a request to an upstream service
+#[derive(Debug, Error)]
+pub enum ProxyError {
+    #[error("Unknown upstream service: {0}")]
+    UnknownService(String),
+    #[error("Upstream service {0} is unavailable")]
+    CircuitOpen(String),
+    #[error("Upstream service {0} timed out")]
+    Timeout(String),
+    #[error("Upstream service {0} request failed: {1}")]
+    Upstream(String, String),
+    #[error("Request body exceeds {0} bytes")]
+    PayloadTooLarge(u64),
+}
+
+impl IntoResponse for ProxyError {
+    fn into_response(self) -> axum::response::Response {
+        let (status, error_message) = match self {
+            ProxyError::UnknownService(_) => {
+                (axum::http::StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
+            }
+            ProxyError::CircuitOpen(_) => {
+                (axum::http::StatusCode::SERVICE_UNAVAILABLE, "Service unavailable")
+            }
+            ProxyError::Timeout(_) => (axum::http::StatusCode::GATEWAY_TIMEOUT, "Upstream timeout"),
+            ProxyError::Upstream(_, _) => (axum::http::StatusCode::BAD_GATEWAY, "Bad gateway"),
+            ProxyError::PayloadTooLarge(_) => {
+                (axum::http::StatusCode::PAYLOAD_TOO_LARGE, "Payload too large")
+            }
+        };
+
+        let body = Json(json!({
+            "error": error_message,
+            "details": self.to_string()
+        }));
+
+        (status, body).into_response()
+    }
+}
 
 /// Proxy service for making HTTP requests to other microservices
 /// Includes circuit breaker pattern, request retry logic, and service discovery
 #[derive(Clone)]
 pub struct ProxyService {
     client: Client,
+    /// One connection pool per upstream, configured from its `UpstreamSettings`
+    clients: Arc<HashMap<String, Client>>,
+    registry: Arc<ServiceRegistry>,
+    /// Signs every upstream request when `SERVICE_SIGNING_KEYS` is set
+    signer: Option<RequestSigner>,
     config: ProxyConfig,
     circuit_breakers: Arc<RwLock<HashMap<String, CircuitBreaker>>>,
     stats: Arc<RwLock<ProxyStats>>,
@@ -22,10 +131,24 @@ pub struct ProxyService {
 #[derive(Debug, Clone)]
 pub struct ProxyConfig {
     pub timeout_seconds: u64,
+    /// Retries after the first attempt; only idempotent requests are retried
     pub max_retries: u32,
+    /// Base of the exponential backoff between retries
     pub retry_delay_ms: u64,
+    /// Upper bound of a single backoff delay
+    pub retry_max_delay_ms: u64,
+    /// Consecutive failures that open the circuit
     pub circuit_breaker_threshold: u32,
+    /// How long an open circuit rejects requests before probing
     pub circuit_breaker_timeout_seconds: u64,
+    /// Failure rate over the recent window that opens the circuit
+    pub circuit_breaker_failure_rate: f64,
+    /// Number of recent requests the failure rate is computed over
+    pub circuit_breaker_window: usize,
+    /// Requests needed in the window before the failure rate is considered
+    pub circuit_breaker_min_requests: usize,
+    /// Concurrent probe requests allowed while half-open
+    pub circuit_breaker_half_open_probes: u32,
     pub enable_service_discovery: bool,
 }
 
@@ -35,13 +158,76 @@ impl Default for ProxyConfig {
             timeout_seconds: 30,
             max_retries: 3,
             retry_delay_ms: 1000,
+            retry_max_delay_ms: 8000,
             circuit_breaker_threshold: 5,
             circuit_breaker_timeout_seconds: 60,
+            circuit_breaker_failure_rate: 0.5,
+            circuit_breaker_window: 20,
+            circuit_breaker_min_requests: 10,
+            circuit_breaker_half_open_probes: 1,
             enable_service_discovery: false,
         }
     }
 }
 
+impl ProxyConfig {
+    /// Defaults overridden by `PROXY_*` environment variables
+    pub fn from_env() -> Self {
+        fn var<T: std::str::FromStr>(name: &str) -> Option<T> {
+            std::env::var(name).ok().and_then(|v| v.parse().ok())
+        }
+
+        let mut config = Self::default();
+        if let Some(v) = var("PROXY_MAX_RETRIES") {
+            config.max_retries = v;
+        }
+        if let Some(v) = var("PROXY_RETRY_DELAY_MS") {
+            config.retry_delay_ms = v;
+        }
+        if let Some(v) = var("PROXY_RETRY_MAX_DELAY_MS") {
+            config.retry_max_delay_ms = v;
+        }
+        if let Some(v) = var("PROXY_CIRCUIT_BREAKER_THRESHOLD") {
+            config.circuit_breaker_threshold = v;
+        }
+        if let Some(v) = var("PROXY_CIRCUIT_BREAKER_TIMEOUT_SECONDS") {
+            config.circuit_breaker_timeout_seconds = v;
+        }
+        if let Some(v) = var::<f64>("PROXY_CIRCUIT_BREAKER_FAILURE_RATE") {
+            config.circuit_breaker_failure_rate = v.clamp(0.0, 1.0);
+        }
+        if let Some(v) = var("PROXY_CIRCUIT_BREAKER_WINDOW") {
+            config.circuit_breaker_window = v;
+        }
+        if let Some(v) = var("PROXY_CIRCUIT_BREAKER_MIN_REQUESTS") {
+            config.circuit_breaker_min_requests = v;
+        }
+        if let Some(v) = var("PROXY_CIRCUIT_BREAKER_HALF_OPEN_PROBES") {
+            config.circuit_breaker_half_open_probes = v;
+        }
+        config
+    }
+
+    /// Delay before retry number `attempt` (1-based): exponential backoff
+    /// with full jitter, so clients retrying together spread out
+    pub fn retry_backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(16);
+        let cap = self
+            .retry_delay_ms
+            .saturating_mul(1u64 << exponent)
+            .min(self.retry_max_delay_ms);
+        Duration::from_millis(rand::thread_rng().gen_range(0..=cap))
+    }
+}
+
+/// Methods that may be sent again without changing the outcome
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::PUT | Method::DELETE
+    )
+}
+
 /// Service registry for microservice discovery
 #[derive(Debug, Clone)]
 pub struct ServiceRegistry {
@@ -56,6 +242,11 @@ pub struct ServiceEndpoint {
     pub health_check_path: Option<String>,
     pub api_version: String,
     pub requires_auth: bool,
+    /// Credential sent as `X-API-Key` on every request to the service
+    #[serde(default, skip_serializing)]
+    pub api_key: Option<String>,
+    #[serde(default)]
+    pub settings: UpstreamSettings,
 }
 
 impl ServiceRegistry {
@@ -74,97 +265,169 @@ impl ServiceRegistry {
         self.services.get(name)
     }
 
-    pub fn default_services() -> Self {
+    /// Backend services the gateway proxies to
+    pub fn from_config(services: &ServicesConfig) -> Self {
         let mut registry = Self::new();
 
-        // Analysis Engine
-        registry.register(
-            "analysis-engine".to_string(),
-            ServiceEndpoint {
-                name: "Analysis Engine".to_string(),
-                base_url: std::env::var("ANALYSIS_ENGINE_URL")
-                    .unwrap_or_else(|_| "http://localhost:8081".to_string()),
-                health_check_path: Some("/health".to_string()),
-                api_version: "v1".to_string(),
-                requires_auth: true,
-            },
-        );
-
-        // Bounty Manager
-        registry.register(
-            "bounty-manager".to_string(),
-            ServiceEndpoint {
-                name: "Bounty Manager".to_string(),
-                base_url: std::env::var("BOUNTY_MANAGER_URL")
-                    .unwrap_or_else(|_| "http://localhost:8082".to_string()),
-                health_check_path: Some("/health".to_string()),
-                api_version: "v1".to_string(),
-                requires_auth: true,
-            },
-        );
-
-        // Notification Service
-        registry.register(
-            "notification-service".to_string(),
-            ServiceEndpoint {
-                name: "Notification Service".to_string(),
-                base_url: std::env::var("NOTIFICATION_SERVICE_URL")
-                    .unwrap_or_else(|_| "http://localhost:8083".to_string()),
-                health_check_path: Some("/health".to_string()),
-                api_version: "v1".to_string(),
-                requires_auth: true,
-            },
-        );
+        for (key, name, base_url, api_key) in [
+            (
+                ANALYSIS_ENGINE,
+                "Analysis Engine",
+                &services.analysis_engine_url,
+                services.analysis_engine_api_key.clone(),
+            ),
+            (BOUNTY_MANAGER, "Bounty Manager", &services.bounty_manager_url, None),
+            (
+                NOTIFICATION_SERVICE,
+                "Notification Service",
+                &services.notification_service_url,
+                None,
+            ),
+            (
+                SUBMISSION_SERVICE,
+                "Submission Service",
+                &services.submission_service_url,
+                None,
+            ),
+            (PAYMENT_SERVICE, "Payment Service", &services.payment_service_url, None),
+            (
+                REPUTATION_SERVICE,
+                "Reputation Service",
+                &services.reputation_service_url,
+                None,
+            ),
+            (
+                CONSENSUS_SERVICE,
+                "Consensus Service",
+                &services.consensus_service_url,
+                None,
+            ),
+        ] {
+            registry.register(
+                key.to_string(),
+                ServiceEndpoint {
+                    name: name.to_string(),
+                    base_url: base_url.trim_end_matches('/').to_string(),
+                    health_check_path: Some("/health".to_string()),
+                    api_version: "v1".to_string(),
+                    requires_auth: true,
+                    api_key,
+                    settings: services.upstream_settings(key),
+                },
+            );
+        }
 
         registry
     }
 }
 
 /// Circuit breaker states
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
 pub enum CircuitBreakerState {
     Closed,      // Normal operation
     Open,        // Failing - reject requests
     HalfOpen,    // Testing if service recovered
 }
 
-/// Circuit breaker for fault tolerance
+/// Circuit breaker for fault tolerance.
+///
+/// Opens after `threshold` consecutive failures, or once the failure rate
+/// over the last `window_size` requests reaches `failure_rate_threshold`
+/// (with at least `min_requests` in the window). After `timeout_duration` it
+/// goes half-open and lets a limited number of probe requests through: a
+/// successful probe closes it, a failed one opens it again.
 #[derive(Debug, Clone)]
 pub struct CircuitBreaker {
     state: CircuitBreakerState,
     failure_count: u32,
-    last_failure_time: Option<Instant>,
+    opened_at: Option<Instant>,
     threshold: u32,
     timeout_duration: Duration,
+    /// Recent outcomes, `true` for a failure
+    outcomes: VecDeque<bool>,
+    window_size: usize,
+    min_requests: usize,
+    failure_rate_threshold: f64,
+    half_open_probes: u32,
+    probes_in_flight: u32,
+}
+
+/// Circuit breaker state as reported by the health endpoint
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct CircuitBreakerSnapshot {
+    pub state: CircuitBreakerState,
+    pub consecutive_failures: u32,
+    pub failure_rate: f64,
+    pub window_requests: usize,
+    /// Seconds until an open circuit starts probing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_in_seconds: Option<u64>,
 }
 
 impl CircuitBreaker {
     pub fn new(threshold: u32, timeout_seconds: u64) -> Self {
+        let defaults = ProxyConfig::default();
         Self {
             state: CircuitBreakerState::Closed,
             failure_count: 0,
-            last_failure_time: None,
-            threshold,
+            opened_at: None,
+            threshold: threshold.max(1),
             timeout_duration: Duration::from_secs(timeout_seconds),
+            outcomes: VecDeque::with_capacity(defaults.circuit_breaker_window),
+            window_size: defaults.circuit_breaker_window,
+            min_requests: defaults.circuit_breaker_min_requests,
+            failure_rate_threshold: defaults.circuit_breaker_failure_rate,
+            half_open_probes: defaults.circuit_breaker_half_open_probes,
+            probes_in_flight: 0,
+        }
+    }
+
+    pub fn from_config(config: &ProxyConfig) -> Self {
+        Self {
+            outcomes: VecDeque::with_capacity(config.circuit_breaker_window),
+            window_size: config.circuit_breaker_window.max(1),
+            min_requests: config.circuit_breaker_min_requests.max(1),
+            failure_rate_threshold: config.circuit_breaker_failure_rate,
+            half_open_probes: config.circuit_breaker_half_open_probes.max(1),
+            ..Self::new(config.circuit_breaker_threshold, config.circuit_breaker_timeout_seconds)
         }
     }
 
     pub fn record_success(&mut self) {
         if self.state == CircuitBreakerState::HalfOpen {
             info!("Circuit breaker recovered - transitioning to Closed");
-            self.state = CircuitBreakerState::Closed;
+            self.reset();
+            return;
         }
+        self.push_outcome(false);
         self.failure_count = 0;
-        self.last_failure_time = None;
     }
 
     pub fn record_failure(&mut self) {
+        self.push_outcome(true);
         self.failure_count += 1;
-        self.last_failure_time = Some(Instant::now());
 
-        if self.failure_count >= self.threshold {
-            warn!("Circuit breaker threshold reached - opening circuit");
-            self.state = CircuitBreakerState::Open;
+        match self.state {
+            CircuitBreakerState::HalfOpen => {
+                warn!("Circuit breaker probe failed - reopening circuit");
+                self.open();
+            }
+            CircuitBreakerState::Closed if self.failure_count >= self.threshold => {
+                warn!("Circuit breaker threshold reached - opening circuit");
+                self.open();
+            }
+            CircuitBreakerState::Closed
+                if self.outcomes.len() >= self.min_requests
+                    && self.failure_rate() >= self.failure_rate_threshold =>
+            {
+                warn!(
+                    "Circuit breaker failure rate {:.0}% reached - opening circuit",
+                    self.failure_rate() * 100.0
+                );
+                self.open();
+            }
+            _ => {}
         }
     }
 
@@ -172,22 +435,77 @@ impl CircuitBreaker {
         match self.state {
             CircuitBreakerState::Closed => true,
             CircuitBreakerState::Open => {
-                // Check if timeout has passed
-                if let Some(last_failure) = self.last_failure_time {
-                    if last_failure.elapsed() >= self.timeout_duration {
-                        info!("Circuit breaker timeout elapsed - transitioning to HalfOpen");
-                        self.state = CircuitBreakerState::HalfOpen;
-                        true
-                    } else {
-                        false
-                    }
+                if self.opened_at.is_some_and(|at| at.elapsed() >= self.timeout_duration) {
+                    info!("Circuit breaker timeout elapsed - transitioning to HalfOpen");
+                    self.state = CircuitBreakerState::HalfOpen;
+                    self.probes_in_flight = 1;
+                    true
+                } else {
+                    false
+                }
+            }
+            CircuitBreakerState::HalfOpen => {
+                if self.probes_in_flight < self.half_open_probes {
+                    self.probes_in_flight += 1;
+                    true
                 } else {
                     false
                 }
             }
-            CircuitBreakerState::HalfOpen => true,
         }
     }
+
+    /// Give back a probe slot for a request that never reached the upstream
+    pub fn release_probe(&mut self) {
+        self.probes_in_flight = self.probes_in_flight.saturating_sub(1);
+    }
+
+    pub fn reset(&mut self) {
+        self.state = CircuitBreakerState::Closed;
+        self.failure_count = 0;
+        self.opened_at = None;
+        self.outcomes.clear();
+        self.probes_in_flight = 0;
+    }
+
+    pub fn snapshot(&self) -> CircuitBreakerSnapshot {
+        let retry_in_seconds = match (&self.state, self.opened_at) {
+            (CircuitBreakerState::Open, Some(at)) => {
+                Some(self.timeout_duration.saturating_sub(at.elapsed()).as_secs())
+            }
+            _ => None,
+        };
+
+        CircuitBreakerSnapshot {
+            state: self.state.clone(),
+            consecutive_failures: self.failure_count,
+            failure_rate: self.failure_rate(),
+            window_requests: self.outcomes.len(),
+            retry_in_seconds,
+        }
+    }
+
+    fn failure_rate(&self) -> f64 {
+        if self.outcomes.is_empty() {
+            return 0.0;
+        }
+        self.outcomes.iter().filter(|failed| **failed).count() as f64 / self.outcomes.len() as f64
+    }
+
+    fn push_outcome(&mut self, failed: bool) {
+        if self.outcomes.len() == self.window_size {
+            self.outcomes.pop_front();
+        }
+        self.outcomes.push_back(failed);
+    }
+
+    fn open(&mut self) {
+        self.state = CircuitBreakerState::Open;
+        self.opened_at = Some(Instant::now());
+        self.probes_in_flight = 0;
+        // Start the next closed period with a clean window
+        self.outcomes.clear();
+    }
 }
 
 /// Proxy statistics
@@ -203,17 +521,47 @@ pub struct ProxyStats {
 }
 
 impl ProxyService {
-    /// Create a new proxy service
+    /// Create a new proxy service for the default service locations
     pub fn new(config: ProxyConfig) -> Result<Self> {
+        Self::with_registry(config, ServiceRegistry::from_config(&ServicesConfig::default()))
+    }
+
+    /// Create a proxy service with a connection pool per registered service
+    pub fn with_registry(config: ProxyConfig, registry: ServiceRegistry) -> Result<Self> {
         let client = Client::builder()
             .timeout(Duration::from_secs(config.timeout_seconds))
             .build()
             .context("Failed to create HTTP client")?;
 
+        let clients = registry
+            .services
+            .iter()
+            .map(|(key, endpoint)| {
+                let settings = endpoint.settings;
+                let client = Client::builder()
+                    .connect_timeout(Duration::from_millis(settings.connect_timeout_ms))
+                    .timeout(Duration::from_secs(settings.request_timeout_seconds))
+                    .pool_max_idle_per_host(settings.pool_max_idle_per_host)
+                    .pool_idle_timeout(Duration::from_secs(settings.pool_idle_timeout_seconds))
+                    .redirect(reqwest::redirect::Policy::none())
+                    .build()
+                    .with_context(|| format!("Failed to create HTTP client for {}", key))?;
+                Ok((key.clone(), client))
+            })
+            .collect::<Result<HashMap<_, _>>>()?;
+
+        let signer = RequestSigner::from_env().context("Invalid service signing keys")?;
+        if signer.is_none() {
+            warn!("SERVICE_SIGNING_KEYS not set, upstream requests are not signed");
+        }
+
         info!("Proxy service initialized with config: {:?}", config);
 
         Ok(Self {
             client,
+            clients: Arc::new(clients),
+            registry: Arc::new(registry),
+            signer,
             config,
             circuit_breakers: Arc::new(RwLock::new(HashMap::new())),
             stats: Arc::new(RwLock::new(ProxyStats::default())),
@@ -262,7 +610,12 @@ impl ProxyService {
         self.request(Method::DELETE, service_name, path, None::<()>, headers).await
     }
 
-    /// Make an HTTP request with circuit breaker and retry logic
+    /// Make an HTTP request with circuit breaker and retry logic.
+    ///
+    /// Idempotent requests are retried on connection errors, timeouts and
+    /// 5xx responses; other requests only when the connection could not be
+    /// established, since the upstream never saw them. Every attempt counts
+    /// towards the circuit breaker, and retries stop as soon as it opens.
     pub async fn request<T: Serialize>(
         &self,
         method: Method,
@@ -271,61 +624,54 @@ impl ProxyService {
         body: Option<T>,
         headers: Option<HashMap<String, String>>,
     ) -> Result<Response> {
-        // Check circuit breaker
-        {
-            let mut breakers = self.circuit_breakers.write().await;
-            let breaker = breakers
-                .entry(service_name.to_string())
-                .or_insert_with(|| {
-                    CircuitBreaker::new(
-                        self.config.circuit_breaker_threshold,
-                        self.config.circuit_breaker_timeout_seconds,
-                    )
-                });
-
-            if !breaker.can_attempt_request() {
-                let mut stats = self.stats.write().await;
-                stats.circuit_breaker_trips += 1;
-
-                return Err(anyhow::anyhow!(
-                    "Circuit breaker is open for service: {}",
-                    service_name
-                ));
-            }
-        }
-
         // Get service endpoint
-        let registry = ServiceRegistry::default_services();
-        let endpoint = registry
+        let endpoint = self
+            .registry
             .get(service_name)
             .ok_or_else(|| anyhow::anyhow!("Service not found: {}", service_name))?;
 
         let url = format!("{}{}", endpoint.base_url, path);
         debug!("Proxy request: {} {}", method, url);
-
-        // Update stats
-        {
-            let mut stats = self.stats.write().await;
-            stats.total_requests += 1;
-            *stats.requests_by_service.entry(service_name.to_string()).or_insert(0) += 1;
-        }
-
-        let start_time = Instant::now();
+        let signed_path = reqwest::Url::parse(&url).map(|url| path_and_query(&url))?;
+        let body = body.map(|body| serde_json::to_vec(&body)).transpose()?;
+        let identity = |name: &str| {
+            headers.as_ref().and_then(|map| {
+                map.iter()
+                    .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                    .map(|(_, value)| value.as_str())
+            })
+        };
+
+        self.count_request(service_name).await;
+        let idempotent = is_idempotent(&method);
         let mut last_error = None;
 
-        // Retry logic
         for attempt in 0..=self.config.max_retries {
             if attempt > 0 {
-                let delay = Duration::from_millis(self.config.retry_delay_ms * attempt as u64);
+                let delay = self.config.retry_backoff(attempt);
                 debug!("Retrying request (attempt {}/{}) after {:?}", attempt, self.config.max_retries, delay);
                 tokio::time::sleep(delay).await;
+                self.stats.write().await.retried_requests += 1;
+            }
 
-                let mut stats = self.stats.write().await;
-                stats.retried_requests += 1;
+            if !self.circuit_allows(service_name).await {
+                return Err(last_error.unwrap_or_else(|| {
+                    anyhow::anyhow!("Circuit breaker is open for service: {}", service_name)
+                }));
             }
 
             // Build request
-            let mut request = self.client.request(method.clone(), &url);
+            let mut request = self.client_for(service_name).request(method.clone(), &url);
+            if let Some(ref api_key) = endpoint.api_key {
+                request = request.header("x-api-key", api_key);
+            }
+
+            // Continue the caller's request ID and trace unless given
+            for (name, value) in observability::propagation_headers() {
+                if identity(name).is_none() {
+                    request = request.header(name, value);
+                }
+            }
 
             // Add headers
             if let Some(ref header_map) = headers {
@@ -336,71 +682,284 @@ impl ProxyService {
 
             // Add body if provided
             if let Some(ref body_data) = body {
-                request = request.json(body_data);
+                request = request
+                    .header(reqwest::header::CONTENT_TYPE, "application/json")
+                    .body(body_data.clone());
+            }
+
+            if let Some(ref signer) = self.signer {
+                let signature = signer.sign_identity_now(
+                    method.as_str(),
+                    &signed_path,
+                    Some(body.as_deref().unwrap_or_default()),
+                    SignedIdentity::user(identity("x-user-id"), identity("x-user-role"))
+                        .with_organization(identity("x-org-id"), identity("x-org-role"))
+                        .with_scopes(identity(permissions::SCOPES_HEADER)),
+                );
+                for (name, value) in signature.pairs() {
+                    request = request.header(name, value);
+                }
             }
 
             // Execute request
+            let start_time = Instant::now();
             match request.send().await {
                 Ok(response) => {
-                    let elapsed = start_time.elapsed();
                     let status = response.status();
+                    debug!("Proxy response: {} ({:?})", status, start_time.elapsed());
 
-                    debug!("Proxy response: {} ({:?})", status, elapsed);
-
-                    // Update stats
-                    {
-                        let mut stats = self.stats.write().await;
-                        let total = stats.successful_requests + stats.failed_requests;
-                        let current_avg = stats.avg_response_time_ms;
-                        stats.avg_response_time_ms =
-                            (current_avg * total + elapsed.as_millis() as u64) / (total + 1);
-
-                        if status.is_success() {
-                            stats.successful_requests += 1;
-
-                            // Record success in circuit breaker
-                            let mut breakers = self.circuit_breakers.write().await;
-                            if let Some(breaker) = breakers.get_mut(service_name) {
-                                breaker.record_success();
-                            }
-
-                            return Ok(response);
-                        } else {
-                            stats.failed_requests += 1;
-                            last_error = Some(anyhow::anyhow!("HTTP error: {}", status));
-                        }
+                    // Client errors are the caller's problem, not the upstream's
+                    self.record_attempt(service_name, start_time.elapsed(), !status.is_server_error())
+                        .await;
+                    if !status.is_server_error() {
+                        return Ok(response);
                     }
 
-                    // Don't retry client errors (4xx), only server errors (5xx)
-                    if status.is_client_error() {
-                        return Ok(response);
+                    last_error = Some(anyhow::anyhow!("HTTP error: {}", status));
+                    if !idempotent {
+                        break;
                     }
                 }
                 Err(e) => {
                     error!("Proxy request failed: {}", e);
+                    self.record_attempt(service_name, start_time.elapsed(), false).await;
+                    let retryable = idempotent || e.is_connect();
                     last_error = Some(anyhow::anyhow!("Request failed: {}", e));
+                    if !retryable {
+                        break;
+                    }
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("Request failed after {} retries", self.config.max_retries)))
+    }
+
+    /// Stream a request to an upstream service and stream its response back.
+    ///
+    /// Bodies are forwarded as they arrive, so large uploads are never
+    /// buffered in the gateway; only bodies small enough to be covered by
+    /// the request signature are read first. Only requests without a body
+    /// are retried: a streamed body cannot be replayed.
+    pub async fn forward(
+        &self,
+        service_name: &str,
+        upstream_path: &str,
+        request: axum::extract::Request,
+        max_body_bytes: u64,
+    ) -> Result<axum::response::Response, ProxyError> {
+        let endpoint = self
+            .registry
+            .get(service_name)
+            .ok_or_else(|| ProxyError::UnknownService(service_name.to_string()))?;
+
+        let (parts, body) = request.into_parts();
+
+        let declared_length = parts
+            .headers
+            .get(axum::http::header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok());
+        if declared_length.is_some_and(|len| len > max_body_bytes) {
+            return Err(ProxyError::PayloadTooLarge(max_body_bytes));
+        }
+        let has_body = declared_length.is_some_and(|len| len > 0)
+            || parts.headers.contains_key(axum::http::header::TRANSFER_ENCODING);
 
-                    let mut stats = self.stats.write().await;
-                    stats.failed_requests += 1;
+        let mut url = format!("{}{}", endpoint.base_url, upstream_path);
+        if let Some(query) = parts.uri.query() {
+            url.push('?');
+            url.push_str(query);
+        }
+
+        let method = Method::from_bytes(parts.method.as_str().as_bytes())
+            .map_err(|e| ProxyError::Upstream(service_name.to_string(), e.to_string()))?;
+        let signed_path = reqwest::Url::parse(&url)
+            .map(|url| path_and_query(&url))
+            .map_err(|e| ProxyError::Upstream(service_name.to_string(), e.to_string()))?;
+        let headers = upstream_headers(&parts, endpoint);
+
+        debug!("Forwarding {} {} to {}", parts.method, parts.uri.path(), url);
+
+        // Small bodies are buffered so the signature covers them; larger
+        // ones stream through as UNSIGNED-PAYLOAD
+        let mut buffered = None;
+        let mut body = Some(body);
+        if has_body
+            && self.signer.is_some()
+            && declared_length.is_some_and(|len| len <= MAX_SIGNED_BODY_BYTES as u64)
+        {
+            let bytes = axum::body::to_bytes(body.take().unwrap_or_default(), MAX_SIGNED_BODY_BYTES)
+                .await
+                .map_err(|_| ProxyError::PayloadTooLarge(max_body_bytes))?;
+            buffered = Some(bytes);
+        }
+
+        let max_attempts = if !has_body && is_idempotent(&method) {
+            self.config.max_retries + 1
+        } else {
+            1
+        };
+        let mut body = body.filter(|_| has_body);
+        let too_large = Arc::new(AtomicBool::new(false));
+
+        self.count_request(service_name).await;
+        let mut attempt = 0;
+        let response = loop {
+            if attempt > 0 {
+                tokio::time::sleep(self.config.retry_backoff(attempt)).await;
+                self.stats.write().await.retried_requests += 1;
+            }
+            attempt += 1;
+            let last_attempt = attempt >= max_attempts;
+
+            if !self.circuit_allows(service_name).await {
+                return Err(ProxyError::CircuitOpen(service_name.to_string()));
+            }
+
+            let mut upstream_request = self
+                .client_for(service_name)
+                .request(method.clone(), &url)
+                .headers(headers.clone());
+            if let Some(ref signer) = self.signer {
+                let payload = match (&buffered, has_body) {
+                    (Some(bytes), _) => Some(bytes.as_ref()),
+                    (None, false) => Some(&[][..]),
+                    (None, true) => None,
+                };
+                let identity = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+                let signature = signer.sign_identity_now(
+                    method.as_str(),
+                    &signed_path,
+                    payload,
+                    SignedIdentity::user(identity("x-user-id"), identity("x-user-role"))
+                        .with_organization(identity("x-org-id"), identity("x-org-role"))
+                        .with_scopes(identity(permissions::SCOPES_HEADER)),
+                );
+                for (name, value) in signature.pairs() {
+                    upstream_request = upstream_request.header(name, value);
                 }
             }
+            if let Some(ref bytes) = buffered {
+                upstream_request = upstream_request.body(bytes.clone());
+            } else if let Some(body) = body.take() {
+                let (tx, rx) = mpsc::channel(8);
+                tokio::spawn(pump_body(body, tx, max_body_bytes, too_large.clone()));
+                let stream = futures_util::stream::unfold(rx, |mut rx| async move {
+                    rx.recv().await.map(|chunk| (chunk, rx))
+                });
+                upstream_request = upstream_request.body(reqwest::Body::wrap_stream(stream));
+            }
+
+            let start_time = Instant::now();
+            match upstream_request.send().await {
+                Ok(response) => {
+                    let status = response.status();
+                    self.record_attempt(service_name, start_time.elapsed(), !status.is_server_error())
+                        .await;
+                    if status.is_server_error() && !last_attempt {
+                        debug!("{} answered {}, retrying", service_name, status);
+                        continue;
+                    }
+                    break response;
+                }
+                Err(_) if too_large.load(Ordering::Relaxed) => {
+                    self.release_probe(service_name).await;
+                    return Err(ProxyError::PayloadTooLarge(max_body_bytes));
+                }
+                Err(e) => {
+                    warn!("Proxy request to {} failed: {}", service_name, e);
+                    self.record_attempt(service_name, start_time.elapsed(), false).await;
+                    if !last_attempt {
+                        continue;
+                    }
+                    return Err(if e.is_timeout() {
+                        ProxyError::Timeout(service_name.to_string())
+                    } else {
+                        ProxyError::Upstream(service_name.to_string(), e.to_string())
+                    });
+                }
+            }
+        };
+
+        let mut builder = axum::response::Response::builder().status(response.status().as_u16());
+        for (name, value) in response.headers() {
+            if !HOP_BY_HOP_HEADERS.contains(&name.as_str()) {
+                builder = builder.header(name.as_str(), value.as_bytes());
+            }
+        }
+
+        builder
+            .body(Body::from_stream(response.bytes_stream()))
+            .map_err(|e| ProxyError::Upstream(service_name.to_string(), e.to_string()))
+    }
+
+    /// Connection pool for a service, falling back to the shared client
+    fn client_for(&self, service_name: &str) -> &Client {
+        self.clients.get(service_name).unwrap_or(&self.client)
+    }
+
+    /// Whether the circuit breaker lets a request through to the service
+    async fn circuit_allows(&self, service_name: &str) -> bool {
+        let mut breakers = self.circuit_breakers.write().await;
+        let breaker = breakers
+            .entry(service_name.to_string())
+            .or_insert_with(|| CircuitBreaker::from_config(&self.config));
+
+        if breaker.can_attempt_request() {
+            return true;
+        }
+
+        self.stats.write().await.circuit_breaker_trips += 1;
+        false
+    }
+
+    async fn release_probe(&self, service_name: &str) {
+        if let Some(breaker) = self.circuit_breakers.write().await.get_mut(service_name) {
+            breaker.release_probe();
         }
+    }
 
-        // All retries failed - record circuit breaker failure
+    async fn count_request(&self, service_name: &str) {
+        let mut stats = self.stats.write().await;
+        stats.total_requests += 1;
+        *stats.requests_by_service.entry(service_name.to_string()).or_insert(0) += 1;
+    }
+
+    /// Update stats and the circuit breaker after one attempt
+    async fn record_attempt(&self, service_name: &str, elapsed: Duration, success: bool) {
         {
-            let mut breakers = self.circuit_breakers.write().await;
-            if let Some(breaker) = breakers.get_mut(service_name) {
+            let mut stats = self.stats.write().await;
+            let total = stats.successful_requests + stats.failed_requests;
+            stats.avg_response_time_ms =
+                (stats.avg_response_time_ms * total + elapsed.as_millis() as u64) / (total + 1);
+            if success {
+                stats.successful_requests += 1;
+            } else {
+                stats.failed_requests += 1;
+            }
+        }
+
+        let mut breakers = self.circuit_breakers.write().await;
+        if let Some(breaker) = breakers.get_mut(service_name) {
+            if success {
+                breaker.record_success();
+            } else {
                 breaker.record_failure();
             }
         }
+    }
 
-        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("Request failed after {} retries", self.config.max_retries)))
+    /// Names of all registered upstream services
+    pub fn service_names(&self) -> impl Iterator<Item = &str> {
+        self.registry.services.keys().map(String::as_str)
     }
 
-    /// Check health of a service
+    /// Check health of a service. Bypasses the circuit breaker so a
+    /// recovering service shows up as soon as it answers.
     pub async fn health_check(&self, service_name: &str) -> Result<bool> {
-        let registry = ServiceRegistry::default_services();
-        let endpoint = registry
+        let endpoint = self
+            .registry
             .get(service_name)
             .ok_or_else(|| anyhow::anyhow!("Service not found: {}", service_name))?;
 
@@ -411,10 +970,13 @@ impl ProxyService {
 
         let url = format!("{}{}", endpoint.base_url, health_path);
 
-        match self.client.get(&url).send().await {
-            Ok(response) => Ok(response.status().is_success()),
-            Err(_) => Ok(false),
-        }
+        let response = self
+            .client_for(service_name)
+            .get(&url)
+            .send()
+            .await
+            .with_context(|| format!("{} is unreachable", service_name))?;
+        Ok(response.status().is_success())
     }
 
     /// Get proxy statistics
@@ -429,6 +991,23 @@ impl ProxyService {
         info!("Proxy statistics reset");
     }
 
+    /// Circuit breaker state of every registered upstream; upstreams that
+    /// have not been called yet are reported closed
+    pub async fn circuit_breaker_snapshots(&self) -> BTreeMap<String, CircuitBreakerSnapshot> {
+        let breakers = self.circuit_breakers.read().await;
+        self.registry
+            .services
+            .keys()
+            .map(|name| {
+                let snapshot = breakers
+                    .get(name)
+                    .map(CircuitBreaker::snapshot)
+                    .unwrap_or_else(|| CircuitBreaker::from_config(&self.config).snapshot());
+                (name.clone(), snapshot)
+            })
+            .collect()
+    }
+
     /// Get circuit breaker status for a service
     pub async fn get_circuit_breaker_state(&self, service_name: &str) -> Option<CircuitBreakerState> {
         let breakers = self.circuit_breakers.read().await;
@@ -440,9 +1019,7 @@ impl ProxyService {
         let mut breakers = self.circuit_breakers.write().await;
 
         if let Some(breaker) = breakers.get_mut(service_name) {
-            breaker.state = CircuitBreakerState::Closed;
-            breaker.failure_count = 0;
-            breaker.last_failure_time = None;
+            breaker.reset();
             info!("Circuit breaker reset for service: {}", service_name);
             Ok(())
         } else {
@@ -477,18 +1054,132 @@ impl ProxyService {
     }
 }
 
+/// Headers sent upstream: the caller's end-to-end headers, plus forwarding
+/// information, the request ID and trace context, the authenticated
+/// identity and the service credential
+fn upstream_headers(parts: &axum::http::request::Parts, endpoint: &ServiceEndpoint) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    for (name, value) in &parts.headers {
+        let name = name.as_str();
+        if HOP_BY_HOP_HEADERS.contains(&name) || GATEWAY_OWNED_HEADERS.contains(&name) {
+            continue;
+        }
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_bytes(value.as_bytes()),
+        ) {
+            headers.append(name, value);
+        }
+    }
+
+    let mut set = |name: &'static str, value: &str| {
+        if let Ok(value) = HeaderValue::from_str(value) {
+            headers.insert(name, value);
+        }
+    };
+
+    let forwarded_for = parts
+        .headers
+        .get("x-forwarded-for")
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    let peer = parts
+        .extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string());
+    match (forwarded_for, peer) {
+        (Some(chain), Some(peer)) => set("x-forwarded-for", &format!("{}, {}", chain, peer)),
+        (Some(chain), None) => set("x-forwarded-for", &chain),
+        (None, Some(peer)) => set("x-forwarded-for", &peer),
+        (None, None) => {}
+    }
+    if let Some(host) = parts.headers.get(axum::http::header::HOST).and_then(|v| v.to_str().ok()) {
+        set("x-forwarded-host", host);
+    }
+    // The caller's IDs were taken over by `log_context_middleware`; the
+    // upstream's span becomes a child of the gateway's
+    for (name, value) in observability::propagation_headers() {
+        set(name, &value);
+    }
+    if let Some(claims) = parts.extensions.get::<Claims>() {
+        set("x-user-id", &claims.sub.to_string());
+        set("x-user-role", &claims.role);
+    }
+    if let Some(context) = parts.extensions.get::<AuthContext>() {
+        let scopes: Scopes = context.permissions.iter().map(String::as_str).collect();
+        set(permissions::SCOPES_HEADER, &scopes.to_string());
+        if let (Some(org_id), Some(org_role)) = (&context.organization_id, &context.organization_role) {
+            set("x-org-id", org_id);
+            set("x-org-role", org_role);
+        }
+    }
+    if let Some(ref api_key) = endpoint.api_key {
+        set("x-api-key", api_key);
+    }
+
+    headers
+}
+
+/// Path and query of an upstream URL as it goes on the wire, which is what
+/// the request signature covers
+fn path_and_query(url: &reqwest::Url) -> String {
+    match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    }
+}
+
+/// Copy the client body into the channel feeding the upstream request,
+/// failing the stream once it grows past `limit`
+async fn pump_body(
+    body: Body,
+    tx: mpsc::Sender<std::io::Result<Bytes>>,
+    limit: u64,
+    too_large: Arc<AtomicBool>,
+) {
+    let mut stream = body.into_data_stream();
+    let mut received = 0u64;
+
+    while let Some(chunk) = stream.next().await {
+        let item = match chunk {
+            Ok(bytes) => {
+                received += bytes.len() as u64;
+                if received > limit {
+                    too_large.store(true, Ordering::Relaxed);
+                    Err(std::io::Error::other("request body too large"))
+                } else {
+                    Ok(bytes)
+                }
+            }
+            Err(e) => Err(std::io::Error::other(e)),
+        };
+
+        let failed = item.is_err();
+        if tx.send(item).await.is_err() || failed {
+            break;
+        }
+    }
+}
+
 /// Builder for proxy service configuration
 pub struct ProxyServiceBuilder {
     config: ProxyConfig,
+    registry: Option<ServiceRegistry>,
 }
 
 impl ProxyServiceBuilder {
     pub fn new() -> Self {
         Self {
             config: ProxyConfig::default(),
+            registry: None,
         }
     }
 
+    pub fn registry(mut self, registry: ServiceRegistry) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
     pub fn timeout(mut self, seconds: u64) -> Self {
         self.config.timeout_seconds = seconds;
         self
@@ -510,13 +1201,18 @@ impl ProxyServiceBuilder {
     }
 
     pub fn build(self) -> Result<ProxyService> {
-        ProxyService::new(self.config)
+        match self.registry {
+            Some(registry) => ProxyService::with_registry(self.config, registry),
+            None => ProxyService::new(self.config),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use shared::observability::{with_log_context, LogContext, TraceParent};
+    use uuid::Uuid;
 
     #[test]
     fn test_circuit_breaker_closed_state() {
@@ -550,6 +1246,64 @@ mod tests {
         assert_eq!(breaker.failure_count, 0);
     }
 
+    #[test]
+    fn test_circuit_breaker_opens_on_failure_rate() {
+        let mut breaker = CircuitBreaker::from_config(&ProxyConfig {
+            circuit_breaker_threshold: 100,
+            circuit_breaker_window: 10,
+            circuit_breaker_min_requests: 10,
+            circuit_breaker_failure_rate: 0.5,
+            ..ProxyConfig::default()
+        });
+
+        // Alternating outcomes never reach the consecutive threshold
+        for _ in 0..4 {
+            breaker.record_success();
+            breaker.record_failure();
+        }
+        breaker.record_success();
+        assert_eq!(breaker.state, CircuitBreakerState::Closed);
+
+        breaker.record_failure();
+        assert_eq!(breaker.state, CircuitBreakerState::Open);
+    }
+
+    #[test]
+    fn test_circuit_breaker_half_open_probes() {
+        let mut breaker = CircuitBreaker::new(1, 0);
+        breaker.record_failure();
+        assert_eq!(breaker.state, CircuitBreakerState::Open);
+
+        // One probe at a time while half-open
+        assert!(breaker.can_attempt_request());
+        assert_eq!(breaker.state, CircuitBreakerState::HalfOpen);
+        assert!(!breaker.can_attempt_request());
+
+        // A failed probe reopens the circuit
+        breaker.record_failure();
+        assert_eq!(breaker.state, CircuitBreakerState::Open);
+
+        assert!(breaker.can_attempt_request());
+        breaker.record_success();
+        assert_eq!(breaker.state, CircuitBreakerState::Closed);
+        assert!(breaker.can_attempt_request());
+    }
+
+    #[test]
+    fn test_retry_backoff_is_bounded() {
+        let config = ProxyConfig {
+            retry_delay_ms: 100,
+            retry_max_delay_ms: 300,
+            ..ProxyConfig::default()
+        };
+
+        for _ in 0..50 {
+            assert!(config.retry_backoff(1) <= Duration::from_millis(100));
+            assert!(config.retry_backoff(2) <= Duration::from_millis(200));
+            assert!(config.retry_backoff(10) <= Duration::from_millis(300));
+        }
+    }
+
     #[test]
     fn test_service_registry() {
         let mut registry = ServiceRegistry::new();
@@ -562,6 +1316,8 @@ mod tests {
                 health_check_path: Some("/health".to_string()),
                 api_version: "v1".to_string(),
                 requires_auth: true,
+                api_key: None,
+                settings: UpstreamSettings::default(),
             },
         );
 
@@ -569,6 +1325,122 @@ mod tests {
         assert!(registry.get("unknown-service").is_none());
     }
 
+    #[test]
+    fn test_upstream_headers_replace_identity() {
+        let user_id = Uuid::new_v4();
+        let mut request = axum::http::Request::builder()
+            .uri("/api/v1/analysis/file")
+            .header("authorization", "Bearer token")
+            .header("x-user-id", "spoofed")
+            .header("x-org-role", "owner")
+            .header("x-api-key", "client-key")
+            .header("connection", "keep-alive")
+            .header("content-type", "multipart/form-data; boundary=x")
+            .header("x-forwarded-for", "203.0.113.7")
+            .header("x-nexus-signature", "forged")
+            .body(())
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(Claims::new(user_id, "a@b.c".to_string(), "user".to_string(), 1));
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([10, 0, 0, 1], 4000))));
+        let (parts, _) = request.into_parts();
+
+        let endpoint = ServiceEndpoint {
+            name: "Analysis Engine".to_string(),
+            base_url: "http://localhost:8081".to_string(),
+            health_check_path: None,
+            api_version: "v1".to_string(),
+            requires_auth: true,
+            api_key: Some("gateway-key".to_string()),
+            settings: UpstreamSettings::default(),
+        };
+        let headers = upstream_headers(&parts, &endpoint);
+
+        assert!(headers.get("authorization").is_none());
+        assert!(headers.get("x-org-role").is_none());
+        assert!(headers.get("connection").is_none());
+        assert!(headers.get("x-nexus-signature").is_none());
+        assert_eq!(headers["x-user-id"], user_id.to_string().as_str());
+        assert_eq!(headers["x-api-key"], "gateway-key");
+        assert_eq!(headers["x-forwarded-for"], "203.0.113.7, 10.0.0.1");
+        assert_eq!(headers["content-type"], "multipart/form-data; boundary=x");
+        assert!(headers.contains_key("x-request-id"));
+        assert!(headers.contains_key("traceparent"));
+    }
+
+    #[tokio::test]
+    async fn test_upstream_headers_continue_trace() {
+        let (parts, _) = axum::http::Request::builder()
+            .uri("/api/v1/bounties/42/payout")
+            .header("x-request-id", "client-chosen")
+            .header("traceparent", "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01")
+            .body(())
+            .unwrap()
+            .into_parts();
+        let endpoint = ServiceEndpoint {
+            name: "Bounty Manager".to_string(),
+            base_url: "http://localhost:8082".to_string(),
+            health_check_path: None,
+            api_version: "v1".to_string(),
+            requires_auth: true,
+            api_key: None,
+            settings: UpstreamSettings::default(),
+        };
+
+        let context = LogContext::from_headers(Some("req-payout-7"), parts.headers["traceparent"].to_str().ok());
+        let gateway_span = context.span_id.clone().unwrap();
+        let headers = with_log_context(context, async { upstream_headers(&parts, &endpoint) }).await;
+
+        assert_eq!(headers["x-request-id"], "req-payout-7");
+        assert_eq!(headers.get_all("traceparent").iter().count(), 1);
+        let traceparent = TraceParent::parse(headers["traceparent"].to_str().unwrap()).unwrap();
+        assert_eq!(traceparent.trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(traceparent.parent_id, gateway_span);
+    }
+
+    #[test]
+    fn test_upstream_headers_carry_organization() {
+        let claims = Claims::new(Uuid::new_v4(), "a@b.c".to_string(), "user".to_string(), 1);
+        let org_id = Uuid::new_v4().to_string();
+        let mut request = axum::http::Request::builder()
+            .uri("/api/v1/bounties")
+            .header("x-org-id", "spoofed")
+            .body(())
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(claims.auth_context().with_organization(org_id.clone(), "billing"));
+        request.extensions_mut().insert(claims);
+        let (parts, _) = request.into_parts();
+        let endpoint = ServiceEndpoint {
+            name: "Bounty Manager".to_string(),
+            base_url: "http://localhost:8082".to_string(),
+            health_check_path: None,
+            api_version: "v1".to_string(),
+            requires_auth: true,
+            api_key: None,
+            settings: UpstreamSettings::default(),
+        };
+
+        let headers = upstream_headers(&parts, &endpoint);
+        assert_eq!(headers.get_all("x-org-id").iter().count(), 1);
+        assert_eq!(headers["x-org-id"], org_id.as_str());
+        assert_eq!(headers["x-org-role"], "billing");
+        assert_eq!(headers["x-user-scopes"], "analysis:submit bounty:create webhooks:manage");
+    }
+
+    #[test]
+    fn test_signed_path_matches_wire_format() {
+        let url = reqwest::Url::parse("http://payment-service:8080/api/v1/payments/balance/0xAb?page=2&q=a b").unwrap();
+        assert_eq!(path_and_query(&url), "/api/v1/payments/balance/0xAb?page=2&q=a%20b");
+
+        let url = reqwest::Url::parse("http://payment-service:8080/api/v1/payments/stake/slash").unwrap();
+        assert_eq!(path_and_query(&url), "/api/v1/payments/stake/slash");
+    }
+
     #[test]
     fn test_proxy_config_defaults() {
         let config = ProxyConfig::default();
@@ -0,0 +1,298 @@
+//! Organization quotas
+//!
+//! Enterprise customers get limits pooled across every member and API key
+//! of their organization: a shared requests-per-minute window and daily
+//! budgets of submissions and analyses. Definitions live in Postgres
+//! (`organization_quotas`, NULL meaning unlimited) and are cached in Redis;
+//! consumption is counted in Redis per UTC day.
+
+use anyhow::{Context, Result};
+use axum::http::Method;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use redis::aio::MultiplexedConnection;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::middleware::route_policy::{api_relative, pattern_matches};
+
+/// Daily counters outlive their day so late refunds still find them
+const COUNTER_TTL_SECS: i64 = 2 * 24 * 60 * 60;
+
+/// Increment a daily counter unless it would exceed the limit.
+/// Returns `{allowed, used}`.
+const CONSUME_SCRIPT: &str = r#"
+local used = redis.call('INCR', KEYS[1])
+if used == 1 then
+    redis.call('EXPIRE', KEYS[1], ARGV[2])
+end
+if used > tonumber(ARGV[1]) then
+    redis.call('DECR', KEYS[1])
+    return {0, used - 1}
+end
+return {1, used}
+"#;
+
+/// Daily budgets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QuotaBudget {
+    /// Samples handed to the submission pipeline
+    Submissions,
+    /// Files, URLs and hashes scanned directly by the analysis engine
+    Analyses,
+}
+
+/// Requests that draw from a budget
+const BUDGET_ROUTES: &[(&str, QuotaBudget)] = &[
+    ("/submissions", QuotaBudget::Submissions),
+    ("/submissions/file", QuotaBudget::Submissions),
+    ("/submissions/url", QuotaBudget::Submissions),
+    ("/analysis/file", QuotaBudget::Analyses),
+    ("/analysis/url", QuotaBudget::Analyses),
+    ("/analysis/hash", QuotaBudget::Analyses),
+];
+
+impl QuotaBudget {
+    pub const ALL: [QuotaBudget; 2] = [QuotaBudget::Submissions, QuotaBudget::Analyses];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuotaBudget::Submissions => "submissions",
+            QuotaBudget::Analyses => "analyses",
+        }
+    }
+
+    /// Budget a request draws from, if any
+    pub fn for_request(method: &Method, path: &str) -> Option<Self> {
+        if method != Method::POST {
+            return None;
+        }
+        let path = api_relative(path);
+        BUDGET_ROUTES
+            .iter()
+            .find(|(pattern, _)| pattern_matches(pattern, path))
+            .map(|(_, budget)| *budget)
+    }
+
+    /// Daily limit of this budget, `None` when unlimited
+    pub fn limit(&self, quota: &OrganizationQuota) -> Option<u32> {
+        let limit = match self {
+            QuotaBudget::Submissions => quota.daily_submissions,
+            QuotaBudget::Analyses => quota.daily_analyses,
+        };
+        limit.map(|l| l.max(0) as u32)
+    }
+}
+
+/// Limits of an organization; `None` means unlimited
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
+pub struct OrganizationQuota {
+    /// Requests per minute shared by every member and API key
+    pub requests_per_minute: Option<i32>,
+    pub daily_submissions: Option<i32>,
+    pub daily_analyses: Option<i32>,
+}
+
+/// Outcome of drawing from a daily budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuotaDecision {
+    pub allowed: bool,
+    pub limit: u32,
+    pub used: u32,
+    pub reset_at: DateTime<Utc>,
+}
+
+impl QuotaDecision {
+    pub fn remaining(&self) -> u32 {
+        self.limit.saturating_sub(self.used)
+    }
+}
+
+/// Budgets reset at the next UTC midnight
+pub fn quota_reset_at(now: DateTime<Utc>) -> DateTime<Utc> {
+    (now.date_naive() + Duration::days(1)).and_hms_opt(0, 0, 0).unwrap().and_utc()
+}
+
+fn counter_key(organization_id: Uuid, date: NaiveDate, budget: QuotaBudget) -> String {
+    format!("quota:{}:{}:{}", organization_id, date.format("%Y%m%d"), budget.as_str())
+}
+
+fn definition_key(organization_id: Uuid) -> String {
+    format!("quota:def:{}", organization_id)
+}
+
+/// Loads quota definitions and tracks consumption
+pub struct QuotaService {
+    pool: PgPool,
+    redis: MultiplexedConnection,
+    cache_seconds: u64,
+}
+
+impl QuotaService {
+    pub fn new(pool: PgPool, redis: MultiplexedConnection, cache_seconds: u64) -> Self {
+        Self { pool, redis, cache_seconds }
+    }
+
+    /// Limits of an organization (cached in Redis); unlimited if none are set
+    pub async fn quota(&self, organization_id: Uuid) -> Result<OrganizationQuota> {
+        let cache_key = definition_key(organization_id);
+        let mut conn = self.redis.clone();
+
+        let cached: Option<String> = conn.get(&cache_key).await?;
+        if let Some(quota) = cached.and_then(|c| serde_json::from_str(&c).ok()) {
+            return Ok(quota);
+        }
+
+        let quota = sqlx::query_as::<_, OrganizationQuota>(
+            r#"
+            SELECT requests_per_minute, daily_submissions, daily_analyses
+            FROM organization_quotas
+            WHERE organization_id = $1
+            "#,
+        )
+        .bind(organization_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .unwrap_or_default();
+
+        if self.cache_seconds > 0 {
+            let _: () = conn
+                .set_ex(&cache_key, serde_json::to_string(&quota)?, self.cache_seconds)
+                .await?;
+        }
+        Ok(quota)
+    }
+
+    /// Replace an organization's limits. Returns `None` if the organization
+    /// does not exist.
+    pub async fn set_quota(
+        &self,
+        organization_id: Uuid,
+        quota: &OrganizationQuota,
+    ) -> Result<Option<OrganizationQuota>> {
+        let stored = sqlx::query_as::<_, OrganizationQuota>(
+            r#"
+            INSERT INTO organization_quotas
+                (organization_id, requests_per_minute, daily_submissions, daily_analyses)
+            SELECT id, $2, $3, $4 FROM organizations WHERE id = $1
+            ON CONFLICT (organization_id) DO UPDATE SET
+                requests_per_minute = EXCLUDED.requests_per_minute,
+                daily_submissions = EXCLUDED.daily_submissions,
+                daily_analyses = EXCLUDED.daily_analyses,
+                updated_at = NOW()
+            RETURNING requests_per_minute, daily_submissions, daily_analyses
+            "#,
+        )
+        .bind(organization_id)
+        .bind(quota.requests_per_minute)
+        .bind(quota.daily_submissions)
+        .bind(quota.daily_analyses)
+        .fetch_optional(&self.pool)
+        .await
+        .context("Failed to store organization quota")?;
+
+        // Every instance picks up the change on its next lookup
+        let mut conn = self.redis.clone();
+        let _: () = conn.del(definition_key(organization_id)).await?;
+
+        Ok(stored)
+    }
+
+    /// Draw one unit from today's budget
+    pub async fn consume(
+        &self,
+        organization_id: Uuid,
+        budget: QuotaBudget,
+        limit: u32,
+    ) -> Result<QuotaDecision> {
+        let now = Utc::now();
+        let mut conn = self.redis.clone();
+        let (allowed, used): (i32, u32) = redis::Script::new(CONSUME_SCRIPT)
+            .key(counter_key(organization_id, now.date_naive(), budget))
+            .arg(limit)
+            .arg(COUNTER_TTL_SECS)
+            .invoke_async(&mut conn)
+            .await
+            .context("Failed to consume quota")?;
+
+        Ok(QuotaDecision {
+            allowed: allowed == 1,
+            limit,
+            used,
+            reset_at: quota_reset_at(now),
+        })
+    }
+
+    /// Give back a unit drawn for a request that failed
+    pub async fn refund(&self, organization_id: Uuid, budget: QuotaBudget, drawn_at: DateTime<Utc>) -> Result<()> {
+        let key = counter_key(organization_id, drawn_at.date_naive(), budget);
+        let mut conn = self.redis.clone();
+        let used: i64 = conn.decr(&key, 1).await?;
+        if used < 0 {
+            let _: () = conn.del(&key).await?;
+        }
+        Ok(())
+    }
+
+    /// Units of a budget used today
+    pub async fn used_today(&self, organization_id: Uuid, budget: QuotaBudget) -> Result<u32> {
+        let mut conn = self.redis.clone();
+        let used: Option<u32> = conn
+            .get(counter_key(organization_id, Utc::now().date_naive(), budget))
+            .await?;
+        Ok(used.unwrap_or(0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_budget_for_request() {
+        for (method, path, budget) in [
+            (Method::POST, "/api/v1/submissions/file", Some(QuotaBudget::Submissions)),
+            (Method::POST, "/submissions/url", Some(QuotaBudget::Submissions)),
+            (Method::POST, "/api/submissions", Some(QuotaBudget::Submissions)),
+            (Method::POST, "/api/v1/analysis/hash", Some(QuotaBudget::Analyses)),
+            (Method::POST, "/analysis/file", Some(QuotaBudget::Analyses)),
+            // Engine verdicts and reads are not budgeted
+            (Method::POST, "/api/v1/analysis/submit", None),
+            (Method::POST, "/api/v1/submissions/42/vote", None),
+            (Method::GET, "/api/v1/submissions/file", None),
+        ] {
+            assert_eq!(QuotaBudget::for_request(&method, path), budget, "{} {}", method, path);
+        }
+    }
+
+    #[test]
+    fn test_budget_limits() {
+        let quota = OrganizationQuota {
+            requests_per_minute: None,
+            daily_submissions: Some(500),
+            daily_analyses: None,
+        };
+        assert_eq!(QuotaBudget::Submissions.limit(&quota), Some(500));
+        assert_eq!(QuotaBudget::Analyses.limit(&quota), None);
+    }
+
+    #[test]
+    fn test_quota_resets_at_utc_midnight() {
+        let now = Utc.with_ymd_and_hms(2026, 12, 31, 23, 59, 30).unwrap();
+        assert_eq!(quota_reset_at(now), Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_counter_key() {
+        let org = Uuid::nil();
+        let date = NaiveDate::from_ymd_opt(2026, 3, 9).unwrap();
+        assert_eq!(
+            counter_key(org, date, QuotaBudget::Analyses),
+            format!("quota:{}:20260309:analyses", org)
+        );
+    }
+}
@@ -0,0 +1,670 @@
+//! Role-based access control
+//!
+//! Roles map to permissions in Postgres (`roles`, `role_permissions`) and
+//! users can be granted roles on top of the one in their access token
+//! (`user_roles`), directly or through their organization
+//! (`organization_roles`). A caller's permissions are the union of all of
+//! them; the auth middleware resolves them once per request into the
+//! `AuthContext` that route policies and `require_permission` check, and the
+//! proxy forwards them to services as scopes (`shared::permissions`).
+//!
+//! Access tokens carry the permissions held when they were issued as
+//! `scopes`. A token never grants more than its scopes, and grants revoked
+//! since it was issued stop applying once the cache below expires.
+//!
+//! Callers who belong to an organization carry it and their role in it, and
+//! organization API keys authenticate as the organization with the key's
+//! role.
+//!
+//! Role tables, per-user grants and memberships are cached for [`CACHE_TTL`], so a change
+//! made on another gateway instance takes effect within that window. If
+//! Postgres is unreachable the built-in defaults for the token's role apply.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::{BTreeSet, HashMap};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tokio::sync::RwLock;
+use tracing::warn;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::middleware::auth::{Claims, REFRESH_TOKEN_ROLE};
+use crate::middleware::route_policy::{
+    SCOPE_ADMIN_CONSOLE, SCOPE_ANALYSIS_SUBMIT, SCOPE_BOUNTY_CREATE, SCOPE_BOUNTY_MANAGE,
+    SCOPE_ROLES_MANAGE, SCOPE_SUBMISSIONS_VERIFY, SCOPE_WEBHOOKS_MANAGE,
+};
+use crate::utils::{AuthContext, HashUtils};
+use shared::permissions;
+use shared::types::OrganizationRole;
+
+pub const CACHE_TTL: Duration = Duration::from_secs(30);
+/// Prefix of organization API keys
+pub const API_KEY_PREFIX: &str = "nxs_";
+/// Token role of callers using an organization API key
+const API_KEY_ROLE: &str = "user";
+const MAX_CACHED_USERS: usize = 10_000;
+const FOREIGN_KEY_VIOLATION: &str = "23503";
+
+/// Permission that implies every other one
+pub const PERMISSION_ALL: &str = permissions::ALL;
+
+/// Every permission a role can be granted, with a description
+pub const PERMISSIONS: &[(&str, &str)] = &[
+    (PERMISSION_ALL, "Every permission"),
+    (SCOPE_ADMIN_CONSOLE, "Administration APIs such as billing and archives"),
+    (SCOPE_ANALYSIS_SUBMIT, "Submit artifacts and analyses"),
+    (SCOPE_BOUNTY_CREATE, "Create bounties"),
+    (SCOPE_BOUNTY_MANAGE, "Update, extend, cancel and finalize any bounty"),
+    (SCOPE_ROLES_MANAGE, "Define roles and assign them to users"),
+    (SCOPE_SUBMISSIONS_VERIFY, "Verify engine submissions"),
+    (SCOPE_WEBHOOKS_MANAGE, "Manage webhook subscriptions"),
+    (permissions::KYC_REVIEW, "Review KYC submissions and revoke certificates"),
+    (permissions::USERS_MANAGE, "List, suspend and reactivate accounts"),
+    (permissions::USERS_IMPERSONATE, "Act as another user"),
+    (permissions::BOUNTY_MODERATE, "Moderate bounties, embargoes and reports"),
+    (permissions::PAYMENTS_APPROVE, "Approve withdrawals and operate the treasury"),
+    (permissions::CONSENSUS_OVERRIDE, "Override, replay and recalculate consensus"),
+    (permissions::REPUTATION_MANAGE, "Adjust reputation, decay, badges and appeals"),
+];
+
+/// Permissions of the built-in roles, used when Postgres is unavailable.
+/// Must match the seed data in `migrations/005_rbac.sql` and
+/// `migrations/009_permission_scopes.sql`.
+pub fn default_permissions(role: &str) -> Vec<String> {
+    let permissions: &[&str] = match role {
+        "admin" => &[PERMISSION_ALL],
+        "moderator" => &[
+            SCOPE_ADMIN_CONSOLE,
+            SCOPE_ANALYSIS_SUBMIT,
+            SCOPE_BOUNTY_CREATE,
+            SCOPE_BOUNTY_MANAGE,
+            SCOPE_SUBMISSIONS_VERIFY,
+            SCOPE_WEBHOOKS_MANAGE,
+            permissions::BOUNTY_MODERATE,
+        ],
+        "user" => &[SCOPE_ANALYSIS_SUBMIT, SCOPE_BOUNTY_CREATE, SCOPE_WEBHOOKS_MANAGE],
+        _ => &[],
+    };
+    permissions.iter().map(|p| p.to_string()).collect()
+}
+
+#[derive(Debug, Error)]
+pub enum RbacError {
+    #[error("Unknown permission: {0}")]
+    UnknownPermission(String),
+    #[error("Invalid role name: {0}")]
+    InvalidRoleName(String),
+    #[error("Role not found: {0}")]
+    RoleNotFound(String),
+    #[error("User not found: {0}")]
+    UserNotFound(Uuid),
+    #[error("Organization not found: {0}")]
+    OrganizationNotFound(Uuid),
+    #[error("Built-in role cannot be deleted: {0}")]
+    SystemRole(String),
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// A role and the permissions it grants
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct Role {
+    pub name: String,
+    pub description: Option<String>,
+    /// Built-in roles can be edited but not deleted
+    pub is_system: bool,
+    pub permissions: Vec<String>,
+}
+
+/// A role granted to a user
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, ToSchema)]
+pub struct RoleGrant {
+    pub role: String,
+    pub granted_by: Option<Uuid>,
+    pub granted_at: DateTime<Utc>,
+}
+
+/// A role granted to every member of an organization
+#[derive(Debug, Clone, Serialize, sqlx::FromRow, ToSchema)]
+pub struct OrganizationRoleGrant {
+    pub role: String,
+    pub granted_by: Option<Uuid>,
+    pub granted_at: DateTime<Utc>,
+}
+
+/// Body of a role upsert
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct RoleDefinition {
+    pub description: Option<String>,
+    pub permissions: Vec<String>,
+}
+
+pub fn validate_role_name(name: &str) -> Result<(), RbacError> {
+    let valid = (2..=50).contains(&name.len())
+        && name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+        && name != REFRESH_TOKEN_ROLE;
+    if valid {
+        Ok(())
+    } else {
+        Err(RbacError::InvalidRoleName(name.to_string()))
+    }
+}
+
+pub fn validate_permissions(permissions: &[String]) -> Result<(), RbacError> {
+    match permissions
+        .iter()
+        .find(|p| !PERMISSIONS.iter().any(|(known, _)| known == p))
+    {
+        Some(unknown) => Err(RbacError::UnknownPermission(unknown.clone())),
+        None => Ok(()),
+    }
+}
+
+/// Union of the permissions of the token role and the assigned roles.
+/// Built-in roles missing from the table fall back to their defaults.
+pub fn effective_permissions(
+    table: &HashMap<String, Vec<String>>,
+    token_role: &str,
+    assigned: &[String],
+) -> Vec<String> {
+    let mut permissions = BTreeSet::new();
+    match table.get(token_role) {
+        Some(granted) => permissions.extend(granted.iter().cloned()),
+        None => permissions.extend(default_permissions(token_role)),
+    }
+    for role in assigned {
+        if let Some(granted) = table.get(role) {
+            permissions.extend(granted.iter().cloned());
+        }
+    }
+    permissions.into_iter().collect()
+}
+
+/// Live permissions limited to a token's scopes. Tokens without scopes
+/// (issued before scopes existed) are limited by the live permissions alone.
+pub fn narrow_to_scopes(live: Vec<String>, token_scopes: &[String]) -> Vec<String> {
+    if token_scopes.is_empty() {
+        return live;
+    }
+    if token_scopes.iter().any(|s| s == PERMISSION_ALL) {
+        return live;
+    }
+    let live_has_all = live.iter().any(|p| p == PERMISSION_ALL);
+    token_scopes
+        .iter()
+        .filter(|scope| live_has_all || live.contains(scope))
+        .cloned()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+type RoleTable = HashMap<String, Vec<String>>;
+
+/// Organization a caller belongs to and their role in it
+type Membership = Option<(Uuid, String)>;
+
+pub struct RbacService {
+    pool: PgPool,
+    roles: RwLock<Option<(Instant, RoleTable)>>,
+    user_roles: RwLock<HashMap<Uuid, (Instant, Vec<String>)>>,
+    memberships: RwLock<HashMap<Uuid, (Instant, Membership)>>,
+}
+
+impl RbacService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            roles: RwLock::new(None),
+            user_roles: RwLock::new(HashMap::new()),
+            memberships: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Caller context carrying the caller's effective permissions and
+    /// organization membership
+    pub async fn auth_context(&self, claims: &Claims) -> AuthContext {
+        let permissions = narrow_to_scopes(self.permissions_for(claims.sub, &claims.role).await, &claims.scopes);
+        let context = claims.auth_context_with(permissions);
+        if claims.role == REFRESH_TOKEN_ROLE {
+            return context;
+        }
+        match self.membership(claims.sub).await {
+            Ok(Some((organization_id, role))) => context.with_organization(organization_id.to_string(), role),
+            Ok(None) => context,
+            Err(e) => {
+                warn!("Failed to resolve organization of {}: {}", claims.sub, e);
+                context
+            }
+        }
+    }
+
+    /// Caller of an organization API key (`nxs_...`), acting for the
+    /// organization with the key's role. Revoked keys and keys of
+    /// deactivated organizations are rejected.
+    pub async fn api_key_context(&self, api_key: &str) -> Result<Option<(Claims, AuthContext)>, sqlx::Error> {
+        if !api_key.starts_with(API_KEY_PREFIX) {
+            return Ok(None);
+        }
+
+        let key: Option<(Uuid, Uuid, String)> = sqlx::query_as(
+            r#"
+            UPDATE organization_api_keys k SET last_used_at = NOW()
+            FROM organizations o
+            WHERE k.key_hash = $1 AND k.revoked_at IS NULL
+              AND o.id = k.organization_id AND o.is_active = TRUE
+            RETURNING k.id, k.organization_id, k.role
+            "#,
+        )
+        .bind(HashUtils::sha256(api_key.as_bytes()))
+        .fetch_optional(&self.pool)
+        .await?;
+        let Some((key_id, organization_id, role)) = key else {
+            return Ok(None);
+        };
+
+        let claims = Claims::new(key_id, String::new(), API_KEY_ROLE.to_string(), 1);
+        let permissions = match role.parse::<OrganizationRole>() {
+            Ok(role) if role.manages_bounties() => default_permissions(API_KEY_ROLE),
+            _ => Vec::new(),
+        };
+        let context = claims
+            .auth_context_with(permissions)
+            .with_api_key(key_id.to_string())
+            .with_organization(organization_id.to_string(), role);
+        Ok(Some((claims, context)))
+    }
+
+    /// Effective permissions of a user, falling back to the token role's
+    /// defaults when the tables cannot be read
+    pub async fn permissions_for(&self, user_id: Uuid, token_role: &str) -> Vec<String> {
+        if token_role == REFRESH_TOKEN_ROLE {
+            return Vec::new();
+        }
+
+        let resolved = async {
+            let table = self.role_table().await?;
+            let assigned = self.assigned_roles(user_id).await?;
+            Ok::<_, sqlx::Error>(effective_permissions(&table, token_role, &assigned))
+        };
+        match resolved.await {
+            Ok(permissions) => permissions,
+            Err(e) => {
+                warn!("Falling back to default permissions for {}: {}", user_id, e);
+                default_permissions(token_role)
+            }
+        }
+    }
+
+    async fn role_table(&self) -> Result<RoleTable, sqlx::Error> {
+        if let Some((loaded_at, table)) = self.roles.read().await.as_ref() {
+            if loaded_at.elapsed() < CACHE_TTL {
+                return Ok(table.clone());
+            }
+        }
+
+        let rows: Vec<(String, String)> =
+            sqlx::query_as("SELECT role, permission FROM role_permissions")
+                .fetch_all(&self.pool)
+                .await?;
+        let mut table = RoleTable::new();
+        for (role, permission) in rows {
+            table.entry(role).or_default().push(permission);
+        }
+        // Roles without permissions still override the built-in defaults
+        let names: Vec<(String,)> = sqlx::query_as("SELECT name FROM roles")
+            .fetch_all(&self.pool)
+            .await?;
+        for (name,) in names {
+            table.entry(name).or_default();
+        }
+
+        *self.roles.write().await = Some((Instant::now(), table.clone()));
+        Ok(table)
+    }
+
+    async fn assigned_roles(&self, user_id: Uuid) -> Result<Vec<String>, sqlx::Error> {
+        if let Some((loaded_at, roles)) = self.user_roles.read().await.get(&user_id) {
+            if loaded_at.elapsed() < CACHE_TTL {
+                return Ok(roles.clone());
+            }
+        }
+
+        let roles: Vec<(String,)> = sqlx::query_as(
+            r#"
+            SELECT role FROM user_roles WHERE user_id = $1
+            UNION
+            SELECT g.role
+            FROM organization_roles g
+            JOIN organization_members m ON m.organization_id = g.organization_id
+            JOIN organizations o ON o.id = g.organization_id
+            WHERE m.user_id = $1 AND o.is_active = TRUE
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+        let roles: Vec<String> = roles.into_iter().map(|(role,)| role).collect();
+
+        let mut cache = self.user_roles.write().await;
+        if cache.len() >= MAX_CACHED_USERS {
+            cache.retain(|_, (loaded_at, _)| loaded_at.elapsed() < CACHE_TTL);
+        }
+        cache.insert(user_id, (Instant::now(), roles.clone()));
+        Ok(roles)
+    }
+
+    async fn membership(&self, user_id: Uuid) -> Result<Membership, sqlx::Error> {
+        if let Some((loaded_at, membership)) = self.memberships.read().await.get(&user_id) {
+            if loaded_at.elapsed() < CACHE_TTL {
+                return Ok(membership.clone());
+            }
+        }
+
+        let membership: Membership = sqlx::query_as(
+            r#"
+            SELECT m.organization_id, m.role
+            FROM organization_members m
+            JOIN organizations o ON o.id = m.organization_id
+            WHERE m.user_id = $1 AND o.is_active = TRUE
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let mut cache = self.memberships.write().await;
+        if cache.len() >= MAX_CACHED_USERS {
+            cache.retain(|_, (loaded_at, _)| loaded_at.elapsed() < CACHE_TTL);
+        }
+        cache.insert(user_id, (Instant::now(), membership.clone()));
+        Ok(membership)
+    }
+
+    async fn invalidate_roles(&self) {
+        *self.roles.write().await = None;
+    }
+
+    async fn invalidate_user(&self, user_id: Uuid) {
+        self.user_roles.write().await.remove(&user_id);
+    }
+
+    pub async fn list_roles(&self) -> Result<Vec<Role>, RbacError> {
+        let roles: Vec<(String, Option<String>, bool)> =
+            sqlx::query_as("SELECT name, description, is_system FROM roles ORDER BY name")
+                .fetch_all(&self.pool)
+                .await?;
+        let table = self.role_table().await?;
+
+        Ok(roles
+            .into_iter()
+            .map(|(name, description, is_system)| {
+                let mut permissions = table.get(&name).cloned().unwrap_or_default();
+                permissions.sort();
+                Role {
+                    name,
+                    description,
+                    is_system,
+                    permissions,
+                }
+            })
+            .collect())
+    }
+
+    /// Create a role or replace its description and permissions
+    pub async fn upsert_role(&self, name: &str, definition: RoleDefinition) -> Result<Role, RbacError> {
+        validate_role_name(name)?;
+        validate_permissions(&definition.permissions)?;
+        let permissions: Vec<String> = definition
+            .permissions
+            .into_iter()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        let mut tx = self.pool.begin().await?;
+        let (is_system,): (bool,) = sqlx::query_as(
+            "INSERT INTO roles (name, description) VALUES ($1, $2)
+             ON CONFLICT (name) DO UPDATE SET description = EXCLUDED.description, updated_at = NOW()
+             RETURNING is_system",
+        )
+        .bind(name)
+        .bind(&definition.description)
+        .fetch_one(&mut *tx)
+        .await?;
+        sqlx::query("DELETE FROM role_permissions WHERE role = $1")
+            .bind(name)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("INSERT INTO role_permissions (role, permission) SELECT $1, UNNEST($2::text[])")
+            .bind(name)
+            .bind(&permissions)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        self.invalidate_roles().await;
+
+        Ok(Role {
+            name: name.to_string(),
+            description: definition.description,
+            is_system,
+            permissions,
+        })
+    }
+
+    /// Delete a custom role; its grants go with it
+    pub async fn delete_role(&self, name: &str) -> Result<(), RbacError> {
+        let is_system: Option<(bool,)> = sqlx::query_as("SELECT is_system FROM roles WHERE name = $1")
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await?;
+        match is_system {
+            None => return Err(RbacError::RoleNotFound(name.to_string())),
+            Some((true,)) => return Err(RbacError::SystemRole(name.to_string())),
+            Some((false,)) => {}
+        }
+
+        sqlx::query("DELETE FROM roles WHERE name = $1 AND NOT is_system")
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+        self.invalidate_roles().await;
+        self.user_roles.write().await.clear();
+        Ok(())
+    }
+
+    pub async fn grants_for(&self, user_id: Uuid) -> Result<Vec<RoleGrant>, RbacError> {
+        Ok(sqlx::query_as(
+            "SELECT role, granted_by, granted_at FROM user_roles WHERE user_id = $1 ORDER BY role",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    pub async fn assign_role(&self, user_id: Uuid, role: &str, granted_by: Uuid) -> Result<(), RbacError> {
+        let exists: Option<(String,)> = sqlx::query_as("SELECT name FROM roles WHERE name = $1")
+            .bind(role)
+            .fetch_optional(&self.pool)
+            .await?;
+        if exists.is_none() {
+            return Err(RbacError::RoleNotFound(role.to_string()));
+        }
+
+        sqlx::query(
+            "INSERT INTO user_roles (user_id, role, granted_by) VALUES ($1, $2, $3)
+             ON CONFLICT (user_id, role) DO NOTHING",
+        )
+        .bind(user_id)
+        .bind(role)
+        .bind(granted_by)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| match &e {
+            sqlx::Error::Database(db) if db.code().as_deref() == Some(FOREIGN_KEY_VIOLATION) => {
+                RbacError::UserNotFound(user_id)
+            }
+            _ => RbacError::Database(e),
+        })?;
+        self.invalidate_user(user_id).await;
+        Ok(())
+    }
+
+    /// Returns whether the user had the role
+    pub async fn revoke_role(&self, user_id: Uuid, role: &str) -> Result<bool, RbacError> {
+        let result = sqlx::query("DELETE FROM user_roles WHERE user_id = $1 AND role = $2")
+            .bind(user_id)
+            .bind(role)
+            .execute(&self.pool)
+            .await?;
+        self.invalidate_user(user_id).await;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn organization_grants(&self, organization_id: Uuid) -> Result<Vec<OrganizationRoleGrant>, RbacError> {
+        Ok(sqlx::query_as(
+            "SELECT role, granted_by, granted_at FROM organization_roles WHERE organization_id = $1 ORDER BY role",
+        )
+        .bind(organization_id)
+        .fetch_all(&self.pool)
+        .await?)
+    }
+
+    /// Grant a role to every current and future member of an organization
+    pub async fn assign_organization_role(
+        &self,
+        organization_id: Uuid,
+        role: &str,
+        granted_by: Uuid,
+    ) -> Result<(), RbacError> {
+        let exists: Option<(String,)> = sqlx::query_as("SELECT name FROM roles WHERE name = $1")
+            .bind(role)
+            .fetch_optional(&self.pool)
+            .await?;
+        if exists.is_none() {
+            return Err(RbacError::RoleNotFound(role.to_string()));
+        }
+
+        sqlx::query(
+            "INSERT INTO organization_roles (organization_id, role, granted_by) VALUES ($1, $2, $3)
+             ON CONFLICT (organization_id, role) DO NOTHING",
+        )
+        .bind(organization_id)
+        .bind(role)
+        .bind(granted_by)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| match &e {
+            sqlx::Error::Database(db) if db.code().as_deref() == Some(FOREIGN_KEY_VIOLATION) => {
+                RbacError::OrganizationNotFound(organization_id)
+            }
+            _ => RbacError::Database(e),
+        })?;
+        // Members' cached grants are not indexed by organization
+        self.user_roles.write().await.clear();
+        Ok(())
+    }
+
+    /// Returns whether the organization had the role
+    pub async fn revoke_organization_role(&self, organization_id: Uuid, role: &str) -> Result<bool, RbacError> {
+        let result = sqlx::query("DELETE FROM organization_roles WHERE organization_id = $1 AND role = $2")
+            .bind(organization_id)
+            .bind(role)
+            .execute(&self.pool)
+            .await?;
+        self.user_roles.write().await.clear();
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(entries: &[(&str, &[&str])]) -> RoleTable {
+        entries
+            .iter()
+            .map(|(role, permissions)| {
+                (role.to_string(), permissions.iter().map(|p| p.to_string()).collect())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_token_scopes_narrow_live_permissions() {
+        let live = vec![SCOPE_BOUNTY_CREATE.to_string(), permissions::KYC_REVIEW.to_string()];
+        // Legacy tokens without scopes get the live permissions
+        assert_eq!(narrow_to_scopes(live.clone(), &[]), live);
+        // Revoked grants drop out; tokens cannot add permissions
+        assert_eq!(
+            narrow_to_scopes(
+                live.clone(),
+                &[permissions::KYC_REVIEW.to_string(), permissions::PAYMENTS_APPROVE.to_string()]
+            ),
+            vec![permissions::KYC_REVIEW.to_string()]
+        );
+        // A live wildcard honours whatever the token lists
+        assert_eq!(
+            narrow_to_scopes(vec![PERMISSION_ALL.to_string()], &[permissions::PAYMENTS_APPROVE.to_string()]),
+            vec![permissions::PAYMENTS_APPROVE.to_string()]
+        );
+    }
+
+    #[test]
+    fn test_default_permissions_are_in_catalog() {
+        for role in ["admin", "moderator", "user"] {
+            let permissions = default_permissions(role);
+            assert!(!permissions.is_empty(), "{}", role);
+            assert!(validate_permissions(&permissions).is_ok(), "{}", role);
+        }
+        assert!(default_permissions(REFRESH_TOKEN_ROLE).is_empty());
+        assert!(default_permissions("unknown").is_empty());
+    }
+
+    #[test]
+    fn test_effective_permissions_merge_assigned_roles() {
+        let table = table(&[
+            ("user", &[SCOPE_ANALYSIS_SUBMIT]),
+            ("triager", &[SCOPE_SUBMISSIONS_VERIFY, SCOPE_ANALYSIS_SUBMIT]),
+        ]);
+
+        assert_eq!(
+            effective_permissions(&table, "user", &["triager".to_string()]),
+            vec![SCOPE_ANALYSIS_SUBMIT.to_string(), SCOPE_SUBMISSIONS_VERIFY.to_string()]
+        );
+        // Grants of deleted roles are ignored
+        assert_eq!(
+            effective_permissions(&table, "user", &["gone".to_string()]),
+            vec![SCOPE_ANALYSIS_SUBMIT.to_string()]
+        );
+    }
+
+    #[test]
+    fn test_table_overrides_built_in_defaults() {
+        let table = table(&[("user", &[])]);
+        assert!(effective_permissions(&table, "user", &[]).is_empty());
+        // Built-in roles missing from the table keep their defaults
+        assert_eq!(effective_permissions(&table, "moderator", &[]), default_permissions("moderator"));
+    }
+
+    #[test]
+    fn test_role_definition_validation() {
+        assert!(validate_role_name("triager").is_ok());
+        assert!(validate_role_name("tier-2_support").is_ok());
+        for name in ["", "x", "Triager", "1st", "with space", REFRESH_TOKEN_ROLE] {
+            assert!(validate_role_name(name).is_err(), "{}", name);
+        }
+
+        assert!(validate_permissions(&[SCOPE_BOUNTY_CREATE.to_string()]).is_ok());
+        assert!(matches!(
+            validate_permissions(&["bounty:delete".to_string()]),
+            Err(RbacError::UnknownPermission(p)) if p == "bounty:delete"
+        ));
+    }
+}
@@ -0,0 +1,500 @@
+//! Real-time platform events for WebSocket clients
+//!
+//! One Redis pub/sub subscription per gateway instance listens on the event
+//! channels services publish to and fans the events out to every open
+//! WebSocket over a broadcast channel. Each connection then applies its own
+//! topic filter, so adding a client never adds a Redis subscription.
+//!
+//! Verdicts (`analysis_completed`, `consensus_reached`) only go to the
+//! bounty's creator and the sample's submitter, and not while an embargo
+//! withholds the bounty's verdicts from them (`services::embargo`). The hub
+//! works out those recipients once per event, before the fan-out.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use crate::services::embargo::{self, Viewer};
+
+/// Events buffered per connection before a slow client starts skipping
+const BROADCAST_CAPACITY: usize = 1024;
+const RECONNECT_DELAY_MIN: Duration = Duration::from_millis(500);
+const RECONNECT_DELAY_MAX: Duration = Duration::from_secs(30);
+
+/// Event topics a WebSocket client can subscribe to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Topic {
+    AnalysisCompleted,
+    BountyUpdated,
+    ConsensusReached,
+    PaymentSettled,
+}
+
+impl Topic {
+    pub const ALL: [Topic; 4] = [
+        Topic::AnalysisCompleted,
+        Topic::BountyUpdated,
+        Topic::ConsensusReached,
+        Topic::PaymentSettled,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Topic::AnalysisCompleted => "analysis_completed",
+            Topic::BountyUpdated => "bounty_updated",
+            Topic::ConsensusReached => "consensus_reached",
+            Topic::PaymentSettled => "payment_settled",
+        }
+    }
+
+    /// Redis channel the topic is published on. Payments are published by
+    /// the payment service as `payment_processed`.
+    pub fn redis_channel(&self) -> &'static str {
+        match self {
+            Topic::AnalysisCompleted => "events:analysis_completed",
+            Topic::BountyUpdated => "events:bounty_updated",
+            Topic::ConsensusReached => "events:consensus_reached",
+            Topic::PaymentSettled => "events:payment_processed",
+        }
+    }
+
+    pub fn from_redis_channel(channel: &str) -> Option<Topic> {
+        Topic::ALL.into_iter().find(|topic| topic.redis_channel() == channel)
+    }
+
+    /// Topics that carry one user's data and only go to that user (or admins)
+    pub fn is_personal(&self) -> bool {
+        matches!(self, Topic::PaymentSettled)
+    }
+
+    /// Topics that carry a bounty's verdicts and only go to the bounty's
+    /// parties (or admins)
+    pub fn carries_verdict(&self) -> bool {
+        matches!(self, Topic::AnalysisCompleted | Topic::ConsensusReached)
+    }
+}
+
+impl fmt::Display for Topic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Topic {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Topic::ALL
+            .into_iter()
+            .find(|topic| topic.as_str() == s)
+            .ok_or_else(|| format!("Unknown topic: {}", s))
+    }
+}
+
+/// An event received from Redis, normalized for delivery
+#[derive(Debug, Clone, PartialEq)]
+pub struct RealtimeEvent {
+    pub topic: Topic,
+    pub data: serde_json::Value,
+    /// Recipient of a personal event, if the payload names one
+    pub user_id: Option<Uuid>,
+    /// Who may see a verdict event besides admins, once the hub has
+    /// resolved it
+    pub recipients: Option<HashSet<Uuid>>,
+    pub received_at: DateTime<Utc>,
+}
+
+impl RealtimeEvent {
+    /// Parse a pub/sub message. Services publish either a `NexusEvent`
+    /// (`{"event_type", "data"}`), a gateway `Event` (`{"payload", ...}`) or
+    /// the bare payload; clients always get the bare payload.
+    pub fn from_message(channel: &str, payload: &str) -> Option<RealtimeEvent> {
+        let topic = Topic::from_redis_channel(channel)?;
+        let mut value: serde_json::Value = serde_json::from_str(payload).ok()?;
+
+        let data = match value.as_object_mut() {
+            Some(object) if object.contains_key("event_type") && object.contains_key("data") => {
+                object.remove("data").unwrap_or_default()
+            }
+            Some(object) if object.contains_key("event_type") && object.contains_key("payload") => {
+                object.remove("payload").unwrap_or_default()
+            }
+            _ => value,
+        };
+
+        let user_id = ["recipient_id", "user_id"]
+            .iter()
+            .find_map(|field| data.get(field)?.as_str()?.parse().ok());
+
+        Some(RealtimeEvent {
+            topic,
+            data,
+            user_id,
+            recipients: None,
+            received_at: Utc::now(),
+        })
+    }
+
+    fn uuid_field(&self, field: &str) -> Option<Uuid> {
+        self.data.get(field)?.as_str()?.parse().ok()
+    }
+}
+
+/// The bounty's creator and the sample's submitter, less those the bounty's
+/// verdict embargo withholds verdicts from
+async fn verdict_recipients(pool: &PgPool, event: &RealtimeEvent) -> Result<HashSet<Uuid>> {
+    let Some(bounty_id) = event.uuid_field("bounty_id") else {
+        return Ok(HashSet::new());
+    };
+    let parties: Vec<(Uuid, Option<Uuid>)> = sqlx::query_as(
+        r#"
+        SELECT b.creator_id, s.submitter_id
+        FROM bounties b
+        LEFT JOIN submissions s ON s.id = COALESCE($2, b.submission_id)
+        WHERE b.id = $1
+        "#,
+    )
+    .bind(bounty_id)
+    .bind(event.uuid_field("submission_id"))
+    .fetch_all(pool)
+    .await
+    .context("Failed to load bounty parties")?;
+
+    let mut recipients = HashSet::new();
+    for user_id in parties.into_iter().flat_map(|(creator, submitter)| [Some(creator), submitter]).flatten() {
+        let viewer = Viewer { user_id, is_admin: false };
+        if !embargo::is_withheld(pool, bounty_id, Some(viewer)).await? {
+            recipients.insert(user_id);
+        }
+    }
+    Ok(recipients)
+}
+
+/// Topics one connection receives, plus who it belongs to
+#[derive(Debug, Clone)]
+pub struct TopicFilter {
+    user_id: Uuid,
+    is_admin: bool,
+    topics: HashSet<Topic>,
+}
+
+impl TopicFilter {
+    pub fn new(user_id: Uuid, is_admin: bool, topics: impl IntoIterator<Item = Topic>) -> Self {
+        Self {
+            user_id,
+            is_admin,
+            topics: topics.into_iter().collect(),
+        }
+    }
+
+    pub fn subscribe(&mut self, topics: &[Topic]) {
+        self.topics.extend(topics.iter().copied());
+    }
+
+    pub fn unsubscribe(&mut self, topics: &[Topic]) {
+        for topic in topics {
+            self.topics.remove(topic);
+        }
+    }
+
+    /// Subscribed topics in a stable order
+    pub fn topics(&self) -> Vec<Topic> {
+        Topic::ALL
+            .into_iter()
+            .filter(|topic| self.topics.contains(topic))
+            .collect()
+    }
+
+    pub fn accepts(&self, event: &RealtimeEvent) -> bool {
+        if !self.topics.contains(&event.topic) {
+            return false;
+        }
+        if self.is_admin {
+            return true;
+        }
+        if event.topic.is_personal() {
+            return event.user_id == Some(self.user_id);
+        }
+        if event.topic.carries_verdict() {
+            return event.recipients.as_ref().is_some_and(|users| users.contains(&self.user_id));
+        }
+        true
+    }
+}
+
+/// Fan-out of Redis events to the WebSocket connections of this instance
+pub struct RealtimeHub {
+    sender: broadcast::Sender<RealtimeEvent>,
+}
+
+impl Default for RealtimeHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RealtimeHub {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(BROADCAST_CAPACITY);
+        Self { sender }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<RealtimeEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Deliver an event to every open connection
+    pub fn dispatch(&self, event: RealtimeEvent) {
+        // An error only means nobody is connected
+        let _ = self.sender.send(event);
+    }
+
+    pub fn connection_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    /// Subscribe to every topic channel and keep resubscribing after Redis
+    /// drops the connection
+    pub fn spawn_listener(self: std::sync::Arc<Self>, redis_url: String, pool: PgPool) {
+        tokio::spawn(async move {
+            let mut delay = RECONNECT_DELAY_MIN;
+            loop {
+                match self.listen(&redis_url, &pool).await {
+                    Ok(()) => {
+                        warn!("Realtime event stream ended, resubscribing");
+                        delay = RECONNECT_DELAY_MIN;
+                    }
+                    Err(e) => error!("Realtime event listener failed: {:#}", e),
+                }
+                tokio::time::sleep(delay).await;
+                delay = (delay * 2).min(RECONNECT_DELAY_MAX);
+            }
+        });
+    }
+
+    async fn listen(&self, redis_url: &str, pool: &PgPool) -> Result<()> {
+        let client = redis::Client::open(redis_url).context("Invalid Redis URL")?;
+        let mut pubsub = client
+            .get_async_connection()
+            .await
+            .context("Failed to connect to Redis")?
+            .into_pubsub();
+
+        for topic in Topic::ALL {
+            pubsub
+                .subscribe(topic.redis_channel())
+                .await
+                .with_context(|| format!("Failed to subscribe to {}", topic.redis_channel()))?;
+        }
+        info!("Realtime hub subscribed to {} event channels", Topic::ALL.len());
+
+        let mut messages = pubsub.on_message();
+        while let Some(message) = messages.next().await {
+            let channel = message.get_channel_name().to_string();
+            let payload: String = match message.get_payload() {
+                Ok(payload) => payload,
+                Err(e) => {
+                    warn!("Unreadable payload on {}: {}", channel, e);
+                    continue;
+                }
+            };
+
+            match RealtimeEvent::from_message(&channel, &payload) {
+                Some(mut event) => {
+                    if event.topic.carries_verdict() {
+                        // Nobody but admins gets a verdict whose audience is unknown
+                        let recipients = verdict_recipients(pool, &event).await.unwrap_or_else(|e| {
+                            warn!("Failed to resolve recipients of {}: {:#}", event.topic, e);
+                            HashSet::new()
+                        });
+                        event.recipients = Some(recipients);
+                    }
+                    debug!("Dispatching {} to {} connections", event.topic, self.connection_count());
+                    self.dispatch(event);
+                }
+                None => warn!("Dropping malformed event on {}", channel),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Messages pushed to WebSocket clients
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WebSocketMessage {
+    /// Sent once the connection is authenticated
+    Connected {
+        connection_id: Uuid,
+        user_id: Uuid,
+        topics: Vec<Topic>,
+    },
+    /// Current topic filter after a subscribe or unsubscribe
+    Subscriptions { topics: Vec<Topic> },
+    Event {
+        topic: Topic,
+        data: serde_json::Value,
+        received_at: DateTime<Utc>,
+    },
+    /// The connection fell behind and `skipped` events were dropped
+    Lagged { skipped: u64 },
+    Pong,
+    Error { message: String },
+}
+
+impl From<RealtimeEvent> for WebSocketMessage {
+    fn from(event: RealtimeEvent) -> Self {
+        WebSocketMessage::Event {
+            topic: event.topic,
+            data: event.data,
+            received_at: event.received_at,
+        }
+    }
+}
+
+/// Messages accepted from WebSocket clients
+#[derive(Debug, Clone, PartialEq, Deserialize, utoipa::ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Subscribe { topics: Vec<Topic> },
+    Unsubscribe { topics: Vec<Topic> },
+    Ping,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn event(topic: Topic, user_id: Option<Uuid>) -> RealtimeEvent {
+        RealtimeEvent {
+            topic,
+            data: json!({}),
+            user_id,
+            recipients: None,
+            received_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_topic_channels_round_trip() {
+        for topic in Topic::ALL {
+            assert_eq!(Topic::from_redis_channel(topic.redis_channel()), Some(topic));
+            assert_eq!(topic.as_str().parse::<Topic>(), Ok(topic));
+        }
+        assert_eq!(Topic::from_redis_channel("events:user_registered"), None);
+        assert!("payment_processed".parse::<Topic>().is_err());
+    }
+
+    #[test]
+    fn test_event_unwraps_publisher_envelopes() {
+        let recipient = Uuid::new_v4();
+
+        let nexus_event = json!({
+            "event_type": "PaymentProcessed",
+            "data": { "recipient_id": recipient, "amount": 10 }
+        });
+        let event = RealtimeEvent::from_message("events:payment_processed", &nexus_event.to_string())
+            .unwrap();
+        assert_eq!(event.topic, Topic::PaymentSettled);
+        assert_eq!(event.data, json!({ "recipient_id": recipient, "amount": 10 }));
+        assert_eq!(event.user_id, Some(recipient));
+
+        let gateway_event = json!({
+            "id": Uuid::new_v4(),
+            "event_type": "consensus_reached",
+            "payload": { "bounty_id": "b-1" }
+        });
+        let event = RealtimeEvent::from_message("events:consensus_reached", &gateway_event.to_string())
+            .unwrap();
+        assert_eq!(event.data, json!({ "bounty_id": "b-1" }));
+
+        let bare = json!({ "submission_id": "s-1", "status": "completed" });
+        let event =
+            RealtimeEvent::from_message("events:analysis_completed", &bare.to_string()).unwrap();
+        assert_eq!(event.data, bare);
+        assert_eq!(event.user_id, None);
+
+        assert!(RealtimeEvent::from_message("events:analysis_completed", "not json").is_none());
+        assert!(RealtimeEvent::from_message("events:other", "{}").is_none());
+    }
+
+    #[test]
+    fn test_filter_follows_subscriptions() {
+        let mut filter = TopicFilter::new(Uuid::new_v4(), false, [Topic::BountyUpdated]);
+        assert!(filter.accepts(&event(Topic::BountyUpdated, None)));
+        assert!(!filter.accepts(&event(Topic::AnalysisCompleted, None)));
+
+        filter.subscribe(&[Topic::AnalysisCompleted, Topic::ConsensusReached]);
+        filter.unsubscribe(&[Topic::BountyUpdated]);
+        assert_eq!(filter.topics(), vec![Topic::AnalysisCompleted, Topic::ConsensusReached]);
+        assert!(!filter.accepts(&event(Topic::BountyUpdated, None)));
+    }
+
+    #[test]
+    fn test_personal_topics_reach_only_their_user() {
+        let user = Uuid::new_v4();
+        let payment = |recipient| event(Topic::PaymentSettled, recipient);
+
+        let own = TopicFilter::new(user, false, Topic::ALL);
+        assert!(own.accepts(&payment(Some(user))));
+        assert!(!own.accepts(&payment(Some(Uuid::new_v4()))));
+        assert!(!own.accepts(&payment(None)));
+
+        let admin = TopicFilter::new(Uuid::new_v4(), true, Topic::ALL);
+        assert!(admin.accepts(&payment(Some(user))));
+    }
+
+    #[test]
+    fn test_verdicts_reach_only_resolved_recipients() {
+        let creator = Uuid::new_v4();
+        let mut verdict = event(Topic::ConsensusReached, None);
+        let own = TopicFilter::new(creator, false, Topic::ALL);
+        assert!(!own.accepts(&verdict), "unresolved verdicts are not sent");
+
+        verdict.recipients = Some(HashSet::from([creator]));
+        assert!(own.accepts(&verdict));
+        assert!(!TopicFilter::new(Uuid::new_v4(), false, Topic::ALL).accepts(&verdict));
+
+        // Withheld from everyone, e.g. while embargoed
+        verdict.recipients = Some(HashSet::new());
+        assert!(!own.accepts(&verdict));
+        assert!(TopicFilter::new(Uuid::new_v4(), true, Topic::ALL).accepts(&verdict));
+    }
+
+    #[test]
+    fn test_message_wire_format() {
+        let message: ClientMessage =
+            serde_json::from_str(r#"{"type":"subscribe","topics":["payment_settled"]}"#).unwrap();
+        assert_eq!(message, ClientMessage::Subscribe { topics: vec![Topic::PaymentSettled] });
+        assert!(serde_json::from_str::<ClientMessage>(r#"{"type":"subscribe","topics":["x"]}"#).is_err());
+
+        let pushed = serde_json::to_value(WebSocketMessage::from(event(Topic::BountyUpdated, None)))
+            .unwrap();
+        assert_eq!(pushed["type"], "event");
+        assert_eq!(pushed["topic"], "bounty_updated");
+    }
+
+    #[tokio::test]
+    async fn test_hub_fans_out_to_every_connection() {
+        let hub = RealtimeHub::new();
+        let mut first = hub.subscribe();
+        let mut second = hub.subscribe();
+        assert_eq!(hub.connection_count(), 2);
+
+        hub.dispatch(event(Topic::ConsensusReached, None));
+        assert_eq!(first.recv().await.unwrap().topic, Topic::ConsensusReached);
+        assert_eq!(second.recv().await.unwrap().topic, Topic::ConsensusReached);
+    }
+}
@@ -5,6 +5,28 @@ use std::time::Duration;
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
+/// KEYS[1] = window key; ARGV = now_ms, window_ms, limit, member
+const SLIDING_WINDOW_SCRIPT: &str = r#"
+local now = tonumber(ARGV[1])
+local window = tonumber(ARGV[2])
+local limit = tonumber(ARGV[3])
+redis.call('ZREMRANGEBYSCORE', KEYS[1], 0, now - window)
+local count = redis.call('ZCARD', KEYS[1])
+local allowed = 0
+if count < limit then
+    redis.call('ZADD', KEYS[1], now, ARGV[4])
+    count = count + 1
+    allowed = 1
+end
+redis.call('PEXPIRE', KEYS[1], window)
+local oldest = redis.call('ZRANGE', KEYS[1], 0, 0, 'WITHSCORES')
+local oldest_ms = now
+if oldest[2] then
+    oldest_ms = tonumber(oldest[2])
+end
+return {allowed, count, oldest_ms}
+"#;
+
 #[derive(Clone)]
 pub struct RedisService {
     client: Client,
@@ -261,6 +283,35 @@ impl RedisService {
         Ok(allowed)
     }
 
+    /// Sliding-window hit against a sorted set of request timestamps.
+    ///
+    /// Returns `(allowed, count_in_window, oldest_hit_ms)`; rejected requests
+    /// are not recorded, so a client hammering past its limit does not extend
+    /// its own lockout.
+    pub async fn sliding_window_hit(
+        &self,
+        key: &str,
+        limit: u32,
+        window_ms: u64,
+    ) -> Result<(bool, u32, i64)> {
+        let script = redis::Script::new(SLIDING_WINDOW_SCRIPT);
+        let now_ms = chrono::Utc::now().timestamp_millis();
+        let member = format!("{}-{}", now_ms, Uuid::new_v4().simple());
+
+        let mut conn = self.connection_pool.clone();
+        let (allowed, count, oldest): (i32, u32, i64) = script
+            .key(key)
+            .arg(now_ms)
+            .arg(window_ms)
+            .arg(limit)
+            .arg(member)
+            .invoke_async(&mut conn)
+            .await
+            .context("Failed to evaluate sliding window rate limit")?;
+
+        Ok((allowed == 1, count, oldest))
+    }
+
     // Real-time notifications and pub/sub
     pub async fn publish_analysis_complete(
         &self,
@@ -0,0 +1,430 @@
+//! Login sessions
+//!
+//! Every login opens a session in Redis and the tokens it issues carry its
+//! id (`sid`), so sessions survive gateway restarts and are shared by every
+//! instance. A session expires after `security.session_timeout_minutes`
+//! without activity; authenticated requests push the expiry back, at most
+//! once per [`TOUCH_INTERVAL`] to keep writes off the hot path. Revoking a
+//! session invalidates its access and refresh tokens immediately.
+//!
+//! Each session also holds a random secret its CSRF tokens are signed with
+//! (`middleware::csrf`); it stays in Redis and is never listed.
+//!
+//! Sessions record the device they were opened from: a fingerprint of the
+//! client's `X-Device-Id` (or, without one, its User-Agent) and the country
+//! the edge proxy reported. The devices and countries a user has signed in
+//! from are remembered in Postgres (`services::known_devices`) so logins from
+//! new ones can be reported to the owner.
+
+use anyhow::{Context, Result};
+use axum::http::HeaderMap;
+use chrono::{DateTime, Utc};
+use redis::aio::MultiplexedConnection;
+use rand::RngCore;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Minimum time between two expiry refreshes of the same session
+pub const TOUCH_INTERVAL: Duration = Duration::from_secs(60);
+
+const SESSION_PREFIX: &str = "auth_session:";
+const USER_SESSIONS_PREFIX: &str = "auth_sessions:";
+
+/// Stable per-install id clients may send, so a device keeps its fingerprint
+/// across browser updates
+pub const DEVICE_ID_HEADER: &str = "x-device-id";
+
+/// Headers edge proxies (Cloudflare, CloudFront, nginx geoip) report the
+/// client's country in, as an ISO 3166 alpha-2 code
+const COUNTRY_HEADERS: &[&str] = &["cf-ipcountry", "cloudfront-viewer-country", "x-country-code"];
+
+fn session_key(session_id: Uuid) -> String {
+    format!("{}{}", SESSION_PREFIX, session_id)
+}
+
+fn user_sessions_key(user_id: Uuid) -> String {
+    format!("{}{}", USER_SESSIONS_PREFIX, user_id)
+}
+
+/// Device a session was opened from
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct DeviceInfo {
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    /// Short description such as "Firefox on Linux"
+    pub label: String,
+    /// Hash identifying the device across sessions; empty for sessions
+    /// opened before fingerprints existed
+    #[serde(default)]
+    pub fingerprint: String,
+    /// Country the edge proxy placed the client in
+    #[serde(default)]
+    pub country: Option<String>,
+}
+
+impl DeviceInfo {
+    pub fn new(user_agent: Option<String>, ip_address: Option<String>) -> Self {
+        let label = user_agent
+            .as_deref()
+            .map(describe_user_agent)
+            .unwrap_or_else(|| "Unknown device".to_string());
+        let fingerprint = device_fingerprint(user_agent.as_deref().unwrap_or_default());
+        Self {
+            user_agent,
+            ip_address,
+            label,
+            fingerprint,
+            country: None,
+        }
+    }
+
+    /// Device of a login request: its User-Agent, device id and country
+    pub fn from_request(headers: &HeaderMap, ip_address: Option<String>) -> Self {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+        };
+
+        let mut device = Self::new(header(axum::http::header::USER_AGENT.as_str()).map(str::to_string), ip_address);
+        if let Some(device_id) = header(DEVICE_ID_HEADER) {
+            device.fingerprint = device_fingerprint(device_id);
+        }
+        device.country = COUNTRY_HEADERS.iter().find_map(|name| header(name)).and_then(country_code);
+        device
+    }
+}
+
+fn device_fingerprint(identity: &str) -> String {
+    hex::encode(Sha256::digest(identity.as_bytes()))
+}
+
+/// Normalized country code; `None` for the placeholders proxies send when
+/// they do not know (`XX`) or the client is on Tor (`T1`)
+fn country_code(value: &str) -> Option<String> {
+    let code = value.to_ascii_uppercase();
+    let valid = code.len() == 2 && code.bytes().all(|b| b.is_ascii_uppercase());
+    (valid && code != "XX" && code != "T1").then_some(code)
+}
+
+/// Browser (or client) and platform named by a User-Agent header
+pub fn describe_user_agent(user_agent: &str) -> String {
+    let client = [
+        ("Edg/", "Edge"),
+        ("OPR/", "Opera"),
+        ("Firefox/", "Firefox"),
+        ("Chrome/", "Chrome"),
+        ("Safari/", "Safari"),
+        ("curl/", "curl"),
+        ("python-requests/", "Python"),
+        ("okhttp/", "Android app"),
+    ]
+    .iter()
+    .find(|(marker, _)| user_agent.contains(marker))
+    .map(|(_, name)| *name);
+
+    let platform = [
+        ("Android", "Android"),
+        ("iPhone", "iOS"),
+        ("iPad", "iPadOS"),
+        ("Windows", "Windows"),
+        ("Mac OS X", "macOS"),
+        ("CrOS", "ChromeOS"),
+        ("Linux", "Linux"),
+    ]
+    .iter()
+    .find(|(marker, _)| user_agent.contains(marker))
+    .map(|(_, name)| *name);
+
+    match (client, platform) {
+        (Some(client), Some(platform)) => format!("{} on {}", client, platform),
+        (Some(client), None) => client.to_string(),
+        (None, Some(platform)) => platform.to_string(),
+        (None, None) => "Unknown device".to_string(),
+    }
+}
+
+/// An open login session
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Session {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub device: DeviceInfo,
+    pub created_at: DateTime<Utc>,
+    pub last_active_at: DateTime<Utc>,
+}
+
+/// A session as stored in Redis
+#[derive(Serialize, Deserialize)]
+struct StoredSession {
+    #[serde(flatten)]
+    session: Session,
+    /// Empty for sessions opened before CSRF secrets existed
+    #[serde(default)]
+    csrf_secret: String,
+}
+
+fn new_csrf_secret() -> String {
+    let mut secret = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut secret);
+    hex::encode(secret)
+}
+
+impl Session {
+    fn is_due_for_touch(&self, now: DateTime<Utc>) -> bool {
+        (now - self.last_active_at).to_std().unwrap_or_default() >= TOUCH_INTERVAL
+    }
+}
+
+/// Redis-backed session store shared by all gateway instances
+pub struct SessionStore {
+    conn: MultiplexedConnection,
+    idle_timeout: Duration,
+}
+
+impl SessionStore {
+    pub fn new(conn: MultiplexedConnection, idle_timeout: Duration) -> Self {
+        Self { conn, idle_timeout }
+    }
+
+    async fn save(&self, stored: &StoredSession) -> Result<()> {
+        let session = &stored.session;
+        let payload = serde_json::to_string(stored).context("Failed to serialize session")?;
+        let ttl = self.idle_timeout.as_secs().max(1);
+        let user_key = user_sessions_key(session.user_id);
+
+        let mut conn = self.conn.clone();
+        let _: () = redis::pipe()
+            .atomic()
+            .set_ex(session_key(session.id), payload, ttl)
+            .ignore()
+            .sadd(&user_key, session.id.to_string())
+            .ignore()
+            .expire(&user_key, ttl as i64)
+            .ignore()
+            .query_async(&mut conn)
+            .await
+            .context("Failed to store session")?;
+        Ok(())
+    }
+
+    async fn load(&self, session_id: Uuid) -> Result<Option<StoredSession>> {
+        let mut conn = self.conn.clone();
+        let raw: Option<String> = conn
+            .get(session_key(session_id))
+            .await
+            .context("Failed to load session")?;
+        raw.map(|raw| serde_json::from_str(&raw).context("Failed to parse session"))
+            .transpose()
+    }
+
+    /// Open a session for a fresh login
+    pub async fn create(&self, user_id: Uuid, device: DeviceInfo) -> Result<Session> {
+        let now = Utc::now();
+        let stored = StoredSession {
+            session: Session {
+                id: Uuid::new_v4(),
+                user_id,
+                device,
+                created_at: now,
+                last_active_at: now,
+            },
+            csrf_secret: new_csrf_secret(),
+        };
+        self.save(&stored).await?;
+        Ok(stored.session)
+    }
+
+    /// The user's live session, with its idle expiry pushed back.
+    /// `None` if it expired, was revoked or belongs to someone else.
+    pub async fn touch(&self, session_id: Uuid, user_id: Uuid) -> Result<Option<Session>> {
+        let Some(mut stored) = self.load(session_id).await? else {
+            return Ok(None);
+        };
+        if stored.session.user_id != user_id {
+            return Ok(None);
+        }
+
+        let now = Utc::now();
+        if stored.session.is_due_for_touch(now) {
+            stored.session.last_active_at = now;
+            self.save(&stored).await?;
+        }
+        Ok(Some(stored.session))
+    }
+
+    /// Secret the session's CSRF tokens are signed with. `None` if the
+    /// session is gone or predates CSRF secrets.
+    pub async fn csrf_secret(&self, session_id: Uuid) -> Result<Option<String>> {
+        Ok(self
+            .load(session_id)
+            .await?
+            .map(|stored| stored.csrf_secret)
+            .filter(|secret| !secret.is_empty()))
+    }
+
+    /// The user's live sessions, most recently active first
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<Session>> {
+        let user_key = user_sessions_key(user_id);
+        let mut conn = self.conn.clone();
+        let ids: Vec<String> = conn
+            .smembers(&user_key)
+            .await
+            .context("Failed to list sessions")?;
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let keys: Vec<String> = ids.iter().map(|id| format!("{}{}", SESSION_PREFIX, id)).collect();
+        let raw: Vec<Option<String>> = redis::cmd("MGET")
+            .arg(&keys)
+            .query_async(&mut conn)
+            .await
+            .context("Failed to load sessions")?;
+
+        let mut sessions = Vec::with_capacity(ids.len());
+        let mut stale = Vec::new();
+        for (id, raw) in ids.iter().zip(raw) {
+            match raw.and_then(|raw| serde_json::from_str::<Session>(&raw).ok()) {
+                Some(session) => sessions.push(session),
+                None => stale.push(id.clone()),
+            }
+        }
+        if !stale.is_empty() {
+            let _: () = conn
+                .srem(&user_key, stale)
+                .await
+                .context("Failed to prune expired sessions")?;
+        }
+
+        sessions.sort_by_key(|session| std::cmp::Reverse(session.last_active_at));
+        Ok(sessions)
+    }
+
+    /// End one of the user's sessions. Returns `false` if it was not theirs
+    /// or no longer exists.
+    pub async fn revoke(&self, user_id: Uuid, session_id: Uuid) -> Result<bool> {
+        match self.load(session_id).await? {
+            Some(stored) if stored.session.user_id == user_id => {}
+            _ => return Ok(false),
+        }
+
+        let mut conn = self.conn.clone();
+        let _: () = redis::pipe()
+            .atomic()
+            .del(session_key(session_id))
+            .ignore()
+            .srem(user_sessions_key(user_id), session_id.to_string())
+            .ignore()
+            .query_async(&mut conn)
+            .await
+            .context("Failed to revoke session")?;
+        Ok(true)
+    }
+
+    /// End all of the user's sessions ("log out everywhere"), except `keep`
+    /// if given. Returns how many were ended.
+    pub async fn revoke_all(&self, user_id: Uuid, keep: Option<Uuid>) -> Result<usize> {
+        let doomed: Vec<Uuid> = self
+            .list(user_id)
+            .await?
+            .into_iter()
+            .map(|session| session.id)
+            .filter(|id| Some(*id) != keep)
+            .collect();
+        if doomed.is_empty() {
+            return Ok(0);
+        }
+
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        for session_id in &doomed {
+            pipe.del(session_key(*session_id)).ignore();
+            pipe.srem(user_sessions_key(user_id), session_id.to_string()).ignore();
+        }
+        let mut conn = self.conn.clone();
+        let _: () = pipe
+            .query_async(&mut conn)
+            .await
+            .context("Failed to revoke sessions")?;
+        Ok(doomed.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_user_agent() {
+        assert_eq!(
+            describe_user_agent(
+                "Mozilla/5.0 (X11; Linux x86_64; rv:125.0) Gecko/20100101 Firefox/125.0"
+            ),
+            "Firefox on Linux"
+        );
+        assert_eq!(
+            describe_user_agent(
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) \
+                 Chrome/124.0.0.0 Safari/537.36 Edg/124.0.0.0"
+            ),
+            "Edge on Windows"
+        );
+        assert_eq!(
+            describe_user_agent(
+                "Mozilla/5.0 (iPhone; CPU iPhone OS 17_4 like Mac OS X) AppleWebKit/605.1.15 \
+                 (KHTML, like Gecko) Version/17.4 Mobile/15E148 Safari/604.1"
+            ),
+            "Safari on iOS"
+        );
+        assert_eq!(describe_user_agent("curl/8.5.0"), "curl");
+        assert_eq!(describe_user_agent("something else"), "Unknown device");
+        assert_eq!(DeviceInfo::new(None, None).label, "Unknown device");
+    }
+
+    #[test]
+    fn test_device_from_request() {
+        let mut headers = HeaderMap::new();
+        headers.insert("user-agent", "curl/8.5.0".parse().unwrap());
+        headers.insert("cf-ipcountry", "de".parse().unwrap());
+        let device = DeviceInfo::from_request(&headers, Some("203.0.113.7".to_string()));
+        assert_eq!(device.label, "curl");
+        assert_eq!(device.country.as_deref(), Some("DE"));
+        assert_eq!(device.fingerprint, DeviceInfo::new(Some("curl/8.5.0".to_string()), None).fingerprint);
+
+        // A device id outweighs the User-Agent, which changes on updates
+        headers.insert(DEVICE_ID_HEADER, "install-1".parse().unwrap());
+        let with_id = DeviceInfo::from_request(&headers, None);
+        assert_ne!(with_id.fingerprint, device.fingerprint);
+        headers.insert("user-agent", "curl/8.6.0".parse().unwrap());
+        assert_eq!(DeviceInfo::from_request(&headers, None).fingerprint, with_id.fingerprint);
+
+        // Unknown and Tor placeholders are not countries
+        headers.insert("cf-ipcountry", "XX".parse().unwrap());
+        assert_eq!(DeviceInfo::from_request(&headers, None).country, None);
+        headers.insert("cf-ipcountry", "T1".parse().unwrap());
+        assert_eq!(DeviceInfo::from_request(&headers, None).country, None);
+    }
+
+    #[test]
+    fn test_touch_is_throttled() {
+        let now = Utc::now();
+        let mut session = Session {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            device: DeviceInfo::default(),
+            created_at: now,
+            last_active_at: now,
+        };
+        assert!(!session.is_due_for_touch(now + chrono::Duration::seconds(10)));
+
+        session.last_active_at = now - chrono::Duration::seconds(61);
+        assert!(session.is_due_for_touch(now));
+    }
+}
@@ -0,0 +1,559 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::{Datelike, Duration, NaiveDate, Utc};
+use redis::aio::MultiplexedConnection;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::config::UsageConfig;
+
+/// Set of per-organization day counters that have not been flushed yet
+const DIRTY_SET: &str = "usage:dirty";
+const FLUSH_BATCH_SIZE: usize = 100;
+/// Unflushed counters survive a few days of Postgres outage before expiring
+const COUNTER_TTL_SECS: i64 = 7 * 24 * 60 * 60;
+const ORG_CACHE_TTL_SECS: u64 = 300;
+const NO_ORG: &str = "none";
+
+/// Metered resources. The names double as Redis hash fields and column names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsageMetric {
+    ApiCalls,
+    AnalysisSeconds,
+    StorageBytes,
+}
+
+impl UsageMetric {
+    pub const ALL: [UsageMetric; 3] = [
+        UsageMetric::ApiCalls,
+        UsageMetric::AnalysisSeconds,
+        UsageMetric::StorageBytes,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UsageMetric::ApiCalls => "api_calls",
+            UsageMetric::AnalysisSeconds => "analysis_seconds",
+            UsageMetric::StorageBytes => "storage_bytes",
+        }
+    }
+}
+
+/// Usage of one organization on one UTC day
+#[derive(Debug, Clone, Default, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
+pub struct DailyUsage {
+    pub usage_date: NaiveDate,
+    pub api_calls: i64,
+    pub analysis_seconds: i64,
+    pub storage_bytes: i64,
+}
+
+impl DailyUsage {
+    fn add(&mut self, counters: &HashMap<String, i64>) {
+        self.api_calls += counters.get(UsageMetric::ApiCalls.as_str()).copied().unwrap_or(0);
+        self.analysis_seconds += counters
+            .get(UsageMetric::AnalysisSeconds.as_str())
+            .copied()
+            .unwrap_or(0);
+        self.storage_bytes += counters
+            .get(UsageMetric::StorageBytes.as_str())
+            .copied()
+            .unwrap_or(0);
+    }
+}
+
+/// A calendar month in `YYYY-MM` form
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BillingPeriod {
+    pub year: i32,
+    pub month: u32,
+}
+
+impl BillingPeriod {
+    /// The month before the one containing `date`
+    pub fn previous(date: NaiveDate) -> Self {
+        let first = date.with_day(1).unwrap() - Duration::days(1);
+        Self {
+            year: first.year(),
+            month: first.month(),
+        }
+    }
+
+    pub fn first_day(&self) -> NaiveDate {
+        NaiveDate::from_ymd_opt(self.year, self.month, 1).unwrap()
+    }
+
+    /// First day of the following month (exclusive end of the period)
+    pub fn end(&self) -> NaiveDate {
+        if self.month == 12 {
+            NaiveDate::from_ymd_opt(self.year + 1, 1, 1).unwrap()
+        } else {
+            NaiveDate::from_ymd_opt(self.year, self.month + 1, 1).unwrap()
+        }
+    }
+}
+
+impl fmt::Display for BillingPeriod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}", self.year, self.month)
+    }
+}
+
+impl FromStr for BillingPeriod {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (year, month) = s
+            .split_once('-')
+            .ok_or_else(|| anyhow!("Billing period must be YYYY-MM"))?;
+        let period = Self {
+            year: year.parse().context("Invalid billing year")?,
+            month: month.parse().context("Invalid billing month")?,
+        };
+        if year.len() != 4 || !(1..=12).contains(&period.month) {
+            return Err(anyhow!("Billing period must be YYYY-MM"));
+        }
+        Ok(period)
+    }
+}
+
+/// One organization's line in a monthly billing export
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct BillingLine {
+    pub organization_id: Uuid,
+    pub organization_name: String,
+    pub billing_email: Option<String>,
+    pub api_calls: i64,
+    pub analysis_seconds: i64,
+    pub storage_bytes: i64,
+}
+
+impl BillingLine {
+    /// Analysis time is billed in whole minutes, rounded up
+    pub fn analysis_minutes(&self) -> i64 {
+        (self.analysis_seconds + 59) / 60
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Render billing lines as CSV for the payment-service fee engine or external billing
+pub fn billing_csv(period: BillingPeriod, lines: &[BillingLine]) -> String {
+    let mut csv = String::from(
+        "period,organization_id,organization_name,billing_email,api_calls,analysis_minutes,storage_bytes\n",
+    );
+    for line in lines {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            period,
+            line.organization_id,
+            csv_field(&line.organization_name),
+            csv_field(line.billing_email.as_deref().unwrap_or("")),
+            line.api_calls,
+            line.analysis_minutes(),
+            line.storage_bytes
+        ));
+    }
+    csv
+}
+
+fn counter_key(organization_id: Uuid, date: NaiveDate) -> String {
+    format!("usage:{}:{}", organization_id, date.format("%Y%m%d"))
+}
+
+fn parse_counter_key(key: &str) -> Option<(Uuid, NaiveDate)> {
+    let mut parts = key.strip_prefix("usage:")?.split(':');
+    let organization_id = Uuid::parse_str(parts.next()?).ok()?;
+    let date = NaiveDate::parse_from_str(parts.next()?, "%Y%m%d").ok()?;
+    Some((organization_id, date))
+}
+
+/// Meters per-organization usage in Redis and rolls it up into Postgres
+pub struct UsageMeter {
+    pool: PgPool,
+    redis: MultiplexedConnection,
+}
+
+impl UsageMeter {
+    pub fn new(pool: PgPool, redis: MultiplexedConnection) -> Self {
+        Self { pool, redis }
+    }
+
+    /// Organization a user's usage is attributed to (cached in Redis)
+    pub async fn organization_for_user(&self, user_id: Uuid) -> Result<Option<Uuid>> {
+        let cache_key = format!("usage:org_of:{}", user_id);
+        let mut conn = self.redis.clone();
+
+        let cached: Option<String> = conn.get(&cache_key).await?;
+        if let Some(cached) = cached {
+            return Ok(Uuid::parse_str(&cached).ok());
+        }
+
+        let organization_id: Option<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT m.organization_id
+            FROM organization_members m
+            JOIN organizations o ON o.id = m.organization_id
+            WHERE m.user_id = $1 AND o.is_active = TRUE
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let value = organization_id.map_or_else(|| NO_ORG.to_string(), |id| id.to_string());
+        let _: () = conn.set_ex(&cache_key, value, ORG_CACHE_TTL_SECS).await?;
+
+        Ok(organization_id)
+    }
+
+    /// Add `amount` to today's counter for `metric`
+    pub async fn record(&self, organization_id: Uuid, metric: UsageMetric, amount: i64) -> Result<()> {
+        if amount <= 0 {
+            return Ok(());
+        }
+
+        let key = counter_key(organization_id, Utc::now().date_naive());
+        let mut conn = self.redis.clone();
+        let _: () = redis::pipe()
+            .atomic()
+            .hincr(&key, metric.as_str(), amount)
+            .ignore()
+            .expire(&key, COUNTER_TTL_SECS)
+            .ignore()
+            .sadd(DIRTY_SET, &key)
+            .ignore()
+            .query_async(&mut conn)
+            .await
+            .context("Failed to record usage")?;
+
+        Ok(())
+    }
+
+    /// Move all pending Redis counters into `organization_usage_daily`.
+    ///
+    /// Returns the number of organization-days flushed. The first failure
+    /// stops the flush: the failing counters are added back to Redis, and
+    /// the rest of the popped batch is marked dirty again, so everything is
+    /// retried on the next flush.
+    pub async fn flush(&self) -> Result<usize> {
+        let mut conn = self.redis.clone();
+        let mut flushed = 0;
+
+        loop {
+            let keys: Vec<String> = redis::cmd("SPOP")
+                .arg(DIRTY_SET)
+                .arg(FLUSH_BATCH_SIZE)
+                .query_async(&mut conn)
+                .await?;
+            if keys.is_empty() {
+                break;
+            }
+
+            let mut pending = keys.into_iter();
+            while let Some(key) = pending.next() {
+                let Some((organization_id, date)) = parse_counter_key(&key) else {
+                    warn!("Dropping malformed usage key {}", key);
+                    continue;
+                };
+
+                // Read and clear atomically so concurrent increments land in a fresh hash
+                let read: redis::RedisResult<(HashMap<String, i64>,)> = redis::pipe()
+                    .atomic()
+                    .hgetall(&key)
+                    .del(&key)
+                    .ignore()
+                    .query_async(&mut conn)
+                    .await;
+                let counters = match read {
+                    Ok((counters,)) => counters,
+                    Err(e) => {
+                        self.requeue(std::iter::once(key).chain(pending).collect()).await;
+                        return Err(e.into());
+                    }
+                };
+                if counters.is_empty() {
+                    continue;
+                }
+
+                if let Err(e) = self.persist(organization_id, date, &counters).await {
+                    self.restore(&key, &counters).await;
+                    self.requeue(pending.collect()).await;
+                    return Err(e);
+                }
+                flushed += 1;
+            }
+        }
+
+        Ok(flushed)
+    }
+
+    /// Mark popped keys dirty again without touching their counters
+    async fn requeue(&self, keys: Vec<String>) {
+        if keys.is_empty() {
+            return;
+        }
+        let mut conn = self.redis.clone();
+        let requeued: redis::RedisResult<()> = conn.sadd(DIRTY_SET, &keys).await;
+        if let Err(e) = requeued {
+            error!("Lost track of {} usage counters: {} ({:?})", keys.len(), e, keys);
+        }
+    }
+
+    async fn persist(
+        &self,
+        organization_id: Uuid,
+        date: NaiveDate,
+        counters: &HashMap<String, i64>,
+    ) -> Result<()> {
+        let mut usage = DailyUsage::default();
+        usage.add(counters);
+
+        sqlx::query(
+            r#"
+            INSERT INTO organization_usage_daily
+                (organization_id, usage_date, api_calls, analysis_seconds, storage_bytes)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (organization_id, usage_date) DO UPDATE SET
+                api_calls = organization_usage_daily.api_calls + EXCLUDED.api_calls,
+                analysis_seconds = organization_usage_daily.analysis_seconds + EXCLUDED.analysis_seconds,
+                storage_bytes = organization_usage_daily.storage_bytes + EXCLUDED.storage_bytes,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(organization_id)
+        .bind(date)
+        .bind(usage.api_calls)
+        .bind(usage.analysis_seconds)
+        .bind(usage.storage_bytes)
+        .execute(&self.pool)
+        .await
+        .context("Failed to persist usage rollup")?;
+
+        Ok(())
+    }
+
+    async fn restore(&self, key: &str, counters: &HashMap<String, i64>) {
+        let mut conn = self.redis.clone();
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        for (field, amount) in counters {
+            pipe.hincr(key, field, *amount).ignore();
+        }
+        pipe.expire(key, COUNTER_TTL_SECS).ignore();
+        pipe.sadd(DIRTY_SET, key).ignore();
+
+        let restored: redis::RedisResult<()> = pipe.query_async(&mut conn).await;
+        if let Err(e) = restored {
+            error!("Lost usage counters for {}: {} ({:?})", key, e, counters);
+        }
+    }
+
+    /// Daily usage for an organization between `from` and `to` inclusive,
+    /// including counters that have not been flushed yet
+    pub async fn daily_usage(
+        &self,
+        organization_id: Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<DailyUsage>> {
+        let rows = sqlx::query_as::<_, DailyUsage>(
+            r#"
+            SELECT usage_date, api_calls, analysis_seconds, storage_bytes
+            FROM organization_usage_daily
+            WHERE organization_id = $1 AND usage_date BETWEEN $2 AND $3
+            "#,
+        )
+        .bind(organization_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut by_date: HashMap<NaiveDate, DailyUsage> =
+            rows.into_iter().map(|row| (row.usage_date, row)).collect();
+
+        // Pending counters only exist for days still inside the Redis TTL
+        let mut conn = self.redis.clone();
+        let oldest_pending = Utc::now().date_naive() - Duration::seconds(COUNTER_TTL_SECS);
+        let mut date = from.max(oldest_pending);
+        while date <= to {
+            let pending: HashMap<String, i64> = conn.hgetall(counter_key(organization_id, date)).await?;
+            if !pending.is_empty() {
+                by_date
+                    .entry(date)
+                    .or_insert_with(|| DailyUsage {
+                        usage_date: date,
+                        ..Default::default()
+                    })
+                    .add(&pending);
+            }
+            date += Duration::days(1);
+        }
+
+        let mut days: Vec<DailyUsage> = by_date.into_values().collect();
+        days.sort_by_key(|day| day.usage_date);
+        Ok(days)
+    }
+
+    /// Per-organization totals for a billing period, from flushed rollups
+    pub async fn billing_lines(&self, period: BillingPeriod) -> Result<Vec<BillingLine>> {
+        let lines = sqlx::query_as::<_, BillingLine>(
+            r#"
+            SELECT o.id AS organization_id,
+                   o.name AS organization_name,
+                   o.billing_email,
+                   SUM(u.api_calls)::BIGINT AS api_calls,
+                   SUM(u.analysis_seconds)::BIGINT AS analysis_seconds,
+                   SUM(u.storage_bytes)::BIGINT AS storage_bytes
+            FROM organization_usage_daily u
+            JOIN organizations o ON o.id = u.organization_id
+            WHERE u.usage_date >= $1 AND u.usage_date < $2
+            GROUP BY o.id, o.name, o.billing_email
+            ORDER BY o.name
+            "#,
+        )
+        .bind(period.first_day())
+        .bind(period.end())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(lines)
+    }
+
+    /// Write the CSV export for `period` into `dir` and record it.
+    ///
+    /// Pending counters are flushed first so the export is complete.
+    pub async fn export_billing(&self, period: BillingPeriod, dir: &Path) -> Result<(PathBuf, usize)> {
+        self.flush().await?;
+        let lines = self.billing_lines(period).await?;
+
+        tokio::fs::create_dir_all(dir)
+            .await
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        let path = dir.join(format!("billing-{}.csv", period));
+        tokio::fs::write(&path, billing_csv(period, &lines))
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO billing_exports (period, file_path, organization_count)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (period) DO UPDATE SET
+                file_path = EXCLUDED.file_path,
+                organization_count = EXCLUDED.organization_count,
+                generated_at = NOW()
+            "#,
+        )
+        .bind(period.to_string())
+        .bind(path.to_string_lossy().to_string())
+        .bind(lines.len() as i32)
+        .execute(&self.pool)
+        .await?;
+
+        Ok((path, lines.len()))
+    }
+
+    async fn export_exists(&self, period: BillingPeriod) -> Result<bool> {
+        let exists: bool =
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM billing_exports WHERE period = $1)")
+                .bind(period.to_string())
+                .fetch_one(&self.pool)
+                .await?;
+        Ok(exists)
+    }
+}
+
+/// Periodically flush counters and generate last month's export once it is over
+pub fn spawn_usage_worker(meter: Arc<UsageMeter>, config: UsageConfig) {
+    tokio::spawn(async move {
+        let mut ticker =
+            tokio::time::interval(std::time::Duration::from_secs(config.flush_interval_seconds));
+        let export_dir = PathBuf::from(&config.billing_export_dir);
+
+        loop {
+            ticker.tick().await;
+
+            if let Err(e) = meter.flush().await {
+                error!("Usage flush failed: {:#}", e);
+                continue;
+            }
+
+            let period = BillingPeriod::previous(Utc::now().date_naive());
+            match meter.export_exists(period).await {
+                Ok(true) => {}
+                Ok(false) => match meter.export_billing(period, &export_dir).await {
+                    Ok((path, count)) => info!(
+                        "Generated billing export for {} ({} organizations): {}",
+                        period,
+                        count,
+                        path.display()
+                    ),
+                    Err(e) => error!("Billing export for {} failed: {:#}", period, e),
+                },
+                Err(e) => error!("Failed to check billing exports: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_billing_period_parsing_and_bounds() {
+        let period: BillingPeriod = "2024-12".parse().unwrap();
+        assert_eq!(period.first_day(), NaiveDate::from_ymd_opt(2024, 12, 1).unwrap());
+        assert_eq!(period.end(), NaiveDate::from_ymd_opt(2025, 1, 1).unwrap());
+        assert_eq!(period.to_string(), "2024-12");
+
+        assert!("2024-13".parse::<BillingPeriod>().is_err());
+        assert!("24-01".parse::<BillingPeriod>().is_err());
+
+        let jan = NaiveDate::from_ymd_opt(2025, 1, 15).unwrap();
+        assert_eq!(BillingPeriod::previous(jan), period);
+    }
+
+    #[test]
+    fn test_counter_key_round_trip() {
+        let org = Uuid::new_v4();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 9).unwrap();
+        let key = counter_key(org, date);
+        assert_eq!(key, format!("usage:{}:20240309", org));
+        assert_eq!(parse_counter_key(&key), Some((org, date)));
+        assert_eq!(parse_counter_key("usage:org_of:x"), None);
+    }
+
+    #[test]
+    fn test_billing_csv_rounds_minutes_and_quotes_names() {
+        let line = BillingLine {
+            organization_id: Uuid::nil(),
+            organization_name: "Acme, \"Labs\"".to_string(),
+            billing_email: None,
+            api_calls: 10,
+            analysis_seconds: 61,
+            storage_bytes: 2048,
+        };
+        let csv = billing_csv("2024-05".parse().unwrap(), &[line]);
+        let row = csv.lines().nth(1).unwrap();
+        assert_eq!(
+            row,
+            format!("2024-05,{},\"Acme, \"\"Labs\"\"\",,10,2,2048", Uuid::nil())
+        );
+    }
+}
@@ -15,6 +15,10 @@ pub enum ApiError {
     Internal(String),
     Database(sqlx::Error),
     Conflict(String),
+    /// Login locked out after repeated failures (`shared::login_guard`)
+    TooManyRequests { message: String, retry_after_secs: u64 },
+    /// Login needs a solved CAPTCHA in `captcha_token`
+    CaptchaRequired,
 }
 
 pub type ApiResult<T> = Result<T, ApiError>;
@@ -22,6 +26,28 @@ pub type ApiResult<T> = Result<T, ApiError>;
 impl IntoResponse for ApiError {
     fn into_response(self) -> axum::response::Response {
         let (status, message) = match self {
+            ApiError::TooManyRequests { message, retry_after_secs } => {
+                let body = json!({
+                    "success": false,
+                    "error": message,
+                    "data": serde_json::Value::Null
+                });
+                return (
+                    StatusCode::TOO_MANY_REQUESTS,
+                    [("Retry-After", retry_after_secs.to_string())],
+                    Json(body),
+                )
+                    .into_response();
+            }
+            ApiError::CaptchaRequired => {
+                let body = json!({
+                    "success": false,
+                    "error": "CAPTCHA required",
+                    "captcha_required": true,
+                    "data": serde_json::Value::Null
+                });
+                return (StatusCode::UNAUTHORIZED, Json(body)).into_response();
+            }
             ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg),
             ApiError::Unauthorized => (StatusCode::UNAUTHORIZED, "Unauthorized".to_string()),
             ApiError::Forbidden => (StatusCode::FORBIDDEN, "Forbidden".to_string()),
@@ -58,49 +84,3 @@ impl From<anyhow::Error> for ApiError {
     }
 }
 
-// API Response wrapper
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ApiResponse<T> {
-    pub success: bool,
-    pub data: Option<T>,
-    pub message: Option<String>,
-    pub error: Option<String>,
-}
-
-impl<T> ApiResponse<T> {
-    pub fn success(data: T) -> Self {
-        Self {
-            success: true,
-            data: Some(data),
-            message: None,
-            error: None,
-        }
-    }
-
-    pub fn success_with_message(data: T, message: String) -> Self {
-        Self {
-            success: true,
-            data: Some(data),
-            message: Some(message),
-            error: None,
-        }
-    }
-
-    pub fn success_no_data(message: String) -> Self {
-        Self {
-            success: true,
-            data: None,
-            message: Some(message),
-            error: None,
-        }
-    }
-
-    pub fn error(error: String) -> Self {
-        Self {
-            success: false,
-            data: None,
-            message: None,
-            error: Some(error),
-        }
-    }
-}
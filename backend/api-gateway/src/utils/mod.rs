@@ -5,7 +5,8 @@ pub mod serialization;
 pub mod validation;
 
 // Re-export commonly used types
-pub use errors::{ApiError, ApiResult, ApiResponse};
+pub use errors::{ApiError, ApiResult};
+pub use crate::models::response::ApiResponse;
 pub use circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitBreakerStats, CircuitBreakerError, State as CircuitBreakerState};
 pub use serialization::{
     JsonUtils, Base64Utils, HexUtils, MsgPackUtils, QueryStringUtils,
@@ -29,7 +30,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use chrono::{DateTime, Utc};
 
-// ApiResponse is now defined in errors.rs and re-exported above
+// ApiResponse is the v1 envelope from models::response, re-exported above
 
 /// Pagination parameters for list endpoints
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -193,7 +194,7 @@ impl RequestMetadata {
 }
 
 /// Health check response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct HealthCheck {
     pub status: String,
     pub timestamp: DateTime<Utc>,
@@ -202,12 +203,15 @@ pub struct HealthCheck {
     pub checks: HashMap<String, ServiceHealth>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct ServiceHealth {
     pub status: String,
     pub response_time_ms: Option<u64>,
     pub last_check: DateTime<Utc>,
     pub error: Option<String>,
+    /// Gateway circuit breaker for proxied services
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub circuit_breaker: Option<crate::services::proxy_service::CircuitBreakerSnapshot>,
 }
 
 impl ServiceHealth {
@@ -217,6 +221,7 @@ impl ServiceHealth {
             response_time_ms: Some(response_time_ms),
             last_check: Utc::now(),
             error: None,
+            circuit_breaker: None,
         }
     }
 
@@ -226,6 +231,7 @@ impl ServiceHealth {
             response_time_ms: None,
             last_check: Utc::now(),
             error: Some(error),
+            circuit_breaker: None,
         }
     }
 }
@@ -242,7 +248,7 @@ pub struct RateLimitInfo {
 impl RateLimitInfo {
     pub fn new(limit: u32, remaining: u32, reset_at: DateTime<Utc>) -> Self {
         let retry_after = if remaining == 0 {
-            Some((reset_at - Utc::now()).num_seconds() as u32)
+            Some((reset_at - Utc::now()).num_seconds().max(0) as u32)
         } else {
             None
         };
@@ -298,6 +304,11 @@ pub struct AuthContext {
     pub user_id: String,
     pub ethereum_address: String,
     pub api_key_id: Option<String>,
+    /// Organization the caller acts for and their role in it
+    #[serde(default)]
+    pub organization_id: Option<String>,
+    #[serde(default)]
+    pub organization_role: Option<String>,
     pub permissions: Vec<String>,
     pub rate_limit_tier: String,
     pub authenticated_at: DateTime<Utc>,
@@ -309,6 +320,8 @@ impl AuthContext {
             user_id,
             ethereum_address,
             api_key_id: None,
+            organization_id: None,
+            organization_role: None,
             permissions: vec!["basic".to_string()],
             rate_limit_tier: "standard".to_string(),
             authenticated_at: Utc::now(),
@@ -320,11 +333,27 @@ impl AuthContext {
         self
     }
 
+    pub fn with_organization(mut self, organization_id: impl Into<String>, role: impl Into<String>) -> Self {
+        self.organization_id = Some(organization_id.into());
+        self.organization_role = Some(role.into());
+        self
+    }
+
     pub fn with_permissions(mut self, permissions: Vec<String>) -> Self {
         self.permissions = permissions;
         self
     }
 
+    pub fn with_rate_limit_tier(mut self, tier: impl Into<String>) -> Self {
+        self.rate_limit_tier = tier.into();
+        self
+    }
+
+    /// Organization the caller acts for, if they belong to one
+    pub fn organization(&self) -> Option<uuid::Uuid> {
+        self.organization_id.as_deref().and_then(|id| uuid::Uuid::parse_str(id).ok())
+    }
+
     pub fn has_permission(&self, permission: &str) -> bool {
         self.permissions.contains(&permission.to_string()) || 
         self.permissions.contains(&"admin".to_string())
@@ -412,12 +441,12 @@ mod tests {
         let success_response = ApiResponse::success("test data");
         assert!(success_response.success);
         assert_eq!(success_response.data, Some("test data"));
-        assert!(success_response.error.is_none());
+        assert!(success_response.message.is_none());
 
-        let error_response: ApiResponse<()> = ApiResponse::error("test error".to_string());
+        let error_response: ApiResponse<()> = ApiResponse::error("test error");
         assert!(!error_response.success);
         assert!(error_response.data.is_none());
-        assert_eq!(error_response.error, Some("test error".to_string()));
+        assert_eq!(error_response.message, Some("test error".to_string()));
     }
 
     #[test]
@@ -0,0 +1,47 @@
+-- Cold storage for finalized bounties
+
+-- Full row snapshots of archived bounties and their dependents. Rows are
+-- stored as JSONB so archived data survives later schema changes and can be
+-- restored with jsonb_populate_record.
+CREATE TABLE IF NOT EXISTS archived_bounties (
+    id UUID PRIMARY KEY,
+    data JSONB NOT NULL,
+    archived_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS archived_submissions (
+    id UUID PRIMARY KEY,
+    bounty_id UUID NOT NULL,
+    data JSONB NOT NULL,
+    archived_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_archived_submissions_bounty ON archived_submissions(bounty_id);
+
+CREATE TABLE IF NOT EXISTS archived_payouts (
+    id UUID PRIMARY KEY,
+    bounty_id UUID NOT NULL,
+    data JSONB NOT NULL,
+    archived_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_archived_payouts_bounty ON archived_payouts(bounty_id);
+
+-- Lightweight summary kept for listings while the bounty is archived
+CREATE TABLE IF NOT EXISTS bounty_archive_summaries (
+    bounty_id UUID PRIMARY KEY,
+    creator VARCHAR(255) NOT NULL,
+    title VARCHAR(500) NOT NULL,
+    artifact_type VARCHAR(50) NOT NULL,
+    artifact_hash VARCHAR(255),
+    reward_amount BIGINT NOT NULL,
+    currency VARCHAR(100) NOT NULL,
+    status VARCHAR(50) NOT NULL,
+    submission_count INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    finalized_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    archived_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_bounty_archive_summaries_creator ON bounty_archive_summaries(creator);
+CREATE INDEX idx_bounty_archive_summaries_archived ON bounty_archive_summaries(archived_at DESC);
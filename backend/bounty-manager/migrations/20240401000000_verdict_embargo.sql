@@ -0,0 +1,23 @@
+-- Verdict embargoes for law-enforcement sensitive bounties
+
+-- While an embargo is active, verdicts of the bounty are withheld from public
+-- bounty APIs and the consensus-service intelligence feed; payouts are not
+-- affected. Kept apart from `bounties` (without a foreign key) so that
+-- archiving a bounty does not lift its embargo.
+CREATE TABLE IF NOT EXISTS bounty_verdict_embargoes (
+    bounty_id UUID PRIMARY KEY,
+    -- Organization of the bounty creator; its members may see the verdicts
+    organization_id UUID,
+    creator_user_id UUID,
+    reason TEXT,
+    placed_by UUID NOT NULL,
+    -- NULL keeps the embargo until it is released manually
+    embargo_until TIMESTAMP WITH TIME ZONE,
+    released_at TIMESTAMP WITH TIME ZONE,
+    released_by UUID,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_bounty_verdict_embargoes_active
+    ON bounty_verdict_embargoes(bounty_id) WHERE released_at IS NULL;
@@ -0,0 +1,9 @@
+-- Submission intake: reputation requirement per bounty, one submission per
+-- engine
+
+-- Minimum reputation-service score an engine needs to submit; NULL admits
+-- any engine. Nullable so archived bounties rehydrate unchanged.
+ALTER TABLE bounties ADD COLUMN IF NOT EXISTS min_reputation INTEGER;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_submissions_bounty_engine_unique
+    ON submissions(bounty_id, engine_id);
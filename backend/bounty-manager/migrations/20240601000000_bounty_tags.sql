@@ -0,0 +1,41 @@
+-- Bounty tags: a curated taxonomy of threat categories that bounties are
+-- tagged with, so engines can find and specialize in the work they are good at
+
+CREATE TABLE IF NOT EXISTS bounty_tags (
+    -- Lowercase, hyphenated identifier such as `ransomware`
+    slug VARCHAR(64) PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    -- Grouping shown in the taxonomy, such as `malware` or `attack`
+    category VARCHAR(64) NOT NULL,
+    description TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_bounty_tags_category ON bounty_tags(category);
+
+-- Kept apart from `bounties` (without a foreign key) so that archiving a
+-- bounty keeps its tags for rehydration
+CREATE TABLE IF NOT EXISTS bounty_tag_assignments (
+    bounty_id UUID NOT NULL,
+    tag_slug VARCHAR(64) NOT NULL REFERENCES bounty_tags(slug) ON UPDATE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (bounty_id, tag_slug)
+);
+
+CREATE INDEX IF NOT EXISTS idx_bounty_tag_assignments_tag ON bounty_tag_assignments(tag_slug);
+
+INSERT INTO bounty_tags (slug, name, category, description) VALUES
+    ('ransomware', 'Ransomware', 'malware', 'Encrypts or locks data and demands payment'),
+    ('trojan', 'Trojan', 'malware', 'Disguised as legitimate software to gain access'),
+    ('cryptominer', 'Cryptominer', 'malware', 'Mines cryptocurrency on compromised hosts'),
+    ('infostealer', 'Infostealer', 'malware', 'Harvests credentials, cookies and wallets'),
+    ('botnet', 'Botnet', 'malware', 'Enrolls hosts into a remotely controlled network'),
+    ('rootkit', 'Rootkit', 'malware', 'Hides its presence at kernel or firmware level'),
+    ('phishing', 'Phishing', 'attack', 'Lures users into giving up credentials or running payloads'),
+    ('apt', 'APT', 'attack', 'Targeted intrusion by an advanced persistent threat actor'),
+    ('exploit', 'Exploit', 'attack', 'Exploits a software vulnerability'),
+    ('supply-chain', 'Supply chain', 'attack', 'Compromise delivered through a trusted dependency or vendor'),
+    ('malicious-url', 'Malicious URL', 'infrastructure', 'Hosts or redirects to malicious content'),
+    ('c2', 'Command and control', 'infrastructure', 'Controls compromised hosts')
+ON CONFLICT (slug) DO NOTHING;
@@ -0,0 +1,56 @@
+-- Standing bounties: templates that spawn child bounties on a schedule, or
+-- whenever a new artifact matches a watch rule
+
+CREATE TABLE IF NOT EXISTS standing_bounties (
+    id UUID PRIMARY KEY,
+    creator VARCHAR(255) NOT NULL,
+    title VARCHAR(500) NOT NULL,
+    description TEXT NOT NULL,
+    -- Terms every child bounty is opened with
+    reward_amount BIGINT NOT NULL,
+    currency VARCHAR(100) NOT NULL,
+    min_stake BIGINT NOT NULL,
+    max_participants INTEGER,
+    min_reputation INTEGER,
+    deadline_hours INTEGER NOT NULL,
+    consensus_threshold REAL NOT NULL DEFAULT 0.75,
+    tags TEXT[] NOT NULL DEFAULT '{}',
+    -- 'schedule': re-open the artifact below every interval_hours
+    -- 'watch':    open a child for each new artifact matching the watch rule
+    trigger_type VARCHAR(20) NOT NULL CHECK (trigger_type IN ('schedule', 'watch')),
+    artifact_type VARCHAR(50),
+    artifact_data JSONB,
+    interval_hours INTEGER,
+    next_run_at TIMESTAMP WITH TIME ZONE,
+    -- 'hash_prefix', 'yara_family' or 'domain_pattern'
+    watch_kind VARCHAR(20),
+    watch_pattern VARCHAR(255),
+    -- Artifacts seen up to here have been matched against the watch rule
+    watched_until TIMESTAMP WITH TIME ZONE,
+    -- Stop after this many children; NULL for no limit
+    max_children INTEGER,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CHECK (trigger_type <> 'schedule' OR (interval_hours > 0 AND artifact_data IS NOT NULL)),
+    CHECK (trigger_type <> 'watch' OR (watch_kind IS NOT NULL AND watch_pattern IS NOT NULL))
+);
+
+CREATE INDEX IF NOT EXISTS idx_standing_bounties_creator ON standing_bounties(creator);
+CREATE INDEX IF NOT EXISTS idx_standing_bounties_due
+    ON standing_bounties(next_run_at) WHERE enabled AND trigger_type = 'schedule';
+
+-- Children spawned by a standing bounty. `trigger_ref` identifies what
+-- spawned a child (a schedule slot or a matched artifact) so no trigger
+-- spawns twice.
+CREATE TABLE IF NOT EXISTS standing_bounty_children (
+    standing_bounty_id UUID NOT NULL REFERENCES standing_bounties(id) ON DELETE CASCADE,
+    trigger_ref VARCHAR(300) NOT NULL,
+    bounty_id UUID NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (standing_bounty_id, trigger_ref)
+);
+
+CREATE INDEX IF NOT EXISTS idx_standing_bounty_children_bounty ON standing_bounty_children(bounty_id);
+CREATE INDEX IF NOT EXISTS idx_bounties_created_at ON bounties(created_at);
+CREATE INDEX IF NOT EXISTS idx_submissions_submitted_at ON submissions(submitted_at);
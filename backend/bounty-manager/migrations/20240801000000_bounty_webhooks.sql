@@ -0,0 +1,39 @@
+-- Bounty event webhooks: creators subscribe a URL to events of their
+-- bounties, and every event is queued as a delivery that is retried with
+-- backoff until the endpoint accepts it
+
+CREATE TABLE IF NOT EXISTS bounty_webhooks (
+    id UUID PRIMARY KEY,
+    bounty_id UUID NOT NULL,
+    creator VARCHAR(255) NOT NULL,
+    url VARCHAR(2048) NOT NULL,
+    -- Key of the HMAC-SHA256 signature sent with every delivery
+    secret VARCHAR(128) NOT NULL,
+    -- 'submission_received', 'consensus_reached', 'dispute_opened', 'payout_executed'
+    events TEXT[] NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_bounty_webhooks_bounty ON bounty_webhooks(bounty_id);
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    id UUID PRIMARY KEY,
+    webhook_id UUID NOT NULL REFERENCES bounty_webhooks(id) ON DELETE CASCADE,
+    bounty_id UUID NOT NULL,
+    event_type VARCHAR(50) NOT NULL,
+    payload JSONB NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'delivered', 'failed')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    -- Outcome of the latest attempt
+    response_status INTEGER,
+    last_error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    delivered_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_webhook ON webhook_deliveries(webhook_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_due
+    ON webhook_deliveries(next_attempt_at) WHERE status = 'pending';
@@ -0,0 +1,21 @@
+-- On-chain verification of creators' reward deposits. A funded escrow only
+-- activates its bounty once the deposit transaction's BountyCreated event
+-- matches the bounty and has enough confirmations.
+
+CREATE TABLE IF NOT EXISTS bounty_deposit_verifications (
+    bounty_id UUID PRIMARY KEY,
+    -- 'pending' until confirmed, 'verified', or 'mismatch' when the deposit
+    -- does not fund this bounty
+    status VARCHAR(20) NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'verified', 'mismatch')),
+    deposit_tx_hash VARCHAR(66),
+    -- The contract's id for the bounty, from the BountyCreated event
+    on_chain_id VARCHAR(78),
+    block_number BIGINT,
+    confirmations INTEGER NOT NULL DEFAULT 0,
+    detail TEXT,
+    checked_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    verified_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_bounty_deposit_verifications_pending
+    ON bounty_deposit_verifications(checked_at) WHERE status = 'pending';
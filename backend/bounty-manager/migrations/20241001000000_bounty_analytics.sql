@@ -0,0 +1,45 @@
+-- Bounty analytics, rebuilt periodically by the analytics worker from live
+-- and archived bounties. Reward sums are NUMERIC: wei totals overflow BIGINT.
+
+CREATE TABLE IF NOT EXISTS bounty_stats_summary (
+    id SMALLINT PRIMARY KEY CHECK (id = 1),
+    total_bounties BIGINT NOT NULL,
+    active_bounties BIGINT NOT NULL,
+    completed_bounties BIGINT NOT NULL,
+    -- From creation to completion, over completed bounties
+    avg_hours_to_consensus DOUBLE PRECISION,
+    refreshed_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS bounty_stats_daily (
+    day DATE PRIMARY KEY,
+    bounties_created BIGINT NOT NULL,
+    bounties_completed BIGINT NOT NULL,
+    avg_hours_to_consensus DOUBLE PRECISION
+);
+
+CREATE TABLE IF NOT EXISTS bounty_stats_tokens (
+    currency VARCHAR(100) PRIMARY KEY,
+    bounty_count BIGINT NOT NULL,
+    completed_count BIGINT NOT NULL,
+    total_offered NUMERIC NOT NULL,
+    total_paid NUMERIC NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS bounty_stats_artifact_types (
+    artifact_type VARCHAR(50) PRIMARY KEY,
+    bounty_count BIGINT NOT NULL,
+    completed_count BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS bounty_stats_creators (
+    creator VARCHAR(255) PRIMARY KEY,
+    bounty_count BIGINT NOT NULL,
+    active_count BIGINT NOT NULL,
+    completed_count BIGINT NOT NULL,
+    total_offered NUMERIC NOT NULL,
+    avg_hours_to_consensus DOUBLE PRECISION,
+    last_bounty_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_bounty_stats_creators_count ON bounty_stats_creators(bounty_count DESC);
@@ -0,0 +1,27 @@
+-- Engines taking part in a bounty. An engine joins by locking its stake in
+-- the payment-service and may withdraw, with the stake released, until it
+-- submits. Kept apart from `bounties` (without a foreign key) like tag
+-- assignments, so archiving a bounty keeps its participants.
+
+CREATE TABLE IF NOT EXISTS bounty_participants (
+    bounty_id UUID NOT NULL,
+    engine_id VARCHAR(255) NOT NULL,
+    -- 'joined' until the engine submits, 'submitted', or 'withdrawn'
+    status VARCHAR(20) NOT NULL DEFAULT 'joined' CHECK (status IN ('joined', 'submitted', 'withdrawn')),
+    wallet_address VARCHAR(42),
+    stake_amount BIGINT NOT NULL,
+    -- The payment-service stake; NULL for engines that submitted without joining
+    stake_id UUID,
+    joined_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    submitted_at TIMESTAMP WITH TIME ZONE,
+    withdrawn_at TIMESTAMP WITH TIME ZONE,
+    PRIMARY KEY (bounty_id, engine_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_bounty_participants_engine ON bounty_participants(engine_id);
+
+-- Engines that already submitted are participants
+INSERT INTO bounty_participants (bounty_id, engine_id, status, stake_amount, joined_at, submitted_at)
+SELECT bounty_id, engine_id, 'submitted', stake_amount, submitted_at, submitted_at
+FROM submissions
+ON CONFLICT (bounty_id, engine_id) DO NOTHING;
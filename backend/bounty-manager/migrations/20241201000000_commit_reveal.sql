@@ -0,0 +1,22 @@
+-- Blind submissions. On a commit-reveal bounty engines submit only a hash of
+-- their verdict and a nonce until the deadline, and reveal the verdict
+-- between the deadline and `reveal_deadline`, so no engine can copy another's
+-- verdict. `reveal_deadline` is NULL for bounties taking verdicts directly.
+
+ALTER TABLE bounties ADD COLUMN IF NOT EXISTS reveal_deadline TIMESTAMP WITH TIME ZONE;
+
+CREATE TABLE IF NOT EXISTS submission_commitments (
+    bounty_id UUID NOT NULL,
+    engine_id VARCHAR(255) NOT NULL,
+    -- Hex SHA-256 of "<verdict>:<nonce>", verdict in lowercase
+    commitment CHAR(64) NOT NULL,
+    engine_type VARCHAR(50) NOT NULL,
+    confidence REAL NOT NULL CHECK (confidence >= 0 AND confidence <= 1),
+    stake_amount BIGINT NOT NULL,
+    transaction_hash VARCHAR(255),
+    committed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    -- The submission created by the reveal
+    submission_id UUID,
+    revealed_at TIMESTAMP WITH TIME ZONE,
+    PRIMARY KEY (bounty_id, engine_id)
+);
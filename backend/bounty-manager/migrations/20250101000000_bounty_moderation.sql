@@ -0,0 +1,61 @@
+-- Bounty moderation: abuse reports, the admin review queue and an audit
+-- trail of moderation decisions
+
+-- One open or decided case per bounty. While held, the bounty is
+-- `UnderReview` and `held_from_status` keeps the status an approval restores.
+-- Kept apart from `bounties` (without a foreign key) so the decision outlives
+-- archival.
+CREATE TABLE IF NOT EXISTS bounty_moderation_cases (
+    bounty_id UUID PRIMARY KEY,
+    -- `pending` (reported, still live), `held`, `approved` or `taken_down`
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    report_count INTEGER NOT NULL DEFAULT 0,
+    -- Reports in a severe category (CSAM or illegal content) go first
+    severe BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Content heuristics the bounty matched
+    flags TEXT[] NOT NULL DEFAULT '{}',
+    held_from_status VARCHAR(50),
+    opened_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    decided_at TIMESTAMP WITH TIME ZONE,
+    decided_by UUID,
+    CONSTRAINT bounty_moderation_cases_status_check
+        CHECK (status IN ('pending', 'held', 'approved', 'taken_down'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_bounty_moderation_cases_queue
+    ON bounty_moderation_cases(severe DESC, opened_at) WHERE status IN ('pending', 'held');
+
+CREATE TABLE IF NOT EXISTS bounty_reports (
+    id UUID PRIMARY KEY,
+    bounty_id UUID NOT NULL,
+    reporter_id UUID NOT NULL,
+    category VARCHAR(30) NOT NULL,
+    details TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (bounty_id, reporter_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_bounty_reports_bounty ON bounty_reports(bounty_id, created_at);
+
+-- Every hold and decision; `moderator_id` is NULL for automatic holds
+CREATE TABLE IF NOT EXISTS bounty_moderation_actions (
+    id UUID PRIMARY KEY,
+    bounty_id UUID NOT NULL,
+    action VARCHAR(20) NOT NULL,
+    moderator_id UUID,
+    reason TEXT,
+    previous_status VARCHAR(50) NOT NULL,
+    new_status VARCHAR(50) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_bounty_moderation_actions_bounty
+    ON bounty_moderation_actions(bounty_id, created_at);
+
+-- Artifacts of taken-down bounties; new bounties on them are held
+CREATE TABLE IF NOT EXISTS moderation_blocked_artifacts (
+    artifact_hash VARCHAR(128) PRIMARY KEY,
+    bounty_id UUID NOT NULL,
+    blocked_by UUID NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
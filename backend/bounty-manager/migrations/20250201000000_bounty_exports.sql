@@ -0,0 +1,26 @@
+-- Background exports of a creator's bounties, generated by the export worker
+-- into files served through signed download links
+
+CREATE TABLE IF NOT EXISTS bounty_exports (
+    id UUID PRIMARY KEY,
+    creator VARCHAR(255) NOT NULL,
+    -- `csv` or `ndjson`
+    format VARCHAR(10) NOT NULL,
+    -- Bounties created in [range_start, range_end)
+    range_start TIMESTAMP WITH TIME ZONE NOT NULL,
+    range_end TIMESTAMP WITH TIME ZONE NOT NULL,
+    -- `pending`, `running`, `completed`, `failed` or `expired`
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    row_count BIGINT,
+    file_path TEXT,
+    error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMP WITH TIME ZONE,
+    completed_at TIMESTAMP WITH TIME ZONE,
+    -- When the file is deleted and its download link stops working
+    expires_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_bounty_exports_creator ON bounty_exports(creator, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_bounty_exports_pending ON bounty_exports(created_at) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_bounty_exports_expiry ON bounty_exports(expires_at) WHERE status = 'completed';
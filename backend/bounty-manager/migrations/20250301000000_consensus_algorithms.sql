@@ -0,0 +1,5 @@
+-- How the consensus-service aggregates a bounty's votes: simple_majority,
+-- reputation_weighted, stake_weighted or bayesian_truth_serum. NULL leaves
+-- the choice to the consensus-service default.
+
+ALTER TABLE bounties ADD COLUMN IF NOT EXISTS consensus_algorithm VARCHAR(50);
@@ -0,0 +1,5 @@
+-- Mobile malware gets its own tag, so engines can build a mobile reputation
+
+INSERT INTO bounty_tags (slug, name, category, description) VALUES
+    ('mobile', 'Mobile', 'platform', 'Targets Android or iOS devices')
+ON CONFLICT (slug) DO NOTHING;
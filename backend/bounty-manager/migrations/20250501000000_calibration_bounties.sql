@@ -0,0 +1,11 @@
+-- Calibration bounties. The platform injects bounties on artifacts whose
+-- verdict it already knows; new engines are on probation until they settle
+-- enough of them. The verdict is forwarded to the consensus-service with each
+-- submission and never shown to engines.
+
+CREATE TABLE IF NOT EXISTS calibration_bounties (
+    bounty_id UUID PRIMARY KEY REFERENCES bounties(id) ON DELETE CASCADE,
+    expected_verdict VARCHAR(20) NOT NULL CHECK (expected_verdict IN ('malicious', 'benign', 'suspicious')),
+    created_by UUID NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
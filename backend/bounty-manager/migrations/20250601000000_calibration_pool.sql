@@ -0,0 +1,31 @@
+-- Calibration artifact pool. Platform operators keep a pool of artifacts
+-- whose verdict is known; the calibration worker injects them as bounties
+-- that look like any other, from the platform's creator wallets with
+-- jittered terms, so engines can't tell them apart. Titles, descriptions and
+-- tags are written per artifact for the same reason.
+
+CREATE TABLE IF NOT EXISTS calibration_artifacts (
+    id UUID PRIMARY KEY,
+    artifact_type VARCHAR(50) NOT NULL,
+    artifact_data JSONB NOT NULL,
+    expected_verdict VARCHAR(20) NOT NULL CHECK (expected_verdict IN ('malicious', 'benign', 'suspicious')),
+    title VARCHAR(500) NOT NULL,
+    description TEXT NOT NULL,
+    tags TEXT[] NOT NULL DEFAULT '{}',
+    -- Retire the artifact after this many bounties; NULL for no limit
+    max_uses INTEGER,
+    times_used INTEGER NOT NULL DEFAULT 0,
+    last_used_at TIMESTAMP WITH TIME ZONE,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    added_by UUID NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_calibration_artifacts_next
+    ON calibration_artifacts(last_used_at NULLS FIRST) WHERE active;
+
+-- The pool artifact a calibration bounty was injected on; NULL for bounties
+-- an admin marked by hand
+ALTER TABLE calibration_bounties
+    ADD COLUMN IF NOT EXISTS artifact_id UUID REFERENCES calibration_artifacts(id) ON DELETE SET NULL;
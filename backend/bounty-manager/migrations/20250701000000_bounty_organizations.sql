@@ -0,0 +1,9 @@
+-- Bounties created on behalf of an organization. Its owners, admins and
+-- analysts manage them alongside the creator, and the escrow is attributed
+-- to the organization. The organizations table lives in the central schema,
+-- so there is no foreign key here.
+
+ALTER TABLE bounties ADD COLUMN IF NOT EXISTS organization_id UUID;
+
+CREATE INDEX IF NOT EXISTS idx_bounties_organization ON bounties(organization_id)
+    WHERE organization_id IS NOT NULL;
@@ -0,0 +1,40 @@
+-- A deposit transaction, and the on-chain bounty it creates, fund exactly
+-- one bounty. Rows already marked 'mismatch' keep their hashes for the
+-- record and are not covered.
+
+-- Existing duplicates keep the first bounty they verified, or were checked, for
+WITH ranked AS (
+    SELECT bounty_id,
+           ROW_NUMBER() OVER (
+               PARTITION BY LOWER(deposit_tx_hash)
+               ORDER BY verified_at NULLS LAST, checked_at, bounty_id
+           ) AS position
+    FROM bounty_deposit_verifications
+    WHERE deposit_tx_hash IS NOT NULL AND status <> 'mismatch'
+)
+UPDATE bounty_deposit_verifications v
+SET status = 'mismatch', detail = 'deposit transaction already funds another bounty'
+FROM ranked r
+WHERE r.bounty_id = v.bounty_id AND r.position > 1;
+
+WITH ranked AS (
+    SELECT bounty_id,
+           ROW_NUMBER() OVER (
+               PARTITION BY on_chain_id
+               ORDER BY verified_at NULLS LAST, checked_at, bounty_id
+           ) AS position
+    FROM bounty_deposit_verifications
+    WHERE on_chain_id IS NOT NULL AND status <> 'mismatch'
+)
+UPDATE bounty_deposit_verifications v
+SET status = 'mismatch', detail = 'on-chain bounty already funds another bounty'
+FROM ranked r
+WHERE r.bounty_id = v.bounty_id AND r.position > 1;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_bounty_deposit_verifications_tx
+    ON bounty_deposit_verifications(LOWER(deposit_tx_hash))
+    WHERE deposit_tx_hash IS NOT NULL AND status <> 'mismatch';
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_bounty_deposit_verifications_on_chain
+    ON bounty_deposit_verifications(on_chain_id)
+    WHERE on_chain_id IS NOT NULL AND status <> 'mismatch';
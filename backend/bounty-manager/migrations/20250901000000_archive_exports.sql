@@ -0,0 +1,9 @@
+-- Parquet copies of archived bounties in object storage
+
+-- Object key of the bounty's export; NULL until the archival worker has
+-- uploaded it
+ALTER TABLE bounty_archive_summaries ADD COLUMN IF NOT EXISTS export_key TEXT;
+ALTER TABLE bounty_archive_summaries ADD COLUMN IF NOT EXISTS exported_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX IF NOT EXISTS idx_bounty_archive_summaries_unexported
+    ON bounty_archive_summaries(archived_at) WHERE export_key IS NULL;
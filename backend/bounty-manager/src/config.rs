@@ -12,6 +12,18 @@ pub struct Config {
     pub redis: RedisConfig,
     pub bounty: BountyConfig,
     pub consensus: ConsensusConfig,
+    pub archival: ArchivalConfig,
+    pub expiration: ExpirationConfig,
+    pub embargo: EmbargoConfig,
+    pub payment: PaymentServiceConfig,
+    pub intake: SubmissionIntakeConfig,
+    pub standing: StandingBountyConfig,
+    pub webhooks: WebhookConfig,
+    pub deposits: DepositVerificationConfig,
+    pub analytics: AnalyticsConfig,
+    pub moderation: ModerationConfig,
+    pub exports: ExportConfig,
+    pub calibration: CalibrationConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -62,6 +74,156 @@ pub struct ConsensusConfig {
     pub enable_weighted_voting: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivalConfig {
+    pub enabled: bool,
+    pub retention_days: u64,
+    pub batch_size: i64,
+    pub interval_seconds: u64,
+    /// Archived bounties are also exported as Parquet to this bucket when set
+    pub s3_bucket: Option<String>,
+    pub s3_endpoint: String,
+    pub s3_region: String,
+    pub s3_access_key: String,
+    pub s3_secret_key: String,
+}
+
+/// Expiry of bounties past their deadline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpirationConfig {
+    pub enabled: bool,
+    pub interval_seconds: u64,
+    pub batch_size: i64,
+    /// How long a closed bounty waits for the consensus-service's verdict
+    /// before its reward is refunded as having no consensus
+    pub consensus_wait_seconds: i64,
+}
+
+/// Verdict embargoes: how long verdicts stay withheld when no end is given,
+/// and the longest fixed period that may be requested
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbargoConfig {
+    pub default_days: u32,
+    pub max_days: u32,
+}
+
+/// Reward escrow through the payment-service: how often bounties awaiting
+/// their deposit are checked, and how long a deposit may take before the
+/// bounty is cancelled
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentServiceConfig {
+    pub url: String,
+    pub timeout_seconds: u64,
+    pub funding_poll_seconds: u64,
+    pub funding_timeout_hours: u64,
+}
+
+/// Submission intake: where an engine's reputation is checked before it may
+/// submit, and where accepted verdicts are forwarded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmissionIntakeConfig {
+    pub reputation_service_url: String,
+    pub consensus_service_url: String,
+    pub timeout_seconds: u64,
+}
+
+/// Standing bounties: how often due schedules and watch rules are checked,
+/// and how many new artifacts a watch scans per run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StandingBountyConfig {
+    pub enabled: bool,
+    pub interval_seconds: u64,
+    pub batch_size: i64,
+}
+
+/// Bounty event webhooks: how many a bounty may have, and how deliveries are
+/// sent and retried
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub enabled: bool,
+    pub max_per_bounty: i64,
+    pub interval_seconds: u64,
+    pub batch_size: i64,
+    pub timeout_seconds: u64,
+    /// Attempts before a delivery is marked failed
+    pub max_attempts: u32,
+    /// Retries wait this long, doubling per failure up to the maximum
+    pub backoff_base_seconds: u64,
+    pub backoff_max_seconds: u64,
+}
+
+/// On-chain verification of reward deposits: when enabled, a funded escrow
+/// only activates its bounty once the blockchain sync has matched the
+/// deposit's BountyCreated event and it has enough confirmations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DepositVerificationConfig {
+    pub enabled: bool,
+    pub confirmations: u64,
+    pub batch_size: i64,
+}
+
+/// Periodic aggregation of bounty statistics into the analytics tables
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalyticsConfig {
+    pub enabled: bool,
+    pub interval_seconds: u64,
+}
+
+/// Abuse reports and the content heuristics that hold new bounties for
+/// review. The lists come from the operator; none are built in.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModerationConfig {
+    /// Distinct reporters that put a bounty on hold
+    pub report_hold_threshold: i64,
+    /// Hold on the first CSAM or illegal-content report
+    pub hold_on_severe_report: bool,
+    /// File of known illegal-content hashes, one per line
+    pub hash_list_path: Option<String>,
+    pub blocked_terms: Vec<String>,
+    pub blocked_domains: Vec<String>,
+}
+
+/// Bounty data exports: small ones stream straight back, larger ones are
+/// written to `directory` by the export worker and downloaded through links
+/// signed with `signing_secret`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportConfig {
+    pub enabled: bool,
+    pub interval_seconds: u64,
+    /// Larger exports are generated in the background
+    pub inline_max_rows: i64,
+    pub max_range_days: i64,
+    /// Bounties read per query
+    pub page_size: i64,
+    pub directory: String,
+    /// Random per process when unset, so links do not survive restarts
+    pub signing_secret: String,
+    /// How long a finished export can be downloaded before it is deleted
+    pub link_ttl_hours: u64,
+}
+
+/// Injection of calibration bounties from the artifact pool. Terms are drawn
+/// from ranges and creators from the platform's wallets, so calibration
+/// bounties don't share tell-tale terms or a creator.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalibrationConfig {
+    pub enabled: bool,
+    pub interval_seconds: u64,
+    /// Calibration bounties kept awaiting funding or open at once
+    pub target_open: i64,
+    /// Platform wallets calibration bounties are posted from
+    pub creators: Vec<String>,
+    /// Reward token address
+    pub currency: String,
+    pub reward_min: u64,
+    pub reward_max: u64,
+    pub min_stake: u64,
+    pub deadline_hours_min: u32,
+    pub deadline_hours_max: u32,
+    /// How long before an artifact is injected again
+    pub reuse_cooldown_days: i64,
+}
+
 impl Config {
     /// Load configuration from environment variables
     pub fn from_env() -> Result<Self, ConfigError> {
@@ -160,6 +322,237 @@ impl Config {
                     .parse()
                     .unwrap_or(true),
             },
+            archival: ArchivalConfig {
+                enabled: env::var("ARCHIVAL_ENABLED")
+                    .unwrap_or_else(|_| "true".to_string())
+                    .parse()
+                    .unwrap_or(true),
+                retention_days: env::var("ARCHIVAL_RETENTION_DAYS")
+                    .unwrap_or_else(|_| "90".to_string())
+                    .parse()
+                    .unwrap_or(90),
+                batch_size: env::var("ARCHIVAL_BATCH_SIZE")
+                    .unwrap_or_else(|_| "100".to_string())
+                    .parse()
+                    .unwrap_or(100),
+                interval_seconds: env::var("ARCHIVAL_INTERVAL_SECONDS")
+                    .unwrap_or_else(|_| "3600".to_string())
+                    .parse()
+                    .unwrap_or(3600),
+                s3_bucket: env::var("ARCHIVE_S3_BUCKET").ok().filter(|b| !b.is_empty()),
+                s3_endpoint: env::var("S3_ENDPOINT").unwrap_or_else(|_| "http://minio:9000".to_string()),
+                s3_region: env::var("S3_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
+                s3_access_key: env::var("S3_ACCESS_KEY").unwrap_or_default(),
+                s3_secret_key: env::var("S3_SECRET_KEY").unwrap_or_default(),
+            },
+            expiration: ExpirationConfig {
+                enabled: env::var("EXPIRATION_ENABLED")
+                    .unwrap_or_else(|_| "true".to_string())
+                    .parse()
+                    .unwrap_or(true),
+                interval_seconds: env::var("EXPIRATION_INTERVAL_SECONDS")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()
+                    .unwrap_or(60),
+                batch_size: env::var("EXPIRATION_BATCH_SIZE")
+                    .unwrap_or_else(|_| "100".to_string())
+                    .parse()
+                    .unwrap_or(100),
+                consensus_wait_seconds: env::var("EXPIRATION_CONSENSUS_WAIT_SECONDS")
+                    .unwrap_or_else(|_| "900".to_string())
+                    .parse()
+                    .unwrap_or(900),
+            },
+            embargo: EmbargoConfig {
+                default_days: env::var("VERDICT_EMBARGO_DEFAULT_DAYS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()
+                    .unwrap_or(30),
+                max_days: env::var("VERDICT_EMBARGO_MAX_DAYS")
+                    .unwrap_or_else(|_| "365".to_string())
+                    .parse()
+                    .unwrap_or(365),
+            },
+            payment: PaymentServiceConfig {
+                url: env::var("PAYMENT_SERVICE_URL")
+                    .unwrap_or_else(|_| "http://localhost:8085".to_string()),
+                timeout_seconds: env::var("PAYMENT_SERVICE_TIMEOUT_SECONDS")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()
+                    .unwrap_or(10),
+                funding_poll_seconds: env::var("ESCROW_FUNDING_POLL_SECONDS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()
+                    .unwrap_or(30),
+                funding_timeout_hours: env::var("ESCROW_FUNDING_TIMEOUT_HOURS")
+                    .unwrap_or_else(|_| "24".to_string())
+                    .parse()
+                    .unwrap_or(24),
+            },
+            intake: SubmissionIntakeConfig {
+                reputation_service_url: env::var("REPUTATION_SERVICE_URL")
+                    .unwrap_or_else(|_| "http://localhost:8086".to_string()),
+                consensus_service_url: env::var("CONSENSUS_SERVICE_URL")
+                    .unwrap_or_else(|_| "http://localhost:8087".to_string()),
+                timeout_seconds: env::var("INTAKE_SERVICE_TIMEOUT_SECONDS")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()
+                    .unwrap_or(10),
+            },
+            standing: StandingBountyConfig {
+                enabled: env::var("STANDING_BOUNTIES_ENABLED")
+                    .unwrap_or_else(|_| "true".to_string())
+                    .parse()
+                    .unwrap_or(true),
+                interval_seconds: env::var("STANDING_BOUNTY_INTERVAL_SECONDS")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()
+                    .unwrap_or(60),
+                batch_size: env::var("STANDING_BOUNTY_BATCH_SIZE")
+                    .unwrap_or_else(|_| "100".to_string())
+                    .parse()
+                    .unwrap_or(100),
+            },
+            webhooks: WebhookConfig {
+                enabled: env::var("WEBHOOK_DELIVERY_ENABLED")
+                    .unwrap_or_else(|_| "true".to_string())
+                    .parse()
+                    .unwrap_or(true),
+                max_per_bounty: env::var("WEBHOOKS_PER_BOUNTY")
+                    .unwrap_or_else(|_| "5".to_string())
+                    .parse()
+                    .unwrap_or(5),
+                interval_seconds: env::var("WEBHOOK_DELIVERY_INTERVAL_SECONDS")
+                    .unwrap_or_else(|_| "5".to_string())
+                    .parse()
+                    .unwrap_or(5),
+                batch_size: env::var("WEBHOOK_DELIVERY_BATCH_SIZE")
+                    .unwrap_or_else(|_| "100".to_string())
+                    .parse()
+                    .unwrap_or(100),
+                timeout_seconds: env::var("WEBHOOK_TIMEOUT_SECONDS")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()
+                    .unwrap_or(10),
+                max_attempts: env::var("WEBHOOK_MAX_ATTEMPTS")
+                    .unwrap_or_else(|_| "8".to_string())
+                    .parse()
+                    .unwrap_or(8),
+                backoff_base_seconds: env::var("WEBHOOK_BACKOFF_BASE_SECONDS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()
+                    .unwrap_or(30),
+                backoff_max_seconds: env::var("WEBHOOK_BACKOFF_MAX_SECONDS")
+                    .unwrap_or_else(|_| "3600".to_string())
+                    .parse()
+                    .unwrap_or(3600),
+            },
+            deposits: DepositVerificationConfig {
+                enabled: env::var("DEPOSIT_VERIFICATION_ENABLED")
+                    .unwrap_or_else(|_| "false".to_string())
+                    .parse()
+                    .unwrap_or(false),
+                confirmations: env::var("DEPOSIT_CONFIRMATIONS")
+                    .unwrap_or_else(|_| "12".to_string())
+                    .parse()
+                    .unwrap_or(12),
+                batch_size: env::var("DEPOSIT_VERIFICATION_BATCH_SIZE")
+                    .unwrap_or_else(|_| "100".to_string())
+                    .parse()
+                    .unwrap_or(100),
+            },
+            analytics: AnalyticsConfig {
+                enabled: env::var("ANALYTICS_ENABLED")
+                    .unwrap_or_else(|_| "true".to_string())
+                    .parse()
+                    .unwrap_or(true),
+                interval_seconds: env::var("ANALYTICS_INTERVAL_SECONDS")
+                    .unwrap_or_else(|_| "300".to_string())
+                    .parse()
+                    .unwrap_or(300),
+            },
+            moderation: ModerationConfig {
+                report_hold_threshold: env::var("MODERATION_REPORT_HOLD_THRESHOLD")
+                    .unwrap_or_else(|_| "3".to_string())
+                    .parse()
+                    .unwrap_or(3),
+                hold_on_severe_report: env::var("MODERATION_HOLD_ON_SEVERE_REPORT")
+                    .unwrap_or_else(|_| "true".to_string())
+                    .parse()
+                    .unwrap_or(true),
+                hash_list_path: env::var("MODERATION_HASH_LIST_PATH").ok().filter(|path| !path.is_empty()),
+                blocked_terms: list_var("MODERATION_BLOCKED_TERMS"),
+                blocked_domains: list_var("MODERATION_BLOCKED_DOMAINS"),
+            },
+            exports: ExportConfig {
+                enabled: env::var("EXPORTS_ENABLED")
+                    .unwrap_or_else(|_| "true".to_string())
+                    .parse()
+                    .unwrap_or(true),
+                interval_seconds: env::var("EXPORT_INTERVAL_SECONDS")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()
+                    .unwrap_or(10),
+                inline_max_rows: env::var("EXPORT_INLINE_MAX_ROWS")
+                    .unwrap_or_else(|_| "10000".to_string())
+                    .parse()
+                    .unwrap_or(10000),
+                max_range_days: env::var("EXPORT_MAX_RANGE_DAYS")
+                    .unwrap_or_else(|_| "366".to_string())
+                    .parse()
+                    .unwrap_or(366),
+                page_size: env::var("EXPORT_PAGE_SIZE")
+                    .unwrap_or_else(|_| "500".to_string())
+                    .parse()
+                    .unwrap_or(500),
+                directory: env::var("EXPORT_DIRECTORY").unwrap_or_else(|_| "./exports".to_string()),
+                signing_secret: env::var("EXPORT_SIGNING_SECRET").unwrap_or_default(),
+                link_ttl_hours: env::var("EXPORT_LINK_TTL_HOURS")
+                    .unwrap_or_else(|_| "24".to_string())
+                    .parse()
+                    .unwrap_or(24),
+            },
+            calibration: CalibrationConfig {
+                enabled: env::var("CALIBRATION_INJECTION_ENABLED")
+                    .unwrap_or_else(|_| "false".to_string())
+                    .parse()
+                    .unwrap_or(false),
+                interval_seconds: env::var("CALIBRATION_INTERVAL_SECONDS")
+                    .unwrap_or_else(|_| "600".to_string())
+                    .parse()
+                    .unwrap_or(600),
+                target_open: env::var("CALIBRATION_TARGET_OPEN")
+                    .unwrap_or_else(|_| "5".to_string())
+                    .parse()
+                    .unwrap_or(5),
+                creators: list_var("CALIBRATION_CREATORS"),
+                currency: env::var("CALIBRATION_CURRENCY")
+                    .unwrap_or_else(|_| "0x0000000000000000000000000000000000000000".to_string()),
+                reward_min: env::var("CALIBRATION_REWARD_MIN")
+                    .unwrap_or_else(|_| "1000".to_string())
+                    .parse()
+                    .unwrap_or(1000),
+                reward_max: env::var("CALIBRATION_REWARD_MAX")
+                    .unwrap_or_else(|_| "5000".to_string())
+                    .parse()
+                    .unwrap_or(5000),
+                min_stake: env::var("CALIBRATION_MIN_STAKE")
+                    .unwrap_or_else(|_| "1000".to_string())
+                    .parse()
+                    .unwrap_or(1000),
+                deadline_hours_min: env::var("CALIBRATION_DEADLINE_HOURS_MIN")
+                    .unwrap_or_else(|_| "12".to_string())
+                    .parse()
+                    .unwrap_or(12),
+                deadline_hours_max: env::var("CALIBRATION_DEADLINE_HOURS_MAX")
+                    .unwrap_or_else(|_| "72".to_string())
+                    .parse()
+                    .unwrap_or(72),
+                reuse_cooldown_days: env::var("CALIBRATION_REUSE_COOLDOWN_DAYS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()
+                    .unwrap_or(30),
+            },
         })
     }
 
@@ -190,6 +583,92 @@ impl Config {
             return Err(ConfigError::InvalidConfig("Min quality score must be between 0.0 and 1.0".to_string()));
         }
 
+        if self.archival.retention_days == 0 || self.archival.batch_size <= 0 {
+            return Err(ConfigError::InvalidConfig("Archival retention and batch size must be > 0".to_string()));
+        }
+
+        if self.archival.s3_bucket.is_some()
+            && (self.archival.s3_access_key.is_empty() || self.archival.s3_secret_key.is_empty())
+        {
+            return Err(ConfigError::InvalidConfig(
+                "ARCHIVE_S3_BUCKET requires S3_ACCESS_KEY and S3_SECRET_KEY".to_string(),
+            ));
+        }
+
+        if self.expiration.interval_seconds == 0 || self.expiration.batch_size <= 0 {
+            return Err(ConfigError::InvalidConfig("Expiration interval and batch size must be > 0".to_string()));
+        }
+
+        if self.expiration.consensus_wait_seconds <= 0 {
+            return Err(ConfigError::InvalidConfig("Expiration consensus wait must be > 0".to_string()));
+        }
+
+        if self.standing.interval_seconds == 0 || self.standing.batch_size <= 0 {
+            return Err(ConfigError::InvalidConfig("Standing bounty interval and batch size must be > 0".to_string()));
+        }
+
+        if self.webhooks.max_attempts == 0
+            || self.webhooks.interval_seconds == 0
+            || self.webhooks.batch_size <= 0
+            || self.webhooks.backoff_base_seconds > self.webhooks.backoff_max_seconds
+        {
+            return Err(ConfigError::InvalidConfig(
+                "Webhook attempts, interval and batch size must be > 0 and backoff base <= max".to_string(),
+            ));
+        }
+
+        if self.deposits.confirmations == 0 || self.deposits.batch_size <= 0 {
+            return Err(ConfigError::InvalidConfig("Deposit confirmations and batch size must be > 0".to_string()));
+        }
+
+        if self.analytics.interval_seconds == 0 {
+            return Err(ConfigError::InvalidConfig("Analytics interval must be > 0".to_string()));
+        }
+
+        if self.moderation.report_hold_threshold <= 0 {
+            return Err(ConfigError::InvalidConfig("Moderation report hold threshold must be > 0".to_string()));
+        }
+
+        if self.exports.interval_seconds == 0
+            || self.exports.inline_max_rows < 0
+            || self.exports.max_range_days <= 0
+            || self.exports.page_size <= 0
+            || self.exports.link_ttl_hours == 0
+        {
+            return Err(ConfigError::InvalidConfig(
+                "Export interval, range, page size and link lifetime must be > 0".to_string(),
+            ));
+        }
+
+        if self.embargo.default_days == 0 || self.embargo.default_days > self.embargo.max_days {
+            return Err(ConfigError::InvalidConfig("Embargo default must be between 1 and the maximum days".to_string()));
+        }
+
+        if self.payment.funding_poll_seconds == 0 || self.payment.funding_timeout_hours == 0 {
+            return Err(ConfigError::InvalidConfig("Escrow funding poll and timeout must be > 0".to_string()));
+        }
+
+        if self.intake.timeout_seconds == 0 {
+            return Err(ConfigError::InvalidConfig("Intake service timeout must be > 0".to_string()));
+        }
+
+        let calibration = &self.calibration;
+        if calibration.enabled
+            && (calibration.creators.is_empty()
+                || calibration.interval_seconds == 0
+                || calibration.target_open <= 0
+                || calibration.min_stake == 0
+                || calibration.reward_min == 0
+                || calibration.reward_min > calibration.reward_max
+                || calibration.deadline_hours_min == 0
+                || calibration.deadline_hours_min > calibration.deadline_hours_max
+                || calibration.reuse_cooldown_days < 0)
+        {
+            return Err(ConfigError::InvalidConfig(
+                "Calibration injection needs creator wallets, terms > 0 and ranges with min <= max".to_string(),
+            ));
+        }
+
         Ok(())
     }
 }
@@ -233,10 +712,106 @@ impl Default for Config {
                 voting_window_hours: 48,
                 enable_weighted_voting: true,
             },
+            archival: ArchivalConfig {
+                enabled: true,
+                retention_days: 90,
+                batch_size: 100,
+                interval_seconds: 3600,
+                s3_bucket: None,
+                s3_endpoint: "http://minio:9000".to_string(),
+                s3_region: "us-east-1".to_string(),
+                s3_access_key: String::new(),
+                s3_secret_key: String::new(),
+            },
+            expiration: ExpirationConfig {
+                enabled: true,
+                interval_seconds: 60,
+                batch_size: 100,
+                consensus_wait_seconds: 900,
+            },
+            embargo: EmbargoConfig {
+                default_days: 30,
+                max_days: 365,
+            },
+            payment: PaymentServiceConfig {
+                url: "http://localhost:8085".to_string(),
+                timeout_seconds: 10,
+                funding_poll_seconds: 30,
+                funding_timeout_hours: 24,
+            },
+            intake: SubmissionIntakeConfig {
+                reputation_service_url: "http://localhost:8086".to_string(),
+                consensus_service_url: "http://localhost:8087".to_string(),
+                timeout_seconds: 10,
+            },
+            standing: StandingBountyConfig {
+                enabled: true,
+                interval_seconds: 60,
+                batch_size: 100,
+            },
+            webhooks: WebhookConfig {
+                enabled: true,
+                max_per_bounty: 5,
+                interval_seconds: 5,
+                batch_size: 100,
+                timeout_seconds: 10,
+                max_attempts: 8,
+                backoff_base_seconds: 30,
+                backoff_max_seconds: 3600,
+            },
+            deposits: DepositVerificationConfig {
+                enabled: false,
+                confirmations: 12,
+                batch_size: 100,
+            },
+            analytics: AnalyticsConfig {
+                enabled: true,
+                interval_seconds: 300,
+            },
+            moderation: ModerationConfig {
+                report_hold_threshold: 3,
+                hold_on_severe_report: true,
+                hash_list_path: None,
+                blocked_terms: Vec::new(),
+                blocked_domains: Vec::new(),
+            },
+            exports: ExportConfig {
+                enabled: true,
+                interval_seconds: 10,
+                inline_max_rows: 10000,
+                max_range_days: 366,
+                page_size: 500,
+                directory: "./exports".to_string(),
+                signing_secret: String::new(),
+                link_ttl_hours: 24,
+            },
+            calibration: CalibrationConfig {
+                enabled: false,
+                interval_seconds: 600,
+                target_open: 5,
+                creators: Vec::new(),
+                currency: "0x0000000000000000000000000000000000000000".to_string(),
+                reward_min: 1000,
+                reward_max: 5000,
+                min_stake: 1000,
+                deadline_hours_min: 12,
+                deadline_hours_max: 72,
+                reuse_cooldown_days: 30,
+            },
         }
     }
 }
 
+/// Comma-separated, lowercased entries of a list variable
+fn list_var(name: &str) -> Vec<String> {
+    env::var(name)
+        .unwrap_or_default()
+        .split(',')
+        .map(|entry| entry.trim().to_lowercase())
+        .filter(|entry| !entry.is_empty())
+        .collect()
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum ConfigError {
     #[error("Configuration file not found")]
@@ -275,4 +850,110 @@ mod tests {
         config.consensus.consensus_threshold = 1.5;
         assert!(config.validate().is_err());
     }
+
+    #[test]
+    fn test_invalid_archival_retention() {
+        let mut config = Config::default();
+        config.archival.retention_days = 0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_archive_bucket_requires_credentials() {
+        let mut config = Config::default();
+        config.archival.s3_bucket = Some("archive".to_string());
+        assert!(config.validate().is_err());
+
+        config.archival.s3_access_key = "access".to_string();
+        config.archival.s3_secret_key = "secret".to_string();
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_invalid_expiration_batch() {
+        let mut config = Config::default();
+        config.expiration.batch_size = 0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_invalid_expiration_consensus_wait() {
+        let mut config = Config::default();
+        config.expiration.consensus_wait_seconds = 0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_invalid_standing_bounty_interval() {
+        let mut config = Config::default();
+        config.standing.interval_seconds = 0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_invalid_webhook_backoff() {
+        let mut config = Config::default();
+        config.webhooks.backoff_base_seconds = config.webhooks.backoff_max_seconds + 1;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_invalid_deposit_confirmations() {
+        let mut config = Config::default();
+        config.deposits.confirmations = 0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_invalid_analytics_interval() {
+        let mut config = Config::default();
+        config.analytics.interval_seconds = 0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_invalid_moderation_threshold() {
+        let mut config = Config::default();
+        config.moderation.report_hold_threshold = 0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_invalid_export_page_size() {
+        let mut config = Config::default();
+        config.exports.page_size = 0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_calibration_injection_needs_creators() {
+        let mut config = Config::default();
+        config.calibration.enabled = true;
+        assert!(config.validate().is_err());
+        config.calibration.creators = vec!["0x00000000000000000000000000000000000000aa".to_string()];
+        assert!(config.validate().is_ok());
+        config.calibration.reward_min = config.calibration.reward_max + 1;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_invalid_embargo_default() {
+        let mut config = Config::default();
+        config.embargo.default_days = config.embargo.max_days + 1;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_invalid_escrow_funding_poll() {
+        let mut config = Config::default();
+        config.payment.funding_poll_seconds = 0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_invalid_intake_timeout() {
+        let mut config = Config::default();
+        config.intake.timeout_seconds = 0;
+        assert!(config.validate().is_err());
+    }
 }
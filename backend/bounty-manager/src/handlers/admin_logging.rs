@@ -0,0 +1,71 @@
+// backend/bounty-manager/src/handlers/admin_logging.rs
+//
+// bounty-manager runs axum 0.8, so it cannot use the shared crate's axum 0.7
+// routes; these are thin equivalents over the same shared logging functions.
+
+use axum::{
+    extract::Request,
+    http::{HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use shared::observability::{
+    admin_token_matches, log_level_handle, request_span, set_log_level, with_log_context,
+    LogContext, LogLevelBody, ADMIN_TOKEN_HEADER, REQUEST_ID_HEADER, TRACEPARENT_HEADER,
+};
+use tracing::Instrument;
+
+/// Attach request/trace IDs to everything logged while handling the request
+pub async fn log_context_middleware(mut request: Request, next: Next) -> Response {
+    let (context, span) = {
+        let header = |name: &str| request.headers().get(name).and_then(|v| v.to_str().ok());
+        let context = LogContext::from_headers(header(REQUEST_ID_HEADER), header(TRACEPARENT_HEADER));
+        let span = request_span(
+            request.method().as_str(),
+            request.uri().path(),
+            context.request_id.as_deref().unwrap_or_default(),
+            header(TRACEPARENT_HEADER),
+        );
+        (context, span)
+    };
+    let request_id = HeaderValue::from_str(context.request_id.as_deref().unwrap_or_default()).ok();
+    if let Some(ref value) = request_id {
+        request.headers_mut().insert(REQUEST_ID_HEADER, value.clone());
+    }
+
+    let mut response = with_log_context(context, next.run(request).instrument(span)).await;
+    if let Some(value) = request_id {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+fn authorized(headers: &HeaderMap) -> bool {
+    admin_token_matches(headers.get(ADMIN_TOKEN_HEADER).and_then(|v| v.to_str().ok()))
+}
+
+/// Current log filter
+pub async fn get_log_level(headers: HeaderMap) -> Response {
+    if !authorized(&headers) {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    match log_level_handle() {
+        Some(handle) => Json(LogLevelBody { level: handle.current() }).into_response(),
+        None => StatusCode::SERVICE_UNAVAILABLE.into_response(),
+    }
+}
+
+/// Replace the log filter at runtime
+pub async fn put_log_level(headers: HeaderMap, Json(body): Json<LogLevelBody>) -> Response {
+    if !authorized(&headers) {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    match set_log_level(&body.level) {
+        Ok(()) => Json(body).into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": e.to_string() })),
+        )
+            .into_response(),
+    }
+}
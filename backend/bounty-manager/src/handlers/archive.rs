@@ -0,0 +1,65 @@
+// backend/bounty-manager/src/handlers/archive.rs
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde::Deserialize;
+use shared::types::ApiResponse;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::handlers::bounty_crud::BountyManagerState;
+use crate::models::archive::{ArchivedBountySummary, BountyArchive};
+
+#[derive(Debug, Deserialize)]
+pub struct ArchiveListParams {
+    pub creator: Option<String>,
+    pub page: Option<u32>,
+    pub per_page: Option<u32>,
+}
+
+/// List archived bounties from their summary rows
+pub async fn list_archived_bounties(
+    State(state): State<BountyManagerState>,
+    Query(params): Query<ArchiveListParams>,
+) -> Result<Json<ApiResponse<Vec<ArchivedBountySummary>>>, StatusCode> {
+    let page = params.page.unwrap_or(1).max(1);
+    let per_page = params.per_page.unwrap_or(20).min(100);
+    let offset = (page - 1) as i64 * per_page as i64;
+
+    let summaries = BountyArchive::list_summaries(
+        &state.db,
+        params.creator.as_deref(),
+        per_page as i64,
+        offset,
+    )
+    .await
+    .map_err(|e| {
+        error!("Failed to list archived bounties: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(ApiResponse::success(summaries)))
+}
+
+/// Restore an archived bounty to the hot tables (e.g. before opening a dispute)
+pub async fn rehydrate_bounty(
+    State(state): State<BountyManagerState>,
+    Path(bounty_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<()>>, StatusCode> {
+    let restored = BountyArchive::rehydrate(&state.db, bounty_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to rehydrate bounty {}: {}", bounty_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    if !restored {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    info!("Rehydrated archived bounty {}", bounty_id);
+    Ok(Json(ApiResponse::success(())))
+}
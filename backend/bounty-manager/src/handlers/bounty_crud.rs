@@ -1,6 +1,6 @@
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::Json,
     Extension,
 };
@@ -10,6 +10,18 @@ use std::sync::Arc;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use shared::types::ApiResponse;
+use shared::messaging::EventPublisher;
+use crate::config::{DepositVerificationConfig, EmbargoConfig, ExportConfig, ModerationConfig, WebhookConfig};
+use tracing::{error, info, warn};
+use crate::handlers::embargo::{self, Caller};
+use crate::handlers::moderation;
+use crate::models::analytics::{ArtifactTypeStats, BountyAnalytics, CreatorStats, DailyStats, TokenStats};
+use crate::models::bounty::BountyModel;
+use crate::models::deposit::DepositVerification;
+use crate::models::tag::{normalize_tags, BountyTag};
+use crate::services::intake::IntakeClient;
+use crate::services::moderation::ContentScreen;
+use crate::services::payment::{Escrow, PaymentClient, PaymentClientError, RefundReason};
 use crate::services::reputation::ReputationService;
 
 // Common types
@@ -32,6 +44,9 @@ pub struct Bounty {
     pub currency: String,   // Token contract address
     pub min_stake: u64,     // Minimum stake required to participate
     pub max_participants: Option<u32>,
+    /// Minimum engine reputation to submit
+    #[serde(default)]
+    pub min_reputation: Option<u32>,
     pub deadline: DateTime<Utc>,
     pub status: BountyStatus,
     pub consensus_threshold: f32, // Percentage needed for consensus
@@ -39,6 +54,32 @@ pub struct Bounty {
     pub updated_at: DateTime<Utc>,
     pub submissions: Vec<SubmissionSummary>,
     pub metadata: HashMap<String, String>,
+    /// Slugs of the taxonomy tags the bounty carries
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Set when submissions are withheld under a verdict embargo
+    #[serde(default)]
+    pub verdict_embargoed: bool,
+    /// Commit-reveal bounties take verdict commitments until the deadline
+    /// and reveals until this time
+    #[serde(default)]
+    pub reveal_deadline: Option<DateTime<Utc>>,
+    /// How submissions are aggregated into the verdict; the
+    /// consensus-service default if not chosen
+    #[serde(default)]
+    pub consensus_algorithm: Option<ConsensusAlgorithm>,
+    /// Organization the bounty was created for; its bounty managers can
+    /// fund and cancel it
+    #[serde(default)]
+    pub organization_id: Option<Uuid>,
+}
+
+impl Bounty {
+    /// Strip verdict details for callers outside the embargo
+    fn withhold_verdicts(&mut self) {
+        self.submissions.clear();
+        self.verdict_embargoed = true;
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -61,8 +102,11 @@ pub struct ArtifactData {
     pub upload_path: Option<String>, // Internal storage path
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum BountyStatus {
+    /// Reward escrow awaiting the creator's on-chain deposit; not yet open
+    /// to submissions
+    PendingFunding,
     Active,
     InProgress,
     Completed,
@@ -71,6 +115,48 @@ pub enum BountyStatus {
     UnderReview,
 }
 
+impl BountyStatus {
+    /// Name stored in `bounties.status`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BountyStatus::PendingFunding => "PendingFunding",
+            BountyStatus::Active => "Active",
+            BountyStatus::InProgress => "InProgress",
+            BountyStatus::Completed => "Completed",
+            BountyStatus::Expired => "Expired",
+            BountyStatus::Cancelled => "Cancelled",
+            BountyStatus::UnderReview => "UnderReview",
+        }
+    }
+}
+
+/// How the consensus-service aggregates a bounty's submissions
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ConsensusAlgorithm {
+    /// One vote per submission
+    SimpleMajority,
+    /// Votes weighted by engine reputation, confidence and submission time
+    ReputationWeighted,
+    /// Votes weighted by the stake behind them
+    StakeWeighted,
+    /// Bayesian truth serum: verdicts more common than the engines predicted
+    /// gain weight; engines submit predictions with their verdicts
+    BayesianTruthSerum,
+}
+
+impl ConsensusAlgorithm {
+    /// Name stored in `bounties.consensus_algorithm` and used by the
+    /// consensus-service
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConsensusAlgorithm::SimpleMajority => "simple_majority",
+            ConsensusAlgorithm::ReputationWeighted => "reputation_weighted",
+            ConsensusAlgorithm::StakeWeighted => "stake_weighted",
+            ConsensusAlgorithm::BayesianTruthSerum => "bayesian_truth_serum",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubmissionSummary {
     pub id: Uuid,
@@ -101,9 +187,35 @@ pub struct CreateBountyRequest {
     pub currency: String,
     pub min_stake: u64,
     pub max_participants: Option<u32>,
+    /// Minimum reputation-service score an engine needs to submit
+    #[serde(default)]
+    pub min_reputation: Option<u32>,
     pub deadline_hours: u32, // Hours from now
     pub consensus_threshold: Option<f32>,
     pub metadata: Option<HashMap<String, String>>,
+    /// Taxonomy tags, such as `ransomware` or `phishing`
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// The creator's reward deposit, if already sent; it can also be
+    /// attached later through the funding endpoint
+    pub deposit_tx_hash: Option<String>,
+    /// Make the bounty commit-reveal: engines commit to verdicts until the
+    /// deadline and reveal them within this many hours after it
+    #[serde(default)]
+    pub reveal_window_hours: Option<u32>,
+    /// How submissions are aggregated into the verdict, to suit the
+    /// creator's risk tolerance
+    #[serde(default)]
+    pub consensus_algorithm: Option<ConsensusAlgorithm>,
+    /// Create the bounty for the caller's organization; needs an owner,
+    /// admin or analyst role in it
+    #[serde(default)]
+    pub organization_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FundBountyRequest {
+    pub deposit_tx_hash: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -139,47 +251,269 @@ pub struct BountyStatsResponse {
     pub total_bounties: u64,
     pub active_bounties: u64,
     pub completed_bounties: u64,
-    pub total_rewards_paid: u64,
-    pub avg_resolution_time_hours: f32,
+    /// From creation to consensus, over completed bounties
+    pub avg_resolution_time_hours: Option<f64>,
     pub top_currencies: Vec<CurrencyStats>,
+    pub top_artifact_types: Vec<ArtifactTypeStats>,
+    /// Bounties created and completed per day, oldest first
+    pub daily: Vec<DailyStats>,
+    /// When the analytics worker last aggregated; `None` before its first run
+    pub refreshed_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct CurrencyStats {
     pub currency: String,
-    pub total_amount: u64,
+    /// Rewards offered and paid out, in wei
+    pub total_amount: String,
+    pub total_paid: String,
     pub bounty_count: u32,
+    pub completed_count: u32,
+}
+
+impl From<TokenStats> for CurrencyStats {
+    fn from(stats: TokenStats) -> Self {
+        Self {
+            currency: stats.currency,
+            total_amount: stats.total_offered,
+            total_paid: stats.total_paid,
+            bounty_count: stats.bounty_count as u32,
+            completed_count: stats.completed_count as u32,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BountyStatsQuery {
+    /// Days of daily history, 30 by default
+    pub days: Option<u32>,
+    pub top: Option<u32>,
 }
 
 // Application state (would typically come from dependency injection)
 #[derive(Clone)]
 pub struct BountyManagerState {
     // Database connection pool, blockchain client, etc.
+    pub db: sqlx::PgPool,
     pub reputation_service: Arc<ReputationService>,
+    pub embargo: EmbargoConfig,
+    /// Reward escrow in the payment-service
+    pub payments: Arc<PaymentClient>,
+    /// Reputation checks and consensus forwarding for submissions
+    pub intake: Arc<IntakeClient>,
+    pub webhooks: WebhookConfig,
+    pub deposits: DepositVerificationConfig,
+    pub moderation: ModerationConfig,
+    /// Content heuristics that hold new bounties for review
+    pub content_screen: Arc<ContentScreen>,
+    pub exports: ExportConfig,
+    /// Bounty events for the consensus-service
+    pub events: Arc<EventPublisher>,
+}
+
+pub(crate) fn payment_error(context: &str, e: PaymentClientError) -> StatusCode {
+    match e {
+        PaymentClientError::Rejected { status, message } => {
+            warn!("{}: payment-service returned {}: {}", context, status, message);
+            match status {
+                400 => StatusCode::BAD_REQUEST,
+                402 => StatusCode::PAYMENT_REQUIRED,
+                404 => StatusCode::NOT_FOUND,
+                409 => StatusCode::CONFLICT,
+                _ => StatusCode::BAD_GATEWAY,
+            }
+        }
+        PaymentClientError::Unavailable(message) => {
+            error!("{}: {}", context, message);
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+    }
+}
+
+pub(crate) fn db_error(context: &str, e: sqlx::Error) -> StatusCode {
+    error!("{}: {}", context, e);
+    StatusCode::INTERNAL_SERVER_ERROR
+}
+
+/// The creator, or a bounty manager of the organization the bounty belongs to
+fn may_manage(bounty: &BountyModel, user_address: &str, headers: &HeaderMap) -> bool {
+    bounty.creator == user_address
+        || Caller::from_headers(headers).is_some_and(|caller| caller.manages_bounties_of(bounty.organization_id))
+}
+
+/// Longest reveal phase a commit-reveal bounty may have
+const MAX_REVEAL_WINDOW_HOURS: u32 = 168;
+
+/// Token contract and wallet addresses are 20-byte hex
+pub(crate) fn is_address(value: &str) -> bool {
+    value.len() == 42
+        && value.starts_with("0x")
+        && value[2..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn to_model(bounty: &Bounty) -> Result<BountyModel, StatusCode> {
+    let amount = |value: u64| i64::try_from(value).map_err(|_| StatusCode::BAD_REQUEST);
+    Ok(BountyModel {
+        id: bounty.id,
+        creator: bounty.creator.clone(),
+        title: bounty.title.clone(),
+        description: bounty.description.clone(),
+        artifact_type: format!("{:?}", bounty.artifact_type),
+        artifact_hash: bounty.artifact_data.hash.clone(),
+        artifact_url: bounty.artifact_data.url.clone(),
+        file_name: bounty.artifact_data.file_name.clone(),
+        file_size: bounty.artifact_data.file_size.map(amount).transpose()?,
+        mime_type: bounty.artifact_data.mime_type.clone(),
+        upload_path: bounty.artifact_data.upload_path.clone(),
+        reward_amount: amount(bounty.reward_amount)?,
+        currency: bounty.currency.clone(),
+        min_stake: amount(bounty.min_stake)?,
+        max_participants: bounty.max_participants.map(|n| n as i32),
+        deadline: bounty.deadline,
+        status: bounty.status.as_str().to_string(),
+        consensus_threshold: bounty.consensus_threshold,
+        created_at: bounty.created_at,
+        updated_at: bounty.updated_at,
+        metadata: serde_json::to_value(&bounty.metadata).ok(),
+        min_reputation: bounty
+            .min_reputation
+            .map(|score| i32::try_from(score).map_err(|_| StatusCode::BAD_REQUEST))
+            .transpose()?,
+        reveal_deadline: bounty.reveal_deadline,
+        consensus_algorithm: bounty.consensus_algorithm.map(|algorithm| algorithm.as_str().to_string()),
+        organization_id: bounty.organization_id,
+    })
+}
+
+/// Check the reward terms shared by one-off and standing bounties
+pub(crate) fn validate_terms(
+    title: &str,
+    description: &str,
+    reward_amount: u64,
+    min_stake: u64,
+    currency: &str,
+) -> Result<(), StatusCode> {
+    if title.is_empty() || description.is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    if reward_amount == 0 || min_stake == 0 {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    if !is_address(currency) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    Ok(())
+}
+
+/// Normalize requested tags and check they are in the taxonomy
+pub(crate) async fn validate_tags(db: &sqlx::PgPool, tags: &[String]) -> Result<Vec<String>, StatusCode> {
+    let tags = normalize_tags(tags).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let unknown = BountyTag::unknown(db, &tags)
+        .await
+        .map_err(|e| db_error("Failed to check bounty tags", e))?;
+    if !unknown.is_empty() {
+        warn!("Rejected bounty with unknown tags {:?}", unknown);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    Ok(tags)
+}
+
+/// Escrow a new bounty's reward and save it: active if the deposit has
+/// already confirmed (and, with `verify_deposits`, been verified on-chain),
+/// otherwise awaiting funding. A bounty matching the content heuristics is
+/// held for moderation review instead.
+pub(crate) async fn open_bounty(
+    db: &sqlx::PgPool,
+    payments: &PaymentClient,
+    content_screen: &ContentScreen,
+    mut bounty: Bounty,
+    deposit_tx_hash: Option<&str>,
+    verify_deposits: bool,
+) -> Result<Bounty, StatusCode> {
+    // Escrow the reward first; the payment-service refuses a creator who
+    // cannot cover it
+    let escrow = payments
+        .deposit(
+            bounty.id,
+            bounty.reward_amount,
+            &bounty.creator,
+            &bounty.currency,
+            deposit_tx_hash,
+            bounty.organization_id,
+        )
+        .await
+        .map_err(|e| payment_error("Failed to escrow bounty reward", e))?;
+    let funded = escrow.is_funded()
+        && DepositVerification::confirmed(db, verify_deposits, bounty.id, escrow.deposit_tx_hash.as_deref())
+            .await
+            .map_err(|e| db_error("Failed to check deposit verification", e))?;
+    bounty.status = if funded {
+        BountyStatus::Active
+    } else {
+        BountyStatus::PendingFunding
+    };
+
+    let model = to_model(&bounty)?;
+    if let Err(e) = BountyModel::create(db, &model).await {
+        // Do not leave the reward locked for a bounty that does not exist
+        if let Err(refund_error) = payments.refund(bounty.id, RefundReason::Unfunded).await {
+            error!("Failed to release escrow of unsaved bounty {}: {}", bounty.id, refund_error);
+        }
+        return Err(db_error("Failed to save bounty", e));
+    }
+    info!("Created bounty {} ({:?})", bounty.id, bounty.status);
+
+    if !bounty.tags.is_empty() {
+        if let Err(e) = BountyTag::assign(db, bounty.id, &bounty.tags).await {
+            // The bounty stands; it can be found untagged
+            error!("Failed to tag bounty {}: {}", bounty.id, e);
+            bounty.tags.clear();
+        }
+    }
+
+    let flags = moderation::screen(db, content_screen, &model).await;
+    if !flags.is_empty() {
+        match moderation::hold(db, bounty.id, &flags, Some("content heuristics"), None).await {
+            Ok(Some(_)) => bounty.status = BountyStatus::UnderReview,
+            Ok(None) => {}
+            Err(e) => error!("Failed to hold flagged bounty {}: {}", bounty.id, e),
+        }
+    }
+
+    Ok(bounty)
 }
 
 // Handler implementations
 pub async fn create_bounty(
     State(state): State<BountyManagerState>,
     Extension(user_address): Extension<String>, // From auth middleware
+    headers: HeaderMap,
     Json(req): Json<CreateBountyRequest>,
 ) -> Result<Json<ApiResponse<Bounty>>, StatusCode> {
-    // Validate request
-    if req.title.is_empty() || req.description.is_empty() {
-        return Err(StatusCode::BAD_REQUEST);
+    if req.organization_id.is_some()
+        && !Caller::from_headers(&headers).is_some_and(|caller| caller.manages_bounties_of(req.organization_id))
+    {
+        return Err(StatusCode::FORBIDDEN);
     }
 
-    if req.reward_amount == 0 || req.min_stake == 0 {
+    // Validate request
+    validate_terms(&req.title, &req.description, req.reward_amount, req.min_stake, &req.currency)?;
+    let tags = validate_tags(&state.db, &req.tags).await?;
+    if req
+        .reveal_window_hours
+        .is_some_and(|hours| hours == 0 || hours > MAX_REVEAL_WINDOW_HOURS)
+    {
         return Err(StatusCode::BAD_REQUEST);
     }
 
     // Create bounty
-    let bounty_id = Uuid::new_v4();
     let now = Utc::now();
     let deadline = now + chrono::Duration::hours(req.deadline_hours as i64);
-
     let bounty = Bounty {
-        id: bounty_id,
+        id: Uuid::new_v4(),
         creator: user_address,
         title: req.title,
         description: req.description,
@@ -189,45 +523,155 @@ pub async fn create_bounty(
         currency: req.currency,
         min_stake: req.min_stake,
         max_participants: req.max_participants,
+        min_reputation: req.min_reputation,
         deadline,
-        status: BountyStatus::Active,
+        status: BountyStatus::PendingFunding,
         consensus_threshold: req.consensus_threshold.unwrap_or(0.75),
         created_at: now,
         updated_at: now,
         submissions: Vec::new(),
         metadata: req.metadata.unwrap_or_default(),
+        tags,
+        verdict_embargoed: false,
+        reveal_deadline: req
+            .reveal_window_hours
+            .map(|hours| deadline + chrono::Duration::hours(hours as i64)),
+        consensus_algorithm: req.consensus_algorithm,
+        organization_id: req.organization_id,
     };
+    let bounty = open_bounty(
+        &state.db,
+        &state.payments,
+        &state.content_screen,
+        bounty,
+        req.deposit_tx_hash.as_deref(),
+        state.deposits.enabled,
+    )
+    .await?;
 
-    // TODO: Save to database
-    // TODO: Create blockchain transaction for bounty creation
     // TODO: Emit event for real-time updates
 
     Ok(Json(ApiResponse::success(bounty)))
 }
 
+/// Attach the creator's on-chain reward deposit to a bounty awaiting
+/// funding. The bounty is activated once the deposit confirms.
+pub async fn fund_bounty(
+    State(state): State<BountyManagerState>,
+    Extension(user_address): Extension<String>,
+    headers: HeaderMap,
+    Path(bounty_id): Path<Uuid>,
+    Json(req): Json<FundBountyRequest>,
+) -> Result<Json<ApiResponse<Escrow>>, StatusCode> {
+    let bounty = BountyModel::find_by_id(&state.db, bounty_id)
+        .await
+        .map_err(|e| db_error("Failed to load bounty", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if !may_manage(&bounty, &user_address, &headers) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    if bounty.status != BountyStatus::PendingFunding.as_str() {
+        return Err(StatusCode::CONFLICT);
+    }
+    // A deposit transaction funds one bounty only
+    if DepositVerification::funds_other(&state.db, bounty_id, Some(&req.deposit_tx_hash), None)
+        .await
+        .map_err(|e| db_error("Failed to check deposit verification", e))?
+        .is_some()
+    {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    let escrow = state
+        .payments
+        .deposit(
+            bounty_id,
+            bounty.reward_amount as u64,
+            &bounty.creator,
+            &bounty.currency,
+            Some(&req.deposit_tx_hash),
+            bounty.organization_id,
+        )
+        .await
+        .map_err(|e| payment_error("Failed to attach bounty deposit", e))?;
+    let verified = escrow.is_funded()
+        && DepositVerification::confirmed(&state.db, state.deposits.enabled, bounty_id, Some(&req.deposit_tx_hash))
+            .await
+            .map_err(|e| db_error("Failed to check deposit verification", e))?;
+    if verified
+        && BountyModel::transition(
+            &state.db,
+            bounty_id,
+            BountyStatus::PendingFunding.as_str(),
+            BountyStatus::Active.as_str(),
+        )
+        .await
+        .map_err(|e| db_error("Failed to activate bounty", e))?
+    {
+        info!("Bounty {} funded and activated", bounty_id);
+    }
+
+    Ok(Json(ApiResponse::success(escrow)))
+}
+
+/// Where on-chain verification of the caller's reward deposit stands
+pub async fn get_deposit_verification(
+    State(state): State<BountyManagerState>,
+    Extension(user_address): Extension<String>,
+    Path(bounty_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<DepositVerification>>, StatusCode> {
+    let bounty = BountyModel::find_by_id(&state.db, bounty_id)
+        .await
+        .map_err(|e| db_error("Failed to load bounty", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if bounty.creator != user_address {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let verification = DepositVerification::find(&state.db, bounty_id)
+        .await
+        .map_err(|e| db_error("Failed to load deposit verification", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(ApiResponse::success(verification)))
+}
+
 pub async fn get_bounty(
-    State(_state): State<BountyManagerState>,
+    State(state): State<BountyManagerState>,
     Path(bounty_id): Path<Uuid>,
+    headers: HeaderMap,
 ) -> Result<Json<ApiResponse<Bounty>>, StatusCode> {
     // TODO: Fetch from database
     // For now, return a mock bounty
-    let mock_bounty = create_mock_bounty(bounty_id);
+    let mut mock_bounty = create_mock_bounty(bounty_id);
+
+    let withheld = embargo::withheld_for(&state, Caller::from_headers(&headers), &[bounty_id]).await?;
+    if withheld.contains(&bounty_id) {
+        mock_bounty.withhold_verdicts();
+    }
 
     Ok(Json(ApiResponse::success(mock_bounty)))
 }
 
 pub async fn list_bounties(
-    State(_state): State<BountyManagerState>,
+    State(state): State<BountyManagerState>,
     Query(pagination): Query<PaginationParams>,
     Query(filters): Query<BountyFilters>,
+    headers: HeaderMap,
 ) -> Result<Json<ApiResponse<BountyListResponse>>, StatusCode> {
     let page = pagination.page.unwrap_or(1);
     let per_page = pagination.per_page.unwrap_or(20).min(100);
 
     // TODO: Implement database query with filters and pagination
-    let bounties = create_mock_bounty_list();
+    let mut bounties = create_mock_bounty_list();
     let total_count = bounties.len();
 
+    let ids: Vec<Uuid> = bounties.iter().map(|b| b.id).collect();
+    let withheld = embargo::withheld_for(&state, Caller::from_headers(&headers), &ids).await?;
+    for bounty in bounties.iter_mut().filter(|b| withheld.contains(&b.id)) {
+        bounty.withhold_verdicts();
+    }
+
     let response_data = BountyListResponse {
         bounties,
         total_count,
@@ -256,6 +700,15 @@ pub async fn update_bounty(
         return Err(StatusCode::FORBIDDEN);
     }
 
+    // Activation follows the escrow; cancelling goes through the cancel
+    // endpoint so the reward is refunded
+    if req.status.is_some()
+        && (bounty.status == BountyStatus::PendingFunding
+            || matches!(req.status, Some(BountyStatus::PendingFunding | BountyStatus::Cancelled)))
+    {
+        return Err(StatusCode::CONFLICT);
+    }
+
     // Apply updates
     if let Some(title) = req.title {
         bounty.title = title;
@@ -281,45 +734,102 @@ pub async fn update_bounty(
     Ok(Json(ApiResponse::success(bounty)))
 }
 
+/// Cancel a bounty and refund its reward escrow to the creator
 pub async fn cancel_bounty(
-    State(_state): State<BountyManagerState>,
+    State(state): State<BountyManagerState>,
     Extension(user_address): Extension<String>,
+    headers: HeaderMap,
     Path(bounty_id): Path<Uuid>,
 ) -> Result<Json<ApiResponse<()>>, StatusCode> {
-    // TODO: Fetch bounty and verify ownership
-    // TODO: Check if bounty can be cancelled
-    // TODO: Handle refunds and blockchain transactions
+    let bounty = BountyModel::find_by_id(&state.db, bounty_id)
+        .await
+        .map_err(|e| db_error("Failed to load bounty", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if !may_manage(&bounty, &user_address, &headers) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    let cancellable = [
+        BountyStatus::PendingFunding.as_str(),
+        BountyStatus::Active.as_str(),
+        BountyStatus::InProgress.as_str(),
+    ];
+    if !cancellable.contains(&bounty.status.as_str()) {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    match state.payments.refund(bounty_id, RefundReason::Cancelled).await {
+        Ok(escrow) => info!("Escrow for cancelled bounty {} is {}", bounty_id, escrow.status),
+        // Bounties created before escrow have nothing to refund
+        Err(PaymentClientError::Rejected { status: 404, .. }) => {}
+        Err(e) => return Err(payment_error("Failed to refund bounty escrow", e)),
+    }
+    BountyModel::update_status(&state.db, bounty_id, BountyStatus::Cancelled.as_str())
+        .await
+        .map_err(|e| db_error("Failed to cancel bounty", e))?;
 
     Ok(Json(ApiResponse::success(())))
 }
 
 pub async fn get_bounty_stats(
-    State(_state): State<BountyManagerState>,
+    State(state): State<BountyManagerState>,
+    Query(params): Query<BountyStatsQuery>,
 ) -> Result<Json<ApiResponse<BountyStatsResponse>>, StatusCode> {
-    // TODO: Implement real statistics from database
+    let days = params.days.unwrap_or(30).clamp(1, 365) as i32;
+    let top = params.top.unwrap_or(10).clamp(1, 100) as i64;
+
+    let summary = BountyAnalytics::summary(&state.db)
+        .await
+        .map_err(|e| db_error("Failed to load bounty stats", e))?;
+    let daily = BountyAnalytics::daily(&state.db, days)
+        .await
+        .map_err(|e| db_error("Failed to load daily bounty stats", e))?;
+    let tokens = BountyAnalytics::tokens(&state.db, top)
+        .await
+        .map_err(|e| db_error("Failed to load token stats", e))?;
+    let artifact_types = BountyAnalytics::artifact_types(&state.db, top)
+        .await
+        .map_err(|e| db_error("Failed to load artifact type stats", e))?;
+
     let stats = BountyStatsResponse {
-        total_bounties: 156,
-        active_bounties: 23,
-        completed_bounties: 128,
-        total_rewards_paid: 2500000, // wei
-        avg_resolution_time_hours: 4.2,
-        top_currencies: vec![
-            CurrencyStats {
-                currency: "0x...".to_string(), // ETH address
-                total_amount: 1500000,
-                bounty_count: 89,
-            },
-            CurrencyStats {
-                currency: "0x...".to_string(), // USDC address
-                total_amount: 1000000,
-                bounty_count: 67,
-            },
-        ],
+        total_bounties: summary.as_ref().map_or(0, |s| s.total_bounties as u64),
+        active_bounties: summary.as_ref().map_or(0, |s| s.active_bounties as u64),
+        completed_bounties: summary.as_ref().map_or(0, |s| s.completed_bounties as u64),
+        avg_resolution_time_hours: summary.as_ref().and_then(|s| s.avg_hours_to_consensus),
+        top_currencies: tokens.into_iter().map(CurrencyStats::from).collect(),
+        top_artifact_types: artifact_types,
+        daily,
+        refreshed_at: summary.map(|s| s.refreshed_at),
     };
 
     Ok(Json(ApiResponse::success(stats)))
 }
 
+pub async fn list_creator_stats(
+    State(state): State<BountyManagerState>,
+    Query(params): Query<PaginationParams>,
+) -> Result<Json<ApiResponse<Vec<CreatorStats>>>, StatusCode> {
+    let page = params.page.unwrap_or(1).max(1);
+    let per_page = params.per_page.unwrap_or(20).clamp(1, 100);
+
+    let creators = BountyAnalytics::creators(&state.db, per_page as i64, ((page - 1) * per_page) as i64)
+        .await
+        .map_err(|e| db_error("Failed to load creator stats", e))?;
+
+    Ok(Json(ApiResponse::success(creators)))
+}
+
+pub async fn get_creator_stats(
+    State(state): State<BountyManagerState>,
+    Path(creator): Path<String>,
+) -> Result<Json<ApiResponse<CreatorStats>>, StatusCode> {
+    let stats = BountyAnalytics::creator(&state.db, &creator)
+        .await
+        .map_err(|e| db_error("Failed to load creator stats", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(ApiResponse::success(stats)))
+}
+
 pub async fn submit_to_bounty(
     State(state): State<BountyManagerState>,
     Extension(engine_id): Extension<String>, // From auth middleware
@@ -389,6 +899,7 @@ fn create_mock_bounty(id: Uuid) -> Bounty {
         currency: "0x...".to_string(),
         min_stake: 10000,
         max_participants: Some(10),
+        min_reputation: None,
         deadline: Utc::now() + chrono::Duration::hours(24),
         status: BountyStatus::Active,
         consensus_threshold: 0.75,
@@ -396,6 +907,11 @@ fn create_mock_bounty(id: Uuid) -> Bounty {
         updated_at: Utc::now() - chrono::Duration::hours(2),
         submissions: vec![],
         metadata: HashMap::new(),
+        tags: vec!["trojan".to_string()],
+        verdict_embargoed: false,
+        reveal_deadline: None,
+        consensus_algorithm: None,
+        organization_id: None,
     }
 }
 
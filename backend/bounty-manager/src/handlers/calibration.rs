@@ -0,0 +1,207 @@
+// backend/bounty-manager/src/handlers/calibration.rs
+//
+// Calibration bounties are ordinary bounties the platform posts on artifacts
+// whose verdict it already knows. Engines cannot tell them apart; the known
+// verdict goes to the consensus-service with each submission, the bounty
+// settles on it, and the reputation-service measures engines' accuracy on
+// the result. Most come from the calibration pool, which the calibration
+// worker injects from; admins can also mark a bounty by hand.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use shared::types::ApiResponse;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::handlers::bounty_crud::{db_error, validate_tags, ArtifactData, ArtifactType, BountyManagerState};
+use crate::handlers::embargo::require_admin;
+use crate::models::bounty::BountyModel;
+use crate::models::calibration::{CalibrationArtifact, CalibrationBounty, CALIBRATION_VERDICTS};
+use crate::models::submission::SubmissionModel;
+
+#[derive(Debug, Deserialize)]
+pub struct MarkCalibrationRequest {
+    /// `malicious`, `benign` or `suspicious`
+    pub expected_verdict: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CalibrationListParams {
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+    /// Artifacts only: just the active ones
+    #[serde(default)]
+    pub active: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddCalibrationArtifactRequest {
+    pub artifact_type: ArtifactType,
+    pub artifact_data: ArtifactData,
+    /// `malicious`, `benign` or `suspicious`
+    pub expected_verdict: String,
+    /// Bounty text it is injected with, written like a real creator's
+    pub title: String,
+    pub description: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Retire it after this many bounties
+    pub max_uses: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetCalibrationArtifactActiveRequest {
+    pub active: bool,
+}
+
+fn expected_verdict(verdict: &str) -> Result<String, StatusCode> {
+    let verdict = verdict.trim().to_lowercase();
+    if !CALIBRATION_VERDICTS.contains(&verdict.as_str()) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    Ok(verdict)
+}
+
+/// Make a bounty a calibration bounty with the verdict its artifact is known
+/// to have. Only before any engine submits, so every vote on it is settled
+/// against the same verdict.
+pub async fn mark_calibration_bounty(
+    State(state): State<BountyManagerState>,
+    headers: HeaderMap,
+    Path(bounty_id): Path<Uuid>,
+    Json(req): Json<MarkCalibrationRequest>,
+) -> Result<Json<ApiResponse<CalibrationBounty>>, StatusCode> {
+    let caller = require_admin(&headers)?;
+    let expected_verdict = expected_verdict(&req.expected_verdict)?;
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| db_error("Failed to start transaction", e))?;
+    // Submissions are saved while the bounty row is held
+    BountyModel::find_for_update(&mut tx, bounty_id)
+        .await
+        .map_err(|e| db_error("Failed to lock bounty", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let engines = SubmissionModel::engines_for_bounty(&mut *tx, bounty_id)
+        .await
+        .map_err(|e| db_error("Failed to load bounty submissions", e))?;
+    if !engines.is_empty() {
+        return Err(StatusCode::CONFLICT);
+    }
+    let calibration = CalibrationBounty::mark(&mut *tx, bounty_id, &expected_verdict, caller.user_id, None)
+        .await
+        .map_err(|e| db_error("Failed to mark calibration bounty", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| db_error("Failed to mark calibration bounty", e))?;
+
+    info!("Bounty {} marked as a calibration bounty by {}", bounty_id, caller.user_id);
+    Ok(Json(ApiResponse::success(calibration)))
+}
+
+/// Calibration bounties, newest first
+pub async fn list_calibration_bounties(
+    State(state): State<BountyManagerState>,
+    headers: HeaderMap,
+    Query(params): Query<CalibrationListParams>,
+) -> Result<Json<ApiResponse<Vec<CalibrationBounty>>>, StatusCode> {
+    require_admin(&headers)?;
+    let limit = params.limit.unwrap_or(50).clamp(1, 200) as i64;
+    let offset = params.offset.unwrap_or(0) as i64;
+
+    let bounties = CalibrationBounty::list(&state.db, limit, offset)
+        .await
+        .map_err(|e| db_error("Failed to load calibration bounties", e))?;
+
+    Ok(Json(ApiResponse::success(bounties)))
+}
+
+/// Add an artifact to the calibration pool
+pub async fn add_calibration_artifact(
+    State(state): State<BountyManagerState>,
+    headers: HeaderMap,
+    Json(req): Json<AddCalibrationArtifactRequest>,
+) -> Result<Json<ApiResponse<CalibrationArtifact>>, StatusCode> {
+    let caller = require_admin(&headers)?;
+    let expected_verdict = expected_verdict(&req.expected_verdict)?;
+    if req.title.trim().is_empty()
+        || req.description.trim().is_empty()
+        || (req.artifact_data.hash.is_none() && req.artifact_data.url.is_none())
+        || req.max_uses == Some(0)
+    {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let tags = validate_tags(&state.db, &req.tags).await?;
+    let max_uses = req.max_uses.map(i32::try_from).transpose().map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let now = Utc::now();
+    let artifact = CalibrationArtifact {
+        id: Uuid::new_v4(),
+        artifact_type: format!("{:?}", req.artifact_type),
+        artifact_data: serde_json::to_value(&req.artifact_data).map_err(|_| StatusCode::BAD_REQUEST)?,
+        expected_verdict,
+        title: req.title,
+        description: req.description,
+        tags,
+        max_uses,
+        times_used: 0,
+        last_used_at: None,
+        active: true,
+        added_by: caller.user_id,
+        created_at: now,
+        updated_at: now,
+    };
+    let artifact = CalibrationArtifact::create(&state.db, &artifact)
+        .await
+        .map_err(|e| db_error("Failed to add calibration artifact", e))?;
+
+    info!("Calibration artifact {} added to the pool by {}", artifact.id, caller.user_id);
+    Ok(Json(ApiResponse::success(artifact)))
+}
+
+/// The calibration pool, newest first
+pub async fn list_calibration_artifacts(
+    State(state): State<BountyManagerState>,
+    headers: HeaderMap,
+    Query(params): Query<CalibrationListParams>,
+) -> Result<Json<ApiResponse<Vec<CalibrationArtifact>>>, StatusCode> {
+    require_admin(&headers)?;
+    let limit = params.limit.unwrap_or(50).clamp(1, 200) as i64;
+    let offset = params.offset.unwrap_or(0) as i64;
+
+    let artifacts = CalibrationArtifact::list(&state.db, params.active, limit, offset)
+        .await
+        .map_err(|e| db_error("Failed to load calibration artifacts", e))?;
+
+    Ok(Json(ApiResponse::success(artifacts)))
+}
+
+/// Take an artifact out of the pool, e.g. once engines may have learnt it,
+/// or put it back. Bounties already injected on it are unaffected.
+pub async fn set_calibration_artifact_active(
+    State(state): State<BountyManagerState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(req): Json<SetCalibrationArtifactActiveRequest>,
+) -> Result<Json<ApiResponse<CalibrationArtifact>>, StatusCode> {
+    let caller = require_admin(&headers)?;
+    let artifact = CalibrationArtifact::set_active(&state.db, id, req.active)
+        .await
+        .map_err(|e| db_error("Failed to update calibration artifact", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    info!(
+        "Calibration artifact {} {} by {}",
+        id,
+        if req.active { "activated" } else { "retired" },
+        caller.user_id
+    );
+    Ok(Json(ApiResponse::success(artifact)))
+}
@@ -13,6 +13,8 @@ use chrono::{DateTime, Utc};
 use shared::types::ApiResponse;
 use super::bounty_crud::PaginationParams;
 use crate::handlers::bounty_crud::{BountyManagerState, ThreatVerdict};
+use crate::models::webhook::{WebhookDelivery, WebhookEvent};
+use tracing::warn;
 
 /// Represents a dispute raised against a submission or bounty outcome
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -237,7 +239,7 @@ pub struct DisputeStatsResponse {
 
 /// Create a new dispute
 pub async fn create_dispute(
-    State(_state): State<BountyManagerState>,
+    State(state): State<BountyManagerState>,
     Extension(disputer_id): Extension<String>, // From auth middleware
     Json(req): Json<CreateDisputeRequest>,
 ) -> Result<Json<ApiResponse<Dispute>>, StatusCode> {
@@ -298,7 +300,16 @@ pub async fn create_dispute(
 
     // TODO: Save to database
     // TODO: Create blockchain transaction for dispute stake
-    // TODO: Emit dispute created event
+    let event = serde_json::json!({
+        "dispute_id": dispute.id,
+        "submission_id": dispute.submission_id,
+        "disputer_id": dispute.disputer_id,
+        "dispute_type": dispute.dispute_type,
+        "severity": dispute.severity,
+    });
+    if let Err(e) = WebhookDelivery::enqueue(&state.db, dispute.bounty_id, WebhookEvent::DisputeOpened, &event).await {
+        warn!("Failed to queue dispute webhooks for bounty {}: {}", dispute.bounty_id, e);
+    }
     // TODO: Notify relevant parties
 
     Ok(Json(ApiResponse::success(dispute)))
@@ -0,0 +1,214 @@
+// backend/bounty-manager/src/handlers/embargo.rs
+
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use shared::permissions::{self, Scopes};
+use shared::types::{ApiResponse, OrganizationRole};
+use std::collections::HashSet;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::handlers::bounty_crud::BountyManagerState;
+use crate::models::embargo::{withheld_bounties, BountyCreator, VerdictEmbargo};
+
+/// Caller identity as forwarded by the API gateway
+#[derive(Debug, Clone, Copy)]
+pub struct Caller {
+    pub user_id: Uuid,
+    /// Holds the bounty:moderate scope
+    pub is_admin: bool,
+    /// The caller's organization and role in it (`x-org-id`/`x-org-role`)
+    pub organization: Option<(Uuid, OrganizationRole)>,
+}
+
+impl Caller {
+    /// `None` for anonymous requests
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+        let user_id = header("x-user-id").and_then(|v| Uuid::parse_str(v).ok())?;
+        let is_admin = Scopes::from_header_values(header(permissions::SCOPES_HEADER), header("x-user-role"))
+            .allows(permissions::BOUNTY_MODERATE);
+        let organization = header("x-org-id")
+            .and_then(|v| Uuid::parse_str(v).ok())
+            .zip(header("x-org-role").and_then(|v| v.parse().ok()));
+
+        Some(Self { user_id, is_admin, organization })
+    }
+
+    /// Whether the caller may create and manage bounties owned by
+    /// `organization_id`
+    pub fn manages_bounties_of(&self, organization_id: Option<Uuid>) -> bool {
+        match (organization_id, self.organization) {
+            (Some(owner), Some((member_of, role))) => owner == member_of && role.manages_bounties(),
+            _ => false,
+        }
+    }
+}
+
+/// The caller, if they hold bounty:moderate: the taxonomy and moderation
+/// are admin-only
+pub(crate) fn require_admin(headers: &HeaderMap) -> Result<Caller, StatusCode> {
+    let caller = Caller::from_headers(headers).ok_or(StatusCode::UNAUTHORIZED)?;
+    if caller.is_admin {
+        Ok(caller)
+    } else {
+        Err(StatusCode::FORBIDDEN)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PlaceEmbargoRequest {
+    /// Length of the embargo; the configured default when omitted
+    pub days: Option<u32>,
+    /// Keep the embargo until it is released manually
+    #[serde(default)]
+    pub until_released: bool,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EmbargoStatus {
+    #[serde(flatten)]
+    pub embargo: VerdictEmbargo,
+    pub active: bool,
+}
+
+impl From<VerdictEmbargo> for EmbargoStatus {
+    fn from(embargo: VerdictEmbargo) -> Self {
+        let active = embargo.is_active_at(Utc::now());
+        Self { embargo, active }
+    }
+}
+
+fn internal_error(context: &str, e: sqlx::Error) -> StatusCode {
+    error!("{}: {}", context, e);
+    StatusCode::INTERNAL_SERVER_ERROR
+}
+
+/// Bounties among `bounty_ids` whose verdicts must be hidden from `caller`.
+/// Callers should fail closed if this errors.
+pub async fn withheld_for(
+    state: &BountyManagerState,
+    caller: Option<Caller>,
+    bounty_ids: &[Uuid],
+) -> Result<HashSet<Uuid>, StatusCode> {
+    let embargoes = VerdictEmbargo::find_active(&state.db, bounty_ids)
+        .await
+        .map_err(|e| internal_error("Failed to load verdict embargoes", e))?;
+    if embargoes.is_empty() {
+        return Ok(HashSet::new());
+    }
+
+    let user = match caller {
+        Some(caller) => {
+            let organization_id = BountyCreator::organization_of(&state.db, caller.user_id)
+                .await
+                .map_err(|e| internal_error("Failed to resolve caller organization", e))?;
+            Some((caller.user_id, organization_id, caller.is_admin))
+        }
+        None => None,
+    };
+
+    Ok(withheld_bounties(&embargoes, user))
+}
+
+/// Admins and members of the creator's organization (or the creator
+/// themselves) may manage a bounty's embargo
+async fn authorize(
+    state: &BountyManagerState,
+    caller: Caller,
+    bounty_id: Uuid,
+) -> Result<BountyCreator, StatusCode> {
+    let creator = BountyCreator::of_bounty(&state.db, bounty_id)
+        .await
+        .map_err(|e| internal_error("Failed to resolve bounty creator", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    if caller.is_admin || creator.user_id == Some(caller.user_id) {
+        return Ok(creator);
+    }
+
+    let organization_id = BountyCreator::organization_of(&state.db, caller.user_id)
+        .await
+        .map_err(|e| internal_error("Failed to resolve caller organization", e))?;
+    if organization_id.is_some() && organization_id == creator.organization_id {
+        Ok(creator)
+    } else {
+        Err(StatusCode::FORBIDDEN)
+    }
+}
+
+/// Current embargo of a bounty
+pub async fn get_embargo(
+    State(state): State<BountyManagerState>,
+    Path(bounty_id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Json<ApiResponse<EmbargoStatus>>, StatusCode> {
+    let caller = Caller::from_headers(&headers).ok_or(StatusCode::UNAUTHORIZED)?;
+    authorize(&state, caller, bounty_id).await?;
+
+    let embargo = VerdictEmbargo::find(&state.db, bounty_id)
+        .await
+        .map_err(|e| internal_error("Failed to load verdict embargo", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(ApiResponse::success(embargo.into())))
+}
+
+/// Withhold a bounty's verdicts for a period or until released. Payouts to
+/// participants go ahead as usual.
+pub async fn place_embargo(
+    State(state): State<BountyManagerState>,
+    Path(bounty_id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(req): Json<PlaceEmbargoRequest>,
+) -> Result<Json<ApiResponse<EmbargoStatus>>, StatusCode> {
+    let caller = Caller::from_headers(&headers).ok_or(StatusCode::UNAUTHORIZED)?;
+    let creator = authorize(&state, caller, bounty_id).await?;
+
+    let embargo_until = if req.until_released {
+        None
+    } else {
+        let days = req.days.unwrap_or(state.embargo.default_days);
+        if days == 0 || days > state.embargo.max_days {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+        Some(Utc::now() + Duration::days(days as i64))
+    };
+
+    let reason = req.reason.as_deref().map(str::trim).filter(|r| !r.is_empty());
+    let embargo = VerdictEmbargo::place(&state.db, bounty_id, &creator, reason, caller.user_id, embargo_until)
+        .await
+        .map_err(|e| internal_error("Failed to place verdict embargo", e))?;
+
+    info!(
+        "Verdict embargo on bounty {} placed by {} until {}",
+        bounty_id,
+        caller.user_id,
+        embargo_until.map_or_else(|| "released".to_string(), |t| t.to_rfc3339())
+    );
+    Ok(Json(ApiResponse::success(embargo.into())))
+}
+
+/// Lift an embargo before it runs out
+pub async fn release_embargo(
+    State(state): State<BountyManagerState>,
+    Path(bounty_id): Path<Uuid>,
+    headers: HeaderMap,
+) -> Result<Json<ApiResponse<EmbargoStatus>>, StatusCode> {
+    let caller = Caller::from_headers(&headers).ok_or(StatusCode::UNAUTHORIZED)?;
+    authorize(&state, caller, bounty_id).await?;
+
+    let embargo = VerdictEmbargo::release(&state.db, bounty_id, caller.user_id)
+        .await
+        .map_err(|e| internal_error("Failed to release verdict embargo", e))?
+        .ok_or(StatusCode::CONFLICT)?;
+
+    info!("Verdict embargo on bounty {} released by {}", bounty_id, caller.user_id);
+    Ok(Json(ApiResponse::success(embargo.into())))
+}
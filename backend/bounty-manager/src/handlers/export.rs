@@ -0,0 +1,252 @@
+// backend/bounty-manager/src/handlers/export.rs
+
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
+    Extension,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use shared::types::ApiResponse;
+use sqlx::PgPool;
+use tokio::io::AsyncReadExt;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::handlers::bounty_crud::{db_error, BountyManagerState};
+use crate::models::export::{BountyExport, ExportCursor, ExportFormat, ExportRow};
+use crate::services::export::{download_url, preamble, render, verify_download};
+
+/// Range of an export when `from` is omitted
+const DEFAULT_RANGE_DAYS: i64 = 30;
+
+/// Bytes read per chunk of a download
+const DOWNLOAD_CHUNK: usize = 64 * 1024;
+
+#[derive(Debug, Deserialize)]
+pub struct ExportParams {
+    /// `csv` (default) or `ndjson`
+    pub format: Option<String>,
+    /// Bounties created from here (inclusive); 30 days before `to` by default
+    pub from: Option<DateTime<Utc>>,
+    /// Up to here (exclusive); now by default
+    pub to: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DownloadParams {
+    pub expires: i64,
+    pub signature: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportStatus {
+    #[serde(flatten)]
+    pub export: BountyExport,
+    /// Signed link, once the export is ready
+    pub download_url: Option<String>,
+}
+
+impl ExportStatus {
+    fn new(export: BountyExport, secret: &str) -> Self {
+        let download_url = match (export.status.as_str(), export.expires_at) {
+            (BountyExport::COMPLETED, Some(expires_at)) => Some(download_url(secret, export.id, expires_at.timestamp())),
+            _ => None,
+        };
+        Self { export, download_url }
+    }
+}
+
+/// Where paging through an export stands
+struct Pages {
+    db: PgPool,
+    creator: String,
+    format: ExportFormat,
+    range_start: DateTime<Utc>,
+    range_end: DateTime<Utc>,
+    page_size: i64,
+    cursor: Option<ExportCursor>,
+    started: bool,
+    done: bool,
+}
+
+/// Next chunk of an export: the preamble, then one page of rows at a time
+async fn next_chunk(mut pages: Pages) -> Option<(Result<String, std::io::Error>, Pages)> {
+    if pages.done {
+        return None;
+    }
+    if !pages.started {
+        pages.started = true;
+        return Some((Ok(preamble(pages.format).to_string()), pages));
+    }
+
+    match ExportRow::page(
+        &pages.db,
+        &pages.creator,
+        pages.range_start,
+        pages.range_end,
+        pages.cursor,
+        pages.page_size,
+    )
+    .await
+    {
+        Ok(rows) if rows.is_empty() => None,
+        Ok(rows) => {
+            let last = rows.last().expect("page is not empty");
+            pages.cursor = Some(ExportCursor {
+                created_at: last.created_at,
+                bounty_id: last.bounty_id,
+            });
+            Some((Ok(render(pages.format, &rows)), pages))
+        }
+        Err(e) => {
+            // Cut the response short rather than end it looking complete
+            error!("Failed to read export page for {}: {}", pages.creator, e);
+            pages.done = true;
+            Some((Err(std::io::Error::other(e)), pages))
+        }
+    }
+}
+
+fn attachment(format: ExportFormat, name: &str, body: Body) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}.{}\"", name, format.as_str()),
+            ),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+/// Export the caller's bounties created in a date range, with their
+/// submissions, verdicts and payouts. Small exports stream back directly;
+/// larger ones are generated in the background (202 with the export, whose
+/// status carries a signed download link once ready).
+pub async fn export_bounties(
+    State(state): State<BountyManagerState>,
+    Extension(user_address): Extension<String>, // From auth middleware
+    Query(params): Query<ExportParams>,
+) -> Result<Response, StatusCode> {
+    let format = match params.format.as_deref() {
+        Some(format) => ExportFormat::parse(format).ok_or(StatusCode::BAD_REQUEST)?,
+        None => ExportFormat::Csv,
+    };
+    let range_end = params.to.unwrap_or_else(Utc::now);
+    let range_start = params.from.unwrap_or(range_end - Duration::days(DEFAULT_RANGE_DAYS));
+    if range_start >= range_end || range_end - range_start > Duration::days(state.exports.max_range_days) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let rows = ExportRow::count(&state.db, &user_address, range_start, range_end)
+        .await
+        .map_err(|e| db_error("Failed to size export", e))?;
+    if rows > state.exports.inline_max_rows {
+        let export = BountyExport::create(&state.db, &user_address, format, range_start, range_end)
+            .await
+            .map_err(|e| db_error("Failed to queue export", e))?;
+        info!("Queued export {} of {} rows for {}", export.id, rows, user_address);
+        let status = ExportStatus::new(export, &state.exports.signing_secret);
+        return Ok((StatusCode::ACCEPTED, Json(ApiResponse::success(status))).into_response());
+    }
+
+    let pages = Pages {
+        db: state.db.clone(),
+        creator: user_address,
+        format,
+        range_start,
+        range_end,
+        page_size: state.exports.page_size,
+        cursor: None,
+        started: false,
+        done: false,
+    };
+    let name = format!(
+        "bounties-{}-{}",
+        range_start.format("%Y%m%d"),
+        range_end.format("%Y%m%d")
+    );
+
+    Ok(attachment(format, &name, Body::from_stream(futures::stream::unfold(pages, next_chunk))))
+}
+
+/// The caller's background exports, newest first
+pub async fn list_exports(
+    State(state): State<BountyManagerState>,
+    Extension(user_address): Extension<String>,
+) -> Result<Json<ApiResponse<Vec<ExportStatus>>>, StatusCode> {
+    let exports = BountyExport::list(&state.db, &user_address, 50)
+        .await
+        .map_err(|e| db_error("Failed to list exports", e))?
+        .into_iter()
+        .map(|export| ExportStatus::new(export, &state.exports.signing_secret))
+        .collect();
+
+    Ok(Json(ApiResponse::success(exports)))
+}
+
+/// One of the caller's background exports
+pub async fn get_export(
+    State(state): State<BountyManagerState>,
+    Extension(user_address): Extension<String>,
+    Path(export_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<ExportStatus>>, StatusCode> {
+    let export = BountyExport::find(&state.db, export_id)
+        .await
+        .map_err(|e| db_error("Failed to load export", e))?
+        .filter(|export| export.creator == user_address)
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(ApiResponse::success(ExportStatus::new(export, &state.exports.signing_secret))))
+}
+
+/// Download a finished export through its signed link
+pub async fn download_export(
+    State(state): State<BountyManagerState>,
+    Path(export_id): Path<Uuid>,
+    Query(params): Query<DownloadParams>,
+) -> Result<Response, StatusCode> {
+    if !verify_download(
+        &state.exports.signing_secret,
+        export_id,
+        params.expires,
+        &params.signature,
+        Utc::now().timestamp(),
+    ) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let export = BountyExport::find(&state.db, export_id)
+        .await
+        .map_err(|e| db_error("Failed to load export", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let (BountyExport::COMPLETED, Some(path)) = (export.status.as_str(), export.file_path.as_deref()) else {
+        return Err(StatusCode::GONE);
+    };
+    let format = ExportFormat::parse(&export.format).ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+    let file = tokio::fs::File::open(path).await.map_err(|e| {
+        error!("Failed to open export file {}: {}", path, e);
+        StatusCode::GONE
+    })?;
+
+    // The file is dropped after a read error, ending the stream
+    let chunks = futures::stream::unfold(Some(file), |file| async move {
+        let mut file = file?;
+        let mut buffer = vec![0; DOWNLOAD_CHUNK];
+        match file.read(&mut buffer).await {
+            Ok(0) => None,
+            Ok(n) => {
+                buffer.truncate(n);
+                Some((Ok::<_, std::io::Error>(buffer), Some(file)))
+            }
+            Err(e) => Some((Err(e), None)),
+        }
+    });
+
+    Ok(attachment(format, &format!("bounties-{}", export_id), Body::from_stream(chunks)))
+}
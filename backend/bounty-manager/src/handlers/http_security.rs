@@ -0,0 +1,67 @@
+// backend/bounty-manager/src/handlers/http_security.rs
+//
+// axum 0.8 counterpart of the shared crate's CORS layer and security headers
+// middleware, built from the same shared policy and header values.
+
+use axum::{
+    extract::Request,
+    http::{header, HeaderName, HeaderValue, Method},
+    middleware::Next,
+    response::Response,
+};
+use shared::http_security::{self as policy, CorsPolicy};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+/// CORS layer for `CORS_ALLOWED_ORIGINS` or the environment's frontends
+pub fn cors_layer() -> CorsLayer {
+    let cors = CorsPolicy::from_env();
+    let layer = CorsLayer::new()
+        .allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::PATCH,
+            Method::DELETE,
+            Method::OPTIONS,
+        ])
+        .allow_headers(
+            policy::ALLOWED_HEADERS
+                .iter()
+                .map(|h| HeaderName::from_static(h))
+                .collect::<Vec<_>>(),
+        )
+        .expose_headers(
+            policy::EXPOSED_HEADERS
+                .iter()
+                .map(|h| HeaderName::from_static(h))
+                .collect::<Vec<_>>(),
+        )
+        .max_age(cors.max_age);
+
+    match cors.origins(policy::is_production()) {
+        // Browsers reject credentials on a wildcard origin
+        None => layer.allow_origin(AllowOrigin::any()),
+        Some(origins) => layer
+            .allow_origin(AllowOrigin::list(
+                origins.into_iter().filter_map(|o| o.parse::<HeaderValue>().ok()),
+            ))
+            .allow_credentials(cors.allow_credentials),
+    }
+}
+
+/// HSTS, nosniff, frame-ancestors and a CSP on HTML responses
+pub async fn security_headers_middleware(request: Request, next: Next) -> Response {
+    let mut response = next.run(request).await;
+
+    let headers = response.headers_mut();
+    let is_html = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/html"));
+    for (name, value) in policy::security_headers(is_html) {
+        if !headers.contains_key(name) {
+            headers.insert(name, HeaderValue::from_static(value));
+        }
+    }
+    response
+}
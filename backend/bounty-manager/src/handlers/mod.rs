@@ -15,22 +15,27 @@ pub use bounty_crud::{
     
     // Request/Response DTOs
     CreateBountyRequest,
+    FundBountyRequest,
     UpdateBountyRequest,
     BountyFilters,
     BountyListResponse,
     BountyStatsResponse,
     CurrencyStats,
+    BountyStatsQuery,
     SubmissionRequest,
     SubmissionResponse,
     AnalysisData,
     
     // Handler functions
     create_bounty,
+    fund_bounty,
     get_bounty,
     list_bounties,
     update_bounty,
     cancel_bounty,
     get_bounty_stats,
+    list_creator_stats,
+    get_creator_stats,
     submit_to_bounty,
 };
 // Additional essential handlers
@@ -39,6 +44,18 @@ pub mod payout;
 pub mod reputation_handler;
 pub mod dispute;
 pub mod validation;
+pub mod archive;
+pub mod admin_logging;
+pub mod service_auth;
+pub mod http_security;
+pub mod embargo;
+pub mod tags;
+pub mod standing;
+pub mod webhooks;
+pub mod participants;
+pub mod moderation;
+pub mod export;
+pub mod calibration;
 
 // Re-export from additional handlers
 pub use submission::{
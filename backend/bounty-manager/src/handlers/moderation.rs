@@ -0,0 +1,358 @@
+// backend/bounty-manager/src/handlers/moderation.rs
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use shared::types::ApiResponse;
+use sqlx::PgPool;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::handlers::bounty_crud::{db_error, payment_error, BountyManagerState, BountyStatus};
+use crate::handlers::embargo::{require_admin, Caller};
+use crate::models::bounty::BountyModel;
+use crate::models::moderation::{BlockedArtifact, BountyReport, ModerationAction, ModerationCase, ReportCategory};
+use crate::services::moderation::{ContentScreen, FLAG_TAKEN_DOWN_ARTIFACT};
+use crate::services::payment::{PaymentClientError, RefundReason};
+
+/// Longest report details or moderator reason accepted
+const MAX_NOTE_LEN: usize = 2000;
+
+/// Statuses a bounty can be held or taken down from
+const HOLDABLE: [&str; 3] = ["PendingFunding", "Active", "InProgress"];
+
+#[derive(Debug, Deserialize)]
+pub struct ReportBountyRequest {
+    pub category: ReportCategory,
+    pub details: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ModerationQueueParams {
+    /// `pending` or `held`; both when omitted
+    pub status: Option<String>,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ModerationDecisionRequest {
+    /// Required for takedowns
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModerationCaseDetail {
+    #[serde(flatten)]
+    pub case: ModerationCase,
+    pub reports: Vec<BountyReport>,
+    pub actions: Vec<ModerationAction>,
+}
+
+/// Trimmed note, `None` if blank; too long a note is a bad request
+fn note(value: Option<&str>) -> Result<Option<&str>, StatusCode> {
+    match value.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(v) if v.len() > MAX_NOTE_LEN => Err(StatusCode::BAD_REQUEST),
+        v => Ok(v),
+    }
+}
+
+/// Heuristics a new bounty matches. Fails closed: if taken-down artifacts
+/// cannot be checked, the bounty is held as if it matched.
+pub(crate) async fn screen(db: &PgPool, content: &ContentScreen, bounty: &BountyModel) -> Vec<&'static str> {
+    let mut flags = content.screen(bounty);
+    if let Some(hash) = bounty.artifact_hash.as_deref() {
+        match BlockedArtifact::is_blocked(db, hash).await {
+            Ok(false) => {}
+            Ok(true) => flags.push(FLAG_TAKEN_DOWN_ARTIFACT),
+            Err(e) => {
+                error!("Failed to check blocked artifacts for bounty {}: {}", bounty.id, e);
+                flags.push(FLAG_TAKEN_DOWN_ARTIFACT);
+            }
+        }
+    }
+    flags
+}
+
+/// Put a bounty on hold for review: it moves to `UnderReview`, so engines
+/// can no longer join or submit, until a moderator decides. `None` if the
+/// bounty is gone or not in a holdable status.
+pub(crate) async fn hold(
+    db: &PgPool,
+    bounty_id: Uuid,
+    flags: &[&str],
+    reason: Option<&str>,
+    moderator_id: Option<Uuid>,
+) -> Result<Option<ModerationCase>, sqlx::Error> {
+    let mut tx = db.begin().await?;
+    let Some(bounty) = BountyModel::find_for_update(&mut tx, bounty_id).await? else {
+        return Ok(None);
+    };
+    if !HOLDABLE.contains(&bounty.status.as_str()) {
+        return Ok(None);
+    }
+
+    let under_review = BountyStatus::UnderReview.as_str();
+    BountyModel::transition(&mut *tx, bounty_id, &bounty.status, under_review).await?;
+    let case = ModerationCase::hold(&mut *tx, bounty_id, &bounty.status, flags).await?;
+    ModerationAction::record(
+        &mut *tx,
+        bounty_id,
+        ModerationAction::HOLD,
+        moderator_id,
+        reason,
+        &bounty.status,
+        under_review,
+    )
+    .await?;
+    tx.commit().await?;
+
+    warn!("Bounty {} held for moderation review ({:?})", bounty_id, reason);
+    Ok(Some(case))
+}
+
+/// Report a bounty for abuse. Enough reports, or a single severe one,
+/// hold it for review.
+pub async fn report_bounty(
+    State(state): State<BountyManagerState>,
+    headers: HeaderMap,
+    Path(bounty_id): Path<Uuid>,
+    Json(req): Json<ReportBountyRequest>,
+) -> Result<Json<ApiResponse<BountyReport>>, StatusCode> {
+    let caller = Caller::from_headers(&headers).ok_or(StatusCode::UNAUTHORIZED)?;
+    let details = note(req.details.as_deref())?;
+
+    BountyModel::find_by_id(&state.db, bounty_id)
+        .await
+        .map_err(|e| db_error("Failed to load bounty", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| db_error("Failed to start transaction", e))?;
+    let report = BountyReport::create(&mut *tx, bounty_id, caller.user_id, req.category, details)
+        .await
+        .map_err(|e| db_error("Failed to save report", e))?
+        .ok_or(StatusCode::CONFLICT)?;
+    let case = ModerationCase::add_report(&mut *tx, bounty_id, req.category.is_severe())
+        .await
+        .map_err(|e| db_error("Failed to open moderation case", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| db_error("Failed to save report", e))?;
+    info!("Bounty {} reported ({}) by {}", bounty_id, req.category.as_str(), caller.user_id);
+
+    let severe_hold = req.category.is_severe() && state.moderation.hold_on_severe_report;
+    let threshold_hold = i64::from(case.report_count) >= state.moderation.report_hold_threshold;
+    if case.status == ModerationCase::PENDING && (severe_hold || threshold_hold) {
+        let reason = if severe_hold {
+            format!("{} report", req.category.as_str())
+        } else {
+            format!("{} reports", case.report_count)
+        };
+        // The report stands either way; the case is in the review queue
+        if let Err(e) = hold(&state.db, bounty_id, &[], Some(&reason), None).await {
+            error!("Failed to hold reported bounty {}: {}", bounty_id, e);
+        }
+    }
+
+    Ok(Json(ApiResponse::success(report)))
+}
+
+/// Open moderation cases, severe ones and then the oldest first
+pub async fn list_moderation_queue(
+    State(state): State<BountyManagerState>,
+    headers: HeaderMap,
+    Query(params): Query<ModerationQueueParams>,
+) -> Result<Json<ApiResponse<Vec<ModerationCase>>>, StatusCode> {
+    require_admin(&headers)?;
+    let status = params.status.as_deref();
+    if status.is_some_and(|s| s != ModerationCase::PENDING && s != ModerationCase::HELD) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let limit = params.limit.unwrap_or(50).clamp(1, 200) as i64;
+    let offset = params.offset.unwrap_or(0) as i64;
+
+    let cases = ModerationCase::queue(&state.db, status, limit, offset)
+        .await
+        .map_err(|e| db_error("Failed to load moderation queue", e))?;
+
+    Ok(Json(ApiResponse::success(cases)))
+}
+
+/// A bounty's moderation case with its reports and audit trail
+pub async fn get_moderation_case(
+    State(state): State<BountyManagerState>,
+    headers: HeaderMap,
+    Path(bounty_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<ModerationCaseDetail>>, StatusCode> {
+    require_admin(&headers)?;
+
+    let case = ModerationCase::find(&state.db, bounty_id)
+        .await
+        .map_err(|e| db_error("Failed to load moderation case", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let reports = BountyReport::list(&state.db, bounty_id)
+        .await
+        .map_err(|e| db_error("Failed to load reports", e))?;
+    let actions = ModerationAction::list(&state.db, bounty_id)
+        .await
+        .map_err(|e| db_error("Failed to load moderation actions", e))?;
+
+    Ok(Json(ApiResponse::success(ModerationCaseDetail { case, reports, actions })))
+}
+
+/// Hold a bounty while it is investigated
+pub async fn hold_bounty(
+    State(state): State<BountyManagerState>,
+    headers: HeaderMap,
+    Path(bounty_id): Path<Uuid>,
+    Json(req): Json<ModerationDecisionRequest>,
+) -> Result<Json<ApiResponse<ModerationCase>>, StatusCode> {
+    let caller = require_admin(&headers)?;
+    let reason = note(req.reason.as_deref())?;
+
+    BountyModel::find_by_id(&state.db, bounty_id)
+        .await
+        .map_err(|e| db_error("Failed to load bounty", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let case = hold(&state.db, bounty_id, &[], reason, Some(caller.user_id))
+        .await
+        .map_err(|e| db_error("Failed to hold bounty", e))?
+        .ok_or(StatusCode::CONFLICT)?;
+
+    Ok(Json(ApiResponse::success(case)))
+}
+
+/// Clear a bounty: close its case and, if held, restore the status it was
+/// held from
+pub async fn approve_bounty(
+    State(state): State<BountyManagerState>,
+    headers: HeaderMap,
+    Path(bounty_id): Path<Uuid>,
+    Json(req): Json<ModerationDecisionRequest>,
+) -> Result<Json<ApiResponse<ModerationCase>>, StatusCode> {
+    let caller = require_admin(&headers)?;
+    let reason = note(req.reason.as_deref())?;
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| db_error("Failed to start transaction", e))?;
+    let bounty = BountyModel::find_for_update(&mut tx, bounty_id)
+        .await
+        .map_err(|e| db_error("Failed to lock bounty", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let case = ModerationCase::find(&mut *tx, bounty_id)
+        .await
+        .map_err(|e| db_error("Failed to load moderation case", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if case.status != ModerationCase::PENDING && case.status != ModerationCase::HELD {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    let mut new_status = bounty.status.clone();
+    if case.status == ModerationCase::HELD {
+        new_status = case
+            .held_from_status
+            .clone()
+            .unwrap_or_else(|| BountyStatus::Active.as_str().to_string());
+        let restored = BountyModel::transition(&mut *tx, bounty_id, BountyStatus::UnderReview.as_str(), &new_status)
+            .await
+            .map_err(|e| db_error("Failed to restore bounty", e))?;
+        if !restored {
+            return Err(StatusCode::CONFLICT);
+        }
+    }
+
+    let case = ModerationCase::decide(&mut *tx, bounty_id, ModerationCase::APPROVED, caller.user_id)
+        .await
+        .map_err(|e| db_error("Failed to save moderation decision", e))?;
+    ModerationAction::record(
+        &mut *tx,
+        bounty_id,
+        ModerationAction::APPROVE,
+        Some(caller.user_id),
+        reason,
+        &bounty.status,
+        &new_status,
+    )
+    .await
+    .map_err(|e| db_error("Failed to save moderation action", e))?;
+    tx.commit()
+        .await
+        .map_err(|e| db_error("Failed to save moderation decision", e))?;
+    info!("Bounty {} approved by moderator {}", bounty_id, caller.user_id);
+
+    Ok(Json(ApiResponse::success(case)))
+}
+
+/// Take a bounty down: it is cancelled, its reward refunded to the creator
+/// and its artifact blocked from new bounties
+pub async fn takedown_bounty(
+    State(state): State<BountyManagerState>,
+    headers: HeaderMap,
+    Path(bounty_id): Path<Uuid>,
+    Json(req): Json<ModerationDecisionRequest>,
+) -> Result<Json<ApiResponse<ModerationCase>>, StatusCode> {
+    let caller = require_admin(&headers)?;
+    let reason = note(req.reason.as_deref())?.ok_or(StatusCode::BAD_REQUEST)?;
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| db_error("Failed to start transaction", e))?;
+    let bounty = BountyModel::find_for_update(&mut tx, bounty_id)
+        .await
+        .map_err(|e| db_error("Failed to lock bounty", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if bounty.status != BountyStatus::UnderReview.as_str() && !HOLDABLE.contains(&bounty.status.as_str()) {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    let cancelled = BountyStatus::Cancelled.as_str();
+    BountyModel::transition(&mut *tx, bounty_id, &bounty.status, cancelled)
+        .await
+        .map_err(|e| db_error("Failed to cancel bounty", e))?;
+    let case = ModerationCase::decide(&mut *tx, bounty_id, ModerationCase::TAKEN_DOWN, caller.user_id)
+        .await
+        .map_err(|e| db_error("Failed to save moderation decision", e))?;
+    ModerationAction::record(
+        &mut *tx,
+        bounty_id,
+        ModerationAction::TAKEDOWN,
+        Some(caller.user_id),
+        Some(reason),
+        &bounty.status,
+        cancelled,
+    )
+    .await
+    .map_err(|e| db_error("Failed to save moderation action", e))?;
+    if let Some(hash) = bounty.artifact_hash.as_deref() {
+        BlockedArtifact::block(&mut *tx, hash, bounty_id, caller.user_id)
+            .await
+            .map_err(|e| db_error("Failed to block artifact", e))?;
+    }
+
+    // Refund before committing so an unavailable payment-service leaves the
+    // bounty as it was; refunding again is harmless
+    match state.payments.refund(bounty_id, RefundReason::Cancelled).await {
+        Ok(escrow) => info!("Escrow for taken-down bounty {} is {}", bounty_id, escrow.status),
+        // Bounties created before escrow have nothing to refund
+        Err(PaymentClientError::Rejected { status: 404, .. }) => {}
+        Err(e) => return Err(payment_error("Failed to refund bounty escrow", e)),
+    }
+    tx.commit()
+        .await
+        .map_err(|e| db_error("Failed to save moderation decision", e))?;
+    warn!("Bounty {} taken down by moderator {}: {}", bounty_id, caller.user_id, reason);
+
+    Ok(Json(ApiResponse::success(case)))
+}
@@ -0,0 +1,198 @@
+// backend/bounty-manager/src/handlers/participants.rs
+
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::Json,
+    Extension,
+};
+use serde::{Deserialize, Serialize};
+use shared::types::ApiResponse;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::handlers::bounty_crud::{db_error, is_address, payment_error, BountyManagerState};
+use crate::handlers::embargo::Caller;
+use crate::handlers::submission::{accepts_submissions, intake_error};
+use crate::models::archive::FINALIZED_STATUSES;
+use crate::models::bounty::BountyModel;
+use crate::models::participant::{BountyParticipant, ParticipantEntry};
+
+#[derive(Debug, Deserialize)]
+pub struct JoinBountyRequest {
+    /// Wallet the stake is locked from
+    pub wallet_address: String,
+    /// In wei; at least the bounty's minimum stake
+    pub stake_amount: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ParticipantListResponse {
+    pub participants: Vec<ParticipantEntry>,
+    /// Verdicts are shown to the creator once the bounty is finalized, so
+    /// engines cannot follow each other's verdicts while it is open
+    pub verdicts_visible: bool,
+}
+
+fn engine_of(headers: &HeaderMap) -> Result<Uuid, StatusCode> {
+    Caller::from_headers(headers)
+        .map(|caller| caller.user_id)
+        .ok_or(StatusCode::UNAUTHORIZED)
+}
+
+/// List a bounty's participants with their stake and submission status
+pub async fn list_participants(
+    State(state): State<BountyManagerState>,
+    Path(bounty_id): Path<Uuid>,
+    Extension(user_address): Extension<String>, // From auth middleware
+) -> Result<Json<ApiResponse<ParticipantListResponse>>, StatusCode> {
+    let bounty = BountyModel::find_by_id(&state.db, bounty_id)
+        .await
+        .map_err(|e| db_error("Failed to load bounty", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let verdicts_visible =
+        bounty.creator == user_address && FINALIZED_STATUSES.contains(&bounty.status.as_str());
+    let participants = BountyParticipant::list(&state.db, bounty_id)
+        .await
+        .map_err(|e| db_error("Failed to list bounty participants", e))?
+        .into_iter()
+        .map(|entry| if verdicts_visible { entry } else { entry.without_verdict() })
+        .collect();
+
+    Ok(Json(ApiResponse::success(ParticipantListResponse {
+        participants,
+        verdicts_visible,
+    })))
+}
+
+/// Join a bounty ahead of submitting. The engine must meet the bounty's
+/// stake and reputation requirements and fit within its participant limit;
+/// its stake is locked in the payment-service.
+pub async fn join_bounty(
+    State(state): State<BountyManagerState>,
+    headers: HeaderMap,
+    Path(bounty_id): Path<Uuid>,
+    Json(req): Json<JoinBountyRequest>,
+) -> Result<Json<ApiResponse<BountyParticipant>>, StatusCode> {
+    let engine = engine_of(&headers)?;
+    let engine_id = engine.to_string();
+
+    if !is_address(&req.wallet_address) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let stake_amount = i64::try_from(req.stake_amount).map_err(|_| StatusCode::BAD_REQUEST)?;
+
+    let bounty = BountyModel::find_by_id(&state.db, bounty_id)
+        .await
+        .map_err(|e| db_error("Failed to load bounty", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if !accepts_submissions(&bounty) {
+        return Err(StatusCode::CONFLICT);
+    }
+    if stake_amount == 0 || stake_amount < bounty.min_stake {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let reputation_score = state
+        .intake
+        .engine_reputation(&engine_id, &[])
+        .await
+        .map_err(|e| intake_error("Failed to check engine reputation", e))?
+        .score;
+    if bounty.min_reputation.is_some_and(|min| reputation_score < min) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    // Lock the bounty row like a submission does, so joins and submissions
+    // cannot both take the last seat
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| db_error("Failed to start transaction", e))?;
+    let bounty = BountyModel::find_for_update(&mut tx, bounty_id)
+        .await
+        .map_err(|e| db_error("Failed to lock bounty", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if !accepts_submissions(&bounty) {
+        return Err(StatusCode::CONFLICT);
+    }
+    let seated = BountyParticipant::seated(&mut *tx, bounty_id)
+        .await
+        .map_err(|e| db_error("Failed to load bounty participants", e))?;
+    if seated.contains(&engine_id) {
+        return Err(StatusCode::CONFLICT);
+    }
+    if bounty
+        .max_participants
+        .is_some_and(|max| seated.len() >= max.max(0) as usize)
+    {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    // Locking again after a failed commit returns the same stake
+    let stake = state
+        .payments
+        .lock_stake(bounty_id, engine, &req.wallet_address, req.stake_amount)
+        .await
+        .map_err(|e| payment_error("Failed to lock stake", e))?;
+    let participant = BountyParticipant::join(&mut *tx, bounty_id, &engine_id, &req.wallet_address, stake_amount, stake.id)
+        .await
+        .map_err(|e| db_error("Failed to save participant", e))?
+        .ok_or(StatusCode::CONFLICT)?;
+    tx.commit()
+        .await
+        .map_err(|e| db_error("Failed to save participant", e))?;
+    info!("Engine {} joined bounty {} with stake {}", engine_id, bounty_id, stake.id);
+
+    Ok(Json(ApiResponse::success(participant)))
+}
+
+/// Withdraw from a bounty before submitting; the stake is released back to
+/// the engine
+pub async fn withdraw_from_bounty(
+    State(state): State<BountyManagerState>,
+    headers: HeaderMap,
+    Path(bounty_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<BountyParticipant>>, StatusCode> {
+    let engine_id = engine_of(&headers)?.to_string();
+
+    // Hold the bounty row so a submission cannot land mid-withdrawal
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| db_error("Failed to start transaction", e))?;
+    BountyModel::find_for_update(&mut tx, bounty_id)
+        .await
+        .map_err(|e| db_error("Failed to lock bounty", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let participant = BountyParticipant::find(&mut *tx, bounty_id, &engine_id)
+        .await
+        .map_err(|e| db_error("Failed to load participant", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if participant.status != BountyParticipant::JOINED {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    let participant = BountyParticipant::withdraw(&mut *tx, bounty_id, &engine_id)
+        .await
+        .map_err(|e| db_error("Failed to withdraw participant", e))?
+        .ok_or(StatusCode::CONFLICT)?;
+    // Release before committing so an unavailable payment-service leaves
+    // the engine seated; releasing again is harmless
+    if let Some(stake_id) = participant.stake_id {
+        state
+            .payments
+            .unlock_stake(stake_id)
+            .await
+            .map_err(|e| payment_error("Failed to release stake", e))?;
+    }
+    tx.commit()
+        .await
+        .map_err(|e| db_error("Failed to withdraw participant", e))?;
+    info!("Engine {} withdrew from bounty {}", engine_id, bounty_id);
+
+    Ok(Json(ApiResponse::success(participant)))
+}
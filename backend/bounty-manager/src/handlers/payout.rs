@@ -12,7 +12,7 @@ use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use shared::types::ApiResponse;
 use super::bounty_crud::PaginationParams;
-use crate::handlers::bounty_crud::{BountyManagerState, ThreatVerdict};
+use crate::handlers::bounty_crud::{payment_error, BountyManagerState, ThreatVerdict};
 use crate::handlers::submission::{Submission, SubmissionStatus};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -186,6 +186,13 @@ pub async fn process_bounty_completion(
         blockchain_transactions: vec![],
     };
 
+    // Rewards are paid from the bounty's escrow
+    state
+        .payments
+        .release(bounty_id)
+        .await
+        .map_err(|e| payment_error("Failed to release bounty escrow", e))?;
+
     // TODO: Save payout info to database
     // TODO: Start async payout processing
 
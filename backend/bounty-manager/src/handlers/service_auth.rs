@@ -0,0 +1,62 @@
+// backend/bounty-manager/src/handlers/service_auth.rs
+//
+// axum 0.8 counterpart of the shared crate's `verify_signature_middleware`,
+// checking gateway request signatures with the same shared verifier.
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use shared::request_signing::{
+    SignatureVerifier, CONTENT_SHA256_HEADER, MAX_SIGNED_BODY_BYTES, UNSIGNED_PAYLOAD,
+};
+use tracing::warn;
+
+fn rejected(status: StatusCode) -> Response {
+    (status, Json(serde_json::json!({ "error": "Invalid service signature" }))).into_response()
+}
+
+/// Reject requests that do not carry a valid service signature
+pub async fn verify_signature_middleware(
+    State(verifier): State<SignatureVerifier>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !verifier.requires_signature(request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    let (parts, body) = request.into_parts();
+    let path_and_query = parts
+        .uri
+        .path_and_query()
+        .map(|pq| pq.as_str())
+        .unwrap_or_else(|| parts.uri.path())
+        .to_string();
+    let header = |name: &str| parts.headers.get(name).and_then(|v| v.to_str().ok());
+
+    let (body, bytes) = if header(CONTENT_SHA256_HEADER) == Some(UNSIGNED_PAYLOAD) {
+        (body, None)
+    } else {
+        match axum::body::to_bytes(body, MAX_SIGNED_BODY_BYTES).await {
+            Ok(bytes) => (Body::from(bytes.clone()), Some(bytes)),
+            Err(_) => return rejected(StatusCode::PAYLOAD_TOO_LARGE),
+        }
+    };
+
+    if let Err(e) = verifier.verify(
+        header,
+        parts.method.as_str(),
+        &path_and_query,
+        bytes.as_deref(),
+        chrono::Utc::now().timestamp(),
+    ) {
+        warn!(method = %parts.method, path = %parts.uri.path(), "Rejected service request: {}", e);
+        return rejected(StatusCode::UNAUTHORIZED);
+    }
+
+    next.run(Request::from_parts(parts, body)).await
+}
@@ -0,0 +1,281 @@
+// backend/bounty-manager/src/handlers/standing.rs
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    Extension,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use shared::types::ApiResponse;
+use std::collections::HashMap;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::handlers::bounty_crud::{
+    db_error, validate_tags, validate_terms, ArtifactData, ArtifactType, Bounty, BountyManagerState, BountyStatus,
+};
+use crate::models::bounty::BountyModel;
+use crate::models::standing::{StandingBounty, StandingBountyChild, WatchRule};
+
+/// What makes a standing bounty open a child bounty
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StandingTrigger {
+    /// Re-open the same artifact every `interval_hours`, first at `start_at`
+    /// (default now)
+    Schedule {
+        interval_hours: u32,
+        artifact_type: ArtifactType,
+        artifact_data: ArtifactData,
+        start_at: Option<DateTime<Utc>>,
+    },
+    /// Open a child for each new artifact matching the rule
+    Watch { rule: WatchRule },
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateStandingBountyRequest {
+    pub title: String,
+    pub description: String,
+    /// Terms each child bounty is opened with
+    pub reward_amount: u64,
+    pub currency: String,
+    pub min_stake: u64,
+    pub max_participants: Option<u32>,
+    #[serde(default)]
+    pub min_reputation: Option<u32>,
+    pub deadline_hours: u32,
+    pub consensus_threshold: Option<f32>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub trigger: StandingTrigger,
+    /// Stop after this many children
+    pub max_children: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetStandingEnabledRequest {
+    pub enabled: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StandingBountyResponse {
+    #[serde(flatten)]
+    pub standing: StandingBounty,
+    pub children: Vec<StandingBountyChild>,
+}
+
+/// The artifact a bounty was opened on
+pub(crate) fn artifact_of(bounty: &BountyModel) -> Option<(ArtifactType, ArtifactData)> {
+    let artifact_type = serde_json::from_value(serde_json::Value::String(bounty.artifact_type.clone())).ok()?;
+    let artifact_data = ArtifactData {
+        hash: bounty.artifact_hash.clone(),
+        url: bounty.artifact_url.clone(),
+        file_name: bounty.file_name.clone(),
+        file_size: bounty.file_size.and_then(|size| u64::try_from(size).ok()),
+        mime_type: bounty.mime_type.clone(),
+        upload_path: bounty.upload_path.clone(),
+    };
+    Some((artifact_type, artifact_data))
+}
+
+/// Identifies a bounty's artifact, so a watch opens one child per artifact
+/// however many bounties or submissions it turns up in
+pub(crate) fn artifact_key(bounty: &BountyModel) -> String {
+    let key = match (&bounty.artifact_hash, &bounty.artifact_url) {
+        (Some(hash), _) => format!("hash:{}", hash.to_ascii_lowercase()),
+        (None, Some(url)) => format!("url:{}", url),
+        (None, None) => format!("bounty:{}", bounty.id),
+    };
+    key.chars().take(300).collect()
+}
+
+/// A child bounty of `standing` on the given artifact, awaiting funding
+pub(crate) fn child_bounty(
+    standing: &StandingBounty,
+    artifact_type: ArtifactType,
+    artifact_data: ArtifactData,
+    trigger_ref: &str,
+) -> Bounty {
+    let now = Utc::now();
+    let metadata = HashMap::from([
+        ("standing_bounty_id".to_string(), standing.id.to_string()),
+        ("standing_trigger".to_string(), trigger_ref.to_string()),
+    ]);
+
+    Bounty {
+        id: Uuid::new_v4(),
+        creator: standing.creator.clone(),
+        title: standing.title.clone(),
+        description: standing.description.clone(),
+        artifact_type,
+        artifact_data,
+        reward_amount: standing.reward_amount as u64,
+        currency: standing.currency.clone(),
+        min_stake: standing.min_stake as u64,
+        max_participants: standing.max_participants.map(|n| n as u32),
+        min_reputation: standing.min_reputation.map(|score| score as u32),
+        deadline: now + Duration::hours(standing.deadline_hours as i64),
+        status: BountyStatus::PendingFunding,
+        consensus_threshold: standing.consensus_threshold,
+        created_at: now,
+        updated_at: now,
+        submissions: Vec::new(),
+        metadata,
+        tags: standing.tags.clone(),
+        verdict_embargoed: false,
+        reveal_deadline: None,
+        consensus_algorithm: None,
+        organization_id: None,
+    }
+}
+
+/// Create a standing bounty. Its children are opened by the standing bounty
+/// worker and escrowed like any other bounty.
+pub async fn create_standing_bounty(
+    State(state): State<BountyManagerState>,
+    Extension(user_address): Extension<String>, // From auth middleware
+    Json(req): Json<CreateStandingBountyRequest>,
+) -> Result<Json<ApiResponse<StandingBounty>>, StatusCode> {
+    validate_terms(&req.title, &req.description, req.reward_amount, req.min_stake, &req.currency)?;
+    let tags = validate_tags(&state.db, &req.tags).await?;
+    if req.deadline_hours == 0 || req.max_children == Some(0) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let amount = |value: u64| i64::try_from(value).map_err(|_| StatusCode::BAD_REQUEST);
+    let count = |value: u32| i32::try_from(value).map_err(|_| StatusCode::BAD_REQUEST);
+    let now = Utc::now();
+    let mut standing = StandingBounty {
+        id: Uuid::new_v4(),
+        creator: user_address,
+        title: req.title,
+        description: req.description,
+        reward_amount: amount(req.reward_amount)?,
+        currency: req.currency,
+        min_stake: amount(req.min_stake)?,
+        max_participants: req.max_participants.map(count).transpose()?,
+        min_reputation: req.min_reputation.map(count).transpose()?,
+        deadline_hours: count(req.deadline_hours)?,
+        consensus_threshold: req.consensus_threshold.unwrap_or(0.75),
+        tags,
+        trigger_type: String::new(),
+        artifact_type: None,
+        artifact_data: None,
+        interval_hours: None,
+        next_run_at: None,
+        watch_kind: None,
+        watch_pattern: None,
+        watched_until: None,
+        max_children: req.max_children.map(count).transpose()?,
+        enabled: true,
+        created_at: now,
+        updated_at: now,
+    };
+
+    match req.trigger {
+        StandingTrigger::Schedule {
+            interval_hours,
+            artifact_type,
+            artifact_data,
+            start_at,
+        } => {
+            if interval_hours == 0 || (artifact_data.hash.is_none() && artifact_data.url.is_none()) {
+                return Err(StatusCode::BAD_REQUEST);
+            }
+            standing.trigger_type = StandingBounty::SCHEDULE.to_string();
+            standing.artifact_type = Some(format!("{:?}", artifact_type));
+            standing.artifact_data = serde_json::to_value(&artifact_data).ok();
+            standing.interval_hours = Some(count(interval_hours)?);
+            standing.next_run_at = Some(start_at.unwrap_or(now).max(now));
+        }
+        StandingTrigger::Watch { rule } => {
+            let rule = rule.normalized().map_err(|e| {
+                warn!("Rejected standing bounty watch: {}", e);
+                StatusCode::BAD_REQUEST
+            })?;
+            standing.trigger_type = StandingBounty::WATCH.to_string();
+            standing.watch_kind = Some(rule.kind.as_str().to_string());
+            standing.watch_pattern = Some(rule.pattern);
+            // Only artifacts seen from now on spawn children
+            standing.watched_until = Some(now);
+        }
+    }
+
+    let standing = StandingBounty::create(&state.db, &standing)
+        .await
+        .map_err(|e| db_error("Failed to save standing bounty", e))?;
+
+    info!(
+        "Created standing bounty {} ({}) for {}",
+        standing.id, standing.trigger_type, standing.creator
+    );
+    Ok(Json(ApiResponse::success(standing)))
+}
+
+/// The caller's standing bounties
+pub async fn list_standing_bounties(
+    State(state): State<BountyManagerState>,
+    Extension(user_address): Extension<String>,
+) -> Result<Json<ApiResponse<Vec<StandingBounty>>>, StatusCode> {
+    let standing = StandingBounty::list_by_creator(&state.db, &user_address)
+        .await
+        .map_err(|e| db_error("Failed to list standing bounties", e))?;
+
+    Ok(Json(ApiResponse::success(standing)))
+}
+
+/// A standing bounty of the caller's and the children it has opened
+pub async fn get_standing_bounty(
+    State(state): State<BountyManagerState>,
+    Path(id): Path<Uuid>,
+    Extension(user_address): Extension<String>,
+) -> Result<Json<ApiResponse<StandingBountyResponse>>, StatusCode> {
+    let standing = owned_standing_bounty(&state, id, &user_address).await?;
+    let children = StandingBounty::children(&state.db, id)
+        .await
+        .map_err(|e| db_error("Failed to list standing bounty children", e))?;
+
+    Ok(Json(ApiResponse::success(StandingBountyResponse { standing, children })))
+}
+
+/// Pause or resume a standing bounty. Children already opened are unaffected.
+pub async fn set_standing_bounty_enabled(
+    State(state): State<BountyManagerState>,
+    Path(id): Path<Uuid>,
+    Extension(user_address): Extension<String>,
+    Json(req): Json<SetStandingEnabledRequest>,
+) -> Result<Json<ApiResponse<StandingBounty>>, StatusCode> {
+    owned_standing_bounty(&state, id, &user_address).await?;
+
+    let standing = StandingBounty::set_enabled(&state.db, id, req.enabled)
+        .await
+        .map_err(|e| db_error("Failed to update standing bounty", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    info!(
+        "Standing bounty {} {} by {}",
+        id,
+        if req.enabled { "resumed" } else { "paused" },
+        user_address
+    );
+    Ok(Json(ApiResponse::success(standing)))
+}
+
+async fn owned_standing_bounty(
+    state: &BountyManagerState,
+    id: Uuid,
+    user_address: &str,
+) -> Result<StandingBounty, StatusCode> {
+    let standing = StandingBounty::find(&state.db, id)
+        .await
+        .map_err(|e| db_error("Failed to load standing bounty", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    if standing.creator != user_address {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(standing)
+}
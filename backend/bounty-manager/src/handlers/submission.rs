@@ -2,17 +2,30 @@
 
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::Json,
-    Extension,
 };
 use serde::{Deserialize, Serialize};
+use sqlx::{Postgres, Transaction};
 use std::collections::HashMap;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use shared::types::ApiResponse;
+use tracing::{error, info, warn};
 use super::bounty_crud::PaginationParams;
-use crate::handlers::bounty_crud::{BountyManagerState, ThreatVerdict};
+use crate::handlers::bounty_crud::{db_error, is_address, payment_error, BountyManagerState, BountyStatus, ThreatVerdict};
+use crate::handlers::embargo::Caller;
+use crate::models::bounty::BountyModel;
+use crate::models::calibration::CalibrationBounty;
+use crate::models::commitment::{is_commitment, SubmissionCommitment};
+use crate::models::participant::BountyParticipant;
+use crate::models::submission::SubmissionModel;
+use crate::models::tag::BountyTag;
+use crate::models::webhook::{WebhookDelivery, WebhookEvent};
+use crate::services::intake::{
+    EngineReputation, ForwardedArtifact, ForwardedIndicators, ForwardedSubmission, IntakeClientError,
+};
+use shared::messaging::{NexusEvent, SubmissionReceivedEvent};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Submission {
@@ -58,6 +71,67 @@ pub struct AnalysisDetails {
     pub metadata: HashMap<String, String>,
 }
 
+impl AnalysisDetails {
+    /// Malware and YARA families the analysis reported
+    pub fn families(&self) -> impl Iterator<Item = &str> {
+        let yara = self
+            .static_analysis
+            .iter()
+            .flat_map(|analysis| analysis.yara_matches.iter().map(|m| m.rule_family.as_str()));
+        self.malware_families.iter().map(String::as_str).chain(yara)
+    }
+
+    /// The analysis as plain text: families, indicators and metadata, in a
+    /// stable order so identical analyses give identical text
+    pub fn summary_text(&self) -> String {
+        let mut lines: Vec<String> = self.families().map(str::to_string).collect();
+        lines.extend(self.threat_indicators.iter().map(|indicator| {
+            format!(
+                "{} {} {}",
+                indicator.indicator_type,
+                indicator.value,
+                indicator.description.as_deref().unwrap_or_default()
+            )
+        }));
+        let mut metadata: Vec<_> = self.metadata.iter().collect();
+        metadata.sort();
+        lines.extend(metadata.into_iter().map(|(key, value)| format!("{}: {}", key, value)));
+        lines.join("\n")
+    }
+
+    /// The artifact's import hash, fuzzy hash and contacted domains, from
+    /// the metadata, the threat indicators and the network analysis
+    pub fn indicators(&self) -> ForwardedIndicators {
+        let reported = |kind: &str| {
+            self.metadata.get(kind).cloned().or_else(|| {
+                self.threat_indicators
+                    .iter()
+                    .find(|indicator| indicator.indicator_type.eq_ignore_ascii_case(kind))
+                    .map(|indicator| indicator.value.clone())
+            })
+        };
+        let mut domains: Vec<String> = self
+            .threat_indicators
+            .iter()
+            .filter(|indicator| matches!(indicator.indicator_type.to_lowercase().as_str(), "domain" | "url"))
+            .map(|indicator| indicator.value.clone())
+            .collect();
+        if let Some(network) = &self.network_analysis {
+            domains.extend(network.dns_requests.iter().cloned());
+            domains.extend(network.suspicious_domains.iter().cloned());
+            domains.extend(network.http_requests.iter().map(|request| request.url.clone()));
+        }
+        domains.sort();
+        domains.dedup();
+
+        ForwardedIndicators {
+            imphash: reported("imphash"),
+            ssdeep: reported("ssdeep"),
+            domains,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ThreatIndicator {
     pub indicator_type: String, // "hash", "ip", "domain", "registry_key", etc.
@@ -144,6 +218,66 @@ pub struct SubmitAnalysisRequest {
     pub stake_amount: u64,
     pub analysis_details: AnalysisDetails,
     pub engine_type: EngineType,
+    /// The on-chain stake transaction, if already sent
+    #[serde(default)]
+    pub transaction_hash: Option<String>,
+    /// Wallet the stake is locked from; not needed once the engine joined
+    #[serde(default)]
+    pub wallet_address: Option<String>,
+    /// How the engine expects the other engines to vote, for bounties
+    /// aggregated by Bayesian truth serum
+    #[serde(default)]
+    pub prediction: Option<VerdictPrediction>,
+}
+
+/// An engine's prediction of the share of engines giving each verdict, 0.0
+/// to 1.0 each
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VerdictPrediction {
+    #[serde(default)]
+    pub malicious: f64,
+    #[serde(default)]
+    pub benign: f64,
+    #[serde(default)]
+    pub suspicious: f64,
+    #[serde(default)]
+    pub unknown: f64,
+}
+
+impl VerdictPrediction {
+    /// Whether every share is between 0 and 1 and some verdict is expected
+    fn is_valid(&self) -> bool {
+        let shares = [self.malicious, self.benign, self.suspicious, self.unknown];
+        shares.iter().all(|share| (0.0..=1.0).contains(share)) && shares.iter().sum::<f64>() > 0.0
+    }
+}
+
+/// A sealed verdict on a commit-reveal bounty
+#[derive(Debug, Deserialize)]
+pub struct CommitVerdictRequest {
+    /// Hex SHA-256 of "<verdict>:<nonce>", verdict in lowercase, e.g.
+    /// "malicious:<nonce>"; the nonce should be at least 32 random characters
+    pub commitment: String,
+    pub confidence: f32,
+    pub stake_amount: u64,
+    pub engine_type: EngineType,
+    /// The on-chain stake transaction, if already sent
+    #[serde(default)]
+    pub transaction_hash: Option<String>,
+    /// Wallet the stake is locked from; not needed once the engine joined
+    #[serde(default)]
+    pub wallet_address: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RevealVerdictRequest {
+    pub verdict: ThreatVerdict,
+    pub nonce: String,
+    pub analysis_details: AnalysisDetails,
+    /// How the engine expects the other engines to vote, for bounties
+    /// aggregated by Bayesian truth serum
+    #[serde(default)]
+    pub prediction: Option<VerdictPrediction>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -173,13 +307,220 @@ pub struct ConsensusData {
     pub weighted_score: f32, // Weighted by reputation and stake
 }
 
+pub(crate) fn intake_error(context: &str, e: IntakeClientError) -> StatusCode {
+    match e {
+        IntakeClientError::Rejected { service, status, message } => {
+            warn!("{}: {} returned {}: {}", context, service, status, message);
+            StatusCode::BAD_GATEWAY
+        }
+        IntakeClientError::Unavailable(service, message) => {
+            error!("{}: {} unavailable: {}", context, service, message);
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+    }
+}
+
+/// Funded bounties take submissions until their deadline
+pub(crate) fn accepts_submissions(bounty: &BountyModel) -> bool {
+    let open = bounty.status == BountyStatus::Active.as_str()
+        || bounty.status == BountyStatus::InProgress.as_str();
+    open && bounty.deadline > Utc::now()
+}
+
+fn to_model(submission: &Submission) -> Result<SubmissionModel, StatusCode> {
+    Ok(SubmissionModel {
+        id: submission.id,
+        bounty_id: submission.bounty_id,
+        engine_id: submission.engine_id.clone(),
+        engine_type: format!("{:?}", submission.engine_type),
+        verdict: format!("{:?}", submission.verdict),
+        confidence: submission.confidence,
+        stake_amount: i64::try_from(submission.stake_amount).map_err(|_| StatusCode::BAD_REQUEST)?,
+        analysis_details: serde_json::to_value(&submission.analysis_details)
+            .map_err(|_| StatusCode::BAD_REQUEST)?,
+        status: format!("{:?}", submission.status),
+        transaction_hash: submission.transaction_hash.clone(),
+        submitted_at: submission.submitted_at,
+        processed_at: submission.processed_at,
+        accuracy_score: submission.accuracy_score,
+    })
+}
+
 // Handler implementations
+
+/// An engine cleared to submit to a bounty. The bounty row stays locked in
+/// `tx` until the submission is saved, so concurrent submissions cannot both
+/// take the last seat.
+struct Admission {
+    tx: Transaction<'static, Postgres>,
+    bounty: BountyModel,
+    reputation: EngineReputation,
+    /// Stake locked for this submission, to be released if it is not saved;
+    /// `None` for engines that locked theirs on joining
+    locked_stake: Option<Uuid>,
+    /// Wallet the engine's stake is locked from, if known
+    wallet_address: Option<String>,
+}
+
+/// Check that the engine may submit: the bounty is open and takes this kind
+/// of submission (a verdict, or a commitment on commit-reveal bounties), the
+/// stake covers its minimum, the engine meets its reputation requirement,
+/// has not submitted yet and fits within its participant limit. Engines that
+/// did not join have their stake locked from `wallet_address`; those that
+/// joined are held to the stake they locked then.
+async fn admit(
+    state: &BountyManagerState,
+    bounty_id: Uuid,
+    engine: Uuid,
+    wallet_address: Option<&str>,
+    stake_amount: i64,
+    commit_reveal: bool,
+) -> Result<Admission, StatusCode> {
+    let engine_id = engine.to_string();
+    if wallet_address.is_some_and(|wallet| !is_address(wallet)) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let bounty = BountyModel::find_by_id(&state.db, bounty_id)
+        .await
+        .map_err(|e| db_error("Failed to load bounty", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if bounty.reveal_deadline.is_some() != commit_reveal {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    if !accepts_submissions(&bounty) {
+        return Err(StatusCode::CONFLICT);
+    }
+    if stake_amount < bounty.min_stake {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let reputation = engine_reputation(state, bounty_id, &engine_id).await?;
+    if bounty.min_reputation.is_some_and(|min| reputation.score < min) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| db_error("Failed to start transaction", e))?;
+    let bounty = BountyModel::find_for_update(&mut tx, bounty_id)
+        .await
+        .map_err(|e| db_error("Failed to lock bounty", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if !accepts_submissions(&bounty) {
+        return Err(StatusCode::CONFLICT);
+    }
+    let engines = SubmissionModel::engines_for_bounty(&mut *tx, bounty_id)
+        .await
+        .map_err(|e| db_error("Failed to load bounty submissions", e))?;
+    if engines.iter().any(|engine| *engine == engine_id) {
+        return Err(StatusCode::CONFLICT);
+    }
+    // Engines that joined already hold their seat and stake; those that
+    // committed hold them as submitted
+    let participant = BountyParticipant::find(&mut *tx, bounty_id, &engine_id)
+        .await
+        .map_err(|e| db_error("Failed to load participant", e))?;
+    let mut wallet_address = wallet_address.map(str::to_string);
+    let locked_stake = match participant {
+        Some(participant) if participant.status == BountyParticipant::JOINED => {
+            if stake_amount > participant.stake_amount {
+                return Err(StatusCode::BAD_REQUEST);
+            }
+            wallet_address = participant.wallet_address.or(wallet_address);
+            None
+        }
+        Some(participant) if participant.status == BountyParticipant::SUBMITTED => {
+            return Err(StatusCode::CONFLICT);
+        }
+        _ => {
+            let seated = BountyParticipant::seated(&mut *tx, bounty_id)
+                .await
+                .map_err(|e| db_error("Failed to load bounty participants", e))?;
+            if bounty
+                .max_participants
+                .is_some_and(|max| seated.len() >= max.max(0) as usize)
+            {
+                return Err(StatusCode::CONFLICT);
+            }
+
+            // Locked while the bounty row is held, like a join; locking
+            // again after a failed submission returns the same stake
+            let wallet_address = wallet_address.as_deref().ok_or(StatusCode::BAD_REQUEST)?;
+            let stake = state
+                .payments
+                .lock_stake(bounty_id, engine, wallet_address, stake_amount as u64)
+                .await
+                .map_err(|e| payment_error("Failed to lock stake", e))?;
+            Some(stake.id)
+        }
+    };
+
+    Ok(Admission {
+        tx,
+        bounty,
+        reputation,
+        locked_stake,
+        wallet_address,
+    })
+}
+
+/// The engine's reputation for a bounty, in the threat categories of its
+/// tags as well as overall
+async fn engine_reputation(
+    state: &BountyManagerState,
+    bounty_id: Uuid,
+    engine_id: &str,
+) -> Result<EngineReputation, StatusCode> {
+    let tags = BountyTag::of_bounty(&state.db, bounty_id)
+        .await
+        .map_err(|e| db_error("Failed to load bounty tags", e))?;
+    state
+        .intake
+        .engine_reputation(engine_id, &tags)
+        .await
+        .map_err(|e| intake_error("Failed to check engine reputation", e))
+}
+
+/// Caller's IP as forwarded by the gateway
+fn client_ip(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get("X-Forwarded-For")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(',').next())
+        .or_else(|| headers.get("X-Real-IP").and_then(|v| v.to_str().ok()))
+        .map(|ip| ip.trim().to_string())
+        .filter(|ip| !ip.is_empty())
+}
+
+/// Release a stake locked for a submission that was not saved. If this
+/// fails too the stake stays locked, and the engine's next attempt reuses it.
+async fn release_stake(state: &BountyManagerState, stake_id: Option<Uuid>) {
+    let Some(stake_id) = stake_id else {
+        return;
+    };
+    if let Err(e) = state.payments.unlock_stake(stake_id).await {
+        error!("Failed to release stake {} of an unsaved submission: {}", stake_id, e);
+    }
+}
+
+/// Submit an engine's analysis to a bounty. The engine must stake at least
+/// the bounty's minimum, meet its reputation requirement and fit within its
+/// participant limit; the verdict is forwarded to the consensus-service.
+/// Commit-reveal bounties take commitments instead.
 pub async fn submit_analysis(
     State(state): State<BountyManagerState>,
-    Extension(engine_id): Extension<String>,
+    headers: HeaderMap,
     Path(bounty_id): Path<Uuid>,
     Json(req): Json<SubmitAnalysisRequest>,
 ) -> Result<Json<ApiResponse<Submission>>, StatusCode> {
+    let engine = Caller::from_headers(&headers)
+        .ok_or(StatusCode::UNAUTHORIZED)?
+        .user_id;
+    let engine_id = engine.to_string();
+
     // Validate request
     if req.confidence < 0.0 || req.confidence > 1.0 {
         return Err(StatusCode::BAD_REQUEST);
@@ -189,16 +530,12 @@ pub async fn submit_analysis(
         return Err(StatusCode::BAD_REQUEST);
     }
 
-    // TODO: Validate bounty exists and is active
-    // TODO: Check if engine already submitted to this bounty
-    // TODO: Verify minimum stake requirements
-    // TODO: Check engine reputation requirements
-
-    let submission_id = Uuid::new_v4();
-    let now = Utc::now();
+    if req.prediction.as_ref().is_some_and(|prediction| !prediction.is_valid()) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
 
     let submission = Submission {
-        id: submission_id,
+        id: Uuid::new_v4(),
         bounty_id,
         engine_id: engine_id.clone(),
         engine_type: req.engine_type,
@@ -207,16 +544,312 @@ pub async fn submit_analysis(
         stake_amount: req.stake_amount,
         analysis_details: req.analysis_details,
         status: SubmissionStatus::Pending,
-        transaction_hash: None, // Will be updated after blockchain confirmation
-        submitted_at: now,
+        transaction_hash: req.transaction_hash,
+        submitted_at: Utc::now(),
         processed_at: None,
         accuracy_score: None,
     };
+    let model = to_model(&submission)?;
+
+    let Admission {
+        mut tx,
+        bounty,
+        reputation,
+        locked_stake,
+        wallet_address,
+    } = admit(&state, bounty_id, engine, req.wallet_address.as_deref(), model.stake_amount, false).await?;
+    let source_ip = client_ip(&headers);
+
+    let saved = async {
+        save_submission(&mut tx, &model, wallet_address.as_deref(), locked_stake).await?;
+
+        // Forward before committing so a consensus-service outage rolls the
+        // submission back; the engine's retry replaces the vote if the
+        // commit itself fails
+        let calibration = CalibrationBounty::find(&mut *tx, bounty_id)
+            .await
+            .map_err(|e| db_error("Failed to load calibration bounty", e))?;
+        let forwarded = ForwardedSubmission {
+            engine_id: &engine_id,
+            verdict: model.verdict.to_lowercase(),
+            confidence: model.confidence,
+            reputation_score: reputation.score,
+            specialization_score: reputation.specialization_score,
+            probation: reputation.probation,
+            ground_truth: calibration.as_ref().map(|c| c.expected_verdict.as_str()),
+            stake_amount: model.stake_amount,
+            prediction: req.prediction.as_ref(),
+            nonce: None,
+            algorithm: bounty.consensus_algorithm.as_deref(),
+            source_ip: source_ip.as_deref(),
+            wallet_address: wallet_address.as_deref(),
+            analysis_text: submission.analysis_details.summary_text(),
+            indicators: submission.analysis_details.indicators(),
+            artifact: ForwardedArtifact {
+                file_type: bounty.mime_type.as_deref(),
+                file_size: bounty.file_size,
+            },
+        };
+        state
+            .intake
+            .forward_submission(bounty_id, &forwarded)
+            .await
+            .map_err(|e| intake_error("Failed to forward submission to consensus", e))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| db_error("Failed to save submission", e))
+    }
+    .await;
+    if saved.is_err() {
+        release_stake(&state, locked_stake).await;
+    }
+    saved?;
+    info!("Engine {} submitted to bounty {}", engine_id, bounty_id);
+    announce_submission(&state, &submission).await;
+
+    Ok(Json(ApiResponse::success(submission)))
+}
 
-    // TODO: Create blockchain transaction for stake
-    // TODO: Save submission to database
-    // TODO: Emit real-time event
-    // TODO: Check if consensus threshold is reached
+/// Announce a saved submission so the consensus-service recalculates the
+/// bounty's provisional consensus. A missed announcement is harmless: the
+/// verdict was already forwarded and counts when the bounty closes.
+async fn announce_submission(state: &BountyManagerState, submission: &Submission) {
+    use shared::types::common::ThreatVerdict as Verdict;
+
+    let event = NexusEvent::SubmissionReceived(SubmissionReceivedEvent {
+        submission_id: submission.id,
+        bounty_id: submission.bounty_id,
+        engine_id: submission.engine_id.clone(),
+        submitter_id: Uuid::parse_str(&submission.engine_id).unwrap_or_default(),
+        verdict: match submission.verdict {
+            ThreatVerdict::Malicious => Verdict::Malicious,
+            ThreatVerdict::Benign => Verdict::Benign,
+            ThreatVerdict::Suspicious => Verdict::Suspicious,
+            ThreatVerdict::Unknown => Verdict::Unknown,
+        },
+        confidence_score: submission.confidence,
+        stake_amount: u128::from(submission.stake_amount),
+        submitted_at: submission.submitted_at,
+    });
+    if let Err(e) = state.events.publish(&event).await {
+        warn!("Failed to announce submission {} to bounty {}: {}", submission.id, submission.bounty_id, e);
+    }
+}
+
+/// Store a submission, seat its engine with the stake locked for it and
+/// queue its webhook event
+async fn save_submission(
+    tx: &mut Transaction<'static, Postgres>,
+    model: &SubmissionModel,
+    wallet_address: Option<&str>,
+    stake_id: Option<Uuid>,
+) -> Result<(), StatusCode> {
+    SubmissionModel::create(&mut **tx, model)
+        .await
+        .map_err(|e| db_error("Failed to save submission", e))?;
+    BountyParticipant::mark_submitted(&mut **tx, model.bounty_id, &model.engine_id, model.stake_amount, wallet_address, stake_id)
+        .await
+        .map_err(|e| db_error("Failed to save participant", e))?;
+    let event = serde_json::json!({
+        "submission_id": model.id,
+        "engine_id": model.engine_id,
+        "verdict": model.verdict,
+        "confidence": model.confidence,
+        "submitted_at": model.submitted_at,
+    });
+    WebhookDelivery::enqueue(&mut **tx, model.bounty_id, WebhookEvent::SubmissionReceived, &event)
+        .await
+        .map_err(|e| db_error("Failed to queue submission webhooks", e))?;
+
+    Ok(())
+}
+
+/// Commit to a verdict on a commit-reveal bounty. Only the commitment is
+/// stored until the deadline, so no engine can see another's verdict; the
+/// engine reveals it once the submission window closes.
+pub async fn commit_verdict(
+    State(state): State<BountyManagerState>,
+    headers: HeaderMap,
+    Path(bounty_id): Path<Uuid>,
+    Json(req): Json<CommitVerdictRequest>,
+) -> Result<Json<ApiResponse<SubmissionCommitment>>, StatusCode> {
+    let engine = Caller::from_headers(&headers)
+        .ok_or(StatusCode::UNAUTHORIZED)?
+        .user_id;
+    let engine_id = engine.to_string();
+
+    if !is_commitment(&req.commitment) || !(0.0..=1.0).contains(&req.confidence) || req.stake_amount == 0 {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let commitment = SubmissionCommitment {
+        bounty_id,
+        engine_id: engine_id.clone(),
+        commitment: req.commitment.to_lowercase(),
+        engine_type: format!("{:?}", req.engine_type),
+        confidence: req.confidence,
+        stake_amount: i64::try_from(req.stake_amount).map_err(|_| StatusCode::BAD_REQUEST)?,
+        transaction_hash: req.transaction_hash,
+        committed_at: Utc::now(),
+        submission_id: None,
+        revealed_at: None,
+    };
+
+    let wallet_address = req.wallet_address.as_deref();
+    let Admission {
+        mut tx,
+        bounty,
+        locked_stake,
+        ..
+    } = admit(&state, bounty_id, engine, wallet_address, commitment.stake_amount, true).await?;
+
+    let saved = async {
+        SubmissionCommitment::create(&mut *tx, &commitment)
+            .await
+            .map_err(|e| db_error("Failed to save commitment", e))?;
+        BountyParticipant::mark_submitted(
+            &mut *tx,
+            bounty_id,
+            &engine_id,
+            commitment.stake_amount,
+            wallet_address,
+            locked_stake,
+        )
+        .await
+        .map_err(|e| db_error("Failed to save participant", e))?;
+
+        // The consensus-service holds the commitment too and refuses any
+        // other verdict from the engine
+        state
+            .intake
+            .forward_commitment(bounty_id, &engine_id, &commitment.commitment, bounty.deadline)
+            .await
+            .map_err(|e| intake_error("Failed to forward commitment to consensus", e))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| db_error("Failed to save commitment", e))
+    }
+    .await;
+    if saved.is_err() {
+        release_stake(&state, locked_stake).await;
+    }
+    saved?;
+    info!("Engine {} committed to a verdict on bounty {}", engine_id, bounty_id);
+
+    Ok(Json(ApiResponse::success(commitment)))
+}
+
+/// Reveal a committed verdict once the submission window of a commit-reveal
+/// bounty has closed. The verdict and nonce must open the engine's
+/// commitment; the verdict then counts like any other submission.
+pub async fn reveal_verdict(
+    State(state): State<BountyManagerState>,
+    headers: HeaderMap,
+    Path(bounty_id): Path<Uuid>,
+    Json(req): Json<RevealVerdictRequest>,
+) -> Result<Json<ApiResponse<Submission>>, StatusCode> {
+    let engine_id = Caller::from_headers(&headers)
+        .ok_or(StatusCode::UNAUTHORIZED)?
+        .user_id
+        .to_string();
+    if req.prediction.as_ref().is_some_and(|prediction| !prediction.is_valid()) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let reputation = engine_reputation(&state, bounty_id, &engine_id).await?;
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| db_error("Failed to start transaction", e))?;
+    let bounty = BountyModel::find_for_update(&mut tx, bounty_id)
+        .await
+        .map_err(|e| db_error("Failed to lock bounty", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    let reveal_deadline = bounty.reveal_deadline.ok_or(StatusCode::BAD_REQUEST)?;
+    let now = Utc::now();
+    let open = bounty.status == BountyStatus::Active.as_str() || bounty.status == BountyStatus::InProgress.as_str();
+    if !open || now < bounty.deadline || now >= reveal_deadline {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    let commitment = SubmissionCommitment::find(&mut *tx, bounty_id, &engine_id)
+        .await
+        .map_err(|e| db_error("Failed to load commitment", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    if commitment.revealed_at.is_some() {
+        return Err(StatusCode::CONFLICT);
+    }
+    let verdict = format!("{:?}", req.verdict);
+    if !commitment.opens(&verdict, &req.nonce) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let submission = Submission {
+        id: Uuid::new_v4(),
+        bounty_id,
+        engine_id: engine_id.clone(),
+        engine_type: serde_json::from_value(serde_json::Value::String(commitment.engine_type.clone()))
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?,
+        verdict: req.verdict,
+        confidence: commitment.confidence,
+        stake_amount: commitment.stake_amount as u64,
+        analysis_details: req.analysis_details,
+        status: SubmissionStatus::Pending,
+        transaction_hash: commitment.transaction_hash.clone(),
+        submitted_at: now,
+        processed_at: None,
+        accuracy_score: None,
+    };
+    let model = to_model(&submission)?;
+    // The stake was locked when the engine committed
+    save_submission(&mut tx, &model, None, None).await?;
+    SubmissionCommitment::mark_revealed(&mut *tx, bounty_id, &engine_id, submission.id)
+        .await
+        .map_err(|e| db_error("Failed to save reveal", e))?;
+    let participant = BountyParticipant::find(&mut *tx, bounty_id, &engine_id)
+        .await
+        .map_err(|e| db_error("Failed to load participant", e))?;
+    let wallet_address = participant.and_then(|participant| participant.wallet_address);
+    let source_ip = client_ip(&headers);
+
+    let calibration = CalibrationBounty::find(&mut *tx, bounty_id)
+        .await
+        .map_err(|e| db_error("Failed to load calibration bounty", e))?;
+    let forwarded = ForwardedSubmission {
+        engine_id: &engine_id,
+        verdict: model.verdict.to_lowercase(),
+        confidence: model.confidence,
+        reputation_score: reputation.score,
+        specialization_score: reputation.specialization_score,
+        probation: reputation.probation,
+        ground_truth: calibration.as_ref().map(|c| c.expected_verdict.as_str()),
+        stake_amount: model.stake_amount,
+        prediction: req.prediction.as_ref(),
+        nonce: Some(&req.nonce),
+        algorithm: bounty.consensus_algorithm.as_deref(),
+        source_ip: source_ip.as_deref(),
+        wallet_address: wallet_address.as_deref(),
+        analysis_text: submission.analysis_details.summary_text(),
+        indicators: submission.analysis_details.indicators(),
+        artifact: ForwardedArtifact {
+            file_type: bounty.mime_type.as_deref(),
+            file_size: bounty.file_size,
+        },
+    };
+    state
+        .intake
+        .forward_submission(bounty_id, &forwarded)
+        .await
+        .map_err(|e| intake_error("Failed to forward reveal to consensus", e))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| db_error("Failed to save reveal", e))?;
+    info!("Engine {} revealed its verdict on bounty {}", engine_id, bounty_id);
+    announce_submission(&state, &submission).await;
 
     Ok(Json(ApiResponse::success(submission)))
 }
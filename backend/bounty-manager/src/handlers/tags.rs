@@ -0,0 +1,167 @@
+// backend/bounty-manager/src/handlers/tags.rs
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use shared::types::ApiResponse;
+use tracing::info;
+
+use crate::handlers::bounty_crud::{db_error, BountyManagerState, BountyStatus};
+use crate::handlers::embargo::require_admin;
+use crate::models::bounty::BountyModel;
+use crate::models::tag::{is_valid_slug, BountyTag, TagStats};
+
+#[derive(Debug, Deserialize)]
+pub struct TagListParams {
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpsertTagRequest {
+    pub name: String,
+    pub category: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TaggedBountyParams {
+    pub status: Option<BountyStatus>,
+    pub page: Option<u32>,
+    pub per_page: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TaggedBountiesResponse {
+    pub tag: String,
+    pub bounties: Vec<BountyModel>,
+    pub page: u32,
+    pub per_page: u32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TagStatsParams {
+    /// Include how this engine has done under each tag
+    pub engine_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TagStatsEntry {
+    #[serde(flatten)]
+    pub stats: TagStats,
+    /// Share of the engine's ruled-on submissions that matched consensus
+    pub engine_accuracy: Option<f64>,
+}
+
+/// The tag taxonomy, optionally one category of it
+pub async fn list_tags(
+    State(state): State<BountyManagerState>,
+    Query(params): Query<TagListParams>,
+) -> Result<Json<ApiResponse<Vec<BountyTag>>>, StatusCode> {
+    let tags = BountyTag::list(&state.db, params.category.as_deref())
+        .await
+        .map_err(|e| db_error("Failed to list bounty tags", e))?;
+
+    Ok(Json(ApiResponse::success(tags)))
+}
+
+/// Add a tag to the taxonomy or rename and recategorize it
+pub async fn upsert_tag(
+    State(state): State<BountyManagerState>,
+    Path(slug): Path<String>,
+    headers: HeaderMap,
+    Json(req): Json<UpsertTagRequest>,
+) -> Result<Json<ApiResponse<BountyTag>>, StatusCode> {
+    let caller = require_admin(&headers)?;
+
+    let name = req.name.trim();
+    let category = req.category.trim().to_ascii_lowercase();
+    if !is_valid_slug(&slug) || name.is_empty() || name.len() > 100 || !is_valid_slug(&category) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let description = req.description.as_deref().map(str::trim).filter(|d| !d.is_empty());
+
+    let tag = BountyTag::upsert(&state.db, &slug, name, &category, description)
+        .await
+        .map_err(|e| db_error("Failed to save bounty tag", e))?;
+
+    info!("Bounty tag {} saved by {}", slug, caller.user_id);
+    Ok(Json(ApiResponse::success(tag)))
+}
+
+/// Remove a tag no bounty carries
+pub async fn delete_tag(
+    State(state): State<BountyManagerState>,
+    Path(slug): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<ApiResponse<String>>, StatusCode> {
+    let caller = require_admin(&headers)?;
+
+    let usage = BountyTag::usage(&state.db, &slug)
+        .await
+        .map_err(|e| db_error("Failed to check bounty tag usage", e))?;
+    if usage > 0 {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    if !BountyTag::delete(&state.db, &slug)
+        .await
+        .map_err(|e| db_error("Failed to delete bounty tag", e))?
+    {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    info!("Bounty tag {} deleted by {}", slug, caller.user_id);
+    Ok(Json(ApiResponse::success(format!("Tag {} deleted", slug))))
+}
+
+/// Bounties carrying a tag, newest first
+pub async fn list_tagged_bounties(
+    State(state): State<BountyManagerState>,
+    Path(slug): Path<String>,
+    Query(params): Query<TaggedBountyParams>,
+) -> Result<Json<ApiResponse<TaggedBountiesResponse>>, StatusCode> {
+    let page = params.page.unwrap_or(1).max(1);
+    let per_page = params.per_page.unwrap_or(20).min(100);
+    let offset = (page - 1) as i64 * per_page as i64;
+
+    let bounties = BountyTag::bounties(
+        &state.db,
+        &slug,
+        params.status.as_ref().map(BountyStatus::as_str),
+        per_page as i64,
+        offset,
+    )
+    .await
+    .map_err(|e| db_error("Failed to list tagged bounties", e))?;
+
+    Ok(Json(ApiResponse::success(TaggedBountiesResponse {
+        tag: slug,
+        bounties,
+        page,
+        per_page,
+    })))
+}
+
+/// Bounty and submission activity per tag, so engines can see where the
+/// work is and where they do best
+pub async fn tag_stats(
+    State(state): State<BountyManagerState>,
+    Query(params): Query<TagStatsParams>,
+) -> Result<Json<ApiResponse<Vec<TagStatsEntry>>>, StatusCode> {
+    let stats = BountyTag::stats(&state.db, params.engine_id.as_deref())
+        .await
+        .map_err(|e| db_error("Failed to compute tag statistics", e))?;
+
+    let entries = stats
+        .into_iter()
+        .map(|stats| TagStatsEntry {
+            engine_accuracy: stats.engine_accuracy(),
+            stats,
+        })
+        .collect();
+
+    Ok(Json(ApiResponse::success(entries)))
+}
@@ -0,0 +1,173 @@
+// backend/bounty-manager/src/handlers/webhooks.rs
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+    Extension,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use shared::types::ApiResponse;
+use shared::webhooks;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::handlers::bounty_crud::{db_error, BountyManagerState, PaginationParams};
+use crate::models::bounty::BountyModel;
+use crate::models::webhook::{BountyWebhook, WebhookDelivery, WebhookEvent};
+
+#[derive(Debug, Deserialize)]
+pub struct RegisterWebhookRequest {
+    /// HTTPS endpoint deliveries are POSTed to
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+}
+
+/// A newly registered webhook with the secret its deliveries are signed
+/// with; the secret is not shown again
+#[derive(Debug, Serialize)]
+pub struct RegisteredWebhook {
+    #[serde(flatten)]
+    pub webhook: BountyWebhook,
+    pub secret: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeliveryLogResponse {
+    pub webhook_id: Uuid,
+    pub deliveries: Vec<WebhookDelivery>,
+    pub page: u32,
+    pub per_page: u32,
+}
+
+/// Only a bounty's creator manages its webhooks
+async fn require_creator(state: &BountyManagerState, bounty_id: Uuid, user_address: &str) -> Result<(), StatusCode> {
+    let bounty = BountyModel::find_by_id(&state.db, bounty_id)
+        .await
+        .map_err(|e| db_error("Failed to load bounty", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    if bounty.creator != user_address {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(())
+}
+
+/// Subscribe a URL to events of one of the caller's bounties
+pub async fn register_webhook(
+    State(state): State<BountyManagerState>,
+    Path(bounty_id): Path<Uuid>,
+    Extension(user_address): Extension<String>, // From auth middleware
+    Json(req): Json<RegisterWebhookRequest>,
+) -> Result<Json<ApiResponse<RegisteredWebhook>>, StatusCode> {
+    require_creator(&state, bounty_id, &user_address).await?;
+
+    if req.events.is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    if let Err(e) = webhooks::validate(&req.url).await {
+        info!("Rejected webhook URL for bounty {}: {}", bounty_id, e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let mut events: Vec<String> = Vec::new();
+    for event in &req.events {
+        let name = event.as_str().to_string();
+        if !events.contains(&name) {
+            events.push(name);
+        }
+    }
+
+    let registered = BountyWebhook::count_for_bounty(&state.db, bounty_id)
+        .await
+        .map_err(|e| db_error("Failed to count bounty webhooks", e))?;
+    if registered >= state.webhooks.max_per_bounty {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    let secret = format!("whsec_{}", hex::encode(rand::random::<[u8; 32]>()));
+    let now = Utc::now();
+    let webhook = BountyWebhook::create(
+        &state.db,
+        &BountyWebhook {
+            id: Uuid::new_v4(),
+            bounty_id,
+            creator: user_address,
+            url: req.url,
+            secret: secret.clone(),
+            events,
+            enabled: true,
+            created_at: now,
+            updated_at: now,
+        },
+    )
+    .await
+    .map_err(|e| db_error("Failed to save webhook", e))?;
+
+    info!("Webhook {} registered on bounty {} ({:?})", webhook.id, bounty_id, webhook.events);
+    Ok(Json(ApiResponse::success(RegisteredWebhook { webhook, secret })))
+}
+
+/// The webhooks registered on one of the caller's bounties
+pub async fn list_webhooks(
+    State(state): State<BountyManagerState>,
+    Path(bounty_id): Path<Uuid>,
+    Extension(user_address): Extension<String>,
+) -> Result<Json<ApiResponse<Vec<BountyWebhook>>>, StatusCode> {
+    require_creator(&state, bounty_id, &user_address).await?;
+
+    let webhooks = BountyWebhook::list_for_bounty(&state.db, bounty_id)
+        .await
+        .map_err(|e| db_error("Failed to list webhooks", e))?;
+
+    Ok(Json(ApiResponse::success(webhooks)))
+}
+
+/// Remove a webhook; its queued deliveries are dropped with it
+pub async fn delete_webhook(
+    State(state): State<BountyManagerState>,
+    Path((bounty_id, webhook_id)): Path<(Uuid, Uuid)>,
+    Extension(user_address): Extension<String>,
+) -> Result<Json<ApiResponse<String>>, StatusCode> {
+    require_creator(&state, bounty_id, &user_address).await?;
+
+    if !BountyWebhook::delete(&state.db, bounty_id, webhook_id)
+        .await
+        .map_err(|e| db_error("Failed to delete webhook", e))?
+    {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    info!("Webhook {} removed from bounty {}", webhook_id, bounty_id);
+    Ok(Json(ApiResponse::success(format!("Webhook {} deleted", webhook_id))))
+}
+
+/// A webhook's deliveries, newest first, with the outcome of each one's
+/// latest attempt
+pub async fn list_webhook_deliveries(
+    State(state): State<BountyManagerState>,
+    Path((bounty_id, webhook_id)): Path<(Uuid, Uuid)>,
+    Extension(user_address): Extension<String>,
+    Query(pagination): Query<PaginationParams>,
+) -> Result<Json<ApiResponse<DeliveryLogResponse>>, StatusCode> {
+    require_creator(&state, bounty_id, &user_address).await?;
+    BountyWebhook::find(&state.db, bounty_id, webhook_id)
+        .await
+        .map_err(|e| db_error("Failed to load webhook", e))?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let page = pagination.page.unwrap_or(1).max(1);
+    let per_page = pagination.per_page.unwrap_or(20).min(100);
+    let offset = (page - 1) as i64 * per_page as i64;
+
+    let deliveries = WebhookDelivery::log(&state.db, webhook_id, per_page as i64, offset)
+        .await
+        .map_err(|e| db_error("Failed to list webhook deliveries", e))?;
+
+    Ok(Json(ApiResponse::success(DeliveryLogResponse {
+        webhook_id,
+        deliveries,
+        page,
+        per_page,
+    })))
+}
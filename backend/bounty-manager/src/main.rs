@@ -2,7 +2,7 @@ use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
     response::Json,
-    routing::{get, post, put},
+    routing::{delete, get, post, put},
     Router,
 };
 use serde::{Deserialize, Serialize};
@@ -10,7 +10,7 @@ use sqlx::{PgPool, Row};
 use std::{collections::HashMap, net::SocketAddr, sync::Arc};
 use tokio::net::TcpListener;
 use tower::ServiceBuilder;
-use tower_http::{cors::CorsLayer, trace::TraceLayer};
+use tower_http::trace::TraceLayer;
 use tracing::{info, warn, error};
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
@@ -121,6 +121,8 @@ pub struct ApiResponse<T> {
     pub success: bool,
     pub data: Option<T>,
     pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
     pub timestamp: DateTime<Utc>,
 }
 
@@ -130,6 +132,7 @@ impl<T> ApiResponse<T> {
             success: true,
             data: Some(data),
             error: None,
+            request_id: shared::observability::current_request_id(),
             timestamp: Utc::now(),
         }
     }
@@ -139,6 +142,7 @@ impl<T> ApiResponse<T> {
             success: false,
             data: None,
             error: Some(message.to_string()),
+            request_id: shared::observability::current_request_id(),
             timestamp: Utc::now(),
         }
     }
@@ -147,7 +151,7 @@ impl<T> ApiResponse<T> {
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize tracing
-    tracing_subscriber::fmt::init();
+    shared::observability::init_service_logging(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))?;
 
     // Load configuration
     let database_url = std::env::var("DATABASE_URL")
@@ -168,19 +172,156 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("Running database migrations...");
     sqlx::migrate!("./migrations").run(&db).await?;
 
+    // Start archival of old finalized bounties
+    let app_config = config::Config::from_env()?;
+    app_config.validate()?;
+    if app_config.archival.enabled {
+        let archival_worker = workers::ArchivalWorker::new(db.clone(), app_config.archival.clone());
+        tokio::spawn(async move {
+            archival_worker.run().await;
+        });
+    }
+
     // Initialize reputation service
     let reputation_service = Arc::new(reputation::ReputationService::new());
 
+    // Bounty rewards are escrowed in the payment-service; bounties stay
+    // inactive until the creator's deposit confirms
+    let payments = Arc::new(services::PaymentClient::new(&app_config.payment)?);
+    let funding_worker = workers::FundingWorker::new(
+        db.clone(),
+        payments.clone(),
+        app_config.payment.clone(),
+        app_config.deposits.enabled,
+        shared::messaging::EventSubscriber::from_url(&redis_url)?,
+    );
+    tokio::spawn(async move {
+        funding_worker.run().await;
+    });
+
+    // Bounty events exchanged with the consensus-service over Redis
+    let events = Arc::new(shared::messaging::EventPublisher::from_url(&redis_url)?);
+    let consensus_service = Arc::new(services::consensus::ConsensusService::new(
+        app_config.consensus.min_submissions,
+        app_config.consensus.consensus_threshold,
+        app_config.consensus.enable_weighted_voting,
+    ));
+
+    // Pay out bounties as the consensus-service announces their verdicts
+    let consensus_worker = workers::ConsensusWorker::new(
+        db.clone(),
+        consensus_service.clone(),
+        payments.clone(),
+        shared::messaging::EventSubscriber::from_url(&redis_url)?,
+    );
+    tokio::spawn(async move {
+        consensus_worker.run().await;
+    });
+
+    // Close bounties whose deadline passed for the consensus-service to
+    // settle, or refund them
+    if app_config.expiration.enabled {
+        let expiration_worker = workers::ExpirationWorker::new(
+            db.clone(),
+            consensus_service,
+            payments.clone(),
+            events.clone(),
+            app_config.expiration.clone(),
+        );
+        tokio::spawn(async move {
+            expiration_worker.run().await;
+        });
+    }
+
+    // Content heuristics that hold new bounties, including standing bounty
+    // children, for moderation review
+    let content_screen = Arc::new(services::ContentScreen::new(&app_config.moderation)?);
+    info!("Moderation screening with {} known hashes", content_screen.hash_count());
+
+    // Open child bounties of standing bounties as their schedules come due
+    // or their watch rules match
+    if app_config.standing.enabled {
+        let standing_worker = workers::StandingBountyWorker::new(
+            db.clone(),
+            payments.clone(),
+            content_screen.clone(),
+            app_config.standing.clone(),
+            app_config.deposits.enabled,
+        );
+        tokio::spawn(async move {
+            standing_worker.run().await;
+        });
+    }
+
+    // Keep calibration bounties from the artifact pool open
+    if app_config.calibration.enabled {
+        let calibration_worker = workers::CalibrationWorker::new(
+            db.clone(),
+            payments.clone(),
+            content_screen.clone(),
+            app_config.calibration.clone(),
+            app_config.deposits.enabled,
+        );
+        tokio::spawn(async move {
+            calibration_worker.run().await;
+        });
+    }
+
+    // Deliver queued bounty events to creators' webhooks
+    if app_config.webhooks.enabled {
+        let webhook_worker = workers::WebhookWorker::new(
+            db.clone(),
+            services::WebhookSender::new(&app_config.webhooks)?,
+            app_config.webhooks.clone(),
+        );
+        tokio::spawn(async move {
+            webhook_worker.run().await;
+        });
+    }
+
+    // Rebuild the aggregates behind /bounties/stats
+    if app_config.analytics.enabled {
+        let analytics_worker = workers::AnalyticsWorker::new(db.clone(), app_config.analytics.clone());
+        tokio::spawn(async move {
+            analytics_worker.run().await;
+        });
+    }
+
+    // Generate large bounty exports in the background
+    let mut exports = app_config.exports.clone();
+    if exports.signing_secret.is_empty() {
+        warn!("EXPORT_SIGNING_SECRET not set; export download links will not survive a restart");
+        exports.signing_secret = hex::encode(rand::random::<[u8; 32]>());
+    }
+    if exports.enabled {
+        let export_worker = workers::ExportWorker::new(db.clone(), exports.clone());
+        tokio::spawn(async move {
+            export_worker.run().await;
+        });
+    }
+
     // Create application state using BountyManagerState
     let state = bounty_crud::BountyManagerState {
+        db: db.clone(),
         reputation_service: reputation_service.clone(),
+        embargo: app_config.embargo.clone(),
+        payments,
+        intake: Arc::new(services::IntakeClient::new(&app_config.intake)?),
+        webhooks: app_config.webhooks.clone(),
+        deposits: app_config.deposits.clone(),
+        moderation: app_config.moderation.clone(),
+        content_screen,
+        exports,
+        events,
     };
 
     // Build router
-    let app = create_router(state);
+    let verifier = shared::request_signing::SignatureVerifier::from_env()?;
+    let app = create_router(state, verifier);
 
     // Start blockchain sync service in the background
     let sync_db = db.clone();
+    let sync_deposits = app_config.deposits.clone();
     tokio::spawn(async move {
         // Initialize blockchain service for sync
         let rpc_url = std::env::var("BLOCKCHAIN_RPC_URL").unwrap_or_else(|_| "http://localhost:8545".to_string());
@@ -191,6 +332,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
         if private_key.is_empty() || bounty_manager_addr.is_empty() {
             warn!("Blockchain sync not started: BLOCKCHAIN_PRIVATE_KEY or BOUNTY_MANAGER_ADDRESS not set");
+            if sync_deposits.enabled {
+                warn!("Deposit verification is enabled but cannot run; funded bounties will stay pending");
+            }
             return;
         }
 
@@ -211,6 +355,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 let sync_service = services::blockchain_sync::BlockchainSyncService::new(
                     sync_db,
                     Arc::new(blockchain_service),
+                    sync_deposits,
                 );
                 info!("Blockchain sync service starting...");
                 if let Err(e) = sync_service.start().await {
@@ -233,7 +378,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-fn create_router(state: bounty_crud::BountyManagerState) -> Router {
+fn create_router(
+    state: bounty_crud::BountyManagerState,
+    verifier: shared::request_signing::SignatureVerifier,
+) -> Router {
     Router::new()
         // Health check
         .route("/health", get(health_check))
@@ -244,22 +392,122 @@ fn create_router(state: bounty_crud::BountyManagerState) -> Router {
         .route("/bounties/:id", get(bounty_crud::get_bounty))
         .route("/bounties/:id", put(bounty_crud::update_bounty))
         .route("/bounties/:id/cancel", post(bounty_crud::cancel_bounty))
+        .route("/bounties/:id/funding", post(bounty_crud::fund_bounty))
+        .route("/bounties/:id/deposit-verification", get(bounty_crud::get_deposit_verification))
+
+        // Archive routes
+        .route("/bounties/archived", get(handlers::archive::list_archived_bounties))
+        .route("/bounties/:id/rehydrate", post(handlers::archive::rehydrate_bounty))
+
+        // Verdict embargo routes
+        .route(
+            "/bounties/:id/embargo",
+            get(handlers::embargo::get_embargo).put(handlers::embargo::place_embargo),
+        )
+        .route("/bounties/:id/embargo/release", post(handlers::embargo::release_embargo))
+
+        // Tag taxonomy routes
+        .route("/tags", get(handlers::tags::list_tags))
+        .route("/tags/stats", get(handlers::tags::tag_stats))
+        .route(
+            "/tags/:slug",
+            put(handlers::tags::upsert_tag).delete(handlers::tags::delete_tag),
+        )
+        .route("/tags/:slug/bounties", get(handlers::tags::list_tagged_bounties))
+
+        // Standing bounty routes
+        .route(
+            "/standing-bounties",
+            get(handlers::standing::list_standing_bounties).post(handlers::standing::create_standing_bounty),
+        )
+        .route("/standing-bounties/:id", get(handlers::standing::get_standing_bounty))
+        .route("/standing-bounties/:id/enabled", put(handlers::standing::set_standing_bounty_enabled))
+
+        // Participant routes
+        .route(
+            "/bounties/:id/participants",
+            get(handlers::participants::list_participants).post(handlers::participants::join_bounty),
+        )
+        .route(
+            "/bounties/:id/participants/withdraw",
+            post(handlers::participants::withdraw_from_bounty),
+        )
 
-        // Stats route
+        // Bounty event webhook routes
+        .route(
+            "/bounties/:id/webhooks",
+            get(handlers::webhooks::list_webhooks).post(handlers::webhooks::register_webhook),
+        )
+        .route(
+            "/bounties/:id/webhooks/:webhook_id",
+            delete(handlers::webhooks::delete_webhook),
+        )
+        .route(
+            "/bounties/:id/webhooks/:webhook_id/deliveries",
+            get(handlers::webhooks::list_webhook_deliveries),
+        )
+
+        // Export routes
+        .route("/bounties/export", get(handlers::export::export_bounties))
+        .route("/bounties/exports", get(handlers::export::list_exports))
+        .route("/bounties/exports/:id", get(handlers::export::get_export))
+        .route("/bounties/exports/:id/download", get(handlers::export::download_export))
+
+        // Moderation routes
+        .route("/bounties/:id/report", post(handlers::moderation::report_bounty))
+        .route("/admin/moderation/queue", get(handlers::moderation::list_moderation_queue))
+        .route("/admin/moderation/bounties/:id", get(handlers::moderation::get_moderation_case))
+        .route("/admin/moderation/bounties/:id/hold", post(handlers::moderation::hold_bounty))
+        .route("/admin/moderation/bounties/:id/approve", post(handlers::moderation::approve_bounty))
+        .route("/admin/moderation/bounties/:id/takedown", post(handlers::moderation::takedown_bounty))
+
+        // Calibration bounty routes
+        .route("/admin/calibration/bounties", get(handlers::calibration::list_calibration_bounties))
+        .route(
+            "/admin/calibration/bounties/:id",
+            put(handlers::calibration::mark_calibration_bounty),
+        )
+        .route(
+            "/admin/calibration/artifacts",
+            get(handlers::calibration::list_calibration_artifacts)
+                .post(handlers::calibration::add_calibration_artifact),
+        )
+        .route(
+            "/admin/calibration/artifacts/:id/active",
+            put(handlers::calibration::set_calibration_artifact_active),
+        )
+
+        // Stats routes
         .route("/bounties/stats", get(bounty_crud::get_bounty_stats))
+        .route("/bounties/stats/creators", get(bounty_crud::list_creator_stats))
+        .route("/bounties/stats/creators/:creator", get(bounty_crud::get_creator_stats))
+
+        // Admin
+        .route(
+            "/admin/log-level",
+            get(handlers::admin_logging::get_log_level).put(handlers::admin_logging::put_log_level),
+        )
 
-        // TODO: Add more routes as handlers are implemented
-        // .route("/bounties/:id/submit", post(handlers::submit_analysis))
+        // Submission intake
+        .route("/bounties/:id/submit", post(handlers::submit_analysis))
+        .route("/bounties/:id/commitments", post(handlers::submission::commit_verdict))
+        .route("/bounties/:id/reveal", post(handlers::submission::reveal_verdict))
 
         // State management
         .with_state(state)
 
         // Middleware
+        .layer(axum::middleware::from_fn_with_state(
+            verifier,
+            handlers::service_auth::verify_signature_middleware,
+        ))
         .layer(
             ServiceBuilder::new()
                 .layer(TraceLayer::new_for_http())
-                .layer(CorsLayer::permissive())
+                .layer(handlers::http_security::cors_layer())
         )
+        .layer(axum::middleware::from_fn(handlers::http_security::security_headers_middleware))
+        .layer(axum::middleware::from_fn(handlers::admin_logging::log_context_middleware))
 }
 
 async fn health_check() -> Json<ApiResponse<HashMap<String, String>>> {
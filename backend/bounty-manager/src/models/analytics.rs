@@ -0,0 +1,230 @@
+// backend/bounty-manager/src/models/analytics.rs
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+
+/// Live bounties and the summaries of archived ones, with when each
+/// completed bounty reached consensus
+const ALL_BOUNTIES: &str = r#"
+    WITH all_bounties AS (
+        SELECT creator, artifact_type, reward_amount, currency, status, created_at,
+               CASE WHEN status = 'Completed' THEN updated_at END AS completed_at
+        FROM bounties
+        UNION ALL
+        SELECT creator, artifact_type, reward_amount, currency, status, created_at,
+               CASE WHEN status = 'Completed' THEN finalized_at END AS completed_at
+        FROM bounty_archive_summaries
+    )
+"#;
+
+const CREATOR_COLUMNS: &str = "creator, bounty_count, active_count, completed_count, \
+     total_offered::TEXT AS total_offered, avg_hours_to_consensus, last_bounty_at";
+
+const HOURS_TO_CONSENSUS: &str = "AVG(EXTRACT(EPOCH FROM completed_at - created_at) / 3600.0)::DOUBLE PRECISION";
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct StatsSummary {
+    pub total_bounties: i64,
+    pub active_bounties: i64,
+    pub completed_bounties: i64,
+    pub avg_hours_to_consensus: Option<f64>,
+    pub refreshed_at: DateTime<Utc>,
+}
+
+/// Bounties created and completed on one day
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DailyStats {
+    pub day: NaiveDate,
+    pub bounties_created: i64,
+    pub bounties_completed: i64,
+    /// Of the bounties completed that day
+    pub avg_hours_to_consensus: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TokenStats {
+    pub currency: String,
+    pub bounty_count: i64,
+    pub completed_count: i64,
+    /// In wei, as decimal strings so large sums are not rounded
+    pub total_offered: String,
+    pub total_paid: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ArtifactTypeStats {
+    pub artifact_type: String,
+    pub bounty_count: i64,
+    pub completed_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CreatorStats {
+    pub creator: String,
+    pub bounty_count: i64,
+    pub active_count: i64,
+    pub completed_count: i64,
+    /// In wei, summed over currencies
+    pub total_offered: String,
+    pub avg_hours_to_consensus: Option<f64>,
+    pub last_bounty_at: DateTime<Utc>,
+}
+
+/// Aggregates rebuilt by the analytics worker
+pub struct BountyAnalytics;
+
+impl BountyAnalytics {
+    /// Rebuild every analytics table in one transaction, so readers never see
+    /// a partial refresh
+    pub async fn refresh(pool: &PgPool) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        for table in [
+            "bounty_stats_summary",
+            "bounty_stats_daily",
+            "bounty_stats_tokens",
+            "bounty_stats_artifact_types",
+            "bounty_stats_creators",
+        ] {
+            sqlx::query(&format!("DELETE FROM {}", table)).execute(&mut *tx).await?;
+        }
+
+        sqlx::query(&format!(
+            r#"{ALL_BOUNTIES}
+            INSERT INTO bounty_stats_summary
+                (id, total_bounties, active_bounties, completed_bounties, avg_hours_to_consensus, refreshed_at)
+            SELECT 1, COUNT(*),
+                   COUNT(*) FILTER (WHERE status IN ('Active', 'InProgress')),
+                   COUNT(*) FILTER (WHERE status = 'Completed'),
+                   {HOURS_TO_CONSENSUS},
+                   NOW()
+            FROM all_bounties
+            "#
+        ))
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(&format!(
+            r#"{ALL_BOUNTIES}
+            , created AS (
+                SELECT created_at::DATE AS day, COUNT(*) AS n FROM all_bounties GROUP BY 1
+            ), completed AS (
+                SELECT completed_at::DATE AS day, COUNT(*) AS n, {HOURS_TO_CONSENSUS} AS hours
+                FROM all_bounties WHERE completed_at IS NOT NULL GROUP BY 1
+            )
+            INSERT INTO bounty_stats_daily (day, bounties_created, bounties_completed, avg_hours_to_consensus)
+            SELECT COALESCE(c.day, d.day), COALESCE(c.n, 0), COALESCE(d.n, 0), d.hours
+            FROM created c FULL JOIN completed d ON d.day = c.day
+            "#
+        ))
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(&format!(
+            r#"{ALL_BOUNTIES}
+            INSERT INTO bounty_stats_tokens (currency, bounty_count, completed_count, total_offered, total_paid)
+            SELECT currency, COUNT(*), COUNT(*) FILTER (WHERE status = 'Completed'),
+                   SUM(reward_amount::NUMERIC),
+                   COALESCE(SUM(reward_amount::NUMERIC) FILTER (WHERE status = 'Completed'), 0)
+            FROM all_bounties
+            GROUP BY currency
+            "#
+        ))
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(&format!(
+            r#"{ALL_BOUNTIES}
+            INSERT INTO bounty_stats_artifact_types (artifact_type, bounty_count, completed_count)
+            SELECT artifact_type, COUNT(*), COUNT(*) FILTER (WHERE status = 'Completed')
+            FROM all_bounties
+            GROUP BY artifact_type
+            "#
+        ))
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(&format!(
+            r#"{ALL_BOUNTIES}
+            INSERT INTO bounty_stats_creators
+                (creator, bounty_count, active_count, completed_count, total_offered, avg_hours_to_consensus, last_bounty_at)
+            SELECT creator, COUNT(*),
+                   COUNT(*) FILTER (WHERE status IN ('Active', 'InProgress')),
+                   COUNT(*) FILTER (WHERE status = 'Completed'),
+                   SUM(reward_amount::NUMERIC),
+                   {HOURS_TO_CONSENSUS},
+                   MAX(created_at)
+            FROM all_bounties
+            GROUP BY creator
+            "#
+        ))
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await
+    }
+
+    /// Totals as of the last refresh; `None` before the first one
+    pub async fn summary(pool: &PgPool) -> Result<Option<StatsSummary>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM bounty_stats_summary WHERE id = 1")
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// The last `days` days, oldest first
+    pub async fn daily(pool: &PgPool, days: i32) -> Result<Vec<DailyStats>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT * FROM bounty_stats_daily
+            WHERE day > CURRENT_DATE - $1
+            ORDER BY day
+            "#,
+        )
+        .bind(days)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Tokens by number of bounties
+    pub async fn tokens(pool: &PgPool, limit: i64) -> Result<Vec<TokenStats>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT currency, bounty_count, completed_count,
+                   total_offered::TEXT AS total_offered, total_paid::TEXT AS total_paid
+            FROM bounty_stats_tokens
+            ORDER BY bounty_count DESC, currency
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Artifact types by number of bounties
+    pub async fn artifact_types(pool: &PgPool, limit: i64) -> Result<Vec<ArtifactTypeStats>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM bounty_stats_artifact_types ORDER BY bounty_count DESC, artifact_type LIMIT $1")
+            .bind(limit)
+            .fetch_all(pool)
+            .await
+    }
+
+    /// Creators by number of bounties
+    pub async fn creators(pool: &PgPool, limit: i64, offset: i64) -> Result<Vec<CreatorStats>, sqlx::Error> {
+        sqlx::query_as(&format!(
+            "SELECT {CREATOR_COLUMNS} FROM bounty_stats_creators ORDER BY bounty_count DESC, creator LIMIT $1 OFFSET $2"
+        ))
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn creator(pool: &PgPool, creator: &str) -> Result<Option<CreatorStats>, sqlx::Error> {
+        sqlx::query_as(&format!("SELECT {CREATOR_COLUMNS} FROM bounty_stats_creators WHERE creator = $1"))
+            .bind(creator)
+            .fetch_optional(pool)
+            .await
+    }
+}
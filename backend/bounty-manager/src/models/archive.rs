@@ -0,0 +1,287 @@
+// backend/bounty-manager/src/models/archive.rs
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+/// Statuses after which a bounty no longer changes and may be archived
+pub const FINALIZED_STATUSES: [&str; 3] = ["Completed", "Cancelled", "Expired"];
+
+/// Summary row kept in the hot database for archived bounties
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ArchivedBountySummary {
+    pub bounty_id: Uuid,
+    pub creator: String,
+    pub title: String,
+    pub artifact_type: String,
+    pub artifact_hash: Option<String>,
+    pub reward_amount: i64,
+    pub currency: String,
+    pub status: String,
+    pub submission_count: i32,
+    pub created_at: DateTime<Utc>,
+    pub finalized_at: DateTime<Utc>,
+    pub archived_at: DateTime<Utc>,
+    /// Object key of the Parquet export, once uploaded
+    pub export_key: Option<String>,
+    pub exported_at: Option<DateTime<Utc>>,
+}
+
+/// One archived row as written to a Parquet export: the bounty itself, one
+/// of its submissions or one of its payouts
+#[derive(Debug, Clone, FromRow)]
+pub struct ArchivedRecord {
+    /// `bounty`, `submission` or `payout`
+    pub kind: String,
+    pub id: Uuid,
+    pub bounty_id: Uuid,
+    /// The archived row as JSON
+    pub data: String,
+}
+
+pub struct BountyArchive;
+
+impl BountyArchive {
+    /// Find finalized bounties whose last update is older than `finalized_before`.
+    ///
+    /// Bounties with disputes or unsettled payouts stay in the hot tables.
+    pub async fn find_archivable(
+        pool: &PgPool,
+        finalized_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        let ids: Vec<(Uuid,)> = sqlx::query_as(
+            r#"
+            SELECT b.id
+            FROM bounties b
+            WHERE b.status = ANY($1)
+              AND b.updated_at < $2
+              AND NOT EXISTS (SELECT 1 FROM disputes d WHERE d.bounty_id = b.id)
+              AND NOT EXISTS (
+                  SELECT 1 FROM payouts p
+                  WHERE p.bounty_id = b.id AND p.status NOT IN ('Completed', 'Failed')
+              )
+            ORDER BY b.updated_at
+            LIMIT $3
+            "#,
+        )
+        .bind(&FINALIZED_STATUSES[..])
+        .bind(finalized_before)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(ids.into_iter().map(|(id,)| id).collect())
+    }
+
+    /// Move a bounty, its submissions and payouts into the archive tables.
+    ///
+    /// Returns `false` if the bounty no longer exists in the hot tables.
+    pub async fn archive(pool: &PgPool, bounty_id: Uuid) -> Result<bool, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let summary = sqlx::query(
+            r#"
+            INSERT INTO bounty_archive_summaries (
+                bounty_id, creator, title, artifact_type, artifact_hash, reward_amount,
+                currency, status, submission_count, created_at, finalized_at
+            )
+            SELECT b.id, b.creator, b.title, b.artifact_type, b.artifact_hash, b.reward_amount,
+                   b.currency, b.status,
+                   (SELECT COUNT(*) FROM submissions s WHERE s.bounty_id = b.id)::INTEGER,
+                   b.created_at, b.updated_at
+            FROM bounties b
+            WHERE b.id = $1
+            ON CONFLICT (bounty_id) DO NOTHING
+            "#,
+        )
+        .bind(bounty_id)
+        .execute(&mut *tx)
+        .await?;
+
+        if summary.rows_affected() == 0 {
+            tx.rollback().await?;
+            return Ok(false);
+        }
+
+        sqlx::query("INSERT INTO archived_bounties (id, data) SELECT id, to_jsonb(b) FROM bounties b WHERE id = $1")
+            .bind(bounty_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            "INSERT INTO archived_submissions (id, bounty_id, data) SELECT id, bounty_id, to_jsonb(s) FROM submissions s WHERE bounty_id = $1",
+        )
+        .bind(bounty_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            "INSERT INTO archived_payouts (id, bounty_id, data) SELECT id, bounty_id, to_jsonb(p) FROM payouts p WHERE bounty_id = $1",
+        )
+        .bind(bounty_id)
+        .execute(&mut *tx)
+        .await?;
+
+        // Payouts reference submissions, so they go first
+        for table in ["payouts", "submissions"] {
+            sqlx::query(&format!("DELETE FROM {} WHERE bounty_id = $1", table))
+                .bind(bounty_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        sqlx::query("DELETE FROM bounties WHERE id = $1")
+            .bind(bounty_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Restore an archived bounty into the hot tables, e.g. so a dispute can
+    /// be opened against it. `updated_at` is bumped so the archival worker does
+    /// not immediately move it back.
+    ///
+    /// Returns `false` if the bounty is not archived.
+    pub async fn rehydrate(pool: &PgPool, bounty_id: Uuid) -> Result<bool, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let restored = sqlx::query(
+            r#"
+            INSERT INTO bounties
+            SELECT r.* FROM archived_bounties a, jsonb_populate_record(NULL::bounties, a.data) r
+            WHERE a.id = $1
+            "#,
+        )
+        .bind(bounty_id)
+        .execute(&mut *tx)
+        .await?;
+
+        if restored.rows_affected() == 0 {
+            tx.rollback().await?;
+            return Ok(false);
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO submissions
+            SELECT r.* FROM archived_submissions a, jsonb_populate_record(NULL::submissions, a.data) r
+            WHERE a.bounty_id = $1
+            "#,
+        )
+        .bind(bounty_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO payouts
+            SELECT r.* FROM archived_payouts a, jsonb_populate_record(NULL::payouts, a.data) r
+            WHERE a.bounty_id = $1
+            "#,
+        )
+        .bind(bounty_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("UPDATE bounties SET updated_at = $1 WHERE id = $2")
+            .bind(Utc::now())
+            .bind(bounty_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for table in ["archived_payouts", "archived_submissions"] {
+            sqlx::query(&format!("DELETE FROM {} WHERE bounty_id = $1", table))
+                .bind(bounty_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        sqlx::query("DELETE FROM archived_bounties WHERE id = $1")
+            .bind(bounty_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM bounty_archive_summaries WHERE bounty_id = $1")
+            .bind(bounty_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Archived bounties without a Parquet export yet, oldest first
+    pub async fn find_unexported(
+        pool: &PgPool,
+        limit: i64,
+    ) -> Result<Vec<(Uuid, DateTime<Utc>)>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT bounty_id, archived_at FROM bounty_archive_summaries
+            WHERE export_key IS NULL
+            ORDER BY archived_at
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// The archived rows of a bounty, its submissions and its payouts
+    pub async fn records(pool: &PgPool, bounty_id: Uuid) -> Result<Vec<ArchivedRecord>, sqlx::Error> {
+        sqlx::query_as::<_, ArchivedRecord>(
+            r#"
+            SELECT 'bounty' AS kind, id, id AS bounty_id, data::TEXT AS data
+            FROM archived_bounties WHERE id = $1
+            UNION ALL
+            SELECT 'submission', id, bounty_id, data::TEXT FROM archived_submissions WHERE bounty_id = $1
+            UNION ALL
+            SELECT 'payout', id, bounty_id, data::TEXT FROM archived_payouts WHERE bounty_id = $1
+            "#,
+        )
+        .bind(bounty_id)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Record where a bounty's export was uploaded
+    pub async fn mark_exported(pool: &PgPool, bounty_id: Uuid, export_key: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE bounty_archive_summaries SET export_key = $2, exported_at = NOW() WHERE bounty_id = $1",
+        )
+        .bind(bounty_id)
+        .bind(export_key)
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// List archived bounty summaries, most recently archived first
+    pub async fn list_summaries(
+        pool: &PgPool,
+        creator: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<ArchivedBountySummary>, sqlx::Error> {
+        let records = sqlx::query_as::<_, ArchivedBountySummary>(
+            r#"
+            SELECT * FROM bounty_archive_summaries
+            WHERE ($1::VARCHAR IS NULL OR creator = $1)
+            ORDER BY archived_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(creator)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(records)
+    }
+}
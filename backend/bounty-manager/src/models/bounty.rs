@@ -2,7 +2,7 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, PgPool};
+use sqlx::{FromRow, PgExecutor, PgPool, Postgres, Transaction};
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -28,6 +28,15 @@ pub struct BountyModel {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub metadata: Option<sqlx::types::JsonValue>,
+    /// Minimum engine reputation to submit; `None` admits any engine
+    pub min_reputation: Option<i32>,
+    /// End of the reveal phase of a commit-reveal bounty; `None` for bounties
+    /// taking verdicts directly
+    pub reveal_deadline: Option<DateTime<Utc>>,
+    /// How the consensus-service aggregates votes; `None` for its default
+    pub consensus_algorithm: Option<String>,
+    /// Organization the bounty belongs to, if any
+    pub organization_id: Option<Uuid>,
 }
 
 impl BountyModel {
@@ -39,9 +48,10 @@ impl BountyModel {
                 id, creator, title, description, artifact_type, artifact_hash,
                 artifact_url, file_name, file_size, mime_type, upload_path,
                 reward_amount, currency, min_stake, max_participants, deadline,
-                status, consensus_threshold, created_at, updated_at, metadata
+                status, consensus_threshold, created_at, updated_at, metadata,
+                min_reputation, reveal_deadline, consensus_algorithm, organization_id
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25)
             RETURNING *
             "#
         )
@@ -66,6 +76,10 @@ impl BountyModel {
         .bind(&bounty.created_at)
         .bind(&bounty.updated_at)
         .bind(&bounty.metadata)
+        .bind(bounty.min_reputation)
+        .bind(bounty.reveal_deadline)
+        .bind(&bounty.consensus_algorithm)
+        .bind(bounty.organization_id)
         .fetch_one(pool)
         .await?;
 
@@ -84,6 +98,22 @@ impl BountyModel {
         Ok(record)
     }
 
+    /// Find a bounty and lock it for the rest of the transaction, so
+    /// concurrent submissions see each other when checking participant limits
+    pub async fn find_for_update(
+        tx: &mut Transaction<'_, Postgres>,
+        id: Uuid,
+    ) -> Result<Option<BountyModel>, sqlx::Error> {
+        let record = sqlx::query_as::<_, BountyModel>(
+            "SELECT * FROM bounties WHERE id = $1 FOR UPDATE"
+        )
+        .bind(id)
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        Ok(record)
+    }
+
     /// Update bounty status
     pub async fn update_status(pool: &PgPool, id: Uuid, status: &str) -> Result<(), sqlx::Error> {
         sqlx::query(
@@ -98,6 +128,26 @@ impl BountyModel {
         Ok(())
     }
 
+    /// Move a bounty from one status to another; `false` if it is no longer
+    /// in `from`, e.g. because moderation held it meanwhile
+    pub async fn transition<'e, E: PgExecutor<'e>>(
+        executor: E,
+        id: Uuid,
+        from: &str,
+        to: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE bounties SET status = $1, updated_at = NOW() WHERE id = $2 AND status = $3"
+        )
+        .bind(to)
+        .bind(id)
+        .bind(from)
+        .execute(executor)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
     /// List bounties with filters
     pub async fn list(
         pool: &PgPool,
@@ -164,6 +214,60 @@ impl BountyModel {
         Ok(records)
     }
 
+    /// Open bounties whose deadline, or for commit-reveal bounties whose
+    /// reveal phase, has passed, oldest first
+    pub async fn find_expired(pool: &PgPool, now: DateTime<Utc>, limit: i64) -> Result<Vec<BountyModel>, sqlx::Error> {
+        let records = sqlx::query_as::<_, BountyModel>(
+            r#"
+            SELECT * FROM bounties
+            WHERE status IN ('PendingFunding', 'Active', 'InProgress')
+              AND COALESCE(reveal_deadline, deadline) <= $1
+            ORDER BY COALESCE(reveal_deadline, deadline)
+            LIMIT $2
+            "#
+        )
+        .bind(now)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    /// Move an open bounty to Expired. Returns false if it was no longer
+    /// open, e.g. because another replica expired it first.
+    pub async fn expire(pool: &PgPool, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE bounties SET status = 'Expired', updated_at = $1
+            WHERE id = $2 AND status IN ('PendingFunding', 'Active', 'InProgress')
+            "#
+        )
+        .bind(Utc::now())
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Move an open bounty to Completed once its verdict is settled. Returns
+    /// false if it was no longer open.
+    pub async fn complete(pool: &PgPool, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE bounties SET status = 'Completed', updated_at = $1
+            WHERE id = $2 AND status IN ('Active', 'InProgress')
+            "#
+        )
+        .bind(Utc::now())
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
     /// Delete a bounty
     pub async fn delete(pool: &PgPool, id: Uuid) -> Result<(), sqlx::Error> {
         sqlx::query("DELETE FROM bounties WHERE id = $1")
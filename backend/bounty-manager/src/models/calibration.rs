@@ -0,0 +1,224 @@
+// backend/bounty-manager/src/models/calibration.rs
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgExecutor};
+use uuid::Uuid;
+
+/// Verdicts a calibration bounty can be known to have
+pub const CALIBRATION_VERDICTS: [&str; 3] = ["malicious", "benign", "suspicious"];
+
+/// A bounty the platform injected on an artifact whose verdict it knows, for
+/// measuring engines' accuracy and for engines on probation to calibrate on
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CalibrationBounty {
+    pub bounty_id: Uuid,
+    /// Lowercase verdict
+    pub expected_verdict: String,
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    /// The pool artifact it was injected on; `None` when marked by hand
+    pub artifact_id: Option<Uuid>,
+}
+
+/// An artifact in the calibration pool, with the bounty text it is injected
+/// with
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CalibrationArtifact {
+    pub id: Uuid,
+    pub artifact_type: String,
+    pub artifact_data: sqlx::types::JsonValue,
+    /// Lowercase verdict
+    pub expected_verdict: String,
+    pub title: String,
+    pub description: String,
+    pub tags: Vec<String>,
+    /// Retired after this many bounties
+    pub max_uses: Option<i32>,
+    pub times_used: i32,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub active: bool,
+    pub added_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl CalibrationBounty {
+    /// Make a bounty a calibration bounty, or change its verdict
+    pub async fn mark<'e, E: PgExecutor<'e>>(
+        executor: E,
+        bounty_id: Uuid,
+        expected_verdict: &str,
+        created_by: Uuid,
+        artifact_id: Option<Uuid>,
+    ) -> Result<CalibrationBounty, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            INSERT INTO calibration_bounties (bounty_id, expected_verdict, created_by, artifact_id)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (bounty_id) DO UPDATE
+            SET expected_verdict = EXCLUDED.expected_verdict,
+                created_by = EXCLUDED.created_by,
+                artifact_id = EXCLUDED.artifact_id,
+                created_at = NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(bounty_id)
+        .bind(expected_verdict)
+        .bind(created_by)
+        .bind(artifact_id)
+        .fetch_one(executor)
+        .await
+    }
+
+    /// Calibration bounties still awaiting funding or open to submissions
+    pub async fn open_count<'e, E: PgExecutor<'e>>(executor: E) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM calibration_bounties c
+            JOIN bounties b ON b.id = c.bounty_id
+            WHERE b.status IN ('PendingFunding', 'Active', 'InProgress')
+            "#,
+        )
+        .fetch_one(executor)
+        .await
+    }
+
+    pub async fn find<'e, E: PgExecutor<'e>>(
+        executor: E,
+        bounty_id: Uuid,
+    ) -> Result<Option<CalibrationBounty>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM calibration_bounties WHERE bounty_id = $1")
+            .bind(bounty_id)
+            .fetch_optional(executor)
+            .await
+    }
+
+    /// Newest first
+    pub async fn list<'e, E: PgExecutor<'e>>(
+        executor: E,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<CalibrationBounty>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM calibration_bounties ORDER BY created_at DESC LIMIT $1 OFFSET $2")
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(executor)
+            .await
+    }
+}
+
+impl CalibrationArtifact {
+    pub async fn create<'e, E: PgExecutor<'e>>(
+        executor: E,
+        artifact: &CalibrationArtifact,
+    ) -> Result<CalibrationArtifact, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            INSERT INTO calibration_artifacts (
+                id, artifact_type, artifact_data, expected_verdict, title, description, tags,
+                max_uses, times_used, last_used_at, active, added_by, created_at, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+            RETURNING *
+            "#,
+        )
+        .bind(artifact.id)
+        .bind(&artifact.artifact_type)
+        .bind(&artifact.artifact_data)
+        .bind(&artifact.expected_verdict)
+        .bind(&artifact.title)
+        .bind(&artifact.description)
+        .bind(&artifact.tags)
+        .bind(artifact.max_uses)
+        .bind(artifact.times_used)
+        .bind(artifact.last_used_at)
+        .bind(artifact.active)
+        .bind(artifact.added_by)
+        .bind(artifact.created_at)
+        .bind(artifact.updated_at)
+        .fetch_one(executor)
+        .await
+    }
+
+    /// Newest first, optionally only the active ones
+    pub async fn list<'e, E: PgExecutor<'e>>(
+        executor: E,
+        active_only: bool,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<CalibrationArtifact>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT * FROM calibration_artifacts
+            WHERE active OR NOT $1
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(active_only)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(executor)
+        .await
+    }
+
+    pub async fn set_active<'e, E: PgExecutor<'e>>(
+        executor: E,
+        id: Uuid,
+        active: bool,
+    ) -> Result<Option<CalibrationArtifact>, sqlx::Error> {
+        sqlx::query_as(
+            "UPDATE calibration_artifacts SET active = $2, updated_at = NOW() WHERE id = $1 RETURNING *",
+        )
+        .bind(id)
+        .bind(active)
+        .fetch_optional(executor)
+        .await
+    }
+
+    /// The active artifact used longest ago, skipping those used since
+    /// `reused_after` and those used up. Claims it by recording the use, so
+    /// concurrent workers never pick the same one.
+    pub async fn claim_next<'e, E: PgExecutor<'e>>(
+        executor: E,
+        reused_after: DateTime<Utc>,
+    ) -> Result<Option<CalibrationArtifact>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            UPDATE calibration_artifacts
+            SET times_used = times_used + 1, last_used_at = NOW(), updated_at = NOW()
+            WHERE id = (
+                SELECT id FROM calibration_artifacts
+                WHERE active
+                  AND (last_used_at IS NULL OR last_used_at < $1)
+                  AND (max_uses IS NULL OR times_used < max_uses)
+                ORDER BY last_used_at NULLS FIRST, created_at
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING *
+            "#,
+        )
+        .bind(reused_after)
+        .fetch_optional(executor)
+        .await
+    }
+
+    /// Give back a claimed use whose bounty was never opened. The artifact
+    /// still waits out its cooldown, which only delays it.
+    pub async fn release<'e, E: PgExecutor<'e>>(executor: E, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE calibration_artifacts
+            SET times_used = GREATEST(times_used - 1, 0), updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .execute(executor)
+        .await?;
+        Ok(())
+    }
+}
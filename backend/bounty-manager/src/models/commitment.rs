@@ -0,0 +1,142 @@
+// backend/bounty-manager/src/models/commitment.rs
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::{FromRow, PgExecutor};
+use uuid::Uuid;
+
+/// Shortest nonce accepted in a reveal. The nonce keeps the handful of
+/// possible verdicts from being guessed from a commitment.
+pub const MIN_NONCE_LEN: usize = 16;
+
+/// The commitment to `verdict` under `nonce`: hex SHA-256 of
+/// "<verdict>:<nonce>", with the verdict in lowercase
+pub fn commitment_of(verdict: &str, nonce: &str) -> String {
+    hex::encode(Sha256::digest(format!("{}:{}", verdict.to_lowercase(), nonce)))
+}
+
+/// Commitments are 32-byte hex digests
+pub fn is_commitment(value: &str) -> bool {
+    value.len() == 64 && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// An engine's sealed verdict on a commit-reveal bounty
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SubmissionCommitment {
+    pub bounty_id: Uuid,
+    pub engine_id: String,
+    pub commitment: String,
+    pub engine_type: String,
+    pub confidence: f32,
+    pub stake_amount: i64,
+    pub transaction_hash: Option<String>,
+    pub committed_at: DateTime<Utc>,
+    pub submission_id: Option<Uuid>,
+    pub revealed_at: Option<DateTime<Utc>>,
+}
+
+impl SubmissionCommitment {
+    /// Whether `verdict` and `nonce` open this commitment
+    pub fn opens(&self, verdict: &str, nonce: &str) -> bool {
+        nonce.len() >= MIN_NONCE_LEN && commitment_of(verdict, nonce).eq_ignore_ascii_case(&self.commitment)
+    }
+
+    pub async fn create<'e, E: PgExecutor<'e>>(
+        executor: E,
+        commitment: &SubmissionCommitment,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO submission_commitments (
+                bounty_id, engine_id, commitment, engine_type, confidence,
+                stake_amount, transaction_hash, committed_at
+            )
+            VALUES ($1, $2, LOWER($3), $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(commitment.bounty_id)
+        .bind(&commitment.engine_id)
+        .bind(&commitment.commitment)
+        .bind(&commitment.engine_type)
+        .bind(commitment.confidence)
+        .bind(commitment.stake_amount)
+        .bind(&commitment.transaction_hash)
+        .bind(commitment.committed_at)
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn find<'e, E: PgExecutor<'e>>(
+        executor: E,
+        bounty_id: Uuid,
+        engine_id: &str,
+    ) -> Result<Option<SubmissionCommitment>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM submission_commitments WHERE bounty_id = $1 AND engine_id = $2")
+            .bind(bounty_id)
+            .bind(engine_id)
+            .fetch_optional(executor)
+            .await
+    }
+
+    /// Link a commitment to the submission its reveal created
+    pub async fn mark_revealed<'e, E: PgExecutor<'e>>(
+        executor: E,
+        bounty_id: Uuid,
+        engine_id: &str,
+        submission_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE submission_commitments
+            SET submission_id = $3, revealed_at = NOW()
+            WHERE bounty_id = $1 AND engine_id = $2
+            "#,
+        )
+        .bind(bounty_id)
+        .bind(engine_id)
+        .bind(submission_id)
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NONCE: &str = "3f9a1c7e5b2d4f60";
+
+    fn sealed(verdict: &str, nonce: &str) -> SubmissionCommitment {
+        SubmissionCommitment {
+            bounty_id: Uuid::new_v4(),
+            engine_id: "engine".to_string(),
+            commitment: commitment_of(verdict, nonce),
+            engine_type: "Automated".to_string(),
+            confidence: 0.9,
+            stake_amount: 1000,
+            transaction_hash: None,
+            committed_at: Utc::now(),
+            submission_id: None,
+            revealed_at: None,
+        }
+    }
+
+    #[test]
+    fn test_commitment_opens_with_its_verdict_and_nonce() {
+        let commitment = sealed("Malicious", NONCE);
+        assert!(is_commitment(&commitment.commitment));
+        assert!(commitment.opens("malicious", NONCE));
+        assert!(!commitment.opens("benign", NONCE));
+        assert!(!commitment.opens("malicious", "3f9a1c7e5b2d4f61"));
+    }
+
+    #[test]
+    fn test_short_nonces_do_not_open() {
+        assert!(!sealed("benign", "abc").opens("benign", "abc"));
+    }
+}
@@ -0,0 +1,208 @@
+// backend/bounty-manager/src/models/deposit.rs
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+/// Cached on-chain verification of a bounty's reward deposit
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DepositVerification {
+    pub bounty_id: Uuid,
+    /// `pending`, `verified` or `mismatch`
+    pub status: String,
+    pub deposit_tx_hash: Option<String>,
+    pub on_chain_id: Option<String>,
+    pub block_number: Option<i64>,
+    pub confirmations: i32,
+    /// Why the deposit is not verified yet, or does not match
+    pub detail: Option<String>,
+    pub checked_at: DateTime<Utc>,
+    pub verified_at: Option<DateTime<Utc>>,
+}
+
+impl DepositVerification {
+    pub const PENDING: &'static str = "pending";
+    pub const VERIFIED: &'static str = "verified";
+    pub const MISMATCH: &'static str = "mismatch";
+
+    pub async fn find(pool: &PgPool, bounty_id: Uuid) -> Result<Option<DepositVerification>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM bounty_deposit_verifications WHERE bounty_id = $1")
+            .bind(bounty_id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// Whether a funded escrow may activate its bounty. With verification
+    /// enabled that takes a verified deposit; until then the deposit is
+    /// queued for the blockchain sync to verify.
+    pub async fn confirmed(
+        pool: &PgPool,
+        enabled: bool,
+        bounty_id: Uuid,
+        deposit_tx_hash: Option<&str>,
+    ) -> Result<bool, sqlx::Error> {
+        if !enabled {
+            return Ok(true);
+        }
+        let verification = Self::track(pool, bounty_id, deposit_tx_hash).await?;
+        Ok(verification.status == Self::VERIFIED)
+    }
+
+    /// The other bounty a deposit transaction or on-chain bounty already
+    /// funds, if any
+    pub async fn funds_other(
+        pool: &PgPool,
+        bounty_id: Uuid,
+        deposit_tx_hash: Option<&str>,
+        on_chain_id: Option<&str>,
+    ) -> Result<Option<Uuid>, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT bounty_id FROM bounty_deposit_verifications
+            WHERE bounty_id <> $1
+              AND status <> 'mismatch'
+              AND (LOWER(deposit_tx_hash) = LOWER($2) OR on_chain_id = $3)
+            LIMIT 1
+            "#,
+        )
+        .bind(bounty_id)
+        .bind(deposit_tx_hash)
+        .bind(on_chain_id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Queue a deposit for verification. A new transaction hash restarts
+    /// verification of a deposit that did not match; one that already funds
+    /// another bounty is recorded as a mismatch.
+    pub async fn track(
+        pool: &PgPool,
+        bounty_id: Uuid,
+        deposit_tx_hash: Option<&str>,
+    ) -> Result<DepositVerification, sqlx::Error> {
+        if let Some(other) = Self::funds_other(pool, bounty_id, deposit_tx_hash, None).await? {
+            return sqlx::query_as(
+                r#"
+                INSERT INTO bounty_deposit_verifications (bounty_id, status, deposit_tx_hash, detail)
+                VALUES ($1, 'mismatch', $2, $3)
+                ON CONFLICT (bounty_id) DO UPDATE
+                SET status = 'mismatch', deposit_tx_hash = EXCLUDED.deposit_tx_hash,
+                    detail = EXCLUDED.detail, checked_at = NOW()
+                RETURNING *
+                "#,
+            )
+            .bind(bounty_id)
+            .bind(deposit_tx_hash)
+            .bind(format!("deposit transaction already funds bounty {}", other))
+            .fetch_one(pool)
+            .await;
+        }
+
+        sqlx::query_as(
+            r#"
+            INSERT INTO bounty_deposit_verifications (bounty_id, deposit_tx_hash)
+            VALUES ($1, $2)
+            ON CONFLICT (bounty_id) DO UPDATE
+            SET status = CASE
+                    WHEN EXCLUDED.deposit_tx_hash IS NOT NULL
+                         AND EXCLUDED.deposit_tx_hash IS DISTINCT FROM bounty_deposit_verifications.deposit_tx_hash
+                    THEN 'pending'
+                    ELSE bounty_deposit_verifications.status
+                END,
+                deposit_tx_hash = COALESCE(EXCLUDED.deposit_tx_hash, bounty_deposit_verifications.deposit_tx_hash)
+            RETURNING *
+            "#,
+        )
+        .bind(bounty_id)
+        .bind(deposit_tx_hash)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Pending verifications of bounties still awaiting funding, least
+    /// recently checked first
+    pub async fn pending(pool: &PgPool, limit: i64) -> Result<Vec<DepositVerification>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT v.* FROM bounty_deposit_verifications v
+            JOIN bounties b ON b.id = v.bounty_id
+            WHERE v.status = 'pending' AND b.status = 'PendingFunding'
+            ORDER BY v.checked_at
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Attach a `BountyCreated` transaction to the oldest pending
+    /// verification that lacks one, matching the bounty by creator and
+    /// artifact. A transaction already attached elsewhere is left alone.
+    pub async fn attach_transaction(
+        pool: &PgPool,
+        creator: &str,
+        artifact_hash: &str,
+        deposit_tx_hash: &str,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE bounty_deposit_verifications
+            SET deposit_tx_hash = $3
+            WHERE bounty_id = (
+                    SELECT v.bounty_id FROM bounty_deposit_verifications v
+                    JOIN bounties b ON b.id = v.bounty_id
+                    WHERE v.status = 'pending'
+                      AND v.deposit_tx_hash IS NULL
+                      AND LOWER(b.creator) = LOWER($1)
+                      AND LOWER(b.artifact_hash) = LOWER($2)
+                    ORDER BY b.created_at
+                    LIMIT 1
+                )
+              AND NOT EXISTS (
+                    SELECT 1 FROM bounty_deposit_verifications
+                    WHERE LOWER(deposit_tx_hash) = LOWER($3) AND status <> 'mismatch'
+                )
+            "#,
+        )
+        .bind(creator)
+        .bind(artifact_hash)
+        .bind(deposit_tx_hash)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Record the outcome of a check
+    pub async fn record(
+        pool: &PgPool,
+        bounty_id: Uuid,
+        status: &str,
+        on_chain_id: Option<&str>,
+        block_number: Option<i64>,
+        confirmations: i32,
+        detail: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE bounty_deposit_verifications
+            SET status = $2, on_chain_id = COALESCE($3, on_chain_id), block_number = $4,
+                confirmations = $5, detail = $6, checked_at = NOW(),
+                verified_at = CASE WHEN $2 = 'verified' THEN NOW() ELSE verified_at END
+            WHERE bounty_id = $1
+            "#,
+        )
+        .bind(bounty_id)
+        .bind(status)
+        .bind(on_chain_id)
+        .bind(block_number)
+        .bind(confirmations)
+        .bind(detail)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,214 @@
+// backend/bounty-manager/src/models/embargo.rs
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// Embargo on the verdicts of one bounty
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct VerdictEmbargo {
+    pub bounty_id: Uuid,
+    pub organization_id: Option<Uuid>,
+    pub creator_user_id: Option<Uuid>,
+    pub reason: Option<String>,
+    pub placed_by: Uuid,
+    /// `None` until released manually
+    pub embargo_until: Option<DateTime<Utc>>,
+    pub released_at: Option<DateTime<Utc>>,
+    pub released_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Account and organization behind a bounty's creator wallet
+#[derive(Debug, Clone, FromRow)]
+pub struct BountyCreator {
+    pub user_id: Option<Uuid>,
+    pub organization_id: Option<Uuid>,
+}
+
+impl VerdictEmbargo {
+    pub fn is_active_at(&self, now: DateTime<Utc>) -> bool {
+        self.released_at.is_none() && self.embargo_until.is_none_or(|until| until > now)
+    }
+
+    /// Whether a user may see verdicts despite the embargo: admins, the
+    /// creator and members of the creator's organization
+    pub fn grants_access(&self, user_id: Uuid, organization_id: Option<Uuid>, is_admin: bool) -> bool {
+        is_admin
+            || self.creator_user_id == Some(user_id)
+            || (organization_id.is_some() && organization_id == self.organization_id)
+    }
+
+    pub async fn find(pool: &PgPool, bounty_id: Uuid) -> Result<Option<VerdictEmbargo>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM bounty_verdict_embargoes WHERE bounty_id = $1")
+            .bind(bounty_id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// Embargoes in force for any of `bounty_ids`
+    pub async fn find_active(pool: &PgPool, bounty_ids: &[Uuid]) -> Result<Vec<VerdictEmbargo>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT * FROM bounty_verdict_embargoes
+            WHERE bounty_id = ANY($1)
+              AND released_at IS NULL
+              AND (embargo_until IS NULL OR embargo_until > NOW())
+            "#,
+        )
+        .bind(bounty_ids)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Place an embargo, or replace an existing one (including a released
+    /// or lapsed one) with a new end
+    pub async fn place(
+        pool: &PgPool,
+        bounty_id: Uuid,
+        creator: &BountyCreator,
+        reason: Option<&str>,
+        placed_by: Uuid,
+        embargo_until: Option<DateTime<Utc>>,
+    ) -> Result<VerdictEmbargo, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            INSERT INTO bounty_verdict_embargoes (
+                bounty_id, organization_id, creator_user_id, reason, placed_by, embargo_until
+            )
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (bounty_id) DO UPDATE
+            SET organization_id = EXCLUDED.organization_id,
+                creator_user_id = EXCLUDED.creator_user_id,
+                reason = EXCLUDED.reason,
+                placed_by = EXCLUDED.placed_by,
+                embargo_until = EXCLUDED.embargo_until,
+                released_at = NULL,
+                released_by = NULL,
+                updated_at = NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(bounty_id)
+        .bind(creator.organization_id)
+        .bind(creator.user_id)
+        .bind(reason)
+        .bind(placed_by)
+        .bind(embargo_until)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Lift an embargo early. Returns `None` if none is in force.
+    pub async fn release(pool: &PgPool, bounty_id: Uuid, released_by: Uuid) -> Result<Option<VerdictEmbargo>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            UPDATE bounty_verdict_embargoes
+            SET released_at = NOW(), released_by = $2, updated_at = NOW()
+            WHERE bounty_id = $1
+              AND released_at IS NULL
+              AND (embargo_until IS NULL OR embargo_until > NOW())
+            RETURNING *
+            "#,
+        )
+        .bind(bounty_id)
+        .bind(released_by)
+        .fetch_optional(pool)
+        .await
+    }
+}
+
+impl BountyCreator {
+    /// Resolve the creator wallet of a live or archived bounty. Returns
+    /// `None` if the bounty is unknown.
+    pub async fn of_bounty(pool: &PgPool, bounty_id: Uuid) -> Result<Option<BountyCreator>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT u.id AS user_id, m.organization_id
+            FROM (
+                SELECT creator FROM bounties WHERE id = $1
+                UNION ALL
+                SELECT creator FROM bounty_archive_summaries WHERE bounty_id = $1
+                LIMIT 1
+            ) b
+            LEFT JOIN users u ON LOWER(u.wallet_address) = LOWER(b.creator)
+            LEFT JOIN organization_members m ON m.user_id = u.id
+            "#,
+        )
+        .bind(bounty_id)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Organization a user belongs to, if any
+    pub async fn organization_of(pool: &PgPool, user_id: Uuid) -> Result<Option<Uuid>, sqlx::Error> {
+        sqlx::query_scalar("SELECT organization_id FROM organization_members WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await
+    }
+}
+
+/// Bounty ids among `embargoes` that `user` may not see verdicts for
+pub fn withheld_bounties(
+    embargoes: &[VerdictEmbargo],
+    user: Option<(Uuid, Option<Uuid>, bool)>,
+) -> HashSet<Uuid> {
+    embargoes
+        .iter()
+        .filter(|e| !user.is_some_and(|(id, org, admin)| e.grants_access(id, org, admin)))
+        .map(|e| e.bounty_id)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn embargo(until: Option<DateTime<Utc>>) -> VerdictEmbargo {
+        VerdictEmbargo {
+            bounty_id: Uuid::new_v4(),
+            organization_id: Some(Uuid::new_v4()),
+            creator_user_id: Some(Uuid::new_v4()),
+            reason: None,
+            placed_by: Uuid::new_v4(),
+            embargo_until: until,
+            released_at: None,
+            released_by: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_embargo_lapses_or_is_released() {
+        let now = Utc::now();
+        assert!(embargo(None).is_active_at(now));
+        assert!(embargo(Some(now + Duration::days(1))).is_active_at(now));
+        assert!(!embargo(Some(now - Duration::seconds(1))).is_active_at(now));
+
+        let mut released = embargo(None);
+        released.released_at = Some(now);
+        assert!(!released.is_active_at(now));
+    }
+
+    #[test]
+    fn test_access_limited_to_creator_org_and_admins() {
+        let e = embargo(None);
+        let outsider = Uuid::new_v4();
+
+        assert!(e.grants_access(outsider, None, true));
+        assert!(e.grants_access(e.creator_user_id.unwrap(), None, false));
+        assert!(e.grants_access(outsider, e.organization_id, false));
+        assert!(!e.grants_access(outsider, Some(Uuid::new_v4()), false));
+        assert!(!e.grants_access(outsider, None, false));
+
+        let withheld = withheld_bounties(std::slice::from_ref(&e), None);
+        assert!(withheld.contains(&e.bounty_id));
+        assert!(withheld_bounties(std::slice::from_ref(&e), Some((outsider, e.organization_id, false))).is_empty());
+    }
+}
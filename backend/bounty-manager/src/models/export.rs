@@ -0,0 +1,315 @@
+// backend/bounty-manager/src/models/export.rs
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+/// Live and archived bounties, submissions and payouts. Archived rows are
+/// read back through the live tables' row types, so columns added since
+/// they were archived come back NULL.
+const ALL_RECORDS: &str = r#"
+    WITH all_bounties AS (
+        SELECT id, creator, title, artifact_type, artifact_hash, status, reward_amount, currency,
+               created_at, deadline, FALSE AS archived
+        FROM bounties
+        WHERE creator = $1 AND created_at >= $2 AND created_at < $3
+        UNION ALL
+        SELECT b.id, b.creator, b.title, b.artifact_type, b.artifact_hash, b.status, b.reward_amount, b.currency,
+               b.created_at, b.deadline, TRUE
+        FROM bounty_archive_summaries sm
+        JOIN archived_bounties a ON a.id = sm.bounty_id
+        CROSS JOIN LATERAL jsonb_populate_record(NULL::bounties, a.data) b
+        WHERE sm.creator = $1 AND sm.created_at >= $2 AND sm.created_at < $3
+    ), all_submissions AS (
+        SELECT id, bounty_id, engine_id, verdict, confidence, stake_amount, status, submitted_at FROM submissions
+        UNION ALL
+        SELECT s.id, s.bounty_id, s.engine_id, s.verdict, s.confidence, s.stake_amount, s.status, s.submitted_at
+        FROM archived_submissions a
+        CROSS JOIN LATERAL jsonb_populate_record(NULL::submissions, a.data) s
+    ), all_payouts AS (
+        SELECT submission_id, amount, status, transaction_hash FROM payouts
+        UNION ALL
+        SELECT p.submission_id, p.amount, p.status, p.transaction_hash
+        FROM archived_payouts a
+        CROSS JOIN LATERAL jsonb_populate_record(NULL::payouts, a.data) p
+    )
+"#;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    /// One JSON object per line
+    Ndjson,
+}
+
+impl ExportFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Ndjson => "ndjson",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "csv" => Some(ExportFormat::Csv),
+            "ndjson" => Some(ExportFormat::Ndjson),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Ndjson => "application/x-ndjson",
+        }
+    }
+}
+
+/// One submission of an exported bounty, with its payouts; a bounty without
+/// submissions is one row with the submission columns empty
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ExportRow {
+    pub bounty_id: Uuid,
+    pub title: String,
+    pub artifact_type: String,
+    pub artifact_hash: Option<String>,
+    pub bounty_status: String,
+    pub reward_amount: i64,
+    pub currency: String,
+    pub created_at: DateTime<Utc>,
+    pub deadline: DateTime<Utc>,
+    pub archived: bool,
+    pub submission_id: Option<Uuid>,
+    pub engine_id: Option<String>,
+    pub verdict: Option<String>,
+    pub confidence: Option<f32>,
+    pub stake_amount: Option<i64>,
+    pub submission_status: Option<String>,
+    pub submitted_at: Option<DateTime<Utc>>,
+    /// Summed over the submission's payouts
+    pub payout_amount: Option<i64>,
+    /// Distinct payout statuses, `;`-separated
+    pub payout_status: Option<String>,
+    pub payout_transactions: Option<String>,
+}
+
+/// Where the next page of an export starts: after this bounty
+#[derive(Debug, Clone, Copy)]
+pub struct ExportCursor {
+    pub created_at: DateTime<Utc>,
+    pub bounty_id: Uuid,
+}
+
+impl ExportRow {
+    /// Rows of the next `limit` bounties of a creator created in the range,
+    /// oldest first
+    pub async fn page(
+        pool: &PgPool,
+        creator: &str,
+        range_start: DateTime<Utc>,
+        range_end: DateTime<Utc>,
+        after: Option<ExportCursor>,
+        limit: i64,
+    ) -> Result<Vec<ExportRow>, sqlx::Error> {
+        sqlx::query_as(&format!(
+            r#"{ALL_RECORDS}
+            , page AS (
+                SELECT * FROM all_bounties
+                WHERE $4::TIMESTAMPTZ IS NULL OR (created_at, id) > ($4, $5)
+                ORDER BY created_at, id
+                LIMIT $6
+            )
+            SELECT p.id AS bounty_id, p.title, p.artifact_type, p.artifact_hash, p.status AS bounty_status,
+                   p.reward_amount, p.currency, p.created_at, p.deadline, p.archived,
+                   s.id AS submission_id, s.engine_id, s.verdict, s.confidence, s.stake_amount,
+                   s.status AS submission_status, s.submitted_at,
+                   pay.amount AS payout_amount, pay.status AS payout_status, pay.transactions AS payout_transactions
+            FROM page p
+            LEFT JOIN all_submissions s ON s.bounty_id = p.id
+            LEFT JOIN LATERAL (
+                SELECT SUM(amount)::BIGINT AS amount,
+                       string_agg(DISTINCT status, ';') AS status,
+                       string_agg(transaction_hash, ';') AS transactions
+                FROM all_payouts
+                WHERE submission_id = s.id
+            ) pay ON TRUE
+            ORDER BY p.created_at, p.id, s.submitted_at, s.id
+            "#
+        ))
+        .bind(creator)
+        .bind(range_start)
+        .bind(range_end)
+        .bind(after.map(|cursor| cursor.created_at))
+        .bind(after.map(|cursor| cursor.bounty_id))
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Rows an export of the range would have
+    pub async fn count(
+        pool: &PgPool,
+        creator: &str,
+        range_start: DateTime<Utc>,
+        range_end: DateTime<Utc>,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(&format!(
+            r#"{ALL_RECORDS}
+            SELECT COUNT(*) FROM all_bounties b
+            LEFT JOIN all_submissions s ON s.bounty_id = b.id
+            "#
+        ))
+        .bind(creator)
+        .bind(range_start)
+        .bind(range_end)
+        .fetch_one(pool)
+        .await
+    }
+}
+
+/// An export generated in the background
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BountyExport {
+    pub id: Uuid,
+    pub creator: String,
+    pub format: String,
+    pub range_start: DateTime<Utc>,
+    pub range_end: DateTime<Utc>,
+    /// `pending`, `running`, `completed`, `failed` or `expired`
+    pub status: String,
+    pub row_count: Option<i64>,
+    #[serde(skip_serializing)]
+    pub file_path: Option<String>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl BountyExport {
+    pub const COMPLETED: &'static str = "completed";
+
+    pub async fn create(
+        pool: &PgPool,
+        creator: &str,
+        format: ExportFormat,
+        range_start: DateTime<Utc>,
+        range_end: DateTime<Utc>,
+    ) -> Result<BountyExport, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            INSERT INTO bounty_exports (id, creator, format, range_start, range_end)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(creator)
+        .bind(format.as_str())
+        .bind(range_start)
+        .bind(range_end)
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find(pool: &PgPool, id: Uuid) -> Result<Option<BountyExport>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM bounty_exports WHERE id = $1")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    /// A creator's exports, newest first
+    pub async fn list(pool: &PgPool, creator: &str, limit: i64) -> Result<Vec<BountyExport>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM bounty_exports WHERE creator = $1 ORDER BY created_at DESC LIMIT $2")
+            .bind(creator)
+            .bind(limit)
+            .fetch_all(pool)
+            .await
+    }
+
+    /// Pending exports the caller may generate now, marked running. Exports
+    /// left running by a worker that stopped are taken over after
+    /// `stale_after`.
+    pub async fn claim(
+        pool: &PgPool,
+        stale_after: chrono::Duration,
+        limit: i64,
+    ) -> Result<Vec<BountyExport>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            UPDATE bounty_exports SET status = 'running', started_at = NOW()
+            WHERE id IN (
+                SELECT id FROM bounty_exports
+                WHERE status = 'pending' OR (status = 'running' AND started_at < $1)
+                ORDER BY created_at
+                LIMIT $2
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING *
+            "#,
+        )
+        .bind(Utc::now() - stale_after)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn complete(
+        pool: &PgPool,
+        id: Uuid,
+        row_count: i64,
+        file_path: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE bounty_exports
+            SET status = 'completed', row_count = $2, file_path = $3, completed_at = NOW(), expires_at = $4
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(row_count)
+        .bind(file_path)
+        .bind(expires_at)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn fail(pool: &PgPool, id: Uuid, error: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE bounty_exports SET status = 'failed', error = $2, completed_at = NOW() WHERE id = $1")
+            .bind(id)
+            .bind(error)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Completed exports whose files are past their expiry
+    pub async fn expired(pool: &PgPool, now: DateTime<Utc>, limit: i64) -> Result<Vec<BountyExport>, sqlx::Error> {
+        sqlx::query_as(
+            "SELECT * FROM bounty_exports WHERE status = 'completed' AND expires_at <= $1 ORDER BY expires_at LIMIT $2",
+        )
+        .bind(now)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn mark_expired(pool: &PgPool, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE bounty_exports SET status = 'expired', file_path = NULL WHERE id = $1")
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -4,8 +4,21 @@ pub mod bounty;
 pub mod submission;
 pub mod payout;
 pub mod reputation;
+pub mod archive;
+pub mod embargo;
+pub mod tag;
+pub mod standing;
+pub mod webhook;
+pub mod deposit;
+pub mod analytics;
+pub mod participant;
+pub mod commitment;
+pub mod moderation;
+pub mod export;
+pub mod calibration;
 
 pub use bounty::*;
 pub use submission::*;
 pub use payout::*;
 pub use reputation::*;
+pub use archive::*;
@@ -0,0 +1,301 @@
+// backend/bounty-manager/src/models/moderation.rs
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgExecutor, PgPool};
+use uuid::Uuid;
+
+/// Why a user reported a bounty
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportCategory {
+    Csam,
+    IllegalContent,
+    PersonalData,
+    Harassment,
+    Spam,
+    Other,
+}
+
+impl ReportCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReportCategory::Csam => "csam",
+            ReportCategory::IllegalContent => "illegal_content",
+            ReportCategory::PersonalData => "personal_data",
+            ReportCategory::Harassment => "harassment",
+            ReportCategory::Spam => "spam",
+            ReportCategory::Other => "other",
+        }
+    }
+
+    /// Severe reports go to the head of the review queue
+    pub fn is_severe(&self) -> bool {
+        matches!(self, ReportCategory::Csam | ReportCategory::IllegalContent)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BountyReport {
+    pub id: Uuid,
+    pub bounty_id: Uuid,
+    pub reporter_id: Uuid,
+    pub category: String,
+    pub details: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A bounty in, or decided by, moderation review
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ModerationCase {
+    pub bounty_id: Uuid,
+    /// `pending`, `held`, `approved` or `taken_down`
+    pub status: String,
+    pub report_count: i32,
+    pub severe: bool,
+    pub flags: Vec<String>,
+    /// Status an approval restores to a held bounty
+    pub held_from_status: Option<String>,
+    pub opened_at: DateTime<Utc>,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub decided_by: Option<Uuid>,
+}
+
+/// One entry of the moderation audit trail
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ModerationAction {
+    pub id: Uuid,
+    pub bounty_id: Uuid,
+    /// `hold`, `approve` or `takedown`
+    pub action: String,
+    /// `None` for automatic holds
+    pub moderator_id: Option<Uuid>,
+    pub reason: Option<String>,
+    pub previous_status: String,
+    pub new_status: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl BountyReport {
+    /// Record a report; `None` if the reporter already reported the bounty
+    pub async fn create<'e, E: PgExecutor<'e>>(
+        executor: E,
+        bounty_id: Uuid,
+        reporter_id: Uuid,
+        category: ReportCategory,
+        details: Option<&str>,
+    ) -> Result<Option<BountyReport>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            INSERT INTO bounty_reports (id, bounty_id, reporter_id, category, details)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (bounty_id, reporter_id) DO NOTHING
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(bounty_id)
+        .bind(reporter_id)
+        .bind(category.as_str())
+        .bind(details)
+        .fetch_optional(executor)
+        .await
+    }
+
+    pub async fn list(pool: &PgPool, bounty_id: Uuid) -> Result<Vec<BountyReport>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM bounty_reports WHERE bounty_id = $1 ORDER BY created_at")
+            .bind(bounty_id)
+            .fetch_all(pool)
+            .await
+    }
+}
+
+impl ModerationCase {
+    pub const PENDING: &'static str = "pending";
+    pub const HELD: &'static str = "held";
+    pub const APPROVED: &'static str = "approved";
+    pub const TAKEN_DOWN: &'static str = "taken_down";
+
+    pub async fn find<'e, E: PgExecutor<'e>>(
+        executor: E,
+        bounty_id: Uuid,
+    ) -> Result<Option<ModerationCase>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM bounty_moderation_cases WHERE bounty_id = $1")
+            .bind(bounty_id)
+            .fetch_optional(executor)
+            .await
+    }
+
+    /// Count a new report, opening a pending case if the bounty has none.
+    /// Decided cases keep their decision.
+    pub async fn add_report<'e, E: PgExecutor<'e>>(
+        executor: E,
+        bounty_id: Uuid,
+        severe: bool,
+    ) -> Result<ModerationCase, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            INSERT INTO bounty_moderation_cases (bounty_id, report_count, severe)
+            VALUES ($1, 1, $2)
+            ON CONFLICT (bounty_id) DO UPDATE
+            SET report_count = bounty_moderation_cases.report_count + 1,
+                severe = bounty_moderation_cases.severe OR EXCLUDED.severe
+            RETURNING *
+            "#,
+        )
+        .bind(bounty_id)
+        .bind(severe)
+        .fetch_one(executor)
+        .await
+    }
+
+    /// Open cases, severe ones and then the oldest first
+    pub async fn queue(
+        pool: &PgPool,
+        status: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<ModerationCase>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT * FROM bounty_moderation_cases
+            WHERE status IN ('pending', 'held') AND ($1::TEXT IS NULL OR status = $1)
+            ORDER BY severe DESC, opened_at
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(status)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Mark the case held, remembering the status the bounty was held from
+    /// and adding the heuristics it matched. A re-held case is reopened.
+    pub async fn hold<'e, E: PgExecutor<'e>>(
+        executor: E,
+        bounty_id: Uuid,
+        held_from_status: &str,
+        flags: &[&str],
+    ) -> Result<ModerationCase, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            INSERT INTO bounty_moderation_cases (bounty_id, status, flags, held_from_status)
+            VALUES ($1, 'held', $2, $3)
+            ON CONFLICT (bounty_id) DO UPDATE
+            SET status = 'held',
+                flags = ARRAY(SELECT DISTINCT unnest(bounty_moderation_cases.flags || EXCLUDED.flags)),
+                held_from_status = EXCLUDED.held_from_status,
+                decided_at = NULL,
+                decided_by = NULL
+            RETURNING *
+            "#,
+        )
+        .bind(bounty_id)
+        .bind(flags)
+        .bind(held_from_status)
+        .fetch_one(executor)
+        .await
+    }
+
+    /// Record a moderator's approval or takedown
+    pub async fn decide<'e, E: PgExecutor<'e>>(
+        executor: E,
+        bounty_id: Uuid,
+        status: &str,
+        moderator_id: Uuid,
+    ) -> Result<ModerationCase, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            INSERT INTO bounty_moderation_cases (bounty_id, status, decided_at, decided_by)
+            VALUES ($1, $2, NOW(), $3)
+            ON CONFLICT (bounty_id) DO UPDATE
+            SET status = EXCLUDED.status, decided_at = NOW(), decided_by = EXCLUDED.decided_by
+            RETURNING *
+            "#,
+        )
+        .bind(bounty_id)
+        .bind(status)
+        .bind(moderator_id)
+        .fetch_one(executor)
+        .await
+    }
+}
+
+impl ModerationAction {
+    pub const HOLD: &'static str = "hold";
+    pub const APPROVE: &'static str = "approve";
+    pub const TAKEDOWN: &'static str = "takedown";
+
+    pub async fn record<'e, E: PgExecutor<'e>>(
+        executor: E,
+        bounty_id: Uuid,
+        action: &str,
+        moderator_id: Option<Uuid>,
+        reason: Option<&str>,
+        previous_status: &str,
+        new_status: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO bounty_moderation_actions
+                (id, bounty_id, action, moderator_id, reason, previous_status, new_status)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(bounty_id)
+        .bind(action)
+        .bind(moderator_id)
+        .bind(reason)
+        .bind(previous_status)
+        .bind(new_status)
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    /// A bounty's audit trail, oldest first
+    pub async fn list(pool: &PgPool, bounty_id: Uuid) -> Result<Vec<ModerationAction>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM bounty_moderation_actions WHERE bounty_id = $1 ORDER BY created_at")
+            .bind(bounty_id)
+            .fetch_all(pool)
+            .await
+    }
+}
+
+/// Artifacts of taken-down bounties
+pub struct BlockedArtifact;
+
+impl BlockedArtifact {
+    pub async fn is_blocked(pool: &PgPool, artifact_hash: &str) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM moderation_blocked_artifacts WHERE artifact_hash = LOWER($1))")
+            .bind(artifact_hash)
+            .fetch_one(pool)
+            .await
+    }
+
+    pub async fn block<'e, E: PgExecutor<'e>>(
+        executor: E,
+        artifact_hash: &str,
+        bounty_id: Uuid,
+        blocked_by: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO moderation_blocked_artifacts (artifact_hash, bounty_id, blocked_by)
+            VALUES (LOWER($1), $2, $3)
+            ON CONFLICT (artifact_hash) DO NOTHING
+            "#,
+        )
+        .bind(artifact_hash)
+        .bind(bounty_id)
+        .bind(blocked_by)
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+}
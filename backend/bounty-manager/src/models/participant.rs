@@ -0,0 +1,172 @@
+// backend/bounty-manager/src/models/participant.rs
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgExecutor, PgPool};
+use uuid::Uuid;
+
+/// An engine taking part in a bounty
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BountyParticipant {
+    pub bounty_id: Uuid,
+    pub engine_id: String,
+    /// `joined`, `submitted` or `withdrawn`
+    pub status: String,
+    pub wallet_address: Option<String>,
+    pub stake_amount: i64,
+    pub stake_id: Option<Uuid>,
+    pub joined_at: DateTime<Utc>,
+    pub submitted_at: Option<DateTime<Utc>>,
+    pub withdrawn_at: Option<DateTime<Utc>>,
+}
+
+/// A participant with its submission, if any
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ParticipantEntry {
+    pub engine_id: String,
+    pub status: String,
+    pub stake_amount: i64,
+    pub joined_at: DateTime<Utc>,
+    pub submitted_at: Option<DateTime<Utc>>,
+    pub withdrawn_at: Option<DateTime<Utc>>,
+    pub submission_id: Option<Uuid>,
+    pub verdict: Option<String>,
+    pub confidence: Option<f32>,
+}
+
+impl ParticipantEntry {
+    /// Drop the verdict, e.g. while other engines could still follow it
+    pub fn without_verdict(mut self) -> Self {
+        self.verdict = None;
+        self.confidence = None;
+        self
+    }
+}
+
+impl BountyParticipant {
+    pub const JOINED: &'static str = "joined";
+    pub const SUBMITTED: &'static str = "submitted";
+
+    pub async fn find<'e, E: PgExecutor<'e>>(
+        executor: E,
+        bounty_id: Uuid,
+        engine_id: &str,
+    ) -> Result<Option<BountyParticipant>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM bounty_participants WHERE bounty_id = $1 AND engine_id = $2")
+            .bind(bounty_id)
+            .bind(engine_id)
+            .fetch_optional(executor)
+            .await
+    }
+
+    /// Engines holding a seat on a bounty: joined or submitted
+    pub async fn seated<'e, E: PgExecutor<'e>>(executor: E, bounty_id: Uuid) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT engine_id FROM bounty_participants WHERE bounty_id = $1 AND status IN ('joined', 'submitted')",
+        )
+        .bind(bounty_id)
+        .fetch_all(executor)
+        .await
+    }
+
+    /// Record an engine joining, or rejoining after a withdrawal; `None` if
+    /// it already holds a seat
+    pub async fn join<'e, E: PgExecutor<'e>>(
+        executor: E,
+        bounty_id: Uuid,
+        engine_id: &str,
+        wallet_address: &str,
+        stake_amount: i64,
+        stake_id: Uuid,
+    ) -> Result<Option<BountyParticipant>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            INSERT INTO bounty_participants (bounty_id, engine_id, wallet_address, stake_amount, stake_id)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (bounty_id, engine_id) DO UPDATE
+            SET status = 'joined', wallet_address = EXCLUDED.wallet_address,
+                stake_amount = EXCLUDED.stake_amount, stake_id = EXCLUDED.stake_id,
+                joined_at = NOW(), withdrawn_at = NULL
+            WHERE bounty_participants.status = 'withdrawn'
+            RETURNING *
+            "#,
+        )
+        .bind(bounty_id)
+        .bind(engine_id)
+        .bind(wallet_address)
+        .bind(stake_amount)
+        .bind(stake_id)
+        .fetch_optional(executor)
+        .await
+    }
+
+    /// Mark an engine as submitted, seating engines that submit without
+    /// joining first with the stake locked as they submit
+    pub async fn mark_submitted<'e, E: PgExecutor<'e>>(
+        executor: E,
+        bounty_id: Uuid,
+        engine_id: &str,
+        stake_amount: i64,
+        wallet_address: Option<&str>,
+        stake_id: Option<Uuid>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO bounty_participants (bounty_id, engine_id, status, wallet_address, stake_amount, stake_id, submitted_at)
+            VALUES ($1, $2, 'submitted', $3, $4, $5, NOW())
+            ON CONFLICT (bounty_id, engine_id) DO UPDATE
+            SET status = 'submitted', submitted_at = NOW(), withdrawn_at = NULL,
+                wallet_address = COALESCE(EXCLUDED.wallet_address, bounty_participants.wallet_address),
+                stake_amount = GREATEST(bounty_participants.stake_amount, EXCLUDED.stake_amount),
+                stake_id = COALESCE(EXCLUDED.stake_id, bounty_participants.stake_id)
+            "#,
+        )
+        .bind(bounty_id)
+        .bind(engine_id)
+        .bind(wallet_address)
+        .bind(stake_amount)
+        .bind(stake_id)
+        .execute(executor)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Withdraw an engine that has not submitted; `None` if it holds no
+    /// such seat
+    pub async fn withdraw<'e, E: PgExecutor<'e>>(
+        executor: E,
+        bounty_id: Uuid,
+        engine_id: &str,
+    ) -> Result<Option<BountyParticipant>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            UPDATE bounty_participants
+            SET status = 'withdrawn', withdrawn_at = NOW()
+            WHERE bounty_id = $1 AND engine_id = $2 AND status = 'joined'
+            RETURNING *
+            "#,
+        )
+        .bind(bounty_id)
+        .bind(engine_id)
+        .fetch_optional(executor)
+        .await
+    }
+
+    /// A bounty's participants in the order they joined
+    pub async fn list(pool: &PgPool, bounty_id: Uuid) -> Result<Vec<ParticipantEntry>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT p.engine_id, p.status, p.stake_amount, p.joined_at, p.submitted_at, p.withdrawn_at,
+                   s.id AS submission_id, s.verdict, s.confidence
+            FROM bounty_participants p
+            LEFT JOIN submissions s ON s.bounty_id = p.bounty_id AND s.engine_id = p.engine_id
+            WHERE p.bounty_id = $1
+            ORDER BY p.joined_at, p.engine_id
+            "#,
+        )
+        .bind(bounty_id)
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -0,0 +1,411 @@
+// backend/bounty-manager/src/models/standing.rs
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use super::bounty::BountyModel;
+use super::submission::SubmissionModel;
+
+/// What a watch rule's pattern is matched against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchKind {
+    /// Leading hex digits of an artifact's hash
+    HashPrefix,
+    /// A malware or YARA family reported by an engine's analysis
+    YaraFamily,
+    /// A domain of an artifact's URL; `*.example.com` for subdomains only
+    DomainPattern,
+}
+
+impl WatchKind {
+    /// Name stored in `standing_bounties.watch_kind`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WatchKind::HashPrefix => "hash_prefix",
+            WatchKind::YaraFamily => "yara_family",
+            WatchKind::DomainPattern => "domain_pattern",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<WatchKind> {
+        match value {
+            "hash_prefix" => Some(WatchKind::HashPrefix),
+            "yara_family" => Some(WatchKind::YaraFamily),
+            "domain_pattern" => Some(WatchKind::DomainPattern),
+            _ => None,
+        }
+    }
+}
+
+/// Which new artifacts spawn a child of a watching standing bounty
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WatchRule {
+    pub kind: WatchKind,
+    pub pattern: String,
+}
+
+impl WatchRule {
+    /// Lowercase the pattern and check it suits the rule's kind
+    pub fn normalized(self) -> Result<WatchRule, String> {
+        let pattern = self.pattern.trim().to_ascii_lowercase();
+        let valid = match self.kind {
+            WatchKind::HashPrefix => {
+                (4..=128).contains(&pattern.len()) && pattern.chars().all(|c| c.is_ascii_hexdigit())
+            }
+            WatchKind::YaraFamily => !pattern.is_empty() && pattern.len() <= 100,
+            WatchKind::DomainPattern => {
+                let domain = pattern.strip_prefix("*.").unwrap_or(&pattern);
+                domain.contains('.')
+                    && domain.len() <= 253
+                    && domain
+                        .split('.')
+                        .all(|label| !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'))
+            }
+        };
+        if valid {
+            Ok(WatchRule { kind: self.kind, pattern })
+        } else {
+            Err(format!("invalid {} pattern {:?}", self.kind.as_str(), self.pattern))
+        }
+    }
+
+    /// Whether an artifact hash such as `sha256:ab12…` starts with the prefix
+    pub fn matches_hash(&self, hash: &str) -> bool {
+        let digest = hash.rsplit(':').next().unwrap_or(hash).trim().to_ascii_lowercase();
+        self.kind == WatchKind::HashPrefix && digest.starts_with(&self.pattern)
+    }
+
+    /// Whether the host of an artifact URL (or a bare domain) matches
+    pub fn matches_url(&self, url: &str) -> bool {
+        if self.kind != WatchKind::DomainPattern {
+            return false;
+        }
+        let host = url_host(url);
+        match self.pattern.strip_prefix("*.") {
+            Some(parent) => host.ends_with(&format!(".{}", parent)),
+            None => host == self.pattern || host.ends_with(&format!(".{}", self.pattern)),
+        }
+    }
+
+    /// Whether any reported family is the watched one
+    pub fn matches_family<'a>(&self, mut families: impl Iterator<Item = &'a str>) -> bool {
+        self.kind == WatchKind::YaraFamily && families.any(|family| family.trim().eq_ignore_ascii_case(&self.pattern))
+    }
+}
+
+/// Lowercase host of a URL, without scheme, credentials, port or path
+fn url_host(url: &str) -> String {
+    let rest = url.trim().split_once("://").map_or(url.trim(), |(_, rest)| rest);
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = authority.rsplit('@').next().unwrap_or_default();
+    host.split(':').next().unwrap_or_default().trim_end_matches('.').to_ascii_lowercase()
+}
+
+/// A bounty template that spawns child bounties on a schedule or for new
+/// artifacts matching a watch rule
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct StandingBounty {
+    pub id: Uuid,
+    pub creator: String,
+    pub title: String,
+    pub description: String,
+    pub reward_amount: i64,
+    pub currency: String,
+    pub min_stake: i64,
+    pub max_participants: Option<i32>,
+    pub min_reputation: Option<i32>,
+    pub deadline_hours: i32,
+    pub consensus_threshold: f32,
+    pub tags: Vec<String>,
+    /// `schedule` or `watch`
+    pub trigger_type: String,
+    /// The artifact a schedule re-opens
+    pub artifact_type: Option<String>,
+    pub artifact_data: Option<sqlx::types::JsonValue>,
+    pub interval_hours: Option<i32>,
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub watch_kind: Option<String>,
+    pub watch_pattern: Option<String>,
+    pub watched_until: Option<DateTime<Utc>>,
+    pub max_children: Option<i32>,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A bounty spawned by a standing bounty
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct StandingBountyChild {
+    pub standing_bounty_id: Uuid,
+    /// The schedule slot or matched artifact that spawned it
+    pub trigger_ref: String,
+    pub bounty_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+impl StandingBounty {
+    pub const SCHEDULE: &'static str = "schedule";
+    pub const WATCH: &'static str = "watch";
+
+    pub fn watch_rule(&self) -> Option<WatchRule> {
+        Some(WatchRule {
+            kind: WatchKind::parse(self.watch_kind.as_deref()?)?,
+            pattern: self.watch_pattern.clone()?,
+        })
+    }
+
+    pub async fn create(pool: &PgPool, standing: &StandingBounty) -> Result<StandingBounty, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            INSERT INTO standing_bounties (
+                id, creator, title, description, reward_amount, currency, min_stake,
+                max_participants, min_reputation, deadline_hours, consensus_threshold, tags,
+                trigger_type, artifact_type, artifact_data, interval_hours, next_run_at,
+                watch_kind, watch_pattern, watched_until, max_children, enabled,
+                created_at, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17,
+                    $18, $19, $20, $21, $22, $23, $24)
+            RETURNING *
+            "#,
+        )
+        .bind(standing.id)
+        .bind(&standing.creator)
+        .bind(&standing.title)
+        .bind(&standing.description)
+        .bind(standing.reward_amount)
+        .bind(&standing.currency)
+        .bind(standing.min_stake)
+        .bind(standing.max_participants)
+        .bind(standing.min_reputation)
+        .bind(standing.deadline_hours)
+        .bind(standing.consensus_threshold)
+        .bind(&standing.tags)
+        .bind(&standing.trigger_type)
+        .bind(&standing.artifact_type)
+        .bind(&standing.artifact_data)
+        .bind(standing.interval_hours)
+        .bind(standing.next_run_at)
+        .bind(&standing.watch_kind)
+        .bind(&standing.watch_pattern)
+        .bind(standing.watched_until)
+        .bind(standing.max_children)
+        .bind(standing.enabled)
+        .bind(standing.created_at)
+        .bind(standing.updated_at)
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find(pool: &PgPool, id: Uuid) -> Result<Option<StandingBounty>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM standing_bounties WHERE id = $1")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    pub async fn list_by_creator(pool: &PgPool, creator: &str) -> Result<Vec<StandingBounty>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM standing_bounties WHERE creator = $1 ORDER BY created_at DESC")
+            .bind(creator)
+            .fetch_all(pool)
+            .await
+    }
+
+    /// Pause or resume spawning. A resumed schedule runs at its next slot
+    /// from now rather than catching up.
+    pub async fn set_enabled(pool: &PgPool, id: Uuid, enabled: bool) -> Result<Option<StandingBounty>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            UPDATE standing_bounties
+            SET enabled = $2,
+                next_run_at = CASE
+                    WHEN $2 AND trigger_type = 'schedule' AND next_run_at < NOW() THEN NOW()
+                    ELSE next_run_at
+                END,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(id)
+        .bind(enabled)
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Enabled schedules whose next run is due
+    pub async fn due_schedules(pool: &PgPool, now: DateTime<Utc>, limit: i64) -> Result<Vec<StandingBounty>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT * FROM standing_bounties
+            WHERE enabled AND trigger_type = 'schedule' AND next_run_at <= $1
+            ORDER BY next_run_at
+            LIMIT $2
+            "#,
+        )
+        .bind(now)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Enabled watches
+    pub async fn active_watches(pool: &PgPool) -> Result<Vec<StandingBounty>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM standing_bounties WHERE enabled AND trigger_type = 'watch' ORDER BY created_at")
+            .fetch_all(pool)
+            .await
+    }
+
+    pub async fn schedule_next_run(pool: &PgPool, id: Uuid, next_run_at: DateTime<Utc>) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE standing_bounties SET next_run_at = $2, updated_at = NOW() WHERE id = $1")
+            .bind(id)
+            .bind(next_run_at)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Record that artifacts up to `watched_until` have been matched
+    pub async fn advance_watch(pool: &PgPool, id: Uuid, watched_until: DateTime<Utc>) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE standing_bounties
+            SET watched_until = GREATEST(COALESCE(watched_until, $2), $2), updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(watched_until)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn disable(pool: &PgPool, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE standing_bounties SET enabled = FALSE, updated_at = NOW() WHERE id = $1")
+            .bind(id)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Reserve a trigger for a child bounty; `false` if it already spawned one
+    pub async fn claim_child(pool: &PgPool, id: Uuid, trigger_ref: &str, bounty_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO standing_bounty_children (standing_bounty_id, trigger_ref, bounty_id)
+            VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(id)
+        .bind(trigger_ref)
+        .bind(bounty_id)
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Give up a claimed trigger whose child could not be opened, so it is
+    /// retried
+    pub async fn release_child(pool: &PgPool, id: Uuid, trigger_ref: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM standing_bounty_children WHERE standing_bounty_id = $1 AND trigger_ref = $2")
+            .bind(id)
+            .bind(trigger_ref)
+            .execute(pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn children(pool: &PgPool, id: Uuid) -> Result<Vec<StandingBountyChild>, sqlx::Error> {
+        sqlx::query_as(
+            "SELECT * FROM standing_bounty_children WHERE standing_bounty_id = $1 ORDER BY created_at DESC",
+        )
+        .bind(id)
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn child_count(pool: &PgPool, id: Uuid) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar("SELECT COUNT(*) FROM standing_bounty_children WHERE standing_bounty_id = $1")
+            .bind(id)
+            .fetch_one(pool)
+            .await
+    }
+
+    /// Bounties created after `since`, oldest first
+    pub async fn bounties_since(pool: &PgPool, since: DateTime<Utc>, limit: i64) -> Result<Vec<BountyModel>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM bounties WHERE created_at > $1 ORDER BY created_at LIMIT $2")
+            .bind(since)
+            .bind(limit)
+            .fetch_all(pool)
+            .await
+    }
+
+    /// Submissions made after `since`, oldest first
+    pub async fn submissions_since(
+        pool: &PgPool,
+        since: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<SubmissionModel>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM submissions WHERE submitted_at > $1 ORDER BY submitted_at LIMIT $2")
+            .bind(since)
+            .bind(limit)
+            .fetch_all(pool)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(kind: WatchKind, pattern: &str) -> WatchRule {
+        WatchRule { kind, pattern: pattern.to_string() }.normalized().unwrap()
+    }
+
+    #[test]
+    fn test_pattern_validation() {
+        assert!(WatchRule { kind: WatchKind::HashPrefix, pattern: "ab1".into() }.normalized().is_err());
+        assert!(WatchRule { kind: WatchKind::HashPrefix, pattern: "xyz123".into() }.normalized().is_err());
+        assert!(WatchRule { kind: WatchKind::DomainPattern, pattern: "localhost".into() }.normalized().is_err());
+        assert_eq!(rule(WatchKind::HashPrefix, " ABCD12 ").pattern, "abcd12");
+        assert_eq!(rule(WatchKind::DomainPattern, "*.Example.com").pattern, "*.example.com");
+    }
+
+    #[test]
+    fn test_hash_prefix_ignores_algorithm() {
+        let watch = rule(WatchKind::HashPrefix, "deadbeef");
+        assert!(watch.matches_hash("sha256:DEADBEEF0011"));
+        assert!(watch.matches_hash("deadbeef"));
+        assert!(!watch.matches_hash("sha256:00deadbeef"));
+    }
+
+    #[test]
+    fn test_domain_pattern() {
+        let domain = rule(WatchKind::DomainPattern, "example.com");
+        assert!(domain.matches_url("https://user@login.example.com:8443/path?q=1"));
+        assert!(domain.matches_url("example.com"));
+        assert!(!domain.matches_url("https://notexample.com/"));
+
+        let subdomains = rule(WatchKind::DomainPattern, "*.example.com");
+        assert!(subdomains.matches_url("http://a.b.example.com"));
+        assert!(!subdomains.matches_url("http://example.com"));
+    }
+
+    #[test]
+    fn test_family_match() {
+        let family = rule(WatchKind::YaraFamily, "Emotet");
+        assert!(family.matches_family(["Trojan.Generic", "emotet"].into_iter()));
+        assert!(!family.matches_family(["Emotet.B"].into_iter()));
+        assert!(!rule(WatchKind::HashPrefix, "abcd").matches_family(["abcd"].into_iter()));
+    }
+}
@@ -2,7 +2,7 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, PgPool};
+use sqlx::{FromRow, PgExecutor, PgPool};
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -23,7 +23,10 @@ pub struct SubmissionModel {
 }
 
 impl SubmissionModel {
-    pub async fn create(pool: &PgPool, submission: &SubmissionModel) -> Result<SubmissionModel, sqlx::Error> {
+    pub async fn create<'e, E: PgExecutor<'e>>(
+        executor: E,
+        submission: &SubmissionModel,
+    ) -> Result<SubmissionModel, sqlx::Error> {
         let record = sqlx::query_as::<_, SubmissionModel>(
             r#"
             INSERT INTO submissions (
@@ -48,7 +51,7 @@ impl SubmissionModel {
         .bind(&submission.submitted_at)
         .bind(&submission.processed_at)
         .bind(submission.accuracy_score)
-        .fetch_one(pool)
+        .fetch_one(executor)
         .await?;
 
         Ok(record)
@@ -76,6 +79,17 @@ impl SubmissionModel {
         Ok(records)
     }
 
+    /// Engines that submitted to a bounty
+    pub async fn engines_for_bounty<'e, E: PgExecutor<'e>>(
+        executor: E,
+        bounty_id: Uuid,
+    ) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar("SELECT engine_id FROM submissions WHERE bounty_id = $1")
+            .bind(bounty_id)
+            .fetch_all(executor)
+            .await
+    }
+
     pub async fn find_by_engine(pool: &PgPool, engine_id: &str) -> Result<Vec<SubmissionModel>, sqlx::Error> {
         let records = sqlx::query_as::<_, SubmissionModel>(
             "SELECT * FROM submissions WHERE engine_id = $1 ORDER BY submitted_at DESC"
@@ -0,0 +1,277 @@
+// backend/bounty-manager/src/models/tag.rs
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use super::bounty::BountyModel;
+
+/// Most tags a single bounty may carry
+pub const MAX_TAGS_PER_BOUNTY: usize = 10;
+
+/// A tag of the bounty taxonomy, such as `ransomware` or `phishing`
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BountyTag {
+    pub slug: String,
+    pub name: String,
+    /// Grouping in the taxonomy, such as `malware` or `attack`
+    pub category: String,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Bounty and submission activity under one tag
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct TagStats {
+    pub slug: String,
+    pub name: String,
+    pub category: String,
+    pub bounty_count: i64,
+    /// Active or in-progress bounties still taking submissions
+    pub open_bounties: i64,
+    /// In wei
+    pub total_reward: i64,
+    pub submission_count: i64,
+    /// The requested engine's submissions under the tag
+    pub engine_submissions: i64,
+    /// Those of the engine's submissions that consensus has ruled on
+    pub engine_finalized: i64,
+    /// Those that matched consensus
+    pub engine_correct: i64,
+}
+
+impl TagStats {
+    /// Share of the engine's ruled-on submissions that matched consensus
+    pub fn engine_accuracy(&self) -> Option<f64> {
+        (self.engine_finalized > 0).then(|| self.engine_correct as f64 / self.engine_finalized as f64)
+    }
+}
+
+/// Whether `slug` is a lowercase, hyphenated tag identifier
+pub fn is_valid_slug(slug: &str) -> bool {
+    !slug.is_empty()
+        && slug.len() <= 64
+        && !slug.starts_with('-')
+        && !slug.ends_with('-')
+        && slug.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// Lowercase and deduplicate requested tags, keeping their order. Fails with
+/// the first invalid tag, or if there are too many.
+pub fn normalize_tags(tags: &[String]) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let slug = tag.trim().to_ascii_lowercase();
+        if !is_valid_slug(&slug) {
+            return Err(tag.clone());
+        }
+        if !normalized.contains(&slug) {
+            normalized.push(slug);
+        }
+    }
+    if normalized.len() > MAX_TAGS_PER_BOUNTY {
+        return Err(format!("more than {} tags", MAX_TAGS_PER_BOUNTY));
+    }
+    Ok(normalized)
+}
+
+impl BountyTag {
+    /// The taxonomy, optionally limited to one category
+    pub async fn list(pool: &PgPool, category: Option<&str>) -> Result<Vec<BountyTag>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT * FROM bounty_tags
+            WHERE $1::TEXT IS NULL OR category = $1
+            ORDER BY category, slug
+            "#,
+        )
+        .bind(category)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Of `slugs`, those not in the taxonomy
+    pub async fn unknown(pool: &PgPool, slugs: &[String]) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT s FROM UNNEST($1::TEXT[]) AS s
+            WHERE NOT EXISTS (SELECT 1 FROM bounty_tags WHERE slug = s)
+            "#,
+        )
+        .bind(slugs)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Add a tag to the taxonomy or update its name, category and description
+    pub async fn upsert(
+        pool: &PgPool,
+        slug: &str,
+        name: &str,
+        category: &str,
+        description: Option<&str>,
+    ) -> Result<BountyTag, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            INSERT INTO bounty_tags (slug, name, category, description)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (slug) DO UPDATE
+            SET name = EXCLUDED.name,
+                category = EXCLUDED.category,
+                description = EXCLUDED.description,
+                updated_at = NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(slug)
+        .bind(name)
+        .bind(category)
+        .bind(description)
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Number of bounties, including archived ones, carrying a tag
+    pub async fn usage(pool: &PgPool, slug: &str) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar("SELECT COUNT(*) FROM bounty_tag_assignments WHERE tag_slug = $1")
+            .bind(slug)
+            .fetch_one(pool)
+            .await
+    }
+
+    /// Remove an unused tag; `false` if there was none
+    pub async fn delete(pool: &PgPool, slug: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM bounty_tags WHERE slug = $1")
+            .bind(slug)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Slugs of the tags a bounty carries
+    pub async fn of_bounty(pool: &PgPool, bounty_id: Uuid) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar("SELECT tag_slug FROM bounty_tag_assignments WHERE bounty_id = $1 ORDER BY tag_slug")
+            .bind(bounty_id)
+            .fetch_all(pool)
+            .await
+    }
+
+    /// Tag a bounty; tags it already has are kept
+    pub async fn assign(pool: &PgPool, bounty_id: Uuid, slugs: &[String]) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO bounty_tag_assignments (bounty_id, tag_slug)
+            SELECT $1, UNNEST($2::TEXT[])
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(bounty_id)
+        .bind(slugs)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Bounties carrying a tag, newest first, optionally in one status
+    pub async fn bounties(
+        pool: &PgPool,
+        slug: &str,
+        status: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<BountyModel>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT b.* FROM bounties b
+            JOIN bounty_tag_assignments a ON a.bounty_id = b.id
+            WHERE a.tag_slug = $1
+              AND ($2::TEXT IS NULL OR b.status = $2)
+            ORDER BY b.created_at DESC
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(slug)
+        .bind(status)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Activity per tag, busiest first. With `engine_id`, also how that
+    /// engine has done under each tag.
+    pub async fn stats(pool: &PgPool, engine_id: Option<&str>) -> Result<Vec<TagStats>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT t.slug, t.name, t.category,
+                   COALESCE(b.bounty_count, 0) AS bounty_count,
+                   COALESCE(b.open_bounties, 0) AS open_bounties,
+                   COALESCE(b.total_reward, 0) AS total_reward,
+                   COALESCE(s.submission_count, 0) AS submission_count,
+                   COALESCE(s.engine_submissions, 0) AS engine_submissions,
+                   COALESCE(s.engine_finalized, 0) AS engine_finalized,
+                   COALESCE(s.engine_correct, 0) AS engine_correct
+            FROM bounty_tags t
+            LEFT JOIN (
+                SELECT a.tag_slug,
+                       COUNT(*) AS bounty_count,
+                       COUNT(*) FILTER (WHERE b.status IN ('Active', 'InProgress')) AS open_bounties,
+                       SUM(b.reward_amount)::BIGINT AS total_reward
+                FROM bounty_tag_assignments a
+                JOIN bounties b ON b.id = a.bounty_id
+                GROUP BY a.tag_slug
+            ) b ON b.tag_slug = t.slug
+            LEFT JOIN (
+                SELECT a.tag_slug,
+                       COUNT(*) AS submission_count,
+                       COUNT(*) FILTER (WHERE s.engine_id = $1) AS engine_submissions,
+                       COUNT(*) FILTER (
+                           WHERE s.engine_id = $1
+                             AND s.status IN ('Correct', 'Incorrect', 'Slashed', 'Rewarded')
+                       ) AS engine_finalized,
+                       COUNT(*) FILTER (
+                           WHERE s.engine_id = $1 AND s.status IN ('Correct', 'Rewarded')
+                       ) AS engine_correct
+                FROM bounty_tag_assignments a
+                JOIN submissions s ON s.bounty_id = a.bounty_id
+                GROUP BY a.tag_slug
+            ) s ON s.tag_slug = t.slug
+            ORDER BY bounty_count DESC, t.slug
+            "#,
+        )
+        .bind(engine_id)
+        .fetch_all(pool)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slug_format() {
+        assert!(is_valid_slug("ransomware"));
+        assert!(is_valid_slug("supply-chain"));
+        assert!(is_valid_slug("c2"));
+        assert!(!is_valid_slug(""));
+        assert!(!is_valid_slug("Ransomware"));
+        assert!(!is_valid_slug("-apt"));
+        assert!(!is_valid_slug("info stealer"));
+    }
+
+    #[test]
+    fn test_normalize_tags() {
+        let tags = vec![" Phishing".to_string(), "apt".to_string(), "phishing".to_string()];
+        assert_eq!(normalize_tags(&tags).unwrap(), vec!["phishing", "apt"]);
+
+        assert_eq!(normalize_tags(&["bad tag".to_string()]), Err("bad tag".to_string()));
+
+        let too_many: Vec<String> = (0..=MAX_TAGS_PER_BOUNTY).map(|i| format!("tag-{}", i)).collect();
+        assert!(normalize_tags(&too_many).is_err());
+    }
+}
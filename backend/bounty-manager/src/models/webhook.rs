@@ -0,0 +1,257 @@
+// backend/bounty-manager/src/models/webhook.rs
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgExecutor, PgPool};
+use uuid::Uuid;
+
+/// Bounty events a webhook can subscribe to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    SubmissionReceived,
+    ConsensusReached,
+    DisputeOpened,
+    PayoutExecuted,
+}
+
+impl WebhookEvent {
+    /// Name stored in `bounty_webhooks.events` and sent as `X-Nexus-Event`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::SubmissionReceived => "submission_received",
+            WebhookEvent::ConsensusReached => "consensus_reached",
+            WebhookEvent::DisputeOpened => "dispute_opened",
+            WebhookEvent::PayoutExecuted => "payout_executed",
+        }
+    }
+}
+
+/// A creator's subscription to events of one of their bounties
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BountyWebhook {
+    pub id: Uuid,
+    pub bounty_id: Uuid,
+    pub creator: String,
+    pub url: String,
+    /// Only shown once, when the webhook is registered
+    #[serde(skip_serializing)]
+    pub secret: String,
+    pub events: Vec<String>,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// One event queued for, or sent to, a webhook
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub bounty_id: Uuid,
+    pub event_type: String,
+    pub payload: sqlx::types::JsonValue,
+    /// `pending`, `delivered` or `failed` once retries are exhausted
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+impl BountyWebhook {
+    pub async fn create(pool: &PgPool, webhook: &BountyWebhook) -> Result<BountyWebhook, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            INSERT INTO bounty_webhooks (id, bounty_id, creator, url, secret, events, enabled, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING *
+            "#,
+        )
+        .bind(webhook.id)
+        .bind(webhook.bounty_id)
+        .bind(&webhook.creator)
+        .bind(&webhook.url)
+        .bind(&webhook.secret)
+        .bind(&webhook.events)
+        .bind(webhook.enabled)
+        .bind(webhook.created_at)
+        .bind(webhook.updated_at)
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find(pool: &PgPool, bounty_id: Uuid, id: Uuid) -> Result<Option<BountyWebhook>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM bounty_webhooks WHERE id = $1 AND bounty_id = $2")
+            .bind(id)
+            .bind(bounty_id)
+            .fetch_optional(pool)
+            .await
+    }
+
+    pub async fn list_for_bounty(pool: &PgPool, bounty_id: Uuid) -> Result<Vec<BountyWebhook>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM bounty_webhooks WHERE bounty_id = $1 ORDER BY created_at")
+            .bind(bounty_id)
+            .fetch_all(pool)
+            .await
+    }
+
+    /// Number of webhooks registered on a bounty
+    pub async fn count_for_bounty(pool: &PgPool, bounty_id: Uuid) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar("SELECT COUNT(*) FROM bounty_webhooks WHERE bounty_id = $1")
+            .bind(bounty_id)
+            .fetch_one(pool)
+            .await
+    }
+
+    /// Remove a webhook and its delivery log; `false` if there was none
+    pub async fn delete(pool: &PgPool, bounty_id: Uuid, id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM bounty_webhooks WHERE id = $1 AND bounty_id = $2")
+            .bind(id)
+            .bind(bounty_id)
+            .execute(pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+impl WebhookDelivery {
+    /// Queue an event for every enabled webhook of the bounty subscribed to
+    /// it. Run inside the transaction that records the event, so the event
+    /// is queued if and only if it happened.
+    pub async fn enqueue<'e, E: PgExecutor<'e>>(
+        executor: E,
+        bounty_id: Uuid,
+        event: WebhookEvent,
+        payload: &serde_json::Value,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO webhook_deliveries (id, webhook_id, bounty_id, event_type, payload)
+            SELECT md5(random()::TEXT || clock_timestamp()::TEXT || w.id::TEXT)::UUID, w.id, w.bounty_id, $2, $3
+            FROM bounty_webhooks w
+            WHERE w.bounty_id = $1 AND w.enabled AND $2 = ANY(w.events)
+            "#,
+        )
+        .bind(bounty_id)
+        .bind(event.as_str())
+        .bind(payload)
+        .execute(executor)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Pending deliveries whose next attempt is due, oldest first, with the
+    /// webhook they go to. Locked so concurrent workers do not send twice.
+    pub async fn due(
+        pool: &PgPool,
+        now: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<(WebhookDelivery, BountyWebhook)>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let deliveries: Vec<WebhookDelivery> = sqlx::query_as(
+            r#"
+            SELECT * FROM webhook_deliveries
+            WHERE status = 'pending' AND next_attempt_at <= $1
+            ORDER BY next_attempt_at
+            LIMIT $2
+            FOR UPDATE SKIP LOCKED
+            "#,
+        )
+        .bind(now)
+        .bind(limit)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        // Claim the batch for a minute; a crashed worker's claim then lapses
+        let ids: Vec<Uuid> = deliveries.iter().map(|d| d.id).collect();
+        sqlx::query("UPDATE webhook_deliveries SET next_attempt_at = $2 + INTERVAL '1 minute' WHERE id = ANY($1)")
+            .bind(&ids)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+
+        let webhooks: Vec<BountyWebhook> = sqlx::query_as(
+            "SELECT * FROM bounty_webhooks WHERE id IN (SELECT webhook_id FROM webhook_deliveries WHERE id = ANY($1))",
+        )
+        .bind(&ids)
+        .fetch_all(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(deliveries
+            .into_iter()
+            .filter_map(|delivery| {
+                let webhook = webhooks.iter().find(|w| w.id == delivery.webhook_id)?.clone();
+                Some((delivery, webhook))
+            })
+            .collect())
+    }
+
+    pub async fn mark_delivered(pool: &PgPool, id: Uuid, response_status: u16) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = 'delivered', attempts = attempts + 1, response_status = $2,
+                last_error = NULL, delivered_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(response_status as i32)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Record a failed attempt: retry at `retry_at`, or give up if `None`
+    pub async fn mark_failed(
+        pool: &PgPool,
+        id: Uuid,
+        response_status: Option<u16>,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE webhook_deliveries
+            SET attempts = attempts + 1,
+                status = CASE WHEN $4::TIMESTAMPTZ IS NULL THEN 'failed' ELSE 'pending' END,
+                next_attempt_at = COALESCE($4, next_attempt_at),
+                response_status = $2,
+                last_error = $3
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(response_status.map(i32::from))
+        .bind(error)
+        .bind(retry_at)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// A webhook's deliveries, newest first
+    pub async fn log(pool: &PgPool, webhook_id: Uuid, limit: i64, offset: i64) -> Result<Vec<WebhookDelivery>, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT * FROM webhook_deliveries
+            WHERE webhook_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(webhook_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await
+    }
+}
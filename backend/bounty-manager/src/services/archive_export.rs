@@ -0,0 +1,167 @@
+// backend/bounty-manager/src/services/archive_export.rs
+//
+// Parquet exports of archived bounties. Each archived bounty is written to
+// `archive/<yyyy>/<mm>/<bounty_id>.parquet` (partitioned by archival month)
+// with one row per archived record: the bounty, its submissions and its
+// payouts, each as the JSON snapshot held in the archive tables. The archive
+// tables stay the source for rehydration; the export is the long-term copy.
+
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use arrow_array::{ArrayRef, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema};
+use aws_config::{BehaviorVersion, Region};
+use aws_sdk_s3::{
+    config::{Credentials, SharedCredentialsProvider},
+    primitives::ByteStream,
+    Client,
+};
+use chrono::{DateTime, Utc};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::ArchivalConfig;
+use crate::models::archive::{ArchivedRecord, BountyArchive};
+
+/// Uploads Parquet exports of archived bounties to object storage
+pub struct ArchiveExporter {
+    s3: Client,
+    bucket: String,
+}
+
+impl ArchiveExporter {
+    /// `None` when no archive bucket is configured
+    pub fn from_config(config: &ArchivalConfig) -> Option<Self> {
+        let bucket = config.s3_bucket.clone()?;
+
+        let credentials = Credentials::new(
+            config.s3_access_key.clone(),
+            config.s3_secret_key.clone(),
+            None,
+            None,
+            "nexus-security",
+        );
+
+        let s3_config = aws_sdk_s3::Config::builder()
+            .region(Region::new(config.s3_region.clone()))
+            .endpoint_url(config.s3_endpoint.clone())
+            .credentials_provider(SharedCredentialsProvider::new(credentials))
+            .force_path_style(true)
+            .behavior_version(BehaviorVersion::latest())
+            .build();
+
+        Some(Self {
+            s3: Client::from_conf(s3_config),
+            bucket,
+        })
+    }
+
+    /// Export one archived bounty and record its object key.
+    ///
+    /// Re-exporting a bounty (e.g. after it was rehydrated and archived
+    /// again) overwrites the earlier object.
+    pub async fn export(&self, pool: &PgPool, bounty_id: Uuid, archived_at: DateTime<Utc>) -> Result<String> {
+        let records = BountyArchive::records(pool, bounty_id)
+            .await
+            .context("Failed to load archived records")?;
+        if records.is_empty() {
+            anyhow::bail!("Bounty {} has no archived records", bounty_id);
+        }
+
+        let body = to_parquet(&records)?;
+        let key = object_key(bounty_id, archived_at);
+
+        self.s3
+            .put_object()
+            .bucket(&self.bucket)
+            .key(&key)
+            .body(ByteStream::from(body))
+            .content_type("application/vnd.apache.parquet")
+            .send()
+            .await
+            .context("Failed to upload archive export")?;
+
+        BountyArchive::mark_exported(pool, bounty_id, &key)
+            .await
+            .context("Failed to record archive export")?;
+
+        Ok(key)
+    }
+}
+
+/// Object key of a bounty's export
+pub fn object_key(bounty_id: Uuid, archived_at: DateTime<Utc>) -> String {
+    format!("archive/{}/{}.parquet", archived_at.format("%Y/%m"), bounty_id)
+}
+
+/// Encode archived records as a single Parquet row group
+pub fn to_parquet(records: &[ArchivedRecord]) -> Result<Vec<u8>> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("kind", DataType::Utf8, false),
+        Field::new("id", DataType::Utf8, false),
+        Field::new("bounty_id", DataType::Utf8, false),
+        Field::new("data", DataType::Utf8, false),
+    ]));
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from_iter_values(records.iter().map(|r| r.kind.as_str()))),
+        Arc::new(StringArray::from_iter_values(records.iter().map(|r| r.id.to_string()))),
+        Arc::new(StringArray::from_iter_values(records.iter().map(|r| r.bounty_id.to_string()))),
+        Arc::new(StringArray::from_iter_values(records.iter().map(|r| r.data.as_str()))),
+    ];
+    let batch = RecordBatch::try_new(schema.clone(), columns).context("Invalid archive batch")?;
+
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut buffer = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut buffer, schema, Some(properties))
+        .context("Failed to start Parquet file")?;
+    writer.write(&batch).context("Failed to write Parquet rows")?;
+    writer.close().context("Failed to finish Parquet file")?;
+
+    Ok(buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_object_key_is_partitioned_by_month() {
+        let bounty_id = Uuid::nil();
+        let archived_at = Utc.with_ymd_and_hms(2024, 3, 9, 12, 0, 0).unwrap();
+        assert_eq!(
+            object_key(bounty_id, archived_at),
+            format!("archive/2024/03/{}.parquet", bounty_id)
+        );
+    }
+
+    #[test]
+    fn test_to_parquet_writes_a_parquet_file() {
+        let bounty_id = Uuid::new_v4();
+        let records = vec![
+            ArchivedRecord {
+                kind: "bounty".to_string(),
+                id: bounty_id,
+                bounty_id,
+                data: r#"{"title":"Sample"}"#.to_string(),
+            },
+            ArchivedRecord {
+                kind: "submission".to_string(),
+                id: Uuid::new_v4(),
+                bounty_id,
+                data: r#"{"verdict":"malicious"}"#.to_string(),
+            },
+        ];
+
+        let file = to_parquet(&records).unwrap();
+        assert!(file.starts_with(b"PAR1"));
+        assert!(file.ends_with(b"PAR1"));
+    }
+}
@@ -41,6 +41,55 @@ pub struct PayoutTransaction {
     pub block_number: Option<u64>,
 }
 
+/// A bounty escrowed on-chain by `BountyManager.createBounty`, as recorded
+/// by its `BountyCreated` event
+#[derive(Debug, Clone, PartialEq)]
+pub struct OnChainDeposit {
+    pub on_chain_id: U256,
+    pub creator: Address,
+    pub artifact_hash: String,
+    pub reward: U256,
+    pub block_number: u64,
+}
+
+impl OnChainDeposit {
+    /// How the deposit differs from the bounty it should fund, if it does.
+    /// Rewards are escrowed in the contract's token, so `currency` must be
+    /// `token`.
+    pub fn mismatch(
+        &self,
+        creator: &str,
+        artifact_hash: Option<&str>,
+        reward_wei: u64,
+        currency: &str,
+        token: Address,
+    ) -> Option<String> {
+        if creator.parse::<Address>().ok() != Some(self.creator) {
+            return Some(format!("deposit made by {:?}, not the creator", self.creator));
+        }
+        if !artifact_hash.is_some_and(|hash| hash.trim().eq_ignore_ascii_case(self.artifact_hash.trim())) {
+            return Some(format!("deposit is for artifact {}", self.artifact_hash));
+        }
+        if self.reward != U256::from(reward_wei) {
+            return Some(format!("deposit of {} does not match the reward", self.reward));
+        }
+        if currency.parse::<Address>().ok() != Some(token) {
+            return Some(format!("rewards are escrowed in {:?}, not the bounty currency", token));
+        }
+        None
+    }
+}
+
+/// What a creator's deposit transaction shows on-chain
+#[derive(Debug, Clone, PartialEq)]
+pub enum DepositLookup {
+    /// Not mined yet
+    Pending,
+    /// Reverted, or did not create a bounty
+    Failed(String),
+    Created(OnChainDeposit),
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TransactionStatus {
     Pending,
@@ -224,6 +273,73 @@ impl BlockchainService {
         }
     }
 
+    /// The bounty a creator's `createBounty` transaction escrowed
+    pub async fn bounty_deposit(&self, tx_hash: &str) -> Result<DepositLookup, BlockchainError> {
+        use ethers::abi::{decode as abi_decode, ParamType};
+        use ethers::utils::keccak256;
+
+        let hash: H256 = tx_hash
+            .parse()
+            .map_err(|_| BlockchainError::ContractError("Invalid transaction hash".to_string()))?;
+
+        let receipt = match self.client
+            .get_transaction_receipt(hash)
+            .await
+            .map_err(|e| BlockchainError::ConnectionError(e.to_string()))?
+        {
+            Some(receipt) => receipt,
+            None => return Ok(DepositLookup::Pending),
+        };
+        if receipt.status != Some(1.into()) {
+            return Ok(DepositLookup::Failed("deposit transaction reverted".to_string()));
+        }
+        let Some(block_number) = receipt.block_number.map(|n| n.as_u64()) else {
+            return Ok(DepositLookup::Pending);
+        };
+
+        let bounty_created = H256::from(keccak256("BountyCreated(uint256,address,string,uint256,uint256)"));
+        let deposit = receipt
+            .logs
+            .iter()
+            .filter(|log| log.address == self.bounty_manager.address())
+            .filter(|log| log.topics.first() == Some(&bounty_created) && log.topics.len() >= 3)
+            .find_map(|log| {
+                let data = abi_decode(
+                    &[ParamType::String, ParamType::Uint(256), ParamType::Uint(256)],
+                    &log.data,
+                )
+                .ok()?;
+                Some(OnChainDeposit {
+                    on_chain_id: U256::from(log.topics[1].as_bytes()),
+                    creator: Address::from_slice(&log.topics[2].as_bytes()[12..]),
+                    artifact_hash: data.first()?.clone().into_string()?,
+                    reward: data.get(1)?.clone().into_uint()?,
+                    block_number,
+                })
+            });
+
+        Ok(match deposit {
+            Some(deposit) => DepositLookup::Created(deposit),
+            None => DepositLookup::Failed("transaction did not create a bounty".to_string()),
+        })
+    }
+
+    /// Blocks mined on top of `block_number`, counting its own
+    pub async fn confirmations(&self, block_number: u64) -> Result<u64, BlockchainError> {
+        let current = self.client
+            .get_block_number()
+            .await
+            .map_err(|e| BlockchainError::ConnectionError(e.to_string()))?
+            .as_u64();
+
+        Ok((current + 1).saturating_sub(block_number))
+    }
+
+    /// The token bounty rewards are escrowed in
+    pub fn token_address(&self) -> Address {
+        self.threat_token.address()
+    }
+
     /// Get token balance for an address
     pub async fn get_balance(&self, address: &str) -> Result<u64, BlockchainError> {
         let addr: Address = address
@@ -256,3 +372,38 @@ impl BlockchainService {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CREATOR: &str = "0x1111111111111111111111111111111111111111";
+    const TOKEN: &str = "0x2222222222222222222222222222222222222222";
+
+    fn deposit() -> OnChainDeposit {
+        OnChainDeposit {
+            on_chain_id: U256::from(7),
+            creator: CREATOR.parse().unwrap(),
+            artifact_hash: "QmArtifact".to_string(),
+            reward: U256::from(1_000u64),
+            block_number: 100,
+        }
+    }
+
+    #[test]
+    fn test_matching_deposit() {
+        let token: Address = TOKEN.parse().unwrap();
+        assert_eq!(deposit().mismatch(CREATOR, Some("qmartifact"), 1_000, TOKEN, token), None);
+    }
+
+    #[test]
+    fn test_deposit_mismatches() {
+        let token: Address = TOKEN.parse().unwrap();
+        let deposit = deposit();
+        assert!(deposit.mismatch(TOKEN, Some("QmArtifact"), 1_000, TOKEN, token).is_some());
+        assert!(deposit.mismatch(CREATOR, Some("QmOther"), 1_000, TOKEN, token).is_some());
+        assert!(deposit.mismatch(CREATOR, None, 1_000, TOKEN, token).is_some());
+        assert!(deposit.mismatch(CREATOR, Some("QmArtifact"), 999, TOKEN, token).is_some());
+        assert!(deposit.mismatch(CREATOR, Some("QmArtifact"), 1_000, CREATOR, token).is_some());
+    }
+}
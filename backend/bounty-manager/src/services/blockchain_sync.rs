@@ -10,8 +10,10 @@ use uuid::Uuid;
 use chrono::Utc;
 use ethers::providers::Middleware;
 
-use crate::services::blockchain::BlockchainService;
+use crate::config::DepositVerificationConfig;
+use crate::services::blockchain::{BlockchainService, DepositLookup};
 use crate::models::{bounty::BountyModel, submission::SubmissionModel, payout::PayoutModel};
+use crate::models::deposit::DepositVerification;
 
 /// Service for synchronizing blockchain state with database
 #[derive(Clone)]
@@ -20,6 +22,7 @@ pub struct BlockchainSyncService {
     blockchain: Arc<BlockchainService>,
     last_synced_block: Arc<RwLock<u64>>,
     sync_interval_seconds: u64,
+    deposits: DepositVerificationConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -53,12 +56,13 @@ pub enum BlockchainEventType {
 }
 
 impl BlockchainSyncService {
-    pub fn new(db: PgPool, blockchain: Arc<BlockchainService>) -> Self {
+    pub fn new(db: PgPool, blockchain: Arc<BlockchainService>, deposits: DepositVerificationConfig) -> Self {
         Self {
             db,
             blockchain,
             last_synced_block: Arc::new(RwLock::new(0)),
             sync_interval_seconds: 15, // Sync every 15 seconds
+            deposits,
         }
     }
 
@@ -90,6 +94,12 @@ impl BlockchainSyncService {
             if let Err(e) = self.sync_blocks().await {
                 error!("Error syncing blocks: {}", e);
             }
+
+            if self.deposits.enabled {
+                if let Err(e) = self.verify_pending_deposits().await {
+                    error!("Error verifying bounty deposits: {}", e);
+                }
+            }
         }
     }
 
@@ -356,7 +366,14 @@ impl BlockchainSyncService {
             bounty_id, creator, artifact_hash, reward, event.transaction_hash
         );
 
-        // TODO: look up DB bounty by blockchain_tx_hash and update on_chain_id + status to Active
+        // Deposits attached without a transaction hash are found by their event
+        if self.deposits.enabled && !artifact_hash.is_empty() && creator != "unknown" {
+            let attached =
+                DepositVerification::attach_transaction(&self.db, creator, artifact_hash, &event.transaction_hash).await?;
+            if attached > 0 {
+                info!("Matched deposit {} to {} pending bounties", event.transaction_hash, attached);
+            }
+        }
         Ok(())
     }
 
@@ -438,6 +455,124 @@ impl BlockchainSyncService {
         Ok(())
     }
 
+    /// Check pending deposit verifications against the chain. A deposit is
+    /// verified once its BountyCreated event matches the bounty's creator,
+    /// artifact, reward and currency and it has the configured number of
+    /// confirmations; until then it is rechecked on every sync.
+    async fn verify_pending_deposits(&self) -> Result<(), SyncError> {
+        let pending = DepositVerification::pending(&self.db, self.deposits.batch_size).await?;
+
+        for verification in pending {
+            if let Err(e) = self.verify_deposit(&verification).await {
+                warn!("Error verifying deposit of bounty {}: {}", verification.bounty_id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn verify_deposit(&self, verification: &DepositVerification) -> Result<(), SyncError> {
+        let bounty_id = verification.bounty_id;
+        let Some(tx_hash) = verification.deposit_tx_hash.as_deref() else {
+            // Waiting for the BountyCreated event to name the transaction
+            return Ok(());
+        };
+        let Some(bounty) = BountyModel::find_by_id(&self.db, bounty_id).await? else {
+            return Ok(());
+        };
+
+        let deposit = match self
+            .blockchain
+            .bounty_deposit(tx_hash)
+            .await
+            .map_err(|e| SyncError::BlockchainError(e.to_string()))?
+        {
+            DepositLookup::Pending => {
+                DepositVerification::record(
+                    &self.db,
+                    bounty_id,
+                    DepositVerification::PENDING,
+                    None,
+                    None,
+                    0,
+                    Some("deposit transaction not mined"),
+                )
+                .await?;
+                return Ok(());
+            }
+            DepositLookup::Failed(reason) => {
+                warn!("Deposit {} of bounty {} rejected: {}", tx_hash, bounty_id, reason);
+                DepositVerification::record(&self.db, bounty_id, DepositVerification::MISMATCH, None, None, 0, Some(&reason))
+                    .await?;
+                return Ok(());
+            }
+            DepositLookup::Created(deposit) => deposit,
+        };
+
+        let on_chain_id = deposit.on_chain_id.to_string();
+        let block_number = Some(deposit.block_number as i64);
+        if let Some(other) =
+            DepositVerification::funds_other(&self.db, bounty_id, Some(tx_hash), Some(&on_chain_id)).await?
+        {
+            warn!("Deposit {} of bounty {} already funds bounty {}", tx_hash, bounty_id, other);
+            DepositVerification::record(
+                &self.db,
+                bounty_id,
+                DepositVerification::MISMATCH,
+                Some(&on_chain_id),
+                block_number,
+                0,
+                Some(&format!("deposit already funds bounty {}", other)),
+            )
+            .await?;
+            return Ok(());
+        }
+        if let Some(reason) = deposit.mismatch(
+            &bounty.creator,
+            bounty.artifact_hash.as_deref(),
+            bounty.reward_amount as u64,
+            &bounty.currency,
+            self.blockchain.token_address(),
+        ) {
+            warn!("Deposit {} of bounty {} rejected: {}", tx_hash, bounty_id, reason);
+            DepositVerification::record(
+                &self.db,
+                bounty_id,
+                DepositVerification::MISMATCH,
+                Some(&on_chain_id),
+                block_number,
+                0,
+                Some(&reason),
+            )
+            .await?;
+            return Ok(());
+        }
+
+        let confirmations = self
+            .blockchain
+            .confirmations(deposit.block_number)
+            .await
+            .map_err(|e| SyncError::BlockchainError(e.to_string()))?;
+        let (status, detail) = if confirmations >= self.deposits.confirmations {
+            info!("Deposit of bounty {} verified at {} confirmations", bounty_id, confirmations);
+            (DepositVerification::VERIFIED, None)
+        } else {
+            (DepositVerification::PENDING, Some("awaiting confirmations"))
+        };
+        DepositVerification::record(
+            &self.db,
+            bounty_id,
+            status,
+            Some(&on_chain_id),
+            block_number,
+            confirmations.min(i32::MAX as u64) as i32,
+            detail,
+        )
+        .await?;
+
+        Ok(())
+    }
+
     /// Get current block number from blockchain
     async fn get_current_block_number(&self) -> Result<u64, SyncError> {
         let client = self.blockchain.get_client();
@@ -0,0 +1,166 @@
+// backend/bounty-manager/src/services/export.rs
+//
+// Rendering of bounty exports and the signed links their files are
+// downloaded through. A link is `/bounties/exports/<id>/download` with
+// `expires` (unix seconds) and `signature`, the hex HMAC-SHA256 of
+// `<id>:<expires>` under the export signing secret, so it works without the
+// creator's session until it expires.
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::models::export::{ExportFormat, ExportRow};
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub const CSV_HEADER: &str = "bounty_id,title,artifact_type,artifact_hash,bounty_status,reward_amount,currency,\
+created_at,deadline,archived,submission_id,engine_id,verdict,confidence,stake_amount,submission_status,\
+submitted_at,payout_amount,payout_status,payout_transactions\n";
+
+/// Quote a CSV field if it holds a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn optional<T: ToString>(value: &Option<T>) -> String {
+    value.as_ref().map(T::to_string).unwrap_or_default()
+}
+
+/// Text that starts an export, before any rows
+pub fn preamble(format: ExportFormat) -> &'static str {
+    match format {
+        ExportFormat::Csv => CSV_HEADER,
+        ExportFormat::Ndjson => "",
+    }
+}
+
+/// Render rows, one line each
+pub fn render(format: ExportFormat, rows: &[ExportRow]) -> String {
+    let mut out = String::new();
+    for row in rows {
+        match format {
+            ExportFormat::Csv => {
+                let fields = [
+                    row.bounty_id.to_string(),
+                    csv_field(&row.title),
+                    csv_field(&row.artifact_type),
+                    csv_field(row.artifact_hash.as_deref().unwrap_or("")),
+                    csv_field(&row.bounty_status),
+                    row.reward_amount.to_string(),
+                    csv_field(&row.currency),
+                    row.created_at.to_rfc3339(),
+                    row.deadline.to_rfc3339(),
+                    row.archived.to_string(),
+                    optional(&row.submission_id),
+                    csv_field(row.engine_id.as_deref().unwrap_or("")),
+                    csv_field(row.verdict.as_deref().unwrap_or("")),
+                    optional(&row.confidence),
+                    optional(&row.stake_amount),
+                    csv_field(row.submission_status.as_deref().unwrap_or("")),
+                    row.submitted_at.map(|at| at.to_rfc3339()).unwrap_or_default(),
+                    optional(&row.payout_amount),
+                    csv_field(row.payout_status.as_deref().unwrap_or("")),
+                    csv_field(row.payout_transactions.as_deref().unwrap_or("")),
+                ];
+                out.push_str(&fields.join(","));
+            }
+            ExportFormat::Ndjson => {
+                out.push_str(&serde_json::to_string(row).expect("export rows serialize"));
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn download_mac(secret: &str, export_id: Uuid, expires: i64) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(format!("{}:{}", export_id, expires).as_bytes());
+    mac
+}
+
+/// Signed download link of an export, valid until `expires`
+pub fn download_url(secret: &str, export_id: Uuid, expires: i64) -> String {
+    let signature = hex::encode(download_mac(secret, export_id, expires).finalize().into_bytes());
+    format!(
+        "/bounties/exports/{}/download?expires={}&signature={}",
+        export_id, expires, signature
+    )
+}
+
+/// Whether a download link's signature is genuine and it has not expired
+pub fn verify_download(secret: &str, export_id: Uuid, expires: i64, signature: &str, now: i64) -> bool {
+    let Ok(signature) = hex::decode(signature) else {
+        return false;
+    };
+    now < expires && download_mac(secret, export_id, expires).verify_slice(&signature).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn row(title: &str) -> ExportRow {
+        ExportRow {
+            bounty_id: Uuid::nil(),
+            title: title.to_string(),
+            artifact_type: "File".to_string(),
+            artifact_hash: None,
+            bounty_status: "Completed".to_string(),
+            reward_amount: 1000,
+            currency: "ETH".to_string(),
+            created_at: Utc::now(),
+            deadline: Utc::now(),
+            archived: false,
+            submission_id: None,
+            engine_id: Some("engine-1".to_string()),
+            verdict: Some("Malicious".to_string()),
+            confidence: Some(0.9),
+            stake_amount: Some(10),
+            submission_status: Some("Accepted".to_string()),
+            submitted_at: None,
+            payout_amount: None,
+            payout_status: None,
+            payout_transactions: None,
+        }
+    }
+
+    #[test]
+    fn test_csv_rows_match_header_and_escape_fields() {
+        let csv = render(ExportFormat::Csv, &[row("Dropper, \"stage 2\"")]);
+        assert!(csv.contains(",\"Dropper, \"\"stage 2\"\"\",File,"));
+
+        let columns = CSV_HEADER.trim_end().split(',').count();
+        let plain = render(ExportFormat::Csv, &[row("Dropper")]);
+        assert_eq!(plain.trim_end().split(',').count(), columns);
+    }
+
+    #[test]
+    fn test_ndjson_is_one_object_per_line() {
+        let ndjson = render(ExportFormat::Ndjson, &[row("a"), row("b")]);
+        let lines: Vec<&str> = ndjson.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let parsed: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(parsed["title"], "b");
+    }
+
+    #[test]
+    fn test_download_links_verify_until_expiry() {
+        let id = Uuid::new_v4();
+        let url = download_url("secret", id, 2_000);
+        let signature = url.rsplit("signature=").next().unwrap();
+
+        assert!(verify_download("secret", id, 2_000, signature, 1_999));
+        assert!(!verify_download("secret", id, 2_000, signature, 2_000));
+        assert!(!verify_download("secret", id, 2_001, signature, 1_999));
+        assert!(!verify_download("other", id, 2_000, signature, 1_999));
+        assert!(!verify_download("secret", Uuid::new_v4(), 2_000, signature, 1_999));
+        assert!(!verify_download("secret", id, 2_000, "zz", 1_999));
+    }
+}
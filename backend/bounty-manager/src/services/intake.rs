@@ -0,0 +1,256 @@
+// backend/bounty-manager/src/services/intake.rs
+//
+// Clients used when an engine submits to a bounty: the reputation-service
+// gates who may submit, and accepted verdicts are forwarded to the
+// consensus-service.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use shared::request_signing::RequestSigner;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::config::SubmissionIntakeConfig;
+use crate::handlers::submission::VerdictPrediction;
+
+#[derive(Debug, Deserialize)]
+struct ReputationResponse {
+    score: f64,
+    #[serde(default)]
+    specialization_score: Option<i32>,
+    #[serde(default)]
+    probation: bool,
+}
+
+/// An engine's reputation for one bounty
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EngineReputation {
+    /// Global score
+    pub score: i32,
+    /// Score in the threat categories the bounty's tags put it in, if the
+    /// engine has settled submissions in any of them
+    pub specialization_score: Option<i32>,
+    /// The engine has yet to settle its calibration bounties
+    pub probation: bool,
+}
+
+/// An accepted verdict as forwarded to the consensus-service
+#[derive(Debug, Serialize)]
+pub struct ForwardedSubmission<'a> {
+    pub engine_id: &'a str,
+    /// Lowercase verdict
+    pub verdict: String,
+    pub confidence: f32,
+    pub reputation_score: i32,
+    /// Reputation in the bounty's threat categories, weighted instead of
+    /// the global score when present
+    pub specialization_score: Option<i32>,
+    /// Votes of engines on probation carry less weight and earn less
+    pub probation: bool,
+    /// The known verdict of a calibration bounty
+    pub ground_truth: Option<&'a str>,
+    pub stake_amount: i64,
+    /// The engine's prediction of how the other engines vote
+    pub prediction: Option<&'a VerdictPrediction>,
+    /// Opens the engine's commitment on commit-reveal bounties
+    pub nonce: Option<&'a str>,
+    /// The bounty's consensus algorithm, if it chose one
+    pub algorithm: Option<&'a str>,
+    /// Where the engine submitted from, for collusion detection
+    pub source_ip: Option<&'a str>,
+    /// Wallet the engine staked from, for collusion detection
+    pub wallet_address: Option<&'a str>,
+    /// Text of the analysis, compared for copied analyses
+    pub analysis_text: String,
+    /// What the engine saw of the artifact, for relating it to others
+    pub indicators: ForwardedIndicators,
+    /// The artifact the bounty asks engines to analyze
+    pub artifact: ForwardedArtifact<'a>,
+}
+
+/// What engines are asked to analyze, matched against the capabilities of
+/// registered engines
+#[derive(Debug, Default, Serialize)]
+pub struct ForwardedArtifact<'a> {
+    /// MIME type
+    pub file_type: Option<&'a str>,
+    /// Size in bytes
+    pub file_size: Option<i64>,
+}
+
+/// Indicators that relate an artifact to other artifacts
+#[derive(Debug, Default, Serialize)]
+pub struct ForwardedIndicators {
+    /// PE import hash
+    pub imphash: Option<String>,
+    /// ssdeep fuzzy hash
+    pub ssdeep: Option<String>,
+    /// Domains the artifact contacted
+    pub domains: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ForwardedCommitment<'a> {
+    engine_id: &'a str,
+    commitment: &'a str,
+    reveal_after: DateTime<Utc>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum IntakeClientError {
+    /// The service refused the request
+    #[error("{service} rejected the request ({status}): {message}")]
+    Rejected {
+        service: &'static str,
+        status: u16,
+        message: String,
+    },
+
+    #[error("{0} unavailable: {1}")]
+    Unavailable(&'static str, String),
+}
+
+#[derive(Clone)]
+pub struct IntakeClient {
+    http: reqwest::Client,
+    reputation_url: String,
+    consensus_url: String,
+    signer: Option<RequestSigner>,
+}
+
+impl IntakeClient {
+    pub fn new(config: &SubmissionIntakeConfig) -> anyhow::Result<Self> {
+        Ok(Self {
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(config.timeout_seconds))
+                .build()?,
+            reputation_url: config.reputation_service_url.trim_end_matches('/').to_string(),
+            consensus_url: config.consensus_service_url.trim_end_matches('/').to_string(),
+            signer: RequestSigner::from_env()?,
+        })
+    }
+
+    /// The engine's reputation for a bounty tagged `tags`; engines without a
+    /// record are new, with a score of 0 and on probation
+    pub async fn engine_reputation(
+        &self,
+        engine_id: &str,
+        tags: &[String],
+    ) -> Result<EngineReputation, IntakeClientError> {
+        const SERVICE: &str = "reputation-service";
+        let mut path = format!("/api/v1/reputation/engine/{}", engine_id);
+        if !tags.is_empty() {
+            // Tag slugs are lowercase, digits and hyphens, safe in a query
+            path.push_str("?tags=");
+            path.push_str(&tags.join(","));
+        }
+        let response = match self.send(SERVICE, &self.reputation_url, "GET", &path, None).await {
+            Ok(response) => response,
+            Err(IntakeClientError::Rejected { status: 404, .. }) => {
+                return Ok(EngineReputation {
+                    probation: true,
+                    ..EngineReputation::default()
+                })
+            }
+            Err(e) => return Err(e),
+        };
+
+        response
+            .json::<ReputationResponse>()
+            .await
+            .map(|body| EngineReputation {
+                score: body.score.round() as i32,
+                specialization_score: body.specialization_score,
+                probation: body.probation,
+            })
+            .map_err(|e| IntakeClientError::Unavailable(SERVICE, format!("invalid response: {}", e)))
+    }
+
+    /// Forward an accepted verdict to the consensus-service, with the nonce
+    /// opening the engine's commitment on commit-reveal bounties. Forwarding
+    /// the same engine again replaces its vote.
+    pub async fn forward_submission(
+        &self,
+        bounty_id: Uuid,
+        submission: &ForwardedSubmission<'_>,
+    ) -> Result<(), IntakeClientError> {
+        const SERVICE: &str = "consensus-service";
+        let body = serde_json::to_vec(submission).map_err(|e| IntakeClientError::Unavailable(SERVICE, e.to_string()))?;
+        let path = format!("/api/v1/consensus/bounty/{}/submissions", bounty_id);
+        self.send(SERVICE, &self.consensus_url, "POST", &path, Some(body))
+            .await
+            .map(|_| ())
+    }
+
+    /// Forward an engine's commitment on a commit-reveal bounty; the
+    /// consensus-service refuses its verdict unless it opens the commitment
+    /// after `reveal_after`
+    pub async fn forward_commitment(
+        &self,
+        bounty_id: Uuid,
+        engine_id: &str,
+        commitment: &str,
+        reveal_after: DateTime<Utc>,
+    ) -> Result<(), IntakeClientError> {
+        const SERVICE: &str = "consensus-service";
+        let body = ForwardedCommitment {
+            engine_id,
+            commitment,
+            reveal_after,
+        };
+        let body = serde_json::to_vec(&body).map_err(|e| IntakeClientError::Unavailable(SERVICE, e.to_string()))?;
+        let path = format!("/api/v1/consensus/bounty/{}/commitments", bounty_id);
+        self.send(SERVICE, &self.consensus_url, "POST", &path, Some(body))
+            .await
+            .map(|_| ())
+    }
+
+    async fn send(
+        &self,
+        service: &'static str,
+        base_url: &str,
+        method: &str,
+        path: &str,
+        body: Option<Vec<u8>>,
+    ) -> Result<reqwest::Response, IntakeClientError> {
+        let body = body.unwrap_or_default();
+        let url = format!("{}{}", base_url, path);
+        let mut request = match method {
+            "GET" => self.http.get(&url),
+            _ => self
+                .http
+                .post(&url)
+                .header("content-type", "application/json")
+                .body(body.clone()),
+        };
+        for (name, value) in shared::observability::propagation_headers() {
+            request = request.header(name, value);
+        }
+        if let Some(signer) = &self.signer {
+            for (name, value) in signer.sign_now(method, path, Some(&body), None, None).pairs() {
+                request = request.header(name, value);
+            }
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| IntakeClientError::Unavailable(service, e.to_string()))?;
+        let status = response.status();
+        if !status.is_success() {
+            let message = response
+                .json::<serde_json::Value>()
+                .await
+                .ok()
+                .and_then(|body| body.get("error").and_then(|e| e.as_str()).map(str::to_string))
+                .unwrap_or_else(|| status.to_string());
+            return Err(IntakeClientError::Rejected {
+                service,
+                status: status.as_u16(),
+                message,
+            });
+        }
+
+        Ok(response)
+    }
+}
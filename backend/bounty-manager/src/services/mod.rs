@@ -5,6 +5,12 @@ pub mod consensus;
 pub mod notification;
 pub mod ranking;
 pub mod scoring;
+pub mod payment;
+pub mod intake;
+pub mod webhook;
+pub mod moderation;
+pub mod export;
+pub mod archive_export;
 
 pub use reputation::ReputationService;
 pub use blockchain::BlockchainService;
@@ -12,4 +18,9 @@ pub use blockchain_sync::BlockchainSyncService;
 pub use consensus::ConsensusService;
 pub use notification::NotificationService;
 pub use ranking::RankingService;
-pub use scoring::ScoringService;
\ No newline at end of file
+pub use scoring::ScoringService;
+pub use payment::PaymentClient;
+pub use intake::IntakeClient;
+pub use webhook::WebhookSender;
+pub use moderation::ContentScreen;
+pub use archive_export::ArchiveExporter;
@@ -0,0 +1,163 @@
+// backend/bounty-manager/src/services/moderation.rs
+//
+// Content heuristics run on every new bounty. A bounty matching any of them
+// is held for admin review before engines can see it. The lists come from
+// the operator (see `ModerationConfig`); flags name the heuristic that
+// matched, never the term, so the review queue does not repeat the content.
+
+use std::collections::HashSet;
+
+use crate::config::ModerationConfig;
+use crate::models::bounty::BountyModel;
+
+/// Artifact hash is on the known illegal-content hash list
+pub const FLAG_KNOWN_HASH: &str = "known_hash";
+/// Artifact hash belongs to a bounty that was taken down
+pub const FLAG_TAKEN_DOWN_ARTIFACT: &str = "taken_down_artifact";
+pub const FLAG_BLOCKED_TERM: &str = "blocked_term";
+pub const FLAG_BLOCKED_DOMAIN: &str = "blocked_domain";
+
+#[derive(Debug, Default)]
+pub struct ContentScreen {
+    known_hashes: HashSet<String>,
+    blocked_terms: Vec<String>,
+    blocked_domains: Vec<String>,
+}
+
+impl ContentScreen {
+    /// Load the hash list; a configured list that cannot be read is an
+    /// error rather than silently screening nothing
+    pub fn new(config: &ModerationConfig) -> anyhow::Result<Self> {
+        let known_hashes = match &config.hash_list_path {
+            Some(path) => std::fs::read_to_string(path)
+                .map_err(|e| anyhow::anyhow!("cannot read moderation hash list {}: {}", path, e))?
+                .lines()
+                .map(|line| line.trim().to_lowercase())
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .collect(),
+            None => HashSet::new(),
+        };
+
+        Ok(Self {
+            known_hashes,
+            blocked_terms: config.blocked_terms.clone(),
+            blocked_domains: config.blocked_domains.clone(),
+        })
+    }
+
+    pub fn hash_count(&self) -> usize {
+        self.known_hashes.len()
+    }
+
+    /// Heuristics the bounty matches
+    pub fn screen(&self, bounty: &BountyModel) -> Vec<&'static str> {
+        let mut flags = Vec::new();
+
+        if bounty
+            .artifact_hash
+            .as_deref()
+            .is_some_and(|hash| self.known_hashes.contains(&hash.to_lowercase()))
+        {
+            flags.push(FLAG_KNOWN_HASH);
+        }
+
+        let text = [
+            Some(bounty.title.as_str()),
+            Some(bounty.description.as_str()),
+            bounty.file_name.as_deref(),
+            bounty.artifact_url.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join("\n")
+        .to_lowercase();
+        if self.blocked_terms.iter().any(|term| text.contains(term.as_str())) {
+            flags.push(FLAG_BLOCKED_TERM);
+        }
+
+        let host = bounty
+            .artifact_url
+            .as_deref()
+            .and_then(|url| reqwest::Url::parse(url).ok())
+            .and_then(|url| url.host_str().map(str::to_lowercase));
+        if host.is_some_and(|host| self.blocked_domains.iter().any(|domain| is_within(&host, domain))) {
+            flags.push(FLAG_BLOCKED_DOMAIN);
+        }
+
+        flags
+    }
+}
+
+/// Whether `host` is `domain` or one of its subdomains
+fn is_within(host: &str, domain: &str) -> bool {
+    host == domain || host.strip_suffix(domain).is_some_and(|prefix| prefix.ends_with('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn bounty(title: &str, hash: Option<&str>, url: Option<&str>) -> BountyModel {
+        BountyModel {
+            id: Uuid::new_v4(),
+            creator: "0x0000000000000000000000000000000000000001".to_string(),
+            title: title.to_string(),
+            description: "Sample for analysis".to_string(),
+            artifact_type: "Url".to_string(),
+            artifact_hash: hash.map(str::to_string),
+            artifact_url: url.map(str::to_string),
+            file_name: None,
+            file_size: None,
+            mime_type: None,
+            upload_path: None,
+            reward_amount: 1000,
+            currency: "ETH".to_string(),
+            min_stake: 10,
+            max_participants: None,
+            deadline: Utc::now(),
+            status: "Active".to_string(),
+            consensus_threshold: 0.75,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            metadata: None,
+            min_reputation: None,
+            reveal_deadline: None,
+            consensus_algorithm: None,
+            organization_id: None,
+        }
+    }
+
+    fn screen() -> ContentScreen {
+        ContentScreen {
+            known_hashes: HashSet::from(["ab12".to_string()]),
+            blocked_terms: vec!["forbidden phrase".to_string()],
+            blocked_domains: vec!["blocked.example".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_clean_bounty_passes() {
+        let flags = screen().screen(&bounty("Suspicious dropper", Some("cd34"), Some("https://example.com/a")));
+        assert!(flags.is_empty());
+    }
+
+    #[test]
+    fn test_heuristics_flag_bounty() {
+        let flags = screen().screen(&bounty(
+            "Contains a Forbidden Phrase",
+            Some("AB12"),
+            Some("https://cdn.blocked.example/payload"),
+        ));
+        assert_eq!(flags, vec![FLAG_KNOWN_HASH, FLAG_BLOCKED_TERM, FLAG_BLOCKED_DOMAIN]);
+    }
+
+    #[test]
+    fn test_domain_match_needs_label_boundary() {
+        assert!(is_within("blocked.example", "blocked.example"));
+        assert!(is_within("a.blocked.example", "blocked.example"));
+        assert!(!is_within("notblocked.example", "blocked.example"));
+    }
+}
@@ -67,6 +67,31 @@ impl NotificationService {
         self.send_notification(notification).await
     }
 
+    /// Send bounty expired notification to the creator and participants
+    pub async fn notify_bounty_expired(
+        &self,
+        bounty_id: Uuid,
+        recipients: Vec<String>,
+        title: &str,
+        outcome: &str,
+    ) -> Result<(), NotificationError> {
+        for recipient in recipients {
+            let notification = Notification {
+                id: Uuid::new_v4(),
+                recipient,
+                notification_type: NotificationType::BountyExpired,
+                title: "Bounty Expired".to_string(),
+                message: format!("Bounty '{}' has expired: {}", title, outcome),
+                data: Some(serde_json::json!({ "bounty_id": bounty_id, "outcome": outcome })),
+                created_at: chrono::Utc::now(),
+            };
+
+            self.send_notification(notification).await?;
+        }
+
+        Ok(())
+    }
+
     /// Send submission received notification
     pub async fn notify_submission_received(
         &self,
@@ -0,0 +1,272 @@
+// backend/bounty-manager/src/services/payment.rs
+//
+// Client for the payment-service's bounty escrow and engine stakes. A
+// bounty's reward is escrowed when the bounty is created, and the bounty
+// only becomes active once the creator's deposit has confirmed on-chain.
+// Engines lock a stake when they join a bounty.
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use shared::request_signing::RequestSigner;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::config::PaymentServiceConfig;
+
+/// A bounty's reward escrow
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Escrow {
+    pub bounty_id: Uuid,
+    pub holder_address: String,
+    /// In wei
+    pub amount: String,
+    pub status: String,
+    pub deposit_tx_hash: Option<String>,
+}
+
+impl Escrow {
+    /// The deposit confirmed; the bounty may be activated
+    pub fn is_funded(&self) -> bool {
+        self.status == "funded"
+    }
+
+    /// The deposit reverted; the creator may submit another
+    pub fn is_failed(&self) -> bool {
+        self.status == "failed"
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct EscrowResponse {
+    escrow: Escrow,
+}
+
+/// A stake an engine holds on a bounty
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Stake {
+    pub id: Uuid,
+    pub bounty_id: Uuid,
+    pub address: String,
+    /// In wei
+    pub amount: String,
+    pub status: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct StakeResponse {
+    stake: Stake,
+}
+
+#[derive(Debug, Serialize)]
+struct LockStakeRequest<'a> {
+    user_id: Uuid,
+    bounty_id: Uuid,
+    address: &'a str,
+    /// Serialized as a string so wei amounts are not rounded
+    amount: String,
+}
+
+/// Why a bounty's reward goes back to its creator; the payment-service keeps
+/// a platform fee for cancelled and expired bounties
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RefundReason {
+    /// The creator or a moderator cancelled the bounty
+    Cancelled,
+    /// The bounty expired with too few submissions or no consensus
+    Expired,
+    /// The bounty never went live
+    Unfunded,
+}
+
+#[derive(Debug, Serialize)]
+struct RefundRequest {
+    reason: RefundReason,
+}
+
+#[derive(Debug, Serialize)]
+struct UnlockStakeRequest {
+    stake_id: Uuid,
+}
+
+#[derive(Debug, Serialize)]
+struct DepositRequest<'a> {
+    bounty_id: Uuid,
+    /// Serialized as a string so wei amounts are not rounded
+    amount: String,
+    creator_address: &'a str,
+    token_address: &'a str,
+    deposit_tx_hash: Option<&'a str>,
+    organization_id: Option<Uuid>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PaymentClientError {
+    /// The payment-service refused the request
+    #[error("payment-service rejected the request ({status}): {message}")]
+    Rejected { status: u16, message: String },
+
+    #[error("payment-service unavailable: {0}")]
+    Unavailable(String),
+}
+
+#[derive(Clone)]
+pub struct PaymentClient {
+    http: reqwest::Client,
+    base_url: String,
+    signer: Option<RequestSigner>,
+}
+
+impl PaymentClient {
+    pub fn new(config: &PaymentServiceConfig) -> anyhow::Result<Self> {
+        Ok(Self {
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(config.timeout_seconds))
+                .build()?,
+            base_url: config.url.trim_end_matches('/').to_string(),
+            signer: RequestSigner::from_env()?,
+        })
+    }
+
+    /// Open the escrow for a bounty, or attach the creator's deposit
+    /// transaction to it. Escrow of an organization's bounty is attributed
+    /// to the organization.
+    pub async fn deposit(
+        &self,
+        bounty_id: Uuid,
+        amount: u64,
+        creator_address: &str,
+        token_address: &str,
+        deposit_tx_hash: Option<&str>,
+        organization_id: Option<Uuid>,
+    ) -> Result<Escrow, PaymentClientError> {
+        let body = DepositRequest {
+            bounty_id,
+            amount: amount.to_string(),
+            creator_address,
+            token_address,
+            deposit_tx_hash,
+            organization_id,
+        };
+        let key = format!("deposit:{}:{}", bounty_id, deposit_tx_hash.unwrap_or("open"));
+        self.send("POST", "/api/v1/payments/bounty/deposit", Some(&body), Some(&key))
+            .await
+            .map(|body: EscrowResponse| body.escrow)
+    }
+
+    /// The bounty's escrow, `None` if none was opened
+    pub async fn escrow(&self, bounty_id: Uuid) -> Result<Option<Escrow>, PaymentClientError> {
+        let path = format!("/api/v1/payments/bounty/{}/escrow", bounty_id);
+        match self.send::<(), EscrowResponse>("GET", &path, None, None).await {
+            Ok(body) => Ok(Some(body.escrow)),
+            Err(PaymentClientError::Rejected { status: 404, .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Pay out the escrow of a completed bounty
+    pub async fn release(&self, bounty_id: Uuid) -> Result<Escrow, PaymentClientError> {
+        let path = format!("/api/v1/payments/bounty/{}/release", bounty_id);
+        let key = format!("release:{}", bounty_id);
+        self.send::<(), EscrowResponse>("POST", &path, None, Some(&key))
+            .await
+            .map(|body| body.escrow)
+    }
+
+    /// Return the escrow of a cancelled or expired bounty to its creator
+    pub async fn refund(&self, bounty_id: Uuid, reason: RefundReason) -> Result<Escrow, PaymentClientError> {
+        let path = format!("/api/v1/payments/bounty/{}/refund", bounty_id);
+        let key = format!("refund:{}", bounty_id);
+        let body = RefundRequest { reason };
+        self.send("POST", &path, Some(&body), Some(&key))
+            .await
+            .map(|body: EscrowResponse| body.escrow)
+    }
+
+    /// Lock an engine's stake as it joins a bounty; repeating the call
+    /// returns the stake already locked
+    pub async fn lock_stake(
+        &self,
+        bounty_id: Uuid,
+        engine_id: Uuid,
+        address: &str,
+        amount: u64,
+    ) -> Result<Stake, PaymentClientError> {
+        let body = LockStakeRequest {
+            user_id: engine_id,
+            bounty_id,
+            address,
+            amount: amount.to_string(),
+        };
+        let key = format!("stake-lock:{}:{}", bounty_id, engine_id);
+        self.send("POST", "/api/v1/payments/stake/lock", Some(&body), Some(&key))
+            .await
+            .map(|body: StakeResponse| body.stake)
+    }
+
+    /// Release a stake back to its engine
+    pub async fn unlock_stake(&self, stake_id: Uuid) -> Result<Stake, PaymentClientError> {
+        let body = UnlockStakeRequest { stake_id };
+        let key = format!("stake-unlock:{}", stake_id);
+        self.send("POST", "/api/v1/payments/stake/unlock", Some(&body), Some(&key))
+            .await
+            .map(|body: StakeResponse| body.stake)
+    }
+
+    /// `idempotency_key` names the operation, so a retry of the same one
+    /// is applied by the payment service at most once
+    async fn send<B: Serialize, R: DeserializeOwned>(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<&B>,
+        idempotency_key: Option<&str>,
+    ) -> Result<R, PaymentClientError> {
+        let body = match body {
+            Some(body) => serde_json::to_vec(body).map_err(|e| PaymentClientError::Unavailable(e.to_string()))?,
+            None => Vec::new(),
+        };
+        let url = format!("{}{}", self.base_url, path);
+        let mut request = match method {
+            "GET" => self.http.get(&url),
+            _ => self
+                .http
+                .post(&url)
+                .header("content-type", "application/json")
+                .body(body.clone()),
+        };
+        for (name, value) in shared::observability::propagation_headers() {
+            request = request.header(name, value);
+        }
+        if let Some(key) = idempotency_key {
+            request = request.header("idempotency-key", key);
+        }
+        if let Some(signer) = &self.signer {
+            for (name, value) in signer.sign_now(method, path, Some(&body), None, None).pairs() {
+                request = request.header(name, value);
+            }
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| PaymentClientError::Unavailable(e.to_string()))?;
+        let status = response.status();
+        if !status.is_success() {
+            let message = response
+                .json::<serde_json::Value>()
+                .await
+                .ok()
+                .and_then(|body| body.get("error").and_then(|e| e.as_str()).map(str::to_string))
+                .unwrap_or_else(|| status.to_string());
+            return Err(PaymentClientError::Rejected {
+                status: status.as_u16(),
+                message,
+            });
+        }
+
+        response
+            .json::<R>()
+            .await
+            .map_err(|e| PaymentClientError::Unavailable(format!("invalid response: {}", e)))
+    }
+}
@@ -0,0 +1,88 @@
+// backend/bounty-manager/src/services/webhook.rs
+//
+// Sends bounty events to creators' webhooks. Each delivery is signed with the
+// webhook's secret (`shared::webhooks::sign`), so receivers can check both
+// origin and freshness.
+
+use chrono::Utc;
+use shared::webhooks::{self, sign};
+use std::time::Duration;
+
+use crate::config::WebhookConfig;
+use crate::models::webhook::{BountyWebhook, WebhookDelivery};
+
+#[derive(Debug, thiserror::Error)]
+pub enum WebhookSendError {
+    /// The endpoint answered with a non-2xx status
+    #[error("endpoint responded {0}")]
+    Rejected(u16),
+
+    #[error("endpoint unreachable: {0}")]
+    Unreachable(String),
+}
+
+impl WebhookSendError {
+    pub fn response_status(&self) -> Option<u16> {
+        match self {
+            WebhookSendError::Rejected(status) => Some(*status),
+            WebhookSendError::Unreachable(_) => None,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct WebhookSender {
+    http: reqwest::Client,
+}
+
+impl WebhookSender {
+    pub fn new(config: &WebhookConfig) -> anyhow::Result<Self> {
+        Ok(Self {
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(config.timeout_seconds))
+                // A redirect could carry the signed payload somewhere the
+                // creator did not register
+                .redirect(reqwest::redirect::Policy::none())
+                .build()?,
+        })
+    }
+
+    /// POST a delivery to its webhook; the endpoint's status on success
+    pub async fn send(&self, webhook: &BountyWebhook, delivery: &WebhookDelivery) -> Result<u16, WebhookSendError> {
+        let body = serde_json::to_vec(&serde_json::json!({
+            "id": delivery.id,
+            "event": delivery.event_type,
+            "bounty_id": delivery.bounty_id,
+            "created_at": delivery.created_at,
+            "data": delivery.payload,
+        }))
+        .map_err(|e| WebhookSendError::Unreachable(e.to_string()))?;
+        // The host was public when the webhook was registered; DNS may have
+        // changed since
+        let url = webhooks::parse(&webhook.url).map_err(|e| WebhookSendError::Unreachable(e.to_string()))?;
+        webhooks::check_resolved(&url)
+            .await
+            .map_err(|e| WebhookSendError::Unreachable(e.to_string()))?;
+        let timestamp = Utc::now().timestamp();
+
+        let response = self
+            .http
+            .post(url)
+            .header("content-type", "application/json")
+            .header("x-nexus-event", &delivery.event_type)
+            .header("x-nexus-delivery", delivery.id.to_string())
+            .header("x-nexus-timestamp", timestamp.to_string())
+            .header("x-nexus-signature", sign(&webhook.secret, timestamp, &body))
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| WebhookSendError::Unreachable(e.to_string()))?;
+
+        let status = response.status();
+        if status.is_success() {
+            Ok(status.as_u16())
+        } else {
+            Err(WebhookSendError::Rejected(status.as_u16()))
+        }
+    }
+}
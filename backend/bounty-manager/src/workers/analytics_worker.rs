@@ -0,0 +1,51 @@
+// backend/bounty-manager/src/workers/analytics_worker.rs
+
+use sqlx::PgPool;
+use tokio::time::{interval, Duration};
+use tracing::{debug, error, info};
+use crate::config::AnalyticsConfig;
+use crate::models::analytics::BountyAnalytics;
+
+pub struct AnalyticsWorker {
+    db: PgPool,
+    config: AnalyticsConfig,
+}
+
+impl AnalyticsWorker {
+    pub fn new(db: PgPool, config: AnalyticsConfig) -> Self {
+        Self { db, config }
+    }
+
+    /// Start the analytics worker
+    pub async fn run(&self) {
+        info!(
+            "Starting analytics worker (interval: {}s)...",
+            self.config.interval_seconds
+        );
+        let mut ticker = interval(Duration::from_secs(self.config.interval_seconds));
+
+        loop {
+            ticker.tick().await;
+
+            if let Err(e) = self.refresh().await {
+                error!("Error aggregating bounty stats: {}", e);
+            }
+        }
+    }
+
+    /// Rebuild the bounty statistics from live and archived bounties
+    async fn refresh(&self) -> Result<(), WorkerError> {
+        BountyAnalytics::refresh(&self.db)
+            .await
+            .map_err(|e| WorkerError::DatabaseError(e.to_string()))?;
+
+        debug!("Bounty stats refreshed");
+        Ok(())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum WorkerError {
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
@@ -0,0 +1,93 @@
+// backend/bounty-manager/src/workers/archival_worker.rs
+
+use chrono::Utc;
+use sqlx::PgPool;
+use tokio::time::{interval, Duration};
+use tracing::{error, info};
+use crate::config::ArchivalConfig;
+use crate::models::archive::BountyArchive;
+use crate::services::ArchiveExporter;
+
+pub struct ArchivalWorker {
+    db: PgPool,
+    config: ArchivalConfig,
+    exporter: Option<ArchiveExporter>,
+}
+
+impl ArchivalWorker {
+    pub fn new(db: PgPool, config: ArchivalConfig) -> Self {
+        let exporter = ArchiveExporter::from_config(&config);
+        Self { db, config, exporter }
+    }
+
+    /// Start the archival worker
+    pub async fn run(&self) {
+        info!(
+            "Starting archival worker (retention: {} days)...",
+            self.config.retention_days
+        );
+        let mut ticker = interval(Duration::from_secs(self.config.interval_seconds));
+
+        loop {
+            ticker.tick().await;
+
+            if let Err(e) = self.archive_finalized_bounties().await {
+                error!("Error archiving bounties: {}", e);
+            }
+
+            if let Err(e) = self.export_archived_bounties().await {
+                error!("Error exporting archived bounties: {}", e);
+            }
+        }
+    }
+
+    /// Move one batch of old finalized bounties to the archive tables
+    async fn archive_finalized_bounties(&self) -> Result<(), WorkerError> {
+        let cutoff = Utc::now() - chrono::Duration::days(self.config.retention_days as i64);
+
+        let bounty_ids = BountyArchive::find_archivable(&self.db, cutoff, self.config.batch_size)
+            .await
+            .map_err(|e| WorkerError::DatabaseError(e.to_string()))?;
+
+        if bounty_ids.is_empty() {
+            return Ok(());
+        }
+
+        info!("Archiving {} finalized bounties", bounty_ids.len());
+
+        for bounty_id in bounty_ids {
+            if let Err(e) = BountyArchive::archive(&self.db, bounty_id).await {
+                error!("Error archiving bounty {}: {}", bounty_id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Upload Parquet exports of archived bounties that have none yet.
+    /// Failed uploads are retried on the next tick.
+    async fn export_archived_bounties(&self) -> Result<(), WorkerError> {
+        let Some(exporter) = &self.exporter else {
+            return Ok(());
+        };
+
+        let pending = BountyArchive::find_unexported(&self.db, self.config.batch_size)
+            .await
+            .map_err(|e| WorkerError::DatabaseError(e.to_string()))?;
+
+        for (bounty_id, archived_at) in pending {
+            match exporter.export(&self.db, bounty_id, archived_at).await {
+                Ok(key) => info!("Exported archived bounty {} to {}", bounty_id, key),
+                Err(e) => error!("Error exporting archived bounty {}: {:#}", bounty_id, e),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum WorkerError {
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
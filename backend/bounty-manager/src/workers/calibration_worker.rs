@@ -0,0 +1,177 @@
+// backend/bounty-manager/src/workers/calibration_worker.rs
+
+use chrono::{Duration as ChronoDuration, Utc};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::time::{interval, Duration};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+use crate::config::CalibrationConfig;
+use crate::handlers::bounty_crud::{open_bounty, ArtifactData, ArtifactType, Bounty, BountyStatus};
+use crate::models::calibration::{CalibrationArtifact, CalibrationBounty};
+use crate::services::moderation::ContentScreen;
+use crate::services::payment::PaymentClient;
+
+/// Keeps `target_open` calibration bounties going by injecting artifacts
+/// from the calibration pool, least recently used first. Each goes out as an
+/// ordinary bounty: posted from one of the platform's wallets with terms
+/// drawn from the configured ranges and no metadata, escrowed and screened
+/// like any other. Only `calibration_bounties` records what it is.
+pub struct CalibrationWorker {
+    db: PgPool,
+    payments: Arc<PaymentClient>,
+    content_screen: Arc<ContentScreen>,
+    config: CalibrationConfig,
+    verify_deposits: bool,
+}
+
+impl CalibrationWorker {
+    pub fn new(
+        db: PgPool,
+        payments: Arc<PaymentClient>,
+        content_screen: Arc<ContentScreen>,
+        config: CalibrationConfig,
+        verify_deposits: bool,
+    ) -> Self {
+        Self {
+            db,
+            payments,
+            content_screen,
+            config,
+            verify_deposits,
+        }
+    }
+
+    /// Start the calibration worker
+    pub async fn run(&self) {
+        info!(
+            "Starting calibration worker (checking every {}s)...",
+            self.config.interval_seconds
+        );
+        let mut ticker = interval(Duration::from_secs(self.config.interval_seconds));
+
+        loop {
+            ticker.tick().await;
+
+            match self.top_up().await {
+                Ok(0) => {}
+                Ok(opened) => info!("Injected {} calibration bounty(ies)", opened),
+                Err(e) => error!("Error injecting calibration bounties: {}", e),
+            }
+        }
+    }
+
+    /// Inject artifacts until `target_open` calibration bounties are open or
+    /// the pool has none due
+    async fn top_up(&self) -> Result<i64, WorkerError> {
+        let open = CalibrationBounty::open_count(&self.db)
+            .await
+            .map_err(|e| WorkerError::DatabaseError(e.to_string()))?;
+
+        let mut opened = 0;
+        while open + opened < self.config.target_open {
+            let reused_after = Utc::now() - ChronoDuration::days(self.config.reuse_cooldown_days);
+            let Some(artifact) = CalibrationArtifact::claim_next(&self.db, reused_after)
+                .await
+                .map_err(|e| WorkerError::DatabaseError(e.to_string()))?
+            else {
+                warn!(
+                    "Calibration pool has no artifact due; {} of {} calibration bounties open",
+                    open + opened,
+                    self.config.target_open
+                );
+                break;
+            };
+
+            if let Err(e) = self.inject(&artifact).await {
+                if let Err(e) = CalibrationArtifact::release(&self.db, artifact.id).await {
+                    error!("Failed to release calibration artifact {}: {}", artifact.id, e);
+                }
+                return Err(e);
+            }
+            opened += 1;
+        }
+        Ok(opened)
+    }
+
+    async fn inject(&self, artifact: &CalibrationArtifact) -> Result<(), WorkerError> {
+        let Some(bounty) = self.bounty_for(artifact) else {
+            warn!("Calibration artifact {} is unreadable; deactivating it", artifact.id);
+            CalibrationArtifact::set_active(&self.db, artifact.id, false)
+                .await
+                .map_err(|e| WorkerError::DatabaseError(e.to_string()))?;
+            return Err(WorkerError::InjectError(format!("artifact {} is unreadable", artifact.id)));
+        };
+        let bounty_id = bounty.id;
+
+        open_bounty(&self.db, &self.payments, &self.content_screen, bounty, None, self.verify_deposits)
+            .await
+            .map_err(|status| WorkerError::InjectError(format!("bounty not opened ({})", status)))?;
+        // Engines can find the bounty from here, but its verdict is settled
+        // on whenever any submission forwards it, so one that gets in first
+        // is still settled against the known verdict
+        CalibrationBounty::mark(
+            &self.db,
+            bounty_id,
+            &artifact.expected_verdict,
+            artifact.added_by,
+            Some(artifact.id),
+        )
+        .await
+        .map_err(|e| WorkerError::DatabaseError(e.to_string()))?;
+
+        info!("Injected calibration artifact {} as bounty {}", artifact.id, bounty_id);
+        Ok(())
+    }
+
+    /// An ordinary-looking bounty on the artifact, awaiting funding
+    fn bounty_for(&self, artifact: &CalibrationArtifact) -> Option<Bounty> {
+        let artifact_type: ArtifactType =
+            serde_json::from_value(serde_json::Value::String(artifact.artifact_type.clone())).ok()?;
+        let artifact_data: ArtifactData = serde_json::from_value(artifact.artifact_data.clone()).ok()?;
+
+        let mut rng = rand::thread_rng();
+        let creator = self.config.creators.choose(&mut rng)?.clone();
+        let reward_amount = rng.gen_range(self.config.reward_min..=self.config.reward_max);
+        let deadline_hours = rng.gen_range(self.config.deadline_hours_min..=self.config.deadline_hours_max);
+        let now = Utc::now();
+
+        Some(Bounty {
+            id: Uuid::new_v4(),
+            creator,
+            title: artifact.title.clone(),
+            description: artifact.description.clone(),
+            artifact_type,
+            artifact_data,
+            reward_amount,
+            currency: self.config.currency.clone(),
+            min_stake: self.config.min_stake,
+            max_participants: None,
+            min_reputation: None,
+            deadline: now + ChronoDuration::hours(deadline_hours as i64),
+            status: BountyStatus::PendingFunding,
+            consensus_threshold: 0.75,
+            created_at: now,
+            updated_at: now,
+            submissions: Vec::new(),
+            metadata: HashMap::new(),
+            tags: artifact.tags.clone(),
+            verdict_embargoed: false,
+            reveal_deadline: None,
+            consensus_algorithm: None,
+            organization_id: None,
+        })
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum WorkerError {
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+
+    #[error("Inject error: {0}")]
+    InjectError(String),
+}
@@ -1,134 +1,180 @@
 // backend/bounty-manager/src/workers/consensus_worker.rs
 
+use futures::StreamExt;
+use shared::messaging::{ConsensusReachedEvent, EventSubscriber, NexusEvent, RESUBSCRIBE_DELAY};
 use sqlx::PgPool;
 use std::sync::Arc;
-use tokio::time::{interval, Duration};
-use tracing::{error, info};
-use crate::services::consensus::{ConsensusService, SubmissionData};
+use tokio::time::sleep;
+use tracing::{error, info, warn};
+use crate::handlers::BountyStatus;
+use crate::services::consensus::ConsensusService;
 use crate::models::submission::SubmissionModel;
 use crate::models::bounty::BountyModel;
-use uuid::Uuid;
-
+use crate::models::webhook::{WebhookDelivery, WebhookEvent};
+use crate::services::notification::NotificationService;
+use crate::services::payment::{PaymentClient, PaymentClientError};
+
+/// Pays out bounties as the consensus-service announces their verdicts:
+/// releases the reward escrow, completes the bounty and scores each
+/// submission against the verdict. Verdicts are announced again while a
+/// closed bounty stays open, so one missed here is picked up later.
 pub struct ConsensusWorker {
     db: PgPool,
     consensus_service: Arc<ConsensusService>,
-    check_interval_seconds: u64,
+    payments: Arc<PaymentClient>,
+    events: EventSubscriber,
+    notification_service: NotificationService,
 }
 
 impl ConsensusWorker {
-    pub fn new(db: PgPool, consensus_service: Arc<ConsensusService>) -> Self {
+    pub fn new(
+        db: PgPool,
+        consensus_service: Arc<ConsensusService>,
+        payments: Arc<PaymentClient>,
+        events: EventSubscriber,
+    ) -> Self {
         Self {
             db,
             consensus_service,
-            check_interval_seconds: 60, // Check every minute
+            payments,
+            events,
+            notification_service: NotificationService::new(),
         }
     }
 
     /// Start the consensus worker
     pub async fn run(&self) {
         info!("Starting consensus worker...");
-        let mut ticker = interval(Duration::from_secs(self.check_interval_seconds));
 
         loop {
-            ticker.tick().await;
-            
-            if let Err(e) = self.process_pending_bounties().await {
-                error!("Error processing bounties for consensus: {}", e);
+            match self.events.subscribe(&["consensus_reached"]).await {
+                Ok(events) => {
+                    let mut events = Box::pin(events);
+                    while let Some(event) = events.next().await {
+                        let NexusEvent::ConsensusReached(reached) = event else {
+                            continue;
+                        };
+                        if let Err(e) = self.settle(&reached).await {
+                            error!("Error settling consensus for bounty {}: {}", reached.bounty_id, e);
+                        }
+                    }
+                    warn!("Consensus event stream ended");
+                }
+                Err(e) => error!("Consensus event subscription failed: {}", e),
             }
+            sleep(RESUBSCRIBE_DELAY).await;
         }
     }
 
-    /// Process all pending bounties to check for consensus
-    async fn process_pending_bounties(&self) -> Result<(), WorkerError> {
-        // Get all active bounties
-        let bounties = BountyModel::find_active(&self.db)
+    /// Pay out a bounty on its verdict. Bounties no longer open, e.g.
+    /// already settled or held for moderation, are left alone.
+    async fn settle(&self, reached: &ConsensusReachedEvent) -> Result<(), WorkerError> {
+        let bounty_id = reached.bounty_id;
+        let Some(bounty) = BountyModel::find_by_id(&self.db, bounty_id)
             .await
-            .map_err(|e| WorkerError::DatabaseError(e.to_string()))?;
-
-        info!("Checking {} active bounties for consensus", bounties.len());
+            .map_err(|e| WorkerError::DatabaseError(e.to_string()))?
+        else {
+            return Ok(());
+        };
+        if bounty.status != BountyStatus::Active.as_str() && bounty.status != BountyStatus::InProgress.as_str() {
+            return Ok(());
+        }
 
-        for bounty in bounties {
-            if let Err(e) = self.check_bounty_consensus(bounty.id).await {
-                error!("Error checking consensus for bounty {}: {}", bounty.id, e);
-            }
+        let final_verdict = format!("{:?}", reached.final_verdict);
+        info!(
+            "Consensus reached for bounty {}: {} (confidence: {})",
+            bounty_id, final_verdict, reached.confidence
+        );
+
+        // Release the reward escrow first; if that fails the bounty stays
+        // open and is settled when the verdict is announced again
+        match self.payments.release(bounty_id).await {
+            Ok(_) => {}
+            // Bounties created before escrow have nothing to settle
+            Err(PaymentClientError::Rejected { status: 404, .. }) => {}
+            Err(e) => return Err(WorkerError::PaymentError(e.to_string())),
         }
 
-        Ok(())
-    }
+        if !BountyModel::complete(&self.db, bounty_id)
+            .await
+            .map_err(|e| WorkerError::DatabaseError(e.to_string()))?
+        {
+            return Ok(());
+        }
 
-    /// Check if a specific bounty has reached consensus
-    async fn check_bounty_consensus(&self, bounty_id: Uuid) -> Result<(), WorkerError> {
-        // Get all submissions for this bounty
+        // Update submission statuses based on accuracy
         let submissions = SubmissionModel::find_by_bounty(&self.db, bounty_id)
             .await
             .map_err(|e| WorkerError::DatabaseError(e.to_string()))?;
-
-        if submissions.is_empty() {
-            return Ok(());
+        record_verdicts(&self.db, &self.consensus_service, &submissions, &final_verdict).await?;
+
+        let event = serde_json::json!({
+            "verdict": final_verdict,
+            "confidence": reached.confidence,
+            "agreement_score": reached.agreement_score,
+            "submissions": reached.total_submissions,
+        });
+        if let Err(e) = WebhookDelivery::enqueue(&self.db, bounty_id, WebhookEvent::ConsensusReached, &event).await {
+            warn!("Failed to queue consensus webhooks for bounty {}: {}", bounty_id, e);
         }
 
-        // Convert to submission data
-        let submission_data: Vec<SubmissionData> = submissions.iter().map(|s| {
-            SubmissionData {
-                submission_id: s.id,
-                verdict: s.verdict.clone(),
-                confidence: s.confidence,
-                stake_amount: s.stake_amount as u64,
-                reputation_score: 1.0, // TODO: Get actual reputation score
+        let mut recipients = vec![bounty.creator.clone()];
+        for submission in &submissions {
+            if !recipients.contains(&submission.engine_id) {
+                recipients.push(submission.engine_id.clone());
             }
-        }).collect();
-
-        // Calculate consensus
-        let consensus_result = self.consensus_service.calculate_consensus(submission_data);
-
-        if consensus_result.consensus_reached {
-            info!(
-                "Consensus reached for bounty {}: {} (confidence: {})",
-                bounty_id, consensus_result.final_verdict, consensus_result.confidence
-            );
-
-            // Update bounty status
-            BountyModel::update_status(&self.db, bounty_id, "Completed")
-                .await
-                .map_err(|e| WorkerError::DatabaseError(e.to_string()))?;
-
-            // Update submission statuses based on accuracy
-            for submission in &submissions {
-                let accuracy = self.consensus_service.calculate_accuracy_score(
-                    &submission.verdict,
-                    &consensus_result.final_verdict,
-                    submission.confidence,
-                );
-
-                SubmissionModel::update_accuracy_score(&self.db, submission.id, accuracy)
-                    .await
-                    .map_err(|e| WorkerError::DatabaseError(e.to_string()))?;
-
-                // Update status to Correct or Incorrect
-                let new_status = if submission.verdict == consensus_result.final_verdict {
-                    "Correct"
-                } else {
-                    "Incorrect"
-                };
-
-                SubmissionModel::update_status(&self.db, submission.id, new_status)
-                    .await
-                    .map_err(|e| WorkerError::DatabaseError(e.to_string()))?;
-            }
-
-            // TODO: Trigger payout worker
-            // TODO: Send notifications
+        }
+        if let Err(e) = self
+            .notification_service
+            .notify_consensus_reached(bounty_id, recipients, &final_verdict)
+            .await
+        {
+            warn!("Failed to notify participants of consensus on bounty {}: {}", bounty_id, e);
         }
 
         Ok(())
     }
 }
 
+/// Score each submission against the final verdict and mark it Correct or
+/// Incorrect
+pub async fn record_verdicts(
+    db: &PgPool,
+    consensus_service: &ConsensusService,
+    submissions: &[SubmissionModel],
+    final_verdict: &str,
+) -> Result<(), WorkerError> {
+    for submission in submissions {
+        let accuracy = consensus_service.calculate_accuracy_score(
+            &submission.verdict,
+            final_verdict,
+            submission.confidence,
+        );
+
+        SubmissionModel::update_accuracy_score(db, submission.id, accuracy)
+            .await
+            .map_err(|e| WorkerError::DatabaseError(e.to_string()))?;
+
+        // Update status to Correct or Incorrect
+        let new_status = if submission.verdict == final_verdict {
+            "Correct"
+        } else {
+            "Incorrect"
+        };
+
+        SubmissionModel::update_status(db, submission.id, new_status)
+            .await
+            .map_err(|e| WorkerError::DatabaseError(e.to_string()))?;
+    }
+
+    Ok(())
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum WorkerError {
     #[error("Database error: {0}")]
     DatabaseError(String),
 
-    #[error("Consensus error: {0}")]
-    ConsensusError(String),
+    #[error("Payment error: {0}")]
+    PaymentError(String),
 }
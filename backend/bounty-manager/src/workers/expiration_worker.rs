@@ -0,0 +1,168 @@
+// backend/bounty-manager/src/workers/expiration_worker.rs
+
+use chrono::{Duration as ChronoDuration, Utc};
+use shared::messaging::{BountyClosedEvent, EventPublisher, NexusEvent};
+use sqlx::PgPool;
+use std::sync::Arc;
+use tokio::time::{interval, Duration};
+use tracing::{debug, error, info, warn};
+use crate::config::ExpirationConfig;
+use crate::handlers::BountyStatus;
+use crate::models::bounty::BountyModel;
+use crate::models::submission::SubmissionModel;
+use crate::services::consensus::ConsensusService;
+use crate::services::notification::NotificationService;
+use crate::services::payment::{PaymentClient, PaymentClientError, RefundReason};
+
+/// Closes bounties past their deadline. Bounties with enough submissions
+/// are announced as closed so the consensus-service settles their verdict,
+/// which the consensus worker pays out. The reward is refunded when there
+/// are fewer than `min_submissions`, or when no verdict arrives within the
+/// consensus wait, and everyone involved is notified.
+pub struct ExpirationWorker {
+    db: PgPool,
+    consensus_service: Arc<ConsensusService>,
+    payments: Arc<PaymentClient>,
+    events: Arc<EventPublisher>,
+    notification_service: NotificationService,
+    config: ExpirationConfig,
+}
+
+impl ExpirationWorker {
+    pub fn new(
+        db: PgPool,
+        consensus_service: Arc<ConsensusService>,
+        payments: Arc<PaymentClient>,
+        events: Arc<EventPublisher>,
+        config: ExpirationConfig,
+    ) -> Self {
+        Self {
+            db,
+            consensus_service,
+            payments,
+            events,
+            notification_service: NotificationService::new(),
+            config,
+        }
+    }
+
+    /// Start the expiration worker
+    pub async fn run(&self) {
+        info!(
+            "Starting expiration worker (checking every {}s)...",
+            self.config.interval_seconds
+        );
+        let mut ticker = interval(Duration::from_secs(self.config.interval_seconds));
+
+        loop {
+            ticker.tick().await;
+
+            if let Err(e) = self.expire_overdue_bounties().await {
+                error!("Error expiring bounties: {}", e);
+            }
+        }
+    }
+
+    async fn expire_overdue_bounties(&self) -> Result<(), WorkerError> {
+        let bounties = BountyModel::find_expired(&self.db, Utc::now(), self.config.batch_size)
+            .await
+            .map_err(|e| WorkerError::DatabaseError(e.to_string()))?;
+
+        if bounties.is_empty() {
+            return Ok(());
+        }
+
+        info!("Expiring {} bounties past their deadline", bounties.len());
+
+        for bounty in bounties {
+            if let Err(e) = self.expire_bounty(&bounty).await {
+                error!("Error expiring bounty {}: {}", bounty.id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn expire_bounty(&self, bounty: &BountyModel) -> Result<(), WorkerError> {
+        let submissions = SubmissionModel::find_by_bounty(&self.db, bounty.id)
+            .await
+            .map_err(|e| WorkerError::DatabaseError(e.to_string()))?;
+
+        let closed_at = bounty.reveal_deadline.unwrap_or(bounty.deadline);
+        let (reason, refund) = if bounty.status == BountyStatus::PendingFunding.as_str() {
+            ("never funded", RefundReason::Unfunded)
+        } else if !self.consensus_service.can_reach_consensus(submissions.len() as u32) {
+            ("too few submissions", RefundReason::Expired)
+        } else if Utc::now() < closed_at + ChronoDuration::seconds(self.config.consensus_wait_seconds) {
+            // Announced on every run until the verdict settles the bounty,
+            // so an announcement or verdict lost in transit is made up for
+            return self.announce_closed(bounty, submissions.len()).await;
+        } else {
+            ("no consensus", RefundReason::Expired)
+        };
+
+        // Refund the escrow before the status changes, so a payment-service
+        // outage leaves the bounty to be retried on the next run. The call
+        // is idempotent.
+        match self.payments.refund(bounty.id, refund).await {
+            Ok(_) => {}
+            // Bounties created before escrow have nothing to settle
+            Err(PaymentClientError::Rejected { status: 404, .. }) => {}
+            Err(e) => return Err(WorkerError::PaymentError(e.to_string())),
+        }
+
+        if !BountyModel::expire(&self.db, bounty.id)
+            .await
+            .map_err(|e| WorkerError::DatabaseError(e.to_string()))?
+        {
+            return Ok(());
+        }
+        let outcome = format!("reward refunded ({})", reason);
+        info!("Bounty {} expired: {}", bounty.id, outcome);
+
+        let mut recipients = vec![bounty.creator.clone()];
+        for submission in &submissions {
+            if !recipients.contains(&submission.engine_id) {
+                recipients.push(submission.engine_id.clone());
+            }
+        }
+        if let Err(e) = self
+            .notification_service
+            .notify_bounty_expired(bounty.id, recipients, &bounty.title, &outcome)
+            .await
+        {
+            warn!("Failed to notify participants of expired bounty {}: {}", bounty.id, e);
+        }
+
+        Ok(())
+    }
+
+    /// Tell the consensus-service the bounty takes no more submissions
+    async fn announce_closed(&self, bounty: &BountyModel, submissions: usize) -> Result<(), WorkerError> {
+        let event = NexusEvent::BountyClosed(BountyClosedEvent {
+            bounty_id: bounty.id,
+            artifact_hash: bounty.artifact_hash.clone(),
+            total_submissions: submissions as u32,
+            closed_at: bounty.reveal_deadline.unwrap_or(bounty.deadline),
+        });
+        self.events
+            .publish(&event)
+            .await
+            .map_err(|e| WorkerError::EventError(e.to_string()))?;
+        debug!("Bounty {} closed with {} submissions, awaiting consensus", bounty.id, submissions);
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum WorkerError {
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+
+    #[error("Payment error: {0}")]
+    PaymentError(String),
+
+    #[error("Event error: {0}")]
+    EventError(String),
+}
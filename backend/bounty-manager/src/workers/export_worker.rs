@@ -0,0 +1,154 @@
+// backend/bounty-manager/src/workers/export_worker.rs
+
+use chrono::{Duration as ChronoDuration, Utc};
+use sqlx::PgPool;
+use std::path::PathBuf;
+use tokio::io::AsyncWriteExt;
+use tokio::time::{interval, Duration};
+use tracing::{error, info, warn};
+use crate::config::ExportConfig;
+use crate::models::export::{BountyExport, ExportCursor, ExportFormat, ExportRow};
+use crate::services::export::{preamble, render};
+
+/// Exports generated per tick
+const BATCH_SIZE: i64 = 4;
+
+/// A running export not finished within this long is taken over
+const STALE_AFTER_MINUTES: i64 = 60;
+
+/// Generates queued bounty exports into files for signed download, and
+/// deletes them once their links expire
+pub struct ExportWorker {
+    db: PgPool,
+    config: ExportConfig,
+}
+
+impl ExportWorker {
+    pub fn new(db: PgPool, config: ExportConfig) -> Self {
+        Self { db, config }
+    }
+
+    /// Start the export worker
+    pub async fn run(&self) {
+        info!(
+            "Starting export worker (checking every {}s)...",
+            self.config.interval_seconds
+        );
+        if let Err(e) = tokio::fs::create_dir_all(&self.config.directory).await {
+            error!("Cannot create export directory {}: {}", self.config.directory, e);
+        }
+        let mut ticker = interval(Duration::from_secs(self.config.interval_seconds));
+
+        loop {
+            ticker.tick().await;
+
+            if let Err(e) = self.generate_pending().await {
+                error!("Error generating exports: {}", e);
+            }
+            if let Err(e) = self.remove_expired().await {
+                error!("Error removing expired exports: {}", e);
+            }
+        }
+    }
+
+    async fn generate_pending(&self) -> Result<(), WorkerError> {
+        let exports = BountyExport::claim(&self.db, ChronoDuration::minutes(STALE_AFTER_MINUTES), BATCH_SIZE)
+            .await
+            .map_err(|e| WorkerError::DatabaseError(e.to_string()))?;
+
+        for export in exports {
+            match self.generate(&export).await {
+                Ok((rows, path)) => {
+                    let expires_at = Utc::now() + ChronoDuration::hours(self.config.link_ttl_hours as i64);
+                    BountyExport::complete(&self.db, export.id, rows, &path.to_string_lossy(), expires_at)
+                        .await
+                        .map_err(|e| WorkerError::DatabaseError(e.to_string()))?;
+                    info!("Export {} ready: {} rows", export.id, rows);
+                }
+                Err(e) => {
+                    warn!("Export {} failed: {}", export.id, e);
+                    BountyExport::fail(&self.db, export.id, &e.to_string())
+                        .await
+                        .map_err(|e| WorkerError::DatabaseError(e.to_string()))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write an export to its file, page by page; the rows written and the path
+    async fn generate(&self, export: &BountyExport) -> Result<(i64, PathBuf), WorkerError> {
+        let format = ExportFormat::parse(&export.format)
+            .ok_or_else(|| WorkerError::ExportError(format!("unknown format {}", export.format)))?;
+        let path = PathBuf::from(&self.config.directory).join(format!("{}.{}", export.id, format.as_str()));
+        let mut file = tokio::fs::File::create(&path)
+            .await
+            .map_err(|e| WorkerError::ExportError(e.to_string()))?;
+
+        file.write_all(preamble(format).as_bytes())
+            .await
+            .map_err(|e| WorkerError::ExportError(e.to_string()))?;
+        let mut rows = 0;
+        let mut cursor = None;
+        loop {
+            let page = ExportRow::page(
+                &self.db,
+                &export.creator,
+                export.range_start,
+                export.range_end,
+                cursor,
+                self.config.page_size,
+            )
+            .await
+            .map_err(|e| WorkerError::DatabaseError(e.to_string()))?;
+            let Some(last) = page.last() else {
+                break;
+            };
+            cursor = Some(ExportCursor {
+                created_at: last.created_at,
+                bounty_id: last.bounty_id,
+            });
+            rows += page.len() as i64;
+            file.write_all(render(format, &page).as_bytes())
+                .await
+                .map_err(|e| WorkerError::ExportError(e.to_string()))?;
+        }
+        file.flush().await.map_err(|e| WorkerError::ExportError(e.to_string()))?;
+
+        Ok((rows, path))
+    }
+
+    async fn remove_expired(&self) -> Result<(), WorkerError> {
+        let expired = BountyExport::expired(&self.db, Utc::now(), 100)
+            .await
+            .map_err(|e| WorkerError::DatabaseError(e.to_string()))?;
+
+        for export in expired {
+            if let Some(path) = export.file_path.as_deref() {
+                match tokio::fs::remove_file(path).await {
+                    Ok(()) => {}
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(e) => {
+                        warn!("Failed to delete expired export file {}: {}", path, e);
+                        continue;
+                    }
+                }
+            }
+            BountyExport::mark_expired(&self.db, export.id)
+                .await
+                .map_err(|e| WorkerError::DatabaseError(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum WorkerError {
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+
+    #[error("Export error: {0}")]
+    ExportError(String),
+}
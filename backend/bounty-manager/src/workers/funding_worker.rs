@@ -0,0 +1,198 @@
+// backend/bounty-manager/src/workers/funding_worker.rs
+
+use chrono::Utc;
+use futures::StreamExt;
+use shared::messaging::{EventSubscriber, NexusEvent, PaymentEventKind, RESUBSCRIBE_DELAY};
+use sqlx::PgPool;
+use std::sync::Arc;
+use tokio::time::{interval, sleep, Duration};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+use crate::config::PaymentServiceConfig;
+use crate::handlers::BountyStatus;
+use crate::models::bounty::BountyModel;
+use crate::models::deposit::DepositVerification;
+use crate::services::payment::{PaymentClient, RefundReason};
+
+const BATCH_SIZE: i64 = 100;
+
+/// Activates bounties once their reward deposit confirms on-chain (and,
+/// with `verify_deposits`, the blockchain sync has verified it), and
+/// cancels (refunding any late deposit) those that stay unfunded past the
+/// funding timeout. A bounty is checked as soon as the payment-service
+/// announces its escrow confirmed; the poll catches missed announcements and
+/// the timeout.
+pub struct FundingWorker {
+    db: PgPool,
+    payments: Arc<PaymentClient>,
+    config: PaymentServiceConfig,
+    verify_deposits: bool,
+    events: EventSubscriber,
+}
+
+impl FundingWorker {
+    pub fn new(
+        db: PgPool,
+        payments: Arc<PaymentClient>,
+        config: PaymentServiceConfig,
+        verify_deposits: bool,
+        events: EventSubscriber,
+    ) -> Self {
+        Self {
+            db,
+            payments,
+            config,
+            verify_deposits,
+            events,
+        }
+    }
+
+    /// Start the funding worker
+    pub async fn run(&self) {
+        info!(
+            "Starting escrow funding worker (polling every {}s)...",
+            self.config.funding_poll_seconds
+        );
+        tokio::join!(self.poll(), self.listen());
+    }
+
+    async fn poll(&self) {
+        let mut ticker = interval(Duration::from_secs(self.config.funding_poll_seconds));
+
+        loop {
+            ticker.tick().await;
+
+            if let Err(e) = self.check_pending_bounties().await {
+                error!("Error checking bounty funding: {}", e);
+            }
+        }
+    }
+
+    async fn listen(&self) {
+        loop {
+            match self.events.subscribe(&["payment_updated"]).await {
+                Ok(events) => {
+                    let mut events = Box::pin(events);
+                    while let Some(event) = events.next().await {
+                        let NexusEvent::PaymentUpdated(updated) = event else {
+                            continue;
+                        };
+                        if updated.kind != PaymentEventKind::EscrowConfirmed {
+                            continue;
+                        }
+                        let Some(bounty_id) = updated.bounty_id else {
+                            continue;
+                        };
+                        if let Err(e) = self.check_funded(bounty_id).await {
+                            warn!("Error checking funding of bounty {}: {}", bounty_id, e);
+                        }
+                    }
+                    warn!("Payment event stream ended");
+                }
+                Err(e) => error!("Payment event subscription failed: {}", e),
+            }
+            sleep(RESUBSCRIBE_DELAY).await;
+        }
+    }
+
+    /// Check a bounty whose escrow was announced confirmed, if it still
+    /// waits for funding
+    async fn check_funded(&self, bounty_id: Uuid) -> Result<(), WorkerError> {
+        let Some(bounty) = BountyModel::find_by_id(&self.db, bounty_id)
+            .await
+            .map_err(|e| WorkerError::DatabaseError(e.to_string()))?
+        else {
+            return Ok(());
+        };
+        if bounty.status != BountyStatus::PendingFunding.as_str() {
+            return Ok(());
+        }
+        self.check_bounty(&bounty, false).await
+    }
+
+    async fn check_pending_bounties(&self) -> Result<(), WorkerError> {
+        let bounties = BountyModel::list(
+            &self.db,
+            Some(BountyStatus::PendingFunding.as_str()),
+            None,
+            BATCH_SIZE,
+            0,
+        )
+        .await
+        .map_err(|e| WorkerError::DatabaseError(e.to_string()))?;
+
+        let cutoff = Utc::now() - chrono::Duration::hours(self.config.funding_timeout_hours as i64);
+        for bounty in bounties {
+            if let Err(e) = self.check_bounty(&bounty, bounty.created_at < cutoff).await {
+                warn!("Error checking funding of bounty {}: {}", bounty.id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn check_bounty(&self, bounty: &BountyModel, timed_out: bool) -> Result<(), WorkerError> {
+        let escrow = self
+            .payments
+            .escrow(bounty.id)
+            .await
+            .map_err(|e| WorkerError::PaymentError(e.to_string()))?;
+
+        let funded = match escrow.as_ref().filter(|escrow| escrow.is_funded()) {
+            Some(escrow) => DepositVerification::confirmed(
+                &self.db,
+                self.verify_deposits,
+                bounty.id,
+                escrow.deposit_tx_hash.as_deref(),
+            )
+            .await
+            .map_err(|e| WorkerError::DatabaseError(e.to_string()))?,
+            None => false,
+        };
+        if funded {
+            let activated = BountyModel::transition(
+                &self.db,
+                bounty.id,
+                BountyStatus::PendingFunding.as_str(),
+                BountyStatus::Active.as_str(),
+            )
+            .await
+            .map_err(|e| WorkerError::DatabaseError(e.to_string()))?;
+            if activated {
+                info!("Bounty {} funded and activated", bounty.id);
+            }
+            return Ok(());
+        }
+        if escrow.as_ref().is_some_and(|escrow| escrow.is_failed()) {
+            warn!("Reward deposit for bounty {} failed; awaiting a new one", bounty.id);
+        }
+
+        if timed_out {
+            // A deposit that confirms after this is refunded by the payment-service
+            if escrow.is_some() {
+                self.payments
+                    .refund(bounty.id, RefundReason::Unfunded)
+                    .await
+                    .map_err(|e| WorkerError::PaymentError(e.to_string()))?;
+            }
+            BountyModel::update_status(&self.db, bounty.id, BountyStatus::Cancelled.as_str())
+                .await
+                .map_err(|e| WorkerError::DatabaseError(e.to_string()))?;
+            info!(
+                "Cancelled bounty {}: not funded within {} hours",
+                bounty.id, self.config.funding_timeout_hours
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum WorkerError {
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+
+    #[error("Payment error: {0}")]
+    PaymentError(String),
+}
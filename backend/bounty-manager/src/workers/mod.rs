@@ -4,8 +4,24 @@ pub mod consensus_worker;
 pub mod payout_worker;
 pub mod validation_worker;
 pub mod reputation_worker;
+pub mod archival_worker;
+pub mod funding_worker;
+pub mod expiration_worker;
+pub mod standing_worker;
+pub mod webhook_worker;
+pub mod analytics_worker;
+pub mod export_worker;
+pub mod calibration_worker;
 
 pub use consensus_worker::ConsensusWorker;
 pub use payout_worker::PayoutWorker;
 pub use validation_worker::ValidationWorker;
 pub use reputation_worker::ReputationWorker;
+pub use archival_worker::ArchivalWorker;
+pub use funding_worker::FundingWorker;
+pub use expiration_worker::ExpirationWorker;
+pub use standing_worker::StandingBountyWorker;
+pub use webhook_worker::WebhookWorker;
+pub use analytics_worker::AnalyticsWorker;
+pub use export_worker::ExportWorker;
+pub use calibration_worker::CalibrationWorker;
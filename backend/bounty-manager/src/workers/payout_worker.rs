@@ -7,6 +7,7 @@ use tracing::{error, info};
 use crate::services::blockchain::BlockchainService;
 use crate::services::notification::NotificationService;
 use crate::models::payout::PayoutModel;
+use crate::models::webhook::{WebhookDelivery, WebhookEvent};
 
 pub struct PayoutWorker {
     db: PgPool,
@@ -89,6 +90,18 @@ impl PayoutWorker {
         .await
         .map_err(|e| WorkerError::DatabaseError(e.to_string()))?;
 
+        let event = serde_json::json!({
+            "payout_id": payout.id,
+            "submission_id": payout.submission_id,
+            "recipient": payout.recipient,
+            "amount": payout.amount.to_string(),
+            "currency": payout.currency,
+            "transaction_hash": transaction.transaction_hash,
+        });
+        if let Err(e) = WebhookDelivery::enqueue(&self.db, payout.bounty_id, WebhookEvent::PayoutExecuted, &event).await {
+            error!("Failed to queue payout webhooks for bounty {}: {}", payout.bounty_id, e);
+        }
+
         // Send notification
         if let Err(e) = self.notification_service
             .notify_payout_processed(
@@ -0,0 +1,280 @@
+// backend/bounty-manager/src/workers/standing_worker.rs
+
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use sqlx::PgPool;
+use std::sync::Arc;
+use tokio::time::{interval, Duration};
+use tracing::{error, info, warn};
+use crate::config::StandingBountyConfig;
+use crate::handlers::bounty_crud::{open_bounty, ArtifactData, ArtifactType};
+use crate::handlers::standing::{artifact_key, artifact_of, child_bounty};
+use crate::handlers::AnalysisDetails;
+use crate::models::bounty::BountyModel;
+use crate::models::standing::{StandingBounty, WatchKind, WatchRule};
+use crate::services::moderation::ContentScreen;
+use crate::services::payment::PaymentClient;
+
+/// What came of a trigger
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Spawn {
+    Opened,
+    /// The trigger already has a child, or has nothing to open one on
+    Skipped,
+    /// The standing bounty has all the children it may have and is now disabled
+    LimitReached,
+}
+
+/// Opens child bounties of standing bounties: for schedules whose next run
+/// is due, and for new bounties or submissions whose artifact matches a
+/// watch rule. Children are escrowed like any bounty, so they stay pending
+/// until the creator funds them.
+pub struct StandingBountyWorker {
+    db: PgPool,
+    payments: Arc<PaymentClient>,
+    content_screen: Arc<ContentScreen>,
+    config: StandingBountyConfig,
+    verify_deposits: bool,
+}
+
+impl StandingBountyWorker {
+    pub fn new(
+        db: PgPool,
+        payments: Arc<PaymentClient>,
+        content_screen: Arc<ContentScreen>,
+        config: StandingBountyConfig,
+        verify_deposits: bool,
+    ) -> Self {
+        Self {
+            db,
+            payments,
+            content_screen,
+            config,
+            verify_deposits,
+        }
+    }
+
+    /// Start the standing bounty worker
+    pub async fn run(&self) {
+        info!(
+            "Starting standing bounty worker (checking every {}s)...",
+            self.config.interval_seconds
+        );
+        let mut ticker = interval(Duration::from_secs(self.config.interval_seconds));
+
+        loop {
+            ticker.tick().await;
+
+            if let Err(e) = self.run_schedules().await {
+                error!("Error running standing bounty schedules: {}", e);
+            }
+            if let Err(e) = self.run_watches().await {
+                error!("Error running standing bounty watches: {}", e);
+            }
+        }
+    }
+
+    async fn run_schedules(&self) -> Result<(), WorkerError> {
+        let due = StandingBounty::due_schedules(&self.db, Utc::now(), self.config.batch_size)
+            .await
+            .map_err(|e| WorkerError::DatabaseError(e.to_string()))?;
+
+        for standing in due {
+            if let Err(e) = self.run_schedule(&standing).await {
+                error!("Error running standing bounty {}: {}", standing.id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn run_schedule(&self, standing: &StandingBounty) -> Result<(), WorkerError> {
+        let (Some(next_run_at), Some(interval_hours)) = (standing.next_run_at, standing.interval_hours) else {
+            return Ok(());
+        };
+        let artifact = standing.artifact_type.as_ref().zip(standing.artifact_data.as_ref()).and_then(
+            |(artifact_type, artifact_data)| {
+                let artifact_type: ArtifactType =
+                    serde_json::from_value(serde_json::Value::String(artifact_type.clone())).ok()?;
+                let artifact_data: ArtifactData = serde_json::from_value(artifact_data.clone()).ok()?;
+                Some((artifact_type, artifact_data))
+            },
+        );
+        let Some((artifact_type, artifact_data)) = artifact else {
+            warn!("Standing bounty {} has an unreadable artifact; disabling it", standing.id);
+            return self.disable(standing.id).await;
+        };
+
+        // One child per slot, so a run retried after a crash does not open
+        // the slot twice
+        let trigger_ref = format!("schedule:{}", next_run_at.to_rfc3339());
+        if self.spawn(standing, artifact_type, artifact_data, &trigger_ref).await? == Spawn::LimitReached {
+            return Ok(());
+        }
+
+        // Slots missed while the worker was down are skipped, not caught up
+        let interval = ChronoDuration::hours(interval_hours as i64);
+        let now = Utc::now();
+        let mut next = next_run_at + interval;
+        if next <= now {
+            next = now + interval;
+        }
+        StandingBounty::schedule_next_run(&self.db, standing.id, next)
+            .await
+            .map_err(|e| WorkerError::DatabaseError(e.to_string()))
+    }
+
+    async fn run_watches(&self) -> Result<(), WorkerError> {
+        let watches = StandingBounty::active_watches(&self.db)
+            .await
+            .map_err(|e| WorkerError::DatabaseError(e.to_string()))?;
+
+        for standing in watches {
+            if let Err(e) = self.run_watch(&standing).await {
+                error!("Error running standing bounty {}: {}", standing.id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Match the artifacts seen since the watch last ran. The watermark only
+    /// moves past artifacts that were handled, so a failed spawn is retried.
+    async fn run_watch(&self, standing: &StandingBounty) -> Result<(), WorkerError> {
+        let Some(rule) = standing.watch_rule() else {
+            warn!("Standing bounty {} has an unreadable watch rule; disabling it", standing.id);
+            return self.disable(standing.id).await;
+        };
+        let since = standing.watched_until.unwrap_or(standing.created_at);
+
+        let candidates = self.watch_candidates(&rule, since).await?;
+        let mut watched_until = since;
+        let mut result = Ok(());
+        for (seen_at, bounty) in candidates {
+            if let Some(bounty) = bounty {
+                match self.spawn_for(standing, &bounty).await {
+                    Ok(Spawn::LimitReached) => return Ok(()),
+                    Ok(_) => {}
+                    Err(e) => {
+                        result = Err(e);
+                        break;
+                    }
+                }
+            }
+            watched_until = seen_at;
+        }
+
+        if watched_until > since {
+            StandingBounty::advance_watch(&self.db, standing.id, watched_until)
+                .await
+                .map_err(|e| WorkerError::DatabaseError(e.to_string()))?;
+        }
+        result
+    }
+
+    /// Bounties or submissions newer than `since`, oldest first, each with
+    /// the bounty whose artifact matched the rule, if any
+    async fn watch_candidates(
+        &self,
+        rule: &WatchRule,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<(DateTime<Utc>, Option<BountyModel>)>, WorkerError> {
+        let db_error = |e: sqlx::Error| WorkerError::DatabaseError(e.to_string());
+
+        if rule.kind != WatchKind::YaraFamily {
+            let bounties = StandingBounty::bounties_since(&self.db, since, self.config.batch_size)
+                .await
+                .map_err(db_error)?;
+            return Ok(bounties
+                .into_iter()
+                .map(|bounty| {
+                    let matched = bounty.artifact_hash.as_deref().is_some_and(|hash| rule.matches_hash(hash))
+                        || bounty.artifact_url.as_deref().is_some_and(|url| rule.matches_url(url));
+                    (bounty.created_at, matched.then_some(bounty))
+                })
+                .collect());
+        }
+
+        let submissions = StandingBounty::submissions_since(&self.db, since, self.config.batch_size)
+            .await
+            .map_err(db_error)?;
+        let mut candidates = Vec::with_capacity(submissions.len());
+        for submission in submissions {
+            // Submissions whose analysis does not parse report no families
+            let matched = serde_json::from_value::<AnalysisDetails>(submission.analysis_details.clone())
+                .is_ok_and(|details| rule.matches_family(details.families()));
+            let bounty = if matched {
+                BountyModel::find_by_id(&self.db, submission.bounty_id)
+                    .await
+                    .map_err(db_error)?
+            } else {
+                None
+            };
+            candidates.push((submission.submitted_at, bounty));
+        }
+        Ok(candidates)
+    }
+
+    /// Open a child on the artifact of a bounty that matched a watch
+    async fn spawn_for(&self, standing: &StandingBounty, bounty: &BountyModel) -> Result<Spawn, WorkerError> {
+        let Some((artifact_type, artifact_data)) = artifact_of(bounty) else {
+            warn!("Bounty {} matched standing bounty {} but has no usable artifact", bounty.id, standing.id);
+            return Ok(Spawn::Skipped);
+        };
+        // Keyed by artifact, so the child itself never triggers another
+        self.spawn(standing, artifact_type, artifact_data, &artifact_key(bounty)).await
+    }
+
+    async fn spawn(
+        &self,
+        standing: &StandingBounty,
+        artifact_type: ArtifactType,
+        artifact_data: ArtifactData,
+        trigger_ref: &str,
+    ) -> Result<Spawn, WorkerError> {
+        if let Some(max_children) = standing.max_children {
+            let children = StandingBounty::child_count(&self.db, standing.id)
+                .await
+                .map_err(|e| WorkerError::DatabaseError(e.to_string()))?;
+            if children >= max_children as i64 {
+                info!("Standing bounty {} reached its {} children; disabling it", standing.id, max_children);
+                self.disable(standing.id).await?;
+                return Ok(Spawn::LimitReached);
+            }
+        }
+
+        let child = child_bounty(standing, artifact_type, artifact_data, trigger_ref);
+        let child_id = child.id;
+        if !StandingBounty::claim_child(&self.db, standing.id, trigger_ref, child_id)
+            .await
+            .map_err(|e| WorkerError::DatabaseError(e.to_string()))?
+        {
+            return Ok(Spawn::Skipped);
+        }
+
+        if let Err(status) = open_bounty(&self.db, &self.payments, &self.content_screen, child, None, self.verify_deposits).await {
+            // Free the trigger so the next run tries again
+            if let Err(e) = StandingBounty::release_child(&self.db, standing.id, trigger_ref).await {
+                error!("Failed to release trigger {} of standing bounty {}: {}", trigger_ref, standing.id, e);
+            }
+            return Err(WorkerError::SpawnError(format!("child bounty not opened ({})", status)));
+        }
+
+        info!("Standing bounty {} opened bounty {} ({})", standing.id, child_id, trigger_ref);
+        Ok(Spawn::Opened)
+    }
+
+    async fn disable(&self, id: uuid::Uuid) -> Result<(), WorkerError> {
+        StandingBounty::disable(&self.db, id)
+            .await
+            .map_err(|e| WorkerError::DatabaseError(e.to_string()))
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum WorkerError {
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+
+    #[error("Spawn error: {0}")]
+    SpawnError(String),
+}
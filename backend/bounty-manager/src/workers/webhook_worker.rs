@@ -0,0 +1,99 @@
+// backend/bounty-manager/src/workers/webhook_worker.rs
+
+use chrono::Utc;
+use shared::webhooks::retry_delay;
+use sqlx::PgPool;
+use tokio::time::{interval, Duration};
+use tracing::{error, info, warn};
+use crate::config::WebhookConfig;
+use crate::models::webhook::{BountyWebhook, WebhookDelivery};
+use crate::services::webhook::WebhookSender;
+
+/// Sends queued bounty events to creators' webhooks, retrying failed
+/// deliveries with exponential backoff until `max_attempts`
+pub struct WebhookWorker {
+    db: PgPool,
+    sender: WebhookSender,
+    config: WebhookConfig,
+}
+
+impl WebhookWorker {
+    pub fn new(db: PgPool, sender: WebhookSender, config: WebhookConfig) -> Self {
+        Self { db, sender, config }
+    }
+
+    /// Start the webhook delivery worker
+    pub async fn run(&self) {
+        info!(
+            "Starting webhook worker (checking every {}s)...",
+            self.config.interval_seconds
+        );
+        let mut ticker = interval(Duration::from_secs(self.config.interval_seconds));
+
+        loop {
+            ticker.tick().await;
+
+            if let Err(e) = self.deliver_due().await {
+                error!("Error delivering webhooks: {}", e);
+            }
+        }
+    }
+
+    async fn deliver_due(&self) -> Result<(), WorkerError> {
+        let due = WebhookDelivery::due(&self.db, Utc::now(), self.config.batch_size)
+            .await
+            .map_err(|e| WorkerError::DatabaseError(e.to_string()))?;
+
+        for (delivery, webhook) in due {
+            if let Err(e) = self.deliver(&delivery, &webhook).await {
+                error!("Error recording webhook delivery {}: {}", delivery.id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn deliver(&self, delivery: &WebhookDelivery, webhook: &BountyWebhook) -> Result<(), WorkerError> {
+        let result = if webhook.enabled {
+            self.sender.send(webhook, delivery).await
+        } else {
+            // Events queued before the webhook was disabled are dropped
+            return WebhookDelivery::mark_failed(&self.db, delivery.id, None, "webhook disabled", None)
+                .await
+                .map_err(|e| WorkerError::DatabaseError(e.to_string()));
+        };
+
+        match result {
+            Ok(status) => WebhookDelivery::mark_delivered(&self.db, delivery.id, status).await,
+            Err(e) => {
+                let attempts = delivery.attempts.max(0) as u32 + 1;
+                let retry_at = (attempts < self.config.max_attempts).then(|| {
+                    let delay = retry_delay(
+                        attempts,
+                        Duration::from_secs(self.config.backoff_base_seconds),
+                        Duration::from_secs(self.config.backoff_max_seconds),
+                    );
+                    Utc::now() + chrono::Duration::seconds(delay.as_secs() as i64)
+                });
+                match retry_at {
+                    Some(at) => warn!(
+                        "Webhook delivery {} to {} failed ({}); retrying at {}",
+                        delivery.id, webhook.url, e, at
+                    ),
+                    None => warn!(
+                        "Webhook delivery {} to {} failed after {} attempts: {}",
+                        delivery.id, webhook.url, attempts, e
+                    ),
+                }
+                WebhookDelivery::mark_failed(&self.db, delivery.id, e.response_status(), &e.to_string(), retry_at).await
+            }
+        }
+        .map_err(|e| WorkerError::DatabaseError(e.to_string()))
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum WorkerError {
+    #[error("Database error: {0}")]
+    DatabaseError(String),
+}
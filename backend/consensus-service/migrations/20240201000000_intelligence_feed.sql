@@ -0,0 +1,36 @@
+-- Public intelligence feed built from finalized consensus results
+
+-- Artifact identity and finalization time are what the feed exposes;
+-- participant and vote data stay internal
+ALTER TABLE consensus_results ADD COLUMN IF NOT EXISTS artifact_hash VARCHAR(128);
+ALTER TABLE consensus_results ADD COLUMN IF NOT EXISTS finalized_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_consensus_feed_order
+    ON consensus_results(finalized_at, id)
+    WHERE finalized_at IS NOT NULL AND artifact_hash IS NOT NULL;
+
+-- API keys for feed consumers (only the SHA-256 of the key is stored)
+CREATE TABLE IF NOT EXISTS feed_api_keys (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    key_hash VARCHAR(64) NOT NULL UNIQUE,
+    name VARCHAR(100) NOT NULL,
+    tier VARCHAR(20) NOT NULL DEFAULT 'community' CHECK (
+        tier IN ('community', 'standard', 'premium')
+    ),
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    last_used_at TIMESTAMPTZ,
+    expires_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_feed_api_keys_active ON feed_api_keys(is_active);
+
+-- Signed daily bulk exports written to object storage
+CREATE TABLE IF NOT EXISTS feed_exports (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    export_date DATE NOT NULL UNIQUE,
+    object_key TEXT NOT NULL,
+    signature VARCHAR(64) NOT NULL,
+    entry_count INTEGER NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
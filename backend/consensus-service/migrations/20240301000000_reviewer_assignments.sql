@@ -0,0 +1,60 @@
+-- Reviewer assignment for human-review stages and dispute panels
+
+-- Reviewers eligible for assignment
+CREATE TABLE IF NOT EXISTS reviewer_profiles (
+    user_id UUID PRIMARY KEY,
+    -- Reviewers never judge work from their own organization
+    organization_id UUID,
+    specializations TEXT[] NOT NULL DEFAULT '{}',
+    -- Offset from UTC, used to spread a panel across time zones
+    utc_offset_minutes INTEGER NOT NULL DEFAULT 0 CHECK (
+        utc_offset_minutes BETWEEN -720 AND 840
+    ),
+    max_open_assignments INTEGER NOT NULL DEFAULT 5 CHECK (max_open_assignments > 0),
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- A set of reviewers needed for one submission review or dispute
+CREATE TABLE IF NOT EXISTS review_panels (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    kind VARCHAR(20) NOT NULL CHECK (kind IN ('human_review', 'dispute_panel')),
+    -- Submission under human review, or the dispute being judged
+    subject_id UUID NOT NULL,
+    bounty_id UUID NOT NULL,
+    submitter_id UUID NOT NULL,
+    submitter_organization_id UUID,
+    required_specializations TEXT[] NOT NULL DEFAULT '{}',
+    -- Further users who must not sit on the panel (e.g. the disputer)
+    excluded_reviewers UUID[] NOT NULL DEFAULT '{}',
+    panel_size INTEGER NOT NULL CHECK (panel_size > 0),
+    staffed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (kind, subject_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_review_panels_unstaffed
+    ON review_panels(created_at) WHERE staffed_at IS NULL;
+
+-- Offers made to reviewers; a reviewer is offered a seat on a panel at most once
+CREATE TABLE IF NOT EXISTS review_assignments (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    panel_id UUID REFERENCES review_panels(id) ON DELETE CASCADE NOT NULL,
+    reviewer_id UUID REFERENCES reviewer_profiles(user_id) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending' CHECK (
+        status IN ('pending', 'accepted', 'declined', 'expired', 'completed')
+    ),
+    score DOUBLE PRECISION NOT NULL,
+    offered_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    respond_by TIMESTAMPTZ NOT NULL,
+    responded_at TIMESTAMPTZ,
+    decline_reason TEXT,
+    completed_at TIMESTAMPTZ,
+    UNIQUE (panel_id, reviewer_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_review_assignments_reviewer
+    ON review_assignments(reviewer_id, offered_at DESC);
+CREATE INDEX IF NOT EXISTS idx_review_assignments_overdue
+    ON review_assignments(respond_by) WHERE status = 'pending';
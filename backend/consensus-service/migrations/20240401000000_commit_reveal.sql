@@ -0,0 +1,15 @@
+-- Blind submissions: on commit-reveal bounties an engine first commits to a
+-- hash of its verdict and a nonce, and its vote is only recorded once it
+-- reveals a matching verdict after the submission window closes
+
+CREATE TABLE IF NOT EXISTS consensus_commitments (
+    bounty_id UUID NOT NULL,
+    engine_id VARCHAR(255) NOT NULL,
+    -- Hex SHA-256 of "<verdict>:<nonce>"
+    commitment CHAR(64) NOT NULL,
+    -- When the submission window closes and reveals are accepted
+    reveal_after TIMESTAMPTZ NOT NULL,
+    committed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    revealed_at TIMESTAMPTZ,
+    PRIMARY KEY (bounty_id, engine_id)
+);
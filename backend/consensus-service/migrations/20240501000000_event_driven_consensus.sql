@@ -0,0 +1,15 @@
+-- Consensus is calculated from bounty-manager events: provisionally as
+-- submissions arrive, and finally when the bounty closes. Each bounty keeps
+-- one result, which no longer changes once finalized.
+
+DELETE FROM consensus_results a
+USING consensus_results b
+WHERE a.bounty_id = b.bounty_id
+  AND (a.created_at, a.id) < (b.created_at, b.id);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_consensus_results_bounty_unique ON consensus_results(bounty_id);
+
+-- Share of the vote behind the final verdict, 0 to 100
+ALTER TABLE consensus_results ADD COLUMN IF NOT EXISTS agreement_score DECIMAL(7,4);
+-- Whether the verdict met the consensus threshold with enough submissions
+ALTER TABLE consensus_results ADD COLUMN IF NOT EXISTS consensus_reached BOOLEAN NOT NULL DEFAULT false;
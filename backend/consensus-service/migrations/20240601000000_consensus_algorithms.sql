@@ -0,0 +1,19 @@
+-- Bounties choose how their votes are aggregated. The bounty-manager passes
+-- the algorithm along with submissions; bounties without a row here use the
+-- service default.
+
+CREATE TABLE IF NOT EXISTS consensus_bounty_settings (
+    bounty_id UUID PRIMARY KEY,
+    -- simple_majority, reputation_weighted, stake_weighted or bayesian_truth_serum
+    algorithm VARCHAR(50) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Stake behind each vote, for stake-weighted consensus
+ALTER TABLE consensus_submissions ADD COLUMN IF NOT EXISTS stake_amount BIGINT NOT NULL DEFAULT 0;
+-- The engine's predicted share of each verdict, for Bayesian truth serum
+ALTER TABLE consensus_submissions ADD COLUMN IF NOT EXISTS prediction JSONB;
+
+-- Algorithm the result was calculated with
+ALTER TABLE consensus_results ADD COLUMN IF NOT EXISTS algorithm VARCHAR(50);
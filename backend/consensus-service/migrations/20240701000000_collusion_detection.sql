@@ -0,0 +1,34 @@
+-- Collusion and Sybil detection. Submissions carry where they came from and
+-- what they said, so engines acting as a ring can be spotted: engines that
+-- agree against the majority, submit simultaneously, share an address or
+-- wallet funding, or copy each other's analyses. Rings found are kept as
+-- clusters for admins to review; their votes count for less meanwhile.
+
+ALTER TABLE consensus_submissions ADD COLUMN IF NOT EXISTS source_ip VARCHAR(64);
+ALTER TABLE consensus_submissions ADD COLUMN IF NOT EXISTS wallet_address VARCHAR(42);
+ALTER TABLE consensus_submissions ADD COLUMN IF NOT EXISTS analysis_text TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_submissions_submitted_at ON consensus_submissions(submitted_at);
+
+CREATE TABLE IF NOT EXISTS collusion_clusters (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- Sorted member engine IDs joined by commas; a ring found again updates
+    -- its cluster
+    cluster_key TEXT NOT NULL UNIQUE,
+    engine_ids TEXT[] NOT NULL,
+    score DOUBLE PRECISION NOT NULL,
+    -- Evidence per pair of members
+    evidence JSONB NOT NULL,
+    -- flagged | confirmed | dismissed
+    status VARCHAR(20) NOT NULL DEFAULT 'flagged',
+    -- Vote weight left to the members while the cluster is not dismissed
+    weight_factor DOUBLE PRECISION NOT NULL,
+    detected_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    reviewed_by UUID,
+    reviewed_at TIMESTAMPTZ,
+    review_notes TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_collusion_clusters_engines ON collusion_clusters USING GIN (engine_ids);
+CREATE INDEX IF NOT EXISTS idx_collusion_clusters_status ON collusion_clusters(status, detected_at DESC);
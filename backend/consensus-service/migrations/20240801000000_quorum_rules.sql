@@ -0,0 +1,13 @@
+-- Results are finalized before the bounty closes once a quorum agrees on a
+-- verdict, or once the maximum wait after the first submission has passed.
+-- Bounties may set their own quorum; rules left NULL use the service default.
+
+ALTER TABLE consensus_bounty_settings ALTER COLUMN algorithm DROP NOT NULL;
+ALTER TABLE consensus_bounty_settings ADD COLUMN IF NOT EXISTS min_submissions INTEGER;
+ALTER TABLE consensus_bounty_settings ADD COLUMN IF NOT EXISTS min_total_stake BIGINT;
+ALTER TABLE consensus_bounty_settings ADD COLUMN IF NOT EXISTS max_wait_hours INTEGER;
+
+-- bounty_closed, quorum_met or max_wait_elapsed
+ALTER TABLE consensus_results ADD COLUMN IF NOT EXISTS finalized_reason VARCHAR(20);
+
+CREATE INDEX IF NOT EXISTS idx_consensus_results_open ON consensus_results(bounty_id) WHERE finalized_at IS NULL;
@@ -0,0 +1,6 @@
+-- Votes are filtered before aggregation: dropped for engines with poor
+-- accuracy or a duplicate identity, down-weighted for uncalibrated
+-- confidence. Each result records the weight behind every counted vote and
+-- the filtering decisions, as {"votes": [...], "filtered": [...]}.
+
+ALTER TABLE consensus_results ADD COLUMN IF NOT EXISTS weighted_votes JSONB;
@@ -0,0 +1,53 @@
+-- Disputes against finalized verdicts. The disputer stakes tokens behind the
+-- claim and an arbitration panel of high-trust reviewers votes on it before
+-- its deadline; the panel's verdict replaces the bounty's when it differs.
+
+CREATE TABLE IF NOT EXISTS disputes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    bounty_id UUID NOT NULL,
+    submission_id UUID,
+    initiator_id UUID NOT NULL,
+    disputed_verdict VARCHAR(50) NOT NULL,
+    claimed_verdict VARCHAR(50) NOT NULL,
+    reason TEXT NOT NULL,
+    evidence JSONB,
+    wallet_address VARCHAR(42) NOT NULL,
+    -- In wei, locked through the payment-service
+    stake_amount NUMERIC(78, 0) NOT NULL,
+    stake_id UUID,
+    -- open until a panel is seated, under_review while it votes
+    status VARCHAR(20) NOT NULL DEFAULT 'open' CHECK (
+        status IN ('open', 'under_review', 'resolved', 'rejected')
+    ),
+    panel_id UUID REFERENCES review_panels(id),
+    voting_deadline TIMESTAMPTZ NOT NULL,
+    outcome VARCHAR(20) CHECK (outcome IN ('upheld', 'rejected', 'inconclusive')),
+    final_verdict VARCHAR(50),
+    resolution TEXT,
+    -- Stake and reputation changes applied on resolution
+    settlement JSONB,
+    -- NULL when the panel's vote resolved the dispute
+    resolved_by UUID,
+    resolved_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- One live dispute per user and bounty
+CREATE UNIQUE INDEX IF NOT EXISTS idx_disputes_live
+    ON disputes(bounty_id, initiator_id) WHERE status IN ('open', 'under_review');
+CREATE INDEX IF NOT EXISTS idx_disputes_bounty ON disputes(bounty_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_disputes_pending
+    ON disputes(voting_deadline) WHERE status IN ('open', 'under_review');
+
+-- One vote per arbitrator holding a seat on the dispute's panel
+CREATE TABLE IF NOT EXISTS dispute_votes (
+    dispute_id UUID REFERENCES disputes(id) ON DELETE CASCADE NOT NULL,
+    arbitrator_id UUID NOT NULL,
+    verdict VARCHAR(50) NOT NULL,
+    rationale TEXT,
+    voted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (dispute_id, arbitrator_id)
+);
+
+-- Panels restricted to these reviewers; NULL lets any active reviewer sit
+ALTER TABLE review_panels ADD COLUMN IF NOT EXISTS eligible_reviewers UUID[];
@@ -0,0 +1,28 @@
+-- Each finalized consensus keeps a snapshot of what it was calculated from:
+-- the votes before filtering, the engines' history, wallets and collusion
+-- factors, the settings in force and the outcome as calculated. Snapshots
+-- never change, so an audit can replay a result long after the weighting,
+-- or the result itself after an upheld dispute, has moved on.
+
+CREATE TABLE IF NOT EXISTS consensus_snapshots (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    bounty_id UUID NOT NULL UNIQUE,
+    algorithm VARCHAR(32) NOT NULL,
+    inputs JSONB NOT NULL,
+    settings JSONB NOT NULL,
+    outcome JSONB NOT NULL,
+    finalized_reason VARCHAR(32) NOT NULL,
+    finalized_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE OR REPLACE FUNCTION reject_consensus_snapshot_change() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'consensus snapshots are immutable';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS consensus_snapshots_immutable ON consensus_snapshots;
+CREATE TRIGGER consensus_snapshots_immutable
+    BEFORE UPDATE OR DELETE ON consensus_snapshots
+    FOR EACH ROW EXECUTE FUNCTION reject_consensus_snapshot_change();
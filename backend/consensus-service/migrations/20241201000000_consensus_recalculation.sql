@@ -0,0 +1,31 @@
+-- Batches of bounties queued for consensus recalculation, e.g. after a bug
+-- in the scoring is fixed. Each bounty in a batch is an item the worker
+-- claims, recalculates and records the outcome of: its provisional result
+-- updated, its final result replayed from its snapshot, or the failure.
+-- `differences` lists what came out differently, as
+-- [{"field", "engine_id", "original", "replayed"}].
+
+CREATE TABLE IF NOT EXISTS consensus_recalculation_batches (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    filter JSONB NOT NULL,
+    requested_by UUID NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMPTZ,
+    completed_at TIMESTAMPTZ
+);
+
+CREATE TABLE IF NOT EXISTS consensus_recalculation_items (
+    batch_id UUID NOT NULL REFERENCES consensus_recalculation_batches(id) ON DELETE CASCADE,
+    bounty_id UUID NOT NULL,
+    status VARCHAR(16) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'running', 'updated', 'replayed', 'no_snapshot', 'failed')),
+    differences JSONB,
+    error TEXT,
+    claimed_at TIMESTAMPTZ,
+    processed_at TIMESTAMPTZ,
+    PRIMARY KEY (batch_id, bounty_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_recalculation_items_open
+    ON consensus_recalculation_items(status)
+    WHERE status IN ('pending', 'running');
@@ -0,0 +1,52 @@
+-- Engines report the import hash, ssdeep fuzzy hash and contacted domains of
+-- the artifact they analyzed. Once a result is final, the indicators most
+-- engines agree on are kept in artifact_indicators and compared with other
+-- finalized artifacts; related artifacts with contradicting verdicts are
+-- opened as consensus_conflicts for analysts to review. `relations` lists
+-- what links the pair, as [{"kind", "value", "similarity"}].
+
+ALTER TABLE consensus_submissions ADD COLUMN IF NOT EXISTS imphash VARCHAR(32);
+ALTER TABLE consensus_submissions ADD COLUMN IF NOT EXISTS ssdeep VARCHAR(160);
+ALTER TABLE consensus_submissions ADD COLUMN IF NOT EXISTS contacted_domains TEXT[];
+
+-- Set once a finalized result has been compared with other artifacts
+ALTER TABLE consensus_results ADD COLUMN IF NOT EXISTS correlated_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_consensus_results_uncorrelated
+    ON consensus_results(finalized_at)
+    WHERE finalized_at IS NOT NULL AND correlated_at IS NULL;
+
+CREATE TABLE IF NOT EXISTS artifact_indicators (
+    bounty_id UUID PRIMARY KEY,
+    artifact_hash VARCHAR(128),
+    imphash VARCHAR(32),
+    ssdeep VARCHAR(160),
+    ssdeep_block_size BIGINT,
+    domains TEXT[] NOT NULL DEFAULT '{}',
+    finalized_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_artifact_indicators_imphash ON artifact_indicators(imphash) WHERE imphash IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_artifact_indicators_block_size ON artifact_indicators(ssdeep_block_size) WHERE ssdeep_block_size IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_artifact_indicators_domains ON artifact_indicators USING GIN (domains);
+
+CREATE TABLE IF NOT EXISTS consensus_conflicts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    bounty_id UUID NOT NULL,
+    artifact_hash VARCHAR(128),
+    verdict VARCHAR(20) NOT NULL,
+    related_bounty_id UUID NOT NULL,
+    related_artifact_hash VARCHAR(128),
+    related_verdict VARCHAR(20) NOT NULL,
+    relations JSONB NOT NULL DEFAULT '[]',
+    status VARCHAR(20) NOT NULL DEFAULT 'open' CHECK (status IN ('open', 'confirmed', 'dismissed')),
+    detected_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    reviewed_by UUID,
+    reviewed_at TIMESTAMPTZ,
+    review_notes TEXT
+);
+
+-- One conflict per pair of bounties, whichever was finalized first
+CREATE UNIQUE INDEX IF NOT EXISTS idx_consensus_conflicts_pair
+    ON consensus_conflicts (LEAST(bounty_id, related_bounty_id), GREATEST(bounty_id, related_bounty_id));
+CREATE INDEX IF NOT EXISTS idx_consensus_conflicts_status ON consensus_conflicts(status, detected_at DESC);
@@ -0,0 +1,24 @@
+-- Automated engines register the MIME types they handle ("application/*"
+-- covers a whole type, "*/*" every artifact), the largest file they take and
+-- how long they usually take to vote, then send heartbeats while running.
+-- Matched against the artifact the bounty-manager forwards with a bounty's
+-- submissions, this tells engines that are offline from those that declined
+-- to vote.
+
+CREATE TABLE IF NOT EXISTS engine_registry (
+    engine_id VARCHAR(255) PRIMARY KEY,
+    owner_id UUID NOT NULL,
+    file_types TEXT[] NOT NULL,
+    max_file_size BIGINT,
+    expected_latency_secs BIGINT,
+    accepting_work BOOLEAN NOT NULL DEFAULT TRUE,
+    last_heartbeat_at TIMESTAMPTZ,
+    registered_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_engine_registry_owner ON engine_registry(owner_id);
+
+-- The artifact a bounty asks engines to analyze
+ALTER TABLE consensus_bounty_settings ADD COLUMN IF NOT EXISTS artifact_type VARCHAR(255);
+ALTER TABLE consensus_bounty_settings ADD COLUMN IF NOT EXISTS artifact_size BIGINT;
@@ -0,0 +1,12 @@
+-- How each finalized bounty settles, planned when its result is finalized:
+-- per engine, whether it is rewarded, slashed or refunded, its share of the
+-- reward, the share of its stake forfeited and its reputation change. The
+-- plan is announced, signed, with the final verdict; the payment and
+-- reputation services apply it by plan_id, so announcing it again is safe.
+
+CREATE TABLE IF NOT EXISTS consensus_settlement_plans (
+    bounty_id UUID PRIMARY KEY,
+    plan_id UUID NOT NULL UNIQUE,
+    plan JSONB NOT NULL,
+    planned_at TIMESTAMPTZ NOT NULL
+);
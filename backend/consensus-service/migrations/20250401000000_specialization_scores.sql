@@ -0,0 +1,4 @@
+-- The engine's reputation in the threat categories of the bounty's tags,
+-- forwarded with its submission. Reputation-weighted consensus weights the
+-- vote by it instead of the global reputation_score when it is set.
+ALTER TABLE consensus_submissions ADD COLUMN IF NOT EXISTS specialization_score INTEGER;
@@ -0,0 +1,8 @@
+-- New engines are on probation until they complete their calibration
+-- bounties: their votes count for less and their reward share is capped.
+ALTER TABLE consensus_submissions ADD COLUMN IF NOT EXISTS probation BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- The known verdict of a calibration bounty, forwarded by the bounty-manager.
+-- Calibration bounties settle on it instead of the engines' consensus.
+ALTER TABLE consensus_bounty_settings ADD COLUMN IF NOT EXISTS ground_truth VARCHAR(20)
+    CHECK (ground_truth IN ('malicious', 'benign', 'suspicious'));
@@ -0,0 +1,350 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+
+use crate::config::ConsensusConfig;
+use crate::models::{AlgorithmKind, SubmissionVote, Verdict, VerdictShares, VoteWeight};
+
+/// Lowest share a prediction or verdict counts with, so the logarithms in
+/// Bayesian truth serum stay finite
+const MIN_SHARE: f64 = 0.001;
+
+/// How a bounty's votes are weighed against each other. The verdict is the
+/// one carrying the most weight; see `ConsensusAggregator`.
+pub trait ConsensusAlgorithm: Send + Sync {
+    /// The weight each vote carries, in vote order
+    fn vote_weights(&self, votes: &[SubmissionVote]) -> Vec<VoteWeight>;
+}
+
+/// The implementation of an algorithm kind
+pub fn algorithm(kind: AlgorithmKind, config: &ConsensusConfig) -> Box<dyn ConsensusAlgorithm> {
+    match kind {
+        AlgorithmKind::SimpleMajority => Box::new(SimpleMajority),
+        AlgorithmKind::ReputationWeighted => Box::new(ReputationWeighted::new(config.clone())),
+        AlgorithmKind::StakeWeighted => Box::new(StakeWeighted),
+        AlgorithmKind::BayesianTruthSerum => Box::new(BayesianTruthSerum::default()),
+    }
+}
+
+fn vote_weight(vote: &SubmissionVote, weight: Decimal, factors: BTreeMap<String, Decimal>) -> VoteWeight {
+    VoteWeight {
+        engine_id: vote.engine_id.clone(),
+        verdict: vote.verdict.clone(),
+        factors,
+        weight,
+    }
+}
+
+fn to_decimal(value: f64) -> Decimal {
+    Decimal::try_from(value).unwrap_or_default().round_dp(6)
+}
+
+/// One vote per submission
+pub struct SimpleMajority;
+
+impl ConsensusAlgorithm for SimpleMajority {
+    fn vote_weights(&self, votes: &[SubmissionVote]) -> Vec<VoteWeight> {
+        votes
+            .iter()
+            .map(|vote| vote_weight(vote, Decimal::ONE, BTreeMap::new()))
+            .collect()
+    }
+}
+
+/// Votes weighted by the engine's reputation, its confidence and how early it
+/// submitted
+pub struct ReputationWeighted {
+    config: ConsensusConfig,
+}
+
+impl ReputationWeighted {
+    pub fn new(config: ConsensusConfig) -> Self {
+        Self { config }
+    }
+
+    /// Calculate vote weight based on multiple factors
+    fn calculate_vote_weight(&self, vote: &SubmissionVote, first_submitted: DateTime<Utc>) -> VoteWeight {
+        // Normalize reputation score (0-10000 range to 0-1), in the bounty's
+        // categories where the engine has a specialization score
+        let reputation_factor = Decimal::from(vote.weighted_reputation()) / Decimal::from(10000);
+
+        // Confidence factor (already 0-1)
+        let confidence_factor = vote.confidence;
+
+        // Time factor (early submissions weighted slightly higher)
+        let time_factor = self.calculate_time_factor(vote.submitted_at, first_submitted);
+
+        // Weighted combination
+        let reputation_weight = Decimal::try_from(self.config.reputation_weight).unwrap_or(Decimal::new(5, 1));
+        let confidence_weight = Decimal::try_from(self.config.confidence_weight).unwrap_or(Decimal::new(3, 1));
+        let time_weight = Decimal::try_from(self.config.time_weight).unwrap_or(Decimal::new(2, 1));
+
+        let total_weight = reputation_factor * reputation_weight
+            + confidence_factor * confidence_weight
+            + time_factor * time_weight;
+
+        let factors = BTreeMap::from([
+            ("reputation".to_string(), reputation_factor),
+            ("confidence".to_string(), confidence_factor),
+            ("time".to_string(), time_factor),
+        ]);
+        vote_weight(vote, total_weight / (reputation_weight + confidence_weight + time_weight), factors)
+    }
+
+    /// Time factor of a submission: 1.0 within the grace period after the
+    /// first submission, then halving every half-life down to the minimum.
+    /// The grace period keeps racing to submit first from paying off, and
+    /// the minimum keeps late, careful analyses from counting for little.
+    fn calculate_time_factor(&self, submitted_at: DateTime<Utc>, first_submitted: DateTime<Utc>) -> Decimal {
+        let min_factor = self.config.min_time_factor.clamp(0.0, 1.0);
+        let elapsed = (submitted_at - first_submitted).num_seconds().max(0) as f64;
+        let late = elapsed - self.config.time_decay_grace_secs as f64;
+        if late <= 0.0 {
+            return Decimal::ONE;
+        }
+
+        let factor = if self.config.time_decay_half_life_secs == 0 {
+            min_factor
+        } else {
+            0.5_f64.powf(late / self.config.time_decay_half_life_secs as f64).max(min_factor)
+        };
+        Decimal::try_from(factor).unwrap_or(Decimal::ONE).round_dp(4)
+    }
+}
+
+impl ConsensusAlgorithm for ReputationWeighted {
+    fn vote_weights(&self, votes: &[SubmissionVote]) -> Vec<VoteWeight> {
+        let Some(first_submitted) = votes.iter().map(|v| v.submitted_at).min() else {
+            return Vec::new();
+        };
+
+        votes
+            .iter()
+            .map(|vote| self.calculate_vote_weight(vote, first_submitted))
+            .collect()
+    }
+}
+
+/// Votes weighted by the stake behind them: engines that put more at risk
+/// count for more. If nothing was staked every vote counts the same.
+pub struct StakeWeighted;
+
+impl ConsensusAlgorithm for StakeWeighted {
+    fn vote_weights(&self, votes: &[SubmissionVote]) -> Vec<VoteWeight> {
+        let total_stake: i64 = votes.iter().map(|v| v.stake_amount.max(0)).sum();
+        if total_stake == 0 {
+            return SimpleMajority.vote_weights(votes);
+        }
+
+        votes
+            .iter()
+            .map(|vote| {
+                let share = Decimal::from(vote.stake_amount.max(0)) / Decimal::from(total_stake);
+                let factors = BTreeMap::from([("stake_share".to_string(), share.round_dp(6))]);
+                vote_weight(vote, share, factors)
+            })
+            .collect()
+    }
+}
+
+/// Bayesian truth serum (Prelec, 2004). Engines also predict how the others
+/// will vote; a verdict gains weight when more engines give it than the
+/// engines predicted, which favours informed minorities over a herd, and
+/// engines that predicted the vote well gain weight. Engines that made no
+/// prediction are taken to expect every verdict equally.
+pub struct BayesianTruthSerum {
+    /// Weight of the prediction score against the information score
+    prediction_weight: f64,
+}
+
+impl Default for BayesianTruthSerum {
+    fn default() -> Self {
+        Self { prediction_weight: 1.0 }
+    }
+}
+
+impl ConsensusAlgorithm for BayesianTruthSerum {
+    fn vote_weights(&self, votes: &[SubmissionVote]) -> Vec<VoteWeight> {
+        if votes.is_empty() {
+            return Vec::new();
+        }
+        let n = votes.len() as f64;
+
+        // Share of the engines giving each verdict
+        let mut actual: HashMap<String, f64> = HashMap::new();
+        for vote in votes {
+            *actual.entry(vote.verdict.to_string()).or_insert(0.0) += 1.0 / n;
+        }
+        let predictions: Vec<VerdictShares> = votes
+            .iter()
+            .map(|vote| vote.prediction.clone().unwrap_or_else(VerdictShares::uniform).normalized())
+            .collect();
+
+        // Geometric mean of the predicted shares of each verdict
+        let predicted = |verdict: &Verdict| {
+            let log_sum: f64 = predictions
+                .iter()
+                .map(|p| p.share(verdict).max(MIN_SHARE).ln())
+                .sum();
+            (log_sum / n).exp()
+        };
+
+        votes
+            .iter()
+            .zip(&predictions)
+            .map(|(vote, prediction)| {
+                let information_score = (actual[&vote.verdict.to_string()] / predicted(&vote.verdict)).ln();
+                // How close the engine came to the actual vote; 0 for a
+                // perfect prediction, lower the further off it was
+                let prediction_score = if vote.prediction.is_some() {
+                    Verdict::ALL
+                        .iter()
+                        .filter_map(|verdict| {
+                            let share = actual.get(&verdict.to_string())?;
+                            Some(share * (prediction.share(verdict).max(MIN_SHARE) / share).ln())
+                        })
+                        .sum()
+                } else {
+                    0.0
+                };
+
+                let factors = BTreeMap::from([
+                    ("information_score".to_string(), to_decimal(information_score)),
+                    ("prediction_score".to_string(), to_decimal(prediction_score)),
+                ]);
+                let weight = (information_score + self.prediction_weight * prediction_score).exp();
+                vote_weight(vote, to_decimal(weight), factors)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn test_config() -> ConsensusConfig {
+        ConsensusConfig {
+            min_submissions: 3,
+            max_submissions: 100,
+            consensus_threshold: 0.66,
+            weighted_voting: true,
+            reputation_weight: 0.5,
+            confidence_weight: 0.3,
+            time_weight: 0.2,
+            time_decay_grace_secs: 300,
+            time_decay_half_life_secs: 3600,
+            min_time_factor: 0.5,
+            dispute_threshold: 0.4,
+            min_total_stake: 0,
+            auto_finalize_hours: 24,
+            auto_finalize_interval_secs: 60,
+            probation_weight: 0.5,
+        }
+    }
+
+    fn vote(engine_id: &str, verdict: Verdict, submitted_at: DateTime<Utc>) -> SubmissionVote {
+        SubmissionVote {
+            submission_id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            engine_id: engine_id.to_string(),
+            verdict,
+            confidence: Decimal::new(80, 2),
+            reputation_score: 5000,
+            specialization_score: None,
+            stake_amount: 0,
+            prediction: None,
+            probation: false,
+            submitted_at,
+        }
+    }
+
+    #[test]
+    fn test_time_decay_weighting() {
+        let algorithm = ReputationWeighted::new(test_config());
+        let first = Utc::now();
+        let at = |engine_id: &str, minutes: i64| {
+            vote(engine_id, Verdict::Malicious, first + chrono::Duration::minutes(minutes))
+        };
+
+        let weights = algorithm.vote_weights(&[
+            at("first", 0),
+            at("within_grace", 4),
+            at("one_half_life", 65),
+            at("much_later", 600),
+        ]);
+
+        assert_eq!(weights[0].factors["time"], Decimal::ONE);
+        assert_eq!(weights[1].factors["time"], Decimal::ONE);
+        assert_eq!(weights[1].weight, weights[0].weight);
+        assert_eq!(weights[2].factors["time"], Decimal::new(5, 1));
+        assert!(weights[2].weight < weights[0].weight);
+        // Late submissions keep the minimum factor
+        assert_eq!(weights[3].factors["time"], Decimal::new(5, 1));
+    }
+
+    #[test]
+    fn test_specialization_score_replaces_global_reputation() {
+        let algorithm = ReputationWeighted::new(test_config());
+        let now = Utc::now();
+        let generalist = vote("generalist", Verdict::Benign, now);
+        let mut specialist = vote("specialist", Verdict::Malicious, now);
+        specialist.specialization_score = Some(9000);
+        let mut novice = vote("novice", Verdict::Malicious, now);
+        novice.specialization_score = Some(1000);
+
+        let weights = algorithm.vote_weights(&[generalist, specialist, novice]);
+        assert_eq!(weights[0].factors["reputation"], Decimal::new(5, 1));
+        assert_eq!(weights[1].factors["reputation"], Decimal::new(9, 1));
+        assert_eq!(weights[2].factors["reputation"], Decimal::new(1, 1));
+        assert!(weights[1].weight > weights[0].weight);
+        assert!(weights[2].weight < weights[0].weight);
+    }
+
+    #[test]
+    fn test_stake_weighting() {
+        let now = Utc::now();
+        let mut whale = vote("whale", Verdict::Benign, now);
+        whale.stake_amount = 300;
+        let mut minnow = vote("minnow", Verdict::Malicious, now);
+        minnow.stake_amount = 100;
+
+        let weights = StakeWeighted.vote_weights(&[whale, minnow]);
+        assert_eq!(weights[0].weight, Decimal::new(75, 2));
+        assert_eq!(weights[1].weight, Decimal::new(25, 2));
+
+        // Without stakes every vote counts the same
+        let weights = StakeWeighted.vote_weights(&[vote("a", Verdict::Benign, now), vote("b", Verdict::Malicious, now)]);
+        assert_eq!(weights[0].weight, weights[1].weight);
+    }
+
+    #[test]
+    fn test_truth_serum_favours_surprisingly_common_verdicts() {
+        let now = Utc::now();
+        // Everyone expects most engines to call the sample benign, yet only
+        // the benign voters do; the malicious minority is more common than
+        // predicted
+        let expect_benign = VerdictShares {
+            malicious: 0.1,
+            benign: 0.9,
+            ..Default::default()
+        };
+        let votes: Vec<SubmissionVote> = [Verdict::Benign, Verdict::Benign, Verdict::Benign, Verdict::Malicious, Verdict::Malicious]
+            .into_iter()
+            .enumerate()
+            .map(|(i, verdict)| {
+                let mut vote = vote(&format!("engine{}", i), verdict, now);
+                vote.prediction = Some(expect_benign.clone());
+                vote
+            })
+            .collect();
+
+        let weights = BayesianTruthSerum::default().vote_weights(&votes);
+        let support = |verdict: Verdict| -> Decimal {
+            weights.iter().filter(|w| w.verdict == verdict).map(|w| w.weight).sum()
+        };
+        assert!(support(Verdict::Malicious) > support(Verdict::Benign));
+    }
+}
@@ -0,0 +1,303 @@
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use super::ConsensusAggregator;
+use crate::models::{
+    AlgorithmKind, ConsensusExplanation, OutlierReason, QuorumRules, SubmissionVote, Verdict,
+    VerdictDistribution, VerdictTally, VoteExplanation, VoteStats, VoteWeight,
+};
+
+fn stats<'a>(distribution: &'a VerdictDistribution, verdict: &Verdict) -> &'a VoteStats {
+    match verdict {
+        Verdict::Malicious => &distribution.malicious,
+        Verdict::Benign => &distribution.benign,
+        Verdict::Suspicious => &distribution.suspicious,
+        Verdict::Unknown => &distribution.unknown,
+    }
+}
+
+/// Middle vote weight; the mean of the two middle ones for an even count
+fn median(weights: &[VoteWeight]) -> Decimal {
+    let mut sorted: Vec<Decimal> = weights.iter().map(|w| w.weight).collect();
+    sorted.sort();
+    match sorted.len() {
+        0 => Decimal::ZERO,
+        n if n % 2 == 1 => sorted[n / 2],
+        n => (sorted[n / 2 - 1] + sorted[n / 2]) / Decimal::TWO,
+    }
+}
+
+impl ConsensusAggregator {
+    /// Derive a bounty's consensus step by step from its votes and the
+    /// weights calculated for them: the weight behind each verdict, the
+    /// threshold it was held to, why the final verdict won and which votes
+    /// stand out
+    pub fn explain(
+        &self,
+        bounty_id: Uuid,
+        algorithm: AlgorithmKind,
+        quorum: QuorumRules,
+        votes: &[SubmissionVote],
+        weights: &[VoteWeight],
+    ) -> ConsensusExplanation {
+        let threshold = Decimal::try_from(self.config.consensus_threshold * 100.0)
+            .unwrap_or(Decimal::new(66, 0))
+            .round_dp(2);
+        let distribution = self.build_distribution(votes, weights);
+        let (final_verdict, _) = if votes.is_empty() {
+            (Verdict::Unknown, Decimal::ZERO)
+        } else {
+            self.determine_final_verdict(&distribution)
+        };
+        let agreement_score = self.calculate_agreement_score(&distribution);
+        let total_stake: i64 = votes.iter().map(|v| v.stake_amount.max(0)).sum();
+        let consensus_reached =
+            self.consensus_reached(&quorum, votes.len(), total_stake, agreement_score);
+
+        let mut verdicts: Vec<VerdictTally> = Verdict::ALL
+            .iter()
+            .map(|verdict| (verdict, stats(&distribution, verdict)))
+            .filter(|(_, stats)| stats.count > 0)
+            .map(|(verdict, stats)| VerdictTally {
+                verdict: verdict.clone(),
+                votes: stats.count,
+                weight: stats.weighted_count,
+                share: stats.percentage.round_dp(2),
+            })
+            .collect();
+        verdicts.sort_by_key(|tally| std::cmp::Reverse(tally.weight));
+
+        let total_weight: Decimal = weights.iter().map(|w| w.weight).sum();
+        let median_weight = median(weights);
+        let votes = votes
+            .iter()
+            .zip(weights)
+            .map(|(vote, weight)| {
+                let mut outlier = Vec::new();
+                if vote.verdict != final_verdict {
+                    outlier.push(OutlierReason::Dissent);
+                }
+                if weight.weight * Decimal::TWO < median_weight {
+                    outlier.push(OutlierReason::LowWeight);
+                }
+                if weight
+                    .factors
+                    .get("collusion")
+                    .is_some_and(|factor| *factor < Decimal::ONE)
+                {
+                    outlier.push(OutlierReason::CollusionPenalty);
+                }
+
+                VoteExplanation {
+                    engine_id: vote.engine_id.clone(),
+                    verdict: vote.verdict.clone(),
+                    confidence: vote.confidence,
+                    reputation_score: vote.reputation_score,
+                    specialization_score: vote.specialization_score,
+                    stake_amount: vote.stake_amount,
+                    submitted_at: vote.submitted_at,
+                    factors: weight.factors.clone(),
+                    weight: weight.weight,
+                    share: if total_weight > Decimal::ZERO {
+                        (weight.weight / total_weight * Decimal::ONE_HUNDRED).round_dp(2)
+                    } else {
+                        Decimal::ZERO
+                    },
+                    outlier,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let reason = reason(
+            &final_verdict,
+            &verdicts,
+            threshold,
+            &quorum,
+            votes.len(),
+            total_stake,
+            consensus_reached,
+        );
+
+        ConsensusExplanation {
+            bounty_id,
+            algorithm,
+            quorum,
+            threshold,
+            final_verdict,
+            agreement_score,
+            consensus_reached,
+            reason,
+            total_stake,
+            filtered: Vec::new(),
+            verdicts,
+            votes,
+            finalized: None,
+        }
+    }
+}
+
+/// Why `verdict` won, or why it does not stand
+fn reason(
+    verdict: &Verdict,
+    verdicts: &[VerdictTally],
+    threshold: Decimal,
+    quorum: &QuorumRules,
+    submissions: usize,
+    total_stake: i64,
+    reached: bool,
+) -> String {
+    let Some(winner) = verdicts.iter().find(|tally| tally.verdict == *verdict) else {
+        return "No submissions yet".to_string();
+    };
+    let lead = match verdicts.iter().find(|tally| tally.verdict != *verdict) {
+        Some(runner_up) => format!(
+            ", {} points ahead of {}",
+            (winner.share - runner_up.share).round_dp(2),
+            runner_up.verdict.to_string()
+        ),
+        None => ", unopposed".to_string(),
+    };
+
+    if reached {
+        format!(
+            "{} carried {}% of the vote weight{}, meeting the {}% threshold with {} submissions and {} staked",
+            verdict.to_string(),
+            winner.share,
+            lead,
+            threshold,
+            submissions,
+            total_stake
+        )
+    } else if winner.share >= threshold {
+        format!(
+            "{} carried {}% of the vote weight{}, meeting the {}% threshold, but the quorum of {} submissions and {} staked is not met ({} submissions, {} staked)",
+            verdict.to_string(),
+            winner.share,
+            lead,
+            threshold,
+            quorum.min_submissions,
+            quorum.min_total_stake,
+            submissions,
+            total_stake
+        )
+    } else {
+        format!(
+            "{} carried the most vote weight, {}%{}, short of the {}% threshold; no consensus",
+            verdict.to_string(),
+            winner.share,
+            lead,
+            threshold
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConsensusConfig;
+    use chrono::Utc;
+    use std::collections::{BTreeMap, HashMap};
+
+    fn aggregator() -> ConsensusAggregator {
+        ConsensusAggregator::new(ConsensusConfig {
+            min_submissions: 3,
+            max_submissions: 100,
+            consensus_threshold: 0.66,
+            weighted_voting: false,
+            reputation_weight: 0.5,
+            confidence_weight: 0.3,
+            time_weight: 0.2,
+            time_decay_grace_secs: 300,
+            time_decay_half_life_secs: 3600,
+            min_time_factor: 0.5,
+            dispute_threshold: 0.4,
+            min_total_stake: 0,
+            auto_finalize_hours: 24,
+            auto_finalize_interval_secs: 60,
+            probation_weight: 0.5,
+        })
+    }
+
+    fn vote(engine_id: &str, verdict: Verdict) -> SubmissionVote {
+        SubmissionVote {
+            submission_id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            engine_id: engine_id.to_string(),
+            verdict,
+            confidence: Decimal::new(90, 2),
+            reputation_score: 5000,
+            specialization_score: None,
+            stake_amount: 100,
+            prediction: None,
+            probation: false,
+            submitted_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_explanation_flags_outliers() {
+        let aggregator = aggregator();
+        let votes = vec![
+            vote("a", Verdict::Malicious),
+            vote("b", Verdict::Malicious),
+            vote("c", Verdict::Malicious),
+            vote("d", Verdict::Benign),
+            vote("ring", Verdict::Malicious),
+        ];
+        let factors = HashMap::from([("ring".to_string(), BTreeMap::from([("collusion".to_string(), 0.1)]))]);
+        let (_, _, _, weights) =
+            aggregator.calculate_consensus(&votes, AlgorithmKind::SimpleMajority, &factors);
+
+        let explanation = aggregator.explain(
+            Uuid::new_v4(),
+            AlgorithmKind::SimpleMajority,
+            aggregator.default_quorum(),
+            &votes,
+            &weights,
+        );
+
+        assert_eq!(explanation.final_verdict, Verdict::Malicious);
+        assert!(explanation.consensus_reached);
+        assert_eq!(explanation.verdicts[0].verdict, Verdict::Malicious);
+        assert_eq!(explanation.total_stake, 500);
+        assert!(explanation.votes[0].outlier.is_empty());
+        assert_eq!(explanation.votes[3].outlier, vec![OutlierReason::Dissent]);
+        assert_eq!(
+            explanation.votes[4].outlier,
+            vec![OutlierReason::LowWeight, OutlierReason::CollusionPenalty]
+        );
+        assert!(explanation.reason.contains("meeting the 66% threshold"));
+    }
+
+    #[test]
+    fn test_explanation_without_consensus() {
+        let aggregator = aggregator();
+        let votes = vec![
+            vote("a", Verdict::Malicious),
+            vote("b", Verdict::Benign),
+            vote("c", Verdict::Suspicious),
+        ];
+        let (_, _, _, weights) =
+            aggregator.calculate_consensus(&votes, AlgorithmKind::SimpleMajority, &HashMap::new());
+
+        let explanation = aggregator.explain(
+            Uuid::new_v4(),
+            AlgorithmKind::SimpleMajority,
+            aggregator.default_quorum(),
+            &votes,
+            &weights,
+        );
+
+        assert!(!explanation.consensus_reached);
+        assert!(explanation.reason.ends_with("no consensus"));
+
+        let empty = aggregator.explain(
+            Uuid::new_v4(),
+            AlgorithmKind::SimpleMajority,
+            aggregator.default_quorum(),
+            &[],
+            &[],
+        );
+        assert_eq!(empty.reason, "No submissions yet");
+    }
+}
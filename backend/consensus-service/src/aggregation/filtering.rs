@@ -0,0 +1,204 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::config::FilterConfig;
+use crate::models::{FilterAction, FilterDecision, FilterReason, SubmissionVote};
+
+/// An engine's record on bounties finalized with consensus
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct EngineHistory {
+    pub votes: u32,
+    /// Share of those votes that matched the final verdict
+    pub accuracy: f64,
+}
+
+/// The votes left for aggregation and what was done to the others
+#[derive(Debug, Default)]
+pub struct FilteredVotes {
+    pub votes: Vec<SubmissionVote>,
+    pub decisions: Vec<FilterDecision>,
+}
+
+impl FilteredVotes {
+    /// Weight left to each down-weighted engine, by factor name
+    pub fn adjustments(&self) -> HashMap<String, BTreeMap<String, f64>> {
+        let mut adjustments: HashMap<String, BTreeMap<String, f64>> = HashMap::new();
+        for decision in &self.decisions {
+            if decision.action == FilterAction::DownWeighted {
+                adjustments
+                    .entry(decision.engine_id.clone())
+                    .or_default()
+                    .insert(decision.reason.as_str().to_string(), f64::try_from(decision.factor).unwrap_or(1.0));
+            }
+        }
+        adjustments
+    }
+}
+
+fn decision(vote: &SubmissionVote, reason: FilterReason, factor: f64, detail: String) -> FilterDecision {
+    let factor = factor.clamp(0.0, 1.0);
+    FilterDecision {
+        engine_id: vote.engine_id.clone(),
+        action: if factor == 0.0 { FilterAction::Dropped } else { FilterAction::DownWeighted },
+        reason,
+        factor: Decimal::try_from(factor).unwrap_or_default().round_dp(4),
+        detail,
+    }
+}
+
+/// Filter a bounty's votes before aggregation. Votes are dropped from engines
+/// whose accuracy on past bounties is below the minimum and from engines of
+/// an operator that already voted, i.e. sharing a wallet or an engine id up
+/// to case, keeping the earliest vote. Votes whose confidence is implausible
+/// or well above the engine's accuracy are down-weighted.
+pub fn filter_votes(
+    votes: &[SubmissionVote],
+    wallets: &HashMap<String, String>,
+    history: &HashMap<String, EngineHistory>,
+    config: &FilterConfig,
+) -> FilteredVotes {
+    let mut order: Vec<usize> = (0..votes.len()).collect();
+    order.sort_by_key(|&i| votes[i].submitted_at);
+
+    let mut identities: HashMap<String, &str> = HashMap::new();
+    let mut dropped = vec![false; votes.len()];
+    let mut decisions = Vec::new();
+    for i in order {
+        let vote = &votes[i];
+        let mut keys = vec![format!("engine:{}", vote.engine_id.trim().to_lowercase())];
+        if let Some(wallet) = wallets.get(&vote.engine_id) {
+            keys.push(format!("wallet:{}", wallet.to_lowercase()));
+        }
+        if let Some(first) = keys.iter().find_map(|key| identities.get(key)) {
+            let detail = format!("Same operator as engine {}", first);
+            decisions.push(decision(vote, FilterReason::DuplicateIdentity, 0.0, detail));
+            dropped[i] = true;
+            continue;
+        }
+        for key in keys {
+            identities.insert(key, &vote.engine_id);
+        }
+
+        let record = history
+            .get(&vote.engine_id)
+            .filter(|record| record.votes >= config.min_history);
+        if let Some(record) = record.filter(|record| record.accuracy < config.min_accuracy) {
+            let detail = format!(
+                "Accuracy {:.2} over {} finalized votes, below the minimum {:.2}",
+                record.accuracy, record.votes, config.min_accuracy
+            );
+            decisions.push(decision(vote, FilterReason::LowAccuracy, 0.0, detail));
+            dropped[i] = true;
+            continue;
+        }
+
+        let confidence = f64::try_from(vote.confidence).unwrap_or(0.0);
+        if !(config.min_confidence..=config.max_confidence).contains(&confidence) {
+            let detail = format!(
+                "Confidence {:.2} outside {:.2} to {:.2}",
+                confidence, config.min_confidence, config.max_confidence
+            );
+            decisions.push(decision(vote, FilterReason::ImplausibleConfidence, config.confidence_weight_factor, detail));
+        } else if let Some(record) = record.filter(|record| confidence - record.accuracy > config.max_overconfidence) {
+            // Scaled down to the confidence the engine's record supports
+            let factor = (record.accuracy + config.max_overconfidence) / confidence;
+            let detail = format!("Confidence {:.2} against accuracy {:.2}", confidence, record.accuracy);
+            decisions.push(decision(vote, FilterReason::Overconfident, factor, detail));
+        }
+    }
+
+    FilteredVotes {
+        votes: votes
+            .iter()
+            .zip(dropped)
+            .filter(|(_, dropped)| !dropped)
+            .map(|(vote, _)| vote.clone())
+            .collect(),
+        decisions,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Verdict;
+    use chrono::{Duration, Utc};
+    use uuid::Uuid;
+
+    fn config() -> FilterConfig {
+        FilterConfig {
+            min_history: 10,
+            min_accuracy: 0.3,
+            min_confidence: 0.05,
+            max_confidence: 0.99,
+            max_overconfidence: 0.4,
+            confidence_weight_factor: 0.5,
+        }
+    }
+
+    fn vote(engine_id: &str, confidence: i64, minutes: i64) -> SubmissionVote {
+        SubmissionVote {
+            submission_id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            engine_id: engine_id.to_string(),
+            verdict: Verdict::Malicious,
+            confidence: Decimal::new(confidence, 2),
+            reputation_score: 5000,
+            specialization_score: None,
+            stake_amount: 100,
+            prediction: None,
+            probation: false,
+            submitted_at: Utc::now() + Duration::minutes(minutes),
+        }
+    }
+
+    fn history(votes: u32, accuracy: f64) -> EngineHistory {
+        EngineHistory { votes, accuracy }
+    }
+
+    #[test]
+    fn test_drops_inaccurate_engines_with_enough_history() {
+        let votes = vec![vote("poor", 80, 0), vote("new", 80, 1)];
+        let history = HashMap::from([
+            ("poor".to_string(), history(20, 0.2)),
+            ("new".to_string(), history(3, 0.0)),
+        ]);
+
+        let filtered = filter_votes(&votes, &HashMap::new(), &history, &config());
+        assert_eq!(filtered.votes.len(), 1);
+        assert_eq!(filtered.votes[0].engine_id, "new");
+        assert_eq!(filtered.decisions[0].reason, FilterReason::LowAccuracy);
+        assert_eq!(filtered.decisions[0].action, FilterAction::Dropped);
+    }
+
+    #[test]
+    fn test_keeps_earliest_vote_per_operator() {
+        let votes = vec![vote("late", 80, 5), vote("early", 80, 0), vote("EARLY ", 80, 1), vote("other", 80, 2)];
+        let wallets = HashMap::from([
+            ("late".to_string(), "0xABC".to_string()),
+            ("early".to_string(), "0xabc".to_string()),
+        ]);
+
+        let filtered = filter_votes(&votes, &wallets, &HashMap::new(), &config());
+        let kept: Vec<&str> = filtered.votes.iter().map(|v| v.engine_id.as_str()).collect();
+        assert_eq!(kept, vec!["early", "other"]);
+        assert!(filtered
+            .decisions
+            .iter()
+            .all(|d| d.reason == FilterReason::DuplicateIdentity && d.detail.ends_with("early")));
+    }
+
+    #[test]
+    fn test_down_weights_uncalibrated_confidence() {
+        let votes = vec![vote("certain", 100, 0), vote("bold", 95, 1), vote("fine", 60, 2)];
+        let history = HashMap::from([("bold".to_string(), history(10, 0.35))]);
+
+        let filtered = filter_votes(&votes, &HashMap::new(), &history, &config());
+        assert_eq!(filtered.votes.len(), 3);
+        let adjustments = filtered.adjustments();
+        assert_eq!(adjustments["certain"]["implausible_confidence"], 0.5);
+        assert!((adjustments["bold"]["overconfident"] - 0.75 / 0.95).abs() < 1e-4);
+        assert!(!adjustments.contains_key("fine"));
+    }
+}
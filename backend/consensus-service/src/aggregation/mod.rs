@@ -1,7 +1,16 @@
+pub mod algorithms;
+pub mod explanation;
+pub mod filtering;
+pub mod replay;
+
 use crate::config::ConsensusConfig;
-use crate::models::{SubmissionVote, Verdict, VerdictDistribution, VoteStats};
+use crate::models::{
+    AlgorithmKind, FinalizeReason, QuorumRules, SubmissionVote, Verdict, VerdictDistribution, VerdictShares, VoteStats,
+    VoteWeight,
+};
+use chrono::{DateTime, Duration, Utc};
 use rust_decimal::Decimal;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 pub struct ConsensusAggregator {
     config: ConsensusConfig,
@@ -12,122 +21,66 @@ impl ConsensusAggregator {
         Self { config }
     }
 
-    /// Calculate weighted consensus from submissions
+    /// Algorithm for bounties that did not choose one
+    pub fn default_algorithm(&self) -> AlgorithmKind {
+        if self.config.weighted_voting {
+            AlgorithmKind::ReputationWeighted
+        } else {
+            AlgorithmKind::SimpleMajority
+        }
+    }
+
+    /// Quorum rules for bounties that did not set their own
+    pub fn default_quorum(&self) -> QuorumRules {
+        QuorumRules {
+            min_submissions: self.config.min_submissions,
+            min_total_stake: self.config.min_total_stake,
+            max_wait_hours: self.config.auto_finalize_hours,
+        }
+    }
+
+    /// Calculate consensus from submissions with the given algorithm, along
+    /// with the weight it gave each vote. Votes of engines in `adjustments`
+    /// are scaled down by each of their factors, e.g. `collusion` for
+    /// engines suspected of collusion, which are recorded with the weight.
     pub fn calculate_consensus(
         &self,
         votes: &[SubmissionVote],
-    ) -> (Verdict, Decimal, VerdictDistribution) {
+        kind: AlgorithmKind,
+        adjustments: &HashMap<String, BTreeMap<String, f64>>,
+    ) -> (Verdict, Decimal, VerdictDistribution, Vec<VoteWeight>) {
         if votes.is_empty() {
-            return (Verdict::Unknown, Decimal::new(0, 0), VerdictDistribution::default());
+            return (Verdict::Unknown, Decimal::new(0, 0), VerdictDistribution::default(), Vec::new());
         }
 
-        let distribution = if self.config.weighted_voting {
-            self.calculate_weighted_votes(votes)
-        } else {
-            self.calculate_simple_majority(votes)
-        };
-
+        let mut weights = algorithms::algorithm(kind, &self.config).vote_weights(votes);
+        for weight in &mut weights {
+            for (name, factor) in adjustments.get(&weight.engine_id).into_iter().flatten() {
+                let factor = Decimal::try_from(factor.clamp(0.0, 1.0)).unwrap_or(Decimal::ONE);
+                weight.weight *= factor;
+                weight.factors.insert(name.clone(), factor);
+            }
+        }
+        let distribution = self.build_distribution(votes, &weights);
         let (final_verdict, confidence) = self.determine_final_verdict(&distribution);
-        
-        (final_verdict, confidence, distribution)
+
+        (final_verdict, confidence, distribution, weights)
     }
 
-    /// Weighted voting algorithm
-    fn calculate_weighted_votes(&self, votes: &[SubmissionVote]) -> VerdictDistribution {
+    /// Build verdict distribution from weighted votes
+    fn build_distribution(&self, votes: &[SubmissionVote], weights: &[VoteWeight]) -> VerdictDistribution {
         let mut verdict_map: HashMap<String, Vec<WeightedVote>> = HashMap::new();
-
-        for vote in votes {
-            let weight = self.calculate_vote_weight(vote);
-            
+        for (vote, weight) in votes.iter().zip(weights) {
             verdict_map
                 .entry(vote.verdict.to_string())
-                .or_insert_with(Vec::new)
+                .or_default()
                 .push(WeightedVote {
                     engine_id: vote.engine_id.clone(),
-                    weight,
+                    weight: weight.weight,
                     confidence: vote.confidence,
                 });
         }
 
-        self.build_distribution(verdict_map, votes.len())
-    }
-
-    /// Simple majority voting (one vote per submission)
-    fn calculate_simple_majority(&self, votes: &[SubmissionVote]) -> VerdictDistribution {
-        let mut verdict_counts: HashMap<String, usize> = HashMap::new();
-        
-        for vote in votes {
-            *verdict_counts.entry(vote.verdict.to_string()).or_insert(0) += 1;
-        }
-
-        let mut distribution = VerdictDistribution::default();
-        let total = votes.len() as f64;
-
-        for (verdict_str, count) in verdict_counts {
-            let percentage = Decimal::try_from((count as f64 / total) * 100.0).unwrap_or(Decimal::new(0, 0));
-            
-            let voters: Vec<String> = votes
-                .iter()
-                .filter(|v| v.verdict.to_string() == verdict_str)
-                .map(|v| v.engine_id.clone())
-                .collect();
-
-            let avg_confidence = votes
-                .iter()
-                .filter(|v| v.verdict.to_string() == verdict_str)
-                .map(|v| v.confidence)
-                .sum::<Decimal>()
-                / Decimal::from(count);
-
-            let stats = VoteStats {
-                count,
-                weighted_count: Decimal::from(count),
-                percentage,
-                avg_confidence,
-                voters,
-            };
-
-            match verdict_str.as_str() {
-                "malicious" => distribution.malicious = stats,
-                "benign" => distribution.benign = stats,
-                "suspicious" => distribution.suspicious = stats,
-                "unknown" => distribution.unknown = stats,
-                _ => {}
-            }
-        }
-
-        distribution
-    }
-
-    /// Calculate vote weight based on multiple factors
-    fn calculate_vote_weight(&self, vote: &SubmissionVote) -> Decimal {
-        // Normalize reputation score (0-10000 range to 0-1)
-        let reputation_factor = Decimal::from(vote.reputation_score) / Decimal::from(10000);
-        
-        // Confidence factor (already 0-1)
-        let confidence_factor = vote.confidence;
-        
-        // Time factor (early submissions weighted slightly higher)
-        let time_factor = Decimal::new(10, 1); // 1.0 for now, can be time-based
-
-        // Weighted combination
-        let reputation_weight = Decimal::try_from(self.config.reputation_weight).unwrap_or(Decimal::new(5, 1));
-        let confidence_weight = Decimal::try_from(self.config.confidence_weight).unwrap_or(Decimal::new(3, 1));
-        let time_weight = Decimal::try_from(self.config.time_weight).unwrap_or(Decimal::new(2, 1));
-
-        let total_weight = reputation_factor * reputation_weight
-            + confidence_factor * confidence_weight
-            + time_factor * time_weight;
-
-        total_weight / (reputation_weight + confidence_weight + time_weight)
-    }
-
-    /// Build verdict distribution from weighted votes
-    fn build_distribution(
-        &self,
-        verdict_map: HashMap<String, Vec<WeightedVote>>,
-        _total_votes: usize,
-    ) -> VerdictDistribution {
         let mut distribution = VerdictDistribution::default();
         
         let total_weight: Decimal = verdict_map
@@ -215,6 +168,65 @@ impl ConsensusAggregator {
         .unwrap_or(Decimal::new(0, 0))
     }
 
+    /// Whether enough submissions and stake agree on the verdict for it to
+    /// stand
+    pub fn consensus_reached(
+        &self,
+        quorum: &QuorumRules,
+        submissions: usize,
+        total_stake: i64,
+        agreement_score: Decimal,
+    ) -> bool {
+        let threshold = Decimal::try_from(self.config.consensus_threshold * 100.0)
+            .unwrap_or(Decimal::new(66, 0));
+
+        submissions >= quorum.min_submissions
+            && total_stake >= quorum.min_total_stake
+            && agreement_score >= threshold
+    }
+
+    /// Whether an open bounty's result should be finalized now, and why. A
+    /// quorum only counts once every committed engine revealed its verdict,
+    /// so commit-reveal bounties are not settled over engines yet to reveal.
+    pub fn auto_finalize(
+        &self,
+        quorum: &QuorumRules,
+        reached: bool,
+        pending_reveals: bool,
+        first_submitted: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Option<FinalizeReason> {
+        let max_wait = Duration::hours(quorum.max_wait_hours.min(i32::MAX as u64) as i64);
+        if quorum.max_wait_hours > 0 && now - first_submitted >= max_wait {
+            Some(FinalizeReason::MaxWaitElapsed)
+        } else if reached && !pending_reveals {
+            Some(FinalizeReason::QuorumMet)
+        } else {
+            None
+        }
+    }
+
+    /// The share of vote weight behind each verdict that a bounty's progress
+    /// may show: withheld until the quorum's minimum submissions, and at
+    /// least two, are in, so no single vote can be read off it
+    pub fn progress_shares(
+        &self,
+        quorum: &QuorumRules,
+        submissions: usize,
+        distribution: &VerdictDistribution,
+    ) -> Option<VerdictShares> {
+        if submissions < quorum.min_submissions.max(2) {
+            return None;
+        }
+        let share = |stats: &VoteStats| f64::try_from(stats.percentage / Decimal::ONE_HUNDRED).unwrap_or(0.0);
+        Some(VerdictShares {
+            malicious: share(&distribution.malicious),
+            benign: share(&distribution.benign),
+            suspicious: share(&distribution.suspicious),
+            unknown: share(&distribution.unknown),
+        })
+    }
+
     /// Check if result can be disputed (low agreement)
     pub fn can_be_disputed(&self, agreement_score: Decimal) -> bool {
         let dispute_threshold = Decimal::try_from(self.config.dispute_threshold * 100.0)
@@ -245,8 +257,14 @@ mod tests {
             reputation_weight: 0.5,
             confidence_weight: 0.3,
             time_weight: 0.2,
+            time_decay_grace_secs: 300,
+            time_decay_half_life_secs: 3600,
+            min_time_factor: 0.5,
             dispute_threshold: 0.4,
+            min_total_stake: 0,
             auto_finalize_hours: 24,
+            auto_finalize_interval_secs: 60,
+            probation_weight: 0.5,
         }
     }
 
@@ -262,6 +280,10 @@ mod tests {
                 verdict: Verdict::Malicious,
                 confidence: Decimal::new(90, 2),
                 reputation_score: 8000,
+                specialization_score: None,
+                stake_amount: 0,
+                prediction: None,
+                probation: false,
                 submitted_at: Utc::now(),
             },
             SubmissionVote {
@@ -271,6 +293,10 @@ mod tests {
                 verdict: Verdict::Malicious,
                 confidence: Decimal::new(85, 2),
                 reputation_score: 7500,
+                specialization_score: None,
+                stake_amount: 0,
+                prediction: None,
+                probation: false,
                 submitted_at: Utc::now(),
             },
             SubmissionVote {
@@ -280,11 +306,16 @@ mod tests {
                 verdict: Verdict::Benign,
                 confidence: Decimal::new(60, 2),
                 reputation_score: 3000,
+                specialization_score: None,
+                stake_amount: 0,
+                prediction: None,
+                probation: false,
                 submitted_at: Utc::now(),
             },
         ];
 
-        let (verdict, _confidence, distribution) = aggregator.calculate_consensus(&votes);
+        let (verdict, _confidence, distribution, _) =
+            aggregator.calculate_consensus(&votes, aggregator.default_algorithm(), &HashMap::new());
         
         assert_eq!(verdict, Verdict::Malicious);
         assert!(distribution.malicious.count == 2);
@@ -305,6 +336,10 @@ mod tests {
                 verdict: Verdict::Malicious,
                 confidence: Decimal::new(90, 2),
                 reputation_score: 1000,
+                specialization_score: None,
+                stake_amount: 0,
+                prediction: None,
+                probation: false,
                 submitted_at: Utc::now(),
             },
             SubmissionVote {
@@ -314,11 +349,110 @@ mod tests {
                 verdict: Verdict::Malicious,
                 confidence: Decimal::new(85, 2),
                 reputation_score: 1000,
+                specialization_score: None,
+                stake_amount: 0,
+                prediction: None,
+                probation: false,
                 submitted_at: Utc::now(),
             },
         ];
 
-        let (verdict, _, _) = aggregator.calculate_consensus(&votes);
+        let (verdict, _, _, _) = aggregator.calculate_consensus(&votes, aggregator.default_algorithm(), &HashMap::new());
+        assert_eq!(verdict, Verdict::Malicious);
+    }
+
+    #[test]
+    fn test_consensus_needs_submissions_and_agreement() {
+        let aggregator = ConsensusAggregator::new(test_config());
+        let quorum = aggregator.default_quorum();
+
+        assert!(aggregator.consensus_reached(&quorum, 3, 0, Decimal::new(70, 0)));
+        assert!(!aggregator.consensus_reached(&quorum, 2, 0, Decimal::new(100, 0)));
+        assert!(!aggregator.consensus_reached(&quorum, 5, 0, Decimal::new(50, 0)));
+
+        let staked = QuorumRules {
+            min_total_stake: 1000,
+            ..quorum
+        };
+        assert!(!aggregator.consensus_reached(&staked, 3, 999, Decimal::new(70, 0)));
+        assert!(aggregator.consensus_reached(&staked, 3, 1000, Decimal::new(70, 0)));
+    }
+
+    #[test]
+    fn test_auto_finalize_rules() {
+        let aggregator = ConsensusAggregator::new(test_config());
+        let quorum = aggregator.default_quorum();
+        let first = Utc::now();
+        let soon = first + Duration::hours(1);
+        let late = first + Duration::hours(24);
+
+        assert_eq!(aggregator.auto_finalize(&quorum, false, false, first, soon), None);
+        assert_eq!(
+            aggregator.auto_finalize(&quorum, true, false, first, soon),
+            Some(FinalizeReason::QuorumMet)
+        );
+        // Engines yet to reveal hold the quorum off, but not the maximum wait
+        assert_eq!(aggregator.auto_finalize(&quorum, true, true, first, soon), None);
+        assert_eq!(
+            aggregator.auto_finalize(&quorum, false, true, first, late),
+            Some(FinalizeReason::MaxWaitElapsed)
+        );
+
+        let no_wait = QuorumRules {
+            max_wait_hours: 0,
+            ..quorum
+        };
+        assert_eq!(aggregator.auto_finalize(&no_wait, false, false, first, late + Duration::days(365)), None);
+    }
+
+    #[test]
+    fn test_progress_withholds_shares_of_few_votes() {
+        let aggregator = ConsensusAggregator::new(test_config());
+        let quorum = aggregator.default_quorum();
+        let mut distribution = VerdictDistribution::default();
+        distribution.malicious.percentage = Decimal::new(75, 0);
+        distribution.benign.percentage = Decimal::new(25, 0);
+
+        assert!(aggregator.progress_shares(&quorum, 2, &distribution).is_none());
+        let shares = aggregator.progress_shares(&quorum, 4, &distribution).unwrap();
+        assert_eq!(shares.malicious, 0.75);
+        assert_eq!(shares.benign, 0.25);
+
+        let single = QuorumRules {
+            min_submissions: 1,
+            ..quorum
+        };
+        assert!(aggregator.progress_shares(&single, 1, &distribution).is_none());
+    }
+
+    #[test]
+    fn test_collusion_factor_scales_votes_down() {
+        let mut config = test_config();
+        config.weighted_voting = false;
+        let aggregator = ConsensusAggregator::new(config);
+        let vote = |engine_id: &str, verdict: Verdict| SubmissionVote {
+            submission_id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            engine_id: engine_id.to_string(),
+            verdict,
+            confidence: Decimal::new(90, 2),
+            reputation_score: 1000,
+            specialization_score: None,
+            stake_amount: 0,
+            prediction: None,
+            probation: false,
+            submitted_at: Utc::now(),
+        };
+        let votes = vec![
+            vote("honest", Verdict::Malicious),
+            vote("sybil1", Verdict::Benign),
+            vote("sybil2", Verdict::Benign),
+        ];
+        let collusion = BTreeMap::from([("collusion".to_string(), 0.1)]);
+        let factors = HashMap::from([("sybil1".to_string(), collusion.clone()), ("sybil2".to_string(), collusion)]);
+
+        let (verdict, _, _, weights) = aggregator.calculate_consensus(&votes, AlgorithmKind::SimpleMajority, &factors);
         assert_eq!(verdict, Verdict::Malicious);
+        assert_eq!(weights[1].factors["collusion"], Decimal::new(1, 1));
     }
 }
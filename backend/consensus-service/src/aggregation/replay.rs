@@ -0,0 +1,365 @@
+//! Everything a consensus is calculated from, so a finalized one can be
+//! replayed later: to check it for an audit, or to see what a change to the
+//! weighting would have made of it.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use uuid::Uuid;
+
+use super::filtering::{self, EngineHistory};
+use super::ConsensusAggregator;
+use crate::config::{ConsensusConfig, FilterConfig};
+use crate::models::{AlgorithmKind, FilterDecision, FinalizeReason, QuorumRules, SubmissionVote, Verdict, VerdictDistribution, VoteWeight};
+
+/// A bounty's votes and what is known about their engines, as they were when
+/// its consensus was calculated
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsensusInputs {
+    pub algorithm: AlgorithmKind,
+    pub quorum: QuorumRules,
+    /// Every revealed vote, before filtering
+    pub votes: Vec<SubmissionVote>,
+    /// Wallet of each engine that gave one, for spotting shared operators
+    pub wallets: HashMap<String, String>,
+    /// Each engine's record on other finalized bounties
+    pub history: HashMap<String, EngineHistory>,
+    /// Weight left to engines in suspected voting rings
+    pub collusion: HashMap<String, f64>,
+}
+
+/// The settings that shaped a consensus
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsensusSettings {
+    pub consensus: ConsensusConfig,
+    pub filter: FilterConfig,
+}
+
+/// A consensus as calculated from its inputs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsensusOutcome {
+    pub algorithm: AlgorithmKind,
+    pub verdict: Verdict,
+    pub confidence: Decimal,
+    pub agreement_score: Decimal,
+    pub consensus_reached: bool,
+    pub distribution: VerdictDistribution,
+    pub weights: Vec<VoteWeight>,
+    pub filtered: Vec<FilterDecision>,
+}
+
+/// Something a replay calculated differently; engine-level differences name
+/// the engine
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ReplayDifference {
+    /// `algorithm`, `verdict`, `consensus_reached`, `confidence`,
+    /// `agreement_score`, or an engine's `weight` or `filter`
+    pub field: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub engine_id: Option<String>,
+    pub original: Value,
+    pub replayed: Value,
+}
+
+/// How to replay a finalized consensus
+#[derive(Debug, Default, Deserialize)]
+pub struct ReplayRequest {
+    /// Replay with this algorithm instead of the one the bounty used
+    pub algorithm: Option<AlgorithmKind>,
+    /// Replay with the service's current settings instead of the recorded ones
+    #[serde(default)]
+    pub current_settings: bool,
+}
+
+/// Which settings a replay used
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplaySettings {
+    /// Those recorded when the consensus was finalized
+    Recorded,
+    /// The service's current settings
+    Current,
+}
+
+/// A finalized consensus recalculated from its snapshot
+#[derive(Debug, Serialize)]
+pub struct ConsensusReplay {
+    pub bounty_id: Uuid,
+    pub snapshot_id: Uuid,
+    pub finalized_reason: Option<FinalizeReason>,
+    pub finalized_at: DateTime<Utc>,
+    pub settings: ReplaySettings,
+    /// The consensus as calculated when it was finalized
+    pub original: ConsensusOutcome,
+    pub replayed: ConsensusOutcome,
+    /// Empty when the replay matches the original
+    pub differences: Vec<ReplayDifference>,
+}
+
+impl ReplayDifference {
+    fn new(field: &str, engine_id: Option<&str>, original: Value, replayed: Value) -> Self {
+        Self {
+            field: field.to_string(),
+            engine_id: engine_id.map(str::to_string),
+            original,
+            replayed,
+        }
+    }
+}
+
+impl ConsensusAggregator {
+    /// Filter, weigh and aggregate a bounty's votes with the given algorithm;
+    /// votes of engines on probation are scaled down by `probation_weight`.
+    /// Returns the outcome and the votes that counted towards it.
+    pub fn aggregate(
+        &self,
+        inputs: &ConsensusInputs,
+        algorithm: AlgorithmKind,
+        filter: &FilterConfig,
+    ) -> (ConsensusOutcome, Vec<SubmissionVote>) {
+        let filtered = filtering::filter_votes(&inputs.votes, &inputs.wallets, &inputs.history, filter);
+        let mut adjustments = filtered.adjustments();
+        for (engine_id, factor) in &inputs.collusion {
+            adjustments
+                .entry(engine_id.clone())
+                .or_default()
+                .insert("collusion".to_string(), *factor);
+        }
+        for vote in inputs.votes.iter().filter(|vote| vote.probation) {
+            adjustments
+                .entry(vote.engine_id.clone())
+                .or_default()
+                .insert("probation".to_string(), self.config.probation_weight);
+        }
+        let votes = filtered.votes;
+        let (verdict, confidence, distribution, weights) = self.calculate_consensus(&votes, algorithm, &adjustments);
+        let agreement_score = self.calculate_agreement_score(&distribution);
+        let total_stake = votes.iter().map(|v| v.stake_amount.max(0)).sum();
+
+        let outcome = ConsensusOutcome {
+            algorithm,
+            verdict,
+            confidence,
+            consensus_reached: self.consensus_reached(&inputs.quorum, votes.len(), total_stake, agreement_score),
+            agreement_score,
+            distribution,
+            weights,
+            filtered: filtered.decisions,
+        };
+        (outcome, votes)
+    }
+}
+
+/// Settings recorded in a snapshot, laid over the current ones so settings
+/// added since the snapshot was taken keep their current value
+pub fn overlay(current: Value, recorded: Value) -> Value {
+    match (current, recorded) {
+        (Value::Object(mut current), Value::Object(recorded)) => {
+            for (key, value) in recorded {
+                let merged = match current.remove(&key) {
+                    Some(existing) => overlay(existing, value),
+                    None => value,
+                };
+                current.insert(key, merged);
+            }
+            Value::Object(current)
+        }
+        (_, recorded) => recorded,
+    }
+}
+
+fn filter_label(decision: &FilterDecision) -> Value {
+    json!({
+        "action": decision.action,
+        "reason": decision.reason,
+        "factor": decision.factor,
+    })
+}
+
+/// What a replay calculated differently from the original. Scores and
+/// weights are compared to four decimal places, below which the difference
+/// is rounding.
+pub fn differences(original: &ConsensusOutcome, replayed: &ConsensusOutcome) -> Vec<ReplayDifference> {
+    let mut differences = Vec::new();
+    if original.algorithm != replayed.algorithm {
+        differences.push(ReplayDifference::new("algorithm", None, json!(original.algorithm), json!(replayed.algorithm)));
+    }
+    if original.verdict != replayed.verdict {
+        differences.push(ReplayDifference::new("verdict", None, json!(original.verdict), json!(replayed.verdict)));
+    }
+    if original.consensus_reached != replayed.consensus_reached {
+        differences.push(ReplayDifference::new(
+            "consensus_reached",
+            None,
+            json!(original.consensus_reached),
+            json!(replayed.consensus_reached),
+        ));
+    }
+    for (field, before, after) in [
+        ("confidence", original.confidence, replayed.confidence),
+        ("agreement_score", original.agreement_score, replayed.agreement_score),
+    ] {
+        if before.round_dp(4) != after.round_dp(4) {
+            differences.push(ReplayDifference::new(field, None, json!(before.round_dp(4)), json!(after.round_dp(4))));
+        }
+    }
+
+    let weights = |outcome: &ConsensusOutcome| -> BTreeMap<String, Decimal> {
+        outcome.weights.iter().map(|w| (w.engine_id.clone(), w.weight.round_dp(4))).collect()
+    };
+    let filters = |outcome: &ConsensusOutcome| -> BTreeMap<String, Value> {
+        outcome.filtered.iter().map(|d| (d.engine_id.clone(), filter_label(d))).collect()
+    };
+    let (before, after) = (weights(original), weights(replayed));
+    let engines: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    for engine_id in engines {
+        let (was, is) = (before.get(engine_id), after.get(engine_id));
+        if was != is {
+            differences.push(ReplayDifference::new("weight", Some(engine_id), json!(was), json!(is)));
+        }
+    }
+    let (before, after) = (filters(original), filters(replayed));
+    let engines: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    for engine_id in engines {
+        let (was, is) = (before.get(engine_id), after.get(engine_id));
+        if was != is {
+            differences.push(ReplayDifference::new("filter", Some(engine_id), json!(was), json!(is)));
+        }
+    }
+
+    differences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn settings() -> ConsensusSettings {
+        ConsensusSettings {
+            consensus: ConsensusConfig {
+                min_submissions: 3,
+                max_submissions: 100,
+                consensus_threshold: 0.66,
+                weighted_voting: true,
+                reputation_weight: 0.5,
+                confidence_weight: 0.3,
+                time_weight: 0.2,
+                time_decay_grace_secs: 300,
+                time_decay_half_life_secs: 3600,
+                min_time_factor: 0.5,
+                dispute_threshold: 0.4,
+                min_total_stake: 0,
+                auto_finalize_hours: 24,
+                auto_finalize_interval_secs: 60,
+                probation_weight: 0.5,
+            },
+            filter: FilterConfig {
+                min_history: 10,
+                min_accuracy: 0.3,
+                min_confidence: 0.05,
+                max_confidence: 0.99,
+                max_overconfidence: 0.4,
+                confidence_weight_factor: 0.5,
+            },
+        }
+    }
+
+    fn vote(engine_id: &str, verdict: Verdict, reputation_score: i32) -> SubmissionVote {
+        SubmissionVote {
+            submission_id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            engine_id: engine_id.to_string(),
+            verdict,
+            confidence: Decimal::new(80, 2),
+            reputation_score,
+            specialization_score: None,
+            stake_amount: 100,
+            prediction: None,
+            probation: false,
+            submitted_at: Utc::now(),
+        }
+    }
+
+    fn inputs() -> ConsensusInputs {
+        ConsensusInputs {
+            algorithm: AlgorithmKind::ReputationWeighted,
+            quorum: QuorumRules {
+                min_submissions: 3,
+                min_total_stake: 0,
+                max_wait_hours: 24,
+            },
+            votes: vec![
+                vote("a", Verdict::Malicious, 9000),
+                vote("b", Verdict::Malicious, 8000),
+                vote("c", Verdict::Benign, 1000),
+                vote("d", Verdict::Benign, 1000),
+            ],
+            wallets: HashMap::new(),
+            history: HashMap::new(),
+            collusion: HashMap::from([("a".to_string(), 0.1)]),
+        }
+    }
+
+    #[test]
+    fn test_replay_with_same_inputs_matches() {
+        let settings = settings();
+        let aggregator = ConsensusAggregator::new(settings.consensus.clone());
+        let inputs = inputs();
+
+        let (original, counted) = aggregator.aggregate(&inputs, inputs.algorithm, &settings.filter);
+        let recorded: ConsensusInputs = serde_json::from_str(&serde_json::to_string(&inputs).unwrap()).unwrap();
+        let (replayed, _) = aggregator.aggregate(&recorded, recorded.algorithm, &settings.filter);
+
+        assert_eq!(counted.len(), 4);
+        assert!(original.weights.iter().any(|w| w.factors.contains_key("collusion")));
+        assert!(differences(&original, &replayed).is_empty());
+    }
+
+    #[test]
+    fn test_replay_reports_what_another_algorithm_changes() {
+        let settings = settings();
+        let aggregator = ConsensusAggregator::new(settings.consensus.clone());
+        let inputs = inputs();
+
+        let (original, _) = aggregator.aggregate(&inputs, AlgorithmKind::ReputationWeighted, &settings.filter);
+        let (replayed, _) = aggregator.aggregate(&inputs, AlgorithmKind::SimpleMajority, &settings.filter);
+        let differences = differences(&original, &replayed);
+
+        assert_eq!(differences[0].field, "algorithm");
+        assert!(differences
+            .iter()
+            .any(|d| d.field == "weight" && d.engine_id.as_deref() == Some("a")));
+        assert!(differences.iter().all(|d| d.original != d.replayed));
+    }
+
+    #[test]
+    fn test_probation_votes_count_for_less() {
+        let settings = settings();
+        let aggregator = ConsensusAggregator::new(settings.consensus.clone());
+        let mut inputs = inputs();
+        inputs.votes[2].probation = true;
+
+        let (outcome, _) = aggregator.aggregate(&inputs, AlgorithmKind::SimpleMajority, &settings.filter);
+        let weight = |engine_id: &str| outcome.weights.iter().find(|w| w.engine_id == engine_id).unwrap();
+
+        assert_eq!(weight("c").weight, Decimal::new(5, 1));
+        assert_eq!(weight("c").factors.get("probation"), Some(&Decimal::new(5, 1)));
+        assert_eq!(weight("d").weight, Decimal::ONE);
+        assert!(!weight("d").factors.contains_key("probation"));
+    }
+
+    #[test]
+    fn test_recorded_settings_overlay_current_ones() {
+        let current = json!({"consensus": {"consensus_threshold": 0.7, "new_setting": 3}, "filter": {"min_history": 5}});
+        let recorded = json!({"consensus": {"consensus_threshold": 0.66}, "filter": {"min_history": 10}});
+
+        assert_eq!(
+            overlay(current, recorded),
+            json!({"consensus": {"consensus_threshold": 0.66, "new_setting": 3}, "filter": {"min_history": 10}})
+        );
+    }
+}
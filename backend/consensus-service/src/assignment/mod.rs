@@ -0,0 +1,410 @@
+//! Reviewer assignment for human-review stages and dispute panels.
+//!
+//! Seats are offered one reviewer at a time, ranked by how well their
+//! specializations cover the panel's needs, how much review work they took on
+//! recently and how far their time zone is from those already on the panel.
+//! The submitter, members of the submitter's organization and anyone offered
+//! the panel before are never considered. Offers that are declined or not
+//! answered in time are handed to the next best reviewer.
+
+pub mod service;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use thiserror::Error;
+use uuid::Uuid;
+
+pub use service::AssignmentService;
+
+use crate::config::AssignmentConfig;
+
+/// Largest panel a request may ask for
+pub const MAX_PANEL_SIZE: i32 = 15;
+
+const MINUTES_PER_DAY: i32 = 24 * 60;
+
+#[derive(Debug, Error)]
+pub enum AssignmentError {
+    #[error("{0}")]
+    Validation(String),
+
+    #[error("{0}")]
+    NotFound(String),
+
+    #[error("{0}")]
+    Conflict(String),
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewKind {
+    HumanReview,
+    DisputePanel,
+}
+
+impl ReviewKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReviewKind::HumanReview => "human_review",
+            ReviewKind::DisputePanel => "dispute_panel",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "human_review" => Some(ReviewKind::HumanReview),
+            "dispute_panel" => Some(ReviewKind::DisputePanel),
+            _ => None,
+        }
+    }
+
+    /// Reviewers seated when the request does not say otherwise
+    pub fn default_panel_size(&self) -> i32 {
+        match self {
+            ReviewKind::HumanReview => 1,
+            ReviewKind::DisputePanel => 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AssignmentStatus {
+    Pending,
+    Accepted,
+    Declined,
+    Expired,
+    Completed,
+}
+
+impl AssignmentStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AssignmentStatus::Pending => "pending",
+            AssignmentStatus::Accepted => "accepted",
+            AssignmentStatus::Declined => "declined",
+            AssignmentStatus::Expired => "expired",
+            AssignmentStatus::Completed => "completed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(AssignmentStatus::Pending),
+            "accepted" => Some(AssignmentStatus::Accepted),
+            "declined" => Some(AssignmentStatus::Declined),
+            "expired" => Some(AssignmentStatus::Expired),
+            "completed" => Some(AssignmentStatus::Completed),
+            _ => None,
+        }
+    }
+}
+
+/// A reviewer who can be assigned
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewerProfile {
+    pub user_id: Uuid,
+    pub organization_id: Option<Uuid>,
+    pub specializations: Vec<String>,
+    pub utc_offset_minutes: i32,
+    pub max_open_assignments: i32,
+    pub is_active: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpsertReviewerRequest {
+    #[serde(default)]
+    pub specializations: Vec<String>,
+    #[serde(default)]
+    pub utc_offset_minutes: i32,
+    pub max_open_assignments: Option<i32>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreatePanelRequest {
+    pub kind: ReviewKind,
+    pub subject_id: Uuid,
+    pub bounty_id: Uuid,
+    pub submitter_id: Uuid,
+    #[serde(default)]
+    pub required_specializations: Vec<String>,
+    #[serde(default)]
+    pub excluded_reviewers: Vec<Uuid>,
+    /// Only these reviewers may be offered seats, e.g. the high-trust users
+    /// an arbitration panel is drawn from; anyone when omitted
+    #[serde(default)]
+    pub eligible_reviewers: Option<Vec<Uuid>>,
+    pub panel_size: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReviewPanel {
+    pub id: Uuid,
+    pub kind: ReviewKind,
+    pub subject_id: Uuid,
+    pub bounty_id: Uuid,
+    pub panel_size: i32,
+    pub required_specializations: Vec<String>,
+    pub staffed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub assignments: Vec<ReviewAssignment>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReviewAssignment {
+    pub id: Uuid,
+    pub panel_id: Uuid,
+    pub reviewer_id: Uuid,
+    pub status: AssignmentStatus,
+    pub score: f64,
+    pub offered_at: DateTime<Utc>,
+    pub respond_by: DateTime<Utc>,
+    pub responded_at: Option<DateTime<Utc>>,
+    pub decline_reason: Option<String>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AssignmentListQuery {
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct DeclineRequest {
+    pub reason: Option<String>,
+}
+
+/// An active reviewer with their current workload
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub user_id: Uuid,
+    pub organization_id: Option<Uuid>,
+    pub specializations: Vec<String>,
+    pub utc_offset_minutes: i32,
+    pub max_open_assignments: i32,
+    /// Pending and accepted assignments
+    pub open_assignments: i64,
+    /// Assignments offered within the load window
+    pub recent_assignments: i64,
+}
+
+/// Who may sit on a panel and what it needs
+#[derive(Debug, Clone, Default)]
+pub struct PanelNeeds {
+    pub submitter_id: Uuid,
+    pub submitter_organization_id: Option<Uuid>,
+    pub required_specializations: Vec<String>,
+    /// Explicit exclusions plus everyone already offered a seat
+    pub excluded: HashSet<Uuid>,
+    /// The only reviewers who may sit on the panel, if restricted
+    pub eligible: Option<HashSet<Uuid>>,
+    /// Time zones of reviewers currently holding a seat
+    pub seated_offsets: Vec<i32>,
+}
+
+impl PanelNeeds {
+    /// Conflict of interest, prior offer, or no capacity left
+    pub fn is_eligible(&self, candidate: &Candidate) -> bool {
+        candidate.user_id != self.submitter_id
+            && !self.excluded.contains(&candidate.user_id)
+            && self
+                .eligible
+                .as_ref()
+                .is_none_or(|eligible| eligible.contains(&candidate.user_id))
+            && !(candidate.organization_id.is_some()
+                && candidate.organization_id == self.submitter_organization_id)
+            && candidate.open_assignments < i64::from(candidate.max_open_assignments)
+            && (self.required_specializations.is_empty()
+                || specialization_match(&self.required_specializations, &candidate.specializations) > 0.0)
+    }
+}
+
+/// Share of the required specializations the reviewer covers (1.0 when none are required)
+pub fn specialization_match(required: &[String], offered: &[String]) -> f64 {
+    if required.is_empty() {
+        return 1.0;
+    }
+    let covered = required
+        .iter()
+        .filter(|r| offered.iter().any(|o| o.eq_ignore_ascii_case(r)))
+        .count();
+    covered as f64 / required.len() as f64
+}
+
+/// Close to 1.0 for idle reviewers, falling as recent assignments pile up
+pub fn load_score(candidate: &Candidate) -> f64 {
+    1.0 / (1.0 + candidate.open_assignments as f64 + 0.5 * candidate.recent_assignments as f64)
+}
+
+/// Distance to the nearest seated time zone, as a share of the furthest
+/// possible (12 hours). An empty panel scores 1.0.
+pub fn timezone_spread(offset_minutes: i32, seated: &[i32]) -> f64 {
+    seated
+        .iter()
+        .map(|other| {
+            let diff = (offset_minutes - other).rem_euclid(MINUTES_PER_DAY);
+            diff.min(MINUTES_PER_DAY - diff)
+        })
+        .min()
+        .map_or(1.0, |nearest| f64::from(nearest) / f64::from(MINUTES_PER_DAY / 2))
+}
+
+pub fn score(config: &AssignmentConfig, needs: &PanelNeeds, candidate: &Candidate) -> f64 {
+    config.specialization_weight
+        * specialization_match(&needs.required_specializations, &candidate.specializations)
+        + config.load_weight * load_score(candidate)
+        + config.timezone_weight * timezone_spread(candidate.utc_offset_minutes, &needs.seated_offsets)
+}
+
+/// Pick up to `seats` reviewers, best first.
+///
+/// Selection is greedy so each pick widens the time-zone coverage the next
+/// one is measured against. Ties go to the lower user id to keep results
+/// reproducible.
+pub fn select_reviewers(
+    config: &AssignmentConfig,
+    mut needs: PanelNeeds,
+    candidates: &[Candidate],
+    seats: usize,
+) -> Vec<(Uuid, f64)> {
+    let mut selected = Vec::with_capacity(seats);
+    while selected.len() < seats {
+        let best = candidates
+            .iter()
+            .filter(|c| needs.is_eligible(c))
+            .map(|c| (c, score(config, &needs, c)))
+            .max_by(|(a, sa), (b, sb)| sa.total_cmp(sb).then_with(|| b.user_id.cmp(&a.user_id)));
+
+        let Some((candidate, score)) = best else {
+            break;
+        };
+        needs.excluded.insert(candidate.user_id);
+        needs.seated_offsets.push(candidate.utc_offset_minutes);
+        selected.push((candidate.user_id, score));
+    }
+    selected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> AssignmentConfig {
+        AssignmentConfig {
+            response_timeout_secs: 3600,
+            load_window_days: 30,
+            specialization_weight: 0.5,
+            load_weight: 0.3,
+            timezone_weight: 0.2,
+            sweep_interval_secs: 60,
+        }
+    }
+
+    fn candidate(specializations: &[&str], offset_hours: i32) -> Candidate {
+        Candidate {
+            user_id: Uuid::new_v4(),
+            organization_id: None,
+            specializations: specializations.iter().map(|s| s.to_string()).collect(),
+            utc_offset_minutes: offset_hours * 60,
+            max_open_assignments: 5,
+            open_assignments: 0,
+            recent_assignments: 0,
+        }
+    }
+
+    #[test]
+    fn test_conflicts_of_interest_are_excluded() {
+        let org = Uuid::new_v4();
+        let submitter = candidate(&[], 0);
+        let mut colleague = candidate(&[], 0);
+        colleague.organization_id = Some(org);
+        let mut busy = candidate(&[], 0);
+        busy.open_assignments = 5;
+        let declined = candidate(&[], 0);
+        let outsider = candidate(&[], 0);
+
+        let needs = PanelNeeds {
+            submitter_id: submitter.user_id,
+            submitter_organization_id: Some(org),
+            excluded: HashSet::from([declined.user_id]),
+            ..Default::default()
+        };
+
+        assert!(!needs.is_eligible(&submitter));
+        assert!(!needs.is_eligible(&colleague));
+        assert!(!needs.is_eligible(&busy));
+        assert!(!needs.is_eligible(&declined));
+        assert!(needs.is_eligible(&outsider));
+    }
+
+    #[test]
+    fn test_restricted_panels_only_seat_eligible_reviewers() {
+        let trusted = candidate(&[], 0);
+        let other = candidate(&[], 0);
+
+        let needs = PanelNeeds {
+            submitter_id: Uuid::new_v4(),
+            eligible: Some(HashSet::from([trusted.user_id])),
+            ..Default::default()
+        };
+
+        assert!(needs.is_eligible(&trusted));
+        assert!(!needs.is_eligible(&other));
+    }
+
+    #[test]
+    fn test_specialization_match() {
+        let required = vec!["ransomware".to_string(), "macos".to_string()];
+        assert_eq!(specialization_match(&required, &["Ransomware".to_string()]), 0.5);
+        assert_eq!(specialization_match(&[], &[]), 1.0);
+
+        let needs = PanelNeeds {
+            required_specializations: required,
+            ..Default::default()
+        };
+        assert!(!needs.is_eligible(&candidate(&["phishing"], 0)));
+        assert!(needs.is_eligible(&candidate(&["macos"], 0)));
+    }
+
+    #[test]
+    fn test_timezone_spread_wraps_around_the_day() {
+        assert_eq!(timezone_spread(0, &[]), 1.0);
+        assert_eq!(timezone_spread(0, &[0]), 0.0);
+        assert_eq!(timezone_spread(12 * 60, &[0]), 1.0);
+        // UTC+11 and UTC-11 are two hours apart
+        assert!((timezone_spread(11 * 60, &[-11 * 60]) - 2.0 / 12.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_selection_prefers_idle_specialists_across_time_zones() {
+        let mut loaded = candidate(&["ransomware"], 0);
+        loaded.recent_assignments = 8;
+        let idle = candidate(&["ransomware"], 0);
+        let same_zone = candidate(&["ransomware"], 1);
+        let far_zone = candidate(&["ransomware"], -8);
+        let candidates = vec![loaded.clone(), idle.clone(), same_zone, far_zone.clone()];
+
+        let needs = PanelNeeds {
+            submitter_id: Uuid::new_v4(),
+            required_specializations: vec!["ransomware".to_string()],
+            ..Default::default()
+        };
+        let picked: Vec<Uuid> = select_reviewers(&config(), needs.clone(), &candidates, 2)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+
+        assert_eq!(picked.len(), 2);
+        assert!(!picked.contains(&loaded.user_id));
+        assert!(picked.contains(&far_zone.user_id));
+
+        // Never more seats than eligible reviewers
+        assert_eq!(select_reviewers(&config(), needs, &candidates, 10).len(), 4);
+    }
+}
@@ -0,0 +1,538 @@
+use chrono::{Duration, Utc};
+use sqlx::{PgPool, Postgres, Row, Transaction};
+use std::collections::HashSet;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::{
+    select_reviewers, AssignmentError, AssignmentStatus, Candidate, CreatePanelRequest,
+    PanelNeeds, ReviewAssignment, ReviewKind, ReviewPanel, ReviewerProfile,
+    UpsertReviewerRequest, MAX_PANEL_SIZE,
+};
+use crate::config::AssignmentConfig;
+
+const DEFAULT_MAX_OPEN_ASSIGNMENTS: i32 = 5;
+
+type Result<T> = std::result::Result<T, AssignmentError>;
+
+pub struct AssignmentService {
+    config: AssignmentConfig,
+    db_pool: PgPool,
+}
+
+impl AssignmentService {
+    pub fn new(config: AssignmentConfig, db_pool: PgPool) -> Self {
+        Self { config, db_pool }
+    }
+
+    pub async fn upsert_reviewer(
+        &self,
+        user_id: Uuid,
+        request: UpsertReviewerRequest,
+    ) -> Result<ReviewerProfile> {
+        if !(-720..=840).contains(&request.utc_offset_minutes) {
+            return Err(AssignmentError::Validation(
+                "utc_offset_minutes must be between -720 and 840".to_string(),
+            ));
+        }
+        let max_open = request.max_open_assignments.unwrap_or(DEFAULT_MAX_OPEN_ASSIGNMENTS);
+        if max_open < 1 {
+            return Err(AssignmentError::Validation(
+                "max_open_assignments must be at least 1".to_string(),
+            ));
+        }
+        let specializations: Vec<String> = request
+            .specializations
+            .iter()
+            .map(|s| s.trim().to_lowercase())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        let row = sqlx::query(
+            r#"
+            INSERT INTO reviewer_profiles
+                (user_id, organization_id, specializations, utc_offset_minutes, max_open_assignments, is_active)
+            VALUES ($1, (SELECT organization_id FROM organization_members WHERE user_id = $1), $2, $3, $4, $5)
+            ON CONFLICT (user_id) DO UPDATE SET
+                organization_id = EXCLUDED.organization_id,
+                specializations = EXCLUDED.specializations,
+                utc_offset_minutes = EXCLUDED.utc_offset_minutes,
+                max_open_assignments = EXCLUDED.max_open_assignments,
+                is_active = EXCLUDED.is_active,
+                updated_at = NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(&specializations)
+        .bind(request.utc_offset_minutes)
+        .bind(max_open)
+        .bind(request.is_active.unwrap_or(true))
+        .fetch_one(&self.db_pool)
+        .await?;
+
+        Ok(profile_from_row(&row))
+    }
+
+    /// A reviewer's own edit: only their specializations change
+    pub async fn update_specializations(
+        &self,
+        user_id: Uuid,
+        specializations: &[String],
+    ) -> Result<ReviewerProfile> {
+        let specializations: Vec<String> = specializations
+            .iter()
+            .map(|s| s.trim().to_lowercase())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        let row = sqlx::query(
+            r#"
+            UPDATE reviewer_profiles
+            SET specializations = $2,
+                organization_id = (SELECT organization_id FROM organization_members WHERE user_id = $1),
+                updated_at = NOW()
+            WHERE user_id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(&specializations)
+        .fetch_optional(&self.db_pool)
+        .await?
+        .ok_or_else(|| AssignmentError::NotFound(format!("Reviewer {} not found", user_id)))?;
+
+        Ok(profile_from_row(&row))
+    }
+
+    /// Open a panel for a submission or dispute and offer its seats
+    pub async fn create_panel(&self, request: CreatePanelRequest) -> Result<ReviewPanel> {
+        let panel_size = request
+            .panel_size
+            .unwrap_or_else(|| request.kind.default_panel_size());
+        if !(1..=MAX_PANEL_SIZE).contains(&panel_size) {
+            return Err(AssignmentError::Validation(format!(
+                "panel_size must be between 1 and {}",
+                MAX_PANEL_SIZE
+            )));
+        }
+        let specializations: Vec<String> = request
+            .required_specializations
+            .iter()
+            .map(|s| s.trim().to_lowercase())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        let mut tx = self.db_pool.begin().await?;
+        let panel_id: Option<Uuid> = sqlx::query_scalar(
+            r#"
+            INSERT INTO review_panels
+                (kind, subject_id, bounty_id, submitter_id, submitter_organization_id,
+                 required_specializations, excluded_reviewers, eligible_reviewers, panel_size)
+            VALUES ($1, $2, $3, $4,
+                    (SELECT organization_id FROM organization_members WHERE user_id = $4),
+                    $5, $6, $7, $8)
+            ON CONFLICT (kind, subject_id) DO NOTHING
+            RETURNING id
+            "#,
+        )
+        .bind(request.kind.as_str())
+        .bind(request.subject_id)
+        .bind(request.bounty_id)
+        .bind(request.submitter_id)
+        .bind(&specializations)
+        .bind(&request.excluded_reviewers)
+        .bind(&request.eligible_reviewers)
+        .bind(panel_size)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(panel_id) = panel_id else {
+            return Err(AssignmentError::Conflict(format!(
+                "A {} panel already exists for {}",
+                request.kind.as_str(),
+                request.subject_id
+            )));
+        };
+
+        self.fill_panel(&mut tx, panel_id).await?;
+        tx.commit().await?;
+
+        self.get_panel(panel_id)
+            .await?
+            .ok_or_else(|| AssignmentError::NotFound(format!("Panel {} not found", panel_id)))
+    }
+
+    pub async fn get_panel(&self, panel_id: Uuid) -> Result<Option<ReviewPanel>> {
+        let row = sqlx::query("SELECT * FROM review_panels WHERE id = $1")
+            .bind(panel_id)
+            .fetch_optional(&self.db_pool)
+            .await?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let assignments = sqlx::query(
+            "SELECT * FROM review_assignments WHERE panel_id = $1 ORDER BY offered_at, id",
+        )
+        .bind(panel_id)
+        .fetch_all(&self.db_pool)
+        .await?
+        .iter()
+        .map(assignment_from_row)
+        .collect();
+
+        let kind: String = row.get("kind");
+        Ok(Some(ReviewPanel {
+            id: row.get("id"),
+            kind: ReviewKind::parse(&kind).unwrap_or(ReviewKind::HumanReview),
+            subject_id: row.get("subject_id"),
+            bounty_id: row.get("bounty_id"),
+            panel_size: row.get("panel_size"),
+            required_specializations: row.get("required_specializations"),
+            staffed_at: row.get("staffed_at"),
+            created_at: row.get("created_at"),
+            assignments,
+        }))
+    }
+
+    /// A reviewer's assignments, newest first
+    pub async fn list_for_reviewer(
+        &self,
+        reviewer_id: Uuid,
+        status: Option<AssignmentStatus>,
+    ) -> Result<Vec<ReviewAssignment>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM review_assignments
+            WHERE reviewer_id = $1 AND ($2::text IS NULL OR status = $2)
+            ORDER BY offered_at DESC
+            LIMIT 200
+            "#,
+        )
+        .bind(reviewer_id)
+        .bind(status.map(|s| s.as_str()))
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(rows.iter().map(assignment_from_row).collect())
+    }
+
+    pub async fn get_assignment(&self, assignment_id: Uuid) -> Result<Option<ReviewAssignment>> {
+        let row = sqlx::query("SELECT * FROM review_assignments WHERE id = $1")
+            .bind(assignment_id)
+            .fetch_optional(&self.db_pool)
+            .await?;
+        Ok(row.as_ref().map(assignment_from_row))
+    }
+
+    /// Take a pending seat. The panel counts as staffed once every seat is accepted.
+    pub async fn accept(&self, assignment_id: Uuid, reviewer_id: Uuid) -> Result<ReviewAssignment> {
+        let mut tx = self.db_pool.begin().await?;
+        let assignment = self
+            .transition(
+                &mut tx,
+                assignment_id,
+                reviewer_id,
+                AssignmentStatus::Pending,
+                "status = 'accepted', responded_at = NOW()",
+                None,
+            )
+            .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE review_panels p SET staffed_at = NOW()
+            WHERE p.id = $1 AND p.staffed_at IS NULL
+              AND (SELECT COUNT(*) FROM review_assignments a
+                   WHERE a.panel_id = p.id AND a.status IN ('accepted', 'completed')) >= p.panel_size
+            "#,
+        )
+        .bind(assignment.panel_id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        info!("Reviewer {} accepted assignment {}", reviewer_id, assignment_id);
+        Ok(assignment)
+    }
+
+    /// Turn down a pending seat; it is offered to the next best reviewer
+    pub async fn decline(
+        &self,
+        assignment_id: Uuid,
+        reviewer_id: Uuid,
+        reason: Option<String>,
+    ) -> Result<ReviewAssignment> {
+        let mut tx = self.db_pool.begin().await?;
+        let assignment = self
+            .transition(
+                &mut tx,
+                assignment_id,
+                reviewer_id,
+                AssignmentStatus::Pending,
+                "status = 'declined', responded_at = NOW(), decline_reason = $3",
+                reason,
+            )
+            .await?;
+        self.fill_panel(&mut tx, assignment.panel_id).await?;
+        tx.commit().await?;
+
+        info!("Reviewer {} declined assignment {}", reviewer_id, assignment_id);
+        Ok(assignment)
+    }
+
+    /// Mark an accepted review as done, freeing the reviewer's capacity
+    pub async fn complete(&self, assignment_id: Uuid, reviewer_id: Uuid) -> Result<ReviewAssignment> {
+        let mut tx = self.db_pool.begin().await?;
+        let assignment = self
+            .transition(
+                &mut tx,
+                assignment_id,
+                reviewer_id,
+                AssignmentStatus::Accepted,
+                "status = 'completed', completed_at = NOW()",
+                None,
+            )
+            .await?;
+        tx.commit().await?;
+        Ok(assignment)
+    }
+
+    /// Expire unanswered offers and top up every panel still short of reviewers.
+    ///
+    /// Returns the number of new offers made.
+    pub async fn reassign_overdue(&self) -> Result<usize> {
+        let expired = sqlx::query(
+            r#"
+            UPDATE review_assignments SET status = 'expired'
+            WHERE status = 'pending' AND respond_by <= NOW()
+            "#,
+        )
+        .execute(&self.db_pool)
+        .await?
+        .rows_affected();
+        if expired > 0 {
+            info!("Expired {} unanswered review assignments", expired);
+        }
+
+        let panels: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT p.id FROM review_panels p
+            WHERE p.staffed_at IS NULL
+              AND (SELECT COUNT(*) FROM review_assignments a
+                   WHERE a.panel_id = p.id
+                     AND a.status IN ('pending', 'accepted', 'completed')) < p.panel_size
+            ORDER BY p.created_at
+            "#,
+        )
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        let mut offered = 0;
+        for panel_id in panels {
+            let mut tx = self.db_pool.begin().await?;
+            offered += self.fill_panel(&mut tx, panel_id).await?;
+            tx.commit().await?;
+        }
+        Ok(offered)
+    }
+
+    /// Apply a status change to the caller's own assignment, if it is in `from`
+    async fn transition(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        assignment_id: Uuid,
+        reviewer_id: Uuid,
+        from: AssignmentStatus,
+        set: &str,
+        reason: Option<String>,
+    ) -> Result<ReviewAssignment> {
+        let sql = format!(
+            "UPDATE review_assignments SET {} \
+             WHERE id = $1 AND reviewer_id = $2 AND status = $4 \
+               AND ($4 <> 'pending' OR respond_by > NOW()) \
+             RETURNING *",
+            set
+        );
+        let row = sqlx::query(&sql)
+            .bind(assignment_id)
+            .bind(reviewer_id)
+            .bind(reason)
+            .bind(from.as_str())
+            .fetch_optional(&mut **tx)
+            .await?;
+        if let Some(row) = row {
+            return Ok(assignment_from_row(&row));
+        }
+
+        match self.get_assignment(assignment_id).await? {
+            Some(existing) if existing.reviewer_id == reviewer_id => {
+                Err(AssignmentError::Conflict(format!(
+                    "Assignment {} is {} and can no longer be changed this way",
+                    assignment_id,
+                    if existing.status == AssignmentStatus::Pending {
+                        "past its response deadline"
+                    } else {
+                        existing.status.as_str()
+                    }
+                )))
+            }
+            _ => Err(AssignmentError::NotFound(format!(
+                "Assignment {} not found",
+                assignment_id
+            ))),
+        }
+    }
+
+    /// Offer open seats on a panel to the best eligible reviewers.
+    ///
+    /// The panel row is locked for the duration so a decline racing the
+    /// timeout sweep cannot offer the same seat twice. Returns the number of
+    /// offers made; panels with too few eligible reviewers stay short and are
+    /// retried by the sweep.
+    async fn fill_panel(&self, tx: &mut Transaction<'_, Postgres>, panel_id: Uuid) -> Result<usize> {
+        let panel = sqlx::query(
+            r#"
+            SELECT submitter_id, submitter_organization_id, required_specializations,
+                   excluded_reviewers, eligible_reviewers, panel_size
+            FROM review_panels WHERE id = $1
+            FOR UPDATE
+            "#,
+        )
+        .bind(panel_id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        let offers = sqlx::query(
+            r#"
+            SELECT a.reviewer_id, a.status, r.utc_offset_minutes
+            FROM review_assignments a
+            JOIN reviewer_profiles r ON r.user_id = a.reviewer_id
+            WHERE a.panel_id = $1
+            "#,
+        )
+        .bind(panel_id)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        let mut excluded: HashSet<Uuid> = panel
+            .get::<Vec<Uuid>, _>("excluded_reviewers")
+            .into_iter()
+            .collect();
+        let mut seated_offsets = Vec::new();
+        for offer in &offers {
+            excluded.insert(offer.get("reviewer_id"));
+            let status: String = offer.get("status");
+            if matches!(status.as_str(), "pending" | "accepted" | "completed") {
+                seated_offsets.push(offer.get("utc_offset_minutes"));
+            }
+        }
+
+        let panel_size: i32 = panel.get("panel_size");
+        let seats = (panel_size as usize).saturating_sub(seated_offsets.len());
+        if seats == 0 {
+            return Ok(0);
+        }
+
+        let needs = PanelNeeds {
+            submitter_id: panel.get("submitter_id"),
+            submitter_organization_id: panel.get("submitter_organization_id"),
+            required_specializations: panel.get("required_specializations"),
+            excluded,
+            eligible: panel
+                .get::<Option<Vec<Uuid>>, _>("eligible_reviewers")
+                .map(|eligible| eligible.into_iter().collect()),
+            seated_offsets,
+        };
+        let candidates = self.load_candidates(tx).await?;
+        let selected = select_reviewers(&self.config, needs, &candidates, seats);
+
+        let respond_by = Utc::now() + Duration::seconds(self.config.response_timeout_secs as i64);
+        for (reviewer_id, score) in &selected {
+            sqlx::query(
+                r#"
+                INSERT INTO review_assignments (panel_id, reviewer_id, score, respond_by)
+                VALUES ($1, $2, $3, $4)
+                "#,
+            )
+            .bind(panel_id)
+            .bind(reviewer_id)
+            .bind(score)
+            .bind(respond_by)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        if selected.len() < seats {
+            warn!(
+                "Panel {} is short {} reviewer(s): not enough eligible reviewers",
+                panel_id,
+                seats - selected.len()
+            );
+        }
+        Ok(selected.len())
+    }
+
+    async fn load_candidates(&self, tx: &mut Transaction<'_, Postgres>) -> Result<Vec<Candidate>> {
+        let window_start = Utc::now() - Duration::days(self.config.load_window_days);
+        let rows = sqlx::query(
+            r#"
+            SELECT r.user_id,
+                   (SELECT organization_id FROM organization_members WHERE user_id = r.user_id) AS organization_id,
+                   r.specializations, r.utc_offset_minutes,
+                   r.max_open_assignments,
+                   COUNT(a.id) FILTER (WHERE a.status IN ('pending', 'accepted')) AS open_assignments,
+                   COUNT(a.id) FILTER (WHERE a.offered_at >= $1) AS recent_assignments
+            FROM reviewer_profiles r
+            LEFT JOIN review_assignments a ON a.reviewer_id = r.user_id
+            WHERE r.is_active = true
+            GROUP BY r.user_id
+            "#,
+        )
+        .bind(window_start)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| Candidate {
+                user_id: row.get("user_id"),
+                organization_id: row.get("organization_id"),
+                specializations: row.get("specializations"),
+                utc_offset_minutes: row.get("utc_offset_minutes"),
+                max_open_assignments: row.get("max_open_assignments"),
+                open_assignments: row.get("open_assignments"),
+                recent_assignments: row.get("recent_assignments"),
+            })
+            .collect())
+    }
+}
+
+fn profile_from_row(row: &sqlx::postgres::PgRow) -> ReviewerProfile {
+    ReviewerProfile {
+        user_id: row.get("user_id"),
+        organization_id: row.get("organization_id"),
+        specializations: row.get("specializations"),
+        utc_offset_minutes: row.get("utc_offset_minutes"),
+        max_open_assignments: row.get("max_open_assignments"),
+        is_active: row.get("is_active"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+fn assignment_from_row(row: &sqlx::postgres::PgRow) -> ReviewAssignment {
+    let status: String = row.get("status");
+    ReviewAssignment {
+        id: row.get("id"),
+        panel_id: row.get("panel_id"),
+        reviewer_id: row.get("reviewer_id"),
+        status: AssignmentStatus::parse(&status).unwrap_or(AssignmentStatus::Pending),
+        score: row.get("score"),
+        offered_at: row.get("offered_at"),
+        respond_by: row.get("respond_by"),
+        responded_at: row.get("responded_at"),
+        decline_reason: row.get("decline_reason"),
+        completed_at: row.get("completed_at"),
+    }
+}
@@ -0,0 +1,581 @@
+//! Collusion and Sybil detection.
+//!
+//! Engines run by one operator, or coordinating with each other, can outvote
+//! honest engines. Recent submissions are compared pair by pair for signs of
+//! a voting ring: engines that go against the majority together, submit at
+//! the same moment, come from the same address, stake from the same or
+//! commonly funded wallets, or hand in near-identical analyses. Pairs with
+//! enough evidence are joined into clusters, whose members' votes count for
+//! less until an admin confirms or dismisses the cluster.
+
+pub mod service;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use thiserror::Error;
+use uuid::Uuid;
+
+pub use service::CollusionService;
+
+use crate::config::CollusionConfig;
+
+/// Analyses with fewer distinct words are too short to compare
+const MIN_ANALYSIS_WORDS: usize = 5;
+
+#[derive(Debug, Error)]
+pub enum CollusionError {
+    #[error("{0}")]
+    Validation(String),
+
+    #[error("{0}")]
+    NotFound(String),
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ClusterStatus {
+    /// Found by the analyzer and awaiting review
+    Flagged,
+    /// An admin agreed the members collude
+    Confirmed,
+    /// An admin found the members independent
+    Dismissed,
+}
+
+impl ClusterStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClusterStatus::Flagged => "flagged",
+            ClusterStatus::Confirmed => "confirmed",
+            ClusterStatus::Dismissed => "dismissed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "flagged" => Some(ClusterStatus::Flagged),
+            "confirmed" => Some(ClusterStatus::Confirmed),
+            "dismissed" => Some(ClusterStatus::Dismissed),
+            _ => None,
+        }
+    }
+}
+
+/// One submission, as compared by the analyzer
+#[derive(Debug, Clone)]
+pub struct SubmissionRecord {
+    pub bounty_id: Uuid,
+    pub engine_id: String,
+    pub verdict: String,
+    pub submitted_at: DateTime<Utc>,
+    pub source_ip: Option<String>,
+    pub wallet_address: Option<String>,
+    pub analysis_text: Option<String>,
+}
+
+/// A sign that two engines act together
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Evidence {
+    /// Whenever either engine went against the majority, both did, with the
+    /// same verdict
+    CorrelatedVerdicts { against_majority: usize, together: usize },
+    /// The engines submitted to most of their shared bounties at the same
+    /// moment
+    SynchronizedSubmissions { shared_bounties: usize, simultaneous: usize },
+    /// The engines submitted from the same address
+    SharedIp { ip: String },
+    /// The engines staked from the same wallet
+    SharedWallet { wallet: String },
+    /// One engine's wallet funded the other's, or both were funded from the
+    /// same wallet
+    CommonFunding { funder: String },
+    /// The engines handed in near-identical analyses
+    SimilarAnalyses { bounty_id: Uuid, similarity: f64 },
+}
+
+impl Evidence {
+    /// How strongly the evidence points to collusion; one strong sign or two
+    /// weaker ones flag a pair at the default flag score
+    pub fn score(&self) -> f64 {
+        match self {
+            Evidence::SharedWallet { .. } => 1.0,
+            Evidence::CommonFunding { .. } | Evidence::SimilarAnalyses { .. } => 0.75,
+            Evidence::CorrelatedVerdicts { .. }
+            | Evidence::SynchronizedSubmissions { .. }
+            | Evidence::SharedIp { .. } => 0.5,
+        }
+    }
+}
+
+/// The evidence against one pair of engines
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairEvidence {
+    pub engines: [String; 2],
+    pub score: f64,
+    pub evidence: Vec<Evidence>,
+}
+
+/// Engines linked by flagged pairs
+#[derive(Debug, Clone)]
+pub struct DetectedCluster {
+    /// Sorted
+    pub engine_ids: Vec<String>,
+    /// Score of the most suspicious pair
+    pub score: f64,
+    pub pairs: Vec<PairEvidence>,
+}
+
+impl DetectedCluster {
+    /// Identifies the cluster when the same engines are found again
+    pub fn key(&self) -> String {
+        self.engine_ids.join(",")
+    }
+}
+
+/// A cluster as stored for review
+#[derive(Debug, Clone, Serialize)]
+pub struct CollusionCluster {
+    pub id: Uuid,
+    pub engine_ids: Vec<String>,
+    pub score: f64,
+    pub evidence: Vec<PairEvidence>,
+    pub status: ClusterStatus,
+    /// Vote weight left to the members
+    pub weight_factor: f64,
+    pub detected_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub reviewed_by: Option<Uuid>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub review_notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClusterListQuery {
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReviewClusterRequest {
+    pub status: ClusterStatus,
+    pub notes: Option<String>,
+}
+
+/// How two engines' submissions compare across the bounties both voted on
+#[derive(Default)]
+struct PairStats {
+    shared_bounties: usize,
+    simultaneous: usize,
+    against_majority: usize,
+    together: usize,
+    most_similar: Option<(Uuid, f64)>,
+}
+
+fn pair_key(a: &str, b: &str) -> (String, String) {
+    if a <= b {
+        (a.to_string(), b.to_string())
+    } else {
+        (b.to_string(), a.to_string())
+    }
+}
+
+fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Jaccard similarity of two analyses' words; `None` if either is too short
+/// to tell
+pub fn text_similarity(a: &str, b: &str) -> Option<f64> {
+    let (a, b) = (words(a), words(b));
+    if a.len() < MIN_ANALYSIS_WORDS || b.len() < MIN_ANALYSIS_WORDS {
+        return None;
+    }
+    let shared = a.intersection(&b).count();
+    Some(shared as f64 / (a.len() + b.len() - shared) as f64)
+}
+
+/// The verdict most engines gave, if one did
+fn majority_verdict(submissions: &[&SubmissionRecord]) -> Option<String> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for submission in submissions {
+        *counts.entry(submission.verdict.as_str()).or_insert(0) += 1;
+    }
+    let top = counts.values().copied().max()?;
+    let mut leaders = counts.iter().filter(|(_, count)| **count == top);
+    let (verdict, _) = leaders.next()?;
+    if leaders.next().is_some() {
+        return None;
+    }
+    Some(verdict.to_string())
+}
+
+/// Compare every pair of engines that voted on the same bounties
+fn compare_votes(
+    records: &[SubmissionRecord],
+    config: &CollusionConfig,
+) -> BTreeMap<(String, String), PairStats> {
+    let mut by_bounty: HashMap<Uuid, Vec<&SubmissionRecord>> = HashMap::new();
+    for record in records {
+        by_bounty.entry(record.bounty_id).or_default().push(record);
+    }
+
+    let mut pairs: BTreeMap<(String, String), PairStats> = BTreeMap::new();
+    for (bounty_id, submissions) in by_bounty {
+        let majority = majority_verdict(&submissions);
+        for (i, a) in submissions.iter().enumerate() {
+            for b in &submissions[i + 1..] {
+                if a.engine_id == b.engine_id {
+                    continue;
+                }
+                let stats = pairs.entry(pair_key(&a.engine_id, &b.engine_id)).or_default();
+                stats.shared_bounties += 1;
+                if (a.submitted_at - b.submitted_at).num_seconds().abs() <= config.timing_window_secs {
+                    stats.simultaneous += 1;
+                }
+                if let Some(majority) = &majority {
+                    let a_against = a.verdict != *majority;
+                    let b_against = b.verdict != *majority;
+                    if a_against || b_against {
+                        stats.against_majority += 1;
+                        if a.verdict == b.verdict {
+                            stats.together += 1;
+                        }
+                    }
+                }
+                if let (Some(text_a), Some(text_b)) = (&a.analysis_text, &b.analysis_text) {
+                    if let Some(similarity) = text_similarity(text_a, text_b) {
+                        if stats.most_similar.is_none_or(|(_, most)| similarity > most) {
+                            stats.most_similar = Some((bounty_id, similarity));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    pairs
+}
+
+/// Pairs of engines sharing any value, e.g. a source address, with the
+/// first value they share
+fn shared_values(values: &HashMap<String, BTreeSet<String>>) -> BTreeMap<(String, String), String> {
+    let mut engines_by_value: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for (engine, engine_values) in values {
+        for value in engine_values {
+            engines_by_value.entry(value).or_default().insert(engine);
+        }
+    }
+
+    let mut pairs = BTreeMap::new();
+    for (value, engines) in engines_by_value {
+        let engines: Vec<&str> = engines.into_iter().collect();
+        for (i, a) in engines.iter().enumerate() {
+            for b in &engines[i + 1..] {
+                pairs.entry(pair_key(a, b)).or_insert_with(|| value.to_string());
+            }
+        }
+    }
+    pairs
+}
+
+/// Pairs of engines whose wallets are linked by funding: one funded the
+/// other, or both were funded from the same wallet. Wallets funding more than
+/// `max_fanout` engines, such as exchanges and the platform itself, link
+/// nobody.
+fn funding_links(
+    wallets: &HashMap<String, BTreeSet<String>>,
+    funders: &HashMap<String, Vec<String>>,
+    max_fanout: usize,
+) -> BTreeMap<(String, String), String> {
+    // Every wallet an engine's wallets were funded from, plus its own, which
+    // funded itself in effect
+    let mut sources: HashMap<String, BTreeSet<String>> = HashMap::new();
+    for (engine, engine_wallets) in wallets {
+        let engine_sources = sources.entry(engine.clone()).or_default();
+        for wallet in engine_wallets {
+            engine_sources.insert(wallet.clone());
+            if let Some(wallet_funders) = funders.get(wallet) {
+                engine_sources.extend(wallet_funders.iter().map(|f| f.to_lowercase()));
+            }
+        }
+    }
+
+    let mut fanout: HashMap<&str, usize> = HashMap::new();
+    for engine_sources in sources.values() {
+        for source in engine_sources {
+            *fanout.entry(source).or_insert(0) += 1;
+        }
+    }
+    let linking: HashMap<String, BTreeSet<String>> = sources
+        .iter()
+        .map(|(engine, engine_sources)| {
+            let kept = engine_sources
+                .iter()
+                .filter(|source| fanout[source.as_str()] <= max_fanout)
+                .cloned()
+                .collect();
+            (engine.clone(), kept)
+        })
+        .collect();
+
+    // Sharing a wallet outright is reported as such, not as funding
+    let shared = shared_values(wallets);
+    shared_values(&linking)
+        .into_iter()
+        .filter(|(pair, _)| !shared.contains_key(pair))
+        .collect()
+}
+
+/// Find the pairs of engines with enough evidence of collusion and join
+/// them into clusters. `funders` maps lowercase wallets to the wallets that
+/// funded them.
+pub fn analyze(
+    records: &[SubmissionRecord],
+    funders: &HashMap<String, Vec<String>>,
+    config: &CollusionConfig,
+) -> Vec<DetectedCluster> {
+    let mut ips: HashMap<String, BTreeSet<String>> = HashMap::new();
+    let mut wallets: HashMap<String, BTreeSet<String>> = HashMap::new();
+    for record in records {
+        if let Some(ip) = record.source_ip.as_deref().filter(|ip| !ip.is_empty()) {
+            ips.entry(record.engine_id.clone()).or_default().insert(ip.to_string());
+        }
+        if let Some(wallet) = record.wallet_address.as_deref().filter(|w| !w.is_empty()) {
+            wallets
+                .entry(record.engine_id.clone())
+                .or_default()
+                .insert(wallet.to_lowercase());
+        }
+    }
+
+    let mut evidence: BTreeMap<(String, String), Vec<Evidence>> = BTreeMap::new();
+    for (pair, stats) in compare_votes(records, config) {
+        let found = evidence.entry(pair).or_default();
+        if stats.against_majority >= config.min_shared_bounties
+            && stats.together as f64 / stats.against_majority as f64 >= config.agreement_threshold
+        {
+            found.push(Evidence::CorrelatedVerdicts {
+                against_majority: stats.against_majority,
+                together: stats.together,
+            });
+        }
+        if stats.shared_bounties >= config.min_shared_bounties
+            && stats.simultaneous as f64 / stats.shared_bounties as f64 >= config.timing_threshold
+        {
+            found.push(Evidence::SynchronizedSubmissions {
+                shared_bounties: stats.shared_bounties,
+                simultaneous: stats.simultaneous,
+            });
+        }
+        if let Some((bounty_id, similarity)) = stats.most_similar {
+            if similarity >= config.text_similarity_threshold {
+                found.push(Evidence::SimilarAnalyses {
+                    bounty_id,
+                    similarity: (similarity * 1000.0).round() / 1000.0,
+                });
+            }
+        }
+    }
+    for (pair, ip) in shared_values(&ips) {
+        evidence.entry(pair).or_default().push(Evidence::SharedIp { ip });
+    }
+    for (pair, wallet) in shared_values(&wallets) {
+        evidence.entry(pair).or_default().push(Evidence::SharedWallet { wallet });
+    }
+    for (pair, funder) in funding_links(&wallets, funders, config.max_funder_fanout) {
+        evidence.entry(pair).or_default().push(Evidence::CommonFunding { funder });
+    }
+
+    let flagged: Vec<PairEvidence> = evidence
+        .into_iter()
+        .filter_map(|((a, b), evidence)| {
+            let score = evidence.iter().map(Evidence::score).sum::<f64>();
+            (score >= config.flag_score).then_some(PairEvidence {
+                engines: [a, b],
+                score,
+                evidence,
+            })
+        })
+        .collect();
+
+    cluster(flagged)
+}
+
+/// Join flagged pairs sharing an engine into clusters
+fn cluster(pairs: Vec<PairEvidence>) -> Vec<DetectedCluster> {
+    let mut parent: HashMap<String, String> = HashMap::new();
+    fn root(parent: &mut HashMap<String, String>, engine: &str) -> String {
+        let mut current = engine.to_string();
+        while let Some(next) = parent.get(&current).filter(|next| **next != current) {
+            current = next.clone();
+        }
+        parent.insert(engine.to_string(), current.clone());
+        current
+    }
+
+    for pair in &pairs {
+        let a = root(&mut parent, &pair.engines[0]);
+        let b = root(&mut parent, &pair.engines[1]);
+        if a != b {
+            parent.insert(a, b);
+        }
+    }
+
+    let mut clusters: BTreeMap<String, Vec<PairEvidence>> = BTreeMap::new();
+    for pair in pairs {
+        let cluster_root = root(&mut parent, &pair.engines[0]);
+        clusters.entry(cluster_root).or_default().push(pair);
+    }
+
+    clusters
+        .into_values()
+        .map(|pairs| {
+            let engine_ids: BTreeSet<String> = pairs.iter().flat_map(|p| p.engines.iter().cloned()).collect();
+            DetectedCluster {
+                engine_ids: engine_ids.into_iter().collect(),
+                score: pairs.iter().map(|p| p.score).fold(0.0, f64::max),
+                pairs,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> CollusionConfig {
+        CollusionConfig {
+            lookback_days: 30,
+            min_shared_bounties: 3,
+            agreement_threshold: 0.9,
+            timing_window_secs: 5,
+            timing_threshold: 0.5,
+            text_similarity_threshold: 0.85,
+            flag_score: 1.0,
+            max_funder_fanout: 20,
+            flagged_weight_factor: 0.5,
+            confirmed_weight_factor: 0.1,
+            payment_service_url: None,
+            scan_interval_secs: 3600,
+        }
+    }
+
+    fn record(bounty_id: Uuid, engine_id: &str, verdict: &str, submitted_at: DateTime<Utc>) -> SubmissionRecord {
+        SubmissionRecord {
+            bounty_id,
+            engine_id: engine_id.to_string(),
+            verdict: verdict.to_string(),
+            submitted_at,
+            source_ip: None,
+            wallet_address: None,
+            analysis_text: None,
+        }
+    }
+
+    #[test]
+    fn test_ring_voting_together_at_once_is_flagged() {
+        let start = Utc::now();
+        let mut records = Vec::new();
+        for i in 0..4 {
+            let bounty_id = Uuid::new_v4();
+            let at = start + chrono::Duration::hours(i);
+            // Honest engines, minutes apart
+            for (n, engine) in ["honest1", "honest2", "honest3"].iter().enumerate() {
+                records.push(record(bounty_id, engine, "malicious", at + chrono::Duration::minutes(n as i64 * 10)));
+            }
+            // The ring votes against them within a second of each other
+            records.push(record(bounty_id, "ring1", "benign", at + chrono::Duration::minutes(45)));
+            records.push(record(bounty_id, "ring2", "benign", at + chrono::Duration::minutes(45) + chrono::Duration::seconds(1)));
+        }
+
+        let clusters = analyze(&records, &HashMap::new(), &test_config());
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].engine_ids, vec!["ring1".to_string(), "ring2".to_string()]);
+        let evidence = &clusters[0].pairs[0].evidence;
+        assert!(evidence.iter().any(|e| matches!(e, Evidence::CorrelatedVerdicts { .. })));
+        assert!(evidence.iter().any(|e| matches!(e, Evidence::SynchronizedSubmissions { .. })));
+    }
+
+    #[test]
+    fn test_shared_ip_alone_is_not_enough() {
+        let bounty_id = Uuid::new_v4();
+        let now = Utc::now();
+        let mut a = record(bounty_id, "a", "malicious", now);
+        let mut b = record(bounty_id, "b", "malicious", now + chrono::Duration::minutes(30));
+        a.source_ip = Some("203.0.113.7".to_string());
+        b.source_ip = Some("203.0.113.7".to_string());
+
+        assert!(analyze(&[a.clone(), b.clone()], &HashMap::new(), &test_config()).is_empty());
+
+        // A shared stake wallet on top of it is
+        a.wallet_address = Some("0xAAAA".to_string());
+        b.wallet_address = Some("0xaaaa".to_string());
+        let clusters = analyze(&[a, b], &HashMap::new(), &test_config());
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].pairs[0].evidence.len(), 2);
+    }
+
+    #[test]
+    fn test_common_funding_links_engines_but_exchanges_do_not() {
+        let now = Utc::now();
+        let engines = ["a", "b", "c"];
+        let records: Vec<SubmissionRecord> = engines
+            .iter()
+            .map(|engine| {
+                let mut r = record(Uuid::new_v4(), engine, "benign", now);
+                r.wallet_address = Some(format!("0x{}", engine));
+                r
+            })
+            .collect();
+        // a and b were funded by the same wallet; everyone used the exchange
+        let funders = HashMap::from([
+            ("0xa".to_string(), vec!["0xfunder".to_string(), "0xexchange".to_string()]),
+            ("0xb".to_string(), vec!["0xfunder".to_string(), "0xexchange".to_string()]),
+            ("0xc".to_string(), vec!["0xexchange".to_string()]),
+        ]);
+        let mut config = test_config();
+        config.max_funder_fanout = 2;
+        config.flag_score = 0.75;
+
+        let clusters = analyze(&records, &funders, &config);
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].engine_ids, vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(
+            clusters[0].pairs[0].evidence,
+            vec![Evidence::CommonFunding { funder: "0xfunder".to_string() }]
+        );
+    }
+
+    #[test]
+    fn test_copied_analyses_are_similar() {
+        let original = "Sample drops a loader that injects into explorer.exe and beacons to evil.example";
+        let copied = "sample drops a loader that injects into explorer.exe and beacons to evil.example!";
+        let different = "Benign installer signed by a known vendor with no network activity observed";
+
+        assert_eq!(text_similarity(original, copied), Some(1.0));
+        assert!(text_similarity(original, different).unwrap() < 0.2);
+        assert_eq!(text_similarity("too short", original), None);
+    }
+
+    #[test]
+    fn test_pairs_sharing_an_engine_form_one_cluster() {
+        let pair = |a: &str, b: &str| PairEvidence {
+            engines: [a.to_string(), b.to_string()],
+            score: 1.0,
+            evidence: vec![Evidence::SharedWallet { wallet: "0x1".to_string() }],
+        };
+
+        let clusters = cluster(vec![pair("a", "b"), pair("x", "y"), pair("b", "c")]);
+        assert_eq!(clusters.len(), 2);
+        let keys: HashSet<String> = clusters.iter().map(DetectedCluster::key).collect();
+        assert!(keys.contains("a,b,c"));
+        assert!(keys.contains("x,y"));
+    }
+}
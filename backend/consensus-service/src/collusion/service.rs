@@ -0,0 +1,268 @@
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use shared::request_signing::RequestSigner;
+use sqlx::{PgPool, Row};
+use std::collections::{BTreeSet, HashMap};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::{
+    analyze, ClusterStatus, CollusionCluster, CollusionError, DetectedCluster, PairEvidence,
+    ReviewClusterRequest, SubmissionRecord,
+};
+use crate::config::CollusionConfig;
+
+/// Most wallets looked up in one request to the payment-service
+const FUNDER_BATCH_SIZE: usize = 500;
+
+type Result<T> = std::result::Result<T, CollusionError>;
+
+#[derive(Serialize)]
+struct FundersRequest<'a> {
+    addresses: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct FundersResponse {
+    funders: HashMap<String, Vec<String>>,
+}
+
+pub struct CollusionService {
+    config: CollusionConfig,
+    db_pool: PgPool,
+    http: reqwest::Client,
+    signer: Option<RequestSigner>,
+}
+
+/// Vote weight left to each of `engine_ids` that is in a cluster not yet
+/// dismissed; engines missing from the result count fully
+pub async fn weight_factors(pool: &PgPool, engine_ids: &[String]) -> sqlx::Result<HashMap<String, f64>> {
+    let rows = sqlx::query(
+        r#"
+        SELECT engine_id, MIN(weight_factor) AS weight_factor
+        FROM collusion_clusters, UNNEST(engine_ids) AS engine_id
+        WHERE status <> 'dismissed' AND engine_id = ANY($1)
+        GROUP BY engine_id
+        "#,
+    )
+    .bind(engine_ids)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.get("engine_id"), row.get("weight_factor")))
+        .collect())
+}
+
+impl CollusionService {
+    pub fn new(config: CollusionConfig, db_pool: PgPool) -> anyhow::Result<Self> {
+        Ok(Self {
+            config,
+            db_pool,
+            http: reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(30))
+                .build()?,
+            signer: RequestSigner::from_env()?,
+        })
+    }
+
+    /// Compare recent submissions and store the clusters found. Clusters
+    /// found before keep their review status and get the latest evidence.
+    /// Returns how many clusters are new.
+    pub async fn scan(&self) -> Result<usize> {
+        let since = Utc::now() - Duration::days(self.config.lookback_days);
+        let rows = sqlx::query(
+            r#"
+            SELECT bounty_id, engine_id, verdict, submitted_at, source_ip, wallet_address, analysis_text
+            FROM consensus_submissions
+            WHERE submitted_at >= $1
+            "#,
+        )
+        .bind(since)
+        .fetch_all(&self.db_pool)
+        .await?;
+        let records: Vec<SubmissionRecord> = rows
+            .into_iter()
+            .map(|row| SubmissionRecord {
+                bounty_id: row.get("bounty_id"),
+                engine_id: row.get("engine_id"),
+                verdict: row.get("verdict"),
+                submitted_at: row.get("submitted_at"),
+                source_ip: row.get("source_ip"),
+                wallet_address: row.get("wallet_address"),
+                analysis_text: row.get("analysis_text"),
+            })
+            .collect();
+
+        let wallets: BTreeSet<String> = records
+            .iter()
+            .filter_map(|r| r.wallet_address.as_deref())
+            .map(str::to_lowercase)
+            .collect();
+        let funders = match self.funders(&wallets.into_iter().collect::<Vec<_>>()).await {
+            Ok(funders) => funders,
+            Err(e) => {
+                // The other signals still count
+                warn!("Wallet funding not compared: {}", e);
+                HashMap::new()
+            }
+        };
+
+        let mut new_clusters = 0;
+        for cluster in analyze(&records, &funders, &self.config) {
+            if self.store(&cluster).await? {
+                new_clusters += 1;
+                warn!(
+                    "Flagged possible collusion between engines {} (score {:.2})",
+                    cluster.engine_ids.join(", "),
+                    cluster.score
+                );
+            }
+        }
+        Ok(new_clusters)
+    }
+
+    /// The wallets that funded each of `wallets`, from the payment-service
+    async fn funders(&self, wallets: &[String]) -> anyhow::Result<HashMap<String, Vec<String>>> {
+        let Some(base_url) = &self.config.payment_service_url else {
+            return Ok(HashMap::new());
+        };
+        const PATH: &str = "/api/v1/payments/wallets/funders";
+
+        let mut funders = HashMap::new();
+        for batch in wallets.chunks(FUNDER_BATCH_SIZE) {
+            let body = serde_json::to_vec(&FundersRequest { addresses: batch })?;
+            let mut request = self
+                .http
+                .post(format!("{}{}", base_url.trim_end_matches('/'), PATH))
+                .header("content-type", "application/json")
+                .body(body.clone());
+            for (name, value) in shared::observability::propagation_headers() {
+                request = request.header(name, value);
+            }
+            if let Some(signer) = &self.signer {
+                for (name, value) in signer.sign_now("POST", PATH, Some(&body), None, None).pairs() {
+                    request = request.header(name, value);
+                }
+            }
+
+            let response = request.send().await?.error_for_status()?;
+            funders.extend(response.json::<FundersResponse>().await?.funders);
+        }
+        Ok(funders)
+    }
+
+    /// Store a detected cluster; true if it was not known before
+    async fn store(&self, cluster: &DetectedCluster) -> Result<bool> {
+        let evidence = serde_json::to_string(&cluster.pairs)
+            .map_err(|e| CollusionError::Validation(format!("Unserializable evidence: {}", e)))?;
+        let inserted: bool = sqlx::query_scalar(
+            r#"
+            INSERT INTO collusion_clusters (cluster_key, engine_ids, score, evidence, weight_factor)
+            VALUES ($1, $2, $3, $4::JSONB, $5)
+            ON CONFLICT (cluster_key) DO UPDATE
+            SET score = EXCLUDED.score,
+                evidence = EXCLUDED.evidence,
+                updated_at = NOW()
+            RETURNING (xmax = 0)
+            "#,
+        )
+        .bind(cluster.key())
+        .bind(&cluster.engine_ids)
+        .bind(cluster.score)
+        .bind(evidence)
+        .bind(self.config.flagged_weight_factor)
+        .fetch_one(&self.db_pool)
+        .await?;
+
+        Ok(inserted)
+    }
+
+    /// Clusters for review, most recently detected first
+    pub async fn list(&self, status: Option<&str>) -> Result<Vec<CollusionCluster>> {
+        let status = status
+            .map(|s| {
+                ClusterStatus::parse(s)
+                    .ok_or_else(|| CollusionError::Validation(format!("Unknown cluster status: {}", s)))
+            })
+            .transpose()?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT id, engine_ids, score, evidence::text AS evidence, status, weight_factor,
+                   detected_at, updated_at, reviewed_by, reviewed_at, review_notes
+            FROM collusion_clusters
+            WHERE $1::VARCHAR IS NULL OR status = $1
+            ORDER BY detected_at DESC
+            LIMIT 200
+            "#,
+        )
+        .bind(status.map(|s| s.as_str()))
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(rows.iter().map(cluster_from_row).collect())
+    }
+
+    /// Confirm or dismiss a cluster, setting the weight its members' votes
+    /// carry from now on
+    pub async fn review(
+        &self,
+        cluster_id: Uuid,
+        reviewer_id: Uuid,
+        request: ReviewClusterRequest,
+    ) -> Result<CollusionCluster> {
+        let weight_factor = match request.status {
+            ClusterStatus::Flagged => self.config.flagged_weight_factor,
+            ClusterStatus::Confirmed => self.config.confirmed_weight_factor,
+            ClusterStatus::Dismissed => 1.0,
+        };
+
+        let row = sqlx::query(
+            r#"
+            UPDATE collusion_clusters
+            SET status = $2, weight_factor = $3, reviewed_by = $4, reviewed_at = NOW(),
+                review_notes = $5, updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, engine_ids, score, evidence::text AS evidence, status, weight_factor,
+                      detected_at, updated_at, reviewed_by, reviewed_at, review_notes
+            "#,
+        )
+        .bind(cluster_id)
+        .bind(request.status.as_str())
+        .bind(weight_factor)
+        .bind(reviewer_id)
+        .bind(&request.notes)
+        .fetch_optional(&self.db_pool)
+        .await?
+        .ok_or_else(|| CollusionError::NotFound(format!("Cluster {} not found", cluster_id)))?;
+
+        let cluster = cluster_from_row(&row);
+        info!(
+            "Collusion cluster {} marked {} by {}",
+            cluster_id,
+            request.status.as_str(),
+            reviewer_id
+        );
+        Ok(cluster)
+    }
+}
+
+fn cluster_from_row(row: &sqlx::postgres::PgRow) -> CollusionCluster {
+    let evidence: String = row.get("evidence");
+    let status: String = row.get("status");
+    CollusionCluster {
+        id: row.get("id"),
+        engine_ids: row.get("engine_ids"),
+        score: row.get("score"),
+        evidence: serde_json::from_str::<Vec<PairEvidence>>(&evidence).unwrap_or_default(),
+        status: ClusterStatus::parse(&status).unwrap_or(ClusterStatus::Flagged),
+        weight_factor: row.get("weight_factor"),
+        detected_at: row.get("detected_at"),
+        updated_at: row.get("updated_at"),
+        reviewed_by: row.get("reviewed_by"),
+        reviewed_at: row.get("reviewed_at"),
+        review_notes: row.get("review_notes"),
+    }
+}
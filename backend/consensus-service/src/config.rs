@@ -1,4 +1,5 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -7,6 +8,15 @@ pub struct Config {
     pub database: DatabaseConfig,
     pub redis: RedisConfig,
     pub consensus: ConsensusConfig,
+    pub feed: FeedConfig,
+    pub assignment: AssignmentConfig,
+    pub collusion: CollusionConfig,
+    pub filter: FilterConfig,
+    pub dispute: DisputeConfig,
+    pub recalculation: RecalculationConfig,
+    pub correlation: CorrelationConfig,
+    pub registry: RegistryConfig,
+    pub settlement: SettlementConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,8 +45,166 @@ pub struct ConsensusConfig {
     pub reputation_weight: f64,
     pub confidence_weight: f64,
     pub time_weight: f64,
+    /// Submissions within this long of the first one all count as early
+    pub time_decay_grace_secs: u64,
+    /// How long after the grace period a submission's time factor halves
+    pub time_decay_half_life_secs: u64,
+    /// Time factor late submissions decay to and no further, 0.0 to 1.0
+    pub min_time_factor: f64,
     pub dispute_threshold: f64,
+    /// Stake the submissions must add up to before a verdict stands
+    pub min_total_stake: i64,
+    /// Hours after a bounty's first submission its result is finalized even
+    /// if the bounty has not closed; 0 waits for the bounty to close
     pub auto_finalize_hours: u64,
+    /// How often open bounties are checked for quorum
+    pub auto_finalize_interval_secs: u64,
+    /// Weight left to votes of engines on probation, 0.0 to 1.0
+    pub probation_weight: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeedConfig {
+    pub s3_endpoint: String,
+    pub s3_region: String,
+    pub s3_bucket: String,
+    pub s3_access_key: String,
+    pub s3_secret_key: String,
+    pub signing_secret: String,
+    pub presigned_url_ttl_secs: u64,
+    pub export_interval_secs: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssignmentConfig {
+    /// How long a reviewer has to accept or decline before the seat is reassigned
+    pub response_timeout_secs: u64,
+    /// Window over which recent assignments count towards a reviewer's load
+    pub load_window_days: i64,
+    pub specialization_weight: f64,
+    pub load_weight: f64,
+    pub timezone_weight: f64,
+    pub sweep_interval_secs: u64,
+}
+
+/// Votes dropped or down-weighted before aggregation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterConfig {
+    /// Finalized votes an engine needs before its accuracy is judged
+    pub min_history: u32,
+    /// Share of finalized votes an engine must have called right for its
+    /// votes to count
+    pub min_accuracy: f64,
+    /// Confidence outside this range is not a calibrated estimate
+    pub min_confidence: f64,
+    pub max_confidence: f64,
+    /// How far an engine's confidence may exceed its accuracy
+    pub max_overconfidence: f64,
+    /// Weight left to votes with implausible confidence, 0.0 to 1.0
+    pub confidence_weight_factor: f64,
+}
+
+/// Staked disputes and the arbitration panels that judge them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisputeConfig {
+    /// Smallest stake, in wei, a dispute may be raised with
+    pub min_stake: Decimal,
+    /// Hours after a bounty is finalized its verdict may be disputed
+    pub window_hours: i64,
+    /// Hours the panel has to vote once the dispute is raised
+    pub voting_hours: i64,
+    /// Reputation an arbitrator needs, besides having voting power
+    pub min_arbitrator_reputation: i32,
+    /// Votes a panel must cast for its majority to decide the dispute
+    pub min_votes: usize,
+    /// Where dispute stakes are locked, released and slashed
+    pub payment_service_url: String,
+    /// Where arbitrators' reputation is looked up and adjustments are sent
+    pub reputation_service_url: String,
+    pub resolve_interval_secs: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollusionConfig {
+    /// How far back submissions are compared
+    pub lookback_days: i64,
+    /// Bounties two engines must share before their voting is compared
+    pub min_shared_bounties: usize,
+    /// Share of shared bounties two engines must agree on, against the
+    /// majority, for their verdicts to count as correlated
+    pub agreement_threshold: f64,
+    /// Submissions this close together count as simultaneous
+    pub timing_window_secs: i64,
+    /// Share of shared bounties two engines must submit to simultaneously
+    pub timing_threshold: f64,
+    /// Jaccard similarity above which two analyses count as copied
+    pub text_similarity_threshold: f64,
+    /// Evidence score at which a pair of engines is flagged
+    pub flag_score: f64,
+    /// Wallets funding more engine wallets than this, e.g. exchanges, do not
+    /// link the engines they funded
+    pub max_funder_fanout: usize,
+    /// Vote weight left to engines in flagged and confirmed clusters
+    pub flagged_weight_factor: f64,
+    pub confirmed_weight_factor: f64,
+    /// Where wallet funding is looked up; funding is not compared if unset
+    pub payment_service_url: Option<String>,
+    pub scan_interval_secs: u64,
+}
+
+/// Batch recalculation of consensus results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecalculationConfig {
+    /// Most bounties one batch may recalculate
+    pub max_bounties: usize,
+    /// Bounties recalculated per pass of the worker
+    pub chunk_size: i64,
+    /// Seconds after which a bounty claimed by a worker that stopped is
+    /// claimed again
+    pub claim_timeout_secs: i64,
+    pub interval_secs: u64,
+}
+
+/// Correlation of finalized artifacts by their reported indicators
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorrelationConfig {
+    /// How far back finalized artifacts are compared
+    pub lookback_days: i64,
+    /// ssdeep similarity, 0 to 100, at which two artifacts count as related
+    pub ssdeep_threshold: u32,
+    /// Engines that must report a contacted domain for it to count
+    pub min_domain_reports: usize,
+    /// Domains contacted by more artifacts than this, e.g. CDNs and update
+    /// servers, do not relate the artifacts that contacted them
+    pub max_domain_fanout: i64,
+    /// Most related artifacts compared with each finalized one
+    pub max_candidates: i64,
+    /// Finalized results compared per pass
+    pub batch_size: i64,
+    pub scan_interval_secs: u64,
+}
+
+/// Registry of automated engines
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistryConfig {
+    /// Engines not heard from for this long count as offline
+    pub heartbeat_timeout_secs: i64,
+}
+
+/// How finalized bounties settle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettlementConfig {
+    /// Share of its stake a fully confident wrong vote loses, before
+    /// weighting by reputation
+    pub slash_rate: f64,
+    /// Most of its stake a wrong vote can lose
+    pub max_slash_fraction: f64,
+    /// Reputation a fully confident correct vote gains
+    pub reputation_reward: i32,
+    /// Reputation a fully confident wrong vote loses
+    pub reputation_penalty: i32,
+    /// Largest share of a bounty's reward an engine on probation can earn
+    pub probation_max_reward_share: f64,
 }
 
 impl Config {
@@ -82,12 +250,207 @@ impl Config {
                 time_weight: std::env::var("TIME_WEIGHT")
                     .unwrap_or_else(|_| "0.2".to_string())
                     .parse()?,
+                time_decay_grace_secs: std::env::var("TIME_DECAY_GRACE_SECS")
+                    .unwrap_or_else(|_| "300".to_string())
+                    .parse()?,
+                time_decay_half_life_secs: std::env::var("TIME_DECAY_HALF_LIFE_SECS")
+                    .unwrap_or_else(|_| "3600".to_string())
+                    .parse()?,
+                min_time_factor: std::env::var("MIN_TIME_FACTOR")
+                    .unwrap_or_else(|_| "0.5".to_string())
+                    .parse()?,
                 dispute_threshold: std::env::var("DISPUTE_THRESHOLD")
                     .unwrap_or_else(|_| "0.4".to_string())
                     .parse()?,
+                min_total_stake: std::env::var("MIN_TOTAL_STAKE")
+                    .unwrap_or_else(|_| "0".to_string())
+                    .parse()?,
                 auto_finalize_hours: std::env::var("AUTO_FINALIZE_HOURS")
                     .unwrap_or_else(|_| "24".to_string())
                     .parse()?,
+                auto_finalize_interval_secs: std::env::var("AUTO_FINALIZE_INTERVAL_SECS")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()?,
+                probation_weight: std::env::var("PROBATION_VOTE_WEIGHT")
+                    .unwrap_or_else(|_| "0.5".to_string())
+                    .parse()?,
+            },
+            feed: FeedConfig {
+                s3_endpoint: std::env::var("S3_ENDPOINT")
+                    .unwrap_or_else(|_| "http://minio:9000".to_string()),
+                s3_region: std::env::var("S3_REGION")
+                    .unwrap_or_else(|_| "us-east-1".to_string()),
+                s3_bucket: std::env::var("FEED_S3_BUCKET")
+                    .unwrap_or_else(|_| "nexus-intel-feed".to_string()),
+                s3_access_key: std::env::var("S3_ACCESS_KEY").context("S3_ACCESS_KEY must be set")?,
+                s3_secret_key: std::env::var("S3_SECRET_KEY").context("S3_SECRET_KEY must be set")?,
+                signing_secret: std::env::var("FEED_SIGNING_SECRET")
+                    .context("FEED_SIGNING_SECRET must be set")?,
+                presigned_url_ttl_secs: std::env::var("FEED_PRESIGNED_URL_TTL_SECS")
+                    .unwrap_or_else(|_| "3600".to_string())
+                    .parse()?,
+                export_interval_secs: std::env::var("FEED_EXPORT_INTERVAL_SECS")
+                    .unwrap_or_else(|_| "3600".to_string())
+                    .parse()?,
+            },
+            assignment: AssignmentConfig {
+                response_timeout_secs: std::env::var("REVIEW_RESPONSE_TIMEOUT_SECS")
+                    .unwrap_or_else(|_| "86400".to_string())
+                    .parse()?,
+                load_window_days: std::env::var("REVIEW_LOAD_WINDOW_DAYS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()?,
+                specialization_weight: std::env::var("REVIEW_SPECIALIZATION_WEIGHT")
+                    .unwrap_or_else(|_| "0.5".to_string())
+                    .parse()?,
+                load_weight: std::env::var("REVIEW_LOAD_WEIGHT")
+                    .unwrap_or_else(|_| "0.3".to_string())
+                    .parse()?,
+                timezone_weight: std::env::var("REVIEW_TIMEZONE_WEIGHT")
+                    .unwrap_or_else(|_| "0.2".to_string())
+                    .parse()?,
+                sweep_interval_secs: std::env::var("REVIEW_SWEEP_INTERVAL_SECS")
+                    .unwrap_or_else(|_| "300".to_string())
+                    .parse()?,
+            },
+            collusion: CollusionConfig {
+                lookback_days: std::env::var("COLLUSION_LOOKBACK_DAYS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()?,
+                min_shared_bounties: std::env::var("COLLUSION_MIN_SHARED_BOUNTIES")
+                    .unwrap_or_else(|_| "3".to_string())
+                    .parse()?,
+                agreement_threshold: std::env::var("COLLUSION_AGREEMENT_THRESHOLD")
+                    .unwrap_or_else(|_| "0.9".to_string())
+                    .parse()?,
+                timing_window_secs: std::env::var("COLLUSION_TIMING_WINDOW_SECS")
+                    .unwrap_or_else(|_| "5".to_string())
+                    .parse()?,
+                timing_threshold: std::env::var("COLLUSION_TIMING_THRESHOLD")
+                    .unwrap_or_else(|_| "0.5".to_string())
+                    .parse()?,
+                text_similarity_threshold: std::env::var("COLLUSION_TEXT_SIMILARITY_THRESHOLD")
+                    .unwrap_or_else(|_| "0.85".to_string())
+                    .parse()?,
+                flag_score: std::env::var("COLLUSION_FLAG_SCORE")
+                    .unwrap_or_else(|_| "1.0".to_string())
+                    .parse()?,
+                max_funder_fanout: std::env::var("COLLUSION_MAX_FUNDER_FANOUT")
+                    .unwrap_or_else(|_| "20".to_string())
+                    .parse()?,
+                flagged_weight_factor: std::env::var("COLLUSION_FLAGGED_WEIGHT_FACTOR")
+                    .unwrap_or_else(|_| "0.5".to_string())
+                    .parse()?,
+                confirmed_weight_factor: std::env::var("COLLUSION_CONFIRMED_WEIGHT_FACTOR")
+                    .unwrap_or_else(|_| "0.1".to_string())
+                    .parse()?,
+                payment_service_url: std::env::var("PAYMENT_SERVICE_URL").ok(),
+                scan_interval_secs: std::env::var("COLLUSION_SCAN_INTERVAL_SECS")
+                    .unwrap_or_else(|_| "3600".to_string())
+                    .parse()?,
+            },
+            filter: FilterConfig {
+                min_history: std::env::var("FILTER_MIN_HISTORY")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()?,
+                min_accuracy: std::env::var("FILTER_MIN_ACCURACY")
+                    .unwrap_or_else(|_| "0.3".to_string())
+                    .parse()?,
+                min_confidence: std::env::var("FILTER_MIN_CONFIDENCE")
+                    .unwrap_or_else(|_| "0.05".to_string())
+                    .parse()?,
+                max_confidence: std::env::var("FILTER_MAX_CONFIDENCE")
+                    .unwrap_or_else(|_| "0.99".to_string())
+                    .parse()?,
+                max_overconfidence: std::env::var("FILTER_MAX_OVERCONFIDENCE")
+                    .unwrap_or_else(|_| "0.4".to_string())
+                    .parse()?,
+                confidence_weight_factor: std::env::var("FILTER_CONFIDENCE_WEIGHT_FACTOR")
+                    .unwrap_or_else(|_| "0.5".to_string())
+                    .parse()?,
+            },
+            dispute: DisputeConfig {
+                min_stake: std::env::var("DISPUTE_MIN_STAKE")
+                    .unwrap_or_else(|_| "1000000000000000000".to_string())
+                    .parse()?,
+                window_hours: std::env::var("DISPUTE_WINDOW_HOURS")
+                    .unwrap_or_else(|_| "168".to_string())
+                    .parse()?,
+                voting_hours: std::env::var("DISPUTE_VOTING_HOURS")
+                    .unwrap_or_else(|_| "72".to_string())
+                    .parse()?,
+                min_arbitrator_reputation: std::env::var("DISPUTE_MIN_ARBITRATOR_REPUTATION")
+                    .unwrap_or_else(|_| "1000".to_string())
+                    .parse()?,
+                min_votes: std::env::var("DISPUTE_MIN_VOTES")
+                    .unwrap_or_else(|_| "2".to_string())
+                    .parse()?,
+                payment_service_url: std::env::var("PAYMENT_SERVICE_URL")
+                    .unwrap_or_else(|_| "http://localhost:8085".to_string()),
+                reputation_service_url: std::env::var("REPUTATION_SERVICE_URL")
+                    .unwrap_or_else(|_| "http://localhost:8086".to_string()),
+                resolve_interval_secs: std::env::var("DISPUTE_RESOLVE_INTERVAL_SECS")
+                    .unwrap_or_else(|_| "300".to_string())
+                    .parse()?,
+            },
+            recalculation: RecalculationConfig {
+                max_bounties: std::env::var("RECALCULATION_MAX_BOUNTIES")
+                    .unwrap_or_else(|_| "10000".to_string())
+                    .parse()?,
+                chunk_size: std::env::var("RECALCULATION_CHUNK_SIZE")
+                    .unwrap_or_else(|_| "50".to_string())
+                    .parse()?,
+                claim_timeout_secs: std::env::var("RECALCULATION_CLAIM_TIMEOUT_SECS")
+                    .unwrap_or_else(|_| "600".to_string())
+                    .parse()?,
+                interval_secs: std::env::var("RECALCULATION_INTERVAL_SECS")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()?,
+            },
+            correlation: CorrelationConfig {
+                lookback_days: std::env::var("CORRELATION_LOOKBACK_DAYS")
+                    .unwrap_or_else(|_| "180".to_string())
+                    .parse()?,
+                ssdeep_threshold: std::env::var("CORRELATION_SSDEEP_THRESHOLD")
+                    .unwrap_or_else(|_| "80".to_string())
+                    .parse()?,
+                min_domain_reports: std::env::var("CORRELATION_MIN_DOMAIN_REPORTS")
+                    .unwrap_or_else(|_| "2".to_string())
+                    .parse()?,
+                max_domain_fanout: std::env::var("CORRELATION_MAX_DOMAIN_FANOUT")
+                    .unwrap_or_else(|_| "25".to_string())
+                    .parse()?,
+                max_candidates: std::env::var("CORRELATION_MAX_CANDIDATES")
+                    .unwrap_or_else(|_| "1000".to_string())
+                    .parse()?,
+                batch_size: std::env::var("CORRELATION_BATCH_SIZE")
+                    .unwrap_or_else(|_| "100".to_string())
+                    .parse()?,
+                scan_interval_secs: std::env::var("CORRELATION_SCAN_INTERVAL_SECS")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()?,
+            },
+            registry: RegistryConfig {
+                heartbeat_timeout_secs: std::env::var("REGISTRY_HEARTBEAT_TIMEOUT_SECS")
+                    .unwrap_or_else(|_| "120".to_string())
+                    .parse()?,
+            },
+            settlement: SettlementConfig {
+                slash_rate: std::env::var("SETTLEMENT_SLASH_RATE")
+                    .unwrap_or_else(|_| "0.1".to_string())
+                    .parse()?,
+                max_slash_fraction: std::env::var("SETTLEMENT_MAX_SLASH_FRACTION")
+                    .unwrap_or_else(|_| "0.5".to_string())
+                    .parse()?,
+                reputation_reward: std::env::var("SETTLEMENT_REPUTATION_REWARD")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()?,
+                reputation_penalty: std::env::var("SETTLEMENT_REPUTATION_PENALTY")
+                    .unwrap_or_else(|_| "15".to_string())
+                    .parse()?,
+                probation_max_reward_share: std::env::var("SETTLEMENT_PROBATION_MAX_REWARD_SHARE")
+                    .unwrap_or_else(|_| "0.1".to_string())
+                    .parse()?,
             },
         })
     }
@@ -0,0 +1,487 @@
+//! Cross-artifact correlation of consensus results.
+//!
+//! Engines report what they saw of an artifact: its import hash, its ssdeep
+//! fuzzy hash and the domains it contacted. Once a bounty's consensus is
+//! final, its artifact is compared with other finalized artifacts sharing
+//! the import hash, a similar fuzzy hash or a contacted domain. Related
+//! artifacts called benign on one side and malicious or suspicious on the
+//! other are opened as conflicts for an analyst to review.
+
+pub mod service;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use thiserror::Error;
+use uuid::Uuid;
+
+pub use service::CorrelationService;
+
+use crate::config::CorrelationConfig;
+use crate::models::Verdict;
+
+/// Most contacted domains kept per report
+const MAX_DOMAINS: usize = 100;
+
+/// Fuzzy hashes are at most this long per block size
+const SPAMSUM_LENGTH: usize = 64;
+
+/// Hashes sharing no run of this many characters are unrelated
+const ROLLING_WINDOW: usize = 7;
+
+const MIN_BLOCK_SIZE: u64 = 3;
+
+#[derive(Debug, Error)]
+pub enum CorrelationError {
+    #[error("{0}")]
+    Validation(String),
+
+    #[error("{0}")]
+    NotFound(String),
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// What is known of an artifact that can link it to others
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ArtifactIndicators {
+    /// PE import hash, 32 hex characters
+    #[serde(default)]
+    pub imphash: Option<String>,
+    /// ssdeep fuzzy hash, "<block size>:<hash>:<hash>"
+    #[serde(default)]
+    pub ssdeep: Option<String>,
+    /// Domains the artifact contacted
+    #[serde(default)]
+    pub domains: Vec<String>,
+}
+
+impl ArtifactIndicators {
+    /// The indicators with malformed ones dropped, hashes in lowercase where
+    /// case does not matter and domains as bare, lowercase host names
+    pub fn normalized(self) -> Self {
+        let domains: BTreeSet<String> = self.domains.iter().filter_map(|domain| normalize_domain(domain)).collect();
+        Self {
+            imphash: self
+                .imphash
+                .map(|imphash| imphash.trim().to_lowercase())
+                .filter(|imphash| imphash.len() == 32 && imphash.chars().all(|c| c.is_ascii_hexdigit())),
+            ssdeep: self
+                .ssdeep
+                .map(|ssdeep| ssdeep.trim().to_string())
+                .filter(|ssdeep| FuzzyHash::parse(ssdeep).is_some()),
+            domains: domains.into_iter().take(MAX_DOMAINS).collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.imphash.is_none() && self.ssdeep.is_none() && self.domains.is_empty()
+    }
+
+    /// Block sizes of the fuzzy hashes this one can be compared with
+    pub fn comparable_block_sizes(&self) -> Vec<i64> {
+        let Some(hash) = self.ssdeep.as_deref().and_then(FuzzyHash::parse) else {
+            return Vec::new();
+        };
+        let mut sizes = vec![hash.block_size as i64, (hash.block_size * 2) as i64];
+        if hash.block_size % 2 == 0 {
+            sizes.push((hash.block_size / 2) as i64);
+        }
+        sizes
+    }
+}
+
+/// The host name in a domain or URL, if it looks like one
+fn normalize_domain(value: &str) -> Option<String> {
+    let value = value.trim().to_lowercase();
+    let host = value.split_once("://").map_or(value.as_str(), |(_, rest)| rest);
+    let host = host.split(['/', '?', '#']).next()?;
+    let host = host.rsplit_once('@').map_or(host, |(_, host)| host);
+    let host = host.split(':').next()?.trim_end_matches('.');
+    let valid = host.len() <= 253
+        && host.contains('.')
+        && host.split('.').all(|label| !label.is_empty() && label.len() <= 63)
+        && host.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.');
+    valid.then(|| host.to_string())
+}
+
+/// The artifact's indicators as its engines reported them: the import hash
+/// and fuzzy hash most engines reported, and the domains reported by at
+/// least `min_domain_reports` engines
+pub fn consensus_indicators(reports: &[ArtifactIndicators], min_domain_reports: usize) -> ArtifactIndicators {
+    fn most_reported<'a>(values: impl Iterator<Item = &'a String>) -> Option<String> {
+        let mut counts: BTreeMap<&String, usize> = BTreeMap::new();
+        for value in values {
+            *counts.entry(value).or_default() += 1;
+        }
+        // Ties go to the first in order, so the choice is stable
+        counts
+            .into_iter()
+            .fold(None, |best: Option<(&String, usize)>, (value, count)| match best {
+                Some((_, most)) if most >= count => best,
+                _ => Some((value, count)),
+            })
+            .map(|(value, _)| value.clone())
+    }
+
+    let mut domains: BTreeMap<&String, usize> = BTreeMap::new();
+    for report in reports {
+        for domain in &report.domains {
+            *domains.entry(domain).or_default() += 1;
+        }
+    }
+
+    ArtifactIndicators {
+        imphash: most_reported(reports.iter().filter_map(|r| r.imphash.as_ref())),
+        ssdeep: most_reported(reports.iter().filter_map(|r| r.ssdeep.as_ref())),
+        domains: domains
+            .into_iter()
+            .filter(|(_, count)| *count >= min_domain_reports.max(1))
+            .map(|(domain, _)| domain.clone())
+            .collect(),
+    }
+}
+
+/// An ssdeep fuzzy hash
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyHash {
+    pub block_size: u64,
+    /// Hash at the block size
+    first: String,
+    /// Hash at twice the block size
+    second: String,
+}
+
+impl FuzzyHash {
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut parts = value.splitn(3, ':');
+        let block_size: u64 = parts.next()?.parse().ok()?;
+        let first = parts.next()?;
+        let second = parts.next()?.split(',').next()?;
+        let valid = |hash: &str| {
+            hash.len() <= SPAMSUM_LENGTH && hash.chars().all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '/')
+        };
+        if block_size < MIN_BLOCK_SIZE || !valid(first) || !valid(second) {
+            return None;
+        }
+        Some(Self {
+            block_size,
+            first: collapse_runs(first),
+            second: collapse_runs(second),
+        })
+    }
+
+    /// How alike two fuzzy hashes are, 0 to 100, as ssdeep scores them.
+    /// Hashes whose block sizes are more than a factor two apart score 0.
+    pub fn similarity(&self, other: &FuzzyHash) -> u32 {
+        if self.block_size == other.block_size {
+            if self.first == other.first && self.second == other.second {
+                return 100;
+            }
+            score_hashes(&self.first, &other.first, self.block_size)
+                .max(score_hashes(&self.second, &other.second, self.block_size * 2))
+        } else if self.block_size == other.block_size * 2 {
+            score_hashes(&self.first, &other.second, self.block_size)
+        } else if other.block_size == self.block_size * 2 {
+            score_hashes(&self.second, &other.first, other.block_size)
+        } else {
+            0
+        }
+    }
+}
+
+/// Runs of more than three identical characters say little about similarity
+fn collapse_runs(hash: &str) -> String {
+    let mut collapsed = String::with_capacity(hash.len());
+    let mut run = 0;
+    let mut previous = None;
+    for c in hash.chars() {
+        run = if previous == Some(c) { run + 1 } else { 1 };
+        previous = Some(c);
+        if run <= 3 {
+            collapsed.push(c);
+        }
+    }
+    collapsed
+}
+
+/// Edit distance counting insertions and deletions as 1 and substitutions
+/// as 2
+fn edit_distance(a: &[u8], b: &[u8]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, &ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous[j] + if ca == cb { 0 } else { 2 };
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+fn score_hashes(a: &str, b: &str, block_size: u64) -> u32 {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() < ROLLING_WINDOW || b.len() < ROLLING_WINDOW {
+        return 0;
+    }
+    let windows: HashSet<&[u8]> = a.windows(ROLLING_WINDOW).collect();
+    if !b.windows(ROLLING_WINDOW).any(|window| windows.contains(window)) {
+        return 0;
+    }
+
+    let distance = (edit_distance(a, b) * SPAMSUM_LENGTH / (a.len() + b.len())) as u64;
+    let distance = distance * 100 / SPAMSUM_LENGTH as u64;
+    if distance >= 100 {
+        return 0;
+    }
+    let mut score = 100 - distance;
+    // Small block sizes mean small files, whose hashes match too easily
+    if block_size < (99 + ROLLING_WINDOW as u64) / ROLLING_WINDOW as u64 * MIN_BLOCK_SIZE {
+        score = score.min(block_size / MIN_BLOCK_SIZE * a.len().min(b.len()) as u64);
+    }
+    score as u32
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RelationKind {
+    /// Same PE import hash
+    Imphash,
+    /// ssdeep fuzzy hashes at least the configured similarity
+    Ssdeep,
+    /// Both contacted the domain
+    Domain,
+}
+
+/// What links two artifacts
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Relation {
+    pub kind: RelationKind,
+    /// The shared import hash or domain, or the other artifact's fuzzy hash
+    pub value: String,
+    /// Fuzzy hash similarity, 0 to 100
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub similarity: Option<u32>,
+}
+
+/// What links two artifacts. Domains in `common_domains`, contacted by too
+/// many artifacts to say anything, do not count.
+pub fn relations(
+    artifact: &ArtifactIndicators,
+    other: &ArtifactIndicators,
+    common_domains: &HashSet<String>,
+    config: &CorrelationConfig,
+) -> Vec<Relation> {
+    let mut relations = Vec::new();
+    if let (Some(imphash), Some(other_imphash)) = (&artifact.imphash, &other.imphash) {
+        if imphash == other_imphash {
+            relations.push(Relation {
+                kind: RelationKind::Imphash,
+                value: imphash.clone(),
+                similarity: None,
+            });
+        }
+    }
+    if let (Some(hash), Some(other_hash)) = (&artifact.ssdeep, &other.ssdeep) {
+        if let (Some(parsed), Some(other_parsed)) = (FuzzyHash::parse(hash), FuzzyHash::parse(other_hash)) {
+            let similarity = parsed.similarity(&other_parsed);
+            if similarity >= config.ssdeep_threshold {
+                relations.push(Relation {
+                    kind: RelationKind::Ssdeep,
+                    value: other_hash.clone(),
+                    similarity: Some(similarity),
+                });
+            }
+        }
+    }
+    let other_domains: HashSet<&String> = other.domains.iter().collect();
+    for domain in &artifact.domains {
+        if other_domains.contains(domain) && !common_domains.contains(domain) {
+            relations.push(Relation {
+                kind: RelationKind::Domain,
+                value: domain.clone(),
+                similarity: None,
+            });
+        }
+    }
+    relations
+}
+
+/// Whether two verdicts on related artifacts contradict each other: one
+/// benign and the other malicious or suspicious. Unknown contradicts
+/// nothing.
+pub fn conflicting(verdict: &Verdict, other: &Verdict) -> bool {
+    let harmful = |verdict: &Verdict| matches!(verdict, Verdict::Malicious | Verdict::Suspicious);
+    (*verdict == Verdict::Benign && harmful(other)) || (harmful(verdict) && *other == Verdict::Benign)
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictStatus {
+    /// Awaiting an analyst
+    Open,
+    /// One of the verdicts is wrong
+    Confirmed,
+    /// The artifacts are not really related, or both verdicts hold
+    Dismissed,
+}
+
+impl ConflictStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConflictStatus::Open => "open",
+            ConflictStatus::Confirmed => "confirmed",
+            ConflictStatus::Dismissed => "dismissed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "open" => Some(ConflictStatus::Open),
+            "confirmed" => Some(ConflictStatus::Confirmed),
+            "dismissed" => Some(ConflictStatus::Dismissed),
+            _ => None,
+        }
+    }
+}
+
+/// Related artifacts whose final verdicts contradict each other
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsensusConflict {
+    pub id: Uuid,
+    /// The bounty whose finalization found the conflict
+    pub bounty_id: Uuid,
+    pub artifact_hash: Option<String>,
+    pub verdict: Verdict,
+    pub related_bounty_id: Uuid,
+    pub related_artifact_hash: Option<String>,
+    pub related_verdict: Verdict,
+    pub relations: Vec<Relation>,
+    pub status: ConflictStatus,
+    pub detected_at: DateTime<Utc>,
+    pub reviewed_by: Option<Uuid>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub review_notes: Option<String>,
+}
+
+/// Conflicts newest first; `before` pages back from a previous page's last
+/// `detected_at`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ConflictListQuery {
+    pub status: Option<String>,
+    pub before: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReviewConflictRequest {
+    pub status: ConflictStatus,
+    pub notes: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> CorrelationConfig {
+        CorrelationConfig {
+            lookback_days: 90,
+            ssdeep_threshold: 80,
+            min_domain_reports: 1,
+            max_domain_fanout: 20,
+            max_candidates: 1000,
+            batch_size: 100,
+            scan_interval_secs: 60,
+        }
+    }
+
+    const HASH: &str = "96:s4Ud1Lj96tHHlZDrwciQmA+4uy1I0G4HYuL8N3TzS8QsO/wqWXLcMSx:sF1C6tHHDrwcQmA+4uy1Ib4HYuLY3TzS";
+
+    #[test]
+    fn test_fuzzy_hash_similarity() {
+        let hash = FuzzyHash::parse(HASH).unwrap();
+        assert_eq!(hash.similarity(&hash), 100);
+
+        let edited = FuzzyHash::parse(
+            "96:s4Ud1Lj96tHHlZDrwciQmA+4uy1I0G4HYuL8N3TzS8QsO/wqWXLcMSy:sF1C6tHHDrwcQmA+4uy1Ib4HYuLY3TzT",
+        )
+        .unwrap();
+        let similarity = hash.similarity(&edited);
+        assert!((80..100).contains(&similarity), "similarity {}", similarity);
+
+        let unrelated = FuzzyHash::parse("96:abcdefghijklmnopqrstuvwxyzABCDEFGH:abcdefghijklmnopq").unwrap();
+        assert_eq!(hash.similarity(&unrelated), 0);
+
+        let far_block_size = FuzzyHash::parse(&HASH.replacen("96", "384", 1)).unwrap();
+        assert_eq!(hash.similarity(&far_block_size), 0);
+
+        assert!(FuzzyHash::parse("not a hash").is_none());
+        assert_eq!(collapse_runs("aaaaabbbc"), "aaabbbc");
+    }
+
+    #[test]
+    fn test_indicators_are_normalized() {
+        let indicators = ArtifactIndicators {
+            imphash: Some(" F34D5F2D4577ED6D9CEEC516C1F5A744 ".to_string()),
+            ssdeep: Some("garbage".to_string()),
+            domains: vec![
+                "HTTPS://Evil.Example.com:443/path".to_string(),
+                "evil.example.com.".to_string(),
+                "localhost".to_string(),
+            ],
+        }
+        .normalized();
+
+        assert_eq!(indicators.imphash.as_deref(), Some("f34d5f2d4577ed6d9ceec516c1f5a744"));
+        assert_eq!(indicators.ssdeep, None);
+        assert_eq!(indicators.domains, vec!["evil.example.com".to_string()]);
+    }
+
+    #[test]
+    fn test_consensus_indicators_follow_most_engines() {
+        let report = |imphash: &str, domains: &[&str]| ArtifactIndicators {
+            imphash: Some(imphash.to_string()),
+            ssdeep: None,
+            domains: domains.iter().map(|d| d.to_string()).collect(),
+        };
+        let reports = vec![
+            report("aaaa", &["c2.example.com"]),
+            report("bbbb", &["c2.example.com", "decoy.example.com"]),
+            report("bbbb", &[]),
+        ];
+
+        let indicators = consensus_indicators(&reports, 2);
+        assert_eq!(indicators.imphash.as_deref(), Some("bbbb"));
+        assert_eq!(indicators.domains, vec!["c2.example.com".to_string()]);
+        assert_eq!(consensus_indicators(&reports, 1).domains.len(), 2);
+    }
+
+    #[test]
+    fn test_relations_skip_common_domains() {
+        let artifact = ArtifactIndicators {
+            imphash: Some("f34d5f2d4577ed6d9ceec516c1f5a744".to_string()),
+            ssdeep: Some(HASH.to_string()),
+            domains: vec!["c2.example.com".to_string(), "update.microsoft.com".to_string()],
+        };
+        let other = artifact.clone();
+        let common = HashSet::from(["update.microsoft.com".to_string()]);
+
+        let kinds: Vec<RelationKind> = relations(&artifact, &other, &common, &config())
+            .into_iter()
+            .map(|relation| relation.kind)
+            .collect();
+        assert_eq!(kinds, vec![RelationKind::Imphash, RelationKind::Ssdeep, RelationKind::Domain]);
+        assert!(relations(&artifact, &ArtifactIndicators::default(), &common, &config()).is_empty());
+    }
+
+    #[test]
+    fn test_only_benign_against_harmful_conflicts() {
+        assert!(conflicting(&Verdict::Benign, &Verdict::Malicious));
+        assert!(conflicting(&Verdict::Suspicious, &Verdict::Benign));
+        assert!(!conflicting(&Verdict::Malicious, &Verdict::Suspicious));
+        assert!(!conflicting(&Verdict::Benign, &Verdict::Unknown));
+        assert!(!conflicting(&Verdict::Benign, &Verdict::Benign));
+    }
+}
@@ -0,0 +1,325 @@
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{PgPool, Row};
+use std::collections::HashSet;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::{
+    conflicting, consensus_indicators, relations, ArtifactIndicators, ConflictListQuery, ConflictStatus,
+    ConsensusConflict, CorrelationError, FuzzyHash, Relation, ReviewConflictRequest,
+};
+use crate::config::CorrelationConfig;
+use crate::models::Verdict;
+
+/// Most conflicts returned per page
+const MAX_PAGE_SIZE: i64 = 200;
+
+type Result<T> = std::result::Result<T, CorrelationError>;
+
+/// A finalized result not yet compared with other artifacts
+#[derive(Debug, sqlx::FromRow)]
+struct PendingRow {
+    bounty_id: Uuid,
+    artifact_hash: Option<String>,
+    final_verdict: String,
+    consensus_reached: bool,
+    finalized_at: DateTime<Utc>,
+}
+
+/// A finalized artifact that may be related
+#[derive(Debug, sqlx::FromRow)]
+struct CandidateRow {
+    bounty_id: Uuid,
+    artifact_hash: Option<String>,
+    imphash: Option<String>,
+    ssdeep: Option<String>,
+    domains: Vec<String>,
+    final_verdict: String,
+}
+
+pub struct CorrelationService {
+    config: CorrelationConfig,
+    db_pool: PgPool,
+}
+
+impl CorrelationService {
+    pub fn new(config: CorrelationConfig, db_pool: PgPool) -> Self {
+        Self { config, db_pool }
+    }
+
+    /// Compare newly finalized artifacts with the artifacts finalized before
+    /// them and open a conflict for each related pair whose verdicts
+    /// contradict. Returns how many conflicts were opened.
+    pub async fn correlate(&self) -> Result<usize> {
+        let pending: Vec<PendingRow> = sqlx::query_as(
+            r#"
+            SELECT bounty_id, artifact_hash, final_verdict, consensus_reached, finalized_at
+            FROM consensus_results
+            WHERE finalized_at IS NOT NULL AND correlated_at IS NULL
+            ORDER BY finalized_at
+            LIMIT $1
+            "#,
+        )
+        .bind(self.config.batch_size)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        let mut opened = 0;
+        for result in pending {
+            opened += self.correlate_one(&result).await?;
+            sqlx::query("UPDATE consensus_results SET correlated_at = NOW() WHERE bounty_id = $1")
+                .bind(result.bounty_id)
+                .execute(&self.db_pool)
+                .await?;
+        }
+        Ok(opened)
+    }
+
+    async fn correlate_one(&self, result: &PendingRow) -> Result<usize> {
+        let reports: Vec<ArtifactIndicators> = sqlx::query(
+            "SELECT imphash, ssdeep, contacted_domains FROM consensus_submissions WHERE bounty_id = $1",
+        )
+        .bind(result.bounty_id)
+        .fetch_all(&self.db_pool)
+        .await?
+        .iter()
+        .map(|row| ArtifactIndicators {
+            imphash: row.get("imphash"),
+            ssdeep: row.get("ssdeep"),
+            domains: row.get::<Option<Vec<String>>, _>("contacted_domains").unwrap_or_default(),
+        })
+        .collect();
+        let indicators = consensus_indicators(&reports, self.config.min_domain_reports);
+        if indicators.is_empty() {
+            return Ok(0);
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO artifact_indicators (bounty_id, artifact_hash, imphash, ssdeep, ssdeep_block_size, domains, finalized_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (bounty_id) DO UPDATE
+            SET imphash = EXCLUDED.imphash,
+                ssdeep = EXCLUDED.ssdeep,
+                ssdeep_block_size = EXCLUDED.ssdeep_block_size,
+                domains = EXCLUDED.domains
+            "#,
+        )
+        .bind(result.bounty_id)
+        .bind(&result.artifact_hash)
+        .bind(&indicators.imphash)
+        .bind(&indicators.ssdeep)
+        .bind(
+            indicators
+                .ssdeep
+                .as_deref()
+                .and_then(FuzzyHash::parse)
+                .map(|hash| hash.block_size as i64),
+        )
+        .bind(&indicators.domains)
+        .bind(result.finalized_at)
+        .execute(&self.db_pool)
+        .await?;
+
+        // Without consensus there is no verdict to contradict, but the
+        // indicators still link later artifacts
+        let Some(verdict) = Verdict::parse(&result.final_verdict).filter(|_| result.consensus_reached) else {
+            return Ok(0);
+        };
+
+        let common_domains: HashSet<String> = sqlx::query_scalar(
+            r#"
+            SELECT domain
+            FROM artifact_indicators, UNNEST(domains) AS domain
+            WHERE domain = ANY($1)
+            GROUP BY domain
+            HAVING COUNT(*) > $2
+            "#,
+        )
+        .bind(&indicators.domains)
+        .bind(self.config.max_domain_fanout)
+        .fetch_all(&self.db_pool)
+        .await?
+        .into_iter()
+        .collect();
+        let domains: Vec<String> = indicators
+            .domains
+            .iter()
+            .filter(|domain| !common_domains.contains(*domain))
+            .cloned()
+            .collect();
+
+        let candidates: Vec<CandidateRow> = sqlx::query_as(
+            r#"
+            SELECT a.bounty_id, a.artifact_hash, a.imphash, a.ssdeep, a.domains, r.final_verdict
+            FROM artifact_indicators a
+            JOIN consensus_results r ON r.bounty_id = a.bounty_id
+            WHERE a.bounty_id <> $1
+              AND r.consensus_reached
+              AND a.finalized_at >= $2
+              AND (a.imphash = $3 OR a.ssdeep_block_size = ANY($4) OR a.domains && $5)
+            ORDER BY a.finalized_at DESC
+            LIMIT $6
+            "#,
+        )
+        .bind(result.bounty_id)
+        .bind(Utc::now() - Duration::days(self.config.lookback_days))
+        .bind(&indicators.imphash)
+        .bind(indicators.comparable_block_sizes())
+        .bind(&domains)
+        .bind(self.config.max_candidates)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        let mut opened = 0;
+        for candidate in candidates {
+            let Some(related_verdict) = Verdict::parse(&candidate.final_verdict) else {
+                continue;
+            };
+            if !conflicting(&verdict, &related_verdict) {
+                continue;
+            }
+            let other = ArtifactIndicators {
+                imphash: candidate.imphash,
+                ssdeep: candidate.ssdeep,
+                domains: candidate.domains,
+            };
+            let found = relations(&indicators, &other, &common_domains, &self.config);
+            if found.is_empty() {
+                continue;
+            }
+            if self
+                .open_conflict(result, &verdict, candidate.bounty_id, &candidate.artifact_hash, &related_verdict, &found)
+                .await?
+            {
+                opened += 1;
+                warn!(
+                    "Bounty {} ({}) conflicts with related bounty {} ({})",
+                    result.bounty_id,
+                    verdict.to_string(),
+                    candidate.bounty_id,
+                    related_verdict.to_string()
+                );
+            }
+        }
+        Ok(opened)
+    }
+
+    /// Open a conflict between two bounties unless one is already on record
+    /// for the pair
+    async fn open_conflict(
+        &self,
+        result: &PendingRow,
+        verdict: &Verdict,
+        related_bounty_id: Uuid,
+        related_artifact_hash: &Option<String>,
+        related_verdict: &Verdict,
+        relations: &[Relation],
+    ) -> Result<bool> {
+        let opened = sqlx::query(
+            r#"
+            INSERT INTO consensus_conflicts (
+                bounty_id, artifact_hash, verdict, related_bounty_id, related_artifact_hash, related_verdict, relations
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7::JSONB)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(result.bounty_id)
+        .bind(&result.artifact_hash)
+        .bind(verdict.to_string())
+        .bind(related_bounty_id)
+        .bind(related_artifact_hash)
+        .bind(related_verdict.to_string())
+        .bind(serde_json::to_string(relations).unwrap_or_else(|_| "[]".to_string()))
+        .execute(&self.db_pool)
+        .await?;
+        Ok(opened.rows_affected() > 0)
+    }
+
+    /// Conflicts newest first, for analysts to work through
+    pub async fn list(&self, query: &ConflictListQuery) -> Result<Vec<ConsensusConflict>> {
+        let status = query
+            .status
+            .as_deref()
+            .map(|s| {
+                ConflictStatus::parse(s)
+                    .ok_or_else(|| CorrelationError::Validation(format!("Unknown conflict status: {}", s)))
+            })
+            .transpose()?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT id, bounty_id, artifact_hash, verdict, related_bounty_id, related_artifact_hash, related_verdict,
+                   relations::text AS relations, status, detected_at, reviewed_by, reviewed_at, review_notes
+            FROM consensus_conflicts
+            WHERE ($1::VARCHAR IS NULL OR status = $1)
+              AND ($2::TIMESTAMPTZ IS NULL OR detected_at < $2)
+            ORDER BY detected_at DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(status.map(|s| s.as_str()))
+        .bind(query.before)
+        .bind(query.limit.unwrap_or(50).clamp(1, MAX_PAGE_SIZE))
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(rows.iter().map(conflict_from_row).collect())
+    }
+
+    /// Confirm or dismiss a conflict
+    pub async fn review(
+        &self,
+        conflict_id: Uuid,
+        reviewer_id: Uuid,
+        request: ReviewConflictRequest,
+    ) -> Result<ConsensusConflict> {
+        let row = sqlx::query(
+            r#"
+            UPDATE consensus_conflicts
+            SET status = $2, reviewed_by = $3, reviewed_at = NOW(), review_notes = $4
+            WHERE id = $1
+            RETURNING id, bounty_id, artifact_hash, verdict, related_bounty_id, related_artifact_hash, related_verdict,
+                      relations::text AS relations, status, detected_at, reviewed_by, reviewed_at, review_notes
+            "#,
+        )
+        .bind(conflict_id)
+        .bind(request.status.as_str())
+        .bind(reviewer_id)
+        .bind(&request.notes)
+        .fetch_optional(&self.db_pool)
+        .await?
+        .ok_or_else(|| CorrelationError::NotFound(format!("Conflict {} not found", conflict_id)))?;
+
+        info!(
+            "Consensus conflict {} marked {} by {}",
+            conflict_id,
+            request.status.as_str(),
+            reviewer_id
+        );
+        Ok(conflict_from_row(&row))
+    }
+}
+
+fn conflict_from_row(row: &sqlx::postgres::PgRow) -> ConsensusConflict {
+    let relations: String = row.get("relations");
+    let status: String = row.get("status");
+    let verdict: String = row.get("verdict");
+    let related_verdict: String = row.get("related_verdict");
+    ConsensusConflict {
+        id: row.get("id"),
+        bounty_id: row.get("bounty_id"),
+        artifact_hash: row.get("artifact_hash"),
+        verdict: Verdict::parse(&verdict).unwrap_or(Verdict::Unknown),
+        related_bounty_id: row.get("related_bounty_id"),
+        related_artifact_hash: row.get("related_artifact_hash"),
+        related_verdict: Verdict::parse(&related_verdict).unwrap_or(Verdict::Unknown),
+        relations: serde_json::from_str(&relations).unwrap_or_default(),
+        status: ConflictStatus::parse(&status).unwrap_or(ConflictStatus::Open),
+        detected_at: row.get("detected_at"),
+        reviewed_by: row.get("reviewed_by"),
+        reviewed_at: row.get("reviewed_at"),
+        review_notes: row.get("review_notes"),
+    }
+}
@@ -0,0 +1,360 @@
+//! Disputes against finalized verdicts.
+//!
+//! A dispute is raised with a stake locked through the payment-service and
+//! judged by an arbitration panel: reviewers with enough reputation and
+//! voting power in the reputation-service, none of whom voted on the bounty.
+//! Arbitrators vote until the deadline. A majority for another verdict
+//! upholds the dispute, replacing the bounty's verdict, releasing the stake
+//! and correcting the engines' reputation; a majority for the original
+//! verdict rejects it and the stake is slashed to the arbitrators who
+//! confirmed it. Without a majority the verdict stands and the stake is
+//! released.
+
+pub mod service;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use thiserror::Error;
+use uuid::Uuid;
+
+pub use service::DisputeService;
+
+use crate::assignment::AssignmentError;
+use crate::models::{SubmissionVote, Verdict};
+
+#[derive(Debug, Error)]
+pub enum DisputeError {
+    #[error("{0}")]
+    Validation(String),
+
+    #[error("{0}")]
+    NotFound(String),
+
+    #[error("{0}")]
+    Conflict(String),
+
+    #[error("{0}")]
+    Forbidden(String),
+
+    /// The payment-service or reputation-service could not be reached
+    #[error("{0}")]
+    Upstream(String),
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+impl From<AssignmentError> for DisputeError {
+    fn from(e: AssignmentError) -> Self {
+        match e {
+            AssignmentError::Validation(message) => DisputeError::Validation(message),
+            AssignmentError::NotFound(message) => DisputeError::NotFound(message),
+            AssignmentError::Conflict(message) => DisputeError::Conflict(message),
+            AssignmentError::Database(e) => DisputeError::Database(e),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DisputeStatus {
+    /// Waiting for an arbitration panel
+    Open,
+    /// The panel is voting
+    UnderReview,
+    /// Upheld, or closed without a majority
+    Resolved,
+    Rejected,
+}
+
+impl DisputeStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DisputeStatus::Open => "open",
+            DisputeStatus::UnderReview => "under_review",
+            DisputeStatus::Resolved => "resolved",
+            DisputeStatus::Rejected => "rejected",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "open" => Some(DisputeStatus::Open),
+            "under_review" => Some(DisputeStatus::UnderReview),
+            "resolved" => Some(DisputeStatus::Resolved),
+            "rejected" => Some(DisputeStatus::Rejected),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DisputeOutcome {
+    /// The verdict was replaced
+    Upheld,
+    /// The verdict was confirmed
+    Rejected,
+    /// No majority; the verdict stands
+    Inconclusive,
+}
+
+impl DisputeOutcome {
+    /// Outcome of deciding on `verdict` for a dispute of `disputed`
+    pub fn of(disputed: &Verdict, verdict: Option<&Verdict>) -> Self {
+        match verdict {
+            Some(verdict) if verdict != disputed => DisputeOutcome::Upheld,
+            Some(_) => DisputeOutcome::Rejected,
+            None => DisputeOutcome::Inconclusive,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DisputeOutcome::Upheld => "upheld",
+            DisputeOutcome::Rejected => "rejected",
+            DisputeOutcome::Inconclusive => "inconclusive",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "upheld" => Some(DisputeOutcome::Upheld),
+            "rejected" => Some(DisputeOutcome::Rejected),
+            "inconclusive" => Some(DisputeOutcome::Inconclusive),
+            _ => None,
+        }
+    }
+
+    /// Status a dispute is closed with
+    pub fn status(&self) -> DisputeStatus {
+        match self {
+            DisputeOutcome::Rejected => DisputeStatus::Rejected,
+            DisputeOutcome::Upheld | DisputeOutcome::Inconclusive => DisputeStatus::Resolved,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Dispute {
+    pub id: Uuid,
+    pub bounty_id: Uuid,
+    pub submission_id: Option<Uuid>,
+    pub initiator_id: Uuid,
+    pub disputed_verdict: Verdict,
+    pub claimed_verdict: Verdict,
+    pub reason: String,
+    pub evidence: Option<serde_json::Value>,
+    pub wallet_address: String,
+    /// In wei
+    pub stake_amount: Decimal,
+    pub stake_id: Option<Uuid>,
+    pub status: DisputeStatus,
+    pub panel_id: Option<Uuid>,
+    pub voting_deadline: DateTime<Utc>,
+    pub outcome: Option<DisputeOutcome>,
+    pub final_verdict: Option<Verdict>,
+    pub resolution: Option<String>,
+    pub settlement: Option<DisputeSettlement>,
+    /// The admin who resolved the dispute; none when the panel's vote did
+    pub resolved_by: Option<Uuid>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub votes: Vec<ArbitrationVote>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ArbitrationVote {
+    pub arbitrator_id: Uuid,
+    pub verdict: Verdict,
+    pub rationale: Option<String>,
+    pub voted_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateDisputeRequest {
+    pub bounty_id: Uuid,
+    pub submission_id: Option<Uuid>,
+    pub disputed_verdict: Verdict,
+    pub claimed_verdict: Verdict,
+    pub reason: String,
+    pub evidence: Option<serde_json::Value>,
+    /// Wallet the stake is locked from
+    pub wallet_address: String,
+    /// In wei
+    pub stake_amount: Decimal,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CastVoteRequest {
+    pub verdict: Verdict,
+    pub rationale: Option<String>,
+}
+
+/// An admin's ruling, taking the place of the panel's vote
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResolveDisputeRequest {
+    pub resolution: String,
+    pub final_verdict: Verdict,
+    /// Paid to the disputer on top of the released stake, in wei
+    pub compensation: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StakeAction {
+    Released,
+    Slashed,
+}
+
+/// What resolving a dispute did to stakes and reputation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisputeSettlement {
+    pub stake_action: StakeAction,
+    pub stake_amount: Decimal,
+    /// False if the payment-service did not confirm the stake change
+    pub stake_applied: bool,
+    pub compensation: Option<Decimal>,
+    /// A slashed stake's split between the arbitrators who confirmed the verdict
+    pub stake_shares: Vec<StakeShare>,
+    pub reputation: Vec<ReputationAdjustment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StakeShare {
+    pub user_id: Uuid,
+    /// In wei
+    pub amount: Decimal,
+}
+
+/// An engine's vote on the bounty, re-judged against the panel's verdict
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReputationAdjustment {
+    pub user_id: Uuid,
+    pub submission_id: Uuid,
+    pub was_correct: bool,
+    pub confidence: Decimal,
+    /// False if the reputation-service did not confirm the update
+    pub applied: bool,
+}
+
+/// The verdict a strict majority of the panel voted for, once at least
+/// `min_votes` votes are in
+pub fn panel_verdict(votes: &[ArbitrationVote], min_votes: usize) -> Option<Verdict> {
+    if votes.is_empty() || votes.len() < min_votes {
+        return None;
+    }
+    let mut counts: HashMap<String, (usize, &Verdict)> = HashMap::new();
+    for vote in votes {
+        counts.entry(vote.verdict.to_string()).or_insert((0, &vote.verdict)).0 += 1;
+    }
+    counts
+        .into_values()
+        .find(|(count, _)| count * 2 > votes.len())
+        .map(|(_, verdict)| verdict.clone())
+}
+
+/// Split a stake into equal whole-wei shares; the first recipient gets what
+/// does not divide evenly
+pub fn split_stake(amount: Decimal, recipients: &[Uuid]) -> Vec<StakeShare> {
+    if recipients.is_empty() {
+        return Vec::new();
+    }
+    let count = Decimal::from(recipients.len());
+    let share = (amount / count).trunc();
+    let remainder = amount - share * count;
+    recipients
+        .iter()
+        .enumerate()
+        .map(|(i, user_id)| StakeShare {
+            user_id: *user_id,
+            amount: if i == 0 { share + remainder } else { share },
+        })
+        .collect()
+}
+
+/// Re-judge the engines' votes against the verdict the panel settled on
+pub fn reputation_adjustments(votes: &[SubmissionVote], verdict: &Verdict) -> Vec<ReputationAdjustment> {
+    votes
+        .iter()
+        .filter(|vote| !vote.user_id.is_nil())
+        .map(|vote| ReputationAdjustment {
+            user_id: vote.user_id,
+            submission_id: vote.submission_id,
+            was_correct: vote.verdict == *verdict,
+            confidence: vote.confidence,
+            applied: false,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vote(verdict: Verdict) -> ArbitrationVote {
+        ArbitrationVote {
+            arbitrator_id: Uuid::new_v4(),
+            verdict,
+            rationale: None,
+            voted_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_panel_verdict_needs_a_strict_majority() {
+        let split = vec![vote(Verdict::Benign), vote(Verdict::Malicious)];
+        assert_eq!(panel_verdict(&split, 2), None);
+
+        let majority = vec![vote(Verdict::Benign), vote(Verdict::Malicious), vote(Verdict::Benign)];
+        assert_eq!(panel_verdict(&majority, 2), Some(Verdict::Benign));
+        assert_eq!(panel_verdict(&majority, 4), None);
+        assert_eq!(panel_verdict(&[], 0), None);
+    }
+
+    #[test]
+    fn test_outcome_of_panel_verdict() {
+        assert_eq!(DisputeOutcome::of(&Verdict::Malicious, Some(&Verdict::Benign)), DisputeOutcome::Upheld);
+        assert_eq!(DisputeOutcome::of(&Verdict::Malicious, Some(&Verdict::Malicious)), DisputeOutcome::Rejected);
+        assert_eq!(DisputeOutcome::of(&Verdict::Malicious, None), DisputeOutcome::Inconclusive);
+        assert_eq!(DisputeOutcome::Inconclusive.status(), DisputeStatus::Resolved);
+    }
+
+    #[test]
+    fn test_split_stake_keeps_every_wei() {
+        let recipients = vec![Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4()];
+        let shares = split_stake(Decimal::new(100, 0), &recipients);
+
+        assert_eq!(shares[0].amount, Decimal::new(34, 0));
+        assert_eq!(shares[1].amount, Decimal::new(33, 0));
+        assert_eq!(shares.iter().map(|s| s.amount).sum::<Decimal>(), Decimal::new(100, 0));
+        assert!(split_stake(Decimal::new(100, 0), &[]).is_empty());
+    }
+
+    #[test]
+    fn test_reputation_adjustments_follow_panel_verdict() {
+        let engine = |verdict| SubmissionVote {
+            submission_id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            engine_id: "engine".to_string(),
+            verdict,
+            confidence: Decimal::new(90, 2),
+            reputation_score: 5000,
+            specialization_score: None,
+            stake_amount: 100,
+            prediction: None,
+            probation: false,
+            submitted_at: Utc::now(),
+        };
+        let mut unnamed = engine(Verdict::Benign);
+        unnamed.user_id = Uuid::nil();
+        let votes = vec![engine(Verdict::Malicious), engine(Verdict::Benign), unnamed];
+
+        let adjustments = reputation_adjustments(&votes, &Verdict::Benign);
+        assert_eq!(adjustments.len(), 2);
+        assert!(!adjustments[0].was_correct);
+        assert!(adjustments[1].was_correct);
+    }
+}
@@ -0,0 +1,904 @@
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use shared::messaging::{DisputeCreatedEvent, DisputeResolvedEvent, EventPublisher, NexusEvent};
+use shared::request_signing::RequestSigner;
+use sqlx::{PgPool, Row};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::{
+    panel_verdict, reputation_adjustments, split_stake, ArbitrationVote, CastVoteRequest,
+    CreateDisputeRequest, Dispute, DisputeError, DisputeOutcome, DisputeSettlement, DisputeStatus,
+    ResolveDisputeRequest, StakeAction,
+};
+use crate::assignment::{AssignmentError, AssignmentService, CreatePanelRequest, ReviewKind};
+use crate::config::DisputeConfig;
+use crate::models::{FinalizeReason, SubmissionVote, Verdict};
+
+/// Most users whose voting power is looked up in one request to the
+/// reputation-service
+const REPUTATION_BATCH_SIZE: usize = 200;
+
+/// Most due disputes handled per pass
+const PROCESS_BATCH: i64 = 200;
+
+const DISPUTE_COLUMNS: &str = "id, bounty_id, submission_id, initiator_id, disputed_verdict, claimed_verdict, \
+                               reason, evidence::text AS evidence, wallet_address, stake_amount::TEXT AS stake_amount, \
+                               stake_id, status, panel_id, voting_deadline, outcome, final_verdict, resolution, \
+                               settlement::text AS settlement, resolved_by, resolved_at, created_at";
+
+type Result<T> = std::result::Result<T, DisputeError>;
+
+#[derive(Serialize)]
+struct LockStakeRequest<'a> {
+    user_id: Uuid,
+    bounty_id: Uuid,
+    /// The dispute id, marking the stake as the dispute's own
+    submission_id: Uuid,
+    address: &'a str,
+    amount: Decimal,
+}
+
+#[derive(Serialize)]
+struct UnlockStakeRequest {
+    stake_id: Uuid,
+}
+
+#[derive(Serialize)]
+struct SlashStakeRequest<'a> {
+    stake_id: Uuid,
+    /// Slashing policy offense the payment-service weighs the slash by
+    offense: &'a str,
+    slash_amount: Decimal,
+    reason: &'a str,
+}
+
+#[derive(Deserialize)]
+struct StakeResponse {
+    stake: LockedStake,
+}
+
+#[derive(Deserialize)]
+struct LockedStake {
+    id: Uuid,
+    submission_id: Option<Uuid>,
+}
+
+#[derive(Serialize)]
+struct VotingPowerRequest<'a> {
+    user_ids: &'a [Uuid],
+}
+
+#[derive(Deserialize)]
+struct VotingPowerResponse {
+    voting_power: Vec<VotingPower>,
+}
+
+#[derive(Deserialize)]
+struct VotingPower {
+    user_id: Uuid,
+    reputation: i32,
+    eligible: bool,
+}
+
+#[derive(Serialize)]
+struct ReputationUpdateRequest {
+    user_id: Uuid,
+    submission_id: Uuid,
+    bounty_id: Uuid,
+    was_correct: bool,
+    confidence_score: Decimal,
+    in_consensus: bool,
+    was_early: bool,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct EngineVoteRow {
+    id: Uuid,
+    engine_id: String,
+    verdict: String,
+    confidence: f64,
+    reputation_score: i32,
+    stake_amount: i64,
+    submitted_at: DateTime<Utc>,
+}
+
+pub struct DisputeService {
+    config: DisputeConfig,
+    db_pool: PgPool,
+    assignments: Arc<AssignmentService>,
+    events: EventPublisher,
+    http: reqwest::Client,
+    signer: Option<RequestSigner>,
+}
+
+impl DisputeService {
+    pub fn new(
+        config: DisputeConfig,
+        redis_url: &str,
+        db_pool: PgPool,
+        assignments: Arc<AssignmentService>,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            config,
+            db_pool,
+            assignments,
+            events: EventPublisher::from_url(redis_url)?,
+            http: reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(30))
+                .build()?,
+            signer: RequestSigner::from_env()?,
+        })
+    }
+
+    /// Raise a dispute against a bounty's final verdict, locking the stake
+    /// behind it and seating an arbitration panel. A panel that cannot be
+    /// seated yet is retried by the dispute resolver.
+    pub async fn create(&self, initiator_id: Uuid, request: CreateDisputeRequest) -> Result<Dispute> {
+        let reason = request.reason.trim();
+        if reason.is_empty() {
+            return Err(DisputeError::Validation("reason is required".to_string()));
+        }
+        if request.claimed_verdict == request.disputed_verdict {
+            return Err(DisputeError::Validation(
+                "claimed_verdict must differ from the disputed verdict".to_string(),
+            ));
+        }
+        if request.wallet_address.trim().is_empty() {
+            return Err(DisputeError::Validation("wallet_address is required".to_string()));
+        }
+        if request.stake_amount.trunc() != request.stake_amount || request.stake_amount < self.config.min_stake {
+            return Err(DisputeError::Validation(format!(
+                "stake_amount must be a whole number of wei, at least {}",
+                self.config.min_stake
+            )));
+        }
+
+        let result = sqlx::query(
+            "SELECT final_verdict, finalized_at FROM consensus_results WHERE bounty_id = $1 AND finalized_at IS NOT NULL",
+        )
+        .bind(request.bounty_id)
+        .fetch_optional(&self.db_pool)
+        .await?
+        .ok_or_else(|| {
+            DisputeError::NotFound(format!("Bounty {} has no final verdict to dispute", request.bounty_id))
+        })?;
+        let final_verdict: String = result.get("final_verdict");
+        if final_verdict != request.disputed_verdict.to_string() {
+            return Err(DisputeError::Conflict(format!(
+                "Bounty {} was finalized as {}, not {}",
+                request.bounty_id,
+                final_verdict,
+                request.disputed_verdict.to_string()
+            )));
+        }
+        let window_closes = result.get::<DateTime<Utc>, _>("finalized_at") + Duration::hours(self.config.window_hours);
+        if Utc::now() > window_closes {
+            return Err(DisputeError::Conflict(format!(
+                "Disputes on bounty {} closed at {}",
+                request.bounty_id, window_closes
+            )));
+        }
+
+        let live: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM disputes
+                WHERE bounty_id = $1 AND initiator_id = $2 AND status IN ('open', 'under_review')
+            )
+            "#,
+        )
+        .bind(request.bounty_id)
+        .bind(initiator_id)
+        .fetch_one(&self.db_pool)
+        .await?;
+        if live {
+            return Err(DisputeError::Conflict(format!(
+                "You already have an open dispute on bounty {}",
+                request.bounty_id
+            )));
+        }
+
+        let dispute_id = Uuid::new_v4();
+        let stake_id = self.lock_stake(dispute_id, initiator_id, &request).await?;
+        let evidence = request.evidence.as_ref().map(|evidence| evidence.to_string());
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO disputes (
+                id, bounty_id, submission_id, initiator_id, disputed_verdict, claimed_verdict,
+                reason, evidence, wallet_address, stake_amount, stake_id, voting_deadline
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8::JSONB, $9, $10::NUMERIC, $11, $12)
+            "#,
+        )
+        .bind(dispute_id)
+        .bind(request.bounty_id)
+        .bind(request.submission_id)
+        .bind(initiator_id)
+        .bind(request.disputed_verdict.to_string())
+        .bind(request.claimed_verdict.to_string())
+        .bind(reason)
+        .bind(evidence)
+        .bind(request.wallet_address.trim())
+        .bind(request.stake_amount.to_string())
+        .bind(stake_id)
+        .bind(Utc::now() + Duration::hours(self.config.voting_hours))
+        .execute(&self.db_pool)
+        .await;
+        if let Err(e) = inserted {
+            if let Err(unlock) = self.release_stake(stake_id).await {
+                warn!("Stake {} of a dispute not recorded is still locked: {}", stake_id, unlock);
+            }
+            return Err(match &e {
+                sqlx::Error::Database(db) if db.is_unique_violation() => DisputeError::Conflict(format!(
+                    "You already have an open dispute on bounty {}",
+                    request.bounty_id
+                )),
+                _ => e.into(),
+            });
+        }
+        info!("Dispute {} raised on bounty {} by {}", dispute_id, request.bounty_id, initiator_id);
+
+        if let Err(e) = self
+            .events
+            .publish(&NexusEvent::DisputeCreated(DisputeCreatedEvent {
+                dispute_id,
+                submission_id: request.submission_id.unwrap_or_default(),
+                bounty_id: request.bounty_id,
+                initiator_id,
+                reason: reason.to_string(),
+                created_at: Utc::now(),
+            }))
+            .await
+        {
+            warn!("Failed to announce dispute {}: {}", dispute_id, e);
+        }
+
+        let dispute = self.require(dispute_id).await?;
+        if let Err(e) = self.assign_panel(&dispute).await {
+            warn!("Arbitration panel for dispute {} not seated yet: {}", dispute_id, e);
+        }
+        self.require(dispute_id).await
+    }
+
+    pub async fn get(&self, dispute_id: Uuid) -> Result<Option<Dispute>> {
+        let row = sqlx::query(&format!("SELECT {} FROM disputes WHERE id = $1", DISPUTE_COLUMNS))
+            .bind(dispute_id)
+            .fetch_optional(&self.db_pool)
+            .await?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let mut dispute = dispute_from_row(&row);
+        dispute.votes = self.votes(&[dispute_id]).await?.remove(&dispute_id).unwrap_or_default();
+        Ok(Some(dispute))
+    }
+
+    /// A bounty's disputes, newest first
+    pub async fn for_bounty(&self, bounty_id: Uuid) -> Result<Vec<Dispute>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM disputes WHERE bounty_id = $1 ORDER BY created_at DESC LIMIT 100",
+            DISPUTE_COLUMNS
+        ))
+        .bind(bounty_id)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        let mut disputes: Vec<Dispute> = rows.iter().map(dispute_from_row).collect();
+        let ids: Vec<Uuid> = disputes.iter().map(|d| d.id).collect();
+        let mut votes = self.votes(&ids).await?;
+        for dispute in &mut disputes {
+            dispute.votes = votes.remove(&dispute.id).unwrap_or_default();
+        }
+        Ok(disputes)
+    }
+
+    /// Record an arbitrator's vote. Only arbitrators who accepted a seat on
+    /// the dispute's panel may vote, once each, before the deadline; voting
+    /// completes their assignment.
+    pub async fn cast_vote(
+        &self,
+        dispute_id: Uuid,
+        arbitrator_id: Uuid,
+        request: CastVoteRequest,
+    ) -> Result<Dispute> {
+        let dispute = self.require(dispute_id).await?;
+        if dispute.status != DisputeStatus::UnderReview {
+            return Err(DisputeError::Conflict(format!(
+                "Dispute {} is {} and not open for votes",
+                dispute_id,
+                dispute.status.as_str()
+            )));
+        }
+        if Utc::now() >= dispute.voting_deadline {
+            return Err(DisputeError::Conflict(format!(
+                "Voting on dispute {} closed at {}",
+                dispute_id, dispute.voting_deadline
+            )));
+        }
+        if dispute.votes.iter().any(|vote| vote.arbitrator_id == arbitrator_id) {
+            return Err(DisputeError::Conflict(format!("You already voted on dispute {}", dispute_id)));
+        }
+
+        let assignment_id: Option<Uuid> = sqlx::query_scalar(
+            "SELECT id FROM review_assignments WHERE panel_id = $1 AND reviewer_id = $2 AND status = 'accepted'",
+        )
+        .bind(dispute.panel_id)
+        .bind(arbitrator_id)
+        .fetch_optional(&self.db_pool)
+        .await?;
+        let Some(assignment_id) = assignment_id else {
+            return Err(DisputeError::Forbidden(
+                "Only arbitrators holding a seat on the dispute's panel may vote".to_string(),
+            ));
+        };
+
+        let rationale = request
+            .rationale
+            .as_deref()
+            .map(str::trim)
+            .filter(|r| !r.is_empty());
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO dispute_votes (dispute_id, arbitrator_id, verdict, rationale)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (dispute_id, arbitrator_id) DO NOTHING
+            "#,
+        )
+        .bind(dispute_id)
+        .bind(arbitrator_id)
+        .bind(request.verdict.to_string())
+        .bind(rationale)
+        .execute(&self.db_pool)
+        .await?
+        .rows_affected();
+        if inserted == 0 {
+            return Err(DisputeError::Conflict(format!("You already voted on dispute {}", dispute_id)));
+        }
+        self.assignments.complete(assignment_id, arbitrator_id).await?;
+
+        info!("Arbitrator {} voted {} on dispute {}", arbitrator_id, request.verdict.to_string(), dispute_id);
+        self.require(dispute_id).await
+    }
+
+    /// Resolve a dispute with an admin's ruling instead of the panel's vote
+    pub async fn resolve(
+        &self,
+        dispute_id: Uuid,
+        admin_id: Uuid,
+        request: ResolveDisputeRequest,
+    ) -> Result<Dispute> {
+        let resolution = request.resolution.trim();
+        if resolution.is_empty() {
+            return Err(DisputeError::Validation("resolution is required".to_string()));
+        }
+        if request
+            .compensation
+            .is_some_and(|c| c < Decimal::ZERO || c.trunc() != c)
+        {
+            return Err(DisputeError::Validation(
+                "compensation must be a whole, non-negative number of wei".to_string(),
+            ));
+        }
+
+        let dispute = self.require(dispute_id).await?;
+        self.settle(
+            &dispute,
+            Some(request.final_verdict),
+            resolution.to_string(),
+            Some(admin_id),
+            request.compensation,
+        )
+        .await
+    }
+
+    /// Seat panels for disputes still waiting for one and resolve those whose
+    /// panel has voted in full or whose deadline has passed. Disputes that
+    /// never got a panel close without a verdict at their deadline. Returns
+    /// how many disputes were resolved.
+    pub async fn process(&self) -> Result<usize> {
+        let due: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT d.id FROM disputes d
+            LEFT JOIN review_panels p ON p.id = d.panel_id
+            WHERE d.status IN ('open', 'under_review')
+              AND (d.status = 'open'
+                   OR d.voting_deadline <= NOW()
+                   OR (SELECT COUNT(*) FROM dispute_votes v WHERE v.dispute_id = d.id) >= p.panel_size)
+            ORDER BY d.created_at
+            LIMIT $1
+            "#,
+        )
+        .bind(PROCESS_BATCH)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        let mut resolved = 0;
+        for dispute_id in due {
+            let Some(dispute) = self.get(dispute_id).await? else {
+                continue;
+            };
+            if dispute.status == DisputeStatus::Open && dispute.voting_deadline > Utc::now() {
+                if let Err(e) = self.assign_panel(&dispute).await {
+                    warn!("Arbitration panel for dispute {} not seated yet: {}", dispute_id, e);
+                }
+                continue;
+            }
+
+            let verdict = panel_verdict(&dispute.votes, self.config.min_votes);
+            let resolution = match (&verdict, dispute.panel_id) {
+                (Some(verdict), _) => format!(
+                    "Panel majority for {} out of {} votes",
+                    verdict.to_string(),
+                    dispute.votes.len()
+                ),
+                (None, None) => "No arbitration panel could be seated before the deadline".to_string(),
+                (None, Some(_)) => format!("No majority among {} panel votes", dispute.votes.len()),
+            };
+            match self.settle(&dispute, verdict, resolution, None, None).await {
+                Ok(_) => resolved += 1,
+                // Resolved by an admin in the meantime
+                Err(DisputeError::Conflict(_)) => {}
+                Err(e) => warn!("Failed to resolve dispute {}: {}", dispute_id, e),
+            }
+        }
+        Ok(resolved)
+    }
+
+    async fn require(&self, dispute_id: Uuid) -> Result<Dispute> {
+        self.get(dispute_id)
+            .await?
+            .ok_or_else(|| DisputeError::NotFound(format!("Dispute {} not found", dispute_id)))
+    }
+
+    /// Votes cast on each of `dispute_ids`, in order
+    async fn votes(&self, dispute_ids: &[Uuid]) -> Result<HashMap<Uuid, Vec<ArbitrationVote>>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT dispute_id, arbitrator_id, verdict, rationale, voted_at
+            FROM dispute_votes
+            WHERE dispute_id = ANY($1)
+            ORDER BY voted_at
+            "#,
+        )
+        .bind(dispute_ids)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        let mut votes: HashMap<Uuid, Vec<ArbitrationVote>> = HashMap::new();
+        for row in rows {
+            let verdict: String = row.get("verdict");
+            votes.entry(row.get("dispute_id")).or_default().push(ArbitrationVote {
+                arbitrator_id: row.get("arbitrator_id"),
+                verdict: Verdict::parse(&verdict).unwrap_or(Verdict::Unknown),
+                rationale: row.get("rationale"),
+                voted_at: row.get("voted_at"),
+            });
+        }
+        Ok(votes)
+    }
+
+    /// Seat an arbitration panel for an open dispute from the reviewers the
+    /// reputation-service trusts, leaving out the disputer and the engines
+    /// that voted on the bounty. The voting deadline runs from the seating.
+    /// Returns false if no reviewer qualifies yet.
+    async fn assign_panel(&self, dispute: &Dispute) -> Result<bool> {
+        let engines: Vec<Uuid> = sqlx::query_scalar::<_, String>(
+            "SELECT engine_id FROM consensus_submissions WHERE bounty_id = $1",
+        )
+        .bind(dispute.bounty_id)
+        .fetch_all(&self.db_pool)
+        .await?
+        .iter()
+        .filter_map(|engine_id| Uuid::parse_str(engine_id).ok())
+        .collect();
+        let candidates: Vec<Uuid> = sqlx::query_scalar::<_, Uuid>(
+            "SELECT user_id FROM reviewer_profiles WHERE is_active = true",
+        )
+        .fetch_all(&self.db_pool)
+        .await?
+        .into_iter()
+        .filter(|user_id| *user_id != dispute.initiator_id && !engines.contains(user_id))
+        .collect();
+
+        let arbitrators = self.trusted(&candidates).await?;
+        if arbitrators.is_empty() {
+            warn!("No reviewer qualifies to arbitrate dispute {}", dispute.id);
+            return Ok(false);
+        }
+
+        let panel_id = match self
+            .assignments
+            .create_panel(CreatePanelRequest {
+                kind: ReviewKind::DisputePanel,
+                subject_id: dispute.id,
+                bounty_id: dispute.bounty_id,
+                submitter_id: dispute.initiator_id,
+                required_specializations: Vec::new(),
+                excluded_reviewers: engines,
+                eligible_reviewers: Some(arbitrators),
+                panel_size: None,
+            })
+            .await
+        {
+            Ok(panel) => panel.id,
+            // Seated by an earlier attempt that failed before recording it
+            Err(AssignmentError::Conflict(_)) => {
+                sqlx::query_scalar("SELECT id FROM review_panels WHERE kind = $1 AND subject_id = $2")
+                    .bind(ReviewKind::DisputePanel.as_str())
+                    .bind(dispute.id)
+                    .fetch_one(&self.db_pool)
+                    .await?
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        sqlx::query(
+            r#"
+            UPDATE disputes SET panel_id = $2, status = 'under_review', voting_deadline = $3
+            WHERE id = $1 AND status = 'open'
+            "#,
+        )
+        .bind(dispute.id)
+        .bind(panel_id)
+        .bind(Utc::now() + Duration::hours(self.config.voting_hours))
+        .execute(&self.db_pool)
+        .await?;
+        info!("Arbitration panel {} seated for dispute {}", panel_id, dispute.id);
+        Ok(true)
+    }
+
+    /// Those of `user_ids` with voting power and enough reputation to arbitrate
+    async fn trusted(&self, user_ids: &[Uuid]) -> Result<Vec<Uuid>> {
+        const PATH: &str = "/api/v1/governance/voting-power/bulk";
+
+        let mut trusted = Vec::new();
+        for batch in user_ids.chunks(REPUTATION_BATCH_SIZE) {
+            let response = self
+                .post(&self.config.reputation_service_url, PATH, &VotingPowerRequest { user_ids: batch }, None)
+                .await?;
+            let powers = response
+                .json::<VotingPowerResponse>()
+                .await
+                .map_err(|e| DisputeError::Upstream(format!("Unreadable voting power: {}", e)))?;
+            trusted.extend(
+                powers
+                    .voting_power
+                    .into_iter()
+                    .filter(|p| p.eligible && p.reputation >= self.config.min_arbitrator_reputation)
+                    .map(|p| p.user_id),
+            );
+        }
+        Ok(trusted)
+    }
+
+    /// Record the ruling on a dispute, replacing the bounty's verdict if the
+    /// dispute is upheld, then settle its stake and the engines' reputation.
+    /// The ruling is recorded first so a dispute is only ever settled once;
+    /// the settlement notes any change the other services did not confirm.
+    async fn settle(
+        &self,
+        dispute: &Dispute,
+        verdict: Option<Verdict>,
+        resolution: String,
+        resolved_by: Option<Uuid>,
+        compensation: Option<Decimal>,
+    ) -> Result<Dispute> {
+        let outcome = DisputeOutcome::of(&dispute.disputed_verdict, verdict.as_ref());
+
+        let mut tx = self.db_pool.begin().await?;
+        let claimed = sqlx::query(
+            r#"
+            UPDATE disputes
+            SET status = $2, outcome = $3, final_verdict = $4, resolution = $5,
+                resolved_by = $6, resolved_at = NOW()
+            WHERE id = $1 AND status IN ('open', 'under_review')
+            "#,
+        )
+        .bind(dispute.id)
+        .bind(outcome.status().as_str())
+        .bind(outcome.as_str())
+        .bind(verdict.as_ref().map(|v| v.to_string()))
+        .bind(&resolution)
+        .bind(resolved_by)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        if claimed == 0 {
+            return Err(DisputeError::Conflict(format!("Dispute {} is already resolved", dispute.id)));
+        }
+
+        if let (DisputeOutcome::Upheld, Some(verdict)) = (outcome, &verdict) {
+            sqlx::query(
+                r#"
+                UPDATE consensus_results
+                SET final_verdict = $2, consensus_reached = true, finalized_reason = $3, updated_at = NOW()
+                WHERE bounty_id = $1 AND finalized_at IS NOT NULL
+                "#,
+            )
+            .bind(dispute.bounty_id)
+            .bind(verdict.to_string())
+            .bind(FinalizeReason::DisputeUpheld.as_str())
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        let settlement = self.settlement(dispute, outcome, verdict.as_ref(), compensation).await?;
+        sqlx::query("UPDATE disputes SET settlement = $2::JSONB WHERE id = $1")
+            .bind(dispute.id)
+            .bind(serde_json::to_string(&settlement).unwrap_or_default())
+            .execute(&self.db_pool)
+            .await?;
+
+        let resolved_at = Utc::now();
+        if let Err(e) = self
+            .events
+            .publish(&NexusEvent::DisputeResolved(DisputeResolvedEvent {
+                dispute_id: dispute.id,
+                submission_id: dispute.submission_id.unwrap_or_default(),
+                bounty_id: dispute.bounty_id,
+                resolution: format!("{}: {}", outcome.as_str(), resolution),
+                // Nil when the panel's vote resolved the dispute
+                resolved_by: resolved_by.unwrap_or_default(),
+                resolved_at,
+            }))
+            .await
+        {
+            warn!("Failed to announce resolution of dispute {}: {}", dispute.id, e);
+        }
+
+        info!("Dispute {} on bounty {} {}", dispute.id, dispute.bounty_id, outcome.as_str());
+        self.require(dispute.id).await
+    }
+
+    /// Release or slash the disputer's stake and, if the verdict changed,
+    /// re-judge the engines' votes
+    async fn settlement(
+        &self,
+        dispute: &Dispute,
+        outcome: DisputeOutcome,
+        verdict: Option<&Verdict>,
+        compensation: Option<Decimal>,
+    ) -> Result<DisputeSettlement> {
+        let (stake_action, stake_shares) = if outcome == DisputeOutcome::Rejected {
+            let confirmed: Vec<Uuid> = dispute
+                .votes
+                .iter()
+                .filter(|vote| Some(&vote.verdict) == verdict)
+                .map(|vote| vote.arbitrator_id)
+                .collect();
+            (StakeAction::Slashed, split_stake(dispute.stake_amount, &confirmed))
+        } else {
+            (StakeAction::Released, Vec::new())
+        };
+
+        let stake_applied = match dispute.stake_id {
+            Some(stake_id) => {
+                let applied = match stake_action {
+                    StakeAction::Released => self.release_stake(stake_id).await,
+                    StakeAction::Slashed => {
+                        let reason = format!("Dispute {} rejected", dispute.id);
+                        self.slash_stake(stake_id, dispute.stake_amount, &reason).await
+                    }
+                };
+                if let Err(e) = &applied {
+                    warn!("Stake {} of dispute {} not settled: {}", stake_id, dispute.id, e);
+                }
+                applied.is_ok()
+            }
+            None => false,
+        };
+
+        let mut reputation = Vec::new();
+        if let (DisputeOutcome::Upheld, Some(verdict)) = (outcome, verdict) {
+            reputation = reputation_adjustments(&self.engine_votes(dispute.bounty_id).await?, verdict);
+            for adjustment in &mut reputation {
+                let path = format!("/api/v1/reputation/user/{}/update", adjustment.user_id);
+                let update = ReputationUpdateRequest {
+                    user_id: adjustment.user_id,
+                    submission_id: adjustment.submission_id,
+                    bounty_id: dispute.bounty_id,
+                    was_correct: adjustment.was_correct,
+                    confidence_score: adjustment.confidence,
+                    in_consensus: adjustment.was_correct,
+                    was_early: false,
+                };
+                match self.post(&self.config.reputation_service_url, &path, &update, None).await {
+                    Ok(_) => adjustment.applied = true,
+                    Err(e) => warn!(
+                        "Reputation of {} not adjusted for dispute {}: {}",
+                        adjustment.user_id, dispute.id, e
+                    ),
+                }
+            }
+        }
+
+        Ok(DisputeSettlement {
+            stake_action,
+            stake_amount: dispute.stake_amount,
+            stake_applied,
+            compensation: compensation.filter(|_| outcome == DisputeOutcome::Upheld),
+            stake_shares,
+            reputation,
+        })
+    }
+
+    async fn engine_votes(&self, bounty_id: Uuid) -> Result<Vec<SubmissionVote>> {
+        let rows: Vec<EngineVoteRow> = sqlx::query_as(
+            r#"
+            SELECT id, engine_id, verdict, confidence::float8 AS confidence,
+                   COALESCE(reputation_score, 0) AS reputation_score, stake_amount, submitted_at
+            FROM consensus_submissions
+            WHERE bounty_id = $1
+            "#,
+        )
+        .bind(bounty_id)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| SubmissionVote {
+                submission_id: row.id,
+                user_id: Uuid::parse_str(&row.engine_id).unwrap_or_default(),
+                verdict: Verdict::parse(&row.verdict).unwrap_or(Verdict::Unknown),
+                confidence: Decimal::try_from(row.confidence).unwrap_or_default(),
+                reputation_score: row.reputation_score,
+                specialization_score: None,
+                stake_amount: row.stake_amount,
+                prediction: None,
+                probation: false,
+                submitted_at: row.submitted_at,
+                engine_id: row.engine_id,
+            })
+            .collect())
+    }
+
+    /// Lock the disputer's stake. A stake the disputer already holds on the
+    /// bounty, e.g. as an engine, cannot back the dispute.
+    async fn lock_stake(&self, dispute_id: Uuid, initiator_id: Uuid, request: &CreateDisputeRequest) -> Result<Uuid> {
+        let lock = LockStakeRequest {
+            user_id: initiator_id,
+            bounty_id: request.bounty_id,
+            submission_id: dispute_id,
+            address: request.wallet_address.trim(),
+            amount: request.stake_amount,
+        };
+        let stake = self
+            .post(
+                &self.config.payment_service_url,
+                "/api/v1/payments/stake/lock",
+                &lock,
+                Some(&format!("dispute-stake-lock:{}", dispute_id)),
+            )
+            .await?
+            .json::<StakeResponse>()
+            .await
+            .map_err(|e| DisputeError::Upstream(format!("Unreadable stake: {}", e)))?
+            .stake;
+        if stake.submission_id != Some(dispute_id) {
+            return Err(DisputeError::Conflict(format!(
+                "You already have a stake locked on bounty {}",
+                request.bounty_id
+            )));
+        }
+        Ok(stake.id)
+    }
+
+    async fn release_stake(&self, stake_id: Uuid) -> Result<()> {
+        self.post(
+            &self.config.payment_service_url,
+            "/api/v1/payments/stake/unlock",
+            &UnlockStakeRequest { stake_id },
+            Some(&format!("stake-unlock:{}", stake_id)),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn slash_stake(&self, stake_id: Uuid, amount: Decimal, reason: &str) -> Result<()> {
+        self.post(
+            &self.config.payment_service_url,
+            "/api/v1/payments/stake/slash",
+            &SlashStakeRequest { stake_id, offense: "rejected_dispute", slash_amount: amount, reason },
+            Some(&format!("stake-slash:{}", stake_id)),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Signed POST to another service. A rejected request is a validation
+    /// error carrying the service's message. Payment operations name
+    /// themselves with an `idempotency_key` so a retry is applied once.
+    async fn post<T: Serialize>(
+        &self,
+        base_url: &str,
+        path: &str,
+        body: &T,
+        idempotency_key: Option<&str>,
+    ) -> Result<reqwest::Response> {
+        let body = serde_json::to_vec(body)
+            .map_err(|e| DisputeError::Validation(format!("Unserializable request: {}", e)))?;
+        let mut request = self
+            .http
+            .post(format!("{}{}", base_url.trim_end_matches('/'), path))
+            .header("content-type", "application/json")
+            .body(body.clone());
+        for (name, value) in shared::observability::propagation_headers() {
+            request = request.header(name, value);
+        }
+        if let Some(key) = idempotency_key {
+            request = request.header("idempotency-key", key);
+        }
+        if let Some(signer) = &self.signer {
+            for (name, value) in signer.sign_now("POST", path, Some(&body), None, None).pairs() {
+                request = request.header(name, value);
+            }
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| DisputeError::Upstream(format!("{} unreachable: {}", base_url, e)))?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let message = response
+            .json::<serde_json::Value>()
+            .await
+            .ok()
+            .and_then(|body| body.get("error").and_then(|e| e.as_str()).map(str::to_string))
+            .unwrap_or_else(|| format!("{} answered {}", base_url, status));
+        Err(if status.is_client_error() {
+            DisputeError::Validation(message)
+        } else {
+            DisputeError::Upstream(message)
+        })
+    }
+}
+
+fn dispute_from_row(row: &sqlx::postgres::PgRow) -> Dispute {
+    let verdict = |column: &str| Verdict::parse(&row.get::<String, _>(column)).unwrap_or(Verdict::Unknown);
+    let status: String = row.get("status");
+    let stake_amount: String = row.get("stake_amount");
+    Dispute {
+        id: row.get("id"),
+        bounty_id: row.get("bounty_id"),
+        submission_id: row.get("submission_id"),
+        initiator_id: row.get("initiator_id"),
+        disputed_verdict: verdict("disputed_verdict"),
+        claimed_verdict: verdict("claimed_verdict"),
+        reason: row.get("reason"),
+        evidence: row
+            .get::<Option<String>, _>("evidence")
+            .and_then(|evidence| serde_json::from_str(&evidence).ok()),
+        wallet_address: row.get("wallet_address"),
+        stake_amount: stake_amount.parse().unwrap_or_default(),
+        stake_id: row.get("stake_id"),
+        status: DisputeStatus::parse(&status).unwrap_or(DisputeStatus::Open),
+        panel_id: row.get("panel_id"),
+        voting_deadline: row.get("voting_deadline"),
+        outcome: row
+            .get::<Option<String>, _>("outcome")
+            .as_deref()
+            .and_then(DisputeOutcome::parse),
+        final_verdict: row
+            .get::<Option<String>, _>("final_verdict")
+            .as_deref()
+            .and_then(Verdict::parse),
+        resolution: row.get("resolution"),
+        settlement: row
+            .get::<Option<String>, _>("settlement")
+            .and_then(|settlement| serde_json::from_str(&settlement).ok()),
+        resolved_by: row.get("resolved_by"),
+        resolved_at: row.get("resolved_at"),
+        created_at: row.get("created_at"),
+        votes: Vec::new(),
+    }
+}
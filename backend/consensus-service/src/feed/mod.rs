@@ -0,0 +1,211 @@
+//! Public intelligence feed built from finalized consensus verdicts.
+//!
+//! Entries carry only the artifact hash, verdict, confidence and finalization
+//! time. Participant identities, stakes and individual votes never leave the
+//! service.
+
+pub mod service;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+pub use service::FeedService;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Access tier attached to a feed API key
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedTier {
+    Community,
+    Standard,
+    Premium,
+}
+
+impl FeedTier {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "community" => Some(FeedTier::Community),
+            "standard" => Some(FeedTier::Standard),
+            "premium" => Some(FeedTier::Premium),
+            _ => None,
+        }
+    }
+
+    /// Largest page a single request may return
+    pub fn max_page_size(&self) -> u32 {
+        match self {
+            FeedTier::Community => 50,
+            FeedTier::Standard => 250,
+            FeedTier::Premium => 1000,
+        }
+    }
+
+    /// How long a verdict must have been final before this tier can see it
+    pub fn delay(&self) -> Duration {
+        match self {
+            FeedTier::Community => Duration::hours(24),
+            FeedTier::Standard => Duration::hours(1),
+            FeedTier::Premium => Duration::zero(),
+        }
+    }
+
+    pub fn can_download_bulk(&self) -> bool {
+        matches!(self, FeedTier::Premium)
+    }
+}
+
+/// Authenticated feed consumer
+#[derive(Debug, Clone)]
+pub struct FeedClient {
+    pub key_id: Uuid,
+    pub name: String,
+    pub tier: FeedTier,
+}
+
+/// A single finalized verdict as published in the feed
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FeedEntry {
+    #[serde(skip)]
+    pub id: Uuid,
+    pub artifact_hash: String,
+    pub verdict: String,
+    pub confidence: f64,
+    pub finalized_at: DateTime<Utc>,
+    /// When the verdict entered the feed: `finalized_at`, or the end of a
+    /// verdict embargo that held it back
+    #[serde(skip)]
+    pub published_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeedQuery {
+    pub cursor: Option<String>,
+    pub limit: Option<u32>,
+    pub verdict: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FeedPage {
+    pub entries: Vec<FeedEntry>,
+    pub next_cursor: Option<String>,
+    pub tier: FeedTier,
+}
+
+/// Metadata for a signed daily bulk export
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkExport {
+    pub export_date: chrono::NaiveDate,
+    pub object_key: String,
+    pub signature: String,
+    pub entry_count: i32,
+    pub download_url: String,
+    pub expires_in_secs: u64,
+}
+
+/// Opaque pagination cursor pointing just past the last returned entry.
+///
+/// Entries are ordered by `(published_at, id)`, so the cursor encodes both to
+/// stay stable when several verdicts are published in the same instant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeedCursor {
+    pub published_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl FeedCursor {
+    pub fn from_entry(entry: &FeedEntry) -> Self {
+        Self {
+            published_at: entry.published_at,
+            id: entry.id,
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        format!("{}_{}", self.published_at.timestamp_micros(), self.id.simple())
+    }
+
+    pub fn decode(value: &str) -> Option<Self> {
+        let (micros, id) = value.split_once('_')?;
+        let published_at = Utc.timestamp_micros(micros.parse().ok()?).single()?;
+        let id = Uuid::parse_str(id).ok()?;
+        Some(Self { published_at, id })
+    }
+}
+
+/// SHA-256 of a raw API key, as stored in `feed_api_keys.key_hash`
+pub fn hash_api_key(api_key: &str) -> String {
+    hex::encode(Sha256::digest(api_key.as_bytes()))
+}
+
+/// Serialize entries as newline-delimited JSON for bulk export
+pub fn to_jsonl(entries: &[FeedEntry]) -> serde_json::Result<Vec<u8>> {
+    let mut body = Vec::new();
+    for entry in entries {
+        serde_json::to_writer(&mut body, entry)?;
+        body.push(b'\n');
+    }
+    Ok(body)
+}
+
+/// HMAC-SHA256 signature consumers use to verify a bulk export
+pub fn sign_export(secret: &str, body: &[u8]) -> String {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
+        .expect("HMAC can take key of any size");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(hash: &str) -> FeedEntry {
+        FeedEntry {
+            id: Uuid::new_v4(),
+            artifact_hash: hash.to_string(),
+            verdict: "malicious".to_string(),
+            confidence: 0.92,
+            finalized_at: Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap(),
+            published_at: Utc.with_ymd_and_hms(2024, 3, 2, 9, 0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_cursor_roundtrip() {
+        let cursor = FeedCursor::from_entry(&entry("abc"));
+        assert_eq!(FeedCursor::decode(&cursor.encode()), Some(cursor));
+        assert_eq!(FeedCursor::decode("not-a-cursor"), None);
+    }
+
+    #[test]
+    fn test_entries_omit_internal_id() {
+        let json = serde_json::to_value(entry("abc")).unwrap();
+        assert!(json.get("id").is_none());
+        assert!(json.get("published_at").is_none());
+        assert_eq!(json["artifact_hash"], "abc");
+    }
+
+    #[test]
+    fn test_export_signature() {
+        let body = to_jsonl(&[entry("a"), entry("b")]).unwrap();
+        assert_eq!(body.iter().filter(|b| **b == b'\n').count(), 2);
+
+        let signature = sign_export("secret", &body);
+        assert_eq!(signature.len(), 64);
+        assert_eq!(signature, sign_export("secret", &body));
+        assert_ne!(signature, sign_export("other", &body));
+    }
+
+    #[test]
+    fn test_tier_limits() {
+        assert!(FeedTier::Community.max_page_size() < FeedTier::Premium.max_page_size());
+        assert!(FeedTier::Community.delay() > FeedTier::Premium.delay());
+        assert!(!FeedTier::Standard.can_download_bulk());
+        assert!(FeedTier::Premium.can_download_bulk());
+        assert_eq!(FeedTier::parse("standard"), Some(FeedTier::Standard));
+    }
+}
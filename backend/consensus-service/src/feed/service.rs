@@ -0,0 +1,278 @@
+use anyhow::{Context, Result};
+use aws_config::{BehaviorVersion, Region};
+use aws_sdk_s3::{
+    config::{Credentials, SharedCredentialsProvider},
+    primitives::ByteStream,
+    Client,
+};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use sqlx::{PgPool, Row};
+use tracing::info;
+use uuid::Uuid;
+
+use super::{
+    hash_api_key, sign_export, to_jsonl, BulkExport, FeedClient, FeedCursor, FeedEntry,
+    FeedPage, FeedTier,
+};
+use crate::config::FeedConfig;
+
+const DEFAULT_PAGE_SIZE: u32 = 50;
+
+/// Finalized verdicts with the time they became public: their finalization,
+/// or the end of a verdict embargo (kept by the bounty manager) that held
+/// them back. Verdicts still under embargo are left out.
+const PUBLISHED_RESULTS: &str = r#"
+    SELECT c.id, c.artifact_hash, c.final_verdict, c.confidence::float8 AS confidence, c.finalized_at,
+           GREATEST(c.finalized_at, COALESCE(e.released_at, e.embargo_until, c.finalized_at)) AS published_at
+    FROM consensus_results c
+    LEFT JOIN bounty_verdict_embargoes e ON e.bounty_id = c.bounty_id
+    WHERE c.finalized_at IS NOT NULL
+      AND c.artifact_hash IS NOT NULL
+      AND NOT (e.bounty_id IS NOT NULL
+               AND e.released_at IS NULL
+               AND (e.embargo_until IS NULL OR e.embargo_until > NOW()))
+"#;
+
+pub struct FeedService {
+    config: FeedConfig,
+    db_pool: PgPool,
+    s3: Client,
+}
+
+impl FeedService {
+    pub fn new(config: FeedConfig, db_pool: PgPool) -> Self {
+        let credentials = Credentials::new(
+            config.s3_access_key.clone(),
+            config.s3_secret_key.clone(),
+            None,
+            None,
+            "nexus-security",
+        );
+
+        let s3_config = aws_sdk_s3::Config::builder()
+            .region(Region::new(config.s3_region.clone()))
+            .endpoint_url(config.s3_endpoint.clone())
+            .credentials_provider(SharedCredentialsProvider::new(credentials))
+            .force_path_style(true)
+            .behavior_version(BehaviorVersion::latest())
+            .build();
+
+        Self {
+            config,
+            db_pool,
+            s3: Client::from_conf(s3_config),
+        }
+    }
+
+    /// Resolve a raw API key to a feed client, or `None` if unknown/inactive
+    pub async fn authenticate(&self, api_key: &str) -> Result<Option<FeedClient>> {
+        let row = sqlx::query(
+            r#"
+            UPDATE feed_api_keys
+            SET last_used_at = NOW()
+            WHERE key_hash = $1
+              AND is_active = true
+              AND (expires_at IS NULL OR expires_at > NOW())
+            RETURNING id, name, tier
+            "#,
+        )
+        .bind(hash_api_key(api_key))
+        .fetch_optional(&self.db_pool)
+        .await?;
+
+        Ok(row.and_then(|row| {
+            let tier: String = row.get("tier");
+            Some(FeedClient {
+                key_id: row.get("id"),
+                name: row.get("name"),
+                tier: FeedTier::parse(&tier)?,
+            })
+        }))
+    }
+
+    /// Fetch one page of finalized verdicts visible to the given tier.
+    ///
+    /// Verdicts of bounties under a verdict embargo are left out until it
+    /// lapses or is released; they are then published at the release time,
+    /// so consumers already past their finalization still receive them.
+    pub async fn list_entries(
+        &self,
+        tier: FeedTier,
+        cursor: Option<FeedCursor>,
+        limit: Option<u32>,
+        verdict: Option<&str>,
+    ) -> Result<FeedPage> {
+        let limit = limit
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, tier.max_page_size());
+        let visible_before = Utc::now() - tier.delay();
+
+        let query = format!(
+            r#"
+            SELECT * FROM ({}) published
+            WHERE published_at <= $1
+              AND ($2::timestamptz IS NULL OR (published_at, id) > ($2, $3))
+              AND ($4::text IS NULL OR final_verdict = $4)
+            ORDER BY published_at, id
+            LIMIT $5
+            "#,
+            PUBLISHED_RESULTS
+        );
+        let rows = sqlx::query(&query)
+            .bind(visible_before)
+            .bind(cursor.map(|c| c.published_at))
+            .bind(cursor.map(|c| c.id).unwrap_or_else(Uuid::nil))
+            .bind(verdict)
+            .bind(i64::from(limit) + 1)
+            .fetch_all(&self.db_pool)
+            .await?;
+
+        let mut entries: Vec<FeedEntry> = rows.iter().map(entry_from_row).collect();
+        let has_more = entries.len() > limit as usize;
+        entries.truncate(limit as usize);
+
+        let next_cursor = if has_more {
+            entries.last().map(|e| FeedCursor::from_entry(e).encode())
+        } else {
+            None
+        };
+
+        Ok(FeedPage {
+            entries,
+            next_cursor,
+            tier,
+        })
+    }
+
+    /// Look up a bulk export and hand out a time-limited download link
+    pub async fn get_bulk_export(&self, date: NaiveDate) -> Result<Option<BulkExport>> {
+        let row = sqlx::query(
+            "SELECT object_key, signature, entry_count FROM feed_exports WHERE export_date = $1",
+        )
+        .bind(date)
+        .fetch_optional(&self.db_pool)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let object_key: String = row.get("object_key");
+        let presigned = self
+            .s3
+            .get_object()
+            .bucket(&self.config.s3_bucket)
+            .key(&object_key)
+            .presigned(
+                aws_sdk_s3::presigning::PresigningConfig::expires_in(
+                    std::time::Duration::from_secs(self.config.presigned_url_ttl_secs),
+                )
+                .context("Failed to create presigning config")?,
+            )
+            .await
+            .context("Failed to generate presigned URL")?;
+
+        Ok(Some(BulkExport {
+            export_date: date,
+            object_key,
+            signature: row.get("signature"),
+            entry_count: row.get("entry_count"),
+            download_url: presigned.uri().to_string(),
+            expires_in_secs: self.config.presigned_url_ttl_secs,
+        }))
+    }
+
+    /// Write the bulk file for `date` if it has not been exported yet.
+    ///
+    /// The file holds the verdicts published that day: those finalized that
+    /// day without an embargo, and those whose embargo ended that day.
+    ///
+    /// Returns `true` when a new export was written.
+    pub async fn export_day(&self, date: NaiveDate) -> Result<bool> {
+        let exists: bool =
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM feed_exports WHERE export_date = $1)")
+                .bind(date)
+                .fetch_one(&self.db_pool)
+                .await?;
+        if exists {
+            return Ok(false);
+        }
+
+        let start: DateTime<Utc> = date.and_hms_opt(0, 0, 0).unwrap().and_utc();
+        let end = start + Duration::days(1);
+
+        let query = format!(
+            r#"
+            SELECT * FROM ({}) published
+            WHERE published_at >= $1
+              AND published_at < $2
+            ORDER BY published_at, id
+            "#,
+            PUBLISHED_RESULTS
+        );
+        let rows = sqlx::query(&query)
+            .bind(start)
+            .bind(end)
+            .fetch_all(&self.db_pool)
+            .await?;
+
+        let entries: Vec<FeedEntry> = rows.iter().map(entry_from_row).collect();
+        let body = to_jsonl(&entries)?;
+        let signature = sign_export(&self.config.signing_secret, &body);
+        let object_key = format!("feed/{}/verdicts.jsonl", date.format("%Y/%m/%d"));
+
+        self.s3
+            .put_object()
+            .bucket(&self.config.s3_bucket)
+            .key(&object_key)
+            .body(ByteStream::from(body))
+            .content_type("application/x-ndjson")
+            .metadata("signature", &signature)
+            .send()
+            .await
+            .context("Failed to upload feed export")?;
+
+        self.s3
+            .put_object()
+            .bucket(&self.config.s3_bucket)
+            .key(format!("{}.sig", object_key))
+            .body(ByteStream::from(signature.clone().into_bytes()))
+            .content_type("text/plain")
+            .send()
+            .await
+            .context("Failed to upload feed export signature")?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO feed_exports (export_date, object_key, signature, entry_count)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (export_date) DO NOTHING
+            "#,
+        )
+        .bind(date)
+        .bind(&object_key)
+        .bind(&signature)
+        .bind(entries.len() as i32)
+        .execute(&self.db_pool)
+        .await?;
+
+        info!(
+            "Exported {} feed entries for {} to {}",
+            entries.len(),
+            date,
+            object_key
+        );
+        Ok(true)
+    }
+}
+
+fn entry_from_row(row: &sqlx::postgres::PgRow) -> FeedEntry {
+    FeedEntry {
+        id: row.get("id"),
+        artifact_hash: row.get("artifact_hash"),
+        verdict: row.get("final_verdict"),
+        confidence: row.get("confidence"),
+        finalized_at: row.get("finalized_at"),
+        published_at: row.get("published_at"),
+    }
+}
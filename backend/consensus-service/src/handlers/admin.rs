@@ -1,6 +1,9 @@
-use axum::{extract::{State, Path}, response::Json, http::StatusCode};
+use axum::{extract::{State, Path}, response::Json, http::{HeaderMap, StatusCode}};
 use serde_json::{json, Value};
 use std::sync::Arc;
+use uuid::Uuid;
+use super::assignment::admin;
+use crate::aggregation::replay::ReplayRequest;
 use crate::AppState;
 
 pub async fn recalculate_consensus(
@@ -16,3 +19,30 @@ pub async fn override_consensus(
 ) -> (StatusCode, Json<Value>) {
     (StatusCode::OK, Json(json!({"message": "Consensus overridden"})))
 }
+
+/// Recalculate a finalized consensus from its snapshot and report what the
+/// current aggregation, or another algorithm, makes of it differently
+pub async fn replay_consensus(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(bounty_id): Path<Uuid>,
+    Json(payload): Json<ReplayRequest>,
+) -> (StatusCode, Json<Value>) {
+    if let Err(response) = admin(&headers) {
+        return response;
+    }
+    match state.consensus_service.replay(bounty_id, &payload).await {
+        Ok(Some(replay)) => (StatusCode::OK, Json(json!(replay))),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Bounty has no finalized consensus snapshot"})),
+        ),
+        Err(e) => {
+            tracing::error!("Failed to replay consensus for bounty {}: {}", bounty_id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to replay consensus"})),
+            )
+        }
+    }
+}
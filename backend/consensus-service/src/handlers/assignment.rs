@@ -0,0 +1,227 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use serde_json::{json, Value};
+use shared::permissions::{self, Scopes};
+use std::sync::Arc;
+use tracing::error;
+use uuid::Uuid;
+
+use crate::assignment::{
+    AssignmentError, AssignmentListQuery, AssignmentStatus, CreatePanelRequest, DeclineRequest,
+    UpsertReviewerRequest,
+};
+use crate::AppState;
+
+/// Caller identity as forwarded by the API gateway
+pub(crate) struct Caller {
+    pub(crate) user_id: Uuid,
+    /// Holds the consensus:override scope
+    pub(crate) is_admin: bool,
+}
+
+pub(crate) fn caller(headers: &HeaderMap) -> Result<Caller, (StatusCode, Json<Value>)> {
+    let user_id = headers
+        .get("x-user-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| Uuid::parse_str(v).ok())
+        .ok_or_else(|| {
+            (
+                StatusCode::UNAUTHORIZED,
+                Json(json!({"error": "Missing or invalid X-User-Id header"})),
+            )
+        })?;
+    let is_admin = Scopes::from_headers(headers).allows(permissions::CONSENSUS_OVERRIDE);
+
+    Ok(Caller { user_id, is_admin })
+}
+
+/// The caller, if they are an admin
+pub(crate) fn admin(headers: &HeaderMap) -> Result<Caller, (StatusCode, Json<Value>)> {
+    let caller = caller(headers)?;
+    if !caller.is_admin {
+        return Err((StatusCode::FORBIDDEN, Json(json!({"error": "Admin role required"}))));
+    }
+    Ok(caller)
+}
+
+/// The caller, if they are an analyst or an admin
+pub(crate) fn analyst(headers: &HeaderMap) -> Result<Caller, (StatusCode, Json<Value>)> {
+    let caller = caller(headers)?;
+    let is_analyst = headers
+        .get("x-user-role")
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|role| role == "analyst");
+    if !caller.is_admin && !is_analyst {
+        return Err((StatusCode::FORBIDDEN, Json(json!({"error": "Analyst role required"}))));
+    }
+    Ok(caller)
+}
+
+fn error_response(e: AssignmentError) -> (StatusCode, Json<Value>) {
+    let status = match &e {
+        AssignmentError::Validation(_) => StatusCode::BAD_REQUEST,
+        AssignmentError::NotFound(_) => StatusCode::NOT_FOUND,
+        AssignmentError::Conflict(_) => StatusCode::CONFLICT,
+        AssignmentError::Database(err) => {
+            error!("Reviewer assignment query failed: {}", err);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Internal server error"})),
+            );
+        }
+    };
+    (status, Json(json!({"error": e.to_string()})))
+}
+
+/// Admins manage any reviewer profile; reviewers may only edit their own
+/// specializations
+pub async fn upsert_reviewer(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(user_id): Path<Uuid>,
+    Json(payload): Json<UpsertReviewerRequest>,
+) -> (StatusCode, Json<Value>) {
+    let caller = match caller(&headers) {
+        Ok(caller) => caller,
+        Err(response) => return response,
+    };
+
+    let result = if caller.is_admin {
+        state.assignment_service.upsert_reviewer(user_id, payload).await
+    } else if caller.user_id == user_id {
+        state
+            .assignment_service
+            .update_specializations(user_id, &payload.specializations)
+            .await
+    } else {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": "Reviewers can only edit their own profile"})),
+        );
+    };
+
+    match result {
+        Ok(profile) => (StatusCode::OK, Json(json!(profile))),
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn list_reviewer_assignments(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(reviewer_id): Path<Uuid>,
+    Query(query): Query<AssignmentListQuery>,
+) -> (StatusCode, Json<Value>) {
+    let caller = match caller(&headers) {
+        Ok(caller) => caller,
+        Err(response) => return response,
+    };
+    if caller.user_id != reviewer_id && !caller.is_admin {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": "Reviewers can only list their own assignments"})),
+        );
+    }
+
+    let status = match query.status.as_deref().map(AssignmentStatus::parse) {
+        Some(None) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({"error": "Unknown assignment status"})),
+            )
+        }
+        Some(status) => status,
+        None => None,
+    };
+
+    match state.assignment_service.list_for_reviewer(reviewer_id, status).await {
+        Ok(assignments) => (StatusCode::OK, Json(json!({"assignments": assignments}))),
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn create_panel(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<CreatePanelRequest>,
+) -> (StatusCode, Json<Value>) {
+    if let Err(response) = admin(&headers) {
+        return response;
+    }
+
+    match state.assignment_service.create_panel(payload).await {
+        Ok(panel) => (StatusCode::CREATED, Json(json!(panel))),
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn get_panel(
+    State(state): State<Arc<AppState>>,
+    Path(panel_id): Path<Uuid>,
+) -> (StatusCode, Json<Value>) {
+    match state.assignment_service.get_panel(panel_id).await {
+        Ok(Some(panel)) => (StatusCode::OK, Json(json!(panel))),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": format!("Panel {} not found", panel_id)})),
+        ),
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn accept_assignment(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(assignment_id): Path<Uuid>,
+) -> (StatusCode, Json<Value>) {
+    let caller = match caller(&headers) {
+        Ok(caller) => caller,
+        Err(response) => return response,
+    };
+
+    match state.assignment_service.accept(assignment_id, caller.user_id).await {
+        Ok(assignment) => (StatusCode::OK, Json(json!(assignment))),
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn decline_assignment(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(assignment_id): Path<Uuid>,
+    payload: Option<Json<DeclineRequest>>,
+) -> (StatusCode, Json<Value>) {
+    let caller = match caller(&headers) {
+        Ok(caller) => caller,
+        Err(response) => return response,
+    };
+    let reason = payload.and_then(|Json(body)| body.reason);
+
+    match state
+        .assignment_service
+        .decline(assignment_id, caller.user_id, reason)
+        .await
+    {
+        Ok(assignment) => (StatusCode::OK, Json(json!(assignment))),
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn complete_assignment(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(assignment_id): Path<Uuid>,
+) -> (StatusCode, Json<Value>) {
+    let caller = match caller(&headers) {
+        Ok(caller) => caller,
+        Err(response) => return response,
+    };
+
+    match state.assignment_service.complete(assignment_id, caller.user_id).await {
+        Ok(assignment) => (StatusCode::OK, Json(json!(assignment))),
+        Err(e) => error_response(e),
+    }
+}
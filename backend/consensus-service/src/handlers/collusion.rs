@@ -0,0 +1,60 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tracing::error;
+use uuid::Uuid;
+
+use super::assignment::admin;
+use crate::collusion::{ClusterListQuery, CollusionError, ReviewClusterRequest};
+use crate::AppState;
+
+fn error_response(e: CollusionError) -> (StatusCode, Json<Value>) {
+    let status = match &e {
+        CollusionError::Validation(_) => StatusCode::BAD_REQUEST,
+        CollusionError::NotFound(_) => StatusCode::NOT_FOUND,
+        CollusionError::Database(err) => {
+            error!("Collusion query failed: {}", err);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Internal server error"})),
+            );
+        }
+    };
+    (status, Json(json!({"error": e.to_string()})))
+}
+
+/// Suspected voting rings with the evidence against them
+pub async fn list_clusters(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<ClusterListQuery>,
+) -> (StatusCode, Json<Value>) {
+    if let Err(response) = admin(&headers) {
+        return response;
+    }
+    match state.collusion_service.list(query.status.as_deref()).await {
+        Ok(clusters) => (StatusCode::OK, Json(json!({"clusters": clusters}))),
+        Err(e) => error_response(e),
+    }
+}
+
+/// Confirm or dismiss a suspected voting ring
+pub async fn review_cluster(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(cluster_id): Path<Uuid>,
+    Json(payload): Json<ReviewClusterRequest>,
+) -> (StatusCode, Json<Value>) {
+    let reviewer = match admin(&headers) {
+        Ok(caller) => caller,
+        Err(response) => return response,
+    };
+    match state.collusion_service.review(cluster_id, reviewer.user_id, payload).await {
+        Ok(cluster) => (StatusCode::OK, Json(json!(cluster))),
+        Err(e) => error_response(e),
+    }
+}
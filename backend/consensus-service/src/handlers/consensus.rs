@@ -1,14 +1,175 @@
-use axum::{extract::{State, Path}, response::Json, http::StatusCode};
+use axum::{
+    extract::{State, Path},
+    response::{sse::{Event, KeepAlive, Sse}, IntoResponse, Json, Response},
+    http::StatusCode,
+};
+use chrono::{DateTime, Utc};
+use futures::{stream, StreamExt};
 use serde_json::{json, Value};
 use std::sync::Arc;
+use uuid::Uuid;
 use crate::AppState;
 use crate::models::*;
+use crate::validators::commitment;
 
+/// A bounty's current consensus, including the weight applied to each vote
 pub async fn get_bounty_consensus(
-    State(_state): State<Arc<AppState>>,
-    Path(_bounty_id): Path<String>,
+    State(state): State<Arc<AppState>>,
+    Path(bounty_id): Path<Uuid>,
 ) -> (StatusCode, Json<Value>) {
-    (StatusCode::OK, Json(json!({"verdict": "malicious"})))
+    match state.consensus_service.consensus(bounty_id).await {
+        Ok(consensus) => (StatusCode::OK, Json(json!(consensus))),
+        Err(e) => {
+            tracing::error!("Failed to calculate consensus for bounty {}: {}", bounty_id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to calculate consensus"})),
+            )
+        }
+    }
+}
+
+/// Server-sent `progress` events as a bounty's votes arrive and its
+/// distribution shifts, ending once consensus is final. Individual verdicts
+/// are never sent.
+pub async fn stream_consensus(
+    State(state): State<Arc<AppState>>,
+    Path(bounty_id): Path<Uuid>,
+) -> Response {
+    let updates = match state.consensus_service.progress_updates(bounty_id).await {
+        Ok(updates) => updates,
+        Err(e) => {
+            tracing::error!("Failed to subscribe to consensus progress for bounty {}: {}", bounty_id, e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to stream consensus"})),
+            )
+                .into_response();
+        }
+    };
+    let current = match state.consensus_service.progress(bounty_id).await {
+        Ok(current) => current,
+        Err(e) => {
+            tracing::error!("Failed to read consensus progress for bounty {}: {}", bounty_id, e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to stream consensus"})),
+            )
+                .into_response();
+        }
+    };
+
+    let events = stream::once(async move { current })
+        .chain(updates)
+        .scan(false, |finished, progress| {
+            if *finished {
+                return futures::future::ready(None);
+            }
+            *finished = progress.is_finalized;
+            futures::future::ready(Some(progress))
+        })
+        .map(|progress| Event::default().event("progress").json_data(progress));
+
+    Sse::new(events).keep_alive(KeepAlive::default()).into_response()
+}
+
+/// How a bounty's consensus was derived: each vote's weight components, the
+/// threshold applied, why the final verdict won and which votes stand out
+pub async fn get_consensus_explanation(
+    State(state): State<Arc<AppState>>,
+    Path(bounty_id): Path<Uuid>,
+) -> (StatusCode, Json<Value>) {
+    match state.consensus_service.explanation(bounty_id).await {
+        Ok(explanation) => (StatusCode::OK, Json(json!(explanation))),
+        Err(e) => {
+            tracing::error!("Failed to explain consensus for bounty {}: {}", bounty_id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to explain consensus"})),
+            )
+        }
+    }
+}
+
+/// How a finalized bounty settles: who is rewarded, who is slashed and how
+/// each engine's reputation moves. Whether it was a calibration bounty is
+/// only for the services settling it, so engines can't pick them out.
+pub async fn get_settlement(
+    State(state): State<Arc<AppState>>,
+    Path(bounty_id): Path<Uuid>,
+) -> (StatusCode, Json<Value>) {
+    match state.consensus_service.settlement(bounty_id).await {
+        Ok(Some(mut plan)) => {
+            plan.calibration = false;
+            (StatusCode::OK, Json(json!(plan)))
+        }
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Bounty has no settlement plan; it may not be final yet"})),
+        ),
+        Err(e) => {
+            tracing::error!("Failed to load settlement plan for bounty {}: {}", bounty_id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to load settlement plan"})),
+            )
+        }
+    }
+}
+
+/// A bounty's quorum rules
+pub async fn get_quorum(
+    State(state): State<Arc<AppState>>,
+    Path(bounty_id): Path<Uuid>,
+) -> (StatusCode, Json<Value>) {
+    match state.consensus_service.quorum(bounty_id).await {
+        Ok(quorum) => (StatusCode::OK, Json(json!(quorum))),
+        Err(e) => {
+            tracing::error!("Failed to load quorum rules for bounty {}: {}", bounty_id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to load quorum rules"})),
+            )
+        }
+    }
+}
+
+/// Set when a bounty's result may be finalized before the bounty closes.
+/// Rules left out use the service default; a final result keeps its rules.
+pub async fn set_quorum(
+    State(state): State<Arc<AppState>>,
+    Path(bounty_id): Path<Uuid>,
+    Json(payload): Json<QuorumRulesRequest>,
+) -> (StatusCode, Json<Value>) {
+    let max_submissions = state.config.consensus.max_submissions;
+    if payload.min_submissions.is_some_and(|n| n == 0 || n > max_submissions)
+        || payload.min_total_stake.is_some_and(|stake| stake < 0)
+    {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": format!(
+                    "min_submissions must be between 1 and {} and min_total_stake must not be negative",
+                    max_submissions
+                )
+            })),
+        );
+    }
+
+    match state.consensus_service.set_quorum(bounty_id, &payload).await {
+        Ok(Some(quorum)) => (StatusCode::OK, Json(json!(quorum))),
+        Ok(None) => (
+            StatusCode::CONFLICT,
+            Json(json!({"error": "Consensus on this bounty is already final"})),
+        ),
+        Err(e) => {
+            tracing::error!("Failed to set quorum rules for bounty {}: {}", bounty_id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to set quorum rules"})),
+            )
+        }
+    }
 }
 
 pub async fn calculate_consensus(
@@ -19,6 +180,275 @@ pub async fn calculate_consensus(
     (StatusCode::OK, Json(json!({"message": "Consensus calculated"})))
 }
 
+#[derive(Debug, sqlx::FromRow)]
+struct Commitment {
+    commitment: String,
+    reveal_after: DateTime<Utc>,
+}
+
+/// At most `max` characters of `value`, to fit its column
+fn truncate(value: &str, max: usize) -> &str {
+    value.char_indices().nth(max).map_or(value, |(end, _)| &value[..end])
+}
+
+fn internal_error(context: &str, bounty_id: Uuid, e: sqlx::Error) -> (StatusCode, Json<Value>) {
+    tracing::error!("{} for bounty {}: {}", context, bounty_id, e);
+    (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": context})))
+}
+
+/// Record an engine's commitment on a commit-reveal bounty. Forwarding the
+/// same commitment again is harmless; a different one is refused, so an
+/// engine cannot change its verdict once committed.
+pub async fn record_commitment(
+    State(state): State<Arc<AppState>>,
+    Path(bounty_id): Path<Uuid>,
+    Json(payload): Json<RecordCommitmentRequest>,
+) -> (StatusCode, Json<Value>) {
+    if payload.engine_id.trim().is_empty() || !commitment::is_commitment(&payload.commitment) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "engine_id is required and commitment must be a hex SHA-256 digest"})),
+        );
+    }
+    if Utc::now() >= payload.reveal_after {
+        return (
+            StatusCode::CONFLICT,
+            Json(json!({"error": "the submission window has closed"})),
+        );
+    }
+
+    let stored = sqlx::query_scalar::<_, String>(
+        r#"
+        INSERT INTO consensus_commitments (bounty_id, engine_id, commitment, reveal_after)
+        VALUES ($1, $2, LOWER($3), $4)
+        ON CONFLICT (bounty_id, engine_id) DO UPDATE SET bounty_id = EXCLUDED.bounty_id
+        RETURNING commitment
+        "#,
+    )
+    .bind(bounty_id)
+    .bind(&payload.engine_id)
+    .bind(&payload.commitment)
+    .bind(payload.reveal_after)
+    .fetch_one(&state.db_pool)
+    .await;
+
+    match stored {
+        Ok(stored) if stored.eq_ignore_ascii_case(&payload.commitment) => {
+            (StatusCode::OK, Json(json!({"bounty_id": bounty_id, "engine_id": payload.engine_id})))
+        }
+        Ok(_) => (
+            StatusCode::CONFLICT,
+            Json(json!({"error": "engine already committed to a different verdict"})),
+        ),
+        Err(e) => internal_error("Failed to record commitment", bounty_id, e),
+    }
+}
+
+/// Refuse a verdict on a commit-reveal bounty unless it opens the engine's
+/// commitment after the submission window closed. Bounties nobody committed
+/// to take verdicts directly.
+async fn check_reveal(
+    state: &AppState,
+    bounty_id: Uuid,
+    payload: &RecordSubmissionRequest,
+) -> Result<(), (StatusCode, Json<Value>)> {
+    let blind: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM consensus_commitments WHERE bounty_id = $1)")
+        .bind(bounty_id)
+        .fetch_one(&state.db_pool)
+        .await
+        .map_err(|e| internal_error("Failed to load commitments", bounty_id, e))?;
+    if !blind {
+        return Ok(());
+    }
+
+    let own: Option<Commitment> = sqlx::query_as(
+        "SELECT commitment, reveal_after FROM consensus_commitments WHERE bounty_id = $1 AND engine_id = $2",
+    )
+    .bind(bounty_id)
+    .bind(&payload.engine_id)
+    .fetch_optional(&state.db_pool)
+    .await
+    .map_err(|e| internal_error("Failed to load commitments", bounty_id, e))?;
+    let Some(own) = own else {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "bounty takes committed verdicts only"})),
+        ));
+    };
+    if Utc::now() < own.reveal_after {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({"error": "verdicts are revealed after the submission window closes"})),
+        ));
+    }
+    let verdict = payload.verdict.to_string();
+    if !payload
+        .nonce
+        .as_deref()
+        .is_some_and(|nonce| commitment::opens(&own.commitment, &verdict, nonce))
+    {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "verdict and nonce do not match the commitment"})),
+        ));
+    }
+
+    sqlx::query("UPDATE consensus_commitments SET revealed_at = NOW() WHERE bounty_id = $1 AND engine_id = $2")
+        .bind(bounty_id)
+        .bind(&payload.engine_id)
+        .execute(&state.db_pool)
+        .await
+        .map_err(|e| internal_error("Failed to record reveal", bounty_id, e))?;
+    Ok(())
+}
+
+/// Record an engine's verdict on a bounty. The bounty-manager forwards each
+/// submission it accepts; forwarding the same engine again replaces its vote,
+/// so retries are safe. On commit-reveal bounties the verdict is only
+/// accepted after the submission window closes, from an engine that
+/// committed to it. The bounty's consensus algorithm, artifact and, for
+/// calibration bounties, ground truth are recorded when given.
+pub async fn record_submission(
+    State(state): State<Arc<AppState>>,
+    Path(bounty_id): Path<Uuid>,
+    Json(payload): Json<RecordSubmissionRequest>,
+) -> (StatusCode, Json<Value>) {
+    if payload.engine_id.trim().is_empty() || !(0.0..=1.0).contains(&payload.confidence) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "engine_id is required and confidence must be between 0 and 1"})),
+        );
+    }
+    if payload.stake_amount < 0 || payload.prediction.as_ref().is_some_and(|p| !p.is_valid()) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "stake_amount must not be negative and prediction shares must be between 0 and 1"})),
+        );
+    }
+
+    if payload.ground_truth == Some(Verdict::Unknown) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "ground_truth must be malicious, benign or suspicious"})),
+        );
+    }
+
+    if let Err(refusal) = check_reveal(&state, bounty_id, &payload).await {
+        return refusal;
+    }
+
+    if let Some(algorithm) = payload.algorithm {
+        let chosen = sqlx::query(
+            r#"
+            INSERT INTO consensus_bounty_settings (bounty_id, algorithm)
+            VALUES ($1, $2)
+            ON CONFLICT (bounty_id) DO UPDATE
+            SET algorithm = EXCLUDED.algorithm, updated_at = NOW()
+            WHERE consensus_bounty_settings.algorithm IS DISTINCT FROM EXCLUDED.algorithm
+            "#,
+        )
+        .bind(bounty_id)
+        .bind(algorithm.as_str())
+        .execute(&state.db_pool)
+        .await;
+        if let Err(e) = chosen {
+            return internal_error("Failed to record consensus algorithm", bounty_id, e);
+        }
+    }
+
+    if let Some(artifact) = payload.artifact.as_ref().filter(|a| a.file_type.is_some() || a.file_size.is_some()) {
+        let recorded = sqlx::query(
+            r#"
+            INSERT INTO consensus_bounty_settings (bounty_id, artifact_type, artifact_size)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (bounty_id) DO UPDATE
+            SET artifact_type = COALESCE(EXCLUDED.artifact_type, consensus_bounty_settings.artifact_type),
+                artifact_size = COALESCE(EXCLUDED.artifact_size, consensus_bounty_settings.artifact_size),
+                updated_at = NOW()
+            WHERE consensus_bounty_settings.artifact_type IS NULL OR consensus_bounty_settings.artifact_size IS NULL
+            "#,
+        )
+        .bind(bounty_id)
+        .bind(artifact.file_type.as_deref().map(|file_type| truncate(file_type.trim(), 255).to_ascii_lowercase()))
+        .bind(artifact.file_size.filter(|size| *size >= 0))
+        .execute(&state.db_pool)
+        .await;
+        if let Err(e) = recorded {
+            return internal_error("Failed to record bounty artifact", bounty_id, e);
+        }
+    }
+
+    if let Some(ground_truth) = &payload.ground_truth {
+        // A calibration bounty's verdict is fixed when it is injected
+        let recorded = sqlx::query(
+            r#"
+            INSERT INTO consensus_bounty_settings (bounty_id, ground_truth)
+            VALUES ($1, $2)
+            ON CONFLICT (bounty_id) DO UPDATE
+            SET ground_truth = EXCLUDED.ground_truth, updated_at = NOW()
+            WHERE consensus_bounty_settings.ground_truth IS NULL
+            "#,
+        )
+        .bind(bounty_id)
+        .bind(ground_truth.to_string())
+        .execute(&state.db_pool)
+        .await;
+        if let Err(e) = recorded {
+            return internal_error("Failed to record calibration verdict", bounty_id, e);
+        }
+    }
+
+    let indicators = payload.indicators.clone().unwrap_or_default().normalized();
+    let recorded = sqlx::query_scalar::<_, Uuid>(
+        r#"
+        INSERT INTO consensus_submissions (
+            bounty_id, engine_id, verdict, confidence, reputation_score, stake_amount, prediction,
+            source_ip, wallet_address, analysis_text, imphash, ssdeep, contacted_domains, specialization_score,
+            probation
+        )
+        VALUES ($1, $2, $3, $4::NUMERIC, $5, $6, $7::JSONB, $8, LOWER($9), $10, $11, $12, $13, $14, $15)
+        ON CONFLICT (bounty_id, engine_id) DO UPDATE
+        SET verdict = EXCLUDED.verdict,
+            confidence = EXCLUDED.confidence,
+            reputation_score = EXCLUDED.reputation_score,
+            specialization_score = EXCLUDED.specialization_score,
+            probation = EXCLUDED.probation,
+            stake_amount = EXCLUDED.stake_amount,
+            prediction = EXCLUDED.prediction,
+            source_ip = COALESCE(EXCLUDED.source_ip, consensus_submissions.source_ip),
+            wallet_address = COALESCE(EXCLUDED.wallet_address, consensus_submissions.wallet_address),
+            analysis_text = EXCLUDED.analysis_text,
+            imphash = EXCLUDED.imphash,
+            ssdeep = EXCLUDED.ssdeep,
+            contacted_domains = EXCLUDED.contacted_domains,
+            submitted_at = NOW()
+        RETURNING id
+        "#,
+    )
+    .bind(bounty_id)
+    .bind(&payload.engine_id)
+    .bind(payload.verdict.to_string())
+    .bind(payload.confidence)
+    .bind(payload.reputation_score)
+    .bind(payload.stake_amount)
+    .bind(payload.prediction.as_ref().and_then(|p| serde_json::to_string(p).ok()))
+    .bind(payload.source_ip.as_deref().map(|ip| truncate(ip, 64)))
+    .bind(payload.wallet_address.as_deref().map(|wallet| truncate(wallet, 42)))
+    .bind(&payload.analysis_text)
+    .bind(&indicators.imphash)
+    .bind(&indicators.ssdeep)
+    .bind(&indicators.domains)
+    .bind(payload.specialization_score)
+    .bind(payload.probation)
+    .fetch_one(&state.db_pool)
+    .await;
+
+    match recorded {
+        Ok(id) => (StatusCode::OK, Json(json!({"id": id, "bounty_id": bounty_id}))),
+        Err(e) => internal_error("Failed to record submission", bounty_id, e),
+    }
+}
+
 pub async fn get_submission_consensus(
     State(_state): State<Arc<AppState>>,
     Path(_submission_id): Path<String>,
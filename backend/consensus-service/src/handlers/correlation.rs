@@ -0,0 +1,64 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tracing::error;
+use uuid::Uuid;
+
+use super::assignment::analyst;
+use crate::correlation::{ConflictListQuery, CorrelationError, ReviewConflictRequest};
+use crate::AppState;
+
+fn error_response(e: CorrelationError) -> (StatusCode, Json<Value>) {
+    let status = match &e {
+        CorrelationError::Validation(_) => StatusCode::BAD_REQUEST,
+        CorrelationError::NotFound(_) => StatusCode::NOT_FOUND,
+        CorrelationError::Database(err) => {
+            error!("Correlation query failed: {}", err);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Internal server error"})),
+            );
+        }
+    };
+    (status, Json(json!({"error": e.to_string()})))
+}
+
+/// Related artifacts whose final verdicts contradict each other, newest
+/// first
+pub async fn list_conflicts(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<ConflictListQuery>,
+) -> (StatusCode, Json<Value>) {
+    if let Err(response) = analyst(&headers) {
+        return response;
+    }
+    match state.correlation_service.list(&query).await {
+        Ok(conflicts) => {
+            let next_before = conflicts.last().map(|conflict| conflict.detected_at);
+            (StatusCode::OK, Json(json!({"conflicts": conflicts, "next_before": next_before})))
+        }
+        Err(e) => error_response(e),
+    }
+}
+
+/// Confirm or dismiss a conflict between related artifacts
+pub async fn review_conflict(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(conflict_id): Path<Uuid>,
+    Json(payload): Json<ReviewConflictRequest>,
+) -> (StatusCode, Json<Value>) {
+    let reviewer = match analyst(&headers) {
+        Ok(caller) => caller,
+        Err(response) => return response,
+    };
+    match state.correlation_service.review(conflict_id, reviewer.user_id, payload).await {
+        Ok(conflict) => (StatusCode::OK, Json(json!(conflict))),
+        Err(e) => error_response(e),
+    }
+}
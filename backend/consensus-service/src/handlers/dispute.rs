@@ -1,34 +1,108 @@
-use axum::{extract::{State, Path}, response::Json, http::StatusCode};
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
 use serde_json::{json, Value};
 use std::sync::Arc;
+use tracing::error;
+use uuid::Uuid;
+
+use super::assignment::caller;
+use crate::dispute::{CastVoteRequest, CreateDisputeRequest, DisputeError, ResolveDisputeRequest};
 use crate::AppState;
-use crate::models::*;
 
+fn error_response(e: DisputeError) -> (StatusCode, Json<Value>) {
+    let status = match &e {
+        DisputeError::Validation(_) => StatusCode::BAD_REQUEST,
+        DisputeError::NotFound(_) => StatusCode::NOT_FOUND,
+        DisputeError::Conflict(_) => StatusCode::CONFLICT,
+        DisputeError::Forbidden(_) => StatusCode::FORBIDDEN,
+        DisputeError::Upstream(_) => StatusCode::BAD_GATEWAY,
+        DisputeError::Database(err) => {
+            error!("Dispute query failed: {}", err);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Internal server error"})),
+            );
+        }
+    };
+    (status, Json(json!({"error": e.to_string()})))
+}
+
+/// Dispute a bounty's final verdict, staking tokens behind the claim
 pub async fn create_dispute(
-    State(_state): State<Arc<AppState>>,
-    Json(_payload): Json<CreateDisputeRequest>,
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<CreateDisputeRequest>,
 ) -> (StatusCode, Json<Value>) {
-    (StatusCode::OK, Json(json!({"message": "Dispute created"})))
+    let caller = match caller(&headers) {
+        Ok(caller) => caller,
+        Err(response) => return response,
+    };
+    match state.dispute_service.create(caller.user_id, payload).await {
+        Ok(dispute) => (StatusCode::CREATED, Json(json!(dispute))),
+        Err(e) => error_response(e),
+    }
 }
 
 pub async fn get_dispute(
-    State(_state): State<Arc<AppState>>,
-    Path(_dispute_id): Path<String>,
+    State(state): State<Arc<AppState>>,
+    Path(dispute_id): Path<Uuid>,
+) -> (StatusCode, Json<Value>) {
+    match state.dispute_service.get(dispute_id).await {
+        Ok(Some(dispute)) => (StatusCode::OK, Json(json!(dispute))),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": format!("Dispute {} not found", dispute_id)})),
+        ),
+        Err(e) => error_response(e),
+    }
+}
+
+/// An arbitrator's vote on a dispute their panel is judging
+pub async fn cast_vote(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(dispute_id): Path<Uuid>,
+    Json(payload): Json<CastVoteRequest>,
 ) -> (StatusCode, Json<Value>) {
-    (StatusCode::OK, Json(json!({"dispute": {}})))
+    let caller = match caller(&headers) {
+        Ok(caller) => caller,
+        Err(response) => return response,
+    };
+    match state.dispute_service.cast_vote(dispute_id, caller.user_id, payload).await {
+        Ok(dispute) => (StatusCode::OK, Json(json!(dispute))),
+        Err(e) => error_response(e),
+    }
 }
 
+/// Resolve a dispute with an admin's ruling instead of the panel's vote
 pub async fn resolve_dispute(
-    State(_state): State<Arc<AppState>>,
-    Path(_dispute_id): Path<String>,
-    Json(_payload): Json<ResolveDisputeRequest>,
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(dispute_id): Path<Uuid>,
+    Json(payload): Json<ResolveDisputeRequest>,
 ) -> (StatusCode, Json<Value>) {
-    (StatusCode::OK, Json(json!({"message": "Dispute resolved"})))
+    let caller = match caller(&headers) {
+        Ok(caller) => caller,
+        Err(response) => return response,
+    };
+    if !caller.is_admin {
+        return (StatusCode::FORBIDDEN, Json(json!({"error": "Admin role required"})));
+    }
+    match state.dispute_service.resolve(dispute_id, caller.user_id, payload).await {
+        Ok(dispute) => (StatusCode::OK, Json(json!(dispute))),
+        Err(e) => error_response(e),
+    }
 }
 
 pub async fn get_bounty_disputes(
-    State(_state): State<Arc<AppState>>,
-    Path(_bounty_id): Path<String>,
+    State(state): State<Arc<AppState>>,
+    Path(bounty_id): Path<Uuid>,
 ) -> (StatusCode, Json<Value>) {
-    (StatusCode::OK, Json(json!({"disputes": []})))
+    match state.dispute_service.for_bounty(bounty_id).await {
+        Ok(disputes) => (StatusCode::OK, Json(json!({"disputes": disputes}))),
+        Err(e) => error_response(e),
+    }
 }
@@ -0,0 +1,124 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use chrono::NaiveDate;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tracing::{debug, error};
+
+use crate::feed::{FeedClient, FeedCursor, FeedQuery};
+use crate::AppState;
+
+const API_KEY_HEADER: &str = "x-api-key";
+
+async fn authenticate(
+    state: &AppState,
+    headers: &HeaderMap,
+) -> Result<FeedClient, (StatusCode, Json<Value>)> {
+    let api_key = headers
+        .get(API_KEY_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| {
+            (
+                StatusCode::UNAUTHORIZED,
+                Json(json!({"error": "Missing X-API-Key header"})),
+            )
+        })?;
+
+    match state.feed_service.authenticate(api_key).await {
+        Ok(Some(client)) => {
+            debug!("Feed request from {} ({})", client.name, client.key_id);
+            Ok(client)
+        }
+        Ok(None) => Err((
+            StatusCode::UNAUTHORIZED,
+            Json(json!({"error": "Invalid or expired API key"})),
+        )),
+        Err(e) => {
+            error!("Feed API key lookup failed: {}", e);
+            Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Internal server error"})),
+            ))
+        }
+    }
+}
+
+pub async fn list_verdicts(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(query): Query<FeedQuery>,
+) -> (StatusCode, Json<Value>) {
+    let client = match authenticate(&state, &headers).await {
+        Ok(client) => client,
+        Err(response) => return response,
+    };
+
+    let cursor = match query.cursor.as_deref().map(FeedCursor::decode) {
+        Some(None) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({"error": "Invalid cursor"})),
+            )
+        }
+        Some(cursor) => cursor,
+        None => None,
+    };
+
+    match state
+        .feed_service
+        .list_entries(client.tier, cursor, query.limit, query.verdict.as_deref())
+        .await
+    {
+        Ok(page) => (StatusCode::OK, Json(json!(page))),
+        Err(e) => {
+            error!("Failed to load feed page: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Internal server error"})),
+            )
+        }
+    }
+}
+
+pub async fn get_bulk_export(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(date): Path<String>,
+) -> (StatusCode, Json<Value>) {
+    let client = match authenticate(&state, &headers).await {
+        Ok(client) => client,
+        Err(response) => return response,
+    };
+
+    if !client.tier.can_download_bulk() {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": "Bulk exports require a premium feed key"})),
+        );
+    }
+
+    let Ok(date) = NaiveDate::parse_from_str(&date, "%Y-%m-%d") else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "Date must be formatted as YYYY-MM-DD"})),
+        );
+    };
+
+    match state.feed_service.get_bulk_export(date).await {
+        Ok(Some(export)) => (StatusCode::OK, Json(json!(export))),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": format!("No export available for {}", date)})),
+        ),
+        Err(e) => {
+            error!("Failed to load bulk export for {}: {}", date, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Internal server error"})),
+            )
+        }
+    }
+}
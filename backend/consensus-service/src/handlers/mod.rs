@@ -3,3 +3,9 @@ pub mod consensus;
 pub mod dispute;
 pub mod validation;
 pub mod admin;
+pub mod feed;
+pub mod assignment;
+pub mod collusion;
+pub mod recalculation;
+pub mod correlation;
+pub mod registry;
@@ -0,0 +1,59 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tracing::error;
+use uuid::Uuid;
+
+use super::assignment::admin;
+use crate::recalculation::{RecalculationError, RecalculationFilter};
+use crate::AppState;
+
+fn error_response(e: RecalculationError) -> (StatusCode, Json<Value>) {
+    let status = match &e {
+        RecalculationError::Validation(_) => StatusCode::BAD_REQUEST,
+        RecalculationError::NotFound(_) => StatusCode::NOT_FOUND,
+        RecalculationError::Database(err) => {
+            error!("Recalculation query failed: {}", err);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Internal server error"})),
+            );
+        }
+    };
+    (status, Json(json!({"error": e.to_string()})))
+}
+
+/// Queue the bounties matching a filter for consensus recalculation
+pub async fn create_batch(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<RecalculationFilter>,
+) -> (StatusCode, Json<Value>) {
+    let caller = match admin(&headers) {
+        Ok(caller) => caller,
+        Err(response) => return response,
+    };
+    match state.recalculation_service.create(payload, caller.user_id).await {
+        Ok(batch) => (StatusCode::ACCEPTED, Json(json!(batch))),
+        Err(e) => error_response(e),
+    }
+}
+
+/// A recalculation batch's progress and the bounties that changed or failed
+pub async fn get_batch(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(batch_id): Path<Uuid>,
+) -> (StatusCode, Json<Value>) {
+    if let Err(response) = admin(&headers) {
+        return response;
+    }
+    match state.recalculation_service.get(batch_id).await {
+        Ok(batch) => (StatusCode::OK, Json(json!(batch))),
+        Err(e) => error_response(e),
+    }
+}
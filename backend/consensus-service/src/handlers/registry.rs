@@ -0,0 +1,111 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    response::Json,
+};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tracing::error;
+use uuid::Uuid;
+
+use super::assignment::caller;
+use crate::registry::{HeartbeatRequest, RegisterEngineRequest, RegistryError};
+use crate::AppState;
+
+fn error_response(e: RegistryError) -> (StatusCode, Json<Value>) {
+    let status = match &e {
+        RegistryError::Validation(_) => StatusCode::BAD_REQUEST,
+        RegistryError::NotFound(_) => StatusCode::NOT_FOUND,
+        RegistryError::Forbidden(_) => StatusCode::FORBIDDEN,
+        RegistryError::Database(err) => {
+            error!("Engine registry query failed: {}", err);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Internal server error"})),
+            );
+        }
+    };
+    (status, Json(json!({"error": e.to_string()})))
+}
+
+/// Register an automated engine's capabilities, or update them
+pub async fn register_engine(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(engine_id): Path<String>,
+    Json(payload): Json<RegisterEngineRequest>,
+) -> (StatusCode, Json<Value>) {
+    let caller = match caller(&headers) {
+        Ok(caller) => caller,
+        Err(response) => return response,
+    };
+    match state
+        .registry_service
+        .register(&engine_id, caller.user_id, caller.is_admin, payload)
+        .await
+    {
+        Ok(engine) => (StatusCode::OK, Json(json!(engine))),
+        Err(e) => error_response(e),
+    }
+}
+
+/// Record that an engine is alive, and whether it is taking work
+pub async fn engine_heartbeat(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(engine_id): Path<String>,
+    payload: Option<Json<HeartbeatRequest>>,
+) -> (StatusCode, Json<Value>) {
+    let caller = match caller(&headers) {
+        Ok(caller) => caller,
+        Err(response) => return response,
+    };
+    let request = payload.map(|Json(request)| request).unwrap_or_default();
+    match state
+        .registry_service
+        .heartbeat(&engine_id, caller.user_id, caller.is_admin, &request)
+        .await
+    {
+        Ok(engine) => (StatusCode::OK, Json(json!(engine))),
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn list_engines(State(state): State<Arc<AppState>>) -> (StatusCode, Json<Value>) {
+    match state.registry_service.list().await {
+        Ok(engines) => (StatusCode::OK, Json(json!({"engines": engines}))),
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn get_engine(
+    State(state): State<Arc<AppState>>,
+    Path(engine_id): Path<String>,
+) -> (StatusCode, Json<Value>) {
+    match state.registry_service.get(&engine_id).await {
+        Ok(engine) => (StatusCode::OK, Json(json!(engine))),
+        Err(e) => error_response(e),
+    }
+}
+
+/// Which registered engines voted on a bounty, and why the others have not:
+/// offline, declined, unable to analyze the artifact, or still pending
+pub async fn get_bounty_engines(
+    State(state): State<Arc<AppState>>,
+    Path(bounty_id): Path<Uuid>,
+) -> (StatusCode, Json<Value>) {
+    let quorum = match state.consensus_service.quorum(bounty_id).await {
+        Ok(quorum) => quorum,
+        Err(e) => {
+            error!("Failed to load quorum for bounty {}: {}", bounty_id, e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Internal server error"})),
+            );
+        }
+    };
+    match state.registry_service.bounty_availability(bounty_id, &quorum).await {
+        Ok(availability) => (StatusCode::OK, Json(json!(availability))),
+        Err(e) => error_response(e),
+    }
+}
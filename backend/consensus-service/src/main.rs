@@ -1,24 +1,36 @@
 mod aggregation;
+mod assignment;
+mod collusion;
 mod config;
+mod correlation;
+mod dispute;
+mod feed;
 mod handlers;
 mod models;
+mod recalculation;
+mod registry;
 mod services;
+mod settlement;
 mod validators;
 mod workers;
 
 use anyhow::Result;
 use axum::{
-    routing::{get, post},
+    routing::{get, post, put},
     Router,
 };
 use std::sync::Arc;
-use tower_http::{
-    cors::{Any, CorsLayer},
-    trace::TraceLayer,
-};
+use tower_http::trace::TraceLayer;
 use tracing::{info, warn};
 
+use crate::assignment::AssignmentService;
+use crate::collusion::CollusionService;
 use crate::config::Config;
+use crate::correlation::CorrelationService;
+use crate::dispute::DisputeService;
+use crate::feed::FeedService;
+use crate::recalculation::RecalculationService;
+use crate::registry::RegistryService;
 use crate::services::consensus_service::ConsensusService;
 
 #[tokio::main]
@@ -27,12 +39,7 @@ async fn main() -> Result<()> {
     dotenvy::dotenv().ok();
 
     // Initialize tracing
-    tracing_subscriber::fmt()
-        .with_target(false)
-        .with_thread_ids(true)
-        .with_line_number(true)
-        .json()
-        .init();
+    shared::observability::init_service_logging(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))?;
 
     info!("Starting Consensus Service...");
 
@@ -67,21 +74,98 @@ async fn main() -> Result<()> {
     );
     info!("Consensus service initialized");
 
+    let feed_service = Arc::new(FeedService::new(config.feed.clone(), db_pool.clone()));
+    info!("Intelligence feed initialized");
+
+    let assignment_service = Arc::new(AssignmentService::new(
+        config.assignment.clone(),
+        db_pool.clone(),
+    ));
+
+    let dispute_service = Arc::new(DisputeService::new(
+        config.dispute.clone(),
+        &config.redis.url,
+        db_pool.clone(),
+        assignment_service.clone(),
+    )?);
+
+    let collusion_service = Arc::new(CollusionService::new(config.collusion.clone(), db_pool.clone())?);
+
+    let correlation_service = Arc::new(CorrelationService::new(config.correlation.clone(), db_pool.clone()));
+
+    let registry_service = Arc::new(RegistryService::new(config.registry.clone(), db_pool.clone()));
+
+    let recalculation_service = Arc::new(RecalculationService::new(
+        config.recalculation.clone(),
+        db_pool.clone(),
+        consensus_service.clone(),
+    ));
+
     // Start background workers
     let service_clone = consensus_service.clone();
+    let redis_url = config.redis.url.clone();
     tokio::spawn(async move {
-        if let Err(e) = workers::consensus_processor::start(service_clone).await {
+        if let Err(e) = workers::consensus_processor::start(service_clone, redis_url).await {
             warn!("Consensus processor error: {}", e);
         }
     });
 
     let service_clone = consensus_service.clone();
+    let finalize_interval = config.consensus.auto_finalize_interval_secs;
+    tokio::spawn(async move {
+        if let Err(e) = workers::auto_finalizer::start(service_clone, finalize_interval).await {
+            warn!("Auto-finalizer error: {}", e);
+        }
+    });
+
+    let dispute_clone = dispute_service.clone();
+    let resolve_interval = config.dispute.resolve_interval_secs;
     tokio::spawn(async move {
-        if let Err(e) = workers::dispute_resolver::start(service_clone).await {
+        if let Err(e) = workers::dispute_resolver::start(dispute_clone, resolve_interval).await {
             warn!("Dispute resolver error: {}", e);
         }
     });
 
+    let feed_clone = feed_service.clone();
+    let export_interval = config.feed.export_interval_secs;
+    tokio::spawn(async move {
+        if let Err(e) = workers::feed_exporter::start(feed_clone, export_interval).await {
+            warn!("Feed exporter error: {}", e);
+        }
+    });
+
+    let assignment_clone = assignment_service.clone();
+    let sweep_interval = config.assignment.sweep_interval_secs;
+    tokio::spawn(async move {
+        if let Err(e) = workers::assignment_sweeper::start(assignment_clone, sweep_interval).await {
+            warn!("Assignment sweeper error: {}", e);
+        }
+    });
+
+    let collusion_clone = collusion_service.clone();
+    let scan_interval = config.collusion.scan_interval_secs;
+    tokio::spawn(async move {
+        if let Err(e) = workers::collusion_scanner::start(collusion_clone, scan_interval).await {
+            warn!("Collusion scanner error: {}", e);
+        }
+    });
+
+    let recalculation_clone = recalculation_service.clone();
+    let recalculation_interval = config.recalculation.interval_secs;
+    tokio::spawn(async move {
+        if let Err(e) = workers::recalculation_runner::start(recalculation_clone, recalculation_interval).await {
+            warn!("Recalculation runner error: {}", e);
+        }
+    });
+
+    let correlation_clone = correlation_service.clone();
+    let correlation_interval = config.correlation.scan_interval_secs;
+    tokio::spawn(async move {
+        if let Err(e) = workers::correlation_scanner::start(correlation_clone, correlation_interval).await {
+            warn!("Correlation scanner error: {}", e);
+        }
+    });
+
     info!("Background workers started");
 
     // Build application state
@@ -90,35 +174,92 @@ async fn main() -> Result<()> {
         db_pool,
         redis_conn,
         consensus_service,
+        feed_service,
+        assignment_service,
+        collusion_service,
+        dispute_service,
+        recalculation_service,
+        correlation_service,
+        registry_service,
     });
 
-    // Configure CORS
-    let cors = CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods(Any)
-        .allow_headers(Any);
+    // Browser origins from CORS_ALLOWED_ORIGINS or the environment's defaults
+    let cors = shared::http_security::CorsPolicy::from_env().layer();
+
+    // Admin endpoints, for callers with the consensus:override scope
+    let admin = Router::new()
+        .route("/api/v1/admin/consensus/recalculate/:bounty_id", post(handlers::admin::recalculate_consensus))
+        .route("/api/v1/admin/consensus/override/:bounty_id", post(handlers::admin::override_consensus))
+        .route("/api/v1/admin/consensus/:bounty_id/replay", post(handlers::admin::replay_consensus))
+        .route("/api/v1/admin/consensus/recalculate-batch", post(handlers::recalculation::create_batch))
+        .route("/api/v1/admin/consensus/recalculate-batch/:batch_id", get(handlers::recalculation::get_batch))
+        .route("/api/v1/admin/collusion/clusters", get(handlers::collusion::list_clusters))
+        .route("/api/v1/admin/collusion/clusters/:cluster_id/review", post(handlers::collusion::review_cluster))
+        .route_layer(axum::middleware::from_fn(shared::permissions::require_scope(
+            shared::permissions::CONSENSUS_OVERRIDE,
+        )));
 
     // Build router
     let app = Router::new()
         .route("/health", get(handlers::health::health_check))
         // Consensus endpoints
         .route("/api/v1/consensus/bounty/:bounty_id", get(handlers::consensus::get_bounty_consensus))
+        .route("/api/v1/consensus/bounty/:bounty_id/explanation", get(handlers::consensus::get_consensus_explanation))
+        .route("/api/v1/consensus/bounty/:bounty_id/stream", get(handlers::consensus::stream_consensus))
+        .route("/api/v1/consensus/bounty/:bounty_id/engines", get(handlers::registry::get_bounty_engines))
+        .route("/api/v1/consensus/bounty/:bounty_id/settlement", get(handlers::consensus::get_settlement))
         .route("/api/v1/consensus/bounty/:bounty_id/calculate", post(handlers::consensus::calculate_consensus))
+        .route("/api/v1/consensus/bounty/:bounty_id/submissions", post(handlers::consensus::record_submission))
+        .route("/api/v1/consensus/bounty/:bounty_id/commitments", post(handlers::consensus::record_commitment))
+        .route(
+            "/api/v1/consensus/bounty/:bounty_id/quorum",
+            get(handlers::consensus::get_quorum).put(handlers::consensus::set_quorum),
+        )
         .route("/api/v1/consensus/submission/:submission_id", get(handlers::consensus::get_submission_consensus))
         .route("/api/v1/consensus/stats/:bounty_id", get(handlers::consensus::get_consensus_stats))
+        .route("/api/v1/consensus/conflicts", get(handlers::correlation::list_conflicts))
+        .route("/api/v1/consensus/conflicts/:conflict_id/review", post(handlers::correlation::review_conflict))
+        // Engine registry endpoints
+        .route("/api/v1/engines", get(handlers::registry::list_engines))
+        .route(
+            "/api/v1/engines/:engine_id",
+            get(handlers::registry::get_engine).put(handlers::registry::register_engine),
+        )
+        .route("/api/v1/engines/:engine_id/heartbeat", post(handlers::registry::engine_heartbeat))
         // Dispute endpoints
         .route("/api/v1/disputes/create", post(handlers::dispute::create_dispute))
         .route("/api/v1/disputes/:dispute_id", get(handlers::dispute::get_dispute))
+        .route("/api/v1/disputes/:dispute_id/votes", post(handlers::dispute::cast_vote))
         .route("/api/v1/disputes/:dispute_id/resolve", post(handlers::dispute::resolve_dispute))
         .route("/api/v1/disputes/bounty/:bounty_id", get(handlers::dispute::get_bounty_disputes))
         // Validation endpoints
         .route("/api/v1/validation/submission/:submission_id", post(handlers::validation::validate_submission))
         .route("/api/v1/validation/batch", post(handlers::validation::batch_validate))
-        // Admin endpoints
-        .route("/api/v1/admin/consensus/recalculate/:bounty_id", post(handlers::admin::recalculate_consensus))
-        .route("/api/v1/admin/consensus/override/:bounty_id", post(handlers::admin::override_consensus))
+        // Reviewer assignment endpoints
+        .route("/api/v1/reviewers/:user_id", put(handlers::assignment::upsert_reviewer))
+        .route("/api/v1/reviewers/:user_id/assignments", get(handlers::assignment::list_reviewer_assignments))
+        .route("/api/v1/assignments/panels", post(handlers::assignment::create_panel))
+        .route("/api/v1/assignments/panels/:panel_id", get(handlers::assignment::get_panel))
+        .route("/api/v1/assignments/:assignment_id/accept", post(handlers::assignment::accept_assignment))
+        .route("/api/v1/assignments/:assignment_id/decline", post(handlers::assignment::decline_assignment))
+        .route("/api/v1/assignments/:assignment_id/complete", post(handlers::assignment::complete_assignment))
+        // Intelligence feed endpoints (API key required)
+        .route("/api/v1/feed/verdicts", get(handlers::feed::list_verdicts))
+        .route("/api/v1/feed/bulk/:date", get(handlers::feed::get_bulk_export))
+        .merge(admin)
+        .merge(shared::observability::log_level_routes())
+        .layer(axum::middleware::from_fn_with_state(
+            shared::request_signing::SignatureVerifier::from_env()?,
+            shared::request_signing::verify_signature_middleware,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            shared::chaos::FaultInjector::from_env(),
+            shared::chaos::chaos_middleware,
+        ))
         .layer(cors)
+        .layer(axum::middleware::from_fn(shared::http_security::security_headers_middleware))
         .layer(TraceLayer::new_for_http())
+        .layer(axum::middleware::from_fn(shared::observability::log_context_middleware))
         .with_state(app_state);
 
     // Start server
@@ -137,4 +278,11 @@ pub struct AppState {
     pub db_pool: sqlx::PgPool,
     pub redis_conn: redis::aio::ConnectionManager,
     pub consensus_service: Arc<ConsensusService>,
+    pub feed_service: Arc<FeedService>,
+    pub assignment_service: Arc<AssignmentService>,
+    pub collusion_service: Arc<CollusionService>,
+    pub dispute_service: Arc<DisputeService>,
+    pub recalculation_service: Arc<RecalculationService>,
+    pub correlation_service: Arc<CorrelationService>,
+    pub registry_service: Arc<RegistryService>,
 }
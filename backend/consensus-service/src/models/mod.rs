@@ -1,9 +1,13 @@
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use thiserror::Error;
 use uuid::Uuid;
 
+use crate::correlation::ArtifactIndicators;
+use crate::registry::ArtifactProfile;
+
 pub type ConsensusResult<T> = Result<T, ConsensusError>;
 
 #[derive(Debug, Error)]
@@ -44,6 +48,179 @@ impl ToString for Verdict {
     }
 }
 
+impl Verdict {
+    pub const ALL: [Verdict; 4] = [Verdict::Malicious, Verdict::Benign, Verdict::Suspicious, Verdict::Unknown];
+
+    /// Parse the lowercase name a verdict is stored under
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "malicious" => Some(Verdict::Malicious),
+            "benign" => Some(Verdict::Benign),
+            "suspicious" => Some(Verdict::Suspicious),
+            "unknown" => Some(Verdict::Unknown),
+            _ => None,
+        }
+    }
+}
+
+impl From<Verdict> for shared::types::common::ThreatVerdict {
+    fn from(verdict: Verdict) -> Self {
+        match verdict {
+            Verdict::Malicious => Self::Malicious,
+            Verdict::Benign => Self::Benign,
+            Verdict::Suspicious => Self::Suspicious,
+            Verdict::Unknown => Self::Unknown,
+        }
+    }
+}
+
+/// How a bounty's votes are aggregated into its verdict; see
+/// `aggregation::algorithms`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AlgorithmKind {
+    SimpleMajority,
+    ReputationWeighted,
+    StakeWeighted,
+    BayesianTruthSerum,
+}
+
+impl AlgorithmKind {
+    /// Name stored in `consensus_bounty_settings.algorithm`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlgorithmKind::SimpleMajority => "simple_majority",
+            AlgorithmKind::ReputationWeighted => "reputation_weighted",
+            AlgorithmKind::StakeWeighted => "stake_weighted",
+            AlgorithmKind::BayesianTruthSerum => "bayesian_truth_serum",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "simple_majority" => Some(AlgorithmKind::SimpleMajority),
+            "reputation_weighted" => Some(AlgorithmKind::ReputationWeighted),
+            "stake_weighted" => Some(AlgorithmKind::StakeWeighted),
+            "bayesian_truth_serum" => Some(AlgorithmKind::BayesianTruthSerum),
+            _ => None,
+        }
+    }
+}
+
+/// When a bounty's consensus may be finalized before the bounty closes: once
+/// enough submissions and stake agree on a verdict, or at the latest once the
+/// maximum wait after the first submission has passed
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct QuorumRules {
+    pub min_submissions: usize,
+    /// Total stake behind the submissions
+    pub min_total_stake: i64,
+    /// Hours after the first submission the result is finalized regardless;
+    /// 0 waits for the bounty to close
+    pub max_wait_hours: u64,
+}
+
+/// A bounty's quorum rules; rules left out use the service default
+#[derive(Debug, Deserialize)]
+pub struct QuorumRulesRequest {
+    #[serde(default)]
+    pub min_submissions: Option<usize>,
+    #[serde(default)]
+    pub min_total_stake: Option<i64>,
+    #[serde(default)]
+    pub max_wait_hours: Option<u64>,
+}
+
+/// Why a bounty's result was finalized
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FinalizeReason {
+    /// The bounty-manager closed the bounty
+    BountyClosed,
+    /// The quorum agreed on a verdict before the bounty closed
+    QuorumMet,
+    /// The maximum wait passed without the bounty closing
+    MaxWaitElapsed,
+    /// An arbitration panel overturned the verdict the bounty was finalized with
+    DisputeUpheld,
+}
+
+impl FinalizeReason {
+    /// Name stored in `consensus_results.finalized_reason`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FinalizeReason::BountyClosed => "bounty_closed",
+            FinalizeReason::QuorumMet => "quorum_met",
+            FinalizeReason::MaxWaitElapsed => "max_wait_elapsed",
+            FinalizeReason::DisputeUpheld => "dispute_upheld",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "bounty_closed" => Some(FinalizeReason::BountyClosed),
+            "quorum_met" => Some(FinalizeReason::QuorumMet),
+            "max_wait_elapsed" => Some(FinalizeReason::MaxWaitElapsed),
+            "dispute_upheld" => Some(FinalizeReason::DisputeUpheld),
+            _ => None,
+        }
+    }
+}
+
+/// Share of engines giving each verdict, 0.0 to 1.0 each
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VerdictShares {
+    #[serde(default)]
+    pub malicious: f64,
+    #[serde(default)]
+    pub benign: f64,
+    #[serde(default)]
+    pub suspicious: f64,
+    #[serde(default)]
+    pub unknown: f64,
+}
+
+impl VerdictShares {
+    /// Every verdict equally likely
+    pub fn uniform() -> Self {
+        Self {
+            malicious: 0.25,
+            benign: 0.25,
+            suspicious: 0.25,
+            unknown: 0.25,
+        }
+    }
+
+    pub fn share(&self, verdict: &Verdict) -> f64 {
+        match verdict {
+            Verdict::Malicious => self.malicious,
+            Verdict::Benign => self.benign,
+            Verdict::Suspicious => self.suspicious,
+            Verdict::Unknown => self.unknown,
+        }
+    }
+
+    /// Whether every share is between 0 and 1 and some verdict is expected
+    pub fn is_valid(&self) -> bool {
+        let shares = [self.malicious, self.benign, self.suspicious, self.unknown];
+        shares.iter().all(|share| (0.0..=1.0).contains(share)) && shares.iter().sum::<f64>() > 0.0
+    }
+
+    /// Scaled so the shares add up to 1
+    pub fn normalized(self) -> Self {
+        let total = self.malicious + self.benign + self.suspicious + self.unknown;
+        if total <= 0.0 {
+            return Self::uniform();
+        }
+        Self {
+            malicious: self.malicious / total,
+            benign: self.benign / total,
+            suspicious: self.suspicious / total,
+            unknown: self.unknown / total,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct BountyConsensus {
     pub id: Uuid,
@@ -53,6 +230,7 @@ pub struct BountyConsensus {
     pub total_submissions: i32,
     pub agreement_score: Decimal,
     pub participating_engines: Vec<String>,
+    /// `WeightedVotes`: each vote's weight and the filtering decisions
     pub weighted_votes: serde_json::Value,
     pub verdict_distribution: serde_json::Value,
     pub is_disputed: bool,
@@ -69,9 +247,29 @@ pub struct SubmissionVote {
     pub verdict: Verdict,
     pub confidence: Decimal,
     pub reputation_score: i32,
+    /// The engine's reputation in the bounty's threat categories, if it has
+    /// settled submissions in any of them
+    #[serde(default)]
+    pub specialization_score: Option<i32>,
+    /// Stake the engine locked for the submission
+    pub stake_amount: i64,
+    /// The engine's prediction of how the other engines vote
+    pub prediction: Option<VerdictShares>,
+    /// The engine is new and has yet to complete its calibration bounties;
+    /// its vote counts for less and its reward share is capped
+    #[serde(default)]
+    pub probation: bool,
     pub submitted_at: DateTime<Utc>,
 }
 
+impl SubmissionVote {
+    /// Reputation the vote carries: the engine's specialization score in the
+    /// bounty's categories, its global score without one
+    pub fn weighted_reputation(&self) -> i32 {
+        self.specialization_score.unwrap_or(self.reputation_score)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VerdictDistribution {
     pub malicious: VoteStats,
@@ -89,30 +287,15 @@ pub struct VoteStats {
     pub voters: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
-pub struct Dispute {
-    pub id: Uuid,
-    pub bounty_id: Uuid,
-    pub submission_id: Option<Uuid>,
-    pub initiator_id: Uuid,
-    pub disputed_verdict: String,
-    pub claimed_verdict: String,
-    pub reason: String,
-    pub evidence: Option<serde_json::Value>,
-    pub status: String,
-    pub resolution: Option<String>,
-    pub resolved_by: Option<Uuid>,
-    pub resolved_at: Option<DateTime<Utc>>,
-    pub created_at: DateTime<Utc>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "snake_case")]
-pub enum DisputeStatus {
-    Open,
-    UnderReview,
-    Resolved,
-    Rejected,
+/// The weight a vote carried in consensus, and the factors behind it. The
+/// factors depend on the algorithm, e.g. `reputation`, `confidence` and
+/// `time` for reputation-weighted consensus.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoteWeight {
+    pub engine_id: String,
+    pub verdict: Verdict,
+    pub factors: BTreeMap<String, Decimal>,
+    pub weight: Decimal,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -121,33 +304,232 @@ pub struct ConsensusCalculationRequest {
     pub force_recalculate: Option<bool>,
 }
 
+/// An engine's verdict on a bounty, forwarded by the bounty-manager when the
+/// engine submits
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecordSubmissionRequest {
+    pub engine_id: String,
+    pub verdict: Verdict,
+    /// 0.0 to 1.0
+    pub confidence: f64,
+    /// The engine's reputation when it submitted, used for weighted voting
+    #[serde(default)]
+    pub reputation_score: i32,
+    /// The engine's reputation in the threat categories of the bounty's
+    /// tags, weighted instead of `reputation_score` when present
+    #[serde(default)]
+    pub specialization_score: Option<i32>,
+    /// Stake the engine locked for the submission, used for stake-weighted
+    /// consensus
+    #[serde(default)]
+    pub stake_amount: i64,
+    /// The engine's prediction of how the other engines vote, used for
+    /// Bayesian truth serum consensus
+    #[serde(default)]
+    pub prediction: Option<VerdictShares>,
+    /// The bounty's consensus algorithm; the service default if never given
+    #[serde(default)]
+    pub algorithm: Option<AlgorithmKind>,
+    /// Where the engine submitted from, for collusion detection
+    #[serde(default)]
+    pub source_ip: Option<String>,
+    /// Wallet the engine staked from, for collusion detection
+    #[serde(default)]
+    pub wallet_address: Option<String>,
+    /// Text of the engine's analysis, compared for copied analyses
+    #[serde(default)]
+    pub analysis_text: Option<String>,
+    /// What the engine saw of the artifact, for relating it to others
+    #[serde(default)]
+    pub indicators: Option<ArtifactIndicators>,
+    /// The artifact the bounty asks engines to analyze, matched against the
+    /// capabilities of registered engines
+    #[serde(default)]
+    pub artifact: Option<ArtifactProfile>,
+    /// Opens the engine's commitment on commit-reveal bounties
+    #[serde(default)]
+    pub nonce: Option<String>,
+    /// The engine is on probation until it completes its calibration bounties
+    #[serde(default)]
+    pub probation: bool,
+    /// The known verdict of a calibration bounty, which the bounty settles on
+    /// instead of the consensus
+    #[serde(default)]
+    pub ground_truth: Option<Verdict>,
+}
+
+/// An engine's commitment to a verdict on a commit-reveal bounty, forwarded
+/// by the bounty-manager when the engine commits
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecordCommitmentRequest {
+    pub engine_id: String,
+    /// Hex SHA-256 of "<verdict>:<nonce>"
+    pub commitment: String,
+    /// When the submission window closes; the verdict is not accepted before
+    pub reveal_after: DateTime<Utc>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ConsensusResponse {
     pub bounty_id: Uuid,
+    pub algorithm: AlgorithmKind,
+    pub quorum: QuorumRules,
     pub final_verdict: Verdict,
     pub confidence_score: Decimal,
     pub agreement_score: Decimal,
     pub verdict_distribution: VerdictDistribution,
+    /// Weights applied to each vote
+    pub vote_weights: Vec<VoteWeight>,
+    /// Votes dropped or down-weighted before aggregation
+    pub filtered_votes: Vec<FilterDecision>,
     pub total_submissions: usize,
     pub is_finalized: bool,
     pub can_be_disputed: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct CreateDisputeRequest {
+/// A bounty's consensus as it builds, streamed to those watching it. Never
+/// includes individual votes, and the verdict only once final.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsensusProgress {
     pub bounty_id: Uuid,
-    pub submission_id: Option<Uuid>,
-    pub disputed_verdict: Verdict,
-    pub claimed_verdict: Verdict,
-    pub reason: String,
-    pub evidence: Option<serde_json::Value>,
+    pub quorum: QuorumRules,
+    pub total_submissions: usize,
+    pub total_stake: i64,
+    /// Share of the vote weight behind each verdict; withheld while there
+    /// are too few votes to keep any one of them from being read off it
+    pub distribution: Option<VerdictShares>,
+    pub agreement_score: Decimal,
+    pub consensus_reached: bool,
+    pub is_finalized: bool,
+    pub final_verdict: Option<Verdict>,
+    pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ResolveDisputeRequest {
-    pub resolution: String,
+/// A vote dropped or down-weighted before aggregation, and why
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterDecision {
+    pub engine_id: String,
+    pub action: FilterAction,
+    pub reason: FilterReason,
+    /// Weight left to the vote; 0 for dropped votes
+    pub factor: Decimal,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterAction {
+    Dropped,
+    DownWeighted,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterReason {
+    /// The engine called too few finalized bounties right
+    LowAccuracy,
+    /// Confidence outside the range of a calibrated estimate
+    ImplausibleConfidence,
+    /// Confidence well above the engine's accuracy
+    Overconfident,
+    /// Another engine of the same operator already voted
+    DuplicateIdentity,
+}
+
+impl FilterReason {
+    /// Name of the weight factor a down-weighted vote carries
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FilterReason::LowAccuracy => "low_accuracy",
+            FilterReason::ImplausibleConfidence => "implausible_confidence",
+            FilterReason::Overconfident => "overconfident",
+            FilterReason::DuplicateIdentity => "duplicate_identity",
+        }
+    }
+}
+
+/// The weight behind each counted vote and the votes filtered out, as
+/// recorded in `BountyConsensus::weighted_votes`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WeightedVotes {
+    pub votes: Vec<VoteWeight>,
+    pub filtered: Vec<FilterDecision>,
+}
+
+/// How a bounty's consensus was derived from its votes, for disputes and
+/// audits
+#[derive(Debug, Serialize)]
+pub struct ConsensusExplanation {
+    pub bounty_id: Uuid,
+    pub algorithm: AlgorithmKind,
+    pub quorum: QuorumRules,
+    /// Share of the vote weight, 0 to 100, a verdict needs to stand
+    pub threshold: Decimal,
     pub final_verdict: Verdict,
-    pub compensation: Option<Decimal>,
+    pub agreement_score: Decimal,
+    pub consensus_reached: bool,
+    /// Why the final verdict won
+    pub reason: String,
+    pub total_stake: i64,
+    /// Votes dropped or down-weighted before aggregation
+    pub filtered: Vec<FilterDecision>,
+    /// Verdicts by the weight behind them, heaviest first
+    pub verdicts: Vec<VerdictTally>,
+    pub votes: Vec<VoteExplanation>,
+    /// The stored final result; the derivation above is recalculated from
+    /// the votes as they are now
+    pub finalized: Option<FinalizedResult>,
+}
+
+/// The votes and weight behind one verdict
+#[derive(Debug, Serialize)]
+pub struct VerdictTally {
+    pub verdict: Verdict,
+    pub votes: usize,
+    pub weight: Decimal,
+    /// Share of the total vote weight, 0 to 100
+    pub share: Decimal,
+}
+
+/// One vote, the inputs to its weight and how it compares with the others
+#[derive(Debug, Serialize)]
+pub struct VoteExplanation {
+    pub engine_id: String,
+    pub verdict: Verdict,
+    pub confidence: Decimal,
+    pub reputation_score: i32,
+    pub specialization_score: Option<i32>,
+    pub stake_amount: i64,
+    pub submitted_at: DateTime<Utc>,
+    /// The algorithm's weight components, e.g. `reputation`, `confidence`
+    /// and `time`, plus `collusion` for penalized engines
+    pub factors: BTreeMap<String, Decimal>,
+    pub weight: Decimal,
+    /// Share of the total vote weight, 0 to 100
+    pub share: Decimal,
+    /// Why the vote stands out; empty for ordinary votes
+    pub outlier: Vec<OutlierReason>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OutlierReason {
+    /// Voted against the final verdict
+    Dissent,
+    /// Carried less than half the median vote weight
+    LowWeight,
+    /// Weight was reduced for suspected collusion
+    CollusionPenalty,
+}
+
+/// A bounty's result as finalized
+#[derive(Debug, Serialize)]
+pub struct FinalizedResult {
+    pub verdict: Option<Verdict>,
+    pub agreement_score: Option<Decimal>,
+    pub consensus_reached: bool,
+    pub reason: Option<FinalizeReason>,
+    pub finalized_at: DateTime<Utc>,
 }
 
 impl Default for VerdictDistribution {
@@ -0,0 +1,282 @@
+//! Batch recalculation of consensus results.
+//!
+//! After a bug in the scoring is found and fixed, every bounty it may have
+//! touched needs its result recalculated. An admin picks the bounties by when
+//! they were first voted on, the algorithm their result used and an engine
+//! that voted on them; a worker then recalculates them a chunk at a time,
+//! recording what changed for each. Open bounties get their provisional
+//! result updated. Final results stand, as payouts were settled on them, and
+//! are replayed from their snapshot instead, so the bounties whose verdict
+//! would change can be taken up through disputes.
+
+pub mod service;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+use uuid::Uuid;
+
+pub use service::RecalculationService;
+
+use crate::aggregation::replay::ReplayDifference;
+use crate::models::AlgorithmKind;
+
+#[derive(Debug, Error)]
+pub enum RecalculationError {
+    #[error("{0}")]
+    Validation(String),
+
+    #[error("{0}")]
+    NotFound(String),
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// What recalculating one bounty did
+#[derive(Debug, Clone)]
+pub enum Recalculation {
+    /// The open bounty's provisional result was updated, changing these
+    Updated(Vec<ReplayDifference>),
+    /// The final result stands; replaying its snapshot changed these
+    Replayed(Vec<ReplayDifference>),
+    /// Finalized before snapshots were kept, so it cannot be replayed
+    NoSnapshot,
+}
+
+/// Which bounties to recalculate. Criteria left out match every bounty, but
+/// at least one must be given.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecalculationFilter {
+    /// Bounties first voted on at or after this time
+    pub from: Option<DateTime<Utc>>,
+    /// Bounties first voted on before this time
+    pub to: Option<DateTime<Utc>>,
+    /// Bounties whose result used this algorithm
+    pub algorithm: Option<AlgorithmKind>,
+    /// Bounties this engine voted on
+    pub engine_id: Option<String>,
+}
+
+impl RecalculationFilter {
+    pub fn validate(&self) -> Result<(), RecalculationError> {
+        if self.from.is_none() && self.to.is_none() && self.algorithm.is_none() && self.engine_id.is_none() {
+            return Err(RecalculationError::Validation(
+                "Give a date range, algorithm or engine to pick the bounties to recalculate".to_string(),
+            ));
+        }
+        if let (Some(from), Some(to)) = (self.from, self.to) {
+            if from >= to {
+                return Err(RecalculationError::Validation("from must be before to".to_string()));
+            }
+        }
+        if self.engine_id.as_deref().is_some_and(|engine_id| engine_id.trim().is_empty()) {
+            return Err(RecalculationError::Validation("engine_id must not be empty".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BatchStatus {
+    /// Waiting for the worker to pick it up
+    Queued,
+    Running,
+    /// Every bounty in the batch was processed
+    Completed,
+}
+
+impl BatchStatus {
+    fn of(started_at: Option<DateTime<Utc>>, completed_at: Option<DateTime<Utc>>) -> Self {
+        match (started_at, completed_at) {
+            (_, Some(_)) => BatchStatus::Completed,
+            (Some(_), None) => BatchStatus::Running,
+            (None, None) => BatchStatus::Queued,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemStatus {
+    Pending,
+    /// Claimed by a worker
+    Running,
+    /// The open bounty's provisional result was updated
+    Updated,
+    /// The final result was replayed from its snapshot
+    Replayed,
+    /// The final result has no snapshot to replay
+    NoSnapshot,
+    Failed,
+}
+
+impl ItemStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ItemStatus::Pending => "pending",
+            ItemStatus::Running => "running",
+            ItemStatus::Updated => "updated",
+            ItemStatus::Replayed => "replayed",
+            ItemStatus::NoSnapshot => "no_snapshot",
+            ItemStatus::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(ItemStatus::Pending),
+            "running" => Some(ItemStatus::Running),
+            "updated" => Some(ItemStatus::Updated),
+            "replayed" => Some(ItemStatus::Replayed),
+            "no_snapshot" => Some(ItemStatus::NoSnapshot),
+            "failed" => Some(ItemStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+impl Recalculation {
+    pub fn status(&self) -> ItemStatus {
+        match self {
+            Recalculation::Updated(_) => ItemStatus::Updated,
+            Recalculation::Replayed(_) => ItemStatus::Replayed,
+            Recalculation::NoSnapshot => ItemStatus::NoSnapshot,
+        }
+    }
+
+    pub fn differences(&self) -> &[ReplayDifference] {
+        match self {
+            Recalculation::Updated(differences) | Recalculation::Replayed(differences) => differences,
+            Recalculation::NoSnapshot => &[],
+        }
+    }
+}
+
+/// How far a batch has got
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct BatchProgress {
+    pub total: i64,
+    /// Bounties processed, whatever the outcome
+    pub processed: i64,
+    pub updated: i64,
+    pub replayed: i64,
+    pub no_snapshot: i64,
+    pub failed: i64,
+    /// Processed bounties whose result came out differently
+    pub changed: i64,
+    /// Share of the batch processed, 0 to 100
+    pub percent: f64,
+}
+
+impl BatchProgress {
+    /// Progress from the number of bounties in each status, and how many of
+    /// them changed
+    pub fn from_counts(counts: &[(ItemStatus, i64)], changed: i64) -> Self {
+        let mut progress = BatchProgress {
+            changed,
+            ..Default::default()
+        };
+        for &(status, count) in counts {
+            progress.total += count;
+            match status {
+                ItemStatus::Pending | ItemStatus::Running => continue,
+                ItemStatus::Updated => progress.updated += count,
+                ItemStatus::Replayed => progress.replayed += count,
+                ItemStatus::NoSnapshot => progress.no_snapshot += count,
+                ItemStatus::Failed => progress.failed += count,
+            }
+            progress.processed += count;
+        }
+        if progress.total > 0 {
+            progress.percent = (progress.processed as f64 * 10000.0 / progress.total as f64).round() / 100.0;
+        }
+        progress
+    }
+}
+
+/// A bounty in a batch whose result changed, or that failed
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchItem {
+    pub bounty_id: Uuid,
+    pub status: ItemStatus,
+    pub differences: Value,
+    pub error: Option<String>,
+    pub processed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecalculationBatch {
+    pub id: Uuid,
+    pub filter: RecalculationFilter,
+    pub status: BatchStatus,
+    pub requested_by: Uuid,
+    pub progress: BatchProgress,
+    /// Bounties whose result changed or that failed, as far as processed
+    pub items: Vec<BatchItem>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_filter_needs_a_criterion_and_an_ordered_range() {
+        assert!(RecalculationFilter::default().validate().is_err());
+
+        let now = Utc::now();
+        let backwards = RecalculationFilter {
+            from: Some(now),
+            to: Some(now - Duration::days(1)),
+            ..Default::default()
+        };
+        assert!(backwards.validate().is_err());
+
+        let blank_engine = RecalculationFilter {
+            engine_id: Some("  ".to_string()),
+            ..Default::default()
+        };
+        assert!(blank_engine.validate().is_err());
+
+        let by_algorithm = RecalculationFilter {
+            algorithm: Some(AlgorithmKind::StakeWeighted),
+            ..Default::default()
+        };
+        assert!(by_algorithm.validate().is_ok());
+    }
+
+    #[test]
+    fn test_progress_counts_finished_bounties() {
+        let progress = BatchProgress::from_counts(
+            &[
+                (ItemStatus::Pending, 5),
+                (ItemStatus::Running, 1),
+                (ItemStatus::Updated, 2),
+                (ItemStatus::Replayed, 3),
+                (ItemStatus::Failed, 1),
+            ],
+            2,
+        );
+
+        assert_eq!(progress.total, 12);
+        assert_eq!(progress.processed, 6);
+        assert_eq!(progress.replayed, 3);
+        assert_eq!(progress.changed, 2);
+        assert_eq!(progress.percent, 50.0);
+        assert_eq!(BatchProgress::from_counts(&[], 0).percent, 0.0);
+    }
+
+    #[test]
+    fn test_batch_status_follows_its_timestamps() {
+        let now = Some(Utc::now());
+        assert_eq!(BatchStatus::of(None, None), BatchStatus::Queued);
+        assert_eq!(BatchStatus::of(now, None), BatchStatus::Running);
+        assert_eq!(BatchStatus::of(now, now), BatchStatus::Completed);
+    }
+}
@@ -0,0 +1,296 @@
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use super::{
+    BatchItem, BatchProgress, BatchStatus, ItemStatus, RecalculationBatch, RecalculationError,
+    RecalculationFilter,
+};
+use crate::config::RecalculationConfig;
+use crate::services::consensus_service::ConsensusService;
+
+/// Most changed or failed bounties listed with a batch
+const MAX_LISTED_ITEMS: i64 = 500;
+
+type Result<T> = std::result::Result<T, RecalculationError>;
+
+#[derive(Debug, sqlx::FromRow)]
+struct BatchRow {
+    id: Uuid,
+    filter: String,
+    requested_by: Uuid,
+    created_at: DateTime<Utc>,
+    started_at: Option<DateTime<Utc>>,
+    completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct CountRow {
+    status: String,
+    count: i64,
+    changed: i64,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct ItemRow {
+    bounty_id: Uuid,
+    status: String,
+    differences: Option<String>,
+    error: Option<String>,
+    processed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct ClaimedRow {
+    batch_id: Uuid,
+    bounty_id: Uuid,
+}
+
+pub struct RecalculationService {
+    config: RecalculationConfig,
+    db_pool: PgPool,
+    consensus: Arc<ConsensusService>,
+}
+
+impl RecalculationService {
+    pub fn new(config: RecalculationConfig, db_pool: PgPool, consensus: Arc<ConsensusService>) -> Self {
+        Self {
+            config,
+            db_pool,
+            consensus,
+        }
+    }
+
+    /// Queue every bounty matching the filter for recalculation
+    pub async fn create(&self, filter: RecalculationFilter, requested_by: Uuid) -> Result<RecalculationBatch> {
+        filter.validate()?;
+        let bounty_ids: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT s.bounty_id
+            FROM consensus_submissions s
+            LEFT JOIN consensus_results r ON r.bounty_id = s.bounty_id
+            GROUP BY s.bounty_id, r.algorithm
+            HAVING ($1::timestamptz IS NULL OR MIN(s.submitted_at) >= $1)
+               AND ($2::timestamptz IS NULL OR MIN(s.submitted_at) < $2)
+               AND ($3::text IS NULL OR r.algorithm = $3)
+               AND ($4::text IS NULL OR BOOL_OR(s.engine_id = $4))
+            ORDER BY MIN(s.submitted_at)
+            LIMIT $5
+            "#,
+        )
+        .bind(filter.from)
+        .bind(filter.to)
+        .bind(filter.algorithm.map(|algorithm| algorithm.as_str()))
+        .bind(filter.engine_id.as_deref())
+        .bind(self.config.max_bounties as i64 + 1)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        if bounty_ids.is_empty() {
+            return Err(RecalculationError::Validation("No bounties match the filter".to_string()));
+        }
+        if bounty_ids.len() > self.config.max_bounties {
+            return Err(RecalculationError::Validation(format!(
+                "More than {} bounties match the filter; narrow it down",
+                self.config.max_bounties
+            )));
+        }
+
+        let mut tx = self.db_pool.begin().await?;
+        let batch_id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO consensus_recalculation_batches (filter, requested_by)
+            VALUES ($1::JSONB, $2)
+            RETURNING id
+            "#,
+        )
+        .bind(serde_json::json!(filter).to_string())
+        .bind(requested_by)
+        .fetch_one(&mut *tx)
+        .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO consensus_recalculation_items (batch_id, bounty_id)
+            SELECT $1, UNNEST($2::uuid[])
+            "#,
+        )
+        .bind(batch_id)
+        .bind(&bounty_ids)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        info!(
+            "Queued {} bounties for recalculation in batch {} by {}",
+            bounty_ids.len(),
+            batch_id,
+            requested_by
+        );
+        self.get(batch_id).await
+    }
+
+    /// A batch with its progress and the bounties that changed or failed
+    pub async fn get(&self, batch_id: Uuid) -> Result<RecalculationBatch> {
+        let batch: BatchRow = sqlx::query_as(
+            r#"
+            SELECT id, filter::text AS filter, requested_by, created_at, started_at, completed_at
+            FROM consensus_recalculation_batches
+            WHERE id = $1
+            "#,
+        )
+        .bind(batch_id)
+        .fetch_optional(&self.db_pool)
+        .await?
+        .ok_or_else(|| RecalculationError::NotFound(format!("Recalculation batch {} not found", batch_id)))?;
+
+        let counts: Vec<CountRow> = sqlx::query_as(
+            r#"
+            SELECT status, COUNT(*) AS count,
+                   COUNT(*) FILTER (WHERE jsonb_array_length(COALESCE(differences, '[]'::jsonb)) > 0) AS changed
+            FROM consensus_recalculation_items
+            WHERE batch_id = $1
+            GROUP BY status
+            "#,
+        )
+        .bind(batch_id)
+        .fetch_all(&self.db_pool)
+        .await?;
+        let changed = counts.iter().map(|row| row.changed).sum();
+        let counts: Vec<(ItemStatus, i64)> = counts
+            .iter()
+            .filter_map(|row| Some((ItemStatus::parse(&row.status)?, row.count)))
+            .collect();
+
+        let items: Vec<ItemRow> = sqlx::query_as(
+            r#"
+            SELECT bounty_id, status, differences::text AS differences, error, processed_at
+            FROM consensus_recalculation_items
+            WHERE batch_id = $1
+              AND (status = 'failed' OR jsonb_array_length(COALESCE(differences, '[]'::jsonb)) > 0)
+            ORDER BY processed_at
+            LIMIT $2
+            "#,
+        )
+        .bind(batch_id)
+        .bind(MAX_LISTED_ITEMS)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(RecalculationBatch {
+            id: batch.id,
+            filter: serde_json::from_str(&batch.filter).unwrap_or_default(),
+            status: BatchStatus::of(batch.started_at, batch.completed_at),
+            requested_by: batch.requested_by,
+            progress: BatchProgress::from_counts(&counts, changed),
+            items: items
+                .into_iter()
+                .filter_map(|row| {
+                    Some(BatchItem {
+                        bounty_id: row.bounty_id,
+                        status: ItemStatus::parse(&row.status)?,
+                        differences: row
+                            .differences
+                            .and_then(|differences| serde_json::from_str(&differences).ok())
+                            .unwrap_or_default(),
+                        error: row.error,
+                        processed_at: row.processed_at,
+                    })
+                })
+                .collect(),
+            created_at: batch.created_at,
+            started_at: batch.started_at,
+            completed_at: batch.completed_at,
+        })
+    }
+
+    /// Recalculate the next chunk of queued bounties, oldest batch first.
+    /// Bounties claimed by a worker that stopped are claimed again once the
+    /// claim times out. Returns how many bounties were processed.
+    pub async fn process(&self) -> Result<usize> {
+        let stale = Utc::now() - Duration::seconds(self.config.claim_timeout_secs);
+        let claimed: Vec<ClaimedRow> = sqlx::query_as(
+            r#"
+            UPDATE consensus_recalculation_items i
+            SET status = 'running', claimed_at = NOW()
+            FROM (
+                SELECT i.batch_id, i.bounty_id
+                FROM consensus_recalculation_items i
+                JOIN consensus_recalculation_batches b ON b.id = i.batch_id
+                WHERE i.status = 'pending' OR (i.status = 'running' AND i.claimed_at < $2)
+                ORDER BY b.created_at, i.bounty_id
+                LIMIT $1
+                FOR UPDATE OF i SKIP LOCKED
+            ) next
+            WHERE i.batch_id = next.batch_id AND i.bounty_id = next.bounty_id
+            RETURNING i.batch_id, i.bounty_id
+            "#,
+        )
+        .bind(self.config.chunk_size)
+        .bind(stale)
+        .fetch_all(&self.db_pool)
+        .await?;
+        if claimed.is_empty() {
+            return Ok(0);
+        }
+
+        let batch_ids: Vec<Uuid> = claimed.iter().map(|row| row.batch_id).collect();
+        sqlx::query(
+            "UPDATE consensus_recalculation_batches SET started_at = COALESCE(started_at, NOW()) WHERE id = ANY($1)",
+        )
+        .bind(&batch_ids)
+        .execute(&self.db_pool)
+        .await?;
+
+        for row in &claimed {
+            let (status, differences, error) = match self.consensus.recalculate(row.bounty_id).await {
+                Ok(recalculation) => (
+                    recalculation.status(),
+                    serde_json::to_string(recalculation.differences()).ok(),
+                    None,
+                ),
+                Err(e) => {
+                    warn!("Failed to recalculate consensus for bounty {}: {}", row.bounty_id, e);
+                    (ItemStatus::Failed, None, Some(e.to_string()))
+                }
+            };
+            sqlx::query(
+                r#"
+                UPDATE consensus_recalculation_items
+                SET status = $3, differences = $4::JSONB, error = $5, processed_at = NOW()
+                WHERE batch_id = $1 AND bounty_id = $2
+                "#,
+            )
+            .bind(row.batch_id)
+            .bind(row.bounty_id)
+            .bind(status.as_str())
+            .bind(differences)
+            .bind(error)
+            .execute(&self.db_pool)
+            .await?;
+        }
+
+        let completed: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            UPDATE consensus_recalculation_batches b
+            SET completed_at = NOW()
+            WHERE b.id = ANY($1)
+              AND b.completed_at IS NULL
+              AND NOT EXISTS (
+                  SELECT 1 FROM consensus_recalculation_items i
+                  WHERE i.batch_id = b.id AND i.status IN ('pending', 'running')
+              )
+            RETURNING b.id
+            "#,
+        )
+        .bind(&batch_ids)
+        .fetch_all(&self.db_pool)
+        .await?;
+        for batch_id in completed {
+            info!("Recalculation batch {} completed", batch_id);
+        }
+
+        Ok(claimed.len())
+    }
+}
@@ -0,0 +1,403 @@
+//! Registry of automated engines.
+//!
+//! Automated engines register what they can analyze: the file types they
+//! handle, the largest file they take and how long they usually take to
+//! vote. While running they send heartbeats, saying whether they are taking
+//! work. Matched against a bounty's artifact, this tells an engine that is
+//! offline apart from one that declined to vote, and shows whether the
+//! engines still to vote can make up the bounty's quorum.
+
+pub mod service;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use thiserror::Error;
+use uuid::Uuid;
+
+pub use service::RegistryService;
+
+/// Most file types an engine may register
+const MAX_FILE_TYPES: usize = 100;
+
+#[derive(Debug, Error)]
+pub enum RegistryError {
+    #[error("{0}")]
+    Validation(String),
+
+    #[error("{0}")]
+    NotFound(String),
+
+    #[error("{0}")]
+    Forbidden(String),
+
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+}
+
+/// What an engine can analyze, as it registers it
+#[derive(Debug, Clone, Deserialize)]
+pub struct RegisterEngineRequest {
+    /// MIME types the engine handles; "application/*" covers a whole type
+    /// and "*/*" every artifact
+    pub file_types: Vec<String>,
+    /// Largest artifact in bytes the engine takes; no limit if not given
+    #[serde(default)]
+    pub max_file_size: Option<i64>,
+    /// How long after a bounty's first vote the engine usually votes
+    #[serde(default)]
+    pub expected_latency_secs: Option<i64>,
+}
+
+impl RegisterEngineRequest {
+    /// The request with its file types trimmed, lowercased and deduplicated
+    pub fn normalized(mut self) -> Result<Self, RegistryError> {
+        let mut seen = HashSet::new();
+        self.file_types = self
+            .file_types
+            .iter()
+            .map(|file_type| file_type.trim().to_ascii_lowercase())
+            .filter(|file_type| !file_type.is_empty() && seen.insert(file_type.clone()))
+            .collect();
+
+        if self.file_types.is_empty() || self.file_types.len() > MAX_FILE_TYPES {
+            return Err(RegistryError::Validation(format!(
+                "Register between 1 and {} file types",
+                MAX_FILE_TYPES
+            )));
+        }
+        if let Some(invalid) = self.file_types.iter().find(|file_type| !is_mime_pattern(file_type)) {
+            return Err(RegistryError::Validation(format!(
+                "{} is not a MIME type such as application/pdf or application/*",
+                invalid
+            )));
+        }
+        if self.max_file_size.is_some_and(|size| size <= 0) {
+            return Err(RegistryError::Validation("max_file_size must be positive".to_string()));
+        }
+        if self.expected_latency_secs.is_some_and(|secs| secs <= 0) {
+            return Err(RegistryError::Validation("expected_latency_secs must be positive".to_string()));
+        }
+        Ok(self)
+    }
+}
+
+fn is_mime_pattern(value: &str) -> bool {
+    match value.split_once('/') {
+        Some((kind, subtype)) => {
+            let token = |part: &str| {
+                !part.is_empty()
+                    && part
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || "!#$&^_.+-".contains(c))
+            };
+            (kind == "*" && subtype == "*") || (token(kind) && (subtype == "*" || token(subtype)))
+        }
+        None => false,
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct HeartbeatRequest {
+    /// Whether the engine is taking work; unchanged if not given
+    #[serde(default)]
+    pub accepting_work: Option<bool>,
+}
+
+/// A registered engine
+#[derive(Debug, Clone, Serialize)]
+pub struct RegisteredEngine {
+    pub engine_id: String,
+    pub owner_id: Uuid,
+    pub file_types: Vec<String>,
+    pub max_file_size: Option<i64>,
+    pub expected_latency_secs: Option<i64>,
+    /// Whether the engine said it is taking work in its last heartbeat
+    pub accepting_work: bool,
+    pub last_heartbeat_at: Option<DateTime<Utc>>,
+    /// Whether a heartbeat arrived within the timeout
+    pub online: bool,
+    pub registered_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl RegisteredEngine {
+    /// Whether the engine can analyze the artifact. Whatever is not known of
+    /// the artifact does not rule the engine out.
+    pub fn handles(&self, artifact: &ArtifactProfile) -> bool {
+        let size_ok = match (self.max_file_size, artifact.file_size) {
+            (Some(max), Some(size)) => size <= max,
+            _ => true,
+        };
+        let type_ok = match artifact.file_type.as_deref() {
+            Some(file_type) => {
+                let file_type = file_type.trim().to_ascii_lowercase();
+                let kind = file_type.split('/').next().unwrap_or_default();
+                self.file_types.iter().any(|pattern| {
+                    pattern == "*/*"
+                        || *pattern == file_type
+                        || pattern.strip_suffix("/*").is_some_and(|pattern_kind| pattern_kind == kind)
+                })
+            }
+            None => true,
+        };
+        size_ok && type_ok
+    }
+}
+
+/// Whether an engine last heard from at `last_heartbeat_at` counts as online
+pub fn is_online(last_heartbeat_at: Option<DateTime<Utc>>, now: DateTime<Utc>, timeout: Duration) -> bool {
+    last_heartbeat_at.is_some_and(|at| now - at <= timeout)
+}
+
+/// The artifact a bounty asks engines to analyze, as forwarded by the
+/// bounty-manager with its submissions
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ArtifactProfile {
+    /// MIME type
+    #[serde(default)]
+    pub file_type: Option<String>,
+    /// Size in bytes
+    #[serde(default)]
+    pub file_size: Option<i64>,
+}
+
+/// Where a registered engine stands on a bounty
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EngineStatus {
+    Voted,
+    /// Its capabilities do not cover the artifact
+    Ineligible,
+    /// No heartbeat within the timeout
+    Offline,
+    /// Online but not taking work, or its expected latency passed without
+    /// a vote
+    Declined,
+    /// Online and within its expected latency
+    Pending,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EngineAvailability {
+    pub engine_id: String,
+    pub status: EngineStatus,
+}
+
+/// Where each registered engine stands on a bounty. A vote counts whatever
+/// the engine's state now; engines that have not voted are checked against
+/// the artifact, then their heartbeat, then whether they are taking work and
+/// still within their expected latency of the bounty's first vote.
+pub fn availability(
+    engines: &[RegisteredEngine],
+    artifact: &ArtifactProfile,
+    voted: &HashSet<String>,
+    first_submitted: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+    heartbeat_timeout: Duration,
+) -> Vec<EngineAvailability> {
+    engines
+        .iter()
+        .map(|engine| {
+            let overdue = match (engine.expected_latency_secs, first_submitted) {
+                (Some(latency), Some(first)) => now - first > Duration::seconds(latency),
+                _ => false,
+            };
+            let status = if voted.contains(&engine.engine_id) {
+                EngineStatus::Voted
+            } else if !engine.handles(artifact) {
+                EngineStatus::Ineligible
+            } else if !is_online(engine.last_heartbeat_at, now, heartbeat_timeout) {
+                EngineStatus::Offline
+            } else if !engine.accepting_work || overdue {
+                EngineStatus::Declined
+            } else {
+                EngineStatus::Pending
+            };
+            EngineAvailability {
+                engine_id: engine.engine_id.clone(),
+                status,
+            }
+        })
+        .collect()
+}
+
+/// How many registered engines are in each state
+#[derive(Debug, Clone, Default, Serialize, PartialEq, Eq)]
+pub struct AvailabilityCounts {
+    pub voted: usize,
+    pub ineligible: usize,
+    pub offline: usize,
+    pub declined: usize,
+    pub pending: usize,
+}
+
+impl AvailabilityCounts {
+    pub fn of(engines: &[EngineAvailability]) -> Self {
+        let mut counts = AvailabilityCounts::default();
+        for engine in engines {
+            match engine.status {
+                EngineStatus::Voted => counts.voted += 1,
+                EngineStatus::Ineligible => counts.ineligible += 1,
+                EngineStatus::Offline => counts.offline += 1,
+                EngineStatus::Declined => counts.declined += 1,
+                EngineStatus::Pending => counts.pending += 1,
+            }
+        }
+        counts
+    }
+}
+
+/// The registered engines' standing on a bounty
+#[derive(Debug, Clone, Serialize)]
+pub struct BountyAvailability {
+    pub bounty_id: Uuid,
+    pub artifact: ArtifactProfile,
+    /// Votes so far, from registered engines or not
+    pub submissions: usize,
+    pub min_submissions: usize,
+    /// Whether the votes so far and those of pending engines can make up
+    /// the quorum's minimum submissions
+    pub quorum_reachable: bool,
+    pub counts: AvailabilityCounts,
+    pub engines: Vec<EngineAvailability>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn engine(engine_id: &str, file_types: &[&str]) -> RegisteredEngine {
+        let now = Utc::now();
+        RegisteredEngine {
+            engine_id: engine_id.to_string(),
+            owner_id: Uuid::new_v4(),
+            file_types: file_types.iter().map(|t| t.to_string()).collect(),
+            max_file_size: None,
+            expected_latency_secs: None,
+            accepting_work: true,
+            last_heartbeat_at: Some(now),
+            online: true,
+            registered_at: now,
+            updated_at: now,
+        }
+    }
+
+    fn artifact(file_type: &str, file_size: i64) -> ArtifactProfile {
+        ArtifactProfile {
+            file_type: Some(file_type.to_string()),
+            file_size: Some(file_size),
+        }
+    }
+
+    #[test]
+    fn test_registration_normalizes_and_checks_file_types() {
+        let request = RegisterEngineRequest {
+            file_types: vec![" Application/PDF ".to_string(), "application/pdf".to_string(), "image/*".to_string()],
+            max_file_size: Some(1 << 20),
+            expected_latency_secs: Some(60),
+        };
+        let normalized = request.normalized().unwrap();
+        assert_eq!(normalized.file_types, vec!["application/pdf", "image/*"]);
+
+        for file_types in [vec![], vec!["pdf"], vec!["*/pdf"], vec!["application/ pdf"]] {
+            let request = RegisterEngineRequest {
+                file_types: file_types.into_iter().map(String::from).collect(),
+                max_file_size: None,
+                expected_latency_secs: None,
+            };
+            assert!(request.normalized().is_err());
+        }
+
+        let zero_size = RegisterEngineRequest {
+            file_types: vec!["*/*".to_string()],
+            max_file_size: Some(0),
+            expected_latency_secs: None,
+        };
+        assert!(zero_size.normalized().is_err());
+    }
+
+    #[test]
+    fn test_engine_handles_matching_types_within_its_size() {
+        let mut pe = engine("pe", &["application/x-dosexec", "application/x-msdownload"]);
+        pe.max_file_size = Some(100);
+        assert!(pe.handles(&artifact("application/x-dosexec", 100)));
+        assert!(!pe.handles(&artifact("application/x-dosexec", 101)));
+        assert!(!pe.handles(&artifact("application/pdf", 10)));
+        assert!(pe.handles(&ArtifactProfile::default()));
+
+        let documents = engine("documents", &["application/*"]);
+        assert!(documents.handles(&artifact("Application/PDF", 10)));
+        assert!(!documents.handles(&artifact("image/png", 10)));
+        assert!(engine("any", &["*/*"]).handles(&artifact("image/png", 10)));
+    }
+
+    #[test]
+    fn test_availability_tells_offline_from_declined() {
+        let now = Utc::now();
+        let first = now - Duration::minutes(10);
+        let timeout = Duration::minutes(2);
+
+        let voted_then_offline = RegisteredEngine {
+            last_heartbeat_at: Some(now - Duration::hours(1)),
+            ..engine("voted", &["*/*"])
+        };
+        let offline = RegisteredEngine {
+            last_heartbeat_at: Some(now - Duration::minutes(5)),
+            ..engine("offline", &["*/*"])
+        };
+        let never_heard = RegisteredEngine {
+            last_heartbeat_at: None,
+            ..engine("never", &["*/*"])
+        };
+        let busy = RegisteredEngine {
+            accepting_work: false,
+            ..engine("busy", &["*/*"])
+        };
+        let slow = RegisteredEngine {
+            expected_latency_secs: Some(60),
+            ..engine("slow", &["*/*"])
+        };
+        let waiting = RegisteredEngine {
+            expected_latency_secs: Some(3600),
+            ..engine("waiting", &["*/*"])
+        };
+        let images = engine("images", &["image/*"]);
+
+        let engines = vec![voted_then_offline, offline, never_heard, busy, slow, waiting, images];
+        let voted: HashSet<String> = ["voted".to_string()].into();
+        let result = availability(
+            &engines,
+            &artifact("application/pdf", 10),
+            &voted,
+            Some(first),
+            now,
+            timeout,
+        );
+        let statuses: Vec<EngineStatus> = result.iter().map(|engine| engine.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                EngineStatus::Voted,
+                EngineStatus::Offline,
+                EngineStatus::Offline,
+                EngineStatus::Declined,
+                EngineStatus::Declined,
+                EngineStatus::Pending,
+                EngineStatus::Ineligible,
+            ]
+        );
+
+        let counts = AvailabilityCounts::of(&result);
+        assert_eq!(
+            counts,
+            AvailabilityCounts {
+                voted: 1,
+                ineligible: 1,
+                offline: 2,
+                declined: 2,
+                pending: 1,
+            }
+        );
+    }
+}
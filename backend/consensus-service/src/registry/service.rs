@@ -0,0 +1,238 @@
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use std::collections::HashSet;
+use tracing::info;
+use uuid::Uuid;
+
+use super::{
+    availability, is_online, ArtifactProfile, AvailabilityCounts, BountyAvailability, EngineStatus,
+    HeartbeatRequest, RegisterEngineRequest, RegisteredEngine, RegistryError,
+};
+use crate::config::RegistryConfig;
+use crate::models::QuorumRules;
+
+/// Longest engine ID accepted
+const MAX_ENGINE_ID_LENGTH: usize = 255;
+
+type Result<T> = std::result::Result<T, RegistryError>;
+
+#[derive(Debug, sqlx::FromRow)]
+struct EngineRow {
+    engine_id: String,
+    owner_id: Uuid,
+    file_types: Vec<String>,
+    max_file_size: Option<i64>,
+    expected_latency_secs: Option<i64>,
+    accepting_work: bool,
+    last_heartbeat_at: Option<DateTime<Utc>>,
+    registered_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, sqlx::FromRow)]
+struct ArtifactRow {
+    artifact_type: Option<String>,
+    artifact_size: Option<i64>,
+}
+
+pub struct RegistryService {
+    config: RegistryConfig,
+    db_pool: PgPool,
+}
+
+impl RegistryService {
+    pub fn new(config: RegistryConfig, db_pool: PgPool) -> Self {
+        Self { config, db_pool }
+    }
+
+    fn heartbeat_timeout(&self) -> Duration {
+        Duration::seconds(self.config.heartbeat_timeout_secs)
+    }
+
+    /// Register an engine or update its capabilities. An engine stays with
+    /// the user who registered it; only they or an admin may update it.
+    pub async fn register(
+        &self,
+        engine_id: &str,
+        caller_id: Uuid,
+        is_admin: bool,
+        request: RegisterEngineRequest,
+    ) -> Result<RegisteredEngine> {
+        if engine_id.trim().is_empty() || engine_id.len() > MAX_ENGINE_ID_LENGTH {
+            return Err(RegistryError::Validation(format!(
+                "Engine ID must be between 1 and {} characters",
+                MAX_ENGINE_ID_LENGTH
+            )));
+        }
+        let request = request.normalized()?;
+
+        let row: Option<EngineRow> = sqlx::query_as(
+            r#"
+            INSERT INTO engine_registry (engine_id, owner_id, file_types, max_file_size, expected_latency_secs)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (engine_id) DO UPDATE
+            SET file_types = EXCLUDED.file_types,
+                max_file_size = EXCLUDED.max_file_size,
+                expected_latency_secs = EXCLUDED.expected_latency_secs,
+                updated_at = NOW()
+            WHERE engine_registry.owner_id = $2 OR $6
+            RETURNING engine_id, owner_id, file_types, max_file_size, expected_latency_secs, accepting_work,
+                      last_heartbeat_at, registered_at, updated_at
+            "#,
+        )
+        .bind(engine_id)
+        .bind(caller_id)
+        .bind(&request.file_types)
+        .bind(request.max_file_size)
+        .bind(request.expected_latency_secs)
+        .bind(is_admin)
+        .fetch_optional(&self.db_pool)
+        .await?;
+
+        let row = row.ok_or_else(|| {
+            RegistryError::Forbidden(format!("Engine {} is registered to another user", engine_id))
+        })?;
+        info!(
+            "Engine {} registered by {} for {} file type(s)",
+            engine_id,
+            caller_id,
+            row.file_types.len()
+        );
+        Ok(self.engine_from_row(row, Utc::now()))
+    }
+
+    /// Record that an engine is alive, and whether it is taking work
+    pub async fn heartbeat(
+        &self,
+        engine_id: &str,
+        caller_id: Uuid,
+        is_admin: bool,
+        request: &HeartbeatRequest,
+    ) -> Result<RegisteredEngine> {
+        let row: Option<EngineRow> = sqlx::query_as(
+            r#"
+            UPDATE engine_registry
+            SET last_heartbeat_at = NOW(), accepting_work = COALESCE($2, accepting_work)
+            WHERE engine_id = $1 AND (owner_id = $3 OR $4)
+            RETURNING engine_id, owner_id, file_types, max_file_size, expected_latency_secs, accepting_work,
+                      last_heartbeat_at, registered_at, updated_at
+            "#,
+        )
+        .bind(engine_id)
+        .bind(request.accepting_work)
+        .bind(caller_id)
+        .bind(is_admin)
+        .fetch_optional(&self.db_pool)
+        .await?;
+
+        match row {
+            Some(row) => Ok(self.engine_from_row(row, Utc::now())),
+            None => {
+                // Tell an unknown engine apart from someone else's
+                self.get(engine_id).await?;
+                Err(RegistryError::Forbidden(format!(
+                    "Engine {} is registered to another user",
+                    engine_id
+                )))
+            }
+        }
+    }
+
+    pub async fn get(&self, engine_id: &str) -> Result<RegisteredEngine> {
+        let row: EngineRow = sqlx::query_as(
+            r#"
+            SELECT engine_id, owner_id, file_types, max_file_size, expected_latency_secs, accepting_work,
+                   last_heartbeat_at, registered_at, updated_at
+            FROM engine_registry
+            WHERE engine_id = $1
+            "#,
+        )
+        .bind(engine_id)
+        .fetch_optional(&self.db_pool)
+        .await?
+        .ok_or_else(|| RegistryError::NotFound(format!("Engine {} is not registered", engine_id)))?;
+
+        Ok(self.engine_from_row(row, Utc::now()))
+    }
+
+    /// Every registered engine, most recently heard from first
+    pub async fn list(&self) -> Result<Vec<RegisteredEngine>> {
+        let rows: Vec<EngineRow> = sqlx::query_as(
+            r#"
+            SELECT engine_id, owner_id, file_types, max_file_size, expected_latency_secs, accepting_work,
+                   last_heartbeat_at, registered_at, updated_at
+            FROM engine_registry
+            ORDER BY last_heartbeat_at DESC NULLS LAST, engine_id
+            "#,
+        )
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        let now = Utc::now();
+        Ok(rows.into_iter().map(|row| self.engine_from_row(row, now)).collect())
+    }
+
+    /// Where each registered engine stands on a bounty, and whether the
+    /// engines still to vote can make up its quorum
+    pub async fn bounty_availability(&self, bounty_id: Uuid, quorum: &QuorumRules) -> Result<BountyAvailability> {
+        let artifact: ArtifactRow = sqlx::query_as(
+            "SELECT artifact_type, artifact_size FROM consensus_bounty_settings WHERE bounty_id = $1",
+        )
+        .bind(bounty_id)
+        .fetch_optional(&self.db_pool)
+        .await?
+        .unwrap_or_default();
+        let artifact = ArtifactProfile {
+            file_type: artifact.artifact_type,
+            file_size: artifact.artifact_size,
+        };
+
+        let votes: Vec<(String, DateTime<Utc>)> =
+            sqlx::query_as("SELECT engine_id, submitted_at FROM consensus_submissions WHERE bounty_id = $1")
+                .bind(bounty_id)
+                .fetch_all(&self.db_pool)
+                .await?;
+        let first_submitted = votes.iter().map(|(_, submitted_at)| *submitted_at).min();
+        let voted: HashSet<String> = votes.into_iter().map(|(engine_id, _)| engine_id).collect();
+
+        let engines = self.list().await?;
+        let engines = availability(
+            &engines,
+            &artifact,
+            &voted,
+            first_submitted,
+            Utc::now(),
+            self.heartbeat_timeout(),
+        );
+        let counts = AvailabilityCounts::of(&engines);
+        let pending = engines
+            .iter()
+            .filter(|engine| engine.status == EngineStatus::Pending)
+            .count();
+
+        Ok(BountyAvailability {
+            bounty_id,
+            artifact,
+            submissions: voted.len(),
+            min_submissions: quorum.min_submissions,
+            quorum_reachable: voted.len() + pending >= quorum.min_submissions,
+            counts,
+            engines,
+        })
+    }
+
+    fn engine_from_row(&self, row: EngineRow, now: DateTime<Utc>) -> RegisteredEngine {
+        RegisteredEngine {
+            online: is_online(row.last_heartbeat_at, now, self.heartbeat_timeout()),
+            engine_id: row.engine_id,
+            owner_id: row.owner_id,
+            file_types: row.file_types,
+            max_file_size: row.max_file_size,
+            expected_latency_secs: row.expected_latency_secs,
+            accepting_work: row.accepting_work,
+            last_heartbeat_at: row.last_heartbeat_at,
+            registered_at: row.registered_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
@@ -1,15 +1,159 @@
 use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use sqlx::PgPool;
+use futures::{Stream, StreamExt};
+use std::collections::HashMap;
 use redis::aio::ConnectionManager;
+use shared::messaging::{
+    BountyClosedEvent, ConsensusReachedEvent, EventPublisher, EventSignature, NexusEvent, SettlementPlan,
+    SettlementPlannedEvent,
+};
+use shared::request_signing::RequestSigner;
+use tracing::{info, warn};
+use uuid::Uuid;
 
-use crate::config::Config;
+use crate::config::{Config, SettlementConfig};
+use crate::aggregation::filtering::EngineHistory;
+use crate::aggregation::replay::{
+    self, ConsensusInputs, ConsensusOutcome, ConsensusReplay, ConsensusSettings, ReplayRequest, ReplaySettings,
+};
 use crate::aggregation::ConsensusAggregator;
+use crate::collusion;
+use crate::recalculation::Recalculation;
+use crate::settlement;
+use crate::models::{
+    AlgorithmKind, ConsensusExplanation, ConsensusProgress, ConsensusResponse, FilterDecision, FinalizeReason, FinalizedResult, QuorumRules, QuorumRulesRequest, SubmissionVote, Verdict,
+    VerdictDistribution, VerdictShares, VoteWeight, WeightedVotes,
+};
+
+/// Most open bounties checked for quorum per pass
+const AUTO_FINALIZE_BATCH: i64 = 500;
+
+/// Redis channel a bounty's consensus progress is published on
+fn progress_channel(bounty_id: Uuid) -> String {
+    format!("consensus_progress:{}", bounty_id)
+}
 
 pub struct ConsensusService {
-    config: Config,
     db_pool: PgPool,
     redis_conn: ConnectionManager,
+    redis_client: redis::Client,
     aggregator: ConsensusAggregator,
+    settings: ConsensusSettings,
+    settlement: SettlementConfig,
+    events: EventPublisher,
+    /// Signs settlement plans; unsigned when service signing keys are unset
+    signer: Option<RequestSigner>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct VoteRow {
+    id: Uuid,
+    engine_id: String,
+    verdict: String,
+    confidence: f64,
+    reputation_score: i32,
+    specialization_score: Option<i32>,
+    stake_amount: i64,
+    prediction: Option<String>,
+    probation: bool,
+    wallet_address: Option<String>,
+    submitted_at: DateTime<Utc>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct HistoryRow {
+    engine_id: String,
+    votes: i64,
+    accuracy: f64,
+}
+
+/// A finalized result, as stored
+#[derive(Debug, sqlx::FromRow)]
+struct FinalResult {
+    final_verdict: String,
+    confidence: f64,
+    agreement_score: Option<f64>,
+    total_submissions: i32,
+    consensus_reached: bool,
+    finalized_reason: Option<String>,
+    finalized_at: DateTime<Utc>,
+}
+
+/// How and when a result is being finalized
+struct Finalization {
+    reason: FinalizeReason,
+    artifact_hash: Option<String>,
+    finalized_at: DateTime<Utc>,
+}
+
+/// A result as stored, open or final
+#[derive(Debug, sqlx::FromRow)]
+struct StoredRow {
+    final_verdict: String,
+    confidence: f64,
+    agreement_score: Option<f64>,
+    consensus_reached: bool,
+    algorithm: Option<String>,
+    weighted_votes: Option<String>,
+}
+
+/// What a finalized consensus was calculated from, as stored
+#[derive(Debug, sqlx::FromRow)]
+struct SnapshotRow {
+    id: Uuid,
+    inputs: String,
+    settings: String,
+    outcome: String,
+    finalized_reason: Option<String>,
+    finalized_at: DateTime<Utc>,
+}
+
+/// A bounty's settings as stored; NULL columns use the service default
+#[derive(Debug, Default, sqlx::FromRow)]
+struct SettingsRow {
+    algorithm: Option<String>,
+    min_submissions: Option<i32>,
+    min_total_stake: Option<i64>,
+    max_wait_hours: Option<i32>,
+    /// Known verdict of a calibration bounty
+    ground_truth: Option<String>,
+}
+
+/// Consensus on a bounty's submissions so far
+struct Calculation {
+    algorithm: AlgorithmKind,
+    quorum: QuorumRules,
+    verdict: Verdict,
+    confidence: Decimal,
+    agreement_score: Decimal,
+    distribution: VerdictDistribution,
+    weights: Vec<VoteWeight>,
+    filtered: Vec<FilterDecision>,
+    submissions: usize,
+    votes: Vec<SubmissionVote>,
+    first_submitted: Option<DateTime<Utc>>,
+    reached: bool,
+    /// Known verdict of a calibration bounty, which it settles on
+    ground_truth: Option<Verdict>,
+    /// What the consensus was calculated from, snapshotted when it is final
+    inputs: ConsensusInputs,
+}
+
+impl Calculation {
+    fn outcome(&self) -> ConsensusOutcome {
+        ConsensusOutcome {
+            algorithm: self.algorithm,
+            verdict: self.verdict.clone(),
+            confidence: self.confidence,
+            agreement_score: self.agreement_score,
+            consensus_reached: self.reached,
+            distribution: self.distribution.clone(),
+            weights: self.weights.clone(),
+            filtered: self.filtered.clone(),
+        }
+    }
 }
 
 impl ConsensusService {
@@ -19,12 +163,725 @@ impl ConsensusService {
         redis_conn: ConnectionManager,
     ) -> Result<Self> {
         let aggregator = ConsensusAggregator::new(config.consensus.clone());
-        
+        let events = EventPublisher::from_url(&config.redis.url)?;
+
         Ok(Self {
-            config,
             db_pool,
             redis_conn,
+            redis_client: redis::Client::open(config.redis.url.clone())?,
             aggregator,
+            settings: ConsensusSettings {
+                consensus: config.consensus.clone(),
+                filter: config.filter.clone(),
+            },
+            settlement: config.settlement.clone(),
+            events,
+            signer: RequestSigner::from_env()?,
+        })
+    }
+
+    /// A bounty's consensus over its submissions so far, with the weight
+    /// each vote carried
+    pub async fn consensus(&self, bounty_id: Uuid) -> Result<ConsensusResponse> {
+        let calculation = self.calculate(bounty_id).await?;
+        let is_finalized = self.final_result(bounty_id).await?.is_some();
+
+        Ok(ConsensusResponse {
+            bounty_id,
+            algorithm: calculation.algorithm,
+            quorum: calculation.quorum,
+            final_verdict: calculation.verdict,
+            confidence_score: calculation.confidence,
+            can_be_disputed: self.aggregator.can_be_disputed(calculation.agreement_score),
+            agreement_score: calculation.agreement_score,
+            verdict_distribution: calculation.distribution,
+            vote_weights: calculation.weights,
+            filtered_votes: calculation.filtered,
+            total_submissions: calculation.submissions,
+            is_finalized,
         })
     }
+
+    /// How a bounty's consensus follows from its votes, alongside the stored
+    /// final result if there is one
+    pub async fn explanation(&self, bounty_id: Uuid) -> Result<ConsensusExplanation> {
+        let calculation = self.calculate(bounty_id).await?;
+        let mut explanation = self.aggregator.explain(
+            bounty_id,
+            calculation.algorithm,
+            calculation.quorum,
+            &calculation.votes,
+            &calculation.weights,
+        );
+        explanation.filtered = calculation.filtered;
+        explanation.finalized = self.final_result(bounty_id).await?.map(|result| FinalizedResult {
+            verdict: Verdict::parse(&result.final_verdict),
+            agreement_score: result.agreement_score.and_then(|score| Decimal::try_from(score).ok()),
+            consensus_reached: result.consensus_reached,
+            reason: result.finalized_reason.as_deref().and_then(FinalizeReason::parse),
+            finalized_at: result.finalized_at,
+        });
+
+        Ok(explanation)
+    }
+
+    /// Recalculate the provisional result of an open bounty after a
+    /// submission. Finalized results are left alone.
+    pub async fn update_provisional(&self, bounty_id: Uuid) -> Result<()> {
+        let calculation = self.calculate(bounty_id).await?;
+        if self.store(bounty_id, &calculation, None).await? {
+            self.publish_progress(bounty_id, &calculation).await;
+        }
+        Ok(())
+    }
+
+    /// Where a bounty's consensus stands, without its individual votes
+    pub async fn progress(&self, bounty_id: Uuid) -> Result<ConsensusProgress> {
+        let calculation = self.calculate(bounty_id).await?;
+        self.build_progress(bounty_id, &calculation).await
+    }
+
+    /// A bounty's progress each time it changes. Subscribe before reading the
+    /// current progress so no change falls in between.
+    pub async fn progress_updates(&self, bounty_id: Uuid) -> Result<impl Stream<Item = ConsensusProgress>> {
+        let mut pubsub = self.redis_client.get_async_connection().await?.into_pubsub();
+        pubsub.subscribe(progress_channel(bounty_id)).await?;
+
+        Ok(pubsub.into_on_message().filter_map(|msg| async move {
+            let payload: String = msg.get_payload().ok()?;
+            serde_json::from_str(&payload).ok()
+        }))
+    }
+
+    /// Finalize an open bounty's result early if its quorum agreed on a
+    /// verdict or its maximum wait passed. Only the call that finalizes the
+    /// result announces it, so checking again cannot trigger a second payout.
+    pub async fn auto_finalize(&self, bounty_id: Uuid) -> Result<Option<FinalizeReason>> {
+        let calculation = self.calculate(bounty_id).await?;
+        let Some(first_submitted) = calculation.first_submitted else {
+            return Ok(None);
+        };
+        let pending_reveals: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM consensus_commitments WHERE bounty_id = $1 AND revealed_at IS NULL)",
+        )
+        .bind(bounty_id)
+        .fetch_one(&self.db_pool)
+        .await?;
+
+        let Some(reason) = self.aggregator.auto_finalize(
+            &calculation.quorum,
+            calculation.reached,
+            pending_reveals,
+            first_submitted,
+            Utc::now(),
+        ) else {
+            return Ok(None);
+        };
+        // The feed and correlation only cover results with an artifact
+        let finalization = Finalization {
+            reason,
+            artifact_hash: self.artifact_hash(bounty_id).await?,
+            finalized_at: Utc::now(),
+        };
+        if !self.store(bounty_id, &calculation, Some(&finalization)).await? {
+            return Ok(None);
+        }
+        self.publish_progress(bounty_id, &calculation).await;
+        info!(
+            "Finalized consensus for bounty {} ({}): {} with {}% agreement over {} submissions",
+            bounty_id,
+            reason.as_str(),
+            calculation.verdict.to_string(),
+            calculation.agreement_score.round_dp(2),
+            calculation.submissions
+        );
+
+        self.announce(bounty_id).await?;
+        Ok(Some(reason))
+    }
+
+    /// Hash of the artifact a bounty is for, from the bounty-manager's table
+    async fn artifact_hash(&self, bounty_id: Uuid) -> Result<Option<String>> {
+        let hash: Option<Option<String>> = sqlx::query_scalar("SELECT artifact_hash FROM bounties WHERE id = $1")
+            .bind(bounty_id)
+            .fetch_optional(&self.db_pool)
+            .await?;
+        Ok(hash.flatten().filter(|hash| !hash.is_empty()))
+    }
+
+    /// Bounties with submissions whose result is not final yet, oldest first
+    pub async fn open_bounties(&self) -> Result<Vec<Uuid>> {
+        let bounties = sqlx::query_scalar(
+            r#"
+            SELECT s.bounty_id
+            FROM consensus_submissions s
+            LEFT JOIN consensus_results r ON r.bounty_id = s.bounty_id
+            WHERE r.finalized_at IS NULL
+            GROUP BY s.bounty_id
+            ORDER BY MIN(s.submitted_at)
+            LIMIT $1
+            "#,
+        )
+        .bind(AUTO_FINALIZE_BATCH)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(bounties)
+    }
+
+    /// A bounty's quorum rules
+    pub async fn quorum(&self, bounty_id: Uuid) -> Result<QuorumRules> {
+        Ok(self.quorum_rules(&self.settings(bounty_id).await?))
+    }
+
+    /// Set a bounty's quorum rules. Returns `None` once its result is final,
+    /// as the rules no longer matter.
+    pub async fn set_quorum(&self, bounty_id: Uuid, request: &QuorumRulesRequest) -> Result<Option<QuorumRules>> {
+        if self.final_result(bounty_id).await?.is_some() {
+            return Ok(None);
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO consensus_bounty_settings (bounty_id, min_submissions, min_total_stake, max_wait_hours)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (bounty_id) DO UPDATE
+            SET min_submissions = EXCLUDED.min_submissions,
+                min_total_stake = EXCLUDED.min_total_stake,
+                max_wait_hours = EXCLUDED.max_wait_hours,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(bounty_id)
+        .bind(request.min_submissions.map(|n| n.min(i32::MAX as usize) as i32))
+        .bind(request.min_total_stake)
+        .bind(request.max_wait_hours.map(|hours| hours.min(i32::MAX as u64) as i32))
+        .execute(&self.db_pool)
+        .await?;
+
+        self.quorum(bounty_id).await.map(Some)
+    }
+
+    /// Finalize the result of a closed bounty and announce its verdict if
+    /// the submissions reached consensus. A bounty closed again, e.g. because
+    /// the bounty-manager missed the announcement, gets its stored result
+    /// announced again rather than recalculated.
+    pub async fn finalize(&self, closed: &BountyClosedEvent) -> Result<()> {
+        let calculation = self.calculate(closed.bounty_id).await?;
+        let finalization = Finalization {
+            reason: FinalizeReason::BountyClosed,
+            artifact_hash: closed.artifact_hash.clone(),
+            finalized_at: closed.closed_at,
+        };
+        if self.store(closed.bounty_id, &calculation, Some(&finalization)).await? {
+            self.publish_progress(closed.bounty_id, &calculation).await;
+            info!(
+                "Finalized consensus for bounty {}: {} with {}% agreement over {} submissions",
+                closed.bounty_id,
+                calculation.verdict.to_string(),
+                calculation.agreement_score.round_dp(2),
+                calculation.submissions
+            );
+        } else if let Some(hash) = &closed.artifact_hash {
+            // Finalized early, possibly before the hash was known
+            sqlx::query(
+                "UPDATE consensus_results SET artifact_hash = $2, updated_at = NOW()
+                 WHERE bounty_id = $1 AND artifact_hash IS NULL",
+            )
+            .bind(closed.bounty_id)
+            .bind(hash)
+            .execute(&self.db_pool)
+            .await?;
+        }
+
+        self.announce(closed.bounty_id).await
+    }
+
+    /// Recalculate a finalized consensus from its snapshot with the current
+    /// aggregation, and report what came out differently. The recorded
+    /// settings are used unless asked for the current ones. `None` if the
+    /// bounty has no snapshot, e.g. because it is not final yet.
+    pub async fn replay(&self, bounty_id: Uuid, request: &ReplayRequest) -> Result<Option<ConsensusReplay>> {
+        let snapshot: Option<SnapshotRow> = sqlx::query_as(
+            r#"
+            SELECT id, inputs::text AS inputs, settings::text AS settings, outcome::text AS outcome,
+                   finalized_reason, finalized_at
+            FROM consensus_snapshots
+            WHERE bounty_id = $1
+            "#,
+        )
+        .bind(bounty_id)
+        .fetch_optional(&self.db_pool)
+        .await?;
+        let Some(snapshot) = snapshot else {
+            return Ok(None);
+        };
+
+        let inputs: ConsensusInputs = serde_json::from_str(&snapshot.inputs)?;
+        let original: ConsensusOutcome = serde_json::from_str(&snapshot.outcome)?;
+        let (settings, used) = if request.current_settings {
+            (self.settings.clone(), ReplaySettings::Current)
+        } else {
+            let recorded = replay::overlay(
+                serde_json::to_value(&self.settings)?,
+                serde_json::from_str(&snapshot.settings)?,
+            );
+            (serde_json::from_value(recorded)?, ReplaySettings::Recorded)
+        };
+        let aggregator = ConsensusAggregator::new(settings.consensus);
+        let (replayed, _) = aggregator.aggregate(
+            &inputs,
+            request.algorithm.unwrap_or(inputs.algorithm),
+            &settings.filter,
+        );
+
+        Ok(Some(ConsensusReplay {
+            bounty_id,
+            snapshot_id: snapshot.id,
+            finalized_reason: snapshot.finalized_reason.as_deref().and_then(FinalizeReason::parse),
+            finalized_at: snapshot.finalized_at,
+            settings: used,
+            differences: replay::differences(&original, &replayed),
+            original,
+            replayed,
+        }))
+    }
+
+    /// Recalculate a bounty's result with the current aggregation. An open
+    /// bounty's provisional result is updated. A final result stands, as
+    /// payouts were settled on it; its snapshot is replayed instead so what
+    /// would change is on record.
+    pub async fn recalculate(&self, bounty_id: Uuid) -> Result<Recalculation> {
+        if self.final_result(bounty_id).await?.is_none() {
+            let before = self.stored_outcome(bounty_id).await?;
+            let calculation = self.calculate(bounty_id).await?;
+            if self.store(bounty_id, &calculation, None).await? {
+                self.publish_progress(bounty_id, &calculation).await;
+                let differences = before
+                    .map(|before| replay::differences(&before, &calculation.outcome()))
+                    .unwrap_or_default();
+                return Ok(Recalculation::Updated(differences));
+            }
+            // Finalized in the meantime
+        }
+
+        Ok(match self.replay(bounty_id, &ReplayRequest::default()).await? {
+            Some(replay) => Recalculation::Replayed(replay.differences),
+            None => Recalculation::NoSnapshot,
+        })
+    }
+
+    async fn build_progress(&self, bounty_id: Uuid, calculation: &Calculation) -> Result<ConsensusProgress> {
+        let finalized = self.final_result(bounty_id).await?;
+        Ok(ConsensusProgress {
+            bounty_id,
+            total_submissions: calculation.submissions,
+            total_stake: calculation.votes.iter().map(|v| v.stake_amount.max(0)).sum(),
+            distribution: self.aggregator.progress_shares(
+                &calculation.quorum,
+                calculation.submissions,
+                &calculation.distribution,
+            ),
+            quorum: calculation.quorum.clone(),
+            agreement_score: calculation.agreement_score.round_dp(2),
+            consensus_reached: calculation.reached,
+            is_finalized: finalized.is_some(),
+            final_verdict: finalized.and_then(|result| Verdict::parse(&result.final_verdict)),
+            updated_at: Utc::now(),
+        })
+    }
+
+    /// Tell those streaming a bounty's progress that it changed. Watchers
+    /// pick up the next change if this one is lost.
+    async fn publish_progress(&self, bounty_id: Uuid, calculation: &Calculation) {
+        let published = async {
+            let progress = self.build_progress(bounty_id, calculation).await?;
+            let mut conn = self.redis_conn.clone();
+            redis::cmd("PUBLISH")
+                .arg(progress_channel(bounty_id))
+                .arg(serde_json::to_string(&progress)?)
+                .query_async::<_, i64>(&mut conn)
+                .await?;
+            anyhow::Ok(())
+        };
+        if let Err(e) = published.await {
+            warn!("Failed to publish consensus progress for bounty {}: {}", bounty_id, e);
+        }
+    }
+
+    /// Announce how a finalized bounty settles, and its final verdict if its
+    /// submissions reached consensus
+    async fn announce(&self, bounty_id: Uuid) -> Result<()> {
+        let Some(result) = self.final_result(bounty_id).await? else {
+            return Ok(());
+        };
+        if let Some(plan) = self.settlement(bounty_id).await? {
+            self.publish_settlement(plan).await?;
+        }
+        if !result.consensus_reached {
+            info!("No consensus on bounty {}", bounty_id);
+            return Ok(());
+        }
+        let Some(verdict) = Verdict::parse(&result.final_verdict) else {
+            warn!("Bounty {} has unknown verdict {}", bounty_id, result.final_verdict);
+            return Ok(());
+        };
+
+        self.events
+            .publish(&NexusEvent::ConsensusReached(ConsensusReachedEvent {
+                bounty_id,
+                final_verdict: verdict.into(),
+                confidence: result.confidence,
+                agreement_score: result.agreement_score.unwrap_or_default(),
+                total_submissions: result.total_submissions.max(0) as u32,
+                reached_at: result.finalized_at,
+            }))
+            .await
+    }
+
+    /// How a finalized bounty settles, as planned when it was finalized
+    pub async fn settlement(&self, bounty_id: Uuid) -> Result<Option<SettlementPlan>> {
+        let plan: Option<String> =
+            sqlx::query_scalar("SELECT plan::text FROM consensus_settlement_plans WHERE bounty_id = $1")
+                .bind(bounty_id)
+                .fetch_optional(&self.db_pool)
+                .await?;
+        Ok(plan.and_then(|plan| serde_json::from_str(&plan).ok()))
+    }
+
+    /// Publish a settlement plan, signed as it is sent so the signature is
+    /// fresh each time the plan is announced again
+    async fn publish_settlement(&self, plan: SettlementPlan) -> Result<()> {
+        let payload = serde_json::to_vec(&plan)?;
+        let signature = match &self.signer {
+            Some(signer) => signer.sign_event("settlement_planned", &payload),
+            None => EventSignature {
+                key_id: String::new(),
+                timestamp: Utc::now().timestamp(),
+                signature: String::new(),
+            },
+        };
+        self.events
+            .publish(&NexusEvent::SettlementPlanned(SettlementPlannedEvent { plan, signature }))
+            .await
+    }
+
+    async fn settings(&self, bounty_id: Uuid) -> Result<SettingsRow> {
+        let settings = sqlx::query_as(
+            r#"
+            SELECT algorithm, min_submissions, min_total_stake, max_wait_hours, ground_truth
+            FROM consensus_bounty_settings
+            WHERE bounty_id = $1
+            "#,
+        )
+        .bind(bounty_id)
+        .fetch_optional(&self.db_pool)
+        .await?;
+
+        Ok(settings.unwrap_or_default())
+    }
+
+    /// The algorithm a bounty chose, or the default
+    fn algorithm(&self, settings: &SettingsRow) -> AlgorithmKind {
+        settings
+            .algorithm
+            .as_deref()
+            .and_then(AlgorithmKind::parse)
+            .unwrap_or_else(|| self.aggregator.default_algorithm())
+    }
+
+    /// The quorum rules a bounty set, the default for those it did not
+    fn quorum_rules(&self, settings: &SettingsRow) -> QuorumRules {
+        let default = self.aggregator.default_quorum();
+        QuorumRules {
+            min_submissions: settings
+                .min_submissions
+                .map_or(default.min_submissions, |n| n.max(1) as usize),
+            min_total_stake: settings.min_total_stake.unwrap_or(default.min_total_stake),
+            max_wait_hours: settings
+                .max_wait_hours
+                .map_or(default.max_wait_hours, |hours| hours.max(0) as u64),
+        }
+    }
+
+    async fn calculate(&self, bounty_id: Uuid) -> Result<Calculation> {
+        let settings = self.settings(bounty_id).await?;
+        let algorithm = self.algorithm(&settings);
+        let quorum = self.quorum_rules(&settings);
+        let rows: Vec<VoteRow> = sqlx::query_as(
+            r#"
+            SELECT id, engine_id, verdict, confidence::float8 AS confidence, reputation_score,
+                   specialization_score, stake_amount, prediction::text AS prediction, probation, wallet_address,
+                   submitted_at
+            FROM consensus_submissions
+            WHERE bounty_id = $1
+            "#,
+        )
+        .bind(bounty_id)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        let wallets: HashMap<String, String> = rows
+            .iter()
+            .filter_map(|row| Some((row.engine_id.clone(), row.wallet_address.clone()?)))
+            .collect();
+        let votes: Vec<SubmissionVote> = rows
+            .into_iter()
+            .filter_map(|row| {
+                Some(SubmissionVote {
+                    submission_id: row.id,
+                    user_id: Uuid::parse_str(&row.engine_id).unwrap_or_default(),
+                    verdict: Verdict::parse(&row.verdict)?,
+                    engine_id: row.engine_id,
+                    confidence: Decimal::try_from(row.confidence).unwrap_or_default(),
+                    reputation_score: row.reputation_score,
+                    specialization_score: row.specialization_score,
+                    stake_amount: row.stake_amount,
+                    prediction: row
+                        .prediction
+                        .and_then(|prediction| serde_json::from_str::<VerdictShares>(&prediction).ok()),
+                    probation: row.probation,
+                    submitted_at: row.submitted_at,
+                })
+            })
+            .collect();
+
+        let first_submitted = votes.iter().map(|v| v.submitted_at).min();
+
+        let engine_ids: Vec<String> = votes.iter().map(|v| v.engine_id.clone()).collect();
+        let inputs = ConsensusInputs {
+            algorithm,
+            quorum,
+            history: self.engine_history(bounty_id, &engine_ids).await?,
+            collusion: collusion::service::weight_factors(&self.db_pool, &engine_ids).await?,
+            votes,
+            wallets,
+        };
+        let (outcome, votes) = self.aggregator.aggregate(&inputs, algorithm, &self.settings.filter);
+
+        Ok(Calculation {
+            algorithm,
+            quorum: inputs.quorum.clone(),
+            verdict: outcome.verdict,
+            confidence: outcome.confidence,
+            agreement_score: outcome.agreement_score,
+            distribution: outcome.distribution,
+            weights: outcome.weights,
+            filtered: outcome.filtered,
+            reached: outcome.consensus_reached,
+            submissions: votes.len(),
+            first_submitted,
+            votes,
+            ground_truth: settings.ground_truth.as_deref().and_then(Verdict::parse),
+            inputs,
+        })
+    }
+
+    /// Each engine's record on other bounties finalized with consensus
+    async fn engine_history(&self, bounty_id: Uuid, engine_ids: &[String]) -> Result<HashMap<String, EngineHistory>> {
+        let rows: Vec<HistoryRow> = sqlx::query_as(
+            r#"
+            SELECT s.engine_id, COUNT(*) AS votes,
+                   AVG(CASE WHEN s.verdict = r.final_verdict THEN 1.0 ELSE 0.0 END)::float8 AS accuracy
+            FROM consensus_submissions s
+            JOIN consensus_results r ON r.bounty_id = s.bounty_id
+            WHERE s.engine_id = ANY($1)
+              AND s.bounty_id <> $2
+              AND r.finalized_at IS NOT NULL
+              AND r.consensus_reached
+            GROUP BY s.engine_id
+            "#,
+        )
+        .bind(engine_ids)
+        .bind(bounty_id)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let history = EngineHistory {
+                    votes: row.votes.clamp(0, u32::MAX as i64) as u32,
+                    accuracy: row.accuracy,
+                };
+                (row.engine_id, history)
+            })
+            .collect())
+    }
+
+    /// Store a bounty's result, finalizing it if given a finalization.
+    /// Returns false if the result was already final; a final result never
+    /// changes, so only one caller ever finalizes it.
+    async fn store(
+        &self,
+        bounty_id: Uuid,
+        calculation: &Calculation,
+        finalization: Option<&Finalization>,
+    ) -> Result<bool> {
+        let weighted_votes = WeightedVotes {
+            votes: calculation.weights.clone(),
+            filtered: calculation.filtered.clone(),
+        };
+        let mut tx = self.db_pool.begin().await?;
+        let stored = sqlx::query(
+            r#"
+            INSERT INTO consensus_results (
+                bounty_id, final_verdict, confidence, total_submissions,
+                malicious_count, benign_count, suspicious_count, unknown_count,
+                weighted_voting, agreement_score, consensus_reached, artifact_hash, finalized_at, algorithm,
+                finalized_reason, weighted_votes
+            )
+            VALUES ($1, $2, $3::NUMERIC, $4, $5, $6, $7, $8, $9, $10::NUMERIC, $11, $12, $13, $14, $15, $16::JSONB)
+            ON CONFLICT (bounty_id) DO UPDATE
+            SET final_verdict = EXCLUDED.final_verdict,
+                confidence = EXCLUDED.confidence,
+                total_submissions = EXCLUDED.total_submissions,
+                malicious_count = EXCLUDED.malicious_count,
+                benign_count = EXCLUDED.benign_count,
+                suspicious_count = EXCLUDED.suspicious_count,
+                unknown_count = EXCLUDED.unknown_count,
+                weighted_voting = EXCLUDED.weighted_voting,
+                agreement_score = EXCLUDED.agreement_score,
+                consensus_reached = EXCLUDED.consensus_reached,
+                artifact_hash = COALESCE(EXCLUDED.artifact_hash, consensus_results.artifact_hash),
+                finalized_at = EXCLUDED.finalized_at,
+                algorithm = EXCLUDED.algorithm,
+                finalized_reason = EXCLUDED.finalized_reason,
+                weighted_votes = EXCLUDED.weighted_votes,
+                updated_at = NOW()
+            WHERE consensus_results.finalized_at IS NULL
+            "#,
+        )
+        .bind(bounty_id)
+        .bind(calculation.verdict.to_string())
+        .bind(calculation.confidence.round_dp(4).to_string())
+        .bind(calculation.submissions as i32)
+        .bind(calculation.distribution.malicious.count as i32)
+        .bind(calculation.distribution.benign.count as i32)
+        .bind(calculation.distribution.suspicious.count as i32)
+        .bind(calculation.distribution.unknown.count as i32)
+        .bind(calculation.algorithm != AlgorithmKind::SimpleMajority)
+        .bind(calculation.agreement_score.round_dp(4).to_string())
+        .bind(calculation.reached)
+        .bind(finalization.and_then(|f| f.artifact_hash.clone()))
+        .bind(finalization.map(|f| f.finalized_at))
+        .bind(calculation.algorithm.as_str())
+        .bind(finalization.map(|f| f.reason.as_str()))
+        .bind(serde_json::to_string(&weighted_votes)?)
+        .execute(&mut *tx)
+        .await?;
+        if stored.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        if let Some(finalization) = finalization {
+            sqlx::query(
+                r#"
+                INSERT INTO consensus_snapshots (bounty_id, algorithm, inputs, settings, outcome, finalized_reason, finalized_at)
+                VALUES ($1, $2, $3::JSONB, $4::JSONB, $5::JSONB, $6, $7)
+                "#,
+            )
+            .bind(bounty_id)
+            .bind(calculation.algorithm.as_str())
+            .bind(serde_json::to_string(&calculation.inputs)?)
+            .bind(serde_json::to_string(&self.settings)?)
+            .bind(serde_json::to_string(&calculation.outcome())?)
+            .bind(finalization.reason.as_str())
+            .bind(finalization.finalized_at)
+            .execute(&mut *tx)
+            .await?;
+
+            // A calibration bounty settles on its known verdict, whatever
+            // the engines agreed on
+            let (verdict, reached) = match &calculation.ground_truth {
+                Some(truth) => (truth, true),
+                None => (&calculation.verdict, calculation.reached),
+            };
+            let mut plan = settlement::plan(
+                bounty_id,
+                verdict,
+                reached,
+                &calculation.inputs.votes,
+                &calculation.weights,
+                &self.settlement,
+                finalization.finalized_at,
+            );
+            plan.calibration = calculation.ground_truth.is_some();
+            sqlx::query(
+                r#"
+                INSERT INTO consensus_settlement_plans (bounty_id, plan_id, plan, planned_at)
+                VALUES ($1, $2, $3::JSONB, $4)
+                "#,
+            )
+            .bind(bounty_id)
+            .bind(plan.plan_id)
+            .bind(serde_json::to_string(&plan)?)
+            .bind(plan.planned_at)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// A bounty's stored result as an outcome, to compare a recalculation
+    /// against. The distribution is left out as results store only counts.
+    async fn stored_outcome(&self, bounty_id: Uuid) -> Result<Option<ConsensusOutcome>> {
+        let row: Option<StoredRow> = sqlx::query_as(
+            r#"
+            SELECT final_verdict, confidence::float8 AS confidence, agreement_score::float8 AS agreement_score,
+                   consensus_reached, algorithm, weighted_votes::text AS weighted_votes
+            FROM consensus_results
+            WHERE bounty_id = $1
+            "#,
+        )
+        .bind(bounty_id)
+        .fetch_optional(&self.db_pool)
+        .await?;
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let Some(verdict) = Verdict::parse(&row.final_verdict) else {
+            return Ok(None);
+        };
+        let weighted_votes: WeightedVotes = row
+            .weighted_votes
+            .and_then(|votes| serde_json::from_str(&votes).ok())
+            .unwrap_or_default();
+
+        Ok(Some(ConsensusOutcome {
+            algorithm: row
+                .algorithm
+                .as_deref()
+                .and_then(AlgorithmKind::parse)
+                .unwrap_or_else(|| self.aggregator.default_algorithm()),
+            verdict,
+            confidence: Decimal::try_from(row.confidence).unwrap_or_default(),
+            agreement_score: row
+                .agreement_score
+                .and_then(|score| Decimal::try_from(score).ok())
+                .unwrap_or_default(),
+            consensus_reached: row.consensus_reached,
+            distribution: VerdictDistribution::default(),
+            weights: weighted_votes.votes,
+            filtered: weighted_votes.filtered,
+        }))
+    }
+
+    async fn final_result(&self, bounty_id: Uuid) -> Result<Option<FinalResult>> {
+        let result = sqlx::query_as(
+            r#"
+            SELECT final_verdict, confidence::float8 AS confidence, agreement_score::float8 AS agreement_score,
+                   total_submissions, consensus_reached, finalized_reason, finalized_at
+            FROM consensus_results
+            WHERE bounty_id = $1 AND finalized_at IS NOT NULL
+            "#,
+        )
+        .bind(bounty_id)
+        .fetch_optional(&self.db_pool)
+        .await?;
+
+        Ok(result)
+    }
 }
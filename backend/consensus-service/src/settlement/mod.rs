@@ -0,0 +1,284 @@
+//! Settlement plans for finalized bounties.
+//!
+//! Once a bounty's consensus is final, the consensus-service decides how it
+//! settles: engines that voted for the final verdict share the reward by the
+//! weight their votes carried, engines that voted against it lose part of
+//! their stake, and each engine's reputation moves with how confidently it
+//! was right or wrong. The plan is published as one signed event, so the
+//! payment and reputation services apply the same outcome.
+//!
+//! Slashing is weighted by reputation: an engine whose reputation stands
+//! above the bounty's other voters swayed the vote more, so a wrong vote
+//! costs it a larger share of its stake.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use shared::messaging::{SettlementEntry, SettlementOutcome, SettlementPlan};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::config::SettlementConfig;
+use crate::models::{SubmissionVote, Verdict, VoteWeight};
+
+/// Bounds on how far an engine's reputation relative to the other voters
+/// scales its slash
+const MIN_INFLUENCE: f64 = 0.5;
+const MAX_INFLUENCE: f64 = 2.0;
+
+/// How a bounty settles. `votes` are every vote submitted, including those
+/// filtered out before aggregation; `weights` are the weights of the votes
+/// that counted. Votes that counted for the final verdict are rewarded,
+/// filtered ones only get their stake back.
+pub fn plan(
+    bounty_id: Uuid,
+    verdict: &Verdict,
+    consensus_reached: bool,
+    votes: &[SubmissionVote],
+    weights: &[VoteWeight],
+    config: &SettlementConfig,
+    planned_at: DateTime<Utc>,
+) -> SettlementPlan {
+    let weight_of: HashMap<&str, f64> = weights
+        .iter()
+        .map(|w| (w.engine_id.as_str(), w.weight.to_f64().unwrap_or(0.0).max(0.0)))
+        .collect();
+    let rewarded_weight: f64 = votes
+        .iter()
+        .filter(|vote| consensus_reached && vote.verdict == *verdict)
+        .filter_map(|vote| weight_of.get(vote.engine_id.as_str()))
+        .sum();
+    let mean_reputation = if votes.is_empty() {
+        0.0
+    } else {
+        votes.iter().map(|vote| vote.weighted_reputation().max(0) as f64).sum::<f64>() / votes.len() as f64
+    };
+
+    let mut entries: Vec<SettlementEntry> = votes
+        .iter()
+        .map(|vote| {
+            let confidence = vote.confidence.clamp(Decimal::ZERO, Decimal::ONE).to_f64().unwrap_or(0.0);
+            let weight = weight_of.get(vote.engine_id.as_str()).copied().unwrap_or(0.0);
+            let outcome = if !consensus_reached || vote.verdict == Verdict::Unknown {
+                SettlementOutcome::Refunded
+            } else if vote.verdict != *verdict {
+                SettlementOutcome::Slashed
+            } else if weight > 0.0 && rewarded_weight > 0.0 {
+                SettlementOutcome::Rewarded
+            } else {
+                SettlementOutcome::Refunded
+            };
+
+            let mut entry = SettlementEntry {
+                engine_id: vote.engine_id.clone(),
+                user_id: (!vote.user_id.is_nil()).then_some(vote.user_id),
+                submission_id: vote.submission_id,
+                verdict: vote.verdict.clone().into(),
+                outcome,
+                stake_amount: vote.stake_amount.max(0),
+                reward_share: 0.0,
+                slash_fraction: 0.0,
+                slash_amount: 0,
+                reputation_delta: 0,
+            };
+            match outcome {
+                SettlementOutcome::Rewarded => {
+                    entry.reward_share = weight / rewarded_weight;
+                    entry.reputation_delta = (config.reputation_reward as f64 * confidence).round() as i32;
+                }
+                SettlementOutcome::Slashed => {
+                    let influence = if mean_reputation > 0.0 {
+                        (vote.weighted_reputation().max(0) as f64 / mean_reputation).clamp(MIN_INFLUENCE, MAX_INFLUENCE)
+                    } else {
+                        1.0
+                    };
+                    entry.slash_fraction =
+                        (config.slash_rate * confidence * influence).clamp(0.0, config.max_slash_fraction);
+                    entry.slash_amount = (entry.stake_amount as f64 * entry.slash_fraction).floor() as i64;
+                    entry.reputation_delta = -((config.reputation_penalty as f64 * confidence).round() as i32);
+                }
+                SettlementOutcome::Refunded => {}
+            }
+            entry
+        })
+        .collect();
+    cap_probation_rewards(&mut entries, votes, config.probation_max_reward_share);
+
+    SettlementPlan {
+        plan_id: Uuid::new_v4(),
+        bounty_id,
+        final_verdict: verdict.clone().into(),
+        consensus_reached,
+        calibration: false,
+        entries,
+        planned_at,
+    }
+}
+
+/// Cap the reward shares of engines on probation at `cap`; what the cap
+/// holds back goes to the other rewarded engines by their shares. If only
+/// engines on probation were rewarded, the rest of the reward is unclaimed.
+fn cap_probation_rewards(entries: &mut [SettlementEntry], votes: &[SubmissionVote], cap: f64) {
+    let cap = cap.clamp(0.0, 1.0);
+    let mut held_back = 0.0;
+    for (entry, _) in entries.iter_mut().zip(votes).filter(|(_, vote)| vote.probation) {
+        let excess = (entry.reward_share - cap).max(0.0);
+        entry.reward_share -= excess;
+        held_back += excess;
+    }
+
+    let established: f64 = entries
+        .iter()
+        .zip(votes)
+        .filter(|(entry, vote)| !vote.probation && entry.outcome == SettlementOutcome::Rewarded)
+        .map(|(entry, _)| entry.reward_share)
+        .sum();
+    if held_back <= 0.0 || established <= 0.0 {
+        return;
+    }
+    for (entry, _) in entries
+        .iter_mut()
+        .zip(votes)
+        .filter(|(entry, vote)| !vote.probation && entry.outcome == SettlementOutcome::Rewarded)
+    {
+        entry.reward_share += held_back * entry.reward_share / established;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn config() -> SettlementConfig {
+        SettlementConfig {
+            slash_rate: 0.2,
+            max_slash_fraction: 0.3,
+            reputation_reward: 10,
+            reputation_penalty: 20,
+            probation_max_reward_share: 0.1,
+        }
+    }
+
+    fn vote(engine_id: &str, verdict: Verdict, confidence: i64, reputation_score: i32) -> SubmissionVote {
+        SubmissionVote {
+            submission_id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            engine_id: engine_id.to_string(),
+            verdict,
+            confidence: Decimal::new(confidence, 2),
+            reputation_score,
+            specialization_score: None,
+            stake_amount: 1000,
+            prediction: None,
+            probation: false,
+            submitted_at: Utc::now(),
+        }
+    }
+
+    fn weight(engine_id: &str, verdict: Verdict, weight: i64) -> VoteWeight {
+        VoteWeight {
+            engine_id: engine_id.to_string(),
+            verdict,
+            factors: BTreeMap::new(),
+            weight: Decimal::from(weight),
+        }
+    }
+
+    #[test]
+    fn test_correct_votes_share_the_reward_by_weight() {
+        let votes = vec![
+            vote("a", Verdict::Malicious, 100, 50),
+            vote("b", Verdict::Malicious, 50, 50),
+            vote("copied", Verdict::Malicious, 100, 50),
+            vote("unsure", Verdict::Unknown, 100, 50),
+        ];
+        let weights = vec![
+            weight("a", Verdict::Malicious, 3),
+            weight("b", Verdict::Malicious, 1),
+            weight("unsure", Verdict::Unknown, 1),
+        ];
+        let plan = plan(Uuid::new_v4(), &Verdict::Malicious, true, &votes, &weights, &config(), Utc::now());
+
+        let outcomes: Vec<SettlementOutcome> = plan.entries.iter().map(|e| e.outcome).collect();
+        assert_eq!(
+            outcomes,
+            vec![
+                SettlementOutcome::Rewarded,
+                SettlementOutcome::Rewarded,
+                SettlementOutcome::Refunded,
+                SettlementOutcome::Refunded,
+            ]
+        );
+        assert_eq!(plan.entries[0].reward_share, 0.75);
+        assert_eq!(plan.entries[1].reward_share, 0.25);
+        assert_eq!(plan.entries[0].reputation_delta, 10);
+        assert_eq!(plan.entries[1].reputation_delta, 5);
+        assert!(plan.entries.iter().all(|e| e.slash_amount == 0));
+    }
+
+    #[test]
+    fn test_wrong_votes_are_slashed_by_reputation_and_confidence() {
+        let votes = vec![
+            vote("right", Verdict::Malicious, 100, 100),
+            vote("trusted", Verdict::Benign, 100, 300),
+            vote("novice", Verdict::Benign, 50, 0),
+        ];
+        let weights = vec![
+            weight("right", Verdict::Malicious, 1),
+            weight("trusted", Verdict::Benign, 1),
+            weight("novice", Verdict::Benign, 1),
+        ];
+        let plan = plan(Uuid::new_v4(), &Verdict::Malicious, true, &votes, &weights, &config(), Utc::now());
+
+        let trusted = &plan.entries[1];
+        assert_eq!(trusted.outcome, SettlementOutcome::Slashed);
+        // 0.2 x 1.0 confidence x 2.0 influence (2.25 capped), capped at 0.3
+        assert_eq!(trusted.slash_fraction, 0.3);
+        assert_eq!(trusted.slash_amount, 300);
+        assert_eq!(trusted.reputation_delta, -20);
+
+        let novice = &plan.entries[2];
+        assert_eq!(novice.outcome, SettlementOutcome::Slashed);
+        // 0.2 x 0.5 confidence x 0.5 influence floor
+        assert!((novice.slash_fraction - 0.05).abs() < 1e-9);
+        assert_eq!(novice.slash_amount, 50);
+        assert_eq!(novice.reputation_delta, -10);
+        assert_eq!(plan.entries[0].reward_share, 1.0);
+    }
+
+    #[test]
+    fn test_probation_rewards_are_capped() {
+        let mut newcomer = vote("newcomer", Verdict::Malicious, 100, 0);
+        newcomer.probation = true;
+        let votes = vec![vote("a", Verdict::Malicious, 100, 50), vote("b", Verdict::Malicious, 100, 50), newcomer];
+        let weights = vec![
+            weight("a", Verdict::Malicious, 3),
+            weight("b", Verdict::Malicious, 1),
+            weight("newcomer", Verdict::Malicious, 4),
+        ];
+        let shared = plan(Uuid::new_v4(), &Verdict::Malicious, true, &votes, &weights, &config(), Utc::now());
+
+        // The newcomer's 0.5 is capped at 0.1 and the 0.4 split 3:1
+        assert!((shared.entries[2].reward_share - 0.1).abs() < 1e-9);
+        assert!((shared.entries[0].reward_share - 0.675).abs() < 1e-9);
+        assert!((shared.entries[1].reward_share - 0.225).abs() < 1e-9);
+        assert_eq!(shared.entries[2].reputation_delta, 10);
+
+        // Alone, the newcomer still gets no more than the cap
+        let alone = plan(Uuid::new_v4(), &Verdict::Malicious, true, &votes[2..], &weights[2..], &config(), Utc::now());
+        assert!((alone.entries[0].reward_share - 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_no_consensus_refunds_everyone() {
+        let votes = vec![vote("a", Verdict::Malicious, 90, 50), vote("b", Verdict::Benign, 90, 50)];
+        let weights = vec![weight("a", Verdict::Malicious, 1), weight("b", Verdict::Benign, 1)];
+        let plan = plan(Uuid::new_v4(), &Verdict::Malicious, false, &votes, &weights, &config(), Utc::now());
+
+        assert!(plan
+            .entries
+            .iter()
+            .all(|e| e.outcome == SettlementOutcome::Refunded && e.reputation_delta == 0 && e.reward_share == 0.0));
+    }
+}
@@ -0,0 +1,54 @@
+// Commit-reveal verdict commitments
+//
+// An engine on a commit-reveal bounty commits to the hex SHA-256 of
+// "<verdict>:<nonce>", with the verdict in lowercase, and later reveals the
+// verdict and nonce. The nonce keeps the four possible verdicts from being
+// guessed from the hash, so it must be long and random.
+
+use sha2::{Digest, Sha256};
+
+/// Shortest nonce accepted in a reveal
+pub const MIN_NONCE_LEN: usize = 16;
+
+/// The commitment to `verdict` under `nonce`
+pub fn commitment_of(verdict: &str, nonce: &str) -> String {
+    hex::encode(Sha256::digest(format!("{}:{}", verdict.to_lowercase(), nonce)))
+}
+
+/// Whether `verdict` and `nonce` open `commitment`
+pub fn opens(commitment: &str, verdict: &str, nonce: &str) -> bool {
+    nonce.len() >= MIN_NONCE_LEN && commitment_of(verdict, nonce).eq_ignore_ascii_case(commitment)
+}
+
+/// Commitments are 32-byte hex digests
+pub fn is_commitment(value: &str) -> bool {
+    value.len() == 64 && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NONCE: &str = "3f9a1c7e5b2d4f60";
+
+    #[test]
+    fn test_commitment_opens_with_its_verdict_and_nonce() {
+        let commitment = commitment_of("malicious", NONCE);
+        assert!(is_commitment(&commitment));
+        assert!(opens(&commitment, "malicious", NONCE));
+        assert!(opens(&commitment.to_uppercase(), "Malicious", NONCE));
+    }
+
+    #[test]
+    fn test_commitment_rejects_other_verdicts_and_nonces() {
+        let commitment = commitment_of("malicious", NONCE);
+        assert!(!opens(&commitment, "benign", NONCE));
+        assert!(!opens(&commitment, "malicious", "3f9a1c7e5b2d4f61"));
+    }
+
+    #[test]
+    fn test_short_nonces_are_rejected() {
+        let commitment = commitment_of("benign", "abc");
+        assert!(!opens(&commitment, "benign", "abc"));
+    }
+}
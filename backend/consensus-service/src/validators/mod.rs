@@ -1,3 +1,4 @@
 // Validators for submission data and consensus rules
 pub mod submission;
 pub mod consensus_rules;
+pub mod commitment;
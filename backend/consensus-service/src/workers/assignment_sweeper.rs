@@ -0,0 +1,19 @@
+use anyhow::Result;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::assignment::AssignmentService;
+
+/// Expires review offers that were not answered in time and offers the
+/// freed seats, plus any seats left open earlier, to the next reviewers.
+pub async fn start(service: Arc<AssignmentService>, interval_secs: u64) -> Result<()> {
+    info!("Assignment sweeper worker started");
+    loop {
+        match service.reassign_overdue().await {
+            Ok(0) => {}
+            Ok(offered) => info!("Offered {} review seat(s) to new reviewers", offered),
+            Err(e) => warn!("Review reassignment failed: {}", e),
+        }
+        tokio::time::sleep(tokio::time::Duration::from_secs(interval_secs)).await;
+    }
+}
@@ -0,0 +1,24 @@
+use anyhow::Result;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::services::consensus_service::ConsensusService;
+
+/// Finalizes open bounties once their quorum agrees on a verdict or their
+/// maximum wait passes, without waiting for the bounty-manager to close them.
+pub async fn start(service: Arc<ConsensusService>, interval_secs: u64) -> Result<()> {
+    info!("Auto-finalizer worker started");
+    loop {
+        match service.open_bounties().await {
+            Ok(bounties) => {
+                for bounty_id in bounties {
+                    if let Err(e) = service.auto_finalize(bounty_id).await {
+                        warn!("Failed to auto-finalize bounty {}: {}", bounty_id, e);
+                    }
+                }
+            }
+            Err(e) => warn!("Failed to load open bounties: {}", e),
+        }
+        tokio::time::sleep(tokio::time::Duration::from_secs(interval_secs)).await;
+    }
+}
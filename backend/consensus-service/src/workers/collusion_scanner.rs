@@ -0,0 +1,19 @@
+use anyhow::Result;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::collusion::CollusionService;
+
+/// Compares recent submissions for voting rings and flags the clusters found
+/// for admin review.
+pub async fn start(service: Arc<CollusionService>, interval_secs: u64) -> Result<()> {
+    info!("Collusion scanner worker started");
+    loop {
+        match service.scan().await {
+            Ok(0) => {}
+            Ok(flagged) => info!("Flagged {} new collusion cluster(s)", flagged),
+            Err(e) => warn!("Collusion scan failed: {}", e),
+        }
+        tokio::time::sleep(tokio::time::Duration::from_secs(interval_secs)).await;
+    }
+}
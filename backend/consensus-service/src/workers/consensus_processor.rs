@@ -1,13 +1,35 @@
 use anyhow::Result;
+use futures::StreamExt;
+use shared::messaging::{EventSubscriber, NexusEvent, RESUBSCRIBE_DELAY};
 use std::sync::Arc;
-use tracing::info;
+use tracing::{info, warn};
 
 use crate::services::consensus_service::ConsensusService;
 
-pub async fn start(_service: Arc<ConsensusService>) -> Result<()> {
+/// Calculates consensus as the bounty-manager announces submissions and
+/// closes bounties. Events missed while disconnected are made up for: a
+/// closed bounty is announced again until its verdict is settled.
+pub async fn start(service: Arc<ConsensusService>, redis_url: String) -> Result<()> {
     info!("Consensus processor worker started");
+    let subscriber = EventSubscriber::from_url(&redis_url)?;
     loop {
-        tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
-        // Process pending consensus calculations
+        match subscriber.subscribe(&["submission_received", "bounty_closed"]).await {
+            Ok(events) => {
+                let mut events = Box::pin(events);
+                while let Some(event) = events.next().await {
+                    let processed = match &event {
+                        NexusEvent::SubmissionReceived(e) => service.update_provisional(e.bounty_id).await,
+                        NexusEvent::BountyClosed(e) => service.finalize(e).await,
+                        _ => Ok(()),
+                    };
+                    if let Err(e) = processed {
+                        warn!("Failed to process {}: {}", event.get_title(), e);
+                    }
+                }
+                warn!("Consensus event stream ended");
+            }
+            Err(e) => warn!("Consensus event subscription failed: {}", e),
+        }
+        tokio::time::sleep(RESUBSCRIBE_DELAY).await;
     }
 }
@@ -0,0 +1,19 @@
+use anyhow::Result;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::correlation::CorrelationService;
+
+/// Compares newly finalized artifacts with related ones and opens conflicts
+/// where their verdicts contradict.
+pub async fn start(service: Arc<CorrelationService>, interval_secs: u64) -> Result<()> {
+    info!("Correlation scanner worker started");
+    loop {
+        match service.correlate().await {
+            Ok(0) => {}
+            Ok(opened) => info!("Opened {} consensus conflict(s)", opened),
+            Err(e) => warn!("Artifact correlation failed: {}", e),
+        }
+        tokio::time::sleep(tokio::time::Duration::from_secs(interval_secs)).await;
+    }
+}
@@ -1,13 +1,19 @@
 use anyhow::Result;
 use std::sync::Arc;
-use tracing::info;
+use tracing::{info, warn};
 
-use crate::services::consensus_service::ConsensusService;
+use crate::dispute::DisputeService;
 
-pub async fn start(_service: Arc<ConsensusService>) -> Result<()> {
+/// Seats arbitration panels for new disputes and resolves disputes once their
+/// panel has voted or their deadline has passed.
+pub async fn start(service: Arc<DisputeService>, interval_secs: u64) -> Result<()> {
     info!("Dispute resolver worker started");
     loop {
-        tokio::time::sleep(tokio::time::Duration::from_secs(300)).await;
-        // Process and resolve disputes
+        match service.process().await {
+            Ok(0) => {}
+            Ok(resolved) => info!("Resolved {} dispute(s)", resolved),
+            Err(e) => warn!("Dispute resolution failed: {}", e),
+        }
+        tokio::time::sleep(tokio::time::Duration::from_secs(interval_secs)).await;
     }
 }
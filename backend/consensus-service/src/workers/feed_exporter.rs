@@ -0,0 +1,19 @@
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::feed::FeedService;
+
+/// Periodically writes the signed bulk file for the previous UTC day.
+/// Exports are idempotent, so running on several replicas is safe.
+pub async fn start(service: Arc<FeedService>, interval_secs: u64) -> Result<()> {
+    info!("Feed exporter worker started");
+    loop {
+        let yesterday = (Utc::now() - Duration::days(1)).date_naive();
+        if let Err(e) = service.export_day(yesterday).await {
+            warn!("Feed export for {} failed: {}", yesterday, e);
+        }
+        tokio::time::sleep(tokio::time::Duration::from_secs(interval_secs)).await;
+    }
+}
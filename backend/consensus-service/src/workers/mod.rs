@@ -1,2 +1,8 @@
 pub mod consensus_processor;
 pub mod dispute_resolver;
+pub mod feed_exporter;
+pub mod assignment_sweeper;
+pub mod collusion_scanner;
+pub mod auto_finalizer;
+pub mod recalculation_runner;
+pub mod correlation_scanner;
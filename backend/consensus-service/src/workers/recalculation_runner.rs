@@ -0,0 +1,19 @@
+use anyhow::Result;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::recalculation::RecalculationService;
+
+/// Works through queued recalculation batches a chunk at a time, without
+/// pausing while there is more to do.
+pub async fn start(service: Arc<RecalculationService>, interval_secs: u64) -> Result<()> {
+    info!("Recalculation runner worker started");
+    loop {
+        match service.process().await {
+            Ok(0) => {}
+            Ok(_) => continue,
+            Err(e) => warn!("Consensus recalculation failed: {}", e),
+        }
+        tokio::time::sleep(tokio::time::Duration::from_secs(interval_secs)).await;
+    }
+}
@@ -0,0 +1,28 @@
+-- Quiet hours and deferred delivery
+
+-- Local wall-clock window in the user's timezone (user_settings.timezone).
+-- The window may wrap past midnight, e.g. 22:00-07:00.
+ALTER TABLE notification_preferences ADD COLUMN IF NOT EXISTS quiet_hours_start TIME;
+ALTER TABLE notification_preferences ADD COLUMN IF NOT EXISTS quiet_hours_end TIME;
+
+-- Notifications held back by quiet hours, delivered by the scheduler once
+-- deliver_at has passed
+CREATE TABLE IF NOT EXISTS scheduled_notifications (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL,
+    payload JSONB NOT NULL,
+    deliver_at TIMESTAMPTZ NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending' CHECK (
+        status IN ('pending', 'sending', 'sent', 'failed')
+    ),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    claimed_at TIMESTAMPTZ,
+    sent_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_scheduled_notifications_due
+    ON scheduled_notifications(deliver_at) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_scheduled_notifications_user_id
+    ON scheduled_notifications(user_id);
@@ -77,6 +77,12 @@ impl EmailChannel {
             include_str!("../templates/email/reputation_updated.hbs"),
         ).map_err(|e| NotificationError::TemplateError(format!("Failed to register template: {}", e)))?;
 
+        // Passwordless sign-in template
+        engine.register_template_string(
+            "magic_link",
+            include_str!("../templates/email/magic_link.hbs"),
+        ).map_err(|e| NotificationError::TemplateError(format!("Failed to register template: {}", e)))?;
+
         // Generic notification template
         engine.register_template_string(
             "generic_notification",
@@ -94,6 +100,7 @@ impl EmailChannel {
             NexusEvent::SubmissionReceived(_) => "submission_received",
             NexusEvent::PaymentProcessed(_) => "payment_processed",
             NexusEvent::ReputationUpdated(_) => "reputation_updated",
+            NexusEvent::MagicLinkRequested(_) => "magic_link",
             _ => "generic_notification",
         }
     }
@@ -130,9 +137,48 @@ impl EmailChannel {
                 data.insert("new_score".to_string(), serde_json::json!(e.new_score));
                 data.insert("change_reason".to_string(), serde_json::json!(e.change_reason));
             }
+            NexusEvent::BadgeAwarded(e) => {
+                data.insert("badge_name".to_string(), serde_json::json!(e.badge_name));
+                data.insert("badge_icon".to_string(), serde_json::json!(e.icon));
+                data.insert("badge_rarity".to_string(), serde_json::json!(e.rarity));
+            }
+            NexusEvent::WithdrawalUpdated(e) => {
+                if let Some(withdrawal_id) = e.withdrawal_id {
+                    data.insert("withdrawal_id".to_string(), serde_json::json!(withdrawal_id.to_string()));
+                }
+                data.insert("amount".to_string(), serde_json::json!(e.amount));
+                data.insert("address".to_string(), serde_json::json!(e.address));
+            }
+            NexusEvent::PaymentUpdated(e) => {
+                if let Some(bounty_id) = e.bounty_id {
+                    data.insert("bounty_id".to_string(), serde_json::json!(bounty_id.to_string()));
+                }
+                data.insert("amount".to_string(), serde_json::json!(e.amount));
+                data.insert("tx_hash".to_string(), serde_json::json!(e.tx_hash));
+            }
             NexusEvent::UserRegistered(e) => {
                 data.insert("username".to_string(), serde_json::json!(e.username));
             }
+            NexusEvent::MagicLinkRequested(e) => {
+                data.insert("login_url".to_string(), serde_json::json!(e.login_url));
+                data.insert("expires_at".to_string(), serde_json::json!(e.expires_at.to_rfc3339()));
+            }
+            NexusEvent::AccountLocked(e) => {
+                data.insert("failed_attempts".to_string(), serde_json::json!(e.failed_attempts));
+                data.insert("locked_until".to_string(), serde_json::json!(e.locked_until.to_rfc3339()));
+            }
+            NexusEvent::NewDeviceLogin(e) => {
+                data.insert("device".to_string(), serde_json::json!(e.device));
+                data.insert("ip_address".to_string(), serde_json::json!(e.ip_address));
+                data.insert("country".to_string(), serde_json::json!(e.country));
+                data.insert("logged_in_at".to_string(), serde_json::json!(e.logged_in_at.to_rfc3339()));
+            }
+            NexusEvent::OrganizationInvitationSent(e) => {
+                data.insert("organization_name".to_string(), serde_json::json!(e.organization_name));
+                data.insert("role".to_string(), serde_json::json!(e.role));
+                data.insert("accept_url".to_string(), serde_json::json!(e.accept_url));
+                data.insert("expires_at".to_string(), serde_json::json!(e.expires_at.to_rfc3339()));
+            }
             _ => {}
         }
 
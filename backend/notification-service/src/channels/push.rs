@@ -197,6 +197,7 @@ impl PushChannel {
             NexusEvent::BountyCompleted(_) => "bounty_completed",
             NexusEvent::BountyExpired(_) => "bounty_expired",
             NexusEvent::BountyCancelled(_) => "bounty_cancelled",
+            NexusEvent::BountyClosed(_) => "bounty_closed",
             NexusEvent::SubmissionReceived(_) => "submission_received",
             NexusEvent::SubmissionValidated(_) => "submission_validated",
             NexusEvent::SubmissionRejected(_) => "submission_rejected",
@@ -204,14 +205,25 @@ impl PushChannel {
             NexusEvent::AnalysisCompleted(_) => "analysis_completed",
             NexusEvent::AnalysisFailed(_) => "analysis_failed",
             NexusEvent::ReputationUpdated(_) => "reputation_updated",
+            NexusEvent::BadgeAwarded(_) => "badge_awarded",
+            NexusEvent::ReputationRankChanged(_) => "reputation_rank_changed",
+            NexusEvent::StreakMilestoneReached(_) => "streak_milestone_reached",
             NexusEvent::PaymentProcessed(_) => "payment_processed",
             NexusEvent::PaymentFailed(_) => "payment_failed",
             NexusEvent::StakeSlashed(_) => "stake_slashed",
+            NexusEvent::WithdrawalUpdated(_) => "withdrawal_updated",
+            NexusEvent::PaymentUpdated(_) => "payment_updated",
             NexusEvent::UserRegistered(_) => "user_registered",
             NexusEvent::UserVerified(_) => "user_verified",
+            NexusEvent::MagicLinkRequested(_) => "magic_link_requested",
+            NexusEvent::AccountLocked(_) => "account_locked",
+            NexusEvent::NewDeviceLogin(_) => "new_device_login",
+            NexusEvent::OrganizationInvitationSent(_) => "organization_invitation_sent",
             NexusEvent::EngineRegistered(_) => "engine_registered",
             NexusEvent::DisputeCreated(_) => "dispute_created",
             NexusEvent::DisputeResolved(_) => "dispute_resolved",
+            NexusEvent::ConsensusReached(_) => "consensus_reached",
+            NexusEvent::SettlementPlanned(_) => "settlement_planned",
             NexusEvent::SystemAlert(_) => "system_alert",
         }
         .to_string()
@@ -222,8 +234,13 @@ impl PushChannel {
         match event {
             NexusEvent::BountyCreated(_) => "BOUNTY_NOTIFICATION",
             NexusEvent::SubmissionReceived(_) => "SUBMISSION_NOTIFICATION",
-            NexusEvent::PaymentProcessed(_) => "PAYMENT_NOTIFICATION",
-            NexusEvent::ReputationUpdated(_) => "REPUTATION_NOTIFICATION",
+            NexusEvent::PaymentProcessed(_) | NexusEvent::WithdrawalUpdated(_) | NexusEvent::PaymentUpdated(_) => {
+                "PAYMENT_NOTIFICATION"
+            }
+            NexusEvent::ReputationUpdated(_)
+            | NexusEvent::BadgeAwarded(_)
+            | NexusEvent::ReputationRankChanged(_)
+            | NexusEvent::StreakMilestoneReached(_) => "REPUTATION_NOTIFICATION",
             _ => "GENERAL_NOTIFICATION",
         }
         .to_string()
@@ -63,6 +63,7 @@ impl WebhookChannel {
             NexusEvent::BountyCompleted(_) => "bounty.completed",
             NexusEvent::BountyExpired(_) => "bounty.expired",
             NexusEvent::BountyCancelled(_) => "bounty.cancelled",
+            NexusEvent::BountyClosed(_) => "bounty.closed",
             NexusEvent::SubmissionReceived(_) => "submission.received",
             NexusEvent::SubmissionValidated(_) => "submission.validated",
             NexusEvent::SubmissionRejected(_) => "submission.rejected",
@@ -70,14 +71,25 @@ impl WebhookChannel {
             NexusEvent::AnalysisCompleted(_) => "analysis.completed",
             NexusEvent::AnalysisFailed(_) => "analysis.failed",
             NexusEvent::ReputationUpdated(_) => "reputation.updated",
+            NexusEvent::BadgeAwarded(_) => "reputation.badge_awarded",
+            NexusEvent::ReputationRankChanged(_) => "reputation.rank_changed",
+            NexusEvent::StreakMilestoneReached(_) => "reputation.streak_milestone",
             NexusEvent::PaymentProcessed(_) => "payment.processed",
             NexusEvent::PaymentFailed(_) => "payment.failed",
             NexusEvent::StakeSlashed(_) => "stake.slashed",
+            NexusEvent::WithdrawalUpdated(_) => "payment.withdrawal_updated",
+            NexusEvent::PaymentUpdated(_) => "payment.updated",
             NexusEvent::UserRegistered(_) => "user.registered",
             NexusEvent::UserVerified(_) => "user.verified",
+            NexusEvent::MagicLinkRequested(_) => "user.magic_link_requested",
+            NexusEvent::AccountLocked(_) => "user.account_locked",
+            NexusEvent::NewDeviceLogin(_) => "user.new_device_login",
+            NexusEvent::OrganizationInvitationSent(_) => "organization.invitation_sent",
             NexusEvent::EngineRegistered(_) => "engine.registered",
             NexusEvent::DisputeCreated(_) => "dispute.created",
             NexusEvent::DisputeResolved(_) => "dispute.resolved",
+            NexusEvent::ConsensusReached(_) => "consensus.reached",
+            NexusEvent::SettlementPlanned(_) => "settlement.planned",
             NexusEvent::SystemAlert(_) => "system.alert",
         }
         .to_string()
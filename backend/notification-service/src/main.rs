@@ -3,6 +3,8 @@ mod config;
 mod handlers;
 mod models;
 mod notification_manager;
+mod quiet_hours;
+mod scheduler;
 mod templates;
 
 use anyhow::Result;
@@ -11,10 +13,7 @@ use axum::{
     Router,
 };
 use std::sync::Arc;
-use tower_http::{
-    cors::{Any, CorsLayer},
-    trace::TraceLayer,
-};
+use tower_http::trace::TraceLayer;
 use tracing::{info, warn};
 
 use crate::config::Config;
@@ -26,12 +25,7 @@ async fn main() -> Result<()> {
     dotenvy::dotenv().ok();
 
     // Initialize tracing
-    tracing_subscriber::fmt()
-        .with_target(false)
-        .with_thread_ids(true)
-        .with_line_number(true)
-        .json()
-        .init();
+    shared::observability::init_service_logging(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))?;
 
     info!("Starting Notification Service...");
 
@@ -83,6 +77,13 @@ async fn main() -> Result<()> {
         }
     });
 
+    let manager_clone = notification_manager.clone();
+    tokio::spawn(async move {
+        if let Err(e) = manager_clone.start_scheduler_worker().await {
+            warn!("Scheduler worker error: {}", e);
+        }
+    });
+
     info!("Background workers started");
 
     // Build application state
@@ -93,11 +94,8 @@ async fn main() -> Result<()> {
         notification_manager,
     });
 
-    // Configure CORS
-    let cors = CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods(Any)
-        .allow_headers(Any);
+    // Browser origins from CORS_ALLOWED_ORIGINS or the environment's defaults
+    let cors = shared::http_security::CorsPolicy::from_env().layer();
 
     // Build router
     let app = Router::new()
@@ -110,8 +108,19 @@ async fn main() -> Result<()> {
         .route("/api/v1/webhooks/register", post(handlers::webhook::register_webhook))
         .route("/api/v1/webhooks/unregister", post(handlers::webhook::unregister_webhook))
         .route("/ws", get(handlers::websocket::websocket_handler))
+        .merge(shared::observability::log_level_routes())
+        .layer(axum::middleware::from_fn_with_state(
+            shared::request_signing::SignatureVerifier::from_env()?,
+            shared::request_signing::verify_signature_middleware,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            shared::chaos::FaultInjector::from_env(),
+            shared::chaos::chaos_middleware,
+        ))
         .layer(cors)
+        .layer(axum::middleware::from_fn(shared::http_security::security_headers_middleware))
         .layer(TraceLayer::new_for_http())
+        .layer(axum::middleware::from_fn(shared::observability::log_context_middleware))
         .with_state(app_state);
 
     // Start server
@@ -3,13 +3,20 @@ use futures_util::StreamExt;
 use redis::aio::ConnectionManager;
 use sqlx::PgPool;
 use std::sync::Arc;
-use tracing::{error, info};
+use std::time::Duration;
+use tracing::{error, info, warn};
 use uuid::Uuid;
 
 use crate::channels::{EmailChannel, PushChannel, WebhookChannel, WebSocketChannel};
 use crate::config::Config;
 use crate::models::{NotificationChannel, NotificationPreferences, NotificationRecord, NotificationStatus};
-use shared::messaging::event_types::NotificationPayload;
+use crate::quiet_hours::QuietHours;
+use crate::scheduler::NotificationScheduler;
+use shared::messaging::event_types::{NotificationPayload, NotificationPriority};
+
+/// How often the scheduler worker looks for deferred notifications that are due
+const SCHEDULER_POLL_INTERVAL: Duration = Duration::from_secs(30);
+const SCHEDULER_BATCH_SIZE: i64 = 100;
 
 pub struct NotificationManager {
     config: Config,
@@ -19,6 +26,7 @@ pub struct NotificationManager {
     push_channel: Arc<PushChannel>,
     webhook_channel: Arc<WebhookChannel>,
     websocket_channel: Arc<WebSocketChannel>,
+    scheduler: NotificationScheduler,
 }
 
 impl NotificationManager {
@@ -34,6 +42,7 @@ impl NotificationManager {
         ));
         let webhook_channel = Arc::new(WebhookChannel::new(config.webhook.signing_secret.clone()));
         let websocket_channel = Arc::new(WebSocketChannel::new());
+        let scheduler = NotificationScheduler::new(db_pool.clone());
 
         Ok(Self {
             config,
@@ -43,12 +52,71 @@ impl NotificationManager {
             push_channel,
             webhook_channel,
             websocket_channel,
+            scheduler,
         })
     }
 
+    /// Deliver a notification now, or defer it to the end of the user's
+    /// quiet hours. Critical notifications (security alerts) always go out
+    /// immediately.
     pub async fn send_notification(&self, payload: &NotificationPayload) -> Result<()> {
         info!("Processing notification for user {}", payload.user_id);
 
+        if let Some(deliver_at) = self.deferred_until(payload).await {
+            match self.scheduler.schedule(payload, deliver_at).await {
+                Ok(()) => {
+                    info!(
+                        "Notification {} deferred to {} (quiet hours)",
+                        payload.notification_id, deliver_at
+                    );
+                    return Ok(());
+                }
+                Err(e) => warn!(
+                    "Failed to defer notification {}, delivering now: {}",
+                    payload.notification_id, e
+                ),
+            }
+        }
+
+        self.deliver(payload).await
+    }
+
+    /// When a notification should be delivered if the user is in quiet hours.
+    /// Fails open: if the settings can't be read the notification is sent.
+    async fn deferred_until(&self, payload: &NotificationPayload) -> Option<chrono::DateTime<chrono::Utc>> {
+        if payload.priority == NotificationPriority::Critical {
+            return None;
+        }
+
+        match self.quiet_hours_for(payload.user_id).await {
+            Ok(quiet_hours) => quiet_hours?.deferred_until(chrono::Utc::now()),
+            Err(e) => {
+                warn!("Failed to load quiet hours for user {}: {}", payload.user_id, e);
+                None
+            }
+        }
+    }
+
+    async fn quiet_hours_for(&self, user_id: Uuid) -> Result<Option<QuietHours>> {
+        let row: Option<(chrono::NaiveTime, chrono::NaiveTime, Option<String>)> = sqlx::query_as(
+            r#"
+            SELECT np.quiet_hours_start, np.quiet_hours_end, us.timezone
+            FROM notification_preferences np
+            LEFT JOIN user_settings us ON us.user_id = np.user_id
+            WHERE np.user_id = $1
+              AND np.quiet_hours_start IS NOT NULL
+              AND np.quiet_hours_end IS NOT NULL
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.db_pool)
+        .await?;
+
+        Ok(row.and_then(|(start, end, timezone)| QuietHours::new(start, end, timezone.as_deref())))
+    }
+
+    async fn deliver(&self, payload: &NotificationPayload) -> Result<()> {
+
         // Get user preferences
         let prefs = self.get_user_preferences(payload.user_id).await?;
 
@@ -93,6 +161,13 @@ impl NotificationManager {
         let channels = vec![
             "events:user_registered",
             "events:payment_processed",
+            "events:magic_link_requested",
+            "events:account_locked",
+            "events:new_device_login",
+            "events:organization_invitation_sent",
+            "events:badge_awarded",
+            "events:withdrawal_updated",
+            "events:payment_updated",
         ];
 
         // Get a new Redis connection for Pub/Sub (must be dedicated)
@@ -139,7 +214,7 @@ impl NotificationManager {
     }
 
     async fn process_event(&self, channel: &str, payload: &str) -> Result<()> {
-        use shared::messaging::event_types::{NexusEvent, UserRegisteredEvent, PaymentProcessedEvent, NotificationChannel, NotificationPriority, NotificationPayload};
+        use shared::messaging::event_types::{NexusEvent, UserRegisteredEvent, PaymentProcessedEvent, MagicLinkRequestedEvent, AccountLockedEvent, NewDeviceLoginEvent, OrganizationInvitationSentEvent, NotificationChannel, NotificationPriority, NotificationPayload, PaymentEventKind};
 
         // Deserialize the event based on channel
         let event: NexusEvent = match channel {
@@ -151,6 +226,38 @@ impl NotificationManager {
                 let payment_event: PaymentProcessedEvent = serde_json::from_str(payload)?;
                 NexusEvent::PaymentProcessed(payment_event)
             }
+            "events:magic_link_requested" => {
+                let magic_link_event: MagicLinkRequestedEvent = serde_json::from_str(payload)?;
+                return self.send_magic_link(magic_link_event).await;
+            }
+            "events:account_locked" => {
+                let locked_event: AccountLockedEvent = serde_json::from_str(payload)?;
+                let email = locked_event.email.clone();
+                return self
+                    .send_direct_email(locked_event.user_id, &email, NexusEvent::AccountLocked(locked_event))
+                    .await;
+            }
+            "events:new_device_login" => {
+                let login_event: NewDeviceLoginEvent = serde_json::from_str(payload)?;
+                let email = login_event.email.clone();
+                return self
+                    .send_direct_email(login_event.user_id, &email, NexusEvent::NewDeviceLogin(login_event))
+                    .await;
+            }
+            "events:organization_invitation_sent" => {
+                let invitation: OrganizationInvitationSentEvent = serde_json::from_str(payload)?;
+                // Addresses without an account yet are keyed by the invitation
+                let recipient = invitation.user_id.unwrap_or(invitation.invitation_id);
+                let email = invitation.email.clone();
+                return self
+                    .send_direct_email(recipient, &email, NexusEvent::OrganizationInvitationSent(invitation))
+                    .await;
+            }
+            // Published as a whole event by the reputation-service
+            "events:badge_awarded" => serde_json::from_str(payload)?,
+            // Published as a whole event by the payment-service
+            "events:withdrawal_updated" => serde_json::from_str(payload)?,
+            "events:payment_updated" => serde_json::from_str(payload)?,
             _ => {
                 info!("Ignoring unhandled channel: {}", channel);
                 return Ok(());
@@ -161,6 +268,15 @@ impl NotificationManager {
         let user_id = match &event {
             NexusEvent::UserRegistered(e) => e.user_id,
             NexusEvent::PaymentProcessed(e) => e.recipient_id,
+            NexusEvent::BadgeAwarded(e) => e.user_id,
+            NexusEvent::WithdrawalUpdated(e) => e.user_id,
+            // Completed withdrawals are already announced as withdrawal
+            // updates, and escrow deposits name only the creator's wallet
+            NexusEvent::PaymentUpdated(e) if e.kind == PaymentEventKind::WithdrawalCompleted => return Ok(()),
+            NexusEvent::PaymentUpdated(e) => match e.user_id {
+                Some(user_id) => user_id,
+                None => return Ok(()),
+            },
             _ => {
                 error!("Unexpected event type for channel: {}", channel);
                 return Ok(());
@@ -176,6 +292,11 @@ impl NotificationManager {
             priority: match &event {
                 NexusEvent::UserRegistered(_) => NotificationPriority::Normal,
                 NexusEvent::PaymentProcessed(_) => NotificationPriority::High,
+                NexusEvent::BadgeAwarded(_) => NotificationPriority::Low,
+                // Security-relevant: a withdrawal or allowlist change the
+                // user did not make must reach them
+                NexusEvent::WithdrawalUpdated(_) => NotificationPriority::High,
+                NexusEvent::PaymentUpdated(_) => NotificationPriority::High,
                 _ => NotificationPriority::Normal,
             },
             created_at: chrono::Utc::now(),
@@ -194,6 +315,79 @@ impl NotificationManager {
         Ok(())
     }
 
+    /// Sign-in links go straight to the address on the event: they must be
+    /// delivered regardless of notification preferences, and only by email.
+    async fn send_magic_link(&self, event: shared::messaging::event_types::MagicLinkRequestedEvent) -> Result<()> {
+        use shared::messaging::event_types::NexusEvent;
+
+        let email = event.email.clone();
+        self.send_direct_email(event.user_id, &email, NexusEvent::MagicLinkRequested(event))
+            .await
+    }
+
+    /// Security mail (sign-in links, lockout and new sign-in notices) and
+    /// invitations bypass preferences and quiet hours
+    async fn send_direct_email(
+        &self,
+        user_id: Uuid,
+        email: &str,
+        event: shared::messaging::event_types::NexusEvent,
+    ) -> Result<()> {
+        use shared::messaging::event_types::{NotificationChannel, NotificationPriority, NotificationPayload};
+        use crate::models::NotificationChannel as _;
+
+        let payload = NotificationPayload {
+            notification_id: Uuid::new_v4(),
+            user_id,
+            channels: vec![NotificationChannel::Email],
+            event,
+            priority: NotificationPriority::Critical,
+            created_at: chrono::Utc::now(),
+        };
+
+        self.email_channel.send(&payload, email).await?;
+        info!("{} email sent for user {}", payload.event.get_title(), user_id);
+        Ok(())
+    }
+
+    /// Deliver deferred notifications once they are due. Quiet hours are
+    /// checked again, so a window the user extended in the meantime holds.
+    pub async fn start_scheduler_worker(&self) -> Result<()> {
+        info!("Starting notification scheduler worker...");
+        let mut interval = tokio::time::interval(SCHEDULER_POLL_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            let due = match self.scheduler.claim_due(SCHEDULER_BATCH_SIZE).await {
+                Ok(due) => due,
+                Err(e) => {
+                    error!("Failed to claim scheduled notifications: {}", e);
+                    continue;
+                }
+            };
+
+            for scheduled in due {
+                let result = match self.deferred_until(&scheduled.payload).await {
+                    Some(deliver_at) => self.scheduler.reschedule(scheduled.id, deliver_at).await,
+                    None => match self.deliver(&scheduled.payload).await {
+                        Ok(()) => self.scheduler.mark_sent(scheduled.id).await,
+                        Err(e) => {
+                            warn!("Scheduled notification {} failed: {}", scheduled.id, e);
+                            self.scheduler
+                                .mark_failed(scheduled.id, scheduled.attempts, &e.to_string(), true)
+                                .await
+                        }
+                    },
+                };
+
+                if let Err(e) = result {
+                    error!("Failed to update scheduled notification {}: {}", scheduled.id, e);
+                }
+            }
+        }
+    }
+
     pub async fn start_retry_worker(&self) -> Result<()> {
         info!("Starting retry worker...");
         // TODO: Retry failed notifications
@@ -0,0 +1,138 @@
+use chrono::{DateTime, Duration, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+/// A daily window, in the user's local time, during which non-critical
+/// notifications are held back. `start` is inclusive and `end` exclusive; a
+/// window whose start is after its end wraps past midnight (22:00-07:00).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    pub timezone: Tz,
+}
+
+impl QuietHours {
+    /// Build a window from stored settings. Unknown timezones fall back to
+    /// UTC rather than dropping the window; an empty window yields `None`.
+    pub fn new(start: NaiveTime, end: NaiveTime, timezone: Option<&str>) -> Option<Self> {
+        if start == end {
+            return None;
+        }
+
+        let timezone = match timezone.map(str::parse::<Tz>) {
+            Some(Ok(tz)) => tz,
+            Some(Err(_)) => {
+                tracing::warn!("Unknown timezone {:?}, using UTC for quiet hours", timezone);
+                Tz::UTC
+            }
+            None => Tz::UTC,
+        };
+
+        Some(Self { start, end, timezone })
+    }
+
+    fn wraps_midnight(&self) -> bool {
+        self.start > self.end
+    }
+
+    /// Whether a local wall-clock time falls inside the window
+    pub fn contains(&self, local: NaiveTime) -> bool {
+        if self.wraps_midnight() {
+            local >= self.start || local < self.end
+        } else {
+            local >= self.start && local < self.end
+        }
+    }
+
+    /// When a notification arriving at `now` may be delivered, or `None` if
+    /// it can go out immediately
+    pub fn deferred_until(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let local = now.with_timezone(&self.timezone);
+        if !self.contains(local.time()) {
+            return None;
+        }
+
+        let mut end_date = local.date_naive();
+        if self.wraps_midnight() && local.time() >= self.start {
+            end_date = end_date.succ_opt()?;
+        }
+
+        Some(self.resolve(end_date.and_time(self.end)))
+    }
+
+    /// Resolve a local time to UTC. An ambiguous time (clocks going back)
+    /// resolves to its first occurrence; a time skipped by clocks going
+    /// forward moves to the first valid time after the gap.
+    fn resolve(&self, mut local: NaiveDateTime) -> DateTime<Utc> {
+        loop {
+            if let Some(time) = self.timezone.from_local_datetime(&local).earliest() {
+                return time.with_timezone(&Utc);
+            }
+            local += Duration::minutes(15);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_window_wrapping_midnight() {
+        let quiet = QuietHours::new(time(22, 0), time(7, 0), Some("UTC")).unwrap();
+
+        assert!(quiet.contains(time(23, 30)));
+        assert!(quiet.contains(time(3, 0)));
+        assert!(!quiet.contains(time(7, 0)));
+        assert!(!quiet.contains(time(12, 0)));
+
+        assert_eq!(
+            quiet.deferred_until(utc("2024-03-01T23:30:00Z")),
+            Some(utc("2024-03-02T07:00:00Z"))
+        );
+        assert_eq!(
+            quiet.deferred_until(utc("2024-03-02T03:00:00Z")),
+            Some(utc("2024-03-02T07:00:00Z"))
+        );
+        assert_eq!(quiet.deferred_until(utc("2024-03-02T12:00:00Z")), None);
+    }
+
+    #[test]
+    fn test_window_in_user_timezone() {
+        // 13:00-14:00 in Berlin is 12:00-13:00 UTC in winter
+        let quiet = QuietHours::new(time(13, 0), time(14, 0), Some("Europe/Berlin")).unwrap();
+
+        assert_eq!(quiet.deferred_until(utc("2024-01-15T11:30:00Z")), None);
+        assert_eq!(
+            quiet.deferred_until(utc("2024-01-15T12:30:00Z")),
+            Some(utc("2024-01-15T13:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn test_window_ending_in_dst_gap() {
+        // Clocks in New York skip 02:00-03:00 on 2024-03-10
+        let quiet = QuietHours::new(time(23, 0), time(2, 30), Some("America/New_York")).unwrap();
+
+        assert_eq!(
+            quiet.deferred_until(utc("2024-03-10T05:00:00Z")),
+            Some(utc("2024-03-10T07:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn test_invalid_and_empty_settings() {
+        let quiet = QuietHours::new(time(22, 0), time(7, 0), Some("Mars/Olympus")).unwrap();
+        assert_eq!(quiet.timezone, Tz::UTC);
+
+        assert!(QuietHours::new(time(8, 0), time(8, 0), None).is_none());
+    }
+}
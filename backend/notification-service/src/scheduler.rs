@@ -0,0 +1,138 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use shared::messaging::event_types::NotificationPayload;
+
+/// Failed deliveries are retried this many times before giving up
+const MAX_ATTEMPTS: i32 = 5;
+/// A row left in `sending` this long belongs to a worker that died
+const CLAIM_TIMEOUT_SECONDS: i64 = 300;
+
+/// A notification held back until `deliver_at`
+pub struct ScheduledNotification {
+    pub id: Uuid,
+    pub payload: NotificationPayload,
+    pub attempts: i32,
+}
+
+/// Durable queue of deferred notifications in `scheduled_notifications`.
+/// Rows are claimed with `SKIP LOCKED`, so several service instances can
+/// drain the queue without delivering anything twice.
+pub struct NotificationScheduler {
+    db_pool: PgPool,
+}
+
+impl NotificationScheduler {
+    pub fn new(db_pool: PgPool) -> Self {
+        Self { db_pool }
+    }
+
+    /// Queue a notification for later delivery. Scheduling the same
+    /// notification twice keeps the first entry.
+    pub async fn schedule(&self, payload: &NotificationPayload, deliver_at: DateTime<Utc>) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO scheduled_notifications (id, user_id, payload, deliver_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (id) DO NOTHING
+            "#,
+        )
+        .bind(payload.notification_id)
+        .bind(payload.user_id)
+        .bind(serde_json::to_value(payload)?)
+        .bind(deliver_at)
+        .execute(&self.db_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Claim up to `limit` notifications that are due
+    pub async fn claim_due(&self, limit: i64) -> Result<Vec<ScheduledNotification>> {
+        let rows: Vec<(Uuid, serde_json::Value, i32)> = sqlx::query_as(
+            r#"
+            UPDATE scheduled_notifications
+            SET status = 'sending', claimed_at = NOW(), attempts = attempts + 1
+            WHERE id IN (
+                SELECT id FROM scheduled_notifications
+                WHERE deliver_at <= NOW()
+                  AND (status = 'pending'
+                       OR (status = 'sending' AND claimed_at < NOW() - make_interval(secs => $2)))
+                ORDER BY deliver_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, payload, attempts
+            "#,
+        )
+        .bind(limit)
+        .bind(CLAIM_TIMEOUT_SECONDS as f64)
+        .fetch_all(&self.db_pool)
+        .await?;
+
+        let mut due = Vec::with_capacity(rows.len());
+        for (id, payload, attempts) in rows {
+            match serde_json::from_value(payload) {
+                Ok(payload) => due.push(ScheduledNotification { id, payload, attempts }),
+                Err(e) => self.mark_failed(id, attempts, &format!("Invalid payload: {}", e), false).await?,
+            }
+        }
+        Ok(due)
+    }
+
+    /// Put a claimed notification back to wait for a later time
+    pub async fn reschedule(&self, id: Uuid, deliver_at: DateTime<Utc>) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE scheduled_notifications
+            SET status = 'pending', deliver_at = $2, claimed_at = NULL, attempts = attempts - 1
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(deliver_at)
+        .execute(&self.db_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn mark_sent(&self, id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE scheduled_notifications SET status = 'sent', sent_at = NOW() WHERE id = $1")
+            .bind(id)
+            .execute(&self.db_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Record a failed delivery; it is retried with backoff until
+    /// `MAX_ATTEMPTS` is reached, unless `retry` is false
+    pub async fn mark_failed(&self, id: Uuid, attempts: i32, error: &str, retry: bool) -> Result<()> {
+        if retry && attempts < MAX_ATTEMPTS {
+            let backoff = Duration::minutes(1i64 << attempts.clamp(0, 6));
+            sqlx::query(
+                r#"
+                UPDATE scheduled_notifications
+                SET status = 'pending', deliver_at = $2, claimed_at = NULL, last_error = $3
+                WHERE id = $1
+                "#,
+            )
+            .bind(id)
+            .bind(Utc::now() + backoff)
+            .bind(error)
+            .execute(&self.db_pool)
+            .await?;
+        } else {
+            sqlx::query("UPDATE scheduled_notifications SET status = 'failed', last_error = $2 WHERE id = $1")
+                .bind(id)
+                .bind(error)
+                .execute(&self.db_pool)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
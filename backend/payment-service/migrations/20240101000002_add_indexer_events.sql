@@ -0,0 +1,16 @@
+-- Migration: record activity pushed by external indexers (webhook ingestion)
+
+CREATE TABLE IF NOT EXISTS indexer_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    delivery_id VARCHAR(255) NOT NULL,
+    provider VARCHAR(50) NOT NULL,
+    tx_hash VARCHAR(66) NOT NULL,
+    reported_block BIGINT,
+    -- confirmed | failed | unmined | untracked | block_mismatch | prior status
+    outcome VARCHAR(50) NOT NULL,
+    received_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (delivery_id, tx_hash)
+);
+
+CREATE INDEX IF NOT EXISTS idx_indexer_events_tx_hash ON indexer_events(tx_hash);
+CREATE INDEX IF NOT EXISTS idx_indexer_events_mismatch ON indexer_events(received_at) WHERE outcome = 'block_mismatch';
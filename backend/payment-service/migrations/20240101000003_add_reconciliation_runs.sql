@@ -0,0 +1,49 @@
+-- Migration: persist balance reconciliation runs so drift can be reported
+
+CREATE TABLE IF NOT EXISTS reconciliation_runs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ,
+    wallets_checked INTEGER NOT NULL DEFAULT 0,
+    wallets_failed INTEGER NOT NULL DEFAULT 0,
+    wallet_mismatches INTEGER NOT NULL DEFAULT 0,
+    unmatched_transactions INTEGER NOT NULL DEFAULT 0,
+    -- Sum of |chain - ledger| over every wallet checked, in wei
+    total_ledger_drift NUMERIC(78, 0) NOT NULL DEFAULT 0,
+    -- ok | info | warning | critical (highest finding in the run)
+    severity VARCHAR(20) NOT NULL DEFAULT 'ok',
+    alerted_at TIMESTAMPTZ
+);
+
+-- One row per wallet checked. Deltas are chain minus the other source, in wei.
+CREATE TABLE IF NOT EXISTS reconciliation_wallet_deltas (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    run_id UUID NOT NULL REFERENCES reconciliation_runs(id) ON DELETE CASCADE,
+    address VARCHAR(42) NOT NULL,
+    chain_balance NUMERIC(78, 0) NOT NULL,
+    db_balance NUMERIC(78, 0) NOT NULL,
+    ledger_balance NUMERIC(78, 0) NOT NULL,
+    db_delta NUMERIC(78, 0) NOT NULL,
+    ledger_delta NUMERIC(78, 0) NOT NULL,
+    severity VARCHAR(20) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Transactions whose database record does not agree with the chain or the indexer
+CREATE TABLE IF NOT EXISTS reconciliation_unmatched_transactions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    run_id UUID NOT NULL REFERENCES reconciliation_runs(id) ON DELETE CASCADE,
+    tx_hash VARCHAR(66) NOT NULL,
+    -- missing_on_chain | status_mismatch | stale_pending | untracked | block_mismatch
+    kind VARCHAR(50) NOT NULL,
+    db_status VARCHAR(50),
+    chain_status VARCHAR(50),
+    detail TEXT,
+    severity VARCHAR(20) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_reconciliation_runs_started_at ON reconciliation_runs(started_at DESC);
+CREATE INDEX IF NOT EXISTS idx_reconciliation_runs_severity ON reconciliation_runs(severity, started_at DESC);
+CREATE INDEX IF NOT EXISTS idx_reconciliation_wallet_deltas_run ON reconciliation_wallet_deltas(run_id, severity);
+CREATE INDEX IF NOT EXISTS idx_reconciliation_unmatched_run ON reconciliation_unmatched_transactions(run_id, severity);
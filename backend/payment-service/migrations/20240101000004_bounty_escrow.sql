@@ -0,0 +1,16 @@
+-- Migration: track bounty reward escrow through its lifecycle
+
+-- pending   deposit submitted, not yet confirmed on-chain
+-- funded    deposit confirmed; the bounty may be activated
+-- failed    deposit reverted; the creator may submit another
+-- released  reward paid out on completion
+-- cancelled bounty cancelled before the deposit confirmed
+-- refunded  refund to the creator queued
+ALTER TABLE escrow_accounts ALTER COLUMN status SET DEFAULT 'pending';
+ALTER TABLE escrow_accounts ADD COLUMN IF NOT EXISTS deposit_tx_hash VARCHAR(66);
+ALTER TABLE escrow_accounts ADD COLUMN IF NOT EXISTS funded_at TIMESTAMPTZ;
+ALTER TABLE escrow_accounts ADD COLUMN IF NOT EXISTS refunded_at TIMESTAMPTZ;
+ALTER TABLE escrow_accounts ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+CREATE INDEX IF NOT EXISTS idx_escrow_accounts_deposit_tx
+    ON escrow_accounts(LOWER(deposit_tx_hash)) WHERE deposit_tx_hash IS NOT NULL;
@@ -0,0 +1,23 @@
+-- Migration: stakes engines lock to take part in a bounty
+
+-- locked    held against the engine's balance while it participates
+-- unlocked  released back to the engine, e.g. on withdrawal
+-- slashed   forfeited by resolution
+CREATE TABLE IF NOT EXISTS stakes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL,
+    bounty_id UUID NOT NULL,
+    submission_id UUID,
+    address VARCHAR(42) NOT NULL,
+    amount DECIMAL(78, 18) NOT NULL,
+    status VARCHAR(50) NOT NULL DEFAULT 'locked',
+    locked_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    unlock_at TIMESTAMPTZ,
+    unlocked_at TIMESTAMPTZ
+);
+
+-- One live stake per engine and bounty
+CREATE UNIQUE INDEX IF NOT EXISTS idx_stakes_bounty_user_locked
+    ON stakes(bounty_id, user_id) WHERE status = 'locked';
+CREATE INDEX IF NOT EXISTS idx_stakes_address_locked
+    ON stakes(LOWER(address)) WHERE status = 'locked';
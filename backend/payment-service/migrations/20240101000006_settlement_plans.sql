@@ -0,0 +1,19 @@
+-- Migration: settlement plans the consensus-service publishes for finalized bounties
+
+-- Each plan is applied once; the consensus-service re-announces plans while
+-- a bounty stays open
+CREATE TABLE IF NOT EXISTS settlement_plans (
+    plan_id UUID PRIMARY KEY,
+    bounty_id UUID NOT NULL,
+    plan JSONB NOT NULL,
+    planned_at TIMESTAMPTZ NOT NULL,
+    applied_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_settlement_plans_bounty ON settlement_plans(bounty_id);
+
+-- slashed            all of the stake forfeited by resolution
+-- partially_slashed  slashed_amount forfeited, the rest released to the engine
+ALTER TABLE stakes ADD COLUMN IF NOT EXISTS slashed_amount DECIMAL(78, 18);
+ALTER TABLE stakes ADD COLUMN IF NOT EXISTS slashed_at TIMESTAMPTZ;
+ALTER TABLE stakes ADD COLUMN IF NOT EXISTS slash_reason TEXT;
@@ -0,0 +1,48 @@
+-- Migration: transactions the service signs and sends from its own wallets
+
+-- One row per wallet nonce. Replacing a stuck transaction re-signs the same
+-- nonce at a higher gas price, so the row follows whichever attempt is
+-- current until one of them is mined.
+--
+-- pending    sent, no attempt mined yet
+-- confirmed  an attempt was mined and succeeded
+-- failed     an attempt was mined and reverted
+-- dropped    the nonce was used by a transaction the service did not send
+CREATE TABLE IF NOT EXISTS outgoing_transactions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    payment_id UUID REFERENCES payments(id),
+    from_address VARCHAR(42) NOT NULL,
+    to_address VARCHAR(42) NOT NULL,
+    value DECIMAL(78, 0) NOT NULL DEFAULT 0,
+    data TEXT NOT NULL DEFAULT '0x',
+    gas_limit DECIMAL(78, 0) NOT NULL,
+    nonce BIGINT NOT NULL,
+    gas_price DECIMAL(78, 0) NOT NULL,
+    tx_hash VARCHAR(66) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    replacements INTEGER NOT NULL DEFAULT 0,
+    mined_tx_hash VARCHAR(66),
+    submitted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    settled_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_outgoing_transactions_wallet_nonce
+    ON outgoing_transactions(LOWER(from_address), nonce);
+CREATE INDEX IF NOT EXISTS idx_outgoing_transactions_pending
+    ON outgoing_transactions(submitted_at) WHERE status = 'pending';
+
+-- Every signed attempt, the first one included
+--
+-- initial   first send of the nonce
+-- fee_bump  replacement at a higher gas price
+CREATE TABLE IF NOT EXISTS outgoing_transaction_attempts (
+    tx_hash VARCHAR(66) PRIMARY KEY,
+    outgoing_id UUID NOT NULL REFERENCES outgoing_transactions(id),
+    gas_price DECIMAL(78, 0) NOT NULL,
+    reason VARCHAR(20) NOT NULL,
+    submitted_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_outgoing_transaction_attempts_outgoing
+    ON outgoing_transaction_attempts(outgoing_id, submitted_at);
@@ -0,0 +1,53 @@
+-- Migration: reward payouts of released escrows, one batch per bounty
+
+-- pending           items still to be sent or confirmed
+-- completed         every item paid
+-- partially_failed  some items paid, the rest out of attempts
+-- failed            no item paid and the rest out of attempts
+CREATE TABLE IF NOT EXISTS payout_batches (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    bounty_id UUID NOT NULL UNIQUE,
+    plan_id UUID NOT NULL,
+    token_address VARCHAR(42) NOT NULL,
+    from_address VARCHAR(42) NOT NULL,
+    total_amount DECIMAL(78, 0) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_payout_batches_pending
+    ON payout_batches(created_at) WHERE status = 'pending';
+
+-- One recipient's part of a batch
+--
+-- kind      reward (an engine's share) or remainder (back to the creator)
+-- method    disperse (one contract call for many items) or transfer
+-- pending   to be sent
+-- sent      in a transaction not yet mined
+-- paid      the transaction succeeded
+-- failed    the transaction reverted or was dropped; retried until out of attempts
+CREATE TABLE IF NOT EXISTS payout_items (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    batch_id UUID NOT NULL REFERENCES payout_batches(id),
+    recipient_address VARCHAR(42) NOT NULL,
+    user_id UUID,
+    kind VARCHAR(20) NOT NULL,
+    amount DECIMAL(78, 0) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    method VARCHAR(20),
+    outgoing_id UUID REFERENCES outgoing_transactions(id),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    error_message TEXT,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    paid_at TIMESTAMPTZ,
+    UNIQUE (batch_id, recipient_address, kind)
+);
+
+CREATE INDEX IF NOT EXISTS idx_payout_items_batch ON payout_items(batch_id);
+CREATE INDEX IF NOT EXISTS idx_payout_items_outgoing ON payout_items(outgoing_id) WHERE status = 'sent';
+
+-- Released while payouts were enabled; the batch is opened once the bounty's
+-- settlement plan is applied
+ALTER TABLE escrow_accounts ADD COLUMN IF NOT EXISTS payout_due BOOLEAN NOT NULL DEFAULT FALSE;
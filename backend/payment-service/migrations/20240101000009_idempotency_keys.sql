@@ -0,0 +1,26 @@
+-- Migration: Idempotency-Keys of mutating payment requests
+
+-- One row per caller, route and key. The first request holds the key while
+-- it runs; its response is then kept until expires_at and replayed to every
+-- retry carrying the same key.
+--
+-- in_flight  the first request is still running (or died before finishing,
+--            in which case a retry may take over after locked_until)
+-- completed  response_status and response_body hold the first response
+CREATE TABLE IF NOT EXISTS idempotency_keys (
+    caller VARCHAR(100) NOT NULL,
+    route VARCHAR(255) NOT NULL,
+    idempotency_key VARCHAR(255) NOT NULL,
+    -- SHA-256 of the request body; a key cannot be reused for another body
+    fingerprint VARCHAR(64) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'in_flight',
+    response_status SMALLINT,
+    response_body JSONB,
+    locked_until TIMESTAMPTZ NOT NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ,
+    PRIMARY KEY (caller, route, idempotency_key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_idempotency_keys_expires ON idempotency_keys(expires_at);
@@ -0,0 +1,70 @@
+-- Migration: withdrawal allowlists, approvals and events
+
+-- Addresses a user may withdraw to. A new address can only be used once
+-- usable_after has passed, so a hijacked account cannot add an address and
+-- drain the balance before its owner notices.
+CREATE TABLE IF NOT EXISTS withdrawal_addresses (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL,
+    address VARCHAR(42) NOT NULL,
+    label VARCHAR(100),
+    usable_after TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    removed_at TIMESTAMPTZ
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_withdrawal_addresses_active
+    ON withdrawal_addresses(user_id, LOWER(address)) WHERE removed_at IS NULL;
+
+-- held       above the approval threshold, waiting for admin approvals
+-- queued     payment queued from the treasury
+-- sent       payment transaction sent, not yet mined
+-- completed  payment confirmed
+-- failed     payment transaction failed
+-- rejected   an admin rejected it
+CREATE TABLE IF NOT EXISTS withdrawals (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL,
+    from_address VARCHAR(42) NOT NULL,
+    to_address VARCHAR(42) NOT NULL,
+    token_address VARCHAR(42) NOT NULL,
+    amount DECIMAL(78, 0) NOT NULL,
+    status VARCHAR(20) NOT NULL,
+    payment_id UUID REFERENCES payments(id),
+    reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_withdrawals_user ON withdrawals(user_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_withdrawals_open
+    ON withdrawals(status) WHERE status IN ('held', 'queued', 'sent');
+
+-- One decision per admin; the requester never decides on their own
+CREATE TABLE IF NOT EXISTS withdrawal_approvals (
+    withdrawal_id UUID NOT NULL REFERENCES withdrawals(id),
+    admin_id UUID NOT NULL,
+    approved BOOLEAN NOT NULL,
+    note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (withdrawal_id, admin_id)
+);
+
+-- Every withdrawal and allowlist change, published to the user (outbox:
+-- notified_at is set once the event is out)
+CREATE TABLE IF NOT EXISTS withdrawal_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL,
+    withdrawal_id UUID REFERENCES withdrawals(id),
+    kind VARCHAR(30) NOT NULL,
+    detail JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    notified_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_withdrawal_events_withdrawal ON withdrawal_events(withdrawal_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_withdrawal_events_unnotified
+    ON withdrawal_events(created_at) WHERE notified_at IS NULL;
+
+-- Withdrawals are paid from the treasury without a bounty
+ALTER TABLE payments ALTER COLUMN bounty_id DROP NOT NULL;
@@ -0,0 +1,37 @@
+-- Migration: platform fee ledger and treasury sweeps
+
+-- Fees accrued to the treasury, one entry per source so an accrual is never
+-- counted twice
+--
+-- reward_fee       share of a bounty reward kept when its payouts opened
+--                  (source_id is the payout batch)
+-- slash_retention  share of a slashed stake (source_id is the stake)
+CREATE TABLE IF NOT EXISTS fee_ledger (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    kind VARCHAR(30) NOT NULL,
+    token_address VARCHAR(42) NOT NULL,
+    amount DECIMAL(78, 0) NOT NULL,
+    bounty_id UUID,
+    source_id UUID NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (kind, source_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_fee_ledger_token ON fee_ledger(LOWER(token_address), created_at DESC);
+
+-- Accrued fees sent from the treasury to the sweep address. The sweep follows
+-- its payment; a failed one no longer counts against the accrued balance.
+CREATE TABLE IF NOT EXISTS fee_sweeps (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    token_address VARCHAR(42) NOT NULL,
+    amount DECIMAL(78, 0) NOT NULL,
+    to_address VARCHAR(42) NOT NULL,
+    payment_id UUID NOT NULL REFERENCES payments(id),
+    requested_by UUID NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_fee_sweeps_token ON fee_sweeps(LOWER(token_address), created_at DESC);
+
+-- Kept from the reward before it was split
+ALTER TABLE payout_batches ADD COLUMN IF NOT EXISTS fee_amount DECIMAL(78, 0) NOT NULL DEFAULT 0;
@@ -0,0 +1,68 @@
+-- Migration: payment events on the message queue and user webhooks
+
+-- Payment lifecycle events, recorded in the transaction that made them
+-- happen and published by the payment event worker. One per kind and source
+-- (escrow, payout item, stake or withdrawal), so a step is never announced
+-- twice.
+--
+-- escrow_confirmed      a bounty's reward deposit funded its escrow
+-- payout_sent           a reward payout was broadcast
+-- payout_confirmed      a reward payout was mined
+-- slash_executed        a settlement plan slashed a stake
+-- withdrawal_completed  a withdrawal was mined
+CREATE TABLE IF NOT EXISTS payment_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    kind VARCHAR(30) NOT NULL,
+    source_id UUID NOT NULL,
+    bounty_id UUID,
+    user_id UUID,
+    address VARCHAR(42),
+    amount DECIMAL(78, 0),
+    token_address VARCHAR(42),
+    tx_hash VARCHAR(66),
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    published_at TIMESTAMPTZ,
+    UNIQUE (kind, source_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_payment_events_unpublished ON payment_events(occurred_at)
+    WHERE published_at IS NULL;
+
+-- A user's subscription to their own payment events. Events that name only a
+-- wallet (escrow deposits) reach the webhooks registered for that wallet.
+CREATE TABLE IF NOT EXISTS payment_webhooks (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL,
+    wallet_address VARCHAR(42),
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    events TEXT[] NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_payment_webhooks_user_id ON payment_webhooks(user_id);
+CREATE INDEX IF NOT EXISTS idx_payment_webhooks_wallet ON payment_webhooks(LOWER(wallet_address))
+    WHERE wallet_address IS NOT NULL;
+
+-- One event queued for, or sent to, a webhook
+CREATE TABLE IF NOT EXISTS payment_webhook_deliveries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    webhook_id UUID NOT NULL REFERENCES payment_webhooks(id) ON DELETE CASCADE,
+    event_id UUID NOT NULL REFERENCES payment_events(id),
+    event_type VARCHAR(30) NOT NULL,
+    payload JSONB NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'delivered', 'failed')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    response_status INTEGER,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    delivered_at TIMESTAMPTZ,
+    UNIQUE (webhook_id, event_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_payment_webhook_deliveries_due ON payment_webhook_deliveries(next_attempt_at)
+    WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_payment_webhook_deliveries_webhook ON payment_webhook_deliveries(webhook_id, created_at DESC);
@@ -0,0 +1,35 @@
+-- Migration: dead-lettered treasury payments and their manual intervention
+
+-- Every failed send of a treasury payment counts an attempt. After
+-- MAX_RETRY_ATTEMPTS the payment is dead-lettered instead of queued again,
+-- until an admin requeues or cancels it.
+--
+-- failure_reason of the latest failure:
+-- insufficient_gas  the treasury could not pay for gas
+-- reverted          the transfer reverted, in estimation or on-chain
+-- nonce_gap         the nonce was taken by another transaction or out of order
+-- other             anything else (node unreachable, malformed payment)
+ALTER TABLE payments
+    ADD COLUMN IF NOT EXISTS attempts INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS failure_reason VARCHAR(30),
+    ADD COLUMN IF NOT EXISTS last_error TEXT,
+    ADD COLUMN IF NOT EXISTS dead_lettered_at TIMESTAMPTZ,
+    -- Set by an admin; used instead of the estimate or the next nonce
+    ADD COLUMN IF NOT EXISTS gas_price_override DECIMAL(78, 0),
+    ADD COLUMN IF NOT EXISTS gas_limit_override DECIMAL(78, 0),
+    ADD COLUMN IF NOT EXISTS nonce_override BIGINT;
+
+CREATE INDEX IF NOT EXISTS idx_payments_dead_lettered ON payments(dead_lettered_at DESC)
+    WHERE status = 'dead_lettered';
+
+-- What admins did to dead-lettered payments
+CREATE TABLE IF NOT EXISTS payment_interventions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    payment_id UUID NOT NULL REFERENCES payments(id),
+    admin_id UUID NOT NULL,
+    action VARCHAR(20) NOT NULL CHECK (action IN ('edit', 'requeue', 'cancel')),
+    detail JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_payment_interventions_payment ON payment_interventions(payment_id, created_at);
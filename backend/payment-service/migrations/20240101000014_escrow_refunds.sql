@@ -0,0 +1,25 @@
+-- Migration: escrow refunds of cancelled and expired bounties
+
+-- One per refunded escrow: what was held, the platform fee kept and what went
+-- back to the creator. The fee is also accrued to the fee ledger as
+-- `refund_fee` (source_id is the refund).
+--
+-- cancelled  the creator or a moderator cancelled the bounty
+-- expired    the bounty expired with too few submissions or no consensus
+-- unfunded   the bounty never went live; its deposit is returned in full
+CREATE TABLE IF NOT EXISTS escrow_refunds (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    escrow_id UUID NOT NULL UNIQUE REFERENCES escrow_accounts(id),
+    bounty_id UUID NOT NULL,
+    reason VARCHAR(20) NOT NULL CHECK (reason IN ('cancelled', 'expired', 'unfunded')),
+    recipient_address VARCHAR(42) NOT NULL,
+    token_address VARCHAR(42) NOT NULL,
+    amount DECIMAL(78, 0) NOT NULL,
+    fee_amount DECIMAL(78, 0) NOT NULL DEFAULT 0,
+    refunded_amount DECIMAL(78, 0) NOT NULL,
+    -- Unset when the fee took the whole escrow
+    payment_id UUID REFERENCES payments(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_escrow_refunds_bounty_id ON escrow_refunds(bounty_id);
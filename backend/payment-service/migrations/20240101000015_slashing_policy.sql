@@ -0,0 +1,56 @@
+-- Migration: slashing policy, appeal holds and redistribution of slashed stakes
+
+-- A slash the policy decided on, held for the appeal window before it is
+-- executed. The stake stays `slash_pending` (still held against the engine's
+-- balance) until then.
+--
+-- offense:
+-- wrong_verdict     voted against the final verdict of a settlement plan
+-- collusion         proven to have colluded with other engines
+-- rejected_dispute  staked on a dispute that was rejected
+--
+-- status:
+-- pending     waiting out the appeal window
+-- appealed    the engine appealed; waiting on an admin
+-- executed    the stake was slashed
+-- overturned  an admin upheld the appeal; the stake was released
+CREATE TABLE IF NOT EXISTS stake_slashes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    stake_id UUID NOT NULL UNIQUE REFERENCES stakes(id),
+    bounty_id UUID NOT NULL,
+    user_id UUID NOT NULL,
+    offense VARCHAR(30) NOT NULL CHECK (offense IN ('wrong_verdict', 'collusion', 'rejected_dispute')),
+    -- Settlement plan whose correct voters share the slashed stake
+    plan_id UUID,
+    fraction DOUBLE PRECISION NOT NULL,
+    amount DECIMAL(78, 0) NOT NULL,
+    -- Reduced to stay within the engine's cap for the period
+    capped BOOLEAN NOT NULL DEFAULT FALSE,
+    reason TEXT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'appealed', 'executed', 'overturned')),
+    execute_after TIMESTAMPTZ NOT NULL,
+    appeal_note TEXT,
+    appealed_at TIMESTAMPTZ,
+    resolved_by UUID,
+    resolution_note TEXT,
+    resolved_at TIMESTAMPTZ,
+    executed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_stake_slashes_due ON stake_slashes(execute_after) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_stake_slashes_user ON stake_slashes(user_id, created_at DESC);
+
+-- Shares of an executed slash paid from the treasury to the engines that
+-- voted for the final verdict
+CREATE TABLE IF NOT EXISTS slash_redistributions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    slash_id UUID NOT NULL REFERENCES stake_slashes(id),
+    user_id UUID NOT NULL,
+    address VARCHAR(42) NOT NULL,
+    amount DECIMAL(78, 0) NOT NULL,
+    payment_id UUID NOT NULL REFERENCES payments(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (slash_id, user_id)
+);
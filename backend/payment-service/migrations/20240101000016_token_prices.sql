@@ -0,0 +1,44 @@
+-- Migration: token/USD rates and fiat values of treasury flows
+
+-- Rates read from each token's price feed (PRICE_FEEDS)
+--
+-- source: coingecko | chainlink | fixed
+CREATE TABLE IF NOT EXISTS token_prices (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    token_address VARCHAR(42) NOT NULL,
+    symbol VARCHAR(20) NOT NULL,
+    usd_rate DECIMAL(38, 18) NOT NULL,
+    source VARCHAR(20) NOT NULL,
+    -- When the feed last updated the rate, not when it was read
+    observed_at TIMESTAMPTZ NOT NULL,
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (token_address, source, observed_at)
+);
+
+CREATE INDEX IF NOT EXISTS idx_token_prices_token ON token_prices(LOWER(token_address), observed_at DESC);
+
+-- The rate last observed at or before the flow's transaction time (or the
+-- first after it) and the flow's USD value at that rate. Set once, so later
+-- price moves never restate past reports.
+--
+-- payments      completed payments at completed_at, deposits at created_at
+-- payout_items  paid items at paid_at
+-- fee_ledger    accrued fees at created_at
+ALTER TABLE payments
+    ADD COLUMN IF NOT EXISTS usd_rate DECIMAL(38, 18),
+    ADD COLUMN IF NOT EXISTS usd_value DECIMAL(38, 8),
+    ADD COLUMN IF NOT EXISTS priced_at TIMESTAMPTZ;
+
+ALTER TABLE payout_items
+    ADD COLUMN IF NOT EXISTS usd_rate DECIMAL(38, 18),
+    ADD COLUMN IF NOT EXISTS usd_value DECIMAL(38, 8),
+    ADD COLUMN IF NOT EXISTS priced_at TIMESTAMPTZ;
+
+ALTER TABLE fee_ledger
+    ADD COLUMN IF NOT EXISTS usd_rate DECIMAL(38, 18),
+    ADD COLUMN IF NOT EXISTS usd_value DECIMAL(38, 8),
+    ADD COLUMN IF NOT EXISTS priced_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_payments_unpriced ON payments(created_at) WHERE usd_rate IS NULL;
+CREATE INDEX IF NOT EXISTS idx_payout_items_unpriced ON payout_items(paid_at) WHERE usd_rate IS NULL AND status = 'paid';
+CREATE INDEX IF NOT EXISTS idx_fee_ledger_unpriced ON fee_ledger(created_at) WHERE usd_rate IS NULL;
@@ -0,0 +1,38 @@
+-- Migration: gasless claims relayed with EIP-2612 permits
+
+-- An analyst's signed permit, relayed by the treasury as three transactions
+-- at consecutive nonces: the permit, the net amount to the analyst's
+-- allowlisted address, and the relay fee to the treasury.
+--
+-- status:
+-- sending    being broadcast
+-- sent       broadcast; waiting for the transfers to be mined
+-- completed  both transfers mined; the fee is in the fee ledger
+-- failed     a transaction failed to broadcast, reverted or was dropped
+CREATE TABLE IF NOT EXISTS relay_claims (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL,
+    owner_address VARCHAR(42) NOT NULL,
+    to_address VARCHAR(42) NOT NULL,
+    token_address VARCHAR(42) NOT NULL,
+    amount DECIMAL(78, 0) NOT NULL,
+    fee_amount DECIMAL(78, 0) NOT NULL,
+    net_amount DECIMAL(78, 0) NOT NULL,
+    permit_nonce DECIMAL(78, 0) NOT NULL,
+    deadline TIMESTAMPTZ NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'sending'
+        CHECK (status IN ('sending', 'sent', 'completed', 'failed')),
+    permit_tx_id UUID REFERENCES outgoing_transactions(id),
+    transfer_tx_id UUID REFERENCES outgoing_transactions(id),
+    fee_tx_id UUID REFERENCES outgoing_transactions(id),
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    completed_at TIMESTAMPTZ,
+    -- A permit can only be used once
+    UNIQUE (token_address, owner_address, permit_nonce)
+);
+
+CREATE INDEX IF NOT EXISTS idx_relay_claims_owner ON relay_claims(LOWER(owner_address), created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_relay_claims_user ON relay_claims(user_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_relay_claims_sent ON relay_claims(updated_at) WHERE status = 'sent';
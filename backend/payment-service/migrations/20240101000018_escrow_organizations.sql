@@ -0,0 +1,8 @@
+-- Migration: escrows held on behalf of an organization
+
+-- Set when an organization member opens the bounty; billing members of the
+-- organization may list its escrows.
+ALTER TABLE escrow_accounts ADD COLUMN IF NOT EXISTS organization_id UUID;
+
+CREATE INDEX IF NOT EXISTS idx_escrow_accounts_organization
+    ON escrow_accounts(organization_id, locked_at DESC) WHERE organization_id IS NOT NULL;
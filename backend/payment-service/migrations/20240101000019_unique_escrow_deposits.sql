@@ -0,0 +1,25 @@
+-- Migration: a deposit transaction funds exactly one escrow
+
+-- Existing duplicates keep the escrow that got furthest with the deposit, or
+-- failing that the first opened; the others lose the hash, and those still
+-- pending fail so their creators can submit another deposit
+WITH ranked AS (
+    SELECT id,
+           ROW_NUMBER() OVER (
+               PARTITION BY LOWER(deposit_tx_hash)
+               ORDER BY funded_at NULLS LAST, locked_at, id
+           ) AS position
+    FROM escrow_accounts
+    WHERE deposit_tx_hash IS NOT NULL
+)
+UPDATE escrow_accounts e
+SET deposit_tx_hash = NULL,
+    status = CASE WHEN e.status = 'pending' THEN 'failed' ELSE e.status END,
+    updated_at = NOW()
+FROM ranked r
+WHERE r.id = e.id AND r.position > 1;
+
+DROP INDEX IF EXISTS idx_escrow_accounts_deposit_tx;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_escrow_accounts_deposit_tx
+    ON escrow_accounts(LOWER(deposit_tx_hash)) WHERE deposit_tx_hash IS NOT NULL;
@@ -10,7 +10,11 @@ abigen!(
         function balanceOf(address account) external view returns (uint256)
         function approve(address spender, uint256 amount) external returns (bool)
         function transferFrom(address from, address to, uint256 amount) external returns (bool)
+        function allowance(address owner, address spender) external view returns (uint256)
+        function decimals() external view returns (uint8)
+        function symbol() external view returns (string)
         event Transfer(address indexed from, address indexed to, uint256 value)
+        event Approval(address indexed owner, address indexed spender, uint256 value)
     ]"#
 );
 
@@ -30,3 +34,32 @@ abigen!(
         event StakeSlashed(bytes32 indexed stakeId, address indexed user, uint256 amount)
     ]"#
 );
+
+// Disperse contract ABI: pulls the total from the sender with transferFrom,
+// then pays each recipient; reverts entirely if any payment fails
+abigen!(
+    DisperseContract,
+    r#"[
+        function disperseToken(address token, address[] recipients, uint256[] values) external
+    ]"#
+);
+
+// Chainlink price feed ABI: `answer` has `decimals()` decimals
+abigen!(
+    PriceAggregator,
+    r#"[
+        function decimals() external view returns (uint8)
+        function latestRoundData() external view returns (uint80 roundId, int256 answer, uint256 startedAt, uint256 updatedAt, uint80 answeredInRound)
+    ]"#
+);
+
+// EIP-2612 permit extension of an ERC-20 token
+abigen!(
+    PermitToken,
+    r#"[
+        function name() external view returns (string)
+        function nonces(address owner) external view returns (uint256)
+        function DOMAIN_SEPARATOR() external view returns (bytes32)
+        function permit(address owner, address spender, uint256 value, uint256 deadline, uint8 v, bytes32 r, bytes32 s) external
+    ]"#
+);
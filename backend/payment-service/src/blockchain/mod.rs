@@ -2,6 +2,6 @@ pub mod contracts;
 pub mod provider;
 pub mod transaction;
 
-pub use contracts::{PaymentContract, TokenContract};
+pub use contracts::{DisperseContract, PaymentContract, PermitToken, PriceAggregator, TokenContract};
 pub use provider::{create_provider, BlockchainProvider};
 pub use transaction::{send_transaction, wait_for_confirmation, TransactionBuilder};
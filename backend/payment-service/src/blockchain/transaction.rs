@@ -3,6 +3,7 @@ use anyhow::Result;
 use ethers::prelude::*;
 use tracing::{info, warn};
 
+#[derive(Debug, Clone)]
 pub struct TransactionBuilder {
     pub from: Address,
     pub to: Address,
@@ -46,12 +47,21 @@ impl TransactionBuilder {
         self
     }
 
+    pub fn nonce(mut self, nonce: U256) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
     pub fn build(self) -> TransactionRequest {
-        TransactionRequest::new()
+        let mut tx = TransactionRequest::new()
             .from(self.from)
             .to(self.to)
             .value(self.value)
-            .data(self.data.unwrap_or_default())
+            .data(self.data.unwrap_or_default());
+        tx.gas = self.gas_limit;
+        tx.gas_price = self.gas_price;
+        tx.nonce = self.nonce;
+        tx
     }
 }
 
@@ -8,6 +8,18 @@ pub struct Config {
     pub redis: RedisConfig,
     pub blockchain: BlockchainConfig,
     pub payment: PaymentConfig,
+    pub indexer: IndexerConfig,
+    pub reconciliation: ReconciliationConfig,
+    pub tokens: TokenConfig,
+    pub transactions: TransactionConfig,
+    pub payouts: PayoutConfig,
+    pub idempotency: IdempotencyConfig,
+    pub withdrawals: WithdrawalConfig,
+    pub fees: FeeConfig,
+    pub webhooks: WebhookConfig,
+    pub slashing: SlashingConfig,
+    pub prices: PriceConfig,
+    pub relay: RelayConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,9 +63,313 @@ pub struct PaymentConfig {
     pub max_retry_attempts: u32,
 }
 
+/// External indexer (Alchemy Notify or a generic HMAC-signed source) pushing
+/// on-chain activity to `/api/v1/webhooks/indexer`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexerConfig {
+    pub enabled: bool,
+    pub provider: String, // "alchemy" | "generic"
+    pub signing_secret: Option<String>,
+    /// Reject generic deliveries whose timestamp is further off than this
+    pub max_clock_skew_seconds: i64,
+    /// How long delivery IDs are remembered for replay protection
+    pub replay_window_seconds: u64,
+    /// Polling interval of the transaction monitor while the indexer is the
+    /// primary source; the poll becomes a safety sweep for missed webhooks
+    pub fallback_poll_seconds: u64,
+}
+
+/// Balance reconciliation worker. Amounts are in wei and compared against
+/// the absolute difference between the chain and the DB or ledger balance.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReconciliationConfig {
+    pub interval_seconds: u64,
+    pub warning_drift_wei: String,
+    pub critical_drift_wei: String,
+    /// Lowest run severity that alerts admins ("info" | "warning" | "critical")
+    pub alert_min_severity: String,
+    /// An alert is not repeated at the same or lower severity within this window
+    pub alert_cooldown_seconds: u64,
+}
+
+/// Transactions the service sends from its own wallets. One whose latest
+/// attempt has gone `stuck_after_seconds` unmined is replaced with the same
+/// nonce at a gas price `fee_bump_percentage` higher, at most
+/// `max_replacements` times and never above `MAX_GAS_PRICE_GWEI`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionConfig {
+    pub check_interval_seconds: u64,
+    pub stuck_after_seconds: i64,
+    /// Nodes reject replacements that raise the gas price by less than 10%
+    pub fee_bump_percentage: u64,
+    pub max_replacements: i32,
+    /// Most pending transactions checked per run
+    pub batch_size: i64,
+}
+
+/// Reward payouts of released escrows from the treasury wallet, off by
+/// default where BountyManager.resolveBounty() pays on-chain. With a
+/// disperse contract (`disperseToken(token, recipients, values)`) up to
+/// `max_batch_size` recipients are paid in one transaction; without one, or
+/// for items a reverted disperse left unpaid, each is a token transfer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PayoutConfig {
+    pub enabled: bool,
+    pub interval_seconds: u64,
+    pub disperse_contract_address: Option<String>,
+    pub max_batch_size: usize,
+    /// Sends of one item before it is left failed
+    pub max_attempts: i32,
+}
+
+/// `Idempotency-Key`s of mutating payment requests, kept in Postgres for
+/// `retention_hours` so a retry within that window replays the first
+/// response. A request that never finishes holds its key for
+/// `in_flight_seconds` before a retry may run it again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdempotencyConfig {
+    pub retention_hours: i64,
+    pub in_flight_seconds: i64,
+    pub purge_interval_seconds: u64,
+}
+
+/// Withdrawal controls. Amounts are base units and apply per token: a user
+/// may withdraw at most `daily_limit` over any 24 hours, and a withdrawal of
+/// `approval_threshold` or more is held until `required_approvals` admins
+/// other than the user approve it. Addresses added to a user's allowlist
+/// can be withdrawn to after `address_cooloff_hours`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WithdrawalConfig {
+    pub daily_limit: String,
+    pub approval_threshold: String,
+    pub required_approvals: i64,
+    pub address_cooloff_hours: i64,
+    /// How often sent withdrawals are settled and events published
+    pub monitor_interval_seconds: u64,
+}
+
+/// Payment events, published on the message queue and delivered to user
+/// webhooks. Deliveries are retried with exponential backoff from
+/// `backoff_base_seconds`, up to `max_attempts`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    /// How often new events are published and due deliveries sent
+    pub interval_seconds: u64,
+    pub batch_size: i64,
+    pub max_attempts: u32,
+    pub backoff_base_seconds: u64,
+    pub backoff_max_seconds: u64,
+    pub timeout_seconds: u64,
+    pub max_per_user: i64,
+}
+
+/// Slashing policy. Each offense forfeits at most its fraction of the stake
+/// (a settlement plan may ask for less). Apart from collusion, an engine
+/// loses no more than `cap_per_period` base units over `cap_period_hours`
+/// (0 for no cap). A slash is executed `appeal_window_hours` after it is
+/// decided unless the engine appeals in the meantime.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlashingConfig {
+    pub wrong_verdict_fraction: f64,
+    pub collusion_fraction: f64,
+    pub rejected_dispute_fraction: f64,
+    pub cap_per_period: String,
+    pub cap_period_hours: i64,
+    pub appeal_window_hours: i64,
+    /// How often slashes past their appeal window are executed
+    pub executor_interval_seconds: u64,
+}
+
+/// Gasless claims: the treasury relays an analyst's EIP-2612 permit and
+/// pays the gas, keeping `fee_percentage` of the claim, and at least
+/// `min_fee` whole tokens, as the relay fee. Each owner address may have
+/// `max_claims_per_day` claims relayed over any 24 hours.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelayConfig {
+    pub enabled: bool,
+    pub fee_percentage: f64,
+    /// In whole tokens of the claimed token
+    pub min_fee: String,
+    pub max_claims_per_day: i64,
+    /// Gas of each `transferFrom`, which cannot be estimated before the
+    /// permit is mined
+    pub transfer_gas_limit: u64,
+    /// Permits must stay valid at least this long to be relayed
+    pub min_deadline_seconds: i64,
+    pub monitor_interval_seconds: u64,
+}
+
+/// Token/USD price feeds, read every `interval_seconds`. A flow is valued at
+/// the rate last observed before its transaction time, or the first after,
+/// if one was observed within `max_age_seconds` of it; otherwise it stays
+/// unpriced.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceConfig {
+    pub feeds: Vec<PriceFeed>,
+    pub coingecko_api_url: String,
+    pub coingecko_api_key: Option<String>,
+    pub interval_seconds: u64,
+    pub max_age_seconds: i64,
+    pub timeout_seconds: u64,
+}
+
+/// Where one reward token's USD rate comes from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceFeed {
+    pub symbol: String,
+    pub token_address: String,
+    pub decimals: u8,
+    pub source: PriceSource,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceSource {
+    /// CoinGecko coin ID
+    Coingecko(String),
+    /// Chainlink TOKEN/USD aggregator address
+    Chainlink(String),
+    /// Pegged rate, e.g. 1 for a USD stablecoin
+    Fixed(String),
+}
+
+impl PriceSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PriceSource::Coingecko(_) => "coingecko",
+            PriceSource::Chainlink(_) => "chainlink",
+            PriceSource::Fixed(_) => "fixed",
+        }
+    }
+}
+
+/// Platform fees, accrued to the treasury in the fee ledger.
+/// `reward_fee_percentage` of each bounty reward is kept when its payouts are
+/// opened, `slash_retention_percentage` of each slashed stake when the slash
+/// is executed (the rest goes to the engines that voted right), and
+/// `cancel_refund_fee_percentage` or `expiry_refund_fee_percentage` of the
+/// escrow of a cancelled or expired bounty when it is refunded. Sweeps move accrued fees from the treasury
+/// wallet to `sweep_address`, the only place they can be sent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeConfig {
+    pub reward_fee_percentage: f64,
+    pub slash_retention_percentage: f64,
+    pub cancel_refund_fee_percentage: f64,
+    pub expiry_refund_fee_percentage: f64,
+    pub sweep_address: Option<String>,
+}
+
+/// ERC-20 tokens bounty rewards may be paid in. The platform token at
+/// `TOKEN_CONTRACT_ADDRESS` is always allowed; `REWARD_TOKENS` adds others as
+/// comma-separated `SYMBOL:ADDRESS:DECIMALS` entries.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenConfig {
+    pub reward_tokens: Vec<RewardToken>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RewardToken {
+    pub symbol: String,
+    pub address: String,
+    /// Base units per whole token are 10^decimals
+    pub decimals: u8,
+}
+
+impl TokenConfig {
+    /// The allowlisted token at `address`
+    pub fn find(&self, address: &str) -> Option<&RewardToken> {
+        self.reward_tokens.iter().find(|token| token.address.eq_ignore_ascii_case(address))
+    }
+
+    /// The platform token, listed first
+    pub fn default_token(&self) -> &RewardToken {
+        &self.reward_tokens[0]
+    }
+}
+
+/// `PRICE_FEEDS` entries are SYMBOL:SOURCE:ID for allowlisted reward tokens,
+/// e.g. `THREAT:coingecko:nexus-threat,USDC:fixed:1`
+fn parse_price_feeds(tokens: &TokenConfig, spec: &str) -> Result<Vec<PriceFeed>> {
+    let mut feeds: Vec<PriceFeed> = Vec::new();
+    for entry in spec.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+        let parts: Vec<&str> = entry.split(':').map(str::trim).collect();
+        let [symbol, source, id] = parts[..] else {
+            anyhow::bail!("PRICE_FEEDS entry '{}' is not SYMBOL:SOURCE:ID", entry);
+        };
+        let Some(token) = tokens.reward_tokens.iter().find(|token| token.symbol.eq_ignore_ascii_case(symbol)) else {
+            anyhow::bail!("PRICE_FEEDS entry '{}' is not for a reward token", entry);
+        };
+        let source = match source {
+            "coingecko" => PriceSource::Coingecko(id.to_string()),
+            "chainlink" => {
+                if id.parse::<ethers::types::Address>().is_err() {
+                    anyhow::bail!("PRICE_FEEDS entry '{}' has an invalid aggregator address", entry);
+                }
+                PriceSource::Chainlink(id.to_string())
+            }
+            "fixed" => {
+                if !id.parse::<f64>().is_ok_and(|rate| rate > 0.0) {
+                    anyhow::bail!("PRICE_FEEDS entry '{}' has an invalid rate", entry);
+                }
+                PriceSource::Fixed(id.to_string())
+            }
+            _ => anyhow::bail!("PRICE_FEEDS entry '{}' has an unknown source (coingecko | chainlink | fixed)", entry),
+        };
+        if feeds.iter().any(|feed| feed.token_address.eq_ignore_ascii_case(&token.address)) {
+            anyhow::bail!("PRICE_FEEDS has more than one feed for {}", token.symbol);
+        }
+        feeds.push(PriceFeed {
+            symbol: token.symbol.clone(),
+            token_address: token.address.clone(),
+            decimals: token.decimals,
+            source,
+        });
+    }
+    Ok(feeds)
+}
+
+fn parse_reward_tokens(default_token: RewardToken, spec: &str) -> Result<Vec<RewardToken>> {
+    let mut tokens = vec![default_token];
+    for entry in spec.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+        let parts: Vec<&str> = entry.split(':').map(str::trim).collect();
+        let [symbol, address, decimals] = parts[..] else {
+            anyhow::bail!("REWARD_TOKENS entry '{}' is not SYMBOL:ADDRESS:DECIMALS", entry);
+        };
+        if address.parse::<ethers::types::Address>().is_err() {
+            anyhow::bail!("REWARD_TOKENS entry '{}' has an invalid address", entry);
+        }
+        let decimals: u8 = decimals.parse()?;
+        if decimals > 36 {
+            anyhow::bail!("REWARD_TOKENS entry '{}' has more than 36 decimals", entry);
+        }
+        if tokens.iter().any(|token: &RewardToken| token.address.eq_ignore_ascii_case(address)) {
+            continue;
+        }
+        tokens.push(RewardToken {
+            symbol: symbol.to_string(),
+            address: address.to_string(),
+            decimals,
+        });
+    }
+    Ok(tokens)
+}
+
 impl Config {
     pub fn from_env() -> Result<Self> {
-        Ok(Self {
+        let tokens = TokenConfig {
+            reward_tokens: parse_reward_tokens(
+                RewardToken {
+                    symbol: std::env::var("TOKEN_SYMBOL")
+                        .unwrap_or_else(|_| "THREAT".to_string()),
+                    address: std::env::var("TOKEN_CONTRACT_ADDRESS")?,
+                    decimals: std::env::var("TOKEN_DECIMALS")
+                        .unwrap_or_else(|_| "18".to_string())
+                        .parse()?,
+                },
+                &std::env::var("REWARD_TOKENS").unwrap_or_default(),
+            )?,
+        };
+        let config = Self {
             server: ServerConfig {
                 host: std::env::var("SERVER_HOST")
                     .unwrap_or_else(|_| "0.0.0.0".to_string()),
@@ -109,6 +425,293 @@ impl Config {
                     .unwrap_or_else(|_| "3".to_string())
                     .parse()?,
             },
-        })
+            indexer: IndexerConfig {
+                enabled: std::env::var("INDEXER_WEBHOOK_ENABLED")
+                    .map(|v| v == "true")
+                    .unwrap_or(false),
+                provider: std::env::var("INDEXER_PROVIDER")
+                    .unwrap_or_else(|_| "alchemy".to_string()),
+                signing_secret: std::env::var("INDEXER_SIGNING_SECRET").ok(),
+                max_clock_skew_seconds: std::env::var("INDEXER_MAX_CLOCK_SKEW")
+                    .unwrap_or_else(|_| "300".to_string())
+                    .parse()?,
+                replay_window_seconds: std::env::var("INDEXER_REPLAY_WINDOW")
+                    .unwrap_or_else(|_| "86400".to_string()) // 24 hours
+                    .parse()?,
+                fallback_poll_seconds: std::env::var("INDEXER_FALLBACK_POLL_SECONDS")
+                    .unwrap_or_else(|_| "300".to_string())
+                    .parse()?,
+            },
+            reconciliation: ReconciliationConfig {
+                interval_seconds: std::env::var("RECONCILIATION_INTERVAL_SECONDS")
+                    .unwrap_or_else(|_| "300".to_string())
+                    .parse()?,
+                warning_drift_wei: std::env::var("RECONCILIATION_WARNING_DRIFT_WEI")
+                    .unwrap_or_else(|_| "1000000000000000000".to_string()), // 1 token
+                critical_drift_wei: std::env::var("RECONCILIATION_CRITICAL_DRIFT_WEI")
+                    .unwrap_or_else(|_| "100000000000000000000".to_string()), // 100 tokens
+                alert_min_severity: std::env::var("RECONCILIATION_ALERT_MIN_SEVERITY")
+                    .unwrap_or_else(|_| "warning".to_string()),
+                alert_cooldown_seconds: std::env::var("RECONCILIATION_ALERT_COOLDOWN_SECONDS")
+                    .unwrap_or_else(|_| "3600".to_string()) // 1 hour
+                    .parse()?,
+            },
+            prices: PriceConfig {
+                feeds: parse_price_feeds(&tokens, &std::env::var("PRICE_FEEDS").unwrap_or_default())?,
+                coingecko_api_url: std::env::var("COINGECKO_API_URL")
+                    .unwrap_or_else(|_| "https://api.coingecko.com/api/v3".to_string()),
+                coingecko_api_key: std::env::var("COINGECKO_API_KEY")
+                    .ok()
+                    .filter(|v| !v.is_empty()),
+                interval_seconds: std::env::var("PRICE_FEED_INTERVAL_SECONDS")
+                    .unwrap_or_else(|_| "300".to_string()) // 5 minutes
+                    .parse()?,
+                max_age_seconds: std::env::var("PRICE_MAX_AGE_SECONDS")
+                    .unwrap_or_else(|_| "3600".to_string()) // 1 hour
+                    .parse()?,
+                timeout_seconds: std::env::var("PRICE_FEED_TIMEOUT_SECONDS")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()?,
+            },
+            relay: RelayConfig {
+                enabled: std::env::var("RELAY_ENABLED")
+                    .map(|v| v == "true")
+                    .unwrap_or(false),
+                fee_percentage: std::env::var("RELAY_FEE_PERCENTAGE")
+                    .unwrap_or_else(|_| "1".to_string())
+                    .parse()?,
+                min_fee: std::env::var("RELAY_MIN_FEE")
+                    .unwrap_or_else(|_| "1".to_string()),
+                max_claims_per_day: std::env::var("RELAY_MAX_CLAIMS_PER_DAY")
+                    .unwrap_or_else(|_| "3".to_string())
+                    .parse()?,
+                transfer_gas_limit: std::env::var("RELAY_TRANSFER_GAS_LIMIT")
+                    .unwrap_or_else(|_| "100000".to_string())
+                    .parse()?,
+                min_deadline_seconds: std::env::var("RELAY_MIN_DEADLINE_SECONDS")
+                    .unwrap_or_else(|_| "600".to_string()) // 10 minutes
+                    .parse()?,
+                monitor_interval_seconds: std::env::var("RELAY_MONITOR_INTERVAL_SECONDS")
+                    .unwrap_or_else(|_| "15".to_string())
+                    .parse()?,
+            },
+            tokens,
+            transactions: TransactionConfig {
+                check_interval_seconds: std::env::var("TX_REPLACEMENT_CHECK_SECONDS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()?,
+                stuck_after_seconds: std::env::var("TX_STUCK_AFTER_SECONDS")
+                    .unwrap_or_else(|_| "180".to_string()) // 3 minutes
+                    .parse()?,
+                fee_bump_percentage: std::env::var("TX_FEE_BUMP_PERCENTAGE")
+                    .unwrap_or_else(|_| "15".to_string())
+                    .parse()?,
+                max_replacements: std::env::var("TX_MAX_REPLACEMENTS")
+                    .unwrap_or_else(|_| "5".to_string())
+                    .parse()?,
+                batch_size: std::env::var("TX_REPLACEMENT_BATCH_SIZE")
+                    .unwrap_or_else(|_| "50".to_string())
+                    .parse()?,
+            },
+            payouts: PayoutConfig {
+                enabled: std::env::var("PAYOUTS_ENABLED")
+                    .map(|v| v == "true")
+                    .unwrap_or(false),
+                interval_seconds: std::env::var("PAYOUT_INTERVAL_SECONDS")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()?,
+                disperse_contract_address: std::env::var("DISPERSE_CONTRACT_ADDRESS")
+                    .ok()
+                    .filter(|v| !v.is_empty()),
+                max_batch_size: std::env::var("PAYOUT_MAX_BATCH_SIZE")
+                    .unwrap_or_else(|_| "100".to_string())
+                    .parse()?,
+                max_attempts: std::env::var("PAYOUT_MAX_ATTEMPTS")
+                    .unwrap_or_else(|_| "3".to_string())
+                    .parse()?,
+            },
+            withdrawals: WithdrawalConfig {
+                daily_limit: std::env::var("WITHDRAWAL_DAILY_LIMIT")
+                    .unwrap_or_else(|_| "10000000000000000000000".to_string()), // 10,000 tokens
+                approval_threshold: std::env::var("WITHDRAWAL_APPROVAL_THRESHOLD")
+                    .unwrap_or_else(|_| "1000000000000000000000".to_string()), // 1,000 tokens
+                required_approvals: std::env::var("WITHDRAWAL_REQUIRED_APPROVALS")
+                    .unwrap_or_else(|_| "2".to_string())
+                    .parse()?,
+                address_cooloff_hours: std::env::var("WITHDRAWAL_ADDRESS_COOLOFF_HOURS")
+                    .unwrap_or_else(|_| "24".to_string())
+                    .parse()?,
+                monitor_interval_seconds: std::env::var("WITHDRAWAL_MONITOR_INTERVAL_SECONDS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()?,
+            },
+            fees: FeeConfig {
+                reward_fee_percentage: std::env::var("PLATFORM_REWARD_FEE_PERCENTAGE")
+                    .unwrap_or_else(|_| "0".to_string())
+                    .parse()?,
+                slash_retention_percentage: std::env::var("PLATFORM_SLASH_RETENTION_PERCENTAGE")
+                    .unwrap_or_else(|_| "20".to_string())
+                    .parse()?,
+                cancel_refund_fee_percentage: std::env::var("PLATFORM_CANCEL_REFUND_FEE_PERCENTAGE")
+                    .unwrap_or_else(|_| "0".to_string())
+                    .parse()?,
+                expiry_refund_fee_percentage: std::env::var("PLATFORM_EXPIRY_REFUND_FEE_PERCENTAGE")
+                    .unwrap_or_else(|_| "0".to_string())
+                    .parse()?,
+                sweep_address: std::env::var("FEE_SWEEP_ADDRESS")
+                    .ok()
+                    .filter(|v| !v.is_empty()),
+            },
+            webhooks: WebhookConfig {
+                interval_seconds: std::env::var("PAYMENT_WEBHOOK_INTERVAL_SECONDS")
+                    .unwrap_or_else(|_| "5".to_string())
+                    .parse()?,
+                batch_size: std::env::var("PAYMENT_WEBHOOK_BATCH_SIZE")
+                    .unwrap_or_else(|_| "100".to_string())
+                    .parse()?,
+                max_attempts: std::env::var("PAYMENT_WEBHOOK_MAX_ATTEMPTS")
+                    .unwrap_or_else(|_| "8".to_string())
+                    .parse()?,
+                backoff_base_seconds: std::env::var("PAYMENT_WEBHOOK_BACKOFF_BASE_SECONDS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()?,
+                backoff_max_seconds: std::env::var("PAYMENT_WEBHOOK_BACKOFF_MAX_SECONDS")
+                    .unwrap_or_else(|_| "3600".to_string()) // 1 hour
+                    .parse()?,
+                timeout_seconds: std::env::var("PAYMENT_WEBHOOK_TIMEOUT_SECONDS")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()?,
+                max_per_user: std::env::var("PAYMENT_WEBHOOKS_PER_USER")
+                    .unwrap_or_else(|_| "5".to_string())
+                    .parse()?,
+            },
+            slashing: SlashingConfig {
+                wrong_verdict_fraction: std::env::var("SLASH_WRONG_VERDICT_FRACTION")
+                    .unwrap_or_else(|_| "1".to_string())
+                    .parse()?,
+                collusion_fraction: std::env::var("SLASH_COLLUSION_FRACTION")
+                    .unwrap_or_else(|_| "1".to_string())
+                    .parse()?,
+                rejected_dispute_fraction: std::env::var("SLASH_REJECTED_DISPUTE_FRACTION")
+                    .unwrap_or_else(|_| "1".to_string())
+                    .parse()?,
+                cap_per_period: std::env::var("SLASH_CAP_PER_PERIOD")
+                    .unwrap_or_else(|_| "0".to_string()),
+                cap_period_hours: std::env::var("SLASH_CAP_PERIOD_HOURS")
+                    .unwrap_or_else(|_| "168".to_string()) // 1 week
+                    .parse()?,
+                appeal_window_hours: std::env::var("SLASH_APPEAL_WINDOW_HOURS")
+                    .unwrap_or_else(|_| "48".to_string())
+                    .parse()?,
+                executor_interval_seconds: std::env::var("SLASH_EXECUTOR_INTERVAL_SECONDS")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()?,
+            },
+            idempotency: IdempotencyConfig {
+                retention_hours: std::env::var("IDEMPOTENCY_RETENTION_HOURS")
+                    .unwrap_or_else(|_| "72".to_string())
+                    .parse()?,
+                in_flight_seconds: std::env::var("IDEMPOTENCY_IN_FLIGHT_SECONDS")
+                    .unwrap_or_else(|_| "300".to_string()) // 5 minutes
+                    .parse()?,
+                purge_interval_seconds: std::env::var("IDEMPOTENCY_PURGE_INTERVAL_SECONDS")
+                    .unwrap_or_else(|_| "3600".to_string()) // 1 hour
+                    .parse()?,
+            },
+        };
+
+        if config.indexer.enabled && config.indexer.signing_secret.is_none() {
+            anyhow::bail!("INDEXER_SIGNING_SECRET is required when INDEXER_WEBHOOK_ENABLED=true");
+        }
+
+        if config.payouts.max_batch_size == 0 || config.payouts.max_attempts < 1 {
+            anyhow::bail!("PAYOUT_MAX_BATCH_SIZE and PAYOUT_MAX_ATTEMPTS must be positive");
+        }
+        if config
+            .payouts
+            .disperse_contract_address
+            .as_deref()
+            .is_some_and(|address| address.parse::<ethers::types::Address>().is_err())
+        {
+            anyhow::bail!("DISPERSE_CONTRACT_ADDRESS is not an address");
+        }
+
+        if config.idempotency.retention_hours < 1 || config.idempotency.in_flight_seconds < 1 {
+            anyhow::bail!("IDEMPOTENCY_RETENTION_HOURS and IDEMPOTENCY_IN_FLIGHT_SECONDS must be positive");
+        }
+
+        for (name, value) in [
+            ("WITHDRAWAL_DAILY_LIMIT", &config.withdrawals.daily_limit),
+            ("WITHDRAWAL_APPROVAL_THRESHOLD", &config.withdrawals.approval_threshold),
+        ] {
+            if ethers::types::U256::from_dec_str(value).is_err() {
+                anyhow::bail!("{} must be a whole number of base units", name);
+            }
+        }
+        if config.withdrawals.required_approvals < 2 {
+            anyhow::bail!("WITHDRAWAL_REQUIRED_APPROVALS must be at least 2; held withdrawals need two people");
+        }
+        if config.withdrawals.address_cooloff_hours < 0 {
+            anyhow::bail!("WITHDRAWAL_ADDRESS_COOLOFF_HOURS must not be negative");
+        }
+
+        for (name, value) in [
+            ("PLATFORM_REWARD_FEE_PERCENTAGE", config.fees.reward_fee_percentage),
+            ("PLATFORM_SLASH_RETENTION_PERCENTAGE", config.fees.slash_retention_percentage),
+            ("PLATFORM_CANCEL_REFUND_FEE_PERCENTAGE", config.fees.cancel_refund_fee_percentage),
+            ("PLATFORM_EXPIRY_REFUND_FEE_PERCENTAGE", config.fees.expiry_refund_fee_percentage),
+        ] {
+            if !(0.0..=100.0).contains(&value) {
+                anyhow::bail!("{} must be between 0 and 100", name);
+            }
+        }
+        if config
+            .fees
+            .sweep_address
+            .as_deref()
+            .is_some_and(|address| address.parse::<ethers::types::Address>().is_err())
+        {
+            anyhow::bail!("FEE_SWEEP_ADDRESS is not an address");
+        }
+
+        if config.webhooks.batch_size < 1 || config.webhooks.max_attempts < 1 || config.webhooks.max_per_user < 1 {
+            anyhow::bail!("PAYMENT_WEBHOOK_BATCH_SIZE, PAYMENT_WEBHOOK_MAX_ATTEMPTS and PAYMENT_WEBHOOKS_PER_USER must be positive");
+        }
+
+        for (name, value) in [
+            ("SLASH_WRONG_VERDICT_FRACTION", config.slashing.wrong_verdict_fraction),
+            ("SLASH_COLLUSION_FRACTION", config.slashing.collusion_fraction),
+            ("SLASH_REJECTED_DISPUTE_FRACTION", config.slashing.rejected_dispute_fraction),
+        ] {
+            if !(0.0..=1.0).contains(&value) {
+                anyhow::bail!("{} must be between 0 and 1", name);
+            }
+        }
+        if ethers::types::U256::from_dec_str(&config.slashing.cap_per_period).is_err() {
+            anyhow::bail!("SLASH_CAP_PER_PERIOD must be a whole number of base units");
+        }
+        if config.slashing.cap_period_hours < 1 || config.slashing.appeal_window_hours < 0 {
+            anyhow::bail!("SLASH_CAP_PERIOD_HOURS must be positive and SLASH_APPEAL_WINDOW_HOURS not negative");
+        }
+
+        if config.prices.interval_seconds == 0 || config.prices.max_age_seconds < 1 {
+            anyhow::bail!("PRICE_FEED_INTERVAL_SECONDS and PRICE_MAX_AGE_SECONDS must be positive");
+        }
+
+        if !(0.0..100.0).contains(&config.relay.fee_percentage) {
+            anyhow::bail!("RELAY_FEE_PERCENTAGE must be at least 0 and below 100");
+        }
+        if !config.relay.min_fee.parse::<rust_decimal::Decimal>().is_ok_and(|fee| !fee.is_sign_negative()) {
+            anyhow::bail!("RELAY_MIN_FEE must be a non-negative number of tokens");
+        }
+        if config.relay.max_claims_per_day < 1 || config.relay.transfer_gas_limit == 0 {
+            anyhow::bail!("RELAY_MAX_CLAIMS_PER_DAY and RELAY_TRANSFER_GAS_LIMIT must be positive");
+        }
+
+        if config.transactions.fee_bump_percentage < 10 {
+            anyhow::bail!("TX_FEE_BUMP_PERCENTAGE must be at least 10; nodes reject smaller replacements");
+        }
+
+        Ok(config)
     }
 }
@@ -1,6 +1,16 @@
-use axum::{extract::{State, Path}, response::Json, http::StatusCode};
+use axum::{extract::{State, Path, Query}, response::{IntoResponse, Json, Response}, http::{HeaderMap, StatusCode}};
+use serde::Deserialize;
 use serde_json::{json, Value};
+use shared::permissions::{self, Scopes};
 use std::sync::Arc;
+use tracing::error;
+use uuid::Uuid;
+use crate::handlers::payment::{csv_response, escrow_error, ReportParams};
+use crate::models::{PaymentError, WithdrawalDecisionRequest};
+use crate::services::dead_letters::{DeadLetterParams, FailureReason, InterventionRequest};
+use crate::services::slashing::{self, AppealDecisionRequest};
+use crate::services::fees::SweepRequest;
+use crate::services::reconciliation::{self, Severity};
 use crate::AppState;
 
 pub async fn get_pending_payments(
@@ -27,3 +37,472 @@ pub async fn get_treasury_balance(
 ) -> (StatusCode, Json<Value>) {
     (StatusCode::OK, Json(json!({"balance": "0"})))
 }
+
+fn payout_error(e: PaymentError) -> (StatusCode, Json<Value>) {
+    match e {
+        PaymentError::NotFound(message) => (StatusCode::NOT_FOUND, Json(json!({"error": message}))),
+        e => {
+            error!("Failed to load payouts: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({"error": "Failed to load payouts"})))
+        }
+    }
+}
+
+/// A bounty's reward payout batch with the status of each recipient
+pub async fn get_payout_batch(
+    State(state): State<Arc<AppState>>,
+    Path(bounty_id): Path<Uuid>,
+) -> (StatusCode, Json<Value>) {
+    match state.payouts.find(bounty_id).await {
+        Ok(Some(batch)) => (StatusCode::OK, Json(json!(batch))),
+        Ok(None) => payout_error(PaymentError::NotFound(format!("No payouts for bounty {}", bounty_id))),
+        Err(e) => payout_error(e),
+    }
+}
+
+/// Send a bounty's failed payouts again
+pub async fn retry_payout_batch(
+    State(state): State<Arc<AppState>>,
+    Path(bounty_id): Path<Uuid>,
+) -> (StatusCode, Json<Value>) {
+    match state.payouts.retry(bounty_id).await {
+        Ok(batch) => (StatusCode::OK, Json(json!({"message": "Failed payouts queued again", "batch": batch}))),
+        Err(e) => payout_error(e),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReconciliationReportParams {
+    /// Only return entries at or above this severity (ok | info | warning | critical)
+    pub min_severity: Option<String>,
+    pub page: Option<u32>,
+    pub per_page: Option<u32>,
+}
+
+impl ReconciliationReportParams {
+    fn severity(&self) -> Result<Severity, (StatusCode, Json<Value>)> {
+        match self.min_severity.as_deref() {
+            None => Ok(Severity::Ok),
+            Some(value) => Severity::parse(value).ok_or_else(|| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    Json(json!({"error": format!("Unknown severity '{}'", value)})),
+                )
+            }),
+        }
+    }
+
+    /// (page, per_page, limit, offset)
+    fn page(&self) -> (u32, u32, i64, i64) {
+        let page = self.page.unwrap_or(1).max(1);
+        let per_page = self.per_page.unwrap_or(20).clamp(1, 100);
+        (page, per_page, per_page as i64, (page - 1) as i64 * per_page as i64)
+    }
+}
+
+fn report_error(e: anyhow::Error) -> (StatusCode, Json<Value>) {
+    error!("Failed to load reconciliation report: {:#}", e);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(json!({"error": "Failed to load reconciliation report"})),
+    )
+}
+
+/// Reconciliation runs, newest first
+pub async fn list_reconciliation_runs(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<ReconciliationReportParams>,
+) -> (StatusCode, Json<Value>) {
+    let severity = match params.severity() {
+        Ok(severity) => severity,
+        Err(response) => return response,
+    };
+    let (page, per_page, limit, offset) = params.page();
+
+    match reconciliation::list_runs(&state.db_pool, severity, limit, offset).await {
+        Ok((runs, total)) => (
+            StatusCode::OK,
+            Json(json!({"runs": runs, "total": total, "page": page, "per_page": per_page})),
+        ),
+        Err(e) => report_error(e),
+    }
+}
+
+pub async fn get_reconciliation_run(
+    State(state): State<Arc<AppState>>,
+    Path(run_id): Path<Uuid>,
+) -> (StatusCode, Json<Value>) {
+    match reconciliation::get_run(&state.db_pool, run_id).await {
+        Ok(Some(run)) => (StatusCode::OK, Json(json!(run))),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": format!("Reconciliation run {} not found", run_id)})),
+        ),
+        Err(e) => report_error(e),
+    }
+}
+
+/// Per-wallet deltas of a run, largest first
+pub async fn get_reconciliation_wallets(
+    State(state): State<Arc<AppState>>,
+    Path(run_id): Path<Uuid>,
+    Query(params): Query<ReconciliationReportParams>,
+) -> (StatusCode, Json<Value>) {
+    let severity = match params.severity() {
+        Ok(severity) => severity,
+        Err(response) => return response,
+    };
+    let (page, per_page, limit, offset) = params.page();
+
+    match reconciliation::list_wallet_deltas(&state.db_pool, run_id, severity, limit, offset).await {
+        Ok((wallets, total)) => (
+            StatusCode::OK,
+            Json(json!({"wallets": wallets, "total": total, "page": page, "per_page": per_page})),
+        ),
+        Err(e) => report_error(e),
+    }
+}
+
+/// Transactions of a run that did not match the chain or the indexer
+pub async fn get_reconciliation_transactions(
+    State(state): State<Arc<AppState>>,
+    Path(run_id): Path<Uuid>,
+    Query(params): Query<ReconciliationReportParams>,
+) -> (StatusCode, Json<Value>) {
+    let severity = match params.severity() {
+        Ok(severity) => severity,
+        Err(response) => return response,
+    };
+    let (page, per_page, limit, offset) = params.page();
+
+    match reconciliation::list_unmatched_transactions(&state.db_pool, run_id, severity, limit, offset)
+        .await
+    {
+        Ok((transactions, total)) => (
+            StatusCode::OK,
+            Json(json!({
+                "transactions": transactions,
+                "total": total,
+                "page": page,
+                "per_page": per_page
+            })),
+        ),
+        Err(e) => report_error(e),
+    }
+}
+
+/// The admin making a request, as forwarded by the API gateway
+fn admin(headers: &HeaderMap) -> Result<Uuid, (StatusCode, Json<Value>)> {
+    let admin_id = headers
+        .get("x-user-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| Uuid::parse_str(v).ok())
+        .ok_or_else(|| {
+            (
+                StatusCode::UNAUTHORIZED,
+                Json(json!({"error": "Missing or invalid X-User-Id header"})),
+            )
+        })?;
+    if !Scopes::from_headers(headers).allows(permissions::PAYMENTS_APPROVE) {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": format!("Missing permission {}", permissions::PAYMENTS_APPROVE)})),
+        ));
+    }
+    Ok(admin_id)
+}
+
+/// Withdrawals held for approval, oldest first
+pub async fn get_held_withdrawals(State(state): State<Arc<AppState>>) -> (StatusCode, Json<Value>) {
+    match state.withdrawals.held().await {
+        Ok(withdrawals) => (StatusCode::OK, Json(json!({"withdrawals": withdrawals}))),
+        Err(e) => escrow_error(e),
+    }
+}
+
+/// A withdrawal with its approvals and events
+pub async fn get_withdrawal(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+) -> (StatusCode, Json<Value>) {
+    match state.withdrawals.find(id).await {
+        Ok(Some(withdrawal)) => (StatusCode::OK, Json(json!(withdrawal))),
+        Ok(None) => escrow_error(PaymentError::NotFound(format!("Withdrawal {} not found", id))),
+        Err(e) => escrow_error(e),
+    }
+}
+
+/// Approve a held withdrawal; it is sent once enough other admins approve
+pub async fn approve_withdrawal(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(payload): Json<WithdrawalDecisionRequest>,
+) -> (StatusCode, Json<Value>) {
+    decide_withdrawal(&state, id, &headers, true, payload.note.as_deref()).await
+}
+
+/// Reject a held withdrawal
+pub async fn reject_withdrawal(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(payload): Json<WithdrawalDecisionRequest>,
+) -> (StatusCode, Json<Value>) {
+    decide_withdrawal(&state, id, &headers, false, payload.note.as_deref()).await
+}
+
+async fn decide_withdrawal(
+    state: &AppState,
+    id: Uuid,
+    headers: &HeaderMap,
+    approved: bool,
+    note: Option<&str>,
+) -> (StatusCode, Json<Value>) {
+    let admin_id = match admin(headers) {
+        Ok(admin_id) => admin_id,
+        Err(response) => return response,
+    };
+    match state.withdrawals.decide(id, admin_id, approved, note).await {
+        Ok(withdrawal) => (StatusCode::OK, Json(json!({"withdrawal": withdrawal}))),
+        Err(e) => escrow_error(e),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FeeLedgerParams {
+    pub token_address: Option<String>,
+    pub page: Option<u32>,
+    pub per_page: Option<u32>,
+}
+
+/// Accrued, swept and available platform fees per token
+pub async fn get_fees(State(state): State<Arc<AppState>>) -> (StatusCode, Json<Value>) {
+    match state.fees.summary().await {
+        Ok(summary) => (StatusCode::OK, Json(json!(summary))),
+        Err(e) => escrow_error(e),
+    }
+}
+
+/// Deposits in, payments and payouts out and fees accrued over a period,
+/// valued in USD at the time of each
+pub async fn get_treasury_report(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<ReportParams>,
+) -> Response {
+    let (from, to, csv) = match params.resolve() {
+        Ok(period) => period,
+        Err(e) => return escrow_error(e).into_response(),
+    };
+    match state.prices.treasury_report(from, to).await {
+        Ok(report) if csv => csv_response(
+            &format!("treasury-{}-{}", from.format("%Y%m%d"), to.format("%Y%m%d")),
+            report.to_csv(),
+        ),
+        Ok(report) => (StatusCode::OK, Json(json!(report))).into_response(),
+        Err(e) => escrow_error(e).into_response(),
+    }
+}
+
+/// Fee ledger entries, newest first
+pub async fn get_fee_ledger(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<FeeLedgerParams>,
+) -> (StatusCode, Json<Value>) {
+    let page = params.page.unwrap_or(1).max(1);
+    let per_page = params.per_page.unwrap_or(20).clamp(1, 100);
+    let offset = (page - 1) as i64 * per_page as i64;
+
+    match state.fees.ledger(params.token_address.as_deref(), per_page as i64, offset).await {
+        Ok((entries, total)) => (
+            StatusCode::OK,
+            Json(json!({"entries": entries, "total": total, "page": page, "per_page": per_page})),
+        ),
+        Err(e) => escrow_error(e),
+    }
+}
+
+/// Treasury sweeps of accrued fees, newest first
+pub async fn get_fee_sweeps(State(state): State<Arc<AppState>>) -> (StatusCode, Json<Value>) {
+    match state.fees.sweeps(100).await {
+        Ok(sweeps) => (StatusCode::OK, Json(json!({"sweeps": sweeps}))),
+        Err(e) => escrow_error(e),
+    }
+}
+
+/// Sweep accrued fees of one token from the treasury to the sweep address
+pub async fn sweep_fees(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<SweepRequest>,
+) -> (StatusCode, Json<Value>) {
+    let admin_id = match admin(&headers) {
+        Ok(admin_id) => admin_id,
+        Err(response) => return response,
+    };
+    match state.fees.sweep(&payload, admin_id).await {
+        Ok(sweep) => (StatusCode::CREATED, Json(json!({"sweep": sweep}))),
+        Err(e) => escrow_error(e),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeadLetterListParams {
+    /// Only payments whose latest failure was this
+    /// (insufficient_gas | reverted | nonce_gap | other)
+    pub reason: Option<String>,
+    pub page: Option<u32>,
+    pub per_page: Option<u32>,
+}
+
+/// Dead-lettered payments, most recent first
+pub async fn get_dead_letters(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<DeadLetterListParams>,
+) -> (StatusCode, Json<Value>) {
+    let reason = match params.reason.as_deref() {
+        None => None,
+        Some(value) => match FailureReason::parse(value) {
+            Some(reason) => Some(reason),
+            None => {
+                return escrow_error(PaymentError::ValidationError(format!("Unknown failure reason '{}'", value)))
+            }
+        },
+    };
+    let page = params.page.unwrap_or(1).max(1);
+    let per_page = params.per_page.unwrap_or(20).clamp(1, 100);
+    let offset = (page - 1) as i64 * per_page as i64;
+
+    match state.dead_letters.list(reason, per_page as i64, offset).await {
+        Ok((payments, total)) => (
+            StatusCode::OK,
+            Json(json!({"payments": payments, "total": total, "page": page, "per_page": per_page})),
+        ),
+        Err(e) => escrow_error(e),
+    }
+}
+
+/// A payment with its transactions and the interventions on it
+pub async fn get_dead_letter(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+) -> (StatusCode, Json<Value>) {
+    match state.dead_letters.find(id).await {
+        Ok(Some(payment)) => (StatusCode::OK, Json(json!(payment))),
+        Ok(None) => escrow_error(PaymentError::NotFound(format!("Payment {} not found", id))),
+        Err(e) => escrow_error(e),
+    }
+}
+
+/// Set the gas price, gas limit and nonce a dead-lettered payment is sent
+/// with once requeued
+pub async fn update_dead_letter(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(payload): Json<DeadLetterParams>,
+) -> (StatusCode, Json<Value>) {
+    let admin_id = match admin(&headers) {
+        Ok(admin_id) => admin_id,
+        Err(response) => return response,
+    };
+    match state.dead_letters.update(id, &payload, admin_id).await {
+        Ok(payment) => (StatusCode::OK, Json(json!({"payment": payment}))),
+        Err(e) => escrow_error(e),
+    }
+}
+
+/// Queue a dead-lettered payment to be sent again
+pub async fn requeue_dead_letter(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(payload): Json<InterventionRequest>,
+) -> (StatusCode, Json<Value>) {
+    let admin_id = match admin(&headers) {
+        Ok(admin_id) => admin_id,
+        Err(response) => return response,
+    };
+    match state.dead_letters.requeue(id, &payload, admin_id).await {
+        Ok(payment) => (StatusCode::OK, Json(json!({"payment": payment}))),
+        Err(e) => escrow_error(e),
+    }
+}
+
+/// Give up on a dead-lettered payment
+pub async fn cancel_dead_letter(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(payload): Json<InterventionRequest>,
+) -> (StatusCode, Json<Value>) {
+    let admin_id = match admin(&headers) {
+        Ok(admin_id) => admin_id,
+        Err(response) => return response,
+    };
+    match state.dead_letters.cancel(id, &payload, admin_id).await {
+        Ok(payment) => (StatusCode::OK, Json(json!({"payment": payment}))),
+        Err(e) => escrow_error(e),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SlashListParams {
+    /// Only slashes in this status (pending | appealed | executed | overturned)
+    pub status: Option<String>,
+    pub page: Option<u32>,
+    pub per_page: Option<u32>,
+}
+
+/// Stake slashes, most recent first
+pub async fn get_slashes(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<SlashListParams>,
+) -> (StatusCode, Json<Value>) {
+    if let Some(status) = params.status.as_deref() {
+        if ![slashing::PENDING, slashing::APPEALED, slashing::EXECUTED, slashing::OVERTURNED].contains(&status) {
+            return escrow_error(PaymentError::ValidationError(format!("Unknown slash status '{}'", status)));
+        }
+    }
+    let page = params.page.unwrap_or(1).max(1);
+    let per_page = params.per_page.unwrap_or(20).clamp(1, 100);
+    let offset = (page - 1) as i64 * per_page as i64;
+
+    match state.slashing.list(params.status.as_deref(), per_page as i64, offset).await {
+        Ok((slashes, total)) => (
+            StatusCode::OK,
+            Json(json!({"slashes": slashes, "total": total, "page": page, "per_page": per_page})),
+        ),
+        Err(e) => escrow_error(e),
+    }
+}
+
+/// A slash with its redistribution payments
+pub async fn get_slash(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+) -> (StatusCode, Json<Value>) {
+    match state.slashing.find(id).await {
+        Ok(Some(slash)) => (StatusCode::OK, Json(json!(slash))),
+        Ok(None) => escrow_error(PaymentError::NotFound(format!("Slash {} not found", id))),
+        Err(e) => escrow_error(e),
+    }
+}
+
+/// Decide an appealed slash: overturn it, releasing the stake, or let it be
+/// executed
+pub async fn decide_slash_appeal(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(payload): Json<AppealDecisionRequest>,
+) -> (StatusCode, Json<Value>) {
+    let admin_id = match admin(&headers) {
+        Ok(admin_id) => admin_id,
+        Err(response) => return response,
+    };
+    match state.slashing.decide(id, admin_id, &payload).await {
+        Ok(slash) => (StatusCode::OK, Json(json!({"slash": slash}))),
+        Err(e) => escrow_error(e),
+    }
+}
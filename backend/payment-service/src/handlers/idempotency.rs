@@ -0,0 +1,159 @@
+use axum::{
+    body::{self, Body},
+    extract::{Request, State},
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use tracing::{error, warn};
+
+use crate::services::idempotency::{self, Claim, KeyScope};
+use crate::AppState;
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+
+/// Longest accepted `Idempotency-Key`
+const MAX_KEY_LENGTH: usize = 255;
+
+/// Request and response bodies are buffered up to this size
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// Caller of requests that are not signed (signing disabled in development)
+const UNSIGNED_CALLER: &str = "unsigned";
+
+fn is_valid_key(key: &str) -> bool {
+    !key.is_empty() && key.len() <= MAX_KEY_LENGTH && key.bytes().all(|b| b.is_ascii_graphic())
+}
+
+/// Whether a response is the request's outcome. Server and gateway errors
+/// (a database or RPC outage) are not: the key is released so the caller's
+/// retry runs the request again.
+fn is_storable(status: StatusCode) -> bool {
+    !status.is_server_error() && !matches!(status, StatusCode::UNAUTHORIZED | StatusCode::TOO_MANY_REQUESTS)
+}
+
+fn rejected(status: StatusCode, message: &str) -> Response {
+    (status, Json(json!({"error": message}))).into_response()
+}
+
+/// Idempotency for mutating payment routes (install with `route_layer`,
+/// inside the signature check so the caller's key id is verified).
+///
+/// A request carrying an `Idempotency-Key` claims it for its caller and
+/// route before the handler runs. Retries with the same key get the first
+/// response back with `Idempotent-Replayed: true`, so a deposit,
+/// distribution or slash retried after a network blip is applied once. A
+/// retry arriving while the first request runs gets 409, and reusing the key
+/// for a different body 422. Requests without a key run as before.
+///
+/// Unlike the gateway's Redis store this fails closed: when the key cannot
+/// be claimed the request is refused with 503 rather than risk running twice.
+pub async fn idempotency_middleware(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(key) = request.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return next.run(request).await;
+    };
+    let key = match key.to_str() {
+        Ok(key) if is_valid_key(key) => key.to_string(),
+        _ => {
+            return rejected(
+                StatusCode::BAD_REQUEST,
+                &format!("Idempotency-Key must be 1-{} printable ASCII characters", MAX_KEY_LENGTH),
+            )
+        }
+    };
+    let caller = request
+        .headers()
+        .get(shared::request_signing::KEY_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or(UNSIGNED_CALLER)
+        .to_string();
+    let scope = KeyScope {
+        caller,
+        route: format!("{} {}", request.method(), request.uri().path()),
+        key,
+    };
+
+    let (parts, body) = request.into_parts();
+    let bytes = match body::to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(_) => return rejected(StatusCode::PAYLOAD_TOO_LARGE, "Request body too large"),
+    };
+    let fingerprint = hex::encode(Sha256::digest(&bytes));
+    let request = Request::from_parts(parts, Body::from(bytes));
+
+    let config = &state.config.idempotency;
+    match idempotency::claim(&state.db_pool, config, &scope, &fingerprint).await {
+        Ok(Claim::Claimed) => {}
+        Ok(Claim::Replay { status, body }) => {
+            let status = StatusCode::from_u16(status).unwrap_or(StatusCode::OK);
+            let mut response = (status, Json(body)).into_response();
+            response
+                .headers_mut()
+                .insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+            return response;
+        }
+        Ok(Claim::InFlight) => {
+            let mut response = rejected(
+                StatusCode::CONFLICT,
+                "A request with this Idempotency-Key is still being processed",
+            );
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from_static("1"));
+            return response;
+        }
+        Ok(Claim::Mismatch) => {
+            return rejected(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "Idempotency-Key was already used for a different request",
+            )
+        }
+        Err(e) => {
+            error!("Failed to claim idempotency key {:?}: {}", scope, e);
+            return rejected(StatusCode::SERVICE_UNAVAILABLE, "Idempotency store unavailable");
+        }
+    }
+
+    let response = next.run(request).await;
+    if !is_storable(response.status()) {
+        release(&state, &scope).await;
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let bytes = match body::to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            release(&state, &scope).await;
+            warn!("Failed to buffer response for idempotency key {:?}: {}", scope, e);
+            return rejected(StatusCode::INTERNAL_SERVER_ERROR, "Failed to read response");
+        }
+    };
+    match serde_json::from_slice::<Value>(&bytes) {
+        Ok(value) => {
+            let stored = idempotency::complete(&state.db_pool, config, &scope, parts.status.as_u16(), &value).await;
+            if let Err(e) = stored {
+                // The claim stays in flight until it lapses, so a retry
+                // meanwhile gets 409 rather than running again
+                error!("Failed to store response for idempotency key {:?}: {}", scope, e);
+            }
+        }
+        Err(_) => release(&state, &scope).await,
+    }
+
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+async fn release(state: &AppState, scope: &KeyScope) {
+    if let Err(e) = idempotency::release(&state.db_pool, scope).await {
+        warn!("Failed to release idempotency key {:?}: {}", scope, e);
+    }
+}
@@ -0,0 +1,46 @@
+use axum::{body::Bytes, extract::State, http::{HeaderMap, StatusCode}, response::Json};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tracing::{error, warn};
+
+use crate::services::indexer::{self, IndexerError};
+use crate::AppState;
+
+/// Receive on-chain activity pushed by the configured indexer.
+/// Non-2xx responses make the indexer retry, so only transient failures
+/// return 5xx.
+pub async fn receive_indexer_webhook(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> (StatusCode, Json<Value>) {
+    let config = &state.config.indexer;
+    if !config.enabled {
+        return (StatusCode::NOT_FOUND, Json(json!({"error": "Indexer ingestion is disabled"})));
+    }
+
+    if let Err(e) = indexer::verify_signature(config, &headers, &body) {
+        warn!("Rejected indexer webhook: {}", e);
+        return (StatusCode::UNAUTHORIZED, Json(json!({"error": e.to_string()})));
+    }
+
+    let delivery = match indexer::parse_delivery(&config.provider, &body) {
+        Ok(delivery) => delivery,
+        Err(e @ IndexerError::Malformed(_)) => {
+            return (StatusCode::BAD_REQUEST, Json(json!({"error": e.to_string()})));
+        }
+        Err(e) => return (StatusCode::UNAUTHORIZED, Json(json!({"error": e.to_string()}))),
+    };
+
+    let mut redis = state.redis_conn.clone();
+    match indexer::ingest(&state.payment_service, &mut redis, delivery).await {
+        Ok(summary) => (StatusCode::OK, Json(json!(summary))),
+        Err(e) => {
+            error!("Failed to ingest indexer delivery: {:#}", e);
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(json!({"error": "Failed to process delivery"})),
+            )
+        }
+    }
+}
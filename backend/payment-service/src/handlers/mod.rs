@@ -1,3 +1,6 @@
 pub mod health;
 pub mod payment;
 pub mod admin;
+pub mod indexer;
+pub mod idempotency;
+pub mod webhooks;
@@ -1,33 +1,157 @@
-use axum::{extract::{State, Path}, response::Json, http::StatusCode};
+use axum::{extract::{State, Path, Query}, response::{IntoResponse, Json, Response}, http::{header, HeaderMap, StatusCode}};
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
 use serde_json::{json, Value};
+use shared::permissions::{self, Scopes};
+use shared::types::OrganizationRole;
 use std::sync::Arc;
+use tracing::error;
+use uuid::Uuid;
 use crate::AppState;
 use crate::models::*;
+use crate::services::escrow::RefundBountyRequest;
+use crate::services::relay::{PermitQuery, RelayClaimRequest};
+use crate::services::slashing::{AppealRequest, SlashStakeRequest};
+use crate::services::{escrow, funding, nonces, prices, stake, withdrawals};
 
+pub(crate) fn escrow_error(e: PaymentError) -> (StatusCode, Json<Value>) {
+    let status = match &e {
+        PaymentError::ValidationError(_) => StatusCode::BAD_REQUEST,
+        PaymentError::InsufficientBalance(_) => StatusCode::PAYMENT_REQUIRED,
+        PaymentError::NotFound(_) => StatusCode::NOT_FOUND,
+        PaymentError::AlreadyProcessed(_) => StatusCode::CONFLICT,
+        PaymentError::LimitExceeded(_) => StatusCode::TOO_MANY_REQUESTS,
+        PaymentError::NotPermitted(_) => StatusCode::FORBIDDEN,
+        PaymentError::BlockchainError(_) => StatusCode::BAD_GATEWAY,
+        _ => {
+            error!("Escrow operation failed: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    };
+    (status, Json(json!({"error": e.to_string()})))
+}
+
+/// The user the gateway authenticated, from X-User-Id
+pub(crate) fn caller_id(headers: &HeaderMap) -> Result<Uuid, (StatusCode, Json<Value>)> {
+    headers
+        .get("x-user-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| Uuid::parse_str(v).ok())
+        .ok_or_else(|| (StatusCode::UNAUTHORIZED, Json(json!({"error": "Missing or invalid X-User-Id header"}))))
+}
+
+/// The caller, refused unless they are `user_id`
+pub(crate) fn require_user(headers: &HeaderMap, user_id: Uuid) -> Result<Uuid, (StatusCode, Json<Value>)> {
+    let caller = caller_id(headers)?;
+    if caller != user_id {
+        return Err((StatusCode::FORBIDDEN, Json(json!({"error": "Not permitted for another user"}))));
+    }
+    Ok(caller)
+}
+
+/// Open the reward escrow for a new bounty, or attach the creator's deposit
+/// transaction to it. The escrow is funded once the deposit confirms.
 pub async fn deposit_bounty_reward(
     State(state): State<Arc<AppState>>,
     Json(payload): Json<DepositBountyRequest>,
 ) -> (StatusCode, Json<Value>) {
-    // Pre-flight check: verify the creator has sufficient token balance
-    match state.payment_service.get_token_balance(&payload.creator_address).await {
-        Ok(balance) => {
-            let required = ethers::types::U256::from_dec_str(&payload.amount.to_string()).unwrap_or_default();
-            if balance < required {
-                return (StatusCode::BAD_REQUEST, Json(json!({
-                    "error": "Insufficient token balance",
-                    "balance": format!("{}", balance),
-                    "required": format!("{}", required)
-                })));
-            }
-            (StatusCode::OK, Json(json!({
-                "message": "Bounty deposit pre-check passed",
-                "balance": format!("{}", balance),
-                "bounty_id": payload.bounty_id
-            })))
-        }
-        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({
-            "error": format!("Failed to check balance: {}", e)
+    match escrow::open(&state.payment_service, &payload).await {
+        Ok(escrow) => (StatusCode::OK, Json(json!({
+            "message": if escrow.is_funded() { "Bounty reward escrowed" } else { "Awaiting deposit confirmation" },
+            "escrow": escrow
         }))),
+        Err(e) => escrow_error(e),
+    }
+}
+
+pub async fn get_bounty_escrow(
+    State(state): State<Arc<AppState>>,
+    Path(bounty_id): Path<Uuid>,
+) -> (StatusCode, Json<Value>) {
+    match escrow::find(&state.db_pool, bounty_id).await {
+        Ok(Some(escrow)) => (StatusCode::OK, Json(json!({"escrow": escrow}))),
+        Ok(None) => escrow_error(PaymentError::NotFound(format!("No escrow for bounty {}", bounty_id))),
+        Err(e) => escrow_error(e),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OrganizationEscrowParams {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Escrows of an organization's bounties, newest first. Open to the
+/// organization's owners, admins and billing members as forwarded by the
+/// gateway, and to callers with the payments:approve scope.
+pub async fn get_organization_escrows(
+    State(state): State<Arc<AppState>>,
+    Path(org_id): Path<Uuid>,
+    headers: HeaderMap,
+    Query(params): Query<OrganizationEscrowParams>,
+) -> (StatusCode, Json<Value>) {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let is_admin = Scopes::from_headers(&headers).allows(permissions::PAYMENTS_APPROVE);
+    let manages_billing = header("x-org-id").and_then(|v| Uuid::parse_str(v).ok()) == Some(org_id)
+        && header("x-org-role")
+            .and_then(|v| v.parse::<OrganizationRole>().ok())
+            .is_some_and(|role| role.manages_billing());
+    if !is_admin && !manages_billing {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": "Organization billing role required"})),
+        );
+    }
+
+    let limit = params.limit.unwrap_or(50).clamp(1, 200);
+    let offset = params.offset.unwrap_or(0).max(0);
+    match escrow::list_for_organization(&state.db_pool, org_id, limit, offset).await {
+        Ok(escrows) => (StatusCode::OK, Json(json!({"escrows": escrows}))),
+        Err(e) => escrow_error(e),
+    }
+}
+
+/// Release a funded escrow when its bounty completes
+pub async fn release_bounty_escrow(
+    State(state): State<Arc<AppState>>,
+    Path(bounty_id): Path<Uuid>,
+) -> (StatusCode, Json<Value>) {
+    match escrow::release(&state.db_pool, bounty_id, state.config.payouts.enabled).await {
+        Ok(escrow) => (StatusCode::OK, Json(json!({"message": "Bounty escrow released", "escrow": escrow}))),
+        Err(e) => escrow_error(e),
+    }
+}
+
+/// Refund the escrow of a cancelled or expired bounty to its creator, less
+/// the platform fee for the reason given (cancelled when there is no body)
+pub async fn refund_bounty_escrow(
+    State(state): State<Arc<AppState>>,
+    Path(bounty_id): Path<Uuid>,
+    payload: Option<Json<RefundBountyRequest>>,
+) -> (StatusCode, Json<Value>) {
+    let reason = payload.map(|Json(payload)| payload.reason).unwrap_or_default();
+    let escrow = match escrow::refund(&state.payment_service, bounty_id, reason).await {
+        Ok(escrow) => escrow,
+        Err(e) => return escrow_error(e),
+    };
+    match escrow::find_refund(&state.db_pool, bounty_id).await {
+        Ok(refund) => (
+            StatusCode::OK,
+            Json(json!({"message": "Bounty escrow refunded", "escrow": escrow, "refund": refund})),
+        ),
+        Err(e) => escrow_error(e),
+    }
+}
+
+/// How a bounty's escrow was refunded: the fee kept and the transfer back
+pub async fn get_bounty_refund(
+    State(state): State<Arc<AppState>>,
+    Path(bounty_id): Path<Uuid>,
+) -> (StatusCode, Json<Value>) {
+    match escrow::find_refund(&state.db_pool, bounty_id).await {
+        Ok(Some(refund)) => (StatusCode::OK, Json(json!({"refund": refund}))),
+        Ok(None) => escrow_error(PaymentError::NotFound(format!("No refund for bounty {}", bounty_id))),
+        Err(e) => escrow_error(e),
     }
 }
 
@@ -42,79 +166,357 @@ pub async fn distribute_bounty_reward(
     })))
 }
 
+/// Lock an engine's stake for a bounty it joins, held against its balance
 pub async fn lock_stake(
     State(state): State<Arc<AppState>>,
     Json(payload): Json<LockStakeRequest>,
 ) -> (StatusCode, Json<Value>) {
-    // Pre-check: user must have sufficient balance for staking
-    match state.payment_service.get_token_balance(&payload.address).await {
-        Ok(balance) => {
-            let required = ethers::types::U256::from_dec_str(&payload.amount.to_string()).unwrap_or_default();
-            if balance < required {
-                return (StatusCode::BAD_REQUEST, Json(json!({
-                    "error": "Insufficient balance for stake",
-                    "balance": format!("{}", balance)
-                })));
-            }
-            (StatusCode::OK, Json(json!({
-                "message": "Stake pre-check passed",
-                "balance": format!("{}", balance),
-                "bounty_id": payload.bounty_id
-            })))
-        }
-        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({
-            "error": format!("Failed to check balance: {}", e)
-        }))),
+    match stake::lock(&state.payment_service, &payload).await {
+        Ok(stake) => (StatusCode::OK, Json(json!({"message": "Stake locked", "stake": stake}))),
+        Err(e) => escrow_error(e),
     }
 }
 
+/// Release a locked stake back to its engine
 pub async fn unlock_stake(
-    State(_state): State<Arc<AppState>>,
+    State(state): State<Arc<AppState>>,
     Json(payload): Json<UnlockStakeRequest>,
 ) -> (StatusCode, Json<Value>) {
-    // Stake unlocking is handled by the smart contract during bounty resolution
-    (StatusCode::OK, Json(json!({
-        "message": "Stake unlock is handled by on-chain bounty resolution",
-        "stake_id": payload.stake_id
-    })))
+    match stake::unlock(&state.db_pool, payload.stake_id).await {
+        Ok(stake) => (StatusCode::OK, Json(json!({"message": "Stake unlocked", "stake": stake}))),
+        Err(e) => escrow_error(e),
+    }
 }
 
+/// Slash a stake for an offense. The policy decides the amount, and the
+/// slash is executed once its appeal window passes.
 pub async fn slash_stake(
-    State(_state): State<Arc<AppState>>,
+    State(state): State<Arc<AppState>>,
     Json(payload): Json<SlashStakeRequest>,
 ) -> (StatusCode, Json<Value>) {
-    // Stake slashing is handled by the smart contract during bounty resolution
-    (StatusCode::OK, Json(json!({
-        "message": "Stake slashing is handled by on-chain bounty resolution",
-        "stake_id": payload.stake_id,
-        "slash_amount": payload.slash_amount.to_string()
-    })))
+    match state.slashing.slash(&payload).await {
+        Ok(Some(slash)) => (StatusCode::OK, Json(json!({"message": "Slash scheduled", "slash": slash}))),
+        Ok(None) => (
+            StatusCode::OK,
+            Json(json!({"message": "Nothing to slash under the policy; stake released", "stake_id": payload.stake_id})),
+        ),
+        Err(e) => escrow_error(e),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SlashHistoryParams {
+    pub limit: Option<i64>,
+}
+
+/// An engine's slashes, newest first
+pub async fn get_slashes(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<Uuid>,
+    headers: HeaderMap,
+    Query(params): Query<SlashHistoryParams>,
+) -> (StatusCode, Json<Value>) {
+    if let Err(response) = require_user(&headers, user_id) {
+        return response;
+    }
+    let limit = params.limit.unwrap_or(50).clamp(1, 200);
+    match state.slashing.for_user(user_id, limit).await {
+        Ok(slashes) => (StatusCode::OK, Json(json!({"slashes": slashes}))),
+        Err(e) => escrow_error(e),
+    }
 }
 
+/// Appeal a scheduled slash of the caller's stake before it is executed
+pub async fn appeal_slash(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    Json(payload): Json<AppealRequest>,
+) -> (StatusCode, Json<Value>) {
+    let user_id = match caller_id(&headers) {
+        Ok(user_id) => user_id,
+        Err(response) => return response,
+    };
+    match state.slashing.appeal(id, user_id, &payload).await {
+        Ok(slash) => (StatusCode::OK, Json(json!({"message": "Slash appealed", "slash": slash}))),
+        Err(e) => escrow_error(e),
+    }
+}
+
+/// Withdraw to an allowlisted address. Large withdrawals are held for
+/// admin approval; the rest are queued for the treasury to send.
 pub async fn withdraw_funds(
-    State(_state): State<Arc<AppState>>,
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
     Json(payload): Json<WithdrawRequest>,
 ) -> (StatusCode, Json<Value>) {
-    (StatusCode::OK, Json(json!({
-        "message": "Withdrawal queued for processing",
-        "to_address": payload.to_address,
-        "amount": payload.amount.to_string()
-    })))
+    let user_id = match caller_id(&headers) {
+        Ok(user_id) => user_id,
+        Err(response) => return response,
+    };
+    match state.withdrawals.request(user_id, &payload).await {
+        Ok(withdrawal) => {
+            let message = if withdrawal.status == withdrawals::HELD {
+                "Withdrawal held for admin approval"
+            } else {
+                "Withdrawal queued for processing"
+            };
+            (StatusCode::OK, Json(json!({"message": message, "withdrawal": withdrawal})))
+        }
+        Err(e) => escrow_error(e),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WithdrawalHistoryParams {
+    pub limit: Option<i64>,
+}
+
+/// A user's withdrawals, newest first
+pub async fn get_withdrawals(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<Uuid>,
+    headers: HeaderMap,
+    Query(params): Query<WithdrawalHistoryParams>,
+) -> (StatusCode, Json<Value>) {
+    if let Err(response) = require_user(&headers, user_id) {
+        return response;
+    }
+    let limit = params.limit.unwrap_or(50).clamp(1, 200);
+    match state.withdrawals.history(user_id, limit).await {
+        Ok(withdrawals) => (StatusCode::OK, Json(json!({"withdrawals": withdrawals}))),
+        Err(e) => escrow_error(e),
+    }
 }
 
+/// Addresses a user may withdraw to
+pub async fn get_withdrawal_addresses(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<Uuid>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<Value>) {
+    if let Err(response) = require_user(&headers, user_id) {
+        return response;
+    }
+    match state.withdrawals.addresses(user_id).await {
+        Ok(addresses) => (StatusCode::OK, Json(json!({"addresses": addresses}))),
+        Err(e) => escrow_error(e),
+    }
+}
+
+/// Allowlist a withdrawal address, usable after the cooling-off period
+pub async fn add_withdrawal_address(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<WithdrawalAddressRequest>,
+) -> (StatusCode, Json<Value>) {
+    let user_id = match caller_id(&headers) {
+        Ok(user_id) => user_id,
+        Err(response) => return response,
+    };
+    match state.withdrawals.add_address(user_id, &payload).await {
+        Ok(address) => (StatusCode::CREATED, Json(json!({"message": "Withdrawal address added", "address": address}))),
+        Err(e) => escrow_error(e),
+    }
+}
+
+pub async fn remove_withdrawal_address(
+    State(state): State<Arc<AppState>>,
+    Path((user_id, address)): Path<(Uuid, String)>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<Value>) {
+    if let Err(response) = require_user(&headers, user_id) {
+        return response;
+    }
+    match state.withdrawals.remove_address(user_id, &address).await {
+        Ok(()) => (StatusCode::OK, Json(json!({"message": "Withdrawal address removed"}))),
+        Err(e) => escrow_error(e),
+    }
+}
+
+/// The permit to sign for a gasless claim, with the relay fee it carries
+pub async fn get_relay_permit(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<PermitQuery>,
+) -> (StatusCode, Json<Value>) {
+    match state.relay.quote(&query).await {
+        Ok(quote) => (StatusCode::OK, Json(json!(quote))),
+        Err(e) => escrow_error(e),
+    }
+}
+
+/// Relay a signed permit to an allowlisted address, the treasury paying the
+/// gas and keeping the relay fee
+pub async fn relay_claim(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<RelayClaimRequest>,
+) -> (StatusCode, Json<Value>) {
+    let user_id = match caller_id(&headers) {
+        Ok(user_id) => user_id,
+        Err(response) => return response,
+    };
+    match state.relay.claim(user_id, &payload).await {
+        Ok(claim) => (StatusCode::ACCEPTED, Json(json!({"message": "Claim relayed", "claim": claim}))),
+        Err(e) => escrow_error(e),
+    }
+}
+
+/// A user's relayed claims, newest first
+pub async fn get_relay_claims(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<Uuid>,
+    headers: HeaderMap,
+    Query(params): Query<WithdrawalHistoryParams>,
+) -> (StatusCode, Json<Value>) {
+    if let Err(response) = require_user(&headers, user_id) {
+        return response;
+    }
+    let limit = params.limit.unwrap_or(50).clamp(1, 200);
+    match state.relay.history(user_id, limit).await {
+        Ok(claims) => (StatusCode::OK, Json(json!({"claims": claims}))),
+        Err(e) => escrow_error(e),
+    }
+}
+
+pub async fn get_relay_claim(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<Value>) {
+    let user_id = match caller_id(&headers) {
+        Ok(user_id) => user_id,
+        Err(response) => return response,
+    };
+    match state.relay.find(id).await {
+        Ok(Some(claim)) if claim.user_id == user_id => (StatusCode::OK, Json(json!(claim))),
+        Ok(Some(_)) | Ok(None) => escrow_error(PaymentError::NotFound(format!("Relay claim {} not found", id))),
+        Err(e) => escrow_error(e),
+    }
+}
+
+/// The wallets that funded each of the given addresses
+pub async fn get_wallet_funders(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<WalletFundersRequest>,
+) -> (StatusCode, Json<Value>) {
+    match funding::funders(&state.db_pool, &payload.addresses).await {
+        Ok(funders) => (StatusCode::OK, Json(json!({"funders": funders}))),
+        Err(e) => escrow_error(e),
+    }
+}
+
+/// Native balance of an address and its balance of every allowed reward token
 pub async fn get_balance(
     State(state): State<Arc<AppState>>,
     Path(address): Path<String>,
 ) -> (StatusCode, Json<Value>) {
-    match state.payment_service.get_token_balance(&address).await {
-        Ok(balance) => (StatusCode::OK, Json(json!({
-            "address": address,
-            "balance": format!("{}", balance),
-            "token": "THREAT"
-        }))),
-        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({
-            "error": format!("Failed to get balance: {}", e)
-        }))),
+    match state.token_service.balances(&address).await {
+        Ok(balances) => (StatusCode::OK, Json(json!(balances))),
+        Err(e) => escrow_error(e),
+    }
+}
+
+/// ERC-20 tokens bounty rewards may be paid in
+pub async fn list_reward_tokens(State(state): State<Arc<AppState>>) -> (StatusCode, Json<Value>) {
+    (StatusCode::OK, Json(json!({"tokens": state.token_service.tokens()})))
+}
+
+/// Whether a creator has approved the payment contract to pull a deposit,
+/// with the approval transaction to sign if not
+pub async fn check_token_approval(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<TokenApprovalRequest>,
+) -> (StatusCode, Json<Value>) {
+    match state.token_service.approval(&payload).await {
+        Ok(approval) => (StatusCode::OK, Json(json!({"approval": approval}))),
+        Err(e) => escrow_error(e),
+    }
+}
+
+/// Latest USD rate of each priced token
+pub async fn get_prices(State(state): State<Arc<AppState>>) -> (StatusCode, Json<Value>) {
+    match state.prices.latest().await {
+        Ok(prices) => (StatusCode::OK, Json(json!({"currency": prices::CURRENCY, "prices": prices}))),
+        Err(e) => escrow_error(e),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PriceHistoryParams {
+    pub limit: Option<i64>,
+}
+
+/// A token's recorded USD rates, newest first
+pub async fn get_price_history(
+    State(state): State<Arc<AppState>>,
+    Path(token_address): Path<String>,
+    Query(params): Query<PriceHistoryParams>,
+) -> (StatusCode, Json<Value>) {
+    let limit = params.limit.unwrap_or(100).clamp(1, 1000);
+    match state.prices.history(&token_address, limit).await {
+        Ok(prices) => (StatusCode::OK, Json(json!({"currency": prices::CURRENCY, "prices": prices}))),
+        Err(e) => escrow_error(e),
+    }
+}
+
+/// Period and format of a fiat report; the last 30 days as JSON by default
+#[derive(Debug, Deserialize)]
+pub struct ReportParams {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// json | csv
+    pub format: Option<String>,
+}
+
+impl ReportParams {
+    /// (from, to, as CSV)
+    pub(crate) fn resolve(&self) -> Result<(DateTime<Utc>, DateTime<Utc>, bool), PaymentError> {
+        let to = self.to.unwrap_or_else(Utc::now);
+        let from = self.from.unwrap_or(to - Duration::days(30));
+        if from >= to {
+            return Err(PaymentError::ValidationError("from must be before to".to_string()));
+        }
+        let csv = match self.format.as_deref() {
+            None | Some("json") => false,
+            Some("csv") => true,
+            Some(other) => return Err(PaymentError::ValidationError(format!("Unknown report format '{}'", other))),
+        };
+        Ok((from, to, csv))
+    }
+}
+
+/// A report as a CSV download named `name`
+pub(crate) fn csv_response(name: &str, csv: String) -> Response {
+    (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}.csv\"", name)),
+        ],
+        csv,
+    )
+        .into_response()
+}
+
+/// Rewards and slash redistributions an address earned, valued in USD at
+/// the time each was paid
+pub async fn get_earnings_report(
+    State(state): State<Arc<AppState>>,
+    Path(address): Path<String>,
+    Query(params): Query<ReportParams>,
+) -> Response {
+    let (from, to, csv) = match params.resolve() {
+        Ok(period) => period,
+        Err(e) => return escrow_error(e).into_response(),
+    };
+    match state.prices.earnings(&address, from, to).await {
+        Ok(report) if csv => csv_response(
+            &format!("earnings-{}-{}-{}", address.to_lowercase(), from.format("%Y%m%d"), to.format("%Y%m%d")),
+            report.to_csv(),
+        ),
+        Ok(report) => (StatusCode::OK, Json(json!(report))).into_response(),
+        Err(e) => escrow_error(e).into_response(),
     }
 }
 
@@ -130,10 +532,18 @@ pub async fn get_transactions(
     })))
 }
 
+/// Status of a transaction from its receipt. For one the service sent
+/// itself, also every attempt at its nonce: a hash that was replaced by a
+/// higher-fee attempt reports `replaced`.
 pub async fn get_transaction_status(
     State(state): State<Arc<AppState>>,
     Path(tx_hash): Path<String>,
 ) -> (StatusCode, Json<Value>) {
+    let history = match nonces::history(&state.db_pool, &tx_hash).await {
+        Ok(history) => history,
+        Err(e) => return escrow_error(e),
+    };
+
     match state.payment_service.get_tx_receipt(&tx_hash).await {
         Ok(Some(receipt)) => {
             let status = if receipt.status == Some(1.into()) {
@@ -145,30 +555,35 @@ pub async fn get_transaction_status(
                 "tx_hash": tx_hash,
                 "status": status,
                 "block_number": receipt.block_number.map(|n| n.as_u64()),
-                "gas_used": receipt.gas_used.map(|g| format!("{}", g))
+                "gas_used": receipt.gas_used.map(|g| format!("{}", g)),
+                "replacement_history": history
+            })))
+        }
+        Ok(None) => {
+            let replaced_by = history.as_ref().and_then(|history| {
+                let current = history.transaction.mined_tx_hash.as_ref().unwrap_or(&history.transaction.tx_hash);
+                (!current.eq_ignore_ascii_case(&tx_hash)).then(|| current.clone())
+            });
+            (StatusCode::OK, Json(json!({
+                "tx_hash": tx_hash,
+                "status": if replaced_by.is_some() { "replaced" } else { "pending" },
+                "replaced_by": replaced_by,
+                "replacement_history": history
             })))
         }
-        Ok(None) => (StatusCode::OK, Json(json!({
-            "tx_hash": tx_hash,
-            "status": "pending"
-        }))),
         Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({
             "error": format!("Failed to get transaction status: {}", e)
         }))),
     }
 }
 
+/// Gas to move an amount of a reward token, estimated against its contract
 pub async fn estimate_gas(
     State(state): State<Arc<AppState>>,
-    Json(_payload): Json<EstimateGasRequest>,
+    Json(payload): Json<EstimateGasRequest>,
 ) -> (StatusCode, Json<Value>) {
-    match state.payment_service.estimate_gas_for_transfer().await {
-        Ok(gas) => (StatusCode::OK, Json(json!({
-            "estimated_gas_cost": format!("{}", gas),
-            "unit": "wei"
-        }))),
-        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({
-            "error": format!("Failed to estimate gas: {}", e)
-        }))),
+    match state.token_service.estimate_gas(&payload).await {
+        Ok(estimate) => (StatusCode::OK, Json(json!(estimate))),
+        Err(e) => escrow_error(e),
     }
 }
@@ -0,0 +1,75 @@
+use axum::{extract::{State, Path, Query}, response::Json, http::{HeaderMap, StatusCode}};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use uuid::Uuid;
+use crate::AppState;
+use crate::handlers::payment::{caller_id, escrow_error, require_user};
+use crate::services::payment_events::RegisterWebhookRequest;
+
+#[derive(Debug, Deserialize)]
+pub struct DeliveryQuery {
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// Subscribe a URL to a user's payment events; the response holds the
+/// signing secret, which is not shown again
+pub async fn register_webhook(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<RegisterWebhookRequest>,
+) -> (StatusCode, Json<Value>) {
+    let user_id = match caller_id(&headers) {
+        Ok(user_id) => user_id,
+        Err(response) => return response,
+    };
+    match state.events.register(user_id, &payload).await {
+        Ok(webhook) => (StatusCode::CREATED, Json(json!(webhook))),
+        Err(e) => escrow_error(e),
+    }
+}
+
+pub async fn list_webhooks(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<Uuid>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<Value>) {
+    if let Err(response) = require_user(&headers, user_id) {
+        return response;
+    }
+    match state.events.list(user_id).await {
+        Ok(webhooks) => (StatusCode::OK, Json(json!({"webhooks": webhooks}))),
+        Err(e) => escrow_error(e),
+    }
+}
+
+pub async fn delete_webhook(
+    State(state): State<Arc<AppState>>,
+    Path((user_id, webhook_id)): Path<(Uuid, Uuid)>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<Value>) {
+    if let Err(response) = require_user(&headers, user_id) {
+        return response;
+    }
+    match state.events.delete(user_id, webhook_id).await {
+        Ok(()) => (StatusCode::OK, Json(json!({"message": "Webhook removed"}))),
+        Err(e) => escrow_error(e),
+    }
+}
+
+/// A webhook's deliveries, newest first
+pub async fn get_webhook_deliveries(
+    State(state): State<Arc<AppState>>,
+    Path((user_id, webhook_id)): Path<(Uuid, Uuid)>,
+    headers: HeaderMap,
+    Query(query): Query<DeliveryQuery>,
+) -> (StatusCode, Json<Value>) {
+    if let Err(response) = require_user(&headers, user_id) {
+        return response;
+    }
+    match state.events.deliveries(user_id, webhook_id, query.page, query.limit).await {
+        Ok(deliveries) => (StatusCode::OK, Json(json!({"deliveries": deliveries}))),
+        Err(e) => escrow_error(e),
+    }
+}
@@ -7,18 +7,26 @@ mod workers;
 
 use anyhow::Result;
 use axum::{
-    routing::{get, post},
+    routing::{delete, get, post},
     Router,
 };
 use std::sync::Arc;
-use tower_http::{
-    cors::{Any, CorsLayer},
-    trace::TraceLayer,
-};
+use tower_http::trace::TraceLayer;
 use tracing::{info, warn};
 
 use crate::config::Config;
+use crate::services::dead_letters::DeadLetterService;
+use crate::services::prices::PriceService;
+use crate::services::relay::RelayService;
+use crate::services::slashing::SlashingService;
 use crate::services::payment_service::PaymentService;
+use crate::services::nonces::NonceManager;
+use crate::services::payouts::PayoutService;
+use crate::services::reconciliation::Reconciler;
+use crate::services::tokens::TokenService;
+use crate::services::fees::FeeService;
+use crate::services::payment_events::PaymentEventService;
+use crate::services::withdrawals::WithdrawalService;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -26,12 +34,7 @@ async fn main() -> Result<()> {
     dotenvy::dotenv().ok();
 
     // Initialize tracing
-    tracing_subscriber::fmt()
-        .with_target(false)
-        .with_thread_ids(true)
-        .with_line_number(true)
-        .json()
-        .init();
+    shared::observability::init_service_logging(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))?;
 
     info!("Starting Payment Service...");
 
@@ -71,6 +74,9 @@ async fn main() -> Result<()> {
     );
     info!("Payment service initialized");
 
+    let token_service = Arc::new(TokenService::new(payment_service.clone()));
+    token_service.verify().await;
+
     // Start background workers
     let service_clone = payment_service.clone();
     tokio::spawn(async move {
@@ -79,20 +85,107 @@ async fn main() -> Result<()> {
         }
     });
 
+    let nonces = Arc::new(NonceManager::new(payment_service.clone()));
     let service_clone = payment_service.clone();
+    let nonces_clone = nonces.clone();
     tokio::spawn(async move {
-        if let Err(e) = workers::pending_payment_processor::start(service_clone).await {
+        if let Err(e) = workers::pending_payment_processor::start(service_clone, nonces_clone).await {
             warn!("Pending payment processor error: {}", e);
         }
     });
 
-    let service_clone = payment_service.clone();
+    let payouts = Arc::new(PayoutService::new(payment_service.clone(), nonces.clone()));
+    if config.payouts.enabled {
+        let payouts_clone = payouts.clone();
+        tokio::spawn(async move {
+            if let Err(e) = workers::payout_batcher::start(payouts_clone).await {
+                warn!("Payout batcher error: {}", e);
+            }
+        });
+    }
+
+    let relay = Arc::new(RelayService::new(payment_service.clone(), nonces.clone()));
+    if config.relay.enabled {
+        let relay_clone = relay.clone();
+        tokio::spawn(async move {
+            if let Err(e) = workers::relay_monitor::start(relay_clone).await {
+                warn!("Relay monitor error: {}", e);
+            }
+        });
+    }
+
     tokio::spawn(async move {
-        if let Err(e) = workers::balance_reconciliation::start(service_clone).await {
+        if let Err(e) = workers::transaction_replacer::start(nonces).await {
+            warn!("Transaction replacer error: {}", e);
+        }
+    });
+
+    let reconciler = Reconciler::new(payment_service.clone(), &config.reconciliation)?;
+    tokio::spawn(async move {
+        if let Err(e) = workers::balance_reconciliation::start(reconciler).await {
             warn!("Balance reconciliation worker error: {}", e);
         }
     });
 
+    let events = shared::messaging::EventSubscriber::from_url(&config.redis.url)?;
+    let service_clone = payment_service.clone();
+    // Unverified plans would move stakes, so there is no running without
+    // the keys to check them
+    let settlement_verifier = shared::request_signing::SignatureVerifier::from_env()?;
+    anyhow::ensure!(
+        settlement_verifier.is_enabled(),
+        "SERVICE_SIGNING_KEYS must be set to verify settlement plans"
+    );
+    tokio::spawn(async move {
+        let listener = workers::settlement_listener::start(service_clone, events, settlement_verifier);
+        if let Err(e) = listener.await {
+            warn!("Settlement listener error: {}", e);
+        }
+    });
+
+    let withdrawals = Arc::new(WithdrawalService::new(payment_service.clone())?);
+    let withdrawals_clone = withdrawals.clone();
+    tokio::spawn(async move {
+        if let Err(e) = workers::withdrawal_monitor::start(withdrawals_clone).await {
+            warn!("Withdrawal monitor error: {}", e);
+        }
+    });
+
+    let fees = Arc::new(FeeService::new(payment_service.clone()));
+    let dead_letters = Arc::new(DeadLetterService::new(payment_service.clone()));
+
+    let slashing = Arc::new(SlashingService::new(payment_service.clone()));
+    let slashing_clone = slashing.clone();
+    tokio::spawn(async move {
+        if let Err(e) = workers::slash_executor::start(slashing_clone).await {
+            warn!("Slash executor error: {}", e);
+        }
+    });
+
+    let events = Arc::new(PaymentEventService::new(payment_service.clone())?);
+    let events_clone = events.clone();
+    tokio::spawn(async move {
+        if let Err(e) = workers::payment_event_publisher::start(events_clone).await {
+            warn!("Payment event publisher error: {}", e);
+        }
+    });
+
+    let prices = Arc::new(PriceService::new(payment_service.clone())?);
+    let prices_clone = prices.clone();
+    tokio::spawn(async move {
+        if let Err(e) = workers::price_feed::start(prices_clone).await {
+            warn!("Price feed worker error: {}", e);
+        }
+    });
+
+    let pool_clone = db_pool.clone();
+    let idempotency_config = config.idempotency.clone();
+    tokio::spawn(async move {
+        if let Err(e) = workers::idempotency_purge::start(pool_clone, idempotency_config).await {
+            warn!("Idempotency purge worker error: {}", e);
+        }
+    });
+
     info!("Background workers started");
 
     // Build application state
@@ -101,36 +194,131 @@ async fn main() -> Result<()> {
         db_pool,
         redis_conn,
         payment_service,
+        token_service,
+        payouts,
+        withdrawals,
+        fees,
+        events,
+        dead_letters,
+        slashing,
+        prices,
+        relay,
     });
 
-    // Configure CORS
-    let cors = CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods(Any)
-        .allow_headers(Any);
+    // Browser origins from CORS_ALLOWED_ORIGINS or the environment's defaults
+    let cors = shared::http_security::CorsPolicy::from_env().layer();
 
-    // Build router
-    let app = Router::new()
-        .route("/health", get(handlers::health::health_check))
-        // Payment endpoints
+    // Payment endpoints that move funds, deduplicated by Idempotency-Key
+    let mutating = Router::new()
         .route("/api/v1/payments/bounty/deposit", post(handlers::payment::deposit_bounty_reward))
         .route("/api/v1/payments/bounty/distribute", post(handlers::payment::distribute_bounty_reward))
+        .route("/api/v1/payments/bounty/:bounty_id/release", post(handlers::payment::release_bounty_escrow))
+        .route("/api/v1/payments/bounty/:bounty_id/refund", post(handlers::payment::refund_bounty_escrow))
         .route("/api/v1/payments/stake/lock", post(handlers::payment::lock_stake))
         .route("/api/v1/payments/stake/unlock", post(handlers::payment::unlock_stake))
         .route("/api/v1/payments/stake/slash", post(handlers::payment::slash_stake))
         .route("/api/v1/payments/withdraw", post(handlers::payment::withdraw_funds))
+        .route("/api/v1/payments/relay/claims", post(handlers::payment::relay_claim))
+        .route_layer(axum::middleware::from_fn_with_state(
+            app_state.clone(),
+            handlers::idempotency::idempotency_middleware,
+        ));
+
+    // Admin endpoints, for callers with the payments:approve scope
+    let admin = Router::new()
+        .route("/api/v1/admin/payments/pending", get(handlers::admin::get_pending_payments))
+        .route("/api/v1/admin/payments/failed", get(handlers::admin::get_failed_payments))
+        .route("/api/v1/admin/payments/:id/retry", post(handlers::admin::retry_payment))
+        .route("/api/v1/admin/payments/dead-letters", get(handlers::admin::get_dead_letters))
+        .route(
+            "/api/v1/admin/payments/dead-letters/:id",
+            get(handlers::admin::get_dead_letter).patch(handlers::admin::update_dead_letter),
+        )
+        .route("/api/v1/admin/payments/dead-letters/:id/requeue", post(handlers::admin::requeue_dead_letter))
+        .route("/api/v1/admin/payments/dead-letters/:id/cancel", post(handlers::admin::cancel_dead_letter))
+        .route("/api/v1/admin/treasury/balance", get(handlers::admin::get_treasury_balance))
+        .route("/api/v1/admin/payouts/:bounty_id", get(handlers::admin::get_payout_batch))
+        .route("/api/v1/admin/payouts/:bounty_id/retry", post(handlers::admin::retry_payout_batch))
+        .route("/api/v1/admin/withdrawals/held", get(handlers::admin::get_held_withdrawals))
+        .route("/api/v1/admin/withdrawals/:id", get(handlers::admin::get_withdrawal))
+        .route("/api/v1/admin/withdrawals/:id/approve", post(handlers::admin::approve_withdrawal))
+        .route("/api/v1/admin/withdrawals/:id/reject", post(handlers::admin::reject_withdrawal))
+        .route("/api/v1/admin/slashes", get(handlers::admin::get_slashes))
+        .route("/api/v1/admin/slashes/:id", get(handlers::admin::get_slash))
+        .route("/api/v1/admin/slashes/:id/resolve", post(handlers::admin::decide_slash_appeal))
+        .route("/api/v1/admin/reports/treasury", get(handlers::admin::get_treasury_report))
+        .route("/api/v1/admin/fees", get(handlers::admin::get_fees))
+        .route("/api/v1/admin/fees/ledger", get(handlers::admin::get_fee_ledger))
+        .route(
+            "/api/v1/admin/fees/sweeps",
+            get(handlers::admin::get_fee_sweeps).post(handlers::admin::sweep_fees),
+        )
+        .route("/api/v1/admin/reconciliation/runs", get(handlers::admin::list_reconciliation_runs))
+        .route("/api/v1/admin/reconciliation/runs/:id", get(handlers::admin::get_reconciliation_run))
+        .route("/api/v1/admin/reconciliation/runs/:id/wallets", get(handlers::admin::get_reconciliation_wallets))
+        .route("/api/v1/admin/reconciliation/runs/:id/transactions", get(handlers::admin::get_reconciliation_transactions))
+        .route_layer(axum::middleware::from_fn(shared::permissions::require_scope(
+            shared::permissions::PAYMENTS_APPROVE,
+        )));
+
+    // Build router
+    let app = Router::new()
+        .route("/health", get(handlers::health::health_check))
+        .merge(mutating)
+        // Payment endpoints
+        .route("/api/v1/payments/bounty/:bounty_id/escrow", get(handlers::payment::get_bounty_escrow))
+        .route("/api/v1/payments/organizations/:org_id/escrows", get(handlers::payment::get_organization_escrows))
+        .route("/api/v1/payments/bounty/:bounty_id/refund", get(handlers::payment::get_bounty_refund))
         .route("/api/v1/payments/balance/:address", get(handlers::payment::get_balance))
+        .route("/api/v1/payments/tokens", get(handlers::payment::list_reward_tokens))
+        .route("/api/v1/payments/tokens/approval", post(handlers::payment::check_token_approval))
+        .route("/api/v1/payments/withdrawals/addresses", post(handlers::payment::add_withdrawal_address))
+        .route("/api/v1/payments/withdrawals/users/:user_id", get(handlers::payment::get_withdrawals))
+        .route("/api/v1/payments/withdrawals/users/:user_id/addresses", get(handlers::payment::get_withdrawal_addresses))
+        .route(
+            "/api/v1/payments/withdrawals/users/:user_id/addresses/:address",
+            delete(handlers::payment::remove_withdrawal_address),
+        )
+        .route("/api/v1/payments/webhooks", post(handlers::webhooks::register_webhook))
+        .route("/api/v1/payments/webhooks/users/:user_id", get(handlers::webhooks::list_webhooks))
+        .route(
+            "/api/v1/payments/webhooks/users/:user_id/:webhook_id",
+            delete(handlers::webhooks::delete_webhook),
+        )
+        .route(
+            "/api/v1/payments/webhooks/users/:user_id/:webhook_id/deliveries",
+            get(handlers::webhooks::get_webhook_deliveries),
+        )
+        .route("/api/v1/payments/relay/permit", get(handlers::payment::get_relay_permit))
+        .route("/api/v1/payments/relay/claims/users/:user_id", get(handlers::payment::get_relay_claims))
+        .route("/api/v1/payments/relay/claims/:id", get(handlers::payment::get_relay_claim))
+        .route("/api/v1/payments/slashes/users/:user_id", get(handlers::payment::get_slashes))
+        .route("/api/v1/payments/slashes/:id/appeal", post(handlers::payment::appeal_slash))
+        .route("/api/v1/payments/prices", get(handlers::payment::get_prices))
+        .route("/api/v1/payments/prices/:token_address", get(handlers::payment::get_price_history))
+        .route("/api/v1/payments/earnings/:address", get(handlers::payment::get_earnings_report))
+        .route("/api/v1/payments/wallets/funders", post(handlers::payment::get_wallet_funders))
         .route("/api/v1/payments/transactions/:address", get(handlers::payment::get_transactions))
         .route("/api/v1/payments/transaction/:tx_hash", get(handlers::payment::get_transaction_status))
+        // On-chain activity pushed by an external indexer
+        .route("/api/v1/webhooks/indexer", post(handlers::indexer::receive_indexer_webhook))
         // Gas estimation
         .route("/api/v1/payments/gas/estimate", post(handlers::payment::estimate_gas))
-        // Admin endpoints
-        .route("/api/v1/admin/payments/pending", get(handlers::admin::get_pending_payments))
-        .route("/api/v1/admin/payments/failed", get(handlers::admin::get_failed_payments))
-        .route("/api/v1/admin/payments/:id/retry", post(handlers::admin::retry_payment))
-        .route("/api/v1/admin/treasury/balance", get(handlers::admin::get_treasury_balance))
+        .merge(admin)
+        .merge(shared::observability::log_level_routes())
+        .layer(axum::middleware::from_fn_with_state(
+            shared::request_signing::SignatureVerifier::from_env()?
+                .exempt("/api/v1/webhooks/indexer"),
+            shared::request_signing::verify_signature_middleware,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            shared::chaos::FaultInjector::from_env(),
+            shared::chaos::chaos_middleware,
+        ))
         .layer(cors)
+        .layer(axum::middleware::from_fn(shared::http_security::security_headers_middleware))
         .layer(TraceLayer::new_for_http())
+        .layer(axum::middleware::from_fn(shared::observability::log_context_middleware))
         .with_state(app_state);
 
     // Start server
@@ -149,4 +337,13 @@ pub struct AppState {
     pub db_pool: sqlx::PgPool,
     pub redis_conn: redis::aio::ConnectionManager,
     pub payment_service: Arc<PaymentService>,
+    pub token_service: Arc<TokenService>,
+    pub payouts: Arc<PayoutService>,
+    pub withdrawals: Arc<WithdrawalService>,
+    pub fees: Arc<FeeService>,
+    pub events: Arc<PaymentEventService>,
+    pub dead_letters: Arc<DeadLetterService>,
+    pub slashing: Arc<SlashingService>,
+    pub prices: Arc<PriceService>,
+    pub relay: Arc<RelayService>,
 }
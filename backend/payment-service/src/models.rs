@@ -36,6 +36,12 @@ pub enum PaymentError {
 
     #[error("Lock error: {0}")]
     LockError(String),
+
+    #[error("Limit exceeded: {0}")]
+    LimitExceeded(String),
+
+    #[error("Not permitted: {0}")]
+    NotPermitted(String),
 }
 
 /// Payment transaction record
@@ -74,6 +80,7 @@ pub enum PaymentType {
     Withdrawal,
     Fee,
     Refund,
+    SlashRedistribution,
 }
 
 impl ToString for PaymentType {
@@ -87,6 +94,7 @@ impl ToString for PaymentType {
             PaymentType::Withdrawal => "withdrawal".to_string(),
             PaymentType::Fee => "fee".to_string(),
             PaymentType::Refund => "refund".to_string(),
+            PaymentType::SlashRedistribution => "slash_redistribution".to_string(),
         }
     }
 }
@@ -196,6 +204,12 @@ pub struct DepositBountyRequest {
     pub amount: Decimal,
     pub creator_address: String,
     pub token_address: Option<String>,
+    /// Creator's on-chain deposit; may be attached by a later call
+    #[serde(default)]
+    pub deposit_tx_hash: Option<String>,
+    /// Organization the bounty is opened for
+    #[serde(default)]
+    pub organization_id: Option<Uuid>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -223,18 +237,34 @@ pub struct UnlockStakeRequest {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct SlashStakeRequest {
-    pub stake_id: Uuid,
-    pub slash_amount: Decimal,
-    pub reason: String,
+pub struct WalletFundersRequest {
+    pub addresses: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WithdrawRequest {
-    pub user_id: Uuid,
+    /// The user's platform wallet the balance is held for
     pub from_address: String,
+    /// Must be on the user's withdrawal allowlist
     pub to_address: String,
+    /// In the token's base units
     pub amount: Decimal,
+    /// The platform token when unset
+    #[serde(default)]
+    pub token_address: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WithdrawalAddressRequest {
+    pub address: String,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WithdrawalDecisionRequest {
+    #[serde(default)]
+    pub note: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -242,7 +272,32 @@ pub struct EstimateGasRequest {
     pub payment_type: PaymentType,
     pub from_address: String,
     pub to_address: String,
+    /// In the token's base units
+    pub amount: Decimal,
+    /// Allowlisted reward token; the platform token if unset
+    #[serde(default)]
+    pub token_address: Option<String>,
+}
+
+/// Whether `owner` has approved the payment contract to pull `amount` of a
+/// reward token for a bounty deposit
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TokenApprovalRequest {
+    pub owner_address: String,
+    /// In the token's base units
     pub amount: Decimal,
+    /// Allowlisted reward token; the platform token if unset
+    #[serde(default)]
+    pub token_address: Option<String>,
+}
+
+/// ERC-20 call a gas estimate covers
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenOperation {
+    Transfer,
+    Approve,
+    TransferFrom,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -0,0 +1,461 @@
+// Dead-lettered payments
+//
+// The pending payment processor sends treasury payments through the nonce
+// manager. A send that fails, or whose nonce is then taken by a transaction
+// the service did not send, counts an attempt and queues the payment again;
+// after `max_retry_attempts` it is dead-lettered instead, with the reason of
+// its latest failure, and left alone until an admin steps in.
+//
+// An admin can look at a dead-lettered payment with its transactions, set the
+// gas price, gas limit or nonce its next send uses, and requeue it (its
+// attempts start over) or cancel it. Every intervention is recorded.
+
+use chrono::{DateTime, Utc};
+use ethers::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::{PgConnection, PgPool};
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::models::{PaymentError, PaymentResult};
+use crate::services::payment_service::PaymentService;
+
+pub const DEAD_LETTERED: &str = "dead_lettered";
+pub const CANCELLED: &str = "cancelled";
+
+/// The least gas a transaction can use
+const MIN_GAS_LIMIT: u64 = 21_000;
+
+const DEAD_LETTER_COLUMNS: &str = "id, bounty_id, payment_type, payer_address, recipient_address, token_address, \
+                                   amount::TEXT AS amount, status, attempts, failure_reason, last_error, \
+                                   gas_price_override::TEXT AS gas_price_override, \
+                                   gas_limit_override::TEXT AS gas_limit_override, nonce_override, \
+                                   dead_lettered_at, created_at, updated_at";
+
+/// Why a send failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureReason {
+    /// The treasury could not pay for gas
+    InsufficientGas,
+    /// The transfer reverted, in estimation or on-chain
+    Reverted,
+    /// The nonce was taken by another transaction or out of order
+    NonceGap,
+    Other,
+}
+
+impl FailureReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::InsufficientGas => "insufficient_gas",
+            Self::Reverted => "reverted",
+            Self::NonceGap => "nonce_gap",
+            Self::Other => "other",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "insufficient_gas" => Some(Self::InsufficientGas),
+            "reverted" => Some(Self::Reverted),
+            "nonce_gap" => Some(Self::NonceGap),
+            "other" => Some(Self::Other),
+            _ => None,
+        }
+    }
+
+    /// Tell the reason from a node or nonce manager error
+    pub fn classify(error: &str) -> Self {
+        let error = error.to_lowercase();
+        if error.contains("insufficient funds") || error.contains("gas required exceeds") {
+            Self::InsufficientGas
+        } else if error.contains("revert") {
+            Self::Reverted
+        } else if error.contains("nonce") {
+            Self::NonceGap
+        } else {
+            Self::Other
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct DeadLetter {
+    pub id: Uuid,
+    pub bounty_id: Option<Uuid>,
+    pub payment_type: String,
+    pub payer_address: String,
+    pub recipient_address: String,
+    pub token_address: String,
+    /// In base units of the token
+    pub amount: String,
+    pub status: String,
+    pub attempts: i32,
+    pub failure_reason: Option<String>,
+    pub last_error: Option<String>,
+    /// In wei
+    pub gas_price_override: Option<String>,
+    pub gas_limit_override: Option<String>,
+    pub nonce_override: Option<i64>,
+    pub dead_lettered_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// One transaction sent for the payment
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct PaymentAttempt {
+    pub transaction_hash: String,
+    pub status: Option<String>,
+    pub error_message: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Intervention {
+    pub id: Uuid,
+    pub admin_id: Uuid,
+    /// `edit`, `requeue` or `cancel`
+    pub action: String,
+    pub detail: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeadLetterDetail {
+    #[serde(flatten)]
+    pub payment: DeadLetter,
+    pub transactions: Vec<PaymentAttempt>,
+    pub interventions: Vec<Intervention>,
+}
+
+/// Parameters of a dead-lettered payment's next send. They replace the
+/// current ones; any left out is cleared and estimated again.
+#[derive(Debug, Deserialize)]
+pub struct DeadLetterParams {
+    /// In wei
+    pub gas_price: Option<String>,
+    pub gas_limit: Option<u64>,
+    pub nonce: Option<u64>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InterventionRequest {
+    pub note: Option<String>,
+}
+
+fn db_error(e: sqlx::Error) -> PaymentError {
+    PaymentError::DatabaseError(e.to_string())
+}
+
+/// Count a failed send of `payment_id`, queueing it again or, once it has
+/// used up `max_attempts`, dead-lettering it. Returns whether it was
+/// dead-lettered.
+pub async fn record_failure(
+    db: &mut PgConnection,
+    payment_id: Uuid,
+    reason: FailureReason,
+    error: &str,
+    max_attempts: u32,
+) -> PaymentResult<bool> {
+    let status: Option<String> = sqlx::query_scalar(
+        r#"
+        UPDATE payments
+        SET attempts = attempts + 1, failure_reason = $2, last_error = $3, transaction_hash = NULL,
+            status = CASE WHEN attempts + 1 >= $4 THEN 'dead_lettered' ELSE 'queued' END,
+            dead_lettered_at = CASE WHEN attempts + 1 >= $4 THEN NOW() END,
+            updated_at = NOW()
+        WHERE id = $1 AND status IN ('queued', 'processing')
+        RETURNING status
+        "#,
+    )
+    .bind(payment_id)
+    .bind(reason.as_str())
+    .bind(error)
+    .bind(max_attempts as i32)
+    .fetch_optional(db)
+    .await
+    .map_err(db_error)?;
+    Ok(status.as_deref() == Some(DEAD_LETTERED))
+}
+
+async fn record_intervention(
+    db: &mut PgConnection,
+    payment_id: Uuid,
+    admin_id: Uuid,
+    action: &str,
+    detail: serde_json::Value,
+) -> PaymentResult<()> {
+    sqlx::query("INSERT INTO payment_interventions (payment_id, admin_id, action, detail) VALUES ($1, $2, $3, $4)")
+        .bind(payment_id)
+        .bind(admin_id)
+        .bind(action)
+        .bind(detail)
+        .execute(db)
+        .await
+        .map_err(db_error)?;
+    Ok(())
+}
+
+pub struct DeadLetterService {
+    service: Arc<PaymentService>,
+}
+
+impl DeadLetterService {
+    pub fn new(service: Arc<PaymentService>) -> Self {
+        Self { service }
+    }
+
+    fn db(&self) -> &PgPool {
+        self.service.db_pool()
+    }
+
+    /// Dead-lettered payments, most recent first
+    pub async fn list(
+        &self,
+        reason: Option<FailureReason>,
+        limit: i64,
+        offset: i64,
+    ) -> PaymentResult<(Vec<DeadLetter>, i64)> {
+        let reason = reason.map(|reason| reason.as_str());
+        let payments = sqlx::query_as::<_, DeadLetter>(&format!(
+            r#"
+            SELECT {} FROM payments
+            WHERE status = $1 AND ($2::TEXT IS NULL OR failure_reason = $2)
+            ORDER BY dead_lettered_at DESC
+            LIMIT $3 OFFSET $4
+            "#,
+            DEAD_LETTER_COLUMNS
+        ))
+        .bind(DEAD_LETTERED)
+        .bind(reason)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(self.db())
+        .await
+        .map_err(db_error)?;
+        let total = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM payments WHERE status = $1 AND ($2::TEXT IS NULL OR failure_reason = $2)",
+        )
+        .bind(DEAD_LETTERED)
+        .bind(reason)
+        .fetch_one(self.db())
+        .await
+        .map_err(db_error)?;
+        Ok((payments, total))
+    }
+
+    /// A payment with its transactions and the interventions on it, whether
+    /// or not it is still dead-lettered
+    pub async fn find(&self, id: Uuid) -> PaymentResult<Option<DeadLetterDetail>> {
+        let payment = sqlx::query_as::<_, DeadLetter>(&format!(
+            "SELECT {} FROM payments WHERE id = $1",
+            DEAD_LETTER_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(self.db())
+        .await
+        .map_err(db_error)?;
+        let Some(payment) = payment else {
+            return Ok(None);
+        };
+
+        let transactions = sqlx::query_as::<_, PaymentAttempt>(
+            r#"
+            SELECT transaction_hash, status, error_message, created_at
+            FROM payment_transactions WHERE payment_id = $1
+            ORDER BY created_at
+            "#,
+        )
+        .bind(id)
+        .fetch_all(self.db())
+        .await
+        .map_err(db_error)?;
+        let interventions = sqlx::query_as::<_, Intervention>(
+            "SELECT id, admin_id, action, detail, created_at FROM payment_interventions WHERE payment_id = $1 ORDER BY created_at",
+        )
+        .bind(id)
+        .fetch_all(self.db())
+        .await
+        .map_err(db_error)?;
+        Ok(Some(DeadLetterDetail { payment, transactions, interventions }))
+    }
+
+    /// The dead-lettered payment `id`, locked for the rest of `db`'s transaction
+    async fn lock(&self, db: &mut PgConnection, id: Uuid) -> PaymentResult<DeadLetter> {
+        let payment = sqlx::query_as::<_, DeadLetter>(&format!(
+            "SELECT {} FROM payments WHERE id = $1 FOR UPDATE",
+            DEAD_LETTER_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&mut *db)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| PaymentError::NotFound(format!("Payment {} not found", id)))?;
+        if payment.status != DEAD_LETTERED {
+            return Err(PaymentError::AlreadyProcessed(format!(
+                "Payment {} is {}, not dead-lettered",
+                id, payment.status
+            )));
+        }
+        Ok(payment)
+    }
+
+    /// Refuse a nonce the payer already used, on-chain or from the service
+    async fn check_nonce(&self, payer: &str, nonce: u64) -> PaymentResult<()> {
+        let address = payer
+            .parse::<Address>()
+            .map_err(|_| PaymentError::ValidationError(format!("{} is not an address", payer)))?;
+        let chain_next = self
+            .service
+            .provider()
+            .get_transaction_count(address, Some(BlockNumber::Pending.into()))
+            .await
+            .map_err(|e| PaymentError::BlockchainError(e.to_string()))?;
+        if U256::from(nonce) < chain_next {
+            return Err(PaymentError::ValidationError(format!(
+                "Nonce {} of {} was already used on-chain (next is {})",
+                nonce, payer, chain_next
+            )));
+        }
+        let taken: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM outgoing_transactions WHERE LOWER(from_address) = LOWER($1) AND nonce = $2)",
+        )
+        .bind(payer)
+        .bind(nonce as i64)
+        .fetch_one(self.db())
+        .await
+        .map_err(db_error)?;
+        if taken {
+            return Err(PaymentError::ValidationError(format!(
+                "Nonce {} of {} was already sent by the service",
+                nonce, payer
+            )));
+        }
+        Ok(())
+    }
+
+    /// Set the gas price, gas limit and nonce of a dead-lettered payment's
+    /// next send
+    pub async fn update(&self, id: Uuid, params: &DeadLetterParams, admin_id: Uuid) -> PaymentResult<DeadLetter> {
+        let gas_price = params
+            .gas_price
+            .as_deref()
+            .map(|value| {
+                U256::from_dec_str(value)
+                    .map_err(|_| PaymentError::ValidationError(format!("Gas price {} is not a whole number", value)))
+            })
+            .transpose()?;
+        if let Some(gas_price) = gas_price {
+            let cap = U256::from(self.service.config().blockchain.max_gas_price_gwei) * U256::exp10(9);
+            if gas_price.is_zero() || gas_price > cap {
+                return Err(PaymentError::ValidationError(format!(
+                    "Gas price must be between 1 and {} wei",
+                    cap
+                )));
+            }
+        }
+        if params.gas_limit.is_some_and(|gas_limit| gas_limit < MIN_GAS_LIMIT) {
+            return Err(PaymentError::ValidationError(format!(
+                "Gas limit must be at least {}",
+                MIN_GAS_LIMIT
+            )));
+        }
+
+        let mut tx = self.db().begin().await.map_err(db_error)?;
+        let payment = self.lock(&mut tx, id).await?;
+        if let Some(nonce) = params.nonce {
+            self.check_nonce(&payment.payer_address, nonce).await?;
+        }
+        let payment = sqlx::query_as::<_, DeadLetter>(&format!(
+            r#"
+            UPDATE payments
+            SET gas_price_override = $2::NUMERIC, gas_limit_override = $3::NUMERIC, nonce_override = $4,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            DEAD_LETTER_COLUMNS
+        ))
+        .bind(id)
+        .bind(gas_price.map(|gas_price| gas_price.to_string()))
+        .bind(params.gas_limit.map(|gas_limit| gas_limit.to_string()))
+        .bind(params.nonce.map(|nonce| nonce as i64))
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        let detail = json!({
+            "gas_price": payment.gas_price_override,
+            "gas_limit": payment.gas_limit_override,
+            "nonce": payment.nonce_override,
+            "note": params.note,
+        });
+        record_intervention(&mut tx, id, admin_id, "edit", detail).await?;
+        tx.commit().await.map_err(db_error)?;
+
+        info!("Admin {} set the send parameters of dead-lettered payment {}", admin_id, id);
+        Ok(payment)
+    }
+
+    /// Queue a dead-lettered payment to be sent again, with its attempts
+    /// starting over
+    pub async fn requeue(&self, id: Uuid, req: &InterventionRequest, admin_id: Uuid) -> PaymentResult<DeadLetter> {
+        let mut tx = self.db().begin().await.map_err(db_error)?;
+        let dead_letter = self.lock(&mut tx, id).await?;
+        let payment = sqlx::query_as::<_, DeadLetter>(&format!(
+            r#"
+            UPDATE payments SET status = 'queued', attempts = 0, dead_lettered_at = NULL, updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            DEAD_LETTER_COLUMNS
+        ))
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        let detail = json!({
+            "attempts": dead_letter.attempts,
+            "failure_reason": dead_letter.failure_reason,
+            "note": req.note,
+        });
+        record_intervention(&mut tx, id, admin_id, "requeue", detail).await?;
+        tx.commit().await.map_err(db_error)?;
+
+        info!("Admin {} requeued dead-lettered payment {}", admin_id, id);
+        Ok(payment)
+    }
+
+    /// Give up on a dead-lettered payment. What it paid for follows it as a
+    /// failure: a withdrawal fails, a fee sweep is released.
+    pub async fn cancel(&self, id: Uuid, req: &InterventionRequest, admin_id: Uuid) -> PaymentResult<DeadLetter> {
+        let mut tx = self.db().begin().await.map_err(db_error)?;
+        let dead_letter = self.lock(&mut tx, id).await?;
+        let payment = sqlx::query_as::<_, DeadLetter>(&format!(
+            "UPDATE payments SET status = $2, updated_at = NOW() WHERE id = $1 RETURNING {}",
+            DEAD_LETTER_COLUMNS
+        ))
+        .bind(id)
+        .bind(CANCELLED)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        let detail = json!({
+            "attempts": dead_letter.attempts,
+            "failure_reason": dead_letter.failure_reason,
+            "note": req.note,
+        });
+        record_intervention(&mut tx, id, admin_id, "cancel", detail).await?;
+        tx.commit().await.map_err(db_error)?;
+
+        warn!(
+            "Admin {} cancelled dead-lettered payment {} of {} to {}",
+            admin_id, id, payment.amount, payment.recipient_address
+        );
+        Ok(payment)
+    }
+}
@@ -0,0 +1,606 @@
+// Bounty reward escrow
+//
+// A bounty's reward is held in escrow from creation until the bounty is
+// completed or cancelled. The creator deposits the reward on-chain and
+// reports the transaction; the escrow stays `pending` until the transaction
+// monitor (or an indexer delivery) confirms it over RPC, and bounty-manager
+// keeps the bounty inactive until then; funding is announced as an
+// `escrow_confirmed` payment event. Completion releases the escrow;
+// cancellation or expiry refunds a funded escrow to the creator, including
+// one whose deposit only confirms after the bounty was cancelled.
+//
+// A refund keeps the platform's configured fee for its reason (cancelled or
+// expired; a bounty that never went live is refunded in full), records the
+// refund and the fee in the same transaction, and is announced as a
+// `refund_issued` payment event.
+//
+// The reward may be in any allowlisted token (see `tokens`); amounts are in
+// that token's base units.
+
+use chrono::{DateTime, Utc};
+use ethers::types::{Address, TransactionReceipt, H256, U256};
+use ethers::utils::keccak256;
+use serde::{Deserialize, Serialize};
+use shared::messaging::PaymentEventKind;
+use sqlx::PgPool;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::models::{DepositBountyRequest, PaymentError, PaymentResult, PaymentType};
+use crate::services::fees::{self, REFUND_FEE};
+use crate::services::payment_events::{self, NewPaymentEvent};
+use crate::services::payment_service::PaymentService;
+use crate::services::tokens;
+use crate::workers::transaction_monitor::reconcile_transaction;
+
+pub const PENDING: &str = "pending";
+pub const FUNDED: &str = "funded";
+pub const FAILED: &str = "failed";
+pub const RELEASED: &str = "released";
+pub const CANCELLED: &str = "cancelled";
+pub const REFUNDED: &str = "refunded";
+
+const ESCROW_COLUMNS: &str = "id, bounty_id, holder_address, amount::TEXT AS amount, token_address, \
+                              COALESCE(status, 'pending') AS status, deposit_tx_hash, locked_at, \
+                              funded_at, released_at, refunded_at, organization_id, updated_at";
+
+/// Reward held for one bounty
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct EscrowAccount {
+    pub id: Uuid,
+    pub bounty_id: Uuid,
+    /// Creator wallet the deposit comes from and refunds go to
+    pub holder_address: String,
+    /// In base units of the token
+    pub amount: String,
+    pub token_address: String,
+    pub status: String,
+    pub deposit_tx_hash: Option<String>,
+    pub locked_at: DateTime<Utc>,
+    pub funded_at: Option<DateTime<Utc>>,
+    pub released_at: Option<DateTime<Utc>>,
+    pub refunded_at: Option<DateTime<Utc>>,
+    /// Organization the bounty was opened for
+    pub organization_id: Option<Uuid>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl EscrowAccount {
+    /// Whether the bounty may be activated
+    pub fn is_funded(&self) -> bool {
+        self.status == FUNDED
+    }
+}
+
+/// Why a bounty's reward goes back to its creator
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RefundReason {
+    /// The creator or a moderator cancelled the bounty
+    #[default]
+    Cancelled,
+    /// The bounty expired with too few submissions or no consensus
+    Expired,
+    /// The bounty never went live
+    Unfunded,
+}
+
+impl RefundReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RefundReason::Cancelled => "cancelled",
+            RefundReason::Expired => "expired",
+            RefundReason::Unfunded => "unfunded",
+        }
+    }
+
+    /// Percentage of the escrow the platform keeps
+    fn fee_percentage(&self, service: &PaymentService) -> f64 {
+        let config = &service.config().fees;
+        match self {
+            RefundReason::Cancelled => config.cancel_refund_fee_percentage,
+            RefundReason::Expired => config.expiry_refund_fee_percentage,
+            RefundReason::Unfunded => 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct RefundBountyRequest {
+    #[serde(default)]
+    pub reason: RefundReason,
+}
+
+/// An escrow returned to its creator
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct EscrowRefund {
+    pub id: Uuid,
+    pub bounty_id: Uuid,
+    pub reason: String,
+    pub recipient_address: String,
+    pub token_address: String,
+    /// All amounts in base units of the token
+    pub amount: String,
+    pub fee_amount: String,
+    pub refunded_amount: String,
+    /// The transfer back; unset when the fee took the whole escrow
+    pub payment_id: Option<Uuid>,
+    /// The transfer's status
+    pub status: Option<String>,
+    pub transaction_hash: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+const REFUND_COLUMNS: &str = "r.id, r.bounty_id, r.reason, r.recipient_address, r.token_address, \
+                              r.amount::TEXT AS amount, r.fee_amount::TEXT AS fee_amount, \
+                              r.refunded_amount::TEXT AS refunded_amount, r.payment_id, p.status, \
+                              p.transaction_hash, r.created_at";
+
+fn db_error(e: sqlx::Error) -> PaymentError {
+    PaymentError::DatabaseError(e.to_string())
+}
+
+/// Attaching a deposit hash another escrow holds trips its unique index
+fn deposit_error(e: sqlx::Error) -> PaymentError {
+    match &e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => {
+            PaymentError::ValidationError("deposit_tx_hash already funds another bounty".to_string())
+        }
+        _ => db_error(e),
+    }
+}
+
+fn is_tx_hash(value: &str) -> bool {
+    value.len() == 66
+        && value.starts_with("0x")
+        && value[2..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+pub async fn find(pool: &PgPool, bounty_id: Uuid) -> PaymentResult<Option<EscrowAccount>> {
+    sqlx::query_as(&format!("SELECT {} FROM escrow_accounts WHERE bounty_id = $1", ESCROW_COLUMNS))
+        .bind(bounty_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(db_error)
+}
+
+/// The refund of a bounty's escrow, if it was refunded
+pub async fn find_refund(pool: &PgPool, bounty_id: Uuid) -> PaymentResult<Option<EscrowRefund>> {
+    sqlx::query_as(&format!(
+        "SELECT {} FROM escrow_refunds r LEFT JOIN payments p ON p.id = r.payment_id WHERE r.bounty_id = $1",
+        REFUND_COLUMNS
+    ))
+    .bind(bounty_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(db_error)
+}
+
+/// An organization's escrows, newest first
+pub async fn list_for_organization(
+    pool: &PgPool,
+    organization_id: Uuid,
+    limit: i64,
+    offset: i64,
+) -> PaymentResult<Vec<EscrowAccount>> {
+    sqlx::query_as(&format!(
+        "SELECT {} FROM escrow_accounts WHERE organization_id = $1 ORDER BY locked_at DESC LIMIT $2 OFFSET $3",
+        ESCROW_COLUMNS
+    ))
+    .bind(organization_id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await
+    .map_err(db_error)
+}
+
+/// Open the escrow for a bounty, or attach the deposit transaction to an
+/// open one. Repeating a call is harmless; a reverted deposit may be
+/// replaced by a new transaction.
+pub async fn open(service: &PaymentService, req: &DepositBountyRequest) -> PaymentResult<EscrowAccount> {
+    let amount = tokens::base_units(req.amount)?;
+    let token = tokens::resolve(service, req.token_address.as_deref())?;
+    let tx_hash = req.deposit_tx_hash.as_deref().map(str::to_lowercase);
+    if tx_hash.as_deref().is_some_and(|hash| !is_tx_hash(hash)) {
+        return Err(PaymentError::ValidationError("deposit_tx_hash is not a transaction hash".to_string()));
+    }
+
+    let pool = service.db_pool();
+    if let Some(tx_hash) = &tx_hash {
+        let claimed: Option<Uuid> = sqlx::query_scalar(
+            "SELECT bounty_id FROM escrow_accounts WHERE LOWER(deposit_tx_hash) = $1 AND bounty_id <> $2",
+        )
+        .bind(tx_hash)
+        .bind(req.bounty_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(db_error)?;
+        if claimed.is_some() {
+            return Err(PaymentError::ValidationError(
+                "deposit_tx_hash already funds another bounty".to_string(),
+            ));
+        }
+    }
+    if let Some(existing) = find(pool, req.bounty_id).await? {
+        if !existing.holder_address.eq_ignore_ascii_case(&req.creator_address)
+            || existing.amount != amount.to_string()
+            || !existing.token_address.eq_ignore_ascii_case(&token.address)
+        {
+            return Err(PaymentError::ValidationError(
+                "Escrow already opened with a different creator, amount or token".to_string(),
+            ));
+        }
+        if existing.status != PENDING && existing.status != FAILED {
+            return Ok(existing);
+        }
+        let Some(tx_hash) = tx_hash else {
+            return Ok(existing);
+        };
+        if existing.status == PENDING && existing.deposit_tx_hash.as_deref() == Some(tx_hash.as_str()) {
+            return Ok(existing);
+        }
+        sqlx::query(
+            "UPDATE escrow_accounts SET deposit_tx_hash = $1, status = 'pending', updated_at = NOW() WHERE bounty_id = $2",
+        )
+        .bind(&tx_hash)
+        .bind(req.bounty_id)
+        .execute(pool)
+        .await
+        .map_err(deposit_error)?;
+        track_deposit(service, &existing.holder_address, &existing.amount, &existing.token_address, req.bounty_id, &tx_hash).await?;
+        return find(pool, req.bounty_id).await?.ok_or_else(|| PaymentError::NotFound(req.bounty_id.to_string()));
+    }
+
+    // Before the deposit is sent the creator must still hold the reward
+    if tx_hash.is_none() {
+        let balance = service
+            .get_token_balance_of(&token.address, &req.creator_address)
+            .await
+            .map_err(|e| PaymentError::BlockchainError(e.to_string()))?;
+        if balance < amount {
+            return Err(PaymentError::InsufficientBalance(format!(
+                "{} balance {} is below the reward of {}",
+                token.symbol, balance, amount
+            )));
+        }
+    }
+
+    let token_address = token.address.clone();
+    sqlx::query(
+        r#"
+        INSERT INTO escrow_accounts (bounty_id, holder_address, amount, token_address, status, deposit_tx_hash, organization_id)
+        VALUES ($1, $2, $3::NUMERIC, $4, 'pending', $5, $6)
+        ON CONFLICT (bounty_id) DO NOTHING
+        "#,
+    )
+    .bind(req.bounty_id)
+    .bind(&req.creator_address)
+    .bind(amount.to_string())
+    .bind(&token_address)
+    .bind(&tx_hash)
+    .bind(req.organization_id)
+    .execute(pool)
+    .await
+    .map_err(deposit_error)?;
+    info!("Opened escrow of {} for bounty {}", amount, req.bounty_id);
+
+    if let Some(tx_hash) = &tx_hash {
+        track_deposit(service, &req.creator_address, &amount.to_string(), &token_address, req.bounty_id, tx_hash).await?;
+    }
+    find(pool, req.bounty_id).await?.ok_or_else(|| PaymentError::NotFound(req.bounty_id.to_string()))
+}
+
+/// Record the deposit so the transaction monitor settles it, and settle it
+/// right away if it is already mined
+async fn track_deposit(
+    service: &PaymentService,
+    creator_address: &str,
+    amount: &str,
+    token_address: &str,
+    bounty_id: Uuid,
+    tx_hash: &str,
+) -> PaymentResult<()> {
+    let pool = service.db_pool();
+    let contract = &service.config().blockchain.payment_contract_address;
+
+    let mut tx = pool.begin().await.map_err(db_error)?;
+    let payment_id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO payments (bounty_id, payer_address, recipient_address, amount, token_address,
+                              transaction_hash, status, payment_type)
+        VALUES ($1, $2, $3, $4::NUMERIC, $5, $6, 'pending', $7)
+        RETURNING id
+        "#,
+    )
+    .bind(bounty_id)
+    .bind(creator_address)
+    .bind(contract)
+    .bind(amount)
+    .bind(token_address)
+    .bind(tx_hash)
+    .bind(PaymentType::BountyDeposit.to_string())
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(db_error)?;
+    let tracked = sqlx::query_scalar::<_, Uuid>(
+        r#"
+        INSERT INTO payment_transactions (payment_id, transaction_hash, from_address, to_address, value)
+        VALUES ($1, $2, $3, $4, $5::NUMERIC)
+        ON CONFLICT (transaction_hash) DO NOTHING
+        RETURNING id
+        "#,
+    )
+    .bind(payment_id)
+    .bind(tx_hash)
+    .bind(creator_address)
+    .bind(contract)
+    .bind(amount)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(db_error)?;
+    tx.commit().await.map_err(db_error)?;
+
+    if let Some(id) = tracked {
+        if let Err(e) = reconcile_transaction(service, id, tx_hash).await {
+            // The monitor retries on its next sweep
+            warn!("Could not check deposit {} yet: {}", tx_hash, e);
+        }
+    }
+    Ok(())
+}
+
+/// Whether `receipt` holds an ERC-20 transfer of at least the escrowed
+/// amount from the holder to the payment contract. A reported hash proves
+/// nothing by itself: it could be any confirmed transaction.
+fn pays_escrow(receipt: &TransactionReceipt, escrow: &EscrowAccount, contract: &str) -> bool {
+    let parse = |value: &str| value.parse::<Address>().ok();
+    let (Some(token), Some(holder), Some(contract), Ok(amount)) = (
+        parse(&escrow.token_address),
+        parse(&escrow.holder_address),
+        parse(contract),
+        U256::from_dec_str(&escrow.amount),
+    ) else {
+        return false;
+    };
+    let transfer_topic = H256::from(keccak256("Transfer(address,address,uint256)"));
+
+    receipt.logs.iter().any(|log| {
+        log.address == token
+            && log.topics.len() == 3
+            && log.topics[0] == transfer_topic
+            && log.topics[1] == H256::from(holder)
+            && log.topics[2] == H256::from(contract)
+            && U256::from_big_endian(&log.data) >= amount
+    })
+}
+
+/// Move the escrow waiting on a deposit once it settles on-chain. Called with
+/// the transaction's new status (`confirmed` or `failed`).
+pub async fn settle_deposit(service: &PaymentService, tx_hash: &str, tx_status: &str) -> PaymentResult<()> {
+    let pool = service.db_pool();
+    // The deposit hash is unique, so a deposit settles at most one escrow
+    let escrow: Option<EscrowAccount> = sqlx::query_as(&format!(
+        "SELECT {} FROM escrow_accounts WHERE LOWER(deposit_tx_hash) = LOWER($1) AND status IN ('pending', 'cancelled')",
+        ESCROW_COLUMNS
+    ))
+    .bind(tx_hash)
+    .fetch_optional(pool)
+    .await
+    .map_err(db_error)?;
+    let Some(escrow) = escrow else {
+        return Ok(());
+    };
+
+    let receipt = match tx_status {
+        "confirmed" => service
+            .get_tx_receipt(tx_hash)
+            .await
+            .map_err(|e| PaymentError::BlockchainError(e.to_string()))?,
+        _ => None,
+    };
+    let contract = &service.config().blockchain.payment_contract_address;
+
+    let paid = receipt
+        .as_ref()
+        .is_some_and(|receipt| pays_escrow(receipt, &escrow, contract));
+    match (escrow.status.as_str(), paid) {
+        (PENDING, true) => {
+            let mut tx = pool.begin().await.map_err(db_error)?;
+            let funded = sqlx::query(
+                "UPDATE escrow_accounts SET status = 'funded', funded_at = NOW(), updated_at = NOW() WHERE id = $1 AND status = 'pending'",
+            )
+            .bind(escrow.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+            if funded.rows_affected() > 0 {
+                let event = NewPaymentEvent {
+                    kind: PaymentEventKind::EscrowConfirmed,
+                    source_id: escrow.id,
+                    bounty_id: Some(escrow.bounty_id),
+                    user_id: None,
+                    address: Some(escrow.holder_address.clone()),
+                    amount: Some(escrow.amount.clone()),
+                    token_address: Some(escrow.token_address.clone()),
+                    tx_hash: Some(tx_hash.to_string()),
+                };
+                payment_events::record(&mut tx, &event).await?;
+            }
+            tx.commit().await.map_err(db_error)?;
+            info!("Escrow for bounty {} funded by {}", escrow.bounty_id, tx_hash);
+        }
+        (PENDING, false) => {
+            sqlx::query("UPDATE escrow_accounts SET status = 'failed', updated_at = NOW() WHERE id = $1 AND status = 'pending'")
+                .bind(escrow.id)
+                .execute(pool)
+                .await
+                .map_err(db_error)?;
+            warn!(
+                "Deposit {} for bounty {} {}",
+                tx_hash,
+                escrow.bounty_id,
+                if tx_status == "confirmed" { "does not pay the escrow" } else { "failed on-chain" }
+            );
+        }
+        (CANCELLED, true) => {
+            // The bounty is gone; hand the late deposit back
+            queue_refund(service, &escrow, CANCELLED, RefundReason::Unfunded).await?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Pay out a funded escrow when its bounty completes. With `payout_due` the
+/// service pays the reward itself (see `payouts`).
+pub async fn release(pool: &PgPool, bounty_id: Uuid, payout_due: bool) -> PaymentResult<EscrowAccount> {
+    let escrow = find(pool, bounty_id).await?.ok_or_else(|| PaymentError::NotFound(bounty_id.to_string()))?;
+    match escrow.status.as_str() {
+        RELEASED => return Ok(escrow),
+        FUNDED => {}
+        status => {
+            return Err(PaymentError::AlreadyProcessed(format!(
+                "escrow for bounty {} is {}, not funded",
+                bounty_id, status
+            )))
+        }
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE escrow_accounts SET status = 'released', released_at = NOW(), payout_due = $2, updated_at = NOW()
+        WHERE id = $1 AND status = 'funded'
+        "#,
+    )
+    .bind(escrow.id)
+    .bind(payout_due)
+    .execute(pool)
+    .await
+    .map_err(db_error)?;
+    info!("Released escrow of {} for bounty {}", escrow.amount, bounty_id);
+    find(pool, bounty_id).await?.ok_or_else(|| PaymentError::NotFound(bounty_id.to_string()))
+}
+
+/// Return the reward to the creator when a bounty is cancelled or expires.
+/// An escrow still waiting on its deposit is cancelled; the deposit is
+/// refunded in full if it confirms later.
+pub async fn refund(service: &PaymentService, bounty_id: Uuid, reason: RefundReason) -> PaymentResult<EscrowAccount> {
+    let pool = service.db_pool();
+    let escrow = find(pool, bounty_id).await?.ok_or_else(|| PaymentError::NotFound(bounty_id.to_string()))?;
+    match escrow.status.as_str() {
+        REFUNDED | CANCELLED => {}
+        FUNDED => queue_refund(service, &escrow, FUNDED, reason).await?,
+        PENDING | FAILED => {
+            sqlx::query(
+                "UPDATE escrow_accounts SET status = 'cancelled', updated_at = NOW() WHERE id = $1 AND status IN ('pending', 'failed')",
+            )
+            .bind(escrow.id)
+            .execute(pool)
+            .await
+            .map_err(db_error)?;
+            info!("Cancelled unfunded escrow for bounty {}", bounty_id);
+        }
+        status => {
+            return Err(PaymentError::AlreadyProcessed(format!(
+                "escrow for bounty {} is {}",
+                bounty_id, status
+            )))
+        }
+    }
+    find(pool, bounty_id).await?.ok_or_else(|| PaymentError::NotFound(bounty_id.to_string()))
+}
+
+/// Mark an escrow refunded and queue the transfer back to its holder, less
+/// the fee for `reason`, for the pending payment processor
+async fn queue_refund(
+    service: &PaymentService,
+    escrow: &EscrowAccount,
+    from_status: &str,
+    reason: RefundReason,
+) -> PaymentResult<()> {
+    let amount = U256::from_dec_str(escrow.amount.split('.').next().unwrap_or_default())
+        .map_err(|_| PaymentError::ValidationError(format!("escrowed amount {} is not a number", escrow.amount)))?;
+    let fee = fees::fee_of(amount, reason.fee_percentage(service));
+    let refunded = amount - fee;
+
+    let mut tx = service.db_pool().begin().await.map_err(db_error)?;
+    let updated = sqlx::query(
+        "UPDATE escrow_accounts SET status = 'refunded', refunded_at = NOW(), updated_at = NOW() WHERE id = $1 AND status = $2",
+    )
+    .bind(escrow.id)
+    .bind(from_status)
+    .execute(&mut *tx)
+    .await
+    .map_err(db_error)?
+    .rows_affected();
+    if updated == 0 {
+        // Settled concurrently
+        return Ok(());
+    }
+
+    let payment_id = if refunded.is_zero() {
+        None
+    } else {
+        let payment_id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO payments (bounty_id, payer_address, recipient_address, amount, token_address, status, payment_type)
+            VALUES ($1, $2, $3, $4::NUMERIC, $5, 'queued', $6)
+            RETURNING id
+            "#,
+        )
+        .bind(escrow.bounty_id)
+        .bind(&service.config().blockchain.payment_contract_address)
+        .bind(&escrow.holder_address)
+        .bind(refunded.to_string())
+        .bind(&escrow.token_address)
+        .bind(PaymentType::Refund.to_string())
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        Some(payment_id)
+    };
+    let refund_id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO escrow_refunds
+            (escrow_id, bounty_id, reason, recipient_address, token_address, amount, fee_amount, refunded_amount, payment_id)
+        VALUES ($1, $2, $3, $4, $5, $6::NUMERIC, $7::NUMERIC, $8::NUMERIC, $9)
+        RETURNING id
+        "#,
+    )
+    .bind(escrow.id)
+    .bind(escrow.bounty_id)
+    .bind(reason.as_str())
+    .bind(&escrow.holder_address)
+    .bind(&escrow.token_address)
+    .bind(amount.to_string())
+    .bind(fee.to_string())
+    .bind(refunded.to_string())
+    .bind(payment_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(db_error)?;
+    fees::accrue(&mut tx, REFUND_FEE, &escrow.token_address, fee, Some(escrow.bounty_id), refund_id).await?;
+    let event = NewPaymentEvent {
+        kind: PaymentEventKind::RefundIssued,
+        source_id: refund_id,
+        bounty_id: Some(escrow.bounty_id),
+        user_id: None,
+        address: Some(escrow.holder_address.clone()),
+        amount: Some(refunded.to_string()),
+        token_address: Some(escrow.token_address.clone()),
+        tx_hash: None,
+    };
+    payment_events::record(&mut tx, &event).await?;
+    tx.commit().await.map_err(db_error)?;
+
+    info!(
+        "Queued refund of {} ({} fee kept) to {} for {} bounty {}",
+        refunded,
+        fee,
+        escrow.holder_address,
+        reason.as_str(),
+        escrow.bounty_id
+    );
+    Ok(())
+}
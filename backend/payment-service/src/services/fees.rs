@@ -0,0 +1,401 @@
+// Platform fees
+//
+// The platform keeps a configured percentage of every bounty reward it pays
+// out, taken off the top before the reward is split among the engines, of
+// every stake a settlement plan slashes, and of the escrow of a cancelled or
+// expired bounty it refunds, and charges a fee for relaying gasless claims.
+// All stay in the treasury and are recorded in the fee ledger per token, once
+// per source.
+//
+// Admins sweep accrued fees out of the treasury to the configured sweep
+// address. A sweep is a treasury payment like any other; while it is pending
+// or once it completed it counts against the accrued balance, and a failed
+// or cancelled one is released again. A sweep never exceeds what the ledger holds nor the
+// treasury's balance of the token.
+
+use chrono::{DateTime, Utc};
+use ethers::types::U256;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::{PgConnection, PgPool};
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::config::FeeConfig;
+use crate::models::{PaymentError, PaymentResult};
+use crate::services::payment_service::PaymentService;
+use crate::services::tokens;
+
+pub const REWARD_FEE: &str = "reward_fee";
+pub const SLASH_RETENTION: &str = "slash_retention";
+pub const REFUND_FEE: &str = "refund_fee";
+pub const RELAY_FEE: &str = "relay_fee";
+
+/// Percentages are applied in billionths
+const FEE_PRECISION: u64 = 1_000_000_000;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenFees {
+    pub symbol: Option<String>,
+    pub token_address: String,
+    /// All amounts in base units of the token
+    pub reward_fees: String,
+    pub slash_retention: String,
+    pub refund_fees: String,
+    pub relay_fees: String,
+    pub accrued: String,
+    /// Sweeps confirmed on-chain
+    pub swept: String,
+    /// Sweeps not yet confirmed
+    pub sweeping: String,
+    /// What a sweep may still take
+    pub available: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FeeSummary {
+    pub reward_fee_percentage: f64,
+    pub slash_retention_percentage: f64,
+    pub cancel_refund_fee_percentage: f64,
+    pub expiry_refund_fee_percentage: f64,
+    pub relay_fee_percentage: f64,
+    pub sweep_address: Option<String>,
+    pub tokens: Vec<TokenFees>,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct FeeLedgerEntry {
+    pub id: Uuid,
+    pub kind: String,
+    pub token_address: String,
+    /// In base units of the token
+    pub amount: String,
+    pub bounty_id: Option<Uuid>,
+    pub source_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct FeeSweep {
+    pub id: Uuid,
+    pub token_address: String,
+    /// In base units of the token
+    pub amount: String,
+    pub to_address: String,
+    pub payment_id: Uuid,
+    /// The sweep payment's status (queued, processing, completed, failed,
+    /// dead_lettered, cancelled)
+    pub status: Option<String>,
+    pub transaction_hash: Option<String>,
+    pub requested_by: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SweepRequest {
+    /// The platform token when unset
+    #[serde(default)]
+    pub token_address: Option<String>,
+    /// In base units; everything available when unset
+    #[serde(default)]
+    pub amount: Option<rust_decimal::Decimal>,
+}
+
+#[derive(sqlx::FromRow)]
+struct TokenTotals {
+    token_address: String,
+    reward_fees: String,
+    slash_retention: String,
+    refund_fees: String,
+    relay_fees: String,
+    swept: String,
+    sweeping: String,
+}
+
+const SWEEP_COLUMNS: &str = "s.id, s.token_address, s.amount::TEXT AS amount, s.to_address, s.payment_id, \
+                             p.status, p.transaction_hash, s.requested_by, s.created_at";
+
+fn db_error(e: sqlx::Error) -> PaymentError {
+    PaymentError::DatabaseError(e.to_string())
+}
+
+fn parse_u256(value: &str) -> PaymentResult<U256> {
+    let whole = value.split('.').next().unwrap_or_default();
+    U256::from_dec_str(whole).map_err(|_| PaymentError::ValidationError(format!("{} is not an amount", value)))
+}
+
+/// `percentage` percent of `amount`, rounded down
+pub fn fee_of(amount: U256, percentage: f64) -> U256 {
+    let fraction = (percentage.clamp(0.0, 100.0) / 100.0 * FEE_PRECISION as f64).round() as u64;
+    amount * U256::from(fraction) / U256::from(FEE_PRECISION)
+}
+
+/// Record a fee in the caller's transaction. Repeating it for the same
+/// source does nothing.
+pub async fn accrue(
+    db: &mut PgConnection,
+    kind: &str,
+    token_address: &str,
+    amount: U256,
+    bounty_id: Option<Uuid>,
+    source_id: Uuid,
+) -> PaymentResult<()> {
+    if amount.is_zero() {
+        return Ok(());
+    }
+    sqlx::query(
+        r#"
+        INSERT INTO fee_ledger (kind, token_address, amount, bounty_id, source_id)
+        VALUES ($1, $2, $3::NUMERIC, $4, $5)
+        ON CONFLICT (kind, source_id) DO NOTHING
+        "#,
+    )
+    .bind(kind)
+    .bind(token_address)
+    .bind(amount.to_string())
+    .bind(bounty_id)
+    .bind(source_id)
+    .execute(db)
+    .await
+    .map_err(db_error)?;
+    Ok(())
+}
+
+pub struct FeeService {
+    service: Arc<PaymentService>,
+    config: FeeConfig,
+}
+
+impl FeeService {
+    pub fn new(service: Arc<PaymentService>) -> Self {
+        let config = service.config().fees.clone();
+        Self { service, config }
+    }
+
+    fn db(&self) -> &PgPool {
+        self.service.db_pool()
+    }
+
+    async fn totals(&self, db: &mut PgConnection, token_address: Option<&str>) -> PaymentResult<Vec<TokenTotals>> {
+        sqlx::query_as::<_, TokenTotals>(
+            r#"
+            WITH accrued AS (
+                SELECT LOWER(token_address) AS token,
+                       COALESCE(SUM(amount) FILTER (WHERE kind = $1), 0) AS reward_fees,
+                       COALESCE(SUM(amount) FILTER (WHERE kind = $2), 0) AS slash_retention,
+                       COALESCE(SUM(amount) FILTER (WHERE kind = $4), 0) AS refund_fees,
+                       COALESCE(SUM(amount) FILTER (WHERE kind = $5), 0) AS relay_fees
+                FROM fee_ledger
+                GROUP BY LOWER(token_address)
+            ), sweeps AS (
+                SELECT LOWER(s.token_address) AS token,
+                       COALESCE(SUM(s.amount) FILTER (WHERE p.status = 'completed'), 0) AS swept,
+                       COALESCE(SUM(s.amount) FILTER (WHERE p.status NOT IN ('completed', 'failed', 'cancelled')), 0) AS sweeping
+                FROM fee_sweeps s
+                JOIN payments p ON p.id = s.payment_id
+                GROUP BY LOWER(s.token_address)
+            )
+            SELECT COALESCE(a.token, s.token) AS token_address,
+                   COALESCE(a.reward_fees, 0)::TEXT AS reward_fees,
+                   COALESCE(a.slash_retention, 0)::TEXT AS slash_retention,
+                   COALESCE(a.refund_fees, 0)::TEXT AS refund_fees,
+                   COALESCE(a.relay_fees, 0)::TEXT AS relay_fees,
+                   COALESCE(s.swept, 0)::TEXT AS swept,
+                   COALESCE(s.sweeping, 0)::TEXT AS sweeping
+            FROM accrued a
+            FULL JOIN sweeps s ON s.token = a.token
+            WHERE $3::TEXT IS NULL OR COALESCE(a.token, s.token) = LOWER($3)
+            ORDER BY 1
+            "#,
+        )
+        .bind(REWARD_FEE)
+        .bind(SLASH_RETENTION)
+        .bind(token_address)
+        .bind(REFUND_FEE)
+        .bind(RELAY_FEE)
+        .fetch_all(db)
+        .await
+        .map_err(db_error)
+    }
+
+    fn token_fees(&self, totals: &TokenTotals) -> PaymentResult<TokenFees> {
+        let reward_fees = parse_u256(&totals.reward_fees)?;
+        let slash_retention = parse_u256(&totals.slash_retention)?;
+        let refund_fees = parse_u256(&totals.refund_fees)?;
+        let relay_fees = parse_u256(&totals.relay_fees)?;
+        let swept = parse_u256(&totals.swept)?;
+        let sweeping = parse_u256(&totals.sweeping)?;
+        let accrued = reward_fees
+            .saturating_add(slash_retention)
+            .saturating_add(refund_fees)
+            .saturating_add(relay_fees);
+        let token = self.service.config().tokens.find(&totals.token_address);
+
+        Ok(TokenFees {
+            symbol: token.map(|token| token.symbol.clone()),
+            token_address: token.map_or_else(|| totals.token_address.clone(), |token| token.address.clone()),
+            reward_fees: reward_fees.to_string(),
+            slash_retention: slash_retention.to_string(),
+            refund_fees: refund_fees.to_string(),
+            relay_fees: relay_fees.to_string(),
+            accrued: accrued.to_string(),
+            swept: swept.to_string(),
+            sweeping: sweeping.to_string(),
+            available: accrued.saturating_sub(swept).saturating_sub(sweeping).to_string(),
+        })
+    }
+
+    /// Accrued, swept and available fees per token
+    pub async fn summary(&self) -> PaymentResult<FeeSummary> {
+        let mut db = self.db().acquire().await.map_err(db_error)?;
+        let tokens = self
+            .totals(&mut db, None)
+            .await?
+            .iter()
+            .map(|totals| self.token_fees(totals))
+            .collect::<PaymentResult<Vec<_>>>()?;
+
+        Ok(FeeSummary {
+            reward_fee_percentage: self.config.reward_fee_percentage,
+            slash_retention_percentage: self.config.slash_retention_percentage,
+            cancel_refund_fee_percentage: self.config.cancel_refund_fee_percentage,
+            expiry_refund_fee_percentage: self.config.expiry_refund_fee_percentage,
+            relay_fee_percentage: self.service.config().relay.fee_percentage,
+            sweep_address: self.config.sweep_address.clone(),
+            tokens,
+        })
+    }
+
+    /// Ledger entries, newest first, optionally of one token
+    pub async fn ledger(&self, token_address: Option<&str>, limit: i64, offset: i64) -> PaymentResult<(Vec<FeeLedgerEntry>, i64)> {
+        let entries = sqlx::query_as::<_, FeeLedgerEntry>(
+            r#"
+            SELECT id, kind, token_address, amount::TEXT AS amount, bounty_id, source_id, created_at
+            FROM fee_ledger
+            WHERE $1::TEXT IS NULL OR LOWER(token_address) = LOWER($1)
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(token_address)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(self.db())
+        .await
+        .map_err(db_error)?;
+        let total = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM fee_ledger WHERE $1::TEXT IS NULL OR LOWER(token_address) = LOWER($1)",
+        )
+        .bind(token_address)
+        .fetch_one(self.db())
+        .await
+        .map_err(db_error)?;
+        Ok((entries, total))
+    }
+
+    /// Sweeps, newest first
+    pub async fn sweeps(&self, limit: i64) -> PaymentResult<Vec<FeeSweep>> {
+        sqlx::query_as::<_, FeeSweep>(&format!(
+            "SELECT {} FROM fee_sweeps s LEFT JOIN payments p ON p.id = s.payment_id ORDER BY s.created_at DESC LIMIT $1",
+            SWEEP_COLUMNS
+        ))
+        .bind(limit)
+        .fetch_all(self.db())
+        .await
+        .map_err(db_error)
+    }
+
+    /// Queue a sweep of accrued fees of one token to the sweep address
+    pub async fn sweep(&self, req: &SweepRequest, requested_by: Uuid) -> PaymentResult<FeeSweep> {
+        let to_address = self
+            .config
+            .sweep_address
+            .clone()
+            .ok_or_else(|| PaymentError::ValidationError("FEE_SWEEP_ADDRESS is not configured".to_string()))?;
+        let token = tokens::resolve(&self.service, req.token_address.as_deref())?;
+        let requested = req.amount.map(tokens::base_units).transpose()?;
+        let treasury = self.service.config().blockchain.treasury_address.clone();
+
+        let mut tx = self.db().begin().await.map_err(db_error)?;
+        // One sweep of a token at a time, so two cannot both take the balance
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext('fee_sweep:' || LOWER($1)))")
+            .bind(&token.address)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+
+        let available = match self.totals(&mut tx, Some(&token.address)).await?.first() {
+            Some(totals) => parse_u256(&self.token_fees(totals)?.available)?,
+            None => U256::zero(),
+        };
+        let amount = requested.unwrap_or(available);
+        if amount.is_zero() {
+            return Err(PaymentError::ValidationError(format!("No {} fees to sweep", token.symbol)));
+        }
+        if amount > available {
+            return Err(PaymentError::InsufficientBalance(format!(
+                "Only {} base units of {} fees are available to sweep",
+                available, token.symbol
+            )));
+        }
+        let held = self
+            .service
+            .get_token_balance_of(&token.address, &treasury)
+            .await
+            .map_err(|e| PaymentError::BlockchainError(e.to_string()))?;
+        if amount > held {
+            return Err(PaymentError::InsufficientBalance(format!(
+                "The treasury holds only {} base units of {}",
+                held, token.symbol
+            )));
+        }
+
+        let payment_id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            INSERT INTO payments
+                (payer_address, recipient_address, amount, token_address, status, payment_type, metadata)
+            VALUES ($1, $2, $3::NUMERIC, $4, 'queued', 'fee', $5)
+            RETURNING id
+            "#,
+        )
+        .bind(&treasury)
+        .bind(&to_address)
+        .bind(amount.to_string())
+        .bind(&token.address)
+        .bind(json!({"fee_sweep": true, "requested_by": requested_by}))
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        let sweep_id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            INSERT INTO fee_sweeps (token_address, amount, to_address, payment_id, requested_by)
+            VALUES ($1, $2::NUMERIC, $3, $4, $5)
+            RETURNING id
+            "#,
+        )
+        .bind(&token.address)
+        .bind(amount.to_string())
+        .bind(&to_address)
+        .bind(payment_id)
+        .bind(requested_by)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        let sweep = sqlx::query_as::<_, FeeSweep>(&format!(
+            "SELECT {} FROM fee_sweeps s LEFT JOIN payments p ON p.id = s.payment_id WHERE s.id = $1",
+            SWEEP_COLUMNS
+        ))
+        .bind(sweep_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        tx.commit().await.map_err(db_error)?;
+
+        info!(
+            "Admin {} swept {} base units of {} fees to {}",
+            requested_by, amount, token.symbol, to_address
+        );
+        Ok(sweep)
+    }
+}
@@ -0,0 +1,46 @@
+// Wallet funding
+//
+// Which wallets sent tokens to which, from the transfers the service
+// recorded. The consensus-service uses it to spot engines whose stake wallets
+// were funded from the same source.
+
+use std::collections::HashMap;
+
+use sqlx::PgPool;
+
+use crate::models::{PaymentError, PaymentResult};
+
+/// Most addresses one request may ask about
+pub const MAX_ADDRESSES: usize = 500;
+
+/// The wallets that sent confirmed transfers to each of `addresses`, keyed by
+/// lowercase address. Addresses nothing was sent to are left out.
+pub async fn funders(pool: &PgPool, addresses: &[String]) -> PaymentResult<HashMap<String, Vec<String>>> {
+    if addresses.len() > MAX_ADDRESSES {
+        return Err(PaymentError::ValidationError(format!(
+            "At most {} addresses may be looked up at once",
+            MAX_ADDRESSES
+        )));
+    }
+    let addresses: Vec<String> = addresses.iter().map(|a| a.to_lowercase()).collect();
+
+    let rows: Vec<(String, String)> = sqlx::query_as(
+        r#"
+        SELECT DISTINCT LOWER(to_address), LOWER(from_address)
+        FROM payment_transactions
+        WHERE LOWER(to_address) = ANY($1)
+          AND status = 'confirmed'
+          AND LOWER(from_address) <> LOWER(to_address)
+        "#,
+    )
+    .bind(&addresses)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| PaymentError::DatabaseError(e.to_string()))?;
+
+    let mut funders: HashMap<String, Vec<String>> = HashMap::new();
+    for (address, funder) in rows {
+        funders.entry(address).or_default().push(funder);
+    }
+    Ok(funders)
+}
@@ -0,0 +1,170 @@
+// Idempotency keys
+//
+// A mutating payment request may carry an `Idempotency-Key`. The key is
+// claimed in Postgres before the handler runs, in the same database the
+// payment itself is recorded in, so a caller retrying after a dropped
+// connection gets the first response back rather than a second deposit,
+// distribution or slash.
+//
+// Keys are scoped by caller (the signing key id of the service that sent the
+// request) and route, and bound to the request body: reusing one for a
+// different body is refused. A claim left in flight by a request that never
+// finished (crash, timeout) may be taken over by a retry of the same body
+// once its lock lapses.
+
+use serde_json::Value;
+use sqlx::PgPool;
+
+use crate::config::IdempotencyConfig;
+use crate::models::{PaymentError, PaymentResult};
+
+const IN_FLIGHT: &str = "in_flight";
+const COMPLETED: &str = "completed";
+
+/// Where a key applies
+#[derive(Debug, Clone)]
+pub struct KeyScope {
+    pub caller: String,
+    pub route: String,
+    pub key: String,
+}
+
+/// Outcome of claiming a key
+#[derive(Debug)]
+pub enum Claim {
+    /// The request is the first with this key and runs
+    Claimed,
+    /// The first request finished; its response is replayed
+    Replay { status: u16, body: Value },
+    /// The first request is still running
+    InFlight,
+    /// The key was used for a different request body
+    Mismatch,
+}
+
+fn db_error(e: sqlx::Error) -> PaymentError {
+    PaymentError::DatabaseError(e.to_string())
+}
+
+/// Claim `scope` for a request whose body hashes to `fingerprint`
+pub async fn claim(
+    pool: &PgPool,
+    config: &IdempotencyConfig,
+    scope: &KeyScope,
+    fingerprint: &str,
+) -> PaymentResult<Claim> {
+    // Inserts a new claim, or takes over an expired key or an abandoned
+    // claim of the same body; anything else leaves the row as it is
+    let claimed = sqlx::query_scalar::<_, String>(
+        r#"
+        INSERT INTO idempotency_keys
+            (caller, route, idempotency_key, fingerprint, status, locked_until, expires_at)
+        VALUES ($1, $2, $3, $4, $5, NOW() + make_interval(secs => $6), NOW() + make_interval(secs => $7))
+        ON CONFLICT (caller, route, idempotency_key) DO UPDATE SET
+            fingerprint = EXCLUDED.fingerprint,
+            status = EXCLUDED.status,
+            response_status = NULL,
+            response_body = NULL,
+            locked_until = EXCLUDED.locked_until,
+            expires_at = EXCLUDED.expires_at,
+            created_at = NOW(),
+            completed_at = NULL
+        WHERE idempotency_keys.expires_at < NOW()
+           OR (idempotency_keys.status = $5
+               AND idempotency_keys.locked_until < NOW()
+               AND idempotency_keys.fingerprint = EXCLUDED.fingerprint)
+        RETURNING idempotency_key
+        "#,
+    )
+    .bind(&scope.caller)
+    .bind(&scope.route)
+    .bind(&scope.key)
+    .bind(fingerprint)
+    .bind(IN_FLIGHT)
+    .bind(config.in_flight_seconds as f64)
+    .bind((config.retention_hours * 3600) as f64)
+    .fetch_optional(pool)
+    .await
+    .map_err(db_error)?;
+    if claimed.is_some() {
+        return Ok(Claim::Claimed);
+    }
+
+    let existing = sqlx::query_as::<_, (String, String, Option<i16>, Option<Value>)>(
+        r#"
+        SELECT fingerprint, status, response_status, response_body
+        FROM idempotency_keys
+        WHERE caller = $1 AND route = $2 AND idempotency_key = $3
+        "#,
+    )
+    .bind(&scope.caller)
+    .bind(&scope.route)
+    .bind(&scope.key)
+    .fetch_optional(pool)
+    .await
+    .map_err(db_error)?;
+
+    Ok(match existing {
+        Some((stored, _, _, _)) if stored != fingerprint => Claim::Mismatch,
+        Some((_, status, Some(response_status), Some(body))) if status == COMPLETED => Claim::Replay {
+            status: response_status as u16,
+            body,
+        },
+        // Still running, or purged since the insert; either way the caller
+        // retries
+        _ => Claim::InFlight,
+    })
+}
+
+/// Store the response of the request holding `scope`
+pub async fn complete(
+    pool: &PgPool,
+    config: &IdempotencyConfig,
+    scope: &KeyScope,
+    status: u16,
+    body: &Value,
+) -> PaymentResult<()> {
+    sqlx::query(
+        r#"
+        UPDATE idempotency_keys
+        SET status = $4, response_status = $5, response_body = $6, completed_at = NOW(),
+            expires_at = NOW() + make_interval(secs => $7)
+        WHERE caller = $1 AND route = $2 AND idempotency_key = $3
+        "#,
+    )
+    .bind(&scope.caller)
+    .bind(&scope.route)
+    .bind(&scope.key)
+    .bind(COMPLETED)
+    .bind(status as i16)
+    .bind(body)
+    .bind((config.retention_hours * 3600) as f64)
+    .execute(pool)
+    .await
+    .map_err(db_error)?;
+    Ok(())
+}
+
+/// Give up a claim so a retry runs the request again
+pub async fn release(pool: &PgPool, scope: &KeyScope) -> PaymentResult<()> {
+    sqlx::query(
+        "DELETE FROM idempotency_keys WHERE caller = $1 AND route = $2 AND idempotency_key = $3 AND status = $4",
+    )
+    .bind(&scope.caller)
+    .bind(&scope.route)
+    .bind(&scope.key)
+    .bind(IN_FLIGHT)
+    .execute(pool)
+    .await
+    .map_err(db_error)?;
+    Ok(())
+}
+
+/// Delete expired keys, returning how many
+pub async fn purge(pool: &PgPool) -> PaymentResult<u64> {
+    let purged = sqlx::query("DELETE FROM idempotency_keys WHERE expires_at < NOW()")
+        .execute(pool)
+        .await
+        .map_err(db_error)?;
+    Ok(purged.rows_affected())
+}
@@ -0,0 +1,323 @@
+// Indexer webhook ingestion
+//
+// Polling misses activity while the service is down. An external indexer
+// (Alchemy Notify, or any source signing with our generic scheme) pushes
+// activity instead and retries until we acknowledge it. Indexer payloads are
+// never trusted for settlement: every reported transaction we track is
+// re-read over RPC before its status changes.
+
+use anyhow::{Context, Result};
+use axum::http::HeaderMap;
+use hmac::{Hmac, Mac};
+use redis::aio::ConnectionManager;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use thiserror::Error;
+use tracing::{info, warn};
+
+use crate::config::IndexerConfig;
+use crate::services::payment_service::PaymentService;
+use crate::workers::transaction_monitor::reconcile_transaction;
+
+/// Alchemy Notify: hex HMAC-SHA256 of the raw body
+pub const ALCHEMY_SIGNATURE_HEADER: &str = "x-alchemy-signature";
+/// Generic: `t=<unix seconds>,v1=<hex HMAC-SHA256 of "<t>.<body>">`
+pub const GENERIC_SIGNATURE_HEADER: &str = "x-indexer-signature";
+
+#[derive(Debug, Error)]
+pub enum IndexerError {
+    #[error("Missing or invalid signature")]
+    InvalidSignature,
+    #[error("Delivery timestamp outside the allowed window")]
+    StaleDelivery,
+    #[error("Malformed payload: {0}")]
+    Malformed(String),
+}
+
+/// Transaction reported by the indexer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexerEvent {
+    pub tx_hash: String,
+    pub block_number: Option<u64>,
+}
+
+/// One webhook delivery, normalized across providers
+#[derive(Debug, Clone)]
+pub struct IndexerDelivery {
+    pub delivery_id: String,
+    pub events: Vec<IndexerEvent>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IngestSummary {
+    pub delivery_id: String,
+    pub duplicate: bool,
+    pub events: usize,
+    pub reconciled: usize,
+}
+
+// ─── Signature verification ───
+
+fn hmac_hex(secret: &str, parts: &[&[u8]]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    for part in parts {
+        mac.update(part);
+    }
+    hex::encode(mac.finalize().into_bytes()).into_bytes()
+}
+
+/// Constant-time comparison of hex digests
+fn digest_matches(expected: &[u8], provided: &str) -> bool {
+    let provided = provided.trim().to_ascii_lowercase().into_bytes();
+    expected.len() == provided.len()
+        && expected
+            .iter()
+            .zip(provided.iter())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+pub fn verify_signature(
+    config: &IndexerConfig,
+    headers: &HeaderMap,
+    body: &[u8],
+) -> Result<(), IndexerError> {
+    let secret = config
+        .signing_secret
+        .as_deref()
+        .ok_or(IndexerError::InvalidSignature)?;
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+
+    match config.provider.as_str() {
+        "alchemy" => {
+            let provided = header(ALCHEMY_SIGNATURE_HEADER).ok_or(IndexerError::InvalidSignature)?;
+            if !digest_matches(&hmac_hex(secret, &[body]), provided) {
+                return Err(IndexerError::InvalidSignature);
+            }
+        }
+        _ => {
+            let provided = header(GENERIC_SIGNATURE_HEADER).ok_or(IndexerError::InvalidSignature)?;
+            let (timestamp, digest) = provided
+                .split_once(',')
+                .and_then(|(t, v)| Some((t.strip_prefix("t=")?, v.strip_prefix("v1=")?)))
+                .ok_or(IndexerError::InvalidSignature)?;
+            let expected = hmac_hex(secret, &[timestamp.as_bytes(), b".", body]);
+            if !digest_matches(&expected, digest) {
+                return Err(IndexerError::InvalidSignature);
+            }
+            // Checked after the MAC so the timestamp itself is authenticated
+            let sent_at: i64 = timestamp.parse().map_err(|_| IndexerError::InvalidSignature)?;
+            if (chrono::Utc::now().timestamp() - sent_at).abs() > config.max_clock_skew_seconds {
+                return Err(IndexerError::StaleDelivery);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// ─── Payload parsing ───
+
+#[derive(Deserialize)]
+struct AlchemyPayload {
+    id: String,
+    event: AlchemyEvent,
+}
+
+#[derive(Deserialize)]
+struct AlchemyEvent {
+    #[serde(default)]
+    activity: Vec<AlchemyActivity>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AlchemyActivity {
+    hash: String,
+    block_num: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GenericPayload {
+    id: String,
+    #[serde(default)]
+    events: Vec<GenericEvent>,
+}
+
+#[derive(Deserialize)]
+struct GenericEvent {
+    tx_hash: String,
+    block_number: Option<u64>,
+}
+
+fn is_tx_hash(hash: &str) -> bool {
+    hash.len() == 66 && hash.starts_with("0x") && hash[2..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+pub fn parse_delivery(provider: &str, body: &[u8]) -> Result<IndexerDelivery, IndexerError> {
+    let (delivery_id, events) = match provider {
+        "alchemy" => {
+            let payload: AlchemyPayload =
+                serde_json::from_slice(body).map_err(|e| IndexerError::Malformed(e.to_string()))?;
+            let events = payload
+                .event
+                .activity
+                .into_iter()
+                .map(|a| IndexerEvent {
+                    tx_hash: a.hash.to_ascii_lowercase(),
+                    block_number: a
+                        .block_num
+                        .and_then(|b| u64::from_str_radix(b.trim_start_matches("0x"), 16).ok()),
+                })
+                .collect::<Vec<_>>();
+            (payload.id, events)
+        }
+        _ => {
+            let payload: GenericPayload =
+                serde_json::from_slice(body).map_err(|e| IndexerError::Malformed(e.to_string()))?;
+            let events = payload
+                .events
+                .into_iter()
+                .map(|e| IndexerEvent {
+                    tx_hash: e.tx_hash.to_ascii_lowercase(),
+                    block_number: e.block_number,
+                })
+                .collect();
+            (payload.id, events)
+        }
+    };
+
+    if delivery_id.is_empty() {
+        return Err(IndexerError::Malformed("missing delivery id".to_string()));
+    }
+    if let Some(bad) = events.iter().find(|e| !is_tx_hash(&e.tx_hash)) {
+        return Err(IndexerError::Malformed(format!("invalid tx hash {}", bad.tx_hash)));
+    }
+
+    Ok(IndexerDelivery { delivery_id, events })
+}
+
+// ─── Ingestion ───
+
+#[derive(sqlx::FromRow)]
+struct TrackedTx {
+    id: uuid::Uuid,
+    status: Option<String>,
+}
+
+/// Record a verified delivery and reconcile every tracked transaction in it
+/// against the chain. Deliveries already seen within the replay window are
+/// acknowledged without being processed again.
+pub async fn ingest(
+    service: &PaymentService,
+    redis: &mut ConnectionManager,
+    delivery: IndexerDelivery,
+) -> Result<IngestSummary> {
+    let config = &service.config().indexer;
+    let replay_key = format!("payment:indexer:delivery:{}", delivery.delivery_id);
+
+    let first_seen: bool = redis::cmd("SET")
+        .arg(&replay_key)
+        .arg(1)
+        .arg("NX")
+        .arg("EX")
+        .arg(config.replay_window_seconds)
+        .query_async::<_, Option<String>>(redis)
+        .await
+        .context("Failed to record indexer delivery")?
+        .is_some();
+
+    if !first_seen {
+        info!("Ignoring replayed indexer delivery {}", delivery.delivery_id);
+        return Ok(IngestSummary {
+            delivery_id: delivery.delivery_id,
+            duplicate: true,
+            events: delivery.events.len(),
+            reconciled: 0,
+        });
+    }
+
+    match process_events(service, config, &delivery).await {
+        Ok(reconciled) => Ok(IngestSummary {
+            delivery_id: delivery.delivery_id,
+            duplicate: false,
+            events: delivery.events.len(),
+            reconciled,
+        }),
+        Err(e) => {
+            // Forget the delivery so the indexer's retry is processed
+            let _: () = redis::cmd("DEL")
+                .arg(&replay_key)
+                .query_async(redis)
+                .await
+                .unwrap_or(());
+            Err(e)
+        }
+    }
+}
+
+async fn process_events(
+    service: &PaymentService,
+    config: &IndexerConfig,
+    delivery: &IndexerDelivery,
+) -> Result<usize> {
+    let mut reconciled = 0;
+
+    for event in &delivery.events {
+        let tracked = sqlx::query_as::<_, TrackedTx>(
+            "SELECT id, status FROM payment_transactions WHERE LOWER(transaction_hash) = $1",
+        )
+        .bind(&event.tx_hash)
+        .fetch_optional(service.db_pool())
+        .await
+        .context("Failed to look up reported transaction")?;
+
+        let outcome = match tracked {
+            None => "untracked".to_string(),
+            Some(tx) if tx.status.as_deref() != Some("pending") => {
+                tx.status.unwrap_or_else(|| "unknown".to_string())
+            }
+            Some(tx) => match reconcile_transaction(service, tx.id, &event.tx_hash).await? {
+                None => "unmined".to_string(),
+                Some((status, chain_block)) => {
+                    reconciled += 1;
+                    if event.block_number.is_some() && event.block_number != chain_block {
+                        // Reorg or a misbehaving indexer; the RPC read wins
+                        warn!(
+                            "Indexer reported {} in block {:?}, chain says {:?}",
+                            event.tx_hash, event.block_number, chain_block
+                        );
+                        "block_mismatch".to_string()
+                    } else {
+                        status.to_string()
+                    }
+                }
+            },
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO indexer_events (delivery_id, provider, tx_hash, reported_block, outcome)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (delivery_id, tx_hash) DO NOTHING
+            "#,
+        )
+        .bind(&delivery.delivery_id)
+        .bind(&config.provider)
+        .bind(&event.tx_hash)
+        .bind(event.block_number.map(|n| n as i64))
+        .bind(&outcome)
+        .execute(service.db_pool())
+        .await
+        .context("Failed to record indexer event")?;
+    }
+
+    info!(
+        "Indexer delivery {}: {} events, {} reconciled",
+        delivery.delivery_id,
+        delivery.events.len(),
+        reconciled
+    );
+    Ok(reconciled)
+}
@@ -1 +1,18 @@
 pub mod payment_service;
+pub mod escrow;
+pub mod stake;
+pub mod funding;
+pub mod indexer;
+pub mod reconciliation;
+pub mod settlement;
+pub mod tokens;
+pub mod nonces;
+pub mod payouts;
+pub mod idempotency;
+pub mod withdrawals;
+pub mod fees;
+pub mod payment_events;
+pub mod dead_letters;
+pub mod slashing;
+pub mod prices;
+pub mod relay;
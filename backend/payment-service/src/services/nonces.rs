@@ -0,0 +1,555 @@
+// Outgoing transactions and their nonces
+//
+// The service signs transactions from its own wallets (the treasury) instead
+// of leaving it to the node, so it assigns their nonces. Sends from one
+// wallet are taken one at a time, under an advisory lock held while the nonce
+// is chosen, the transaction signed and broadcast. The next nonce is one past
+// the last the service used, or the chain's pending count when that is higher
+// (the wallet also sent from elsewhere). A send that fails to broadcast is
+// rolled back and its nonce used again.
+//
+// A transaction sent just before gas prices rise can sit unmined and hold up
+// every later nonce of its wallet. The replacement worker re-signs one whose
+// latest attempt has gone `stuck_after_seconds` unmined at a higher gas price
+// (replace-by-fee), and settles each nonce once any of its attempts is mined.
+// Every attempt is kept, so the history can be looked up by any of their
+// hashes.
+
+use anyhow::Context;
+use chrono::{DateTime, Duration, Utc};
+use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::utils::keccak256;
+use serde::Serialize;
+use sqlx::{PgPool, Postgres, Transaction};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::blockchain::TransactionBuilder;
+use crate::config::TransactionConfig;
+use crate::models::{PaymentError, PaymentResult};
+use crate::services::dead_letters::{self, FailureReason};
+use crate::services::payment_service::PaymentService;
+
+pub const CONFIRMED: &str = "confirmed";
+pub const FAILED: &str = "failed";
+pub const DROPPED: &str = "dropped";
+
+const INITIAL: &str = "initial";
+const FEE_BUMP: &str = "fee_bump";
+
+const OUTGOING_COLUMNS: &str = "id, payment_id, from_address, to_address, value::TEXT AS value, data, \
+                                gas_limit::TEXT AS gas_limit, nonce, gas_price::TEXT AS gas_price, tx_hash, \
+                                status, replacements, mined_tx_hash, submitted_at, settled_at, created_at";
+
+/// One nonce sent from one of the service's wallets
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct OutgoingTransaction {
+    pub id: Uuid,
+    pub payment_id: Option<Uuid>,
+    pub from_address: String,
+    pub to_address: String,
+    /// In wei
+    pub value: String,
+    pub data: String,
+    pub gas_limit: String,
+    pub nonce: i64,
+    /// Of the latest attempt, in wei
+    pub gas_price: String,
+    /// Latest attempt
+    pub tx_hash: String,
+    pub status: String,
+    pub replacements: i32,
+    /// Attempt that was mined
+    pub mined_tx_hash: Option<String>,
+    /// When the latest attempt was sent
+    pub submitted_at: DateTime<Utc>,
+    pub settled_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// One signed attempt at a nonce
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct TransactionAttempt {
+    pub tx_hash: String,
+    /// In wei
+    pub gas_price: String,
+    /// `initial` or `fee_bump`
+    pub reason: String,
+    pub submitted_at: DateTime<Utc>,
+}
+
+/// A nonce with every attempt at it, oldest first
+#[derive(Debug, Serialize)]
+pub struct TransactionHistory {
+    #[serde(flatten)]
+    pub transaction: OutgoingTransaction,
+    pub attempts: Vec<TransactionAttempt>,
+}
+
+fn db_error(e: sqlx::Error) -> PaymentError {
+    PaymentError::DatabaseError(e.to_string())
+}
+
+fn chain_error(e: impl std::fmt::Display) -> PaymentError {
+    PaymentError::BlockchainError(e.to_string())
+}
+
+fn parse_u256(value: &str) -> PaymentResult<U256> {
+    U256::from_dec_str(value).map_err(|_| PaymentError::ValidationError(format!("{} is not a whole number", value)))
+}
+
+/// The outgoing transaction one of whose attempts is `tx_hash`, with all of
+/// its attempts
+pub async fn history(pool: &PgPool, tx_hash: &str) -> PaymentResult<Option<TransactionHistory>> {
+    let transaction: Option<OutgoingTransaction> = sqlx::query_as(&format!(
+        r#"
+        SELECT {} FROM outgoing_transactions
+        WHERE id = (SELECT outgoing_id FROM outgoing_transaction_attempts WHERE LOWER(tx_hash) = LOWER($1))
+        "#,
+        OUTGOING_COLUMNS
+    ))
+    .bind(tx_hash)
+    .fetch_optional(pool)
+    .await
+    .map_err(db_error)?;
+    let Some(transaction) = transaction else {
+        return Ok(None);
+    };
+
+    let attempts = sqlx::query_as(
+        r#"
+        SELECT tx_hash, gas_price::TEXT AS gas_price, reason, submitted_at
+        FROM outgoing_transaction_attempts
+        WHERE outgoing_id = $1
+        ORDER BY submitted_at, tx_hash
+        "#,
+    )
+    .bind(transaction.id)
+    .fetch_all(pool)
+    .await
+    .map_err(db_error)?;
+    Ok(Some(TransactionHistory { transaction, attempts }))
+}
+
+pub struct NonceManager {
+    service: Arc<PaymentService>,
+    config: TransactionConfig,
+    wallets: HashMap<Address, LocalWallet>,
+}
+
+impl NonceManager {
+    /// Manages the treasury wallet when its key is configured and matches
+    /// `TREASURY_ADDRESS`; otherwise no wallet, and nothing can be sent
+    pub fn new(service: Arc<PaymentService>) -> Self {
+        let config = service.config().transactions.clone();
+        let blockchain = &service.config().blockchain;
+        let mut wallets = HashMap::new();
+        match (
+            blockchain.treasury_private_key.parse::<LocalWallet>(),
+            blockchain.treasury_address.parse::<Address>(),
+        ) {
+            (Ok(wallet), Ok(treasury)) if wallet.address() == treasury => {
+                wallets.insert(treasury, wallet.with_chain_id(blockchain.chain_id));
+            }
+            (Ok(_), Ok(_)) => warn!("TREASURY_PRIVATE_KEY does not belong to TREASURY_ADDRESS; treasury sends disabled"),
+            _ => warn!("Treasury key or address is invalid; treasury sends disabled"),
+        }
+
+        Self { service, config, wallets }
+    }
+
+    pub fn interval_seconds(&self) -> u64 {
+        self.config.check_interval_seconds
+    }
+
+    /// Whether the service holds the key of `address`
+    pub fn manages(&self, address: &str) -> bool {
+        address.parse::<Address>().is_ok_and(|address| self.wallets.contains_key(&address))
+    }
+
+    fn wallet(&self, address: &Address) -> PaymentResult<&LocalWallet> {
+        self.wallets
+            .get(address)
+            .ok_or_else(|| PaymentError::ConfigError(format!("No key for wallet {:?}", address)))
+    }
+
+    async fn find(&self, id: Uuid) -> PaymentResult<OutgoingTransaction> {
+        sqlx::query_as(&format!("SELECT {} FROM outgoing_transactions WHERE id = $1", OUTGOING_COLUMNS))
+            .bind(id)
+            .fetch_optional(self.service.db_pool())
+            .await
+            .map_err(db_error)?
+            .ok_or_else(|| PaymentError::NotFound(format!("Outgoing transaction {}", id)))
+    }
+
+    /// Sign and send `tx` from one of the service's wallets at the wallet's
+    /// next nonce, or the builder's when it sets one that is still free,
+    /// recording it against `payment_id`. Gas price and limit are filled in
+    /// when the builder leaves them unset.
+    pub async fn submit(&self, payment_id: Option<Uuid>, tx: TransactionBuilder) -> PaymentResult<OutgoingTransaction> {
+        let wallet = self.wallet(&tx.from)?;
+        let from = format!("{:?}", tx.from);
+        let provider = self.service.provider();
+        let gas_price = match tx.gas_price {
+            Some(gas_price) => gas_price,
+            None => self.service.gas_price().await.map_err(chain_error)?,
+        };
+        let gas_limit = match tx.gas_limit {
+            Some(gas_limit) => gas_limit,
+            None => {
+                let request: TypedTransaction = tx.clone().build().into();
+                provider.estimate_gas(&request, None).await.map_err(chain_error)?
+            }
+        };
+
+        let mut db = self.service.db_pool().begin().await.map_err(db_error)?;
+        lock_wallet(&mut db, &from).await?;
+        let used: Option<i64> =
+            sqlx::query_scalar("SELECT MAX(nonce) FROM outgoing_transactions WHERE LOWER(from_address) = LOWER($1)")
+                .bind(&from)
+                .fetch_one(&mut *db)
+                .await
+                .map_err(db_error)?;
+        let chain_next = provider
+            .get_transaction_count(tx.from, Some(BlockNumber::Pending.into()))
+            .await
+            .map_err(chain_error)?;
+        let nonce = match tx.nonce {
+            // Set by an admin, to fill a gap in the wallet's nonces
+            Some(nonce) => {
+                if nonce < chain_next {
+                    return Err(PaymentError::ValidationError(format!(
+                        "Nonce {} of {} was already used on-chain (next is {})",
+                        nonce, from, chain_next
+                    )));
+                }
+                let taken: bool = sqlx::query_scalar(
+                    "SELECT EXISTS (SELECT 1 FROM outgoing_transactions WHERE LOWER(from_address) = LOWER($1) AND nonce = $2)",
+                )
+                .bind(&from)
+                .bind(nonce.as_u64() as i64)
+                .fetch_one(&mut *db)
+                .await
+                .map_err(db_error)?;
+                if taken {
+                    return Err(PaymentError::ValidationError(format!(
+                        "Nonce {} of {} was already sent by the service",
+                        nonce, from
+                    )));
+                }
+                nonce
+            }
+            None => chain_next.max(used.map_or(U256::zero(), |used| U256::from(used + 1))),
+        };
+
+        let to = format!("{:?}", tx.to);
+        let value = tx.value.to_string();
+        let data = format!("0x{}", hex::encode(tx.data.clone().unwrap_or_default()));
+        let request = tx.nonce(nonce).gas_limit(gas_limit).gas_price(gas_price).build();
+        let (tx_hash, raw) = sign(wallet, request).await?;
+        let id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO outgoing_transactions
+                (payment_id, from_address, to_address, value, data, gas_limit, nonce, gas_price, tx_hash)
+            VALUES ($1, $2, $3, $4::NUMERIC, $5, $6::NUMERIC, $7, $8::NUMERIC, $9)
+            RETURNING id
+            "#,
+        )
+        .bind(payment_id)
+        .bind(&from)
+        .bind(&to)
+        .bind(&value)
+        .bind(&data)
+        .bind(gas_limit.to_string())
+        .bind(nonce.as_u64() as i64)
+        .bind(gas_price.to_string())
+        .bind(&tx_hash)
+        .fetch_one(&mut *db)
+        .await
+        .map_err(db_error)?;
+        record_attempt(&mut db, id, &tx_hash, gas_price, INITIAL).await?;
+
+        self.broadcast(raw).await?;
+        db.commit().await.map_err(db_error)?;
+        info!("Sent {} from {} at nonce {} ({} wei gas price)", tx_hash, from, nonce, gas_price);
+        self.find(id).await
+    }
+
+    async fn broadcast(&self, raw: Bytes) -> PaymentResult<()> {
+        match self.service.provider().send_raw_transaction(raw).await {
+            Ok(_) => Ok(()),
+            // A retried broadcast of a transaction the node already has
+            Err(e) if e.to_string().contains("already known") => Ok(()),
+            Err(e) => Err(chain_error(e)),
+        }
+    }
+
+    /// Settle pending transactions with a mined attempt and replace stuck
+    /// ones. Returns how many were settled and how many replaced.
+    pub async fn check(&self) -> PaymentResult<(usize, usize)> {
+        let pending: Vec<OutgoingTransaction> = sqlx::query_as(&format!(
+            "SELECT {} FROM outgoing_transactions WHERE status = 'pending' ORDER BY LOWER(from_address), nonce LIMIT $1",
+            OUTGOING_COLUMNS
+        ))
+        .bind(self.config.batch_size)
+        .fetch_all(self.service.db_pool())
+        .await
+        .map_err(db_error)?;
+
+        let stuck_before = Utc::now() - Duration::seconds(self.config.stuck_after_seconds);
+        let (mut settled, mut replaced) = (0, 0);
+        for tx in pending {
+            if self.settle(&tx).await? {
+                settled += 1;
+            } else if tx.submitted_at <= stuck_before && self.replace(&tx).await? {
+                replaced += 1;
+            }
+        }
+        Ok((settled, replaced))
+    }
+
+    /// Settle `tx` if one of its attempts was mined, or its nonce was used by
+    /// a transaction the service did not send. Returns whether it settled.
+    async fn settle(&self, tx: &OutgoingTransaction) -> PaymentResult<bool> {
+        let from: Address = tx.from_address.parse().map_err(chain_error)?;
+        // Read first, so an attempt mined after it still shows its receipt below
+        let mined_nonces = self
+            .service
+            .provider()
+            .get_transaction_count(from, Some(BlockNumber::Latest.into()))
+            .await
+            .map_err(chain_error)?;
+
+        let attempts: Vec<String> =
+            sqlx::query_scalar("SELECT tx_hash FROM outgoing_transaction_attempts WHERE outgoing_id = $1")
+                .bind(tx.id)
+                .fetch_all(self.service.db_pool())
+                .await
+                .map_err(db_error)?;
+        for hash in attempts {
+            let Some(receipt) = self.service.get_tx_receipt(&hash).await.map_err(chain_error)? else {
+                continue;
+            };
+            let status = if receipt.status == Some(1.into()) { CONFIRMED } else { FAILED };
+            self.finish(tx, status, Some(&hash)).await?;
+            info!("Transaction at nonce {} of {} {} as {}", tx.nonce, tx.from_address, status, hash);
+            return Ok(true);
+        }
+
+        if mined_nonces > U256::from(tx.nonce) {
+            self.finish(tx, DROPPED, None).await?;
+            warn!(
+                "Nonce {} of {} was used by a transaction the service did not send; {} dropped",
+                tx.nonce, tx.from_address, tx.tx_hash
+            );
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// Close `tx` and carry the outcome to its payment: a mined attempt
+    /// settles it, a dropped nonce counts a failed attempt and queues it to
+    /// be sent again (or dead-letters it)
+    async fn finish(&self, tx: &OutgoingTransaction, status: &str, mined_tx_hash: Option<&str>) -> PaymentResult<()> {
+        let mut db = self.service.db_pool().begin().await.map_err(db_error)?;
+        let updated = sqlx::query(
+            r#"
+            UPDATE outgoing_transactions SET status = $1, mined_tx_hash = $2, settled_at = NOW()
+            WHERE id = $3 AND status = 'pending'
+            "#,
+        )
+        .bind(status)
+        .bind(mined_tx_hash)
+        .bind(tx.id)
+        .execute(&mut *db)
+        .await
+        .map_err(db_error)?;
+        if updated.rows_affected() == 0 {
+            return Ok(());
+        }
+
+        if let Some(payment_id) = tx.payment_id {
+            match mined_tx_hash {
+                // The transaction monitor settles the ledger entry from here
+                Some(mined_tx_hash) => {
+                    sqlx::query("UPDATE payment_transactions SET transaction_hash = $1 WHERE transaction_hash = $2")
+                        .bind(mined_tx_hash)
+                        .bind(&tx.tx_hash)
+                        .execute(&mut *db)
+                        .await
+                        .map_err(db_error)?;
+                    sqlx::query(
+                        r#"
+                        UPDATE payments
+                        SET status = $1, transaction_hash = $2, updated_at = NOW(),
+                            completed_at = CASE WHEN $1 = 'completed' THEN NOW() END,
+                            failure_reason = CASE WHEN $1 = 'failed' THEN 'reverted' ELSE failure_reason END,
+                            last_error = CASE WHEN $1 = 'failed' THEN 'transaction reverted on-chain' ELSE last_error END
+                        WHERE id = $3
+                        "#,
+                    )
+                    .bind(if status == CONFIRMED { "completed" } else { "failed" })
+                    .bind(mined_tx_hash)
+                    .bind(payment_id)
+                    .execute(&mut *db)
+                    .await
+                    .map_err(db_error)?;
+                }
+                None => {
+                    sqlx::query(
+                        r#"
+                        UPDATE payment_transactions SET status = 'failed', error_message = 'nonce used by another transaction'
+                        WHERE transaction_hash = $1 AND status = 'pending'
+                        "#,
+                    )
+                    .bind(&tx.tx_hash)
+                    .execute(&mut *db)
+                    .await
+                    .map_err(db_error)?;
+                    let max_attempts = self.service.config().payment.max_retry_attempts;
+                    let error = format!("nonce {} used by another transaction", tx.nonce);
+                    if dead_letters::record_failure(&mut db, payment_id, FailureReason::NonceGap, &error, max_attempts).await? {
+                        warn!("Payment {} dead-lettered after its nonce was taken {} times", payment_id, max_attempts);
+                    }
+                }
+            }
+        }
+        db.commit().await.map_err(db_error)
+    }
+
+    /// Re-sign a stuck transaction at a higher gas price. Returns whether a
+    /// replacement was sent; one is not when the limit of replacements or
+    /// the gas price cap is reached, which only postpones the next try.
+    async fn replace(&self, tx: &OutgoingTransaction) -> PaymentResult<bool> {
+        let old_price = parse_u256(&tx.gas_price)?;
+        let bumped = (old_price * U256::from(100 + self.config.fee_bump_percentage) + U256::from(99)) / U256::from(100);
+        let cap = U256::from(self.service.config().blockchain.max_gas_price_gwei) * U256::exp10(9);
+        let network = self.service.gas_price().await.map_err(chain_error)?;
+        let gas_price = bumped.max(network).min(cap);
+
+        if tx.replacements >= self.config.max_replacements || gas_price < bumped {
+            warn!(
+                "Transaction {} at nonce {} of {} is stuck but cannot be replaced ({} replacements, {} wei gas price)",
+                tx.tx_hash, tx.nonce, tx.from_address, tx.replacements, old_price
+            );
+            sqlx::query("UPDATE outgoing_transactions SET submitted_at = NOW() WHERE id = $1")
+                .bind(tx.id)
+                .execute(self.service.db_pool())
+                .await
+                .map_err(db_error)?;
+            return Ok(false);
+        }
+
+        let from: Address = tx.from_address.parse().map_err(chain_error)?;
+        let to: Address = tx.to_address.parse().map_err(chain_error)?;
+        let wallet = self.wallet(&from)?;
+        let data: Bytes = hex::decode(tx.data.trim_start_matches("0x"))
+            .map_err(|e| PaymentError::ValidationError(e.to_string()))?
+            .into();
+        let request = TransactionBuilder::new(from, to)
+            .value(parse_u256(&tx.value)?)
+            .data(data)
+            .gas_limit(parse_u256(&tx.gas_limit)?)
+            .gas_price(gas_price)
+            .nonce(U256::from(tx.nonce))
+            .build();
+
+        let mut db = self.service.db_pool().begin().await.map_err(db_error)?;
+        lock_wallet(&mut db, &tx.from_address).await?;
+        // Settled or replaced by another instance in the meantime
+        let current: Option<String> = sqlx::query_scalar(
+            "SELECT tx_hash FROM outgoing_transactions WHERE id = $1 AND status = 'pending' FOR UPDATE",
+        )
+        .bind(tx.id)
+        .fetch_optional(&mut *db)
+        .await
+        .map_err(db_error)?;
+        if current.as_deref() != Some(tx.tx_hash.as_str()) {
+            return Ok(false);
+        }
+
+        let (tx_hash, raw) = sign(wallet, request).await?;
+        record_attempt(&mut db, tx.id, &tx_hash, gas_price, FEE_BUMP).await?;
+        sqlx::query(
+            r#"
+            UPDATE outgoing_transactions
+            SET tx_hash = $1, gas_price = $2::NUMERIC, replacements = replacements + 1, submitted_at = NOW()
+            WHERE id = $3
+            "#,
+        )
+        .bind(&tx_hash)
+        .bind(gas_price.to_string())
+        .bind(tx.id)
+        .execute(&mut *db)
+        .await
+        .map_err(db_error)?;
+        // Keep the ledger entry on the attempt the transaction monitor should watch
+        sqlx::query("UPDATE payment_transactions SET transaction_hash = $1 WHERE transaction_hash = $2")
+            .bind(&tx_hash)
+            .bind(&tx.tx_hash)
+            .execute(&mut *db)
+            .await
+            .map_err(db_error)?;
+
+        if let Err(e) = self.broadcast(raw).await {
+            // e.g. "nonce too low" once an earlier attempt is mined; settled on the next run
+            warn!("Replacement of {} was not accepted: {}", tx.tx_hash, e);
+            return Ok(false);
+        }
+        db.commit().await.map_err(db_error)?;
+        info!(
+            "Replaced stuck {} at nonce {} of {} with {} ({} -> {} wei gas price)",
+            tx.tx_hash, tx.nonce, tx.from_address, tx_hash, old_price, gas_price
+        );
+        Ok(true)
+    }
+}
+
+/// Serialize sends from one wallet for the rest of `db`
+async fn lock_wallet(db: &mut Transaction<'_, Postgres>, address: &str) -> PaymentResult<()> {
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext('outgoing:' || LOWER($1)))")
+        .bind(address)
+        .execute(&mut **db)
+        .await
+        .map_err(db_error)?;
+    Ok(())
+}
+
+async fn record_attempt(
+    db: &mut Transaction<'_, Postgres>,
+    outgoing_id: Uuid,
+    tx_hash: &str,
+    gas_price: U256,
+    reason: &str,
+) -> PaymentResult<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO outgoing_transaction_attempts (tx_hash, outgoing_id, gas_price, reason)
+        VALUES ($1, $2, $3::NUMERIC, $4)
+        "#,
+    )
+    .bind(tx_hash)
+    .bind(outgoing_id)
+    .bind(gas_price.to_string())
+    .bind(reason)
+    .execute(&mut **db)
+    .await
+    .map_err(db_error)?;
+    Ok(())
+}
+
+/// Sign `request` with `wallet`, returning its hash and raw encoding
+async fn sign(wallet: &LocalWallet, request: TransactionRequest) -> PaymentResult<(String, Bytes)> {
+    let request: TypedTransaction = request.chain_id(wallet.chain_id()).into();
+    let signature = wallet
+        .sign_transaction(&request)
+        .await
+        .context("Failed to sign transaction")
+        .map_err(chain_error)?;
+    let raw = request.rlp_signed(&signature);
+    let tx_hash = format!("{:?}", H256::from(keccak256(&raw)));
+    Ok((tx_hash, raw))
+}
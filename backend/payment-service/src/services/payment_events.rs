@@ -0,0 +1,564 @@
+// Payment events and webhooks
+//
+// Steps other services used to poll transaction status for are recorded in
+// `payment_events`, in the transaction that made them happen: an escrow
+// funded by its deposit, a reward payout broadcast and mined, a stake
+// slashed, a withdrawal mined. The payment event worker publishes each on
+// the message queue as `NexusEvent::PaymentUpdated` and queues it for the
+// webhooks subscribed to it. Publication is at least once; consumers tell
+// repeats apart by `event_id`.
+//
+// Users register HTTPS endpoints for their own events. Escrow deposits name
+// only the creator's wallet, so they reach the webhooks registered for that
+// wallet. Deliveries are retried with exponential backoff until
+// `max_attempts` and signed with the webhook's secret as bounty and
+// reputation webhooks are (`shared::webhooks`).
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use shared::messaging::{NexusEvent, PaymentEventKind, PaymentUpdatedEvent};
+use shared::webhooks::{self, sign};
+use sqlx::{PgConnection, PgPool};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::config::WebhookConfig;
+use crate::models::{PaymentError, PaymentResult};
+use crate::services::payment_service::PaymentService;
+
+/// Deliveries per page when no limit is asked for
+const DEFAULT_DELIVERY_LIMIT: i64 = 20;
+
+/// Most deliveries per page
+const MAX_DELIVERY_LIMIT: i64 = 100;
+
+const EVENT_COLUMNS: &str = "id, kind, bounty_id, user_id, address, amount::TEXT AS amount, token_address, \
+                             tx_hash, occurred_at";
+
+/// A payment event to record; one per kind and source
+#[derive(Debug, Clone)]
+pub struct NewPaymentEvent {
+    pub kind: PaymentEventKind,
+    /// The escrow, payout item, stake or withdrawal the event is about
+    pub source_id: Uuid,
+    pub bounty_id: Option<Uuid>,
+    pub user_id: Option<Uuid>,
+    pub address: Option<String>,
+    /// In base units of the token
+    pub amount: Option<String>,
+    pub token_address: Option<String>,
+    pub tx_hash: Option<String>,
+}
+
+#[derive(sqlx::FromRow)]
+struct PaymentEventRow {
+    id: Uuid,
+    kind: String,
+    bounty_id: Option<Uuid>,
+    user_id: Option<Uuid>,
+    address: Option<String>,
+    amount: Option<String>,
+    token_address: Option<String>,
+    tx_hash: Option<String>,
+    occurred_at: DateTime<Utc>,
+}
+
+impl PaymentEventRow {
+    fn event(self) -> Option<PaymentUpdatedEvent> {
+        Some(PaymentUpdatedEvent {
+            event_id: self.id,
+            kind: PaymentEventKind::parse(&self.kind)?,
+            bounty_id: self.bounty_id,
+            user_id: self.user_id,
+            address: self.address,
+            amount: self.amount.map(|amount| amount.split('.').next().unwrap_or_default().to_string()),
+            token_address: self.token_address,
+            tx_hash: self.tx_hash,
+            occurred_at: self.occurred_at,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct PaymentWebhook {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub wallet_address: Option<String>,
+    pub url: String,
+    /// Only shown once, when the webhook is registered
+    #[serde(skip_serializing)]
+    pub secret: String,
+    pub events: Vec<String>,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A newly registered webhook with the secret its deliveries are signed
+/// with; the secret is not shown again
+#[derive(Debug, Serialize)]
+pub struct RegisteredWebhook {
+    #[serde(flatten)]
+    pub webhook: PaymentWebhook,
+    pub secret: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RegisterWebhookRequest {
+    /// The user's wallet, to also receive escrow events of bounties it funds
+    #[serde(default)]
+    pub wallet_address: Option<String>,
+    /// HTTPS endpoint deliveries are POSTed to
+    pub url: String,
+    pub events: Vec<PaymentEventKind>,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub event_id: Uuid,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    /// `pending`, `delivered` or `failed` once retries are exhausted
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, thiserror::Error)]
+enum SendError {
+    /// The endpoint answered with a non-2xx status
+    #[error("endpoint responded {0}")]
+    Rejected(u16),
+
+    #[error("endpoint unreachable: {0}")]
+    Unreachable(String),
+}
+
+impl SendError {
+    fn response_status(&self) -> Option<i32> {
+        match self {
+            SendError::Rejected(status) => Some(i32::from(*status)),
+            SendError::Unreachable(_) => None,
+        }
+    }
+}
+
+fn db_error(e: sqlx::Error) -> PaymentError {
+    PaymentError::DatabaseError(e.to_string())
+}
+
+/// Record an event in the caller's transaction. Recording the same step
+/// again does nothing.
+pub async fn record(db: &mut PgConnection, event: &NewPaymentEvent) -> PaymentResult<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO payment_events
+            (kind, source_id, bounty_id, user_id, address, amount, token_address, tx_hash)
+        VALUES ($1, $2, $3, $4, $5, $6::NUMERIC, $7, $8)
+        ON CONFLICT (kind, source_id) DO NOTHING
+        "#,
+    )
+    .bind(event.kind.as_str())
+    .bind(event.source_id)
+    .bind(event.bounty_id)
+    .bind(event.user_id)
+    .bind(&event.address)
+    .bind(&event.amount)
+    .bind(&event.token_address)
+    .bind(&event.tx_hash)
+    .execute(db)
+    .await
+    .map_err(db_error)?;
+    Ok(())
+}
+
+pub struct PaymentEventService {
+    service: Arc<PaymentService>,
+    config: WebhookConfig,
+    redis: redis::Client,
+    http: reqwest::Client,
+}
+
+impl PaymentEventService {
+    pub fn new(service: Arc<PaymentService>) -> anyhow::Result<Self> {
+        let config = service.config().webhooks.clone();
+        let redis = redis::Client::open(service.config().redis.url.clone())?;
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_seconds))
+            // A redirect could carry the signed payload somewhere the user
+            // did not register
+            .redirect(reqwest::redirect::Policy::none())
+            .build()?;
+        Ok(Self {
+            service,
+            config,
+            redis,
+            http,
+        })
+    }
+
+    pub fn interval_seconds(&self) -> u64 {
+        self.config.interval_seconds
+    }
+
+    fn db(&self) -> &PgPool {
+        self.service.db_pool()
+    }
+
+    /// Subscribe a URL to the user's payment events
+    pub async fn register(&self, user_id: Uuid, request: &RegisterWebhookRequest) -> PaymentResult<RegisteredWebhook> {
+        webhooks::validate(&request.url)
+            .await
+            .map_err(|e| PaymentError::ValidationError(e.to_string()))?;
+        if request
+            .wallet_address
+            .as_deref()
+            .is_some_and(|address| address.parse::<ethers::types::Address>().is_err())
+        {
+            return Err(PaymentError::ValidationError("wallet_address is not an address".to_string()));
+        }
+        let mut events: Vec<String> = Vec::new();
+        for event in &request.events {
+            let name = event.as_str().to_string();
+            if !events.contains(&name) {
+                events.push(name);
+            }
+        }
+        if events.is_empty() {
+            return Err(PaymentError::ValidationError("Subscribe to at least one event".to_string()));
+        }
+
+        let registered: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM payment_webhooks WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(self.db())
+            .await
+            .map_err(db_error)?;
+        if registered >= self.config.max_per_user {
+            return Err(PaymentError::ValidationError(format!(
+                "At most {} webhooks per user",
+                self.config.max_per_user
+            )));
+        }
+
+        let secret = format!("whsec_{}", hex::encode(ethers::core::rand::random::<[u8; 32]>()));
+        let webhook: PaymentWebhook = sqlx::query_as(
+            r#"
+            INSERT INTO payment_webhooks (user_id, wallet_address, url, secret, events)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(&request.wallet_address)
+        .bind(&request.url)
+        .bind(&secret)
+        .bind(&events)
+        .fetch_one(self.db())
+        .await
+        .map_err(db_error)?;
+        Ok(RegisteredWebhook { webhook, secret })
+    }
+
+    pub async fn list(&self, user_id: Uuid) -> PaymentResult<Vec<PaymentWebhook>> {
+        sqlx::query_as("SELECT * FROM payment_webhooks WHERE user_id = $1 ORDER BY created_at")
+            .bind(user_id)
+            .fetch_all(self.db())
+            .await
+            .map_err(db_error)
+    }
+
+    /// Remove one of the user's webhooks; its queued deliveries go with it
+    pub async fn delete(&self, user_id: Uuid, webhook_id: Uuid) -> PaymentResult<()> {
+        let result = sqlx::query("DELETE FROM payment_webhooks WHERE id = $1 AND user_id = $2")
+            .bind(webhook_id)
+            .bind(user_id)
+            .execute(self.db())
+            .await
+            .map_err(db_error)?;
+        if result.rows_affected() == 0 {
+            return Err(PaymentError::NotFound(format!("Webhook {}", webhook_id)));
+        }
+        Ok(())
+    }
+
+    /// One of the user's webhooks' deliveries, newest first
+    pub async fn deliveries(
+        &self,
+        user_id: Uuid,
+        webhook_id: Uuid,
+        page: Option<i64>,
+        limit: Option<i64>,
+    ) -> PaymentResult<Vec<WebhookDelivery>> {
+        let owned: bool =
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM payment_webhooks WHERE id = $1 AND user_id = $2)")
+                .bind(webhook_id)
+                .bind(user_id)
+                .fetch_one(self.db())
+                .await
+                .map_err(db_error)?;
+        if !owned {
+            return Err(PaymentError::NotFound(format!("Webhook {}", webhook_id)));
+        }
+
+        let limit = limit.unwrap_or(DEFAULT_DELIVERY_LIMIT).clamp(1, MAX_DELIVERY_LIMIT);
+        let offset = (page.unwrap_or(1).max(1) - 1) * limit;
+        sqlx::query_as(
+            r#"
+            SELECT * FROM payment_webhook_deliveries
+            WHERE webhook_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(webhook_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(self.db())
+        .await
+        .map_err(db_error)
+    }
+
+    /// Publish recorded events and queue them for their webhooks, returning
+    /// how many went out. An event that fails to publish is retried on the
+    /// next run.
+    pub async fn publish(&self) -> PaymentResult<usize> {
+        let mut tx = self.db().begin().await.map_err(db_error)?;
+        let rows = sqlx::query_as::<_, PaymentEventRow>(&format!(
+            r#"
+            SELECT {} FROM payment_events
+            WHERE published_at IS NULL
+            ORDER BY occurred_at
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+            "#,
+            EVENT_COLUMNS
+        ))
+        .bind(self.config.batch_size)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(db_error)?;
+
+        let mut published = 0;
+        for row in rows {
+            let id = row.id;
+            let Some(event) = row.event() else {
+                warn!("Payment event {} has an unknown kind", id);
+                continue;
+            };
+            let message = NexusEvent::PaymentUpdated(event.clone());
+            if let Err(e) = shared::messaging::publish_event(&self.redis, &message).await {
+                warn!("Failed to publish payment event {}: {}", id, e);
+                break;
+            }
+
+            let payload = serde_json::to_value(&event).map_err(|e| PaymentError::ValidationError(e.to_string()))?;
+            sqlx::query(
+                r#"
+                INSERT INTO payment_webhook_deliveries (webhook_id, event_id, event_type, payload)
+                SELECT w.id, $1, $2, $3
+                FROM payment_webhooks w
+                WHERE w.enabled AND $2 = ANY(w.events)
+                  AND (w.user_id = $4 OR ($4 IS NULL AND LOWER(w.wallet_address) = LOWER($5)))
+                ON CONFLICT (webhook_id, event_id) DO NOTHING
+                "#,
+            )
+            .bind(id)
+            .bind(event.kind.as_str())
+            .bind(&payload)
+            .bind(event.user_id)
+            .bind(&event.address)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+            sqlx::query("UPDATE payment_events SET published_at = NOW() WHERE id = $1")
+                .bind(id)
+                .execute(&mut *tx)
+                .await
+                .map_err(db_error)?;
+            published += 1;
+        }
+        tx.commit().await.map_err(db_error)?;
+        Ok(published)
+    }
+
+    /// Send the deliveries that are due; returns how many were delivered
+    pub async fn deliver_due(&self) -> PaymentResult<usize> {
+        let now = Utc::now();
+        let mut tx = self.db().begin().await.map_err(db_error)?;
+        let due: Vec<WebhookDelivery> = sqlx::query_as(
+            r#"
+            SELECT * FROM payment_webhook_deliveries
+            WHERE status = 'pending' AND next_attempt_at <= $1
+            ORDER BY next_attempt_at
+            LIMIT $2
+            FOR UPDATE SKIP LOCKED
+            "#,
+        )
+        .bind(now)
+        .bind(self.config.batch_size)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(db_error)?;
+
+        // Claim the batch for a minute; a crashed worker's claim then lapses
+        let ids: Vec<Uuid> = due.iter().map(|d| d.id).collect();
+        sqlx::query(
+            "UPDATE payment_webhook_deliveries SET next_attempt_at = $2 + INTERVAL '1 minute' WHERE id = ANY($1)",
+        )
+        .bind(&ids)
+        .bind(now)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        let webhooks: Vec<PaymentWebhook> = sqlx::query_as(
+            r#"
+            SELECT * FROM payment_webhooks
+            WHERE id IN (SELECT webhook_id FROM payment_webhook_deliveries WHERE id = ANY($1))
+            "#,
+        )
+        .bind(&ids)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        tx.commit().await.map_err(db_error)?;
+
+        let mut delivered = 0;
+        for delivery in &due {
+            let Some(webhook) = webhooks.iter().find(|w| w.id == delivery.webhook_id) else {
+                continue;
+            };
+            if self.deliver(delivery, webhook).await? {
+                delivered += 1;
+            }
+        }
+        Ok(delivered)
+    }
+
+    /// Send one delivery and record the outcome; `true` if it arrived
+    async fn deliver(&self, delivery: &WebhookDelivery, webhook: &PaymentWebhook) -> PaymentResult<bool> {
+        if !webhook.enabled {
+            // Events queued before the webhook was disabled are dropped
+            self.mark_failed(delivery.id, None, "webhook disabled", None).await?;
+            return Ok(false);
+        }
+
+        match self.send(delivery, webhook).await {
+            Ok(status) => {
+                sqlx::query(
+                    r#"
+                    UPDATE payment_webhook_deliveries
+                    SET status = 'delivered', attempts = attempts + 1, response_status = $2,
+                        last_error = NULL, delivered_at = NOW()
+                    WHERE id = $1
+                    "#,
+                )
+                .bind(delivery.id)
+                .bind(i32::from(status))
+                .execute(self.db())
+                .await
+                .map_err(db_error)?;
+                Ok(true)
+            }
+            Err(e) => {
+                let attempts = delivery.attempts.max(0) as u32 + 1;
+                let retry_at = (attempts < self.config.max_attempts).then(|| {
+                    let delay = self.retry_delay(attempts);
+                    Utc::now() + chrono::Duration::seconds(delay.as_secs() as i64)
+                });
+                warn!(
+                    "Payment webhook delivery {} to {} failed on attempt {}: {}",
+                    delivery.id, webhook.url, attempts, e
+                );
+                self.mark_failed(delivery.id, e.response_status(), &e.to_string(), retry_at)
+                    .await?;
+                Ok(false)
+            }
+        }
+    }
+
+    /// POST a delivery to its webhook; the endpoint's status on success
+    async fn send(&self, delivery: &WebhookDelivery, webhook: &PaymentWebhook) -> Result<u16, SendError> {
+        let body = serde_json::to_vec(&serde_json::json!({
+            "id": delivery.id,
+            "event": delivery.event_type,
+            "user_id": webhook.user_id,
+            "created_at": delivery.created_at,
+            "data": delivery.payload,
+        }))
+        .map_err(|e| SendError::Unreachable(e.to_string()))?;
+        // The host was public when the webhook was registered; DNS may have
+        // changed since
+        let url = webhooks::parse(&webhook.url).map_err(|e| SendError::Unreachable(e.to_string()))?;
+        webhooks::check_resolved(&url)
+            .await
+            .map_err(|e| SendError::Unreachable(e.to_string()))?;
+        let timestamp = Utc::now().timestamp();
+
+        let response = self
+            .http
+            .post(url)
+            .header("content-type", "application/json")
+            .header("x-nexus-event", &delivery.event_type)
+            .header("x-nexus-delivery", delivery.id.to_string())
+            .header("x-nexus-timestamp", timestamp.to_string())
+            .header("x-nexus-signature", sign(&webhook.secret, timestamp, &body))
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| SendError::Unreachable(e.to_string()))?;
+
+        let status = response.status();
+        if status.is_success() {
+            Ok(status.as_u16())
+        } else {
+            Err(SendError::Rejected(status.as_u16()))
+        }
+    }
+
+    /// Record a failed attempt: retry at `retry_at`, or give up if `None`
+    async fn mark_failed(
+        &self,
+        id: Uuid,
+        response_status: Option<i32>,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> PaymentResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE payment_webhook_deliveries
+            SET attempts = attempts + 1,
+                status = CASE WHEN $4::TIMESTAMPTZ IS NULL THEN 'failed' ELSE 'pending' END,
+                next_attempt_at = COALESCE($4, next_attempt_at),
+                response_status = $2,
+                last_error = $3
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(response_status)
+        .bind(error)
+        .bind(retry_at)
+        .execute(self.db())
+        .await
+        .map_err(db_error)?;
+        Ok(())
+    }
+
+    /// Wait before retrying after `attempts` failed attempts: doubling from
+    /// the base, capped at the maximum
+    fn retry_delay(&self, attempts: u32) -> Duration {
+        webhooks::retry_delay(
+            attempts,
+            Duration::from_secs(self.config.backoff_base_seconds),
+            Duration::from_secs(self.config.backoff_max_seconds),
+        )
+    }
+}
@@ -6,6 +6,7 @@ use ethers::prelude::*;
 use tracing::info;
 
 use crate::config::Config;
+use crate::blockchain::provider::get_gas_price;
 use crate::blockchain::{BlockchainProvider, TokenContract};
 
 pub struct PaymentService {
@@ -30,30 +31,81 @@ impl PaymentService {
         })
     }
 
+    /// Get the service configuration
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
     /// Get the database pool
     pub fn db_pool(&self) -> &PgPool {
         &self.db_pool
     }
 
-    /// Get a TokenContract instance bound to the provider
-    fn token_contract(&self) -> Result<TokenContract<Provider<Ws>>> {
-        let addr: Address = self.config.blockchain.token_contract_address.parse()
+    /// Get the blockchain provider
+    pub fn provider(&self) -> &BlockchainProvider {
+        &self.provider
+    }
+
+    /// Get a TokenContract instance for the token at `token` bound to the provider
+    pub fn token_contract_at(&self, token: &str) -> Result<TokenContract<Provider<Ws>>> {
+        let addr: Address = token.parse()
             .context("Invalid token contract address")?;
         Ok(TokenContract::new(addr, self.provider.clone()))
     }
 
-    /// Get token balance for an address
+    /// Get platform token balance for an address
     pub async fn get_token_balance(&self, address: &str) -> Result<U256> {
+        self.get_token_balance_of(&self.config.blockchain.token_contract_address, address).await
+    }
+
+    /// Get the balance an address holds of the token at `token`
+    pub async fn get_token_balance_of(&self, token: &str, address: &str) -> Result<U256> {
         let addr: Address = address.parse()
             .context("Invalid Ethereum address")?;
 
-        let token = self.token_contract()?;
+        let token = self.token_contract_at(token)?;
         let balance = token.balance_of(addr).call().await
             .context("Failed to call balanceOf")?;
 
         Ok(balance)
     }
 
+    /// Get native (ETH) balance for an address
+    pub async fn get_native_balance(&self, address: &str) -> Result<U256> {
+        let addr: Address = address.parse()
+            .context("Invalid Ethereum address")?;
+
+        let balance = self.provider
+            .get_balance(addr, None)
+            .await
+            .context("Failed to get balance")?;
+
+        Ok(balance)
+    }
+
+    /// Get how much of the token at `token` `spender` may move for `owner`
+    pub async fn get_token_allowance(&self, token: &str, owner: &str, spender: &str) -> Result<U256> {
+        let owner: Address = owner.parse()
+            .context("Invalid owner address")?;
+        let spender: Address = spender.parse()
+            .context("Invalid spender address")?;
+
+        let token = self.token_contract_at(token)?;
+        let allowance = token.allowance(owner, spender).call().await
+            .context("Failed to call allowance")?;
+
+        Ok(allowance)
+    }
+
+    /// Get the decimals the token at `token` reports
+    pub async fn get_token_decimals(&self, token: &str) -> Result<u8> {
+        let token = self.token_contract_at(token)?;
+        let decimals = token.decimals().call().await
+            .context("Failed to call decimals")?;
+
+        Ok(decimals)
+    }
+
     /// Get transaction receipt from the chain
     pub async fn get_tx_receipt(&self, tx_hash: &str) -> Result<Option<ethers::types::TransactionReceipt>> {
         let hash: H256 = tx_hash.parse()
@@ -67,16 +119,16 @@ impl PaymentService {
         Ok(receipt)
     }
 
-    /// Estimate gas for a standard ERC20 transfer
-    pub async fn estimate_gas_for_transfer(&self) -> Result<U256> {
-        let gas_price = self.provider
-            .get_gas_price()
-            .await
-            .context("Failed to get gas price")?;
-
-        // Standard ERC20 transfer gas ~ 65,000
-        let estimated_gas = U256::from(65_000) * gas_price;
-        Ok(estimated_gas)
+    /// Current gas price with the configured multiplier, capped at the
+    /// configured maximum
+    pub async fn gas_price(&self) -> Result<U256> {
+        get_gas_price(
+            &self.provider,
+            self.config.blockchain.gas_price_multiplier,
+            self.config.blockchain.max_gas_price_gwei,
+        )
+        .await
+        .context("Failed to get gas price")
     }
 
     /// Health check — verifies RPC connectivity
@@ -0,0 +1,612 @@
+// Batch reward payouts
+//
+// Once a bounty's escrow is released and its settlement plan applied, the
+// reward is split by the plan's reward shares among the rewarded engines,
+// each paid to the address it staked from. Whatever the shares leave over
+// (only engines on probation, whose shares are capped, were rewarded, or an
+// engine has no stake address) goes back to the creator. The platform fee is
+// taken off the top first and stays in the treasury, recorded in the fee
+// ledger. The items of one bounty form a batch, paid from the treasury
+// through the nonce manager.
+//
+// With a disperse contract a batch goes out as one transaction per
+// `max_batch_size` items instead of one per recipient. A disperse call is all
+// or nothing, so the items of one that reverts are retried as individual
+// transfers: a recipient the token refuses no longer holds up the others.
+// Every item is tracked on its own and sent up to `max_attempts` times;
+// admins can queue the ones left failed again. Sending and paying each item
+// are announced as payment events.
+
+use chrono::{DateTime, Utc};
+use ethers::types::{Address, U256};
+use serde::Serialize;
+use shared::messaging::{PaymentEventKind, SettlementOutcome, SettlementPlan};
+use sqlx::PgConnection;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::blockchain::{DisperseContract, TokenContract, TransactionBuilder};
+use crate::config::PayoutConfig;
+use crate::models::{PaymentError, PaymentResult};
+use crate::services::fees::{self, REWARD_FEE};
+use crate::services::nonces::NonceManager;
+use crate::services::payment_service::PaymentService;
+
+pub const REWARD: &str = "reward";
+pub const REMAINDER: &str = "remainder";
+
+const DISPERSE: &str = "disperse";
+const TRANSFER: &str = "transfer";
+
+/// Reward shares are applied in billionths
+const SHARE_PRECISION: u64 = 1_000_000_000;
+
+/// Gas of a disperse call, which cannot be estimated before the approval
+/// ahead of it is mined
+const DISPERSE_BASE_GAS: u64 = 60_000;
+const DISPERSE_GAS_PER_RECIPIENT: u64 = 40_000;
+
+/// Batches opened and items sent per run
+const BATCH_LIMIT: i64 = 50;
+const ITEM_LIMIT: i64 = 500;
+
+const BATCH_COLUMNS: &str = "id, bounty_id, plan_id, token_address, from_address, total_amount::TEXT AS total_amount, \
+                             fee_amount::TEXT AS fee_amount, status, created_at, updated_at, completed_at";
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct PayoutBatch {
+    pub id: Uuid,
+    pub bounty_id: Uuid,
+    pub plan_id: Uuid,
+    pub token_address: String,
+    pub from_address: String,
+    /// In base units of the token
+    pub total_amount: String,
+    /// Platform fee kept before the split, in base units
+    pub fee_amount: String,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct PayoutItem {
+    pub id: Uuid,
+    pub recipient_address: String,
+    pub user_id: Option<Uuid>,
+    pub kind: String,
+    /// In base units of the token
+    pub amount: String,
+    pub status: String,
+    pub method: Option<String>,
+    /// Latest transaction the item was sent in, or the one that paid it
+    pub tx_hash: Option<String>,
+    pub attempts: i32,
+    pub error_message: Option<String>,
+    pub updated_at: DateTime<Utc>,
+    pub paid_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PayoutBatchDetail {
+    #[serde(flatten)]
+    pub batch: PayoutBatch,
+    pub items: Vec<PayoutItem>,
+}
+
+#[derive(sqlx::FromRow)]
+struct DueEscrow {
+    bounty_id: Uuid,
+    amount: String,
+    token_address: String,
+    holder_address: String,
+    plan: String,
+}
+
+#[derive(sqlx::FromRow)]
+struct PendingItem {
+    id: Uuid,
+    batch_id: Uuid,
+    token_address: String,
+    recipient_address: String,
+    amount: String,
+    method: Option<String>,
+    attempts: i32,
+}
+
+fn db_error(e: sqlx::Error) -> PaymentError {
+    PaymentError::DatabaseError(e.to_string())
+}
+
+fn parse_address(value: &str) -> PaymentResult<Address> {
+    value
+        .parse()
+        .map_err(|_| PaymentError::ValidationError(format!("{} is not an address", value)))
+}
+
+fn parse_u256(value: &str) -> PaymentResult<U256> {
+    U256::from_dec_str(value).map_err(|_| PaymentError::ValidationError(format!("{} is not a whole number", value)))
+}
+
+/// Record a payment event for each of the items, with the transaction it
+/// went out in. Only an item's first send is announced.
+async fn record_events(db: &mut PgConnection, ids: &[Uuid], kind: PaymentEventKind) -> PaymentResult<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO payment_events (kind, source_id, bounty_id, user_id, address, amount, token_address, tx_hash)
+        SELECT $2, i.id, b.bounty_id, i.user_id, i.recipient_address, i.amount, b.token_address,
+               COALESCE(o.mined_tx_hash, o.tx_hash)
+        FROM payout_items i
+        JOIN payout_batches b ON b.id = i.batch_id
+        LEFT JOIN outgoing_transactions o ON o.id = i.outgoing_id
+        WHERE i.id = ANY($1)
+        ON CONFLICT (kind, source_id) DO NOTHING
+        "#,
+    )
+    .bind(ids)
+    .bind(kind.as_str())
+    .execute(db)
+    .await
+    .map_err(db_error)?;
+    Ok(())
+}
+
+/// `share` (0.0 to 1.0) of `total`, rounded down
+fn share_of(total: U256, share: f64) -> U256 {
+    let share = (share.clamp(0.0, 1.0) * SHARE_PRECISION as f64).round() as u64;
+    total * U256::from(share) / U256::from(SHARE_PRECISION)
+}
+
+pub struct PayoutService {
+    service: Arc<PaymentService>,
+    nonces: Arc<NonceManager>,
+    config: PayoutConfig,
+}
+
+impl PayoutService {
+    pub fn new(service: Arc<PaymentService>, nonces: Arc<NonceManager>) -> Self {
+        let config = service.config().payouts.clone();
+        Self { service, nonces, config }
+    }
+
+    pub fn interval_seconds(&self) -> u64 {
+        self.config.interval_seconds
+    }
+
+    /// Settle sent items, open batches for escrows now due and send pending
+    /// items. Returns (items settled, batches opened, items sent).
+    pub async fn run(&self) -> PaymentResult<(usize, usize, usize)> {
+        let settled = self.settle().await?;
+        let opened = self.open_batches().await?;
+        let sent = self.send_pending().await?;
+        Ok((settled, opened, sent))
+    }
+
+    /// Open a batch for each released escrow due a payout whose settlement
+    /// plan has been applied
+    async fn open_batches(&self) -> PaymentResult<usize> {
+        let treasury = self.service.config().blockchain.treasury_address.clone();
+        if !self.nonces.manages(&treasury) {
+            return Ok(0);
+        }
+
+        let due: Vec<DueEscrow> = sqlx::query_as(
+            r#"
+            SELECT e.bounty_id, e.amount::TEXT AS amount, e.token_address, e.holder_address, p.plan::TEXT AS plan
+            FROM escrow_accounts e
+            JOIN LATERAL (
+                SELECT plan FROM settlement_plans WHERE bounty_id = e.bounty_id ORDER BY planned_at DESC LIMIT 1
+            ) p ON TRUE
+            WHERE e.status = 'released' AND e.payout_due
+              AND NOT EXISTS (SELECT 1 FROM payout_batches b WHERE b.bounty_id = e.bounty_id)
+            ORDER BY e.released_at
+            LIMIT $1
+            "#,
+        )
+        .bind(BATCH_LIMIT)
+        .fetch_all(self.service.db_pool())
+        .await
+        .map_err(db_error)?;
+
+        let mut opened = 0;
+        for escrow in due {
+            match self.open_batch(&escrow, &treasury).await {
+                Ok(true) => opened += 1,
+                Ok(false) => {}
+                Err(e) => warn!("Failed to open payout batch for bounty {}: {}", escrow.bounty_id, e),
+            }
+        }
+        Ok(opened)
+    }
+
+    async fn open_batch(&self, escrow: &DueEscrow, treasury: &str) -> PaymentResult<bool> {
+        let plan: SettlementPlan =
+            serde_json::from_str(&escrow.plan).map_err(|e| PaymentError::ValidationError(e.to_string()))?;
+        let escrowed = parse_u256(escrow.amount.split('.').next().unwrap_or_default())?;
+        let fee = fees::fee_of(escrowed, self.service.config().fees.reward_fee_percentage);
+        let total = escrowed.saturating_sub(fee);
+        let pool = self.service.db_pool();
+
+        // Lowercase address -> (address, user, amount)
+        let mut rewards: BTreeMap<String, (String, Option<Uuid>, U256)> = BTreeMap::new();
+        for entry in &plan.entries {
+            let Some(user_id) = entry.user_id else {
+                continue;
+            };
+            if entry.outcome != SettlementOutcome::Rewarded || entry.reward_share <= 0.0 {
+                continue;
+            }
+            let address: Option<String> = sqlx::query_scalar(
+                "SELECT address FROM stakes WHERE bounty_id = $1 AND user_id = $2 ORDER BY locked_at DESC LIMIT 1",
+            )
+            .bind(plan.bounty_id)
+            .bind(user_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(db_error)?;
+            let Some(address) = address.filter(|address| address.parse::<Address>().is_ok()) else {
+                warn!("Engine {} has no stake address on bounty {}; its reward share returns to the creator", user_id, plan.bounty_id);
+                continue;
+            };
+            let amount = share_of(total, entry.reward_share);
+            if amount.is_zero() {
+                continue;
+            }
+            let reward = rewards.entry(address.to_lowercase()).or_insert((address, Some(user_id), U256::zero()));
+            reward.2 = reward.2.saturating_add(amount);
+        }
+        let rewarded = rewards.values().fold(U256::zero(), |sum, (_, _, amount)| sum.saturating_add(*amount));
+        let remainder = total.saturating_sub(rewarded);
+
+        let mut tx = pool.begin().await.map_err(db_error)?;
+        let batch_id: Option<Uuid> = sqlx::query_scalar(
+            r#"
+            INSERT INTO payout_batches (bounty_id, plan_id, token_address, from_address, total_amount, fee_amount)
+            VALUES ($1, $2, $3, $4, $5::NUMERIC, $6::NUMERIC)
+            ON CONFLICT (bounty_id) DO NOTHING
+            RETURNING id
+            "#,
+        )
+        .bind(escrow.bounty_id)
+        .bind(plan.plan_id)
+        .bind(&escrow.token_address)
+        .bind(treasury)
+        .bind(total.to_string())
+        .bind(fee.to_string())
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        let Some(batch_id) = batch_id else {
+            return Ok(false);
+        };
+        fees::accrue(&mut tx, REWARD_FEE, &escrow.token_address, fee, Some(escrow.bounty_id), batch_id).await?;
+
+        let remainder_item = (!remainder.is_zero()).then(|| (escrow.holder_address.clone(), None, remainder, REMAINDER));
+        let items = rewards
+            .into_values()
+            .map(|(address, user_id, amount)| (address, user_id, amount, REWARD))
+            .chain(remainder_item);
+        for (address, user_id, amount, kind) in items {
+            sqlx::query(
+                r#"
+                INSERT INTO payout_items (batch_id, recipient_address, user_id, kind, amount)
+                VALUES ($1, $2, $3, $4, $5::NUMERIC)
+                "#,
+            )
+            .bind(batch_id)
+            .bind(&address)
+            .bind(user_id)
+            .bind(kind)
+            .bind(amount.to_string())
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        }
+        tx.commit().await.map_err(db_error)?;
+
+        info!(
+            "Opened payout batch for bounty {}: {} to engines, {} back to the creator, {} platform fee",
+            escrow.bounty_id, rewarded, remainder, fee
+        );
+        Ok(true)
+    }
+
+    /// Send pending items: never-sent ones of a batch together through the
+    /// disperse contract when there is one, the rest as transfers
+    async fn send_pending(&self) -> PaymentResult<usize> {
+        let pending: Vec<PendingItem> = sqlx::query_as(
+            r#"
+            SELECT i.id, i.batch_id, b.token_address, i.recipient_address, i.amount::TEXT AS amount,
+                   i.method, i.attempts
+            FROM payout_items i
+            JOIN payout_batches b ON b.id = i.batch_id
+            WHERE i.status = 'pending' AND b.status = 'pending'
+            ORDER BY b.created_at, i.id
+            LIMIT $1
+            "#,
+        )
+        .bind(ITEM_LIMIT)
+        .fetch_all(self.service.db_pool())
+        .await
+        .map_err(db_error)?;
+
+        let mut batches: BTreeMap<Uuid, Vec<PendingItem>> = BTreeMap::new();
+        for item in pending {
+            batches.entry(item.batch_id).or_default().push(item);
+        }
+
+        let mut sent = 0;
+        for items in batches.into_values() {
+            let (dispersed, transfers): (Vec<PendingItem>, Vec<PendingItem>) = items
+                .into_iter()
+                .partition(|item| self.config.disperse_contract_address.is_some() && item.method.is_none());
+
+            for chunk in dispersed.chunks(self.config.max_batch_size) {
+                if chunk.len() == 1 {
+                    // Cheaper as a plain transfer
+                    sent += self.send_transfer(&chunk[0]).await? as usize;
+                    continue;
+                }
+                match self.send_disperse(chunk).await {
+                    Ok(()) => sent += chunk.len(),
+                    Err(e) => {
+                        warn!("Disperse of {} payout(s) could not be sent: {}", chunk.len(), e);
+                        let failed: Vec<(Uuid, i32)> = chunk.iter().map(|item| (item.id, item.attempts + 1)).collect();
+                        self.record_failure(&failed, &e.to_string()).await?;
+                    }
+                }
+            }
+            for item in &transfers {
+                sent += self.send_transfer(item).await? as usize;
+            }
+        }
+        Ok(sent)
+    }
+
+    /// Approve the disperse contract for the chunk's total, then disperse it;
+    /// the two go out at consecutive nonces
+    async fn send_disperse(&self, items: &[PendingItem]) -> PaymentResult<()> {
+        let Some(disperse) = self.config.disperse_contract_address.as_deref() else {
+            return Err(PaymentError::ConfigError("No disperse contract".to_string()));
+        };
+        let disperse = parse_address(disperse)?;
+        let treasury = parse_address(&self.service.config().blockchain.treasury_address)?;
+        let token = parse_address(&items[0].token_address)?;
+
+        let mut recipients = Vec::with_capacity(items.len());
+        let mut values = Vec::with_capacity(items.len());
+        for item in items {
+            recipients.push(parse_address(&item.recipient_address)?);
+            values.push(parse_u256(&item.amount)?);
+        }
+        let total = values.iter().fold(U256::zero(), |sum, value| sum.saturating_add(*value));
+
+        let provider = self.service.provider().clone();
+        let approve = TokenContract::new(token, provider.clone())
+            .approve(disperse, total)
+            .calldata()
+            .unwrap_or_default();
+        self.nonces
+            .submit(None, TransactionBuilder::new(treasury, token).data(approve))
+            .await?;
+
+        let call = DisperseContract::new(disperse, provider)
+            .disperse_token(token, recipients, values)
+            .calldata()
+            .unwrap_or_default();
+        let gas_limit = U256::from(DISPERSE_BASE_GAS + DISPERSE_GAS_PER_RECIPIENT * items.len() as u64);
+        let outgoing = self
+            .nonces
+            .submit(None, TransactionBuilder::new(treasury, disperse).data(call).gas_limit(gas_limit))
+            .await?;
+
+        let ids: Vec<Uuid> = items.iter().map(|item| item.id).collect();
+        self.mark_sent(&ids, DISPERSE, outgoing.id).await?;
+        info!("Dispersed {} payout(s) of {} in {}", items.len(), total, outgoing.tx_hash);
+        Ok(())
+    }
+
+    /// Returns whether the transfer was sent; a failure is recorded on the item
+    async fn send_transfer(&self, item: &PendingItem) -> PaymentResult<bool> {
+        let result = async {
+            let treasury = parse_address(&self.service.config().blockchain.treasury_address)?;
+            let token = parse_address(&item.token_address)?;
+            let data = TokenContract::new(token, self.service.provider().clone())
+                .transfer(parse_address(&item.recipient_address)?, parse_u256(&item.amount)?)
+                .calldata()
+                .unwrap_or_default();
+            self.nonces
+                .submit(None, TransactionBuilder::new(treasury, token).data(data))
+                .await
+        }
+        .await;
+
+        match result {
+            Ok(outgoing) => {
+                self.mark_sent(&[item.id], TRANSFER, outgoing.id).await?;
+                Ok(true)
+            }
+            Err(e) => {
+                warn!("Payout {} to {} could not be sent: {}", item.id, item.recipient_address, e);
+                self.record_failure(&[(item.id, item.attempts + 1)], &e.to_string()).await?;
+                Ok(false)
+            }
+        }
+    }
+
+    async fn mark_sent(&self, ids: &[Uuid], method: &str, outgoing_id: Uuid) -> PaymentResult<()> {
+        let mut tx = self.service.db_pool().begin().await.map_err(db_error)?;
+        sqlx::query(
+            r#"
+            UPDATE payout_items
+            SET status = 'sent', method = $2, outgoing_id = $3, attempts = attempts + 1,
+                error_message = NULL, updated_at = NOW()
+            WHERE id = ANY($1)
+            "#,
+        )
+        .bind(ids)
+        .bind(method)
+        .bind(outgoing_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        record_events(&mut tx, ids, PaymentEventKind::PayoutSent).await?;
+        tx.commit().await.map_err(db_error)?;
+        Ok(())
+    }
+
+    /// Record a failed send of each item with the attempts made so far,
+    /// leaving it failed once out of attempts. A failed item is only ever
+    /// sent again as a transfer.
+    async fn record_failure(&self, items: &[(Uuid, i32)], error: &str) -> PaymentResult<()> {
+        for &(id, attempts) in items {
+            let status = if attempts >= self.config.max_attempts { "failed" } else { "pending" };
+            sqlx::query(
+                r#"
+                UPDATE payout_items
+                SET status = $2, method = COALESCE(method, 'transfer'), attempts = $3,
+                    error_message = $4, updated_at = NOW()
+                WHERE id = $1
+                "#,
+            )
+            .bind(id)
+            .bind(status)
+            .bind(attempts)
+            .bind(error)
+            .execute(self.service.db_pool())
+            .await
+            .map_err(db_error)?;
+        }
+        Ok(())
+    }
+
+    /// Settle sent items whose transaction was mined or dropped, then close
+    /// batches with nothing left to send. Returns how many items settled.
+    async fn settle(&self) -> PaymentResult<usize> {
+        let pool = self.service.db_pool();
+        let settled: Vec<(Uuid, i32, String)> = sqlx::query_as(
+            r#"
+            SELECT i.id, i.attempts, o.status
+            FROM payout_items i
+            JOIN outgoing_transactions o ON o.id = i.outgoing_id
+            WHERE i.status = 'sent' AND o.status <> 'pending'
+            "#,
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(db_error)?;
+
+        for (id, attempts, tx_status) in &settled {
+            if tx_status == "confirmed" {
+                let mut tx = pool.begin().await.map_err(db_error)?;
+                sqlx::query("UPDATE payout_items SET status = 'paid', paid_at = NOW(), updated_at = NOW() WHERE id = $1")
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(db_error)?;
+                record_events(&mut tx, &[*id], PaymentEventKind::PayoutConfirmed).await?;
+                tx.commit().await.map_err(db_error)?;
+            } else {
+                // `attempts` already counts the send that failed
+                self.record_failure(&[(*id, *attempts)], &format!("transaction {}", tx_status)).await?;
+            }
+        }
+
+        let closed: Vec<(Uuid, String)> = sqlx::query_as(
+            r#"
+            UPDATE payout_batches b
+            SET status = CASE WHEN s.paid = s.total THEN 'completed'
+                              WHEN s.paid > 0 THEN 'partially_failed'
+                              ELSE 'failed' END,
+                completed_at = NOW(), updated_at = NOW()
+            FROM (
+                SELECT batch_id, COUNT(*) AS total,
+                       COUNT(*) FILTER (WHERE status = 'paid') AS paid,
+                       COUNT(*) FILTER (WHERE status IN ('pending', 'sent')) AS open
+                FROM payout_items GROUP BY batch_id
+            ) s
+            WHERE s.batch_id = b.id AND b.status = 'pending' AND s.open = 0
+            RETURNING b.bounty_id, b.status
+            "#,
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(db_error)?;
+        for (bounty_id, status) in closed {
+            if status == "completed" {
+                info!("Payouts for bounty {} completed", bounty_id);
+            } else {
+                warn!("Payouts for bounty {} {}", bounty_id, status.replace('_', " "));
+            }
+        }
+        Ok(settled.len())
+    }
+
+    pub async fn find(&self, bounty_id: Uuid) -> PaymentResult<Option<PayoutBatchDetail>> {
+        let pool = self.service.db_pool();
+        let batch: Option<PayoutBatch> =
+            sqlx::query_as(&format!("SELECT {} FROM payout_batches WHERE bounty_id = $1", BATCH_COLUMNS))
+                .bind(bounty_id)
+                .fetch_optional(pool)
+                .await
+                .map_err(db_error)?;
+        let Some(batch) = batch else {
+            return Ok(None);
+        };
+
+        let items = sqlx::query_as(
+            r#"
+            SELECT i.id, i.recipient_address, i.user_id, i.kind, i.amount::TEXT AS amount, i.status, i.method,
+                   COALESCE(o.mined_tx_hash, o.tx_hash) AS tx_hash, i.attempts, i.error_message,
+                   i.updated_at, i.paid_at
+            FROM payout_items i
+            LEFT JOIN outgoing_transactions o ON o.id = i.outgoing_id
+            WHERE i.batch_id = $1
+            ORDER BY i.kind DESC, i.recipient_address
+            "#,
+        )
+        .bind(batch.id)
+        .fetch_all(pool)
+        .await
+        .map_err(db_error)?;
+        Ok(Some(PayoutBatchDetail { batch, items }))
+    }
+
+    /// Queue a bounty's failed items to be sent again, as transfers, with a
+    /// fresh set of attempts
+    pub async fn retry(&self, bounty_id: Uuid) -> PaymentResult<PayoutBatchDetail> {
+        let pool = self.service.db_pool();
+        let mut tx = pool.begin().await.map_err(db_error)?;
+        let requeued = sqlx::query(
+            r#"
+            UPDATE payout_items i
+            SET status = 'pending', attempts = 0, error_message = NULL, updated_at = NOW()
+            FROM payout_batches b
+            WHERE b.id = i.batch_id AND b.bounty_id = $1 AND i.status = 'failed'
+            "#,
+        )
+        .bind(bounty_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?
+        .rows_affected();
+        if requeued > 0 {
+            sqlx::query(
+                "UPDATE payout_batches SET status = 'pending', completed_at = NULL, updated_at = NOW() WHERE bounty_id = $1",
+            )
+            .bind(bounty_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        }
+        tx.commit().await.map_err(db_error)?;
+
+        info!("Queued {} failed payout(s) of bounty {} again", requeued, bounty_id);
+        self.find(bounty_id)
+            .await?
+            .ok_or_else(|| PaymentError::NotFound(format!("No payouts for bounty {}", bounty_id)))
+    }
+}
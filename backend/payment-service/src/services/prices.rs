@@ -0,0 +1,581 @@
+// Token prices and fiat reporting
+//
+// Each reward token with a price feed (PRICE_FEEDS) has its USD rate read
+// from CoinGecko, a Chainlink aggregator or a fixed peg every few minutes
+// and recorded in `token_prices`. Treasury flows are then valued once, at
+// the rate last observed at or before their transaction time (or the first
+// after it), and that value is stored next to the token amount: completed
+// payments, paid payout items and accrued fees. A flow with no rate within
+// PRICE_MAX_AGE_SECONDS of it stays unpriced and is counted as such in
+// reports, rather than valued at a rate from another day.
+//
+// Treasury and earnings reports sum the stored values, so they read the
+// same however the token has moved since, and export as CSV for accounting.
+
+use chrono::{DateTime, Utc};
+use ethers::types::{Address, I256};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::blockchain::PriceAggregator;
+use crate::config::{PriceConfig, PriceFeed, PriceSource};
+use crate::models::{PaymentError, PaymentResult};
+use crate::services::payment_service::PaymentService;
+use crate::services::tokens::format_amount;
+
+pub const CURRENCY: &str = "USD";
+
+/// Flows of one table valued per run
+const STAMP_BATCH: i64 = 500;
+
+const PRICE_COLUMNS: &str = "token_address, symbol, usd_rate::TEXT AS usd_rate, source, observed_at, recorded_at";
+
+pub const TREASURY_CSV_HEADER: &str = "direction,category,token_address,symbol,count,amount,amount_tokens,usd_value,unpriced\n";
+
+pub const EARNINGS_CSV_HEADER: &str =
+    "date,category,bounty_id,token_address,symbol,amount,amount_tokens,usd_rate,usd_value,tx_hash\n";
+
+/// A table whose flows are valued: where the token and transaction time
+/// are, and which rows have happened
+struct Valued {
+    table: &'static str,
+    from: &'static str,
+    token: &'static str,
+    at: &'static str,
+    settled: &'static str,
+}
+
+const VALUED: [Valued; 3] = [
+    Valued {
+        table: "payments",
+        from: "payments t",
+        token: "t.token_address",
+        at: "CASE WHEN t.payment_type = 'bounty_deposit' THEN t.created_at ELSE t.completed_at END",
+        settled: "(t.payment_type = 'bounty_deposit' OR t.status = 'completed')",
+    },
+    Valued {
+        table: "payout_items",
+        from: "payout_items t JOIN payout_batches b ON b.id = t.batch_id",
+        token: "b.token_address",
+        at: "t.paid_at",
+        settled: "t.status = 'paid'",
+    },
+    Valued {
+        table: "fee_ledger",
+        from: "fee_ledger t",
+        token: "t.token_address",
+        at: "t.created_at",
+        settled: "TRUE",
+    },
+];
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct TokenPrice {
+    pub token_address: String,
+    pub symbol: String,
+    pub usd_rate: String,
+    pub source: String,
+    /// When the feed last updated the rate
+    pub observed_at: DateTime<Utc>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// One rate read from a feed
+struct Quote {
+    usd_rate: String,
+    observed_at: DateTime<Utc>,
+}
+
+/// Flows of one kind and token over a report's period
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct FlowTotal {
+    /// in (deposits), out (payments and payouts from the platform) or fee
+    pub direction: String,
+    /// Payment type, payout kind or fee kind
+    pub category: String,
+    pub token_address: String,
+    #[sqlx(skip)]
+    pub symbol: Option<String>,
+    pub count: i64,
+    /// In base units
+    pub amount: String,
+    #[sqlx(skip)]
+    pub amount_tokens: Option<String>,
+    /// Of the priced flows
+    pub usd_value: String,
+    /// Flows without a rate near their transaction time
+    pub unpriced: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TreasuryReport {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub currency: &'static str,
+    pub inflow_usd: String,
+    pub outflow_usd: String,
+    pub fees_usd: String,
+    pub unpriced: i64,
+    pub flows: Vec<FlowTotal>,
+}
+
+/// One payment an engine earned
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct EarningsLine {
+    pub at: DateTime<Utc>,
+    /// reward or slash_redistribution
+    pub category: String,
+    pub bounty_id: Option<Uuid>,
+    pub token_address: String,
+    #[sqlx(skip)]
+    pub symbol: Option<String>,
+    /// In base units
+    pub amount: String,
+    #[sqlx(skip)]
+    pub amount_tokens: Option<String>,
+    pub usd_rate: Option<String>,
+    pub usd_value: Option<String>,
+    pub tx_hash: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EarningsReport {
+    pub address: String,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub currency: &'static str,
+    pub total_usd: String,
+    pub unpriced: i64,
+    pub lines: Vec<EarningsLine>,
+}
+
+#[derive(Deserialize)]
+struct CoingeckoQuote {
+    usd: serde_json::Number,
+    last_updated_at: Option<i64>,
+}
+
+fn db_error(e: sqlx::Error) -> PaymentError {
+    PaymentError::DatabaseError(e.to_string())
+}
+
+/// Quote a CSV field if it holds a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn optional<T: ToString>(value: &Option<T>) -> String {
+    value.as_ref().map(T::to_string).unwrap_or_default()
+}
+
+fn sum_usd<'a>(values: impl Iterator<Item = &'a str>) -> String {
+    values
+        .filter_map(|value| Decimal::from_str(value).ok())
+        .sum::<Decimal>()
+        .round_dp(2)
+        .to_string()
+}
+
+/// A feed's number as a plain decimal; CoinGecko writes small rates in
+/// scientific notation
+fn plain_rate(value: &str) -> Option<String> {
+    let rate = Decimal::from_str(value).or_else(|_| Decimal::from_scientific(value)).ok()?;
+    (rate > Decimal::ZERO).then(|| rate.normalize().to_string())
+}
+
+pub struct PriceService {
+    service: Arc<PaymentService>,
+    config: PriceConfig,
+    http: reqwest::Client,
+}
+
+impl PriceService {
+    pub fn new(service: Arc<PaymentService>) -> anyhow::Result<Self> {
+        let config = service.config().prices.clone();
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_seconds))
+            .build()?;
+        Ok(Self { service, config, http })
+    }
+
+    pub fn interval_seconds(&self) -> u64 {
+        self.config.interval_seconds
+    }
+
+    fn db(&self) -> &PgPool {
+        self.service.db_pool()
+    }
+
+    fn symbol(&self, token_address: &str) -> Option<(String, u8)> {
+        self.service
+            .config()
+            .tokens
+            .find(token_address)
+            .map(|token| (token.symbol.clone(), token.decimals))
+    }
+
+    /// `amount` base units of `token_address` as whole tokens
+    fn tokens_of(&self, token_address: &str, amount: &str) -> Option<String> {
+        let (_, decimals) = self.symbol(token_address)?;
+        let whole = amount.split('.').next().unwrap_or_default();
+        ethers::types::U256::from_dec_str(whole)
+            .ok()
+            .map(|amount| format_amount(amount, decimals))
+    }
+
+    /// Read every feed and record the rates not yet seen. A feed that
+    /// fails is skipped until the next run.
+    pub async fn refresh(&self) -> PaymentResult<u64> {
+        let ids: Vec<&str> = self
+            .config
+            .feeds
+            .iter()
+            .filter_map(|feed| match &feed.source {
+                PriceSource::Coingecko(id) => Some(id.as_str()),
+                _ => None,
+            })
+            .collect();
+        let mut coingecko = if ids.is_empty() {
+            HashMap::new()
+        } else {
+            self.coingecko(&ids).await.unwrap_or_else(|e| {
+                warn!("CoinGecko price request failed: {}", e);
+                HashMap::new()
+            })
+        };
+
+        let mut recorded = 0;
+        for feed in &self.config.feeds {
+            let quote = match &feed.source {
+                PriceSource::Coingecko(id) => coingecko.remove(id),
+                PriceSource::Chainlink(aggregator) => match self.chainlink(aggregator).await {
+                    Ok(quote) => Some(quote),
+                    Err(e) => {
+                        warn!("Chainlink price of {} unavailable: {}", feed.symbol, e);
+                        None
+                    }
+                },
+                PriceSource::Fixed(rate) => Some(Quote {
+                    usd_rate: rate.clone(),
+                    observed_at: Utc::now(),
+                }),
+            };
+            if let Some(quote) = quote {
+                recorded += self.record(feed, &quote).await?;
+            }
+        }
+        Ok(recorded)
+    }
+
+    async fn record(&self, feed: &PriceFeed, quote: &Quote) -> PaymentResult<u64> {
+        let recorded = sqlx::query(
+            r#"
+            INSERT INTO token_prices (token_address, symbol, usd_rate, source, observed_at)
+            VALUES ($1, $2, $3::NUMERIC, $4, $5)
+            ON CONFLICT (token_address, source, observed_at) DO NOTHING
+            "#,
+        )
+        .bind(&feed.token_address)
+        .bind(&feed.symbol)
+        .bind(&quote.usd_rate)
+        .bind(feed.source.as_str())
+        .bind(quote.observed_at)
+        .execute(self.db())
+        .await
+        .map_err(db_error)?;
+        Ok(recorded.rows_affected())
+    }
+
+    async fn coingecko(&self, ids: &[&str]) -> anyhow::Result<HashMap<String, Quote>> {
+        let base_url = self.config.coingecko_api_url.trim_end_matches('/');
+        let mut request = self.http.get(format!("{}/simple/price", base_url)).query(&[
+            ("ids", ids.join(",")),
+            ("vs_currencies", "usd".to_string()),
+            ("include_last_updated_at", "true".to_string()),
+        ]);
+        if let Some(key) = &self.config.coingecko_api_key {
+            // Paid plans have their own host and header
+            let header = if base_url.contains("pro-api") { "x-cg-pro-api-key" } else { "x-cg-demo-api-key" };
+            request = request.header(header, key);
+        }
+        let quotes: HashMap<String, CoingeckoQuote> = request.send().await?.error_for_status()?.json().await?;
+
+        Ok(quotes
+            .into_iter()
+            .filter_map(|(id, quote)| {
+                let usd_rate = plain_rate(&quote.usd.to_string())?;
+                let observed_at = quote
+                    .last_updated_at
+                    .and_then(|at| DateTime::from_timestamp(at, 0))
+                    .unwrap_or_else(Utc::now);
+                Some((id, Quote { usd_rate, observed_at }))
+            })
+            .collect())
+    }
+
+    async fn chainlink(&self, aggregator: &str) -> anyhow::Result<Quote> {
+        let aggregator: Address = aggregator.parse()?;
+        let feed = PriceAggregator::new(aggregator, self.service.provider().clone());
+        let decimals = feed.decimals().call().await?;
+        let (_, answer, _, updated_at, _) = feed.latest_round_data().call().await?;
+        if answer <= I256::zero() {
+            anyhow::bail!("aggregator {:?} answered {}", aggregator, answer);
+        }
+        let usd_rate = plain_rate(&format_amount(answer.into_raw(), decimals))
+            .ok_or_else(|| anyhow::anyhow!("aggregator {:?} answered {}", aggregator, answer))?;
+        let observed_at = DateTime::from_timestamp(updated_at.low_u64() as i64, 0).unwrap_or_else(Utc::now);
+        Ok(Quote { usd_rate, observed_at })
+    }
+
+    /// Value flows not yet valued that have a rate near their transaction
+    /// time, returning how many
+    pub async fn stamp(&self) -> PaymentResult<u64> {
+        let mut valued = 0;
+        for feed in &self.config.feeds {
+            for target in &VALUED {
+                let updated = sqlx::query(&format!(
+                    r#"
+                    WITH due AS (
+                        SELECT t.id, r.usd_rate, r.observed_at
+                        FROM {from}
+                        CROSS JOIN LATERAL (
+                            SELECT usd_rate, observed_at FROM token_prices
+                            WHERE LOWER(token_address) = LOWER($1)
+                              AND observed_at BETWEEN {at} - make_interval(secs => $3)
+                                                  AND {at} + make_interval(secs => $3)
+                            ORDER BY observed_at > {at}, ABS(EXTRACT(EPOCH FROM observed_at - {at}))
+                            LIMIT 1
+                        ) r
+                        WHERE t.usd_rate IS NULL AND {settled} AND {at} IS NOT NULL
+                          AND LOWER({token}) = LOWER($1)
+                        LIMIT $4
+                    )
+                    UPDATE {table} t
+                    SET usd_rate = due.usd_rate,
+                        usd_value = ROUND(t.amount / POWER(10::NUMERIC, $2) * due.usd_rate, 8),
+                        priced_at = due.observed_at
+                    FROM due
+                    WHERE t.id = due.id
+                    "#,
+                    from = target.from,
+                    at = target.at,
+                    settled = target.settled,
+                    token = target.token,
+                    table = target.table,
+                ))
+                .bind(&feed.token_address)
+                .bind(feed.decimals as i32)
+                .bind(self.config.max_age_seconds as f64)
+                .bind(STAMP_BATCH)
+                .execute(self.db())
+                .await
+                .map_err(db_error)?;
+                valued += updated.rows_affected();
+            }
+        }
+        if valued > 0 {
+            info!("Valued {} treasury flow(s) in {}", valued, CURRENCY);
+        }
+        Ok(valued)
+    }
+
+    /// The latest rate of each token
+    pub async fn latest(&self) -> PaymentResult<Vec<TokenPrice>> {
+        sqlx::query_as::<_, TokenPrice>(&format!(
+            r#"
+            SELECT DISTINCT ON (LOWER(token_address)) {}
+            FROM token_prices
+            ORDER BY LOWER(token_address), observed_at DESC
+            "#,
+            PRICE_COLUMNS
+        ))
+        .fetch_all(self.db())
+        .await
+        .map_err(db_error)
+    }
+
+    /// A token's rates, newest first
+    pub async fn history(&self, token_address: &str, limit: i64) -> PaymentResult<Vec<TokenPrice>> {
+        sqlx::query_as::<_, TokenPrice>(&format!(
+            r#"
+            SELECT {} FROM token_prices
+            WHERE LOWER(token_address) = LOWER($1)
+            ORDER BY observed_at DESC
+            LIMIT $2
+            "#,
+            PRICE_COLUMNS
+        ))
+        .bind(token_address)
+        .bind(limit)
+        .fetch_all(self.db())
+        .await
+        .map_err(db_error)
+    }
+
+    /// Funded deposits in, payments and payouts out, and fees accrued in
+    /// [from, to), by category and token
+    pub async fn treasury_report(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> PaymentResult<TreasuryReport> {
+        let mut flows = sqlx::query_as::<_, FlowTotal>(
+            r#"
+            WITH flows AS (
+                SELECT 'in' AS direction, p.payment_type AS category, p.token_address, p.amount, p.usd_value
+                FROM payments p
+                WHERE p.payment_type = 'bounty_deposit' AND p.created_at >= $1 AND p.created_at < $2
+                  AND EXISTS (SELECT 1 FROM escrow_accounts e WHERE e.bounty_id = p.bounty_id AND e.funded_at IS NOT NULL)
+                UNION ALL
+                SELECT 'out', p.payment_type, p.token_address, p.amount, p.usd_value
+                FROM payments p
+                WHERE p.status = 'completed' AND p.payment_type <> 'bounty_deposit'
+                  AND p.completed_at >= $1 AND p.completed_at < $2
+                UNION ALL
+                SELECT 'out', 'payout_' || i.kind, b.token_address, i.amount, i.usd_value
+                FROM payout_items i
+                JOIN payout_batches b ON b.id = i.batch_id
+                WHERE i.status = 'paid' AND i.paid_at >= $1 AND i.paid_at < $2
+                UNION ALL
+                SELECT 'fee', f.kind, f.token_address, f.amount, f.usd_value
+                FROM fee_ledger f
+                WHERE f.created_at >= $1 AND f.created_at < $2
+            )
+            SELECT direction, category, LOWER(token_address) AS token_address, COUNT(*) AS count,
+                   TRUNC(SUM(amount))::TEXT AS amount,
+                   COALESCE(SUM(usd_value), 0)::TEXT AS usd_value,
+                   COUNT(*) FILTER (WHERE usd_value IS NULL) AS unpriced
+            FROM flows
+            GROUP BY direction, category, LOWER(token_address)
+            ORDER BY direction, category, LOWER(token_address)
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .fetch_all(self.db())
+        .await
+        .map_err(db_error)?;
+
+        for flow in &mut flows {
+            flow.symbol = self.symbol(&flow.token_address).map(|(symbol, _)| symbol);
+            flow.amount_tokens = self.tokens_of(&flow.token_address, &flow.amount);
+        }
+        let total = |direction: &str| {
+            sum_usd(
+                flows
+                    .iter()
+                    .filter(|flow| flow.direction == direction)
+                    .map(|flow| flow.usd_value.as_str()),
+            )
+        };
+        Ok(TreasuryReport {
+            from,
+            to,
+            currency: CURRENCY,
+            inflow_usd: total("in"),
+            outflow_usd: total("out"),
+            fees_usd: total("fee"),
+            unpriced: flows.iter().map(|flow| flow.unpriced).sum(),
+            flows,
+        })
+    }
+
+    /// Rewards and slash redistributions paid to `address` in [from, to)
+    pub async fn earnings(&self, address: &str, from: DateTime<Utc>, to: DateTime<Utc>) -> PaymentResult<EarningsReport> {
+        if address.parse::<Address>().is_err() {
+            return Err(PaymentError::ValidationError(format!("{} is not an address", address)));
+        }
+        let mut lines = sqlx::query_as::<_, EarningsLine>(
+            r#"
+            SELECT at, category, bounty_id, token_address, TRUNC(amount)::TEXT AS amount,
+                   usd_rate::TEXT AS usd_rate, usd_value::TEXT AS usd_value, tx_hash
+            FROM (
+                SELECT i.paid_at AS at, 'reward' AS category, b.bounty_id, b.token_address, i.amount,
+                       i.usd_rate, i.usd_value, COALESCE(o.mined_tx_hash, o.tx_hash) AS tx_hash
+                FROM payout_items i
+                JOIN payout_batches b ON b.id = i.batch_id
+                LEFT JOIN outgoing_transactions o ON o.id = i.outgoing_id
+                WHERE i.kind = 'reward' AND i.status = 'paid' AND LOWER(i.recipient_address) = LOWER($1)
+                  AND i.paid_at >= $2 AND i.paid_at < $3
+                UNION ALL
+                SELECT p.completed_at, p.payment_type, p.bounty_id, p.token_address, p.amount,
+                       p.usd_rate, p.usd_value, p.transaction_hash
+                FROM payments p
+                WHERE p.payment_type = 'slash_redistribution' AND p.status = 'completed'
+                  AND LOWER(p.recipient_address) = LOWER($1) AND p.completed_at >= $2 AND p.completed_at < $3
+            ) earned
+            ORDER BY at
+            "#,
+        )
+        .bind(address)
+        .bind(from)
+        .bind(to)
+        .fetch_all(self.db())
+        .await
+        .map_err(db_error)?;
+
+        for line in &mut lines {
+            line.symbol = self.symbol(&line.token_address).map(|(symbol, _)| symbol);
+            line.amount_tokens = self.tokens_of(&line.token_address, &line.amount);
+        }
+        Ok(EarningsReport {
+            address: address.to_string(),
+            from,
+            to,
+            currency: CURRENCY,
+            total_usd: sum_usd(lines.iter().filter_map(|line| line.usd_value.as_deref())),
+            unpriced: lines.iter().filter(|line| line.usd_value.is_none()).count() as i64,
+            lines,
+        })
+    }
+}
+
+impl TreasuryReport {
+    pub fn to_csv(&self) -> String {
+        let mut csv = TREASURY_CSV_HEADER.to_string();
+        for flow in &self.flows {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{},{}\n",
+                csv_field(&flow.direction),
+                csv_field(&flow.category),
+                csv_field(&flow.token_address),
+                csv_field(&optional(&flow.symbol)),
+                flow.count,
+                flow.amount,
+                optional(&flow.amount_tokens),
+                flow.usd_value,
+                flow.unpriced,
+            ));
+        }
+        csv
+    }
+}
+
+impl EarningsReport {
+    pub fn to_csv(&self) -> String {
+        let mut csv = EARNINGS_CSV_HEADER.to_string();
+        for line in &self.lines {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{},{},{}\n",
+                line.at.to_rfc3339(),
+                csv_field(&line.category),
+                optional(&line.bounty_id),
+                csv_field(&line.token_address),
+                csv_field(&optional(&line.symbol)),
+                line.amount,
+                optional(&line.amount_tokens),
+                optional(&line.usd_rate),
+                optional(&line.usd_value),
+                csv_field(&optional(&line.tx_hash)),
+            ));
+        }
+        csv
+    }
+}
@@ -0,0 +1,633 @@
+// Balance reconciliation
+//
+// Each run compares three views of every tracked wallet: the chain (source of
+// truth), the cached balance in `wallet_balances`, and the ledger, i.e. the
+// net of confirmed `payment_transactions`. It also collects transactions whose
+// record disagrees with the chain or with what the indexer reported. Runs are
+// persisted so admins can page through them, and a run whose findings reach
+// the configured severity raises a `SystemAlert`.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use ethers::types::{I256, U256};
+use serde::Serialize;
+use shared::messaging::event_types::{AlertSeverity, NexusEvent, SystemAlertEvent};
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::config::ReconciliationConfig;
+use crate::services::payment_service::PaymentService;
+
+/// Most wallets and settled transactions checked per run
+const WALLET_BATCH: i64 = 100;
+const TRANSACTION_BATCH: i64 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Ok,
+    Info,
+    Warning,
+    Critical,
+}
+
+impl Severity {
+    const ALL: [Severity; 4] = [Self::Ok, Self::Info, Self::Warning, Self::Critical];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Critical => "critical",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.as_str() == value)
+    }
+
+    /// This severity and every higher one, for `severity = ANY($n)` filters
+    pub fn and_above(self) -> Vec<&'static str> {
+        Self::ALL
+            .into_iter()
+            .filter(|s| *s >= self)
+            .map(Self::as_str)
+            .collect()
+    }
+
+    fn alert_severity(self) -> AlertSeverity {
+        match self {
+            Self::Ok | Self::Info => AlertSeverity::Info,
+            Self::Warning => AlertSeverity::Warning,
+            Self::Critical => AlertSeverity::Critical,
+        }
+    }
+}
+
+/// Maps the size of a balance difference to a severity
+#[derive(Debug, Clone)]
+pub struct DriftThresholds {
+    pub warning: U256,
+    pub critical: U256,
+}
+
+impl DriftThresholds {
+    pub fn classify(&self, delta: I256) -> Severity {
+        let magnitude = delta.unsigned_abs();
+        if magnitude.is_zero() {
+            Severity::Ok
+        } else if magnitude < self.warning {
+            Severity::Info
+        } else if magnitude < self.critical {
+            Severity::Warning
+        } else {
+            Severity::Critical
+        }
+    }
+}
+
+/// One tracked wallet as seen by the chain, the DB and the ledger
+#[derive(Debug, Clone)]
+struct WalletCheck {
+    address: String,
+    chain_balance: U256,
+    db_balance: U256,
+    ledger_balance: I256,
+    db_delta: I256,
+    ledger_delta: I256,
+    severity: Severity,
+}
+
+/// A transaction whose record disagrees with the chain or the indexer
+#[derive(Debug, Clone)]
+struct UnmatchedTx {
+    tx_hash: String,
+    kind: &'static str,
+    db_status: Option<String>,
+    chain_status: Option<String>,
+    detail: Option<String>,
+    severity: Severity,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ReconciliationRun {
+    pub id: Uuid,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub wallets_checked: i32,
+    pub wallets_failed: i32,
+    pub wallet_mismatches: i32,
+    pub unmatched_transactions: i32,
+    pub total_ledger_drift: String,
+    pub severity: String,
+    pub alerted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct WalletDelta {
+    pub address: String,
+    pub chain_balance: String,
+    pub db_balance: String,
+    pub ledger_balance: String,
+    pub db_delta: String,
+    pub ledger_delta: String,
+    pub severity: String,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct UnmatchedTransaction {
+    pub tx_hash: String,
+    pub kind: String,
+    pub db_status: Option<String>,
+    pub chain_status: Option<String>,
+    pub detail: Option<String>,
+    pub severity: String,
+}
+
+#[derive(sqlx::FromRow)]
+struct SettledTx {
+    transaction_hash: String,
+    status: String,
+}
+
+#[derive(sqlx::FromRow)]
+struct FlaggedIndexerEvent {
+    tx_hash: String,
+    outcome: String,
+    reported_block: Option<i64>,
+}
+
+pub struct Reconciler {
+    service: Arc<PaymentService>,
+    thresholds: DriftThresholds,
+    alert_min_severity: Severity,
+    alert_cooldown_seconds: u64,
+    interval_seconds: u64,
+}
+
+impl Reconciler {
+    pub fn new(service: Arc<PaymentService>, config: &ReconciliationConfig) -> Result<Self> {
+        let thresholds = DriftThresholds {
+            warning: U256::from_dec_str(&config.warning_drift_wei)
+                .context("Invalid RECONCILIATION_WARNING_DRIFT_WEI")?,
+            critical: U256::from_dec_str(&config.critical_drift_wei)
+                .context("Invalid RECONCILIATION_CRITICAL_DRIFT_WEI")?,
+        };
+        let alert_min_severity = Severity::parse(&config.alert_min_severity)
+            .filter(|s| *s != Severity::Ok)
+            .ok_or_else(|| anyhow!("Invalid RECONCILIATION_ALERT_MIN_SEVERITY"))?;
+
+        Ok(Self {
+            service,
+            thresholds,
+            alert_min_severity,
+            alert_cooldown_seconds: config.alert_cooldown_seconds,
+            interval_seconds: config.interval_seconds,
+        })
+    }
+
+    pub fn interval_seconds(&self) -> u64 {
+        self.interval_seconds
+    }
+
+    fn db(&self) -> &PgPool {
+        self.service.db_pool()
+    }
+
+    /// Run one reconciliation pass and persist its findings
+    pub async fn run(&self) -> Result<ReconciliationRun> {
+        let (run_id, started_at): (Uuid, DateTime<Utc>) = sqlx::query_as(
+            "INSERT INTO reconciliation_runs DEFAULT VALUES RETURNING id, started_at",
+        )
+        .fetch_one(self.db())
+        .await
+        .context("Failed to start reconciliation run")?;
+
+        // Transactions and indexer events are only examined once: everything
+        // since the previous completed run, or one interval on the first run
+        let since: DateTime<Utc> = sqlx::query_scalar::<_, Option<DateTime<Utc>>>(
+            "SELECT MAX(started_at) FROM reconciliation_runs WHERE completed_at IS NOT NULL",
+        )
+        .fetch_one(self.db())
+        .await
+        .context("Failed to find previous reconciliation run")?
+        .unwrap_or_else(|| started_at - chrono::Duration::seconds(self.interval_seconds as i64));
+
+        let (wallets, wallets_failed) = self.check_wallets().await?;
+        let unmatched = self.find_unmatched(since).await?;
+
+        let mut tx = self.db().begin().await?;
+        for wallet in &wallets {
+            sqlx::query(
+                r#"
+                INSERT INTO reconciliation_wallet_deltas
+                    (run_id, address, chain_balance, db_balance, ledger_balance, db_delta, ledger_delta, severity)
+                VALUES ($1, $2, $3::NUMERIC, $4::NUMERIC, $5::NUMERIC, $6::NUMERIC, $7::NUMERIC, $8)
+                "#,
+            )
+            .bind(run_id)
+            .bind(&wallet.address)
+            .bind(wallet.chain_balance.to_string())
+            .bind(wallet.db_balance.to_string())
+            .bind(wallet.ledger_balance.to_string())
+            .bind(wallet.db_delta.to_string())
+            .bind(wallet.ledger_delta.to_string())
+            .bind(wallet.severity.as_str())
+            .execute(&mut *tx)
+            .await
+            .context("Failed to record wallet delta")?;
+        }
+        for finding in &unmatched {
+            sqlx::query(
+                r#"
+                INSERT INTO reconciliation_unmatched_transactions
+                    (run_id, tx_hash, kind, db_status, chain_status, detail, severity)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                "#,
+            )
+            .bind(run_id)
+            .bind(&finding.tx_hash)
+            .bind(finding.kind)
+            .bind(&finding.db_status)
+            .bind(&finding.chain_status)
+            .bind(&finding.detail)
+            .bind(finding.severity.as_str())
+            .execute(&mut *tx)
+            .await
+            .context("Failed to record unmatched transaction")?;
+        }
+
+        let total_ledger_drift = wallets
+            .iter()
+            .fold(U256::zero(), |total, w| total.saturating_add(w.ledger_delta.unsigned_abs()));
+        let severity = wallets
+            .iter()
+            .map(|w| w.severity)
+            .chain(unmatched.iter().map(|u| u.severity))
+            .max()
+            .unwrap_or(Severity::Ok);
+        let wallet_mismatches = wallets.iter().filter(|w| w.severity != Severity::Ok).count();
+
+        let run = sqlx::query_as::<_, ReconciliationRun>(
+            r#"
+            UPDATE reconciliation_runs
+            SET completed_at = NOW(), wallets_checked = $2, wallets_failed = $3,
+                wallet_mismatches = $4, unmatched_transactions = $5,
+                total_ledger_drift = $6::NUMERIC, severity = $7
+            WHERE id = $1
+            RETURNING id, started_at, completed_at, wallets_checked, wallets_failed,
+                      wallet_mismatches, unmatched_transactions,
+                      total_ledger_drift::TEXT AS total_ledger_drift, severity, alerted_at
+            "#,
+        )
+        .bind(run_id)
+        .bind(wallets.len() as i32)
+        .bind(wallets_failed as i32)
+        .bind(wallet_mismatches as i32)
+        .bind(unmatched.len() as i32)
+        .bind(total_ledger_drift.to_string())
+        .bind(severity.as_str())
+        .fetch_one(&mut *tx)
+        .await
+        .context("Failed to complete reconciliation run")?;
+        tx.commit().await?;
+
+        if severity >= self.alert_min_severity {
+            if let Err(e) = self.alert(&run, severity).await {
+                warn!("Failed to alert on reconciliation run {}: {:#}", run.id, e);
+            }
+        }
+
+        Ok(run)
+    }
+
+    /// Compare every tracked wallet; the DB copy is corrected to the chain
+    /// value once the difference is recorded. Returns the checks and the
+    /// number of wallets whose chain balance could not be read.
+    async fn check_wallets(&self) -> Result<(Vec<WalletCheck>, usize)> {
+        let addresses = sqlx::query_as::<_, (String, String)>(
+            "SELECT address, balance FROM wallet_balances WHERE tracked = true ORDER BY last_reconciled_at ASC NULLS FIRST LIMIT $1",
+        )
+        .bind(WALLET_BATCH)
+        .fetch_all(self.db())
+        .await
+        .context("Failed to query tracked wallets")?;
+
+        let mut checks = Vec::with_capacity(addresses.len());
+        let mut failed = 0;
+        for (address, db_balance) in addresses {
+            let chain_balance = match self.service.get_token_balance(&address).await {
+                Ok(balance) => balance,
+                Err(e) => {
+                    warn!("Failed to get on-chain balance for {}: {}", address, e);
+                    failed += 1;
+                    continue;
+                }
+            };
+            let db_balance = U256::from_dec_str(&db_balance).unwrap_or_default();
+            let ledger_balance = self.ledger_balance(&address).await?;
+
+            let chain = I256::try_from(chain_balance).unwrap_or(I256::MAX);
+            let db_delta = chain.saturating_sub(I256::try_from(db_balance).unwrap_or(I256::MAX));
+            let ledger_delta = chain.saturating_sub(ledger_balance);
+            let severity = self
+                .thresholds
+                .classify(db_delta)
+                .max(self.thresholds.classify(ledger_delta));
+
+            if chain_balance != db_balance {
+                warn!(
+                    "Balance mismatch for {}: on-chain={}, db={}",
+                    address, chain_balance, db_balance
+                );
+            }
+            sqlx::query(
+                "UPDATE wallet_balances SET balance = $1, last_reconciled_at = NOW(), updated_at = NOW() WHERE address = $2",
+            )
+            .bind(chain_balance.to_string())
+            .bind(&address)
+            .execute(self.db())
+            .await
+            .context("Failed to update wallet balance")?;
+
+            checks.push(WalletCheck {
+                address,
+                chain_balance,
+                db_balance,
+                ledger_balance,
+                db_delta,
+                ledger_delta,
+                severity,
+            });
+        }
+
+        Ok((checks, failed))
+    }
+
+    /// Net of every confirmed transfer into and out of the wallet
+    async fn ledger_balance(&self, address: &str) -> Result<I256> {
+        let net: String = sqlx::query_scalar(
+            r#"
+            SELECT TRUNC(
+                COALESCE(SUM(value) FILTER (WHERE LOWER(to_address) = $1), 0)
+                - COALESCE(SUM(value) FILTER (WHERE LOWER(from_address) = $1), 0)
+            )::TEXT
+            FROM payment_transactions
+            WHERE status = 'confirmed'
+              AND (LOWER(to_address) = $1 OR LOWER(from_address) = $1)
+            "#,
+        )
+        .bind(address.to_lowercase())
+        .fetch_one(self.db())
+        .await
+        .context("Failed to compute ledger balance")?;
+
+        I256::from_dec_str(&net).map_err(|e| anyhow!("Invalid ledger balance {}: {}", net, e))
+    }
+
+    async fn find_unmatched(&self, since: DateTime<Utc>) -> Result<Vec<UnmatchedTx>> {
+        let mut unmatched = Vec::new();
+
+        // Settled in the DB: the receipt must exist and agree
+        let settled = sqlx::query_as::<_, SettledTx>(
+            r#"
+            SELECT transaction_hash, status FROM payment_transactions
+            WHERE status IN ('confirmed', 'failed') AND confirmed_at >= $1
+            ORDER BY confirmed_at ASC
+            LIMIT $2
+            "#,
+        )
+        .bind(since)
+        .bind(TRANSACTION_BATCH)
+        .fetch_all(self.db())
+        .await
+        .context("Failed to query settled transactions")?;
+
+        for tx in settled {
+            let receipt = match self.service.get_tx_receipt(&tx.transaction_hash).await {
+                Ok(receipt) => receipt,
+                Err(e) => {
+                    warn!("Failed to get receipt for {}: {}", tx.transaction_hash, e);
+                    continue;
+                }
+            };
+            let chain_status = receipt.map(|r| {
+                if r.status == Some(1.into()) { "confirmed" } else { "failed" }
+            });
+            let kind = match chain_status {
+                None => "missing_on_chain",
+                Some(status) if status != tx.status => "status_mismatch",
+                Some(_) => continue,
+            };
+            unmatched.push(UnmatchedTx {
+                tx_hash: tx.transaction_hash,
+                kind,
+                db_status: Some(tx.status),
+                chain_status: chain_status.map(str::to_string),
+                detail: None,
+                severity: Severity::Critical,
+            });
+        }
+
+        // Pending for longer than a transaction may take
+        let stale = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT transaction_hash FROM payment_transactions
+            WHERE status = 'pending' AND created_at < NOW() - make_interval(secs => $1)
+            ORDER BY created_at ASC
+            LIMIT $2
+            "#,
+        )
+        .bind(self.service.config().payment.transaction_timeout_seconds as f64)
+        .bind(TRANSACTION_BATCH)
+        .fetch_all(self.db())
+        .await
+        .context("Failed to query stale transactions")?;
+
+        unmatched.extend(stale.into_iter().map(|tx_hash| UnmatchedTx {
+            tx_hash,
+            kind: "stale_pending",
+            db_status: Some("pending".to_string()),
+            chain_status: None,
+            detail: None,
+            severity: Severity::Warning,
+        }));
+
+        // Indexer reports that did not line up with our records
+        let flagged = sqlx::query_as::<_, FlaggedIndexerEvent>(
+            r#"
+            SELECT tx_hash, outcome, reported_block FROM indexer_events
+            WHERE outcome IN ('untracked', 'block_mismatch') AND received_at >= $1
+            ORDER BY received_at ASC
+            LIMIT $2
+            "#,
+        )
+        .bind(since)
+        .bind(TRANSACTION_BATCH)
+        .fetch_all(self.db())
+        .await
+        .context("Failed to query indexer events")?;
+
+        unmatched.extend(flagged.into_iter().map(|event| {
+            let untracked = event.outcome == "untracked";
+            UnmatchedTx {
+                tx_hash: event.tx_hash,
+                kind: if untracked { "untracked" } else { "block_mismatch" },
+                db_status: None,
+                chain_status: None,
+                detail: event.reported_block.map(|b| format!("Indexer reported block {}", b)),
+                severity: if untracked { Severity::Info } else { Severity::Warning },
+            }
+        }));
+
+        Ok(unmatched)
+    }
+
+    /// Publish a `SystemAlert` unless a run at the same or a higher severity
+    /// already alerted within the cooldown
+    async fn alert(&self, run: &ReconciliationRun, severity: Severity) -> Result<()> {
+        let recent: Vec<String> = sqlx::query_scalar(
+            "SELECT severity FROM reconciliation_runs WHERE alerted_at > NOW() - make_interval(secs => $1)",
+        )
+        .bind(self.alert_cooldown_seconds as f64)
+        .fetch_all(self.db())
+        .await?;
+        if recent.iter().filter_map(|s| Severity::parse(s)).any(|s| s >= severity) {
+            return Ok(());
+        }
+
+        let event = NexusEvent::SystemAlert(SystemAlertEvent {
+            alert_id: Uuid::new_v4(),
+            severity: severity.alert_severity(),
+            title: format!("Payment reconciliation drift ({})", severity.as_str()),
+            message: format!(
+                "Reconciliation run {} found {} mismatched wallet(s), {} unmatched transaction(s) and {} wei of ledger drift",
+                run.id, run.wallet_mismatches, run.unmatched_transactions, run.total_ledger_drift
+            ),
+            affected_services: vec!["payment-service".to_string()],
+            created_at: Utc::now(),
+        });
+        let redis_client = redis::Client::open(self.service.config().redis.url.clone())?;
+        shared::messaging::publish_event(&redis_client, &event).await?;
+
+        sqlx::query("UPDATE reconciliation_runs SET alerted_at = NOW() WHERE id = $1")
+            .bind(run.id)
+            .execute(self.db())
+            .await?;
+        info!("Alerted admins about reconciliation run {}", run.id);
+        Ok(())
+    }
+}
+
+// ─── Reports ───
+
+const RUN_COLUMNS: &str = "id, started_at, completed_at, wallets_checked, wallets_failed, \
+     wallet_mismatches, unmatched_transactions, total_ledger_drift::TEXT AS total_ledger_drift, \
+     severity, alerted_at";
+
+/// Runs at or above `min_severity`, newest first, with the total count
+pub async fn list_runs(
+    db: &PgPool,
+    min_severity: Severity,
+    limit: i64,
+    offset: i64,
+) -> Result<(Vec<ReconciliationRun>, i64)> {
+    let severities = min_severity.and_above();
+    let runs = sqlx::query_as::<_, ReconciliationRun>(&format!(
+        "SELECT {} FROM reconciliation_runs WHERE severity = ANY($1) ORDER BY started_at DESC LIMIT $2 OFFSET $3",
+        RUN_COLUMNS
+    ))
+    .bind(&severities)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(db)
+    .await?;
+    let total = sqlx::query_scalar("SELECT COUNT(*) FROM reconciliation_runs WHERE severity = ANY($1)")
+        .bind(&severities)
+        .fetch_one(db)
+        .await?;
+    Ok((runs, total))
+}
+
+pub async fn get_run(db: &PgPool, run_id: Uuid) -> Result<Option<ReconciliationRun>> {
+    let run = sqlx::query_as::<_, ReconciliationRun>(&format!(
+        "SELECT {} FROM reconciliation_runs WHERE id = $1",
+        RUN_COLUMNS
+    ))
+    .bind(run_id)
+    .fetch_optional(db)
+    .await?;
+    Ok(run)
+}
+
+/// Wallet deltas of a run at or above `min_severity`, worst first
+pub async fn list_wallet_deltas(
+    db: &PgPool,
+    run_id: Uuid,
+    min_severity: Severity,
+    limit: i64,
+    offset: i64,
+) -> Result<(Vec<WalletDelta>, i64)> {
+    let severities = min_severity.and_above();
+    let deltas = sqlx::query_as::<_, WalletDelta>(
+        r#"
+        SELECT address, chain_balance::TEXT AS chain_balance, db_balance::TEXT AS db_balance,
+               ledger_balance::TEXT AS ledger_balance, db_delta::TEXT AS db_delta,
+               ledger_delta::TEXT AS ledger_delta, severity
+        FROM reconciliation_wallet_deltas
+        WHERE run_id = $1 AND severity = ANY($2)
+        ORDER BY ABS(ledger_delta) + ABS(db_delta) DESC, address ASC
+        LIMIT $3 OFFSET $4
+        "#,
+    )
+    .bind(run_id)
+    .bind(&severities)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(db)
+    .await?;
+    let total = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM reconciliation_wallet_deltas WHERE run_id = $1 AND severity = ANY($2)",
+    )
+    .bind(run_id)
+    .bind(&severities)
+    .fetch_one(db)
+    .await?;
+    Ok((deltas, total))
+}
+
+/// Unmatched transactions of a run at or above `min_severity`
+pub async fn list_unmatched_transactions(
+    db: &PgPool,
+    run_id: Uuid,
+    min_severity: Severity,
+    limit: i64,
+    offset: i64,
+) -> Result<(Vec<UnmatchedTransaction>, i64)> {
+    let severities = min_severity.and_above();
+    let transactions = sqlx::query_as::<_, UnmatchedTransaction>(
+        r#"
+        SELECT tx_hash, kind, db_status, chain_status, detail, severity
+        FROM reconciliation_unmatched_transactions
+        WHERE run_id = $1 AND severity = ANY($2)
+        ORDER BY created_at ASC, tx_hash ASC
+        LIMIT $3 OFFSET $4
+        "#,
+    )
+    .bind(run_id)
+    .bind(&severities)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(db)
+    .await?;
+    let total = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM reconciliation_unmatched_transactions WHERE run_id = $1 AND severity = ANY($2)",
+    )
+    .bind(run_id)
+    .bind(&severities)
+    .fetch_one(db)
+    .await?;
+    Ok((transactions, total))
+}
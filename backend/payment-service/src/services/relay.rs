@@ -0,0 +1,584 @@
+// Gasless claims
+//
+// An analyst whose rewards sit at an address holding no ETH signs an
+// EIP-2612 permit letting the treasury spend the claim, and the treasury
+// relays it: the permit, the net amount to one of the analyst's allowlisted
+// withdrawal addresses, and the relay fee back to the treasury go out at
+// consecutive nonces through the nonce manager, so the treasury pays the gas.
+// The fee is `fee_percentage` of the claim, and at least `min_fee` whole
+// tokens, and is recorded in the fee ledger once both transfers are mined.
+//
+// The permit is checked against the token's own domain separator and the
+// owner's current permit nonce before anything is sent, and each owner
+// address may have `max_claims_per_day` claims relayed over any 24 hours,
+// whether or not they succeed: every relay costs the treasury gas.
+
+use chrono::{DateTime, TimeZone, Utc};
+use ethers::abi::{self, Token};
+use ethers::types::{Address, Signature, H256, U256};
+use ethers::utils::keccak256;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use shared::messaging::PaymentEventKind;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::blockchain::{PermitToken, TokenContract, TransactionBuilder};
+use crate::config::{RelayConfig, RewardToken};
+use crate::models::{PaymentError, PaymentResult};
+use crate::services::fees::{self, RELAY_FEE};
+use crate::services::nonces::NonceManager;
+use crate::services::payment_events::{self, NewPaymentEvent};
+use crate::services::payment_service::PaymentService;
+use crate::services::tokens;
+use crate::services::withdrawals::{check_allowlisted, check_owned_wallet};
+
+pub const SENDING: &str = "sending";
+pub const SENT: &str = "sent";
+pub const COMPLETED: &str = "completed";
+pub const FAILED: &str = "failed";
+
+const PERMIT_TYPEHASH: &str = "Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)";
+
+/// How long a quoted permit stays valid, unless permits must outlive it
+const QUOTE_VALIDITY_SECONDS: i64 = 3600;
+
+const CLAIM_COLUMNS: &str = "id, user_id, owner_address, to_address, token_address, amount::TEXT AS amount, \
+                             fee_amount::TEXT AS fee_amount, net_amount::TEXT AS net_amount, \
+                             permit_nonce::TEXT AS permit_nonce, deadline, status, permit_tx_id, transfer_tx_id, \
+                             fee_tx_id, error, created_at, updated_at, completed_at";
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct RelayClaim {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// Address the rewards are claimed from, which signed the permit
+    pub owner_address: String,
+    pub to_address: String,
+    pub token_address: String,
+    /// In base units of the token
+    pub amount: String,
+    pub fee_amount: String,
+    pub net_amount: String,
+    pub permit_nonce: String,
+    pub deadline: DateTime<Utc>,
+    pub status: String,
+    pub permit_tx_id: Option<Uuid>,
+    pub transfer_tx_id: Option<Uuid>,
+    pub fee_tx_id: Option<Uuid>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PermitQuery {
+    pub owner_address: String,
+    /// In base units of the token
+    pub amount: Decimal,
+    /// Defaults to the platform token
+    pub token_address: Option<String>,
+}
+
+/// What the owner signs for a claim, as EIP-712 typed data
+#[derive(Debug, Serialize)]
+pub struct PermitQuote {
+    pub token: RewardToken,
+    pub owner_address: String,
+    /// The treasury
+    pub spender_address: String,
+    pub amount: String,
+    pub fee_amount: String,
+    pub net_amount: String,
+    pub nonce: String,
+    /// Unix seconds
+    pub deadline: i64,
+    /// The token's, which the signature is checked against
+    pub domain_separator: String,
+    /// Ready for `eth_signTypedData_v4`. The domain assumes version "1";
+    /// a token with another version shows up as a domain separator mismatch.
+    pub typed_data: Value,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RelayClaimRequest {
+    /// One of the caller's registered wallets
+    pub owner_address: String,
+    /// Must be on the user's withdrawal allowlist
+    pub to_address: String,
+    /// Defaults to the platform token
+    pub token_address: Option<String>,
+    /// In base units of the token; the permit's value
+    pub amount: Decimal,
+    /// The permit's deadline, in unix seconds
+    pub deadline: i64,
+    /// 65-byte permit signature, hex encoded
+    pub signature: String,
+}
+
+#[derive(sqlx::FromRow)]
+struct SentClaim {
+    id: Uuid,
+    user_id: Uuid,
+    to_address: String,
+    token_address: String,
+    net_amount: String,
+    fee_amount: String,
+    permit_status: String,
+    transfer_status: String,
+    fee_status: String,
+    transfer_hash: String,
+}
+
+fn db_error(e: sqlx::Error) -> PaymentError {
+    PaymentError::DatabaseError(e.to_string())
+}
+
+fn chain_error(e: impl std::fmt::Display) -> PaymentError {
+    PaymentError::BlockchainError(e.to_string())
+}
+
+fn parse_address(value: &str, field: &str) -> PaymentResult<Address> {
+    value
+        .parse()
+        .map_err(|_| PaymentError::ValidationError(format!("{} is not an address", field)))
+}
+
+/// EIP-712 digest of a permit under the token's domain separator
+fn permit_digest(domain_separator: [u8; 32], owner: Address, spender: Address, value: U256, nonce: U256, deadline: U256) -> H256 {
+    let permit = abi::encode(&[
+        Token::FixedBytes(keccak256(PERMIT_TYPEHASH).to_vec()),
+        Token::Address(owner),
+        Token::Address(spender),
+        Token::Uint(value),
+        Token::Uint(nonce),
+        Token::Uint(deadline),
+    ]);
+    let mut message = Vec::with_capacity(66);
+    message.extend_from_slice(b"\x19\x01");
+    message.extend_from_slice(&domain_separator);
+    message.extend_from_slice(&keccak256(permit));
+    H256::from(keccak256(message))
+}
+
+pub struct RelayService {
+    service: Arc<PaymentService>,
+    nonces: Arc<NonceManager>,
+    config: RelayConfig,
+}
+
+impl RelayService {
+    pub fn new(service: Arc<PaymentService>, nonces: Arc<NonceManager>) -> Self {
+        let config = service.config().relay.clone();
+        Self { service, nonces, config }
+    }
+
+    pub fn interval_seconds(&self) -> u64 {
+        self.config.monitor_interval_seconds
+    }
+
+    fn db(&self) -> &PgPool {
+        self.service.db_pool()
+    }
+
+    fn ensure_enabled(&self) -> PaymentResult<()> {
+        if self.config.enabled {
+            Ok(())
+        } else {
+            Err(PaymentError::NotPermitted("Gasless claims are disabled".to_string()))
+        }
+    }
+
+    fn treasury(&self) -> PaymentResult<Address> {
+        self.service
+            .config()
+            .blockchain
+            .treasury_address
+            .parse()
+            .map_err(|_| PaymentError::ConfigError("Treasury address is invalid".to_string()))
+    }
+
+    /// The relay fee on `amount`: the percentage, but at least the minimum
+    fn fee(&self, token: &RewardToken, amount: U256) -> PaymentResult<U256> {
+        let min_fee = self
+            .config
+            .min_fee
+            .parse::<Decimal>()
+            .ok()
+            .and_then(|fee| fee.checked_mul(Decimal::from(10u64.pow(token.decimals.min(19) as u32))))
+            .and_then(|fee| U256::from_dec_str(&fee.trunc().to_string()).ok())
+            .ok_or_else(|| PaymentError::ConfigError(format!("{} is not a relay fee", self.config.min_fee)))?;
+        Ok(fees::fee_of(amount, self.config.fee_percentage).max(min_fee))
+    }
+
+    /// The fee and net amount of a claim, refusing one the fee would consume
+    fn split(&self, token: &RewardToken, amount: U256) -> PaymentResult<(U256, U256)> {
+        let fee = self.fee(token, amount)?;
+        if amount <= fee {
+            return Err(PaymentError::ValidationError(format!(
+                "Claims must exceed the relay fee of {} {}",
+                tokens::format_amount(fee, token.decimals),
+                token.symbol
+            )));
+        }
+        Ok((fee, amount - fee))
+    }
+
+    /// The permit the owner must sign to claim `amount` through the relay
+    pub async fn quote(&self, query: &PermitQuery) -> PaymentResult<PermitQuote> {
+        self.ensure_enabled()?;
+        let token = tokens::resolve(&self.service, query.token_address.as_deref())?;
+        let amount = tokens::base_units(query.amount)?;
+        let owner = parse_address(&query.owner_address, "owner_address")?;
+        let (fee, net) = self.split(token, amount)?;
+        let treasury = self.treasury()?;
+
+        let permit = PermitToken::new(parse_address(&token.address, "token_address")?, self.service.provider().clone());
+        let name = permit.name().call().await.map_err(chain_error)?;
+        let nonce = permit.nonces(owner).call().await.map_err(chain_error)?;
+        let domain_separator = permit.domain_separator().call().await.map_err(chain_error)?;
+        let deadline = Utc::now().timestamp() + QUOTE_VALIDITY_SECONDS.max(2 * self.config.min_deadline_seconds);
+
+        let typed_data = json!({
+            "types": {
+                "EIP712Domain": [
+                    {"name": "name", "type": "string"},
+                    {"name": "version", "type": "string"},
+                    {"name": "chainId", "type": "uint256"},
+                    {"name": "verifyingContract", "type": "address"},
+                ],
+                "Permit": [
+                    {"name": "owner", "type": "address"},
+                    {"name": "spender", "type": "address"},
+                    {"name": "value", "type": "uint256"},
+                    {"name": "nonce", "type": "uint256"},
+                    {"name": "deadline", "type": "uint256"},
+                ],
+            },
+            "primaryType": "Permit",
+            "domain": {
+                "name": name,
+                "version": "1",
+                "chainId": self.service.config().blockchain.chain_id,
+                "verifyingContract": token.address,
+            },
+            "message": {
+                "owner": format!("{:?}", owner),
+                "spender": format!("{:?}", treasury),
+                "value": amount.to_string(),
+                "nonce": nonce.to_string(),
+                "deadline": deadline.to_string(),
+            },
+        });
+
+        Ok(PermitQuote {
+            token: token.clone(),
+            owner_address: format!("{:?}", owner),
+            spender_address: format!("{:?}", treasury),
+            amount: amount.to_string(),
+            fee_amount: fee.to_string(),
+            net_amount: net.to_string(),
+            nonce: nonce.to_string(),
+            deadline,
+            domain_separator: format!("{:?}", H256::from(domain_separator)),
+            typed_data,
+        })
+    }
+
+    /// Relay a signed permit: checked, recorded and sent, or refused. The
+    /// claim completes once the transfers are mined.
+    /// Relay a claim for `user_id`, the authenticated caller
+    pub async fn claim(&self, user_id: Uuid, req: &RelayClaimRequest) -> PaymentResult<RelayClaim> {
+        self.ensure_enabled()?;
+        let token = tokens::resolve(&self.service, req.token_address.as_deref())?;
+        let amount = tokens::base_units(req.amount)?;
+        let owner = parse_address(&req.owner_address, "owner_address")?;
+        let to = parse_address(&req.to_address, "to_address")?;
+        let token_address = parse_address(&token.address, "token_address")?;
+        let (fee, net) = self.split(token, amount)?;
+        let treasury = self.treasury()?;
+
+        let now = Utc::now().timestamp();
+        if req.deadline - now < self.config.min_deadline_seconds {
+            return Err(PaymentError::ValidationError(format!(
+                "Permits must stay valid for at least {} seconds",
+                self.config.min_deadline_seconds
+            )));
+        }
+        let deadline = Utc
+            .timestamp_opt(req.deadline, 0)
+            .single()
+            .ok_or_else(|| PaymentError::ValidationError("deadline is not a time".to_string()))?;
+        let signature: Signature = req
+            .signature
+            .parse()
+            .map_err(|_| PaymentError::ValidationError("signature is not a 65-byte hex signature".to_string()))?;
+
+        let provider = self.service.provider().clone();
+        let permit = PermitToken::new(token_address, provider.clone());
+        let nonce = permit.nonces(owner).call().await.map_err(chain_error)?;
+        let domain_separator = permit.domain_separator().call().await.map_err(chain_error)?;
+        let digest = permit_digest(domain_separator, owner, treasury, amount, nonce, U256::from(req.deadline));
+        if signature.recover(digest).ok() != Some(owner) {
+            return Err(PaymentError::NotPermitted(format!(
+                "signature is not a permit by {} for this claim",
+                req.owner_address
+            )));
+        }
+
+        let balance = TokenContract::new(token_address, provider)
+            .balance_of(owner)
+            .call()
+            .await
+            .map_err(chain_error)?;
+        if balance < amount {
+            return Err(PaymentError::InsufficientBalance(format!(
+                "{} holds {} of the {} base units claimed",
+                req.owner_address, balance, amount
+            )));
+        }
+
+        let mut tx = self.db().begin().await.map_err(db_error)?;
+        // One claim per owner at a time, so two cannot both fit the limit
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext('relay:' || LOWER($1)))")
+            .bind(&req.owner_address)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+
+        // The permit does not cover the destination, so both the wallet and
+        // the destination must belong to the caller
+        check_owned_wallet(&mut tx, user_id, &req.owner_address).await?;
+        check_allowlisted(&mut tx, user_id, &req.to_address).await?;
+
+        let relayed = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) FROM relay_claims
+            WHERE LOWER(owner_address) = LOWER($1) AND created_at > NOW() - INTERVAL '24 hours'
+            "#,
+        )
+        .bind(&req.owner_address)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        if relayed >= self.config.max_claims_per_day {
+            return Err(PaymentError::LimitExceeded(format!(
+                "{} already had {} claims relayed in the last 24 hours",
+                req.owner_address, relayed
+            )));
+        }
+
+        let claim = sqlx::query_as::<_, RelayClaim>(&format!(
+            r#"
+            INSERT INTO relay_claims
+                (user_id, owner_address, to_address, token_address, amount, fee_amount, net_amount,
+                 permit_nonce, deadline, status)
+            VALUES ($1, $2, $3, $4, $5::NUMERIC, $6::NUMERIC, $7::NUMERIC, $8::NUMERIC, $9, $10)
+            ON CONFLICT (token_address, owner_address, permit_nonce) DO NOTHING
+            RETURNING {}
+            "#,
+            CLAIM_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(format!("{:?}", owner))
+        .bind(&req.to_address)
+        .bind(&token.address)
+        .bind(amount.to_string())
+        .bind(fee.to_string())
+        .bind(net.to_string())
+        .bind(nonce.to_string())
+        .bind(deadline)
+        .bind(SENDING)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| PaymentError::AlreadyProcessed(format!("Permit {} of {} was already relayed", nonce, req.owner_address)))?;
+        tx.commit().await.map_err(db_error)?;
+
+        match self.send(&claim, token_address, owner, to, &signature).await {
+            Ok(claim) => {
+                info!(
+                    "Relaying claim {} of {} {} from {} to {}",
+                    claim.id, claim.amount, token.symbol, claim.owner_address, claim.to_address
+                );
+                Ok(claim)
+            }
+            Err(e) => {
+                warn!("Relay claim {} could not be sent: {}", claim.id, e);
+                self.fail(claim.id, &e.to_string()).await?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Send the permit and both transfers, recording them on the claim
+    async fn send(
+        &self,
+        claim: &RelayClaim,
+        token: Address,
+        owner: Address,
+        to: Address,
+        signature: &Signature,
+    ) -> PaymentResult<RelayClaim> {
+        let treasury = self.treasury()?;
+        let provider = self.service.provider().clone();
+        let amount = U256::from_dec_str(&claim.amount).map_err(chain_error)?;
+        let fee = U256::from_dec_str(&claim.fee_amount).map_err(chain_error)?;
+        let net = U256::from_dec_str(&claim.net_amount).map_err(chain_error)?;
+        let mut r = [0u8; 32];
+        let mut s = [0u8; 32];
+        signature.r.to_big_endian(&mut r);
+        signature.s.to_big_endian(&mut s);
+
+        let permit = PermitToken::new(token, provider.clone())
+            .permit(owner, treasury, amount, U256::from(claim.deadline.timestamp()), signature.v as u8, r, s)
+            .calldata()
+            .unwrap_or_default();
+        let permit = self
+            .nonces
+            .submit(None, TransactionBuilder::new(treasury, token).data(permit))
+            .await?;
+
+        let erc20 = TokenContract::new(token, provider);
+        let gas_limit = U256::from(self.config.transfer_gas_limit);
+        let transfer = erc20.transfer_from(owner, to, net).calldata().unwrap_or_default();
+        let transfer = self
+            .nonces
+            .submit(None, TransactionBuilder::new(treasury, token).data(transfer).gas_limit(gas_limit))
+            .await?;
+        let fee_transfer = erc20.transfer_from(owner, treasury, fee).calldata().unwrap_or_default();
+        let fee_transfer = self
+            .nonces
+            .submit(None, TransactionBuilder::new(treasury, token).data(fee_transfer).gas_limit(gas_limit))
+            .await?;
+
+        sqlx::query_as::<_, RelayClaim>(&format!(
+            r#"
+            UPDATE relay_claims
+            SET status = $2, permit_tx_id = $3, transfer_tx_id = $4, fee_tx_id = $5, updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            CLAIM_COLUMNS
+        ))
+        .bind(claim.id)
+        .bind(SENT)
+        .bind(permit.id)
+        .bind(transfer.id)
+        .bind(fee_transfer.id)
+        .fetch_one(self.db())
+        .await
+        .map_err(db_error)
+    }
+
+    async fn fail(&self, id: Uuid, error: &str) -> PaymentResult<()> {
+        sqlx::query("UPDATE relay_claims SET status = $2, error = $3, updated_at = NOW() WHERE id = $1")
+            .bind(id)
+            .bind(FAILED)
+            .bind(error)
+            .execute(self.db())
+            .await
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    /// Complete sent claims whose transfers were mined, recording the relay
+    /// fee, and fail those with a transaction that reverted or was dropped.
+    /// Returns how many claims settled.
+    pub async fn settle(&self) -> PaymentResult<usize> {
+        let claims = sqlx::query_as::<_, SentClaim>(
+            r#"
+            SELECT c.id, c.user_id, c.to_address, c.token_address, c.net_amount::TEXT AS net_amount,
+                   c.fee_amount::TEXT AS fee_amount, p.status AS permit_status, t.status AS transfer_status,
+                   f.status AS fee_status, COALESCE(t.mined_tx_hash, t.tx_hash) AS transfer_hash
+            FROM relay_claims c
+            JOIN outgoing_transactions p ON p.id = c.permit_tx_id
+            JOIN outgoing_transactions t ON t.id = c.transfer_tx_id
+            JOIN outgoing_transactions f ON f.id = c.fee_tx_id
+            WHERE c.status = $1
+            ORDER BY c.updated_at
+            "#,
+        )
+        .bind(SENT)
+        .fetch_all(self.db())
+        .await
+        .map_err(db_error)?;
+
+        let mut settled = 0;
+        for claim in claims {
+            let statuses = [
+                ("permit", &claim.permit_status),
+                ("transfer", &claim.transfer_status),
+                ("fee", &claim.fee_status),
+            ];
+            if let Some((step, status)) = statuses.iter().find(|(_, status)| *status == "failed" || *status == "dropped") {
+                warn!("Relay claim {} failed: {} transaction {}", claim.id, step, status);
+                self.fail(claim.id, &format!("{} transaction {}", step, status)).await?;
+                settled += 1;
+            } else if statuses.iter().all(|(_, status)| *status == "confirmed") {
+                self.complete(&claim).await?;
+                settled += 1;
+            }
+        }
+        Ok(settled)
+    }
+
+    async fn complete(&self, claim: &SentClaim) -> PaymentResult<()> {
+        let fee = U256::from_dec_str(&claim.fee_amount).map_err(chain_error)?;
+        let mut tx = self.db().begin().await.map_err(db_error)?;
+        let updated = sqlx::query(
+            "UPDATE relay_claims SET status = $2, completed_at = NOW(), updated_at = NOW() WHERE id = $1 AND status = $3",
+        )
+        .bind(claim.id)
+        .bind(COMPLETED)
+        .bind(SENT)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        if updated.rows_affected() == 0 {
+            return Ok(());
+        }
+
+        fees::accrue(&mut tx, RELAY_FEE, &claim.token_address, fee, None, claim.id).await?;
+        let event = NewPaymentEvent {
+            kind: PaymentEventKind::WithdrawalCompleted,
+            source_id: claim.id,
+            bounty_id: None,
+            user_id: Some(claim.user_id),
+            address: Some(claim.to_address.clone()),
+            amount: Some(claim.net_amount.clone()),
+            token_address: Some(claim.token_address.clone()),
+            tx_hash: Some(claim.transfer_hash.clone()),
+        };
+        payment_events::record(&mut tx, &event).await?;
+        tx.commit().await.map_err(db_error)?;
+
+        info!("Relay claim {} completed in {}", claim.id, claim.transfer_hash);
+        Ok(())
+    }
+
+    /// A user's relayed claims, newest first
+    pub async fn history(&self, user_id: Uuid, limit: i64) -> PaymentResult<Vec<RelayClaim>> {
+        sqlx::query_as::<_, RelayClaim>(&format!(
+            "SELECT {} FROM relay_claims WHERE user_id = $1 ORDER BY created_at DESC LIMIT $2",
+            CLAIM_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(self.db())
+        .await
+        .map_err(db_error)
+    }
+
+    pub async fn find(&self, id: Uuid) -> PaymentResult<Option<RelayClaim>> {
+        sqlx::query_as::<_, RelayClaim>(&format!("SELECT {} FROM relay_claims WHERE id = $1", CLAIM_COLUMNS))
+            .bind(id)
+            .fetch_optional(self.db())
+            .await
+            .map_err(db_error)
+    }
+}
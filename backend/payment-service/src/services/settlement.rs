@@ -0,0 +1,104 @@
+// Settlement of engine stakes
+//
+// When a bounty is finalized the consensus-service publishes one settlement
+// plan saying which engines are rewarded, refunded or slashed. Stakes are
+// settled from that plan rather than re-derived here: a slashed engine's
+// stake goes to the slashing policy as a wrong verdict, asking for the
+// planned fraction, and everyone else gets theirs back. Rewards are paid from
+// the bounty's escrow on release.
+
+use shared::messaging::{SettlementOutcome, SettlementPlan};
+use tracing::info;
+
+use crate::models::{PaymentError, PaymentResult};
+use crate::services::fees;
+use crate::services::payment_service::PaymentService;
+use crate::services::slashing::{self, Offense, SlashOffense};
+use crate::services::stake::{StakeLock, STAKE_COLUMNS};
+
+fn db_error(e: sqlx::Error) -> PaymentError {
+    PaymentError::DatabaseError(e.to_string())
+}
+
+/// Settle a bounty's locked stakes by its plan. Returns `false` when the
+/// plan was already applied, as each plan is announced more than once.
+pub async fn apply(service: &PaymentService, plan: &SettlementPlan) -> PaymentResult<bool> {
+    let pool = service.db_pool();
+    let payload = serde_json::to_string(plan).map_err(|e| PaymentError::ValidationError(e.to_string()))?;
+
+    let mut tx = pool.begin().await.map_err(db_error)?;
+    let recorded = sqlx::query(
+        r#"
+        INSERT INTO settlement_plans (plan_id, bounty_id, plan, planned_at)
+        VALUES ($1, $2, $3::JSONB, $4)
+        ON CONFLICT (plan_id) DO NOTHING
+        "#,
+    )
+    .bind(plan.plan_id)
+    .bind(plan.bounty_id)
+    .bind(payload)
+    .bind(plan.planned_at)
+    .execute(&mut *tx)
+    .await
+    .map_err(db_error)?;
+    if recorded.rows_affected() == 0 {
+        return Ok(false);
+    }
+
+    let (mut slashed, mut released) = (0, 0);
+    for entry in &plan.entries {
+        // Engines without an account never locked a stake
+        let Some(user_id) = entry.user_id else {
+            continue;
+        };
+        let slash = entry.outcome == SettlementOutcome::Slashed && entry.slash_fraction > 0.0;
+        let settled = if slash {
+            let stakes = sqlx::query_as::<_, StakeLock>(&format!(
+                "SELECT {} FROM stakes WHERE bounty_id = $1 AND user_id = $2 AND status = 'locked' FOR UPDATE",
+                STAKE_COLUMNS
+            ))
+            .bind(plan.bounty_id)
+            .bind(user_id)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(db_error)?;
+            let reason = format!("Voted {:?} against the final verdict (plan {})", entry.verdict, plan.plan_id);
+            for stake in &stakes {
+                let staked = slashing::parse_u256(&stake.amount)?;
+                let offense = Offense {
+                    kind: SlashOffense::WrongVerdict,
+                    requested: Some(fees::fee_of(staked, entry.slash_fraction * 100.0)),
+                    plan_id: Some(plan.plan_id),
+                    reason: &reason,
+                };
+                slashing::schedule(service, &mut tx, stake, &offense).await?;
+            }
+            stakes.len() as u64
+        } else {
+            sqlx::query(
+                r#"
+                UPDATE stakes SET status = 'unlocked', unlocked_at = NOW()
+                WHERE bounty_id = $1 AND user_id = $2 AND status = 'locked'
+                "#,
+            )
+            .bind(plan.bounty_id)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?
+            .rows_affected()
+        };
+        if slash {
+            slashed += settled;
+        } else {
+            released += settled;
+        }
+    }
+    tx.commit().await.map_err(db_error)?;
+
+    info!(
+        "Applied settlement plan {} for bounty {}: {} stake(s) sent for slashing, {} released",
+        plan.plan_id, plan.bounty_id, slashed, released
+    );
+    Ok(true)
+}
@@ -0,0 +1,705 @@
+// Stake slashing policy
+//
+// Nothing slashes a stake directly. A settlement plan, a rejected dispute or
+// proven collusion is an offense, and the policy decides what it costs: at
+// most the offense's fraction of the stake (a plan may ask for less), and,
+// apart from collusion, no more than the engine's cap for the period. The
+// slash is then scheduled, not executed: the stake stays held as
+// `slash_pending`, the engine is told, and it has the appeal window to
+// appeal. An admin either overturns the slash, releasing the stake, or lets
+// it stand.
+//
+// The slash executor executes slashes past their window. The platform keeps
+// its retention (see `fees`); the rest of a wrong verdict or collusion slash
+// is paid from the treasury to the engines that voted for the final verdict
+// of the bounty's settlement plan, in proportion to their reward shares. A
+// rejected dispute's stake, and whatever cannot be redistributed, stays with
+// the platform.
+
+use chrono::{DateTime, Duration, Utc};
+use ethers::types::U256;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use shared::messaging::{PaymentEventKind, SettlementOutcome, SettlementPlan};
+use sqlx::{PgConnection, PgPool};
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::config::SlashingConfig;
+use crate::models::{PaymentError, PaymentResult, PaymentType};
+use crate::services::fees::{self, SLASH_RETENTION};
+use crate::services::payment_events::{self, NewPaymentEvent};
+use crate::services::payment_service::PaymentService;
+use crate::services::stake::{self, StakeLock, LOCKED};
+
+pub const SLASH_PENDING: &str = "slash_pending";
+
+pub const PENDING: &str = "pending";
+pub const APPEALED: &str = "appealed";
+pub const EXECUTED: &str = "executed";
+pub const OVERTURNED: &str = "overturned";
+
+/// Slashes executed per run
+const EXECUTE_BATCH: i64 = 100;
+
+/// Reward shares are weighed in billionths
+const SHARE_PRECISION: f64 = 1_000_000_000.0;
+
+const SLASH_COLUMNS: &str = "id, stake_id, bounty_id, user_id, offense, plan_id, fraction, amount::TEXT AS amount, \
+                             capped, reason, status, execute_after, appeal_note, appealed_at, resolved_by, \
+                             resolution_note, resolved_at, executed_at, created_at";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlashOffense {
+    /// Voted against the final verdict of a settlement plan
+    WrongVerdict,
+    /// Proven to have colluded with other engines
+    Collusion,
+    /// Staked on a dispute that was rejected
+    RejectedDispute,
+}
+
+impl SlashOffense {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SlashOffense::WrongVerdict => "wrong_verdict",
+            SlashOffense::Collusion => "collusion",
+            SlashOffense::RejectedDispute => "rejected_dispute",
+        }
+    }
+
+    fn fraction(&self, config: &SlashingConfig) -> f64 {
+        match self {
+            SlashOffense::WrongVerdict => config.wrong_verdict_fraction,
+            SlashOffense::Collusion => config.collusion_fraction,
+            SlashOffense::RejectedDispute => config.rejected_dispute_fraction,
+        }
+    }
+
+    /// Collusion is not held to the cap per period
+    fn capped(&self) -> bool {
+        *self != SlashOffense::Collusion
+    }
+
+    /// Whether the engines that voted right share the slash
+    fn redistributed(&self) -> bool {
+        *self != SlashOffense::RejectedDispute
+    }
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct StakeSlash {
+    pub id: Uuid,
+    pub stake_id: Uuid,
+    pub bounty_id: Uuid,
+    pub user_id: Uuid,
+    pub offense: String,
+    /// Settlement plan whose correct voters share the slash
+    pub plan_id: Option<Uuid>,
+    /// Share of the stake slashed, 0.0 to 1.0
+    pub fraction: f64,
+    /// In base units of the platform token
+    pub amount: String,
+    /// Reduced to stay within the engine's cap for the period
+    pub capped: bool,
+    pub reason: String,
+    pub status: String,
+    /// End of the appeal window
+    pub execute_after: DateTime<Utc>,
+    pub appeal_note: Option<String>,
+    pub appealed_at: Option<DateTime<Utc>>,
+    pub resolved_by: Option<Uuid>,
+    pub resolution_note: Option<String>,
+    pub resolved_at: Option<DateTime<Utc>>,
+    pub executed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// One engine's share of an executed slash
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct SlashRedistribution {
+    pub user_id: Uuid,
+    pub address: String,
+    /// In base units of the platform token
+    pub amount: String,
+    pub payment_id: Uuid,
+    /// The payment's status
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SlashDetail {
+    #[serde(flatten)]
+    pub slash: StakeSlash,
+    pub redistributions: Vec<SlashRedistribution>,
+}
+
+/// What a slash is for, before the policy weighs it
+pub struct Offense<'a> {
+    pub kind: SlashOffense,
+    /// Most the caller asks to slash; the policy's fraction when unset
+    pub requested: Option<U256>,
+    pub plan_id: Option<Uuid>,
+    pub reason: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SlashStakeRequest {
+    pub stake_id: Uuid,
+    pub offense: SlashOffense,
+    /// In wei; at most the offense's fraction of the stake, which is slashed
+    /// when unset
+    pub slash_amount: Option<Decimal>,
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AppealRequest {
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AppealDecisionRequest {
+    /// Release the stake instead of letting the slash stand
+    pub overturn: bool,
+    pub note: Option<String>,
+}
+
+fn db_error(e: sqlx::Error) -> PaymentError {
+    PaymentError::DatabaseError(e.to_string())
+}
+
+pub(crate) fn parse_u256(value: &str) -> PaymentResult<U256> {
+    let whole = value.split('.').next().unwrap_or_default();
+    U256::from_dec_str(whole).map_err(|_| PaymentError::ValidationError(format!("{} is not an amount", value)))
+}
+
+/// `amount` as a share of `of`, for display
+fn share_of(amount: U256, of: U256) -> f64 {
+    if of.is_zero() {
+        return 0.0;
+    }
+    let scaled = amount * U256::from(SHARE_PRECISION as u64) / of;
+    scaled.low_u64() as f64 / SHARE_PRECISION
+}
+
+/// Decide what `offense` costs the locked `stake` and schedule it in the
+/// caller's transaction. Returns `None`, releasing the stake, when the
+/// policy leaves nothing to slash.
+pub async fn schedule(
+    service: &PaymentService,
+    db: &mut PgConnection,
+    stake: &StakeLock,
+    offense: &Offense<'_>,
+) -> PaymentResult<Option<StakeSlash>> {
+    let config = &service.config().slashing;
+    let staked = parse_u256(&stake.amount)?;
+    let most = fees::fee_of(staked, offense.kind.fraction(config) * 100.0);
+    let mut amount = offense.requested.map_or(most, |requested| requested.min(most));
+
+    // One slash of an engine at a time, so two cannot both fit under its cap
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext('slash:' || $1::TEXT))")
+        .bind(stake.user_id)
+        .execute(&mut *db)
+        .await
+        .map_err(db_error)?;
+    let cap = parse_u256(&config.cap_per_period)?;
+    let mut capped = false;
+    if offense.kind.capped() && !cap.is_zero() {
+        let used: String = sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(SUM(amount), 0)::TEXT FROM stake_slashes
+            WHERE user_id = $1 AND offense <> 'collusion' AND status IN ('pending', 'appealed', 'executed')
+              AND created_at > NOW() - make_interval(hours => $2)
+            "#,
+        )
+        .bind(stake.user_id)
+        .bind(config.cap_period_hours as i32)
+        .fetch_one(&mut *db)
+        .await
+        .map_err(db_error)?;
+        let allowance = cap.saturating_sub(parse_u256(&used)?);
+        if amount > allowance {
+            amount = allowance;
+            capped = true;
+        }
+    }
+
+    if amount.is_zero() {
+        sqlx::query("UPDATE stakes SET status = 'unlocked', unlocked_at = NOW() WHERE id = $1 AND status = 'locked'")
+            .bind(stake.id)
+            .execute(&mut *db)
+            .await
+            .map_err(db_error)?;
+        info!(
+            "Released stake {} of {}: the policy leaves nothing to slash for {}",
+            stake.id,
+            stake.user_id,
+            offense.kind.as_str()
+        );
+        return Ok(None);
+    }
+
+    let held = sqlx::query("UPDATE stakes SET status = $2 WHERE id = $1 AND status = 'locked'")
+        .bind(stake.id)
+        .bind(SLASH_PENDING)
+        .execute(&mut *db)
+        .await
+        .map_err(db_error)?;
+    if held.rows_affected() == 0 {
+        return Ok(None);
+    }
+    let execute_after = Utc::now() + Duration::hours(config.appeal_window_hours);
+    let slash = sqlx::query_as::<_, StakeSlash>(&format!(
+        r#"
+        INSERT INTO stake_slashes
+            (stake_id, bounty_id, user_id, offense, plan_id, fraction, amount, capped, reason, execute_after)
+        VALUES ($1, $2, $3, $4, $5, $6, $7::NUMERIC, $8, $9, $10)
+        RETURNING {}
+        "#,
+        SLASH_COLUMNS
+    ))
+    .bind(stake.id)
+    .bind(stake.bounty_id)
+    .bind(stake.user_id)
+    .bind(offense.kind.as_str())
+    .bind(offense.plan_id)
+    .bind(share_of(amount, staked))
+    .bind(amount.to_string())
+    .bind(capped)
+    .bind(offense.reason)
+    .bind(execute_after)
+    .fetch_one(&mut *db)
+    .await
+    .map_err(db_error)?;
+
+    let event = NewPaymentEvent {
+        kind: PaymentEventKind::SlashScheduled,
+        source_id: slash.id,
+        bounty_id: Some(stake.bounty_id),
+        user_id: Some(stake.user_id),
+        address: Some(stake.address.clone()),
+        amount: Some(amount.to_string()),
+        token_address: Some(service.config().tokens.default_token().address.clone()),
+        tx_hash: None,
+    };
+    payment_events::record(db, &event).await?;
+
+    info!(
+        "Scheduled slash of {} of stake {} ({}) for {}, executing after {}",
+        amount,
+        stake.id,
+        offense.kind.as_str(),
+        stake.user_id,
+        execute_after
+    );
+    Ok(Some(slash))
+}
+
+pub struct SlashingService {
+    service: Arc<PaymentService>,
+}
+
+impl SlashingService {
+    pub fn new(service: Arc<PaymentService>) -> Self {
+        Self { service }
+    }
+
+    pub fn interval_seconds(&self) -> u64 {
+        self.service.config().slashing.executor_interval_seconds
+    }
+
+    fn db(&self) -> &PgPool {
+        self.service.db_pool()
+    }
+
+    /// Slash a stake for an offense outside a settlement plan. Repeating the
+    /// call returns the slash already scheduled for the stake.
+    pub async fn slash(&self, req: &SlashStakeRequest) -> PaymentResult<Option<StakeSlash>> {
+        let requested = req
+            .slash_amount
+            .map(|amount| {
+                U256::from_dec_str(&amount.trunc().to_string())
+                    .map_err(|_| PaymentError::ValidationError("slash_amount must be a whole number of wei".to_string()))
+            })
+            .transpose()?;
+
+        let mut tx = self.db().begin().await.map_err(db_error)?;
+        let stake = sqlx::query_as::<_, StakeLock>(&format!(
+            "SELECT {} FROM stakes WHERE id = $1 FOR UPDATE",
+            stake::STAKE_COLUMNS
+        ))
+        .bind(req.stake_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| PaymentError::NotFound(format!("Stake {} not found", req.stake_id)))?;
+        if stake.status != LOCKED {
+            let existing = sqlx::query_as::<_, StakeSlash>(&format!(
+                "SELECT {} FROM stake_slashes WHERE stake_id = $1",
+                SLASH_COLUMNS
+            ))
+            .bind(stake.id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(db_error)?;
+            return match existing {
+                Some(existing) => Ok(Some(existing)),
+                None => Err(PaymentError::AlreadyProcessed(format!("stake {} is {}", stake.id, stake.status))),
+            };
+        }
+
+        // Collusion harmed the bounty's correct voters, who share the slash
+        let plan_id = if req.offense.redistributed() {
+            sqlx::query_scalar::<_, Uuid>(
+                "SELECT plan_id FROM settlement_plans WHERE bounty_id = $1 ORDER BY planned_at DESC LIMIT 1",
+            )
+            .bind(stake.bounty_id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(db_error)?
+        } else {
+            None
+        };
+        let offense = Offense {
+            kind: req.offense,
+            requested,
+            plan_id,
+            reason: &req.reason,
+        };
+        let slash = schedule(&self.service, &mut tx, &stake, &offense).await?;
+        tx.commit().await.map_err(db_error)?;
+        Ok(slash)
+    }
+
+    /// Slashes, most recent first
+    pub async fn list(&self, status: Option<&str>, limit: i64, offset: i64) -> PaymentResult<(Vec<StakeSlash>, i64)> {
+        let slashes = sqlx::query_as::<_, StakeSlash>(&format!(
+            r#"
+            SELECT {} FROM stake_slashes
+            WHERE $1::TEXT IS NULL OR status = $1
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+            SLASH_COLUMNS
+        ))
+        .bind(status)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(self.db())
+        .await
+        .map_err(db_error)?;
+        let total = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM stake_slashes WHERE $1::TEXT IS NULL OR status = $1")
+            .bind(status)
+            .fetch_one(self.db())
+            .await
+            .map_err(db_error)?;
+        Ok((slashes, total))
+    }
+
+    /// An engine's slashes, most recent first
+    pub async fn for_user(&self, user_id: Uuid, limit: i64) -> PaymentResult<Vec<StakeSlash>> {
+        sqlx::query_as::<_, StakeSlash>(&format!(
+            "SELECT {} FROM stake_slashes WHERE user_id = $1 ORDER BY created_at DESC LIMIT $2",
+            SLASH_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(self.db())
+        .await
+        .map_err(db_error)
+    }
+
+    pub async fn find(&self, id: Uuid) -> PaymentResult<Option<SlashDetail>> {
+        let slash = sqlx::query_as::<_, StakeSlash>(&format!("SELECT {} FROM stake_slashes WHERE id = $1", SLASH_COLUMNS))
+            .bind(id)
+            .fetch_optional(self.db())
+            .await
+            .map_err(db_error)?;
+        let Some(slash) = slash else {
+            return Ok(None);
+        };
+        let redistributions = sqlx::query_as::<_, SlashRedistribution>(
+            r#"
+            SELECT r.user_id, r.address, r.amount::TEXT AS amount, r.payment_id, p.status
+            FROM slash_redistributions r
+            LEFT JOIN payments p ON p.id = r.payment_id
+            WHERE r.slash_id = $1
+            ORDER BY r.amount DESC
+            "#,
+        )
+        .bind(id)
+        .fetch_all(self.db())
+        .await
+        .map_err(db_error)?;
+        Ok(Some(SlashDetail { slash, redistributions }))
+    }
+
+    async fn lock(&self, db: &mut PgConnection, id: Uuid) -> PaymentResult<StakeSlash> {
+        sqlx::query_as::<_, StakeSlash>(&format!(
+            "SELECT {} FROM stake_slashes WHERE id = $1 FOR UPDATE",
+            SLASH_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(db)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| PaymentError::NotFound(format!("Slash {} not found", id)))
+    }
+
+    /// Appeal a scheduled slash of one's own stake before its window closes
+    pub async fn appeal(&self, id: Uuid, user_id: Uuid, req: &AppealRequest) -> PaymentResult<StakeSlash> {
+        let mut tx = self.db().begin().await.map_err(db_error)?;
+        let slash = self.lock(&mut tx, id).await?;
+        if slash.user_id != user_id {
+            return Err(PaymentError::NotPermitted("Only the slashed engine may appeal".to_string()));
+        }
+        if slash.status != PENDING || slash.execute_after <= Utc::now() {
+            return Err(PaymentError::AlreadyProcessed(format!(
+                "Slash {} is {}; its appeal window is closed",
+                id, slash.status
+            )));
+        }
+        let slash = sqlx::query_as::<_, StakeSlash>(&format!(
+            r#"
+            UPDATE stake_slashes SET status = $2, appeal_note = $3, appealed_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            SLASH_COLUMNS
+        ))
+        .bind(id)
+        .bind(APPEALED)
+        .bind(&req.note)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        tx.commit().await.map_err(db_error)?;
+
+        info!("Engine {} appealed slash {}", user_id, id);
+        Ok(slash)
+    }
+
+    /// Decide an appeal: overturning releases the stake, otherwise the slash
+    /// is executed on the executor's next run
+    pub async fn decide(&self, id: Uuid, admin_id: Uuid, req: &AppealDecisionRequest) -> PaymentResult<StakeSlash> {
+        let mut tx = self.db().begin().await.map_err(db_error)?;
+        let slash = self.lock(&mut tx, id).await?;
+        if slash.status != APPEALED {
+            return Err(PaymentError::AlreadyProcessed(format!("Slash {} is {}, not appealed", id, slash.status)));
+        }
+        let slash = sqlx::query_as::<_, StakeSlash>(&format!(
+            r#"
+            UPDATE stake_slashes
+            SET status = $2, resolved_by = $3, resolution_note = $4, resolved_at = NOW(),
+                execute_after = LEAST(execute_after, NOW())
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            SLASH_COLUMNS
+        ))
+        .bind(id)
+        .bind(if req.overturn { OVERTURNED } else { PENDING })
+        .bind(admin_id)
+        .bind(&req.note)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        if req.overturn {
+            sqlx::query("UPDATE stakes SET status = 'unlocked', unlocked_at = NOW() WHERE id = $1 AND status = $2")
+                .bind(slash.stake_id)
+                .bind(SLASH_PENDING)
+                .execute(&mut *tx)
+                .await
+                .map_err(db_error)?;
+        }
+        tx.commit().await.map_err(db_error)?;
+
+        info!(
+            "Admin {} {} slash {}",
+            admin_id,
+            if req.overturn { "overturned" } else { "upheld" },
+            id
+        );
+        Ok(slash)
+    }
+
+    /// Execute slashes whose appeal window has passed, returning how many
+    pub async fn execute_due(&self) -> PaymentResult<usize> {
+        let due: Vec<Uuid> = sqlx::query_scalar(
+            "SELECT id FROM stake_slashes WHERE status = 'pending' AND execute_after <= NOW() ORDER BY execute_after LIMIT $1",
+        )
+        .bind(EXECUTE_BATCH)
+        .fetch_all(self.db())
+        .await
+        .map_err(db_error)?;
+
+        let mut executed = 0;
+        for id in due {
+            if self.execute(id).await? {
+                executed += 1;
+            }
+        }
+        Ok(executed)
+    }
+
+    async fn execute(&self, id: Uuid) -> PaymentResult<bool> {
+        let config = self.service.config();
+        let platform_token = config.tokens.default_token().address.clone();
+
+        let mut tx = self.db().begin().await.map_err(db_error)?;
+        let slash = self.lock(&mut tx, id).await?;
+        if slash.status != PENDING || slash.execute_after > Utc::now() {
+            return Ok(false);
+        }
+        let amount = parse_u256(&slash.amount)?;
+        let stake = sqlx::query_as::<_, (Option<String>,)>(
+            r#"
+            UPDATE stakes
+            SET status = CASE WHEN $2::NUMERIC >= amount THEN 'slashed' ELSE 'partially_slashed' END,
+                slashed_amount = $2::NUMERIC, slashed_at = NOW(), slash_reason = $3, unlocked_at = NOW()
+            WHERE id = $1 AND status = $4
+            RETURNING address
+            "#,
+        )
+        .bind(slash.stake_id)
+        .bind(amount.to_string())
+        .bind(&slash.reason)
+        .bind(SLASH_PENDING)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        let Some((address,)) = stake else {
+            return Ok(false);
+        };
+
+        let retained = fees::fee_of(amount, config.fees.slash_retention_percentage);
+        let redistributed = self.redistribute(&mut tx, &slash, amount - retained, &platform_token).await?;
+        let kept = amount - redistributed;
+        fees::accrue(&mut tx, SLASH_RETENTION, &platform_token, kept, Some(slash.bounty_id), slash.stake_id).await?;
+
+        sqlx::query("UPDATE stake_slashes SET status = $2, executed_at = NOW() WHERE id = $1")
+            .bind(id)
+            .bind(EXECUTED)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        let event = NewPaymentEvent {
+            kind: PaymentEventKind::SlashExecuted,
+            source_id: slash.stake_id,
+            bounty_id: Some(slash.bounty_id),
+            user_id: Some(slash.user_id),
+            address,
+            amount: Some(amount.to_string()),
+            token_address: Some(platform_token),
+            tx_hash: None,
+        };
+        payment_events::record(&mut tx, &event).await?;
+        tx.commit().await.map_err(db_error)?;
+
+        info!(
+            "Executed slash {} of {} from stake {}: {} redistributed, {} kept",
+            id, amount, slash.stake_id, redistributed, kept
+        );
+        Ok(true)
+    }
+
+    /// Queue treasury payments of `pool` to the engines that voted for the
+    /// final verdict of the slash's plan, by reward share. Returns how much
+    /// was paid out; the rest stays with the platform.
+    async fn redistribute(
+        &self,
+        db: &mut PgConnection,
+        slash: &StakeSlash,
+        pool: U256,
+        token: &str,
+    ) -> PaymentResult<U256> {
+        let Some(plan_id) = slash.plan_id else {
+            return Ok(U256::zero());
+        };
+        if pool.is_zero() || SlashOffense::RejectedDispute.as_str() == slash.offense {
+            return Ok(U256::zero());
+        }
+        let plan: Option<serde_json::Value> = sqlx::query_scalar("SELECT plan FROM settlement_plans WHERE plan_id = $1")
+            .bind(plan_id)
+            .fetch_optional(&mut *db)
+            .await
+            .map_err(db_error)?;
+        let Some(plan) = plan.and_then(|plan| serde_json::from_value::<SettlementPlan>(plan).ok()) else {
+            return Ok(U256::zero());
+        };
+
+        // Paid to the wallet each engine staked from on the bounty
+        let mut recipients = Vec::new();
+        for entry in &plan.entries {
+            let Some(user_id) = entry.user_id else {
+                continue;
+            };
+            if entry.outcome != SettlementOutcome::Rewarded || entry.reward_share <= 0.0 || user_id == slash.user_id {
+                continue;
+            }
+            let address: Option<String> = sqlx::query_scalar(
+                "SELECT address FROM stakes WHERE bounty_id = $1 AND user_id = $2 ORDER BY locked_at DESC LIMIT 1",
+            )
+            .bind(plan.bounty_id)
+            .bind(user_id)
+            .fetch_optional(&mut *db)
+            .await
+            .map_err(db_error)?;
+            if let Some(address) = address {
+                let weight = U256::from((entry.reward_share.min(1.0) * SHARE_PRECISION).round() as u64);
+                recipients.push((user_id, address, weight));
+            }
+        }
+        let total = recipients.iter().fold(U256::zero(), |total, (_, _, weight)| total + *weight);
+        if total.is_zero() {
+            return Ok(U256::zero());
+        }
+
+        let treasury = &self.service.config().blockchain.treasury_address;
+        let mut paid = U256::zero();
+        for (user_id, address, weight) in recipients {
+            let share = pool * weight / total;
+            if share.is_zero() {
+                continue;
+            }
+            let payment_id = sqlx::query_scalar::<_, Uuid>(
+                r#"
+                INSERT INTO payments
+                    (bounty_id, payer_address, recipient_address, amount, token_address, status, payment_type, metadata)
+                VALUES ($1, $2, $3, $4::NUMERIC, $5, 'queued', $6, $7)
+                RETURNING id
+                "#,
+            )
+            .bind(slash.bounty_id)
+            .bind(treasury)
+            .bind(&address)
+            .bind(share.to_string())
+            .bind(token)
+            .bind(PaymentType::SlashRedistribution.to_string())
+            .bind(json!({"slash_id": slash.id, "user_id": user_id}))
+            .fetch_one(&mut *db)
+            .await
+            .map_err(db_error)?;
+            sqlx::query(
+                r#"
+                INSERT INTO slash_redistributions (slash_id, user_id, address, amount, payment_id)
+                VALUES ($1, $2, $3, $4::NUMERIC, $5)
+                "#,
+            )
+            .bind(slash.id)
+            .bind(user_id)
+            .bind(&address)
+            .bind(share.to_string())
+            .bind(payment_id)
+            .execute(&mut *db)
+            .await
+            .map_err(db_error)?;
+            paid += share;
+        }
+        Ok(paid)
+    }
+}
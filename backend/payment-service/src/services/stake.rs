@@ -0,0 +1,160 @@
+// Engine stakes
+//
+// An engine locks a stake when it joins a bounty, or as it submits without
+// joining. The stake is held against the engine's token balance, so the same
+// tokens cannot back stakes on several bounties at once, and is released back
+// to the engine when it withdraws before submitting.
+
+use chrono::{DateTime, Utc};
+use ethers::types::U256;
+use serde::Serialize;
+use sqlx::PgPool;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::models::{LockStakeRequest, PaymentError, PaymentResult};
+use crate::services::payment_service::PaymentService;
+
+pub const LOCKED: &str = "locked";
+pub const UNLOCKED: &str = "unlocked";
+
+pub(crate) const STAKE_COLUMNS: &str = "id, user_id, bounty_id, submission_id, address, amount::TEXT AS amount, \
+                             status, locked_at, unlock_at, unlocked_at, \
+                             slashed_amount::TEXT AS slashed_amount";
+
+/// Stake one engine holds on one bounty
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct StakeLock {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub bounty_id: Uuid,
+    pub submission_id: Option<Uuid>,
+    pub address: String,
+    /// In wei
+    pub amount: String,
+    pub status: String,
+    pub locked_at: DateTime<Utc>,
+    pub unlock_at: Option<DateTime<Utc>>,
+    pub unlocked_at: Option<DateTime<Utc>>,
+    /// In wei, forfeited when the bounty settled against the engine
+    pub slashed_amount: Option<String>,
+}
+
+fn db_error(e: sqlx::Error) -> PaymentError {
+    PaymentError::DatabaseError(e.to_string())
+}
+
+pub async fn find(pool: &PgPool, stake_id: Uuid) -> PaymentResult<Option<StakeLock>> {
+    sqlx::query_as(&format!("SELECT {} FROM stakes WHERE id = $1", STAKE_COLUMNS))
+        .bind(stake_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(db_error)
+}
+
+/// Lock a stake for an engine taking part in a bounty. Repeating the call
+/// returns the engine's live stake on the bounty. Locks from one address are
+/// taken one at a time, so concurrent stakes cannot together exceed its
+/// balance.
+pub async fn lock(service: &PaymentService, req: &LockStakeRequest) -> PaymentResult<StakeLock> {
+    let amount = U256::from_dec_str(&req.amount.trunc().to_string())
+        .map_err(|_| PaymentError::ValidationError("amount must be a whole number of wei".to_string()))?;
+    if amount.is_zero() {
+        return Err(PaymentError::ValidationError("amount must be positive".to_string()));
+    }
+
+    let mut tx = service.db_pool().begin().await.map_err(db_error)?;
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext(LOWER($1)))")
+        .bind(&req.address)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+
+    let existing: Option<StakeLock> = sqlx::query_as(&format!(
+        "SELECT {} FROM stakes WHERE bounty_id = $1 AND user_id = $2 AND status = 'locked'",
+        STAKE_COLUMNS
+    ))
+    .bind(req.bounty_id)
+    .bind(req.user_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(db_error)?;
+    if let Some(existing) = existing {
+        if !existing.address.eq_ignore_ascii_case(&req.address) || existing.amount != amount.to_string() {
+            return Err(PaymentError::ValidationError(
+                "Stake already locked with a different address or amount".to_string(),
+            ));
+        }
+        return Ok(existing);
+    }
+
+    // Tokens already backing other stakes are not available
+    let balance = service
+        .get_token_balance(&req.address)
+        .await
+        .map_err(|e| PaymentError::BlockchainError(e.to_string()))?;
+    let held: String = sqlx::query_scalar(
+        "SELECT COALESCE(SUM(amount), 0)::TEXT FROM stakes WHERE LOWER(address) = LOWER($1) AND status IN ('locked', 'slash_pending')",
+    )
+    .bind(&req.address)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(db_error)?;
+    let held = U256::from_dec_str(held.split('.').next().unwrap_or("0")).unwrap_or_default();
+    if balance.saturating_sub(held) < amount {
+        return Err(PaymentError::InsufficientBalance(format!(
+            "available balance {} is below the stake of {}",
+            balance.saturating_sub(held),
+            amount
+        )));
+    }
+
+    let unlock_at = req
+        .lock_duration_seconds
+        .map(|seconds| Utc::now() + chrono::Duration::seconds(seconds as i64));
+    let stake: StakeLock = sqlx::query_as(&format!(
+        r#"
+        INSERT INTO stakes (user_id, bounty_id, submission_id, address, amount, unlock_at)
+        VALUES ($1, $2, $3, $4, $5::NUMERIC, $6)
+        RETURNING {}
+        "#,
+        STAKE_COLUMNS
+    ))
+    .bind(req.user_id)
+    .bind(req.bounty_id)
+    .bind(req.submission_id)
+    .bind(&req.address)
+    .bind(amount.to_string())
+    .bind(unlock_at)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| match &e {
+        sqlx::Error::Database(db) if db.is_unique_violation() => {
+            PaymentError::AlreadyProcessed("stake locked concurrently".to_string())
+        }
+        _ => db_error(e),
+    })?;
+    tx.commit().await.map_err(db_error)?;
+    info!("Locked stake of {} for {} on bounty {}", amount, req.user_id, req.bounty_id);
+    Ok(stake)
+}
+
+/// Release a locked stake back to its engine. Releasing twice is harmless.
+pub async fn unlock(pool: &PgPool, stake_id: Uuid) -> PaymentResult<StakeLock> {
+    let stake = find(pool, stake_id).await?.ok_or_else(|| PaymentError::NotFound(stake_id.to_string()))?;
+    match stake.status.as_str() {
+        UNLOCKED => return Ok(stake),
+        LOCKED => {}
+        status => {
+            return Err(PaymentError::AlreadyProcessed(format!("stake {} is {}", stake_id, status)))
+        }
+    }
+
+    sqlx::query("UPDATE stakes SET status = 'unlocked', unlocked_at = NOW() WHERE id = $1 AND status = 'locked'")
+        .bind(stake_id)
+        .execute(pool)
+        .await
+        .map_err(db_error)?;
+    info!("Unlocked stake {} of {} for {}", stake_id, stake.amount, stake.user_id);
+    find(pool, stake_id).await?.ok_or_else(|| PaymentError::NotFound(stake_id.to_string()))
+}
@@ -0,0 +1,348 @@
+// Reward tokens
+//
+// Bounty rewards may be paid in any allowlisted ERC-20 token (`REWARD_TOKENS`,
+// always including the platform token). Amounts move through the API in the
+// token's base units, as the chain has them; responses add the amount
+// normalized by the token's decimals for display.
+//
+// A creator funds a bounty by approving the payment contract for the reward,
+// after which the deposit pulls it with transferFrom. Either way the escrow is
+// funded by the Transfer the token emits, so a plain transfer to the contract
+// is accepted too.
+//
+// Gas is estimated against each token's own contract, since tokens differ in
+// what a call costs. An estimate fails when the sender cannot make the call
+// yet (no balance or allowance); the last estimate for that token and
+// operation stands in, then a typical ERC-20 figure.
+
+use ethers::contract::ContractCall;
+use ethers::providers::{Provider, Ws};
+use ethers::types::{Address, U256};
+use futures::future::try_join_all;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tracing::{debug, info, warn};
+
+use crate::config::RewardToken;
+use crate::models::{EstimateGasRequest, PaymentError, PaymentResult, PaymentType, TokenApprovalRequest, TokenOperation};
+use crate::services::payment_service::PaymentService;
+
+/// transfer, approve and transferFrom all return whether they succeeded
+type TokenCall = ContractCall<Provider<Ws>, bool>;
+
+const NATIVE_SYMBOL: &str = "ETH";
+const NATIVE_DECIMALS: u8 = 18;
+
+/// One balance an address holds
+#[derive(Debug, Serialize)]
+pub struct AssetBalance {
+    pub symbol: String,
+    /// Unset for the native balance
+    pub token_address: Option<String>,
+    pub decimals: u8,
+    /// In base units
+    pub balance: String,
+    /// In whole tokens
+    pub formatted: String,
+}
+
+/// Native and reward token balances of one address
+#[derive(Debug, Serialize)]
+pub struct AddressBalances {
+    pub address: String,
+    pub native: AssetBalance,
+    pub tokens: Vec<AssetBalance>,
+}
+
+/// Transaction for the owner's wallet to sign
+#[derive(Debug, Serialize)]
+pub struct UnsignedTransaction {
+    pub to: String,
+    pub data: String,
+    pub gas_limit: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TokenApproval {
+    pub token: RewardToken,
+    pub owner_address: String,
+    /// The payment contract, which pulls deposits
+    pub spender_address: String,
+    /// In base units
+    pub allowance: String,
+    /// In base units
+    pub required: String,
+    pub approved: bool,
+    /// Approval of the required amount, while the allowance falls short
+    pub approve_transaction: Option<UnsignedTransaction>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GasStep {
+    pub operation: TokenOperation,
+    pub gas_limit: String,
+    /// False when the fallback figure stands in for a failed estimate
+    pub estimated: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TokenGasEstimate {
+    pub token: RewardToken,
+    pub steps: Vec<GasStep>,
+    pub gas_limit: String,
+    /// In wei
+    pub gas_price: String,
+    /// In wei
+    pub estimated_gas_cost: String,
+    /// In ETH
+    pub estimated_gas_cost_eth: String,
+}
+
+/// Typical gas of an ERC-20 call, when the token's own cannot be estimated
+fn fallback_gas(operation: TokenOperation) -> U256 {
+    U256::from(match operation {
+        TokenOperation::Transfer => 65_000u64,
+        TokenOperation::Approve => 46_000,
+        TokenOperation::TransferFrom => 75_000,
+    })
+}
+
+/// `value` base units as whole tokens, without trailing zeros
+pub fn format_amount(value: U256, decimals: u8) -> String {
+    let digits = value.to_string();
+    let decimals = decimals as usize;
+    if decimals == 0 {
+        return digits;
+    }
+    let padded = format!("{:0>width$}", digits, width = decimals + 1);
+    let (whole, fraction) = padded.split_at(padded.len() - decimals);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{}.{}", whole, fraction)
+    }
+}
+
+/// A whole, positive number of base units
+pub fn base_units(amount: Decimal) -> PaymentResult<U256> {
+    let amount = U256::from_dec_str(&amount.trunc().to_string())
+        .map_err(|_| PaymentError::ValidationError("amount must be a whole number of base units".to_string()))?;
+    if amount.is_zero() {
+        return Err(PaymentError::ValidationError("amount must be positive".to_string()));
+    }
+    Ok(amount)
+}
+
+fn address(value: &str, field: &str) -> PaymentResult<Address> {
+    value
+        .parse()
+        .map_err(|_| PaymentError::ValidationError(format!("{} is not an address", field)))
+}
+
+fn chain_error(e: anyhow::Error) -> PaymentError {
+    PaymentError::BlockchainError(e.to_string())
+}
+
+/// The allowlisted token at `token_address`, or the platform token
+pub fn resolve<'a>(service: &'a PaymentService, token_address: Option<&str>) -> PaymentResult<&'a RewardToken> {
+    let tokens = &service.config().tokens;
+    match token_address {
+        None => Ok(tokens.default_token()),
+        Some(token_address) => tokens.find(token_address).ok_or_else(|| {
+            PaymentError::ValidationError(format!("{} is not an allowed reward token", token_address))
+        }),
+    }
+}
+
+pub struct TokenService {
+    service: Arc<PaymentService>,
+    /// Last successful estimate per token address (lowercase) and operation
+    gas_limits: Mutex<HashMap<(String, TokenOperation), U256>>,
+}
+
+impl TokenService {
+    pub fn new(service: Arc<PaymentService>) -> Self {
+        Self {
+            service,
+            gas_limits: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn tokens(&self) -> &[RewardToken] {
+        &self.service.config().tokens.reward_tokens
+    }
+
+    /// Check each allowlisted token's configured decimals against its
+    /// contract. A mismatch would misstate every normalized amount.
+    pub async fn verify(&self) {
+        for token in self.tokens() {
+            match self.service.get_token_decimals(&token.address).await {
+                Ok(decimals) if decimals == token.decimals => {
+                    info!("Reward token {} at {} ({} decimals)", token.symbol, token.address, decimals)
+                }
+                Ok(decimals) => warn!(
+                    "Reward token {} at {} is configured with {} decimals but reports {}",
+                    token.symbol, token.address, token.decimals, decimals
+                ),
+                Err(e) => warn!("Could not read decimals of reward token {}: {}", token.symbol, e),
+            }
+        }
+    }
+
+    /// The address's native balance and its balance of every allowlisted token
+    pub async fn balances(&self, owner: &str) -> PaymentResult<AddressBalances> {
+        address(owner, "address")?;
+        let native = self.service.get_native_balance(owner).await.map_err(chain_error)?;
+        let tokens = try_join_all(self.tokens().iter().map(|token| async move {
+            let balance = self
+                .service
+                .get_token_balance_of(&token.address, owner)
+                .await
+                .map_err(chain_error)?;
+            Ok::<_, PaymentError>(AssetBalance {
+                symbol: token.symbol.clone(),
+                token_address: Some(token.address.clone()),
+                decimals: token.decimals,
+                balance: balance.to_string(),
+                formatted: format_amount(balance, token.decimals),
+            })
+        }))
+        .await?;
+
+        Ok(AddressBalances {
+            address: owner.to_string(),
+            native: AssetBalance {
+                symbol: NATIVE_SYMBOL.to_string(),
+                token_address: None,
+                decimals: NATIVE_DECIMALS,
+                balance: native.to_string(),
+                formatted: format_amount(native, NATIVE_DECIMALS),
+            },
+            tokens,
+        })
+    }
+
+    /// Whether the owner has approved the payment contract for a deposit,
+    /// with the approval to sign if not
+    pub async fn approval(&self, req: &TokenApprovalRequest) -> PaymentResult<TokenApproval> {
+        let token = resolve(&self.service, req.token_address.as_deref())?;
+        let owner = address(&req.owner_address, "owner_address")?;
+        let required = base_units(req.amount)?;
+        let spender_address = self.service.config().blockchain.payment_contract_address.clone();
+        let spender = address(&spender_address, "payment contract")?;
+
+        let allowance = self
+            .service
+            .get_token_allowance(&token.address, &req.owner_address, &spender_address)
+            .await
+            .map_err(chain_error)?;
+        let approved = allowance >= required;
+        let approve_transaction = if approved {
+            None
+        } else {
+            let contract = self.service.token_contract_at(&token.address).map_err(chain_error)?;
+            let call = contract.approve(spender, required).from(owner);
+            let data = call.calldata().unwrap_or_default();
+            let (gas_limit, _) = self.gas_limit(token, TokenOperation::Approve, call).await;
+            Some(UnsignedTransaction {
+                to: token.address.clone(),
+                data: format!("0x{}", hex::encode(&data)),
+                gas_limit: gas_limit.to_string(),
+            })
+        };
+
+        Ok(TokenApproval {
+            token: token.clone(),
+            owner_address: req.owner_address.clone(),
+            spender_address,
+            allowance: allowance.to_string(),
+            required: required.to_string(),
+            approved,
+            approve_transaction,
+        })
+    }
+
+    /// Gas to move `amount` of a reward token. A bounty deposit is the
+    /// creator's approval of the payment contract, unless it already has
+    /// one, and the contract's transferFrom; anything else is a transfer.
+    pub async fn estimate_gas(&self, req: &EstimateGasRequest) -> PaymentResult<TokenGasEstimate> {
+        let token = resolve(&self.service, req.token_address.as_deref())?;
+        let from = address(&req.from_address, "from_address")?;
+        let to = address(&req.to_address, "to_address")?;
+        let amount = base_units(req.amount)?;
+        let contract = self.service.token_contract_at(&token.address).map_err(chain_error)?;
+
+        let mut steps = Vec::new();
+        if req.payment_type == PaymentType::BountyDeposit {
+            let allowance = self
+                .service
+                .get_token_allowance(&token.address, &req.from_address, &req.to_address)
+                .await
+                .map_err(chain_error)?;
+            if allowance < amount {
+                let call = contract.approve(to, amount).from(from);
+                steps.push(self.step(token, TokenOperation::Approve, call).await);
+            }
+            let call = contract.transfer_from(from, to, amount).from(to);
+            steps.push(self.step(token, TokenOperation::TransferFrom, call).await);
+        } else {
+            let call = contract.transfer(to, amount).from(from);
+            steps.push(self.step(token, TokenOperation::Transfer, call).await);
+        }
+
+        let gas_limit = steps
+            .iter()
+            .map(|step| U256::from_dec_str(&step.gas_limit).unwrap_or_default())
+            .fold(U256::zero(), |total, gas| total.saturating_add(gas));
+        let gas_price = self.service.gas_price().await.map_err(chain_error)?;
+        let cost = gas_limit.saturating_mul(gas_price);
+
+        Ok(TokenGasEstimate {
+            token: token.clone(),
+            steps,
+            gas_limit: gas_limit.to_string(),
+            gas_price: gas_price.to_string(),
+            estimated_gas_cost: cost.to_string(),
+            estimated_gas_cost_eth: format_amount(cost, NATIVE_DECIMALS),
+        })
+    }
+
+    async fn step(
+        &self,
+        token: &RewardToken,
+        operation: TokenOperation,
+        call: TokenCall,
+    ) -> GasStep {
+        let (gas_limit, estimated) = self.gas_limit(token, operation, call).await;
+        GasStep {
+            operation,
+            gas_limit: gas_limit.to_string(),
+            estimated,
+        }
+    }
+
+    /// Gas limit of `call`, and whether it was estimated rather than a
+    /// fallback
+    async fn gas_limit(
+        &self,
+        token: &RewardToken,
+        operation: TokenOperation,
+        call: TokenCall,
+    ) -> (U256, bool) {
+        let key = (token.address.to_lowercase(), operation);
+        match call.estimate_gas().await {
+            Ok(gas) => {
+                self.gas_limits.lock().unwrap().insert(key, gas);
+                (gas, true)
+            }
+            Err(e) => {
+                let known = self.gas_limits.lock().unwrap().get(&key).copied();
+                debug!("Gas estimate for {:?} of {} failed: {}", operation, token.symbol, e);
+                (known.unwrap_or_else(|| fallback_gas(operation)), false)
+            }
+        }
+    }
+}
@@ -0,0 +1,779 @@
+// Withdrawal controls
+//
+// A user withdraws from the treasury to an address on their allowlist. New
+// addresses only become usable after a cooling-off period, so an attacker who
+// takes over an account cannot add their own address and withdraw at once;
+// removing an address takes effect immediately.
+//
+// Each user may withdraw up to `daily_limit` of a token over any 24 hours.
+// A withdrawal of `approval_threshold` or more is held until
+// `required_approvals` admins, none of them the user, approve it (one
+// rejection is final); smaller ones are queued straight away. A queued
+// withdrawal is a treasury payment the pending payment processor sends, and
+// follows that payment to completion.
+//
+// Every step, and every allowlist change, is recorded as a withdrawal event
+// in the same transaction and then published to the user as
+// `WithdrawalUpdated` (the events table is the outbox).
+
+use chrono::{DateTime, Duration, Utc};
+use ethers::types::{Address, U256};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use shared::messaging::{NexusEvent, PaymentEventKind, WithdrawalEventKind, WithdrawalUpdatedEvent};
+use sqlx::{PgConnection, PgPool};
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::config::WithdrawalConfig;
+use crate::models::{PaymentError, PaymentResult, WithdrawRequest, WithdrawalAddressRequest};
+use crate::services::payment_events::{self, NewPaymentEvent};
+use crate::services::payment_service::PaymentService;
+use crate::services::tokens;
+
+pub const HELD: &str = "held";
+pub const QUEUED: &str = "queued";
+pub const SENT: &str = "sent";
+pub const COMPLETED: &str = "completed";
+pub const FAILED: &str = "failed";
+pub const REJECTED: &str = "rejected";
+
+/// Events published per run
+const NOTIFY_BATCH: i64 = 100;
+
+const WITHDRAWAL_COLUMNS: &str = "id, user_id, from_address, to_address, token_address, amount::TEXT AS amount, \
+                                  status, payment_id, reason, created_at, updated_at";
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Withdrawal {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub from_address: String,
+    pub to_address: String,
+    pub token_address: String,
+    /// In base units of the token
+    pub amount: String,
+    pub status: String,
+    pub payment_id: Option<Uuid>,
+    /// Why it was held or rejected, or how it failed
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct WithdrawalAddress {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub address: String,
+    pub label: Option<String>,
+    /// Withdrawals to the address are refused until then
+    pub usable_after: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct WithdrawalApproval {
+    pub admin_id: Uuid,
+    pub approved: bool,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct WithdrawalEventRecord {
+    pub id: Uuid,
+    pub kind: String,
+    pub detail: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+    pub notified_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WithdrawalDetail {
+    #[serde(flatten)]
+    pub withdrawal: Withdrawal,
+    pub approvals: Vec<WithdrawalApproval>,
+    pub required_approvals: i64,
+    pub events: Vec<WithdrawalEventRecord>,
+}
+
+/// What an event carries besides its kind, as stored in `detail`
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct EventDetail {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    amount: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tx_hash: Option<String>,
+}
+
+impl EventDetail {
+    fn of(withdrawal: &Withdrawal) -> Self {
+        Self {
+            amount: Some(withdrawal.amount.clone()),
+            token_address: Some(withdrawal.token_address.clone()),
+            address: Some(withdrawal.to_address.clone()),
+            ..Self::default()
+        }
+    }
+
+    fn reason(mut self, reason: impl Into<String>) -> Self {
+        self.reason = Some(reason.into());
+        self
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct UnnotifiedEvent {
+    id: Uuid,
+    user_id: Uuid,
+    withdrawal_id: Option<Uuid>,
+    kind: String,
+    detail: serde_json::Value,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(sqlx::FromRow)]
+struct OpenWithdrawal {
+    id: Uuid,
+    status: String,
+    payment_status: Option<String>,
+    transaction_hash: Option<String>,
+}
+
+fn db_error(e: sqlx::Error) -> PaymentError {
+    PaymentError::DatabaseError(e.to_string())
+}
+
+fn parse_address(value: &str, field: &str) -> PaymentResult<Address> {
+    value
+        .parse()
+        .map_err(|_| PaymentError::ValidationError(format!("{} is not an address", field)))
+}
+
+fn parse_units(value: &str) -> PaymentResult<U256> {
+    let whole = value.split('.').next().unwrap_or_default();
+    U256::from_dec_str(whole).map_err(|_| PaymentError::ConfigError(format!("{} is not an amount", value)))
+}
+
+fn kind_name(kind: WithdrawalEventKind) -> &'static str {
+    match kind {
+        WithdrawalEventKind::Requested => "requested",
+        WithdrawalEventKind::Held => "held",
+        WithdrawalEventKind::ApprovalRecorded => "approval_recorded",
+        WithdrawalEventKind::Approved => "approved",
+        WithdrawalEventKind::Rejected => "rejected",
+        WithdrawalEventKind::Queued => "queued",
+        WithdrawalEventKind::Sent => "sent",
+        WithdrawalEventKind::Completed => "completed",
+        WithdrawalEventKind::Failed => "failed",
+        WithdrawalEventKind::AddressAdded => "address_added",
+        WithdrawalEventKind::AddressRemoved => "address_removed",
+    }
+}
+
+/// Record an event in the caller's transaction, to be published by `notify`
+async fn record(
+    db: &mut PgConnection,
+    user_id: Uuid,
+    withdrawal_id: Option<Uuid>,
+    kind: WithdrawalEventKind,
+    detail: EventDetail,
+) -> PaymentResult<()> {
+    sqlx::query("INSERT INTO withdrawal_events (user_id, withdrawal_id, kind, detail) VALUES ($1, $2, $3, $4)")
+        .bind(user_id)
+        .bind(withdrawal_id)
+        .bind(kind_name(kind))
+        .bind(json!(detail))
+        .execute(db)
+        .await
+        .map_err(db_error)?;
+    Ok(())
+}
+
+/// Refuse unless `address` is a wallet registered to `user_id`: its account
+/// wallet, a Sign-In with Ethereum wallet or a verified wallet connection
+pub(crate) async fn check_owned_wallet(db: &mut PgConnection, user_id: Uuid, address: &str) -> PaymentResult<()> {
+    let owned = sqlx::query_scalar::<_, bool>(
+        r#"
+        SELECT EXISTS (SELECT 1 FROM users WHERE id = $1 AND LOWER(wallet_address) = LOWER($2))
+            OR EXISTS (SELECT 1 FROM siwe_identities WHERE user_id = $1 AND address = LOWER($2))
+            OR EXISTS (
+                SELECT 1 FROM wallet_connections
+                WHERE user_id = $1 AND LOWER(wallet_address) = LOWER($2)
+                  AND is_active AND verified_at IS NOT NULL
+            )
+        "#,
+    )
+    .bind(user_id)
+    .bind(address)
+    .fetch_one(&mut *db)
+    .await
+    .map_err(db_error)?;
+    if !owned {
+        return Err(PaymentError::NotPermitted(format!("{} is not one of your wallets", address)));
+    }
+    Ok(())
+}
+
+/// Refuse `address` unless it is on the user's allowlist and past its
+/// cooling-off period
+pub(crate) async fn check_allowlisted(db: &mut PgConnection, user_id: Uuid, address: &str) -> PaymentResult<()> {
+    let usable_after = sqlx::query_scalar::<_, DateTime<Utc>>(
+        r#"
+        SELECT usable_after FROM withdrawal_addresses
+        WHERE user_id = $1 AND LOWER(address) = LOWER($2) AND removed_at IS NULL
+        "#,
+    )
+    .bind(user_id)
+    .bind(address)
+    .fetch_optional(&mut *db)
+    .await
+    .map_err(db_error)?;
+    match usable_after {
+        None => Err(PaymentError::NotPermitted(format!("{} is not on the withdrawal allowlist", address))),
+        Some(usable_after) if usable_after > Utc::now() => Err(PaymentError::NotPermitted(format!(
+            "{} was allowlisted recently and can be withdrawn to from {}",
+            address,
+            usable_after.to_rfc3339()
+        ))),
+        Some(_) => Ok(()),
+    }
+}
+
+pub struct WithdrawalService {
+    service: Arc<PaymentService>,
+    config: WithdrawalConfig,
+    redis: redis::Client,
+}
+
+impl WithdrawalService {
+    pub fn new(service: Arc<PaymentService>) -> anyhow::Result<Self> {
+        let config = service.config().withdrawals.clone();
+        let redis = redis::Client::open(service.config().redis.url.clone())?;
+        Ok(Self { service, config, redis })
+    }
+
+    pub fn interval_seconds(&self) -> u64 {
+        self.config.monitor_interval_seconds
+    }
+
+    fn db(&self) -> &PgPool {
+        self.service.db_pool()
+    }
+
+    /// Addresses the user may withdraw to, including ones still cooling off
+    pub async fn addresses(&self, user_id: Uuid) -> PaymentResult<Vec<WithdrawalAddress>> {
+        sqlx::query_as::<_, WithdrawalAddress>(
+            r#"
+            SELECT id, user_id, address, label, usable_after, created_at
+            FROM withdrawal_addresses
+            WHERE user_id = $1 AND removed_at IS NULL
+            ORDER BY created_at
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(self.db())
+        .await
+        .map_err(db_error)
+    }
+
+    /// Allowlist an address, usable once the cooling-off period has passed
+    pub async fn add_address(&self, user_id: Uuid, req: &WithdrawalAddressRequest) -> PaymentResult<WithdrawalAddress> {
+        parse_address(&req.address, "address")?;
+        let usable_after = Utc::now() + Duration::hours(self.config.address_cooloff_hours);
+
+        let mut tx = self.db().begin().await.map_err(db_error)?;
+        let added = sqlx::query_as::<_, WithdrawalAddress>(
+            r#"
+            INSERT INTO withdrawal_addresses (user_id, address, label, usable_after)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_id, LOWER(address)) WHERE removed_at IS NULL DO NOTHING
+            RETURNING id, user_id, address, label, usable_after, created_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(&req.address)
+        .bind(&req.label)
+        .bind(usable_after)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| PaymentError::AlreadyProcessed(format!("{} is already allowlisted", req.address)))?;
+
+        let detail = EventDetail {
+            address: Some(added.address.clone()),
+            reason: Some(format!("Usable for withdrawals from {}", usable_after.to_rfc3339())),
+            ..EventDetail::default()
+        };
+        record(&mut tx, user_id, None, WithdrawalEventKind::AddressAdded, detail).await?;
+        tx.commit().await.map_err(db_error)?;
+
+        info!("User {} allowlisted withdrawal address {}", user_id, added.address);
+        Ok(added)
+    }
+
+    pub async fn remove_address(&self, user_id: Uuid, address: &str) -> PaymentResult<()> {
+        let mut tx = self.db().begin().await.map_err(db_error)?;
+        let removed = sqlx::query_scalar::<_, String>(
+            r#"
+            UPDATE withdrawal_addresses SET removed_at = NOW()
+            WHERE user_id = $1 AND LOWER(address) = LOWER($2) AND removed_at IS NULL
+            RETURNING address
+            "#,
+        )
+        .bind(user_id)
+        .bind(address)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| PaymentError::NotFound(format!("{} is not allowlisted", address)))?;
+
+        let detail = EventDetail {
+            address: Some(removed),
+            ..EventDetail::default()
+        };
+        record(&mut tx, user_id, None, WithdrawalEventKind::AddressRemoved, detail).await?;
+        tx.commit().await.map_err(db_error)?;
+        Ok(())
+    }
+
+    /// Request a withdrawal: queued at once, or held for approval above the
+    /// threshold. Refused for an address not (yet) allowlisted, or beyond
+    /// the daily limit.
+    pub async fn request(&self, user_id: Uuid, req: &WithdrawRequest) -> PaymentResult<Withdrawal> {
+        let token = tokens::resolve(&self.service, req.token_address.as_deref())?;
+        let amount = tokens::base_units(req.amount)?;
+        parse_address(&req.from_address, "from_address")?;
+        parse_address(&req.to_address, "to_address")?;
+
+        let payment = &self.service.config().payment;
+        if amount < parse_units(&payment.min_withdraw_amount)? {
+            return Err(PaymentError::ValidationError(format!(
+                "Withdrawals start at {} base units",
+                payment.min_withdraw_amount
+            )));
+        }
+        if amount > parse_units(&payment.max_withdraw_amount)? {
+            return Err(PaymentError::ValidationError(format!(
+                "Withdrawals are limited to {} base units",
+                payment.max_withdraw_amount
+            )));
+        }
+
+        let mut tx = self.db().begin().await.map_err(db_error)?;
+        // One request per user at a time, so two cannot both fit the limit
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext('withdrawals:' || $1::TEXT))")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+
+        check_allowlisted(&mut tx, user_id, &req.to_address).await?;
+
+        let withdrawn = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT COALESCE(SUM(amount), 0)::TEXT FROM withdrawals
+            WHERE user_id = $1 AND LOWER(token_address) = LOWER($2)
+              AND created_at > NOW() - INTERVAL '24 hours'
+              AND status NOT IN ($3, $4)
+            "#,
+        )
+        .bind(user_id)
+        .bind(&token.address)
+        .bind(REJECTED)
+        .bind(FAILED)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        let withdrawn = parse_units(&withdrawn)?;
+        let daily_limit = parse_units(&self.config.daily_limit)?;
+        if withdrawn.saturating_add(amount) > daily_limit {
+            return Err(PaymentError::LimitExceeded(format!(
+                "Withdrawal exceeds the daily limit; {} base units of {} remain for the next 24 hours",
+                daily_limit.saturating_sub(withdrawn),
+                token.symbol
+            )));
+        }
+
+        let held = amount >= parse_units(&self.config.approval_threshold)?;
+        let reason = held.then(|| {
+            format!(
+                "At or above {} base units; awaiting approval by {} admins",
+                self.config.approval_threshold, self.config.required_approvals
+            )
+        });
+        let mut withdrawal = sqlx::query_as::<_, Withdrawal>(&format!(
+            r#"
+            INSERT INTO withdrawals (user_id, from_address, to_address, token_address, amount, status, reason)
+            VALUES ($1, $2, $3, $4, $5::NUMERIC, $6, $7)
+            RETURNING {}
+            "#,
+            WITHDRAWAL_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(&req.from_address)
+        .bind(&req.to_address)
+        .bind(&token.address)
+        .bind(amount.to_string())
+        .bind(if held { HELD } else { QUEUED })
+        .bind(&reason)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(db_error)?;
+
+        record(&mut tx, withdrawal.user_id, Some(withdrawal.id), WithdrawalEventKind::Requested, EventDetail::of(&withdrawal)).await?;
+        if let Some(reason) = reason {
+            let detail = EventDetail::of(&withdrawal).reason(reason);
+            record(&mut tx, withdrawal.user_id, Some(withdrawal.id), WithdrawalEventKind::Held, detail).await?;
+        } else {
+            withdrawal = self.queue(&mut tx, withdrawal).await?;
+        }
+        tx.commit().await.map_err(db_error)?;
+
+        info!(
+            "Withdrawal {} of {} {} by user {} is {}",
+            withdrawal.id, withdrawal.amount, token.symbol, withdrawal.user_id, withdrawal.status
+        );
+        Ok(withdrawal)
+    }
+
+    /// Queue the treasury payment of a withdrawal
+    async fn queue(&self, db: &mut PgConnection, withdrawal: Withdrawal) -> PaymentResult<Withdrawal> {
+        let payment_id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            INSERT INTO payments
+                (payer_address, recipient_address, amount, token_address, status, payment_type, metadata)
+            VALUES ($1, $2, $3::NUMERIC, $4, 'queued', 'withdrawal', $5)
+            RETURNING id
+            "#,
+        )
+        .bind(&self.service.config().blockchain.treasury_address)
+        .bind(&withdrawal.to_address)
+        .bind(&withdrawal.amount)
+        .bind(&withdrawal.token_address)
+        .bind(json!({"withdrawal_id": withdrawal.id, "user_id": withdrawal.user_id}))
+        .fetch_one(&mut *db)
+        .await
+        .map_err(db_error)?;
+
+        let withdrawal = sqlx::query_as::<_, Withdrawal>(&format!(
+            "UPDATE withdrawals SET status = $1, payment_id = $2, updated_at = NOW() WHERE id = $3 RETURNING {}",
+            WITHDRAWAL_COLUMNS
+        ))
+        .bind(QUEUED)
+        .bind(payment_id)
+        .bind(withdrawal.id)
+        .fetch_one(&mut *db)
+        .await
+        .map_err(db_error)?;
+        record(db, withdrawal.user_id, Some(withdrawal.id), WithdrawalEventKind::Queued, EventDetail::of(&withdrawal)).await?;
+        Ok(withdrawal)
+    }
+
+    /// A user's withdrawals, newest first
+    pub async fn history(&self, user_id: Uuid, limit: i64) -> PaymentResult<Vec<Withdrawal>> {
+        sqlx::query_as::<_, Withdrawal>(&format!(
+            "SELECT {} FROM withdrawals WHERE user_id = $1 ORDER BY created_at DESC LIMIT $2",
+            WITHDRAWAL_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(self.db())
+        .await
+        .map_err(db_error)
+    }
+
+    /// Withdrawals waiting for admin approval, oldest first
+    pub async fn held(&self) -> PaymentResult<Vec<Withdrawal>> {
+        sqlx::query_as::<_, Withdrawal>(&format!(
+            "SELECT {} FROM withdrawals WHERE status = $1 ORDER BY created_at ASC",
+            WITHDRAWAL_COLUMNS
+        ))
+        .bind(HELD)
+        .fetch_all(self.db())
+        .await
+        .map_err(db_error)
+    }
+
+    /// A withdrawal with its approvals and events
+    pub async fn find(&self, id: Uuid) -> PaymentResult<Option<WithdrawalDetail>> {
+        let withdrawal = sqlx::query_as::<_, Withdrawal>(&format!(
+            "SELECT {} FROM withdrawals WHERE id = $1",
+            WITHDRAWAL_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(self.db())
+        .await
+        .map_err(db_error)?;
+        let Some(withdrawal) = withdrawal else {
+            return Ok(None);
+        };
+
+        let approvals = sqlx::query_as::<_, WithdrawalApproval>(
+            "SELECT admin_id, approved, note, created_at FROM withdrawal_approvals WHERE withdrawal_id = $1 ORDER BY created_at",
+        )
+        .bind(id)
+        .fetch_all(self.db())
+        .await
+        .map_err(db_error)?;
+        let events = sqlx::query_as::<_, WithdrawalEventRecord>(
+            "SELECT id, kind, detail, created_at, notified_at FROM withdrawal_events WHERE withdrawal_id = $1 ORDER BY created_at",
+        )
+        .bind(id)
+        .fetch_all(self.db())
+        .await
+        .map_err(db_error)?;
+
+        Ok(Some(WithdrawalDetail {
+            withdrawal,
+            approvals,
+            required_approvals: self.config.required_approvals,
+            events,
+        }))
+    }
+
+    /// Record an admin's decision on a held withdrawal. It is queued once
+    /// enough distinct admins approve, and rejected by the first rejection.
+    pub async fn decide(&self, id: Uuid, admin_id: Uuid, approved: bool, note: Option<&str>) -> PaymentResult<Withdrawal> {
+        let mut tx = self.db().begin().await.map_err(db_error)?;
+        let withdrawal = sqlx::query_as::<_, Withdrawal>(&format!(
+            "SELECT {} FROM withdrawals WHERE id = $1 FOR UPDATE",
+            WITHDRAWAL_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| PaymentError::NotFound(format!("Withdrawal {} not found", id)))?;
+
+        if withdrawal.status != HELD {
+            return Err(PaymentError::AlreadyProcessed(format!(
+                "Withdrawal {} is {}",
+                id, withdrawal.status
+            )));
+        }
+        if withdrawal.user_id == admin_id {
+            return Err(PaymentError::NotPermitted(
+                "Admins cannot decide on their own withdrawal".to_string(),
+            ));
+        }
+
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO withdrawal_approvals (withdrawal_id, admin_id, approved, note)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (withdrawal_id, admin_id) DO NOTHING
+            "#,
+        )
+        .bind(id)
+        .bind(admin_id)
+        .bind(approved)
+        .bind(note)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        if inserted.rows_affected() == 0 {
+            return Err(PaymentError::AlreadyProcessed(format!(
+                "You already decided on withdrawal {}",
+                id
+            )));
+        }
+
+        let withdrawal = if !approved {
+            let reason = note.map_or_else(|| "Rejected by an admin".to_string(), str::to_string);
+            let withdrawal = sqlx::query_as::<_, Withdrawal>(&format!(
+                "UPDATE withdrawals SET status = $1, reason = $2, updated_at = NOW() WHERE id = $3 RETURNING {}",
+                WITHDRAWAL_COLUMNS
+            ))
+            .bind(REJECTED)
+            .bind(&reason)
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(db_error)?;
+            let detail = EventDetail::of(&withdrawal).reason(reason);
+            record(&mut tx, withdrawal.user_id, Some(id), WithdrawalEventKind::Rejected, detail).await?;
+            withdrawal
+        } else {
+            let approvals = sqlx::query_scalar::<_, i64>(
+                "SELECT COUNT(*) FROM withdrawal_approvals WHERE withdrawal_id = $1 AND approved",
+            )
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(db_error)?;
+            let detail = EventDetail::of(&withdrawal)
+                .reason(format!("{} of {} approvals", approvals, self.config.required_approvals));
+            record(&mut tx, withdrawal.user_id, Some(id), WithdrawalEventKind::ApprovalRecorded, detail).await?;
+
+            if approvals >= self.config.required_approvals {
+                record(&mut tx, withdrawal.user_id, Some(id), WithdrawalEventKind::Approved, EventDetail::of(&withdrawal)).await?;
+                self.queue(&mut tx, withdrawal).await?
+            } else {
+                withdrawal
+            }
+        };
+        tx.commit().await.map_err(db_error)?;
+
+        info!(
+            "Admin {} {} withdrawal {}, now {}",
+            admin_id,
+            if approved { "approved" } else { "rejected" },
+            id,
+            withdrawal.status
+        );
+        Ok(withdrawal)
+    }
+
+    /// Follow queued and sent withdrawals to their payment's outcome,
+    /// returning how many changed
+    pub async fn sync(&self) -> PaymentResult<usize> {
+        let open = sqlx::query_as::<_, OpenWithdrawal>(
+            r#"
+            SELECT w.id, w.status, p.status AS payment_status, p.transaction_hash
+            FROM withdrawals w
+            LEFT JOIN payments p ON p.id = w.payment_id
+            WHERE w.status IN ($1, $2)
+            ORDER BY w.created_at
+            "#,
+        )
+        .bind(QUEUED)
+        .bind(SENT)
+        .fetch_all(self.db())
+        .await
+        .map_err(db_error)?;
+
+        let mut changed = 0;
+        for withdrawal in open {
+            let next = match (withdrawal.status.as_str(), withdrawal.payment_status.as_deref()) {
+                (_, Some("completed")) => (COMPLETED, WithdrawalEventKind::Completed, None),
+                (_, Some("failed")) => (
+                    FAILED,
+                    WithdrawalEventKind::Failed,
+                    Some("The payment transaction failed".to_string()),
+                ),
+                (_, Some("cancelled")) => (
+                    FAILED,
+                    WithdrawalEventKind::Failed,
+                    Some("The payment was cancelled by an admin".to_string()),
+                ),
+                (QUEUED, Some("processing")) if withdrawal.transaction_hash.is_some() => {
+                    (SENT, WithdrawalEventKind::Sent, None)
+                }
+                _ => continue,
+            };
+            match self.advance(&withdrawal, next).await {
+                Ok(true) => changed += 1,
+                Ok(false) => {}
+                Err(e) => warn!("Failed to update withdrawal {}: {}", withdrawal.id, e),
+            }
+        }
+        Ok(changed)
+    }
+
+    async fn advance(
+        &self,
+        open: &OpenWithdrawal,
+        (status, kind, reason): (&str, WithdrawalEventKind, Option<String>),
+    ) -> PaymentResult<bool> {
+        let mut tx = self.db().begin().await.map_err(db_error)?;
+        let withdrawal = sqlx::query_as::<_, Withdrawal>(&format!(
+            r#"
+            UPDATE withdrawals SET status = $1, reason = COALESCE($2, reason), updated_at = NOW()
+            WHERE id = $3 AND status = $4
+            RETURNING {}
+            "#,
+            WITHDRAWAL_COLUMNS
+        ))
+        .bind(status)
+        .bind(&reason)
+        .bind(open.id)
+        .bind(&open.status)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        let Some(withdrawal) = withdrawal else {
+            return Ok(false);
+        };
+
+        let detail = EventDetail {
+            reason,
+            tx_hash: open.transaction_hash.clone(),
+            ..EventDetail::of(&withdrawal)
+        };
+        if kind == WithdrawalEventKind::Completed {
+            let event = NewPaymentEvent {
+                kind: PaymentEventKind::WithdrawalCompleted,
+                source_id: withdrawal.id,
+                bounty_id: None,
+                user_id: Some(withdrawal.user_id),
+                address: detail.address.clone(),
+                amount: detail.amount.clone(),
+                token_address: detail.token_address.clone(),
+                tx_hash: detail.tx_hash.clone(),
+            };
+            payment_events::record(&mut tx, &event).await?;
+        }
+        record(&mut tx, withdrawal.user_id, Some(withdrawal.id), kind, detail).await?;
+        tx.commit().await.map_err(db_error)?;
+        Ok(true)
+    }
+
+    /// Publish recorded events to their users, returning how many went out.
+    /// An event that fails to publish is retried on the next run.
+    pub async fn notify(&self) -> PaymentResult<usize> {
+        let events = sqlx::query_as::<_, UnnotifiedEvent>(
+            r#"
+            SELECT id, user_id, withdrawal_id, kind, detail, created_at
+            FROM withdrawal_events
+            WHERE notified_at IS NULL
+            ORDER BY created_at
+            LIMIT $1
+            "#,
+        )
+        .bind(NOTIFY_BATCH)
+        .fetch_all(self.db())
+        .await
+        .map_err(db_error)?;
+
+        let mut published = 0;
+        for event in events {
+            let Ok(kind) = serde_json::from_value::<WithdrawalEventKind>(json!(event.kind)) else {
+                warn!("Withdrawal event {} has unknown kind {}", event.id, event.kind);
+                continue;
+            };
+            let detail: EventDetail = serde_json::from_value(event.detail).unwrap_or_default();
+            let message = NexusEvent::WithdrawalUpdated(WithdrawalUpdatedEvent {
+                withdrawal_id: event.withdrawal_id,
+                user_id: event.user_id,
+                kind,
+                amount: detail.amount,
+                token_address: detail.token_address,
+                address: detail.address,
+                reason: detail.reason,
+                tx_hash: detail.tx_hash,
+                occurred_at: event.created_at,
+            });
+            if let Err(e) = shared::messaging::publish_event(&self.redis, &message).await {
+                warn!("Failed to publish withdrawal event {}: {}", event.id, e);
+                break;
+            }
+
+            sqlx::query("UPDATE withdrawal_events SET notified_at = NOW() WHERE id = $1")
+                .bind(event.id)
+                .execute(self.db())
+                .await
+                .map_err(db_error)?;
+            published += 1;
+        }
+        Ok(published)
+    }
+}
@@ -0,0 +1,27 @@
+use anyhow::Result;
+use sqlx::PgPool;
+use tracing::{info, warn};
+
+use crate::config::IdempotencyConfig;
+use crate::services::idempotency;
+
+/// Idempotency purge: deletes keys past their retention.
+pub async fn start(pool: PgPool, config: IdempotencyConfig) -> Result<()> {
+    info!("Idempotency purge worker started");
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(config.purge_interval_seconds));
+
+    loop {
+        interval.tick().await;
+
+        match idempotency::purge(&pool).await {
+            Ok(0) => {}
+            Ok(purged) => info!("Purged {} expired idempotency key(s)", purged),
+            Err(e) => {
+                // Table may not exist yet — non-fatal
+                if !e.to_string().contains("does not exist") {
+                    warn!("Idempotency key purge failed: {}", e);
+                }
+            }
+        }
+    }
+}
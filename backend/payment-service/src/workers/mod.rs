@@ -1,3 +1,12 @@
 pub mod transaction_monitor;
 pub mod pending_payment_processor;
 pub mod balance_reconciliation;
+pub mod settlement_listener;
+pub mod transaction_replacer;
+pub mod payout_batcher;
+pub mod idempotency_purge;
+pub mod withdrawal_monitor;
+pub mod payment_event_publisher;
+pub mod slash_executor;
+pub mod price_feed;
+pub mod relay_monitor;
@@ -0,0 +1,33 @@
+use anyhow::Result;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::services::payment_events::PaymentEventService;
+
+/// Payment event publisher: publishes recorded payment events on the message
+/// queue and sends the webhook deliveries that are due.
+pub async fn start(events: Arc<PaymentEventService>) -> Result<()> {
+    info!("Payment event publisher worker started");
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(events.interval_seconds()));
+
+    loop {
+        interval.tick().await;
+
+        match events.publish().await {
+            Ok(0) => {}
+            Ok(published) => info!("Published {} payment event(s)", published),
+            Err(e) => {
+                // Tables may not exist yet — non-fatal
+                if !e.to_string().contains("does not exist") {
+                    warn!("Payment event publication failed: {}", e);
+                }
+            }
+        }
+
+        if let Err(e) = events.deliver_due().await {
+            if !e.to_string().contains("does not exist") {
+                warn!("Payment webhook deliveries failed: {}", e);
+            }
+        }
+    }
+}
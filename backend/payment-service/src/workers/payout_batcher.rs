@@ -0,0 +1,30 @@
+use anyhow::Result;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::services::payouts::PayoutService;
+
+/// Payout batcher: opens reward payout batches for released escrows, sends
+/// their items from the treasury and tracks each to payment or failure.
+pub async fn start(payouts: Arc<PayoutService>) -> Result<()> {
+    info!("Payout batcher worker started");
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(payouts.interval_seconds()));
+
+    loop {
+        interval.tick().await;
+
+        match payouts.run().await {
+            Ok((0, 0, 0)) => {}
+            Ok((settled, opened, sent)) => info!(
+                "Payouts: {} item(s) settled, {} batch(es) opened, {} item(s) sent",
+                settled, opened, sent
+            ),
+            Err(e) => {
+                // Tables may not exist yet — non-fatal
+                if !e.to_string().contains("does not exist") {
+                    warn!("Payout run failed: {}", e);
+                }
+            }
+        }
+    }
+}
@@ -1,13 +1,22 @@
 use anyhow::Result;
+use ethers::types::{Address, U256};
 use std::sync::Arc;
 use tracing::{info, warn, error};
 
+use crate::blockchain::{TokenContract, TransactionBuilder};
+use crate::models::{PaymentError, PaymentResult};
+use crate::services::dead_letters::{self, FailureReason};
+use crate::services::nonces::NonceManager;
 use crate::services::payment_service::PaymentService;
 
 /// Pending payment processor: fetches queued payments from the database
 /// and processes them by recording status updates.
-/// Actual token transfers happen on-chain via BountyManager.resolveBounty().
-pub async fn start(service: Arc<PaymentService>) -> Result<()> {
+/// Payments from a wallet the service holds the key of (the treasury) are
+/// sent here as token transfers through the nonce manager; all others
+/// happen on-chain via BountyManager.resolveBounty(). A treasury payment
+/// whose send fails is retried on the next run until it runs out of
+/// attempts and is dead-lettered.
+pub async fn start(service: Arc<PaymentService>, nonces: Arc<NonceManager>) -> Result<()> {
     info!("Pending payment processor worker started");
     let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(60));
 
@@ -16,7 +25,10 @@ pub async fn start(service: Arc<PaymentService>) -> Result<()> {
 
         // Query queued payments
         let pending = sqlx::query_as::<_, PendingPayment>(
-            "SELECT id, bounty_id, recipient_address, amount FROM payments WHERE status = 'queued' LIMIT 20"
+            "SELECT id, bounty_id, payer_address, recipient_address, token_address, amount::TEXT AS amount, \
+             gas_price_override::TEXT AS gas_price_override, gas_limit_override::TEXT AS gas_limit_override, \
+             nonce_override \
+             FROM payments WHERE status = 'queued' LIMIT 20"
         )
         .fetch_all(service.db_pool())
         .await;
@@ -24,10 +36,24 @@ pub async fn start(service: Arc<PaymentService>) -> Result<()> {
         match pending {
             Ok(payments) => {
                 for payment in payments {
-                    info!(
-                        "Processing payment {} for bounty {} -> {} ({})",
-                        payment.id, payment.bounty_id, payment.recipient_address, payment.amount
-                    );
+                    match payment.bounty_id {
+                        Some(bounty_id) => info!(
+                            "Processing payment {} for bounty {} -> {} ({})",
+                            payment.id, bounty_id, payment.recipient_address, payment.amount
+                        ),
+                        None => info!(
+                            "Processing payment {} -> {} ({})",
+                            payment.id, payment.recipient_address, payment.amount
+                        ),
+                    }
+
+                    if nonces.manages(&payment.payer_address) {
+                        if let Err(e) = send(&service, &nonces, &payment).await {
+                            warn!("Failed to send payment {}: {}", payment.id, e);
+                            fail(&service, &payment, &e).await;
+                        }
+                        continue;
+                    }
 
                     // Mark as processing
                     let _ = sqlx::query(
@@ -58,10 +84,105 @@ pub async fn start(service: Arc<PaymentService>) -> Result<()> {
     }
 }
 
+/// Send a treasury payment as a transfer of its token and track it in the
+/// ledger, where the transaction monitor settles it
+async fn send(service: &PaymentService, nonces: &NonceManager, payment: &PendingPayment) -> PaymentResult<()> {
+    let parse = |value: &str| {
+        value
+            .parse::<Address>()
+            .map_err(|_| PaymentError::ValidationError(format!("{} is not an address", value)))
+    };
+    let (payer, recipient, token) = (
+        parse(&payment.payer_address)?,
+        parse(&payment.recipient_address)?,
+        parse(&payment.token_address)?,
+    );
+    let whole = payment.amount.split('.').next().unwrap_or_default();
+    let amount = U256::from_dec_str(whole)
+        .map_err(|_| PaymentError::ValidationError(format!("amount {} is not a number", payment.amount)))?;
+
+    let data = TokenContract::new(token, service.provider().clone())
+        .transfer(recipient, amount)
+        .calldata()
+        .unwrap_or_default();
+    let mut builder = TransactionBuilder::new(payer, token).data(data);
+    if let Some(gas_price) = &payment.gas_price_override {
+        builder = builder.gas_price(parse_wei(gas_price)?);
+    }
+    if let Some(gas_limit) = &payment.gas_limit_override {
+        builder = builder.gas_limit(parse_wei(gas_limit)?);
+    }
+    if let Some(nonce) = payment.nonce_override {
+        builder = builder.nonce(U256::from(nonce));
+    }
+    let sent = nonces.submit(Some(payment.id), builder).await?;
+
+    let mut tx = service.db_pool().begin().await.map_err(|e| PaymentError::DatabaseError(e.to_string()))?;
+    sqlx::query(
+        r#"
+        INSERT INTO payment_transactions (payment_id, transaction_hash, from_address, to_address, value)
+        VALUES ($1, $2, $3, $4, $5::NUMERIC)
+        "#,
+    )
+    .bind(payment.id)
+    .bind(&sent.tx_hash)
+    .bind(&payment.payer_address)
+    .bind(&payment.recipient_address)
+    .bind(amount.to_string())
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| PaymentError::DatabaseError(e.to_string()))?;
+    // A nonce set by an admin is used up by this send
+    sqlx::query(
+        "UPDATE payments SET status = 'processing', transaction_hash = $1, nonce_override = NULL, updated_at = NOW() WHERE id = $2",
+    )
+        .bind(&sent.tx_hash)
+        .bind(payment.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| PaymentError::DatabaseError(e.to_string()))?;
+    tx.commit().await.map_err(|e| PaymentError::DatabaseError(e.to_string()))?;
+
+    info!("Sent payment {} as {} (nonce {})", payment.id, sent.tx_hash, sent.nonce);
+    Ok(())
+}
+
+fn parse_wei(value: &str) -> PaymentResult<U256> {
+    U256::from_dec_str(value).map_err(|_| PaymentError::ValidationError(format!("{} is not a whole number", value)))
+}
+
+/// Count a failed send against the payment, dead-lettering it once it has
+/// used up its attempts
+async fn fail(service: &PaymentService, payment: &PendingPayment, failure: &PaymentError) {
+    let max_attempts = service.config().payment.max_retry_attempts;
+    let message = failure.to_string();
+    let reason = FailureReason::classify(&message);
+    let recorded = match service.db_pool().acquire().await {
+        Ok(mut db) => dead_letters::record_failure(&mut db, payment.id, reason, &message, max_attempts).await,
+        Err(e) => Err(PaymentError::DatabaseError(e.to_string())),
+    };
+    match recorded {
+        Ok(true) => error!(
+            "Payment {} dead-lettered after {} failed attempts ({})",
+            payment.id,
+            max_attempts,
+            reason.as_str()
+        ),
+        Ok(false) => {}
+        Err(e) => warn!("Failed to record the failed send of payment {}: {}", payment.id, e),
+    }
+}
+
 #[derive(sqlx::FromRow)]
 struct PendingPayment {
     id: uuid::Uuid,
-    bounty_id: uuid::Uuid,
+    /// Unset for withdrawals
+    bounty_id: Option<uuid::Uuid>,
+    payer_address: String,
     recipient_address: String,
+    token_address: String,
     amount: String,
+    gas_price_override: Option<String>,
+    gas_limit_override: Option<String>,
+    nonce_override: Option<i64>,
 }
@@ -0,0 +1,29 @@
+use anyhow::Result;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::services::prices::PriceService;
+
+/// Price feed: records token/USD rates and values treasury flows at the
+/// rate of their transaction time.
+pub async fn start(prices: Arc<PriceService>) -> Result<()> {
+    info!("Price feed worker started");
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(prices.interval_seconds()));
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = prices.refresh().await {
+            // Tables may not exist yet — non-fatal
+            if !e.to_string().contains("does not exist") {
+                warn!("Price refresh failed: {}", e);
+            }
+        }
+
+        if let Err(e) = prices.stamp().await {
+            if !e.to_string().contains("does not exist") {
+                warn!("Valuing treasury flows failed: {}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use anyhow::Result;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::services::relay::RelayService;
+
+/// Relay monitor: completes relayed gasless claims once their transfers are
+/// mined and fails those whose transactions reverted or were dropped.
+pub async fn start(relay: Arc<RelayService>) -> Result<()> {
+    info!("Relay monitor worker started");
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(relay.interval_seconds()));
+
+    loop {
+        interval.tick().await;
+
+        match relay.settle().await {
+            Ok(0) => {}
+            Ok(settled) => info!("Settled {} relay claim(s)", settled),
+            Err(e) => {
+                // Tables may not exist yet — non-fatal
+                if !e.to_string().contains("does not exist") {
+                    warn!("Relay claim settlement failed: {}", e);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use anyhow::Result;
+use futures::StreamExt;
+use shared::messaging::{EventSubscriber, NexusEvent, SettlementPlannedEvent, RESUBSCRIBE_DELAY};
+use shared::request_signing::SignatureVerifier;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+use crate::services::payment_service::PaymentService;
+use crate::services::settlement;
+
+/// Event the consensus-service publishes settlement plans under
+const SETTLEMENT_EVENT: &str = "settlement_planned";
+
+/// Settlement listener: settles engine stakes from the signed settlement
+/// plans the consensus-service publishes as bounties are finalized. Plans
+/// are announced again while a bounty stays open, so one missed here is
+/// picked up later. Plans are only applied with signing keys configured;
+/// `main` refuses to start without them.
+pub async fn start(
+    service: Arc<PaymentService>,
+    events: EventSubscriber,
+    verifier: SignatureVerifier,
+) -> Result<()> {
+    info!("Settlement listener started");
+
+    loop {
+        match events.subscribe(&[SETTLEMENT_EVENT]).await {
+            Ok(stream) => {
+                let mut stream = Box::pin(stream);
+                while let Some(event) = stream.next().await {
+                    let NexusEvent::SettlementPlanned(planned) = event else {
+                        continue;
+                    };
+                    if let Err(e) = handle(&service, &verifier, &planned).await {
+                        error!("Failed to settle stakes for bounty {}: {:#}", planned.plan.bounty_id, e);
+                    }
+                }
+                warn!("Settlement event stream ended");
+            }
+            Err(e) => error!("Settlement event subscription failed: {}", e),
+        }
+        tokio::time::sleep(RESUBSCRIBE_DELAY).await;
+    }
+}
+
+/// Apply a plan once its signature checks out. Unsigned or tampered plans
+/// are dropped.
+async fn handle(
+    service: &PaymentService,
+    verifier: &SignatureVerifier,
+    planned: &SettlementPlannedEvent,
+) -> Result<()> {
+    let plan = &planned.plan;
+    let payload = serde_json::to_vec(plan)?;
+    let now = chrono::Utc::now().timestamp();
+    if let Err(e) = verifier.verify_event(SETTLEMENT_EVENT, &payload, &planned.signature, now) {
+        warn!("Rejected settlement plan {} for bounty {}: {}", plan.plan_id, plan.bounty_id, e);
+        return Ok(());
+    }
+    settlement::apply(service, plan).await?;
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use anyhow::Result;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::services::slashing::SlashingService;
+
+/// Slash executor: executes scheduled slashes once their appeal window has
+/// passed and redistributes them to the engines that voted right.
+pub async fn start(slashing: Arc<SlashingService>) -> Result<()> {
+    info!("Slash executor worker started");
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(slashing.interval_seconds()));
+
+    loop {
+        interval.tick().await;
+
+        match slashing.execute_due().await {
+            Ok(0) => {}
+            Ok(executed) => info!("Executed {} slash(es)", executed),
+            Err(e) => {
+                // Tables may not exist yet — non-fatal
+                if !e.to_string().contains("does not exist") {
+                    warn!("Slash execution failed: {}", e);
+                }
+            }
+        }
+    }
+}
@@ -2,13 +2,18 @@ use anyhow::Result;
 use std::sync::Arc;
 use tracing::{info, warn, error};
 
+use crate::services::escrow;
 use crate::services::payment_service::PaymentService;
 
 /// Transaction monitor: polls the database for pending transactions
 /// and checks their on-chain receipt status, updating records accordingly.
 pub async fn start(service: Arc<PaymentService>) -> Result<()> {
-    info!("Transaction monitor worker started");
-    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(30));
+    // With an indexer pushing events, polling is only a safety sweep for
+    // webhooks that were never delivered
+    let indexer = &service.config().indexer;
+    let poll_seconds = if indexer.enabled { indexer.fallback_poll_seconds } else { 30 };
+    info!("Transaction monitor worker started (polling every {}s)", poll_seconds);
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(poll_seconds));
 
     loop {
         interval.tick().await;
@@ -23,29 +28,8 @@ pub async fn start(service: Arc<PaymentService>) -> Result<()> {
         match pending {
             Ok(txs) => {
                 for tx in txs {
-                    match service.get_tx_receipt(&tx.tx_hash).await {
-                        Ok(Some(receipt)) => {
-                            let status = if receipt.status == Some(1.into()) {
-                                "confirmed"
-                            } else {
-                                "failed"
-                            };
-                            let _ = sqlx::query(
-                                "UPDATE payment_transactions SET status = $1, block_number = $2, confirmed_at = NOW() WHERE id = $3"
-                            )
-                            .bind(status)
-                            .bind(receipt.block_number.map(|n| n.as_u64() as i64))
-                            .bind(tx.id)
-                            .execute(service.db_pool())
-                            .await;
-                            info!("Transaction {} status: {}", tx.tx_hash, status);
-                        }
-                        Ok(None) => {
-                            // Still pending, no action needed
-                        }
-                        Err(e) => {
-                            warn!("Failed to check tx {}: {}", tx.tx_hash, e);
-                        }
+                    if let Err(e) = reconcile_transaction(&service, tx.id, &tx.tx_hash).await {
+                        warn!("Failed to check tx {}: {}", tx.tx_hash, e);
                     }
                 }
             }
@@ -59,6 +43,41 @@ pub async fn start(service: Arc<PaymentService>) -> Result<()> {
     }
 }
 
+/// Settle a pending transaction from its on-chain receipt. The chain (via
+/// direct RPC) is the source of truth regardless of which source reported
+/// the transaction. Returns the new status and block, or `None` while the
+/// transaction is still unmined.
+pub async fn reconcile_transaction(
+    service: &PaymentService,
+    id: uuid::Uuid,
+    tx_hash: &str,
+) -> Result<Option<(&'static str, Option<u64>)>> {
+    let Some(receipt) = service.get_tx_receipt(tx_hash).await? else {
+        return Ok(None);
+    };
+
+    let status = if receipt.status == Some(1.into()) {
+        "confirmed"
+    } else {
+        "failed"
+    };
+    let block_number = receipt.block_number.map(|n| n.as_u64());
+    sqlx::query(
+        "UPDATE payment_transactions SET status = $1, block_number = $2, confirmed_at = NOW() WHERE id = $3 AND status = 'pending'"
+    )
+    .bind(status)
+    .bind(block_number.map(|n| n as i64))
+    .bind(id)
+    .execute(service.db_pool())
+    .await?;
+    info!("Transaction {} status: {}", tx_hash, status);
+
+    // Bounty deposits gate activation; settle their escrow with the receipt
+    escrow::settle_deposit(service, tx_hash, status).await?;
+
+    Ok(Some((status, block_number)))
+}
+
 #[derive(sqlx::FromRow)]
 struct PendingTx {
     id: uuid::Uuid,
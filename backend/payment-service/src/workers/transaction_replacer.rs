@@ -0,0 +1,30 @@
+use anyhow::Result;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::services::nonces::NonceManager;
+
+/// Transaction replacer: settles the service's own transactions once mined
+/// and replaces stuck ones at a higher gas price.
+pub async fn start(nonces: Arc<NonceManager>) -> Result<()> {
+    info!("Transaction replacer worker started");
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(nonces.interval_seconds()));
+
+    loop {
+        interval.tick().await;
+
+        match nonces.check().await {
+            Ok((0, 0)) => {}
+            Ok((settled, replaced)) => info!(
+                "Settled {} outgoing transaction(s), replaced {} stuck one(s)",
+                settled, replaced
+            ),
+            Err(e) => {
+                // Tables may not exist yet — non-fatal
+                if !e.to_string().contains("does not exist") {
+                    warn!("Outgoing transaction check failed: {}", e);
+                }
+            }
+        }
+    }
+}
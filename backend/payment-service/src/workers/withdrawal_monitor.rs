@@ -0,0 +1,33 @@
+use anyhow::Result;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::services::withdrawals::WithdrawalService;
+
+/// Withdrawal monitor: follows queued withdrawals to their payment's outcome
+/// and publishes withdrawal events to their users.
+pub async fn start(withdrawals: Arc<WithdrawalService>) -> Result<()> {
+    info!("Withdrawal monitor worker started");
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(withdrawals.interval_seconds()));
+
+    loop {
+        interval.tick().await;
+
+        match withdrawals.sync().await {
+            Ok(0) => {}
+            Ok(changed) => info!("Updated {} withdrawal(s) from their payments", changed),
+            Err(e) => {
+                // Tables may not exist yet — non-fatal
+                if !e.to_string().contains("does not exist") {
+                    warn!("Withdrawal sync failed: {}", e);
+                }
+            }
+        }
+
+        if let Err(e) = withdrawals.notify().await {
+            if !e.to_string().contains("does not exist") {
+                warn!("Withdrawal notifications failed: {}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,28 @@
+-- Create voting_power_snapshots table
+CREATE TABLE IF NOT EXISTS voting_power_snapshots (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    label VARCHAR(255) NOT NULL,
+    snapshot_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    block_number BIGINT,
+    formula JSONB NOT NULL,
+    voter_count INTEGER NOT NULL DEFAULT 0,
+    total_voting_power DOUBLE PRECISION NOT NULL DEFAULT 0,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- Create voting_power_snapshot_entries table (eligible users only)
+CREATE TABLE IF NOT EXISTS voting_power_snapshot_entries (
+    snapshot_id UUID NOT NULL,
+    user_id UUID NOT NULL,
+    reputation INTEGER NOT NULL,
+    staked DOUBLE PRECISION NOT NULL,
+    reputation_power DOUBLE PRECISION NOT NULL,
+    stake_power DOUBLE PRECISION NOT NULL,
+    voting_power DOUBLE PRECISION NOT NULL,
+    PRIMARY KEY (snapshot_id, user_id),
+    FOREIGN KEY (snapshot_id) REFERENCES voting_power_snapshots(id) ON DELETE CASCADE
+);
+
+-- Create indexes for point-in-time lookups
+CREATE INDEX IF NOT EXISTS idx_voting_power_snapshots_snapshot_at ON voting_power_snapshots(snapshot_at DESC);
+CREATE INDEX IF NOT EXISTS idx_reputation_history_user_created ON reputation_history(user_id, created_at);
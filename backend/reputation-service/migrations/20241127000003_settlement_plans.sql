@@ -0,0 +1,9 @@
+-- Settlement plans from the consensus-service whose reputation deltas have
+-- been applied. Plans are announced more than once; each is applied once.
+CREATE TABLE IF NOT EXISTS applied_settlement_plans (
+    plan_id UUID PRIMARY KEY,
+    bounty_id UUID NOT NULL,
+    applied_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_reputation_history_bounty_id ON reputation_history(bounty_id);
@@ -0,0 +1,41 @@
+-- Badges awarded automatically as reputation changes
+
+-- Specialization badges count correct analyses of one kind of sample
+ALTER TABLE badges ADD COLUMN IF NOT EXISTS specialization VARCHAR(50);
+ALTER TABLE badges ADD COLUMN IF NOT EXISTS min_specialization_count INTEGER;
+
+-- Settled analyses per user and final verdict of the sample
+CREATE TABLE IF NOT EXISTS user_specializations (
+    user_id UUID NOT NULL,
+    specialization VARCHAR(50) NOT NULL,
+    correct_count INTEGER NOT NULL DEFAULT 0,
+    total_count INTEGER NOT NULL DEFAULT 0,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, specialization),
+    FOREIGN KEY (user_id) REFERENCES user_reputation(user_id) ON DELETE CASCADE
+);
+
+-- Progress towards badges not yet earned, from 0 to 100
+CREATE TABLE IF NOT EXISTS badge_progress (
+    user_id UUID NOT NULL,
+    badge_id UUID NOT NULL,
+    progress DECIMAL(5, 2) NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, badge_id),
+    FOREIGN KEY (user_id) REFERENCES user_reputation(user_id) ON DELETE CASCADE,
+    FOREIGN KEY (badge_id) REFERENCES badges(id) ON DELETE CASCADE
+);
+
+-- Users are evaluated again whenever their reputation changes after the
+-- last evaluation
+ALTER TABLE user_reputation ADD COLUMN IF NOT EXISTS badges_evaluated_at TIMESTAMP WITH TIME ZONE;
+CREATE INDEX IF NOT EXISTS idx_user_reputation_badges_pending ON user_reputation(last_updated)
+    WHERE badges_evaluated_at IS NULL OR last_updated > badges_evaluated_at;
+
+INSERT INTO badges (name, description, icon, rarity, specialization, min_specialization_count)
+VALUES
+    ('Malware Hunter', 'Correctly flagged 25 malicious samples', '🦠', 'uncommon', 'malicious', 25),
+    ('Threat Slayer', 'Correctly flagged 250 malicious samples', '🗡️', 'epic', 'malicious', 250),
+    ('Clean Sweep', 'Correctly cleared 25 benign samples', '🧹', 'uncommon', 'benign', 25),
+    ('Gray Area Expert', 'Correctly called 25 suspicious samples', '🌫️', 'rare', 'suspicious', 25)
+ON CONFLICT (name) DO NOTHING;
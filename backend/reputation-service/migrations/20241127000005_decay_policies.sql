@@ -0,0 +1,51 @@
+-- Reputation decay policies, tuned through the admin API
+
+-- A user decays by the tier their current score falls in: the tier with the
+-- highest min_score not above it. Decay starts grace_period_days after the
+-- user's last settled submission and stops at a floor of
+-- highest_score x lifetime accuracy x accuracy_floor_ratio.
+CREATE TABLE IF NOT EXISTS decay_tiers (
+    name VARCHAR(50) PRIMARY KEY,
+    min_score INTEGER NOT NULL UNIQUE,
+    rate_per_day DOUBLE PRECISION NOT NULL CHECK (rate_per_day >= 0 AND rate_per_day <= 1),
+    grace_period_days INTEGER NOT NULL CHECK (grace_period_days >= 0),
+    accuracy_floor_ratio DOUBLE PRECISION NOT NULL CHECK (accuracy_floor_ratio >= 0 AND accuracy_floor_ratio <= 1),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- One row of settings for decay as a whole
+CREATE TABLE IF NOT EXISTS decay_settings (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    -- Owners of exempted engines keep their score while the engine's share
+    -- of delivered analyses over the window stays at or above this
+    engine_min_uptime DOUBLE PRECISION NOT NULL DEFAULT 0.99,
+    uptime_window_days INTEGER NOT NULL DEFAULT 30,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- Automated engines verified for the decay exemption
+CREATE TABLE IF NOT EXISTS decay_engine_exemptions (
+    engine_id UUID PRIMARY KEY,
+    verified_by UUID,
+    verified_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- Decay runs from the last settled submission, not from the last change,
+-- which decay itself makes
+ALTER TABLE user_reputation ADD COLUMN IF NOT EXISTS last_active_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE user_reputation ADD COLUMN IF NOT EXISTS last_decayed_at TIMESTAMP WITH TIME ZONE;
+UPDATE user_reputation SET last_active_at = last_updated WHERE last_active_at IS NULL;
+ALTER TABLE user_reputation ALTER COLUMN last_active_at SET DEFAULT NOW();
+ALTER TABLE user_reputation ALTER COLUMN last_active_at SET NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_user_reputation_last_active ON user_reputation(last_active_at);
+
+INSERT INTO decay_settings (id) VALUES (TRUE) ON CONFLICT (id) DO NOTHING;
+
+INSERT INTO decay_tiers (name, min_score, rate_per_day, grace_period_days, accuracy_floor_ratio)
+VALUES
+    ('novice', 0, 0.001, 30, 0.5),
+    ('established', 500, 0.002, 21, 0.5),
+    ('expert', 2000, 0.003, 14, 0.6),
+    ('elite', 5000, 0.005, 7, 0.7)
+ON CONFLICT (name) DO NOTHING;
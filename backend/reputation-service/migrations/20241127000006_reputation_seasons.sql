@@ -0,0 +1,56 @@
+-- Quarterly reputation seasons
+
+-- One season per calendar quarter, named like 2026-Q3. When a season ends its
+-- standings are archived, the top of the leaderboard is awarded a season
+-- badge and every score is soft reset to carry_over_ratio of itself.
+CREATE TABLE IF NOT EXISTS reputation_seasons (
+    id VARCHAR(16) PRIMARY KEY,
+    starts_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    ends_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    carry_over_ratio DOUBLE PRECISION NOT NULL CHECK (carry_over_ratio >= 0 AND carry_over_ratio <= 1),
+    status VARCHAR(20) NOT NULL DEFAULT 'active' CHECK (status IN ('active', 'archived')),
+    archived_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    CHECK (ends_at > starts_at)
+);
+
+-- Only one season runs at a time
+CREATE UNIQUE INDEX IF NOT EXISTS idx_reputation_seasons_active ON reputation_seasons(status)
+    WHERE status = 'active';
+
+-- Final leaderboard of an archived season: everyone with a settled
+-- submission during it, ranked by their score when it ended
+CREATE TABLE IF NOT EXISTS season_standings (
+    season_id VARCHAR(16) NOT NULL REFERENCES reputation_seasons(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL,
+    rank INTEGER NOT NULL,
+    score INTEGER NOT NULL,
+    accuracy_rate DECIMAL(5, 2) NOT NULL,
+    total_submissions INTEGER NOT NULL,
+    PRIMARY KEY (season_id, user_id),
+    FOREIGN KEY (user_id) REFERENCES user_reputation(user_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_season_standings_rank ON season_standings(season_id, rank);
+
+-- Season badges awarded when a season ended. user_badges holds a badge once;
+-- this keeps every season it was won in.
+CREATE TABLE IF NOT EXISTS season_awards (
+    season_id VARCHAR(16) NOT NULL REFERENCES reputation_seasons(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL,
+    badge_id UUID NOT NULL REFERENCES badges(id) ON DELETE CASCADE,
+    rank INTEGER NOT NULL,
+    awarded_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (season_id, user_id),
+    FOREIGN KEY (user_id) REFERENCES user_reputation(user_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_season_awards_user_id ON season_awards(user_id);
+
+-- Awarded by rank at the end of a season, never from criteria
+INSERT INTO badges (name, description, icon, rarity)
+VALUES
+    ('Season Champion', 'Finished a season first on the leaderboard', '🏆', 'legendary'),
+    ('Season Podium', 'Finished a season in the top 3', '🥈', 'epic'),
+    ('Season Top 10', 'Finished a season in the top 10', '🎖️', 'rare')
+ON CONFLICT (name) DO NOTHING;
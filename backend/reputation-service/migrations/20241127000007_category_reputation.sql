@@ -0,0 +1,33 @@
+-- Reputation per threat category
+
+-- A bounty is in every category one of its tags (bounty_tag_assignments) is
+-- listed under
+CREATE TABLE IF NOT EXISTS reputation_categories (
+    slug VARCHAR(64) PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    tags TEXT[] NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+-- Settled reputation deltas of bounties in a category, kept beside the
+-- global score
+CREATE TABLE IF NOT EXISTS user_category_reputation (
+    user_id UUID NOT NULL,
+    category VARCHAR(64) NOT NULL REFERENCES reputation_categories(slug) ON DELETE CASCADE,
+    score INTEGER NOT NULL DEFAULT 0,
+    total_submissions INTEGER NOT NULL DEFAULT 0,
+    correct_submissions INTEGER NOT NULL DEFAULT 0,
+    last_updated TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, category),
+    FOREIGN KEY (user_id) REFERENCES user_reputation(user_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_category_reputation_score ON user_category_reputation(category, score DESC);
+
+INSERT INTO reputation_categories (slug, name, tags)
+VALUES
+    ('ransomware', 'Ransomware', ARRAY['ransomware']),
+    ('phishing', 'Phishing', ARRAY['phishing', 'malicious-url']),
+    ('mobile', 'Mobile', ARRAY['mobile']),
+    ('apt', 'APT', ARRAY['apt', 'c2'])
+ON CONFLICT (slug) DO NOTHING;
@@ -0,0 +1,48 @@
+-- Reputation change events and webhooks
+
+-- Score changes are announced from reputation_history, whatever made them
+-- (settlement, decay, season resets). Rows already there when this runs are
+-- history, not news.
+ALTER TABLE reputation_history
+    ADD COLUMN IF NOT EXISTS announced_at TIMESTAMP WITH TIME ZONE;
+
+UPDATE reputation_history SET announced_at = created_at WHERE announced_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_reputation_history_unannounced ON reputation_history(created_at)
+    WHERE announced_at IS NULL;
+
+-- user_reputation.rank holds the last rank announced; ranks are recomputed
+-- and compared on every pass of the event publisher.
+
+-- A user's subscription to changes in their own standing
+CREATE TABLE IF NOT EXISTS reputation_webhooks (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    events TEXT[] NOT NULL,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_reputation_webhooks_user_id ON reputation_webhooks(user_id);
+
+-- One event queued for, or sent to, a webhook
+CREATE TABLE IF NOT EXISTS reputation_webhook_deliveries (
+    id UUID PRIMARY KEY,
+    webhook_id UUID NOT NULL REFERENCES reputation_webhooks(id) ON DELETE CASCADE,
+    event_type VARCHAR(50) NOT NULL,
+    payload JSONB NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'delivered', 'failed')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    response_status INTEGER,
+    last_error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    delivered_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_reputation_webhook_deliveries_due ON reputation_webhook_deliveries(next_attempt_at)
+    WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_reputation_webhook_deliveries_webhook ON reputation_webhook_deliveries(webhook_id, created_at DESC);
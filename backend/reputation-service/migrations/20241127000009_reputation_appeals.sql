@@ -0,0 +1,61 @@
+-- Reputation appeals
+
+-- A user contesting one reputation penalty. Each history entry can be
+-- appealed once; an upheld appeal reverses the penalty with a new history
+-- entry (reversal_history_id).
+CREATE TABLE IF NOT EXISTS reputation_appeals (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES user_reputation(user_id) ON DELETE CASCADE,
+    history_id UUID NOT NULL UNIQUE REFERENCES reputation_history(id) ON DELETE CASCADE,
+    reason TEXT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'open'
+        CHECK (status IN ('open', 'under_review', 'upheld', 'rejected', 'withdrawn')),
+    -- Set while an arbitration panel reviews the appeal
+    voting_deadline TIMESTAMP WITH TIME ZONE,
+    -- The admin who decided, NULL when a panel did
+    decided_by UUID,
+    decision_notes TEXT,
+    reversal_history_id UUID REFERENCES reputation_history(id),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    decided_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_reputation_appeals_user_id ON reputation_appeals(user_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_reputation_appeals_status ON reputation_appeals(status, created_at);
+
+CREATE TABLE IF NOT EXISTS reputation_appeal_evidence (
+    id UUID PRIMARY KEY,
+    appeal_id UUID NOT NULL REFERENCES reputation_appeals(id) ON DELETE CASCADE,
+    description TEXT NOT NULL,
+    url TEXT,
+    added_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_reputation_appeal_evidence_appeal ON reputation_appeal_evidence(appeal_id, added_at);
+
+-- Arbitrators seated on an appeal's panel and, once cast, their votes
+CREATE TABLE IF NOT EXISTS reputation_appeal_panelists (
+    appeal_id UUID NOT NULL REFERENCES reputation_appeals(id) ON DELETE CASCADE,
+    arbitrator_id UUID NOT NULL,
+    uphold BOOLEAN,
+    notes TEXT,
+    seated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    voted_at TIMESTAMP WITH TIME ZONE,
+    PRIMARY KEY (appeal_id, arbitrator_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_reputation_appeal_panelists_arbitrator ON reputation_appeal_panelists(arbitrator_id);
+
+-- Audit trail: everything that happened to an appeal, by whom
+CREATE TABLE IF NOT EXISTS reputation_appeal_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    appeal_id UUID NOT NULL REFERENCES reputation_appeals(id) ON DELETE CASCADE,
+    -- NULL for the system, e.g. a panel's deadline passing
+    actor_id UUID,
+    action VARCHAR(30) NOT NULL,
+    details JSONB NOT NULL DEFAULT '{}'::JSONB,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_reputation_appeal_events_appeal ON reputation_appeal_events(appeal_id, created_at);
@@ -0,0 +1,12 @@
+-- Windowed leaderboards
+
+-- The leaderboard updater adds score changes to the Redis leaderboards as
+-- they are recorded and marks them ranked. Rows already there when this runs
+-- are counted when the leaderboards are first built from the database.
+ALTER TABLE reputation_history
+    ADD COLUMN IF NOT EXISTS ranked_at TIMESTAMP WITH TIME ZONE;
+
+UPDATE reputation_history SET ranked_at = created_at WHERE ranked_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_reputation_history_unranked ON reputation_history(created_at)
+    WHERE ranked_at IS NULL;
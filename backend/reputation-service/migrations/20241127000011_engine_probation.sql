@@ -0,0 +1,26 @@
+-- Probation of new engines
+
+-- Engines start on probation: their votes carry less weight and their reward
+-- share is capped until they settle enough calibration bounties, whose
+-- verdict the platform knows. How many they got right seeds their score.
+-- Users already here predate probation and are through it.
+ALTER TABLE user_reputation
+    ADD COLUMN IF NOT EXISTS calibrations_completed INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS calibrations_correct INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS probation_ended_at TIMESTAMP WITH TIME ZONE;
+
+UPDATE user_reputation SET probation_ended_at = created_at WHERE probation_ended_at IS NULL;
+
+CREATE TABLE IF NOT EXISTS reputation_calibrations (
+    user_id UUID NOT NULL REFERENCES user_reputation(user_id) ON DELETE CASCADE,
+    bounty_id UUID NOT NULL,
+    submission_id UUID NOT NULL,
+    plan_id UUID NOT NULL,
+    verdict VARCHAR(20) NOT NULL,
+    expected_verdict VARCHAR(20) NOT NULL,
+    correct BOOLEAN NOT NULL,
+    settled_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, bounty_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_reputation_calibrations_user ON reputation_calibrations(user_id, settled_at DESC);
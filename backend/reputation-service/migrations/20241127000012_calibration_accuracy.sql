@@ -0,0 +1,29 @@
+-- Engine accuracy on calibration bounties
+
+-- Every engine's calibration results are kept, not only those of engines on
+-- probation: with the verdict known up front they measure true accuracy,
+-- which consensus can't when most engines are wrong together. Results are
+-- checked for degradation once each, as the monitor gets to them.
+ALTER TABLE reputation_calibrations
+    ADD COLUMN IF NOT EXISTS checked_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX IF NOT EXISTS idx_reputation_calibrations_unchecked
+    ON reputation_calibrations(settled_at) WHERE checked_at IS NULL;
+
+-- An engine whose recent calibration accuracy fell well below its own
+-- baseline; resolved once it recovers
+CREATE TABLE IF NOT EXISTS calibration_degradations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES user_reputation(user_id) ON DELETE CASCADE,
+    baseline_correct INTEGER NOT NULL,
+    baseline_samples INTEGER NOT NULL,
+    recent_correct INTEGER NOT NULL,
+    recent_samples INTEGER NOT NULL,
+    detected_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    resolved_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_calibration_degradations_open
+    ON calibration_degradations(user_id) WHERE resolved_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_calibration_degradations_user
+    ON calibration_degradations(user_id, detected_at DESC);
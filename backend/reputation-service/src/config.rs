@@ -7,6 +7,16 @@ pub struct Config {
     pub database: DatabaseConfig,
     pub redis: RedisConfig,
     pub reputation: ReputationConfig,
+    pub governance: GovernanceConfig,
+    pub badges: BadgeConfig,
+    pub seasons: SeasonConfig,
+    pub events: EventConfig,
+    pub webhooks: WebhookConfig,
+    pub standing: StandingConfig,
+    pub appeals: AppealConfig,
+    pub leaderboard: LeaderboardConfig,
+    pub probation: ProbationConfig,
+    pub calibration: CalibrationConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,6 +49,164 @@ pub struct ReputationConfig {
     pub early_submission_bonus: i32,
 }
 
+/// How a raw input is scaled before its weight is applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VotingCurve {
+    Linear,
+    /// Quadratic-voting style; dampens large holders
+    Sqrt,
+    /// ln(1 + x)
+    Log,
+}
+
+impl std::str::FromStr for VotingCurve {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "linear" => Ok(Self::Linear),
+            "sqrt" => Ok(Self::Sqrt),
+            "log" => Ok(Self::Log),
+            other => Err(anyhow::anyhow!("Unknown voting curve: {}", other)),
+        }
+    }
+}
+
+/// Voting power formula. Stored with every snapshot so consumers can see
+/// how the weights were derived.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GovernanceConfig {
+    /// Users below this reputation score have no voting power at all
+    pub min_reputation: i32,
+    pub reputation_weight: f64,
+    pub reputation_curve: VotingCurve,
+    /// Upper bound on the reputation component
+    pub reputation_cap: f64,
+    pub stake_weight: f64,
+    pub stake_curve: VotingCurve,
+    /// Upper bound on the stake component
+    pub stake_cap: f64,
+    /// Upper bound on a single user's total voting power
+    pub max_voting_power: f64,
+    /// Staking pool types that count towards voting power
+    pub stake_pool_types: Vec<String>,
+    /// Chain whose indexed blocks are used to resolve block snapshots
+    pub chain_id: i32,
+    pub max_bulk_users: usize,
+}
+
+/// Automatic badge awarding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BadgeConfig {
+    /// How often users whose reputation changed are evaluated
+    pub evaluation_interval_secs: u64,
+    /// Users evaluated per pass
+    pub batch_size: i64,
+}
+
+/// Quarterly reputation seasons
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeasonConfig {
+    /// Share of each score carried into the next season, set on a season
+    /// when it starts
+    pub carry_over_ratio: f64,
+    /// How often the current season is checked for having ended
+    pub check_interval_secs: u64,
+}
+
+/// Reputation change events, published to the message queue and queued for
+/// users' webhooks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventConfig {
+    /// How often new score changes and rank moves are looked for
+    pub interval_secs: u64,
+    /// Score changes announced per pass
+    pub batch_size: i64,
+    /// Rank moves are announced for users ranked this high or higher before
+    /// or after the move; further down ranks shift with every settlement
+    pub rank_announce_limit: i32,
+}
+
+/// Webhooks users register to follow their own standing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    /// How often due deliveries are sent
+    pub interval_secs: u64,
+    /// Deliveries sent per pass
+    pub batch_size: i64,
+    /// Attempts before a delivery is given up on
+    pub max_attempts: u32,
+    /// Wait after the first failed attempt, doubled after each further one
+    pub backoff_base_secs: u64,
+    pub backoff_max_secs: u64,
+    pub timeout_secs: u64,
+    pub max_per_user: i64,
+}
+
+/// Reputation reads by other services
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StandingConfig {
+    /// Most users looked up in one bulk request
+    pub max_bulk_ids: usize,
+    /// How long a user's standing is served from Redis; consensus weighting
+    /// tolerates this much staleness
+    pub cache_ttl_secs: u64,
+}
+
+/// Appeals against reputation penalties
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppealConfig {
+    /// How long after a penalty it can be appealed
+    pub window_days: i64,
+    /// Arbitrators seated when an appeal is escalated
+    pub panel_size: i64,
+    /// Lowest score a user can arbitrate with
+    pub min_arbitrator_score: i32,
+    /// How long a panel has to decide
+    pub voting_hours: i64,
+    /// How often panels past their deadline are closed
+    pub check_interval_secs: u64,
+    /// Most pieces of evidence on one appeal
+    pub max_evidence: usize,
+}
+
+/// Windowed and filtered leaderboards, kept in Redis
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaderboardConfig {
+    /// How often new score changes are added to the leaderboards
+    pub interval_secs: u64,
+    /// Score changes added per pass
+    pub batch_size: i64,
+    /// How long a windowed leaderboard is served before it is summed again
+    /// from its hourly buckets
+    pub window_cache_secs: u64,
+}
+
+/// Probation of new engines
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProbationConfig {
+    /// Calibration bounties an engine settles before its probation ends
+    pub calibrations: i32,
+    /// Score an engine that got every calibration bounty right starts with
+    pub max_seed_score: i32,
+}
+
+/// Monitoring of engine accuracy on calibration bounties
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalibrationConfig {
+    /// How often new calibration results are checked for degradation
+    pub check_interval_secs: u64,
+    /// Calibration results checked per pass
+    pub batch_size: i64,
+    /// Most recent results an engine's accuracy is judged on
+    pub recent_window: usize,
+    /// Fewest earlier results recent accuracy is compared against
+    pub min_baseline_samples: i32,
+    /// Smallest fall in accuracy, 0.0 to 1.0, flagged as degradation
+    pub min_accuracy_drop: f64,
+}
+
 impl Config {
     pub fn from_env() -> Result<Self> {
         Ok(Self {
@@ -88,6 +256,158 @@ impl Config {
                     .unwrap_or_else(|_| "10".to_string())
                     .parse()?,
             },
+            governance: GovernanceConfig {
+                min_reputation: std::env::var("VOTING_MIN_REPUTATION")
+                    .unwrap_or_else(|_| "100".to_string())
+                    .parse()?,
+                reputation_weight: std::env::var("VOTING_REPUTATION_WEIGHT")
+                    .unwrap_or_else(|_| "1.0".to_string())
+                    .parse()?,
+                reputation_curve: std::env::var("VOTING_REPUTATION_CURVE")
+                    .unwrap_or_else(|_| "linear".to_string())
+                    .parse()?,
+                reputation_cap: std::env::var("VOTING_REPUTATION_CAP")
+                    .unwrap_or_else(|_| "5000".to_string())
+                    .parse()?,
+                stake_weight: std::env::var("VOTING_STAKE_WEIGHT")
+                    .unwrap_or_else(|_| "10.0".to_string())
+                    .parse()?,
+                stake_curve: std::env::var("VOTING_STAKE_CURVE")
+                    .unwrap_or_else(|_| "sqrt".to_string())
+                    .parse()?,
+                stake_cap: std::env::var("VOTING_STAKE_CAP")
+                    .unwrap_or_else(|_| "5000".to_string())
+                    .parse()?,
+                max_voting_power: std::env::var("VOTING_MAX_POWER")
+                    .unwrap_or_else(|_| "8000".to_string())
+                    .parse()?,
+                stake_pool_types: std::env::var("VOTING_STAKE_POOL_TYPES")
+                    .unwrap_or_else(|_| "governance_staking,reputation_staking".to_string())
+                    .split(',')
+                    .map(|t| t.trim().to_string())
+                    .filter(|t| !t.is_empty())
+                    .collect(),
+                chain_id: std::env::var("VOTING_CHAIN_ID")
+                    .unwrap_or_else(|_| "1".to_string())
+                    .parse()?,
+                max_bulk_users: std::env::var("VOTING_MAX_BULK_USERS")
+                    .unwrap_or_else(|_| "500".to_string())
+                    .parse()?,
+            },
+            badges: BadgeConfig {
+                evaluation_interval_secs: std::env::var("BADGE_EVALUATION_INTERVAL_SECS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()?,
+                batch_size: std::env::var("BADGE_EVALUATION_BATCH_SIZE")
+                    .unwrap_or_else(|_| "200".to_string())
+                    .parse()?,
+            },
+            seasons: SeasonConfig {
+                carry_over_ratio: std::env::var("SEASON_CARRY_OVER_RATIO")
+                    .unwrap_or_else(|_| "0.5".to_string())
+                    .parse()?,
+                check_interval_secs: std::env::var("SEASON_CHECK_INTERVAL_SECS")
+                    .unwrap_or_else(|_| "300".to_string())
+                    .parse()?,
+            },
+            events: EventConfig {
+                interval_secs: std::env::var("REPUTATION_EVENTS_INTERVAL_SECS")
+                    .unwrap_or_else(|_| "15".to_string())
+                    .parse()?,
+                batch_size: std::env::var("REPUTATION_EVENTS_BATCH_SIZE")
+                    .unwrap_or_else(|_| "500".to_string())
+                    .parse()?,
+                rank_announce_limit: std::env::var("REPUTATION_RANK_ANNOUNCE_LIMIT")
+                    .unwrap_or_else(|_| "100".to_string())
+                    .parse()?,
+            },
+            webhooks: WebhookConfig {
+                interval_secs: std::env::var("REPUTATION_WEBHOOK_INTERVAL_SECS")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()?,
+                batch_size: std::env::var("REPUTATION_WEBHOOK_BATCH_SIZE")
+                    .unwrap_or_else(|_| "100".to_string())
+                    .parse()?,
+                max_attempts: std::env::var("REPUTATION_WEBHOOK_MAX_ATTEMPTS")
+                    .unwrap_or_else(|_| "8".to_string())
+                    .parse()?,
+                backoff_base_secs: std::env::var("REPUTATION_WEBHOOK_BACKOFF_BASE_SECS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()?,
+                backoff_max_secs: std::env::var("REPUTATION_WEBHOOK_BACKOFF_MAX_SECS")
+                    .unwrap_or_else(|_| "3600".to_string())
+                    .parse()?,
+                timeout_secs: std::env::var("REPUTATION_WEBHOOK_TIMEOUT_SECS")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()?,
+                max_per_user: std::env::var("REPUTATION_WEBHOOKS_PER_USER")
+                    .unwrap_or_else(|_| "5".to_string())
+                    .parse()?,
+            },
+            standing: StandingConfig {
+                max_bulk_ids: std::env::var("REPUTATION_BULK_MAX_IDS")
+                    .unwrap_or_else(|_| "1000".to_string())
+                    .parse()?,
+                cache_ttl_secs: std::env::var("REPUTATION_CACHE_TTL_SECS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()?,
+            },
+            appeals: AppealConfig {
+                window_days: std::env::var("REPUTATION_APPEAL_WINDOW_DAYS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()?,
+                panel_size: std::env::var("REPUTATION_APPEAL_PANEL_SIZE")
+                    .unwrap_or_else(|_| "5".to_string())
+                    .parse()?,
+                min_arbitrator_score: std::env::var("REPUTATION_APPEAL_MIN_ARBITRATOR_SCORE")
+                    .unwrap_or_else(|_| "5000".to_string())
+                    .parse()?,
+                voting_hours: std::env::var("REPUTATION_APPEAL_VOTING_HOURS")
+                    .unwrap_or_else(|_| "72".to_string())
+                    .parse()?,
+                check_interval_secs: std::env::var("REPUTATION_APPEAL_CHECK_INTERVAL_SECS")
+                    .unwrap_or_else(|_| "300".to_string())
+                    .parse()?,
+                max_evidence: std::env::var("REPUTATION_APPEAL_MAX_EVIDENCE")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()?,
+            },
+            leaderboard: LeaderboardConfig {
+                interval_secs: std::env::var("REPUTATION_LEADERBOARD_INTERVAL_SECS")
+                    .unwrap_or_else(|_| "15".to_string())
+                    .parse()?,
+                batch_size: std::env::var("REPUTATION_LEADERBOARD_BATCH_SIZE")
+                    .unwrap_or_else(|_| "1000".to_string())
+                    .parse()?,
+                window_cache_secs: std::env::var("REPUTATION_LEADERBOARD_WINDOW_CACHE_SECS")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()?,
+            },
+            probation: ProbationConfig {
+                calibrations: std::env::var("REPUTATION_PROBATION_CALIBRATIONS")
+                    .unwrap_or_else(|_| "5".to_string())
+                    .parse()?,
+                max_seed_score: std::env::var("REPUTATION_PROBATION_MAX_SEED_SCORE")
+                    .unwrap_or_else(|_| "500".to_string())
+                    .parse()?,
+            },
+            calibration: CalibrationConfig {
+                check_interval_secs: std::env::var("REPUTATION_CALIBRATION_CHECK_INTERVAL_SECS")
+                    .unwrap_or_else(|_| "300".to_string())
+                    .parse()?,
+                batch_size: std::env::var("REPUTATION_CALIBRATION_BATCH_SIZE")
+                    .unwrap_or_else(|_| "500".to_string())
+                    .parse()?,
+                recent_window: std::env::var("REPUTATION_CALIBRATION_RECENT_WINDOW")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()?,
+                min_baseline_samples: std::env::var("REPUTATION_CALIBRATION_MIN_BASELINE_SAMPLES")
+                    .unwrap_or_else(|_| "20".to_string())
+                    .parse()?,
+                min_accuracy_drop: std::env::var("REPUTATION_CALIBRATION_MIN_ACCURACY_DROP")
+                    .unwrap_or_else(|_| "0.2".to_string())
+                    .parse()?,
+            },
         })
     }
 }
@@ -1,7 +1,25 @@
-use axum::{extract::{State, Path}, response::Json, http::StatusCode};
+use axum::{extract::{State, Path}, response::Json, http::{HeaderMap, StatusCode}};
 use serde_json::{json, Value};
 use std::sync::Arc;
+use uuid::Uuid;
 use crate::AppState;
+use crate::models::ReputationError;
+use crate::services::decay::{DecaySettingsRequest, DecayTierRequest};
+
+fn error_response(error: ReputationError) -> (StatusCode, Json<Value>) {
+    let status = match error {
+        ReputationError::ValidationError(_) => StatusCode::BAD_REQUEST,
+        ReputationError::NotFound(_) => StatusCode::NOT_FOUND,
+        ReputationError::DatabaseError(_) | ReputationError::CalculationError(_) => {
+            tracing::error!("Decay policy update failed: {}", error);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to update decay policy"})),
+            );
+        }
+    };
+    (status, Json(json!({"error": error.to_string()})))
+}
 
 pub async fn recalculate_reputation(
     State(_state): State<Arc<AppState>>,
@@ -22,3 +40,78 @@ pub async fn award_badge(
 ) -> (StatusCode, Json<Value>) {
     (StatusCode::OK, Json(json!({"message": "Badge awarded"})))
 }
+
+/// The decay policy in force, with the engines verified for the exemption
+pub async fn get_decay_policy(
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<Value>) {
+    let service = &state.decay_service;
+    let policy = match service.policy().await {
+        Ok(policy) => policy,
+        Err(e) => return error_response(e),
+    };
+    match service.exemptions().await {
+        Ok(exemptions) => (StatusCode::OK, Json(json!({"policy": policy, "exemptions": exemptions}))),
+        Err(e) => error_response(e),
+    }
+}
+
+/// Switch decay on or off, or change the engine uptime SLA
+pub async fn update_decay_settings(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<DecaySettingsRequest>,
+) -> (StatusCode, Json<Value>) {
+    match state.decay_service.update_settings(&payload).await {
+        Ok(policy) => (StatusCode::OK, Json(json!(policy))),
+        Err(e) => error_response(e),
+    }
+}
+
+/// Add a decay tier, or change one; the next decay pass uses it
+pub async fn upsert_decay_tier(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    Json(payload): Json<DecayTierRequest>,
+) -> (StatusCode, Json<Value>) {
+    match state.decay_service.upsert_tier(&name, &payload).await {
+        Ok(tier) => (StatusCode::OK, Json(json!(tier))),
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn delete_decay_tier(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> (StatusCode, Json<Value>) {
+    match state.decay_service.delete_tier(&name).await {
+        Ok(()) => (StatusCode::OK, Json(json!({"message": "Decay tier removed"}))),
+        Err(e) => error_response(e),
+    }
+}
+
+/// Verify an automated engine so its owner is exempt from decay while the
+/// engine keeps the uptime SLA
+pub async fn exempt_engine(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(engine_id): Path<Uuid>,
+) -> (StatusCode, Json<Value>) {
+    let verified_by = headers
+        .get("x-user-id")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| Uuid::parse_str(value).ok());
+    match state.decay_service.exempt_engine(engine_id, verified_by).await {
+        Ok(exemption) => (StatusCode::OK, Json(json!(exemption))),
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn remove_engine_exemption(
+    State(state): State<Arc<AppState>>,
+    Path(engine_id): Path<Uuid>,
+) -> (StatusCode, Json<Value>) {
+    match state.decay_service.remove_exemption(engine_id).await {
+        Ok(()) => (StatusCode::OK, Json(json!({"message": "Decay exemption removed"}))),
+        Err(e) => error_response(e),
+    }
+}
@@ -0,0 +1,186 @@
+use axum::{extract::{State, Path, Query}, response::Json, http::{HeaderMap, StatusCode}};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use uuid::Uuid;
+use crate::AppState;
+use crate::handlers::caller;
+use crate::models::ReputationError;
+use crate::services::appeals::{AddEvidenceRequest, AppealDecisionRequest, FileAppealRequest, PanelVoteRequest};
+
+#[derive(Debug, Deserialize)]
+pub struct AppealQuery {
+    pub status: Option<String>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+fn error_response(error: ReputationError) -> (StatusCode, Json<Value>) {
+    let status = match error {
+        ReputationError::ValidationError(_) => StatusCode::BAD_REQUEST,
+        ReputationError::NotFound(_) => StatusCode::NOT_FOUND,
+        ReputationError::DatabaseError(_) | ReputationError::CalculationError(_) => {
+            tracing::error!("Reputation appeal request failed: {}", error);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to process appeal request"})),
+            );
+        }
+    };
+    (status, Json(json!({"error": error.to_string()})))
+}
+
+/// Appeal one of the caller's penalties
+pub async fn file_appeal(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<FileAppealRequest>,
+) -> (StatusCode, Json<Value>) {
+    let user_id = match caller(&headers) {
+        Ok(user_id) => user_id,
+        Err(response) => return response,
+    };
+    match state.appeal_service.file(user_id, payload).await {
+        Ok(appeal) => {
+            tracing::info!("Appeal {} filed by {} against {}", appeal.id, user_id, appeal.history_id);
+            (StatusCode::CREATED, Json(json!(appeal)))
+        }
+        Err(e) => error_response(e),
+    }
+}
+
+/// The caller's own appeals, and those awaiting their vote as an arbitrator
+pub async fn list_my_appeals(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<Value>) {
+    let user_id = match caller(&headers) {
+        Ok(user_id) => user_id,
+        Err(response) => return response,
+    };
+    let appeals = match state.appeal_service.for_user(user_id).await {
+        Ok(appeals) => appeals,
+        Err(e) => return error_response(e),
+    };
+    match state.appeal_service.awaiting_vote(user_id).await {
+        Ok(awaiting_vote) => (StatusCode::OK, Json(json!({"appeals": appeals, "awaiting_vote": awaiting_vote}))),
+        Err(e) => error_response(e),
+    }
+}
+
+/// An appeal with its audit trail, for the appellant and its panel
+pub async fn get_appeal(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(appeal_id): Path<Uuid>,
+) -> (StatusCode, Json<Value>) {
+    let user_id = match caller(&headers) {
+        Ok(user_id) => user_id,
+        Err(response) => return response,
+    };
+    match state.appeal_service.case(appeal_id).await {
+        Ok(case)
+            if case.appeal.user_id == user_id || case.panel.iter().any(|p| p.arbitrator_id == user_id) =>
+        {
+            (StatusCode::OK, Json(json!(case)))
+        }
+        Ok(_) => error_response(ReputationError::NotFound(format!("Appeal {}", appeal_id))),
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn add_appeal_evidence(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(appeal_id): Path<Uuid>,
+    Json(payload): Json<AddEvidenceRequest>,
+) -> (StatusCode, Json<Value>) {
+    let user_id = match caller(&headers) {
+        Ok(user_id) => user_id,
+        Err(response) => return response,
+    };
+    match state.appeal_service.add_evidence(user_id, appeal_id, payload).await {
+        Ok(evidence) => (StatusCode::OK, Json(json!({"appeal_id": appeal_id, "evidence": evidence}))),
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn withdraw_appeal(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(appeal_id): Path<Uuid>,
+) -> (StatusCode, Json<Value>) {
+    let user_id = match caller(&headers) {
+        Ok(user_id) => user_id,
+        Err(response) => return response,
+    };
+    match state.appeal_service.withdraw(user_id, appeal_id).await {
+        Ok(()) => (StatusCode::OK, Json(json!({"message": "Appeal withdrawn"}))),
+        Err(e) => error_response(e),
+    }
+}
+
+/// A panel arbitrator's vote on whether the penalty should be reversed
+pub async fn vote_on_appeal(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(appeal_id): Path<Uuid>,
+    Json(payload): Json<PanelVoteRequest>,
+) -> (StatusCode, Json<Value>) {
+    let user_id = match caller(&headers) {
+        Ok(user_id) => user_id,
+        Err(response) => return response,
+    };
+    match state.appeal_service.vote(appeal_id, user_id, payload).await {
+        Ok(appeal) => (StatusCode::OK, Json(json!(appeal))),
+        Err(e) => error_response(e),
+    }
+}
+
+/// Appeals for review; undecided ones unless a status is given
+pub async fn list_appeals(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<AppealQuery>,
+) -> (StatusCode, Json<Value>) {
+    match state.appeal_service.list(query.status.as_deref(), query.page, query.limit).await {
+        Ok(appeals) => (StatusCode::OK, Json(json!({"appeals": appeals}))),
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn get_appeal_case(
+    State(state): State<Arc<AppState>>,
+    Path(appeal_id): Path<Uuid>,
+) -> (StatusCode, Json<Value>) {
+    match state.appeal_service.case(appeal_id).await {
+        Ok(case) => (StatusCode::OK, Json(json!(case))),
+        Err(e) => error_response(e),
+    }
+}
+
+/// Hand an appeal to an arbitration panel
+pub async fn escalate_appeal(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(appeal_id): Path<Uuid>,
+) -> (StatusCode, Json<Value>) {
+    let admin_id = caller(&headers).ok();
+    match state.appeal_service.escalate(appeal_id, admin_id).await {
+        Ok(appeal) => (StatusCode::OK, Json(json!(appeal))),
+        Err(e) => error_response(e),
+    }
+}
+
+/// Decide an appeal; upholding it reverses the penalty
+pub async fn decide_appeal(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(appeal_id): Path<Uuid>,
+    Json(payload): Json<AppealDecisionRequest>,
+) -> (StatusCode, Json<Value>) {
+    let admin_id = caller(&headers).ok();
+    match state.appeal_service.decide(appeal_id, admin_id, payload).await {
+        Ok(appeal) => (StatusCode::OK, Json(json!(appeal))),
+        Err(e) => error_response(e),
+    }
+}
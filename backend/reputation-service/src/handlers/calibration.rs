@@ -0,0 +1,44 @@
+use axum::{extract::{State, Path, Query}, response::Json, http::StatusCode};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use uuid::Uuid;
+use crate::AppState;
+use crate::models::ReputationError;
+use crate::services::calibration::CalibrationQuery;
+
+fn error_response(error: ReputationError) -> (StatusCode, Json<Value>) {
+    let status = match error {
+        ReputationError::ValidationError(_) => StatusCode::BAD_REQUEST,
+        ReputationError::NotFound(_) => StatusCode::NOT_FOUND,
+        ReputationError::DatabaseError(_) | ReputationError::CalculationError(_) => {
+            tracing::error!("Calibration report failed: {}", error);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to load calibration accuracy"})),
+            );
+        }
+    };
+    (status, Json(json!({"error": error.to_string()})))
+}
+
+/// Engines' accuracy on calibration bounties, least accurate first
+pub async fn list_engine_calibration(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<CalibrationQuery>,
+) -> (StatusCode, Json<Value>) {
+    match state.calibration_service.engines(&query).await {
+        Ok(engines) => (StatusCode::OK, Json(json!({"engines": engines}))),
+        Err(e) => error_response(e),
+    }
+}
+
+/// One engine's calibration accuracy, degradations and latest results
+pub async fn get_engine_calibration(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<Uuid>,
+) -> (StatusCode, Json<Value>) {
+    match state.calibration_service.engine(user_id).await {
+        Ok(report) => (StatusCode::OK, Json(json!(report))),
+        Err(e) => error_response(e),
+    }
+}
@@ -0,0 +1,112 @@
+use axum::{extract::{State, Path, Query}, response::Json, http::StatusCode};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use uuid::Uuid;
+use crate::AppState;
+use crate::models::*;
+
+fn error_response(error: ReputationError) -> (StatusCode, Json<Value>) {
+    let status = match error {
+        ReputationError::ValidationError(_) => StatusCode::BAD_REQUEST,
+        ReputationError::NotFound(_) => StatusCode::NOT_FOUND,
+        ReputationError::DatabaseError(_) | ReputationError::CalculationError(_) => {
+            tracing::error!("Voting power query failed: {}", error);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to compute voting power"})),
+            );
+        }
+    };
+    (status, Json(json!({"error": error.to_string()})))
+}
+
+/// Voting power of one user, now or at `?timestamp=`, `?block=` or `?snapshot_id=`
+pub async fn get_voting_power(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<Uuid>,
+    Query(query): Query<VotingPowerQuery>,
+) -> (StatusCode, Json<Value>) {
+    let service = &state.voting_power_service;
+    let point = match service.resolve_point(&query).await {
+        Ok(point) => point,
+        Err(e) => return error_response(e),
+    };
+
+    match service.voting_power(&[user_id], &point).await {
+        Ok(mut powers) => match powers.pop() {
+            Some(power) => (StatusCode::OK, Json(json!({"point": point, "voting_power": power}))),
+            None => error_response(ReputationError::NotFound(format!("User {}", user_id))),
+        },
+        Err(e) => error_response(e),
+    }
+}
+
+/// Voting power of many users at the same point in time
+pub async fn get_bulk_voting_power(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<BulkVotingPowerRequest>,
+) -> (StatusCode, Json<Value>) {
+    let service = &state.voting_power_service;
+    let max_users = service.config().max_bulk_users;
+    if payload.user_ids.is_empty() || payload.user_ids.len() > max_users {
+        return error_response(ReputationError::ValidationError(format!(
+            "user_ids must contain between 1 and {} entries",
+            max_users
+        )));
+    }
+
+    let point = match service.resolve_point(&payload.at).await {
+        Ok(point) => point,
+        Err(e) => return error_response(e),
+    };
+
+    match service.voting_power(&payload.user_ids, &point).await {
+        Ok(powers) => {
+            let total: f64 = powers.iter().map(|p| p.voting_power).sum();
+            (
+                StatusCode::OK,
+                Json(json!({
+                    "point": point,
+                    "total_voting_power": total,
+                    "voting_power": powers,
+                })),
+            )
+        }
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn create_snapshot(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<CreateSnapshotRequest>,
+) -> (StatusCode, Json<Value>) {
+    if payload.label.trim().is_empty() {
+        return error_response(ReputationError::ValidationError("label is required".to_string()));
+    }
+
+    let service = &state.voting_power_service;
+    let query = VotingPowerQuery {
+        timestamp: payload.timestamp,
+        block: payload.block,
+        snapshot_id: None,
+    };
+    let point = match service.resolve_point(&query).await {
+        Ok(point) => point,
+        Err(e) => return error_response(e),
+    };
+
+    match service.create_snapshot(payload.label.trim(), &point).await {
+        Ok(snapshot) => (StatusCode::CREATED, Json(json!(snapshot))),
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn get_snapshot(
+    State(state): State<Arc<AppState>>,
+    Path(snapshot_id): Path<Uuid>,
+) -> (StatusCode, Json<Value>) {
+    match state.voting_power_service.get_snapshot(snapshot_id).await {
+        Ok(snapshot) => (StatusCode::OK, Json(json!(snapshot))),
+        Err(e) => error_response(e),
+    }
+}
@@ -2,3 +2,21 @@ pub mod health;
 pub mod reputation;
 pub mod analytics;
 pub mod admin;
+pub mod governance;
+pub mod webhooks;
+pub mod appeals;
+pub mod calibration;
+
+use axum::{http::{HeaderMap, StatusCode}, response::Json};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+/// The user the gateway authenticated, for endpoints that act on the
+/// caller's own standing
+pub(crate) fn caller(headers: &HeaderMap) -> Result<Uuid, (StatusCode, Json<Value>)> {
+    headers
+        .get("x-user-id")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| Uuid::parse_str(value).ok())
+        .ok_or((StatusCode::UNAUTHORIZED, Json(json!({"error": "Authentication required"}))))
+}
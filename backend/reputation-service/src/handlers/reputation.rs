@@ -1,14 +1,33 @@
-use axum::{extract::{State, Path}, response::Json, http::StatusCode};
+use axum::{extract::{State, Path, Query}, response::Json, http::StatusCode};
 use serde_json::{json, Value};
 use std::sync::Arc;
+use uuid::Uuid;
 use crate::AppState;
 use crate::models::*;
+use crate::scoring::leaderboard::{LeaderboardWindow, Participant};
+use crate::services::engine_performance;
+use crate::services::leaderboard::LeaderboardPage;
 
+/// The user's global score and their score in each threat category they
+/// have settled submissions in
 pub async fn get_user_reputation(
-    State(_state): State<Arc<AppState>>,
-    Path(_user_id): Path<String>,
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<Uuid>,
 ) -> (StatusCode, Json<Value>) {
-    (StatusCode::OK, Json(json!({"score": 1000})))
+    match state.standing_service.user(user_id).await {
+        Ok(Some(standing)) => (StatusCode::OK, Json(json!(standing))),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": format!("No reputation for user {}", user_id)})),
+        ),
+        Err(e) => {
+            tracing::error!("Reputation query failed for user {}: {}", user_id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to load reputation"})),
+            )
+        }
+    }
 }
 
 pub async fn get_reputation_history(
@@ -26,22 +45,176 @@ pub async fn update_reputation(
     (StatusCode::OK, Json(json!({"message": "Reputation updated"})))
 }
 
+/// The engine's reputation for a bounty tagged `?tags=a,b`: its global
+/// score and its specialization score in the bounty's threat categories
 pub async fn get_engine_reputation(
-    State(_state): State<Arc<AppState>>,
-    Path(_engine_id): Path<String>,
+    State(state): State<Arc<AppState>>,
+    Path(engine_id): Path<String>,
+    Query(query): Query<EngineReputationQuery>,
+) -> (StatusCode, Json<Value>) {
+    let not_found = || {
+        (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": format!("No reputation for engine {}", engine_id)})),
+        )
+    };
+    // Only engines that are users have a reputation
+    let Ok(user_id) = Uuid::parse_str(&engine_id) else {
+        return not_found();
+    };
+    let tags: Vec<String> = query
+        .tags
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|tag| !tag.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    match state.standing_service.engine(user_id, &tags).await {
+        Ok(Some(standing)) => (StatusCode::OK, Json(json!(standing))),
+        Ok(None) => not_found(),
+        Err(ReputationError::ValidationError(msg)) => (StatusCode::BAD_REQUEST, Json(json!({"error": msg}))),
+        Err(e) => {
+            tracing::error!("Reputation query failed for engine {}: {}", engine_id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to load reputation"})),
+            )
+        }
+    }
+}
+
+/// Reputation of up to `max_bulk_ids` users or engines in one round-trip,
+/// with their specialization scores for a bounty tagged `tags`
+pub async fn get_bulk_reputation(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<BulkReputationRequest>,
+) -> (StatusCode, Json<Value>) {
+    match state.standing_service.bulk(&payload.user_ids, &payload.tags).await {
+        Ok(standings) => (StatusCode::OK, Json(json!(standings))),
+        Err(ReputationError::ValidationError(msg)) => (StatusCode::BAD_REQUEST, Json(json!({"error": msg}))),
+        Err(e) => {
+            tracing::error!("Bulk reputation query failed: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to load reputation"})),
+            )
+        }
+    }
+}
+
+/// Per-engine accuracy, volume and uptime for one operator's engines over
+/// `?from=..&to=..`
+pub async fn get_engine_performance(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<EnginePerformanceQuery>,
 ) -> (StatusCode, Json<Value>) {
-    (StatusCode::OK, Json(json!({"score": 1000})))
+    match engine_performance::for_owner(&state.db_pool, &query).await {
+        Ok(engines) => (StatusCode::OK, Json(json!({"engines": engines}))),
+        Err(ReputationError::ValidationError(msg)) => {
+            (StatusCode::BAD_REQUEST, Json(json!({"error": msg})))
+        }
+        Err(e) => {
+            tracing::error!("Engine performance query failed: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to compute engine performance"})),
+            )
+        }
+    }
 }
 
+/// A season's leaderboard, `?season=2026-Q3` for an archived one; the
+/// current season by default. With `window`, `participant` or
+/// `specialization`, a windowed or filtered board instead (all-time unless a
+/// window is given).
 pub async fn get_leaderboard(
-    State(_state): State<Arc<AppState>>,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<LeaderboardQuery>,
 ) -> (StatusCode, Json<Value>) {
-    (StatusCode::OK, Json(json!({"leaderboard": []})))
+    let result = if query.window.is_some() || query.participant.is_some() || query.specialization.is_some() {
+        filtered_leaderboard(&state, &query).await.map(|page| json!(page))
+    } else {
+        state
+            .season_service
+            .leaderboard(query.season.as_deref(), query.page, query.limit)
+            .await
+            .map(|(season, leaderboard)| json!({"season": season, "leaderboard": leaderboard}))
+    };
+    match result {
+        Ok(body) => (StatusCode::OK, Json(body)),
+        Err(ReputationError::ValidationError(msg)) => (StatusCode::BAD_REQUEST, Json(json!({"error": msg}))),
+        Err(ReputationError::NotFound(msg)) => (StatusCode::NOT_FOUND, Json(json!({"error": msg}))),
+        Err(e) => {
+            tracing::error!("Leaderboard query failed: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to load leaderboard"})),
+            )
+        }
+    }
 }
 
+async fn filtered_leaderboard(state: &AppState, query: &LeaderboardQuery) -> ReputationResult<LeaderboardPage> {
+    if query.season.is_some() {
+        return Err(ReputationError::ValidationError(
+            "season cannot be combined with window, participant or specialization".to_string(),
+        ));
+    }
+    let window = match query.window.as_deref() {
+        None => LeaderboardWindow::AllTime,
+        Some(window) => LeaderboardWindow::parse(window).ok_or_else(|| {
+            ReputationError::ValidationError(format!("window must be 24h, 7d, 30d or all, not {}", window))
+        })?,
+    };
+    let participant = match query.participant.as_deref() {
+        None => Participant::All,
+        Some(participant) => Participant::parse(participant).ok_or_else(|| {
+            ReputationError::ValidationError(format!("participant must be engine, human or all, not {}", participant))
+        })?,
+    };
+    let specialization = query.specialization.as_deref().map(str::trim).filter(|s| !s.is_empty());
+    state
+        .leaderboard_service
+        .board(window, participant, specialization, query.page, query.limit)
+        .await
+}
+
+/// Every season, newest first
+pub async fn get_seasons(
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<Value>) {
+    match state.season_service.seasons().await {
+        Ok(seasons) => (StatusCode::OK, Json(json!({"seasons": seasons}))),
+        Err(e) => {
+            tracing::error!("Season query failed: {}", e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to load seasons"})),
+            )
+        }
+    }
+}
+
+/// Every badge, with whether the user earned it and their progress (0-100)
+/// towards the ones they have not
 pub async fn get_user_badges(
-    State(_state): State<Arc<AppState>>,
-    Path(_user_id): Path<String>,
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<Uuid>,
 ) -> (StatusCode, Json<Value>) {
-    (StatusCode::OK, Json(json!({"badges": []})))
+    match state.badge_service.standings(user_id).await {
+        Ok(badges) => {
+            let earned = badges.iter().filter(|badge| badge.earned).count();
+            (StatusCode::OK, Json(json!({"user_id": user_id, "earned": earned, "badges": badges})))
+        }
+        Err(e) => {
+            tracing::error!("Badge query failed for user {}: {}", user_id, e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to load badges"})),
+            )
+        }
+    }
 }
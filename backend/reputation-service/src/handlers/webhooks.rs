@@ -0,0 +1,97 @@
+use axum::{extract::{State, Path, Query}, response::Json, http::{HeaderMap, StatusCode}};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use uuid::Uuid;
+use crate::AppState;
+use crate::handlers::caller;
+use crate::models::ReputationError;
+use crate::services::webhooks::RegisterWebhookRequest;
+
+#[derive(Debug, Deserialize)]
+pub struct DeliveryQuery {
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+fn error_response(error: ReputationError) -> (StatusCode, Json<Value>) {
+    let status = match error {
+        ReputationError::ValidationError(_) => StatusCode::BAD_REQUEST,
+        ReputationError::NotFound(_) => StatusCode::NOT_FOUND,
+        ReputationError::DatabaseError(_) | ReputationError::CalculationError(_) => {
+            tracing::error!("Reputation webhook request failed: {}", error);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Failed to process webhook request"})),
+            );
+        }
+    };
+    (status, Json(json!({"error": error.to_string()})))
+}
+
+/// Subscribe a URL to changes in the caller's standing; the response holds
+/// the signing secret, which is not shown again
+pub async fn register_webhook(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(payload): Json<RegisterWebhookRequest>,
+) -> (StatusCode, Json<Value>) {
+    let user_id = match caller(&headers) {
+        Ok(user_id) => user_id,
+        Err(response) => return response,
+    };
+    match state.webhook_service.register(user_id, payload).await {
+        Ok(registered) => {
+            tracing::info!("Reputation webhook {} registered by {}", registered.webhook.id, user_id);
+            (StatusCode::CREATED, Json(json!(registered)))
+        }
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn list_webhooks(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<Value>) {
+    let user_id = match caller(&headers) {
+        Ok(user_id) => user_id,
+        Err(response) => return response,
+    };
+    match state.webhook_service.list(user_id).await {
+        Ok(webhooks) => (StatusCode::OK, Json(json!({"webhooks": webhooks}))),
+        Err(e) => error_response(e),
+    }
+}
+
+pub async fn delete_webhook(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(webhook_id): Path<Uuid>,
+) -> (StatusCode, Json<Value>) {
+    let user_id = match caller(&headers) {
+        Ok(user_id) => user_id,
+        Err(response) => return response,
+    };
+    match state.webhook_service.delete(user_id, webhook_id).await {
+        Ok(()) => (StatusCode::OK, Json(json!({"message": "Webhook deleted"}))),
+        Err(e) => error_response(e),
+    }
+}
+
+/// A webhook's deliveries, newest first, with the outcome of each one's
+/// latest attempt
+pub async fn list_webhook_deliveries(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(webhook_id): Path<Uuid>,
+    Query(query): Query<DeliveryQuery>,
+) -> (StatusCode, Json<Value>) {
+    let user_id = match caller(&headers) {
+        Ok(user_id) => user_id,
+        Err(response) => return response,
+    };
+    match state.webhook_service.deliveries(user_id, webhook_id, query.page, query.limit).await {
+        Ok(deliveries) => (StatusCode::OK, Json(json!({"webhook_id": webhook_id, "deliveries": deliveries}))),
+        Err(e) => error_response(e),
+    }
+}
@@ -8,18 +8,26 @@ mod analytics;
 
 use anyhow::Result;
 use axum::{
-    routing::{get, post},
+    routing::{delete, get, post, put},
     Router,
 };
 use std::sync::Arc;
-use tower_http::{
-    cors::{Any, CorsLayer},
-    trace::TraceLayer,
-};
+use tower_http::trace::TraceLayer;
 use tracing::{info, warn};
 
 use crate::config::Config;
+use crate::services::appeals::AppealService;
+use crate::services::badges::BadgeService;
+use crate::services::calibration::CalibrationService;
+use crate::services::decay::DecayService;
+use crate::services::events::ReputationEventService;
+use crate::services::leaderboard::LeaderboardService;
 use crate::services::reputation_service::ReputationService;
+use crate::services::seasons::SeasonService;
+use crate::services::settlement::SettlementService;
+use crate::services::standing::StandingService;
+use crate::services::voting_power::VotingPowerService;
+use crate::services::webhooks::WebhookService;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -27,12 +35,7 @@ async fn main() -> Result<()> {
     dotenvy::dotenv().ok();
 
     // Initialize tracing
-    tracing_subscriber::fmt()
-        .with_target(false)
-        .with_thread_ids(true)
-        .with_line_number(true)
-        .json()
-        .init();
+    shared::observability::init_service_logging(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))?;
 
     info!("Starting Reputation Service...");
 
@@ -67,7 +70,32 @@ async fn main() -> Result<()> {
     );
     info!("Reputation service initialized");
 
+    let voting_power_service = Arc::new(VotingPowerService::new(
+        config.governance.clone(),
+        db_pool.clone(),
+    ));
+
     // Start background workers
+    let event_service = Arc::new(ReputationEventService::new(
+        config.events.clone(),
+        db_pool.clone(),
+        shared::messaging::EventPublisher::from_url(&config.redis.url)?,
+    ));
+    let service_clone = event_service.clone();
+    tokio::spawn(async move {
+        if let Err(e) = workers::event_publisher::start(service_clone).await {
+            warn!("Reputation event publisher error: {}", e);
+        }
+    });
+
+    let webhook_service = Arc::new(WebhookService::new(config.webhooks.clone(), db_pool.clone())?);
+    let service_clone = webhook_service.clone();
+    tokio::spawn(async move {
+        if let Err(e) = workers::webhook_dispatcher::start(service_clone).await {
+            warn!("Reputation webhook dispatcher error: {}", e);
+        }
+    });
+
     let service_clone = reputation_service.clone();
     tokio::spawn(async move {
         if let Err(e) = workers::reputation_calculator::start(service_clone).await {
@@ -75,35 +103,133 @@ async fn main() -> Result<()> {
         }
     });
 
-    let service_clone = reputation_service.clone();
+    let decay_service = Arc::new(DecayService::new(config.reputation.clone(), db_pool.clone()));
+    let service_clone = decay_service.clone();
     tokio::spawn(async move {
         if let Err(e) = workers::decay_processor::start(service_clone).await {
             warn!("Decay processor error: {}", e);
         }
     });
 
-    let service_clone = reputation_service.clone();
+    let season_service = Arc::new(SeasonService::new(
+        config.seasons.clone(),
+        config.reputation.min_score,
+        db_pool.clone(),
+        event_service.clone(),
+    ));
+    let leaderboard_service = Arc::new(LeaderboardService::new(
+        config.leaderboard.clone(),
+        db_pool.clone(),
+        redis_conn.clone(),
+    ));
+    let service_clone = season_service.clone();
+    let leaderboard_clone = leaderboard_service.clone();
     tokio::spawn(async move {
-        if let Err(e) = workers::leaderboard_updater::start(service_clone).await {
+        if let Err(e) = workers::leaderboard_updater::start(service_clone, leaderboard_clone).await {
             warn!("Leaderboard updater error: {}", e);
         }
     });
 
+    let settlement_service = Arc::new(SettlementService::new(config.probation.clone(), db_pool.clone()));
+    let events = shared::messaging::EventSubscriber::from_url(&config.redis.url)?;
+    // Unverified plans would move reputation, so there is no running
+    // without the keys to check them
+    let settlement_verifier = shared::request_signing::SignatureVerifier::from_env()?;
+    anyhow::ensure!(
+        settlement_verifier.is_enabled(),
+        "SERVICE_SIGNING_KEYS must be set to verify settlement plans"
+    );
+    tokio::spawn(async move {
+        let listener = workers::settlement_listener::start(settlement_service, events, settlement_verifier);
+        if let Err(e) = listener.await {
+            warn!("Settlement listener error: {}", e);
+        }
+    });
+
+    let badge_service = Arc::new(BadgeService::new(
+        config.badges.clone(),
+        db_pool.clone(),
+        event_service,
+    ));
+    let service_clone = badge_service.clone();
+    tokio::spawn(async move {
+        if let Err(e) = workers::badge_evaluator::start(service_clone).await {
+            warn!("Badge evaluator error: {}", e);
+        }
+    });
+
+    let appeal_service = Arc::new(AppealService::new(config.appeals.clone(), db_pool.clone()));
+    let service_clone = appeal_service.clone();
+    tokio::spawn(async move {
+        if let Err(e) = workers::appeal_resolver::start(service_clone).await {
+            warn!("Appeal resolver error: {}", e);
+        }
+    });
+
+    let calibration_service = Arc::new(CalibrationService::new(config.calibration.clone(), db_pool.clone()));
+    let service_clone = calibration_service.clone();
+    tokio::spawn(async move {
+        if let Err(e) = workers::calibration_monitor::start(service_clone).await {
+            warn!("Calibration monitor error: {}", e);
+        }
+    });
+
     info!("Background workers started");
 
+    let standing_service = Arc::new(StandingService::new(
+        config.standing.clone(),
+        db_pool.clone(),
+        redis_conn.clone(),
+    ));
+
     // Build application state
     let app_state = Arc::new(AppState {
         config: config.clone(),
         db_pool,
         redis_conn,
         reputation_service,
+        voting_power_service,
+        badge_service,
+        decay_service,
+        season_service,
+        leaderboard_service,
+        standing_service,
+        webhook_service,
+        appeal_service,
+        calibration_service,
     });
 
-    // Configure CORS
-    let cors = CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods(Any)
-        .allow_headers(Any);
+    // Browser origins from CORS_ALLOWED_ORIGINS or the environment's defaults
+    let cors = shared::http_security::CorsPolicy::from_env().layer();
+
+    // Admin endpoints, for callers with the reputation:manage scope
+    let admin = Router::new()
+        .route("/api/v1/admin/reputation/recalculate/:user_id", post(handlers::admin::recalculate_reputation))
+        .route("/api/v1/admin/reputation/reset/:user_id", post(handlers::admin::reset_reputation))
+        .route("/api/v1/admin/badges/award", post(handlers::admin::award_badge))
+        .route("/api/v1/admin/reputation/decay", get(handlers::admin::get_decay_policy))
+        .route("/api/v1/admin/reputation/decay/settings", put(handlers::admin::update_decay_settings))
+        .route(
+            "/api/v1/admin/reputation/decay/tiers/:name",
+            put(handlers::admin::upsert_decay_tier).delete(handlers::admin::delete_decay_tier),
+        )
+        .route(
+            "/api/v1/admin/reputation/decay/exemptions/:engine_id",
+            put(handlers::admin::exempt_engine).delete(handlers::admin::remove_engine_exemption),
+        )
+        .route("/api/v1/admin/reputation/appeals", get(handlers::appeals::list_appeals))
+        .route("/api/v1/admin/reputation/appeals/:appeal_id", get(handlers::appeals::get_appeal_case))
+        .route("/api/v1/admin/reputation/appeals/:appeal_id/escalate", post(handlers::appeals::escalate_appeal))
+        .route("/api/v1/admin/reputation/appeals/:appeal_id/decision", post(handlers::appeals::decide_appeal))
+        .route("/api/v1/admin/reputation/calibration", get(handlers::calibration::list_engine_calibration))
+        .route(
+            "/api/v1/admin/reputation/calibration/:user_id",
+            get(handlers::calibration::get_engine_calibration),
+        )
+        .route("/api/v1/admin/governance/snapshots", post(handlers::governance::create_snapshot))
+        .route_layer(axum::middleware::from_fn(shared::permissions::require_scope(
+            shared::permissions::REPUTATION_MANAGE,
+        )));
 
     // Build router
     let app = Router::new()
@@ -113,18 +239,50 @@ async fn main() -> Result<()> {
         .route("/api/v1/reputation/user/:user_id/history", get(handlers::reputation::get_reputation_history))
         .route("/api/v1/reputation/user/:user_id/update", post(handlers::reputation::update_reputation))
         .route("/api/v1/reputation/engine/:engine_id", get(handlers::reputation::get_engine_reputation))
+        .route("/api/v1/reputation/bulk", post(handlers::reputation::get_bulk_reputation))
+        .route("/api/v1/reputation/engines/performance", get(handlers::reputation::get_engine_performance))
         .route("/api/v1/reputation/leaderboard", get(handlers::reputation::get_leaderboard))
+        .route("/api/v1/reputation/seasons", get(handlers::reputation::get_seasons))
         .route("/api/v1/reputation/badges/:user_id", get(handlers::reputation::get_user_badges))
+        .route(
+            "/api/v1/reputation/webhooks",
+            get(handlers::webhooks::list_webhooks).post(handlers::webhooks::register_webhook),
+        )
+        .route("/api/v1/reputation/webhooks/:webhook_id", delete(handlers::webhooks::delete_webhook))
+        .route(
+            "/api/v1/reputation/webhooks/:webhook_id/deliveries",
+            get(handlers::webhooks::list_webhook_deliveries),
+        )
+        .route(
+            "/api/v1/reputation/appeals",
+            get(handlers::appeals::list_my_appeals).post(handlers::appeals::file_appeal),
+        )
+        .route("/api/v1/reputation/appeals/:appeal_id", get(handlers::appeals::get_appeal))
+        .route("/api/v1/reputation/appeals/:appeal_id/evidence", post(handlers::appeals::add_appeal_evidence))
+        .route("/api/v1/reputation/appeals/:appeal_id/withdraw", post(handlers::appeals::withdraw_appeal))
+        .route("/api/v1/reputation/appeals/:appeal_id/votes", post(handlers::appeals::vote_on_appeal))
+        // Governance endpoints
+        .route("/api/v1/governance/voting-power/bulk", post(handlers::governance::get_bulk_voting_power))
+        .route("/api/v1/governance/voting-power/:user_id", get(handlers::governance::get_voting_power))
+        .route("/api/v1/governance/snapshots/:snapshot_id", get(handlers::governance::get_snapshot))
         // Analytics endpoints
         .route("/api/v1/analytics/reputation/trends", get(handlers::analytics::get_reputation_trends))
         .route("/api/v1/analytics/reputation/distribution", get(handlers::analytics::get_score_distribution))
         .route("/api/v1/analytics/accuracy/stats", get(handlers::analytics::get_accuracy_stats))
-        // Admin endpoints
-        .route("/api/v1/admin/reputation/recalculate/:user_id", post(handlers::admin::recalculate_reputation))
-        .route("/api/v1/admin/reputation/reset/:user_id", post(handlers::admin::reset_reputation))
-        .route("/api/v1/admin/badges/award", post(handlers::admin::award_badge))
+        .merge(admin)
+        .merge(shared::observability::log_level_routes())
+        .layer(axum::middleware::from_fn_with_state(
+            shared::request_signing::SignatureVerifier::from_env()?,
+            shared::request_signing::verify_signature_middleware,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            shared::chaos::FaultInjector::from_env(),
+            shared::chaos::chaos_middleware,
+        ))
         .layer(cors)
+        .layer(axum::middleware::from_fn(shared::http_security::security_headers_middleware))
         .layer(TraceLayer::new_for_http())
+        .layer(axum::middleware::from_fn(shared::observability::log_context_middleware))
         .with_state(app_state);
 
     // Start server
@@ -143,4 +301,13 @@ pub struct AppState {
     pub db_pool: sqlx::PgPool,
     pub redis_conn: redis::aio::ConnectionManager,
     pub reputation_service: Arc<ReputationService>,
+    pub voting_power_service: Arc<VotingPowerService>,
+    pub badge_service: Arc<BadgeService>,
+    pub decay_service: Arc<DecayService>,
+    pub season_service: Arc<SeasonService>,
+    pub leaderboard_service: Arc<LeaderboardService>,
+    pub standing_service: Arc<StandingService>,
+    pub webhook_service: Arc<WebhookService>,
+    pub appeal_service: Arc<AppealService>,
+    pub calibration_service: Arc<CalibrationService>,
 }
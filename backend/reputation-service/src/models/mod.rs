@@ -94,6 +94,9 @@ pub struct BadgeCriteria {
     pub min_accuracy: Option<f64>,
     pub min_submissions: Option<i32>,
     pub min_streak: Option<i32>,
+    /// Final verdict of the samples counted by `min_specialization_count`
+    pub specialization: Option<String>,
+    pub min_specialization_count: Option<i32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -104,6 +107,16 @@ pub struct UserBadge {
     pub progress: Option<Decimal>,
 }
 
+/// Where a user stands on one badge: earned, or how far along (0-100)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BadgeStanding {
+    #[serde(flatten)]
+    pub badge: Badge,
+    pub earned: bool,
+    pub awarded_at: Option<DateTime<Utc>>,
+    pub progress: f64,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ReputationUpdateRequest {
     pub user_id: Uuid,
@@ -115,17 +128,46 @@ pub struct ReputationUpdateRequest {
     pub was_early: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
 pub struct LeaderboardEntry {
     pub rank: i32,
     pub user_id: Uuid,
     pub username: String,
     pub score: i32,
-    pub accuracy_rate: Decimal,
+    pub accuracy_rate: f64,
     pub total_submissions: i32,
     pub badges_count: i32,
 }
 
+/// `tags` are the comma-separated tags of the bounty the engine submits to
+#[derive(Debug, Deserialize)]
+pub struct EngineReputationQuery {
+    pub tags: Option<String>,
+}
+
+/// Engines are looked up by their user id; `tags` are the tags of the
+/// bounty their votes are weighted for
+#[derive(Debug, Deserialize)]
+pub struct BulkReputationRequest {
+    pub user_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// `season` is a season id like `2026-Q3`; the current season if omitted.
+/// `window` (`24h`, `7d`, `30d` or `all`), `participant` (`engine` or
+/// `human`) and `specialization` (a threat category slug) select a board
+/// kept in Redis instead of a season's.
+#[derive(Debug, Deserialize)]
+pub struct LeaderboardQuery {
+    pub season: Option<String>,
+    pub window: Option<String>,
+    pub participant: Option<String>,
+    pub specialization: Option<String>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ReputationStats {
     pub total_users: i64,
@@ -142,3 +184,76 @@ pub struct ScoreDistribution {
     pub count: i64,
     pub percentage: Decimal,
 }
+
+/// A user's governance weight at a point in time
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct VotingPower {
+    pub user_id: Uuid,
+    pub reputation: i32,
+    pub staked: f64,
+    pub reputation_power: f64,
+    pub stake_power: f64,
+    pub voting_power: f64,
+    pub eligible: bool,
+}
+
+/// Voting power frozen for every eligible user at a block or timestamp
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct VotingPowerSnapshot {
+    pub id: Uuid,
+    pub label: String,
+    pub snapshot_at: DateTime<Utc>,
+    pub block_number: Option<i64>,
+    pub formula: serde_json::Value,
+    pub voter_count: i32,
+    pub total_voting_power: f64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Point in time to evaluate voting power at; at most one of the fields may
+/// be set, and none means "now"
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct VotingPowerQuery {
+    pub timestamp: Option<DateTime<Utc>>,
+    pub block: Option<i64>,
+    pub snapshot_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkVotingPowerRequest {
+    pub user_ids: Vec<Uuid>,
+    #[serde(flatten)]
+    pub at: VotingPowerQuery,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateSnapshotRequest {
+    pub label: String,
+    pub timestamp: Option<DateTime<Utc>>,
+    pub block: Option<i64>,
+}
+
+/// How one engine performed over a period, from its analysis results.
+/// Accuracy counts only analyses whose bounty reached consensus; uptime is
+/// the share of assigned analyses the engine delivered (not failed or
+/// timed out).
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct EnginePerformance {
+    pub engine_id: Uuid,
+    pub engine_name: String,
+    pub total_analyses: i64,
+    pub completed_analyses: i64,
+    pub failed_analyses: i64,
+    pub resolved_analyses: i64,
+    pub correct_analyses: i64,
+    pub accuracy_rate: f64,
+    pub uptime_rate: f64,
+    pub avg_confidence: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EnginePerformanceQuery {
+    pub owner_id: Uuid,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+}
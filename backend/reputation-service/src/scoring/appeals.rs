@@ -0,0 +1,88 @@
+/// History reasons that are judgements of the user and may be contested.
+/// Season resets apply to everyone alike and are not.
+pub const APPEALABLE_REASONS: [&str; 2] = ["settlement_slashed", "decay"];
+
+/// Whether a history entry is a penalty the user can appeal
+pub fn is_appealable(reason: &str, score_change: i32) -> bool {
+    score_change < 0 && APPEALABLE_REASONS.contains(&reason)
+}
+
+/// How an appeal was decided
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppealDecision {
+    Upheld,
+    Rejected,
+}
+
+impl AppealDecision {
+    pub fn from_uphold(uphold: bool) -> Self {
+        if uphold {
+            Self::Upheld
+        } else {
+            Self::Rejected
+        }
+    }
+
+    /// The appeal's status once decided
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Upheld => "upheld",
+            Self::Rejected => "rejected",
+        }
+    }
+}
+
+/// A panel's decision, if it has reached one. A majority of the whole panel
+/// decides as soon as it agrees. Once the deadline passes the votes cast
+/// decide, provided at least half the panel voted; a tie rejects, as the
+/// penalty stands unless the panel finds against it. `None` while voting
+/// goes on, or when the deadline passed without enough votes.
+pub fn panel_decision(
+    uphold: usize,
+    reject: usize,
+    panel_size: usize,
+    deadline_passed: bool,
+) -> Option<AppealDecision> {
+    let majority = panel_size / 2 + 1;
+    if uphold >= majority {
+        return Some(AppealDecision::Upheld);
+    }
+    if reject >= majority {
+        return Some(AppealDecision::Rejected);
+    }
+    if !deadline_passed || (uphold + reject) * 2 < panel_size {
+        return None;
+    }
+    Some(AppealDecision::from_uphold(uphold > reject))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_penalties_are_appealable() {
+        assert!(is_appealable("settlement_slashed", -120));
+        assert!(is_appealable("decay", -5));
+        assert!(!is_appealable("settlement_rewarded", 50));
+        assert!(!is_appealable("season_reset", -400));
+        assert!(!is_appealable("decay", 0));
+    }
+
+    #[test]
+    fn test_majority_decides_early() {
+        assert_eq!(panel_decision(3, 0, 5, false), Some(AppealDecision::Upheld));
+        assert_eq!(panel_decision(1, 3, 5, false), Some(AppealDecision::Rejected));
+        assert_eq!(panel_decision(2, 2, 5, false), None);
+        assert_eq!(panel_decision(2, 1, 4, false), None);
+    }
+
+    #[test]
+    fn test_deadline_decides_by_votes_cast() {
+        assert_eq!(panel_decision(2, 1, 5, true), Some(AppealDecision::Upheld));
+        assert_eq!(panel_decision(1, 1, 4, true), Some(AppealDecision::Rejected));
+        // Fewer than half the panel voted
+        assert_eq!(panel_decision(2, 0, 5, true), None);
+        assert_eq!(panel_decision(0, 0, 5, true), None);
+    }
+}
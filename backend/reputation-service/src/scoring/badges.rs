@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+
+use crate::models::BadgeCriteria;
+
+/// Settled submissions needed before accuracy badges can be earned, so one
+/// lucky call is not 100% accuracy
+pub const MIN_ACCURACY_SUBMISSIONS: i32 = 10;
+
+/// What badge criteria are checked against
+#[derive(Debug, Clone, Default)]
+pub struct BadgeStats {
+    /// Highest score reached, so a later drop does not undo a score badge
+    pub highest_score: i32,
+    /// Percentage of settled submissions that were correct
+    pub accuracy_rate: f64,
+    pub total_submissions: i32,
+    /// Longest run of correct submissions
+    pub best_streak: i32,
+    /// Correct submissions per final verdict of the sample
+    pub specializations: HashMap<String, i32>,
+}
+
+/// How far a user is towards a badge, from 0.0 to 1.0 once it is earned.
+/// With several criteria the furthest behind counts. Badges without criteria
+/// are only awarded by hand and never progress.
+pub fn progress(criteria: &BadgeCriteria, stats: &BadgeStats) -> f64 {
+    let mut parts = Vec::new();
+    if let Some(min_score) = criteria.min_score {
+        parts.push(ratio(stats.highest_score as f64, min_score as f64));
+    }
+    if let Some(min_accuracy) = criteria.min_accuracy {
+        parts.push(ratio(stats.accuracy_rate, min_accuracy));
+        parts.push(ratio(stats.total_submissions as f64, MIN_ACCURACY_SUBMISSIONS as f64));
+    }
+    if let Some(min_submissions) = criteria.min_submissions {
+        parts.push(ratio(stats.total_submissions as f64, min_submissions as f64));
+    }
+    if let Some(min_streak) = criteria.min_streak {
+        parts.push(ratio(stats.best_streak as f64, min_streak as f64));
+    }
+    if let Some(min_count) = criteria.min_specialization_count {
+        let count = criteria
+            .specialization
+            .as_ref()
+            .and_then(|specialization| stats.specializations.get(&specialization.to_lowercase()))
+            .copied()
+            .unwrap_or(0);
+        parts.push(ratio(count as f64, min_count as f64));
+    }
+
+    parts.into_iter().reduce(f64::min).unwrap_or(0.0)
+}
+
+fn ratio(value: f64, threshold: f64) -> f64 {
+    if threshold <= 0.0 {
+        return 1.0;
+    }
+    (value / threshold).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn criteria() -> BadgeCriteria {
+        BadgeCriteria {
+            min_score: None,
+            min_accuracy: None,
+            min_submissions: None,
+            min_streak: None,
+            specialization: None,
+            min_specialization_count: None,
+        }
+    }
+
+    fn stats() -> BadgeStats {
+        BadgeStats {
+            highest_score: 250,
+            accuracy_rate: 90.0,
+            total_submissions: 40,
+            best_streak: 6,
+            specializations: HashMap::from([("malicious".to_string(), 10)]),
+        }
+    }
+
+    #[test]
+    fn test_single_criterion_progress() {
+        let score = BadgeCriteria { min_score: Some(500), ..criteria() };
+        assert_eq!(progress(&score, &stats()), 0.5);
+
+        let streak = BadgeCriteria { min_streak: Some(5), ..criteria() };
+        assert_eq!(progress(&streak, &stats()), 1.0);
+
+        let specialization = BadgeCriteria {
+            specialization: Some("Malicious".to_string()),
+            min_specialization_count: Some(25),
+            ..criteria()
+        };
+        assert_eq!(progress(&specialization, &stats()), 0.4);
+    }
+
+    #[test]
+    fn test_furthest_criterion_behind_counts() {
+        let both = BadgeCriteria {
+            min_submissions: Some(20),
+            min_streak: Some(12),
+            ..criteria()
+        };
+        assert_eq!(progress(&both, &stats()), 0.5);
+    }
+
+    #[test]
+    fn test_accuracy_needs_enough_submissions() {
+        let accuracy = BadgeCriteria { min_accuracy: Some(80.0), ..criteria() };
+        let lucky = BadgeStats {
+            accuracy_rate: 100.0,
+            total_submissions: 2,
+            ..stats()
+        };
+        assert_eq!(progress(&accuracy, &lucky), 0.2);
+        assert_eq!(progress(&accuracy, &stats()), 1.0);
+    }
+
+    #[test]
+    fn test_badges_without_criteria_never_progress() {
+        assert_eq!(progress(&criteria(), &stats()), 0.0);
+    }
+}
@@ -0,0 +1,132 @@
+use serde::Serialize;
+
+/// One-sided z-score a drop in accuracy must reach before it is put down to
+/// more than chance, about 95% confidence
+const MIN_Z_SCORE: f64 = 1.645;
+
+/// How an engine did on a run of calibration bounties
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct CalibrationAccuracy {
+    pub correct: i32,
+    pub samples: i32,
+}
+
+impl CalibrationAccuracy {
+    pub fn of(results: &[bool]) -> Self {
+        Self {
+            correct: results.iter().filter(|correct| **correct).count() as i32,
+            samples: results.len() as i32,
+        }
+    }
+
+    /// Share right, 0.0 to 1.0; `None` without samples
+    pub fn accuracy(&self) -> Option<f64> {
+        (self.samples > 0).then(|| self.correct as f64 / self.samples as f64)
+    }
+}
+
+/// When a fall in calibration accuracy counts as degradation
+#[derive(Debug, Clone, Copy)]
+pub struct DegradationPolicy {
+    /// Most recent results compared against the rest
+    pub recent_window: usize,
+    /// Fewest earlier results the recent ones are compared against
+    pub min_baseline_samples: i32,
+    /// Smallest fall in accuracy, 0.0 to 1.0, worth flagging
+    pub min_accuracy_drop: f64,
+}
+
+/// An engine's results, newest first, split into its recent window and the
+/// baseline before it
+pub fn split(results: &[bool], recent_window: usize) -> (CalibrationAccuracy, CalibrationAccuracy) {
+    let (recent, baseline) = results.split_at(recent_window.min(results.len()));
+    (CalibrationAccuracy::of(recent), CalibrationAccuracy::of(baseline))
+}
+
+/// Whether recent accuracy fell below the baseline by at least the policy's
+/// drop, with a full recent window and enough baseline behind it, and by
+/// more than chance explains (a one-sided two-proportion z-test)
+pub fn degraded(policy: &DegradationPolicy, recent: CalibrationAccuracy, baseline: CalibrationAccuracy) -> bool {
+    let enough_recent = recent.samples as usize >= policy.recent_window.max(1);
+    if !enough_recent || baseline.samples < policy.min_baseline_samples.max(1) {
+        return false;
+    }
+    let (Some(recent_accuracy), Some(baseline_accuracy)) = (recent.accuracy(), baseline.accuracy()) else {
+        return false;
+    };
+    let drop = baseline_accuracy - recent_accuracy;
+    if drop < policy.min_accuracy_drop || drop <= 0.0 {
+        return false;
+    }
+
+    let samples = (recent.samples + baseline.samples) as f64;
+    let pooled = (recent.correct + baseline.correct) as f64 / samples;
+    let standard_error =
+        (pooled * (1.0 - pooled) * (1.0 / recent.samples as f64 + 1.0 / baseline.samples as f64)).sqrt();
+    standard_error > 0.0 && drop / standard_error >= MIN_Z_SCORE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> DegradationPolicy {
+        DegradationPolicy {
+            recent_window: 10,
+            min_baseline_samples: 20,
+            min_accuracy_drop: 0.2,
+        }
+    }
+
+    fn results(recent: (usize, usize), baseline: (usize, usize)) -> Vec<bool> {
+        let mut results = Vec::new();
+        for (correct, samples) in [recent, baseline] {
+            results.extend((0..samples).map(|i| i < correct));
+        }
+        results
+    }
+
+    #[test]
+    fn test_split_keeps_newest_results_recent() {
+        let (recent, baseline) = split(&results((3, 10), (18, 20)), 10);
+        assert_eq!(recent, CalibrationAccuracy { correct: 3, samples: 10 });
+        assert_eq!(baseline, CalibrationAccuracy { correct: 18, samples: 20 });
+
+        let (recent, baseline) = split(&[true, false], 10);
+        assert_eq!(recent.samples, 2);
+        assert_eq!(baseline.accuracy(), None);
+    }
+
+    #[test]
+    fn test_sharp_drop_is_degradation() {
+        let (recent, baseline) = split(&results((4, 10), (27, 30)), 10);
+        assert!(degraded(&policy(), recent, baseline));
+    }
+
+    #[test]
+    fn test_small_or_noisy_drops_are_not_degradation() {
+        // Below the minimum drop
+        let (recent, baseline) = split(&results((8, 10), (27, 30)), 10);
+        assert!(!degraded(&policy(), recent, baseline));
+        // Big enough, but within what chance explains on so few samples
+        let wide = DegradationPolicy {
+            recent_window: 3,
+            min_baseline_samples: 3,
+            ..policy()
+        };
+        let (recent, baseline) = split(&results((2, 3), (3, 3)), 3);
+        assert!(!degraded(&wide, recent, baseline));
+        // Improvement
+        let (recent, baseline) = split(&results((10, 10), (15, 30)), 10);
+        assert!(!degraded(&policy(), recent, baseline));
+    }
+
+    #[test]
+    fn test_degradation_needs_enough_samples() {
+        let (recent, baseline) = split(&results((0, 10), (10, 10)), 10);
+        assert!(!degraded(&policy(), recent, baseline));
+        let recent = CalibrationAccuracy { correct: 0, samples: 5 };
+        let baseline = CalibrationAccuracy { correct: 30, samples: 30 };
+        assert!(!degraded(&policy(), recent, baseline));
+    }
+}
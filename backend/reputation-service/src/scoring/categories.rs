@@ -0,0 +1,81 @@
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// A threat category and the bounty tags that put a bounty in it
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ReputationCategory {
+    pub slug: String,
+    pub name: String,
+    pub tags: Vec<String>,
+}
+
+/// Slugs of the categories a bounty with `tags` is in
+pub fn categories_for<'a>(categories: &'a [ReputationCategory], tags: &[String]) -> Vec<&'a str> {
+    categories
+        .iter()
+        .filter(|category| {
+            tags.iter()
+                .any(|tag| category.tags.iter().any(|listed| listed.eq_ignore_ascii_case(tag.trim())))
+        })
+        .map(|category| category.slug.as_str())
+        .collect()
+}
+
+/// Reputation a vote on a bounty in `categories` is weighted by: the mean of
+/// the user's scores in those of them they have settled submissions in.
+/// `None` when they have none, and their global score applies.
+pub fn specialization_score(scores: &HashMap<String, i32>, categories: &[&str]) -> Option<i32> {
+    let matched: Vec<i32> = categories
+        .iter()
+        .filter_map(|category| scores.get(*category).copied())
+        .collect();
+    if matched.is_empty() {
+        return None;
+    }
+    let total: i64 = matched.iter().map(|score| *score as i64).sum();
+    Some((total as f64 / matched.len() as f64).round() as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn categories() -> Vec<ReputationCategory> {
+        vec![
+            ReputationCategory {
+                slug: "phishing".to_string(),
+                name: "Phishing".to_string(),
+                tags: vec!["phishing".to_string(), "malicious-url".to_string()],
+            },
+            ReputationCategory {
+                slug: "apt".to_string(),
+                name: "APT".to_string(),
+                tags: vec!["apt".to_string(), "c2".to_string()],
+            },
+        ]
+    }
+
+    #[test]
+    fn test_tags_map_to_categories() {
+        let categories = categories();
+        let tags = vec!["malicious-url".to_string(), "C2".to_string(), "trojan".to_string()];
+        assert_eq!(categories_for(&categories, &tags), vec!["phishing", "apt"]);
+        assert!(categories_for(&categories, &["trojan".to_string()]).is_empty());
+    }
+
+    #[test]
+    fn test_specialization_score_averages_known_categories() {
+        let scores = HashMap::from([("phishing".to_string(), 300), ("apt".to_string(), 101)]);
+        assert_eq!(specialization_score(&scores, &["phishing"]), Some(300));
+        assert_eq!(specialization_score(&scores, &["phishing", "apt"]), Some(201));
+        // Categories without a record do not drag the mean down
+        assert_eq!(specialization_score(&scores, &["phishing", "mobile"]), Some(300));
+    }
+
+    #[test]
+    fn test_no_record_falls_back_to_global() {
+        let scores = HashMap::from([("apt".to_string(), 500)]);
+        assert_eq!(specialization_score(&scores, &["mobile"]), None);
+        assert_eq!(specialization_score(&scores, &[]), None);
+    }
+}
@@ -0,0 +1,183 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// How fast scores in one band decay
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct DecayTier {
+    pub name: String,
+    /// Lowest score in the tier; it runs up to the next tier's
+    pub min_score: i32,
+    /// Share of the score lost per day of inactivity
+    pub rate_per_day: f64,
+    /// Days without a settled submission before decay starts
+    pub grace_period_days: i32,
+    /// Decay never takes a score below highest score x lifetime accuracy
+    /// x this ratio
+    pub accuracy_floor_ratio: f64,
+}
+
+/// Every decay tier and the settings that apply across them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecayPolicy {
+    pub enabled: bool,
+    /// Ordered by `min_score`
+    pub tiers: Vec<DecayTier>,
+    /// Uptime a verified automated engine must keep for its owner to be
+    /// exempt from decay
+    pub engine_min_uptime: f64,
+    pub uptime_window_days: i32,
+}
+
+impl DecayPolicy {
+    /// The tier a score falls in. Scores below every tier do not decay.
+    pub fn tier_for(&self, score: i32) -> Option<&DecayTier> {
+        self.tiers.iter().rev().find(|tier| tier.min_score <= score)
+    }
+
+    /// Shortest grace period of any tier; nobody decays sooner
+    pub fn min_grace_period_days(&self) -> i32 {
+        self.tiers.iter().map(|tier| tier.grace_period_days).min().unwrap_or(0)
+    }
+}
+
+/// A user as decay sees them
+#[derive(Debug, Clone)]
+pub struct DecayInput {
+    pub score: i32,
+    pub highest_score: i32,
+    /// Lifetime accuracy as a percentage
+    pub accuracy_rate: f64,
+    pub last_active_at: DateTime<Utc>,
+    pub last_decayed_at: Option<DateTime<Utc>>,
+}
+
+/// Decay due for a user since it was last applied
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DecayOutcome {
+    pub tier: String,
+    pub new_score: i32,
+    pub floor: i32,
+    pub days: f64,
+}
+
+/// Lowest score decay may leave a user at under a tier
+pub fn floor(tier: &DecayTier, input: &DecayInput) -> i32 {
+    let accuracy = (input.accuracy_rate / 100.0).clamp(0.0, 1.0);
+    (input.highest_score.max(0) as f64 * accuracy * tier.accuracy_floor_ratio).round() as i32
+}
+
+/// Decay since the later of the end of the grace period and the last time
+/// decay was applied, or `None` if nothing is due
+pub fn decay(policy: &DecayPolicy, input: &DecayInput, min_score: i32, now: DateTime<Utc>) -> Option<DecayOutcome> {
+    if !policy.enabled {
+        return None;
+    }
+    let tier = policy.tier_for(input.score)?;
+    let grace_ends = input.last_active_at + Duration::days(tier.grace_period_days as i64);
+    let since = input.last_decayed_at.map_or(grace_ends, |decayed| decayed.max(grace_ends));
+    if now <= since {
+        return None;
+    }
+
+    let days = (now - since).num_seconds() as f64 / 86_400.0;
+    let floor = floor(tier, input).max(min_score);
+    let decayed = (input.score as f64 * (1.0 - tier.rate_per_day * days).max(0.0)).round() as i32;
+    let new_score = decayed.max(floor);
+    (new_score < input.score).then(|| DecayOutcome {
+        tier: tier.name.clone(),
+        new_score,
+        floor,
+        days,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tier(name: &str, min_score: i32, rate_per_day: f64, grace_period_days: i32) -> DecayTier {
+        DecayTier {
+            name: name.to_string(),
+            min_score,
+            rate_per_day,
+            grace_period_days,
+            accuracy_floor_ratio: 0.5,
+        }
+    }
+
+    fn policy() -> DecayPolicy {
+        DecayPolicy {
+            enabled: true,
+            tiers: vec![tier("novice", 100, 0.01, 30), tier("elite", 5000, 0.02, 7)],
+            engine_min_uptime: 0.99,
+            uptime_window_days: 30,
+        }
+    }
+
+    fn input(score: i32, inactive_days: i64) -> DecayInput {
+        DecayInput {
+            score,
+            highest_score: score,
+            accuracy_rate: 0.0,
+            last_active_at: Utc::now() - Duration::days(inactive_days),
+            last_decayed_at: None,
+        }
+    }
+
+    #[test]
+    fn test_tier_is_picked_by_score() {
+        let policy = policy();
+        assert_eq!(policy.tier_for(50), None);
+        assert_eq!(policy.tier_for(100).unwrap().name, "novice");
+        assert_eq!(policy.tier_for(7000).unwrap().name, "elite");
+        assert_eq!(policy.min_grace_period_days(), 7);
+    }
+
+    #[test]
+    fn test_decay_waits_for_the_grace_period() {
+        let now = Utc::now();
+        assert_eq!(decay(&policy(), &input(1000, 20), 0, now), None);
+
+        // Ten days past the 30-day grace period at 1% a day
+        let outcome = decay(&policy(), &input(1000, 40), 0, now).unwrap();
+        assert_eq!(outcome.tier, "novice");
+        assert_eq!(outcome.new_score, 900);
+
+        // Elite scores decay after a week
+        assert_eq!(decay(&policy(), &input(6000, 17), 0, now).unwrap().new_score, 4800);
+    }
+
+    #[test]
+    fn test_decay_resumes_from_the_last_run() {
+        let now = Utc::now();
+        let user = DecayInput {
+            last_decayed_at: Some(now - Duration::days(5)),
+            ..input(1000, 60)
+        };
+        assert_eq!(decay(&policy(), &user, 0, now).unwrap().new_score, 950);
+    }
+
+    #[test]
+    fn test_floor_follows_lifetime_accuracy() {
+        let now = Utc::now();
+        let accurate = DecayInput {
+            highest_score: 2000,
+            accuracy_rate: 90.0,
+            ..input(1000, 300)
+        };
+        // 2000 x 90% x 0.5
+        let outcome = decay(&policy(), &accurate, 0, now).unwrap();
+        assert_eq!(outcome.floor, 900);
+        assert_eq!(outcome.new_score, 900);
+
+        let at_floor = DecayInput { score: 900, ..accurate };
+        assert_eq!(decay(&policy(), &at_floor, 0, now), None);
+        assert_eq!(decay(&policy(), &input(1000, 300), 950, now).unwrap().new_score, 950);
+    }
+
+    #[test]
+    fn test_disabled_policy_never_decays() {
+        let disabled = DecayPolicy { enabled: false, ..policy() };
+        assert_eq!(decay(&disabled, &input(1000, 300), 0, Utc::now()), None);
+    }
+}
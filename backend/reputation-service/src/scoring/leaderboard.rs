@@ -0,0 +1,149 @@
+use chrono::{DateTime, Duration, DurationRound, Utc};
+
+/// Windowed boards are summed from hourly buckets; buckets outlive the
+/// longest window by a day
+pub const BUCKET_RETENTION_HOURS: i64 = 31 * 24;
+
+/// The period a leaderboard ranks over
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaderboardWindow {
+    Day,
+    Week,
+    Month,
+    /// Ranked by current score rather than points gained
+    AllTime,
+}
+
+impl LeaderboardWindow {
+    pub fn parse(window: &str) -> Option<Self> {
+        match window.trim().to_ascii_lowercase().as_str() {
+            "24h" | "1d" => Some(Self::Day),
+            "7d" => Some(Self::Week),
+            "30d" => Some(Self::Month),
+            "all" | "all-time" | "all_time" => Some(Self::AllTime),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Day => "24h",
+            Self::Week => "7d",
+            Self::Month => "30d",
+            Self::AllTime => "all",
+        }
+    }
+
+    /// Hourly buckets summed for the window, `None` for all-time
+    pub fn hours(&self) -> Option<i64> {
+        match self {
+            Self::Day => Some(24),
+            Self::Week => Some(7 * 24),
+            Self::Month => Some(30 * 24),
+            Self::AllTime => None,
+        }
+    }
+}
+
+/// Whose reputation a leaderboard ranks. Owners of automated engines rank
+/// as engines, everyone else as humans.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Participant {
+    All,
+    Engine,
+    Human,
+}
+
+impl Participant {
+    pub fn parse(participant: &str) -> Option<Self> {
+        match participant.trim().to_ascii_lowercase().as_str() {
+            "all" => Some(Self::All),
+            "engine" | "engines" => Some(Self::Engine),
+            "human" | "humans" => Some(Self::Human),
+            _ => None,
+        }
+    }
+
+    pub fn of(is_engine: bool) -> Self {
+        if is_engine {
+            Self::Engine
+        } else {
+            Self::Human
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::All => "all",
+            Self::Engine => "engine",
+            Self::Human => "human",
+        }
+    }
+}
+
+/// Start of the hourly bucket `at` falls in
+pub fn bucket_start(at: DateTime<Utc>) -> DateTime<Utc> {
+    at.duration_trunc(Duration::hours(1)).unwrap_or(at)
+}
+
+/// Id of the hourly bucket `at` falls in, like `2026101814`
+pub fn bucket_id(at: DateTime<Utc>) -> String {
+    at.format("%Y%m%d%H").to_string()
+}
+
+/// Ids of the buckets summed for a window ending at `now`: the current hour
+/// and the ones before it
+pub fn window_buckets(hours: i64, now: DateTime<Utc>) -> Vec<String> {
+    let current = bucket_start(now);
+    (0..hours).map(|hour| bucket_id(current - Duration::hours(hour))).collect()
+}
+
+/// Ranks for a page of scores sorted high to low, starting `offset` entries
+/// into the board; tied scores share the rank of the first of them. `above`
+/// is how many entries outscore the first one on the page, which may be
+/// tied with entries on the page before.
+pub fn competition_ranks(scores: &[f64], offset: usize, above: usize) -> Vec<usize> {
+    let mut ranks: Vec<usize> = Vec::with_capacity(scores.len());
+    for (i, score) in scores.iter().enumerate() {
+        let rank = match i {
+            0 => above + 1,
+            _ if scores[i - 1] == *score => ranks[i - 1],
+            _ => offset + i + 1,
+        };
+        ranks.push(rank);
+    }
+    ranks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_windows_parse() {
+        assert_eq!(LeaderboardWindow::parse("24h"), Some(LeaderboardWindow::Day));
+        assert_eq!(LeaderboardWindow::parse("7D"), Some(LeaderboardWindow::Week));
+        assert_eq!(LeaderboardWindow::parse("all-time"), Some(LeaderboardWindow::AllTime));
+        assert_eq!(LeaderboardWindow::parse("90d"), None);
+        assert_eq!(Participant::parse("engines"), Some(Participant::Engine));
+        assert_eq!(Participant::parse("robots"), None);
+    }
+
+    #[test]
+    fn test_window_buckets_end_at_current_hour() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 18, 14, 35, 0).unwrap();
+        let buckets = window_buckets(24, now);
+        assert_eq!(buckets.len(), 24);
+        assert_eq!(buckets[0], "2026101814");
+        assert_eq!(buckets[23], "2026101715");
+    }
+
+    #[test]
+    fn test_tied_scores_share_a_rank() {
+        assert_eq!(competition_ranks(&[90.0, 80.0, 80.0, 70.0], 0, 0), vec![1, 2, 2, 4]);
+        // The page starts with two entries tied with the last on the page before
+        assert_eq!(competition_ranks(&[50.0, 50.0, 40.0], 10, 9), vec![10, 10, 13]);
+        assert!(competition_ranks(&[], 20, 20).is_empty());
+    }
+}
@@ -1,5 +1,19 @@
+pub mod appeals;
+pub mod badges;
+pub mod calibration;
+pub mod categories;
+pub mod decay;
+pub mod leaderboard;
+pub mod probation;
+pub mod seasons;
+pub mod streaks;
+pub mod voting_power;
+
+use chrono::{DateTime, Utc};
+
 use crate::config::ReputationConfig;
 use crate::models::{ReputationUpdateRequest, UserReputation};
+use decay::{DecayInput, DecayOutcome, DecayPolicy};
 use rust_decimal::Decimal;
 
 pub struct ReputationScorer {
@@ -54,11 +68,10 @@ impl ReputationScorer {
         Decimal::from(correct) / Decimal::from(total)
     }
 
-    /// Apply time decay to reputation score
-    pub fn apply_decay(&self, current_score: i32, days_inactive: f64) -> i32 {
-        let decay_factor = 1.0 - (self.config.decay_rate_per_day * days_inactive);
-        let new_score = (current_score as f64 * decay_factor.max(0.0)) as i32;
-        new_score.max(self.config.min_score)
+    /// Apply time decay to reputation score under the tier the score falls
+    /// in. Scores never decay below the configured minimum.
+    pub fn apply_decay(&self, policy: &DecayPolicy, input: &DecayInput, now: DateTime<Utc>) -> Option<DecayOutcome> {
+        decay::decay(policy, input, self.config.min_score, now)
     }
 
     /// Calculate percentile rank
@@ -0,0 +1,38 @@
+/// Score an engine starts with once its probation ends, from how many of its
+/// calibration bounties it got right. Guessing gets about half of them right,
+/// so only accuracy above one half earns anything: all right earns
+/// `max_seed_score`, half or fewer nothing.
+pub fn seed_score(correct: i32, completed: i32, max_seed_score: i32) -> i32 {
+    if completed <= 0 {
+        return 0;
+    }
+    let accuracy = correct.clamp(0, completed) as f64 / completed as f64;
+    let above_chance = (2.0 * accuracy - 1.0).max(0.0);
+    (max_seed_score.max(0) as f64 * above_chance).round() as i32
+}
+
+/// Calibration bounties an engine has left to settle
+pub fn calibrations_remaining(completed: i32, required: i32) -> i32 {
+    (required - completed).max(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seed_score_rewards_accuracy_above_chance() {
+        assert_eq!(seed_score(5, 5, 500), 500);
+        assert_eq!(seed_score(4, 5, 500), 300);
+        assert_eq!(seed_score(3, 5, 500), 100);
+        assert_eq!(seed_score(2, 5, 500), 0);
+        assert_eq!(seed_score(0, 5, 500), 0);
+    }
+
+    #[test]
+    fn test_seed_score_without_calibrations() {
+        assert_eq!(seed_score(0, 0, 500), 0);
+        assert_eq!(calibrations_remaining(2, 5), 3);
+        assert_eq!(calibrations_remaining(7, 5), 0);
+    }
+}
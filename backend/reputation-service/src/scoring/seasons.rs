@@ -0,0 +1,94 @@
+use chrono::{DateTime, Datelike, TimeZone, Utc};
+
+/// Lowest rank that earns a season badge
+pub const LAST_AWARDED_RANK: i32 = 10;
+
+/// The calendar quarter a season runs for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeasonPeriod {
+    /// Year and quarter, like `2026-Q3`
+    pub id: String,
+    pub starts_at: DateTime<Utc>,
+    /// Start of the next quarter
+    pub ends_at: DateTime<Utc>,
+}
+
+impl SeasonPeriod {
+    /// The quarter `at` falls in
+    pub fn containing(at: DateTime<Utc>) -> Self {
+        let quarter = at.month0() / 3;
+        let starts_at = Utc.with_ymd_and_hms(at.year(), quarter * 3 + 1, 1, 0, 0, 0).unwrap();
+        let ends_at = if quarter == 3 {
+            Utc.with_ymd_and_hms(at.year() + 1, 1, 1, 0, 0, 0).unwrap()
+        } else {
+            Utc.with_ymd_and_hms(at.year(), quarter * 3 + 4, 1, 0, 0, 0).unwrap()
+        };
+        Self {
+            id: format!("{}-Q{}", at.year(), quarter + 1),
+            starts_at,
+            ends_at,
+        }
+    }
+}
+
+/// Badge earned by finishing a season near the top
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeasonAward {
+    Champion,
+    Podium,
+    TopTen,
+}
+
+impl SeasonAward {
+    /// The award for a final rank; tied users share a rank and its award
+    pub fn for_rank(rank: i32) -> Option<Self> {
+        match rank {
+            1 => Some(Self::Champion),
+            2..=3 => Some(Self::Podium),
+            4..=LAST_AWARDED_RANK => Some(Self::TopTen),
+            _ => None,
+        }
+    }
+
+    /// Name of the badge in `badges`
+    pub fn badge_name(&self) -> &'static str {
+        match self {
+            Self::Champion => "Season Champion",
+            Self::Podium => "Season Podium",
+            Self::TopTen => "Season Top 10",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seasons_follow_calendar_quarters() {
+        let season = SeasonPeriod::containing(Utc.with_ymd_and_hms(2026, 8, 17, 12, 0, 0).unwrap());
+        assert_eq!(season.id, "2026-Q3");
+        assert_eq!(season.starts_at, Utc.with_ymd_and_hms(2026, 7, 1, 0, 0, 0).unwrap());
+        assert_eq!(season.ends_at, Utc.with_ymd_and_hms(2026, 10, 1, 0, 0, 0).unwrap());
+
+        // The first instant of a quarter belongs to it
+        let first = SeasonPeriod::containing(Utc.with_ymd_and_hms(2026, 4, 1, 0, 0, 0).unwrap());
+        assert_eq!(first.id, "2026-Q2");
+    }
+
+    #[test]
+    fn test_last_quarter_ends_next_year() {
+        let season = SeasonPeriod::containing(Utc.with_ymd_and_hms(2026, 12, 31, 23, 59, 59).unwrap());
+        assert_eq!(season.id, "2026-Q4");
+        assert_eq!(season.ends_at, Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap());
+        assert_eq!(SeasonPeriod::containing(season.ends_at).id, "2027-Q1");
+    }
+
+    #[test]
+    fn test_awards_by_rank() {
+        assert_eq!(SeasonAward::for_rank(1), Some(SeasonAward::Champion));
+        assert_eq!(SeasonAward::for_rank(3), Some(SeasonAward::Podium));
+        assert_eq!(SeasonAward::for_rank(10), Some(SeasonAward::TopTen));
+        assert_eq!(SeasonAward::for_rank(11), None);
+    }
+}
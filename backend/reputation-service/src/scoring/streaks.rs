@@ -0,0 +1,39 @@
+/// Streak lengths announced on the way up
+pub const STREAK_MILESTONES: [i32; 5] = [5, 10, 25, 50, 100];
+
+/// Past the last milestone, every this many more is one
+const MILESTONE_STEP_AFTER_LAST: i32 = 100;
+
+/// Whether reaching a streak of `streak` correct submissions is a
+/// milestone. A streak grows one settlement at a time, so it passes
+/// through every milestone on the way.
+pub fn is_milestone(streak: i32) -> bool {
+    let last = STREAK_MILESTONES[STREAK_MILESTONES.len() - 1];
+    if streak > last {
+        return streak % MILESTONE_STEP_AFTER_LAST == 0;
+    }
+    STREAK_MILESTONES.contains(&streak)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_milestones() {
+        assert!(is_milestone(5));
+        assert!(is_milestone(25));
+        assert!(is_milestone(100));
+        assert!(!is_milestone(0));
+        assert!(!is_milestone(6));
+        assert!(!is_milestone(99));
+    }
+
+    #[test]
+    fn test_every_hundred_after_the_last_milestone() {
+        assert!(is_milestone(200));
+        assert!(is_milestone(1000));
+        assert!(!is_milestone(150));
+        assert!(!is_milestone(250));
+    }
+}
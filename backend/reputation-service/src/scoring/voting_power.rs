@@ -0,0 +1,114 @@
+use crate::config::{GovernanceConfig, VotingCurve};
+use crate::models::VotingPower;
+use uuid::Uuid;
+
+pub struct VotingPowerCalculator {
+    config: GovernanceConfig,
+}
+
+impl VotingPowerCalculator {
+    pub fn new(config: GovernanceConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &GovernanceConfig {
+        &self.config
+    }
+
+    /// Calculate governance weight from reputation and stake.
+    ///
+    /// Each component is scaled by its curve and weight and capped on its
+    /// own before the total is capped, so neither reputation nor stake alone
+    /// can reach the maximum.
+    pub fn calculate(&self, user_id: Uuid, reputation: i32, staked: f64) -> VotingPower {
+        let eligible = reputation >= self.config.min_reputation;
+
+        let (reputation_power, stake_power, voting_power) = if eligible {
+            let reputation_power = (self.config.reputation_weight
+                * apply_curve(self.config.reputation_curve, reputation as f64))
+                .min(self.config.reputation_cap);
+            let stake_power = (self.config.stake_weight * apply_curve(self.config.stake_curve, staked))
+                .min(self.config.stake_cap);
+            let voting_power = (reputation_power + stake_power).min(self.config.max_voting_power);
+            (reputation_power, stake_power, voting_power)
+        } else {
+            (0.0, 0.0, 0.0)
+        };
+
+        VotingPower {
+            user_id,
+            reputation,
+            staked,
+            reputation_power: round(reputation_power),
+            stake_power: round(stake_power),
+            voting_power: round(voting_power),
+            eligible,
+        }
+    }
+}
+
+fn apply_curve(curve: VotingCurve, value: f64) -> f64 {
+    let value = value.max(0.0);
+    match curve {
+        VotingCurve::Linear => value,
+        VotingCurve::Sqrt => value.sqrt(),
+        VotingCurve::Log => value.ln_1p(),
+    }
+}
+
+/// Round to 6 decimal places so results compare equal across queries
+fn round(value: f64) -> f64 {
+    (value * 1_000_000.0).round() / 1_000_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> GovernanceConfig {
+        GovernanceConfig {
+            min_reputation: 100,
+            reputation_weight: 1.0,
+            reputation_curve: VotingCurve::Linear,
+            reputation_cap: 5000.0,
+            stake_weight: 10.0,
+            stake_curve: VotingCurve::Sqrt,
+            stake_cap: 5000.0,
+            max_voting_power: 8000.0,
+            stake_pool_types: vec!["governance_staking".to_string()],
+            chain_id: 1,
+            max_bulk_users: 500,
+        }
+    }
+
+    #[test]
+    fn test_voting_power_combines_components() {
+        let calculator = VotingPowerCalculator::new(test_config());
+        let power = calculator.calculate(Uuid::new_v4(), 1200, 10_000.0);
+
+        assert!(power.eligible);
+        assert_eq!(power.reputation_power, 1200.0);
+        assert_eq!(power.stake_power, 1000.0);
+        assert_eq!(power.voting_power, 2200.0);
+    }
+
+    #[test]
+    fn test_voting_power_caps() {
+        let calculator = VotingPowerCalculator::new(test_config());
+        let power = calculator.calculate(Uuid::new_v4(), 9000, 1_000_000.0);
+
+        assert_eq!(power.reputation_power, 5000.0);
+        assert_eq!(power.stake_power, 5000.0);
+        assert_eq!(power.voting_power, 8000.0);
+    }
+
+    #[test]
+    fn test_below_min_reputation_has_no_power() {
+        let calculator = VotingPowerCalculator::new(test_config());
+        let power = calculator.calculate(Uuid::new_v4(), 99, 1_000_000.0);
+
+        assert!(!power.eligible);
+        assert_eq!(power.voting_power, 0.0);
+        assert_eq!(power.staked, 1_000_000.0);
+    }
+}
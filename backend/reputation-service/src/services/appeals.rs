@@ -0,0 +1,852 @@
+// Reputation appeals
+//
+// A user can contest a penalty in their reputation history, a slashed
+// settlement vote or decay, within `window_days` of it, giving their reason
+// and evidence. Admins decide an appeal themselves or escalate it to a panel
+// of arbitrators drawn at random from users scoring at least
+// `min_arbitrator_score`. The panel has `voting_hours` to vote and decides
+// as soon as a majority of it agrees (see `scoring::appeals`); a panel that
+// runs out of time without enough votes hands the appeal back to the admins.
+// An upheld appeal is reversed in the transaction that decides it: the score
+// is restored, a slashed vote stops counting against the user's accuracy,
+// specialization and categories, and an `appeal_reversal` history entry
+// records the reversal, which the event publisher announces like any other
+// score change. Everything done to an appeal is kept in
+// `reputation_appeal_events`.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::{PgConnection, PgPool};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::config::AppealConfig;
+use crate::models::{ReputationError, ReputationHistory, ReputationResult};
+use crate::scoring::appeals::{self, AppealDecision};
+
+/// Longest reason, evidence description or decision note accepted
+const MAX_TEXT_LEN: usize = 5000;
+
+/// Appeals per page when no limit is asked for
+const DEFAULT_APPEAL_LIMIT: i64 = 20;
+
+/// Most appeals per page
+const MAX_APPEAL_LIMIT: i64 = 100;
+
+/// Statuses of an appeal still waiting for a decision
+const PENDING_STATUSES: [&str; 2] = ["open", "under_review"];
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Appeal {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// The penalty appealed
+    pub history_id: Uuid,
+    pub reason: String,
+    /// `open`, `under_review` while a panel votes, `upheld`, `rejected` or
+    /// `withdrawn`
+    pub status: String,
+    pub voting_deadline: Option<DateTime<Utc>>,
+    pub decided_by: Option<Uuid>,
+    pub decision_notes: Option<String>,
+    /// The history entry that reversed the penalty, once upheld
+    pub reversal_history_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub decided_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct AppealEvidence {
+    pub id: Uuid,
+    pub description: String,
+    pub url: Option<String>,
+    pub added_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Panelist {
+    pub arbitrator_id: Uuid,
+    /// `None` until the arbitrator votes
+    pub uphold: Option<bool>,
+    pub notes: Option<String>,
+    pub seated_at: DateTime<Utc>,
+    pub voted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct AppealEvent {
+    /// `None` for the system
+    pub actor_id: Option<Uuid>,
+    pub action: String,
+    pub details: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An appeal with the penalty it contests and everything done to it
+#[derive(Debug, Serialize)]
+pub struct AppealCase {
+    #[serde(flatten)]
+    pub appeal: Appeal,
+    pub penalty: ReputationHistory,
+    pub evidence: Vec<AppealEvidence>,
+    pub panel: Vec<Panelist>,
+    pub events: Vec<AppealEvent>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EvidenceRequest {
+    pub description: String,
+    /// HTTPS link to supporting material, e.g. an analysis report
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FileAppealRequest {
+    /// The reputation history entry of the penalty
+    pub history_id: Uuid,
+    pub reason: String,
+    #[serde(default)]
+    pub evidence: Vec<EvidenceRequest>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddEvidenceRequest {
+    pub evidence: Vec<EvidenceRequest>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PanelVoteRequest {
+    /// Whether the penalty should be reversed
+    pub uphold: bool,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AppealDecisionRequest {
+    pub uphold: bool,
+    pub notes: String,
+}
+
+fn db_error(e: sqlx::Error) -> ReputationError {
+    ReputationError::DatabaseError(e.to_string())
+}
+
+fn validate_text(field: &str, text: &str) -> ReputationResult<()> {
+    if text.trim().is_empty() {
+        return Err(ReputationError::ValidationError(format!("{} is required", field)));
+    }
+    if text.len() > MAX_TEXT_LEN {
+        return Err(ReputationError::ValidationError(format!(
+            "{} must be at most {} characters",
+            field, MAX_TEXT_LEN
+        )));
+    }
+    Ok(())
+}
+
+fn validate_evidence(evidence: &[EvidenceRequest]) -> ReputationResult<()> {
+    for item in evidence {
+        validate_text("Evidence description", &item.description)?;
+        if let Some(url) = &item.url {
+            let valid = reqwest::Url::parse(url)
+                .is_ok_and(|url| url.scheme() == "https" && url.host_str().is_some_and(|h| !h.is_empty()));
+            if !valid {
+                return Err(ReputationError::ValidationError(format!(
+                    "Evidence URL must be an HTTPS URL: {}",
+                    url
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn ensure_pending(appeal: &Appeal) -> ReputationResult<()> {
+    if PENDING_STATUSES.contains(&appeal.status.as_str()) {
+        Ok(())
+    } else {
+        Err(ReputationError::ValidationError(format!(
+            "Appeal {} is already {}",
+            appeal.id, appeal.status
+        )))
+    }
+}
+
+async fn lock_appeal(conn: &mut PgConnection, appeal_id: Uuid) -> ReputationResult<Appeal> {
+    sqlx::query_as("SELECT * FROM reputation_appeals WHERE id = $1 FOR UPDATE")
+        .bind(appeal_id)
+        .fetch_optional(conn)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| ReputationError::NotFound(format!("Appeal {}", appeal_id)))
+}
+
+async fn record(
+    conn: &mut PgConnection,
+    appeal_id: Uuid,
+    actor_id: Option<Uuid>,
+    action: &str,
+    details: serde_json::Value,
+) -> ReputationResult<()> {
+    sqlx::query(
+        "INSERT INTO reputation_appeal_events (appeal_id, actor_id, action, details) VALUES ($1, $2, $3, $4::JSONB)",
+    )
+    .bind(appeal_id)
+    .bind(actor_id)
+    .bind(action)
+    .bind(details.to_string())
+    .execute(conn)
+    .await
+    .map_err(db_error)?;
+    Ok(())
+}
+
+async fn insert_evidence(
+    conn: &mut PgConnection,
+    appeal_id: Uuid,
+    evidence: &[EvidenceRequest],
+) -> ReputationResult<()> {
+    for item in evidence {
+        sqlx::query("INSERT INTO reputation_appeal_evidence (id, appeal_id, description, url) VALUES ($1, $2, $3, $4)")
+            .bind(Uuid::new_v4())
+            .bind(appeal_id)
+            .bind(item.description.trim())
+            .bind(&item.url)
+            .execute(&mut *conn)
+            .await
+            .map_err(db_error)?;
+    }
+    Ok(())
+}
+
+/// The votes to uphold and to reject an appeal, and the panel's decision if
+/// it has reached one
+async fn tally(
+    conn: &mut PgConnection,
+    appeal_id: Uuid,
+    deadline_passed: bool,
+) -> ReputationResult<(i64, i64, Option<AppealDecision>)> {
+    let (uphold, reject, seated): (i64, i64, i64) = sqlx::query_as(
+        r#"
+        SELECT COUNT(*) FILTER (WHERE uphold), COUNT(*) FILTER (WHERE NOT uphold), COUNT(*)
+        FROM reputation_appeal_panelists
+        WHERE appeal_id = $1
+        "#,
+    )
+    .bind(appeal_id)
+    .fetch_one(conn)
+    .await
+    .map_err(db_error)?;
+    let decision = appeals::panel_decision(uphold as usize, reject as usize, seated as usize, deadline_passed);
+    Ok((uphold, reject, decision))
+}
+
+/// Reverse an upheld appeal's penalty. Returns the history entry recording
+/// the reversal.
+async fn reverse(conn: &mut PgConnection, appeal: &Appeal) -> ReputationResult<Uuid> {
+    let penalty: ReputationHistory = sqlx::query_as("SELECT * FROM reputation_history WHERE id = $1")
+        .bind(appeal.history_id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(db_error)?;
+    let slashed = penalty.reason == "settlement_slashed";
+    let restored = -penalty.score_change;
+
+    let score_before: i32 =
+        sqlx::query_scalar("SELECT current_score FROM user_reputation WHERE user_id = $1 FOR UPDATE")
+            .bind(appeal.user_id)
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(db_error)?;
+    let score_after = score_before.saturating_add(restored);
+
+    // A slashed vote stops counting at all: it was not shown to be correct,
+    // only not to deserve the penalty
+    sqlx::query(
+        r#"
+        UPDATE user_reputation
+        SET current_score = $2,
+            highest_score = GREATEST(highest_score, $2),
+            total_submissions = GREATEST(total_submissions - $3, 0),
+            incorrect_submissions = GREATEST(incorrect_submissions - $3, 0),
+            accuracy_rate = COALESCE(ROUND(correct_submissions * 100.0 / NULLIF(total_submissions - $3, 0), 2), 0),
+            last_updated = NOW()
+        WHERE user_id = $1
+        "#,
+    )
+    .bind(appeal.user_id)
+    .bind(score_after)
+    .bind(i32::from(slashed))
+    .execute(&mut *conn)
+    .await
+    .map_err(db_error)?;
+
+    let details = penalty.details.clone().unwrap_or_default();
+    let categories: Vec<String> = details
+        .get("categories")
+        .filter(|_| slashed)
+        .and_then(|categories| serde_json::from_value(categories.clone()).ok())
+        .unwrap_or_default();
+    if slashed {
+        if !categories.is_empty() {
+            sqlx::query(
+                r#"
+                UPDATE user_category_reputation
+                SET score = score + $3,
+                    total_submissions = GREATEST(total_submissions - 1, 0),
+                    last_updated = NOW()
+                WHERE user_id = $1 AND category = ANY($2)
+                "#,
+            )
+            .bind(appeal.user_id)
+            .bind(&categories)
+            .bind(restored)
+            .execute(&mut *conn)
+            .await
+            .map_err(db_error)?;
+        }
+        if let Some(verdict) = details.get("final_verdict").and_then(|v| v.as_str()) {
+            sqlx::query(
+                r#"
+                UPDATE user_specializations
+                SET total_count = GREATEST(total_count - 1, 0), updated_at = NOW()
+                WHERE user_id = $1 AND specialization = $2
+                "#,
+            )
+            .bind(appeal.user_id)
+            .bind(verdict.to_lowercase())
+            .execute(&mut *conn)
+            .await
+            .map_err(db_error)?;
+        }
+    }
+
+    // Categories restored, as settlement records them, for the category
+    // leaderboards
+    let details = json!({
+        "appeal_id": appeal.id,
+        "reversed_history_id": penalty.id,
+        "reversed_reason": penalty.reason,
+        "categories": categories,
+    });
+    sqlx::query_scalar(
+        r#"
+        INSERT INTO reputation_history
+            (user_id, score_before, score_after, score_change, reason, bounty_id, submission_id, details)
+        VALUES ($1, $2, $3, $4, 'appeal_reversal', $5, $6, $7::JSONB)
+        RETURNING id
+        "#,
+    )
+    .bind(appeal.user_id)
+    .bind(score_before)
+    .bind(score_after)
+    .bind(score_after - score_before)
+    .bind(penalty.bounty_id)
+    .bind(penalty.submission_id)
+    .bind(details.to_string())
+    .fetch_one(conn)
+    .await
+    .map_err(db_error)
+}
+
+/// Close an appeal with a decision, reversing the penalty if upheld.
+/// `decided_by` is the admin, or `None` for a panel.
+async fn settle(
+    conn: &mut PgConnection,
+    appeal: &Appeal,
+    decision: AppealDecision,
+    decided_by: Option<Uuid>,
+    notes: &str,
+) -> ReputationResult<()> {
+    let reversal_history_id = match decision {
+        AppealDecision::Upheld => Some(reverse(&mut *conn, appeal).await?),
+        AppealDecision::Rejected => None,
+    };
+    sqlx::query(
+        r#"
+        UPDATE reputation_appeals
+        SET status = $2, decided_by = $3, decision_notes = $4, reversal_history_id = $5,
+            decided_at = NOW(), updated_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(appeal.id)
+    .bind(decision.as_str())
+    .bind(decided_by)
+    .bind(notes)
+    .bind(reversal_history_id)
+    .execute(&mut *conn)
+    .await
+    .map_err(db_error)?;
+    record(
+        conn,
+        appeal.id,
+        decided_by,
+        decision.as_str(),
+        json!({"notes": notes, "reversal_history_id": reversal_history_id}),
+    )
+    .await?;
+
+    info!("Appeal {} by {} {}", appeal.id, appeal.user_id, decision.as_str());
+    Ok(())
+}
+
+pub struct AppealService {
+    config: AppealConfig,
+    db_pool: PgPool,
+}
+
+impl AppealService {
+    pub fn new(config: AppealConfig, db_pool: PgPool) -> Self {
+        Self { config, db_pool }
+    }
+
+    pub fn config(&self) -> &AppealConfig {
+        &self.config
+    }
+
+    /// Appeal one of the user's own penalties
+    pub async fn file(&self, user_id: Uuid, request: FileAppealRequest) -> ReputationResult<Appeal> {
+        validate_text("Reason", &request.reason)?;
+        validate_evidence(&request.evidence)?;
+        if request.evidence.len() > self.config.max_evidence {
+            return Err(ReputationError::ValidationError(format!(
+                "An appeal can have at most {} pieces of evidence",
+                self.config.max_evidence
+            )));
+        }
+
+        let mut tx = self.db_pool.begin().await.map_err(db_error)?;
+        let penalty: ReputationHistory =
+            sqlx::query_as("SELECT * FROM reputation_history WHERE id = $1 AND user_id = $2")
+                .bind(request.history_id)
+                .bind(user_id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(db_error)?
+                .ok_or_else(|| {
+                    ReputationError::NotFound(format!("Reputation history entry {}", request.history_id))
+                })?;
+        if !appeals::is_appealable(&penalty.reason, penalty.score_change) {
+            return Err(ReputationError::ValidationError(
+                "Only penalties from slashed votes or decay can be appealed".to_string(),
+            ));
+        }
+        if penalty.created_at < Utc::now() - Duration::days(self.config.window_days) {
+            return Err(ReputationError::ValidationError(format!(
+                "Penalties can only be appealed within {} days",
+                self.config.window_days
+            )));
+        }
+
+        let appeal: Appeal = sqlx::query_as(
+            r#"
+            INSERT INTO reputation_appeals (id, user_id, history_id, reason)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (history_id) DO NOTHING
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(penalty.id)
+        .bind(request.reason.trim())
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| ReputationError::ValidationError("This penalty has already been appealed".to_string()))?;
+        insert_evidence(&mut tx, appeal.id, &request.evidence).await?;
+        record(
+            &mut tx,
+            appeal.id,
+            Some(user_id),
+            "filed",
+            json!({"score_change": penalty.score_change, "evidence": request.evidence.len()}),
+        )
+        .await?;
+        tx.commit().await.map_err(db_error)?;
+        Ok(appeal)
+    }
+
+    /// Add evidence to one of the user's undecided appeals
+    pub async fn add_evidence(
+        &self,
+        user_id: Uuid,
+        appeal_id: Uuid,
+        request: AddEvidenceRequest,
+    ) -> ReputationResult<Vec<AppealEvidence>> {
+        if request.evidence.is_empty() {
+            return Err(ReputationError::ValidationError("No evidence given".to_string()));
+        }
+        validate_evidence(&request.evidence)?;
+
+        let mut tx = self.db_pool.begin().await.map_err(db_error)?;
+        let appeal = lock_appeal(&mut tx, appeal_id).await?;
+        if appeal.user_id != user_id {
+            return Err(ReputationError::NotFound(format!("Appeal {}", appeal_id)));
+        }
+        ensure_pending(&appeal)?;
+        let existing: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM reputation_appeal_evidence WHERE appeal_id = $1")
+            .bind(appeal_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        if existing as usize + request.evidence.len() > self.config.max_evidence {
+            return Err(ReputationError::ValidationError(format!(
+                "An appeal can have at most {} pieces of evidence",
+                self.config.max_evidence
+            )));
+        }
+        insert_evidence(&mut tx, appeal_id, &request.evidence).await?;
+        sqlx::query("UPDATE reputation_appeals SET updated_at = NOW() WHERE id = $1")
+            .bind(appeal_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        record(&mut tx, appeal_id, Some(user_id), "evidence_added", json!({"count": request.evidence.len()})).await?;
+        tx.commit().await.map_err(db_error)?;
+
+        self.evidence(appeal_id).await
+    }
+
+    /// Withdraw one of the user's undecided appeals; the penalty stands
+    pub async fn withdraw(&self, user_id: Uuid, appeal_id: Uuid) -> ReputationResult<()> {
+        let mut tx = self.db_pool.begin().await.map_err(db_error)?;
+        let appeal = lock_appeal(&mut tx, appeal_id).await?;
+        if appeal.user_id != user_id {
+            return Err(ReputationError::NotFound(format!("Appeal {}", appeal_id)));
+        }
+        ensure_pending(&appeal)?;
+        sqlx::query(
+            "UPDATE reputation_appeals SET status = 'withdrawn', decided_at = NOW(), updated_at = NOW() WHERE id = $1",
+        )
+        .bind(appeal_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        record(&mut tx, appeal_id, Some(user_id), "withdrawn", json!({})).await?;
+        tx.commit().await.map_err(db_error)
+    }
+
+    /// The user's appeals, newest first
+    pub async fn for_user(&self, user_id: Uuid) -> ReputationResult<Vec<Appeal>> {
+        sqlx::query_as("SELECT * FROM reputation_appeals WHERE user_id = $1 ORDER BY created_at DESC")
+            .bind(user_id)
+            .fetch_all(&self.db_pool)
+            .await
+            .map_err(db_error)
+    }
+
+    /// Appeals awaiting the arbitrator's vote, soonest deadline first
+    pub async fn awaiting_vote(&self, arbitrator_id: Uuid) -> ReputationResult<Vec<Appeal>> {
+        sqlx::query_as(
+            r#"
+            SELECT a.* FROM reputation_appeals a
+            JOIN reputation_appeal_panelists p ON p.appeal_id = a.id
+            WHERE p.arbitrator_id = $1 AND p.voted_at IS NULL AND a.status = 'under_review'
+            ORDER BY a.voting_deadline
+            "#,
+        )
+        .bind(arbitrator_id)
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(db_error)
+    }
+
+    /// Appeals for admins to review, oldest first so none waits too long;
+    /// all undecided ones unless a status is given
+    pub async fn list(
+        &self,
+        status: Option<&str>,
+        page: Option<i64>,
+        limit: Option<i64>,
+    ) -> ReputationResult<Vec<Appeal>> {
+        let limit = limit.unwrap_or(DEFAULT_APPEAL_LIMIT).clamp(1, MAX_APPEAL_LIMIT);
+        let offset = (page.unwrap_or(1).max(1) - 1) * limit;
+        let statuses: Vec<&str> = match status {
+            Some(status) => vec![status],
+            None => PENDING_STATUSES.to_vec(),
+        };
+        sqlx::query_as(
+            r#"
+            SELECT * FROM reputation_appeals
+            WHERE status = ANY($1)
+            ORDER BY created_at
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(&statuses)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(db_error)
+    }
+
+    /// An appeal with its penalty, evidence, panel and audit trail
+    pub async fn case(&self, appeal_id: Uuid) -> ReputationResult<AppealCase> {
+        let appeal: Appeal = sqlx::query_as("SELECT * FROM reputation_appeals WHERE id = $1")
+            .bind(appeal_id)
+            .fetch_optional(&self.db_pool)
+            .await
+            .map_err(db_error)?
+            .ok_or_else(|| ReputationError::NotFound(format!("Appeal {}", appeal_id)))?;
+        let penalty: ReputationHistory = sqlx::query_as("SELECT * FROM reputation_history WHERE id = $1")
+            .bind(appeal.history_id)
+            .fetch_one(&self.db_pool)
+            .await
+            .map_err(db_error)?;
+        let evidence = self.evidence(appeal_id).await?;
+        let panel: Vec<Panelist> = sqlx::query_as(
+            r#"
+            SELECT arbitrator_id, uphold, notes, seated_at, voted_at
+            FROM reputation_appeal_panelists
+            WHERE appeal_id = $1
+            ORDER BY seated_at, arbitrator_id
+            "#,
+        )
+        .bind(appeal_id)
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+        let events: Vec<AppealEvent> = sqlx::query_as(
+            r#"
+            SELECT actor_id, action, details, created_at
+            FROM reputation_appeal_events
+            WHERE appeal_id = $1
+            ORDER BY created_at
+            "#,
+        )
+        .bind(appeal_id)
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(AppealCase {
+            appeal,
+            penalty,
+            evidence,
+            panel,
+            events,
+        })
+    }
+
+    async fn evidence(&self, appeal_id: Uuid) -> ReputationResult<Vec<AppealEvidence>> {
+        sqlx::query_as(
+            r#"
+            SELECT id, description, url, added_at
+            FROM reputation_appeal_evidence
+            WHERE appeal_id = $1
+            ORDER BY added_at
+            "#,
+        )
+        .bind(appeal_id)
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(db_error)
+    }
+
+    /// Hand an open appeal to an arbitration panel. An appeal goes before a
+    /// panel once; if the panel cannot decide it the admins do.
+    pub async fn escalate(&self, appeal_id: Uuid, admin_id: Option<Uuid>) -> ReputationResult<Appeal> {
+        let mut tx = self.db_pool.begin().await.map_err(db_error)?;
+        let appeal = lock_appeal(&mut tx, appeal_id).await?;
+        if appeal.status != "open" || appeal.voting_deadline.is_some() {
+            return Err(ReputationError::ValidationError(format!(
+                "Appeal {} cannot be escalated: it is {} and has{} been before a panel",
+                appeal_id,
+                appeal.status,
+                if appeal.voting_deadline.is_some() { "" } else { " not" }
+            )));
+        }
+
+        let seated = sqlx::query(
+            r#"
+            INSERT INTO reputation_appeal_panelists (appeal_id, arbitrator_id)
+            SELECT $1, user_id FROM user_reputation
+            WHERE current_score >= $2 AND user_id <> $3
+            ORDER BY random()
+            LIMIT $4
+            "#,
+        )
+        .bind(appeal_id)
+        .bind(self.config.min_arbitrator_score)
+        .bind(appeal.user_id)
+        .bind(self.config.panel_size)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?
+        .rows_affected();
+        if seated == 0 {
+            return Err(ReputationError::ValidationError(
+                "No users are eligible to arbitrate this appeal".to_string(),
+            ));
+        }
+        if seated < self.config.panel_size as u64 {
+            warn!(
+                "Appeal {} escalated to a panel of {} of the {} wanted",
+                appeal_id, seated, self.config.panel_size
+            );
+        }
+
+        let deadline = Utc::now() + Duration::hours(self.config.voting_hours);
+        let appeal: Appeal = sqlx::query_as(
+            r#"
+            UPDATE reputation_appeals
+            SET status = 'under_review', voting_deadline = $2, updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(appeal_id)
+        .bind(deadline)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        record(
+            &mut tx,
+            appeal_id,
+            admin_id,
+            "escalated",
+            json!({"panel_size": seated, "voting_deadline": deadline}),
+        )
+        .await?;
+        tx.commit().await.map_err(db_error)?;
+        Ok(appeal)
+    }
+
+    /// An arbitrator's vote; the vote that gives either side a majority of
+    /// the panel decides the appeal
+    pub async fn vote(
+        &self,
+        appeal_id: Uuid,
+        arbitrator_id: Uuid,
+        request: PanelVoteRequest,
+    ) -> ReputationResult<Appeal> {
+        if let Some(notes) = &request.notes {
+            validate_text("Notes", notes)?;
+        }
+
+        let mut tx = self.db_pool.begin().await.map_err(db_error)?;
+        let appeal = lock_appeal(&mut tx, appeal_id).await?;
+        let voted_at: Option<Option<DateTime<Utc>>> = sqlx::query_scalar(
+            "SELECT voted_at FROM reputation_appeal_panelists WHERE appeal_id = $1 AND arbitrator_id = $2",
+        )
+        .bind(appeal_id)
+        .bind(arbitrator_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        match voted_at {
+            None => return Err(ReputationError::NotFound(format!("Appeal {}", appeal_id))),
+            Some(Some(_)) => {
+                return Err(ReputationError::ValidationError("You have already voted on this appeal".to_string()))
+            }
+            Some(None) => {}
+        }
+        if appeal.status != "under_review" || appeal.voting_deadline.is_some_and(|deadline| deadline <= Utc::now()) {
+            return Err(ReputationError::ValidationError(format!(
+                "Voting on appeal {} is closed",
+                appeal_id
+            )));
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE reputation_appeal_panelists
+            SET uphold = $3, notes = $4, voted_at = NOW()
+            WHERE appeal_id = $1 AND arbitrator_id = $2
+            "#,
+        )
+        .bind(appeal_id)
+        .bind(arbitrator_id)
+        .bind(request.uphold)
+        .bind(&request.notes)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        record(&mut tx, appeal_id, Some(arbitrator_id), "vote_cast", json!({"uphold": request.uphold})).await?;
+
+        let (uphold, reject, decision) = tally(&mut tx, appeal_id, false).await?;
+        if let Some(decision) = decision {
+            let notes = format!("Decided by the arbitration panel, {} to {}", uphold.max(reject), uphold.min(reject));
+            settle(&mut tx, &appeal, decision, None, &notes).await?;
+        }
+        let appeal = lock_appeal(&mut tx, appeal_id).await?;
+        tx.commit().await.map_err(db_error)?;
+        Ok(appeal)
+    }
+
+    /// An admin's decision, which overrides any panel still voting
+    pub async fn decide(
+        &self,
+        appeal_id: Uuid,
+        admin_id: Option<Uuid>,
+        request: AppealDecisionRequest,
+    ) -> ReputationResult<Appeal> {
+        validate_text("Notes", &request.notes)?;
+
+        let mut tx = self.db_pool.begin().await.map_err(db_error)?;
+        let appeal = lock_appeal(&mut tx, appeal_id).await?;
+        ensure_pending(&appeal)?;
+        settle(
+            &mut tx,
+            &appeal,
+            AppealDecision::from_uphold(request.uphold),
+            admin_id,
+            request.notes.trim(),
+        )
+        .await?;
+        let appeal = lock_appeal(&mut tx, appeal_id).await?;
+        tx.commit().await.map_err(db_error)?;
+        Ok(appeal)
+    }
+
+    /// Close the panels whose voting deadline has passed: decided by the
+    /// votes cast, or handed back to the admins without enough of them.
+    /// Returns how many panels were closed.
+    pub async fn close_expired_panels(&self) -> ReputationResult<usize> {
+        let expired: Vec<Uuid> = sqlx::query_scalar(
+            "SELECT id FROM reputation_appeals WHERE status = 'under_review' AND voting_deadline <= NOW()",
+        )
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+
+        let mut closed = 0;
+        for appeal_id in expired {
+            let mut tx = self.db_pool.begin().await.map_err(db_error)?;
+            let appeal = lock_appeal(&mut tx, appeal_id).await?;
+            // Decided by a vote or an admin since it was read
+            if appeal.status != "under_review" {
+                continue;
+            }
+            let (uphold, reject, decision) = tally(&mut tx, appeal_id, true).await?;
+            match decision {
+                Some(decision) => {
+                    let notes = format!(
+                        "Decided by the arbitration panel at its deadline, {} to {}",
+                        uphold.max(reject),
+                        uphold.min(reject)
+                    );
+                    settle(&mut tx, &appeal, decision, None, &notes).await?;
+                }
+                None => {
+                    sqlx::query("UPDATE reputation_appeals SET status = 'open', updated_at = NOW() WHERE id = $1")
+                        .bind(appeal_id)
+                        .execute(&mut *tx)
+                        .await
+                        .map_err(db_error)?;
+                    record(&mut tx, appeal_id, None, "panel_expired", json!({"uphold": uphold, "reject": reject}))
+                        .await?;
+                    info!("Panel on appeal {} expired without enough votes; back with the admins", appeal_id);
+                }
+            }
+            tx.commit().await.map_err(db_error)?;
+            closed += 1;
+        }
+        Ok(closed)
+    }
+}
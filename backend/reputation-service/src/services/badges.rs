@@ -0,0 +1,286 @@
+// Badge awarding
+//
+// Users whose reputation changed since their badges were last evaluated are
+// checked against every badge's criteria. An earned badge is awarded once
+// (`user_badges` holds one row per user and badge) and announced with a
+// `BadgeAwarded` event for the notification-service and the user's
+// webhooks; progress towards the others is kept in `badge_progress`. A
+// user is marked evaluated as of the reputation change that was evaluated,
+// so a change landing meanwhile is picked up on the next pass.
+
+use chrono::{DateTime, Utc};
+use shared::messaging::{BadgeAwardedEvent, NexusEvent};
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::config::BadgeConfig;
+use crate::models::{Badge, BadgeCriteria, BadgeRarity, BadgeStanding, ReputationError, ReputationResult};
+use crate::scoring::badges::{self, BadgeStats};
+use crate::services::events::ReputationEventService;
+
+#[derive(sqlx::FromRow)]
+struct BadgeRow {
+    id: Uuid,
+    name: String,
+    description: String,
+    icon: String,
+    rarity: String,
+    min_score: Option<i32>,
+    min_accuracy: Option<f64>,
+    min_submissions: Option<i32>,
+    min_streak: Option<i32>,
+    specialization: Option<String>,
+    min_specialization_count: Option<i32>,
+}
+
+impl From<BadgeRow> for Badge {
+    fn from(row: BadgeRow) -> Self {
+        let rarity = match row.rarity.as_str() {
+            "uncommon" => BadgeRarity::Uncommon,
+            "rare" => BadgeRarity::Rare,
+            "epic" => BadgeRarity::Epic,
+            "legendary" => BadgeRarity::Legendary,
+            _ => BadgeRarity::Common,
+        };
+        Badge {
+            id: row.id,
+            name: row.name,
+            description: row.description,
+            icon: row.icon,
+            rarity,
+            criteria: BadgeCriteria {
+                min_score: row.min_score,
+                min_accuracy: row.min_accuracy,
+                min_submissions: row.min_submissions,
+                min_streak: row.min_streak,
+                specialization: row.specialization,
+                min_specialization_count: row.min_specialization_count,
+            },
+        }
+    }
+}
+
+/// A user whose reputation changed since their badges were evaluated
+#[derive(sqlx::FromRow)]
+struct PendingUser {
+    user_id: Uuid,
+    highest_score: i32,
+    accuracy_rate: f64,
+    total_submissions: i32,
+    best_streak: i32,
+    last_updated: DateTime<Utc>,
+}
+
+fn db_error(e: sqlx::Error) -> ReputationError {
+    ReputationError::DatabaseError(e.to_string())
+}
+
+fn rarity_name(rarity: &BadgeRarity) -> &'static str {
+    match rarity {
+        BadgeRarity::Common => "common",
+        BadgeRarity::Uncommon => "uncommon",
+        BadgeRarity::Rare => "rare",
+        BadgeRarity::Epic => "epic",
+        BadgeRarity::Legendary => "legendary",
+    }
+}
+
+/// Progress as stored and returned: a percentage to 2 decimal places
+fn percent(progress: f64) -> f64 {
+    (progress * 10_000.0).round() / 100.0
+}
+
+pub struct BadgeService {
+    config: BadgeConfig,
+    db_pool: PgPool,
+    events: Arc<ReputationEventService>,
+}
+
+impl BadgeService {
+    pub fn new(config: BadgeConfig, db_pool: PgPool, events: Arc<ReputationEventService>) -> Self {
+        Self { config, db_pool, events }
+    }
+
+    pub fn config(&self) -> &BadgeConfig {
+        &self.config
+    }
+
+    pub async fn badges(&self) -> ReputationResult<Vec<Badge>> {
+        let rows: Vec<BadgeRow> = sqlx::query_as(
+            r#"
+            SELECT id, name, description, icon, rarity, min_score, min_accuracy::FLOAT8 AS min_accuracy,
+                   min_submissions, min_streak, specialization, min_specialization_count
+            FROM badges
+            ORDER BY name
+            "#,
+        )
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+        Ok(rows.into_iter().map(Badge::from).collect())
+    }
+
+    /// Every badge with whether the user has earned it, or how close they are
+    pub async fn standings(&self, user_id: Uuid) -> ReputationResult<Vec<BadgeStanding>> {
+        let badges = self.badges().await?;
+        let earned: HashMap<Uuid, DateTime<Utc>> =
+            sqlx::query_as("SELECT badge_id, awarded_at FROM user_badges WHERE user_id = $1")
+                .bind(user_id)
+                .fetch_all(&self.db_pool)
+                .await
+                .map_err(db_error)?
+                .into_iter()
+                .collect();
+        let progress: HashMap<Uuid, f64> =
+            sqlx::query_as("SELECT badge_id, progress::FLOAT8 FROM badge_progress WHERE user_id = $1")
+                .bind(user_id)
+                .fetch_all(&self.db_pool)
+                .await
+                .map_err(db_error)?
+                .into_iter()
+                .collect();
+
+        Ok(badges
+            .into_iter()
+            .map(|badge| {
+                let awarded_at = earned.get(&badge.id).copied();
+                let progress = match awarded_at {
+                    Some(_) => 100.0,
+                    None => progress.get(&badge.id).copied().unwrap_or(0.0),
+                };
+                BadgeStanding {
+                    earned: awarded_at.is_some(),
+                    awarded_at,
+                    progress,
+                    badge,
+                }
+            })
+            .collect())
+    }
+
+    /// Evaluate up to a batch of users whose reputation changed since their
+    /// last evaluation. Returns how many badges were awarded.
+    pub async fn evaluate_pending(&self) -> ReputationResult<usize> {
+        let pending: Vec<PendingUser> = sqlx::query_as(
+            r#"
+            SELECT user_id, highest_score, accuracy_rate::FLOAT8 AS accuracy_rate, total_submissions,
+                   best_streak, last_updated
+            FROM user_reputation
+            WHERE badges_evaluated_at IS NULL OR last_updated > badges_evaluated_at
+            ORDER BY last_updated
+            LIMIT $1
+            "#,
+        )
+        .bind(self.config.batch_size)
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+        if pending.is_empty() {
+            return Ok(0);
+        }
+
+        let badges = self.badges().await?;
+        let mut awarded = 0;
+        for user in &pending {
+            for event in self.evaluate(user, &badges).await? {
+                awarded += 1;
+                info!("Awarded badge {} to {}", event.badge_name, event.user_id);
+                self.events.announce(NexusEvent::BadgeAwarded(event)).await;
+            }
+        }
+        Ok(awarded)
+    }
+
+    /// Award the badges a user has newly earned and record their progress
+    /// towards the rest
+    async fn evaluate(&self, user: &PendingUser, badges: &[Badge]) -> ReputationResult<Vec<BadgeAwardedEvent>> {
+        let specializations: HashMap<String, i32> =
+            sqlx::query_as("SELECT specialization, correct_count FROM user_specializations WHERE user_id = $1")
+                .bind(user.user_id)
+                .fetch_all(&self.db_pool)
+                .await
+                .map_err(db_error)?
+                .into_iter()
+                .collect();
+        let stats = BadgeStats {
+            highest_score: user.highest_score,
+            accuracy_rate: user.accuracy_rate,
+            total_submissions: user.total_submissions,
+            best_streak: user.best_streak,
+            specializations,
+        };
+
+        let mut tx = self.db_pool.begin().await.map_err(db_error)?;
+        let earned: HashSet<Uuid> = sqlx::query_scalar("SELECT badge_id FROM user_badges WHERE user_id = $1")
+            .bind(user.user_id)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(db_error)?
+            .into_iter()
+            .collect();
+
+        let mut awarded = Vec::new();
+        for badge in badges.iter().filter(|badge| !earned.contains(&badge.id)) {
+            let progress = badges::progress(&badge.criteria, &stats);
+            if progress >= 1.0 {
+                let inserted = sqlx::query(
+                    r#"
+                    INSERT INTO user_badges (user_id, badge_id, progress)
+                    VALUES ($1, $2, 100)
+                    ON CONFLICT (user_id, badge_id) DO NOTHING
+                    "#,
+                )
+                .bind(user.user_id)
+                .bind(badge.id)
+                .execute(&mut *tx)
+                .await
+                .map_err(db_error)?;
+                sqlx::query("DELETE FROM badge_progress WHERE user_id = $1 AND badge_id = $2")
+                    .bind(user.user_id)
+                    .bind(badge.id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(db_error)?;
+                if inserted.rows_affected() > 0 {
+                    awarded.push(BadgeAwardedEvent {
+                        user_id: user.user_id,
+                        badge_id: badge.id,
+                        badge_name: badge.name.clone(),
+                        description: badge.description.clone(),
+                        icon: badge.icon.clone(),
+                        rarity: rarity_name(&badge.rarity).to_string(),
+                        awarded_at: Utc::now(),
+                    });
+                }
+            } else {
+                sqlx::query(
+                    r#"
+                    INSERT INTO badge_progress (user_id, badge_id, progress)
+                    VALUES ($1, $2, $3::NUMERIC)
+                    ON CONFLICT (user_id, badge_id) DO UPDATE
+                    SET progress = EXCLUDED.progress, updated_at = NOW()
+                    WHERE badge_progress.progress IS DISTINCT FROM EXCLUDED.progress
+                    "#,
+                )
+                .bind(user.user_id)
+                .bind(badge.id)
+                .bind(percent(progress))
+                .execute(&mut *tx)
+                .await
+                .map_err(db_error)?;
+            }
+        }
+
+        sqlx::query("UPDATE user_reputation SET badges_evaluated_at = $2 WHERE user_id = $1")
+            .bind(user.user_id)
+            .bind(user.last_updated)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        tx.commit().await.map_err(db_error)?;
+        Ok(awarded)
+    }
+}
@@ -0,0 +1,301 @@
+// Engine accuracy on calibration bounties
+//
+// The platform injects bounties on artifacts whose verdict it already knows,
+// indistinguishable from any other, and settlement records each engine's
+// result on them (`reputation_calibrations`). Unlike consensus, which only
+// says whether an engine agreed with the others, these measure whether it
+// was right.
+//
+// The monitor checks each new result once: the engine's most recent
+// `recent_window` results are compared with all of its earlier ones, and a
+// significant fall opens a degradation for admins to look into. It stays
+// open until a later check finds the engine recovered.
+//
+// None of this is served outside the admin API, where it would tell engines
+// which of their bounties were calibration bounties.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::config::CalibrationConfig;
+use crate::models::{ReputationError, ReputationResult};
+use crate::scoring::calibration::{self, CalibrationAccuracy, DegradationPolicy};
+
+const DEFAULT_ENGINE_LIMIT: i64 = 50;
+const MAX_ENGINE_LIMIT: i64 = 200;
+
+/// Results listed with an engine's calibration report
+const REPORT_RESULTS: i64 = 50;
+
+fn db_error(e: sqlx::Error) -> ReputationError {
+    ReputationError::DatabaseError(e.to_string())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CalibrationQuery {
+    /// Only engines with an open degradation
+    pub degraded: Option<bool>,
+    pub page: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+/// An engine's result on one calibration bounty
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct CalibrationResult {
+    pub bounty_id: Uuid,
+    pub submission_id: Uuid,
+    pub verdict: String,
+    pub expected_verdict: String,
+    pub correct: bool,
+    pub settled_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct Degradation {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub baseline_correct: i32,
+    pub baseline_samples: i32,
+    pub recent_correct: i32,
+    pub recent_samples: i32,
+    pub detected_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+/// One engine's calibration accuracy, as listed for admins
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct EngineCalibrationSummary {
+    pub user_id: Uuid,
+    pub samples: i64,
+    pub correct: i64,
+    /// Share right, 0.0 to 1.0
+    pub accuracy: f64,
+    pub last_settled_at: DateTime<Utc>,
+    pub probation: bool,
+    /// When its open degradation was detected, if it has one
+    pub degraded_since: Option<DateTime<Utc>>,
+}
+
+/// One engine's calibration accuracy in full
+#[derive(Debug, Serialize)]
+pub struct EngineCalibration {
+    pub user_id: Uuid,
+    pub probation: bool,
+    /// Calibration bounties settled towards the end of probation
+    pub probation_calibrations: i32,
+    pub lifetime: CalibrationAccuracy,
+    pub recent: CalibrationAccuracy,
+    pub baseline: CalibrationAccuracy,
+    pub degradations: Vec<Degradation>,
+    /// Latest results, newest first
+    pub results: Vec<CalibrationResult>,
+}
+
+pub struct CalibrationService {
+    config: CalibrationConfig,
+    db_pool: PgPool,
+}
+
+impl CalibrationService {
+    pub fn new(config: CalibrationConfig, db_pool: PgPool) -> Self {
+        Self { config, db_pool }
+    }
+
+    pub fn config(&self) -> &CalibrationConfig {
+        &self.config
+    }
+
+    fn policy(&self) -> DegradationPolicy {
+        DegradationPolicy {
+            recent_window: self.config.recent_window,
+            min_baseline_samples: self.config.min_baseline_samples,
+            min_accuracy_drop: self.config.min_accuracy_drop,
+        }
+    }
+
+    /// Every result of the user's, newest first
+    async fn results(&self, user_id: Uuid) -> ReputationResult<Vec<bool>> {
+        sqlx::query_scalar(
+            "SELECT correct FROM reputation_calibrations WHERE user_id = $1 ORDER BY settled_at DESC, bounty_id",
+        )
+        .bind(user_id)
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(db_error)
+    }
+
+    /// Engines with calibration results, least accurate first
+    pub async fn engines(&self, query: &CalibrationQuery) -> ReputationResult<Vec<EngineCalibrationSummary>> {
+        let limit = query.limit.unwrap_or(DEFAULT_ENGINE_LIMIT).clamp(1, MAX_ENGINE_LIMIT);
+        let offset = (query.page.unwrap_or(1).max(1) - 1) * limit;
+        sqlx::query_as(
+            r#"
+            SELECT c.user_id, COUNT(*) AS samples, COUNT(*) FILTER (WHERE c.correct) AS correct,
+                   (COUNT(*) FILTER (WHERE c.correct))::FLOAT8 / COUNT(*) AS accuracy,
+                   MAX(c.settled_at) AS last_settled_at,
+                   BOOL_OR(r.probation_ended_at IS NULL) AS probation,
+                   MAX(d.detected_at) AS degraded_since
+            FROM reputation_calibrations c
+            JOIN user_reputation r ON r.user_id = c.user_id
+            LEFT JOIN calibration_degradations d ON d.user_id = c.user_id AND d.resolved_at IS NULL
+            GROUP BY c.user_id
+            HAVING NOT $1 OR MAX(d.detected_at) IS NOT NULL
+            ORDER BY accuracy, samples DESC, c.user_id
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(query.degraded.unwrap_or(false))
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(db_error)
+    }
+
+    /// The user's calibration accuracy, its degradations and latest results
+    pub async fn engine(&self, user_id: Uuid) -> ReputationResult<EngineCalibration> {
+        let standing: Option<(bool, i32)> = sqlx::query_as(
+            "SELECT probation_ended_at IS NULL, calibrations_completed FROM user_reputation WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+        let (probation, probation_calibrations) =
+            standing.ok_or_else(|| ReputationError::NotFound(format!("Reputation for user {}", user_id)))?;
+
+        let all = self.results(user_id).await?;
+        let (recent, baseline) = calibration::split(&all, self.config.recent_window);
+        let degradations = sqlx::query_as(
+            "SELECT * FROM calibration_degradations WHERE user_id = $1 ORDER BY detected_at DESC",
+        )
+        .bind(user_id)
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+        let results = sqlx::query_as(
+            r#"
+            SELECT bounty_id, submission_id, verdict, expected_verdict, correct, settled_at
+            FROM reputation_calibrations
+            WHERE user_id = $1
+            ORDER BY settled_at DESC, bounty_id
+            LIMIT $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(REPORT_RESULTS)
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(EngineCalibration {
+            user_id,
+            probation,
+            probation_calibrations,
+            lifetime: CalibrationAccuracy::of(&all),
+            recent,
+            baseline,
+            degradations,
+            results,
+        })
+    }
+
+    /// Check a batch of new results for degradation of their engines,
+    /// opening and resolving degradations as needed. Returns how many
+    /// results were checked.
+    pub async fn check(&self) -> ReputationResult<usize> {
+        let unchecked: Vec<(Uuid, Uuid)> = sqlx::query_as(
+            r#"
+            SELECT user_id, bounty_id FROM reputation_calibrations
+            WHERE checked_at IS NULL
+            ORDER BY settled_at
+            LIMIT $1
+            "#,
+        )
+        .bind(self.config.batch_size)
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+        if unchecked.is_empty() {
+            return Ok(0);
+        }
+
+        let mut users: Vec<Uuid> = unchecked.iter().map(|(user_id, _)| *user_id).collect();
+        users.sort();
+        users.dedup();
+        let policy = self.policy();
+        for user_id in users {
+            let results = self.results(user_id).await?;
+            let (recent, baseline) = calibration::split(&results, policy.recent_window);
+            if calibration::degraded(&policy, recent, baseline) {
+                self.open_degradation(user_id, recent, baseline).await?;
+            } else {
+                self.resolve_degradation(user_id).await?;
+            }
+        }
+
+        let (user_ids, bounty_ids): (Vec<Uuid>, Vec<Uuid>) = unchecked.iter().copied().unzip();
+        sqlx::query(
+            r#"
+            UPDATE reputation_calibrations c
+            SET checked_at = NOW()
+            FROM UNNEST($1::UUID[], $2::UUID[]) AS checked(user_id, bounty_id)
+            WHERE c.user_id = checked.user_id AND c.bounty_id = checked.bounty_id
+            "#,
+        )
+        .bind(&user_ids)
+        .bind(&bounty_ids)
+        .execute(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+        Ok(unchecked.len())
+    }
+
+    async fn open_degradation(
+        &self,
+        user_id: Uuid,
+        recent: CalibrationAccuracy,
+        baseline: CalibrationAccuracy,
+    ) -> ReputationResult<()> {
+        let opened = sqlx::query(
+            r#"
+            INSERT INTO calibration_degradations
+                (user_id, baseline_correct, baseline_samples, recent_correct, recent_samples)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (user_id) WHERE resolved_at IS NULL DO NOTHING
+            "#,
+        )
+        .bind(user_id)
+        .bind(baseline.correct)
+        .bind(baseline.samples)
+        .bind(recent.correct)
+        .bind(recent.samples)
+        .execute(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+        if opened.rows_affected() > 0 {
+            warn!(
+                "Engine {} degraded on calibration bounties: {}/{} right recently, {}/{} before",
+                user_id, recent.correct, recent.samples, baseline.correct, baseline.samples
+            );
+        }
+        Ok(())
+    }
+
+    async fn resolve_degradation(&self, user_id: Uuid) -> ReputationResult<()> {
+        let resolved = sqlx::query(
+            "UPDATE calibration_degradations SET resolved_at = NOW() WHERE user_id = $1 AND resolved_at IS NULL",
+        )
+        .bind(user_id)
+        .execute(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+        if resolved.rows_affected() > 0 {
+            info!("Engine {} recovered its accuracy on calibration bounties", user_id);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,437 @@
+// Reputation decay
+//
+// Scores of users who stop submitting decay under the policy in
+// `decay_tiers`/`decay_settings`, which admins tune at runtime; each pass
+// reloads it. Owners of verified automated engines that keep their uptime
+// SLA are exempt: an engine that is always available is not inactive just
+// because no bounty matched it. Each decay step is recorded in
+// `reputation_history`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::PgPool;
+use std::collections::HashSet;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::config::ReputationConfig;
+use crate::models::{ReputationError, ReputationResult};
+use crate::scoring::decay::{DecayInput, DecayPolicy, DecayTier};
+use crate::scoring::ReputationScorer;
+
+/// Users decayed per query
+const DECAY_BATCH_SIZE: i64 = 500;
+
+/// Longest tier name accepted
+const MAX_TIER_NAME_LENGTH: usize = 50;
+
+/// Tier used while no tiers are configured, from `DECAY_RATE_PER_DAY`
+const DEFAULT_TIER: &str = "default";
+
+#[derive(Debug, Deserialize)]
+pub struct DecayTierRequest {
+    pub min_score: i32,
+    pub rate_per_day: f64,
+    pub grace_period_days: i32,
+    pub accuracy_floor_ratio: f64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DecaySettingsRequest {
+    pub enabled: Option<bool>,
+    pub engine_min_uptime: Option<f64>,
+    pub uptime_window_days: Option<i32>,
+}
+
+/// An automated engine whose owner is exempt from decay while it keeps the
+/// uptime SLA
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct EngineExemption {
+    pub engine_id: Uuid,
+    pub verified_by: Option<Uuid>,
+    pub verified_at: DateTime<Utc>,
+}
+
+/// Result of one decay pass
+#[derive(Debug, Default, Serialize)]
+pub struct DecayRun {
+    pub evaluated: usize,
+    pub decayed: usize,
+    pub exempt: usize,
+}
+
+#[derive(sqlx::FromRow)]
+struct SettingsRow {
+    enabled: bool,
+    engine_min_uptime: f64,
+    uptime_window_days: i32,
+}
+
+#[derive(sqlx::FromRow)]
+struct InactiveUser {
+    user_id: Uuid,
+    current_score: i32,
+    highest_score: i32,
+    accuracy_rate: f64,
+    last_active_at: DateTime<Utc>,
+    last_decayed_at: Option<DateTime<Utc>>,
+}
+
+fn db_error(e: sqlx::Error) -> ReputationError {
+    ReputationError::DatabaseError(e.to_string())
+}
+
+pub struct DecayService {
+    db_pool: PgPool,
+    scorer: ReputationScorer,
+    default_rate_per_day: f64,
+}
+
+impl DecayService {
+    pub fn new(config: ReputationConfig, db_pool: PgPool) -> Self {
+        Self {
+            db_pool,
+            default_rate_per_day: config.decay_rate_per_day,
+            scorer: ReputationScorer::new(config),
+        }
+    }
+
+    /// The policy in force. With no tiers configured every score decays at
+    /// `DECAY_RATE_PER_DAY` from the user's last submission.
+    pub async fn policy(&self) -> ReputationResult<DecayPolicy> {
+        let settings: SettingsRow =
+            sqlx::query_as("SELECT enabled, engine_min_uptime, uptime_window_days FROM decay_settings WHERE id")
+                .fetch_optional(&self.db_pool)
+                .await
+                .map_err(db_error)?
+                .unwrap_or(SettingsRow {
+                    enabled: true,
+                    engine_min_uptime: 0.99,
+                    uptime_window_days: 30,
+                });
+        let mut tiers: Vec<DecayTier> = sqlx::query_as(
+            r#"
+            SELECT name, min_score, rate_per_day, grace_period_days, accuracy_floor_ratio
+            FROM decay_tiers
+            ORDER BY min_score
+            "#,
+        )
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+        if tiers.is_empty() {
+            tiers.push(DecayTier {
+                name: DEFAULT_TIER.to_string(),
+                min_score: i32::MIN,
+                rate_per_day: self.default_rate_per_day,
+                grace_period_days: 0,
+                accuracy_floor_ratio: 0.0,
+            });
+        }
+
+        Ok(DecayPolicy {
+            enabled: settings.enabled,
+            tiers,
+            engine_min_uptime: settings.engine_min_uptime,
+            uptime_window_days: settings.uptime_window_days,
+        })
+    }
+
+    pub async fn upsert_tier(&self, name: &str, request: &DecayTierRequest) -> ReputationResult<DecayTier> {
+        let name = name.trim();
+        if name.is_empty() || name.len() > MAX_TIER_NAME_LENGTH || name == DEFAULT_TIER {
+            return Err(ReputationError::ValidationError(format!(
+                "Tier name must be 1 to {} characters and not '{}'",
+                MAX_TIER_NAME_LENGTH, DEFAULT_TIER
+            )));
+        }
+        if !(0.0..=1.0).contains(&request.rate_per_day) {
+            return Err(ReputationError::ValidationError("rate_per_day must be between 0 and 1".to_string()));
+        }
+        if !(0.0..=1.0).contains(&request.accuracy_floor_ratio) {
+            return Err(ReputationError::ValidationError(
+                "accuracy_floor_ratio must be between 0 and 1".to_string(),
+            ));
+        }
+        if request.grace_period_days < 0 {
+            return Err(ReputationError::ValidationError("grace_period_days cannot be negative".to_string()));
+        }
+
+        let tier = sqlx::query_as(
+            r#"
+            INSERT INTO decay_tiers (name, min_score, rate_per_day, grace_period_days, accuracy_floor_ratio)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (name) DO UPDATE
+            SET min_score = EXCLUDED.min_score,
+                rate_per_day = EXCLUDED.rate_per_day,
+                grace_period_days = EXCLUDED.grace_period_days,
+                accuracy_floor_ratio = EXCLUDED.accuracy_floor_ratio,
+                updated_at = NOW()
+            RETURNING name, min_score, rate_per_day, grace_period_days, accuracy_floor_ratio
+            "#,
+        )
+        .bind(name)
+        .bind(request.min_score)
+        .bind(request.rate_per_day)
+        .bind(request.grace_period_days)
+        .bind(request.accuracy_floor_ratio)
+        .fetch_one(&self.db_pool)
+        .await
+        .map_err(|e| match &e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => ReputationError::ValidationError(format!(
+                "Another tier already starts at score {}",
+                request.min_score
+            )),
+            _ => db_error(e),
+        })?;
+        info!("Decay tier {} set: {:?}", name, request);
+        Ok(tier)
+    }
+
+    pub async fn delete_tier(&self, name: &str) -> ReputationResult<()> {
+        let deleted = sqlx::query("DELETE FROM decay_tiers WHERE name = $1")
+            .bind(name)
+            .execute(&self.db_pool)
+            .await
+            .map_err(db_error)?;
+        if deleted.rows_affected() == 0 {
+            return Err(ReputationError::NotFound(format!("Decay tier {}", name)));
+        }
+        info!("Decay tier {} removed", name);
+        Ok(())
+    }
+
+    pub async fn update_settings(&self, request: &DecaySettingsRequest) -> ReputationResult<DecayPolicy> {
+        if request.engine_min_uptime.is_some_and(|uptime| !(0.0..=1.0).contains(&uptime)) {
+            return Err(ReputationError::ValidationError(
+                "engine_min_uptime must be between 0 and 1".to_string(),
+            ));
+        }
+        if request.uptime_window_days.is_some_and(|days| !(1..=365).contains(&days)) {
+            return Err(ReputationError::ValidationError(
+                "uptime_window_days must be between 1 and 365".to_string(),
+            ));
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO decay_settings (id, enabled, engine_min_uptime, uptime_window_days)
+            VALUES (TRUE, COALESCE($1, TRUE), COALESCE($2, 0.99), COALESCE($3, 30))
+            ON CONFLICT (id) DO UPDATE
+            SET enabled = COALESCE($1, decay_settings.enabled),
+                engine_min_uptime = COALESCE($2, decay_settings.engine_min_uptime),
+                uptime_window_days = COALESCE($3, decay_settings.uptime_window_days),
+                updated_at = NOW()
+            "#,
+        )
+        .bind(request.enabled)
+        .bind(request.engine_min_uptime)
+        .bind(request.uptime_window_days)
+        .execute(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+        info!("Decay settings updated: {:?}", request);
+        self.policy().await
+    }
+
+    pub async fn exemptions(&self) -> ReputationResult<Vec<EngineExemption>> {
+        sqlx::query_as("SELECT engine_id, verified_by, verified_at FROM decay_engine_exemptions ORDER BY verified_at")
+            .fetch_all(&self.db_pool)
+            .await
+            .map_err(db_error)
+    }
+
+    /// Verify an automated engine for the decay exemption
+    pub async fn exempt_engine(
+        &self,
+        engine_id: Uuid,
+        verified_by: Option<Uuid>,
+    ) -> ReputationResult<EngineExemption> {
+        let engine_type: Option<String> = sqlx::query_scalar("SELECT engine_type FROM engines WHERE id = $1")
+            .bind(engine_id)
+            .fetch_optional(&self.db_pool)
+            .await
+            .map_err(db_error)?;
+        match engine_type.as_deref() {
+            None => return Err(ReputationError::NotFound(format!("Engine {}", engine_id))),
+            Some("automated") => {}
+            Some(other) => {
+                return Err(ReputationError::ValidationError(format!(
+                    "Only automated engines can be exempt from decay; engine {} is {}",
+                    engine_id, other
+                )))
+            }
+        }
+
+        let exemption = sqlx::query_as(
+            r#"
+            INSERT INTO decay_engine_exemptions (engine_id, verified_by)
+            VALUES ($1, $2)
+            ON CONFLICT (engine_id) DO UPDATE SET verified_by = EXCLUDED.verified_by, verified_at = NOW()
+            RETURNING engine_id, verified_by, verified_at
+            "#,
+        )
+        .bind(engine_id)
+        .bind(verified_by)
+        .fetch_one(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+        info!("Engine {} verified for the decay exemption", engine_id);
+        Ok(exemption)
+    }
+
+    pub async fn remove_exemption(&self, engine_id: Uuid) -> ReputationResult<()> {
+        let deleted = sqlx::query("DELETE FROM decay_engine_exemptions WHERE engine_id = $1")
+            .bind(engine_id)
+            .execute(&self.db_pool)
+            .await
+            .map_err(db_error)?;
+        if deleted.rows_affected() == 0 {
+            return Err(ReputationError::NotFound(format!("Decay exemption for engine {}", engine_id)));
+        }
+        Ok(())
+    }
+
+    /// Owners of verified automated engines that delivered at least the
+    /// policy's share of their assigned analyses over its window
+    async fn exempt_users(&self, policy: &DecayPolicy) -> ReputationResult<HashSet<Uuid>> {
+        let owners: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT e.owner_id
+            FROM decay_engine_exemptions x
+            JOIN engines e ON e.id = x.engine_id
+            LEFT JOIN analysis_results ar
+                ON ar.engine_id = e.id AND ar.created_at >= NOW() - make_interval(days => $2)
+            WHERE e.engine_type = 'automated' AND e.is_active AND e.owner_id IS NOT NULL
+            GROUP BY e.id, e.owner_id
+            HAVING COUNT(ar.id) FILTER (WHERE ar.analysis_status = 'completed')::FLOAT8
+                   / NULLIF(COUNT(ar.id) FILTER (WHERE ar.analysis_status IN ('completed', 'failed', 'timeout')), 0)
+                   >= $1
+            "#,
+        )
+        .bind(policy.engine_min_uptime)
+        .bind(policy.uptime_window_days)
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+        Ok(owners.into_iter().collect())
+    }
+
+    /// Decay every user inactive for longer than the shortest grace period
+    pub async fn run(&self) -> ReputationResult<DecayRun> {
+        let policy = self.policy().await?;
+        let mut run = DecayRun::default();
+        if !policy.enabled {
+            return Ok(run);
+        }
+        let exempt = self.exempt_users(&policy).await?;
+
+        let mut after = Uuid::nil();
+        loop {
+            let users: Vec<InactiveUser> = sqlx::query_as(
+                r#"
+                SELECT user_id, current_score, highest_score, accuracy_rate::FLOAT8 AS accuracy_rate,
+                       last_active_at, last_decayed_at
+                FROM user_reputation
+                WHERE last_active_at < NOW() - make_interval(days => $1) AND user_id > $2
+                ORDER BY user_id
+                LIMIT $3
+                "#,
+            )
+            .bind(policy.min_grace_period_days())
+            .bind(after)
+            .bind(DECAY_BATCH_SIZE)
+            .fetch_all(&self.db_pool)
+            .await
+            .map_err(db_error)?;
+            let Some(last) = users.last() else {
+                break;
+            };
+            after = last.user_id;
+
+            let now = Utc::now();
+            for user in &users {
+                run.evaluated += 1;
+                if exempt.contains(&user.user_id) {
+                    // Exempt time is not decayed later if the exemption lapses
+                    run.exempt += 1;
+                    sqlx::query("UPDATE user_reputation SET last_decayed_at = $2 WHERE user_id = $1")
+                        .bind(user.user_id)
+                        .bind(now)
+                        .execute(&self.db_pool)
+                        .await
+                        .map_err(db_error)?;
+                    continue;
+                }
+                if self.decay_user(&policy, user, now).await? {
+                    run.decayed += 1;
+                }
+            }
+        }
+
+        info!(
+            "Decay pass: {} inactive user(s), {} decayed, {} exempt",
+            run.evaluated, run.decayed, run.exempt
+        );
+        Ok(run)
+    }
+
+    async fn decay_user(
+        &self,
+        policy: &DecayPolicy,
+        user: &InactiveUser,
+        now: DateTime<Utc>,
+    ) -> ReputationResult<bool> {
+        let input = DecayInput {
+            score: user.current_score,
+            highest_score: user.highest_score,
+            accuracy_rate: user.accuracy_rate,
+            last_active_at: user.last_active_at,
+            last_decayed_at: user.last_decayed_at,
+        };
+        let Some(outcome) = self.scorer.apply_decay(policy, &input, now) else {
+            return Ok(false);
+        };
+
+        let mut tx = self.db_pool.begin().await.map_err(db_error)?;
+        // Leave users alone whose score changed since they were read
+        let updated = sqlx::query(
+            r#"
+            UPDATE user_reputation
+            SET current_score = $2,
+                lowest_score = LEAST(lowest_score, $2),
+                last_decayed_at = $4,
+                last_updated = NOW()
+            WHERE user_id = $1 AND current_score = $3
+            "#,
+        )
+        .bind(user.user_id)
+        .bind(outcome.new_score)
+        .bind(user.current_score)
+        .bind(now)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        if updated.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO reputation_history (user_id, score_before, score_after, score_change, reason, details)
+            VALUES ($1, $2, $3, $4, 'decay', $5::JSONB)
+            "#,
+        )
+        .bind(user.user_id)
+        .bind(user.current_score)
+        .bind(outcome.new_score)
+        .bind(outcome.new_score - user.current_score)
+        .bind(json!(outcome).to_string())
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        tx.commit().await.map_err(db_error)?;
+        Ok(true)
+    }
+}
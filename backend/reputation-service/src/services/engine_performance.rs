@@ -0,0 +1,69 @@
+// Engine performance over a period
+//
+// Aggregated straight from `analysis_results`: an analysis is correct when
+// its verdict matches the consensus reached for the same bounty and
+// submission. Used for operator performance certificates, so every engine
+// the operator owns is listed, including ones with no activity.
+
+use chrono::Duration;
+use sqlx::PgPool;
+
+use crate::models::{EnginePerformance, EnginePerformanceQuery, ReputationError, ReputationResult};
+
+/// Longest period a single query may cover
+const MAX_PERIOD_DAYS: i64 = 366;
+
+pub async fn for_owner(
+    db: &PgPool,
+    query: &EnginePerformanceQuery,
+) -> ReputationResult<Vec<EnginePerformance>> {
+    if query.from >= query.to {
+        return Err(ReputationError::ValidationError("`from` must be before `to`".to_string()));
+    }
+    if query.to - query.from > Duration::days(MAX_PERIOD_DAYS) {
+        return Err(ReputationError::ValidationError(format!(
+            "Period cannot exceed {} days",
+            MAX_PERIOD_DAYS
+        )));
+    }
+
+    sqlx::query_as::<_, EnginePerformance>(
+        r#"
+        WITH counts AS (
+            SELECT
+                e.id AS engine_id,
+                e.name AS engine_name,
+                COUNT(ar.id) AS total_analyses,
+                COUNT(ar.id) FILTER (WHERE ar.analysis_status = 'completed') AS completed_analyses,
+                COUNT(ar.id) FILTER (WHERE ar.analysis_status IN ('failed', 'timeout')) AS failed_analyses,
+                COUNT(cr.id) FILTER (WHERE ar.analysis_status = 'completed') AS resolved_analyses,
+                COUNT(cr.id) FILTER (
+                    WHERE ar.analysis_status = 'completed' AND ar.verdict = cr.final_verdict
+                ) AS correct_analyses,
+                COALESCE(AVG(ar.confidence_score) FILTER (WHERE ar.analysis_status = 'completed'), 0)::FLOAT8
+                    AS avg_confidence
+            FROM engines e
+            LEFT JOIN analysis_results ar
+                ON ar.engine_id = e.id AND ar.created_at >= $2 AND ar.created_at < $3
+            LEFT JOIN bounty_participations bp ON bp.id = ar.participation_id
+            LEFT JOIN consensus_results cr
+                ON cr.bounty_id = bp.bounty_id AND cr.submission_id = ar.submission_id
+            WHERE e.owner_id = $1
+            GROUP BY e.id, e.name
+        )
+        SELECT
+            *,
+            COALESCE(correct_analyses::FLOAT8 / NULLIF(resolved_analyses, 0), 0) AS accuracy_rate,
+            COALESCE(completed_analyses::FLOAT8 / NULLIF(completed_analyses + failed_analyses, 0), 0)
+                AS uptime_rate
+        FROM counts
+        ORDER BY engine_name
+        "#,
+    )
+    .bind(query.owner_id)
+    .bind(query.from)
+    .bind(query.to)
+    .fetch_all(db)
+    .await
+    .map_err(|e| ReputationError::DatabaseError(e.to_string()))
+}
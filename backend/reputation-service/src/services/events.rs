@@ -0,0 +1,219 @@
+// Reputation change events
+//
+// Announces changes in a user's standing on the message queue and to the
+// user's webhooks: score changes, leaderboard rank moves, badges and streak
+// milestones. Score changes are read from `reputation_history`, where every
+// change is recorded whatever made it, and marked announced; a settlement's
+// history row also carries the streak it left the user on. Ranks are
+// recomputed each pass and compared with `user_reputation.rank`, the last
+// rank announced. Webhook deliveries are queued in the transaction that
+// marks the change announced, so each is queued exactly once; the queue
+// message follows the commit.
+
+use chrono::{DateTime, Utc};
+use shared::messaging::{
+    EventPublisher, NexusEvent, ReputationRankChangedEvent, ReputationUpdatedEvent, StreakMilestoneReachedEvent,
+};
+use sqlx::{PgExecutor, PgPool};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::config::EventConfig;
+use crate::models::{ReputationError, ReputationResult};
+use crate::scoring::streaks;
+use crate::services::webhooks::{ReputationWebhookEvent, WebhookService};
+
+#[derive(sqlx::FromRow)]
+struct ScoreChange {
+    id: Uuid,
+    user_id: Uuid,
+    score_before: i32,
+    score_after: i32,
+    reason: String,
+    submission_id: Option<Uuid>,
+    details: Option<serde_json::Value>,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(sqlx::FromRow)]
+struct RankChange {
+    user_id: Uuid,
+    old_rank: Option<i32>,
+    new_rank: i32,
+    score: i32,
+}
+
+fn db_error(e: sqlx::Error) -> ReputationError {
+    ReputationError::DatabaseError(e.to_string())
+}
+
+/// The webhook event an event is delivered as, and whose standing it is
+fn webhook_event(event: &NexusEvent) -> Option<(ReputationWebhookEvent, Uuid, serde_json::Value)> {
+    let (kind, user_id, data) = match event {
+        NexusEvent::ReputationUpdated(e) => (ReputationWebhookEvent::ScoreChanged, e.user_id, serde_json::to_value(e)),
+        NexusEvent::ReputationRankChanged(e) => (ReputationWebhookEvent::RankChanged, e.user_id, serde_json::to_value(e)),
+        NexusEvent::BadgeAwarded(e) => (ReputationWebhookEvent::BadgeAwarded, e.user_id, serde_json::to_value(e)),
+        NexusEvent::StreakMilestoneReached(e) => {
+            (ReputationWebhookEvent::StreakMilestone, e.user_id, serde_json::to_value(e))
+        }
+        _ => return None,
+    };
+    Some((kind, user_id, data.ok()?))
+}
+
+pub struct ReputationEventService {
+    config: EventConfig,
+    db_pool: PgPool,
+    publisher: EventPublisher,
+}
+
+impl ReputationEventService {
+    pub fn new(config: EventConfig, db_pool: PgPool, publisher: EventPublisher) -> Self {
+        Self {
+            config,
+            db_pool,
+            publisher,
+        }
+    }
+
+    pub fn config(&self) -> &EventConfig {
+        &self.config
+    }
+
+    /// Announce an event that has already been recorded, e.g. a badge
+    /// awarded
+    pub async fn announce(&self, event: NexusEvent) {
+        if let Err(e) = Self::queue_webhooks(&self.db_pool, &event).await {
+            warn!("Failed to queue webhooks for {}: {}", event.get_title(), e);
+        }
+        self.publish(&[event]).await;
+    }
+
+    /// Announce score changes not yet announced, oldest first, with the
+    /// streak milestones among them. Returns how many changes were read.
+    pub async fn announce_score_changes(&self) -> ReputationResult<usize> {
+        let mut tx = self.db_pool.begin().await.map_err(db_error)?;
+        let changes: Vec<ScoreChange> = sqlx::query_as(
+            r#"
+            SELECT id, user_id, score_before, score_after, reason, submission_id, details, created_at
+            FROM reputation_history
+            WHERE announced_at IS NULL
+            ORDER BY created_at
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+            "#,
+        )
+        .bind(self.config.batch_size)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        if changes.is_empty() {
+            return Ok(0);
+        }
+
+        let mut events = Vec::new();
+        for change in &changes {
+            if change.score_after != change.score_before {
+                events.push(NexusEvent::ReputationUpdated(ReputationUpdatedEvent {
+                    user_id: change.user_id,
+                    old_score: change.score_before,
+                    new_score: change.score_after,
+                    change_reason: change.reason.clone(),
+                    related_submission_id: change.submission_id,
+                    updated_at: change.created_at,
+                }));
+            }
+            let streak = change
+                .details
+                .as_ref()
+                .and_then(|details| details.get("streak"))
+                .and_then(serde_json::Value::as_i64)
+                .and_then(|streak| i32::try_from(streak).ok());
+            if let Some(streak) = streak.filter(|&streak| streaks::is_milestone(streak)) {
+                events.push(NexusEvent::StreakMilestoneReached(StreakMilestoneReachedEvent {
+                    user_id: change.user_id,
+                    streak,
+                    reached_at: change.created_at,
+                }));
+            }
+        }
+        for event in &events {
+            Self::queue_webhooks(&mut *tx, event).await?;
+        }
+        let ids: Vec<Uuid> = changes.iter().map(|c| c.id).collect();
+        sqlx::query("UPDATE reputation_history SET announced_at = NOW() WHERE id = ANY($1)")
+            .bind(&ids)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        tx.commit().await.map_err(db_error)?;
+
+        self.publish(&events).await;
+        Ok(changes.len())
+    }
+
+    /// Re-rank users with settled submissions and announce the moves near
+    /// the top of the leaderboard. A user's first rank is recorded without
+    /// an announcement. Returns how many moves were announced.
+    pub async fn announce_rank_changes(&self) -> ReputationResult<usize> {
+        let now = Utc::now();
+        let mut tx = self.db_pool.begin().await.map_err(db_error)?;
+        let moves: Vec<RankChange> = sqlx::query_as(
+            r#"
+            WITH ranked AS (
+                SELECT user_id, rank AS old_rank, current_score AS score,
+                       RANK() OVER (ORDER BY current_score DESC)::INT AS new_rank
+                FROM user_reputation
+                WHERE total_submissions > 0
+            )
+            UPDATE user_reputation u
+            SET rank = ranked.new_rank
+            FROM ranked
+            WHERE u.user_id = ranked.user_id AND u.rank IS DISTINCT FROM ranked.new_rank
+            RETURNING u.user_id, ranked.old_rank, ranked.new_rank, ranked.score
+            "#,
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(db_error)?;
+
+        let limit = self.config.rank_announce_limit;
+        let events: Vec<NexusEvent> = moves
+            .into_iter()
+            .filter_map(|change| {
+                let old_rank = change.old_rank?;
+                (old_rank <= limit || change.new_rank <= limit).then_some(NexusEvent::ReputationRankChanged(
+                    ReputationRankChangedEvent {
+                        user_id: change.user_id,
+                        old_rank,
+                        new_rank: change.new_rank,
+                        score: change.score,
+                        changed_at: now,
+                    },
+                ))
+            })
+            .collect();
+        for event in &events {
+            Self::queue_webhooks(&mut *tx, event).await?;
+        }
+        tx.commit().await.map_err(db_error)?;
+
+        self.publish(&events).await;
+        Ok(events.len())
+    }
+
+    async fn queue_webhooks<'e, E: PgExecutor<'e>>(executor: E, event: &NexusEvent) -> ReputationResult<()> {
+        if let Some((kind, user_id, payload)) = webhook_event(event) {
+            WebhookService::enqueue(executor, user_id, kind, &payload).await?;
+        }
+        Ok(())
+    }
+
+    async fn publish(&self, events: &[NexusEvent]) {
+        for event in events {
+            if let Err(e) = self.publisher.publish(event).await {
+                warn!("Failed to publish {}: {}", event.get_title(), e);
+            }
+        }
+    }
+}
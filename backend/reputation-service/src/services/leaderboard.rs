@@ -0,0 +1,545 @@
+// Windowed leaderboards
+//
+// Boards over the last 24 hours, 7 days and 30 days rank users by the
+// reputation they gained in the window; the all-time board ranks them by
+// current score. Any board can be narrowed to engines (owners of automated
+// engines) or humans, and to one threat category, where the category score
+// counts instead of the global one.
+//
+// Boards are Redis sorted sets kept up by the leaderboard updater, so reading
+// one never scans the reputation tables. Each score change recorded in
+// `reputation_history` is added once to an hourly bucket of every board it
+// belongs on, then marked ranked; a windowed board is the union of its
+// window's buckets, cached for `window_cache_secs`. All-time boards hold the
+// current scores of every user with a settled submission and are rewritten
+// for the users each batch touches. When Redis has lost the boards, i.e. the
+// marker key is gone, they are rebuilt from the database before anything
+// else.
+
+use chrono::{DateTime, Duration, Utc};
+use redis::aio::ConnectionManager;
+use serde::Serialize;
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::config::LeaderboardConfig;
+use crate::models::{LeaderboardEntry, ReputationError, ReputationResult};
+use crate::scoring::leaderboard::{self, LeaderboardWindow, Participant, BUCKET_RETENTION_HOURS};
+
+/// Entries per page when no limit is asked for
+const DEFAULT_LEADERBOARD_LIMIT: i64 = 50;
+
+/// Most entries per page
+const MAX_LEADERBOARD_LIMIT: i64 = 100;
+
+const KEY_PREFIX: &str = "reputation:leaderboard";
+
+/// Present while the boards are complete; set once they are built
+const BUILT_KEY: &str = "reputation:leaderboard:built";
+
+/// Held by the replica rebuilding the boards
+const REBUILD_LOCK_KEY: &str = "reputation:leaderboard:rebuilding";
+
+const REBUILD_LOCK_SECS: u64 = 600;
+
+/// Commands sent to Redis per round trip while rebuilding
+const REBUILD_PIPELINE_SIZE: usize = 5000;
+
+/// The categories a history entry moved the user's score in, as recorded by
+/// settlement
+const CHANGE_CATEGORIES: &str = "ARRAY(SELECT jsonb_array_elements_text(h.details -> 'categories') \
+                                 WHERE jsonb_typeof(h.details -> 'categories') = 'array')";
+
+/// Whether the user owns an automated engine
+const IS_ENGINE: &str = "EXISTS(SELECT 1 FROM engines e WHERE e.owner_id = r.user_id AND e.engine_type = 'automated')";
+
+#[derive(sqlx::FromRow)]
+struct RankedChange {
+    id: Uuid,
+    user_id: Uuid,
+    score_change: i64,
+    created_at: DateTime<Utc>,
+    categories: Vec<String>,
+    is_engine: bool,
+}
+
+#[derive(sqlx::FromRow)]
+struct AllTimeStanding {
+    user_id: Uuid,
+    score: i32,
+    total_submissions: i32,
+    is_engine: bool,
+}
+
+#[derive(sqlx::FromRow)]
+struct CategoryStanding {
+    user_id: Uuid,
+    category: String,
+    score: i32,
+}
+
+#[derive(sqlx::FromRow)]
+struct EntryDetails {
+    user_id: Uuid,
+    username: String,
+    accuracy_rate: f64,
+    total_submissions: i32,
+    badges_count: i32,
+}
+
+/// A page of a windowed or filtered leaderboard
+#[derive(Debug, Serialize)]
+pub struct LeaderboardPage {
+    pub window: &'static str,
+    pub participant: &'static str,
+    /// Category slug the board is narrowed to
+    pub specialization: Option<String>,
+    /// Users on the board
+    pub total: i64,
+    pub leaderboard: Vec<LeaderboardEntry>,
+}
+
+fn db_error(e: sqlx::Error) -> ReputationError {
+    ReputationError::DatabaseError(e.to_string())
+}
+
+fn redis_error(e: redis::RedisError) -> ReputationError {
+    ReputationError::DatabaseError(format!("Redis: {}", e))
+}
+
+/// Scope of a board: every category, or one
+fn scope(category: Option<&str>) -> String {
+    match category {
+        Some(category) => format!("category:{}", category),
+        None => "all".to_string(),
+    }
+}
+
+fn all_time_key(participant: Participant, scope: &str) -> String {
+    format!("{}:all:{}:{}", KEY_PREFIX, participant.as_str(), scope)
+}
+
+fn bucket_key(bucket: &str, participant: Participant, scope: &str) -> String {
+    format!("{}:hour:{}:{}:{}", KEY_PREFIX, bucket, participant.as_str(), scope)
+}
+
+fn window_key(window: LeaderboardWindow, participant: Participant, scope: &str) -> String {
+    format!("{}:{}:{}:{}", KEY_PREFIX, window.as_str(), participant.as_str(), scope)
+}
+
+/// Add a score change to the hourly bucket of every board it belongs on
+fn add_change(
+    pipe: &mut redis::Pipeline,
+    at: DateTime<Utc>,
+    user_id: Uuid,
+    is_engine: bool,
+    categories: &[String],
+    delta: i64,
+) {
+    let bucket = leaderboard::bucket_id(at);
+    let member = user_id.to_string();
+    let scopes = std::iter::once(scope(None)).chain(categories.iter().map(|c| scope(Some(c))));
+    for scope in scopes {
+        for participant in [Participant::All, Participant::of(is_engine)] {
+            let key = bucket_key(&bucket, participant, &scope);
+            pipe.cmd("ZINCRBY").arg(&key).arg(delta).arg(&member).ignore();
+            pipe.cmd("EXPIRE").arg(&key).arg(BUCKET_RETENTION_HOURS * 3600).ignore();
+        }
+    }
+}
+
+/// Put a user on the all-time boards at their current scores, and off the
+/// boards of the other participant kind
+fn set_all_time(pipe: &mut redis::Pipeline, standing: &AllTimeStanding, categories: &[(String, i32)]) {
+    let member = standing.user_id.to_string();
+    let own = Participant::of(standing.is_engine);
+    let other = Participant::of(!standing.is_engine);
+    let scores = std::iter::once((scope(None), standing.score))
+        .chain(categories.iter().map(|(category, score)| (scope(Some(category)), *score)));
+    for (scope, score) in scores {
+        if standing.total_submissions > 0 {
+            pipe.cmd("ZADD").arg(all_time_key(Participant::All, &scope)).arg(score).arg(&member).ignore();
+            pipe.cmd("ZADD").arg(all_time_key(own, &scope)).arg(score).arg(&member).ignore();
+        } else {
+            pipe.cmd("ZREM").arg(all_time_key(Participant::All, &scope)).arg(&member).ignore();
+            pipe.cmd("ZREM").arg(all_time_key(own, &scope)).arg(&member).ignore();
+        }
+        pipe.cmd("ZREM").arg(all_time_key(other, &scope)).arg(&member).ignore();
+    }
+}
+
+pub struct LeaderboardService {
+    config: LeaderboardConfig,
+    db_pool: PgPool,
+    redis_conn: ConnectionManager,
+}
+
+impl LeaderboardService {
+    pub fn new(config: LeaderboardConfig, db_pool: PgPool, redis_conn: ConnectionManager) -> Self {
+        Self {
+            config,
+            db_pool,
+            redis_conn,
+        }
+    }
+
+    pub fn config(&self) -> &LeaderboardConfig {
+        &self.config
+    }
+
+    /// A page of a board
+    pub async fn board(
+        &self,
+        window: LeaderboardWindow,
+        participant: Participant,
+        specialization: Option<&str>,
+        page: Option<i64>,
+        limit: Option<i64>,
+    ) -> ReputationResult<LeaderboardPage> {
+        let limit = limit.unwrap_or(DEFAULT_LEADERBOARD_LIMIT);
+        if !(1..=MAX_LEADERBOARD_LIMIT).contains(&limit) {
+            return Err(ReputationError::ValidationError(format!(
+                "limit must be between 1 and {}",
+                MAX_LEADERBOARD_LIMIT
+            )));
+        }
+        let page = page.unwrap_or(1);
+        if page < 1 {
+            return Err(ReputationError::ValidationError("page starts at 1".to_string()));
+        }
+        let offset = (page - 1) * limit;
+
+        if let Some(category) = specialization {
+            let known: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM reputation_categories WHERE slug = $1)")
+                .bind(category)
+                .fetch_one(&self.db_pool)
+                .await
+                .map_err(db_error)?;
+            if !known {
+                return Err(ReputationError::NotFound(format!("Category {}", category)));
+            }
+        }
+
+        let mut conn = self.redis_conn.clone();
+        let scope = scope(specialization);
+        let key = match window.hours() {
+            None => all_time_key(participant, &scope),
+            Some(hours) => {
+                let key = window_key(window, participant, &scope);
+                let cached: bool = redis::cmd("EXISTS").arg(&key).query_async(&mut conn).await.map_err(redis_error)?;
+                if !cached {
+                    let buckets: Vec<String> = leaderboard::window_buckets(hours, Utc::now())
+                        .iter()
+                        .map(|bucket| bucket_key(bucket, participant, &scope))
+                        .collect();
+                    redis::pipe()
+                        .atomic()
+                        .cmd("ZUNIONSTORE")
+                        .arg(&key)
+                        .arg(buckets.len())
+                        .arg(&buckets)
+                        .ignore()
+                        .cmd("EXPIRE")
+                        .arg(&key)
+                        .arg(self.config.window_cache_secs)
+                        .ignore()
+                        .query_async::<_, ()>(&mut conn)
+                        .await
+                        .map_err(redis_error)?;
+                }
+                key
+            }
+        };
+
+        let (total, scores): (i64, Vec<(String, f64)>) = redis::pipe()
+            .cmd("ZCARD")
+            .arg(&key)
+            .cmd("ZREVRANGE")
+            .arg(&key)
+            .arg(offset)
+            .arg(offset + limit - 1)
+            .arg("WITHSCORES")
+            .query_async(&mut conn)
+            .await
+            .map_err(redis_error)?;
+        let above: usize = match scores.first() {
+            Some((_, first)) => redis::cmd("ZCOUNT")
+                .arg(&key)
+                .arg(format!("({}", first))
+                .arg("+inf")
+                .query_async(&mut conn)
+                .await
+                .map_err(redis_error)?,
+            None => 0,
+        };
+
+        let user_ids: Vec<Uuid> = scores.iter().filter_map(|(member, _)| Uuid::parse_str(member).ok()).collect();
+        let mut details: HashMap<Uuid, EntryDetails> = sqlx::query_as(
+            r#"
+            SELECT r.user_id, COALESCE(u.username, '') AS username, r.accuracy_rate::FLOAT8 AS accuracy_rate,
+                   r.total_submissions,
+                   (SELECT COUNT(*) FROM user_badges b WHERE b.user_id = r.user_id)::INT AS badges_count
+            FROM user_reputation r
+            LEFT JOIN users u ON u.id = r.user_id
+            WHERE r.user_id = ANY($1)
+            "#,
+        )
+        .bind(&user_ids)
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(db_error)?
+        .into_iter()
+        .map(|entry: EntryDetails| (entry.user_id, entry))
+        .collect();
+
+        let values: Vec<f64> = scores.iter().map(|(_, score)| *score).collect();
+        let ranks = leaderboard::competition_ranks(&values, offset as usize, above);
+        let entries = scores
+            .iter()
+            .zip(ranks)
+            .filter_map(|((member, score), rank)| {
+                let user_id = Uuid::parse_str(member).ok()?;
+                let details = details.remove(&user_id)?;
+                Some(LeaderboardEntry {
+                    rank: rank as i32,
+                    user_id,
+                    username: details.username,
+                    score: *score as i32,
+                    accuracy_rate: details.accuracy_rate,
+                    total_submissions: details.total_submissions,
+                    badges_count: details.badges_count,
+                })
+            })
+            .collect();
+
+        Ok(LeaderboardPage {
+            window: window.as_str(),
+            participant: participant.as_str(),
+            specialization: specialization.map(str::to_string),
+            total,
+            leaderboard: entries,
+        })
+    }
+
+    /// Add score changes not yet ranked to the boards, rebuilding them first
+    /// if Redis lost them. Returns how many changes were ranked.
+    pub async fn update(&self) -> ReputationResult<usize> {
+        let mut conn = self.redis_conn.clone();
+        let built: bool = redis::cmd("EXISTS").arg(BUILT_KEY).query_async(&mut conn).await.map_err(redis_error)?;
+        if !built {
+            return self.rebuild().await;
+        }
+
+        let mut tx = self.db_pool.begin().await.map_err(db_error)?;
+        let changes: Vec<RankedChange> = sqlx::query_as(&format!(
+            r#"
+            SELECT h.id, h.user_id, h.score_change::BIGINT AS score_change, h.created_at,
+                   {} AS categories, {} AS is_engine
+            FROM reputation_history h
+            JOIN user_reputation r ON r.user_id = h.user_id
+            WHERE h.ranked_at IS NULL
+            ORDER BY h.created_at
+            LIMIT $1
+            FOR UPDATE OF h SKIP LOCKED
+            "#,
+            CHANGE_CATEGORIES, IS_ENGINE
+        ))
+        .bind(self.config.batch_size)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        if changes.is_empty() {
+            return Ok(0);
+        }
+
+        let user_ids: Vec<Uuid> = changes
+            .iter()
+            .map(|change| change.user_id)
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let standings: Vec<AllTimeStanding> = sqlx::query_as(&format!(
+            r#"
+            SELECT r.user_id, r.current_score AS score, r.total_submissions, {} AS is_engine
+            FROM user_reputation r
+            WHERE r.user_id = ANY($1)
+            "#,
+            IS_ENGINE
+        ))
+        .bind(&user_ids)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        let category_scores = self.category_scores(&mut tx, Some(&user_ids)).await?;
+
+        let oldest = Utc::now() - Duration::hours(BUCKET_RETENTION_HOURS);
+        let mut pipe = redis::pipe();
+        for change in changes.iter().filter(|change| change.created_at > oldest) {
+            add_change(
+                &mut pipe,
+                change.created_at,
+                change.user_id,
+                change.is_engine,
+                &change.categories,
+                change.score_change,
+            );
+        }
+        for standing in &standings {
+            let categories = category_scores.get(&standing.user_id).map(Vec::as_slice).unwrap_or_default();
+            set_all_time(&mut pipe, standing, categories);
+        }
+        pipe.query_async::<_, ()>(&mut conn).await.map_err(redis_error)?;
+
+        let ids: Vec<Uuid> = changes.iter().map(|change| change.id).collect();
+        sqlx::query("UPDATE reputation_history SET ranked_at = NOW() WHERE id = ANY($1)")
+            .bind(&ids)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        tx.commit().await.map_err(db_error)?;
+        Ok(changes.len())
+    }
+
+    /// Build every board from the database. The changes read are marked
+    /// ranked in the same snapshot, so none is counted twice or missed.
+    async fn rebuild(&self) -> ReputationResult<usize> {
+        let mut conn = self.redis_conn.clone();
+        let locked: Option<String> = redis::cmd("SET")
+            .arg(REBUILD_LOCK_KEY)
+            .arg(1)
+            .arg("NX")
+            .arg("EX")
+            .arg(REBUILD_LOCK_SECS)
+            .query_async(&mut conn)
+            .await
+            .map_err(redis_error)?;
+        if locked.is_none() {
+            return Ok(0);
+        }
+
+        let result = self.rebuild_locked(&mut conn).await;
+        redis::cmd("DEL")
+            .arg(REBUILD_LOCK_KEY)
+            .query_async::<_, ()>(&mut conn)
+            .await
+            .map_err(redis_error)?;
+        result
+    }
+
+    async fn rebuild_locked(&self, conn: &mut ConnectionManager) -> ReputationResult<usize> {
+        let mut stale: Vec<String> = Vec::new();
+        {
+            let mut keys = redis::AsyncCommands::scan_match::<_, String>(conn, format!("{}:*", KEY_PREFIX))
+                .await
+                .map_err(redis_error)?;
+            while let Some(key) = keys.next_item().await {
+                if key != REBUILD_LOCK_KEY {
+                    stale.push(key);
+                }
+            }
+        }
+        for keys in stale.chunks(REBUILD_PIPELINE_SIZE) {
+            redis::cmd("DEL").arg(keys).query_async::<_, ()>(conn).await.map_err(redis_error)?;
+        }
+
+        let mut tx = self.db_pool.begin().await.map_err(db_error)?;
+        sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ")
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        let oldest = leaderboard::bucket_start(Utc::now() - Duration::hours(BUCKET_RETENTION_HOURS));
+        let buckets: Vec<RankedChange> = sqlx::query_as(&format!(
+            r#"
+            SELECT gen_random_uuid() AS id, c.user_id, SUM(c.score_change)::BIGINT AS score_change,
+                   c.hour AS created_at, c.categories, {} AS is_engine
+            FROM (
+                SELECT h.user_id, h.score_change, date_trunc('hour', h.created_at) AS hour, {} AS categories
+                FROM reputation_history h
+                WHERE h.created_at >= $1
+            ) c
+            JOIN user_reputation r ON r.user_id = c.user_id
+            GROUP BY c.user_id, c.hour, c.categories, r.user_id
+            "#,
+            IS_ENGINE, CHANGE_CATEGORIES
+        ))
+        .bind(oldest)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        let standings: Vec<AllTimeStanding> = sqlx::query_as(&format!(
+            r#"
+            SELECT r.user_id, r.current_score AS score, r.total_submissions, {} AS is_engine
+            FROM user_reputation r
+            WHERE r.total_submissions > 0
+            "#,
+            IS_ENGINE
+        ))
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        let category_scores = self.category_scores(&mut tx, None).await?;
+        let ranked = sqlx::query("UPDATE reputation_history SET ranked_at = NOW() WHERE ranked_at IS NULL")
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?
+            .rows_affected();
+
+        for chunk in buckets.chunks(REBUILD_PIPELINE_SIZE) {
+            let mut pipe = redis::pipe();
+            for bucket in chunk {
+                add_change(
+                    &mut pipe,
+                    bucket.created_at,
+                    bucket.user_id,
+                    bucket.is_engine,
+                    &bucket.categories,
+                    bucket.score_change,
+                );
+            }
+            pipe.query_async::<_, ()>(conn).await.map_err(redis_error)?;
+        }
+        for chunk in standings.chunks(REBUILD_PIPELINE_SIZE) {
+            let mut pipe = redis::pipe();
+            for standing in chunk {
+                let categories = category_scores.get(&standing.user_id).map(Vec::as_slice).unwrap_or_default();
+                set_all_time(&mut pipe, standing, categories);
+            }
+            pipe.query_async::<_, ()>(conn).await.map_err(redis_error)?;
+        }
+        tx.commit().await.map_err(db_error)?;
+        redis::cmd("SET").arg(BUILT_KEY).arg(Utc::now().to_rfc3339()).query_async::<_, ()>(conn).await.map_err(redis_error)?;
+
+        info!(
+            "Leaderboards rebuilt: {} hourly total(s), {} ranked user(s)",
+            buckets.len(),
+            standings.len()
+        );
+        Ok(ranked as usize)
+    }
+
+    /// Category scores by user, of the given users or of everyone
+    async fn category_scores(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        user_ids: Option<&[Uuid]>,
+    ) -> ReputationResult<HashMap<Uuid, Vec<(String, i32)>>> {
+        let rows: Vec<CategoryStanding> = sqlx::query_as(
+            r#"
+            SELECT user_id, category, score
+            FROM user_category_reputation
+            WHERE $1::UUID[] IS NULL OR user_id = ANY($1)
+            "#,
+        )
+        .bind(user_ids)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(db_error)?;
+        let mut scores: HashMap<Uuid, Vec<(String, i32)>> = HashMap::new();
+        for row in rows {
+            scores.entry(row.user_id).or_default().push((row.category, row.score));
+        }
+        Ok(scores)
+    }
+}
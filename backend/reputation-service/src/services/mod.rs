@@ -1 +1,13 @@
+pub mod engine_performance;
 pub mod reputation_service;
+pub mod voting_power;
+pub mod settlement;
+pub mod badges;
+pub mod decay;
+pub mod seasons;
+pub mod standing;
+pub mod events;
+pub mod webhooks;
+pub mod appeals;
+pub mod leaderboard;
+pub mod calibration;
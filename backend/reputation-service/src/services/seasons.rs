@@ -0,0 +1,409 @@
+// Reputation seasons
+//
+// Each calendar quarter is a season with its own leaderboard: users with a
+// settled submission during it, ranked by score. When a season ends its
+// standings are archived in `season_standings`, the top ranks are awarded a
+// season badge (announced with `BadgeAwarded` like any other badge) and every
+// score is soft reset to the season's carry-over ratio of itself, recorded in
+// `reputation_history`. Closing a season and opening the next happen in one
+// transaction holding the season's row, so only one replica closes it.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use shared::messaging::{BadgeAwardedEvent, NexusEvent};
+use sqlx::{PgPool, Postgres, Transaction};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::config::SeasonConfig;
+use crate::models::{LeaderboardEntry, ReputationError, ReputationResult};
+use crate::scoring::seasons::{SeasonAward, SeasonPeriod, LAST_AWARDED_RANK};
+use crate::services::events::ReputationEventService;
+
+/// Entries per page when no limit is asked for
+const DEFAULT_LEADERBOARD_LIMIT: i64 = 50;
+
+/// Most entries per page
+const MAX_LEADERBOARD_LIMIT: i64 = 100;
+
+/// Accepted in place of a season id for the season in progress
+const CURRENT_SEASON: &str = "current";
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Season {
+    pub id: String,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub carry_over_ratio: f64,
+    pub status: String,
+    pub archived_at: Option<DateTime<Utc>>,
+}
+
+#[derive(sqlx::FromRow)]
+struct SeasonBadge {
+    id: Uuid,
+    name: String,
+    description: String,
+    icon: String,
+    rarity: String,
+}
+
+fn db_error(e: sqlx::Error) -> ReputationError {
+    ReputationError::DatabaseError(e.to_string())
+}
+
+pub struct SeasonService {
+    config: SeasonConfig,
+    /// Soft resets never take a score below this
+    min_score: i32,
+    db_pool: PgPool,
+    events: Arc<ReputationEventService>,
+}
+
+impl SeasonService {
+    pub fn new(config: SeasonConfig, min_score: i32, db_pool: PgPool, events: Arc<ReputationEventService>) -> Self {
+        Self {
+            config,
+            min_score,
+            db_pool,
+            events,
+        }
+    }
+
+    pub fn config(&self) -> &SeasonConfig {
+        &self.config
+    }
+
+    pub async fn seasons(&self) -> ReputationResult<Vec<Season>> {
+        sqlx::query_as(
+            r#"
+            SELECT id, starts_at, ends_at, carry_over_ratio, status, archived_at
+            FROM reputation_seasons
+            ORDER BY starts_at DESC
+            "#,
+        )
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(db_error)
+    }
+
+    /// The season in progress, opened for the current quarter if none is
+    pub async fn current(&self) -> ReputationResult<Season> {
+        let active: Option<Season> = sqlx::query_as(
+            r#"
+            SELECT id, starts_at, ends_at, carry_over_ratio, status, archived_at
+            FROM reputation_seasons
+            WHERE status = 'active'
+            "#,
+        )
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+        if let Some(season) = active {
+            return Ok(season);
+        }
+
+        let mut tx = self.db_pool.begin().await.map_err(db_error)?;
+        self.open(&mut tx, Utc::now()).await?;
+        tx.commit().await.map_err(db_error)?;
+        sqlx::query_as(
+            r#"
+            SELECT id, starts_at, ends_at, carry_over_ratio, status, archived_at
+            FROM reputation_seasons
+            WHERE status = 'active'
+            "#,
+        )
+        .fetch_one(&self.db_pool)
+        .await
+        .map_err(db_error)
+    }
+
+    async fn season(&self, id: &str) -> ReputationResult<Season> {
+        sqlx::query_as(
+            r#"
+            SELECT id, starts_at, ends_at, carry_over_ratio, status, archived_at
+            FROM reputation_seasons
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| ReputationError::NotFound(format!("Season {}", id)))
+    }
+
+    /// A page of a season's leaderboard: live for the season in progress,
+    /// the archived final standings for one that ended
+    pub async fn leaderboard(
+        &self,
+        season_id: Option<&str>,
+        page: Option<i64>,
+        limit: Option<i64>,
+    ) -> ReputationResult<(Season, Vec<LeaderboardEntry>)> {
+        let limit = limit.unwrap_or(DEFAULT_LEADERBOARD_LIMIT);
+        if !(1..=MAX_LEADERBOARD_LIMIT).contains(&limit) {
+            return Err(ReputationError::ValidationError(format!(
+                "limit must be between 1 and {}",
+                MAX_LEADERBOARD_LIMIT
+            )));
+        }
+        let page = page.unwrap_or(1);
+        if page < 1 {
+            return Err(ReputationError::ValidationError("page starts at 1".to_string()));
+        }
+        let offset = (page - 1) * limit;
+
+        let season = match season_id.map(str::trim) {
+            None | Some("") | Some(CURRENT_SEASON) => self.current().await?,
+            Some(id) => self.season(id).await?,
+        };
+
+        let entries = if season.status == "archived" {
+            sqlx::query_as(
+                r#"
+                SELECT s.rank, s.user_id, COALESCE(u.username, '') AS username, s.score,
+                       s.accuracy_rate::FLOAT8 AS accuracy_rate, s.total_submissions,
+                       (SELECT COUNT(*) FROM user_badges b WHERE b.user_id = s.user_id)::INT AS badges_count
+                FROM season_standings s
+                LEFT JOIN users u ON u.id = s.user_id
+                WHERE s.season_id = $1
+                ORDER BY s.rank, s.user_id
+                LIMIT $2 OFFSET $3
+                "#,
+            )
+            .bind(&season.id)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.db_pool)
+            .await
+        } else {
+            sqlx::query_as(
+                r#"
+                SELECT r.rank::INT AS rank, r.user_id, COALESCE(u.username, '') AS username,
+                       r.current_score AS score, r.accuracy_rate::FLOAT8 AS accuracy_rate, r.total_submissions,
+                       (SELECT COUNT(*) FROM user_badges b WHERE b.user_id = r.user_id)::INT AS badges_count
+                FROM (
+                    SELECT user_id, current_score, accuracy_rate, total_submissions,
+                           RANK() OVER (ORDER BY current_score DESC) AS rank
+                    FROM user_reputation
+                    WHERE last_active_at >= $1
+                ) r
+                LEFT JOIN users u ON u.id = r.user_id
+                ORDER BY r.rank, r.user_id
+                LIMIT $2 OFFSET $3
+                "#,
+            )
+            .bind(season.starts_at)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.db_pool)
+            .await
+        }
+        .map_err(db_error)?;
+        Ok((season, entries))
+    }
+
+    /// Close the season in progress if it has ended and open the next.
+    /// Returns the id of the season closed, if any.
+    pub async fn roll_over(&self) -> ReputationResult<Option<String>> {
+        let season = self.current().await?;
+        let now = Utc::now();
+        if now < season.ends_at {
+            return Ok(None);
+        }
+
+        let Some(awards) = self.close(&season, now).await? else {
+            return Ok(None);
+        };
+        info!("Season {} archived with {} season badge(s)", season.id, awards.len());
+        for event in awards {
+            self.events.announce(NexusEvent::BadgeAwarded(event)).await;
+        }
+        Ok(Some(season.id))
+    }
+
+    /// Archive a season's standings, award its badges, soft reset every
+    /// score and open the next season. `None` if the season was already
+    /// closed.
+    async fn close(
+        &self,
+        season: &Season,
+        now: DateTime<Utc>,
+    ) -> ReputationResult<Option<Vec<BadgeAwardedEvent>>> {
+        let mut tx = self.db_pool.begin().await.map_err(db_error)?;
+        let locked: Option<String> =
+            sqlx::query_scalar("SELECT id FROM reputation_seasons WHERE id = $1 AND status = 'active' FOR UPDATE")
+                .bind(&season.id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(db_error)?;
+        if locked.is_none() {
+            return Ok(None);
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO season_standings (season_id, user_id, rank, score, accuracy_rate, total_submissions)
+            SELECT $1, user_id, RANK() OVER (ORDER BY current_score DESC), current_score, accuracy_rate,
+                   total_submissions
+            FROM user_reputation
+            WHERE last_active_at >= $2
+            ON CONFLICT (season_id, user_id) DO NOTHING
+            "#,
+        )
+        .bind(&season.id)
+        .bind(season.starts_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+
+        let awards = self.award(&mut tx, season, now).await?;
+
+        let reset = sqlx::query(
+            r#"
+            WITH reset AS (
+                UPDATE user_reputation r
+                SET current_score = GREATEST($2, ROUND(r.current_score * $1)::INT),
+                    lowest_score = LEAST(r.lowest_score, GREATEST($2, ROUND(r.current_score * $1)::INT)),
+                    last_updated = $4
+                FROM user_reputation previous
+                WHERE previous.user_id = r.user_id
+                  AND r.current_score > GREATEST($2, ROUND(r.current_score * $1)::INT)
+                RETURNING r.user_id, previous.current_score AS score_before, r.current_score AS score_after
+            )
+            INSERT INTO reputation_history (user_id, score_before, score_after, score_change, reason, details)
+            SELECT user_id, score_before, score_after, score_after - score_before, 'season_reset',
+                   jsonb_build_object('season', $3::TEXT, 'carry_over_ratio', $1)
+            FROM reset
+            "#,
+        )
+        .bind(season.carry_over_ratio)
+        .bind(self.min_score)
+        .bind(&season.id)
+        .bind(now)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+
+        sqlx::query("UPDATE reputation_seasons SET status = 'archived', archived_at = $2 WHERE id = $1")
+            .bind(&season.id)
+            .bind(now)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        self.open(&mut tx, now).await?;
+        tx.commit().await.map_err(db_error)?;
+
+        info!(
+            "Season {} ended; {} score(s) reset to {:.0}%",
+            season.id,
+            reset.rows_affected(),
+            season.carry_over_ratio * 100.0
+        );
+        Ok(Some(awards))
+    }
+
+    /// Award the season badges by final rank
+    async fn award(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        season: &Season,
+        now: DateTime<Utc>,
+    ) -> ReputationResult<Vec<BadgeAwardedEvent>> {
+        let names = [SeasonAward::Champion, SeasonAward::Podium, SeasonAward::TopTen].map(|award| award.badge_name());
+        let badges: HashMap<String, SeasonBadge> =
+            sqlx::query_as("SELECT id, name, description, icon, rarity FROM badges WHERE name = ANY($1)")
+                .bind(&names[..])
+                .fetch_all(&mut **tx)
+                .await
+                .map_err(db_error)?
+                .into_iter()
+                .map(|badge: SeasonBadge| (badge.name.clone(), badge))
+                .collect();
+        let top: Vec<(Uuid, i32)> = sqlx::query_as(
+            "SELECT user_id, rank FROM season_standings WHERE season_id = $1 AND rank <= $2 ORDER BY rank",
+        )
+        .bind(&season.id)
+        .bind(LAST_AWARDED_RANK)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(db_error)?;
+
+        let mut awarded = Vec::new();
+        for (user_id, rank) in top {
+            let Some(award) = SeasonAward::for_rank(rank) else {
+                continue;
+            };
+            let Some(badge) = badges.get(award.badge_name()) else {
+                warn!("Season badge {} is missing; not awarded to {}", award.badge_name(), user_id);
+                continue;
+            };
+
+            sqlx::query(
+                r#"
+                INSERT INTO season_awards (season_id, user_id, badge_id, rank, awarded_at)
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (season_id, user_id) DO NOTHING
+                "#,
+            )
+            .bind(&season.id)
+            .bind(user_id)
+            .bind(badge.id)
+            .bind(rank)
+            .bind(now)
+            .execute(&mut **tx)
+            .await
+            .map_err(db_error)?;
+            sqlx::query(
+                r#"
+                INSERT INTO user_badges (user_id, badge_id, awarded_at, progress)
+                VALUES ($1, $2, $3, 100)
+                ON CONFLICT (user_id, badge_id) DO NOTHING
+                "#,
+            )
+            .bind(user_id)
+            .bind(badge.id)
+            .bind(now)
+            .execute(&mut **tx)
+            .await
+            .map_err(db_error)?;
+
+            awarded.push(BadgeAwardedEvent {
+                user_id,
+                badge_id: badge.id,
+                badge_name: format!("{} ({})", badge.name, season.id),
+                description: badge.description.clone(),
+                icon: badge.icon.clone(),
+                rarity: badge.rarity.clone(),
+                awarded_at: now,
+            });
+        }
+        Ok(awarded)
+    }
+
+    /// Open the season for the quarter `at` falls in
+    async fn open(&self, tx: &mut Transaction<'_, Postgres>, at: DateTime<Utc>) -> ReputationResult<()> {
+        let period = SeasonPeriod::containing(at);
+        let opened = sqlx::query(
+            r#"
+            INSERT INTO reputation_seasons (id, starts_at, ends_at, carry_over_ratio)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(&period.id)
+        .bind(period.starts_at)
+        .bind(period.ends_at)
+        .bind(self.config.carry_over_ratio)
+        .execute(&mut **tx)
+        .await
+        .map_err(db_error)?;
+        if opened.rows_affected() > 0 {
+            info!("Season {} opened", period.id);
+        }
+        Ok(())
+    }
+}
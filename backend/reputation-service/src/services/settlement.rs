@@ -0,0 +1,325 @@
+// Reputation from settlement plans
+//
+// The consensus-service decides how a finalized bounty settles and publishes
+// the plan, reputation deltas included, as one signed event. Deltas are
+// applied as planned rather than re-scored here, so reputation always moves
+// with the stakes the payment-service slashes or releases. Votes that were
+// refunded, e.g. because no consensus was reached, leave reputation alone.
+// Settled votes also count towards the user's specialization in samples of
+// the final verdict's kind, which specialization badges are awarded on, and
+// move the user's score in every threat category the bounty's tags put it in.
+// The streak each vote leaves the user on is kept in the history row, where
+// the event publisher looks for streak milestones.
+//
+// Calibration bounties, whose verdict the platform knows, settle like any
+// other so nothing in a user's reputation tells them apart. Their results are
+// also recorded against the known verdict, which measures engines' true
+// accuracy. New engines are on probation until they settle enough of them,
+// and the last one ends the probation with a score seeded from how many the
+// engine got right; the seeding is recorded without the bounty it came on.
+
+use serde_json::json;
+use shared::messaging::{SettlementEntry, SettlementOutcome, SettlementPlan};
+use shared::types::common::ThreatVerdict;
+use sqlx::{PgConnection, PgPool};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::config::ProbationConfig;
+use crate::models::{ReputationError, ReputationResult};
+use crate::scoring::probation;
+
+fn db_error(e: sqlx::Error) -> ReputationError {
+    ReputationError::DatabaseError(e.to_string())
+}
+
+fn verdict_name(verdict: &ThreatVerdict) -> &'static str {
+    match verdict {
+        ThreatVerdict::Malicious => "malicious",
+        ThreatVerdict::Benign => "benign",
+        ThreatVerdict::Suspicious => "suspicious",
+        ThreatVerdict::Unknown => "unknown",
+    }
+}
+
+pub struct SettlementService {
+    probation: ProbationConfig,
+    db_pool: PgPool,
+}
+
+impl SettlementService {
+    pub fn new(probation: ProbationConfig, db_pool: PgPool) -> Self {
+        Self { probation, db_pool }
+    }
+
+    /// Apply a plan's reputation deltas. Returns `false` when the plan was
+    /// already applied.
+    pub async fn apply(&self, plan: &SettlementPlan) -> ReputationResult<bool> {
+        let mut tx = self.db_pool.begin().await.map_err(db_error)?;
+        let recorded = sqlx::query(
+            "INSERT INTO applied_settlement_plans (plan_id, bounty_id) VALUES ($1, $2) ON CONFLICT (plan_id) DO NOTHING",
+        )
+        .bind(plan.plan_id)
+        .bind(plan.bounty_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        if recorded.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        let specialization = match plan.final_verdict {
+            ThreatVerdict::Malicious => Some("malicious"),
+            ThreatVerdict::Benign => Some("benign"),
+            ThreatVerdict::Suspicious => Some("suspicious"),
+            ThreatVerdict::Unknown => None,
+        };
+        let categories: Vec<String> = sqlx::query_scalar(
+            r#"
+            SELECT slug FROM reputation_categories
+            WHERE tags && ARRAY(SELECT tag_slug::TEXT FROM bounty_tag_assignments WHERE bounty_id = $1)
+            "#,
+        )
+        .bind(plan.bounty_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(db_error)?;
+
+        let mut updated = 0;
+        for entry in &plan.entries {
+            let correct = match entry.outcome {
+                SettlementOutcome::Rewarded => true,
+                SettlementOutcome::Slashed => false,
+                SettlementOutcome::Refunded => continue,
+            };
+            // Engines without an account have no reputation to move
+            let Some(user_id) = entry.user_id else {
+                continue;
+            };
+
+            sqlx::query("INSERT INTO user_reputation (user_id) VALUES ($1) ON CONFLICT (user_id) DO NOTHING")
+                .bind(user_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(db_error)?;
+            let score_before: i32 =
+                sqlx::query_scalar("SELECT current_score FROM user_reputation WHERE user_id = $1 FOR UPDATE")
+                    .bind(user_id)
+                    .fetch_one(&mut *tx)
+                    .await
+                    .map_err(db_error)?;
+            let score_after = score_before.saturating_add(entry.reputation_delta);
+
+            let streak: i32 = sqlx::query_scalar(
+                r#"
+                UPDATE user_reputation
+                SET current_score = $2,
+                    highest_score = GREATEST(highest_score, $2),
+                    lowest_score = LEAST(lowest_score, $2),
+                    total_submissions = total_submissions + 1,
+                    correct_submissions = correct_submissions + CASE WHEN $3 THEN 1 ELSE 0 END,
+                    incorrect_submissions = incorrect_submissions + CASE WHEN $3 THEN 0 ELSE 1 END,
+                    accuracy_rate = ROUND(
+                        (correct_submissions + CASE WHEN $3 THEN 1 ELSE 0 END) * 100.0 / (total_submissions + 1), 2
+                    ),
+                    current_streak = CASE WHEN $3 THEN current_streak + 1 ELSE 0 END,
+                    best_streak = CASE WHEN $3 THEN GREATEST(best_streak, current_streak + 1) ELSE best_streak END,
+                    last_active_at = NOW(),
+                    last_updated = NOW()
+                WHERE user_id = $1
+                RETURNING current_streak
+                "#,
+            )
+            .bind(user_id)
+            .bind(score_after)
+            .bind(correct)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(db_error)?;
+
+            if let Some(specialization) = specialization {
+                sqlx::query(
+                    r#"
+                    INSERT INTO user_specializations (user_id, specialization, correct_count, total_count)
+                    VALUES ($1, $2, CASE WHEN $3 THEN 1 ELSE 0 END, 1)
+                    ON CONFLICT (user_id, specialization) DO UPDATE
+                    SET correct_count = user_specializations.correct_count + EXCLUDED.correct_count,
+                        total_count = user_specializations.total_count + 1,
+                        updated_at = NOW()
+                    "#,
+                )
+                .bind(user_id)
+                .bind(specialization)
+                .bind(correct)
+                .execute(&mut *tx)
+                .await
+                .map_err(db_error)?;
+            }
+
+            if !categories.is_empty() {
+                sqlx::query(
+                    r#"
+                    INSERT INTO user_category_reputation
+                        (user_id, category, score, total_submissions, correct_submissions)
+                    SELECT $1, category, $3, 1, CASE WHEN $4 THEN 1 ELSE 0 END
+                    FROM UNNEST($2::TEXT[]) AS category
+                    ON CONFLICT (user_id, category) DO UPDATE
+                    SET score = user_category_reputation.score + EXCLUDED.score,
+                        total_submissions = user_category_reputation.total_submissions + 1,
+                        correct_submissions = user_category_reputation.correct_submissions
+                            + EXCLUDED.correct_submissions,
+                        last_updated = NOW()
+                    "#,
+                )
+                .bind(user_id)
+                .bind(&categories)
+                .bind(entry.reputation_delta)
+                .bind(correct)
+                .execute(&mut *tx)
+                .await
+                .map_err(db_error)?;
+            }
+
+            let details = json!({
+                "plan_id": plan.plan_id,
+                "engine_id": entry.engine_id,
+                "outcome": entry.outcome,
+                "verdict": entry.verdict,
+                "final_verdict": plan.final_verdict,
+                "categories": categories,
+                "streak": streak,
+            });
+            sqlx::query(
+                r#"
+                INSERT INTO reputation_history
+                    (user_id, score_before, score_after, score_change, reason, bounty_id, submission_id, details)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8::JSONB)
+                "#,
+            )
+            .bind(user_id)
+            .bind(score_before)
+            .bind(score_after)
+            .bind(score_after - score_before)
+            .bind(if correct { "settlement_rewarded" } else { "settlement_slashed" })
+            .bind(plan.bounty_id)
+            .bind(entry.submission_id)
+            .bind(details.to_string())
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+            if plan.calibration {
+                self.calibrate(&mut tx, plan, entry, user_id, correct).await?;
+            }
+            updated += 1;
+        }
+        tx.commit().await.map_err(db_error)?;
+
+        info!(
+            "Applied settlement plan {} for bounty {}: reputation updated for {} user(s)",
+            plan.plan_id, plan.bounty_id, updated
+        );
+        Ok(true)
+    }
+
+    /// Record a calibration bounty's result against its known verdict. For
+    /// users on probation it also counts towards ending it, with a seeded
+    /// score once enough are settled.
+    async fn calibrate(
+        &self,
+        conn: &mut PgConnection,
+        plan: &SettlementPlan,
+        entry: &SettlementEntry,
+        user_id: Uuid,
+        correct: bool,
+    ) -> ReputationResult<()> {
+        let recorded = sqlx::query(
+            r#"
+            INSERT INTO reputation_calibrations
+                (user_id, bounty_id, submission_id, plan_id, verdict, expected_verdict, correct)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (user_id, bounty_id) DO NOTHING
+            "#,
+        )
+        .bind(user_id)
+        .bind(plan.bounty_id)
+        .bind(entry.submission_id)
+        .bind(plan.plan_id)
+        .bind(verdict_name(&entry.verdict))
+        .bind(verdict_name(&plan.final_verdict))
+        .bind(correct)
+        .execute(&mut *conn)
+        .await
+        .map_err(db_error)?;
+        if recorded.rows_affected() == 0 {
+            return Ok(());
+        }
+
+        let progress: Option<(i32, i32, i32)> = sqlx::query_as(
+            r#"
+            UPDATE user_reputation
+            SET calibrations_completed = calibrations_completed + 1,
+                calibrations_correct = calibrations_correct + CASE WHEN $2 THEN 1 ELSE 0 END
+            WHERE user_id = $1 AND probation_ended_at IS NULL
+            RETURNING calibrations_completed, calibrations_correct, current_score
+            "#,
+        )
+        .bind(user_id)
+        .bind(correct)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(db_error)?;
+        let Some((completed, completed_correct, score_before)) = progress else {
+            return Ok(());
+        };
+        if probation::calibrations_remaining(completed, self.probation.calibrations) > 0 {
+            return Ok(());
+        }
+
+        let seed = probation::seed_score(completed_correct, completed, self.probation.max_seed_score);
+        let score_after = score_before.saturating_add(seed);
+        sqlx::query(
+            r#"
+            UPDATE user_reputation
+            SET current_score = $2,
+                highest_score = GREATEST(highest_score, $2),
+                lowest_score = LEAST(lowest_score, $2),
+                probation_ended_at = NOW()
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .bind(score_after)
+        .execute(&mut *conn)
+        .await
+        .map_err(db_error)?;
+
+        // Which bounty ended the probation would give it away as a
+        // calibration bounty
+        let details = json!({
+            "engine_id": entry.engine_id,
+            "calibrations": completed,
+            "correct": completed_correct,
+            "seed_score": seed,
+        });
+        sqlx::query(
+            r#"
+            INSERT INTO reputation_history (user_id, score_before, score_after, score_change, reason, details)
+            VALUES ($1, $2, $3, $4, 'calibration_completed', $5::JSONB)
+            "#,
+        )
+        .bind(user_id)
+        .bind(score_before)
+        .bind(score_after)
+        .bind(score_after - score_before)
+        .bind(details.to_string())
+        .execute(&mut *conn)
+        .await
+        .map_err(db_error)?;
+
+        info!(
+            "User {} completed probation with {}/{} calibration bounties right, seeding {} reputation",
+            user_id, completed_correct, completed, seed
+        );
+        Ok(())
+    }
+}
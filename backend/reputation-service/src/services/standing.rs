@@ -0,0 +1,314 @@
+// Reputation standing
+//
+// A user's reputation as others read it: the global score plus one score per
+// threat category (`user_category_reputation`), built up by settlements of
+// bounties in that category. For an engine submitting to a bounty the
+// categories come from the bounty's tags, and the engine's specialization
+// score in them is what the consensus-service weights its vote by.
+//
+// What vote weighting needs of a user (global score, accuracy and category
+// scores) is cached in Redis for `cache_ttl_secs`, so a bulk lookup of every
+// engine on a bounty costs one MGET and at most two queries for the misses.
+// Users without a record are cached as such too.
+//
+// Standings say whether the user is still on probation, so its votes are
+// weighted down and its rewards capped until it settles its calibration
+// bounties. How many it has settled is left out: a count that moves when a
+// bounty settles would point that bounty out as a calibration bounty.
+
+use chrono::{DateTime, Utc};
+use redis::aio::ConnectionManager;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::config::StandingConfig;
+use crate::models::{ReputationError, ReputationResult};
+use crate::scoring::categories::{self, ReputationCategory};
+
+/// Most tags looked up per request; bounties carry at most 10
+const MAX_TAGS: usize = 20;
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct CategoryScore {
+    pub category: String,
+    pub name: String,
+    pub score: i32,
+    pub total_submissions: i32,
+    pub correct_submissions: i32,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct UserStanding {
+    pub user_id: Uuid,
+    pub score: i32,
+    pub highest_score: i32,
+    pub lowest_score: i32,
+    pub total_submissions: i32,
+    pub correct_submissions: i32,
+    /// Percentage of settled submissions that were correct
+    pub accuracy_rate: f64,
+    pub current_streak: i32,
+    pub best_streak: i32,
+    /// Still settling calibration bounties
+    pub probation: bool,
+    pub last_updated: DateTime<Utc>,
+    #[sqlx(skip)]
+    pub categories: Vec<CategoryScore>,
+}
+
+/// An engine's reputation for a bounty with the given tags
+#[derive(Debug, Serialize)]
+pub struct EngineStanding {
+    pub engine_id: Uuid,
+    /// Global score
+    pub score: i32,
+    /// Categories the bounty is in
+    pub categories: Vec<String>,
+    /// Mean of the engine's scores in those categories; `None` without
+    /// settled submissions in any of them
+    pub specialization_score: Option<i32>,
+    /// Still settling calibration bounties
+    pub probation: bool,
+}
+
+/// One user's reputation for weighting their vote on a bounty
+#[derive(Debug, Serialize)]
+pub struct BulkStanding {
+    pub user_id: Uuid,
+    pub score: i32,
+    /// Percentage of settled submissions that were correct
+    pub accuracy_rate: f64,
+    pub total_submissions: i32,
+    /// Mean of the user's scores in the bounty's categories; `None` without
+    /// settled submissions in any of them
+    pub specialization_score: Option<i32>,
+    /// Still settling calibration bounties
+    pub probation: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkStandings {
+    /// Categories the bounty is in
+    pub categories: Vec<String>,
+    pub reputations: Vec<BulkStanding>,
+    /// Ids without a reputation record
+    pub unknown: Vec<Uuid>,
+}
+
+/// What is cached per user: enough to weight their vote on any bounty
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedStanding {
+    score: i32,
+    accuracy_rate: f64,
+    total_submissions: i32,
+    categories: HashMap<String, i32>,
+    #[serde(default)]
+    probation: bool,
+}
+
+fn cache_key(user_id: Uuid) -> String {
+    format!("reputation:standing:{}", user_id)
+}
+
+fn db_error(e: sqlx::Error) -> ReputationError {
+    ReputationError::DatabaseError(e.to_string())
+}
+
+pub struct StandingService {
+    config: StandingConfig,
+    db_pool: PgPool,
+    redis_conn: ConnectionManager,
+}
+
+impl StandingService {
+    pub fn new(config: StandingConfig, db_pool: PgPool, redis_conn: ConnectionManager) -> Self {
+        Self {
+            config,
+            db_pool,
+            redis_conn,
+        }
+    }
+
+    pub async fn categories(&self) -> ReputationResult<Vec<ReputationCategory>> {
+        sqlx::query_as("SELECT slug, name, tags FROM reputation_categories ORDER BY slug")
+            .fetch_all(&self.db_pool)
+            .await
+            .map_err(db_error)
+    }
+
+    /// The user's global and per-category reputation; `None` for users
+    /// without a record
+    pub async fn user(&self, user_id: Uuid) -> ReputationResult<Option<UserStanding>> {
+        let standing: Option<UserStanding> = sqlx::query_as(
+            r#"
+            SELECT user_id, current_score AS score, highest_score, lowest_score, total_submissions,
+                   correct_submissions, accuracy_rate::FLOAT8 AS accuracy_rate, current_streak, best_streak,
+                   probation_ended_at IS NULL AS probation, last_updated
+            FROM user_reputation
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+        let Some(mut standing) = standing else {
+            return Ok(None);
+        };
+
+        standing.categories = sqlx::query_as(
+            r#"
+            SELECT r.category, c.name, r.score, r.total_submissions, r.correct_submissions
+            FROM user_category_reputation r
+            JOIN reputation_categories c ON c.slug = r.category
+            WHERE r.user_id = $1
+            ORDER BY r.score DESC, r.category
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+        Ok(Some(standing))
+    }
+
+    /// The engine's reputation for a bounty tagged `tags`; `None` for
+    /// engines without a record
+    pub async fn engine(&self, engine_id: Uuid, tags: &[String]) -> ReputationResult<Option<EngineStanding>> {
+        let BulkStandings {
+            categories,
+            reputations,
+            ..
+        } = self.bulk(&[engine_id], tags).await?;
+        Ok(reputations.into_iter().next().map(|standing| EngineStanding {
+            engine_id,
+            score: standing.score,
+            categories,
+            specialization_score: standing.specialization_score,
+            probation: standing.probation,
+        }))
+    }
+
+    /// The reputation of each of `user_ids` for a bounty tagged `tags`, in
+    /// the order asked for
+    pub async fn bulk(&self, user_ids: &[Uuid], tags: &[String]) -> ReputationResult<BulkStandings> {
+        if user_ids.is_empty() || user_ids.len() > self.config.max_bulk_ids {
+            return Err(ReputationError::ValidationError(format!(
+                "user_ids must contain between 1 and {} entries",
+                self.config.max_bulk_ids
+            )));
+        }
+        if tags.len() > MAX_TAGS {
+            return Err(ReputationError::ValidationError(format!("At most {} tags", MAX_TAGS)));
+        }
+        let mut seen = HashSet::new();
+        let user_ids: Vec<Uuid> = user_ids.iter().copied().filter(|id| seen.insert(*id)).collect();
+
+        let all = if tags.is_empty() { Vec::new() } else { self.categories().await? };
+        let matched = categories::categories_for(&all, tags);
+        let mut standings = self.cached(&user_ids).await?;
+
+        let mut reputations = Vec::with_capacity(user_ids.len());
+        let mut unknown = Vec::new();
+        for user_id in user_ids {
+            match standings.remove(&user_id).flatten() {
+                Some(standing) => reputations.push(BulkStanding {
+                    user_id,
+                    score: standing.score,
+                    accuracy_rate: standing.accuracy_rate,
+                    total_submissions: standing.total_submissions,
+                    specialization_score: categories::specialization_score(&standing.categories, &matched),
+                    probation: standing.probation,
+                }),
+                None => unknown.push(user_id),
+            }
+        }
+        Ok(BulkStandings {
+            categories: matched.into_iter().map(str::to_string).collect(),
+            reputations,
+            unknown,
+        })
+    }
+
+    /// Standings of `user_ids` from the cache, loading and caching the
+    /// misses; `None` for users without a record. Without Redis every
+    /// lookup goes to the database.
+    async fn cached(&self, user_ids: &[Uuid]) -> ReputationResult<HashMap<Uuid, Option<CachedStanding>>> {
+        let mut conn = self.redis_conn.clone();
+        let keys: Vec<String> = user_ids.iter().map(|id| cache_key(*id)).collect();
+        let mut found = HashMap::new();
+        match redis::cmd("MGET").arg(&keys).query_async::<_, Vec<Option<String>>>(&mut conn).await {
+            Ok(values) => {
+                for (user_id, value) in user_ids.iter().zip(values) {
+                    let cached = value.and_then(|value| serde_json::from_str::<Option<CachedStanding>>(&value).ok());
+                    if let Some(standing) = cached {
+                        found.insert(*user_id, standing);
+                    }
+                }
+            }
+            Err(e) => warn!("Reputation cache unavailable: {}", e),
+        }
+
+        let misses: Vec<Uuid> = user_ids.iter().copied().filter(|id| !found.contains_key(id)).collect();
+        if misses.is_empty() {
+            return Ok(found);
+        }
+        let rows: Vec<(Uuid, i32, f64, i32, bool)> = sqlx::query_as(
+            r#"
+            SELECT user_id, current_score, accuracy_rate::FLOAT8, total_submissions,
+                   probation_ended_at IS NULL
+            FROM user_reputation
+            WHERE user_id = ANY($1)
+            "#,
+        )
+        .bind(&misses)
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+        let mut loaded: HashMap<Uuid, CachedStanding> = rows
+            .into_iter()
+            .map(|(user_id, score, accuracy_rate, total_submissions, probation)| {
+                let standing = CachedStanding {
+                    score,
+                    accuracy_rate,
+                    total_submissions,
+                    categories: HashMap::new(),
+                    probation,
+                };
+                (user_id, standing)
+            })
+            .collect();
+        let category_scores: Vec<(Uuid, String, i32)> =
+            sqlx::query_as("SELECT user_id, category, score FROM user_category_reputation WHERE user_id = ANY($1)")
+                .bind(&misses)
+                .fetch_all(&self.db_pool)
+                .await
+                .map_err(db_error)?;
+        for (user_id, category, score) in category_scores {
+            if let Some(standing) = loaded.get_mut(&user_id) {
+                standing.categories.insert(category, score);
+            }
+        }
+
+        let mut pipe = redis::pipe();
+        for user_id in misses {
+            let standing = loaded.remove(&user_id);
+            if let Ok(value) = serde_json::to_string(&standing) {
+                pipe.cmd("SET")
+                    .arg(cache_key(user_id))
+                    .arg(value)
+                    .arg("EX")
+                    .arg(self.config.cache_ttl_secs)
+                    .ignore();
+            }
+            found.insert(user_id, standing);
+        }
+        if let Err(e) = pipe.query_async::<_, ()>(&mut conn).await {
+            warn!("Failed to cache reputation standings: {}", e);
+        }
+        Ok(found)
+    }
+}
@@ -0,0 +1,340 @@
+// Governance voting power
+//
+// Voting power is derived from the reputation score and the amount staked in
+// governance pools, both as of a point in time. Historic reputation comes from
+// `reputation_history` and historic stake from the staked/unstaked timestamps
+// on `user_stakes`. A block is resolved to the timestamp of the latest block
+// we have indexed at or below it. Snapshots freeze the result so a vote keeps
+// its weights even if history is later corrected.
+
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::config::GovernanceConfig;
+use crate::models::{ReputationError, ReputationResult, VotingPower, VotingPowerQuery, VotingPowerSnapshot};
+use crate::scoring::voting_power::VotingPowerCalculator;
+
+/// Users computed per query while building a snapshot
+const SNAPSHOT_BATCH_SIZE: usize = 500;
+
+/// The point in time a voting power result refers to
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SnapshotPoint {
+    pub at: DateTime<Utc>,
+    pub block_number: Option<i64>,
+    pub snapshot_id: Option<Uuid>,
+}
+
+#[derive(sqlx::FromRow)]
+struct VotingInputs {
+    user_id: Uuid,
+    reputation: i32,
+    staked: f64,
+}
+
+fn db_error(e: sqlx::Error) -> ReputationError {
+    ReputationError::DatabaseError(e.to_string())
+}
+
+pub struct VotingPowerService {
+    db_pool: PgPool,
+    calculator: VotingPowerCalculator,
+}
+
+impl VotingPowerService {
+    pub fn new(config: GovernanceConfig, db_pool: PgPool) -> Self {
+        Self {
+            db_pool,
+            calculator: VotingPowerCalculator::new(config),
+        }
+    }
+
+    pub fn config(&self) -> &GovernanceConfig {
+        self.calculator.config()
+    }
+
+    /// Resolve a query to a concrete point in time. Future points are
+    /// rejected so a result can never change after it is returned.
+    pub async fn resolve_point(&self, query: &VotingPowerQuery) -> ReputationResult<SnapshotPoint> {
+        let given = [query.timestamp.is_some(), query.block.is_some(), query.snapshot_id.is_some()]
+            .iter()
+            .filter(|set| **set)
+            .count();
+        if given > 1 {
+            return Err(ReputationError::ValidationError(
+                "Specify at most one of timestamp, block or snapshot_id".to_string(),
+            ));
+        }
+
+        if let Some(snapshot_id) = query.snapshot_id {
+            let snapshot = self.get_snapshot(snapshot_id).await?;
+            return Ok(SnapshotPoint {
+                at: snapshot.snapshot_at,
+                block_number: snapshot.block_number,
+                snapshot_id: Some(snapshot.id),
+            });
+        }
+
+        if let Some(block) = query.block {
+            let at = self.resolve_block(block).await?;
+            return Ok(SnapshotPoint { at, block_number: Some(block), snapshot_id: None });
+        }
+
+        let now = Utc::now();
+        let at = query.timestamp.unwrap_or(now);
+        if at > now {
+            return Err(ReputationError::ValidationError(
+                "Timestamp is in the future".to_string(),
+            ));
+        }
+        Ok(SnapshotPoint { at, block_number: None, snapshot_id: None })
+    }
+
+    async fn resolve_block(&self, block: i64) -> ReputationResult<DateTime<Utc>> {
+        if block < 0 {
+            return Err(ReputationError::ValidationError("Block number must not be negative".to_string()));
+        }
+
+        let (latest_block, at): (Option<i64>, Option<DateTime<Utc>>) = sqlx::query_as(
+            r#"
+            SELECT
+                MAX(bt.block_number),
+                MAX(bt.block_timestamp) FILTER (WHERE bt.block_number <= $2)
+            FROM blockchain_transactions bt
+            JOIN blockchain_networks n ON n.id = bt.network_id
+            WHERE n.chain_id = $1 AND bt.block_number IS NOT NULL
+            "#,
+        )
+        .bind(self.config().chain_id)
+        .bind(block)
+        .fetch_one(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+
+        if latest_block.is_none_or(|latest| latest < block) {
+            return Err(ReputationError::ValidationError(format!(
+                "Block {} has not been indexed yet",
+                block
+            )));
+        }
+        at.ok_or_else(|| ReputationError::NotFound(format!("No indexed block at or below {}", block)))
+    }
+
+    /// Voting power for each user at `point`, in the order requested.
+    /// Users unknown at that point are returned with zero power.
+    pub async fn voting_power(
+        &self,
+        user_ids: &[Uuid],
+        point: &SnapshotPoint,
+    ) -> ReputationResult<Vec<VotingPower>> {
+        match point.snapshot_id {
+            Some(snapshot_id) => self.snapshot_voting_power(snapshot_id, user_ids).await,
+            None => self.compute(user_ids, point.at).await,
+        }
+    }
+
+    async fn compute(&self, user_ids: &[Uuid], at: DateTime<Utc>) -> ReputationResult<Vec<VotingPower>> {
+        // Reputation at `at` is the score after the last change before it;
+        // with no earlier change it is the score before the first later one,
+        // or the current score if it never changed.
+        let inputs = sqlx::query_as::<_, VotingInputs>(
+            r#"
+            SELECT
+                u.user_id,
+                CASE WHEN r.user_id IS NULL THEN 0
+                     ELSE COALESCE(h_at.score_after, h_first.score_before, r.current_score)
+                END AS reputation,
+                COALESCE(st.staked, 0)::FLOAT8 AS staked
+            FROM UNNEST($1::uuid[]) WITH ORDINALITY AS u(user_id, ord)
+            LEFT JOIN user_reputation r
+                ON r.user_id = u.user_id AND r.created_at <= $2
+            LEFT JOIN LATERAL (
+                SELECT score_after FROM reputation_history h
+                WHERE h.user_id = u.user_id AND h.created_at <= $2
+                ORDER BY h.created_at DESC LIMIT 1
+            ) h_at ON TRUE
+            LEFT JOIN LATERAL (
+                SELECT score_before FROM reputation_history h
+                WHERE h.user_id = u.user_id
+                ORDER BY h.created_at ASC LIMIT 1
+            ) h_first ON TRUE
+            LEFT JOIN LATERAL (
+                SELECT SUM(s.staked_amount)::FLOAT8 AS staked
+                FROM user_stakes s
+                JOIN staking_pools p ON p.id = s.pool_id
+                WHERE s.user_id = u.user_id
+                  AND p.pool_type = ANY($3)
+                  AND s.staked_at <= $2
+                  AND (s.unstaked_at IS NULL OR s.unstaked_at > $2)
+            ) st ON TRUE
+            ORDER BY u.ord
+            "#,
+        )
+        .bind(user_ids)
+        .bind(at)
+        .bind(&self.config().stake_pool_types)
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(inputs
+            .into_iter()
+            .map(|i| self.calculator.calculate(i.user_id, i.reputation, i.staked))
+            .collect())
+    }
+
+    async fn snapshot_voting_power(
+        &self,
+        snapshot_id: Uuid,
+        user_ids: &[Uuid],
+    ) -> ReputationResult<Vec<VotingPower>> {
+        let entries = sqlx::query_as::<_, VotingPower>(
+            r#"
+            SELECT e.user_id, e.reputation, e.staked, e.reputation_power,
+                   e.stake_power, e.voting_power, TRUE AS eligible
+            FROM voting_power_snapshot_entries e
+            WHERE e.snapshot_id = $1 AND e.user_id = ANY($2)
+            "#,
+        )
+        .bind(snapshot_id)
+        .bind(user_ids)
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+
+        // Only eligible users are stored; everyone else had no power
+        Ok(user_ids
+            .iter()
+            .map(|user_id| {
+                entries
+                    .iter()
+                    .find(|e| e.user_id == *user_id)
+                    .cloned()
+                    .unwrap_or(VotingPower {
+                        user_id: *user_id,
+                        reputation: 0,
+                        staked: 0.0,
+                        reputation_power: 0.0,
+                        stake_power: 0.0,
+                        voting_power: 0.0,
+                        eligible: false,
+                    })
+            })
+            .collect())
+    }
+
+    /// Freeze the voting power of every user with reputation or stake at
+    /// `point`. Only users with non-zero power are stored.
+    pub async fn create_snapshot(
+        &self,
+        label: &str,
+        point: &SnapshotPoint,
+    ) -> ReputationResult<VotingPowerSnapshot> {
+        let candidates: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT user_id FROM user_reputation WHERE created_at <= $1
+            UNION
+            SELECT s.user_id
+            FROM user_stakes s
+            JOIN staking_pools p ON p.id = s.pool_id
+            WHERE p.pool_type = ANY($2)
+              AND s.staked_at <= $1
+              AND (s.unstaked_at IS NULL OR s.unstaked_at > $1)
+            "#,
+        )
+        .bind(point.at)
+        .bind(&self.config().stake_pool_types)
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+
+        let formula = serde_json::to_value(self.config())
+            .map_err(|e| ReputationError::CalculationError(e.to_string()))?;
+
+        let mut tx = self.db_pool.begin().await.map_err(db_error)?;
+
+        let snapshot_id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO voting_power_snapshots (label, snapshot_at, block_number, formula)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id
+            "#,
+        )
+        .bind(label)
+        .bind(point.at)
+        .bind(point.block_number)
+        .bind(&formula)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(db_error)?;
+
+        for batch in candidates.chunks(SNAPSHOT_BATCH_SIZE) {
+            let powers: Vec<VotingPower> = self
+                .compute(batch, point.at)
+                .await?
+                .into_iter()
+                .filter(|p| p.voting_power > 0.0)
+                .collect();
+            if powers.is_empty() {
+                continue;
+            }
+
+            sqlx::query(
+                r#"
+                INSERT INTO voting_power_snapshot_entries
+                    (snapshot_id, user_id, reputation, staked, reputation_power, stake_power, voting_power)
+                SELECT $1, * FROM UNNEST($2::uuid[], $3::int4[], $4::float8[], $5::float8[], $6::float8[], $7::float8[])
+                "#,
+            )
+            .bind(snapshot_id)
+            .bind(powers.iter().map(|p| p.user_id).collect::<Vec<_>>())
+            .bind(powers.iter().map(|p| p.reputation).collect::<Vec<_>>())
+            .bind(powers.iter().map(|p| p.staked).collect::<Vec<_>>())
+            .bind(powers.iter().map(|p| p.reputation_power).collect::<Vec<_>>())
+            .bind(powers.iter().map(|p| p.stake_power).collect::<Vec<_>>())
+            .bind(powers.iter().map(|p| p.voting_power).collect::<Vec<_>>())
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        }
+
+        let snapshot = sqlx::query_as::<_, VotingPowerSnapshot>(
+            r#"
+            UPDATE voting_power_snapshots s
+            SET voter_count = totals.voter_count,
+                total_voting_power = totals.total_voting_power
+            FROM (
+                SELECT COUNT(*)::INT4 AS voter_count,
+                       COALESCE(SUM(voting_power), 0)::FLOAT8 AS total_voting_power
+                FROM voting_power_snapshot_entries
+                WHERE snapshot_id = $1
+            ) totals
+            WHERE s.id = $1
+            RETURNING s.*
+            "#,
+        )
+        .bind(snapshot_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(db_error)?;
+
+        tx.commit().await.map_err(db_error)?;
+
+        info!(
+            "Created voting power snapshot {} at {} with {} voters",
+            snapshot.id, snapshot.snapshot_at, snapshot.voter_count
+        );
+        Ok(snapshot)
+    }
+
+    pub async fn get_snapshot(&self, snapshot_id: Uuid) -> ReputationResult<VotingPowerSnapshot> {
+        sqlx::query_as::<_, VotingPowerSnapshot>("SELECT * FROM voting_power_snapshots WHERE id = $1")
+            .bind(snapshot_id)
+            .fetch_optional(&self.db_pool)
+            .await
+            .map_err(db_error)?
+            .ok_or_else(|| ReputationError::NotFound(format!("Snapshot {}", snapshot_id)))
+    }
+}
@@ -0,0 +1,445 @@
+// Reputation webhooks
+//
+// Users register HTTPS endpoints to follow changes in their own standing:
+// score changes, leaderboard rank moves, badges and streak milestones.
+// Events are queued in `reputation_webhook_deliveries` and sent by the
+// webhook dispatcher, retried with exponential backoff until
+// `max_attempts`. Each delivery is signed with the webhook's secret, as for
+// bounty webhooks (`shared::webhooks`).
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use shared::webhooks::{self, sign};
+use sqlx::{PgExecutor, PgPool};
+use std::time::Duration;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::config::WebhookConfig;
+use crate::models::{ReputationError, ReputationResult};
+
+/// Deliveries per page when no limit is asked for
+const DEFAULT_DELIVERY_LIMIT: i64 = 20;
+
+/// Most deliveries per page
+const MAX_DELIVERY_LIMIT: i64 = 100;
+
+/// Reputation events a webhook can subscribe to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReputationWebhookEvent {
+    ScoreChanged,
+    RankChanged,
+    BadgeAwarded,
+    StreakMilestone,
+}
+
+impl ReputationWebhookEvent {
+    /// Name stored in `reputation_webhooks.events` and sent as
+    /// `X-Nexus-Event`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReputationWebhookEvent::ScoreChanged => "score_changed",
+            ReputationWebhookEvent::RankChanged => "rank_changed",
+            ReputationWebhookEvent::BadgeAwarded => "badge_awarded",
+            ReputationWebhookEvent::StreakMilestone => "streak_milestone",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ReputationWebhook {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub url: String,
+    /// Only shown once, when the webhook is registered
+    #[serde(skip_serializing)]
+    pub secret: String,
+    pub events: Vec<String>,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A newly registered webhook with the secret its deliveries are signed
+/// with; the secret is not shown again
+#[derive(Debug, Serialize)]
+pub struct RegisteredWebhook {
+    #[serde(flatten)]
+    pub webhook: ReputationWebhook,
+    pub secret: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RegisterWebhookRequest {
+    /// HTTPS endpoint deliveries are POSTed to
+    pub url: String,
+    pub events: Vec<ReputationWebhookEvent>,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    /// `pending`, `delivered` or `failed` once retries are exhausted
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, thiserror::Error)]
+enum SendError {
+    /// The endpoint answered with a non-2xx status
+    #[error("endpoint responded {0}")]
+    Rejected(u16),
+
+    #[error("endpoint unreachable: {0}")]
+    Unreachable(String),
+}
+
+impl SendError {
+    fn response_status(&self) -> Option<i32> {
+        match self {
+            SendError::Rejected(status) => Some(i32::from(*status)),
+            SendError::Unreachable(_) => None,
+        }
+    }
+}
+
+fn db_error(e: sqlx::Error) -> ReputationError {
+    ReputationError::DatabaseError(e.to_string())
+}
+
+pub struct WebhookService {
+    config: WebhookConfig,
+    db_pool: PgPool,
+    http: reqwest::Client,
+}
+
+impl WebhookService {
+    pub fn new(config: WebhookConfig, db_pool: PgPool) -> anyhow::Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            // A redirect could carry the signed payload somewhere the user
+            // did not register
+            .redirect(reqwest::redirect::Policy::none())
+            .build()?;
+        Ok(Self { config, db_pool, http })
+    }
+
+    pub fn config(&self) -> &WebhookConfig {
+        &self.config
+    }
+
+    /// Subscribe a URL to changes in the user's standing
+    pub async fn register(&self, user_id: Uuid, request: RegisterWebhookRequest) -> ReputationResult<RegisteredWebhook> {
+        webhooks::validate(&request.url)
+            .await
+            .map_err(|e| ReputationError::ValidationError(e.to_string()))?;
+        let mut events: Vec<String> = Vec::new();
+        for event in &request.events {
+            let name = event.as_str().to_string();
+            if !events.contains(&name) {
+                events.push(name);
+            }
+        }
+        if events.is_empty() {
+            return Err(ReputationError::ValidationError("Subscribe to at least one event".to_string()));
+        }
+
+        let registered: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM reputation_webhooks WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_one(&self.db_pool)
+            .await
+            .map_err(db_error)?;
+        if registered >= self.config.max_per_user {
+            return Err(ReputationError::ValidationError(format!(
+                "At most {} webhooks per user",
+                self.config.max_per_user
+            )));
+        }
+
+        let secret = format!("whsec_{}", hex::encode(rand::random::<[u8; 32]>()));
+        let webhook: ReputationWebhook = sqlx::query_as(
+            r#"
+            INSERT INTO reputation_webhooks (id, user_id, url, secret, events)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(&request.url)
+        .bind(&secret)
+        .bind(&events)
+        .fetch_one(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+        Ok(RegisteredWebhook { webhook, secret })
+    }
+
+    pub async fn list(&self, user_id: Uuid) -> ReputationResult<Vec<ReputationWebhook>> {
+        sqlx::query_as("SELECT * FROM reputation_webhooks WHERE user_id = $1 ORDER BY created_at")
+            .bind(user_id)
+            .fetch_all(&self.db_pool)
+            .await
+            .map_err(db_error)
+    }
+
+    /// Remove one of the user's webhooks; its queued deliveries go with it
+    pub async fn delete(&self, user_id: Uuid, webhook_id: Uuid) -> ReputationResult<()> {
+        let result = sqlx::query("DELETE FROM reputation_webhooks WHERE id = $1 AND user_id = $2")
+            .bind(webhook_id)
+            .bind(user_id)
+            .execute(&self.db_pool)
+            .await
+            .map_err(db_error)?;
+        if result.rows_affected() == 0 {
+            return Err(ReputationError::NotFound(format!("Webhook {}", webhook_id)));
+        }
+        Ok(())
+    }
+
+    /// One of the user's webhooks' deliveries, newest first
+    pub async fn deliveries(
+        &self,
+        user_id: Uuid,
+        webhook_id: Uuid,
+        page: Option<i64>,
+        limit: Option<i64>,
+    ) -> ReputationResult<Vec<WebhookDelivery>> {
+        let owned: bool =
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM reputation_webhooks WHERE id = $1 AND user_id = $2)")
+                .bind(webhook_id)
+                .bind(user_id)
+                .fetch_one(&self.db_pool)
+                .await
+                .map_err(db_error)?;
+        if !owned {
+            return Err(ReputationError::NotFound(format!("Webhook {}", webhook_id)));
+        }
+
+        let limit = limit.unwrap_or(DEFAULT_DELIVERY_LIMIT).clamp(1, MAX_DELIVERY_LIMIT);
+        let offset = (page.unwrap_or(1).max(1) - 1) * limit;
+        sqlx::query_as(
+            r#"
+            SELECT * FROM reputation_webhook_deliveries
+            WHERE webhook_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(webhook_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(db_error)
+    }
+
+    /// Queue an event for every enabled webhook of the user subscribed to
+    /// it. Run inside the transaction that records the event where there is
+    /// one, so the event is queued if and only if it happened.
+    pub async fn enqueue<'e, E: PgExecutor<'e>>(
+        executor: E,
+        user_id: Uuid,
+        event: ReputationWebhookEvent,
+        payload: &serde_json::Value,
+    ) -> ReputationResult<u64> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO reputation_webhook_deliveries (id, webhook_id, event_type, payload)
+            SELECT gen_random_uuid(), w.id, $2, $3
+            FROM reputation_webhooks w
+            WHERE w.user_id = $1 AND w.enabled AND $2 = ANY(w.events)
+            "#,
+        )
+        .bind(user_id)
+        .bind(event.as_str())
+        .bind(payload)
+        .execute(executor)
+        .await
+        .map_err(db_error)?;
+        Ok(result.rows_affected())
+    }
+
+    /// Send the deliveries that are due; returns how many were delivered
+    pub async fn deliver_due(&self) -> ReputationResult<usize> {
+        let now = Utc::now();
+        let mut tx = self.db_pool.begin().await.map_err(db_error)?;
+        let due: Vec<WebhookDelivery> = sqlx::query_as(
+            r#"
+            SELECT * FROM reputation_webhook_deliveries
+            WHERE status = 'pending' AND next_attempt_at <= $1
+            ORDER BY next_attempt_at
+            LIMIT $2
+            FOR UPDATE SKIP LOCKED
+            "#,
+        )
+        .bind(now)
+        .bind(self.config.batch_size)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(db_error)?;
+
+        // Claim the batch for a minute; a crashed dispatcher's claim then
+        // lapses
+        let ids: Vec<Uuid> = due.iter().map(|d| d.id).collect();
+        sqlx::query(
+            "UPDATE reputation_webhook_deliveries SET next_attempt_at = $2 + INTERVAL '1 minute' WHERE id = ANY($1)",
+        )
+        .bind(&ids)
+        .bind(now)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        let webhooks: Vec<ReputationWebhook> = sqlx::query_as(
+            r#"
+            SELECT * FROM reputation_webhooks
+            WHERE id IN (SELECT webhook_id FROM reputation_webhook_deliveries WHERE id = ANY($1))
+            "#,
+        )
+        .bind(&ids)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        tx.commit().await.map_err(db_error)?;
+
+        let mut delivered = 0;
+        for delivery in &due {
+            let Some(webhook) = webhooks.iter().find(|w| w.id == delivery.webhook_id) else {
+                continue;
+            };
+            if self.deliver(delivery, webhook).await? {
+                delivered += 1;
+            }
+        }
+        Ok(delivered)
+    }
+
+    /// Send one delivery and record the outcome; `true` if it arrived
+    async fn deliver(&self, delivery: &WebhookDelivery, webhook: &ReputationWebhook) -> ReputationResult<bool> {
+        if !webhook.enabled {
+            // Events queued before the webhook was disabled are dropped
+            self.mark_failed(delivery.id, None, "webhook disabled", None).await?;
+            return Ok(false);
+        }
+
+        match self.send(delivery, webhook).await {
+            Ok(status) => {
+                sqlx::query(
+                    r#"
+                    UPDATE reputation_webhook_deliveries
+                    SET status = 'delivered', attempts = attempts + 1, response_status = $2,
+                        last_error = NULL, delivered_at = NOW()
+                    WHERE id = $1
+                    "#,
+                )
+                .bind(delivery.id)
+                .bind(i32::from(status))
+                .execute(&self.db_pool)
+                .await
+                .map_err(db_error)?;
+                Ok(true)
+            }
+            Err(e) => {
+                let attempts = delivery.attempts.max(0) as u32 + 1;
+                let retry_at = (attempts < self.config.max_attempts).then(|| {
+                    let delay = self.retry_delay(attempts);
+                    Utc::now() + chrono::Duration::seconds(delay.as_secs() as i64)
+                });
+                warn!(
+                    "Reputation webhook delivery {} to {} failed on attempt {}: {}",
+                    delivery.id, webhook.url, attempts, e
+                );
+                self.mark_failed(delivery.id, e.response_status(), &e.to_string(), retry_at)
+                    .await?;
+                Ok(false)
+            }
+        }
+    }
+
+    /// POST a delivery to its webhook; the endpoint's status on success
+    async fn send(&self, delivery: &WebhookDelivery, webhook: &ReputationWebhook) -> Result<u16, SendError> {
+        let body = serde_json::to_vec(&serde_json::json!({
+            "id": delivery.id,
+            "event": delivery.event_type,
+            "user_id": webhook.user_id,
+            "created_at": delivery.created_at,
+            "data": delivery.payload,
+        }))
+        .map_err(|e| SendError::Unreachable(e.to_string()))?;
+        // The host was public when the webhook was registered; DNS may have
+        // changed since
+        let url = webhooks::parse(&webhook.url).map_err(|e| SendError::Unreachable(e.to_string()))?;
+        webhooks::check_resolved(&url)
+            .await
+            .map_err(|e| SendError::Unreachable(e.to_string()))?;
+        let timestamp = Utc::now().timestamp();
+
+        let response = self
+            .http
+            .post(url)
+            .header("content-type", "application/json")
+            .header("x-nexus-event", &delivery.event_type)
+            .header("x-nexus-delivery", delivery.id.to_string())
+            .header("x-nexus-timestamp", timestamp.to_string())
+            .header("x-nexus-signature", sign(&webhook.secret, timestamp, &body))
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| SendError::Unreachable(e.to_string()))?;
+
+        let status = response.status();
+        if status.is_success() {
+            Ok(status.as_u16())
+        } else {
+            Err(SendError::Rejected(status.as_u16()))
+        }
+    }
+
+    /// Record a failed attempt: retry at `retry_at`, or give up if `None`
+    async fn mark_failed(
+        &self,
+        id: Uuid,
+        response_status: Option<i32>,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> ReputationResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE reputation_webhook_deliveries
+            SET attempts = attempts + 1,
+                status = CASE WHEN $4::TIMESTAMPTZ IS NULL THEN 'failed' ELSE 'pending' END,
+                next_attempt_at = COALESCE($4, next_attempt_at),
+                response_status = $2,
+                last_error = $3
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(response_status)
+        .bind(error)
+        .bind(retry_at)
+        .execute(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+        Ok(())
+    }
+
+    /// Wait before retrying after `attempts` failed attempts: doubling from
+    /// the base, capped at the maximum
+    fn retry_delay(&self, attempts: u32) -> Duration {
+        webhooks::retry_delay(
+            attempts,
+            Duration::from_secs(self.config.backoff_base_secs),
+            Duration::from_secs(self.config.backoff_max_secs),
+        )
+    }
+}
@@ -0,0 +1,23 @@
+use anyhow::Result;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::services::appeals::AppealService;
+
+/// Appeal resolver: closes arbitration panels whose voting deadline has
+/// passed, so an appeal waits at most one interval past it.
+pub async fn start(service: Arc<AppealService>) -> Result<()> {
+    let interval_secs = service.config().check_interval_secs;
+    info!("Appeal resolver worker started (every {}s)", interval_secs);
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(interval_secs));
+
+    loop {
+        interval.tick().await;
+
+        match service.close_expired_panels().await {
+            Ok(0) => {}
+            Ok(closed) => info!("Closed {} expired appeal panel(s)", closed),
+            Err(e) => warn!("Closing expired appeal panels failed: {}", e),
+        }
+    }
+}
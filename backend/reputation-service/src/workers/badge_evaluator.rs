@@ -0,0 +1,23 @@
+use anyhow::Result;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::services::badges::BadgeService;
+
+/// Badge evaluator: awards badges to users whose reputation changed since
+/// they were last evaluated, draining the backlog a batch at a time.
+pub async fn start(service: Arc<BadgeService>) -> Result<()> {
+    let interval_secs = service.config().evaluation_interval_secs;
+    info!("Badge evaluator worker started (every {}s)", interval_secs);
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(interval_secs));
+
+    loop {
+        interval.tick().await;
+
+        match service.evaluate_pending().await {
+            Ok(0) => {}
+            Ok(awarded) => info!("Awarded {} badge(s)", awarded),
+            Err(e) => warn!("Badge evaluation failed: {}", e),
+        }
+    }
+}
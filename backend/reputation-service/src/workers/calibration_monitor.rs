@@ -0,0 +1,23 @@
+use anyhow::Result;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::services::calibration::CalibrationService;
+
+/// Calibration monitor: checks engines' new calibration results for a fall
+/// in accuracy, a batch per pass.
+pub async fn start(service: Arc<CalibrationService>) -> Result<()> {
+    let interval_secs = service.config().check_interval_secs;
+    info!("Calibration monitor worker started (every {}s)", interval_secs);
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(interval_secs));
+
+    loop {
+        interval.tick().await;
+
+        match service.check().await {
+            Ok(0) => {}
+            Ok(checked) => info!("Checked {} calibration result(s)", checked),
+            Err(e) => warn!("Checking calibration results failed: {}", e),
+        }
+    }
+}
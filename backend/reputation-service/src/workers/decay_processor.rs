@@ -1,13 +1,16 @@
 use anyhow::Result;
 use std::sync::Arc;
-use tracing::info;
+use tracing::{info, warn};
 
-use crate::services::reputation_service::ReputationService;
+use crate::services::decay::DecayService;
 
-pub async fn start(_service: Arc<ReputationService>) -> Result<()> {
+pub async fn start(service: Arc<DecayService>) -> Result<()> {
     info!("Decay processor worker started");
     loop {
         tokio::time::sleep(tokio::time::Duration::from_secs(3600)).await;
         // Apply time decay to inactive users
+        if let Err(e) = service.run().await {
+            warn!("Decay pass failed: {}", e);
+        }
     }
 }
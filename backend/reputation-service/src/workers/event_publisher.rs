@@ -0,0 +1,26 @@
+use anyhow::Result;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::services::events::ReputationEventService;
+
+/// Event publisher: announces score changes and streak milestones recorded
+/// since the last pass, then the leaderboard moves they caused.
+pub async fn start(service: Arc<ReputationEventService>) -> Result<()> {
+    let interval_secs = service.config().interval_secs;
+    info!("Reputation event publisher started (every {}s)", interval_secs);
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(interval_secs));
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = service.announce_score_changes().await {
+            warn!("Announcing score changes failed: {}", e);
+        }
+        match service.announce_rank_changes().await {
+            Ok(0) => {}
+            Ok(moves) => info!("Announced {} leaderboard move(s)", moves),
+            Err(e) => warn!("Announcing rank changes failed: {}", e),
+        }
+    }
+}
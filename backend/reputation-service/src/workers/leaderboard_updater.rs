@@ -1,13 +1,35 @@
 use anyhow::Result;
 use std::sync::Arc;
-use tracing::info;
+use tracing::{info, warn};
 
-use crate::services::reputation_service::ReputationService;
+use crate::services::leaderboard::LeaderboardService;
+use crate::services::seasons::SeasonService;
+
+/// Leaderboard updater: adds new score changes to the Redis leaderboards,
+/// and archives the season once its quarter is over and starts the next, so
+/// a season closes at most one check interval late.
+pub async fn start(seasons: Arc<SeasonService>, leaderboard: Arc<LeaderboardService>) -> Result<()> {
+    let season_secs = seasons.config().check_interval_secs;
+    let ranking_secs = leaderboard.config().interval_secs;
+    info!(
+        "Leaderboard updater worker started (rankings every {}s, seasons every {}s)",
+        ranking_secs, season_secs
+    );
+    let mut season_interval = tokio::time::interval(tokio::time::Duration::from_secs(season_secs));
+    let mut ranking_interval = tokio::time::interval(tokio::time::Duration::from_secs(ranking_secs));
 
-pub async fn start(_service: Arc<ReputationService>) -> Result<()> {
-    info!("Leaderboard updater worker started");
     loop {
-        tokio::time::sleep(tokio::time::Duration::from_secs(300)).await;
-        // Update leaderboard rankings
+        tokio::select! {
+            _ = season_interval.tick() => match seasons.roll_over().await {
+                Ok(Some(season)) => info!("Season {} closed", season),
+                Ok(None) => {}
+                Err(e) => warn!("Season roll-over failed: {}", e),
+            },
+            _ = ranking_interval.tick() => match leaderboard.update().await {
+                Ok(0) => {}
+                Ok(ranked) => info!("Ranked {} score change(s)", ranked),
+                Err(e) => warn!("Leaderboard update failed: {}", e),
+            },
+        }
     }
 }
@@ -1,3 +1,9 @@
 pub mod reputation_calculator;
 pub mod decay_processor;
 pub mod leaderboard_updater;
+pub mod settlement_listener;
+pub mod badge_evaluator;
+pub mod event_publisher;
+pub mod webhook_dispatcher;
+pub mod appeal_resolver;
+pub mod calibration_monitor;
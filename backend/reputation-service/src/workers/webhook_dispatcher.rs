@@ -0,0 +1,21 @@
+use anyhow::Result;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::services::webhooks::WebhookService;
+
+/// Webhook dispatcher: sends queued reputation events to users' webhooks,
+/// retrying failed deliveries with backoff.
+pub async fn start(service: Arc<WebhookService>) -> Result<()> {
+    let interval_secs = service.config().interval_secs;
+    info!("Reputation webhook dispatcher started (every {}s)", interval_secs);
+    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(interval_secs));
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = service.deliver_due().await {
+            warn!("Reputation webhook delivery failed: {}", e);
+        }
+    }
+}
@@ -0,0 +1,534 @@
+//! Fault injection for resilience testing
+//!
+//! Lets a service add latency, fail requests or drop responses at
+//! configurable rates so retries, timeouts and circuit breakers can be
+//! exercised against a running mesh. Rules target either an inbound route
+//! (through [`chaos_middleware`]) or a named downstream client (through
+//! [`FaultInjector::intercept`]).
+//!
+//! Injection is off unless `CHAOS_ENABLED=true`, and it refuses to start when
+//! `ENVIRONMENT` is `production`. Rules are a JSON array in `CHAOS_RULES`:
+//!
+//! ```text
+//! CHAOS_RULES='[
+//!   {"route": "/analyze/*", "method": "POST", "latency_rate": 0.5, "latency_ms": [200, 2000]},
+//!   {"route": "/api/v1/reputation/:user_id", "error_rate": 0.2, "error_status": 503},
+//!   {"client": "callbacks", "drop_rate": 0.1}
+//! ]'
+//! ```
+//!
+//! Route patterns use `:name` for one path segment and a trailing `*` for the
+//! rest of the path. The first matching rule applies. `CHAOS_SEED` makes the
+//! dice reproducible and `CHAOS_DROP_HOLD_MS` sets how long a dropped
+//! response keeps the connection waiting (30 seconds by default).
+
+use parking_lot::Mutex;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+
+pub const ENABLED_ENV: &str = "CHAOS_ENABLED";
+pub const RULES_ENV: &str = "CHAOS_RULES";
+pub const SEED_ENV: &str = "CHAOS_SEED";
+pub const DROP_HOLD_ENV: &str = "CHAOS_DROP_HOLD_MS";
+/// Response header naming the fault injected into a request
+pub const FAULT_HEADER: &str = "x-chaos-fault";
+
+const DEFAULT_DROP_HOLD: Duration = Duration::from_secs(30);
+const DEFAULT_ERROR_STATUS: u16 = 503;
+
+#[derive(Debug, Error)]
+pub enum ChaosError {
+    #[error("Invalid {RULES_ENV}: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error("Invalid fault rule {index}: {reason}")]
+    InvalidRule { index: usize, reason: String },
+    #[error("Fault injection cannot be enabled in production")]
+    Production,
+}
+
+/// What a rule applies to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FaultTarget {
+    /// Inbound requests whose path matches the pattern
+    Route(String),
+    /// Outbound calls made through a named client
+    Client(String),
+}
+
+/// Faults to inject for one route or client
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FaultRule {
+    #[serde(flatten)]
+    pub target: FaultTarget,
+    /// Limits a route rule to one HTTP method
+    #[serde(default)]
+    pub method: Option<String>,
+    /// Share of requests that are delayed
+    #[serde(default)]
+    pub latency_rate: f64,
+    /// Delay range in milliseconds, inclusive
+    #[serde(default)]
+    pub latency_ms: (u64, u64),
+    /// Share of requests answered with `error_status` without being handled
+    #[serde(default)]
+    pub error_rate: f64,
+    #[serde(default = "default_error_status")]
+    pub error_status: u16,
+    /// Share of requests that are handled but whose response is lost
+    #[serde(default)]
+    pub drop_rate: f64,
+}
+
+fn default_error_status() -> u16 {
+    DEFAULT_ERROR_STATUS
+}
+
+impl FaultRule {
+    fn validate(&self) -> Result<(), String> {
+        let target = match &self.target {
+            FaultTarget::Route(pattern) => pattern,
+            FaultTarget::Client(name) => name,
+        };
+        if target.is_empty() {
+            return Err("empty target".to_string());
+        }
+        for (name, rate) in [
+            ("latency_rate", self.latency_rate),
+            ("error_rate", self.error_rate),
+            ("drop_rate", self.drop_rate),
+        ] {
+            if !(0.0..=1.0).contains(&rate) {
+                return Err(format!("{} must be between 0 and 1", name));
+            }
+        }
+        if self.latency_ms.0 > self.latency_ms.1 {
+            return Err("latency_ms must be [min, max]".to_string());
+        }
+        if self.latency_rate > 0.0 && self.latency_ms.1 == 0 {
+            return Err("latency_rate needs a latency_ms range".to_string());
+        }
+        if !(400..=599).contains(&self.error_status) {
+            return Err("error_status must be a 4xx or 5xx status".to_string());
+        }
+        Ok(())
+    }
+
+    fn matches_route(&self, method: &str, path: &str) -> bool {
+        let FaultTarget::Route(pattern) = &self.target else {
+            return false;
+        };
+        self.method.as_deref().is_none_or(|m| m.eq_ignore_ascii_case(method))
+            && pattern_matches(pattern, path)
+    }
+
+    fn matches_client(&self, client: &str) -> bool {
+        matches!(&self.target, FaultTarget::Client(name) if name == client)
+    }
+}
+
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let mut pattern_segments = pattern.split('/').filter(|s| !s.is_empty());
+    let mut path_segments = path.split('/').filter(|s| !s.is_empty());
+
+    loop {
+        match (pattern_segments.next(), path_segments.next()) {
+            (Some("*"), _) => return true,
+            (Some(p), Some(s)) if p.starts_with(':') || p == s => continue,
+            (None, None) => return true,
+            _ => return false,
+        }
+    }
+}
+
+/// Settings of a fault injector
+#[derive(Debug, Clone, Default)]
+pub struct ChaosConfig {
+    pub rules: Vec<FaultRule>,
+    /// Seed for reproducible runs; random when `None`
+    pub seed: Option<u64>,
+    /// How long a dropped response holds the connection before giving up
+    pub drop_hold: Option<Duration>,
+}
+
+impl ChaosConfig {
+    /// Parse and validate a JSON array of rules
+    pub fn parse_rules(json: &str) -> Result<Vec<FaultRule>, ChaosError> {
+        let rules: Vec<FaultRule> = serde_json::from_str(json)?;
+        for (index, rule) in rules.iter().enumerate() {
+            rule.validate()
+                .map_err(|reason| ChaosError::InvalidRule { index, reason })?;
+        }
+        Ok(rules)
+    }
+
+    /// Read the configuration from the environment. Returns `None` when
+    /// injection is not enabled and an error when it is enabled in
+    /// production or the rules are invalid.
+    pub fn from_env() -> Result<Option<Self>, ChaosError> {
+        let enabled = std::env::var(ENABLED_ENV).is_ok_and(|v| v == "true");
+        if !enabled {
+            return Ok(None);
+        }
+        if is_production(std::env::var("ENVIRONMENT").ok().as_deref()) {
+            return Err(ChaosError::Production);
+        }
+
+        let rules = match std::env::var(RULES_ENV) {
+            Ok(json) if !json.trim().is_empty() => Self::parse_rules(&json)?,
+            _ => Vec::new(),
+        };
+        let seed = std::env::var(SEED_ENV).ok().and_then(|v| v.parse().ok());
+        let drop_hold = std::env::var(DROP_HOLD_ENV)
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .map(Duration::from_millis);
+
+        Ok(Some(Self { rules, seed, drop_hold }))
+    }
+}
+
+fn is_production(environment: Option<&str>) -> bool {
+    environment.is_some_and(|env| matches!(env.to_ascii_lowercase().as_str(), "production" | "prod"))
+}
+
+/// What to do to one request or call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fault {
+    /// Added before the request is handled
+    pub delay: Option<Duration>,
+    pub action: FaultAction,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultAction {
+    /// Handle the request normally
+    Proceed,
+    /// Answer with this status without handling the request
+    Fail(u16),
+    /// Handle the request but lose the response
+    Drop,
+}
+
+impl Fault {
+    fn label(&self) -> &'static str {
+        match self.action {
+            FaultAction::Fail(_) => "error",
+            FaultAction::Drop => "drop",
+            FaultAction::Proceed => "latency",
+        }
+    }
+}
+
+/// Fault injected into an outbound call
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum InjectedFault {
+    #[error("Injected fault: HTTP {0}")]
+    Status(u16),
+    #[error("Injected fault: response dropped")]
+    Dropped,
+}
+
+struct Injector {
+    rules: Vec<FaultRule>,
+    rng: Mutex<StdRng>,
+    drop_hold: Duration,
+}
+
+/// Decides which faults to inject; cheap to clone. A disabled injector
+/// never injects anything.
+#[derive(Clone, Default)]
+pub struct FaultInjector {
+    inner: Option<Arc<Injector>>,
+}
+
+impl FaultInjector {
+    pub fn new(config: ChaosConfig) -> Self {
+        let rng = match config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        Self {
+            inner: Some(Arc::new(Injector {
+                rules: config.rules,
+                rng: Mutex::new(rng),
+                drop_hold: config.drop_hold.unwrap_or(DEFAULT_DROP_HOLD),
+            })),
+        }
+    }
+
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Injector configured from the environment. Invalid settings are
+    /// logged and leave injection disabled.
+    pub fn from_env() -> Self {
+        match ChaosConfig::from_env() {
+            Ok(Some(config)) => {
+                tracing::warn!(rules = config.rules.len(), "Fault injection enabled");
+                Self::new(config)
+            }
+            Ok(None) => Self::disabled(),
+            Err(e) => {
+                tracing::error!("Fault injection disabled: {}", e);
+                Self::disabled()
+            }
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.inner.as_ref().is_some_and(|inner| !inner.rules.is_empty())
+    }
+
+    /// How long a dropped response holds the connection
+    pub fn drop_hold(&self) -> Duration {
+        self.inner.as_ref().map_or(DEFAULT_DROP_HOLD, |inner| inner.drop_hold)
+    }
+
+    /// Fault for an inbound request, if one should be injected
+    pub fn for_route(&self, method: &str, path: &str) -> Option<Fault> {
+        let inner = self.inner.as_ref()?;
+        let rule = inner.rules.iter().find(|r| r.matches_route(method, path))?;
+        inner.roll(rule)
+    }
+
+    /// Fault for an outbound call through `client`, if one should be injected
+    pub fn for_client(&self, client: &str) -> Option<Fault> {
+        let inner = self.inner.as_ref()?;
+        let rule = inner.rules.iter().find(|r| r.matches_client(client))?;
+        inner.roll(rule)
+    }
+
+    /// Run an outbound call through `client`'s faults. A failed call is not
+    /// made at all; a dropped one is made and its result discarded, which
+    /// callers should treat like a timeout.
+    pub async fn intercept<F: Future>(&self, client: &str, call: F) -> Result<F::Output, InjectedFault> {
+        let Some(fault) = self.for_client(client) else {
+            return Ok(call.await);
+        };
+        tracing::warn!(client, fault = fault.label(), "Injecting fault into outbound call");
+
+        if let Some(delay) = fault.delay {
+            tokio::time::sleep(delay).await;
+        }
+        match fault.action {
+            FaultAction::Proceed => Ok(call.await),
+            FaultAction::Fail(status) => Err(InjectedFault::Status(status)),
+            FaultAction::Drop => {
+                let _ = call.await;
+                Err(InjectedFault::Dropped)
+            }
+        }
+    }
+}
+
+impl Injector {
+    fn roll(&self, rule: &FaultRule) -> Option<Fault> {
+        let mut rng = self.rng.lock();
+
+        let delay = (rule.latency_rate > 0.0 && rng.gen_bool(rule.latency_rate))
+            .then(|| Duration::from_millis(rng.gen_range(rule.latency_ms.0..=rule.latency_ms.1)));
+        let action = if rule.drop_rate > 0.0 && rng.gen_bool(rule.drop_rate) {
+            FaultAction::Drop
+        } else if rule.error_rate > 0.0 && rng.gen_bool(rule.error_rate) {
+            FaultAction::Fail(rule.error_status)
+        } else {
+            FaultAction::Proceed
+        };
+
+        (delay.is_some() || action != FaultAction::Proceed).then_some(Fault { delay, action })
+    }
+}
+
+// ─── axum integration ───
+
+#[cfg(feature = "axum")]
+mod http {
+    use axum::{
+        extract::{Request, State},
+        http::{HeaderValue, StatusCode},
+        middleware::Next,
+        response::{IntoResponse, Response},
+        Json,
+    };
+
+    use super::*;
+
+    fn injected(status: StatusCode, fault: &Fault) -> Response {
+        let mut response = (
+            status,
+            Json(serde_json::json!({ "error": "Injected fault", "fault": fault.label() })),
+        )
+            .into_response();
+        response
+            .headers_mut()
+            .insert(FAULT_HEADER, HeaderValue::from_static(fault.label()));
+        response
+    }
+
+    /// Applies route rules to inbound requests. Install it with
+    /// `axum::middleware::from_fn_with_state(FaultInjector::from_env(), chaos_middleware)`;
+    /// it passes requests straight through when injection is disabled.
+    ///
+    /// A dropped response is produced by the handler and then withheld: the
+    /// connection stays open for the configured hold time and is answered
+    /// with 504, by which point well-behaved clients have timed out.
+    pub async fn chaos_middleware(
+        State(injector): State<FaultInjector>,
+        request: Request,
+        next: Next,
+    ) -> Response {
+        let Some(fault) = injector.for_route(request.method().as_str(), request.uri().path()) else {
+            return next.run(request).await;
+        };
+        tracing::warn!(
+            method = %request.method(),
+            path = %request.uri().path(),
+            fault = fault.label(),
+            "Injecting fault into request"
+        );
+
+        if let Some(delay) = fault.delay {
+            tokio::time::sleep(delay).await;
+        }
+        match fault.action {
+            FaultAction::Proceed => {
+                let mut response = next.run(request).await;
+                response
+                    .headers_mut()
+                    .insert(FAULT_HEADER, HeaderValue::from_static(fault.label()));
+                response
+            }
+            FaultAction::Fail(status) => injected(
+                StatusCode::from_u16(status).unwrap_or(StatusCode::SERVICE_UNAVAILABLE),
+                &fault,
+            ),
+            FaultAction::Drop => {
+                drop(next.run(request).await);
+                tokio::time::sleep(injector.drop_hold()).await;
+                injected(StatusCode::GATEWAY_TIMEOUT, &fault)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "axum")]
+pub use http::chaos_middleware;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn injector(rules: &str) -> FaultInjector {
+        FaultInjector::new(ChaosConfig {
+            rules: ChaosConfig::parse_rules(rules).unwrap(),
+            seed: Some(7),
+            drop_hold: None,
+        })
+    }
+
+    #[test]
+    fn test_parse_rules() {
+        let rules = ChaosConfig::parse_rules(
+            r#"[
+                {"route": "/analyze/*", "method": "POST", "latency_rate": 0.5, "latency_ms": [10, 20]},
+                {"client": "callbacks", "drop_rate": 0.1}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(rules[0].target, FaultTarget::Route("/analyze/*".to_string()));
+        assert_eq!(rules[0].latency_ms, (10, 20));
+        assert_eq!(rules[1].target, FaultTarget::Client("callbacks".to_string()));
+        assert_eq!(rules[1].error_status, 503);
+    }
+
+    #[test]
+    fn test_invalid_rules_rejected() {
+        for rules in [
+            r#"[{"route": "/x", "error_rate": 1.5}]"#,
+            r#"[{"route": "/x", "latency_rate": 0.5}]"#,
+            r#"[{"route": "/x", "latency_rate": 0.5, "latency_ms": [20, 10]}]"#,
+            r#"[{"route": "/x", "error_rate": 0.5, "error_status": 200}]"#,
+            r#"[{"client": ""}]"#,
+            r#"[{"error_rate": 0.5}]"#,
+        ] {
+            assert!(ChaosConfig::parse_rules(rules).is_err(), "{}", rules);
+        }
+    }
+
+    #[test]
+    fn test_refused_in_production() {
+        assert!(is_production(Some("production")));
+        assert!(is_production(Some("PROD")));
+        assert!(!is_production(Some("staging")));
+        assert!(!is_production(None));
+    }
+
+    #[test]
+    fn test_route_matching() {
+        let injector = injector(
+            r#"[
+                {"route": "/bounties/:id/submit", "method": "POST", "error_rate": 1.0},
+                {"route": "/analyze/*", "drop_rate": 1.0}
+            ]"#,
+        );
+
+        let fail = injector.for_route("POST", "/bounties/42/submit").unwrap();
+        assert_eq!(fail.action, FaultAction::Fail(503));
+        assert!(injector.for_route("GET", "/bounties/42/submit").is_none());
+        assert!(injector.for_route("POST", "/bounties/42").is_none());
+        assert_eq!(injector.for_route("GET", "/analyze/file").unwrap().action, FaultAction::Drop);
+        assert!(injector.for_client("/analyze/file").is_none());
+    }
+
+    #[test]
+    fn test_rates_are_respected() {
+        let injector = injector(
+            r#"[{"route": "/flaky", "error_rate": 0.25, "latency_rate": 1.0, "latency_ms": [5, 10]}]"#,
+        );
+
+        let faults: Vec<Fault> = (0..2000).filter_map(|_| injector.for_route("GET", "/flaky")).collect();
+        assert_eq!(faults.len(), 2000);
+        assert!(faults
+            .iter()
+            .all(|f| f.delay.is_some_and(|d| (5..=10).contains(&(d.as_millis() as u64)))));
+
+        let failures = faults.iter().filter(|f| f.action == FaultAction::Fail(503)).count();
+        assert!((400..600).contains(&failures), "{} failures", failures);
+    }
+
+    #[test]
+    fn test_disabled_injector_is_inert() {
+        let injector = FaultInjector::disabled();
+        assert!(!injector.is_enabled());
+        assert!(injector.for_route("GET", "/anything").is_none());
+        assert!(injector.for_client("anything").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_intercept_outbound_calls() {
+        let injector = injector(
+            r#"[
+                {"client": "failing", "error_rate": 1.0, "error_status": 502},
+                {"client": "dropping", "drop_rate": 1.0}
+            ]"#,
+        );
+        let calls = std::sync::atomic::AtomicUsize::new(0);
+        let call = || async { calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) };
+
+        assert_eq!(injector.intercept("failing", call()).await, Err(InjectedFault::Status(502)));
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+        assert_eq!(injector.intercept("dropping", call()).await, Err(InjectedFault::Dropped));
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        assert_eq!(injector.intercept("healthy", call()).await, Ok(1));
+    }
+}
@@ -0,0 +1,275 @@
+//! Runtime feature flags backed by Redis
+//!
+//! Each flag is a Redis hash `feature_flag:{name}`. The `enabled` field holds
+//! the global setting and `tenant:{id}` fields override it for one tenant. A
+//! flag with neither falls back to the default the caller passes, so code can
+//! ship behind a flag before anyone has set it.
+//!
+//! Evaluations are cached per process for a few seconds to keep Redis off the
+//! hot path; [`FeatureFlagClient::state`] always reads through.
+
+use parking_lot::RwLock;
+use redis::aio::MultiplexedConnection;
+use redis::{AsyncCommands, RedisError};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// How long an evaluated flag is reused before Redis is asked again
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(5);
+
+const KEY_PREFIX: &str = "feature_flag:";
+const GLOBAL_FIELD: &str = "enabled";
+const TENANT_FIELD_PREFIX: &str = "tenant:";
+
+/// Flag names are dotted lowercase paths such as `analysis-engine.analyzer.yara`
+pub fn is_valid_flag_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 128
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || matches!(b, b'.' | b'-' | b'_'))
+}
+
+/// Stored settings of one flag
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlagState {
+    /// Global setting; `None` means the caller's default applies
+    pub enabled: Option<bool>,
+    /// Per-tenant overrides of the global setting
+    pub tenants: BTreeMap<String, bool>,
+}
+
+impl FlagState {
+    fn from_fields(fields: HashMap<String, String>) -> Self {
+        let mut state = FlagState::default();
+        for (field, value) in fields {
+            let Ok(value) = value.parse::<bool>() else {
+                continue;
+            };
+            if field == GLOBAL_FIELD {
+                state.enabled = Some(value);
+            } else if let Some(tenant) = field.strip_prefix(TENANT_FIELD_PREFIX) {
+                state.tenants.insert(tenant.to_string(), value);
+            }
+        }
+        state
+    }
+
+    /// A tenant override wins over the global setting, which wins over `default`
+    pub fn evaluate(&self, tenant: Option<&str>, default: bool) -> bool {
+        tenant
+            .and_then(|t| self.tenants.get(t).copied())
+            .or(self.enabled)
+            .unwrap_or(default)
+    }
+}
+
+/// Reads and writes flags; cheap to clone
+#[derive(Clone)]
+pub struct FeatureFlagClient {
+    connection: MultiplexedConnection,
+    cache: Arc<RwLock<HashMap<String, (Instant, FlagState)>>>,
+    cache_ttl: Duration,
+}
+
+impl FeatureFlagClient {
+    pub fn new(connection: MultiplexedConnection, cache_ttl: Duration) -> Self {
+        Self {
+            connection,
+            cache: Arc::new(RwLock::new(HashMap::new())),
+            cache_ttl,
+        }
+    }
+
+    /// Evaluate a flag for a tenant. If Redis is unreachable the last known
+    /// state is used, or `default` if there is none.
+    pub async fn is_enabled(&self, flag: &str, tenant: Option<&str>, default: bool) -> bool {
+        let cached = self.cache.read().get(flag).cloned();
+        if let Some((fetched_at, state)) = &cached {
+            if fetched_at.elapsed() < self.cache_ttl {
+                return state.evaluate(tenant, default);
+            }
+        }
+
+        match self.state(flag).await {
+            Ok(state) => state.evaluate(tenant, default),
+            Err(e) => {
+                tracing::warn!("Failed to read feature flag {}: {}", flag, e);
+                cached.map_or(default, |(_, state)| state.evaluate(tenant, default))
+            }
+        }
+    }
+
+    /// Current settings of a flag, read from Redis
+    pub async fn state(&self, flag: &str) -> Result<FlagState, RedisError> {
+        let mut conn = self.connection.clone();
+        let fields: HashMap<String, String> = conn.hgetall(flag_key(flag)).await?;
+        let state = FlagState::from_fields(fields);
+
+        self.cache
+            .write()
+            .insert(flag.to_string(), (Instant::now(), state.clone()));
+        Ok(state)
+    }
+
+    /// Set a flag globally or for one tenant; `None` removes the setting
+    pub async fn set(
+        &self,
+        flag: &str,
+        tenant: Option<&str>,
+        enabled: Option<bool>,
+    ) -> Result<FlagState, RedisError> {
+        let field = match tenant {
+            Some(tenant) => format!("{}{}", TENANT_FIELD_PREFIX, tenant),
+            None => GLOBAL_FIELD.to_string(),
+        };
+
+        let mut conn = self.connection.clone();
+        match enabled {
+            Some(value) => {
+                let _: () = conn.hset(flag_key(flag), field, value.to_string()).await?;
+            }
+            None => {
+                let _: () = conn.hdel(flag_key(flag), field).await?;
+            }
+        }
+
+        tracing::info!(
+            "Feature flag {} set to {:?} for {}",
+            flag,
+            enabled,
+            tenant.unwrap_or("all tenants")
+        );
+        self.state(flag).await
+    }
+}
+
+fn flag_key(flag: &str) -> String {
+    format!("{}{}", KEY_PREFIX, flag)
+}
+
+// ─── axum integration ───
+
+#[cfg(feature = "axum")]
+mod http {
+    use axum::{
+        extract::{Path, State},
+        http::{HeaderMap, StatusCode},
+        response::{IntoResponse, Response},
+        routing::get,
+        Json, Router,
+    };
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::observability::{admin_token_matches, ADMIN_TOKEN_HEADER};
+
+    #[derive(Debug, Deserialize)]
+    struct SetFlagBody {
+        /// `null` clears the setting
+        enabled: Option<bool>,
+        /// Applies to every tenant when omitted
+        tenant: Option<String>,
+    }
+
+    fn error(status: StatusCode, message: &str) -> Response {
+        (status, Json(serde_json::json!({ "error": message }))).into_response()
+    }
+
+    /// The response to send instead of handling the request, if any
+    fn rejection(headers: &HeaderMap, flag: &str) -> Option<Response> {
+        if !admin_token_matches(headers.get(ADMIN_TOKEN_HEADER).and_then(|v| v.to_str().ok())) {
+            return Some(error(StatusCode::FORBIDDEN, "Forbidden"));
+        }
+        if !is_valid_flag_name(flag) {
+            return Some(error(StatusCode::BAD_REQUEST, "Invalid flag name"));
+        }
+        None
+    }
+
+    async fn get_flag(
+        State(client): State<FeatureFlagClient>,
+        Path(flag): Path<String>,
+        headers: HeaderMap,
+    ) -> Response {
+        if let Some(response) = rejection(&headers, &flag) {
+            return response;
+        }
+        match client.state(&flag).await {
+            Ok(state) => Json(state).into_response(),
+            Err(e) => {
+                tracing::error!("Failed to read feature flag {}: {}", flag, e);
+                error(StatusCode::SERVICE_UNAVAILABLE, "Feature flags unavailable")
+            }
+        }
+    }
+
+    async fn put_flag(
+        State(client): State<FeatureFlagClient>,
+        Path(flag): Path<String>,
+        headers: HeaderMap,
+        Json(body): Json<SetFlagBody>,
+    ) -> Response {
+        if let Some(response) = rejection(&headers, &flag) {
+            return response;
+        }
+        let tenant = body.tenant.as_deref().filter(|t| !t.is_empty());
+        match client.set(&flag, tenant, body.enabled).await {
+            Ok(state) => Json(state).into_response(),
+            Err(e) => {
+                tracing::error!("Failed to update feature flag {}: {}", flag, e);
+                error(StatusCode::SERVICE_UNAVAILABLE, "Feature flags unavailable")
+            }
+        }
+    }
+
+    /// `GET`/`PUT /admin/feature-flags/:flag`, protected by the `X-Admin-Token` header
+    pub fn feature_flag_routes<S>(client: FeatureFlagClient) -> Router<S>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        Router::new()
+            .route("/admin/feature-flags/:flag", get(get_flag).put(put_flag))
+            .with_state(client)
+    }
+}
+
+#[cfg(feature = "axum")]
+pub use http::feature_flag_routes;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tenant_override_wins() {
+        let state = FlagState::from_fields(HashMap::from([
+            ("enabled".to_string(), "true".to_string()),
+            ("tenant:acme".to_string(), "false".to_string()),
+            ("tenant:broken".to_string(), "maybe".to_string()),
+        ]));
+
+        assert_eq!(state.enabled, Some(true));
+        assert_eq!(state.tenants.len(), 1);
+        assert!(!state.evaluate(Some("acme"), true));
+        assert!(state.evaluate(Some("other"), false));
+        assert!(state.evaluate(None, false));
+    }
+
+    #[test]
+    fn test_unset_flag_uses_default() {
+        let state = FlagState::default();
+        assert!(state.evaluate(Some("acme"), true));
+        assert!(!state.evaluate(None, false));
+    }
+
+    #[test]
+    fn test_flag_names() {
+        assert!(is_valid_flag_name("analysis-engine.analyzer.yara"));
+        assert!(!is_valid_flag_name(""));
+        assert!(!is_valid_flag_name("Analyzer Yara"));
+        assert!(!is_valid_flag_name("flags:*"));
+    }
+}
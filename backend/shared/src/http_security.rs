@@ -0,0 +1,275 @@
+//! CORS policy and security response headers shared by every HTTP service
+//!
+//! Services build their CORS layer from [`CorsPolicy`] instead of allowing
+//! any origin: origins come from `CORS_ALLOWED_ORIGINS` (comma separated)
+//! or default to the frontends of the current `ENVIRONMENT`. A `*` origin is
+//! honoured outside production only, and never together with credentials.
+//!
+//! [`security_headers_middleware`] adds HSTS, `X-Content-Type-Options`,
+//! `Referrer-Policy` and a `frame-ancestors` policy to every response, and a
+//! full Content-Security-Policy to HTML ones (API docs, status pages).
+//!
+//! The policy and header values do not depend on a web framework; the layer
+//! and middleware need the `axum` feature. Services on other axum versions
+//! build their own from [`CorsPolicy::origins`] and [`security_headers`].
+
+#[cfg(feature = "axum")]
+use axum::{
+    extract::Request,
+    http::{header, HeaderMap, HeaderName, HeaderValue, Method},
+    middleware::Next,
+    response::Response,
+};
+use std::time::Duration;
+#[cfg(feature = "axum")]
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+/// Comma-separated origins allowed to call the service from a browser
+pub const CORS_ALLOWED_ORIGINS_ENV: &str = "CORS_ALLOWED_ORIGINS";
+
+/// Request headers browsers may send cross-origin
+pub const ALLOWED_HEADERS: &[&str] = &[
+    "authorization",
+    "content-type",
+    "accept",
+    "x-api-key",
+    "x-request-id",
+    "traceparent",
+    "idempotency-key",
+    "x-csrf-token",
+];
+
+/// Response headers scripts may read cross-origin
+pub const EXPOSED_HEADERS: &[&str] = &[
+    "x-request-id",
+    "idempotent-replayed",
+    "retry-after",
+    "x-rate-limit-limit",
+    "x-rate-limit-remaining",
+    "x-rate-limit-reset",
+];
+
+const HSTS: &str = "max-age=31536000; includeSubDomains";
+/// API responses must never be framed
+const API_CSP: &str = "frame-ancestors 'none'";
+/// HTML is limited to our own scripts; inline styles are allowed for the API docs
+const HTML_CSP: &str = "default-src 'self'; script-src 'self'; style-src 'self' 'unsafe-inline'; \
+                        img-src 'self' data:; font-src 'self' data:; connect-src 'self'; \
+                        object-src 'none'; base-uri 'self'; form-action 'self'; frame-ancestors 'none'";
+
+/// Which browser origins may call a service
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorsPolicy {
+    /// Exact origins such as `https://app.nexus-security.com`, or `*`
+    pub allowed_origins: Vec<String>,
+    /// Send cookies and `Authorization` cross-origin; ignored for `*`
+    pub allow_credentials: bool,
+    pub max_age: Duration,
+}
+
+impl CorsPolicy {
+    pub fn new(allowed_origins: Vec<String>) -> Self {
+        Self {
+            allowed_origins,
+            allow_credentials: true,
+            max_age: Duration::from_secs(3600),
+        }
+    }
+
+    /// Frontends of an environment (`ENVIRONMENT`)
+    pub fn for_environment(environment: Option<&str>) -> Self {
+        let origins: &[&str] = match environment.map(str::to_ascii_lowercase).as_deref() {
+            Some("production" | "prod") => &[
+                "https://nexus-security.com",
+                "https://app.nexus-security.com",
+            ],
+            Some("staging") => &[
+                "https://staging.nexus-security.com",
+                "https://preview.nexus-security.com",
+            ],
+            _ => &["http://localhost:3000", "http://localhost:5173"],
+        };
+        Self::new(origins.iter().map(|o| o.to_string()).collect())
+    }
+
+    /// `CORS_ALLOWED_ORIGINS` if set, otherwise the environment's defaults
+    pub fn from_env() -> Self {
+        let environment = std::env::var("ENVIRONMENT").ok();
+        match std::env::var(CORS_ALLOWED_ORIGINS_ENV) {
+            Ok(origins) if !origins.trim().is_empty() => Self::new(parse_origins(&origins)),
+            _ => Self::for_environment(environment.as_deref()),
+        }
+    }
+
+    pub fn with_credentials(mut self, allow_credentials: bool) -> Self {
+        self.allow_credentials = allow_credentials;
+        self
+    }
+
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Origins to allow: `None` means any origin (a `*` outside
+    /// production), in which case credentials must not be allowed
+    pub fn origins(&self, production: bool) -> Option<Vec<&str>> {
+        let wildcard = self.allowed_origins.iter().any(|o| o == "*");
+        if wildcard && !production {
+            return None;
+        }
+        if wildcard {
+            tracing::warn!("Ignoring wildcard CORS origin in production");
+        }
+        Some(
+            self.allowed_origins
+                .iter()
+                .map(String::as_str)
+                .filter(|o| *o != "*")
+                .collect(),
+        )
+    }
+
+    /// CORS layer for the current `ENVIRONMENT`
+    #[cfg(feature = "axum")]
+    pub fn layer(&self) -> CorsLayer {
+        self.layer_for(is_production())
+    }
+
+    #[cfg(feature = "axum")]
+    fn layer_for(&self, production: bool) -> CorsLayer {
+        let layer = CorsLayer::new()
+            .allow_methods([
+                Method::GET,
+                Method::POST,
+                Method::PUT,
+                Method::PATCH,
+                Method::DELETE,
+                Method::OPTIONS,
+            ])
+            .allow_headers(ALLOWED_HEADERS.iter().map(|h| HeaderName::from_static(h)).collect::<Vec<_>>())
+            .expose_headers(EXPOSED_HEADERS.iter().map(|h| HeaderName::from_static(h)).collect::<Vec<_>>())
+            .max_age(self.max_age);
+
+        let Some(origins) = self.origins(production) else {
+            // Browsers reject credentials on a wildcard origin
+            return layer.allow_origin(AllowOrigin::any());
+        };
+        let origins = origins.into_iter().filter_map(|o| match o.parse::<HeaderValue>() {
+            Ok(value) => Some(value),
+            Err(_) => {
+                tracing::warn!("Ignoring invalid CORS origin {:?}", o);
+                None
+            }
+        });
+        layer
+            .allow_origin(AllowOrigin::list(origins))
+            .allow_credentials(self.allow_credentials)
+    }
+}
+
+/// Whether `ENVIRONMENT` names production
+pub fn is_production() -> bool {
+    matches!(
+        std::env::var("ENVIRONMENT").ok().map(|e| e.to_ascii_lowercase()).as_deref(),
+        Some("production" | "prod")
+    )
+}
+
+fn parse_origins(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|o| o.trim().trim_end_matches('/').to_string())
+        .filter(|o| !o.is_empty())
+        .collect()
+}
+
+/// Security headers for a response, by lowercase name
+pub fn security_headers(is_html: bool) -> [(&'static str, &'static str); 5] {
+    [
+        ("strict-transport-security", HSTS),
+        ("x-content-type-options", "nosniff"),
+        ("x-frame-options", "DENY"),
+        ("referrer-policy", "no-referrer"),
+        (
+            "content-security-policy",
+            if is_html { HTML_CSP } else { API_CSP },
+        ),
+    ]
+}
+
+/// Add the security headers to a response, keeping any a handler set
+#[cfg(feature = "axum")]
+pub fn apply_security_headers(headers: &mut HeaderMap) {
+    let is_html = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/html"));
+
+    for (name, value) in security_headers(is_html) {
+        if !headers.contains_key(name) {
+            headers.insert(name, HeaderValue::from_static(value));
+        }
+    }
+}
+
+/// Security headers on every response
+#[cfg(feature = "axum")]
+pub async fn security_headers_middleware(request: Request, next: Next) -> Response {
+    let mut response = next.run(request).await;
+    apply_security_headers(response.headers_mut());
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_environment_defaults() {
+        let production = CorsPolicy::for_environment(Some("Production"));
+        assert!(production
+            .allowed_origins
+            .contains(&"https://app.nexus-security.com".to_string()));
+        assert!(!production.allowed_origins.iter().any(|o| o.contains("localhost")));
+
+        let development = CorsPolicy::for_environment(None);
+        assert!(development.allowed_origins.contains(&"http://localhost:3000".to_string()));
+    }
+
+    #[test]
+    fn test_wildcard_only_outside_production() {
+        let policy = CorsPolicy::new(parse_origins("*, https://app.example.com/"));
+        assert_eq!(policy.allowed_origins, vec!["*", "https://app.example.com"]);
+        assert_eq!(policy.origins(false), None);
+        assert_eq!(policy.origins(true), Some(vec!["https://app.example.com"]));
+
+        // Neither combination may panic inside tower-http
+        #[cfg(feature = "axum")]
+        {
+            let _ = policy.layer_for(false);
+            let _ = policy.layer_for(true);
+        }
+    }
+
+    #[cfg(feature = "axum")]
+    #[test]
+    fn test_security_headers() {
+        let mut json = HeaderMap::new();
+        json.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        apply_security_headers(&mut json);
+        assert_eq!(json[header::X_CONTENT_TYPE_OPTIONS], "nosniff");
+        assert_eq!(json[header::CONTENT_SECURITY_POLICY], API_CSP);
+        assert!(json[header::STRICT_TRANSPORT_SECURITY]
+            .to_str()
+            .unwrap()
+            .starts_with("max-age="));
+
+        let mut html = HeaderMap::new();
+        html.insert(header::CONTENT_TYPE, HeaderValue::from_static("text/html; charset=utf-8"));
+        html.insert(header::X_FRAME_OPTIONS, HeaderValue::from_static("SAMEORIGIN"));
+        apply_security_headers(&mut html);
+        assert_eq!(html[header::CONTENT_SECURITY_POLICY], HTML_CSP);
+        assert_eq!(html[header::X_FRAME_OPTIONS], "SAMEORIGIN");
+    }
+}
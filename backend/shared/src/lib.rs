@@ -31,5 +31,15 @@ pub enum NexusError {
 pub type Result<T> = std::result::Result<T, NexusError>;
 
 // Export modules
+pub mod chaos;
+pub mod feature_flags;
+pub mod http_security;
+pub mod login_guard;
+pub mod permissions;
+#[cfg(feature = "request-signing")]
+pub mod request_signing;
 pub mod types;
+#[cfg(feature = "webhooks")]
+pub mod webhooks;
 pub mod messaging;
+pub mod observability;
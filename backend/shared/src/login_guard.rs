@@ -0,0 +1,341 @@
+//! Brute-force protection for password logins
+//!
+//! Failed attempts are counted in Redis per account (the identifier the
+//! caller typed, so unknown accounts are throttled too) and per client IP,
+//! in a rolling window. Crossing a threshold locks that account or IP; each
+//! further lockout within [`STRIKE_MEMORY`] doubles the lock, up to
+//! `max_lockout`. A successful login clears the account's counters but not
+//! the IP's, so an attacker cannot reset their budget with their own
+//! account.
+//!
+//! After `captcha_after` failures the guard asks for a CAPTCHA. Callers plug
+//! in a [`CaptchaVerifier`]; without one the challenge is never demanded.
+
+use async_trait::async_trait;
+use redis::aio::MultiplexedConnection;
+use redis::{AsyncCommands, RedisResult};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How long past lockouts count towards the next one
+pub const STRIKE_MEMORY: Duration = Duration::from_secs(24 * 3600);
+
+const KEY_PREFIX: &str = "login_guard:";
+
+/// Thresholds and lockout lengths
+#[derive(Debug, Clone)]
+pub struct LoginGuardConfig {
+    /// Failures within the window that lock an account
+    pub max_account_failures: u32,
+    /// Failures within the window that lock a client IP
+    pub max_ip_failures: u32,
+    /// Failures after which a CAPTCHA is required; 0 disables the challenge
+    pub captcha_after: u32,
+    pub failure_window: Duration,
+    /// First lockout; doubled on every further strike
+    pub base_lockout: Duration,
+    pub max_lockout: Duration,
+}
+
+impl Default for LoginGuardConfig {
+    fn default() -> Self {
+        Self {
+            max_account_failures: 5,
+            max_ip_failures: 20,
+            captcha_after: 3,
+            failure_window: Duration::from_secs(15 * 60),
+            base_lockout: Duration::from_secs(60),
+            max_lockout: Duration::from_secs(3600),
+        }
+    }
+}
+
+impl LoginGuardConfig {
+    /// Defaults overridden by `LOGIN_MAX_ACCOUNT_FAILURES`,
+    /// `LOGIN_MAX_IP_FAILURES`, `LOGIN_CAPTCHA_AFTER`,
+    /// `LOGIN_FAILURE_WINDOW_SECS`, `LOGIN_LOCKOUT_BASE_SECS` and
+    /// `LOGIN_LOCKOUT_MAX_SECS`
+    pub fn from_env() -> Self {
+        fn var<T: std::str::FromStr>(name: &str) -> Option<T> {
+            std::env::var(name).ok().and_then(|v| v.parse().ok())
+        }
+
+        let defaults = Self::default();
+        Self {
+            max_account_failures: var("LOGIN_MAX_ACCOUNT_FAILURES")
+                .unwrap_or(defaults.max_account_failures)
+                .max(1),
+            max_ip_failures: var("LOGIN_MAX_IP_FAILURES")
+                .unwrap_or(defaults.max_ip_failures)
+                .max(1),
+            captcha_after: var("LOGIN_CAPTCHA_AFTER").unwrap_or(defaults.captcha_after),
+            failure_window: var("LOGIN_FAILURE_WINDOW_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.failure_window),
+            base_lockout: var("LOGIN_LOCKOUT_BASE_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.base_lockout),
+            max_lockout: var("LOGIN_LOCKOUT_MAX_SECS")
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.max_lockout),
+        }
+    }
+
+    /// Length of the `strike`th lockout (1-based)
+    pub fn lockout_for(&self, strike: u32) -> Duration {
+        let factor = 2u32.saturating_pow(strike.saturating_sub(1));
+        self.base_lockout
+            .checked_mul(factor)
+            .unwrap_or(self.max_lockout)
+            .min(self.max_lockout)
+    }
+}
+
+/// Checks CAPTCHA responses submitted with a login
+#[async_trait]
+pub trait CaptchaVerifier: Send + Sync {
+    async fn verify(&self, token: &str, remote_ip: Option<&str>) -> bool;
+}
+
+/// Verifier for the `siteverify` API shared by hCaptcha, reCAPTCHA and
+/// Cloudflare Turnstile
+#[cfg(feature = "captcha")]
+pub struct SiteVerifyCaptcha {
+    client: reqwest::Client,
+    verify_url: String,
+    secret: String,
+}
+
+#[cfg(feature = "captcha")]
+impl SiteVerifyCaptcha {
+    pub fn new(verify_url: impl Into<String>, secret: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(5))
+                .build()
+                .unwrap_or_default(),
+            verify_url: verify_url.into(),
+            secret: secret.into(),
+        }
+    }
+
+    /// From `CAPTCHA_VERIFY_URL` and `CAPTCHA_SECRET`; `None` unless both are set
+    pub fn from_env() -> Option<Self> {
+        let url = std::env::var("CAPTCHA_VERIFY_URL").ok().filter(|v| !v.is_empty())?;
+        let secret = std::env::var("CAPTCHA_SECRET").ok().filter(|v| !v.is_empty())?;
+        Some(Self::new(url, secret))
+    }
+}
+
+#[cfg(feature = "captcha")]
+#[async_trait]
+impl CaptchaVerifier for SiteVerifyCaptcha {
+    async fn verify(&self, token: &str, remote_ip: Option<&str>) -> bool {
+        #[derive(serde::Deserialize)]
+        struct SiteVerifyResponse {
+            success: bool,
+        }
+
+        let mut form = vec![("secret", self.secret.as_str()), ("response", token)];
+        if let Some(ip) = remote_ip {
+            form.push(("remoteip", ip));
+        }
+
+        let response = match self.client.post(&self.verify_url).form(&form).send().await {
+            Ok(response) => response,
+            Err(e) => {
+                // Fail closed: an unreachable provider must not disable the challenge
+                tracing::warn!("CAPTCHA verification failed: {}", e);
+                return false;
+            }
+        };
+        response
+            .json::<SiteVerifyResponse>()
+            .await
+            .map(|body| body.success)
+            .unwrap_or(false)
+    }
+}
+
+/// Whether a login attempt may go ahead
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoginDecision {
+    Allow,
+    /// Too many failures; retry with a solved CAPTCHA
+    CaptchaRequired,
+    /// The account or IP is locked out
+    Locked { retry_after: Duration },
+}
+
+/// What a failed attempt led to
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FailureOutcome {
+    /// Failures on the account in the current window
+    pub account_failures: u32,
+    /// Set when this failure locked the account
+    pub account_locked_for: Option<Duration>,
+    /// Set when this failure locked the IP
+    pub ip_locked_for: Option<Duration>,
+}
+
+#[derive(Clone, Copy)]
+enum Subject<'a> {
+    Account(&'a str),
+    Ip(&'a str),
+}
+
+impl Subject<'_> {
+    fn key(&self, kind: &str) -> String {
+        match self {
+            Subject::Account(account) => format!(
+                "{}{}:account:{}",
+                KEY_PREFIX,
+                kind,
+                account.trim().to_lowercase()
+            ),
+            Subject::Ip(ip) => format!("{}{}:ip:{}", KEY_PREFIX, kind, ip),
+        }
+    }
+}
+
+/// Counts failed logins and decides lockouts; cheap to clone
+#[derive(Clone)]
+pub struct LoginGuard {
+    connection: MultiplexedConnection,
+    config: LoginGuardConfig,
+    captcha: Option<Arc<dyn CaptchaVerifier>>,
+}
+
+impl LoginGuard {
+    pub fn new(connection: MultiplexedConnection, config: LoginGuardConfig) -> Self {
+        Self {
+            connection,
+            config,
+            captcha: None,
+        }
+    }
+
+    /// Demand a CAPTCHA after `captcha_after` failures
+    pub fn with_captcha(mut self, verifier: Arc<dyn CaptchaVerifier>) -> Self {
+        self.captcha = Some(verifier);
+        self
+    }
+
+    pub fn config(&self) -> &LoginGuardConfig {
+        &self.config
+    }
+
+    /// Call before checking the password
+    pub async fn check(
+        &self,
+        account: &str,
+        ip: Option<&str>,
+        captcha_token: Option<&str>,
+    ) -> RedisResult<LoginDecision> {
+        let mut conn = self.connection.clone();
+
+        let mut subjects = vec![Subject::Account(account)];
+        subjects.extend(ip.map(Subject::Ip));
+
+        let mut failures = 0u32;
+        for subject in &subjects {
+            let ttl_ms: i64 = conn.pttl(subject.key("lock")).await?;
+            if ttl_ms > 0 {
+                return Ok(LoginDecision::Locked {
+                    retry_after: Duration::from_millis(ttl_ms as u64),
+                });
+            }
+            let count: Option<u32> = conn.get(subject.key("fail")).await?;
+            failures = failures.max(count.unwrap_or(0));
+        }
+
+        let Some(captcha) = &self.captcha else {
+            return Ok(LoginDecision::Allow);
+        };
+        if self.config.captcha_after == 0 || failures < self.config.captcha_after {
+            return Ok(LoginDecision::Allow);
+        }
+        match captcha_token {
+            Some(token) if captcha.verify(token, ip).await => Ok(LoginDecision::Allow),
+            _ => Ok(LoginDecision::CaptchaRequired),
+        }
+    }
+
+    /// Call after a wrong password or unknown account
+    pub async fn record_failure(&self, account: &str, ip: Option<&str>) -> RedisResult<FailureOutcome> {
+        let mut outcome = FailureOutcome::default();
+
+        let (failures, locked) = self
+            .strike(Subject::Account(account), self.config.max_account_failures)
+            .await?;
+        outcome.account_failures = failures;
+        outcome.account_locked_for = locked;
+
+        if let Some(ip) = ip {
+            let (_, locked) = self.strike(Subject::Ip(ip), self.config.max_ip_failures).await?;
+            outcome.ip_locked_for = locked;
+        }
+        Ok(outcome)
+    }
+
+    /// Call after a successful login
+    pub async fn record_success(&self, account: &str) -> RedisResult<()> {
+        let subject = Subject::Account(account);
+        let mut conn = self.connection.clone();
+        conn.del::<_, ()>(&[subject.key("fail"), subject.key("strikes")]).await
+    }
+
+    /// Count one failure; lock the subject once it reaches `threshold`
+    async fn strike(&self, subject: Subject<'_>, threshold: u32) -> RedisResult<(u32, Option<Duration>)> {
+        let mut conn = self.connection.clone();
+        let fail_key = subject.key("fail");
+
+        let failures: u32 = conn.incr(&fail_key, 1).await?;
+        if failures == 1 {
+            conn.expire::<_, ()>(&fail_key, self.config.failure_window.as_secs().max(1) as i64)
+                .await?;
+        }
+        if failures < threshold {
+            return Ok((failures, None));
+        }
+
+        let strikes_key = subject.key("strikes");
+        let strikes: u32 = conn.incr(&strikes_key, 1).await?;
+        conn.expire::<_, ()>(&strikes_key, STRIKE_MEMORY.as_secs() as i64).await?;
+
+        let lockout = self.config.lockout_for(strikes);
+        conn.pset_ex::<_, _, ()>(subject.key("lock"), strikes, lockout.as_millis().max(1) as u64)
+            .await?;
+        conn.del::<_, ()>(&fail_key).await?;
+        Ok((failures, Some(lockout)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lockout_doubles_up_to_max() {
+        let config = LoginGuardConfig {
+            base_lockout: Duration::from_secs(60),
+            max_lockout: Duration::from_secs(600),
+            ..LoginGuardConfig::default()
+        };
+
+        assert_eq!(config.lockout_for(1), Duration::from_secs(60));
+        assert_eq!(config.lockout_for(2), Duration::from_secs(120));
+        assert_eq!(config.lockout_for(4), Duration::from_secs(480));
+        assert_eq!(config.lockout_for(5), Duration::from_secs(600));
+        assert_eq!(config.lockout_for(64), Duration::from_secs(600));
+    }
+
+    #[test]
+    fn test_account_keys_are_case_insensitive() {
+        assert_eq!(
+            Subject::Account(" Alice@Example.com").key("fail"),
+            Subject::Account("alice@example.com").key("fail")
+        );
+        assert_eq!(Subject::Ip("10.0.0.1").key("lock"), "login_guard:lock:ip:10.0.0.1");
+    }
+}
@@ -18,6 +18,7 @@ pub enum NexusEvent {
     BountyCompleted(BountyCompletedEvent),
     BountyExpired(BountyExpiredEvent),
     BountyCancelled(BountyCancelledEvent),
+    BountyClosed(BountyClosedEvent),
 
     // Submission events
     SubmissionReceived(SubmissionReceivedEvent),
@@ -31,21 +32,34 @@ pub enum NexusEvent {
 
     // Reputation events
     ReputationUpdated(ReputationUpdatedEvent),
+    BadgeAwarded(BadgeAwardedEvent),
+    ReputationRankChanged(ReputationRankChangedEvent),
+    StreakMilestoneReached(StreakMilestoneReachedEvent),
 
     // Payment events
     PaymentProcessed(PaymentProcessedEvent),
     PaymentFailed(PaymentFailedEvent),
     StakeSlashed(StakeSlashedEvent),
+    WithdrawalUpdated(WithdrawalUpdatedEvent),
+    PaymentUpdated(PaymentUpdatedEvent),
 
     // User events
     UserRegistered(UserRegisteredEvent),
     UserVerified(UserVerifiedEvent),
+    MagicLinkRequested(MagicLinkRequestedEvent),
+    AccountLocked(AccountLockedEvent),
+    NewDeviceLogin(NewDeviceLoginEvent),
+    OrganizationInvitationSent(OrganizationInvitationSentEvent),
     EngineRegistered(EngineRegisteredEvent),
 
     // Dispute events
     DisputeCreated(DisputeCreatedEvent),
     DisputeResolved(DisputeResolvedEvent),
 
+    // Consensus events
+    ConsensusReached(ConsensusReachedEvent),
+    SettlementPlanned(SettlementPlannedEvent),
+
     // System events
     SystemAlert(SystemAlertEvent),
 }
@@ -98,6 +112,17 @@ pub struct BountyCancelledEvent {
     pub cancelled_at: DateTime<Utc>,
 }
 
+/// A bounty's submission window closed; the consensus-service settles its
+/// verdict on the submissions received
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BountyClosedEvent {
+    pub bounty_id: BountyId,
+    /// Published with the verdict in the intelligence feed
+    pub artifact_hash: Option<String>,
+    pub total_submissions: u32,
+    pub closed_at: DateTime<Utc>,
+}
+
 // Submission Events
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubmissionReceivedEvent {
@@ -167,6 +192,34 @@ pub struct ReputationUpdatedEvent {
     pub updated_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BadgeAwardedEvent {
+    pub user_id: UserId,
+    pub badge_id: Uuid,
+    pub badge_name: String,
+    pub description: String,
+    pub icon: String,
+    pub rarity: String,
+    pub awarded_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReputationRankChangedEvent {
+    pub user_id: UserId,
+    pub old_rank: i32,
+    pub new_rank: i32,
+    pub score: i32,
+    pub changed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreakMilestoneReachedEvent {
+    pub user_id: UserId,
+    /// Consecutive correct submissions
+    pub streak: i32,
+    pub reached_at: DateTime<Utc>,
+}
+
 // Payment Events
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PaymentProcessedEvent {
@@ -197,6 +250,103 @@ pub struct StakeSlashedEvent {
     pub slashed_at: DateTime<Utc>,
 }
 
+/// A step of a user's withdrawal, or a change to the addresses they may
+/// withdraw to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WithdrawalUpdatedEvent {
+    /// Unset for allowlist changes
+    pub withdrawal_id: Option<Uuid>,
+    pub user_id: UserId,
+    pub kind: WithdrawalEventKind,
+    /// In the token's base units
+    pub amount: Option<String>,
+    pub token_address: Option<String>,
+    /// Destination of the withdrawal, or the allowlisted address changed
+    pub address: Option<String>,
+    pub reason: Option<String>,
+    /// Payment transaction, once sent
+    pub tx_hash: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WithdrawalEventKind {
+    Requested,
+    Held,
+    ApprovalRecorded,
+    Approved,
+    Rejected,
+    Queued,
+    Sent,
+    Completed,
+    Failed,
+    AddressAdded,
+    AddressRemoved,
+}
+
+/// A step in the life of a payment, once it happened on-chain or in the
+/// ledger, so other services need not poll for it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentUpdatedEvent {
+    /// Unique per event; repeated if the event is published again
+    pub event_id: Uuid,
+    pub kind: PaymentEventKind,
+    pub bounty_id: Option<BountyId>,
+    /// Unset where the payment-service knows only a wallet (escrow deposits)
+    pub user_id: Option<UserId>,
+    /// Depositor, payout recipient, slashed staker, withdrawal destination or
+    /// refunded creator
+    pub address: Option<String>,
+    /// In the token's base units
+    pub amount: Option<String>,
+    pub token_address: Option<String>,
+    pub tx_hash: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentEventKind {
+    EscrowConfirmed,
+    PayoutSent,
+    PayoutConfirmed,
+    SlashExecuted,
+    WithdrawalCompleted,
+    /// A cancelled or expired bounty's escrow was queued back to its creator
+    RefundIssued,
+    /// A slash was decided and will be executed unless appealed in time
+    SlashScheduled,
+}
+
+impl PaymentEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PaymentEventKind::EscrowConfirmed => "escrow_confirmed",
+            PaymentEventKind::PayoutSent => "payout_sent",
+            PaymentEventKind::PayoutConfirmed => "payout_confirmed",
+            PaymentEventKind::SlashExecuted => "slash_executed",
+            PaymentEventKind::WithdrawalCompleted => "withdrawal_completed",
+            PaymentEventKind::RefundIssued => "refund_issued",
+            PaymentEventKind::SlashScheduled => "slash_scheduled",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        [
+            PaymentEventKind::EscrowConfirmed,
+            PaymentEventKind::PayoutSent,
+            PaymentEventKind::PayoutConfirmed,
+            PaymentEventKind::SlashExecuted,
+            PaymentEventKind::WithdrawalCompleted,
+            PaymentEventKind::RefundIssued,
+            PaymentEventKind::SlashScheduled,
+        ]
+        .into_iter()
+        .find(|kind| kind.as_str() == value)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum PaymentType {
     BountyReward,
@@ -221,6 +371,58 @@ pub struct UserVerifiedEvent {
     pub verified_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MagicLinkRequestedEvent {
+    pub user_id: UserId,
+    pub email: String,
+    pub login_url: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Sign-ins to an account were suspended after repeated failed passwords
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountLockedEvent {
+    pub user_id: UserId,
+    pub email: String,
+    pub failed_attempts: u32,
+    /// Client IP of the attempt that triggered the lockout
+    pub ip_address: Option<String>,
+    pub locked_until: DateTime<Utc>,
+}
+
+/// Someone signed in to an account from a device or country it had not
+/// been used from before
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewDeviceLoginEvent {
+    pub user_id: UserId,
+    pub email: String,
+    pub session_id: Uuid,
+    /// Short description such as "Firefox on Linux"
+    pub device: String,
+    pub ip_address: Option<String>,
+    /// ISO 3166 alpha-2 code, when the edge proxy reported one
+    pub country: Option<String>,
+    pub new_device: bool,
+    pub new_country: bool,
+    pub logged_in_at: DateTime<Utc>,
+}
+
+/// Someone was invited to join an organization; mailed to the invited
+/// address, which may not have an account yet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrganizationInvitationSentEvent {
+    pub invitation_id: Uuid,
+    pub organization_id: Uuid,
+    pub organization_name: String,
+    /// The invited address's account, if it has one
+    pub user_id: Option<UserId>,
+    pub email: String,
+    pub role: String,
+    pub invited_by: String,
+    pub accept_url: String,
+    pub expires_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EngineRegisteredEvent {
     pub engine_id: EngineId,
@@ -251,6 +453,84 @@ pub struct DisputeResolvedEvent {
     pub resolved_at: DateTime<Utc>,
 }
 
+// Consensus Events
+/// The submissions to a closed bounty agreed on a verdict
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsensusReachedEvent {
+    pub bounty_id: BountyId,
+    pub final_verdict: ThreatVerdict,
+    /// Average confidence of the engines behind the verdict, 0.0 to 1.0
+    pub confidence: f64,
+    /// Share of the vote behind the verdict, 0 to 100
+    pub agreement_score: f64,
+    pub total_submissions: u32,
+    pub reached_at: DateTime<Utc>,
+}
+
+/// How a finalized bounty settles: who is rewarded, who is slashed and by
+/// how much, and how each engine's reputation moves. Signed by the
+/// consensus-service so the payment and reputation services apply one
+/// outcome rather than each deriving their own.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettlementPlannedEvent {
+    pub plan: SettlementPlan,
+    pub signature: EventSignature,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SettlementPlan {
+    pub plan_id: Uuid,
+    pub bounty_id: BountyId,
+    pub final_verdict: ThreatVerdict,
+    /// Without consensus no verdict stands, so every stake is returned
+    pub consensus_reached: bool,
+    /// The bounty was a calibration bounty: `final_verdict` is its known
+    /// ground truth rather than the engines' consensus
+    #[serde(default)]
+    pub calibration: bool,
+    pub entries: Vec<SettlementEntry>,
+    pub planned_at: DateTime<Utc>,
+}
+
+/// What one engine gets from a bounty's settlement
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SettlementEntry {
+    pub engine_id: EngineId,
+    /// The engine's user, when its ID is one
+    pub user_id: Option<UserId>,
+    pub submission_id: SubmissionId,
+    pub verdict: ThreatVerdict,
+    pub outcome: SettlementOutcome,
+    /// Stake the engine locked, as forwarded with its submission
+    pub stake_amount: i64,
+    /// Share of the bounty reward, 0.0 to 1.0; shares add up to 1.0 unless
+    /// only engines on probation, whose shares are capped, were rewarded
+    pub reward_share: f64,
+    /// Share of the stake forfeited, 0.0 to 1.0
+    pub slash_fraction: f64,
+    pub slash_amount: i64,
+    pub reputation_delta: i32,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SettlementOutcome {
+    /// Voted for the final verdict
+    Rewarded,
+    /// Voted against the final verdict
+    Slashed,
+    /// Abstained, or no verdict stood; the stake is returned
+    Refunded,
+}
+
+/// HMAC signature over an event's payload by a `SERVICE_SIGNING_KEYS` key
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EventSignature {
+    pub key_id: String,
+    pub timestamp: i64,
+    pub signature: String,
+}
+
 // System Events
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemAlertEvent {
@@ -306,6 +586,7 @@ impl NexusEvent {
             NexusEvent::BountyCompleted(_) => "Bounty Completed".to_string(),
             NexusEvent::BountyExpired(_) => "Bounty Expired".to_string(),
             NexusEvent::BountyCancelled(_) => "Bounty Cancelled".to_string(),
+            NexusEvent::BountyClosed(_) => "Bounty Closed".to_string(),
             NexusEvent::SubmissionReceived(_) => "New Submission Received".to_string(),
             NexusEvent::SubmissionValidated(_) => "Submission Validated".to_string(),
             NexusEvent::SubmissionRejected(_) => "Submission Rejected".to_string(),
@@ -313,14 +594,48 @@ impl NexusEvent {
             NexusEvent::AnalysisCompleted(_) => "Analysis Completed".to_string(),
             NexusEvent::AnalysisFailed(_) => "Analysis Failed".to_string(),
             NexusEvent::ReputationUpdated(_) => "Reputation Updated".to_string(),
+            NexusEvent::BadgeAwarded(e) => format!("Badge Earned: {}", e.badge_name),
+            NexusEvent::ReputationRankChanged(e) => format!("Leaderboard Rank: #{}", e.new_rank),
+            NexusEvent::StreakMilestoneReached(e) => format!("{} Correct in a Row", e.streak),
             NexusEvent::PaymentProcessed(_) => "Payment Processed".to_string(),
             NexusEvent::PaymentFailed(_) => "Payment Failed".to_string(),
             NexusEvent::StakeSlashed(_) => "Stake Slashed".to_string(),
+            NexusEvent::WithdrawalUpdated(e) => match e.kind {
+                WithdrawalEventKind::Requested => "Withdrawal Requested",
+                WithdrawalEventKind::Held => "Withdrawal Awaiting Approval",
+                WithdrawalEventKind::ApprovalRecorded => "Withdrawal Approval Recorded",
+                WithdrawalEventKind::Approved => "Withdrawal Approved",
+                WithdrawalEventKind::Rejected => "Withdrawal Rejected",
+                WithdrawalEventKind::Queued => "Withdrawal Queued",
+                WithdrawalEventKind::Sent => "Withdrawal Sent",
+                WithdrawalEventKind::Completed => "Withdrawal Completed",
+                WithdrawalEventKind::Failed => "Withdrawal Failed",
+                WithdrawalEventKind::AddressAdded => "Withdrawal Address Added",
+                WithdrawalEventKind::AddressRemoved => "Withdrawal Address Removed",
+            }
+            .to_string(),
+            NexusEvent::PaymentUpdated(e) => match e.kind {
+                PaymentEventKind::EscrowConfirmed => "Bounty Reward Deposit Confirmed",
+                PaymentEventKind::PayoutSent => "Reward Payout Sent",
+                PaymentEventKind::PayoutConfirmed => "Reward Payout Confirmed",
+                PaymentEventKind::SlashExecuted => "Stake Slashed",
+                PaymentEventKind::WithdrawalCompleted => "Withdrawal Completed",
+                PaymentEventKind::RefundIssued => "Bounty Reward Refunded",
+                PaymentEventKind::SlashScheduled => "Stake Slash Scheduled",
+            }
+            .to_string(),
             NexusEvent::UserRegistered(_) => "Welcome to Nexus Security!".to_string(),
             NexusEvent::UserVerified(_) => "Account Verified".to_string(),
+            NexusEvent::MagicLinkRequested(_) => "Your Nexus Security sign-in link".to_string(),
+            NexusEvent::AccountLocked(_) => "Sign-ins to your account were paused".to_string(),
+            NexusEvent::NewDeviceLogin(e) if e.new_country => "New sign-in from another country".to_string(),
+            NexusEvent::NewDeviceLogin(_) => "New sign-in from a new device".to_string(),
+            NexusEvent::OrganizationInvitationSent(e) => format!("Join {} on Nexus Security", e.organization_name),
             NexusEvent::EngineRegistered(_) => "Engine Registered".to_string(),
             NexusEvent::DisputeCreated(_) => "Dispute Created".to_string(),
             NexusEvent::DisputeResolved(_) => "Dispute Resolved".to_string(),
+            NexusEvent::ConsensusReached(_) => "Consensus Reached".to_string(),
+            NexusEvent::SettlementPlanned(_) => "Settlement Planned".to_string(),
             NexusEvent::SystemAlert(e) => format!("System Alert: {}", e.title),
         }
     }
@@ -344,6 +659,82 @@ impl NexusEvent {
                 "Your reputation has changed from {} to {}. Reason: {}",
                 e.old_score, e.new_score, e.change_reason
             ),
+            NexusEvent::BadgeAwarded(e) => format!(
+                "You earned the {} badge {}: {}",
+                e.badge_name, e.icon, e.description
+            ),
+            NexusEvent::ReputationRankChanged(e) => format!(
+                "You moved from #{} to #{} on the leaderboard with a score of {}",
+                e.old_rank, e.new_rank, e.score
+            ),
+            NexusEvent::StreakMilestoneReached(e) => format!(
+                "Your last {} settled submissions were all correct",
+                e.streak
+            ),
+            NexusEvent::MagicLinkRequested(e) => format!(
+                "Use this link to sign in. It can be used once and expires at {}.",
+                e.expires_at.to_rfc3339()
+            ),
+            NexusEvent::NewDeviceLogin(e) => {
+                let mut location = e.ip_address.clone().unwrap_or_else(|| "an unknown address".to_string());
+                if let Some(country) = &e.country {
+                    location.push_str(&format!(" ({})", country));
+                }
+                format!(
+                    "Your account was signed in to from {} at {} on {}. If this was not you, \
+                     end the session from your session list and change your password.",
+                    e.device,
+                    location,
+                    e.logged_in_at.to_rfc3339()
+                )
+            }
+            NexusEvent::OrganizationInvitationSent(e) => format!(
+                "{} invited you to join {} as {}. The invitation expires at {}.",
+                e.invited_by,
+                e.organization_name,
+                e.role,
+                e.expires_at.to_rfc3339()
+            ),
+            NexusEvent::WithdrawalUpdated(e) => {
+                let mut description = match (&e.amount, &e.address) {
+                    (Some(amount), Some(address)) => format!("Withdrawal of {} to {}", amount, address),
+                    (None, Some(address)) => format!("Address {}", address),
+                    _ => "Your withdrawal".to_string(),
+                };
+                if let Some(reason) = &e.reason {
+                    description.push_str(&format!(": {}", reason));
+                }
+                description.push_str(". If this was not you, contact support right away.");
+                description
+            }
+            NexusEvent::PaymentUpdated(e) => {
+                let amount = e.amount.as_deref().unwrap_or("An amount");
+                let mut description = match e.kind {
+                    PaymentEventKind::EscrowConfirmed => format!("{} was deposited as the bounty reward", amount),
+                    PaymentEventKind::PayoutSent => format!("{} of your reward is on its way", amount),
+                    PaymentEventKind::PayoutConfirmed => format!("{} of your reward was paid", amount),
+                    PaymentEventKind::SlashExecuted => format!("{} of your stake was slashed", amount),
+                    PaymentEventKind::WithdrawalCompleted => format!("{} was withdrawn", amount),
+                    PaymentEventKind::RefundIssued => format!("{} of the bounty reward is being returned to you", amount),
+                    PaymentEventKind::SlashScheduled => {
+                        format!("{} of your stake will be slashed unless you appeal before the deadline", amount)
+                    }
+                };
+                if let Some(tx_hash) = &e.tx_hash {
+                    description.push_str(&format!(". Transaction: {}", tx_hash));
+                }
+                description
+            }
+            NexusEvent::AccountLocked(e) => format!(
+                "We paused sign-ins after {} failed password attempts{}. You can sign in again after {}. \
+                 If this was not you, reset your password and enable two-factor authentication.",
+                e.failed_attempts,
+                e.ip_address
+                    .as_ref()
+                    .map(|ip| format!(" from {}", ip))
+                    .unwrap_or_default(),
+                e.locked_until.to_rfc3339()
+            ),
             _ => "Event occurred".to_string(),
         }
     }
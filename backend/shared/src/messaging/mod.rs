@@ -1,12 +1,14 @@
 /// Messaging and event handling utilities
 pub mod event_types;
 pub mod publisher;
+pub mod subscriber;
 
 // Kafka client module - currently stubbed for future implementation
 // pub mod kafka_client;
 
 pub use event_types::*;
 pub use publisher::*;
+pub use subscriber::*;
 
 /// Message queue trait for abstracting different messaging backends
 #[async_trait::async_trait]
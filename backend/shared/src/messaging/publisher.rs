@@ -74,39 +74,63 @@ impl EventPublisher {
 
     /// Get the Redis channel name for a given event
     fn get_channel_for_event(&self, event: &NexusEvent) -> String {
-        let event_name = match event {
-            NexusEvent::BountyCreated(_) => "bounty_created",
-            NexusEvent::BountyUpdated(_) => "bounty_updated",
-            NexusEvent::BountyCompleted(_) => "bounty_completed",
-            NexusEvent::BountyExpired(_) => "bounty_expired",
-            NexusEvent::BountyCancelled(_) => "bounty_cancelled",
-
-            NexusEvent::SubmissionReceived(_) => "submission_received",
-            NexusEvent::SubmissionValidated(_) => "submission_validated",
-            NexusEvent::SubmissionRejected(_) => "submission_rejected",
-
-            NexusEvent::AnalysisStarted(_) => "analysis_started",
-            NexusEvent::AnalysisCompleted(_) => "analysis_completed",
-            NexusEvent::AnalysisFailed(_) => "analysis_failed",
-
-            NexusEvent::ReputationUpdated(_) => "reputation_updated",
-
-            NexusEvent::PaymentProcessed(_) => "payment_processed",
-            NexusEvent::PaymentFailed(_) => "payment_failed",
-            NexusEvent::StakeSlashed(_) => "stake_slashed",
-
-            NexusEvent::UserRegistered(_) => "user_registered",
-            NexusEvent::UserVerified(_) => "user_verified",
-            NexusEvent::EngineRegistered(_) => "engine_registered",
-
-            NexusEvent::DisputeCreated(_) => "dispute_created",
-            NexusEvent::DisputeResolved(_) => "dispute_resolved",
+        channel_for_event(event)
+    }
+}
 
-            NexusEvent::SystemAlert(_) => "system_alert",
-        };
+/// Redis channel an event is published on
+fn channel_for_event(event: &NexusEvent) -> String {
+    let event_name = match event {
+        NexusEvent::BountyCreated(_) => "bounty_created",
+        NexusEvent::BountyUpdated(_) => "bounty_updated",
+        NexusEvent::BountyCompleted(_) => "bounty_completed",
+        NexusEvent::BountyExpired(_) => "bounty_expired",
+        NexusEvent::BountyCancelled(_) => "bounty_cancelled",
+        NexusEvent::BountyClosed(_) => "bounty_closed",
+
+        NexusEvent::SubmissionReceived(_) => "submission_received",
+        NexusEvent::SubmissionValidated(_) => "submission_validated",
+        NexusEvent::SubmissionRejected(_) => "submission_rejected",
+
+        NexusEvent::AnalysisStarted(_) => "analysis_started",
+        NexusEvent::AnalysisCompleted(_) => "analysis_completed",
+        NexusEvent::AnalysisFailed(_) => "analysis_failed",
+
+        NexusEvent::ReputationUpdated(_) => "reputation_updated",
+        NexusEvent::BadgeAwarded(_) => "badge_awarded",
+        NexusEvent::ReputationRankChanged(_) => "reputation_rank_changed",
+        NexusEvent::StreakMilestoneReached(_) => "streak_milestone_reached",
+
+        NexusEvent::PaymentProcessed(_) => "payment_processed",
+        NexusEvent::PaymentFailed(_) => "payment_failed",
+        NexusEvent::StakeSlashed(_) => "stake_slashed",
+        NexusEvent::WithdrawalUpdated(_) => "withdrawal_updated",
+        NexusEvent::PaymentUpdated(_) => "payment_updated",
+
+        NexusEvent::UserRegistered(_) => "user_registered",
+        NexusEvent::UserVerified(_) => "user_verified",
+        NexusEvent::MagicLinkRequested(_) => "magic_link_requested",
+        NexusEvent::AccountLocked(_) => "account_locked",
+        NexusEvent::NewDeviceLogin(_) => "new_device_login",
+        NexusEvent::OrganizationInvitationSent(_) => "organization_invitation_sent",
+        NexusEvent::EngineRegistered(_) => "engine_registered",
+
+        NexusEvent::DisputeCreated(_) => "dispute_created",
+        NexusEvent::DisputeResolved(_) => "dispute_resolved",
+
+        NexusEvent::ConsensusReached(_) => "consensus_reached",
+        NexusEvent::SettlementPlanned(_) => "settlement_planned",
+
+        NexusEvent::SystemAlert(_) => "system_alert",
+    };
+
+    event_channel(event_name)
+}
 
-        format!("{}{}", EVENT_CHANNEL_PREFIX, event_name)
-    }
+/// Redis channel events of the given name (e.g. `bounty_closed`) are
+/// published on
+pub fn event_channel(event_name: &str) -> String {
+    format!("{}{}", EVENT_CHANNEL_PREFIX, event_name)
 }
 
 /// Publish a single event (convenience function)
@@ -115,6 +139,24 @@ pub async fn publish_event(redis_client: &redis::Client, event: &NexusEvent) ->
     publisher.publish(event).await
 }
 
+/// Publish a single event over a connection the caller already holds, for
+/// services that keep one open rather than connecting per event
+pub async fn publish_event_on<C>(conn: &mut C, event: &NexusEvent) -> Result<()>
+where
+    C: redis::aio::ConnectionLike + Send,
+{
+    let channel = channel_for_event(event);
+    let payload = serde_json::to_string(event)
+        .map_err(|e| anyhow!("Failed to serialize event: {}", e))?;
+
+    conn.publish::<_, _, ()>(&channel, payload)
+        .await
+        .map_err(|e| anyhow!("Failed to publish event to {}: {}", channel, e))?;
+
+    info!("Published event to channel: {}", channel);
+    Ok(())
+}
+
 /// Publish a JSON payload to a specific channel (generic version)
 pub async fn publish_to_channel<T: Serialize>(
     redis_client: &redis::Client,
@@ -169,7 +211,7 @@ mod tests {
         let event = NexusEvent::PaymentProcessed(PaymentProcessedEvent {
             bounty_id: Uuid::new_v4(),
             recipient_id: Uuid::new_v4(),
-            amount: 1000.0,
+            amount: 1000,
             tx_hash: "0x1234567890abcdef".to_string(),
             payment_type: PaymentType::BountyReward,
             processed_at: Utc::now(),
@@ -178,4 +220,23 @@ mod tests {
         let channel = publisher.get_channel_for_event(&event);
         assert_eq!(channel, "events:payment_processed");
     }
+
+    #[test]
+    fn test_consensus_event_channel() {
+        let redis_client = redis::Client::open("redis://localhost:6379").unwrap();
+        let publisher = EventPublisher::new(redis_client);
+
+        let event = NexusEvent::ConsensusReached(ConsensusReachedEvent {
+            bounty_id: Uuid::new_v4(),
+            final_verdict: crate::types::common::ThreatVerdict::Malicious,
+            confidence: 0.9,
+            agreement_score: 80.0,
+            total_submissions: 5,
+            reached_at: Utc::now(),
+        });
+
+        let channel = publisher.get_channel_for_event(&event);
+        assert_eq!(channel, event_channel("consensus_reached"));
+        assert_eq!(channel, "events:consensus_reached");
+    }
 }
\ No newline at end of file
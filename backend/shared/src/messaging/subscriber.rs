@@ -0,0 +1,69 @@
+use std::time::Duration;
+
+use anyhow::{Result, anyhow};
+use futures::{Stream, StreamExt};
+use tracing::{info, warn};
+
+use super::event_types::NexusEvent;
+use super::publisher::event_channel;
+
+/// Wait before subscribing again after a subscription's stream ends
+pub const RESUBSCRIBE_DELAY: Duration = Duration::from_secs(5);
+
+/// Event subscriber for Redis Pub/Sub, receiving what `EventPublisher`
+/// publishes. Pub/Sub does not keep messages for subscribers that are not
+/// connected, so consumers must tolerate missed events.
+pub struct EventSubscriber {
+    redis_client: redis::Client,
+}
+
+impl EventSubscriber {
+    /// Create a new event subscriber
+    pub fn new(redis_client: redis::Client) -> Self {
+        Self { redis_client }
+    }
+
+    /// Create from Redis URL
+    pub fn from_url(redis_url: &str) -> Result<Self> {
+        let redis_client = redis::Client::open(redis_url)
+            .map_err(|e| anyhow!("Failed to create Redis client: {}", e))?;
+        Ok(Self { redis_client })
+    }
+
+    /// Subscribe to events by name (e.g. `bounty_closed`). The stream ends
+    /// when the connection drops; subscribe again to carry on. Messages that
+    /// are not events are skipped.
+    pub async fn subscribe(&self, event_names: &[&str]) -> Result<impl Stream<Item = NexusEvent>> {
+        let conn = self.redis_client
+            .get_async_connection()
+            .await
+            .map_err(|e| anyhow!("Failed to connect to Redis: {}", e))?;
+        let mut pubsub = conn.into_pubsub();
+
+        for event_name in event_names {
+            let channel = event_channel(event_name);
+            pubsub.subscribe(&channel)
+                .await
+                .map_err(|e| anyhow!("Failed to subscribe to {}: {}", channel, e))?;
+            info!("Subscribed to channel: {}", channel);
+        }
+
+        Ok(pubsub.into_on_message().filter_map(|msg| async move {
+            let channel = msg.get_channel_name().to_string();
+            let payload: String = match msg.get_payload() {
+                Ok(payload) => payload,
+                Err(e) => {
+                    warn!("Failed to get message payload from {}: {}", channel, e);
+                    return None;
+                }
+            };
+            match serde_json::from_str(&payload) {
+                Ok(event) => Some(event),
+                Err(e) => {
+                    warn!("Ignoring malformed event on {}: {}", channel, e);
+                    None
+                }
+            }
+        }))
+    }
+}
@@ -1,8 +1,37 @@
 //! Structured logging setup for all services
+//!
+//! The JSON format writes one object per event with the service name and
+//! version plus `request_id`, `trace_id` and `user_id` taken from the
+//! task-local [`LogContext`]. The active filter can be changed at runtime
+//! through [`set_log_level`], which services expose as an admin endpoint.
 
-use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+use std::cell::RefCell;
+use std::fmt as std_fmt;
+use std::future::Future;
+use std::sync::{OnceLock, RwLock};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{fmt, layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter, Registry};
+use uuid::Uuid;
+
+use super::tracing::TraceParent;
 use super::{ObservabilityError, ObservabilityResult};
 
+/// Header carrying the request ID between services
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+/// W3C trace context header
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+/// Header carrying the admin token for the log-level endpoint
+pub const ADMIN_TOKEN_HEADER: &str = "x-admin-token";
+/// Environment variable holding the admin token; the endpoint is disabled when unset
+pub const ADMIN_TOKEN_ENV: &str = "LOG_ADMIN_TOKEN";
+
 /// Log level configuration
 #[derive(Debug, Clone, Copy)]
 pub enum LogLevel {
@@ -23,6 +52,17 @@ impl LogLevel {
             LogLevel::Error => "error",
         }
     }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "trace" => Some(LogLevel::Trace),
+            "debug" => Some(LogLevel::Debug),
+            "info" => Some(LogLevel::Info),
+            "warn" | "warning" => Some(LogLevel::Warn),
+            "error" => Some(LogLevel::Error),
+            _ => None,
+        }
+    }
 }
 
 /// Log format configuration
@@ -36,11 +76,23 @@ pub enum LogFormat {
     Compact,
 }
 
+impl LogFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "pretty" => Some(LogFormat::Pretty),
+            "json" => Some(LogFormat::Json),
+            "compact" => Some(LogFormat::Compact),
+            _ => None,
+        }
+    }
+}
+
 /// Logging configuration
 pub struct LogConfig {
     pub level: LogLevel,
     pub format: LogFormat,
     pub service_name: String,
+    pub service_version: String,
     pub include_line_numbers: bool,
     pub include_thread_ids: bool,
 }
@@ -51,22 +103,293 @@ impl Default for LogConfig {
             level: LogLevel::Info,
             format: LogFormat::Pretty,
             service_name: "nexus-service".to_string(),
+            service_version: env!("CARGO_PKG_VERSION").to_string(),
             include_line_numbers: true,
             include_thread_ids: false,
         }
     }
 }
 
+impl LogConfig {
+    /// Standard configuration for a service: JSON output unless `LOG_FORMAT`
+    /// says otherwise, level from `LOG_LEVEL` (`RUST_LOG` still takes precedence)
+    pub fn for_service(service_name: &str, service_version: &str) -> Self {
+        Self {
+            level: std::env::var("LOG_LEVEL")
+                .ok()
+                .and_then(|l| LogLevel::parse(&l))
+                .unwrap_or(LogLevel::Info),
+            format: std::env::var("LOG_FORMAT")
+                .ok()
+                .and_then(|f| LogFormat::parse(&f))
+                .unwrap_or(LogFormat::Json),
+            service_name: service_name.to_string(),
+            service_version: service_version.to_string(),
+            include_line_numbers: false,
+            include_thread_ids: false,
+        }
+    }
+}
+
+// ─── Task-local request context ───
+
+/// Correlation fields attached to every log line emitted inside a request
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogContext {
+    pub request_id: Option<String>,
+    pub trace_id: Option<String>,
+    /// This service's span in the trace, the parent of outgoing calls
+    pub span_id: Option<String>,
+    pub sampled: bool,
+    pub user_id: Option<Uuid>,
+}
+
+impl LogContext {
+    /// Build a context from incoming headers, generating a request ID if the
+    /// caller did not send one. The trace continues a W3C `traceparent`
+    /// header when present and is otherwise started from the request ID.
+    pub fn from_headers(request_id: Option<&str>, traceparent: Option<&str>) -> Self {
+        let request_id = request_id
+            .map(str::trim)
+            .filter(|id| !id.is_empty() && id.len() <= 128)
+            .map(str::to_string)
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        let trace = traceparent
+            .and_then(TraceParent::parse)
+            .map(|parent| parent.child())
+            .unwrap_or_else(|| TraceParent::root(&request_id));
+
+        Self {
+            request_id: Some(request_id),
+            trace_id: Some(trace.trace_id),
+            span_id: Some(trace.parent_id),
+            sampled: trace.sampled,
+            user_id: None,
+        }
+    }
+}
+
+tokio::task_local! {
+    static LOG_CONTEXT: RefCell<LogContext>;
+}
+
+/// Run `future` with `context` attached to all of its log lines.
+///
+/// Task-locals do not cross `tokio::spawn`; wrap spawned work again with
+/// [`current_log_context`] to keep the correlation fields.
+pub async fn with_log_context<F: Future>(context: LogContext, future: F) -> F::Output {
+    LOG_CONTEXT.scope(RefCell::new(context), future).await
+}
+
+/// The context of the current task, if any
+pub fn current_log_context() -> Option<LogContext> {
+    LOG_CONTEXT.try_with(|ctx| ctx.borrow().clone()).ok()
+}
+
+/// Attach the authenticated user to the current task's context
+pub fn set_log_user_id(user_id: Uuid) {
+    let _ = LOG_CONTEXT.try_with(|ctx| ctx.borrow_mut().user_id = Some(user_id));
+}
+
+// ─── JSON event format ───
+
+/// Collects event fields into a JSON map
+struct JsonVisitor<'a>(&'a mut Map<String, Value>);
+
+impl Visit for JsonVisitor<'_> {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std_fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), Value::from(format!("{:?}", value)));
+    }
+}
+
+/// Standard JSON line format shared by all services
+pub struct NexusJsonFormat {
+    service: String,
+    version: String,
+}
+
+impl NexusJsonFormat {
+    pub fn new(service: &str, version: &str) -> Self {
+        Self {
+            service: service.to_string(),
+            version: version.to_string(),
+        }
+    }
+
+    fn to_json(&self, event: &Event<'_>, context: Option<LogContext>, span: Option<&str>) -> Value {
+        let metadata = event.metadata();
+        let mut fields = Map::new();
+        event.record(&mut JsonVisitor(&mut fields));
+
+        let mut line = Map::new();
+        line.insert(
+            "timestamp".to_string(),
+            Value::from(chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true)),
+        );
+        line.insert("level".to_string(), Value::from(metadata.level().as_str()));
+        line.insert("service".to_string(), Value::from(self.service.as_str()));
+        line.insert("version".to_string(), Value::from(self.version.as_str()));
+        line.insert("target".to_string(), Value::from(metadata.target()));
+        line.insert(
+            "message".to_string(),
+            fields.remove("message").unwrap_or(Value::Null),
+        );
+
+        let context = context.unwrap_or_default();
+        line.insert("request_id".to_string(), Value::from(context.request_id));
+        line.insert("trace_id".to_string(), Value::from(context.trace_id));
+        line.insert(
+            "user_id".to_string(),
+            Value::from(context.user_id.map(|id| id.to_string())),
+        );
+
+        if let Some(span) = span {
+            line.insert("span".to_string(), Value::from(span));
+        }
+        if !fields.is_empty() {
+            line.insert("fields".to_string(), Value::Object(fields));
+        }
+
+        Value::Object(line)
+    }
+}
+
+impl<S, N> FormatEvent<S, N> for NexusJsonFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> std_fmt::Result {
+        let span = ctx.lookup_current().map(|span| span.name());
+        let line = self.to_json(event, current_log_context(), span);
+        writeln!(writer, "{}", line)
+    }
+}
+
+// ─── Runtime log level ───
+
+/// Reloads the global filter without restarting the service
+pub struct LogLevelHandle {
+    handle: reload::Handle<EnvFilter, Registry>,
+    current: RwLock<String>,
+}
+
+impl LogLevelHandle {
+    /// Replace the active filter. Accepts a level (`debug`) or full
+    /// `EnvFilter` directives (`info,sqlx=warn`).
+    pub fn set(&self, directives: &str) -> ObservabilityResult<()> {
+        let filter = EnvFilter::try_new(directives)
+            .map_err(|e| ObservabilityError::Logging(format!("Invalid log filter: {}", e)))?;
+        self.handle
+            .reload(filter)
+            .map_err(|e| ObservabilityError::Logging(e.to_string()))?;
+        *self.current.write().unwrap() = directives.to_string();
+        Ok(())
+    }
+
+    pub fn current(&self) -> String {
+        self.current.read().unwrap().clone()
+    }
+}
+
+static LOG_LEVEL_HANDLE: OnceLock<LogLevelHandle> = OnceLock::new();
+
+/// Handle to the global filter, available once logging is initialized
+pub fn log_level_handle() -> Option<&'static LogLevelHandle> {
+    LOG_LEVEL_HANDLE.get()
+}
+
+/// Change the global log filter at runtime
+pub fn set_log_level(directives: &str) -> ObservabilityResult<()> {
+    let handle = log_level_handle()
+        .ok_or_else(|| ObservabilityError::Logging("Logging is not initialized".to_string()))?;
+    handle.set(directives)?;
+    tracing::warn!(filter = %directives, "Log level changed");
+    Ok(())
+}
+
+/// Body of the admin log-level endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogLevelBody {
+    pub level: String,
+}
+
+/// Check a presented admin token against `LOG_ADMIN_TOKEN` in constant time.
+/// Always fails when the variable is unset.
+pub fn admin_token_matches(presented: Option<&str>) -> bool {
+    let (Ok(expected), Some(presented)) = (std::env::var(ADMIN_TOKEN_ENV), presented) else {
+        return false;
+    };
+    if expected.is_empty() || expected.len() != presented.len() {
+        return false;
+    }
+    expected
+        .bytes()
+        .zip(presented.bytes())
+        .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+        == 0
+}
+
+// ─── Initialization ───
+
+type ExportLayer<S> = Box<dyn tracing_subscriber::Layer<S> + Send + Sync>;
+
+/// Span export next to the log output, see [`super::tracing`]
+#[cfg(feature = "otel")]
+fn export_layer<S>(config: &LogConfig) -> ObservabilityResult<Option<ExportLayer<S>>>
+where
+    S: Subscriber + for<'a> LookupSpan<'a> + Send + Sync,
+{
+    super::tracing::otlp_layer(&config.service_name, &config.service_version)
+}
+
+#[cfg(not(feature = "otel"))]
+fn export_layer<S>(_config: &LogConfig) -> ObservabilityResult<Option<ExportLayer<S>>>
+where
+    S: Subscriber + for<'a> LookupSpan<'a> + Send + Sync,
+{
+    Ok(None)
+}
+
 /// Initialize logging for the service
 pub fn init_logging(config: LogConfig) -> ObservabilityResult<()> {
     // Create filter from environment or config
     let env_filter = EnvFilter::try_from_default_env()
         .unwrap_or_else(|_| EnvFilter::new(config.level.as_str()));
+    let initial_filter = env_filter.to_string();
+    let (filter_layer, handle) = reload::Layer::new(env_filter);
 
     match config.format {
         LogFormat::Pretty => {
             tracing_subscriber::registry()
-                .with(env_filter)
+                .with(filter_layer)
+                .with(export_layer(&config)?)
                 .with(
                     fmt::layer()
                         .with_target(true)
@@ -79,21 +402,21 @@ pub fn init_logging(config: LogConfig) -> ObservabilityResult<()> {
         }
         LogFormat::Json => {
             tracing_subscriber::registry()
-                .with(env_filter)
+                .with(filter_layer)
+                .with(export_layer(&config)?)
                 .with(
-                    fmt::layer()
-                        .json()
-                        .with_target(true)
-                        .with_current_span(true)
-                        .with_thread_ids(config.include_thread_ids)
-                        .with_line_number(config.include_line_numbers)
+                    fmt::layer().event_format(NexusJsonFormat::new(
+                        &config.service_name,
+                        &config.service_version,
+                    ))
                 )
                 .try_init()
                 .map_err(|e| ObservabilityError::Logging(e.to_string()))?;
         }
         LogFormat::Compact => {
             tracing_subscriber::registry()
-                .with(env_filter)
+                .with(filter_layer)
+                .with(export_layer(&config)?)
                 .with(
                     fmt::layer()
                         .compact()
@@ -105,8 +428,14 @@ pub fn init_logging(config: LogConfig) -> ObservabilityResult<()> {
         }
     }
 
+    let _ = LOG_LEVEL_HANDLE.set(LogLevelHandle {
+        handle,
+        current: RwLock::new(initial_filter),
+    });
+
     tracing::info!(
         service = %config.service_name,
+        version = %config.service_version,
         level = %config.level.as_str(),
         "Logging initialized"
     );
@@ -130,9 +459,105 @@ pub fn init_production_logging(service_name: &str) -> ObservabilityResult<()> {
         level: LogLevel::Info,
         include_line_numbers: false,
         include_thread_ids: true,
+        ..Default::default()
     })
 }
 
+/// Standard logging for a service, see [`LogConfig::for_service`]
+pub fn init_service_logging(service_name: &str, service_version: &str) -> ObservabilityResult<()> {
+    init_logging(LogConfig::for_service(service_name, service_version))
+}
+
+// ─── axum integration ───
+
+#[cfg(feature = "axum")]
+mod http {
+    use axum::{
+        extract::Request,
+        http::{HeaderMap, HeaderValue, StatusCode},
+        middleware::Next,
+        response::{IntoResponse, Response},
+        routing::get,
+        Json, Router,
+    };
+    use tracing::Instrument;
+
+    use super::*;
+
+    /// Attach a [`LogContext`] built from the request headers to everything
+    /// the handler logs, and echo the request ID back to the caller.
+    ///
+    /// A generated request ID is also written to the request headers so
+    /// handlers forwarding them pass it on. With span export on, the handler
+    /// runs in a `request` span parented to the caller's `traceparent`.
+    pub async fn log_context_middleware(mut request: Request, next: Next) -> Response {
+        let (context, span) = {
+            let header = |name: &str| request.headers().get(name).and_then(|v| v.to_str().ok());
+            let context = LogContext::from_headers(header(REQUEST_ID_HEADER), header(TRACEPARENT_HEADER));
+            let span = crate::observability::tracing::request_span(
+                request.method().as_str(),
+                request.uri().path(),
+                context.request_id.as_deref().unwrap_or_default(),
+                header(TRACEPARENT_HEADER),
+            );
+            (context, span)
+        };
+        let request_id = HeaderValue::from_str(context.request_id.as_deref().unwrap_or_default()).ok();
+        if let Some(ref value) = request_id {
+            request.headers_mut().insert(REQUEST_ID_HEADER, value.clone());
+        }
+
+        let mut response = with_log_context(context, next.run(request).instrument(span)).await;
+        if let Some(value) = request_id {
+            response.headers_mut().insert(REQUEST_ID_HEADER, value);
+        }
+        response
+    }
+
+    fn authorized(headers: &HeaderMap) -> bool {
+        admin_token_matches(headers.get(ADMIN_TOKEN_HEADER).and_then(|v| v.to_str().ok()))
+    }
+
+    fn forbidden() -> Response {
+        (StatusCode::FORBIDDEN, Json(serde_json::json!({ "error": "Forbidden" }))).into_response()
+    }
+
+    async fn get_log_level(headers: HeaderMap) -> Response {
+        if !authorized(&headers) {
+            return forbidden();
+        }
+        match log_level_handle() {
+            Some(handle) => Json(LogLevelBody { level: handle.current() }).into_response(),
+            None => StatusCode::SERVICE_UNAVAILABLE.into_response(),
+        }
+    }
+
+    async fn put_log_level(headers: HeaderMap, Json(body): Json<LogLevelBody>) -> Response {
+        if !authorized(&headers) {
+            return forbidden();
+        }
+        match set_log_level(&body.level) {
+            Ok(()) => Json(body).into_response(),
+            Err(e) => (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": e.to_string() })),
+            )
+                .into_response(),
+        }
+    }
+
+    /// `GET`/`PUT /admin/log-level`, protected by the `X-Admin-Token` header
+    pub fn log_level_routes<S>() -> Router<S>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        Router::new().route("/admin/log-level", get(get_log_level).put(put_log_level))
+    }
+}
+
+#[cfg(feature = "axum")]
+pub use http::{log_context_middleware, log_level_routes};
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -141,5 +566,48 @@ mod tests {
     fn test_log_level_conversion() {
         assert_eq!(LogLevel::Info.as_str(), "info");
         assert_eq!(LogLevel::Error.as_str(), "error");
+        assert!(matches!(LogLevel::parse("WARNING"), Some(LogLevel::Warn)));
+        assert!(LogLevel::parse("loud").is_none());
+    }
+
+    #[test]
+    fn test_context_from_headers() {
+        let ctx = LogContext::from_headers(
+            Some("req-1"),
+            Some("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"),
+        );
+        assert_eq!(ctx.request_id.as_deref(), Some("req-1"));
+        assert_eq!(ctx.trace_id.as_deref(), Some("4bf92f3577b34da6a3ce929d0e0e4736"));
+
+        assert_ne!(ctx.span_id.as_deref(), Some("00f067aa0ba902b7"));
+        assert!(ctx.sampled);
+
+        let generated = LogContext::from_headers(None, Some("garbage"));
+        let request_id = generated.request_id.clone().unwrap();
+        assert_eq!(generated.trace_id, Some(request_id.replace('-', "")));
+        assert!(generated.span_id.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_task_local_context() {
+        assert!(current_log_context().is_none());
+
+        let user_id = Uuid::new_v4();
+        let ctx = LogContext::from_headers(Some("req-2"), None);
+        let seen = with_log_context(ctx, async move {
+            set_log_user_id(user_id);
+            current_log_context()
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(seen.request_id.as_deref(), Some("req-2"));
+        assert_eq!(seen.user_id, Some(user_id));
+    }
+
+    #[test]
+    fn test_admin_token_requires_configuration() {
+        std::env::remove_var(ADMIN_TOKEN_ENV);
+        assert!(!admin_token_matches(Some("anything")));
     }
 }
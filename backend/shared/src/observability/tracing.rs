@@ -1,9 +1,19 @@
 //! Distributed tracing utilities for request tracking
+//!
+//! Requests carry an `X-Request-Id` and a W3C `traceparent` from the gateway
+//! through every service they touch; [`propagation_headers`] gives the pair
+//! to send on an outgoing call from the current [`LogContext`]. With the
+//! `otel` feature and `OTEL_EXPORTER_OTLP_ENDPOINT` set, spans are also
+//! exported over OTLP so one trace shows the whole call chain.
 
+use std::fmt;
 use std::time::Instant;
+use rand::RngCore;
 use tracing::{info, warn, Span};
 use uuid::Uuid;
 
+use super::logging::{current_log_context, LogContext, REQUEST_ID_HEADER, TRACEPARENT_HEADER};
+
 /// Request tracing context
 #[derive(Debug, Clone)]
 pub struct RequestContext {
@@ -61,6 +71,239 @@ impl RequestContext {
     }
 }
 
+// ─── W3C trace context ───
+
+/// A W3C `traceparent` (`00-<trace id>-<parent id>-<flags>`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceParent {
+    /// 32 lowercase hex digits
+    pub trace_id: String,
+    /// Span of the caller, 16 lowercase hex digits
+    pub parent_id: String,
+    pub sampled: bool,
+}
+
+impl TraceParent {
+    /// Parse a `traceparent` header; invalid or all-zero IDs are rejected
+    pub fn parse(header: &str) -> Option<Self> {
+        let mut parts = header.trim().split('-');
+        let version = parts.next()?;
+        let trace_id = parts.next()?;
+        let parent_id = parts.next()?;
+        let flags = parts.next()?;
+        if version.len() != 2 || version.eq_ignore_ascii_case("ff") || !is_hex(version) {
+            return None;
+        }
+        if !is_trace_id(trace_id, 32) || !is_trace_id(parent_id, 16) || flags.len() != 2 {
+            return None;
+        }
+        let flags = u8::from_str_radix(flags, 16).ok()?;
+
+        Some(Self {
+            trace_id: trace_id.to_lowercase(),
+            parent_id: parent_id.to_lowercase(),
+            sampled: flags & 0x01 == 1,
+        })
+    }
+
+    /// Start a trace. A UUID request ID doubles as the trace ID, so logs
+    /// of requests that arrived without a `traceparent` still line up.
+    pub fn root(request_id: &str) -> Self {
+        let trace_id = Uuid::parse_str(request_id)
+            .ok()
+            .filter(|id| !id.is_nil())
+            .map(|id| id.simple().to_string())
+            .unwrap_or_else(|| random_hex(16));
+        Self {
+            trace_id,
+            parent_id: random_hex(8),
+            sampled: true,
+        }
+    }
+
+    /// The same trace with a new span as parent
+    pub fn child(&self) -> Self {
+        Self {
+            trace_id: self.trace_id.clone(),
+            parent_id: random_hex(8),
+            sampled: self.sampled,
+        }
+    }
+}
+
+impl fmt::Display for TraceParent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "00-{}-{}-{:02x}", self.trace_id, self.parent_id, self.sampled as u8)
+    }
+}
+
+fn is_hex(s: &str) -> bool {
+    s.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn is_trace_id(s: &str, len: usize) -> bool {
+    s.len() == len && is_hex(s) && s.chars().any(|c| c != '0')
+}
+
+fn random_hex(bytes: usize) -> String {
+    let mut buf = vec![0u8; bytes];
+    rand::thread_rng().fill_bytes(&mut buf);
+    buf.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Request ID of the current task
+pub fn current_request_id() -> Option<String> {
+    current_log_context().and_then(|ctx| ctx.request_id)
+}
+
+/// `X-Request-Id` and `traceparent` for a call made on behalf of the
+/// current request. Outside a request a fresh ID and trace are started, so
+/// the callee's logs can still be matched with the caller's.
+pub fn propagation_headers() -> [(&'static str, String); 2] {
+    let context = current_log_context().unwrap_or_default();
+    let request_id = context
+        .request_id
+        .clone()
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    #[cfg(feature = "otel")]
+    if let Some(traceparent) = otel::current_traceparent() {
+        return [
+            (REQUEST_ID_HEADER, request_id),
+            (TRACEPARENT_HEADER, traceparent.to_string()),
+        ];
+    }
+
+    let traceparent = outgoing_traceparent(&context).unwrap_or_else(|| TraceParent::root(&request_id));
+    [
+        (REQUEST_ID_HEADER, request_id),
+        (TRACEPARENT_HEADER, traceparent.to_string()),
+    ]
+}
+
+/// The current span of the request becomes the parent of the callee's
+fn outgoing_traceparent(context: &LogContext) -> Option<TraceParent> {
+    Some(TraceParent {
+        trace_id: context.trace_id.clone()?,
+        parent_id: context.span_id.clone()?,
+        sampled: context.sampled,
+    })
+}
+
+/// Span of an incoming request. With span export on it joins the caller's
+/// trace from its `traceparent` header.
+pub fn request_span(method: &str, path: &str, request_id: &str, traceparent: Option<&str>) -> Span {
+    let span = tracing::info_span!(
+        "request",
+        method = %method,
+        path = %path,
+        request_id = %request_id,
+    );
+    #[cfg(feature = "otel")]
+    if let Some(parent) = traceparent.and_then(TraceParent::parse) {
+        otel::set_remote_parent(&span, &parent);
+    }
+    #[cfg(not(feature = "otel"))]
+    let _ = traceparent;
+    span
+}
+
+// ─── OTLP export ───
+
+/// OTLP collector endpoint; export is off when unset
+pub const OTLP_ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+
+#[cfg(feature = "otel")]
+mod otel {
+    use std::sync::OnceLock;
+
+    use opentelemetry::trace::{TraceContextExt, TracerProvider as _};
+    use opentelemetry::KeyValue;
+    use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+    use opentelemetry_sdk::trace::TracerProvider;
+    use opentelemetry_sdk::{runtime, Resource};
+    use tracing::Subscriber;
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+    use tracing_subscriber::registry::LookupSpan;
+    use tracing_subscriber::Layer;
+
+    use super::{TraceParent, OTLP_ENDPOINT_ENV};
+    use crate::observability::{ObservabilityError, ObservabilityResult};
+
+    static PROVIDER: OnceLock<TracerProvider> = OnceLock::new();
+
+    /// Layer exporting spans to the collector at `OTEL_EXPORTER_OTLP_ENDPOINT`
+    pub fn otlp_layer<S>(
+        service_name: &str,
+        service_version: &str,
+    ) -> ObservabilityResult<Option<Box<dyn Layer<S> + Send + Sync>>>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a> + Send + Sync,
+    {
+        let Some(endpoint) = std::env::var(OTLP_ENDPOINT_ENV).ok().filter(|e| !e.is_empty()) else {
+            return Ok(None);
+        };
+
+        let exporter = SpanExporter::builder()
+            .with_tonic()
+            .with_endpoint(endpoint)
+            .build()
+            .map_err(|e| ObservabilityError::Tracing(e.to_string()))?;
+        let provider = TracerProvider::builder()
+            .with_batch_exporter(exporter, runtime::Tokio)
+            .with_resource(Resource::new([
+                KeyValue::new("service.name", service_name.to_string()),
+                KeyValue::new("service.version", service_version.to_string()),
+            ]))
+            .build();
+        let tracer = provider.tracer(service_name.to_string());
+
+        opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+        opentelemetry::global::set_tracer_provider(provider.clone());
+        let _ = PROVIDER.set(provider);
+
+        Ok(Some(tracing_opentelemetry::layer().with_tracer(tracer).boxed()))
+    }
+
+    /// Make the span of an incoming request a child of the caller's
+    pub(super) fn set_remote_parent(span: &tracing::Span, traceparent: &TraceParent) {
+        let mut carrier = std::collections::HashMap::new();
+        carrier.insert("traceparent".to_string(), traceparent.to_string());
+        let parent = opentelemetry::global::get_text_map_propagator(|p| p.extract(&carrier));
+        span.set_parent(parent);
+    }
+
+    /// `traceparent` of the current exported span, if export is on
+    pub fn current_traceparent() -> Option<TraceParent> {
+        PROVIDER.get()?;
+        let context = tracing::Span::current().context();
+        let span = context.span();
+        let span_context = span.span_context();
+        span_context.is_valid().then(|| TraceParent {
+            trace_id: span_context.trace_id().to_string(),
+            parent_id: span_context.span_id().to_string(),
+            sampled: span_context.is_sampled(),
+        })
+    }
+
+    /// Flush spans still buffered by the batch exporter
+    pub fn shutdown_tracing() {
+        if let Some(provider) = PROVIDER.get() {
+            if let Err(e) = provider.shutdown() {
+                tracing::warn!("Failed to flush spans: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "otel")]
+pub use otel::{otlp_layer, shutdown_tracing};
+
+/// Without the `otel` feature there is nothing to flush
+#[cfg(not(feature = "otel"))]
+pub fn shutdown_tracing() {}
+
 /// Create a new span for a database query
 pub fn db_query_span(query: &str) -> Span {
     tracing::info_span!(
@@ -119,6 +362,57 @@ mod tests {
         assert_eq!(ctx_with_user.user_id, Some(user_id));
     }
 
+    #[test]
+    fn test_traceparent_round_trip() {
+        let header = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let parent = TraceParent::parse(header).unwrap();
+        assert_eq!(parent.trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(parent.parent_id, "00f067aa0ba902b7");
+        assert!(parent.sampled);
+        assert_eq!(parent.to_string(), header);
+
+        let child = parent.child();
+        assert_eq!(child.trace_id, parent.trace_id);
+        assert_ne!(child.parent_id, parent.parent_id);
+        assert!(TraceParent::parse(&child.to_string()).is_some());
+    }
+
+    #[test]
+    fn test_traceparent_rejects_invalid() {
+        for header in [
+            "garbage",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e473-00f067aa0ba902b7-01",
+        ] {
+            assert!(TraceParent::parse(header).is_none(), "{}", header);
+        }
+    }
+
+    #[test]
+    fn test_root_trace_follows_request_id() {
+        let request_id = "7f1b7a5e-3c1d-4f57-9a0e-2b6f1d9e4c21";
+        assert_eq!(TraceParent::root(request_id).trace_id, "7f1b7a5e3c1d4f579a0e2b6f1d9e4c21");
+        assert_eq!(TraceParent::root("not-a-uuid").trace_id.len(), 32);
+    }
+
+    #[tokio::test]
+    async fn test_propagation_headers_continue_trace() {
+        let ctx = LogContext::from_headers(
+            Some("req-3"),
+            Some("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"),
+        );
+        let span_id = ctx.span_id.clone().unwrap();
+        let [(_, request_id), (_, traceparent)] =
+            super::super::logging::with_log_context(ctx, async { propagation_headers() }).await;
+
+        assert_eq!(request_id, "req-3");
+        let traceparent = TraceParent::parse(&traceparent).unwrap();
+        assert_eq!(traceparent.trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(traceparent.parent_id, span_id);
+    }
+
     #[test]
     fn test_elapsed_time() {
         let ctx = RequestContext::new("/test".to_string(), "POST".to_string());
@@ -0,0 +1,201 @@
+//! Permission scopes
+//!
+//! Callers are granted permissions through roles: the built-in role of their
+//! account, roles granted to them and roles granted to their organization
+//! (`role_permissions`, `user_roles`, `organization_roles`). Access tokens
+//! carry the resulting permissions as `scopes`, and the gateway forwards the
+//! ones a request may use in the signed `x-user-scopes` header, space
+//! separated. Services gate their admin routes with [`require_scope`] and read
+//! a caller's scopes with the [`Scopes`] extractor.
+//!
+//! Callers forwarded without scopes get those of their `x-user-role`, so
+//! requests from gateways and services that predate scopes keep working:
+//! `admin` grants everything, every other role nothing.
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// Header carrying the caller's scopes
+pub const SCOPES_HEADER: &str = "x-user-scopes";
+
+/// Permission that implies every other one
+pub const ALL: &str = "admin";
+/// Approve and reject KYC submissions and revoke certificates
+pub const KYC_REVIEW: &str = "kyc:review";
+/// List, suspend and reactivate accounts
+pub const USERS_MANAGE: &str = "users:manage";
+/// Act as another user
+pub const USERS_IMPERSONATE: &str = "users:impersonate";
+/// Moderate bounties: embargoes, reports, takedowns and rehydration
+pub const BOUNTY_MODERATE: &str = "bounty:moderate";
+/// Approve withdrawals and operate the treasury, payouts and fees
+pub const PAYMENTS_APPROVE: &str = "payments:approve";
+/// Override, replay and recalculate consensus and review collusion
+pub const CONSENSUS_OVERRIDE: &str = "consensus:override";
+/// Adjust reputation, decay, badges and appeals
+pub const REPUTATION_MANAGE: &str = "reputation:manage";
+
+/// Permissions services enforce, with a description
+pub const SERVICE_PERMISSIONS: &[(&str, &str)] = &[
+    (KYC_REVIEW, "Review KYC submissions and revoke certificates"),
+    (USERS_MANAGE, "List, suspend and reactivate accounts"),
+    (USERS_IMPERSONATE, "Act as another user"),
+    (BOUNTY_MODERATE, "Moderate bounties, embargoes and reports"),
+    (PAYMENTS_APPROVE, "Approve withdrawals and operate the treasury"),
+    (CONSENSUS_OVERRIDE, "Override, replay and recalculate consensus"),
+    (REPUTATION_MANAGE, "Adjust reputation, decay, badges and appeals"),
+];
+
+/// A caller's permission scopes
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Scopes(BTreeSet<String>);
+
+impl Scopes {
+    /// Scopes from a space-separated list
+    pub fn parse(value: &str) -> Self {
+        value.split_whitespace().map(str::to_string).collect()
+    }
+
+    /// Scopes of a caller forwarded with only a role
+    pub fn for_role(role: &str) -> Self {
+        if role == ALL {
+            std::iter::once(ALL.to_string()).collect()
+        } else {
+            Self::default()
+        }
+    }
+
+    /// Scopes from the forwarded `x-user-scopes` and `x-user-role` headers
+    pub fn from_header_values(scopes: Option<&str>, role: Option<&str>) -> Self {
+        match (scopes, role) {
+            (Some(scopes), _) => Self::parse(scopes),
+            (None, Some(role)) => Self::for_role(role),
+            (None, None) => Self::default(),
+        }
+    }
+
+    /// Whether the scopes grant `scope`, directly or through [`ALL`]
+    pub fn allows(&self, scope: &str) -> bool {
+        self.0.contains(scope) || self.0.contains(ALL)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(String::as_str)
+    }
+}
+
+impl<S: Into<String>> FromIterator<S> for Scopes {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        Self(iter.into_iter().map(Into::into).filter(|s: &String| !s.is_empty()).collect())
+    }
+}
+
+/// Space-separated, as in the header
+impl fmt::Display for Scopes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let joined: Vec<&str> = self.iter().collect();
+        f.write_str(&joined.join(" "))
+    }
+}
+
+#[cfg(feature = "axum")]
+mod http {
+    use super::{Scopes, SCOPES_HEADER};
+    use axum::{
+        async_trait,
+        extract::{FromRequestParts, Request},
+        http::{request::Parts, HeaderMap, StatusCode},
+        middleware::Next,
+        response::{IntoResponse, Json, Response},
+    };
+    use futures::future::BoxFuture;
+    use serde_json::json;
+    use std::convert::Infallible;
+
+    impl Scopes {
+        pub fn from_headers(headers: &HeaderMap) -> Self {
+            let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+            Self::from_header_values(header(SCOPES_HEADER), header("x-user-role"))
+        }
+    }
+
+    /// Scopes a service resolved itself (inserted as an extension, e.g. from
+    /// its own access token) win over the forwarded headers
+    #[async_trait]
+    impl<S: Send + Sync> FromRequestParts<S> for Scopes {
+        type Rejection = Infallible;
+
+        async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+            Ok(match parts.extensions.get::<Scopes>() {
+                Some(scopes) => scopes.clone(),
+                None => Scopes::from_headers(&parts.headers),
+            })
+        }
+    }
+
+    /// Middleware requiring a scope, for a group of routes:
+    ///
+    /// ```ignore
+    /// Router::new()
+    ///     .route("/api/v1/admin/kyc/:id/approve", post(approve))
+    ///     .route_layer(axum::middleware::from_fn(require_scope(KYC_REVIEW)))
+    /// ```
+    ///
+    /// Requests with no caller identity get 401, callers without the scope 403.
+    pub fn require_scope(
+        scope: &'static str,
+    ) -> impl Fn(Request, Next) -> BoxFuture<'static, Response> + Clone + Send + Sync + 'static {
+        move |request, next| {
+            Box::pin(async move {
+                let scopes = match request.extensions().get::<Scopes>() {
+                    Some(scopes) => scopes.clone(),
+                    None if request.headers().contains_key("x-user-id") => Scopes::from_headers(request.headers()),
+                    None => {
+                        return (StatusCode::UNAUTHORIZED, Json(json!({"error": "Authentication required"})))
+                            .into_response()
+                    }
+                };
+                if scopes.allows(scope) {
+                    next.run(request).await
+                } else {
+                    (
+                        StatusCode::FORBIDDEN,
+                        Json(json!({"error": format!("Missing permission {}", scope)})),
+                    )
+                        .into_response()
+                }
+            })
+        }
+    }
+}
+
+#[cfg(feature = "axum")]
+pub use http::require_scope;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scopes_grant_listed_permissions() {
+        let scopes = Scopes::parse("kyc:review  users:manage");
+        assert!(scopes.allows(KYC_REVIEW));
+        assert!(!scopes.allows(PAYMENTS_APPROVE));
+        assert_eq!(scopes.to_string(), "kyc:review users:manage");
+        assert!(Scopes::parse("admin").allows(CONSENSUS_OVERRIDE));
+    }
+
+    #[test]
+    fn test_role_fallback_only_without_scopes() {
+        assert!(Scopes::from_header_values(None, Some("admin")).allows(BOUNTY_MODERATE));
+        assert!(Scopes::from_header_values(None, Some("user")).is_empty());
+        // Forwarded scopes are authoritative, even for admins
+        let narrowed = Scopes::from_header_values(Some("kyc:review"), Some("admin"));
+        assert!(!narrowed.allows(BOUNTY_MODERATE));
+        assert!(Scopes::from_header_values(Some(""), Some("admin")).is_empty());
+    }
+}
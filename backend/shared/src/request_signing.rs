@@ -0,0 +1,768 @@
+//! HMAC signing of service-to-service requests
+//!
+//! Internal services trust the gateway: it authenticates callers and passes
+//! their identity on in `x-user-id`/`x-user-role`. Without a signature anyone
+//! who can reach a service on the network could call it directly with any
+//! identity, or hit internal endpoints such as stake slashing. The gateway
+//! (and any service calling another) signs each request with
+//! [`RequestSigner`], and every service checks it with
+//! [`verify_signature_middleware`].
+//!
+//! A signature covers the method, path and query, a timestamp, the SHA-256
+//! of the body and the identity headers:
+//!
+//! ```text
+//! NEXUS-HMAC-SHA256
+//! POST
+//! /api/v1/payments/stake/slash
+//! 1718031234
+//! 5f70bf18a086007016e948b04aed3b82103a36bea41755b6cddfaf10ace3c6ef
+//! 6c1bb5a0-1d7e-4c55-9ac4-5fd7b43e7bd1
+//! admin
+//! ```
+//!
+//! When the caller acts for an organization the gateway also passes
+//! `x-org-id`/`x-org-role`, and both are appended as two more lines. The
+//! caller's permission scopes (`x-user-scopes`, see [`crate::permissions`])
+//! follow as a last line, after the organization lines (empty if there is
+//! none). Requests without either keep the form above.
+//!
+//! Streamed uploads too large to buffer are signed with
+//! [`UNSIGNED_PAYLOAD`] in place of the body hash. Services only accept that
+//! on routes they list with [`SignatureVerifier::allow_unsigned_payload`].
+//!
+//! Keys come from `SERVICE_SIGNING_KEYS`, a comma-separated list of
+//! `key_id:secret` pairs with secrets of at least 32 bytes. Signers use the
+//! first key and verifiers accept all of them, so a key is rotated by adding
+//! the new one at the end everywhere, moving it to the front, then removing
+//! the old one. Verification is off when the variable is unset.
+//!
+//! Timestamps more than `SERVICE_SIGNING_MAX_SKEW_SECONDS` (300 by default)
+//! from the verifier's clock are rejected. Within that window a captured
+//! request can be replayed; endpoints that move funds should stay idempotent.
+//!
+//! Events that move funds, such as settlement plans, are signed the same way
+//! with [`RequestSigner::sign_event`]: the method is `EVENT`, the path the
+//! event name and the body the event's JSON payload.
+
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+
+use crate::messaging::EventSignature;
+
+pub const KEYS_ENV: &str = "SERVICE_SIGNING_KEYS";
+pub const MAX_SKEW_ENV: &str = "SERVICE_SIGNING_MAX_SKEW_SECONDS";
+
+pub const KEY_ID_HEADER: &str = "x-nexus-key-id";
+pub const TIMESTAMP_HEADER: &str = "x-nexus-timestamp";
+pub const CONTENT_SHA256_HEADER: &str = "x-nexus-content-sha256";
+pub const SIGNATURE_HEADER: &str = "x-nexus-signature";
+/// Identity headers the signature covers, in signing order
+pub const SIGNED_IDENTITY_HEADERS: [&str; 5] =
+    ["x-user-id", "x-user-role", "x-org-id", "x-org-role", crate::permissions::SCOPES_HEADER];
+
+/// Content hash of a request whose body is streamed without being hashed
+pub const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+/// Largest body a signer buffers to hash and a verifier buffers to check
+pub const MAX_SIGNED_BODY_BYTES: usize = 1024 * 1024;
+
+const ALGORITHM: &str = "NEXUS-HMAC-SHA256";
+/// Method signed for events in place of an HTTP method
+const EVENT_METHOD: &str = "EVENT";
+const MIN_SECRET_BYTES: usize = 32;
+const DEFAULT_MAX_SKEW: Duration = Duration::from_secs(300);
+
+/// Paths verifiers never check: health probes and the operator routes that
+/// carry their own `X-Admin-Token`
+const DEFAULT_EXEMPT_PATHS: &[&str] = &["/health", "/admin/log-level", "/admin/feature-flags"];
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Debug, Error)]
+pub enum SigningError {
+    #[error("Invalid {KEYS_ENV}: {0}")]
+    InvalidKeys(String),
+    #[error("Missing {0} header")]
+    MissingHeader(&'static str),
+    #[error("Unknown signing key {0}")]
+    UnknownKey(String),
+    #[error("Request timestamp is outside the allowed clock skew")]
+    Expired,
+    #[error("Body does not match the signed content hash")]
+    BodyMismatch,
+    #[error("Unsigned payloads are not accepted on this route")]
+    UnsignedPayload,
+    #[error("Signature mismatch")]
+    BadSignature,
+}
+
+#[derive(Clone)]
+struct SigningKey {
+    id: String,
+    secret: Vec<u8>,
+}
+
+impl std::fmt::Debug for SigningKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SigningKey").field("id", &self.id).finish_non_exhaustive()
+    }
+}
+
+/// Parse `key_id:secret[,key_id:secret...]`
+fn parse_keys(value: &str) -> Result<Vec<SigningKey>, SigningError> {
+    let mut keys = Vec::new();
+    for entry in value.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let (id, secret) = entry
+            .split_once(':')
+            .ok_or_else(|| SigningError::InvalidKeys("expected key_id:secret".to_string()))?;
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            return Err(SigningError::InvalidKeys(format!("invalid key id {:?}", id)));
+        }
+        if secret.len() < MIN_SECRET_BYTES {
+            return Err(SigningError::InvalidKeys(format!(
+                "secret for {} is shorter than {} bytes",
+                id, MIN_SECRET_BYTES
+            )));
+        }
+        if keys.iter().any(|k: &SigningKey| k.id == id) {
+            return Err(SigningError::InvalidKeys(format!("duplicate key id {}", id)));
+        }
+        keys.push(SigningKey { id: id.to_string(), secret: secret.as_bytes().to_vec() });
+    }
+    if keys.is_empty() {
+        return Err(SigningError::InvalidKeys("no keys".to_string()));
+    }
+    Ok(keys)
+}
+
+/// Keys from the environment, `None` when signing is not configured
+fn keys_from_env() -> Result<Option<Vec<SigningKey>>, SigningError> {
+    match std::env::var(KEYS_ENV) {
+        Ok(value) if !value.trim().is_empty() => parse_keys(&value).map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// Hex SHA-256 of a request body
+pub fn content_sha256(body: &[u8]) -> String {
+    hex::encode(Sha256::digest(body))
+}
+
+/// The signed parts of a request
+#[derive(Debug, Clone, Copy)]
+pub struct SignedRequest<'a> {
+    pub method: &'a str,
+    /// Path and query exactly as sent, e.g. `/api/v1/items?page=2`
+    pub path_and_query: &'a str,
+    pub timestamp: i64,
+    /// [`content_sha256`] of the body, or [`UNSIGNED_PAYLOAD`]
+    pub content_sha256: &'a str,
+    pub identity: SignedIdentity<'a>,
+}
+
+/// The caller a signed request is made for
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SignedIdentity<'a> {
+    pub user_id: Option<&'a str>,
+    pub user_role: Option<&'a str>,
+    pub org_id: Option<&'a str>,
+    pub org_role: Option<&'a str>,
+    /// Space-separated permission scopes
+    pub scopes: Option<&'a str>,
+}
+
+impl<'a> SignedIdentity<'a> {
+    pub fn user(user_id: Option<&'a str>, user_role: Option<&'a str>) -> Self {
+        Self { user_id, user_role, ..Self::default() }
+    }
+
+    pub fn with_organization(mut self, org_id: Option<&'a str>, org_role: Option<&'a str>) -> Self {
+        self.org_id = org_id;
+        self.org_role = org_role;
+        self
+    }
+
+    pub fn with_scopes(mut self, scopes: Option<&'a str>) -> Self {
+        self.scopes = scopes;
+        self
+    }
+}
+
+impl SignedRequest<'_> {
+    fn canonical(&self) -> String {
+        let identity = &self.identity;
+        let mut lines = [
+            ALGORITHM,
+            &self.method.to_ascii_uppercase(),
+            self.path_and_query,
+            &self.timestamp.to_string(),
+            self.content_sha256,
+            identity.user_id.unwrap_or(""),
+            identity.user_role.unwrap_or(""),
+        ]
+        .join("\n");
+        if identity.org_id.is_some() || identity.org_role.is_some() || identity.scopes.is_some() {
+            for part in [identity.org_id, identity.org_role] {
+                lines.push('\n');
+                lines.push_str(part.unwrap_or(""));
+            }
+        }
+        if let Some(scopes) = identity.scopes {
+            lines.push('\n');
+            lines.push_str(scopes);
+        }
+        lines
+    }
+
+    fn mac(&self, secret: &[u8]) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts any key length");
+        mac.update(self.canonical().as_bytes());
+        mac
+    }
+}
+
+/// Headers to add to a signed request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureHeaders {
+    pub key_id: String,
+    pub timestamp: String,
+    pub content_sha256: String,
+    pub signature: String,
+}
+
+impl SignatureHeaders {
+    /// `(name, value)` pairs, ready to set on any HTTP client's request
+    pub fn pairs(&self) -> [(&'static str, &str); 4] {
+        [
+            (KEY_ID_HEADER, &self.key_id),
+            (TIMESTAMP_HEADER, &self.timestamp),
+            (CONTENT_SHA256_HEADER, &self.content_sha256),
+            (SIGNATURE_HEADER, &self.signature),
+        ]
+    }
+}
+
+/// Signs outgoing requests with the first configured key
+#[derive(Debug, Clone)]
+pub struct RequestSigner {
+    key: Arc<SigningKey>,
+}
+
+impl RequestSigner {
+    pub fn new(key_id: &str, secret: &str) -> Result<Self, SigningError> {
+        let key = parse_keys(&format!("{}:{}", key_id, secret))?.remove(0);
+        Ok(Self { key: Arc::new(key) })
+    }
+
+    /// Signer for `SERVICE_SIGNING_KEYS`, `None` when it is unset
+    pub fn from_env() -> Result<Option<Self>, SigningError> {
+        Ok(keys_from_env()?.map(|mut keys| Self { key: Arc::new(keys.remove(0)) }))
+    }
+
+    pub fn key_id(&self) -> &str {
+        &self.key.id
+    }
+
+    /// Sign a request; `timestamp` is normally `Utc::now().timestamp()`
+    pub fn sign(&self, request: &SignedRequest<'_>) -> SignatureHeaders {
+        let signature = request.mac(&self.key.secret).finalize().into_bytes();
+        SignatureHeaders {
+            key_id: self.key.id.clone(),
+            timestamp: request.timestamp.to_string(),
+            content_sha256: request.content_sha256.to_string(),
+            signature: hex::encode(signature),
+        }
+    }
+
+    /// Sign a request sent now with this body (`None` for a streamed body)
+    pub fn sign_now(
+        &self,
+        method: &str,
+        path_and_query: &str,
+        body: Option<&[u8]>,
+        user_id: Option<&str>,
+        user_role: Option<&str>,
+    ) -> SignatureHeaders {
+        self.sign_identity_now(method, path_and_query, body, SignedIdentity::user(user_id, user_role))
+    }
+
+    /// [`Self::sign_now`] for a caller that may act for an organization
+    pub fn sign_identity_now(
+        &self,
+        method: &str,
+        path_and_query: &str,
+        body: Option<&[u8]>,
+        identity: SignedIdentity<'_>,
+    ) -> SignatureHeaders {
+        let content_sha256 = body.map_or_else(|| UNSIGNED_PAYLOAD.to_string(), content_sha256);
+        self.sign(&SignedRequest {
+            method,
+            path_and_query,
+            timestamp: chrono::Utc::now().timestamp(),
+            content_sha256: &content_sha256,
+            identity,
+        })
+    }
+
+    /// Sign an event's JSON payload, published now under `event_name`
+    pub fn sign_event(&self, event_name: &str, payload: &[u8]) -> EventSignature {
+        let timestamp = chrono::Utc::now().timestamp();
+        let signed = self.sign(&SignedRequest {
+            method: EVENT_METHOD,
+            path_and_query: event_name,
+            timestamp,
+            content_sha256: &content_sha256(payload),
+            identity: SignedIdentity::default(),
+        });
+        EventSignature {
+            key_id: signed.key_id,
+            timestamp,
+            signature: signed.signature,
+        }
+    }
+}
+
+struct VerifierInner {
+    keys: HashMap<String, Vec<u8>>,
+    max_skew: Duration,
+    exempt_paths: Vec<String>,
+    unsigned_payload_paths: Vec<String>,
+}
+
+/// Checks signatures on incoming requests. Cheap to clone.
+#[derive(Clone)]
+pub struct SignatureVerifier {
+    inner: Option<Arc<VerifierInner>>,
+}
+
+/// Whether `path` is `prefix` or lies below it
+fn path_matches(path: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim_end_matches('/');
+    path.strip_prefix(prefix).is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+impl SignatureVerifier {
+    pub fn disabled() -> Self {
+        Self { inner: None }
+    }
+
+    /// Verifier accepting every key in `SERVICE_SIGNING_KEYS`, disabled when
+    /// the variable is unset
+    pub fn from_env() -> Result<Self, SigningError> {
+        let Some(keys) = keys_from_env()? else {
+            tracing::warn!("{} not set, service request signatures are not verified", KEYS_ENV);
+            return Ok(Self::disabled());
+        };
+        let max_skew = std::env::var(MAX_SKEW_ENV)
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_MAX_SKEW);
+        let verifier = Self::with_keys(keys, max_skew);
+        tracing::info!("Verifying service request signatures");
+        Ok(verifier)
+    }
+
+    /// Verifier for a single key, mainly for tests
+    pub fn new(key_id: &str, secret: &str, max_skew: Duration) -> Result<Self, SigningError> {
+        Ok(Self::with_keys(parse_keys(&format!("{}:{}", key_id, secret))?, max_skew))
+    }
+
+    fn with_keys(keys: Vec<SigningKey>, max_skew: Duration) -> Self {
+        Self {
+            inner: Some(Arc::new(VerifierInner {
+                keys: keys.into_iter().map(|k| (k.id, k.secret)).collect(),
+                max_skew,
+                exempt_paths: DEFAULT_EXEMPT_PATHS.iter().map(|p| p.to_string()).collect(),
+                unsigned_payload_paths: Vec::new(),
+            })),
+        }
+    }
+
+    fn update(mut self, f: impl FnOnce(&mut VerifierInner)) -> Self {
+        if let Some(inner) = self.inner.as_mut().and_then(Arc::get_mut) {
+            f(inner);
+        }
+        self
+    }
+
+    /// Skip verification for `path` and everything below it, for endpoints
+    /// called from outside the mesh that authenticate callers themselves
+    pub fn exempt(self, path: &str) -> Self {
+        let path = path.to_string();
+        self.update(|inner| inner.exempt_paths.push(path))
+    }
+
+    /// Accept streamed bodies signed with [`UNSIGNED_PAYLOAD`] on `path` and
+    /// below, for upload routes whose bodies the gateway does not buffer
+    pub fn allow_unsigned_payload(self, path: &str) -> Self {
+        let path = path.to_string();
+        self.update(|inner| inner.unsigned_payload_paths.push(path))
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.inner.is_some()
+    }
+
+    /// Whether requests to `path` need a signature
+    pub fn requires_signature(&self, path: &str) -> bool {
+        self.inner
+            .as_ref()
+            .is_some_and(|inner| !inner.exempt_paths.iter().any(|p| path_matches(path, p)))
+    }
+
+    /// Whether `path` accepts [`UNSIGNED_PAYLOAD`]
+    pub fn accepts_unsigned_payload(&self, path: &str) -> bool {
+        self.inner
+            .as_ref()
+            .is_some_and(|inner| inner.unsigned_payload_paths.iter().any(|p| path_matches(path, p)))
+    }
+
+    /// Check a request's signature headers. `body` is the buffered body, or
+    /// `None` when the request claims [`UNSIGNED_PAYLOAD`].
+    pub fn verify<'a>(
+        &self,
+        header: impl Fn(&str) -> Option<&'a str>,
+        method: &str,
+        path_and_query: &str,
+        body: Option<&[u8]>,
+        now: i64,
+    ) -> Result<(), SigningError> {
+        let Some(inner) = self.inner.as_ref() else {
+            return Ok(());
+        };
+        let required = |name: &'static str| header(name).ok_or(SigningError::MissingHeader(name));
+
+        let key_id = required(KEY_ID_HEADER)?;
+        let secret = inner
+            .keys
+            .get(key_id)
+            .ok_or_else(|| SigningError::UnknownKey(key_id.to_string()))?;
+
+        let timestamp: i64 = required(TIMESTAMP_HEADER)?
+            .parse()
+            .map_err(|_| SigningError::Expired)?;
+        if now.abs_diff(timestamp) > inner.max_skew.as_secs() {
+            return Err(SigningError::Expired);
+        }
+
+        let claimed_hash = required(CONTENT_SHA256_HEADER)?;
+        match body {
+            Some(body) if content_sha256(body) != claimed_hash.to_ascii_lowercase() => {
+                return Err(SigningError::BodyMismatch)
+            }
+            Some(_) => {}
+            None if claimed_hash != UNSIGNED_PAYLOAD => return Err(SigningError::BodyMismatch),
+            None => {
+                let path = path_and_query.split('?').next().unwrap_or_default();
+                if !self.accepts_unsigned_payload(path) {
+                    return Err(SigningError::UnsignedPayload);
+                }
+            }
+        }
+
+        let signature = hex::decode(required(SIGNATURE_HEADER)?)
+            .map_err(|_| SigningError::BadSignature)?;
+        let [user_id, user_role, org_id, org_role, scopes] = SIGNED_IDENTITY_HEADERS.map(&header);
+        SignedRequest {
+            method,
+            path_and_query,
+            timestamp,
+            content_sha256: claimed_hash,
+            identity: SignedIdentity { user_id, user_role, org_id, org_role, scopes },
+        }
+        .mac(secret)
+        .verify_slice(&signature)
+        .map_err(|_| SigningError::BadSignature)
+    }
+
+    /// Check the signature on an event's JSON payload published under
+    /// `event_name`
+    pub fn verify_event(
+        &self,
+        event_name: &str,
+        payload: &[u8],
+        signature: &EventSignature,
+        now: i64,
+    ) -> Result<(), SigningError> {
+        let timestamp = signature.timestamp.to_string();
+        let content_sha256 = content_sha256(payload);
+        let header = |name: &str| match name {
+            KEY_ID_HEADER => Some(signature.key_id.as_str()),
+            TIMESTAMP_HEADER => Some(timestamp.as_str()),
+            CONTENT_SHA256_HEADER => Some(content_sha256.as_str()),
+            SIGNATURE_HEADER => Some(signature.signature.as_str()),
+            _ => None,
+        };
+        self.verify(header, EVENT_METHOD, event_name, Some(payload), now)
+    }
+}
+
+// ─── axum integration ───
+
+#[cfg(feature = "axum")]
+mod http {
+    use axum::{
+        body::Body,
+        extract::{Request, State},
+        http::StatusCode,
+        middleware::Next,
+        response::{IntoResponse, Response},
+        Json,
+    };
+
+    use super::*;
+
+    fn rejected(status: StatusCode) -> Response {
+        (status, Json(serde_json::json!({ "error": "Invalid service signature" }))).into_response()
+    }
+
+    /// Rejects requests without a valid service signature with 401. Install
+    /// it with
+    /// `axum::middleware::from_fn_with_state(SignatureVerifier::from_env()?, verify_signature_middleware)`;
+    /// it passes requests straight through when verification is disabled.
+    ///
+    /// Signed bodies are buffered (up to [`MAX_SIGNED_BODY_BYTES`]) to check
+    /// their hash, then handed on unchanged.
+    pub async fn verify_signature_middleware(
+        State(verifier): State<SignatureVerifier>,
+        request: Request,
+        next: Next,
+    ) -> Response {
+        if !verifier.requires_signature(request.uri().path()) {
+            return next.run(request).await;
+        }
+
+        let (parts, body) = request.into_parts();
+        let path_and_query = parts
+            .uri
+            .path_and_query()
+            .map(|pq| pq.as_str())
+            .unwrap_or_else(|| parts.uri.path())
+            .to_string();
+        let header = |name: &str| parts.headers.get(name).and_then(|v| v.to_str().ok());
+
+        let unsigned = header(CONTENT_SHA256_HEADER) == Some(UNSIGNED_PAYLOAD);
+        let (body, bytes) = if unsigned {
+            (body, None)
+        } else {
+            match axum::body::to_bytes(body, MAX_SIGNED_BODY_BYTES).await {
+                Ok(bytes) => (Body::from(bytes.clone()), Some(bytes)),
+                Err(_) => return rejected(StatusCode::PAYLOAD_TOO_LARGE),
+            }
+        };
+
+        let result = verifier.verify(
+            header,
+            parts.method.as_str(),
+            &path_and_query,
+            bytes.as_deref(),
+            chrono::Utc::now().timestamp(),
+        );
+        if let Err(e) = result {
+            tracing::warn!(
+                method = %parts.method,
+                path = %parts.uri.path(),
+                "Rejected service request: {}",
+                e
+            );
+            return rejected(StatusCode::UNAUTHORIZED);
+        }
+
+        next.run(Request::from_parts(parts, body)).await
+    }
+}
+
+#[cfg(feature = "axum")]
+pub use http::verify_signature_middleware;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "0123456789abcdef0123456789abcdef";
+
+    fn headers_fn<'a>(
+        signed: &'a SignatureHeaders,
+        user: Option<&'static str>,
+    ) -> impl Fn(&str) -> Option<&'a str> + 'a {
+        move |name| match name {
+            KEY_ID_HEADER => Some(signed.key_id.as_str()),
+            TIMESTAMP_HEADER => Some(signed.timestamp.as_str()),
+            CONTENT_SHA256_HEADER => Some(signed.content_sha256.as_str()),
+            SIGNATURE_HEADER => Some(signed.signature.as_str()),
+            "x-user-id" => user,
+            _ => None,
+        }
+    }
+
+    fn pair() -> (RequestSigner, SignatureVerifier) {
+        (
+            RequestSigner::new("k1", SECRET).unwrap(),
+            SignatureVerifier::new("k1", SECRET, DEFAULT_MAX_SKEW).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_signed_request_verifies() {
+        let (signer, verifier) = pair();
+        let body = br#"{"amount":"10"}"#;
+        let signed = signer.sign_now("post", "/api/v1/payments/stake/slash?x=1", Some(body), Some("u1"), None);
+        let now = chrono::Utc::now().timestamp();
+
+        assert!(verifier
+            .verify(headers_fn(&signed, Some("u1")), "POST", "/api/v1/payments/stake/slash?x=1", Some(body), now)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_tampering_is_rejected() {
+        let (signer, verifier) = pair();
+        let body = br#"{"amount":"10"}"#;
+        let path = "/api/v1/payments/stake/slash";
+        let signed = signer.sign_now("POST", path, Some(body), Some("u1"), None);
+        let now = chrono::Utc::now().timestamp();
+
+        let verify = |user, method, path, body: &[u8], now| {
+            verifier.verify(headers_fn(&signed, user), method, path, Some(body), now)
+        };
+        assert!(matches!(verify(Some("u2"), "POST", path, body, now), Err(SigningError::BadSignature)));
+        assert!(matches!(verify(Some("u1"), "PUT", path, body, now), Err(SigningError::BadSignature)));
+        assert!(matches!(
+            verify(Some("u1"), "POST", "/api/v1/payments/stake/unlock", body, now),
+            Err(SigningError::BadSignature)
+        ));
+        assert!(matches!(
+            verify(Some("u1"), "POST", path, br#"{"amount":"99"}"#, now),
+            Err(SigningError::BodyMismatch)
+        ));
+        assert!(matches!(verify(Some("u1"), "POST", path, body, now + 301), Err(SigningError::Expired)));
+
+        let other = SignatureVerifier::new("k1", &SECRET.repeat(2), DEFAULT_MAX_SKEW).unwrap();
+        assert!(matches!(
+            other.verify(headers_fn(&signed, Some("u1")), "POST", path, Some(body), now),
+            Err(SigningError::BadSignature)
+        ));
+        let rotated = SignatureVerifier::new("k2", SECRET, DEFAULT_MAX_SKEW).unwrap();
+        assert!(matches!(
+            rotated.verify(headers_fn(&signed, Some("u1")), "POST", path, Some(body), now),
+            Err(SigningError::UnknownKey(_))
+        ));
+    }
+
+    #[test]
+    fn test_organization_headers_are_signed() {
+        let (signer, verifier) = pair();
+        let path = "/api/v1/bounties";
+        let identity = SignedIdentity::user(Some("u1"), Some("user")).with_organization(Some("o1"), Some("admin"));
+        let signed = signer.sign_identity_now("POST", path, Some(b"{}"), identity);
+        let now = chrono::Utc::now().timestamp();
+
+        let verify = |org_role| {
+            let header = |name: &str| match name {
+                "x-user-role" => Some("user"),
+                "x-org-id" => Some("o1"),
+                "x-org-role" => org_role,
+                other => headers_fn(&signed, Some("u1"))(other),
+            };
+            verifier.verify(header, "POST", path, Some(b"{}"), now)
+        };
+        assert!(verify(Some("admin")).is_ok());
+        assert!(matches!(verify(Some("owner")), Err(SigningError::BadSignature)));
+        assert!(matches!(verify(None), Err(SigningError::BadSignature)));
+
+        // Without an organization the signature matches the older form
+        let plain = signer.sign_now("POST", path, Some(b"{}"), Some("u1"), None);
+        assert!(verifier.verify(headers_fn(&plain, Some("u1")), "POST", path, Some(b"{}"), now).is_ok());
+    }
+
+    #[test]
+    fn test_scopes_are_signed() {
+        let (signer, verifier) = pair();
+        let path = "/api/v1/admin/kyc/42/approve";
+        let identity = SignedIdentity::user(Some("u1"), Some("user")).with_scopes(Some("kyc:review"));
+        let signed = signer.sign_identity_now("POST", path, Some(b"{}"), identity);
+        let now = chrono::Utc::now().timestamp();
+
+        let verify = |scopes| {
+            let header = |name: &str| match name {
+                "x-user-role" => Some("user"),
+                "x-user-scopes" => scopes,
+                other => headers_fn(&signed, Some("u1"))(other),
+            };
+            verifier.verify(header, "POST", path, Some(b"{}"), now)
+        };
+        assert!(verify(Some("kyc:review")).is_ok());
+        assert!(matches!(verify(Some("kyc:review admin")), Err(SigningError::BadSignature)));
+        assert!(matches!(verify(None), Err(SigningError::BadSignature)));
+    }
+
+    #[test]
+    fn test_unsigned_payload_only_on_allowed_paths() {
+        let (signer, verifier) = pair();
+        let verifier = verifier.allow_unsigned_payload("/analyze/file");
+        let now = chrono::Utc::now().timestamp();
+
+        let upload = signer.sign_now("POST", "/analyze/file", None, None, None);
+        assert!(verifier.verify(headers_fn(&upload, None), "POST", "/analyze/file", None, now).is_ok());
+
+        let elsewhere = signer.sign_now("POST", "/analyze/url", None, None, None);
+        assert!(matches!(
+            verifier.verify(headers_fn(&elsewhere, None), "POST", "/analyze/url", None, now),
+            Err(SigningError::UnsignedPayload)
+        ));
+    }
+
+    #[test]
+    fn test_exempt_paths_and_key_parsing() {
+        let verifier = SignatureVerifier::new("k1", SECRET, DEFAULT_MAX_SKEW)
+            .unwrap()
+            .exempt("/api/v1/webhooks/indexer");
+        assert!(!verifier.requires_signature("/health"));
+        assert!(!verifier.requires_signature("/api/v1/webhooks/indexer"));
+        assert!(verifier.requires_signature("/healthz"));
+        assert!(verifier.requires_signature("/api/v1/payments/stake/slash"));
+        assert!(!SignatureVerifier::disabled().requires_signature("/api/v1/payments/stake/slash"));
+
+        let keys = parse_keys(&format!("new:{}, old:{}", SECRET, SECRET.to_uppercase())).unwrap();
+        assert_eq!(keys.iter().map(|k| k.id.as_str()).collect::<Vec<_>>(), ["new", "old"]);
+        assert!(parse_keys("k1:short").is_err());
+        assert!(parse_keys(&format!("k1:{},k1:{}", SECRET, SECRET)).is_err());
+        assert!(parse_keys(SECRET).is_err());
+    }
+
+    #[test]
+    fn test_signed_event_verifies_only_unchanged() {
+        let (signer, verifier) = pair();
+        let payload = br#"{"bounty_id":"b1","slash_fraction":0.1}"#;
+        let signature = signer.sign_event("settlement_planned", payload);
+        let now = chrono::Utc::now().timestamp();
+
+        assert!(verifier.verify_event("settlement_planned", payload, &signature, now).is_ok());
+        assert!(matches!(
+            verifier.verify_event("settlement_planned", br#"{"bounty_id":"b1","slash_fraction":1.0}"#, &signature, now),
+            Err(SigningError::BadSignature)
+        ));
+        assert!(matches!(
+            verifier.verify_event("consensus_reached", payload, &signature, now),
+            Err(SigningError::BadSignature)
+        ));
+        assert!(matches!(
+            verifier.verify_event("settlement_planned", payload, &signature, now + 301),
+            Err(SigningError::Expired)
+        ));
+        let unsigned = EventSignature {
+            key_id: String::new(),
+            timestamp: now,
+            signature: String::new(),
+        };
+        assert!(verifier.verify_event("settlement_planned", payload, &unsigned, now).is_err());
+        assert!(SignatureVerifier::disabled()
+            .verify_event("settlement_planned", payload, &unsigned, now)
+            .is_ok());
+    }
+}
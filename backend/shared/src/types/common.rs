@@ -97,6 +97,17 @@ pub enum BountyStatus {
     InReview,
 }
 
+// Organizations
+/// A member's role in their organization, carried by the gateway in `x-org-role`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OrganizationRole {
+    Owner,
+    Admin,
+    Analyst,
+    Billing,
+}
+
 // Analysis results and submissions
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnalysisSubmission {
@@ -364,6 +375,9 @@ pub struct ApiResponse<T> {
     pub success: bool,
     pub data: Option<T>,
     pub error: Option<ApiError>,
+    /// `X-Request-Id` of the request, for matching a response with the logs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
     pub timestamp: DateTime<Utc>,
 }
 
@@ -441,6 +455,46 @@ impl ThreatVerdict {
     }
 }
 
+impl std::str::FromStr for OrganizationRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "owner" => Ok(OrganizationRole::Owner),
+            "admin" => Ok(OrganizationRole::Admin),
+            "analyst" => Ok(OrganizationRole::Analyst),
+            "billing" => Ok(OrganizationRole::Billing),
+            _ => Err(format!("Invalid organization role: {}", s)),
+        }
+    }
+}
+
+impl OrganizationRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrganizationRole::Owner => "owner",
+            OrganizationRole::Admin => "admin",
+            OrganizationRole::Analyst => "analyst",
+            OrganizationRole::Billing => "billing",
+        }
+    }
+
+    /// Invite, remove and change the roles of members, and manage API keys
+    pub fn manages_members(&self) -> bool {
+        matches!(self, OrganizationRole::Owner | OrganizationRole::Admin)
+    }
+
+    /// Create, cancel and fund the organization's bounties
+    pub fn manages_bounties(&self) -> bool {
+        matches!(self, OrganizationRole::Owner | OrganizationRole::Admin | OrganizationRole::Analyst)
+    }
+
+    /// See the organization's escrow and payments
+    pub fn manages_billing(&self) -> bool {
+        matches!(self, OrganizationRole::Owner | OrganizationRole::Admin | OrganizationRole::Billing)
+    }
+}
+
 impl Default for ThreatVerdict {
     fn default() -> Self {
         ThreatVerdict::Unknown
@@ -44,6 +44,9 @@ pub use common::{
     
     // User system
     UserInfo, EngineInfo, EngineMetrics,
+
+    // Organizations
+    OrganizationRole,
     
     // API types
     ApiResponse, ApiError, PaginatedResponse,
@@ -221,6 +224,7 @@ impl<T> ApiResponse<T> {
             success: true,
             data: Some(data),
             error: None,
+            request_id: crate::observability::current_request_id(),
             timestamp: chrono::Utc::now(),
         }
     }
@@ -235,6 +239,7 @@ impl<T> ApiResponse<T> {
                 message: message.into(),
                 details: None,
             }),
+            request_id: crate::observability::current_request_id(),
             timestamp: chrono::Utc::now(),
         }
     }
@@ -253,6 +258,7 @@ impl<T> ApiResponse<T> {
                 message: message.into(),
                 details: Some(details),
             }),
+            request_id: crate::observability::current_request_id(),
             timestamp: chrono::Utc::now(),
         }
     }
@@ -435,6 +441,20 @@ mod tests {
         assert_eq!(ThreatVerdict::default(), ThreatVerdict::Unknown);
     }
     
+    #[test]
+    fn test_organization_roles() {
+        assert_eq!("Billing".parse::<OrganizationRole>().unwrap(), OrganizationRole::Billing);
+        assert!("member".parse::<OrganizationRole>().is_err());
+        assert_eq!(OrganizationRole::Analyst.as_str(), "analyst");
+
+        assert!(OrganizationRole::Admin.manages_members());
+        assert!(!OrganizationRole::Analyst.manages_members());
+        assert!(OrganizationRole::Analyst.manages_bounties());
+        assert!(!OrganizationRole::Billing.manages_bounties());
+        assert!(OrganizationRole::Billing.manages_billing());
+        assert!(!OrganizationRole::Analyst.manages_billing());
+    }
+
     #[test]
     fn test_trust_level_multipliers() {
         assert_eq!(TrustLevel::Elite.stake_multiplier(), 0.5);
@@ -553,4 +573,14 @@ mod tests {
         assert_eq!(error.code, "ERR001");
         assert_eq!(error.message, "Test error");
     }
+
+    #[tokio::test]
+    async fn test_api_response_carries_request_id() {
+        use crate::observability::{with_log_context, LogContext};
+
+        assert!(ApiResponse::success(1).request_id.is_none());
+        let context = LogContext::from_headers(Some("req-payout-1"), None);
+        let response = with_log_context(context, async { ApiResponse::success(1) }).await;
+        assert_eq!(response.request_id.as_deref(), Some("req-payout-1"));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,193 @@
+//! User-registered webhooks
+//!
+//! The bounty, reputation and payment webhooks POST signed payloads to URLs
+//! users choose, so an unchecked URL lets any user make the platform call
+//! its own internal services or the cloud metadata endpoint. [`validate`]
+//! accepts only HTTPS URLs whose host resolves to public addresses;
+//! [`check_resolved`] repeats the address check before each delivery, since
+//! DNS can change after registration.
+//!
+//! Deliveries are signed with the webhook's secret: `X-Nexus-Signature` is
+//! `sha256=` followed by the hex HMAC-SHA256 of `<X-Nexus-Timestamp>.<body>`
+//! ([`sign`]), and failed ones are retried after [`retry_delay`].
+
+use std::net::IpAddr;
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use reqwest::Url;
+use sha2::Sha256;
+use thiserror::Error;
+
+/// Longest URL accepted
+pub const MAX_URL_LENGTH: usize = 2048;
+
+#[derive(Debug, Error)]
+pub enum WebhookUrlError {
+    #[error("Webhook URL must be an HTTPS URL with a host: {0}")]
+    Invalid(String),
+    #[error("Webhook URL must not point at an internal address: {0}")]
+    PrivateAddress(String),
+    #[error("Webhook host could not be resolved: {0}")]
+    Unresolvable(String),
+}
+
+/// Loopback, private, link-local and other non-public addresses
+pub fn is_private(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                // 100.64.0.0/10 carrier-grade NAT
+                || (v4.octets()[0] == 100 && (v4.octets()[1] & 0xc0) == 64)
+        }
+        IpAddr::V6(v6) => {
+            v6.is_loopback()
+                || v6.is_unspecified()
+                // fc00::/7 unique local, fe80::/10 link local
+                || (v6.segments()[0] & 0xfe00) == 0xfc00
+                || (v6.segments()[0] & 0xffc0) == 0xfe80
+                || v6.to_ipv4_mapped().is_some_and(|v4| is_private(IpAddr::V4(v4)))
+        }
+    }
+}
+
+/// Syntax checks only: HTTPS, a host, and no literal internal address
+pub fn parse(raw: &str) -> Result<Url, WebhookUrlError> {
+    if raw.len() > MAX_URL_LENGTH {
+        return Err(WebhookUrlError::Invalid(format!("longer than {} bytes", MAX_URL_LENGTH)));
+    }
+    let url = Url::parse(raw).map_err(|e| WebhookUrlError::Invalid(e.to_string()))?;
+    if url.scheme() != "https" {
+        return Err(WebhookUrlError::Invalid("scheme must be https".to_string()));
+    }
+    let host = match url.host_str() {
+        Some(host) if !host.is_empty() => host,
+        _ => return Err(WebhookUrlError::Invalid("missing host".to_string())),
+    };
+
+    let literal = host.trim_start_matches('[').trim_end_matches(']');
+    if host.eq_ignore_ascii_case("localhost")
+        || host.to_ascii_lowercase().ends_with(".localhost")
+        || literal.parse::<IpAddr>().is_ok_and(is_private)
+    {
+        return Err(WebhookUrlError::PrivateAddress(host.to_string()));
+    }
+    Ok(url)
+}
+
+/// Fails when the URL's host does not resolve, or resolves to any internal
+/// address
+pub async fn check_resolved(url: &Url) -> Result<(), WebhookUrlError> {
+    let host = url.host_str().unwrap_or_default();
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let port = url.port_or_known_default().unwrap_or(443);
+    let addrs = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| WebhookUrlError::Unresolvable(format!("{}: {}", host, e)))?;
+
+    let mut resolved = false;
+    for addr in addrs {
+        if is_private(addr.ip()) {
+            return Err(WebhookUrlError::PrivateAddress(addr.ip().to_string()));
+        }
+        resolved = true;
+    }
+    if !resolved {
+        return Err(WebhookUrlError::Unresolvable(host.to_string()));
+    }
+    Ok(())
+}
+
+/// Check a URL a user wants deliveries sent to
+pub async fn validate(raw: &str) -> Result<Url, WebhookUrlError> {
+    let url = parse(raw)?;
+    check_resolved(&url).await?;
+    Ok(url)
+}
+
+/// Signature header value for a delivery body sent at `timestamp`
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Wait before retrying after `attempts` failed attempts: doubling from
+/// `base`, capped at `max`
+pub fn retry_delay(attempts: u32, base: Duration, max: Duration) -> Duration {
+    let factor = 2u32.saturating_pow(attempts.saturating_sub(1));
+    base.saturating_mul(factor).min(max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_requires_https_with_host() {
+        assert!(parse("https://hooks.example.com/nexus").is_ok());
+        for url in ["http://hooks.example.com/nexus", "ftp://example.com", "https://", "not a url"] {
+            assert!(matches!(parse(url), Err(WebhookUrlError::Invalid(_))), "{} should be rejected", url);
+        }
+        let long = format!("https://example.com/{}", "a".repeat(MAX_URL_LENGTH));
+        assert!(parse(&long).is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_internal_literals() {
+        for url in [
+            "https://localhost/cb",
+            "https://api.localhost/cb",
+            "https://127.0.0.1/cb",
+            "https://10.0.0.5/cb",
+            "https://192.168.1.1/cb",
+            "https://169.254.169.254/latest/meta-data",
+            "https://100.64.0.1/cb",
+            "https://[::1]/cb",
+            "https://[fe80::1]/cb",
+            "https://[fd00::1]/cb",
+            "https://[::ffff:10.0.0.1]/cb",
+        ] {
+            assert!(
+                matches!(parse(url), Err(WebhookUrlError::PrivateAddress(_))),
+                "{} should be rejected",
+                url
+            );
+        }
+        assert!(parse("https://93.184.216.34/cb").is_ok());
+    }
+
+    #[test]
+    fn test_signature_covers_timestamp_and_body() {
+        let signature = sign("secret", 1_700_000_000, b"{\"a\":1}");
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(signature.len(), "sha256=".len() + 64);
+        assert_eq!(signature, sign("secret", 1_700_000_000, b"{\"a\":1}"));
+        assert_ne!(signature, sign("secret", 1_700_000_001, b"{\"a\":1}"));
+        assert_ne!(signature, sign("secret", 1_700_000_000, b"{\"a\":2}"));
+        assert_ne!(signature, sign("other", 1_700_000_000, b"{\"a\":1}"));
+    }
+
+    #[test]
+    fn test_retry_delay_doubles_up_to_cap() {
+        let base = Duration::from_secs(30);
+        let max = Duration::from_secs(3600);
+        assert_eq!(retry_delay(1, base, max), Duration::from_secs(30));
+        assert_eq!(retry_delay(2, base, max), Duration::from_secs(60));
+        assert_eq!(retry_delay(4, base, max), Duration::from_secs(240));
+        assert_eq!(retry_delay(10, base, max), max);
+        assert_eq!(retry_delay(64, base, max), max);
+    }
+
+    #[tokio::test]
+    async fn test_check_resolved_rejects_internal_addresses() {
+        let url = Url::parse("https://127.0.0.1/cb").unwrap();
+        assert!(matches!(check_resolved(&url).await, Err(WebhookUrlError::PrivateAddress(_))));
+    }
+}
@@ -6,6 +6,7 @@ use crate::AppState;
 use crate::db::repository;
 use crate::models::{CreateSubmissionRequest, SubmissionType};
 use crate::queue::publisher;
+use crate::storage::s3_client::UploadTooLarge;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FileSubmissionResponse {
@@ -52,11 +53,9 @@ pub async fn submit_file(
 ) -> Result<Json<FileSubmissionResponse>, (StatusCode, String)> {
     tracing::info!("Received file submission request");
 
-    let mut filename = String::new();
-    let mut file_data: Option<Vec<u8>> = None;
-    let mut content_type: Option<String> = None;
-
-    // Extract file from multipart form
+    // Extract the file from the multipart form, streaming it to S3/MinIO
+    // as it arrives so the whole upload is never held in memory
+    let mut upload = None;
     while let Some(field) = multipart
         .next_field()
         .await
@@ -66,77 +65,62 @@ pub async fn submit_file(
         tracing::debug!("Processing field: {}", field_name);
 
         if field_name == "file" {
-            // Get filename
-            filename = field
-                .file_name()
-                .unwrap_or("unknown")
-                .to_string();
-
-            // Get content type
-            content_type = field.content_type().map(|s| s.to_string());
-
-            // Read file data
-            let data = field
-                .bytes()
-                .await
-                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to read file: {}", e)))?
-                .to_vec();
-
-            // Validate file size
-            if data.len() > MAX_FILE_SIZE {
-                return Err((
-                    StatusCode::PAYLOAD_TOO_LARGE,
-                    format!("File too large. Maximum size is {} MB", MAX_FILE_SIZE / (1024 * 1024)),
-                ));
+            let filename = field.file_name().unwrap_or("unknown").to_string();
+            let content_type = field.content_type().map(|s| s.to_string());
+
+            // Validate content type if provided
+            if let Some(ref ct) = content_type {
+                if !ALLOWED_MIME_TYPES.iter().any(|&allowed| ct.contains(allowed)) {
+                    tracing::warn!("Potentially unsupported MIME type: {}", ct);
+                    // Don't reject, just warn - we'll analyze it anyway
+                }
             }
 
-            if data.is_empty() {
-                return Err((StatusCode::BAD_REQUEST, "Empty file provided".to_string()));
-            }
+            // Generate unique submission ID
+            let submission_id = Uuid::new_v4().to_string();
+
+            // Generate S3 key: submissions/{submission_id}/{filename}
+            let s3_key = format!("submissions/{}/{}", submission_id, filename);
 
-            file_data = Some(data);
+            let stored = state
+                .s3_client
+                .upload_stream(&s3_key, content_type.clone(), field, MAX_FILE_SIZE)
+                .await
+                .map_err(|e| {
+                    if e.is::<UploadTooLarge>() {
+                        return (
+                            StatusCode::PAYLOAD_TOO_LARGE,
+                            format!("File too large. Maximum size is {} MB", MAX_FILE_SIZE / (1024 * 1024)),
+                        );
+                    }
+                    tracing::error!("Failed to upload file to S3: {:#}", e);
+                    (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to store file: {}", e))
+                })?;
+
+            upload = Some((filename, content_type, s3_key, stored));
             break;
         }
     }
 
     // Ensure file was provided
-    let data = file_data.ok_or((StatusCode::BAD_REQUEST, "No file provided".to_string()))?;
-    let file_size = data.len();
-
-    // Validate content type if provided
-    if let Some(ref ct) = content_type {
-        if !ALLOWED_MIME_TYPES.iter().any(|&allowed| ct.contains(allowed)) {
-            tracing::warn!("Potentially unsupported MIME type: {}", ct);
-            // Don't reject, just warn - we'll analyze it anyway
+    let (filename, content_type, s3_key, stored) =
+        upload.ok_or((StatusCode::BAD_REQUEST, "No file provided".to_string()))?;
+
+    if stored.size == 0 {
+        if let Err(e) = state.s3_client.delete_file(&s3_key).await {
+            tracing::error!("Failed to delete empty upload: {}", e);
         }
+        return Err((StatusCode::BAD_REQUEST, "Empty file provided".to_string()));
     }
 
+    let file_size = stored.size;
+    let file_hash = stored.sha256_hash;
+
     tracing::info!(
-        "File received: filename={}, size={} bytes, content_type={:?}",
+        "File uploaded successfully: filename={}, size={} bytes, content_type={:?}, hash={}, key={}",
         filename,
         file_size,
-        content_type
-    );
-
-    // Generate unique submission ID
-    let submission_id = Uuid::new_v4().to_string();
-
-    // Generate S3 key: submissions/{submission_id}/{filename}
-    let s3_key = format!("submissions/{}/{}", submission_id, filename);
-
-    // Upload file to S3/MinIO
-    let file_hash = state
-        .s3_client
-        .upload_file(&s3_key, data, content_type.clone())
-        .await
-        .map_err(|e| {
-            tracing::error!("Failed to upload file to S3: {}", e);
-            (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to store file: {}", e))
-        })?;
-
-    tracing::info!(
-        "File uploaded successfully: submission_id={}, hash={}, key={}",
-        submission_id,
+        content_type,
         file_hash,
         s3_key
     );
@@ -1,11 +1,9 @@
 use axum::{
-    extract::State,
+    extract::{DefaultBodyLimit, State},
     routing::{get, post},
     Router,
 };
 use std::{net::SocketAddr, sync::Arc};
-use tower_http::cors::{Any, CorsLayer};
-use tracing_subscriber;
 use sqlx::PgPool;
 
 mod handlers;
@@ -27,7 +25,7 @@ pub struct AppState {
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize tracing
-    tracing_subscriber::fmt::init();
+    shared::observability::init_service_logging(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))?;
 
     tracing::info!("Starting Submission Service...");
 
@@ -70,18 +68,31 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         redis_client,
     };
 
-    // Build CORS layer
-    let cors = CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods(Any)
-        .allow_headers(Any);
+    // Browser origins from CORS_ALLOWED_ORIGINS or the environment's defaults
+    let cors = shared::http_security::CorsPolicy::from_env().layer();
 
     // Build our application with routes
     let app = Router::new()
         .route("/health", get(health_check))
-        .route("/submit/file", post(handlers::file_upload::submit_file))
+        // The upload handler streams to storage and enforces its own cap
+        .route(
+            "/submit/file",
+            post(handlers::file_upload::submit_file).layer(DefaultBodyLimit::disable()),
+        )
         .route("/submit/url", post(handlers::url_submission::submit_url))
+        .merge(shared::observability::log_level_routes())
+        .layer(axum::middleware::from_fn_with_state(
+            shared::request_signing::SignatureVerifier::from_env()?
+                .allow_unsigned_payload("/submit/file"),
+            shared::request_signing::verify_signature_middleware,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            shared::chaos::FaultInjector::from_env(),
+            shared::chaos::chaos_middleware,
+        ))
         .layer(cors)
+        .layer(axum::middleware::from_fn(shared::http_security::security_headers_middleware))
+        .layer(axum::middleware::from_fn(shared::observability::log_context_middleware))
         .with_state(state);
 
     // Get port from environment or use default
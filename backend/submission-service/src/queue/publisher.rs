@@ -13,7 +13,7 @@ pub async fn publish_to_analysis_queue(
 
     // Push submission ID to the queue (LPUSH for FIFO with BRPOP)
     let submission_id_str = submission_id.to_string();
-    conn.lpush(ANALYSIS_QUEUE_KEY, &submission_id_str).await?;
+    conn.lpush::<_, _, ()>(ANALYSIS_QUEUE_KEY, &submission_id_str).await?;
 
     tracing::info!(
         "Published submission {} to analysis queue",
@@ -53,7 +53,7 @@ pub async fn publish_bulk_to_analysis_queue(
     for id_str in &submission_id_strings {
         pipe.lpush(ANALYSIS_QUEUE_KEY, id_str);
     }
-    pipe.query_async(&mut conn).await?;
+    pipe.query_async::<_, ()>(&mut conn).await?;
 
     tracing::info!(
         "Published {} submissions to analysis queue",
@@ -6,11 +6,17 @@ use aws_config::{BehaviorVersion, Region};
 use aws_sdk_s3::{
     config::{Credentials, SharedCredentialsProvider},
     primitives::ByteStream,
+    types::{CompletedMultipartUpload, CompletedPart},
     Client, Config,
 };
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
 use sha2::{Digest, Sha256};
 use std::env;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
+
+/// Part size of streamed uploads; S3 needs at least 5MB for all but the last part
+const UPLOAD_PART_SIZE: usize = 8 * 1024 * 1024;
 
 /// S3 client for file storage operations
 #[derive(Clone)]
@@ -30,6 +36,18 @@ pub struct FileMetadata {
     pub sha256_hash: String,
 }
 
+/// A streamed upload went over its size limit; nothing was stored
+#[derive(Debug, thiserror::Error)]
+#[error("Upload exceeds {0} bytes")]
+pub struct UploadTooLarge(pub usize);
+
+/// Outcome of [`S3Client::upload_stream`]
+#[derive(Debug, Clone)]
+pub struct StreamedUpload {
+    pub sha256_hash: String,
+    pub size: usize,
+}
+
 impl S3Client {
     /// Create a new S3 client configured for MinIO or AWS S3
     pub async fn new() -> Result<Self> {
@@ -143,6 +161,198 @@ impl S3Client {
         Ok(hash)
     }
 
+    /// Upload a file as it arrives, holding at most one part in memory
+    ///
+    /// Uploads that fit in one part are stored with a single put carrying
+    /// the `sha256` metadata. Larger ones go through an S3 multipart upload;
+    /// their hash is only known once the last part is sent, so it is
+    /// returned but not stored as object metadata. Fails with
+    /// [`UploadTooLarge`] past `max_size`, after aborting the upload.
+    pub async fn upload_stream<S, E>(
+        &self,
+        key: &str,
+        content_type: Option<String>,
+        stream: S,
+        max_size: usize,
+    ) -> Result<StreamedUpload>
+    where
+        S: Stream<Item = std::result::Result<Bytes, E>>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let mut stream = std::pin::pin!(stream);
+        let mut hasher = Sha256::new();
+        let mut size = 0usize;
+        let mut part = Vec::with_capacity(UPLOAD_PART_SIZE);
+        let mut multipart: Option<MultipartUpload> = None;
+
+        let outcome: Result<()> = async {
+            while let Some(chunk) = stream.next().await {
+                let chunk = chunk.context("Failed to read upload")?;
+                size += chunk.len();
+                if size > max_size {
+                    return Err(UploadTooLarge(max_size).into());
+                }
+                hasher.update(&chunk);
+                part.extend_from_slice(&chunk);
+
+                if part.len() >= UPLOAD_PART_SIZE {
+                    if multipart.is_none() {
+                        multipart = Some(self.start_multipart(key, content_type.clone()).await?);
+                    }
+                    if let Some(upload) = multipart.as_mut() {
+                        let data = std::mem::replace(&mut part, Vec::with_capacity(UPLOAD_PART_SIZE));
+                        self.upload_part(upload, data).await?;
+                    }
+                }
+            }
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = outcome {
+            if let Some(upload) = multipart {
+                self.abort_multipart(&upload).await;
+            }
+            return Err(e);
+        }
+
+        let hash = hex::encode(hasher.finalize());
+        match multipart {
+            None => {
+                self.upload_file_with_hash(key, part, content_type, &hash).await?;
+            }
+            Some(mut upload) => {
+                let finished = async {
+                    if !part.is_empty() {
+                        self.upload_part(&mut upload, part).await?;
+                    }
+                    self.complete_multipart(&upload).await
+                }
+                .await;
+                if let Err(e) = finished {
+                    self.abort_multipart(&upload).await;
+                    return Err(e);
+                }
+                info!(
+                    "File uploaded in {} parts: key={}, size={} bytes, hash={}",
+                    upload.parts.len(),
+                    key,
+                    size,
+                    hash
+                );
+            }
+        }
+
+        Ok(StreamedUpload { sha256_hash: hash, size })
+    }
+
+    async fn upload_file_with_hash(
+        &self,
+        key: &str,
+        data: Vec<u8>,
+        content_type: Option<String>,
+        hash: &str,
+    ) -> Result<()> {
+        let mut request = self
+            .client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .body(ByteStream::from(data))
+            .metadata("sha256", hash);
+        if let Some(ct) = content_type {
+            request = request.content_type(ct);
+        }
+        request
+            .send()
+            .await
+            .with_context(|| format!("Failed to upload file with key: {}", key))?;
+
+        info!("File uploaded successfully: key={}, hash={}", key, hash);
+        Ok(())
+    }
+
+    async fn start_multipart(&self, key: &str, content_type: Option<String>) -> Result<MultipartUpload> {
+        let mut request = self
+            .client
+            .create_multipart_upload()
+            .bucket(&self.bucket)
+            .key(key);
+        if let Some(ct) = content_type {
+            request = request.content_type(ct);
+        }
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("Failed to start multipart upload with key: {}", key))?;
+        let upload_id = response
+            .upload_id()
+            .context("Multipart upload has no id")?
+            .to_string();
+
+        debug!("Started multipart upload: key={}, upload_id={}", key, upload_id);
+        Ok(MultipartUpload {
+            key: key.to_string(),
+            upload_id,
+            parts: Vec::new(),
+        })
+    }
+
+    async fn upload_part(&self, upload: &mut MultipartUpload, data: Vec<u8>) -> Result<()> {
+        let part_number = upload.parts.len() as i32 + 1;
+        let response = self
+            .client
+            .upload_part()
+            .bucket(&self.bucket)
+            .key(&upload.key)
+            .upload_id(&upload.upload_id)
+            .part_number(part_number)
+            .body(ByteStream::from(data))
+            .send()
+            .await
+            .with_context(|| format!("Failed to upload part {} of {}", part_number, upload.key))?;
+
+        upload.parts.push(
+            CompletedPart::builder()
+                .set_e_tag(response.e_tag().map(str::to_string))
+                .part_number(part_number)
+                .build(),
+        );
+        Ok(())
+    }
+
+    async fn complete_multipart(&self, upload: &MultipartUpload) -> Result<()> {
+        self.client
+            .complete_multipart_upload()
+            .bucket(&self.bucket)
+            .key(&upload.key)
+            .upload_id(&upload.upload_id)
+            .multipart_upload(
+                CompletedMultipartUpload::builder()
+                    .set_parts(Some(upload.parts.clone()))
+                    .build(),
+            )
+            .send()
+            .await
+            .with_context(|| format!("Failed to complete multipart upload of {}", upload.key))?;
+        Ok(())
+    }
+
+    /// Best effort, a failure is only logged
+    async fn abort_multipart(&self, upload: &MultipartUpload) {
+        if let Err(e) = self
+            .client
+            .abort_multipart_upload()
+            .bucket(&self.bucket)
+            .key(&upload.key)
+            .upload_id(&upload.upload_id)
+            .send()
+            .await
+        {
+            warn!("Failed to abort multipart upload of {}: {}", upload.key, e);
+        }
+    }
+
     /// Download a file from S3/MinIO
     pub async fn download_file(&self, key: &str) -> Result<Vec<u8>> {
         debug!("Downloading file: key={}", key);
@@ -278,3 +488,10 @@ impl S3Client {
         Ok(presigned.uri().to_string())
     }
 }
+
+/// An S3 multipart upload in progress
+struct MultipartUpload {
+    key: String,
+    upload_id: String,
+    parts: Vec<CompletedPart>,
+}
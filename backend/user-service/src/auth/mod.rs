@@ -1,3 +1,6 @@
+pub mod siwe;
+pub mod webauthn;
+
 use argon2::{
     password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
     Argon2,
@@ -7,21 +10,55 @@ use ethers::core::types::Signature;
 use ethers::signers::{LocalWallet, Signer};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
+use shared::permissions::Scopes;
 use std::str::FromStr;
 use uuid::Uuid;
 
 use crate::config::JwtConfig;
-use crate::models::{UserError, UserResult};
+use crate::models::{User, UserError, UserResult};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String,        // User ID
     pub email: String,
     pub username: String,
-    pub is_admin: bool,
+    /// Permissions granted through the user's roles (`shared::permissions`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<String>,
     pub exp: i64,           // Expiry timestamp
     pub iat: i64,           // Issued at timestamp
     pub token_type: String, // "access" or "refresh"
+    /// Set when an admin acts as this user (RFC 8693 actor claim)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub act: Option<ActorClaims>,
+}
+
+/// The admin behind an impersonation token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActorClaims {
+    pub sub: String,        // Admin user ID
+    pub session_id: String, // Impersonation session ID
+}
+
+impl Claims {
+    pub fn is_impersonation(&self) -> bool {
+        self.act.is_some()
+    }
+
+    pub fn scopes(&self) -> Scopes {
+        self.scopes.iter().map(String::as_str).collect()
+    }
+}
+
+/// Claims carried by a single-use passwordless sign-in link
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MagicLinkClaims {
+    pub sub: String,        // User ID
+    pub email: String,
+    pub jti: String,        // One-time token ID, tracked in Redis
+    pub exp: i64,
+    pub iat: i64,
+    pub token_type: String, // Always "magic_link"
 }
 
 pub struct AuthService {
@@ -68,13 +105,13 @@ impl AuthService {
         }
     }
 
-    /// Generate an access token
+    /// Generate an access token carrying the user's permission scopes
     pub fn generate_access_token(
         &self,
         user_id: Uuid,
         email: &str,
         username: &str,
-        is_admin: bool,
+        scopes: Vec<String>,
     ) -> UserResult<String> {
         let now = Utc::now();
         let expiry = now + Duration::hours(self.jwt_config.access_token_expiry_hours as i64);
@@ -83,24 +120,50 @@ impl AuthService {
             sub: user_id.to_string(),
             email: email.to_string(),
             username: username.to_string(),
-            is_admin,
+            scopes,
             exp: expiry.timestamp(),
             iat: now.timestamp(),
             token_type: "access".to_string(),
+            act: None,
         };
 
         encode(&Header::default(), &claims, &self.encoding_key)
             .map_err(|e| UserError::AuthenticationError(format!("Failed to generate token: {}", e)))
     }
 
-    /// Generate a refresh token
-    pub fn generate_refresh_token(
+    /// Generate a short-lived access token for an admin acting as `user`.
+    /// The token never carries scopes, whatever the user's roles.
+    pub fn generate_impersonation_token(
         &self,
-        user_id: Uuid,
-        email: &str,
-        username: &str,
-        is_admin: bool,
+        user: &User,
+        admin_id: Uuid,
+        session_id: Uuid,
+        ttl_minutes: u64,
     ) -> UserResult<String> {
+        let now = Utc::now();
+        let expiry = now + Duration::minutes(ttl_minutes as i64);
+
+        let claims = Claims {
+            sub: user.id.to_string(),
+            email: user.email.clone(),
+            username: user.username.clone(),
+            scopes: Vec::new(),
+            exp: expiry.timestamp(),
+            iat: now.timestamp(),
+            token_type: "access".to_string(),
+            act: Some(ActorClaims {
+                sub: admin_id.to_string(),
+                session_id: session_id.to_string(),
+            }),
+        };
+
+        encode(&Header::default(), &claims, &self.encoding_key)
+            .map_err(|e| UserError::AuthenticationError(format!("Failed to generate token: {}", e)))
+    }
+
+    /// Generate a refresh token. It carries no scopes; they are looked up
+    /// again when it is exchanged.
+    pub fn generate_refresh_token(&self, user_id: Uuid, email: &str, username: &str) -> UserResult<String> {
         let now = Utc::now();
         let expiry = now + Duration::days(self.jwt_config.refresh_token_expiry_days as i64);
 
@@ -108,10 +171,11 @@ impl AuthService {
             sub: user_id.to_string(),
             email: email.to_string(),
             username: username.to_string(),
-            is_admin,
+            scopes: Vec::new(),
             exp: expiry.timestamp(),
             iat: now.timestamp(),
             token_type: "refresh".to_string(),
+            act: None,
         };
 
         encode(&Header::default(), &claims, &self.encoding_key)
@@ -127,6 +191,45 @@ impl AuthService {
             .map_err(|_e| UserError::InvalidToken)
     }
 
+    /// Generate a signed magic link token, returning the token and its one-time ID
+    pub fn generate_magic_link_token(
+        &self,
+        user_id: Uuid,
+        email: &str,
+        expiry_minutes: u64,
+    ) -> UserResult<(String, String)> {
+        let now = Utc::now();
+        let expiry = now + Duration::minutes(expiry_minutes as i64);
+        let jti = Uuid::new_v4().to_string();
+
+        let claims = MagicLinkClaims {
+            sub: user_id.to_string(),
+            email: email.to_string(),
+            jti: jti.clone(),
+            exp: expiry.timestamp(),
+            iat: now.timestamp(),
+            token_type: "magic_link".to_string(),
+        };
+
+        let token = encode(&Header::default(), &claims, &self.encoding_key)
+            .map_err(|e| UserError::AuthenticationError(format!("Failed to generate magic link: {}", e)))?;
+
+        Ok((token, jti))
+    }
+
+    /// Validate and decode a magic link token
+    pub fn validate_magic_link_token(&self, token: &str) -> UserResult<MagicLinkClaims> {
+        let claims = decode::<MagicLinkClaims>(token, &self.decoding_key, &Validation::default())
+            .map(|data| data.claims)
+            .map_err(|_e| UserError::InvalidToken)?;
+
+        if claims.token_type != "magic_link" {
+            return Err(UserError::InvalidToken);
+        }
+
+        Ok(claims)
+    }
+
     /// Verify Ethereum wallet signature
     pub fn verify_wallet_signature(
         &self,
@@ -204,6 +307,7 @@ impl AuthService {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use shared::permissions::{KYC_REVIEW, USERS_MANAGE};
 
     fn get_test_jwt_config() -> JwtConfig {
         JwtConfig {
@@ -231,13 +335,80 @@ mod tests {
         let username = "testuser";
 
         let token = auth_service
-            .generate_access_token(user_id, email, username, false)
+            .generate_access_token(user_id, email, username, vec![KYC_REVIEW.to_string()])
             .unwrap();
 
         let claims = auth_service.validate_token(&token).unwrap();
         assert_eq!(claims.sub, user_id.to_string());
         assert_eq!(claims.email, email);
         assert_eq!(claims.token_type, "access");
+        assert!(claims.scopes().allows(KYC_REVIEW));
+        assert!(!claims.scopes().allows(USERS_MANAGE));
+    }
+
+    #[test]
+    fn test_impersonation_token_carries_both_identities() {
+        let auth_service = AuthService::new(get_test_jwt_config());
+        let now = Utc::now();
+        let user = User {
+            id: Uuid::new_v4(),
+            username: "target".to_string(),
+            email: "target@example.com".to_string(),
+            password_hash: String::new(),
+            ethereum_address: None,
+            email_verified: true,
+            is_active: true,
+            is_admin: true,
+            two_factor_enabled: false,
+            two_factor_secret: None,
+            kyc_status: "pending".to_string(),
+            created_at: now,
+            updated_at: now,
+            last_login: None,
+        };
+        let admin_id = Uuid::new_v4();
+        let session_id = Uuid::new_v4();
+
+        let token = auth_service
+            .generate_impersonation_token(&user, admin_id, session_id, 15)
+            .unwrap();
+        let claims = auth_service.validate_token(&token).unwrap();
+
+        assert_eq!(claims.sub, user.id.to_string());
+        assert!(claims.scopes().is_empty());
+        assert!(claims.is_impersonation());
+        let actor = claims.act.unwrap();
+        assert_eq!(actor.sub, admin_id.to_string());
+        assert_eq!(actor.session_id, session_id.to_string());
+        assert!(claims.exp <= (now + Duration::minutes(15)).timestamp() + 1);
+
+        // Regular tokens carry no actor
+        let access = auth_service
+            .generate_access_token(user.id, &user.email, &user.username, Vec::new())
+            .unwrap();
+        assert!(!auth_service.validate_token(&access).unwrap().is_impersonation());
+    }
+
+    #[test]
+    fn test_magic_link_token_roundtrip() {
+        let auth_service = AuthService::new(get_test_jwt_config());
+        let user_id = Uuid::new_v4();
+
+        let (token, jti) = auth_service
+            .generate_magic_link_token(user_id, "test@example.com", 15)
+            .unwrap();
+
+        let claims = auth_service.validate_magic_link_token(&token).unwrap();
+        assert_eq!(claims.sub, user_id.to_string());
+        assert_eq!(claims.jti, jti);
+        assert_eq!(claims.token_type, "magic_link");
+
+        // Session tokens must not be accepted as magic links and vice versa
+        let access = auth_service
+            .generate_access_token(user_id, "test@example.com", "testuser", Vec::new())
+            .unwrap();
+        assert!(auth_service.validate_magic_link_token(&access).is_err());
+        assert!(auth_service.validate_token(&token).is_err());
     }
 
     #[test]
@@ -0,0 +1,233 @@
+//! Sign-In with Ethereum (EIP-4361) messages
+//!
+//! The wallet signs the message with `personal_sign`; the signature itself is
+//! checked with `AuthService::verify_wallet_signature` and the nonce against
+//! Redis by the caller. This module only parses the message and checks the
+//! fields that do not need either.
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::models::{UserError, UserResult};
+
+const HEADER_SUFFIX: &str = " wants you to sign in with your Ethereum account:";
+
+/// How far in the future `Issued At` may be, for clock skew
+const MAX_CLOCK_SKEW_SECONDS: i64 = 300;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SiweMessage {
+    /// RFC 3986 authority asking for the sign-in, without any scheme
+    pub domain: String,
+    pub address: String,
+    pub statement: Option<String>,
+    pub uri: String,
+    pub version: String,
+    pub chain_id: u64,
+    pub nonce: String,
+    pub issued_at: DateTime<Utc>,
+    pub expiration_time: Option<DateTime<Utc>>,
+    pub not_before: Option<DateTime<Utc>>,
+    pub request_id: Option<String>,
+    pub resources: Vec<String>,
+}
+
+fn invalid(what: &str) -> UserError {
+    UserError::ValidationError(format!("Invalid SIWE message: {}", what))
+}
+
+fn is_address(value: &str) -> bool {
+    value.len() == 42
+        && value.starts_with("0x")
+        && value[2..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn timestamp(value: &str, field: &str) -> UserResult<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|_| invalid(&format!("{} is not an RFC 3339 time", field)))
+}
+
+impl SiweMessage {
+    /// Parse a message in the EIP-4361 format
+    pub fn parse(message: &str) -> UserResult<Self> {
+        let mut lines = message.lines();
+
+        let domain = lines
+            .next()
+            .and_then(|line| line.strip_suffix(HEADER_SUFFIX))
+            .ok_or_else(|| invalid("missing sign-in header"))?;
+        let domain = domain.split_once("://").map_or(domain, |(_, authority)| authority);
+        if domain.is_empty() {
+            return Err(invalid("missing domain"));
+        }
+
+        let address = lines
+            .next()
+            .filter(|line| is_address(line))
+            .ok_or_else(|| invalid("missing or malformed address"))?;
+        if lines.next() != Some("") {
+            return Err(invalid("expected a blank line after the address"));
+        }
+
+        // Either the statement and a blank line, or just the blank line
+        let statement = match lines.next() {
+            Some("") => None,
+            Some(statement) => {
+                if lines.next() != Some("") {
+                    return Err(invalid("expected a blank line after the statement"));
+                }
+                Some(statement.to_string())
+            }
+            None => return Err(invalid("truncated message")),
+        };
+
+        let mut field = |name: &str| -> UserResult<String> {
+            lines
+                .next()
+                .and_then(|line| line.strip_prefix(name))
+                .and_then(|rest| rest.strip_prefix(": "))
+                .map(str::to_string)
+                .ok_or_else(|| invalid(&format!("missing {}", name)))
+        };
+        let uri = field("URI")?;
+        let version = field("Version")?;
+        let chain_id = field("Chain ID")?
+            .parse()
+            .map_err(|_| invalid("Chain ID is not a number"))?;
+        let nonce = field("Nonce")?;
+        if nonce.len() < 8 || !nonce.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(invalid("Nonce must be at least 8 alphanumeric characters"));
+        }
+        let issued_at = timestamp(&field("Issued At")?, "Issued At")?;
+
+        let mut parsed = Self {
+            domain: domain.to_string(),
+            address: address.to_string(),
+            statement,
+            uri,
+            version,
+            chain_id,
+            nonce,
+            issued_at,
+            expiration_time: None,
+            not_before: None,
+            request_id: None,
+            resources: Vec::new(),
+        };
+
+        let mut in_resources = false;
+        for line in lines {
+            if in_resources {
+                let resource = line
+                    .strip_prefix("- ")
+                    .ok_or_else(|| invalid("malformed resource"))?;
+                parsed.resources.push(resource.to_string());
+            } else if let Some(value) = line.strip_prefix("Expiration Time: ") {
+                parsed.expiration_time = Some(timestamp(value, "Expiration Time")?);
+            } else if let Some(value) = line.strip_prefix("Not Before: ") {
+                parsed.not_before = Some(timestamp(value, "Not Before")?);
+            } else if let Some(value) = line.strip_prefix("Request ID: ") {
+                parsed.request_id = Some(value.to_string());
+            } else if line == "Resources:" {
+                in_resources = true;
+            } else {
+                return Err(invalid(&format!("unexpected line '{}'", line)));
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    /// Check the message is for this service and valid at `now`
+    pub fn validate(&self, domain: &str, chain_ids: &[u64], now: DateTime<Utc>) -> UserResult<()> {
+        if !self.domain.eq_ignore_ascii_case(domain) {
+            return Err(UserError::AuthenticationError(format!(
+                "SIWE message is for {}, not {}",
+                self.domain, domain
+            )));
+        }
+        if self.version != "1" {
+            return Err(invalid("only version 1 is supported"));
+        }
+        if !chain_ids.contains(&self.chain_id) {
+            return Err(UserError::AuthenticationError(format!(
+                "Chain {} is not accepted for sign-in",
+                self.chain_id
+            )));
+        }
+        if self.issued_at > now + Duration::seconds(MAX_CLOCK_SKEW_SECONDS) {
+            return Err(UserError::AuthenticationError("SIWE message is issued in the future".to_string()));
+        }
+        if self.expiration_time.is_some_and(|expires| expires <= now) {
+            return Err(UserError::AuthenticationError("SIWE message has expired".to_string()));
+        }
+        if self.not_before.is_some_and(|not_before| not_before > now) {
+            return Err(UserError::AuthenticationError("SIWE message is not valid yet".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: &str = "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2";
+
+    fn message(statement: Option<&str>, extra: &str) -> String {
+        let statement = statement.map(|s| format!("{}\n", s)).unwrap_or_default();
+        format!(
+            "nexus-security.io wants you to sign in with your Ethereum account:\n{}\n\n{}\nURI: https://nexus-security.io/login\nVersion: 1\nChain ID: 1\nNonce: ABCD1234EFGH\nIssued At: 2026-01-01T00:00:00Z{}",
+            ADDRESS, statement, extra
+        )
+    }
+
+    fn at(value: &str) -> DateTime<Utc> {
+        timestamp(value, "test").unwrap()
+    }
+
+    #[test]
+    fn test_parse_with_optional_fields() {
+        let parsed = SiweMessage::parse(&message(
+            Some("Sign in to Nexus-Security"),
+            "\nExpiration Time: 2026-01-01T00:10:00Z\nRequest ID: 42\nResources:\n- https://nexus-security.io/terms",
+        ))
+        .unwrap();
+
+        assert_eq!(parsed.domain, "nexus-security.io");
+        assert_eq!(parsed.address, ADDRESS);
+        assert_eq!(parsed.statement.as_deref(), Some("Sign in to Nexus-Security"));
+        assert_eq!(parsed.chain_id, 1);
+        assert_eq!(parsed.nonce, "ABCD1234EFGH");
+        assert_eq!(parsed.expiration_time, Some(at("2026-01-01T00:10:00Z")));
+        assert_eq!(parsed.request_id.as_deref(), Some("42"));
+        assert_eq!(parsed.resources, vec!["https://nexus-security.io/terms".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_without_statement() {
+        let parsed = SiweMessage::parse(&message(None, "")).unwrap();
+        assert_eq!(parsed.statement, None);
+        assert_eq!(parsed.uri, "https://nexus-security.io/login");
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_messages() {
+        assert!(SiweMessage::parse("hello").is_err());
+        assert!(SiweMessage::parse(&message(None, "").replace(ADDRESS, "0x1234")).is_err());
+        assert!(SiweMessage::parse(&message(None, "").replace("ABCD1234EFGH", "abc")).is_err());
+        assert!(SiweMessage::parse(&message(None, "\nSomething: else")).is_err());
+    }
+
+    #[test]
+    fn test_validate_checks_domain_chain_and_times() {
+        let parsed = SiweMessage::parse(&message(None, "\nExpiration Time: 2026-01-01T00:10:00Z")).unwrap();
+        let now = at("2026-01-01T00:05:00Z");
+
+        assert!(parsed.validate("nexus-security.io", &[1], now).is_ok());
+        assert!(parsed.validate("evil.example", &[1], now).is_err());
+        assert!(parsed.validate("nexus-security.io", &[137], now).is_err());
+        assert!(parsed.validate("nexus-security.io", &[1], at("2026-01-01T00:10:00Z")).is_err());
+        assert!(parsed.validate("nexus-security.io", &[1], at("2025-12-31T23:50:00Z")).is_err());
+    }
+}
@@ -0,0 +1,447 @@
+//! WebAuthn (security keys and passkeys) ceremonies
+//!
+//! Parses what the browser returns from `navigator.credentials.create()` and
+//! `.get()` and checks it against the relying party: client data type,
+//! challenge and origin, the RP ID hash and flags of the authenticator data,
+//! and assertion signatures over `authenticatorData || SHA-256(clientDataJSON)`.
+//! Attestation is not requested, so attestation statements are not checked.
+//!
+//! Challenges are issued and consumed in Redis by the caller.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use ciborium::Value;
+use ring::digest::{digest, SHA256};
+use ring::signature::{self, RsaPublicKeyComponents, UnparsedPublicKey};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::config::WebAuthnConfig;
+use crate::models::{UserError, UserResult};
+
+/// COSE algorithms accepted for new credentials, in order of preference
+pub const SUPPORTED_ALGORITHMS: [i64; 3] = [COSE_ES256, COSE_EDDSA, COSE_RS256];
+
+const COSE_ES256: i64 = -7;
+const COSE_EDDSA: i64 = -8;
+const COSE_RS256: i64 = -257;
+
+const FLAG_USER_PRESENT: u8 = 0x01;
+const FLAG_USER_VERIFIED: u8 = 0x04;
+const FLAG_BACKUP_ELIGIBLE: u8 = 0x08;
+const FLAG_ATTESTED_CREDENTIAL: u8 = 0x40;
+
+/// Longest credential ID the spec allows
+const MAX_CREDENTIAL_ID_LEN: usize = 1023;
+
+/// A challenge waiting for its response, stored under the challenge
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PendingChallenge {
+    /// The registering user, or whose credentials may answer an assertion
+    pub user_id: Option<Uuid>,
+    pub registration: bool,
+    /// Registering a passkey: user verification is required
+    pub passkey: bool,
+}
+
+fn invalid(what: &str) -> UserError {
+    UserError::ValidationError(format!("Invalid WebAuthn response: {}", what))
+}
+
+fn rejected(what: &str) -> UserError {
+    UserError::AuthenticationError(format!("WebAuthn {}", what))
+}
+
+/// Decode a base64url field, with or without padding
+pub fn decode(field: &str, value: &str) -> UserResult<Vec<u8>> {
+    URL_SAFE_NO_PAD
+        .decode(value.trim_end_matches('='))
+        .map_err(|_| invalid(&format!("{} is not base64url", field)))
+}
+
+pub fn encode(bytes: &[u8]) -> String {
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ClientData {
+    #[serde(rename = "type")]
+    kind: String,
+    challenge: String,
+    origin: String,
+    #[serde(default)]
+    cross_origin: bool,
+}
+
+/// The challenge a client data JSON answers, to find it before verifying
+pub fn client_data_challenge(client_data_json: &[u8]) -> UserResult<String> {
+    serde_json::from_slice::<ClientData>(client_data_json)
+        .map(|client_data| client_data.challenge)
+        .map_err(|_| invalid("malformed client data"))
+}
+
+fn check_client_data(
+    rp: &WebAuthnConfig,
+    client_data_json: &[u8],
+    kind: &str,
+    challenge: &str,
+) -> UserResult<()> {
+    let client_data: ClientData =
+        serde_json::from_slice(client_data_json).map_err(|_| invalid("malformed client data"))?;
+
+    if client_data.kind != kind {
+        return Err(invalid(&format!("expected {} client data", kind)));
+    }
+    if client_data.challenge != challenge {
+        return Err(rejected("challenge does not match"));
+    }
+    if client_data.cross_origin || !rp.origins.contains(&client_data.origin) {
+        return Err(rejected(&format!("origin {} is not allowed", client_data.origin)));
+    }
+    Ok(())
+}
+
+/// A credential created during registration
+#[derive(Debug, Clone)]
+pub struct AttestedCredential {
+    pub aaguid: Uuid,
+    pub credential_id: Vec<u8>,
+    /// COSE_Key, as stored and passed back to `verify_assertion`
+    pub public_key: Vec<u8>,
+    pub algorithm: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct AuthenticatorData {
+    pub rp_id_hash: [u8; 32],
+    pub flags: u8,
+    pub sign_count: u32,
+    pub attested_credential: Option<AttestedCredential>,
+}
+
+impl AuthenticatorData {
+    pub fn parse(bytes: &[u8]) -> UserResult<Self> {
+        if bytes.len() < 37 {
+            return Err(invalid("authenticator data is too short"));
+        }
+        let mut rp_id_hash = [0u8; 32];
+        rp_id_hash.copy_from_slice(&bytes[..32]);
+        let flags = bytes[32];
+        let sign_count = u32::from_be_bytes([bytes[33], bytes[34], bytes[35], bytes[36]]);
+
+        let attested_credential = if flags & FLAG_ATTESTED_CREDENTIAL != 0 {
+            let rest = &bytes[37..];
+            if rest.len() < 18 {
+                return Err(invalid("attested credential data is too short"));
+            }
+            let aaguid = Uuid::from_slice(&rest[..16]).map_err(|_| invalid("malformed AAGUID"))?;
+            let id_len = u16::from_be_bytes([rest[16], rest[17]]) as usize;
+            let rest = &rest[18..];
+            if id_len == 0 || id_len > MAX_CREDENTIAL_ID_LEN || rest.len() < id_len {
+                return Err(invalid("malformed credential ID"));
+            }
+            let (credential_id, mut key) = rest.split_at(id_len);
+
+            // The key is followed by extensions, if any; reading it off the
+            // slice tells where it ends
+            let remaining = key.len();
+            let cose: Value = ciborium::de::from_reader(&mut key)
+                .map_err(|_| invalid("malformed credential public key"))?;
+            let public_key = rest[id_len..id_len + remaining - key.len()].to_vec();
+            let algorithm = CoseKey::from_value(&cose)?.algorithm();
+
+            Some(AttestedCredential {
+                aaguid,
+                credential_id: credential_id.to_vec(),
+                public_key,
+                algorithm,
+            })
+        } else {
+            None
+        };
+
+        Ok(Self {
+            rp_id_hash,
+            flags,
+            sign_count,
+            attested_credential,
+        })
+    }
+
+    pub fn user_present(&self) -> bool {
+        self.flags & FLAG_USER_PRESENT != 0
+    }
+
+    /// The authenticator checked a PIN or biometric, not just a touch
+    pub fn user_verified(&self) -> bool {
+        self.flags & FLAG_USER_VERIFIED != 0
+    }
+
+    /// The credential may be synced between devices (a multi-device passkey)
+    pub fn backup_eligible(&self) -> bool {
+        self.flags & FLAG_BACKUP_ELIGIBLE != 0
+    }
+
+    fn check(&self, rp: &WebAuthnConfig) -> UserResult<()> {
+        if self.rp_id_hash.as_slice() != digest(&SHA256, rp.rp_id.as_bytes()).as_ref() {
+            return Err(rejected("credential is for another relying party"));
+        }
+        if !self.user_present() {
+            return Err(rejected("response lacks user presence"));
+        }
+        Ok(())
+    }
+}
+
+enum CoseKey {
+    Es256 { point: Vec<u8> },
+    EdDsa { x: Vec<u8> },
+    Rs256 { n: Vec<u8>, e: Vec<u8> },
+}
+
+impl CoseKey {
+    fn parse(bytes: &[u8]) -> UserResult<Self> {
+        let value: Value =
+            ciborium::de::from_reader(bytes).map_err(|_| invalid("malformed credential public key"))?;
+        Self::from_value(&value)
+    }
+
+    fn from_value(value: &Value) -> UserResult<Self> {
+        let map = value.as_map().ok_or_else(|| invalid("credential public key is not a map"))?;
+        let field = |label: i64| {
+            map.iter()
+                .find(|(key, _)| key.as_integer().is_some_and(|key| i128::from(key) == label as i128))
+                .map(|(_, value)| value)
+        };
+        let int = |label: i64| field(label).and_then(Value::as_integer).map(i128::from);
+        let bytes = |label: i64, len: Option<usize>| {
+            field(label)
+                .and_then(Value::as_bytes)
+                .filter(|bytes| len.map_or(!bytes.is_empty(), |len| bytes.len() == len))
+                .cloned()
+                .ok_or_else(|| invalid("malformed credential public key"))
+        };
+
+        // kty, alg and, for curves, crv
+        match (int(1), int(3)) {
+            (Some(2), Some(alg)) if alg == COSE_ES256 as i128 && int(-1) == Some(1) => {
+                let mut point = vec![0x04];
+                point.extend(bytes(-2, Some(32))?);
+                point.extend(bytes(-3, Some(32))?);
+                Ok(Self::Es256 { point })
+            }
+            (Some(1), Some(alg)) if alg == COSE_EDDSA as i128 && int(-1) == Some(6) => {
+                Ok(Self::EdDsa { x: bytes(-2, Some(32))? })
+            }
+            (Some(3), Some(alg)) if alg == COSE_RS256 as i128 => Ok(Self::Rs256 {
+                n: bytes(-1, None)?,
+                e: bytes(-2, None)?,
+            }),
+            _ => Err(invalid("unsupported credential algorithm")),
+        }
+    }
+
+    fn algorithm(&self) -> i64 {
+        match self {
+            Self::Es256 { .. } => COSE_ES256,
+            Self::EdDsa { .. } => COSE_EDDSA,
+            Self::Rs256 { .. } => COSE_RS256,
+        }
+    }
+
+    fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
+        match self {
+            Self::Es256 { point } => UnparsedPublicKey::new(&signature::ECDSA_P256_SHA256_ASN1, point)
+                .verify(message, signature)
+                .is_ok(),
+            Self::EdDsa { x } => UnparsedPublicKey::new(&signature::ED25519, x)
+                .verify(message, signature)
+                .is_ok(),
+            Self::Rs256 { n, e } => RsaPublicKeyComponents { n, e }
+                .verify(&signature::RSA_PKCS1_2048_8192_SHA256, message, signature)
+                .is_ok(),
+        }
+    }
+}
+
+/// Check a `navigator.credentials.create()` response answering `challenge`
+/// and return the new credential
+pub fn verify_registration(
+    rp: &WebAuthnConfig,
+    challenge: &str,
+    client_data_json: &[u8],
+    attestation_object: &[u8],
+) -> UserResult<(AuthenticatorData, AttestedCredential)> {
+    check_client_data(rp, client_data_json, "webauthn.create", challenge)?;
+
+    let attestation: Value = ciborium::de::from_reader(attestation_object)
+        .map_err(|_| invalid("malformed attestation object"))?;
+    let auth_data = attestation
+        .as_map()
+        .and_then(|map| {
+            map.iter()
+                .find(|(key, _)| key.as_text() == Some("authData"))
+                .and_then(|(_, value)| value.as_bytes())
+        })
+        .ok_or_else(|| invalid("attestation object has no authenticator data"))?;
+
+    let auth_data = AuthenticatorData::parse(auth_data)?;
+    auth_data.check(rp)?;
+    let credential = auth_data
+        .attested_credential
+        .clone()
+        .ok_or_else(|| invalid("registration has no credential"))?;
+    Ok((auth_data, credential))
+}
+
+/// Check a `navigator.credentials.get()` response answering `challenge`,
+/// signed by the credential with COSE key `public_key`
+pub fn verify_assertion(
+    rp: &WebAuthnConfig,
+    challenge: &str,
+    public_key: &[u8],
+    client_data_json: &[u8],
+    authenticator_data: &[u8],
+    signature: &[u8],
+) -> UserResult<AuthenticatorData> {
+    check_client_data(rp, client_data_json, "webauthn.get", challenge)?;
+
+    let parsed = AuthenticatorData::parse(authenticator_data)?;
+    parsed.check(rp)?;
+
+    let mut message = authenticator_data.to_vec();
+    message.extend_from_slice(digest(&SHA256, client_data_json).as_ref());
+    if !CoseKey::parse(public_key)?.verify(&message, signature) {
+        return Err(rejected("signature is invalid"));
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::rand::SystemRandom;
+    use ring::signature::{EcdsaKeyPair, KeyPair, ECDSA_P256_SHA256_ASN1_SIGNING};
+
+    const ORIGIN: &str = "https://nexus-security.io";
+    const CHALLENGE: &str = "c2lnbi1pbi1jaGFsbGVuZ2U";
+
+    fn rp() -> WebAuthnConfig {
+        WebAuthnConfig {
+            rp_id: "nexus-security.io".to_string(),
+            rp_name: "Nexus-Security".to_string(),
+            origins: vec![ORIGIN.to_string()],
+            challenge_ttl_seconds: 300,
+            max_credentials: 10,
+            passkey_login: true,
+        }
+    }
+
+    fn key_pair() -> EcdsaKeyPair {
+        let rng = SystemRandom::new();
+        let pkcs8 = EcdsaKeyPair::generate_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, &rng).unwrap();
+        EcdsaKeyPair::from_pkcs8(&ECDSA_P256_SHA256_ASN1_SIGNING, pkcs8.as_ref(), &rng).unwrap()
+    }
+
+    fn cbor(value: &Value) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        ciborium::ser::into_writer(value, &mut bytes).unwrap();
+        bytes
+    }
+
+    fn cose_key(key: &EcdsaKeyPair) -> Vec<u8> {
+        let point = key.public_key().as_ref();
+        cbor(&Value::Map(vec![
+            (1.into(), 2.into()),
+            (3.into(), (-7).into()),
+            ((-1).into(), 1.into()),
+            ((-2).into(), Value::Bytes(point[1..33].to_vec())),
+            ((-3).into(), Value::Bytes(point[33..].to_vec())),
+        ]))
+    }
+
+    fn auth_data(flags: u8, sign_count: u32, credential: Option<(&[u8], &[u8])>) -> Vec<u8> {
+        let mut data = digest(&SHA256, b"nexus-security.io").as_ref().to_vec();
+        data.push(flags);
+        data.extend(sign_count.to_be_bytes());
+        if let Some((id, key)) = credential {
+            data.extend([0u8; 16]);
+            data.extend((id.len() as u16).to_be_bytes());
+            data.extend(id);
+            data.extend(key);
+        }
+        data
+    }
+
+    fn client_data_for(kind: &str, origin: &str) -> Vec<u8> {
+        serde_json::json!({ "type": kind, "challenge": CHALLENGE, "origin": origin })
+            .to_string()
+            .into_bytes()
+    }
+
+    fn sign(key: &EcdsaKeyPair, auth_data: &[u8], client_data: &[u8]) -> Vec<u8> {
+        let mut message = auth_data.to_vec();
+        message.extend_from_slice(digest(&SHA256, client_data).as_ref());
+        key.sign(&SystemRandom::new(), &message).unwrap().as_ref().to_vec()
+    }
+
+    #[test]
+    fn test_registration_returns_the_credential() {
+        let key = key_pair();
+        let public_key = cose_key(&key);
+        let data = auth_data(0x45, 0, Some((b"credential-1", &public_key)));
+        let attestation = cbor(&Value::Map(vec![
+            ("fmt".into(), "none".into()),
+            ("attStmt".into(), Value::Map(vec![])),
+            ("authData".into(), Value::Bytes(data)),
+        ]));
+        let client_data = client_data_for("webauthn.create", ORIGIN);
+
+        let (parsed, credential) = verify_registration(&rp(), CHALLENGE, &client_data, &attestation).unwrap();
+        assert!(parsed.user_verified());
+        assert_eq!(credential.credential_id, b"credential-1");
+        assert_eq!(credential.public_key, public_key);
+        assert_eq!(credential.algorithm, COSE_ES256);
+
+        assert_eq!(client_data_challenge(&client_data).unwrap(), CHALLENGE);
+        assert!(verify_registration(&rp(), "other-challenge", &client_data, &attestation).is_err());
+    }
+
+    #[test]
+    fn test_credential_key_is_read_up_to_extensions() {
+        let key = key_pair();
+        let public_key = cose_key(&key);
+        let mut data = auth_data(0xC1, 0, Some((b"credential-1", &public_key)));
+        data.extend(cbor(&Value::Map(vec![("credProtect".into(), 2.into())])));
+
+        let credential = AuthenticatorData::parse(&data).unwrap().attested_credential.unwrap();
+        assert_eq!(credential.public_key, public_key);
+    }
+
+    #[test]
+    fn test_assertion_checks_signature_origin_and_rp() {
+        let key = key_pair();
+        let public_key = cose_key(&key);
+        let data = auth_data(0x05, 7, None);
+        let client_data = client_data_for("webauthn.get", ORIGIN);
+        let signature = sign(&key, &data, &client_data);
+
+        let parsed = verify_assertion(&rp(), CHALLENGE, &public_key, &client_data, &data, &signature).unwrap();
+        assert_eq!(parsed.sign_count, 7);
+        assert!(parsed.user_verified());
+
+        // Another key, origin, ceremony or relying party
+        assert!(verify_assertion(&rp(), CHALLENGE, &cose_key(&key_pair()), &client_data, &data, &signature).is_err());
+        let evil = client_data_for("webauthn.get", "https://evil.example");
+        assert!(verify_assertion(&rp(), CHALLENGE, &public_key, &evil, &data, &sign(&key, &data, &evil)).is_err());
+        let create = client_data_for("webauthn.create", ORIGIN);
+        assert!(verify_assertion(&rp(), CHALLENGE, &public_key, &create, &data, &sign(&key, &data, &create)).is_err());
+        let mut other_rp = rp();
+        other_rp.rp_id = "evil.example".to_string();
+        assert!(verify_assertion(&other_rp, CHALLENGE, &public_key, &client_data, &data, &signature).is_err());
+
+        // A touch is required
+        let absent = auth_data(0x00, 8, None);
+        assert!(verify_assertion(&rp(), CHALLENGE, &public_key, &client_data, &absent, &sign(&key, &absent, &client_data)).is_err());
+    }
+}
@@ -8,6 +8,14 @@ pub struct Config {
     pub redis: RedisConfig,
     pub jwt: JwtConfig,
     pub email: EmailConfig,
+    pub magic_link: MagicLinkConfig,
+    pub impersonation: ImpersonationConfig,
+    pub certificates: CertificateConfig,
+    pub siwe: SiweConfig,
+    pub oauth: OAuthConfig,
+    pub webauthn: WebAuthnConfig,
+    pub organizations: OrganizationConfig,
+    pub kyc: KycConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,6 +52,236 @@ pub struct EmailConfig {
     pub from_address: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MagicLinkConfig {
+    pub base_url: String,
+    pub expiry_minutes: u64,
+    pub max_requests_per_hour: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImpersonationConfig {
+    /// Lifetime of an impersonation token; there is no refresh
+    pub token_ttl_minutes: u64,
+    /// How long a user has to approve a request
+    pub consent_ttl_minutes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CertificateConfig {
+    /// Name certificates are issued under
+    pub issuer: String,
+    /// Base64 PKCS#8 Ed25519 key. Without one a key is generated at startup,
+    /// and certificates stop verifying when the service restarts.
+    pub signing_key: Option<String>,
+    pub reputation_service_url: String,
+    /// Public page a certificate ID is appended to
+    pub verification_base_url: String,
+}
+
+/// Sign-In with Ethereum: messages must name `domain` and one of
+/// `chain_ids`, and carry a nonce issued within `nonce_ttl_seconds`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SiweConfig {
+    pub domain: String,
+    /// Suggested to clients for the message's `URI`
+    pub uri: String,
+    pub statement: String,
+    pub chain_ids: Vec<u64>,
+    pub nonce_ttl_seconds: u64,
+}
+
+/// WebAuthn relying party. Credentials are scoped to `rp_id` and only
+/// accepted from `origins`; challenges live `challenge_ttl_seconds` in Redis.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebAuthnConfig {
+    pub rp_id: String,
+    pub rp_name: String,
+    pub origins: Vec<String>,
+    pub challenge_ttl_seconds: u64,
+    /// Authenticators one user may register
+    pub max_credentials: i64,
+    /// Let high-trust users sign in with a passkey alone
+    pub passkey_login: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrganizationConfig {
+    /// Page that accepts an invitation; the token is appended as `?token=`
+    pub invitation_base_url: String,
+    pub invitation_ttl_hours: u64,
+    /// Active API keys one organization may hold
+    pub max_api_keys: i64,
+}
+
+/// Identity verification. With `manual` users upload document URLs for an
+/// admin to review; `sumsub` and `onfido` collect documents in the
+/// provider's SDK and report decisions to `/api/v1/webhooks/kyc/<provider>`.
+/// Admins can decide a verification whichever provider holds it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KycConfig {
+    pub provider: KycProviderKind,
+    pub api_url: String,
+    pub api_token: String,
+    /// Signs API requests (Sumsub)
+    pub api_secret: String,
+    /// Signs webhook deliveries
+    pub webhook_secret: String,
+    /// Verification level applicants are checked at (Sumsub)
+    pub level_name: String,
+    /// Reports a check runs once the user has finished the SDK (Onfido)
+    pub reports: Vec<String>,
+    pub sdk_token_ttl_seconds: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KycProviderKind {
+    /// Document URLs reviewed by an admin
+    Manual,
+    Sumsub,
+    Onfido,
+}
+
+impl KycProviderKind {
+    /// Lowercase, as stored and used in routes
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KycProviderKind::Manual => "manual",
+            KycProviderKind::Sumsub => "sumsub",
+            KycProviderKind::Onfido => "onfido",
+        }
+    }
+}
+
+/// `KYC_PROVIDER` (manual, sumsub or onfido; default manual) and its
+/// `KYC_*` settings. The API token and webhook secret are required for a
+/// provider, and Sumsub also needs `KYC_API_SECRET`.
+fn parse_kyc_config() -> Result<KycConfig> {
+    let var = |key: &str| std::env::var(format!("KYC_{}", key)).ok().filter(|v| !v.is_empty());
+    let provider = match var("PROVIDER").unwrap_or_else(|| "manual".to_string()).to_lowercase().as_str() {
+        "manual" => KycProviderKind::Manual,
+        "sumsub" => KycProviderKind::Sumsub,
+        "onfido" => KycProviderKind::Onfido,
+        other => anyhow::bail!("KYC_PROVIDER must be manual, sumsub or onfido, not {}", other),
+    };
+    let required = |key: &str, needed: bool| match var(key) {
+        Some(value) => Ok(value),
+        None if needed => Err(anyhow::anyhow!("KYC_{} is required for the {} provider", key, provider.as_str())),
+        None => Ok(String::new()),
+    };
+    let default_api_url = match provider {
+        KycProviderKind::Manual => "",
+        KycProviderKind::Sumsub => "https://api.sumsub.com",
+        KycProviderKind::Onfido => "https://api.eu.onfido.com/v3.6",
+    };
+    let external = provider != KycProviderKind::Manual;
+
+    Ok(KycConfig {
+        api_url: var("API_URL")
+            .unwrap_or_else(|| default_api_url.to_string())
+            .trim_end_matches('/')
+            .to_string(),
+        api_token: required("API_TOKEN", external)?,
+        api_secret: required("API_SECRET", provider == KycProviderKind::Sumsub)?,
+        webhook_secret: required("WEBHOOK_SECRET", external)?,
+        level_name: var("LEVEL_NAME").unwrap_or_else(|| "basic-kyc-level".to_string()),
+        reports: var("REPORTS")
+            .unwrap_or_else(|| "document,facial_similarity_photo".to_string())
+            .split(',')
+            .map(|report| report.trim().to_string())
+            .filter(|report| !report.is_empty())
+            .collect(),
+        sdk_token_ttl_seconds: var("SDK_TOKEN_TTL_SECONDS")
+            .unwrap_or_else(|| "1200".to_string())
+            .parse()?,
+        provider,
+    })
+}
+
+/// OAuth2 / OpenID Connect sign-in. Each provider's callback is
+/// `<redirect_base_url>/<name>/callback`; the `state` of an authorization
+/// request lives `state_ttl_seconds` in Redis.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OAuthConfig {
+    pub redirect_base_url: String,
+    pub state_ttl_seconds: u64,
+    pub providers: Vec<OAuthProviderConfig>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OAuthProviderKind {
+    /// GitHub's OAuth apps, which are not OpenID Connect
+    Github,
+    /// Any OpenID Connect issuer, found by discovery
+    Oidc,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OAuthProviderConfig {
+    /// Lowercase, as used in routes
+    pub name: String,
+    pub kind: OAuthProviderKind,
+    pub client_id: String,
+    pub client_secret: String,
+    /// OIDC issuer, e.g. https://accounts.google.com
+    pub issuer: Option<String>,
+    pub scopes: String,
+    /// Create an account on first sign-in when no user matches
+    pub jit_provisioning: bool,
+    /// Role of provisioned accounts: "user" or "admin"
+    pub default_role: String,
+    /// Sign in to an existing account with the same email, if the provider
+    /// says the email is verified
+    pub link_by_email: bool,
+}
+
+/// Providers named in `OAUTH_PROVIDERS` (e.g. "github,google,okta"), each
+/// configured by `OAUTH_<NAME>_*`. `github` and `google` are presets; any
+/// other name is a generic OIDC provider and needs `OAUTH_<NAME>_ISSUER`.
+fn parse_oauth_providers() -> Result<Vec<OAuthProviderConfig>> {
+    let names = std::env::var("OAUTH_PROVIDERS").unwrap_or_default();
+    let mut providers = Vec::new();
+
+    for name in names.split(',').map(|n| n.trim().to_lowercase()).filter(|n| !n.is_empty()) {
+        let var = |key: &str| std::env::var(format!("OAUTH_{}_{}", name.to_uppercase(), key)).ok();
+        let (kind, issuer, scopes) = match name.as_str() {
+            "github" => (OAuthProviderKind::Github, None, "read:user user:email"),
+            "google" => (
+                OAuthProviderKind::Oidc,
+                Some(var("ISSUER").unwrap_or_else(|| "https://accounts.google.com".to_string())),
+                "openid email profile",
+            ),
+            _ => (OAuthProviderKind::Oidc, var("ISSUER"), "openid email profile"),
+        };
+        if kind == OAuthProviderKind::Oidc && issuer.is_none() {
+            anyhow::bail!("OAUTH_{}_ISSUER is required for an OIDC provider", name.to_uppercase());
+        }
+
+        let default_role = var("DEFAULT_ROLE").unwrap_or_else(|| "user".to_string());
+        if default_role != "user" && default_role != "admin" {
+            anyhow::bail!("OAUTH_{}_DEFAULT_ROLE must be user or admin", name.to_uppercase());
+        }
+
+        providers.push(OAuthProviderConfig {
+            client_id: var("CLIENT_ID")
+                .ok_or_else(|| anyhow::anyhow!("OAUTH_{}_CLIENT_ID is required", name.to_uppercase()))?,
+            client_secret: var("CLIENT_SECRET")
+                .ok_or_else(|| anyhow::anyhow!("OAUTH_{}_CLIENT_SECRET is required", name.to_uppercase()))?,
+            scopes: var("SCOPES").unwrap_or_else(|| scopes.to_string()),
+            jit_provisioning: var("JIT_PROVISIONING").map(|v| v == "true").unwrap_or(true),
+            link_by_email: var("LINK_BY_EMAIL").map(|v| v == "true").unwrap_or(true),
+            default_role,
+            kind,
+            issuer,
+            name,
+        });
+    }
+
+    Ok(providers)
+}
+
 impl Config {
     pub fn from_env() -> Result<Self> {
         Ok(Self {
@@ -87,6 +325,89 @@ impl Config {
                 from_address: std::env::var("EMAIL_FROM")
                     .unwrap_or_else(|_| "noreply@nexus-security.io".to_string()),
             },
+            magic_link: MagicLinkConfig {
+                base_url: std::env::var("MAGIC_LINK_BASE_URL")
+                    .unwrap_or_else(|_| "https://nexus-security.io/auth/magic-link".to_string()),
+                expiry_minutes: std::env::var("MAGIC_LINK_EXPIRY_MINUTES")
+                    .unwrap_or_else(|_| "15".to_string())
+                    .parse()?,
+                max_requests_per_hour: std::env::var("MAGIC_LINK_MAX_REQUESTS_PER_HOUR")
+                    .unwrap_or_else(|_| "5".to_string())
+                    .parse()?,
+            },
+            impersonation: ImpersonationConfig {
+                token_ttl_minutes: std::env::var("IMPERSONATION_TOKEN_TTL_MINUTES")
+                    .unwrap_or_else(|_| "15".to_string())
+                    .parse()?,
+                consent_ttl_minutes: std::env::var("IMPERSONATION_CONSENT_TTL_MINUTES")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()?,
+            },
+            certificates: CertificateConfig {
+                issuer: std::env::var("CERTIFICATE_ISSUER")
+                    .unwrap_or_else(|_| "Nexus-Security".to_string()),
+                signing_key: std::env::var("CERTIFICATE_SIGNING_KEY").ok(),
+                reputation_service_url: std::env::var("REPUTATION_SERVICE_URL")
+                    .unwrap_or_else(|_| "http://reputation-service:8080".to_string()),
+                verification_base_url: std::env::var("CERTIFICATE_VERIFICATION_BASE_URL")
+                    .unwrap_or_else(|_| "https://nexus-security.io/certificates".to_string()),
+            },
+            siwe: SiweConfig {
+                domain: std::env::var("SIWE_DOMAIN")
+                    .unwrap_or_else(|_| "nexus-security.io".to_string()),
+                uri: std::env::var("SIWE_URI")
+                    .unwrap_or_else(|_| "https://nexus-security.io".to_string()),
+                statement: std::env::var("SIWE_STATEMENT")
+                    .unwrap_or_else(|_| "Sign in to Nexus-Security".to_string()),
+                chain_ids: std::env::var("SIWE_CHAIN_IDS")
+                    .unwrap_or_else(|_| "1".to_string())
+                    .split(',')
+                    .map(|id| id.trim().parse())
+                    .collect::<Result<_, _>>()?,
+                nonce_ttl_seconds: std::env::var("SIWE_NONCE_TTL_SECONDS")
+                    .unwrap_or_else(|_| "300".to_string())
+                    .parse()?,
+            },
+            oauth: OAuthConfig {
+                redirect_base_url: std::env::var("OAUTH_REDIRECT_BASE_URL")
+                    .unwrap_or_else(|_| "https://nexus-security.io/auth/oauth".to_string()),
+                state_ttl_seconds: std::env::var("OAUTH_STATE_TTL_SECONDS")
+                    .unwrap_or_else(|_| "600".to_string())
+                    .parse()?,
+                providers: parse_oauth_providers()?,
+            },
+            webauthn: WebAuthnConfig {
+                rp_id: std::env::var("WEBAUTHN_RP_ID")
+                    .unwrap_or_else(|_| "nexus-security.io".to_string()),
+                rp_name: std::env::var("WEBAUTHN_RP_NAME")
+                    .unwrap_or_else(|_| "Nexus-Security".to_string()),
+                origins: std::env::var("WEBAUTHN_ORIGINS")
+                    .unwrap_or_else(|_| "https://nexus-security.io".to_string())
+                    .split(',')
+                    .map(|origin| origin.trim().trim_end_matches('/').to_string())
+                    .filter(|origin| !origin.is_empty())
+                    .collect(),
+                challenge_ttl_seconds: std::env::var("WEBAUTHN_CHALLENGE_TTL_SECONDS")
+                    .unwrap_or_else(|_| "300".to_string())
+                    .parse()?,
+                max_credentials: std::env::var("WEBAUTHN_MAX_CREDENTIALS")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()?,
+                passkey_login: std::env::var("WEBAUTHN_PASSKEY_LOGIN")
+                    .unwrap_or_else(|_| "true".to_string())
+                    .parse()?,
+            },
+            organizations: OrganizationConfig {
+                invitation_base_url: std::env::var("ORG_INVITATION_BASE_URL")
+                    .unwrap_or_else(|_| "https://nexus-security.io/organizations/join".to_string()),
+                invitation_ttl_hours: std::env::var("ORG_INVITATION_TTL_HOURS")
+                    .unwrap_or_else(|_| "168".to_string())
+                    .parse()?,
+                max_api_keys: std::env::var("ORG_MAX_API_KEYS")
+                    .unwrap_or_else(|_| "20".to_string())
+                    .parse()?,
+            },
+            kyc: parse_kyc_config()?,
         })
     }
 }
@@ -9,6 +9,7 @@ use uuid::Uuid;
 use crate::auth::Claims;
 use crate::handlers::auth::{AppError, MessageResponse};
 use crate::models::*;
+use crate::services::kyc::KycDecision;
 use crate::AppState;
 
 #[derive(Debug, Deserialize)]
@@ -47,18 +48,11 @@ pub struct RejectKycRequest {
     pub reason: String,
 }
 
-/// List all users (admin only)
+/// List all users (`users:manage`)
 pub async fn list_users(
     State(state): State<Arc<AppState>>,
-    Extension(claims): Extension<Claims>,
     Query(query): Query<ListUsersQuery>,
 ) -> Result<Json<UserListResponse>, AppError> {
-    if !claims.is_admin {
-        return Err(AppError::Unauthorized(
-            "Admin access required".to_string(),
-        ));
-    }
-
     let page = query.page.unwrap_or(1);
     let limit = query.limit.unwrap_or(20).min(100); // Max 100 per page
     let offset = (page - 1) * limit;
@@ -125,35 +119,21 @@ pub async fn list_users(
     }))
 }
 
-/// Get user details (admin only)
+/// Get user details (`users:manage`)
 pub async fn get_user(
     State(state): State<Arc<AppState>>,
-    Extension(claims): Extension<Claims>,
     Path(user_id): Path<Uuid>,
 ) -> Result<Json<User>, AppError> {
-    if !claims.is_admin {
-        return Err(AppError::Unauthorized(
-            "Admin access required".to_string(),
-        ));
-    }
-
     let user = state.user_service.get_user_by_id(user_id).await?;
 
     Ok(Json(user))
 }
 
-/// Suspend user (admin only)
+/// Suspend user (`users:manage`)
 pub async fn suspend_user(
     State(state): State<Arc<AppState>>,
-    Extension(claims): Extension<Claims>,
     Path(user_id): Path<Uuid>,
 ) -> Result<Json<MessageResponse>, AppError> {
-    if !claims.is_admin {
-        return Err(AppError::Unauthorized(
-            "Admin access required".to_string(),
-        ));
-    }
-
     sqlx::query("UPDATE users SET is_active = false WHERE id = $1")
         .bind(user_id)
         .execute(&state.db_pool)
@@ -165,18 +145,11 @@ pub async fn suspend_user(
     }))
 }
 
-/// Activate user (admin only)
+/// Activate user (`users:manage`)
 pub async fn activate_user(
     State(state): State<Arc<AppState>>,
-    Extension(claims): Extension<Claims>,
     Path(user_id): Path<Uuid>,
 ) -> Result<Json<MessageResponse>, AppError> {
-    if !claims.is_admin {
-        return Err(AppError::Unauthorized(
-            "Admin access required".to_string(),
-        ));
-    }
-
     sqlx::query("UPDATE users SET is_active = true WHERE id = $1")
         .bind(user_id)
         .execute(&state.db_pool)
@@ -188,103 +161,39 @@ pub async fn activate_user(
     }))
 }
 
-/// Approve KYC verification (admin only)
+/// Approve KYC verification (`kyc:review`), overriding any provider
 pub async fn approve_kyc(
     State(state): State<Arc<AppState>>,
     Extension(claims): Extension<Claims>,
     Path(user_id): Path<Uuid>,
 ) -> Result<Json<MessageResponse>, AppError> {
-    if !claims.is_admin {
-        return Err(AppError::Unauthorized(
-            "Admin access required".to_string(),
-        ));
-    }
-
     let admin_id = Uuid::parse_str(&claims.sub)
         .map_err(|_| AppError::Unauthorized("Invalid token".to_string()))?;
 
-    // Get latest KYC submission
-    let kyc = state
-        .user_service
-        .get_kyc_status(user_id)
-        .await?
-        .ok_or(AppError::UserError(UserError::NotFound))?;
-
-    // Update KYC status
-    sqlx::query(
-        r#"
-        UPDATE kyc_verifications
-        SET status = 'approved',
-            verified_by = $1,
-            verified_at = NOW()
-        WHERE id = $2
-        "#,
-    )
-    .bind(admin_id)
-    .bind(kyc.id)
-    .execute(&state.db_pool)
-    .await
-    .map_err(|e| AppError::InternalError(e.to_string()))?;
-
-    // Update user KYC status
-    sqlx::query("UPDATE users SET kyc_status = 'approved' WHERE id = $1")
-        .bind(user_id)
-        .execute(&state.db_pool)
-        .await
-        .map_err(|e| AppError::InternalError(e.to_string()))?;
+    state
+        .kyc_service
+        .review(user_id, admin_id, KycDecision::Approved)
+        .await?;
 
     Ok(Json(MessageResponse {
         message: "KYC approved successfully".to_string(),
     }))
 }
 
-/// Reject KYC verification (admin only)
+/// Reject KYC verification (`kyc:review`), overriding any provider
 pub async fn reject_kyc(
     State(state): State<Arc<AppState>>,
     Extension(claims): Extension<Claims>,
     Path(user_id): Path<Uuid>,
     Json(req): Json<RejectKycRequest>,
 ) -> Result<Json<MessageResponse>, AppError> {
-    if !claims.is_admin {
-        return Err(AppError::Unauthorized(
-            "Admin access required".to_string(),
-        ));
-    }
-
     let admin_id = Uuid::parse_str(&claims.sub)
         .map_err(|_| AppError::Unauthorized("Invalid token".to_string()))?;
 
-    // Get latest KYC submission
-    let kyc = state
-        .user_service
-        .get_kyc_status(user_id)
-        .await?
-        .ok_or(AppError::UserError(UserError::NotFound))?;
-
-    // Update KYC status
-    sqlx::query(
-        r#"
-        UPDATE kyc_verifications
-        SET status = 'rejected',
-            rejection_reason = $1,
-            verified_by = $2,
-            verified_at = NOW()
-        WHERE id = $3
-        "#,
-    )
-    .bind(&req.reason)
-    .bind(admin_id)
-    .bind(kyc.id)
-    .execute(&state.db_pool)
-    .await
-    .map_err(|e| AppError::InternalError(e.to_string()))?;
-
-    // Update user KYC status
-    sqlx::query("UPDATE users SET kyc_status = 'rejected' WHERE id = $1")
-        .bind(user_id)
-        .execute(&state.db_pool)
-        .await
-        .map_err(|e| AppError::InternalError(e.to_string()))?;
+    state
+        .kyc_service
+        .review(user_id, admin_id, KycDecision::Rejected(Some(req.reason)))
+        .await?;
 
     Ok(Json(MessageResponse {
         message: "KYC rejected successfully".to_string(),
@@ -1,6 +1,6 @@
 use axum::{
-    extract::{Query, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
     response::{IntoResponse, Response},
     Extension, Json,
 };
@@ -57,9 +57,43 @@ pub async fn register(
 /// Login user
 pub async fn login(
     State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
     Json(req): Json<LoginRequest>,
 ) -> Result<Json<AuthResponse>, AppError> {
-    let response = state.user_service.login(req).await?;
+    let response = state.user_service.login(req, client_ip(&headers)).await?;
+    Ok(Json(response))
+}
+
+/// Caller's IP as forwarded by the gateway
+fn client_ip(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get("X-Forwarded-For")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split(',').next())
+        .or_else(|| headers.get("X-Real-IP").and_then(|v| v.to_str().ok()))
+        .map(|ip| ip.trim().to_string())
+        .filter(|ip| !ip.is_empty())
+}
+
+/// Request a passwordless sign-in link by email
+pub async fn request_magic_link(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<MagicLinkRequest>,
+) -> Result<Json<MessageResponse>, AppError> {
+    state.user_service.request_magic_link(req).await?;
+
+    Ok(Json(MessageResponse {
+        message: "If an account exists for this address, a sign-in link has been sent".to_string(),
+    }))
+}
+
+/// Exchange a magic link token for access and refresh tokens
+pub async fn verify_magic_link(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(req): Json<MagicLinkVerifyRequest>,
+) -> Result<Json<AuthResponse>, AppError> {
+    let response = state.user_service.login_with_magic_link(req, client_ip(&headers)).await?;
     Ok(Json(response))
 }
 
@@ -147,6 +181,81 @@ pub async fn verify_wallet(
     }))
 }
 
+/// Issue a nonce for a Sign-In with Ethereum message
+pub async fn siwe_nonce(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<SiweNonceResponse>, AppError> {
+    let response = state.user_service.siwe_nonce().await?;
+    Ok(Json(response))
+}
+
+/// Exchange a signed SIWE message for access and refresh tokens
+pub async fn verify_siwe(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<SiweVerifyRequest>,
+) -> Result<Json<AuthResponse>, AppError> {
+    let response = state.user_service.login_with_siwe(req).await?;
+    Ok(Json(response))
+}
+
+/// Link the wallet that signed a SIWE message to the caller's account
+pub async fn link_siwe_identity(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<SiweLinkRequest>,
+) -> Result<Json<SiweIdentity>, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Unauthorized("Invalid token".to_string()))?;
+
+    let identity = state.user_service.link_siwe_identity(user_id, req).await?;
+    Ok(Json(identity))
+}
+
+/// Wallets the caller can sign in with
+pub async fn list_siwe_identities(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Vec<SiweIdentity>>, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Unauthorized("Invalid token".to_string()))?;
+
+    let identities = state.user_service.siwe_identities(user_id).await?;
+    Ok(Json(identities))
+}
+
+pub async fn unlink_siwe_identity(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(address): Path<String>,
+) -> Result<Json<MessageResponse>, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Unauthorized("Invalid token".to_string()))?;
+
+    state.user_service.unlink_siwe_identity(user_id, &address).await?;
+
+    Ok(Json(MessageResponse {
+        message: "Wallet unlinked successfully".to_string(),
+    }))
+}
+
+/// Challenge for a security key (second factor) or passkey sign-in
+pub async fn webauthn_options(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<WebAuthnAssertionOptionsRequest>,
+) -> Result<Json<WebAuthnRequestOptions>, AppError> {
+    let options = state.user_service.webauthn_assertion_options(req).await?;
+    Ok(Json(options))
+}
+
+/// Exchange a passkey assertion for access and refresh tokens
+pub async fn login_with_passkey(
+    State(state): State<Arc<AppState>>,
+    Json(assertion): Json<WebAuthnAssertion>,
+) -> Result<Json<AuthResponse>, AppError> {
+    let response = state.user_service.login_with_passkey(assertion).await?;
+    Ok(Json(response))
+}
+
 // ============= Additional Types =============
 
 #[derive(Debug, Deserialize)]
@@ -172,6 +281,26 @@ impl From<UserError> for AppError {
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let (status, message) = match self {
+            AppError::UserError(UserError::LockedOut(retry_after)) => {
+                let body = Json(json!({
+                    "error": "Too many failed sign-in attempts, try again later",
+                    "status": StatusCode::TOO_MANY_REQUESTS.as_u16(),
+                }));
+                return (
+                    StatusCode::TOO_MANY_REQUESTS,
+                    [("Retry-After", retry_after.to_string())],
+                    body,
+                )
+                    .into_response();
+            }
+            AppError::UserError(UserError::CaptchaRequired) => {
+                let body = Json(json!({
+                    "error": "CAPTCHA required",
+                    "captcha_required": true,
+                    "status": StatusCode::UNAUTHORIZED.as_u16(),
+                }));
+                return (StatusCode::UNAUTHORIZED, body).into_response();
+            }
             AppError::UserError(UserError::ValidationError(msg)) => {
                 (StatusCode::BAD_REQUEST, msg)
             }
@@ -195,6 +324,14 @@ impl IntoResponse for AppError {
             AppError::UserError(UserError::InvalidToken) => {
                 (StatusCode::UNAUTHORIZED, "Invalid or expired token".to_string())
             }
+            AppError::UserError(UserError::RateLimited(msg)) => {
+                (StatusCode::TOO_MANY_REQUESTS, msg)
+            }
+            AppError::UserError(UserError::Conflict(msg)) => (StatusCode::CONFLICT, msg),
+            AppError::UserError(UserError::Upstream(msg)) => {
+                tracing::error!("Upstream error: {}", msg);
+                (StatusCode::BAD_GATEWAY, "Upstream service unavailable".to_string())
+            }
             AppError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, msg),
             AppError::InternalError(msg) => {
                 tracing::error!("Internal error: {}", msg);
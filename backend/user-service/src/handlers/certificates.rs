@@ -0,0 +1,107 @@
+use axum::{
+    extract::{Path, State},
+    http::header,
+    response::IntoResponse,
+    Extension, Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::auth::Claims;
+use crate::handlers::auth::{AppError, MessageResponse};
+use crate::models::*;
+use crate::AppState;
+
+fn caller_id(claims: &Claims) -> Result<Uuid, AppError> {
+    Uuid::parse_str(&claims.sub).map_err(|_| AppError::Unauthorized("Invalid token".to_string()))
+}
+
+// ============= Operator side =============
+
+/// Issue a performance certificate for the caller's engines over a period
+pub async fn issue_certificate(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<IssueCertificateRequest>,
+) -> Result<Json<SignedCertificate>, AppError> {
+    let operator_id = caller_id(&claims)?;
+    let certificate = state.certificate_service.issue(operator_id, req).await?;
+    Ok(Json(certificate))
+}
+
+/// The caller's certificates, newest first
+pub async fn list_certificates(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Vec<SignedCertificate>>, AppError> {
+    let operator_id = caller_id(&claims)?;
+    let certificates = state.certificate_service.list(operator_id).await?;
+    Ok(Json(certificates))
+}
+
+/// One certificate as signed JSON
+pub async fn get_certificate(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(certificate_id): Path<Uuid>,
+) -> Result<Json<SignedCertificate>, AppError> {
+    let operator_id = caller_id(&claims)?;
+    let certificate = state.certificate_service.get(operator_id, certificate_id).await?;
+    Ok(Json(certificate))
+}
+
+/// One certificate rendered as a PDF
+pub async fn get_certificate_pdf(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(certificate_id): Path<Uuid>,
+) -> Result<impl IntoResponse, AppError> {
+    let operator_id = caller_id(&claims)?;
+    let pdf = state.certificate_service.pdf(operator_id, certificate_id).await?;
+    let disposition = format!("attachment; filename=\"certificate-{}.pdf\"", certificate_id);
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/pdf".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        pdf,
+    ))
+}
+
+// ============= Public verification =============
+
+/// Check a certificate by ID
+pub async fn verify_certificate(
+    State(state): State<Arc<AppState>>,
+    Path(certificate_id): Path<Uuid>,
+) -> Result<Json<CertificateVerification>, AppError> {
+    let verification = state.certificate_service.verify_by_id(certificate_id).await?;
+    Ok(Json(verification))
+}
+
+/// Check a payload and signature against the published key
+pub async fn verify_certificate_document(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<VerifyCertificateRequest>,
+) -> Result<Json<CertificateVerification>, AppError> {
+    let verification = state.certificate_service.verify_document(req).await?;
+    Ok(Json(verification))
+}
+
+/// The key certificates are signed with
+pub async fn get_public_key(State(state): State<Arc<AppState>>) -> Json<CertificatePublicKey> {
+    Json(state.certificate_service.public_key())
+}
+
+// ============= Admin side =============
+
+/// Revoke a certificate (admin only)
+pub async fn revoke_certificate(
+    State(state): State<Arc<AppState>>,
+    Path(certificate_id): Path<Uuid>,
+) -> Result<Json<MessageResponse>, AppError> {
+    state.certificate_service.revoke(certificate_id).await?;
+    Ok(Json(MessageResponse {
+        message: "Certificate revoked".to_string(),
+    }))
+}
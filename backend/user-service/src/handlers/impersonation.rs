@@ -0,0 +1,104 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::auth::Claims;
+use crate::handlers::auth::AppError;
+use crate::models::*;
+use crate::AppState;
+
+fn caller_id(claims: &Claims) -> Result<Uuid, AppError> {
+    Uuid::parse_str(&claims.sub).map_err(|_| AppError::Unauthorized("Invalid token".to_string()))
+}
+
+// ============= User side =============
+
+/// List impersonation requests addressed to the current user
+pub async fn list_requests(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Vec<ImpersonationSession>>, AppError> {
+    let user_id = caller_id(&claims)?;
+    let sessions = state.impersonation_service.list_for_user(user_id).await?;
+    Ok(Json(sessions))
+}
+
+/// Approve or deny a pending impersonation request
+pub async fn respond_to_request(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(session_id): Path<Uuid>,
+    Json(req): Json<ImpersonationConsentRequest>,
+) -> Result<Json<ImpersonationSession>, AppError> {
+    let user_id = caller_id(&claims)?;
+    let session = state
+        .impersonation_service
+        .respond(user_id, session_id, req.approve)
+        .await?;
+    Ok(Json(session))
+}
+
+/// Withdraw consent, ending the session and revoking its token
+pub async fn revoke_consent(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(session_id): Path<Uuid>,
+) -> Result<Json<ImpersonationSession>, AppError> {
+    let user_id = caller_id(&claims)?;
+    let session = state.impersonation_service.end(user_id, session_id).await?;
+    Ok(Json(session))
+}
+
+// ============= Admin side =============
+
+/// Ask a user for permission to act as them (admin only)
+pub async fn request_impersonation(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<RequestImpersonationRequest>,
+) -> Result<Json<ImpersonationSession>, AppError> {
+    let admin_id = caller_id(&claims)?;
+    let session = state.impersonation_service.request(admin_id, req).await?;
+    Ok(Json(session))
+}
+
+/// Start an approved session and receive the impersonation token (admin only)
+pub async fn start_impersonation(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(session_id): Path<Uuid>,
+) -> Result<Json<ImpersonationTokenResponse>, AppError> {
+    let admin_id = caller_id(&claims)?;
+    let response = state.impersonation_service.start(admin_id, session_id).await?;
+
+    tracing::warn!(
+        "Admin {} started impersonating user {} (session {})",
+        admin_id,
+        response.session.user_id,
+        session_id
+    );
+    Ok(Json(response))
+}
+
+/// End an impersonation session early (admin only)
+pub async fn end_impersonation(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(session_id): Path<Uuid>,
+) -> Result<Json<ImpersonationSession>, AppError> {
+    let admin_id = caller_id(&claims)?;
+    let session = state.impersonation_service.end(admin_id, session_id).await?;
+    Ok(Json(session))
+}
+
+/// Full audit trail of a session (admin only)
+pub async fn get_audit_log(
+    State(state): State<Arc<AppState>>,
+    Path(session_id): Path<Uuid>,
+) -> Result<Json<Vec<ImpersonationAuditEntry>>, AppError> {
+    let entries = state.impersonation_service.audit_log(session_id).await?;
+    Ok(Json(entries))
+}
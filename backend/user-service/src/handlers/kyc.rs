@@ -1,16 +1,28 @@
-use axum::{extract::State, Extension, Json};
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    Extension, Json,
+};
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use std::sync::Arc;
+use tracing::{error, warn};
 use uuid::Uuid;
 
 use crate::auth::Claims;
+use crate::config::KycProviderKind;
 use crate::handlers::auth::{AppError, MessageResponse};
 use crate::models::*;
+use crate::services::kyc::SdkToken;
 use crate::AppState;
 
 #[derive(Debug, Serialize)]
 pub struct SubmitKycResponse {
     pub kyc_id: Uuid,
+    /// "manual", or the provider whose SDK collects the documents
+    pub provider: KycProviderKind,
+    pub sdk_token: Option<SdkToken>,
     pub message: String,
 }
 
@@ -21,20 +33,32 @@ pub struct UploadDocumentsRequest {
     pub selfie_url: String,
 }
 
+fn caller_id(claims: &Claims) -> Result<Uuid, AppError> {
+    Uuid::parse_str(&claims.sub).map_err(|_| AppError::Unauthorized("Invalid token".to_string()))
+}
+
 /// Submit KYC verification request
 pub async fn submit_kyc(
     State(state): State<Arc<AppState>>,
     Extension(claims): Extension<Claims>,
     Json(req): Json<SubmitKycRequest>,
 ) -> Result<Json<SubmitKycResponse>, AppError> {
-    let user_id = Uuid::parse_str(&claims.sub)
-        .map_err(|_| AppError::Unauthorized("Invalid token".to_string()))?;
+    let user_id = caller_id(&claims)?;
+
+    let submission = state.kyc_service.submit(user_id, req).await?;
 
-    let kyc_id = state.user_service.submit_kyc(user_id, req).await?;
+    let message = match submission.provider {
+        KycProviderKind::Manual => {
+            "KYC verification submitted successfully. Please upload required documents."
+        }
+        _ => "KYC verification submitted successfully. Please complete verification with the provided SDK token.",
+    };
 
     Ok(Json(SubmitKycResponse {
-        kyc_id,
-        message: "KYC verification submitted successfully. Please upload required documents.".to_string(),
+        kyc_id: submission.kyc_id,
+        provider: submission.provider,
+        sdk_token: submission.sdk_token,
+        message: message.to_string(),
     }))
 }
 
@@ -43,57 +67,90 @@ pub async fn get_kyc_status(
     State(state): State<Arc<AppState>>,
     Extension(claims): Extension<Claims>,
 ) -> Result<Json<Option<KycVerification>>, AppError> {
-    let user_id = Uuid::parse_str(&claims.sub)
-        .map_err(|_| AppError::Unauthorized("Invalid token".to_string()))?;
+    let user_id = caller_id(&claims)?;
 
-    let kyc = state.user_service.get_kyc_status(user_id).await?;
+    let kyc = state.kyc_service.latest(user_id).await?;
 
     Ok(Json(kyc))
 }
 
-/// Upload KYC documents
+/// Upload KYC documents (manual review only)
 pub async fn upload_documents(
     State(state): State<Arc<AppState>>,
     Extension(claims): Extension<Claims>,
     Json(req): Json<UploadDocumentsRequest>,
 ) -> Result<Json<MessageResponse>, AppError> {
-    let user_id = Uuid::parse_str(&claims.sub)
-        .map_err(|_| AppError::Unauthorized("Invalid token".to_string()))?;
-
-    // Get latest KYC submission
-    let kyc = state
-        .user_service
-        .get_kyc_status(user_id)
-        .await?
-        .ok_or(AppError::UserError(UserError::NotFound))?;
-
-    // Update document URLs
-    sqlx::query(
-        r#"
-        UPDATE kyc_verifications
-        SET document_front_url = $1,
-            document_back_url = $2,
-            selfie_url = $3,
-            status = 'under_review'
-        WHERE id = $4
-        "#,
-    )
-    .bind(&req.document_front_url)
-    .bind(&req.document_back_url)
-    .bind(&req.selfie_url)
-    .bind(kyc.id)
-    .execute(&state.db_pool)
-    .await
-    .map_err(|e| AppError::InternalError(e.to_string()))?;
-
-    // Update user KYC status
-    sqlx::query("UPDATE users SET kyc_status = 'under_review' WHERE id = $1")
-        .bind(user_id)
-        .execute(&state.db_pool)
-        .await
-        .map_err(|e| AppError::InternalError(e.to_string()))?;
+    let user_id = caller_id(&claims)?;
+
+    state
+        .kyc_service
+        .upload_documents(
+            user_id,
+            &req.document_front_url,
+            req.document_back_url.as_deref(),
+            &req.selfie_url,
+        )
+        .await?;
 
     Ok(Json(MessageResponse {
         message: "Documents uploaded successfully. Your KYC is now under review.".to_string(),
     }))
 }
+
+/// New SDK token for a verification the provider is collecting documents for
+pub async fn create_sdk_token(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<SdkToken>, AppError> {
+    let user_id = caller_id(&claims)?;
+
+    let token = state.kyc_service.sdk_token(user_id).await?;
+
+    Ok(Json(token))
+}
+
+/// The user has finished the provider's SDK
+pub async fn complete_kyc(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<MessageResponse>, AppError> {
+    let user_id = caller_id(&claims)?;
+
+    state.kyc_service.complete(user_id).await?;
+
+    Ok(Json(MessageResponse {
+        message: "Documents submitted. Your KYC is now under review.".to_string(),
+    }))
+}
+
+/// Receive a decision from the KYC provider.
+/// Non-2xx responses make the provider retry, so only transient failures
+/// return 5xx.
+pub async fn receive_kyc_webhook(
+    State(state): State<Arc<AppState>>,
+    Path(provider): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> (StatusCode, Json<Value>) {
+    match state.kyc_service.handle_webhook(&provider, &headers, &body).await {
+        Ok(updated) => (StatusCode::OK, Json(json!({ "updated": updated }))),
+        Err(UserError::NotFound) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "Unknown KYC provider"})),
+        ),
+        Err(e @ UserError::AuthenticationError(_)) => {
+            warn!("Rejected {} KYC webhook: {}", provider, e);
+            (StatusCode::UNAUTHORIZED, Json(json!({"error": e.to_string()})))
+        }
+        Err(e @ UserError::ValidationError(_)) => {
+            (StatusCode::BAD_REQUEST, Json(json!({"error": e.to_string()})))
+        }
+        Err(e) => {
+            error!("Failed to process {} KYC webhook: {}", provider, e);
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(json!({"error": "Failed to process delivery"})),
+            )
+        }
+    }
+}
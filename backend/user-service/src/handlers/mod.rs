@@ -5,3 +5,7 @@ pub mod settings;
 pub mod kyc;
 pub mod wallet;
 pub mod admin;
+pub mod impersonation;
+pub mod oauth;
+pub mod organizations;
+pub mod certificates;
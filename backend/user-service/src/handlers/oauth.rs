@@ -0,0 +1,75 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::auth::Claims;
+use crate::handlers::auth::{AppError, MessageResponse};
+use crate::models::*;
+use crate::AppState;
+
+fn caller_id(claims: &Claims) -> Result<Uuid, AppError> {
+    Uuid::parse_str(&claims.sub).map_err(|_| AppError::Unauthorized("Invalid token".to_string()))
+}
+
+/// Providers users can sign in with
+pub async fn list_providers(
+    State(state): State<Arc<AppState>>,
+) -> Json<Vec<OAuthProviderInfo>> {
+    Json(state.user_service.oauth_providers())
+}
+
+/// Start signing in with a provider
+pub async fn authorize(
+    State(state): State<Arc<AppState>>,
+    Path(provider): Path<String>,
+) -> Result<Json<OAuthAuthorizeResponse>, AppError> {
+    let response = state.user_service.start_oauth(&provider, None).await?;
+    Ok(Json(response))
+}
+
+/// Exchange the provider's authorization code for access and refresh tokens
+pub async fn callback(
+    State(state): State<Arc<AppState>>,
+    Path(provider): Path<String>,
+    Json(req): Json<OAuthCallbackRequest>,
+) -> Result<Json<AuthResponse>, AppError> {
+    let response = state.user_service.login_with_oauth(&provider, req).await?;
+    Ok(Json(response))
+}
+
+/// Start linking a provider to the caller's account; the callback completes it
+pub async fn link(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(provider): Path<String>,
+) -> Result<Json<OAuthAuthorizeResponse>, AppError> {
+    let user_id = caller_id(&claims)?;
+    let response = state.user_service.start_oauth(&provider, Some(user_id)).await?;
+    Ok(Json(response))
+}
+
+/// Provider accounts the caller can sign in with
+pub async fn list_identities(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Vec<OAuthIdentity>>, AppError> {
+    let user_id = caller_id(&claims)?;
+    let identities = state.user_service.oauth_identities(user_id).await?;
+    Ok(Json(identities))
+}
+
+pub async fn unlink_identity(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(provider): Path<String>,
+) -> Result<Json<MessageResponse>, AppError> {
+    let user_id = caller_id(&claims)?;
+    state.user_service.unlink_oauth_identity(user_id, &provider).await?;
+
+    Ok(Json(MessageResponse {
+        message: "Provider unlinked successfully".to_string(),
+    }))
+}
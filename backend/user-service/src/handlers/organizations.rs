@@ -0,0 +1,181 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::auth::Claims;
+use crate::handlers::auth::{AppError, MessageResponse};
+use crate::models::*;
+use crate::AppState;
+
+fn caller_id(claims: &Claims) -> Result<Uuid, AppError> {
+    Uuid::parse_str(&claims.sub).map_err(|_| AppError::Unauthorized("Invalid token".to_string()))
+}
+
+// ============= Organization =============
+
+/// Create an organization owned by the current user
+pub async fn create_organization(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<CreateOrganizationRequest>,
+) -> Result<Json<OrganizationMembership>, AppError> {
+    let user_id = caller_id(&claims)?;
+    let membership = state.organization_service.create(user_id, req).await?;
+    Ok(Json(membership))
+}
+
+/// The current user's organization and role
+pub async fn current_organization(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<OrganizationMembership>, AppError> {
+    let user_id = caller_id(&claims)?;
+    let membership = state.organization_service.current(user_id).await?;
+    Ok(Json(membership))
+}
+
+/// Rename the organization or change its billing address (owners and admins)
+pub async fn update_organization(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(org_id): Path<Uuid>,
+    Json(req): Json<UpdateOrganizationRequest>,
+) -> Result<Json<Organization>, AppError> {
+    let user_id = caller_id(&claims)?;
+    let organization = state.organization_service.update(user_id, org_id, req).await?;
+    Ok(Json(organization))
+}
+
+// ============= Members =============
+
+pub async fn list_members(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(org_id): Path<Uuid>,
+) -> Result<Json<Vec<OrganizationMember>>, AppError> {
+    let user_id = caller_id(&claims)?;
+    let members = state.organization_service.members(user_id, org_id).await?;
+    Ok(Json(members))
+}
+
+pub async fn update_member_role(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path((org_id, member_id)): Path<(Uuid, Uuid)>,
+    Json(req): Json<UpdateMemberRoleRequest>,
+) -> Result<Json<OrganizationMember>, AppError> {
+    let user_id = caller_id(&claims)?;
+    let member = state
+        .organization_service
+        .change_role(user_id, org_id, member_id, req.role)
+        .await?;
+    Ok(Json(member))
+}
+
+/// Remove a member; members may remove themselves to leave
+pub async fn remove_member(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path((org_id, member_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<MessageResponse>, AppError> {
+    let user_id = caller_id(&claims)?;
+    state.organization_service.remove_member(user_id, org_id, member_id).await?;
+
+    Ok(Json(MessageResponse {
+        message: "Member removed".to_string(),
+    }))
+}
+
+// ============= Invitations =============
+
+/// Invite an address by email (owners and admins)
+pub async fn invite_member(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(org_id): Path<Uuid>,
+    Json(req): Json<InviteMemberRequest>,
+) -> Result<Json<OrganizationInvitation>, AppError> {
+    let user_id = caller_id(&claims)?;
+    let invitation = state.organization_service.invite(user_id, org_id, req).await?;
+    Ok(Json(invitation))
+}
+
+pub async fn list_invitations(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(org_id): Path<Uuid>,
+) -> Result<Json<Vec<OrganizationInvitation>>, AppError> {
+    let user_id = caller_id(&claims)?;
+    let invitations = state.organization_service.invitations(user_id, org_id).await?;
+    Ok(Json(invitations))
+}
+
+pub async fn revoke_invitation(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path((org_id, invitation_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<MessageResponse>, AppError> {
+    let user_id = caller_id(&claims)?;
+    state
+        .organization_service
+        .revoke_invitation(user_id, org_id, invitation_id)
+        .await?;
+
+    Ok(Json(MessageResponse {
+        message: "Invitation revoked".to_string(),
+    }))
+}
+
+/// Join an organization with the token from an invitation email
+pub async fn accept_invitation(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<AcceptInvitationRequest>,
+) -> Result<Json<OrganizationMembership>, AppError> {
+    let user = state.user_service.get_user_by_id(caller_id(&claims)?).await?;
+    let membership = state
+        .organization_service
+        .accept_invitation(&user, &req.token)
+        .await?;
+    Ok(Json(membership))
+}
+
+// ============= API keys =============
+
+/// Create an API key; the response is the only time the key is shown
+pub async fn create_api_key(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(org_id): Path<Uuid>,
+    Json(req): Json<CreateOrganizationApiKeyRequest>,
+) -> Result<Json<CreatedOrganizationApiKey>, AppError> {
+    let user_id = caller_id(&claims)?;
+    let created = state.organization_service.create_api_key(user_id, org_id, req).await?;
+    Ok(Json(created))
+}
+
+pub async fn list_api_keys(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(org_id): Path<Uuid>,
+) -> Result<Json<Vec<OrganizationApiKey>>, AppError> {
+    let user_id = caller_id(&claims)?;
+    let keys = state.organization_service.api_keys(user_id, org_id).await?;
+    Ok(Json(keys))
+}
+
+pub async fn revoke_api_key(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path((org_id, key_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<MessageResponse>, AppError> {
+    let user_id = caller_id(&claims)?;
+    state.organization_service.revoke_api_key(user_id, org_id, key_id).await?;
+
+    Ok(Json(MessageResponse {
+        message: "API key revoked".to_string(),
+    }))
+}
@@ -1,4 +1,7 @@
-use axum::{extract::State, Extension, Json};
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use uuid::Uuid;
@@ -157,3 +160,56 @@ pub async fn disable_2fa(
         message: "2FA disabled successfully".to_string(),
     }))
 }
+
+/// Options for registering a security key or passkey
+pub async fn webauthn_registration_options(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<WebAuthnRegistrationOptionsRequest>,
+) -> Result<Json<WebAuthnCreationOptions>, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Unauthorized("Invalid token".to_string()))?;
+
+    let options = state.user_service.webauthn_registration_options(user_id, req).await?;
+    Ok(Json(options))
+}
+
+/// Register the security key or passkey that answered the options
+pub async fn register_webauthn_credential(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<WebAuthnRegisterRequest>,
+) -> Result<Json<WebAuthnCredential>, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Unauthorized("Invalid token".to_string()))?;
+
+    let credential = state.user_service.register_webauthn_credential(user_id, req).await?;
+    Ok(Json(credential))
+}
+
+/// Security keys and passkeys of the current user
+pub async fn list_webauthn_credentials(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<Vec<WebAuthnCredential>>, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Unauthorized("Invalid token".to_string()))?;
+
+    let credentials = state.user_service.webauthn_credentials(user_id).await?;
+    Ok(Json(credentials))
+}
+
+pub async fn remove_webauthn_credential(
+    State(state): State<Arc<AppState>>,
+    Extension(claims): Extension<Claims>,
+    Path(credential_id): Path<Uuid>,
+) -> Result<Json<MessageResponse>, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::Unauthorized("Invalid token".to_string()))?;
+
+    state.user_service.remove_webauthn_credential(user_id, credential_id).await?;
+
+    Ok(Json(MessageResponse {
+        message: "Security key removed successfully".to_string(),
+    }))
+}
@@ -12,15 +12,17 @@ use axum::{
     Router,
 };
 use std::sync::Arc;
-use tower_http::{
-    cors::{Any, CorsLayer},
-    trace::TraceLayer,
-};
+use tower_http::trace::TraceLayer;
 use tracing::info;
 
 use crate::config::Config;
 use crate::middleware::{auth_middleware, admin_middleware};
+use crate::services::certificates::CertificateService;
+use crate::services::impersonation::ImpersonationService;
+use crate::services::kyc::KycService;
+use crate::services::organizations::OrganizationService;
 use crate::services::user_service::UserService;
+use shared::permissions::{self, require_scope};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -28,12 +30,7 @@ async fn main() -> Result<()> {
     dotenvy::dotenv().ok();
 
     // Initialize tracing
-    tracing_subscriber::fmt()
-        .with_target(false)
-        .with_thread_ids(true)
-        .with_line_number(true)
-        .json()
-        .init();
+    shared::observability::init_service_logging(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))?;
 
     info!("Starting User Service...");
 
@@ -75,34 +72,75 @@ async fn main() -> Result<()> {
     );
     info!("User service initialized");
 
+    let impersonation_service = Arc::new(ImpersonationService::new(
+        config.clone(),
+        db_pool.clone(),
+        redis_conn.clone(),
+    ));
+
+    let organization_service = Arc::new(OrganizationService::new(
+        config.clone(),
+        db_pool.clone(),
+        redis_conn.clone(),
+    ));
+
+    let certificate_service = Arc::new(CertificateService::new(
+        config.certificates.clone(),
+        db_pool.clone(),
+    )?);
+
+    let kyc_service = Arc::new(KycService::new(config.kyc.clone(), db_pool.clone())?);
+    info!("KYC provider: {}", kyc_service.provider().as_str());
+
     // Build application state
     let app_state = Arc::new(AppState {
         config: config.clone(),
         db_pool,
         redis_conn,
         user_service,
+        impersonation_service,
+        organization_service,
+        certificate_service,
+        kyc_service,
     });
 
-    // Configure CORS
-    let cors = CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods(Any)
-        .allow_headers(Any);
+    // Browser origins from CORS_ALLOWED_ORIGINS or the environment's defaults
+    let cors = shared::http_security::CorsPolicy::from_env().layer();
 
     // Public routes (no authentication required)
     let public_routes = Router::new()
         .route("/health", get(handlers::health::health_check))
         .route("/api/v1/auth/register", post(handlers::auth::register))
         .route("/api/v1/auth/login", post(handlers::auth::login))
+        .route("/api/v1/auth/magic-link", post(handlers::auth::request_magic_link))
+        .route("/api/v1/auth/magic-link/verify", post(handlers::auth::verify_magic_link))
+        .route("/api/v1/auth/siwe/nonce", get(handlers::auth::siwe_nonce))
+        .route("/api/v1/auth/siwe/verify", post(handlers::auth::verify_siwe))
+        .route("/api/v1/auth/webauthn/options", post(handlers::auth::webauthn_options))
+        .route("/api/v1/auth/webauthn/login", post(handlers::auth::login_with_passkey))
+        .route("/api/v1/auth/oauth/providers", get(handlers::oauth::list_providers))
+        .route("/api/v1/auth/oauth/:provider/authorize", get(handlers::oauth::authorize))
+        .route("/api/v1/auth/oauth/:provider/callback", post(handlers::oauth::callback))
         .route("/api/v1/auth/refresh", post(handlers::auth::refresh_token))
         .route("/api/v1/auth/forgot-password", post(handlers::auth::forgot_password))
-        .route("/api/v1/auth/reset-password", post(handlers::auth::reset_password));
+        .route("/api/v1/auth/reset-password", post(handlers::auth::reset_password))
+        .route("/api/v1/certificates/public-key", get(handlers::certificates::get_public_key))
+        .route("/api/v1/certificates/verify", post(handlers::certificates::verify_certificate_document))
+        .route("/api/v1/certificates/:certificate_id/verify", get(handlers::certificates::verify_certificate))
+        // Signed by the KYC provider rather than the gateway
+        .route("/api/v1/webhooks/kyc/:provider", post(handlers::kyc::receive_kyc_webhook));
 
     // Protected routes (authentication required)
     let protected_routes = Router::new()
         .route("/api/v1/auth/logout", post(handlers::auth::logout))
         .route("/api/v1/auth/verify-email", post(handlers::auth::verify_email))
         .route("/api/v1/auth/wallet/verify", post(handlers::auth::verify_wallet))
+        .route("/api/v1/auth/siwe/link", post(handlers::auth::link_siwe_identity))
+        .route("/api/v1/auth/siwe/identities", get(handlers::auth::list_siwe_identities))
+        .route("/api/v1/auth/siwe/identities/:address", delete(handlers::auth::unlink_siwe_identity))
+        .route("/api/v1/auth/oauth/:provider/link", post(handlers::oauth::link))
+        .route("/api/v1/auth/oauth/identities", get(handlers::oauth::list_identities))
+        .route("/api/v1/auth/oauth/identities/:provider", delete(handlers::oauth::unlink_identity))
 
         // Profile endpoints
         .route("/api/v1/profile", get(handlers::profile::get_profile))
@@ -117,35 +155,93 @@ async fn main() -> Result<()> {
         .route("/api/v1/settings/2fa/enable", post(handlers::settings::enable_2fa))
         .route("/api/v1/settings/2fa/disable", post(handlers::settings::disable_2fa))
         .route("/api/v1/settings/2fa/verify", post(handlers::settings::verify_2fa))
+        .route("/api/v1/settings/webauthn/register/options", post(handlers::settings::webauthn_registration_options))
+        .route("/api/v1/settings/webauthn/register", post(handlers::settings::register_webauthn_credential))
+        .route("/api/v1/settings/webauthn/credentials", get(handlers::settings::list_webauthn_credentials))
+        .route("/api/v1/settings/webauthn/credentials/:id", delete(handlers::settings::remove_webauthn_credential))
 
         // KYC endpoints
         .route("/api/v1/kyc/submit", post(handlers::kyc::submit_kyc))
         .route("/api/v1/kyc/status", get(handlers::kyc::get_kyc_status))
         .route("/api/v1/kyc/documents", post(handlers::kyc::upload_documents))
+        .route("/api/v1/kyc/sdk-token", post(handlers::kyc::create_sdk_token))
+        .route("/api/v1/kyc/complete", post(handlers::kyc::complete_kyc))
 
         // Wallet endpoints
         .route("/api/v1/wallet/link", post(handlers::wallet::link_wallet))
         .route("/api/v1/wallet/unlink", delete(handlers::wallet::unlink_wallet))
         .route("/api/v1/wallet/list", get(handlers::wallet::list_wallets))
+
+        // Impersonation consent
+        .route("/api/v1/impersonation/requests", get(handlers::impersonation::list_requests))
+        .route("/api/v1/impersonation/requests/:session_id/consent", post(handlers::impersonation::respond_to_request))
+        .route("/api/v1/impersonation/requests/:session_id/revoke", post(handlers::impersonation::revoke_consent))
+
+        // Organizations
+        .route("/api/v1/organizations", post(handlers::organizations::create_organization))
+        .route("/api/v1/organizations/current", get(handlers::organizations::current_organization))
+        .route("/api/v1/organizations/invitations/accept", post(handlers::organizations::accept_invitation))
+        .route("/api/v1/organizations/:org_id", put(handlers::organizations::update_organization))
+        .route("/api/v1/organizations/:org_id/members", get(handlers::organizations::list_members))
+        .route("/api/v1/organizations/:org_id/members/:user_id", put(handlers::organizations::update_member_role))
+        .route("/api/v1/organizations/:org_id/members/:user_id", delete(handlers::organizations::remove_member))
+        .route("/api/v1/organizations/:org_id/invitations", get(handlers::organizations::list_invitations))
+        .route("/api/v1/organizations/:org_id/invitations", post(handlers::organizations::invite_member))
+        .route("/api/v1/organizations/:org_id/invitations/:invitation_id", delete(handlers::organizations::revoke_invitation))
+        .route("/api/v1/organizations/:org_id/api-keys", get(handlers::organizations::list_api_keys))
+        .route("/api/v1/organizations/:org_id/api-keys", post(handlers::organizations::create_api_key))
+        .route("/api/v1/organizations/:org_id/api-keys/:key_id", delete(handlers::organizations::revoke_api_key))
+
+        // Operator performance certificates
+        .route("/api/v1/certificates", get(handlers::certificates::list_certificates))
+        .route("/api/v1/certificates", post(handlers::certificates::issue_certificate))
+        .route("/api/v1/certificates/:certificate_id", get(handlers::certificates::get_certificate))
+        .route("/api/v1/certificates/:certificate_id/pdf", get(handlers::certificates::get_certificate_pdf))
         .layer(axum_middleware::from_fn_with_state(app_state.clone(), auth_middleware));
 
-    // Admin routes (admin role required)
-    let admin_routes = Router::new()
+    // Admin routes, each group requiring its permission scope
+    let user_admin_routes = Router::new()
         .route("/api/v1/admin/users", get(handlers::admin::list_users))
         .route("/api/v1/admin/users/:user_id", get(handlers::admin::get_user))
         .route("/api/v1/admin/users/:user_id/suspend", post(handlers::admin::suspend_user))
         .route("/api/v1/admin/users/:user_id/activate", post(handlers::admin::activate_user))
+        .route_layer(axum_middleware::from_fn(require_scope(permissions::USERS_MANAGE)));
+
+    let kyc_admin_routes = Router::new()
         .route("/api/v1/admin/kyc/:user_id/approve", post(handlers::admin::approve_kyc))
         .route("/api/v1/admin/kyc/:user_id/reject", post(handlers::admin::reject_kyc))
+        .route("/api/v1/admin/certificates/:certificate_id/revoke", post(handlers::certificates::revoke_certificate))
+        .route_layer(axum_middleware::from_fn(require_scope(permissions::KYC_REVIEW)));
+
+    let impersonation_routes = Router::new()
+        .route("/api/v1/admin/impersonation", post(handlers::impersonation::request_impersonation))
+        .route("/api/v1/admin/impersonation/:session_id/start", post(handlers::impersonation::start_impersonation))
+        .route("/api/v1/admin/impersonation/:session_id/end", post(handlers::impersonation::end_impersonation))
+        .route("/api/v1/admin/impersonation/:session_id/audit", get(handlers::impersonation::get_audit_log))
+        .route_layer(axum_middleware::from_fn(require_scope(permissions::USERS_IMPERSONATE)));
+
+    let admin_routes = Router::new()
+        .merge(user_admin_routes)
+        .merge(kyc_admin_routes)
+        .merge(impersonation_routes)
         .layer(axum_middleware::from_fn_with_state(app_state.clone(), admin_middleware));
 
-    // Combine all routes
+    // Combine all routes. Clients call this service directly with its own
+    // tokens and identity never comes from a header, so unlike the services
+    // behind the gateway it does not verify request signatures.
     let app = Router::new()
         .merge(public_routes)
         .merge(protected_routes)
         .merge(admin_routes)
+        .merge(shared::observability::log_level_routes())
+        .layer(axum::middleware::from_fn_with_state(
+            shared::chaos::FaultInjector::from_env(),
+            shared::chaos::chaos_middleware,
+        ))
         .layer(cors)
+        .layer(axum::middleware::from_fn(shared::http_security::security_headers_middleware))
         .layer(TraceLayer::new_for_http())
+        .layer(axum::middleware::from_fn(shared::observability::log_context_middleware))
         .with_state(app_state);
 
     // Start server
@@ -164,4 +260,8 @@ pub struct AppState {
     pub db_pool: sqlx::PgPool,
     pub redis_conn: redis::aio::ConnectionManager,
     pub user_service: Arc<UserService>,
+    pub impersonation_service: Arc<ImpersonationService>,
+    pub organization_service: Arc<OrganizationService>,
+    pub certificate_service: Arc<CertificateService>,
+    pub kyc_service: Arc<KycService>,
 }
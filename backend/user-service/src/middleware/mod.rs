@@ -1,7 +1,7 @@
 use axum::{
     body::Body,
     extract::{Request, State},
-    http::{header, StatusCode},
+    http::{header, Method, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
     Json,
@@ -44,13 +44,74 @@ pub async fn auth_middleware(
         return Err(AuthError::InvalidTokenType);
     }
 
+    if let Ok(user_id) = claims.sub.parse() {
+        shared::observability::set_log_user_id(user_id);
+    }
+
+    if claims.is_impersonation() {
+        return run_impersonated(&state, claims, request, next).await;
+    }
+
     // Insert claims into request extensions
     request.extensions_mut().insert(claims);
 
     Ok(next.run(request).await)
 }
 
-/// Extract user claims from Authorization header and verify admin role
+/// Impersonation is read-only: support staff see what the user sees but
+/// cannot change anything on their behalf
+fn allowed_while_impersonating(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+/// Run a request made with an impersonation token. The session must still
+/// be live, and the request is audited before it runs; if the audit entry
+/// cannot be written the request is refused.
+async fn run_impersonated(
+    state: &AppState,
+    claims: Claims,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, AuthError> {
+    let impersonation = &state.impersonation_service;
+    let session_id = claims
+        .act
+        .as_ref()
+        .and_then(|actor| Uuid::parse_str(&actor.session_id).ok())
+        .ok_or(AuthError::InvalidToken)?;
+
+    match impersonation.is_active(session_id).await {
+        Ok(true) => {}
+        Ok(false) => return Err(AuthError::InvalidToken),
+        Err(e) => {
+            tracing::error!("Failed to check impersonation session {}: {}", session_id, e);
+            return Err(AuthError::AuditUnavailable);
+        }
+    }
+
+    let blocked = !allowed_while_impersonating(request.method());
+    let entry_id = impersonation
+        .audit_request(&claims, request.method().as_str(), request.uri().path(), blocked)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to audit impersonated request: {}", e);
+            AuthError::AuditUnavailable
+        })?;
+
+    if blocked {
+        return Err(AuthError::ImpersonationReadOnly);
+    }
+
+    request.extensions_mut().insert(claims);
+    let response = next.run(request).await;
+    impersonation
+        .complete_request_audit(entry_id, response.status().as_u16())
+        .await;
+
+    Ok(response)
+}
+
+/// Extract user claims from Authorization header and their permission scopes
 pub async fn admin_middleware(
     State(state): State<Arc<AppState>>,
     mut request: Request,
@@ -76,11 +137,14 @@ pub async fn admin_middleware(
         return Err(AuthError::InvalidTokenType);
     }
 
-    // Check if user is admin
-    if !claims.is_admin {
+    // Admin routes need some permission; each group then requires its own
+    // with `require_scope`. Impersonation tokens never carry scopes.
+    let scopes = claims.scopes();
+    if scopes.is_empty() {
         return Err(AuthError::InsufficientPermissions);
     }
 
+    request.extensions_mut().insert(scopes);
     request.extensions_mut().insert(claims);
 
     Ok(next.run(request).await)
@@ -115,6 +179,8 @@ pub enum AuthError {
     InvalidToken,
     InvalidTokenType,
     InsufficientPermissions,
+    ImpersonationReadOnly,
+    AuditUnavailable,
 }
 
 impl IntoResponse for AuthError {
@@ -125,6 +191,12 @@ impl IntoResponse for AuthError {
             AuthError::InvalidToken => (StatusCode::UNAUTHORIZED, "Invalid or expired token"),
             AuthError::InvalidTokenType => (StatusCode::UNAUTHORIZED, "Invalid token type"),
             AuthError::InsufficientPermissions => (StatusCode::FORBIDDEN, "Insufficient permissions"),
+            AuthError::ImpersonationReadOnly => {
+                (StatusCode::FORBIDDEN, "Changes are not permitted while impersonating")
+            }
+            AuthError::AuditUnavailable => {
+                (StatusCode::SERVICE_UNAVAILABLE, "Impersonation audit unavailable")
+            }
         };
 
         let body = Json(json!({
@@ -28,6 +28,21 @@ pub enum UserError {
     
     #[error("Invalid token")]
     InvalidToken,
+
+    #[error("Rate limit exceeded: {0}")]
+    RateLimited(String),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Upstream service error: {0}")]
+    Upstream(String),
+
+    #[error("Too many failed sign-in attempts, retry in {0}s")]
+    LockedOut(u64),
+
+    #[error("CAPTCHA required")]
+    CaptchaRequired,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -48,6 +63,23 @@ pub struct User {
     pub last_login: Option<DateTime<Utc>>,
 }
 
+/// Domain of the placeholder email of accounts created by signing in with a
+/// wallet; `.invalid` is reserved, so nothing is ever delivered there
+pub const WALLET_ONLY_EMAIL_DOMAIN: &str = "wallet.invalid";
+
+impl User {
+    /// Accounts created by signing in with a wallet or an OAuth provider
+    /// have no password
+    pub fn has_password(&self) -> bool {
+        !self.password_hash.is_empty()
+    }
+
+    /// Verified email and approved KYC: trusted to sign in with a passkey alone
+    pub fn is_high_trust(&self) -> bool {
+        self.email_verified && self.kyc_status == "approved"
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct UserProfile {
     pub user_id: Uuid,
@@ -95,6 +127,12 @@ pub struct KycVerification {
     pub verified_by: Option<Uuid>,
     pub submitted_at: DateTime<Utc>,
     pub verified_at: Option<DateTime<Utc>>,
+    /// "manual", or the provider checking the documents
+    pub provider: String,
+    /// The applicant the provider checks this submission as
+    pub provider_applicant_id: Option<String>,
+    /// Last decision a provider webhook reported
+    pub provider_updated_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -129,6 +167,56 @@ pub struct LoginRequest {
     pub password: String,
     
     pub two_factor_code: Option<String>,
+    /// A security key or passkey assertion instead of a TOTP code
+    #[serde(default)]
+    pub webauthn: Option<WebAuthnAssertion>,
+
+    /// Solved CAPTCHA, required after repeated failures
+    #[serde(default)]
+    pub captcha_token: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct MagicLinkRequest {
+    #[validate(email)]
+    pub email: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MagicLinkVerifyRequest {
+    pub token: String,
+
+    pub two_factor_code: Option<String>,
+    /// A security key or passkey assertion instead of a TOTP code
+    #[serde(default)]
+    pub webauthn: Option<WebAuthnAssertion>,
+
+    /// Solved CAPTCHA, required after repeated failures
+    #[serde(default)]
+    pub captcha_token: Option<String>,
+}
+
+/// How a user authenticated, recorded in the activity log
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LoginMethod {
+    Password,
+    MagicLink,
+    Siwe,
+    OAuth,
+    Passkey,
+}
+
+impl LoginMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LoginMethod::Password => "password",
+            LoginMethod::MagicLink => "magic_link",
+            LoginMethod::Siwe => "siwe",
+            LoginMethod::OAuth => "oauth",
+            LoginMethod::Passkey => "passkey",
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -191,3 +279,472 @@ impl From<User> for UserPublic {
         }
     }
 }
+
+// ============= Sign-In with Ethereum =============
+
+/// What a client needs to build a SIWE message
+#[derive(Debug, Serialize)]
+pub struct SiweNonceResponse {
+    pub nonce: String,
+    pub domain: String,
+    pub uri: String,
+    pub statement: String,
+    pub chain_ids: Vec<u64>,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SiweVerifyRequest {
+    /// The EIP-4361 message, exactly as signed
+    pub message: String,
+    pub signature: String,
+
+    pub two_factor_code: Option<String>,
+    /// A security key or passkey assertion instead of a TOTP code
+    #[serde(default)]
+    pub webauthn: Option<WebAuthnAssertion>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SiweLinkRequest {
+    pub message: String,
+    pub signature: String,
+}
+
+/// A wallet the user can sign in with
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SiweIdentity {
+    /// Lowercase
+    pub address: String,
+    pub user_id: Uuid,
+    /// Chain of the message the wallet was linked with
+    pub chain_id: i64,
+    pub created_at: DateTime<Utc>,
+    pub last_sign_in_at: Option<DateTime<Utc>>,
+}
+
+// ============= OAuth / OIDC =============
+
+#[derive(Debug, Serialize)]
+pub struct OAuthProviderInfo {
+    pub name: String,
+    /// "github" or "oidc"
+    pub kind: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OAuthAuthorizeResponse {
+    /// Send the user here; the provider redirects back with `code` and `state`
+    pub authorization_url: String,
+    pub state: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OAuthCallbackRequest {
+    pub code: String,
+    pub state: String,
+
+    pub two_factor_code: Option<String>,
+    /// A security key or passkey assertion instead of a TOTP code
+    #[serde(default)]
+    pub webauthn: Option<WebAuthnAssertion>,
+}
+
+/// A provider account the user can sign in with
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct OAuthIdentity {
+    pub provider: String,
+    /// The user's ID at the provider
+    pub subject: String,
+    pub user_id: Uuid,
+    /// As last reported by the provider
+    pub email: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_sign_in_at: Option<DateTime<Utc>>,
+}
+
+// ============= WebAuthn =============
+
+/// A registered security key or passkey
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct WebAuthnCredential {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// Base64url, as the browser reports it
+    pub credential_id: String,
+    pub name: String,
+    #[serde(skip)]
+    pub public_key: Vec<u8>,
+    /// COSE algorithm of the key
+    pub algorithm: i32,
+    pub sign_count: i64,
+    pub aaguid: Uuid,
+    pub transports: Vec<String>,
+    /// May be used to sign in without a password
+    pub passkey: bool,
+    /// Synced between devices by its platform
+    pub backup_eligible: bool,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WebAuthnCredentialDescriptor {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub id: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub transports: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WebAuthnRelyingParty {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebAuthnUserEntity {
+    /// Base64url user handle
+    pub id: String,
+    pub name: String,
+    pub display_name: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WebAuthnCredentialParameter {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub alg: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebAuthnAuthenticatorSelection {
+    pub resident_key: String,
+    pub user_verification: String,
+}
+
+/// `PublicKeyCredentialCreationOptions` for `navigator.credentials.create()`;
+/// binary fields are base64url
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebAuthnCreationOptions {
+    pub challenge: String,
+    pub rp: WebAuthnRelyingParty,
+    pub user: WebAuthnUserEntity,
+    pub pub_key_cred_params: Vec<WebAuthnCredentialParameter>,
+    /// Milliseconds
+    pub timeout: u64,
+    pub attestation: String,
+    pub authenticator_selection: WebAuthnAuthenticatorSelection,
+    pub exclude_credentials: Vec<WebAuthnCredentialDescriptor>,
+}
+
+/// `PublicKeyCredentialRequestOptions` for `navigator.credentials.get()`
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebAuthnRequestOptions {
+    pub challenge: String,
+    pub rp_id: String,
+    pub timeout: u64,
+    pub user_verification: String,
+    pub allow_credentials: Vec<WebAuthnCredentialDescriptor>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebAuthnRegistrationOptionsRequest {
+    /// Register a passkey (discoverable, user-verifying) rather than a
+    /// second-factor security key
+    #[serde(default)]
+    pub passkey: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebAuthnRegisterRequest {
+    pub name: String,
+    /// Base64url fields of the `navigator.credentials.create()` response
+    pub client_data_json: String,
+    pub attestation_object: String,
+    #[serde(default)]
+    pub transports: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebAuthnAssertionOptionsRequest {
+    /// Whose credentials to allow, for a second factor; without it the
+    /// options are for a passkey sign-in
+    pub email: Option<String>,
+}
+
+/// Base64url fields of a `navigator.credentials.get()` response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebAuthnAssertion {
+    pub credential_id: String,
+    pub client_data_json: String,
+    pub authenticator_data: String,
+    pub signature: String,
+    pub user_handle: Option<String>,
+}
+
+// ============= Impersonation =============
+
+/// An admin's request to act as a user, from consent to expiry.
+/// Status: pending -> approved | denied | expired, approved -> active -> ended
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ImpersonationSession {
+    pub id: Uuid,
+    pub admin_id: Uuid,
+    pub user_id: Uuid,
+    pub reason: String,
+    pub status: String,
+    pub requested_at: DateTime<Utc>,
+    pub consent_expires_at: DateTime<Utc>,
+    pub consented_at: Option<DateTime<Utc>>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub ended_by: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ImpersonationAuditEntry {
+    pub id: i64,
+    pub session_id: Uuid,
+    pub admin_id: Uuid,
+    pub user_id: Uuid,
+    pub event: String,
+    pub method: Option<String>,
+    pub path: Option<String>,
+    pub status_code: Option<i16>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct RequestImpersonationRequest {
+    pub user_id: Uuid,
+    #[validate(length(min = 10, max = 500))]
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImpersonationConsentRequest {
+    pub approve: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImpersonationTokenResponse {
+    pub access_token: String,
+    pub expires_in: u64,
+    pub session: ImpersonationSession,
+}
+
+// ============= Operator certificates =============
+
+/// One engine's performance over a period, as reported by the
+/// reputation-service
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnginePerformance {
+    pub engine_id: Uuid,
+    pub engine_name: String,
+    pub total_analyses: i64,
+    pub completed_analyses: i64,
+    pub failed_analyses: i64,
+    pub resolved_analyses: i64,
+    pub correct_analyses: i64,
+    pub accuracy_rate: f64,
+    pub uptime_rate: f64,
+    pub avg_confidence: f64,
+}
+
+/// Totals across all of an operator's engines
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerformanceSummary {
+    pub engine_count: usize,
+    pub total_analyses: i64,
+    pub resolved_analyses: i64,
+    pub correct_analyses: i64,
+    pub accuracy_rate: f64,
+    pub uptime_rate: f64,
+}
+
+/// The signed statement about an operator's engines
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperatorCertificate {
+    pub id: Uuid,
+    pub version: u32,
+    pub issuer: String,
+    pub operator_id: Uuid,
+    pub operator_name: String,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub issued_at: DateTime<Utc>,
+    pub engines: Vec<EnginePerformance>,
+    pub summary: PerformanceSummary,
+    pub key_id: String,
+}
+
+/// A certificate as handed out. `payload` is the exact JSON that was
+/// signed; verifiers must check `signature` against it, not against a
+/// re-serialization of `certificate`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SignedCertificate {
+    pub certificate: OperatorCertificate,
+    pub payload: String,
+    pub signature: String,
+    pub algorithm: &'static str,
+    pub verification_url: String,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct CertificateRecord {
+    pub id: Uuid,
+    pub operator_id: Uuid,
+    pub payload: String,
+    pub signature: String,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IssueCertificateRequest {
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VerifyCertificateRequest {
+    pub payload: String,
+    pub signature: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CertificateVerification {
+    pub valid: bool,
+    pub revoked: bool,
+    /// Why the certificate is not valid
+    pub reason: Option<String>,
+    pub certificate: Option<OperatorCertificate>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CertificatePublicKey {
+    pub key_id: String,
+    pub algorithm: &'static str,
+    /// Base64url raw Ed25519 public key
+    pub public_key: String,
+}
+
+// ============= Organizations =============
+
+/// Member roles; see `shared::types::OrganizationRole` for what each may do
+pub use shared::types::OrganizationRole;
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Organization {
+    pub id: Uuid,
+    pub name: String,
+    pub billing_email: Option<String>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// The caller's organization and their role in it
+#[derive(Debug, Clone, Serialize)]
+pub struct OrganizationMembership {
+    pub organization: Organization,
+    pub role: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct OrganizationMember {
+    pub user_id: Uuid,
+    pub username: String,
+    pub email: String,
+    pub role: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Invitations carry a token mailed to the invited address; only its hash
+/// is stored
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct OrganizationInvitation {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub email: String,
+    pub role: String,
+    #[serde(skip)]
+    pub token_hash: String,
+    pub invited_by: Option<Uuid>,
+    pub expires_at: DateTime<Utc>,
+    pub accepted_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An API key the gateway accepts as the organization with `role`
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct OrganizationApiKey {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub name: String,
+    /// First characters of the key, to tell keys apart
+    pub key_prefix: String,
+    #[serde(skip)]
+    pub key_hash: String,
+    pub role: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+/// A new key; `api_key` is only ever returned here
+#[derive(Debug, Serialize)]
+pub struct CreatedOrganizationApiKey {
+    pub api_key: String,
+    pub key: OrganizationApiKey,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateOrganizationRequest {
+    #[validate(length(min = 2, max = 255))]
+    pub name: String,
+    #[validate(email)]
+    pub billing_email: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateOrganizationRequest {
+    #[validate(length(min = 2, max = 255))]
+    pub name: Option<String>,
+    #[validate(email)]
+    pub billing_email: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct InviteMemberRequest {
+    #[validate(email)]
+    pub email: String,
+    pub role: OrganizationRole,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateMemberRoleRequest {
+    pub role: OrganizationRole,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AcceptInvitationRequest {
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateOrganizationApiKeyRequest {
+    #[validate(length(min = 1, max = 64))]
+    pub name: String,
+    pub role: OrganizationRole,
+}
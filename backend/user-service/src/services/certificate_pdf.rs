@@ -0,0 +1,187 @@
+// Minimal PDF writer for operator certificates
+//
+// Emits A4 pages of left-aligned text in the standard Helvetica fonts, so no
+// font files need to be embedded. Text outside printable ASCII is replaced
+// with `?`.
+
+use std::fmt::Write as _;
+
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 56.0;
+
+/// Average Helvetica glyph width as a fraction of the font size, used to
+/// wrap lines without font metrics
+const AVERAGE_GLYPH_WIDTH: f32 = 0.5;
+
+#[derive(Debug, Clone)]
+pub enum Line {
+    Title(String),
+    Heading(String),
+    Text(String),
+    Small(String),
+    Gap,
+}
+
+impl Line {
+    /// Font resource and size
+    fn style(&self) -> (&'static str, f32) {
+        match self {
+            Line::Title(_) => ("F2", 20.0),
+            Line::Heading(_) => ("F2", 13.0),
+            Line::Text(_) => ("F1", 11.0),
+            Line::Small(_) => ("F1", 8.0),
+            Line::Gap => ("F1", 8.0),
+        }
+    }
+
+    fn text(&self) -> &str {
+        match self {
+            Line::Title(t) | Line::Heading(t) | Line::Text(t) | Line::Small(t) => t,
+            Line::Gap => "",
+        }
+    }
+}
+
+/// Render lines top to bottom, starting a new page when one fills up
+pub fn render(lines: &[Line]) -> Vec<u8> {
+    let pages = layout(lines);
+
+    // 1: catalog, 2: page tree, 3-4: fonts, then a page and its content
+    // stream for every page
+    let page_ids: Vec<usize> = (0..pages.len()).map(|i| 5 + 2 * i).collect();
+    let mut objects = vec![
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            page_ids.iter().map(|id| format!("{} 0 R", id)).collect::<Vec<_>>().join(" "),
+            pages.len()
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+            .to_string(),
+    ];
+    for (content, page_id) in pages.iter().zip(&page_ids) {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+             /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+            PAGE_WIDTH,
+            PAGE_HEIGHT,
+            page_id + 1
+        ));
+        objects.push(format!(
+            "<< /Length {} >>\nstream\n{}\nendstream",
+            content.len(),
+            content
+        ));
+    }
+
+    let mut out = String::from("%PDF-1.4\n");
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(out.len());
+        let _ = write!(out, "{} 0 obj\n{}\nendobj\n", i + 1, object);
+    }
+
+    let xref_offset = out.len();
+    let _ = write!(out, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        let _ = writeln!(out, "{:010} 00000 n ", offset);
+    }
+    let _ = write!(
+        out,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref_offset
+    );
+    out.into_bytes()
+}
+
+/// Content streams, one per page
+fn layout(lines: &[Line]) -> Vec<String> {
+    let mut pages = Vec::new();
+    let mut content = String::new();
+    let mut y = PAGE_HEIGHT - MARGIN;
+
+    for line in lines {
+        let (font, size) = line.style();
+        let leading = size * 1.5;
+        let max_chars = ((PAGE_WIDTH - 2.0 * MARGIN) / (size * AVERAGE_GLYPH_WIDTH)) as usize;
+        let wrapped = match line {
+            Line::Gap => vec![String::new()],
+            _ => wrap(&sanitize(line.text()), max_chars),
+        };
+
+        for text in wrapped {
+            if y - leading < MARGIN {
+                pages.push(std::mem::take(&mut content));
+                y = PAGE_HEIGHT - MARGIN;
+            }
+            y -= leading;
+            if !text.is_empty() {
+                let _ = writeln!(
+                    content,
+                    "BT /{} {} Tf {} {:.1} Td ({}) Tj ET",
+                    font,
+                    size,
+                    MARGIN,
+                    y,
+                    escape(&text)
+                );
+            }
+        }
+    }
+
+    pages.push(content);
+    pages
+}
+
+fn sanitize(text: &str) -> String {
+    text.chars()
+        .map(|c| if c == ' ' || c.is_ascii_graphic() { c } else { '?' })
+        .collect()
+}
+
+/// Escape the characters that delimit PDF string literals
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '(' | ')' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Wrap at spaces, breaking words longer than a whole line
+fn wrap(text: &str, max_chars: usize) -> Vec<String> {
+    let max_chars = max_chars.max(1);
+    let mut lines = Vec::new();
+    let mut current = String::new();
+
+    for word in text.split(' ') {
+        let mut word = word;
+        while word.len() > max_chars {
+            if !current.is_empty() {
+                lines.push(std::mem::take(&mut current));
+            }
+            let (head, tail) = word.split_at(max_chars);
+            lines.push(head.to_string());
+            word = tail;
+        }
+        if !current.is_empty() && current.len() + 1 + word.len() > max_chars {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
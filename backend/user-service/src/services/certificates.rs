@@ -0,0 +1,561 @@
+use base64::{engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD}, Engine as _};
+use chrono::{DateTime, Duration, Utc};
+use ring::digest::{digest, SHA256};
+use ring::rand::SystemRandom;
+use ring::signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519};
+use serde::Deserialize;
+use shared::request_signing::RequestSigner;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::CertificateConfig;
+use crate::models::*;
+use crate::services::certificate_pdf::{self, Line};
+
+/// Bumped when the certificate document changes shape
+const CERTIFICATE_VERSION: u32 = 1;
+
+/// Longest period one certificate may cover
+const MAX_PERIOD_DAYS: i64 = 366;
+
+const ALGORITHM: &str = "Ed25519";
+
+/// Ed25519 key certificates are signed with
+pub struct CertificateSigner {
+    key_pair: Ed25519KeyPair,
+    key_id: String,
+}
+
+impl CertificateSigner {
+    pub fn from_config(config: &CertificateConfig) -> anyhow::Result<Self> {
+        let pkcs8 = match &config.signing_key {
+            Some(key) => STANDARD.decode(key.trim())?,
+            None => {
+                tracing::warn!(
+                    "CERTIFICATE_SIGNING_KEY is not set; certificates issued now will not verify after a restart"
+                );
+                Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+                    .map_err(|_| anyhow::anyhow!("Failed to generate certificate signing key"))?
+                    .as_ref()
+                    .to_vec()
+            }
+        };
+        let key_pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(&pkcs8)
+            .map_err(|e| anyhow::anyhow!("Invalid CERTIFICATE_SIGNING_KEY: {}", e))?;
+        let key_id = hex_prefix(digest(&SHA256, key_pair.public_key().as_ref()).as_ref());
+
+        Ok(Self { key_pair, key_id })
+    }
+
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    pub fn sign(&self, payload: &str) -> String {
+        URL_SAFE_NO_PAD.encode(self.key_pair.sign(payload.as_bytes()))
+    }
+
+    pub fn verify(&self, payload: &str, signature: &str) -> bool {
+        let Ok(signature) = URL_SAFE_NO_PAD.decode(signature.trim()) else {
+            return false;
+        };
+        UnparsedPublicKey::new(&ED25519, self.key_pair.public_key().as_ref())
+            .verify(payload.as_bytes(), &signature)
+            .is_ok()
+    }
+
+    pub fn public_key(&self) -> CertificatePublicKey {
+        CertificatePublicKey {
+            key_id: self.key_id.clone(),
+            algorithm: ALGORITHM,
+            public_key: URL_SAFE_NO_PAD.encode(self.key_pair.public_key().as_ref()),
+        }
+    }
+}
+
+/// First 16 bytes of a digest, hex encoded
+fn hex_prefix(bytes: &[u8]) -> String {
+    bytes.iter().take(16).map(|b| format!("{:02x}", b)).collect()
+}
+
+#[derive(Deserialize)]
+struct EnginePerformanceResponse {
+    engines: Vec<EnginePerformance>,
+}
+
+fn db_error(e: sqlx::Error) -> UserError {
+    UserError::DatabaseError(e.to_string())
+}
+
+fn summarize(engines: &[EnginePerformance]) -> PerformanceSummary {
+    let total_analyses = engines.iter().map(|e| e.total_analyses).sum();
+    let resolved_analyses: i64 = engines.iter().map(|e| e.resolved_analyses).sum();
+    let correct_analyses: i64 = engines.iter().map(|e| e.correct_analyses).sum();
+    let completed: i64 = engines.iter().map(|e| e.completed_analyses).sum();
+    let delivered_or_failed = completed + engines.iter().map(|e| e.failed_analyses).sum::<i64>();
+
+    let rate = |part: i64, whole: i64| if whole > 0 { part as f64 / whole as f64 } else { 0.0 };
+    PerformanceSummary {
+        engine_count: engines.len(),
+        total_analyses,
+        resolved_analyses,
+        correct_analyses,
+        accuracy_rate: rate(correct_analyses, resolved_analyses),
+        uptime_rate: rate(completed, delivered_or_failed),
+    }
+}
+
+/// Engine operator performance certificates.
+///
+/// A certificate states how an operator's engines performed over a period,
+/// using figures from the reputation-service. It is signed once at issue
+/// time and the signed JSON is stored verbatim, so anyone holding the
+/// payload and signature can check it against the published public key,
+/// and the public verification endpoint also reports revocation.
+pub struct CertificateService {
+    config: CertificateConfig,
+    db_pool: PgPool,
+    http: reqwest::Client,
+    /// Signs calls to the reputation-service
+    request_signer: Option<RequestSigner>,
+    signer: CertificateSigner,
+}
+
+impl CertificateService {
+    pub fn new(config: CertificateConfig, db_pool: PgPool) -> anyhow::Result<Self> {
+        let signer = CertificateSigner::from_config(&config)?;
+        let http = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(10))
+            .build()?;
+
+        Ok(Self {
+            config,
+            db_pool,
+            http,
+            request_signer: RequestSigner::from_env()?,
+            signer,
+        })
+    }
+
+    pub fn public_key(&self) -> CertificatePublicKey {
+        self.signer.public_key()
+    }
+
+    /// Issue a certificate covering the caller's engines over a period
+    pub async fn issue(
+        &self,
+        operator_id: Uuid,
+        req: IssueCertificateRequest,
+    ) -> UserResult<SignedCertificate> {
+        let now = Utc::now();
+        if req.period_start >= req.period_end {
+            return Err(UserError::ValidationError(
+                "period_start must be before period_end".to_string(),
+            ));
+        }
+        if req.period_end > now {
+            return Err(UserError::ValidationError(
+                "period_end cannot be in the future".to_string(),
+            ));
+        }
+        if req.period_end - req.period_start > Duration::days(MAX_PERIOD_DAYS) {
+            return Err(UserError::ValidationError(format!(
+                "A certificate cannot cover more than {} days",
+                MAX_PERIOD_DAYS
+            )));
+        }
+
+        let operator: User = sqlx::query_as("SELECT * FROM users WHERE id = $1")
+            .bind(operator_id)
+            .fetch_optional(&self.db_pool)
+            .await
+            .map_err(db_error)?
+            .ok_or(UserError::NotFound)?;
+
+        let engines = self
+            .engine_performance(operator_id, req.period_start, req.period_end)
+            .await?;
+        if engines.is_empty() {
+            return Err(UserError::ValidationError(
+                "No engines are registered to this account".to_string(),
+            ));
+        }
+
+        let certificate = OperatorCertificate {
+            id: Uuid::new_v4(),
+            version: CERTIFICATE_VERSION,
+            issuer: self.config.issuer.clone(),
+            operator_id,
+            operator_name: operator.username,
+            period_start: req.period_start,
+            period_end: req.period_end,
+            issued_at: now,
+            summary: summarize(&engines),
+            engines,
+            key_id: self.signer.key_id().to_string(),
+        };
+        let payload = serde_json::to_string(&certificate)
+            .map_err(|e| UserError::ValidationError(e.to_string()))?;
+        let signature = self.signer.sign(&payload);
+
+        let record: CertificateRecord = sqlx::query_as(
+            r#"
+            INSERT INTO operator_certificates
+                (id, operator_id, period_start, period_end, payload, signature, key_id, issued_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id, operator_id, payload, signature, revoked_at
+            "#,
+        )
+        .bind(certificate.id)
+        .bind(operator_id)
+        .bind(certificate.period_start)
+        .bind(certificate.period_end)
+        .bind(&payload)
+        .bind(&signature)
+        .bind(&certificate.key_id)
+        .bind(now)
+        .fetch_one(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+
+        tracing::info!(
+            "Issued performance certificate {} to operator {}",
+            record.id,
+            operator_id
+        );
+        self.signed(record)
+    }
+
+    /// The caller's certificates, newest first
+    pub async fn list(&self, operator_id: Uuid) -> UserResult<Vec<SignedCertificate>> {
+        let records: Vec<CertificateRecord> = sqlx::query_as(
+            r#"
+            SELECT id, operator_id, payload, signature, revoked_at
+            FROM operator_certificates
+            WHERE operator_id = $1
+            ORDER BY issued_at DESC
+            LIMIT 50
+            "#,
+        )
+        .bind(operator_id)
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+
+        records.into_iter().map(|record| self.signed(record)).collect()
+    }
+
+    /// One of the caller's certificates
+    pub async fn get(&self, operator_id: Uuid, id: Uuid) -> UserResult<SignedCertificate> {
+        let record = self.find(id).await?;
+        if record.operator_id != operator_id {
+            return Err(UserError::NotFound);
+        }
+        self.signed(record)
+    }
+
+    /// One of the caller's certificates as a PDF
+    pub async fn pdf(&self, operator_id: Uuid, id: Uuid) -> UserResult<Vec<u8>> {
+        let signed = self.get(operator_id, id).await?;
+        Ok(certificate_pdf::render(&pdf_lines(&signed)))
+    }
+
+    /// Public check of a stored certificate by ID
+    pub async fn verify_by_id(&self, id: Uuid) -> UserResult<CertificateVerification> {
+        let record = self.find(id).await?;
+        Ok(self.check(&record.payload, &record.signature, Some(&record)))
+    }
+
+    /// Public check of a payload and signature someone was handed
+    pub async fn verify_document(
+        &self,
+        req: VerifyCertificateRequest,
+    ) -> UserResult<CertificateVerification> {
+        let certificate: Option<OperatorCertificate> = serde_json::from_str(&req.payload).ok();
+        let record = match &certificate {
+            Some(certificate) => sqlx::query_as::<_, CertificateRecord>(
+                r#"
+                SELECT id, operator_id, payload, signature, revoked_at
+                FROM operator_certificates
+                WHERE id = $1
+                "#,
+            )
+            .bind(certificate.id)
+            .fetch_optional(&self.db_pool)
+            .await
+            .map_err(db_error)?,
+            None => None,
+        };
+
+        let mut verification = self.check(&req.payload, &req.signature, record.as_ref());
+        if verification.valid && record.as_ref().is_none_or(|r| r.payload != req.payload) {
+            verification.valid = false;
+            verification.reason = Some("Certificate was not issued by this service".to_string());
+        }
+        Ok(verification)
+    }
+
+    /// Revoke a certificate, e.g. after its figures were found to be wrong
+    /// (admin only)
+    pub async fn revoke(&self, id: Uuid) -> UserResult<()> {
+        let result = sqlx::query(
+            "UPDATE operator_certificates SET revoked_at = NOW() WHERE id = $1 AND revoked_at IS NULL",
+        )
+        .bind(id)
+        .execute(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+
+        if result.rows_affected() == 0 {
+            return Err(UserError::NotFound);
+        }
+        Ok(())
+    }
+
+    fn check(
+        &self,
+        payload: &str,
+        signature: &str,
+        record: Option<&CertificateRecord>,
+    ) -> CertificateVerification {
+        let certificate: Option<OperatorCertificate> = serde_json::from_str(payload).ok();
+        let revoked = record.is_some_and(|r| r.revoked_at.is_some());
+
+        let reason = match &certificate {
+            None => Some("Payload is not a certificate".to_string()),
+            Some(c) if c.key_id != self.signer.key_id() => {
+                Some("Signed with a key this service no longer uses".to_string())
+            }
+            Some(_) if !self.signer.verify(payload, signature) => {
+                Some("Signature does not match the payload".to_string())
+            }
+            Some(_) if revoked => Some("Certificate has been revoked".to_string()),
+            Some(_) => None,
+        };
+
+        CertificateVerification {
+            valid: reason.is_none(),
+            revoked,
+            reason,
+            certificate,
+        }
+    }
+
+    async fn find(&self, id: Uuid) -> UserResult<CertificateRecord> {
+        sqlx::query_as(
+            r#"
+            SELECT id, operator_id, payload, signature, revoked_at
+            FROM operator_certificates
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(db_error)?
+        .ok_or(UserError::NotFound)
+    }
+
+    fn signed(&self, record: CertificateRecord) -> UserResult<SignedCertificate> {
+        let certificate: OperatorCertificate = serde_json::from_str(&record.payload)
+            .map_err(|e| UserError::DatabaseError(format!("Corrupt certificate {}: {}", record.id, e)))?;
+
+        Ok(SignedCertificate {
+            verification_url: format!(
+                "{}/{}",
+                self.config.verification_base_url.trim_end_matches('/'),
+                record.id
+            ),
+            certificate,
+            payload: record.payload,
+            signature: record.signature,
+            algorithm: ALGORITHM,
+            revoked_at: record.revoked_at,
+        })
+    }
+
+    async fn engine_performance(
+        &self,
+        operator_id: Uuid,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> UserResult<Vec<EnginePerformance>> {
+        let url = reqwest::Url::parse_with_params(
+            &format!(
+                "{}/api/v1/reputation/engines/performance",
+                self.config.reputation_service_url.trim_end_matches('/')
+            ),
+            &[
+                ("owner_id", operator_id.to_string()),
+                ("from", from.to_rfc3339()),
+                ("to", to.to_rfc3339()),
+            ],
+        )
+        .map_err(|e| UserError::Upstream(format!("reputation-service: {}", e)))?;
+
+        let mut request = self.http.get(url.clone());
+        for (name, value) in shared::observability::propagation_headers() {
+            request = request.header(name, value);
+        }
+        if let Some(ref signer) = self.request_signer {
+            let path = format!("{}?{}", url.path(), url.query().unwrap_or_default());
+            for (name, value) in signer.sign_now("GET", &path, Some(b""), None, None).pairs() {
+                request = request.header(name, value);
+            }
+        }
+        let response = request
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| UserError::Upstream(format!("reputation-service: {}", e)))?;
+
+        let body: EnginePerformanceResponse = response
+            .json()
+            .await
+            .map_err(|e| UserError::Upstream(format!("reputation-service: {}", e)))?;
+        Ok(body.engines)
+    }
+}
+
+fn percent(rate: f64) -> String {
+    format!("{:.1}%", rate * 100.0)
+}
+
+fn pdf_lines(signed: &SignedCertificate) -> Vec<Line> {
+    let c = &signed.certificate;
+    let mut lines = vec![
+        Line::Title("Engine Operator Performance Certificate".to_string()),
+        Line::Gap,
+        Line::Text(format!("Issued to {} ({})", c.operator_name, c.operator_id)),
+        Line::Text(format!(
+            "Period: {} to {} (UTC)",
+            c.period_start.format("%Y-%m-%d %H:%M"),
+            c.period_end.format("%Y-%m-%d %H:%M")
+        )),
+        Line::Text(format!("Issued by {} on {}", c.issuer, c.issued_at.format("%Y-%m-%d"))),
+    ];
+    if let Some(revoked_at) = signed.revoked_at {
+        lines.push(Line::Heading(format!(
+            "REVOKED on {}",
+            revoked_at.format("%Y-%m-%d")
+        )));
+    }
+
+    lines.extend([
+        Line::Gap,
+        Line::Heading("Summary".to_string()),
+        Line::Text(format!(
+            "{} engine(s), {} analyses, {} accuracy ({} of {} resolved), {} uptime",
+            c.summary.engine_count,
+            c.summary.total_analyses,
+            percent(c.summary.accuracy_rate),
+            c.summary.correct_analyses,
+            c.summary.resolved_analyses,
+            percent(c.summary.uptime_rate)
+        )),
+        Line::Gap,
+        Line::Heading("Engines".to_string()),
+    ]);
+    for engine in &c.engines {
+        lines.push(Line::Text(format!(
+            "{}: {} analyses, {} accuracy ({} of {} resolved), {} uptime, {:.2} average confidence",
+            engine.engine_name,
+            engine.total_analyses,
+            percent(engine.accuracy_rate),
+            engine.correct_analyses,
+            engine.resolved_analyses,
+            percent(engine.uptime_rate),
+            engine.avg_confidence
+        )));
+    }
+
+    lines.extend([
+        Line::Gap,
+        Line::Heading("Verification".to_string()),
+        Line::Small(format!("Certificate ID: {}", c.id)),
+        Line::Small(format!("Verify at: {}", signed.verification_url)),
+        Line::Small(format!("{} key: {}", signed.algorithm, c.key_id)),
+        Line::Small(format!("Signature: {}", signed.signature)),
+    ]);
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_signer() -> CertificateSigner {
+        CertificateSigner::from_config(&CertificateConfig {
+            issuer: "Nexus-Security".to_string(),
+            signing_key: None,
+            reputation_service_url: "http://localhost".to_string(),
+            verification_base_url: "http://localhost/certificates".to_string(),
+        })
+        .unwrap()
+    }
+
+    fn engine(completed: i64, failed: i64, resolved: i64, correct: i64) -> EnginePerformance {
+        EnginePerformance {
+            engine_id: Uuid::new_v4(),
+            engine_name: "engine".to_string(),
+            total_analyses: completed + failed,
+            completed_analyses: completed,
+            failed_analyses: failed,
+            resolved_analyses: resolved,
+            correct_analyses: correct,
+            accuracy_rate: 0.0,
+            uptime_rate: 0.0,
+            avg_confidence: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_signatures_cover_the_exact_payload() {
+        let signer = test_signer();
+        let payload = r#"{"id":"1","accuracy_rate":0.95}"#;
+        let signature = signer.sign(payload);
+
+        assert!(signer.verify(payload, &signature));
+        assert!(!signer.verify(r#"{"id":"1","accuracy_rate":0.99}"#, &signature));
+        assert!(!signer.verify(payload, "not-a-signature"));
+        assert!(!test_signer().verify(payload, &signature));
+        assert_eq!(signer.key_id().len(), 32);
+    }
+
+    #[test]
+    fn test_summary_weights_engines_by_volume() {
+        let summary = summarize(&[engine(90, 10, 80, 76), engine(10, 0, 10, 5)]);
+        assert_eq!(summary.engine_count, 2);
+        assert_eq!(summary.total_analyses, 110);
+        assert!((summary.accuracy_rate - 81.0 / 90.0).abs() < 1e-9);
+        assert!((summary.uptime_rate - 100.0 / 110.0).abs() < 1e-9);
+
+        let empty = summarize(&[engine(0, 0, 0, 0)]);
+        assert_eq!(empty.accuracy_rate, 0.0);
+        assert_eq!(empty.uptime_rate, 0.0);
+    }
+
+    #[test]
+    fn test_pdf_is_well_formed() {
+        let pdf = certificate_pdf::render(&[
+            Line::Title("Certificate".to_string()),
+            Line::Text("Engine (beta) \\ scanner".to_string()),
+            Line::Small("x".repeat(500)),
+        ]);
+        let text = String::from_utf8(pdf).unwrap();
+
+        assert!(text.starts_with("%PDF-1.4\n"));
+        assert!(text.ends_with("%%EOF\n"));
+        assert!(text.contains(r"(Engine \(beta\) \\ scanner) Tj"));
+
+        // Every xref entry points at the start of its object
+        let xref = text.rfind("\nxref\n").unwrap() + 1;
+        let startxref = text.rsplit("startxref\n").next().unwrap();
+        let startxref: usize = startxref.lines().next().unwrap().parse().unwrap();
+        assert_eq!(startxref, xref);
+        for (i, entry) in text[xref..].lines().skip(3).take_while(|l| l.ends_with(" n ")).enumerate() {
+            let offset: usize = entry[..10].parse().unwrap();
+            assert!(text[offset..].starts_with(&format!("{} 0 obj", i + 1)));
+        }
+    }
+}
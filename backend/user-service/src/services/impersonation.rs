@@ -0,0 +1,310 @@
+use chrono::{Duration, Utc};
+use redis::AsyncCommands;
+use sqlx::PgPool;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::auth::{AuthService, Claims};
+use crate::config::Config;
+use crate::models::*;
+
+/// Admin impersonation: request -> user consent -> short-lived token.
+///
+/// An approved request can be started once. The session's Redis key lives
+/// exactly as long as its token, and ending the session deletes the key, so
+/// the token stops working immediately.
+pub struct ImpersonationService {
+    config: Config,
+    db_pool: PgPool,
+    redis_conn: redis::aio::ConnectionManager,
+    auth_service: AuthService,
+}
+
+fn session_key(session_id: Uuid) -> String {
+    format!("impersonation:session:{}", session_id)
+}
+
+fn db_error(e: sqlx::Error) -> UserError {
+    UserError::DatabaseError(e.to_string())
+}
+
+impl ImpersonationService {
+    pub fn new(config: Config, db_pool: PgPool, redis_conn: redis::aio::ConnectionManager) -> Self {
+        let auth_service = AuthService::new(config.jwt.clone());
+
+        Self {
+            config,
+            db_pool,
+            redis_conn,
+            auth_service,
+        }
+    }
+
+    /// Admin asks to act as a user; nothing is issued until the user consents
+    pub async fn request(
+        &self,
+        admin_id: Uuid,
+        req: RequestImpersonationRequest,
+    ) -> UserResult<ImpersonationSession> {
+        req.validate()
+            .map_err(|e| UserError::ValidationError(format!("{}", e)))?;
+
+        if req.user_id == admin_id {
+            return Err(UserError::ValidationError("Cannot impersonate yourself".to_string()));
+        }
+
+        let target: User = sqlx::query_as("SELECT * FROM users WHERE id = $1")
+            .bind(req.user_id)
+            .fetch_optional(&self.db_pool)
+            .await
+            .map_err(db_error)?
+            .ok_or(UserError::NotFound)?;
+
+        if target.is_admin {
+            return Err(UserError::Unauthorized("Admin accounts cannot be impersonated".to_string()));
+        }
+
+        self.expire_stale(admin_id, req.user_id).await?;
+
+        let consent_expires_at = Utc::now() + Duration::minutes(self.config.impersonation.consent_ttl_minutes as i64);
+        let session = sqlx::query_as::<_, ImpersonationSession>(
+            r#"
+            INSERT INTO impersonation_sessions (id, admin_id, user_id, reason, status, requested_at, consent_expires_at)
+            VALUES ($1, $2, $3, $4, 'pending', NOW(), $5)
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(admin_id)
+        .bind(req.user_id)
+        .bind(req.reason.trim())
+        .bind(consent_expires_at)
+        .fetch_one(&self.db_pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.is_unique_violation() => UserError::Conflict(
+                "An impersonation request for this user is already open".to_string(),
+            ),
+            e => db_error(e),
+        })?;
+
+        self.audit_event(&session, "requested").await?;
+        Ok(session)
+    }
+
+    /// Requests addressed to a user, newest first
+    pub async fn list_for_user(&self, user_id: Uuid) -> UserResult<Vec<ImpersonationSession>> {
+        sqlx::query_as(
+            "SELECT * FROM impersonation_sessions WHERE user_id = $1 ORDER BY requested_at DESC LIMIT 50",
+        )
+        .bind(user_id)
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(db_error)
+    }
+
+    /// The user approves or denies a pending request
+    pub async fn respond(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+        approve: bool,
+    ) -> UserResult<ImpersonationSession> {
+        let status = if approve { "approved" } else { "denied" };
+
+        let session = sqlx::query_as::<_, ImpersonationSession>(
+            r#"
+            UPDATE impersonation_sessions
+            SET status = $3, consented_at = NOW()
+            WHERE id = $1 AND user_id = $2 AND status = 'pending' AND consent_expires_at > NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(session_id)
+        .bind(user_id)
+        .bind(status)
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| UserError::Conflict("No pending impersonation request".to_string()))?;
+
+        self.audit_event(&session, status).await?;
+        Ok(session)
+    }
+
+    /// Start an approved session and issue its token. Each approval yields
+    /// exactly one token.
+    pub async fn start(&self, admin_id: Uuid, session_id: Uuid) -> UserResult<ImpersonationTokenResponse> {
+        let ttl_minutes = self.config.impersonation.token_ttl_minutes;
+        let expires_at = Utc::now() + Duration::minutes(ttl_minutes as i64);
+
+        let session = sqlx::query_as::<_, ImpersonationSession>(
+            r#"
+            UPDATE impersonation_sessions
+            SET status = 'active', started_at = NOW(), expires_at = $3
+            WHERE id = $1 AND admin_id = $2 AND status = 'approved' AND consent_expires_at > NOW()
+            RETURNING *
+            "#,
+        )
+        .bind(session_id)
+        .bind(admin_id)
+        .bind(expires_at)
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(db_error)?
+        .ok_or_else(|| UserError::Conflict("Impersonation request is not approved".to_string()))?;
+
+        let user: User = sqlx::query_as("SELECT * FROM users WHERE id = $1")
+            .bind(session.user_id)
+            .fetch_one(&self.db_pool)
+            .await
+            .map_err(db_error)?;
+
+        let access_token = self
+            .auth_service
+            .generate_impersonation_token(&user, admin_id, session.id, ttl_minutes)?;
+
+        let mut conn = self.redis_conn.clone();
+        conn.set_ex::<_, _, ()>(session_key(session.id), admin_id.to_string(), ttl_minutes * 60)
+            .await
+            .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+
+        self.audit_event(&session, "started").await?;
+
+        Ok(ImpersonationTokenResponse {
+            access_token,
+            expires_in: ttl_minutes * 60,
+            session,
+        })
+    }
+
+    /// End a session; either the admin or the impersonated user may do this.
+    /// Revokes the token immediately.
+    pub async fn end(&self, actor_id: Uuid, session_id: Uuid) -> UserResult<ImpersonationSession> {
+        let session = sqlx::query_as::<_, ImpersonationSession>(
+            r#"
+            UPDATE impersonation_sessions
+            SET status = 'ended', ended_at = NOW(), ended_by = $2
+            WHERE id = $1 AND (admin_id = $2 OR user_id = $2) AND status IN ('pending', 'approved', 'active')
+            RETURNING *
+            "#,
+        )
+        .bind(session_id)
+        .bind(actor_id)
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(db_error)?
+        .ok_or(UserError::NotFound)?;
+
+        let mut conn = self.redis_conn.clone();
+        conn.del::<_, ()>(session_key(session.id))
+            .await
+            .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+
+        self.audit_event(&session, "ended").await?;
+        Ok(session)
+    }
+
+    /// Whether the session behind an impersonation token is still live
+    pub async fn is_active(&self, session_id: Uuid) -> UserResult<bool> {
+        let mut conn = self.redis_conn.clone();
+        conn.exists(session_key(session_id))
+            .await
+            .map_err(|e| UserError::DatabaseError(e.to_string()))
+    }
+
+    /// Record a request made with an impersonation token. Called before the
+    /// request runs so that nothing happens without an audit entry.
+    pub async fn audit_request(
+        &self,
+        claims: &Claims,
+        method: &str,
+        path: &str,
+        blocked: bool,
+    ) -> UserResult<i64> {
+        let actor = claims.act.as_ref().ok_or(UserError::InvalidToken)?;
+        let session_id = Uuid::parse_str(&actor.session_id).map_err(|_| UserError::InvalidToken)?;
+        let admin_id = Uuid::parse_str(&actor.sub).map_err(|_| UserError::InvalidToken)?;
+        let user_id = Uuid::parse_str(&claims.sub).map_err(|_| UserError::InvalidToken)?;
+
+        sqlx::query_scalar(
+            r#"
+            INSERT INTO impersonation_audit_log (session_id, admin_id, user_id, event, method, path, status_code)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id
+            "#,
+        )
+        .bind(session_id)
+        .bind(admin_id)
+        .bind(user_id)
+        .bind(if blocked { "blocked" } else { "request" })
+        .bind(method)
+        .bind(path)
+        .bind(blocked.then_some(403i16))
+        .fetch_one(&self.db_pool)
+        .await
+        .map_err(db_error)
+    }
+
+    /// Attach the response status to a request's audit entry
+    pub async fn complete_request_audit(&self, entry_id: i64, status_code: u16) {
+        let result = sqlx::query("UPDATE impersonation_audit_log SET status_code = $2 WHERE id = $1")
+            .bind(entry_id)
+            .bind(status_code as i16)
+            .execute(&self.db_pool)
+            .await;
+
+        if let Err(e) = result {
+            tracing::error!("Failed to record status for impersonation audit entry {}: {}", entry_id, e);
+        }
+    }
+
+    pub async fn audit_log(&self, session_id: Uuid) -> UserResult<Vec<ImpersonationAuditEntry>> {
+        sqlx::query_as(
+            "SELECT * FROM impersonation_audit_log WHERE session_id = $1 ORDER BY created_at, id",
+        )
+        .bind(session_id)
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(db_error)
+    }
+
+    async fn audit_event(&self, session: &ImpersonationSession, event: &str) -> UserResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO impersonation_audit_log (session_id, admin_id, user_id, event)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(session.id)
+        .bind(session.admin_id)
+        .bind(session.user_id)
+        .bind(event)
+        .execute(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(())
+    }
+
+    /// Close out requests whose consent window or token lifetime has passed
+    async fn expire_stale(&self, admin_id: Uuid, user_id: Uuid) -> UserResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE impersonation_sessions
+            SET status = CASE WHEN status = 'active' THEN 'ended' ELSE 'expired' END,
+                ended_at = CASE WHEN status = 'active' THEN expires_at ELSE ended_at END
+            WHERE admin_id = $1 AND user_id = $2
+              AND ((status IN ('pending', 'approved') AND consent_expires_at <= NOW())
+                   OR (status = 'active' AND expires_at <= NOW()))
+            "#,
+        )
+        .bind(admin_id)
+        .bind(user_id)
+        .execute(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,912 @@
+//! Identity verification providers
+//!
+//! A `KycProvider` registers the user as an applicant, hands the client a
+//! token for the provider's SDK, which collects documents and selfie, and
+//! turns the provider's signed webhook deliveries into decisions.
+//! `ManualProvider` does none of this: users upload document URLs and an
+//! admin decides. Admins can decide any verification, whichever provider
+//! holds it, so manual review is also the fallback for whatever a provider
+//! leaves undecided (Sumsub holds, Onfido `consider` results).
+
+use async_trait::async_trait;
+use axum::http::HeaderMap;
+use chrono::{DateTime, Duration, Utc};
+use ring::digest::{digest, SHA256};
+use ring::hmac;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::config::{KycConfig, KycProviderKind};
+use crate::models::*;
+
+const SUMSUB_DIGEST_HEADER: &str = "x-payload-digest";
+const SUMSUB_DIGEST_ALG_HEADER: &str = "x-payload-digest-alg";
+const ONFIDO_SIGNATURE_HEADER: &str = "x-sha2-signature";
+
+/// Onfido SDK tokens last 90 minutes and cannot be asked to live longer
+const ONFIDO_SDK_TOKEN_MINUTES: i64 = 90;
+
+/// Token the client starts the provider's SDK with
+#[derive(Debug, Clone, Serialize)]
+pub struct SdkToken {
+    pub provider: String,
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Where a review of a verification stands
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KycDecision {
+    /// Documents are in and being checked, by the provider or an admin
+    UnderReview,
+    Approved,
+    Rejected(Option<String>),
+    /// The user has to send documents again
+    Resubmit(Option<String>),
+}
+
+impl KycDecision {
+    /// Status of the verification and of `users.kyc_status`
+    pub fn status(&self) -> &'static str {
+        match self {
+            KycDecision::UnderReview => "under_review",
+            KycDecision::Approved => "approved",
+            KycDecision::Rejected(_) => "rejected",
+            KycDecision::Resubmit(_) => "pending",
+        }
+    }
+
+    fn reason(&self) -> Option<&str> {
+        match self {
+            KycDecision::Rejected(reason) | KycDecision::Resubmit(reason) => reason.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Whether a provider's decision may replace a verification's status.
+    /// Deliveries can arrive out of order, so a late "under review" never
+    /// reopens a decided verification.
+    pub fn supersedes(&self, status: &str) -> bool {
+        match self {
+            KycDecision::UnderReview => status == "pending" || status == "under_review",
+            _ => true,
+        }
+    }
+}
+
+/// A webhook delivery about one applicant
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebhookEvent {
+    pub applicant_id: String,
+    pub kind: String,
+    pub decision: KycDecision,
+}
+
+#[async_trait]
+pub trait KycProvider: Send + Sync {
+    fn kind(&self) -> KycProviderKind;
+
+    /// Register the user with the provider, returning the applicant's ID.
+    /// `None` when the provider has no applicants.
+    async fn create_applicant(&self, user: &User, req: &SubmitKycRequest) -> UserResult<Option<String>>;
+
+    /// Token for the provider's SDK, `None` when the provider has no SDK
+    async fn sdk_token(&self, user_id: Uuid, applicant_id: &str) -> UserResult<Option<SdkToken>>;
+
+    /// Called when the user has finished the SDK
+    async fn documents_submitted(&self, applicant_id: &str) -> UserResult<()>;
+
+    /// Check the signature of a webhook delivery
+    fn verify_webhook(&self, headers: &HeaderMap, body: &[u8]) -> UserResult<()>;
+
+    /// The decision a verified delivery carries, `None` for events that do
+    /// not change a verification
+    async fn parse_webhook(&self, body: &[u8]) -> UserResult<Option<WebhookEvent>>;
+}
+
+fn db_error(e: sqlx::Error) -> UserError {
+    UserError::DatabaseError(e.to_string())
+}
+
+fn upstream(provider: KycProviderKind, e: impl std::fmt::Display) -> UserError {
+    UserError::Upstream(format!("{}: {}", provider.as_str(), e))
+}
+
+fn malformed(e: impl std::fmt::Display) -> UserError {
+    UserError::ValidationError(format!("Malformed webhook payload: {}", e))
+}
+
+fn invalid_signature() -> UserError {
+    UserError::AuthenticationError("Invalid webhook signature".to_string())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hmac_hex(algorithm: hmac::Algorithm, secret: &str, parts: &[&[u8]]) -> String {
+    let key = hmac::Key::new(algorithm, secret.as_bytes());
+    let mut context = hmac::Context::with_key(&key);
+    for part in parts {
+        context.update(part);
+    }
+    hex(context.sign().as_ref())
+}
+
+/// Constant-time comparison of hex digests
+fn digest_matches(expected: &str, provided: &str) -> bool {
+    let provided = provided.trim().to_ascii_lowercase();
+    expected.len() == provided.len()
+        && expected
+            .bytes()
+            .zip(provided.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+/// First and last name, split at the last space
+fn split_name(full_name: &str) -> (&str, &str) {
+    full_name.trim().rsplit_once(' ').unwrap_or((full_name.trim(), ""))
+}
+
+fn http_client() -> UserResult<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .map_err(|e| UserError::Upstream(e.to_string()))
+}
+
+async fn json_response<T: DeserializeOwned>(
+    provider: KycProviderKind,
+    response: reqwest::Response,
+) -> UserResult<T> {
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(upstream(provider, format!("{}: {}", status, body)));
+    }
+    response.json().await.map_err(|e| upstream(provider, e))
+}
+
+#[derive(Debug, Deserialize)]
+struct ApplicantResponse {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    token: String,
+}
+
+// ============= Manual =============
+
+/// Users upload document URLs and an admin approves or rejects them
+pub struct ManualProvider;
+
+#[async_trait]
+impl KycProvider for ManualProvider {
+    fn kind(&self) -> KycProviderKind {
+        KycProviderKind::Manual
+    }
+
+    async fn create_applicant(&self, _user: &User, _req: &SubmitKycRequest) -> UserResult<Option<String>> {
+        Ok(None)
+    }
+
+    async fn sdk_token(&self, _user_id: Uuid, _applicant_id: &str) -> UserResult<Option<SdkToken>> {
+        Ok(None)
+    }
+
+    async fn documents_submitted(&self, _applicant_id: &str) -> UserResult<()> {
+        Ok(())
+    }
+
+    fn verify_webhook(&self, _headers: &HeaderMap, _body: &[u8]) -> UserResult<()> {
+        Err(UserError::NotFound)
+    }
+
+    async fn parse_webhook(&self, _body: &[u8]) -> UserResult<Option<WebhookEvent>> {
+        Err(UserError::NotFound)
+    }
+}
+
+// ============= Sumsub =============
+
+/// Sumsub applicants, checked at the configured level. API requests are
+/// signed with HMAC-SHA256 over timestamp, method, path and body.
+pub struct SumsubProvider {
+    config: KycConfig,
+    http: reqwest::Client,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SumsubWebhook {
+    applicant_id: String,
+    #[serde(rename = "type")]
+    kind: String,
+    review_result: Option<SumsubReviewResult>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SumsubReviewResult {
+    review_answer: String,
+    review_reject_type: Option<String>,
+    moderation_comment: Option<String>,
+}
+
+/// GREEN approves; RED rejects, or asks for new documents when Sumsub lets
+/// the applicant retry. Holds are left for an admin.
+fn sumsub_decision(webhook: &SumsubWebhook) -> Option<KycDecision> {
+    match webhook.kind.as_str() {
+        "applicantPending" | "applicantOnHold" => Some(KycDecision::UnderReview),
+        "applicantReviewed" => {
+            let result = webhook.review_result.as_ref()?;
+            let reason = result.moderation_comment.clone();
+            match (result.review_answer.as_str(), result.review_reject_type.as_deref()) {
+                ("GREEN", _) => Some(KycDecision::Approved),
+                ("RED", Some("RETRY")) => Some(KycDecision::Resubmit(reason)),
+                ("RED", _) => Some(KycDecision::Rejected(reason)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+impl SumsubProvider {
+    pub fn new(config: KycConfig) -> UserResult<Self> {
+        Ok(Self {
+            config,
+            http: http_client()?,
+        })
+    }
+
+    async fn send(&self, method: reqwest::Method, path_and_query: &str, body: Option<Value>) -> UserResult<reqwest::Response> {
+        let body = body.map(|b| b.to_string()).unwrap_or_default();
+        let timestamp = Utc::now().timestamp().to_string();
+        let signature = hmac_hex(
+            hmac::HMAC_SHA256,
+            &self.config.api_secret,
+            &[timestamp.as_bytes(), method.as_str().as_bytes(), path_and_query.as_bytes(), body.as_bytes()],
+        );
+
+        let mut request = self
+            .http
+            .request(method, format!("{}{}", self.config.api_url, path_and_query))
+            .header("X-App-Token", &self.config.api_token)
+            .header("X-App-Access-Ts", timestamp)
+            .header("X-App-Access-Sig", signature)
+            .header("Accept", "application/json");
+        if !body.is_empty() {
+            request = request.header("Content-Type", "application/json").body(body);
+        }
+
+        request.send().await.map_err(|e| upstream(KycProviderKind::Sumsub, e))
+    }
+}
+
+#[async_trait]
+impl KycProvider for SumsubProvider {
+    fn kind(&self) -> KycProviderKind {
+        KycProviderKind::Sumsub
+    }
+
+    async fn create_applicant(&self, user: &User, req: &SubmitKycRequest) -> UserResult<Option<String>> {
+        let mut url = reqwest::Url::parse(&format!("{}/resources/applicants", self.config.api_url))
+            .map_err(|e| upstream(self.kind(), e))?;
+        url.query_pairs_mut().append_pair("levelName", &self.config.level_name);
+        let path_and_query = format!("{}?{}", url.path(), url.query().unwrap_or_default());
+
+        let (first_name, last_name) = split_name(&req.full_name);
+        let body = json!({
+            "externalUserId": user.id.to_string(),
+            "email": user.email,
+            "fixedInfo": {
+                "firstName": first_name,
+                "lastName": last_name,
+                "dob": req.date_of_birth,
+            },
+        });
+
+        let response = self.send(reqwest::Method::POST, &path_and_query, Some(body)).await?;
+        // A user who submits again is still the same applicant
+        if response.status() == reqwest::StatusCode::CONFLICT {
+            let path = format!("/resources/applicants/-;externalUserId={}/one", user.id);
+            let response = self.send(reqwest::Method::GET, &path, None).await?;
+            let applicant: ApplicantResponse = json_response(self.kind(), response).await?;
+            return Ok(Some(applicant.id));
+        }
+
+        let applicant: ApplicantResponse = json_response(self.kind(), response).await?;
+        Ok(Some(applicant.id))
+    }
+
+    async fn sdk_token(&self, user_id: Uuid, _applicant_id: &str) -> UserResult<Option<SdkToken>> {
+        let body = json!({
+            "userId": user_id.to_string(),
+            "levelName": self.config.level_name,
+            "ttlInSecs": self.config.sdk_token_ttl_seconds,
+        });
+        let response = self.send(reqwest::Method::POST, "/resources/accessTokens/sdk", Some(body)).await?;
+        let token: TokenResponse = json_response(self.kind(), response).await?;
+
+        Ok(Some(SdkToken {
+            provider: self.kind().as_str().to_string(),
+            token: token.token,
+            expires_at: Utc::now() + Duration::seconds(self.config.sdk_token_ttl_seconds as i64),
+        }))
+    }
+
+    /// Sumsub starts its review by itself
+    async fn documents_submitted(&self, _applicant_id: &str) -> UserResult<()> {
+        Ok(())
+    }
+
+    fn verify_webhook(&self, headers: &HeaderMap, body: &[u8]) -> UserResult<()> {
+        let provided = header(headers, SUMSUB_DIGEST_HEADER).ok_or_else(invalid_signature)?;
+        let algorithm = match header(headers, SUMSUB_DIGEST_ALG_HEADER).unwrap_or("HMAC_SHA256_HEX") {
+            "HMAC_SHA256_HEX" => hmac::HMAC_SHA256,
+            "HMAC_SHA512_HEX" => hmac::HMAC_SHA512,
+            _ => return Err(invalid_signature()),
+        };
+        if !digest_matches(&hmac_hex(algorithm, &self.config.webhook_secret, &[body]), provided) {
+            return Err(invalid_signature());
+        }
+        Ok(())
+    }
+
+    async fn parse_webhook(&self, body: &[u8]) -> UserResult<Option<WebhookEvent>> {
+        let webhook: SumsubWebhook = serde_json::from_slice(body).map_err(malformed)?;
+        Ok(sumsub_decision(&webhook).map(|decision| WebhookEvent {
+            applicant_id: webhook.applicant_id,
+            kind: webhook.kind,
+            decision,
+        }))
+    }
+}
+
+// ============= Onfido =============
+
+/// Onfido applicants. Once the user has finished the SDK a check with the
+/// configured reports is created; its completion decides the verification.
+pub struct OnfidoProvider {
+    config: KycConfig,
+    http: reqwest::Client,
+}
+
+#[derive(Debug, Deserialize)]
+struct OnfidoWebhook {
+    payload: OnfidoPayload,
+}
+
+#[derive(Debug, Deserialize)]
+struct OnfidoPayload {
+    resource_type: String,
+    action: String,
+    object: OnfidoObject,
+}
+
+#[derive(Debug, Deserialize)]
+struct OnfidoObject {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OnfidoCheck {
+    applicant_id: String,
+    result: Option<String>,
+}
+
+/// `clear` approves; `consider` means a report found something, which an
+/// admin looks at rather than rejecting outright
+fn onfido_decision(result: Option<&str>) -> Option<KycDecision> {
+    match result? {
+        "clear" => Some(KycDecision::Approved),
+        "consider" => Some(KycDecision::UnderReview),
+        _ => None,
+    }
+}
+
+impl OnfidoProvider {
+    pub fn new(config: KycConfig) -> UserResult<Self> {
+        Ok(Self {
+            config,
+            http: http_client()?,
+        })
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        self.http
+            .request(method, format!("{}{}", self.config.api_url, path))
+            .header("Authorization", format!("Token token={}", self.config.api_token))
+    }
+}
+
+#[async_trait]
+impl KycProvider for OnfidoProvider {
+    fn kind(&self) -> KycProviderKind {
+        KycProviderKind::Onfido
+    }
+
+    async fn create_applicant(&self, user: &User, req: &SubmitKycRequest) -> UserResult<Option<String>> {
+        let (first_name, last_name) = split_name(&req.full_name);
+        let response = self
+            .request(reqwest::Method::POST, "/applicants")
+            .json(&json!({
+                "first_name": first_name,
+                "last_name": last_name,
+                "dob": req.date_of_birth,
+                "email": user.email,
+            }))
+            .send()
+            .await
+            .map_err(|e| upstream(self.kind(), e))?;
+
+        let applicant: ApplicantResponse = json_response(self.kind(), response).await?;
+        Ok(Some(applicant.id))
+    }
+
+    async fn sdk_token(&self, _user_id: Uuid, applicant_id: &str) -> UserResult<Option<SdkToken>> {
+        let response = self
+            .request(reqwest::Method::POST, "/sdk_token")
+            .json(&json!({ "applicant_id": applicant_id }))
+            .send()
+            .await
+            .map_err(|e| upstream(self.kind(), e))?;
+        let token: TokenResponse = json_response(self.kind(), response).await?;
+
+        Ok(Some(SdkToken {
+            provider: self.kind().as_str().to_string(),
+            token: token.token,
+            expires_at: Utc::now() + Duration::minutes(ONFIDO_SDK_TOKEN_MINUTES),
+        }))
+    }
+
+    async fn documents_submitted(&self, applicant_id: &str) -> UserResult<()> {
+        let response = self
+            .request(reqwest::Method::POST, "/checks")
+            .json(&json!({
+                "applicant_id": applicant_id,
+                "report_names": self.config.reports,
+            }))
+            .send()
+            .await
+            .map_err(|e| upstream(self.kind(), e))?;
+
+        let _: Value = json_response(self.kind(), response).await?;
+        Ok(())
+    }
+
+    fn verify_webhook(&self, headers: &HeaderMap, body: &[u8]) -> UserResult<()> {
+        let provided = header(headers, ONFIDO_SIGNATURE_HEADER).ok_or_else(invalid_signature)?;
+        if !digest_matches(&hmac_hex(hmac::HMAC_SHA256, &self.config.webhook_secret, &[body]), provided) {
+            return Err(invalid_signature());
+        }
+        Ok(())
+    }
+
+    /// Deliveries only name the check, so a completed one is fetched for
+    /// its applicant and result
+    async fn parse_webhook(&self, body: &[u8]) -> UserResult<Option<WebhookEvent>> {
+        let webhook: OnfidoWebhook = serde_json::from_slice(body).map_err(malformed)?;
+        let payload = webhook.payload;
+        if payload.resource_type != "check" || payload.action != "check.completed" {
+            return Ok(None);
+        }
+
+        let response = self
+            .request(reqwest::Method::GET, &format!("/checks/{}", payload.object.id))
+            .send()
+            .await
+            .map_err(|e| upstream(self.kind(), e))?;
+        let check: OnfidoCheck = json_response(self.kind(), response).await?;
+
+        Ok(onfido_decision(check.result.as_deref()).map(|decision| WebhookEvent {
+            applicant_id: check.applicant_id,
+            kind: payload.action,
+            decision,
+        }))
+    }
+}
+
+// ============= Service =============
+
+/// What a submission returns to the user
+#[derive(Debug, Clone)]
+pub struct KycSubmission {
+    pub kyc_id: Uuid,
+    pub provider: KycProviderKind,
+    pub sdk_token: Option<SdkToken>,
+}
+
+/// KYC submissions and their review, through the configured provider
+pub struct KycService {
+    db_pool: PgPool,
+    provider: Box<dyn KycProvider>,
+}
+
+impl KycService {
+    pub fn new(config: KycConfig, db_pool: PgPool) -> UserResult<Self> {
+        let provider: Box<dyn KycProvider> = match config.provider {
+            KycProviderKind::Manual => Box::new(ManualProvider),
+            KycProviderKind::Sumsub => Box::new(SumsubProvider::new(config)?),
+            KycProviderKind::Onfido => Box::new(OnfidoProvider::new(config)?),
+        };
+
+        Ok(Self { db_pool, provider })
+    }
+
+    pub fn provider(&self) -> KycProviderKind {
+        self.provider.kind()
+    }
+
+    /// The user's latest submission
+    pub async fn latest(&self, user_id: Uuid) -> UserResult<Option<KycVerification>> {
+        sqlx::query_as::<_, KycVerification>(
+            "SELECT * FROM kyc_verifications WHERE user_id = $1 ORDER BY submitted_at DESC LIMIT 1",
+        )
+        .bind(user_id)
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(db_error)
+    }
+
+    /// Start a verification, registering the user with the provider
+    pub async fn submit(&self, user_id: Uuid, req: SubmitKycRequest) -> UserResult<KycSubmission> {
+        let date_of_birth = chrono::NaiveDate::parse_from_str(&req.date_of_birth, "%Y-%m-%d")
+            .map_err(|_| UserError::ValidationError("Invalid date format, use YYYY-MM-DD".to_string()))?;
+
+        if let Some(kyc) = self.latest(user_id).await? {
+            match kyc.status.as_str() {
+                "approved" => return Err(UserError::Conflict("KYC is already approved".to_string())),
+                "under_review" => return Err(UserError::Conflict("KYC is already under review".to_string())),
+                _ => {}
+            }
+        }
+
+        let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_optional(&self.db_pool)
+            .await
+            .map_err(db_error)?
+            .ok_or(UserError::NotFound)?;
+
+        let applicant_id = self.provider.create_applicant(&user, &req).await?;
+
+        // Document URLs are added through the upload endpoint, or stay empty
+        // when the provider's SDK collects the documents
+        let kyc_id = Uuid::new_v4();
+        let mut tx = self.db_pool.begin().await.map_err(db_error)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO kyc_verifications
+            (id, user_id, full_name, date_of_birth, country, document_type, document_number,
+             document_front_url, selfie_url, status, submitted_at, provider, provider_applicant_id)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, '', '', 'pending', NOW(), $8, $9)
+            "#,
+        )
+        .bind(kyc_id)
+        .bind(user_id)
+        .bind(&req.full_name)
+        .bind(date_of_birth)
+        .bind(&req.country)
+        .bind(&req.document_type)
+        .bind(&req.document_number)
+        .bind(self.provider().as_str())
+        .bind(&applicant_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+
+        sqlx::query("UPDATE users SET kyc_status = 'pending' WHERE id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+
+        tx.commit().await.map_err(db_error)?;
+
+        let sdk_token = match &applicant_id {
+            Some(applicant_id) => self.provider.sdk_token(user_id, applicant_id).await?,
+            None => None,
+        };
+
+        Ok(KycSubmission {
+            kyc_id,
+            provider: self.provider(),
+            sdk_token,
+        })
+    }
+
+    /// The user's pending verification, which must be held by the configured
+    /// provider
+    async fn pending_with_provider(&self, user_id: Uuid) -> UserResult<(KycVerification, String)> {
+        let kyc = self.latest(user_id).await?.ok_or(UserError::NotFound)?;
+        if kyc.status != "pending" {
+            return Err(UserError::Conflict("KYC is not awaiting documents".to_string()));
+        }
+        match kyc.provider_applicant_id.clone() {
+            Some(applicant_id) if kyc.provider == self.provider().as_str() => Ok((kyc, applicant_id)),
+            _ => Err(UserError::Conflict(
+                "Documents for this verification are uploaded, not collected by a provider".to_string(),
+            )),
+        }
+    }
+
+    /// A fresh SDK token for the user's pending verification
+    pub async fn sdk_token(&self, user_id: Uuid) -> UserResult<SdkToken> {
+        let (_, applicant_id) = self.pending_with_provider(user_id).await?;
+        self.provider
+            .sdk_token(user_id, &applicant_id)
+            .await?
+            .ok_or_else(|| UserError::Conflict("The KYC provider has no SDK".to_string()))
+    }
+
+    /// The user has finished the provider's SDK; its decision follows by
+    /// webhook
+    pub async fn complete(&self, user_id: Uuid) -> UserResult<()> {
+        let (kyc, applicant_id) = self.pending_with_provider(user_id).await?;
+        self.provider.documents_submitted(&applicant_id).await?;
+
+        let mut tx = self.db_pool.begin().await.map_err(db_error)?;
+        self.apply(&mut tx, &kyc, &KycDecision::UnderReview, None).await?;
+        tx.commit().await.map_err(db_error)
+    }
+
+    /// Document URLs of a manually reviewed verification
+    pub async fn upload_documents(
+        &self,
+        user_id: Uuid,
+        document_front_url: &str,
+        document_back_url: Option<&str>,
+        selfie_url: &str,
+    ) -> UserResult<()> {
+        let kyc = self.latest(user_id).await?.ok_or(UserError::NotFound)?;
+        if kyc.provider != KycProviderKind::Manual.as_str() {
+            return Err(UserError::Conflict(format!(
+                "Documents for this verification are collected by {}",
+                kyc.provider
+            )));
+        }
+
+        let mut tx = self.db_pool.begin().await.map_err(db_error)?;
+
+        sqlx::query(
+            r#"
+            UPDATE kyc_verifications
+            SET document_front_url = $1,
+                document_back_url = $2,
+                selfie_url = $3
+            WHERE id = $4
+            "#,
+        )
+        .bind(document_front_url)
+        .bind(document_back_url)
+        .bind(selfie_url)
+        .bind(kyc.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+
+        self.apply(&mut tx, &kyc, &KycDecision::UnderReview, None).await?;
+        tx.commit().await.map_err(db_error)
+    }
+
+    /// An admin's decision on the user's latest verification, whichever
+    /// provider holds it
+    pub async fn review(&self, user_id: Uuid, admin_id: Uuid, decision: KycDecision) -> UserResult<()> {
+        let kyc = self.latest(user_id).await?.ok_or(UserError::NotFound)?;
+
+        let mut tx = self.db_pool.begin().await.map_err(db_error)?;
+        self.apply(&mut tx, &kyc, &decision, Some(admin_id)).await?;
+        tx.commit().await.map_err(db_error)
+    }
+
+    /// Apply a provider's webhook delivery. Returns whether it changed a
+    /// verification; repeated deliveries, events without a decision and
+    /// verifications an admin has decided are acknowledged and ignored.
+    pub async fn handle_webhook(&self, provider: &str, headers: &HeaderMap, body: &[u8]) -> UserResult<bool> {
+        if provider != self.provider().as_str() {
+            return Err(UserError::NotFound);
+        }
+        self.provider.verify_webhook(headers, body)?;
+
+        let Some(event) = self.provider.parse_webhook(body).await? else {
+            return Ok(false);
+        };
+
+        let mut tx = self.db_pool.begin().await.map_err(db_error)?;
+
+        let recorded = sqlx::query(
+            r#"
+            INSERT INTO kyc_webhook_events (provider, payload_digest, applicant_id, event_type)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (provider, payload_digest) DO NOTHING
+            "#,
+        )
+        .bind(provider)
+        .bind(hex(digest(&SHA256, body).as_ref()))
+        .bind(&event.applicant_id)
+        .bind(&event.kind)
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?
+        .rows_affected();
+        if recorded == 0 {
+            return Ok(false);
+        }
+
+        let kyc = sqlx::query_as::<_, KycVerification>(
+            r#"
+            SELECT * FROM kyc_verifications
+            WHERE provider = $1 AND provider_applicant_id = $2
+            ORDER BY submitted_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(provider)
+        .bind(&event.applicant_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(db_error)?;
+
+        let Some(kyc) = kyc else {
+            tracing::warn!("KYC webhook for unknown {} applicant {}", provider, event.applicant_id);
+            tx.commit().await.map_err(db_error)?;
+            return Ok(false);
+        };
+
+        let applies = kyc.verified_by.is_none() && event.decision.supersedes(&kyc.status);
+        if applies {
+            self.apply(&mut tx, &kyc, &event.decision, None).await?;
+        }
+        tx.commit().await.map_err(db_error)?;
+
+        Ok(applies)
+    }
+
+    /// Set the status of a verification and of its user. Without a reviewer
+    /// the decision is the provider's.
+    async fn apply(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        kyc: &KycVerification,
+        decision: &KycDecision,
+        reviewer: Option<Uuid>,
+    ) -> UserResult<()> {
+        let decided = matches!(decision, KycDecision::Approved | KycDecision::Rejected(_));
+
+        sqlx::query(
+            r#"
+            UPDATE kyc_verifications
+            SET status = $2,
+                rejection_reason = $3,
+                verified_by = $4,
+                verified_at = CASE WHEN $5 THEN NOW() END,
+                provider_updated_at = CASE WHEN $4 IS NULL AND provider <> 'manual'
+                    THEN NOW() ELSE provider_updated_at END
+            WHERE id = $1
+            "#,
+        )
+        .bind(kyc.id)
+        .bind(decision.status())
+        .bind(decision.reason())
+        .bind(reviewer)
+        .bind(decided)
+        .execute(&mut **tx)
+        .await
+        .map_err(db_error)?;
+
+        sqlx::query("UPDATE users SET kyc_status = $1 WHERE id = $2")
+            .bind(decision.status())
+            .bind(kyc.user_id)
+            .execute(&mut **tx)
+            .await
+            .map_err(db_error)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(provider: KycProviderKind) -> KycConfig {
+        KycConfig {
+            provider,
+            api_url: "https://kyc.example".to_string(),
+            api_token: "token".to_string(),
+            api_secret: "api-secret".to_string(),
+            webhook_secret: "webhook-secret".to_string(),
+            level_name: "basic-kyc-level".to_string(),
+            reports: vec!["document".to_string()],
+            sdk_token_ttl_seconds: 600,
+        }
+    }
+
+    fn sumsub_webhook(body: &str) -> SumsubWebhook {
+        serde_json::from_str(body).unwrap()
+    }
+
+    #[test]
+    fn test_sumsub_decisions() {
+        let green = sumsub_webhook(
+            r#"{"applicantId":"a1","type":"applicantReviewed","reviewResult":{"reviewAnswer":"GREEN"}}"#,
+        );
+        assert_eq!(sumsub_decision(&green), Some(KycDecision::Approved));
+
+        let retry = sumsub_webhook(
+            r#"{"applicantId":"a1","type":"applicantReviewed","reviewResult":{"reviewAnswer":"RED","reviewRejectType":"RETRY","moderationComment":"Blurry photo"}}"#,
+        );
+        assert_eq!(
+            sumsub_decision(&retry),
+            Some(KycDecision::Resubmit(Some("Blurry photo".to_string())))
+        );
+        assert_eq!(sumsub_decision(&retry).unwrap().status(), "pending");
+
+        let red = sumsub_webhook(
+            r#"{"applicantId":"a1","type":"applicantReviewed","reviewResult":{"reviewAnswer":"RED","reviewRejectType":"FINAL"}}"#,
+        );
+        assert_eq!(sumsub_decision(&red), Some(KycDecision::Rejected(None)));
+
+        let hold = sumsub_webhook(r#"{"applicantId":"a1","type":"applicantOnHold"}"#);
+        assert_eq!(sumsub_decision(&hold), Some(KycDecision::UnderReview));
+
+        let created = sumsub_webhook(r#"{"applicantId":"a1","type":"applicantCreated"}"#);
+        assert_eq!(sumsub_decision(&created), None);
+    }
+
+    #[test]
+    fn test_onfido_decisions() {
+        assert_eq!(onfido_decision(Some("clear")), Some(KycDecision::Approved));
+        assert_eq!(onfido_decision(Some("consider")), Some(KycDecision::UnderReview));
+        assert_eq!(onfido_decision(None), None);
+    }
+
+    #[test]
+    fn test_late_deliveries_do_not_reopen_decisions() {
+        assert!(KycDecision::UnderReview.supersedes("pending"));
+        assert!(!KycDecision::UnderReview.supersedes("approved"));
+        assert!(!KycDecision::UnderReview.supersedes("rejected"));
+        assert!(KycDecision::Rejected(None).supersedes("approved"));
+        assert!(KycDecision::Approved.supersedes("under_review"));
+    }
+
+    #[test]
+    fn test_webhook_signatures() {
+        let body = br#"{"applicantId":"a1","type":"applicantPending"}"#;
+        let signature = hmac_hex(hmac::HMAC_SHA256, "webhook-secret", &[body]);
+
+        let sumsub = SumsubProvider::new(config(KycProviderKind::Sumsub)).unwrap();
+        let mut headers = HeaderMap::new();
+        assert!(sumsub.verify_webhook(&headers, body).is_err());
+        headers.insert(SUMSUB_DIGEST_HEADER, signature.to_uppercase().parse().unwrap());
+        assert!(sumsub.verify_webhook(&headers, body).is_ok());
+        assert!(sumsub.verify_webhook(&headers, b"{}").is_err());
+        headers.insert(SUMSUB_DIGEST_ALG_HEADER, "HMAC_SHA512_HEX".parse().unwrap());
+        assert!(sumsub.verify_webhook(&headers, body).is_err());
+
+        let onfido = OnfidoProvider::new(config(KycProviderKind::Onfido)).unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert(ONFIDO_SIGNATURE_HEADER, signature.parse().unwrap());
+        assert!(onfido.verify_webhook(&headers, body).is_ok());
+
+        assert!(ManualProvider.verify_webhook(&headers, body).is_err());
+    }
+
+    #[test]
+    fn test_split_name() {
+        assert_eq!(split_name("Ada King Lovelace"), ("Ada King", "Lovelace"));
+        assert_eq!(split_name(" Plato "), ("Plato", ""));
+    }
+}
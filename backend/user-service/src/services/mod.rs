@@ -1,3 +1,9 @@
+pub mod certificate_pdf;
+pub mod certificates;
+pub mod impersonation;
+pub mod kyc;
+pub mod oauth;
+pub mod organizations;
 pub mod user_service;
 
 pub use user_service::UserService;
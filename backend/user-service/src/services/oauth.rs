@@ -0,0 +1,356 @@
+//! OAuth2 / OpenID Connect providers
+//!
+//! Sign-in uses the authorization-code flow with PKCE (S256). GitHub is
+//! spoken to through its REST API; every other provider is OpenID Connect,
+//! its endpoints found through the issuer's discovery document and the
+//! user read from the userinfo endpoint. Tokens are fetched from the token
+//! endpoint over TLS, so the ID token itself is not needed.
+//!
+//! Accounts are matched and provisioned by `UserService`; this module only
+//! talks to providers.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use ring::digest::{digest, SHA256};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::config::{OAuthConfig, OAuthProviderConfig, OAuthProviderKind};
+use crate::models::{UserError, UserResult};
+
+const GITHUB_AUTHORIZE_URL: &str = "https://github.com/login/oauth/authorize";
+const GITHUB_TOKEN_URL: &str = "https://github.com/login/oauth/access_token";
+const GITHUB_API_URL: &str = "https://api.github.com";
+
+/// GitHub's API refuses requests without one
+const USER_AGENT: &str = "nexus-security-user-service";
+
+/// Who the provider says signed in
+#[derive(Debug, Clone)]
+pub struct ExternalIdentity {
+    /// Stable ID at the provider
+    pub subject: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    /// Handle at the provider, used to pick a username
+    pub username: Option<String>,
+}
+
+/// An authorization request waiting for its callback, stored under its state
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PendingAuthorization {
+    pub provider: String,
+    pub code_verifier: String,
+    /// Set when a signed-in user is linking the provider to their account
+    pub link_user_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct OidcEndpoints {
+    authorization_endpoint: String,
+    token_endpoint: String,
+    userinfo_endpoint: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubUser {
+    id: u64,
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubEmail {
+    email: String,
+    primary: bool,
+    verified: bool,
+}
+
+fn upstream(provider: &str, e: impl std::fmt::Display) -> UserError {
+    UserError::Upstream(format!("{}: {}", provider, e))
+}
+
+/// 32 random bytes, base64url encoded: 43 characters, usable as a PKCE
+/// verifier and as a state
+pub fn random_token() -> UserResult<String> {
+    let mut bytes = [0u8; 32];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| UserError::DatabaseError("No randomness available".to_string()))?;
+    Ok(URL_SAFE_NO_PAD.encode(bytes))
+}
+
+/// S256 code challenge of a PKCE verifier (RFC 7636)
+pub fn pkce_challenge(code_verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(digest(&SHA256, code_verifier.as_bytes()))
+}
+
+pub struct OAuthClient {
+    config: OAuthConfig,
+    http: reqwest::Client,
+    /// Discovery documents, by provider name
+    discovered: Mutex<HashMap<String, OidcEndpoints>>,
+}
+
+impl OAuthClient {
+    pub fn new(config: OAuthConfig) -> UserResult<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(10))
+            .user_agent(USER_AGENT)
+            .build()
+            .map_err(|e| UserError::Upstream(e.to_string()))?;
+
+        Ok(Self {
+            config,
+            http,
+            discovered: Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub fn providers(&self) -> &[OAuthProviderConfig] {
+        &self.config.providers
+    }
+
+    pub fn provider(&self, name: &str) -> UserResult<&OAuthProviderConfig> {
+        self.config
+            .providers
+            .iter()
+            .find(|provider| provider.name.eq_ignore_ascii_case(name))
+            .ok_or(UserError::NotFound)
+    }
+
+    pub fn state_ttl_seconds(&self) -> u64 {
+        self.config.state_ttl_seconds
+    }
+
+    fn redirect_uri(&self, provider: &OAuthProviderConfig) -> String {
+        format!("{}/{}/callback", self.config.redirect_base_url.trim_end_matches('/'), provider.name)
+    }
+
+    async fn endpoints(&self, provider: &OAuthProviderConfig) -> UserResult<OidcEndpoints> {
+        let issuer = match provider.kind {
+            OAuthProviderKind::Github => {
+                return Ok(OidcEndpoints {
+                    authorization_endpoint: GITHUB_AUTHORIZE_URL.to_string(),
+                    token_endpoint: GITHUB_TOKEN_URL.to_string(),
+                    userinfo_endpoint: format!("{}/user", GITHUB_API_URL),
+                })
+            }
+            OAuthProviderKind::Oidc => provider.issuer.as_deref().unwrap_or_default(),
+        };
+
+        let mut discovered = self.discovered.lock().await;
+        if let Some(endpoints) = discovered.get(&provider.name) {
+            return Ok(endpoints.clone());
+        }
+        let url = format!("{}/.well-known/openid-configuration", issuer.trim_end_matches('/'));
+        let endpoints: OidcEndpoints = self
+            .http
+            .get(&url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| upstream(&provider.name, e))?
+            .json()
+            .await
+            .map_err(|e| upstream(&provider.name, e))?;
+        discovered.insert(provider.name.clone(), endpoints.clone());
+        Ok(endpoints)
+    }
+
+    /// Where to send the user to sign in at the provider
+    pub async fn authorization_url(
+        &self,
+        provider: &OAuthProviderConfig,
+        state: &str,
+        code_verifier: &str,
+    ) -> UserResult<String> {
+        let endpoints = self.endpoints(provider).await?;
+        let url = reqwest::Url::parse_with_params(
+            &endpoints.authorization_endpoint,
+            &[
+                ("response_type", "code"),
+                ("client_id", provider.client_id.as_str()),
+                ("redirect_uri", self.redirect_uri(provider).as_str()),
+                ("scope", provider.scopes.as_str()),
+                ("state", state),
+                ("code_challenge", pkce_challenge(code_verifier).as_str()),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .map_err(|e| upstream(&provider.name, e))?;
+        Ok(url.to_string())
+    }
+
+    /// Redeem an authorization code and read who it belongs to
+    pub async fn identity(
+        &self,
+        provider: &OAuthProviderConfig,
+        code: &str,
+        code_verifier: &str,
+    ) -> UserResult<ExternalIdentity> {
+        let endpoints = self.endpoints(provider).await?;
+        let redirect_uri = self.redirect_uri(provider);
+        let token: TokenResponse = self
+            .http
+            .post(&endpoints.token_endpoint)
+            .header("Accept", "application/json")
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", redirect_uri.as_str()),
+                ("client_id", provider.client_id.as_str()),
+                ("client_secret", provider.client_secret.as_str()),
+                ("code_verifier", code_verifier),
+            ])
+            .send()
+            .await
+            .map_err(|e| upstream(&provider.name, e))?
+            .json()
+            .await
+            .map_err(|e| upstream(&provider.name, e))?;
+
+        // A bad or reused code is the caller's problem, not the provider's
+        let Some(access_token) = token.access_token else {
+            return Err(UserError::AuthenticationError(format!(
+                "{} refused the authorization code: {}",
+                provider.name,
+                token.error_description.or(token.error).unwrap_or_else(|| "no access token".to_string())
+            )));
+        };
+
+        match provider.kind {
+            OAuthProviderKind::Github => self.github_identity(provider, &access_token).await,
+            OAuthProviderKind::Oidc => self.oidc_identity(provider, &endpoints, &access_token).await,
+        }
+    }
+
+    async fn github_identity(&self, provider: &OAuthProviderConfig, access_token: &str) -> UserResult<ExternalIdentity> {
+        let user: GithubUser = self.get_json(provider, &format!("{}/user", GITHUB_API_URL), access_token).await?;
+        let emails: Vec<GithubEmail> = self
+            .get_json(provider, &format!("{}/user/emails", GITHUB_API_URL), access_token)
+            .await?;
+        let email = emails.into_iter().find(|email| email.primary);
+
+        Ok(ExternalIdentity {
+            subject: user.id.to_string(),
+            email_verified: email.as_ref().is_some_and(|email| email.verified),
+            email: email.map(|email| email.email),
+            username: Some(user.login),
+        })
+    }
+
+    async fn oidc_identity(
+        &self,
+        provider: &OAuthProviderConfig,
+        endpoints: &OidcEndpoints,
+        access_token: &str,
+    ) -> UserResult<ExternalIdentity> {
+        let claims: Value = self.get_json(provider, &endpoints.userinfo_endpoint, access_token).await?;
+        let subject = claims["sub"]
+            .as_str()
+            .ok_or_else(|| upstream(&provider.name, "userinfo has no subject"))?;
+        // Some issuers send the flag as a string
+        let email_verified = match &claims["email_verified"] {
+            Value::Bool(verified) => *verified,
+            Value::String(verified) => verified == "true",
+            _ => false,
+        };
+
+        Ok(ExternalIdentity {
+            subject: subject.to_string(),
+            email: claims["email"].as_str().map(str::to_string),
+            email_verified,
+            username: claims["preferred_username"]
+                .as_str()
+                .or_else(|| claims["nickname"].as_str())
+                .map(str::to_string),
+        })
+    }
+
+    async fn get_json<T: serde::de::DeserializeOwned>(
+        &self,
+        provider: &OAuthProviderConfig,
+        url: &str,
+        access_token: &str,
+    ) -> UserResult<T> {
+        self.http
+            .get(url)
+            .bearer_auth(access_token)
+            .header("Accept", "application/json")
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| upstream(&provider.name, e))?
+            .json()
+            .await
+            .map_err(|e| upstream(&provider.name, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn github() -> OAuthProviderConfig {
+        OAuthProviderConfig {
+            name: "github".to_string(),
+            kind: OAuthProviderKind::Github,
+            client_id: "client-id".to_string(),
+            client_secret: "client-secret".to_string(),
+            issuer: None,
+            scopes: "read:user user:email".to_string(),
+            jit_provisioning: true,
+            default_role: "user".to_string(),
+            link_by_email: true,
+        }
+    }
+
+    #[test]
+    fn test_pkce_challenge_is_unpadded_base64url_sha256() {
+        assert_eq!(
+            pkce_challenge("dBjftJeZ4CVP-mJ92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "ngF5GsXcbwljx6u133FFr3Xht9xooA_DuaX_3QwODtc"
+        );
+    }
+
+    #[test]
+    fn test_random_token_is_a_valid_verifier() {
+        let token = random_token().unwrap();
+        assert_eq!(token.len(), 43);
+        assert!(token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        assert_ne!(token, random_token().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_authorization_url_carries_state_and_challenge() {
+        let client = OAuthClient::new(OAuthConfig {
+            redirect_base_url: "https://nexus-security.io/auth/oauth/".to_string(),
+            state_ttl_seconds: 600,
+            providers: vec![github()],
+        })
+        .unwrap();
+        let provider = client.provider("GitHub").unwrap();
+
+        let url = client.authorization_url(provider, "the-state", "the-verifier").await.unwrap();
+        let url = reqwest::Url::parse(&url).unwrap();
+        let params: HashMap<_, _> = url.query_pairs().into_owned().collect();
+
+        assert_eq!(url.host_str(), Some("github.com"));
+        assert_eq!(params["state"], "the-state");
+        assert_eq!(params["client_id"], "client-id");
+        assert_eq!(params["redirect_uri"], "https://nexus-security.io/auth/oauth/github/callback");
+        assert_eq!(params["code_challenge"], pkce_challenge("the-verifier"));
+        assert_eq!(params["code_challenge_method"], "S256");
+        assert!(client.provider("gitlab").is_err());
+    }
+}
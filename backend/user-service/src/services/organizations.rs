@@ -0,0 +1,603 @@
+use chrono::{Duration, Utc};
+use redis::AsyncCommands;
+use ring::digest::{digest, SHA256};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::config::Config;
+use crate::models::*;
+use crate::services::oauth::random_token;
+
+/// Organizations: members with roles, emailed invitations and API keys.
+///
+/// A user belongs to at most one organization. Every organization keeps at
+/// least one owner; only owners can make or unmake other owners. Membership
+/// changes drop the gateway's cached organization of the affected user.
+pub struct OrganizationService {
+    config: Config,
+    db_pool: PgPool,
+    redis_conn: redis::aio::ConnectionManager,
+}
+
+fn db_error(e: sqlx::Error) -> UserError {
+    UserError::DatabaseError(e.to_string())
+}
+
+fn is_unique_violation(e: &sqlx::Error) -> bool {
+    matches!(e, sqlx::Error::Database(db) if db.is_unique_violation())
+}
+
+fn parse_role(role: &str) -> UserResult<OrganizationRole> {
+    role.parse().map_err(UserError::DatabaseError)
+}
+
+/// Hex SHA-256, as the gateway hashes API keys
+fn sha256_hex(value: &str) -> String {
+    digest(&SHA256, value.as_bytes())
+        .as_ref()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Invitations and API keys hand out member rights, never ownership
+fn grantable(role: OrganizationRole) -> UserResult<OrganizationRole> {
+    if role == OrganizationRole::Owner {
+        return Err(UserError::ValidationError(
+            "Ownership is granted by changing an existing member's role".to_string(),
+        ));
+    }
+    Ok(role)
+}
+
+impl OrganizationService {
+    pub fn new(config: Config, db_pool: PgPool, redis_conn: redis::aio::ConnectionManager) -> Self {
+        Self {
+            config,
+            db_pool,
+            redis_conn,
+        }
+    }
+
+    /// The caller's role in `organization_id`
+    async fn role_in(&self, user_id: Uuid, organization_id: Uuid) -> UserResult<OrganizationRole> {
+        let role: Option<String> = sqlx::query_scalar(
+            "SELECT role FROM organization_members WHERE organization_id = $1 AND user_id = $2",
+        )
+        .bind(organization_id)
+        .bind(user_id)
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+
+        match role {
+            Some(role) => parse_role(&role),
+            None => Err(UserError::Unauthorized("Not a member of this organization".to_string())),
+        }
+    }
+
+    async fn require_manager(&self, user_id: Uuid, organization_id: Uuid) -> UserResult<OrganizationRole> {
+        let role = self.role_in(user_id, organization_id).await?;
+        if !role.manages_members() {
+            return Err(UserError::Unauthorized(
+                "Only owners and admins can manage the organization".to_string(),
+            ));
+        }
+        Ok(role)
+    }
+
+    async fn organization(&self, organization_id: Uuid) -> UserResult<Organization> {
+        sqlx::query_as("SELECT * FROM organizations WHERE id = $1")
+            .bind(organization_id)
+            .fetch_optional(&self.db_pool)
+            .await
+            .map_err(db_error)?
+            .ok_or(UserError::NotFound)
+    }
+
+    /// Forget the gateway's cached organization of a user
+    async fn forget_cached_membership(&self, user_id: Uuid) {
+        let mut conn = self.redis_conn.clone();
+        if let Err(e) = conn.del::<_, ()>(format!("usage:org_of:{}", user_id)).await {
+            tracing::warn!("Failed to drop cached organization of user {}: {}", user_id, e);
+        }
+    }
+
+    /// Lock the organization's owners for the rest of `tx`
+    async fn lock_owners(
+        tx: &mut Transaction<'_, Postgres>,
+        organization_id: Uuid,
+    ) -> UserResult<Vec<Uuid>> {
+        sqlx::query_scalar(
+            "SELECT user_id FROM organization_members WHERE organization_id = $1 AND role = 'owner' FOR UPDATE",
+        )
+        .bind(organization_id)
+        .fetch_all(&mut **tx)
+        .await
+        .map_err(db_error)
+    }
+
+    /// Create an organization owned by the caller
+    pub async fn create(&self, user_id: Uuid, req: CreateOrganizationRequest) -> UserResult<OrganizationMembership> {
+        req.validate()
+            .map_err(|e| UserError::ValidationError(format!("{}", e)))?;
+
+        let mut tx = self.db_pool.begin().await.map_err(db_error)?;
+        let organization: Organization = sqlx::query_as(
+            r#"
+            INSERT INTO organizations (id, name, billing_email, is_active, created_at, updated_at)
+            VALUES ($1, $2, $3, TRUE, NOW(), NOW())
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(req.name.trim())
+        .bind(req.billing_email.as_deref())
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(db_error)?;
+
+        sqlx::query(
+            "INSERT INTO organization_members (organization_id, user_id, role, created_at) VALUES ($1, $2, 'owner', NOW())",
+        )
+        .bind(organization.id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            if is_unique_violation(&e) {
+                UserError::Conflict("You already belong to an organization".to_string())
+            } else {
+                db_error(e)
+            }
+        })?;
+        tx.commit().await.map_err(db_error)?;
+
+        self.forget_cached_membership(user_id).await;
+        tracing::info!("User {} created organization {}", user_id, organization.id);
+
+        Ok(OrganizationMembership {
+            organization,
+            role: OrganizationRole::Owner.as_str().to_string(),
+        })
+    }
+
+    /// The caller's organization
+    pub async fn current(&self, user_id: Uuid) -> UserResult<OrganizationMembership> {
+        let (organization_id, role): (Uuid, String) =
+            sqlx::query_as("SELECT organization_id, role FROM organization_members WHERE user_id = $1")
+                .bind(user_id)
+                .fetch_optional(&self.db_pool)
+                .await
+                .map_err(db_error)?
+                .ok_or(UserError::NotFound)?;
+
+        Ok(OrganizationMembership {
+            organization: self.organization(organization_id).await?,
+            role,
+        })
+    }
+
+    pub async fn update(
+        &self,
+        user_id: Uuid,
+        organization_id: Uuid,
+        req: UpdateOrganizationRequest,
+    ) -> UserResult<Organization> {
+        req.validate()
+            .map_err(|e| UserError::ValidationError(format!("{}", e)))?;
+        self.require_manager(user_id, organization_id).await?;
+
+        sqlx::query_as(
+            r#"
+            UPDATE organizations
+            SET name = COALESCE($2, name),
+                billing_email = COALESCE($3, billing_email),
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(organization_id)
+        .bind(req.name.as_deref().map(str::trim))
+        .bind(req.billing_email.as_deref())
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(db_error)?
+        .ok_or(UserError::NotFound)
+    }
+
+    /// Members, owners first; visible to every member
+    pub async fn members(&self, user_id: Uuid, organization_id: Uuid) -> UserResult<Vec<OrganizationMember>> {
+        self.role_in(user_id, organization_id).await?;
+
+        sqlx::query_as(
+            r#"
+            SELECT m.user_id, u.username, u.email, m.role, m.created_at
+            FROM organization_members m
+            JOIN users u ON u.id = m.user_id
+            WHERE m.organization_id = $1
+            ORDER BY m.role = 'owner' DESC, m.created_at
+            "#,
+        )
+        .bind(organization_id)
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(db_error)
+    }
+
+    /// Change a member's role. Only owners touch the owner role, and the
+    /// last owner cannot step down.
+    pub async fn change_role(
+        &self,
+        user_id: Uuid,
+        organization_id: Uuid,
+        member_id: Uuid,
+        role: OrganizationRole,
+    ) -> UserResult<OrganizationMember> {
+        let caller_role = self.require_manager(user_id, organization_id).await?;
+
+        let mut tx = self.db_pool.begin().await.map_err(db_error)?;
+        let owners = Self::lock_owners(&mut tx, organization_id).await?;
+        let member_is_owner = owners.contains(&member_id);
+
+        if (member_is_owner || role == OrganizationRole::Owner) && caller_role != OrganizationRole::Owner {
+            return Err(UserError::Unauthorized("Only owners can change ownership".to_string()));
+        }
+        if member_is_owner && role != OrganizationRole::Owner && owners.len() == 1 {
+            return Err(UserError::Conflict(
+                "The organization needs another owner first".to_string(),
+            ));
+        }
+
+        let updated = sqlx::query(
+            "UPDATE organization_members SET role = $3 WHERE organization_id = $1 AND user_id = $2",
+        )
+        .bind(organization_id)
+        .bind(member_id)
+        .bind(role.as_str())
+        .execute(&mut *tx)
+        .await
+        .map_err(db_error)?;
+        if updated.rows_affected() == 0 {
+            return Err(UserError::NotFound);
+        }
+        tx.commit().await.map_err(db_error)?;
+
+        tracing::info!(
+            "User {} made {} {} of organization {}",
+            user_id,
+            member_id,
+            role.as_str(),
+            organization_id
+        );
+
+        self.members(user_id, organization_id)
+            .await?
+            .into_iter()
+            .find(|m| m.user_id == member_id)
+            .ok_or(UserError::NotFound)
+    }
+
+    /// Remove a member, or leave when `member_id` is the caller
+    pub async fn remove_member(&self, user_id: Uuid, organization_id: Uuid, member_id: Uuid) -> UserResult<()> {
+        let caller_role = if member_id == user_id {
+            self.role_in(user_id, organization_id).await?
+        } else {
+            self.require_manager(user_id, organization_id).await?
+        };
+
+        let mut tx = self.db_pool.begin().await.map_err(db_error)?;
+        let owners = Self::lock_owners(&mut tx, organization_id).await?;
+        if owners.contains(&member_id) {
+            if caller_role != OrganizationRole::Owner {
+                return Err(UserError::Unauthorized("Only owners can remove an owner".to_string()));
+            }
+            if owners.len() == 1 {
+                return Err(UserError::Conflict(
+                    "The organization needs another owner first".to_string(),
+                ));
+            }
+        }
+
+        let removed = sqlx::query("DELETE FROM organization_members WHERE organization_id = $1 AND user_id = $2")
+            .bind(organization_id)
+            .bind(member_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        if removed.rows_affected() == 0 {
+            return Err(UserError::NotFound);
+        }
+        tx.commit().await.map_err(db_error)?;
+
+        self.forget_cached_membership(member_id).await;
+        tracing::info!("User {} removed {} from organization {}", user_id, member_id, organization_id);
+        Ok(())
+    }
+
+    /// Invite an address and email it a link to join
+    pub async fn invite(
+        &self,
+        user_id: Uuid,
+        organization_id: Uuid,
+        req: InviteMemberRequest,
+    ) -> UserResult<OrganizationInvitation> {
+        req.validate()
+            .map_err(|e| UserError::ValidationError(format!("{}", e)))?;
+        let role = grantable(req.role)?;
+        self.require_manager(user_id, organization_id).await?;
+
+        let email = req.email.trim().to_lowercase();
+        let invitee: Option<(Uuid, Option<Uuid>)> = sqlx::query_as(
+            r#"
+            SELECT u.id, m.organization_id
+            FROM users u
+            LEFT JOIN organization_members m ON m.user_id = u.id
+            WHERE LOWER(u.email) = $1
+            "#,
+        )
+        .bind(&email)
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+        if matches!(invitee, Some((_, Some(org))) if org == organization_id) {
+            return Err(UserError::Conflict("Already a member of this organization".to_string()));
+        }
+
+        let token = random_token()?;
+        let expires_at = Utc::now() + Duration::hours(self.config.organizations.invitation_ttl_hours as i64);
+        let invitation: OrganizationInvitation = sqlx::query_as(
+            r#"
+            INSERT INTO organization_invitations
+                (id, organization_id, email, role, token_hash, invited_by, expires_at, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, NOW())
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(organization_id)
+        .bind(&email)
+        .bind(role.as_str())
+        .bind(sha256_hex(&token))
+        .bind(user_id)
+        .bind(expires_at)
+        .fetch_one(&self.db_pool)
+        .await
+        .map_err(|e| {
+            if is_unique_violation(&e) {
+                UserError::Conflict("This address already has an open invitation".to_string())
+            } else {
+                db_error(e)
+            }
+        })?;
+
+        let organization = self.organization(organization_id).await?;
+        let inviter: String = sqlx::query_scalar("SELECT username FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_one(&self.db_pool)
+            .await
+            .map_err(db_error)?;
+
+        let event = shared::messaging::event_types::OrganizationInvitationSentEvent {
+            invitation_id: invitation.id,
+            organization_id,
+            organization_name: organization.name,
+            user_id: invitee.map(|(id, _)| id),
+            email: email.clone(),
+            role: role.as_str().to_string(),
+            invited_by: inviter,
+            accept_url: format!("{}?token={}", self.config.organizations.invitation_base_url, token),
+            expires_at,
+        };
+        shared::messaging::publish_event(
+            &redis::Client::open(self.config.redis.url.clone())
+                .map_err(|e| UserError::DatabaseError(e.to_string()))?,
+            &shared::messaging::event_types::NexusEvent::OrganizationInvitationSent(event),
+        )
+        .await
+        .map_err(|e| UserError::DatabaseError(format!("Failed to queue invitation email: {}", e)))?;
+
+        tracing::info!("User {} invited a new {} to organization {}", user_id, role.as_str(), organization_id);
+        Ok(invitation)
+    }
+
+    /// Open invitations
+    pub async fn invitations(&self, user_id: Uuid, organization_id: Uuid) -> UserResult<Vec<OrganizationInvitation>> {
+        self.require_manager(user_id, organization_id).await?;
+
+        sqlx::query_as(
+            r#"
+            SELECT * FROM organization_invitations
+            WHERE organization_id = $1 AND accepted_at IS NULL AND revoked_at IS NULL AND expires_at > NOW()
+            ORDER BY created_at DESC
+            "#,
+        )
+        .bind(organization_id)
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(db_error)
+    }
+
+    pub async fn revoke_invitation(&self, user_id: Uuid, organization_id: Uuid, invitation_id: Uuid) -> UserResult<()> {
+        self.require_manager(user_id, organization_id).await?;
+
+        let revoked = sqlx::query(
+            r#"
+            UPDATE organization_invitations SET revoked_at = NOW()
+            WHERE id = $1 AND organization_id = $2 AND accepted_at IS NULL AND revoked_at IS NULL
+            "#,
+        )
+        .bind(invitation_id)
+        .bind(organization_id)
+        .execute(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+        if revoked.rows_affected() == 0 {
+            return Err(UserError::NotFound);
+        }
+        Ok(())
+    }
+
+    /// Join the organization an invitation is for. The caller must be signed
+    /// in with the invited address.
+    pub async fn accept_invitation(&self, user: &User, token: &str) -> UserResult<OrganizationMembership> {
+        let mut tx = self.db_pool.begin().await.map_err(db_error)?;
+        let invitation: OrganizationInvitation = sqlx::query_as(
+            r#"
+            SELECT * FROM organization_invitations
+            WHERE token_hash = $1 AND accepted_at IS NULL AND revoked_at IS NULL AND expires_at > NOW()
+            FOR UPDATE
+            "#,
+        )
+        .bind(sha256_hex(token.trim()))
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(db_error)?
+        .ok_or(UserError::InvalidToken)?;
+
+        if !invitation.email.eq_ignore_ascii_case(user.email.trim()) {
+            return Err(UserError::Unauthorized(
+                "This invitation is for a different email address".to_string(),
+            ));
+        }
+        if !user.email_verified {
+            return Err(UserError::Unauthorized(
+                "Verify your email address before joining an organization".to_string(),
+            ));
+        }
+
+        sqlx::query(
+            "INSERT INTO organization_members (organization_id, user_id, role, created_at) VALUES ($1, $2, $3, NOW())",
+        )
+        .bind(invitation.organization_id)
+        .bind(user.id)
+        .bind(&invitation.role)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            if is_unique_violation(&e) {
+                UserError::Conflict("You already belong to an organization".to_string())
+            } else {
+                db_error(e)
+            }
+        })?;
+        sqlx::query("UPDATE organization_invitations SET accepted_at = NOW() WHERE id = $1")
+            .bind(invitation.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(db_error)?;
+        tx.commit().await.map_err(db_error)?;
+
+        self.forget_cached_membership(user.id).await;
+        tracing::info!("User {} joined organization {}", user.id, invitation.organization_id);
+
+        Ok(OrganizationMembership {
+            organization: self.organization(invitation.organization_id).await?,
+            role: invitation.role,
+        })
+    }
+
+    /// Create an API key; the key itself is returned only this once
+    pub async fn create_api_key(
+        &self,
+        user_id: Uuid,
+        organization_id: Uuid,
+        req: CreateOrganizationApiKeyRequest,
+    ) -> UserResult<CreatedOrganizationApiKey> {
+        req.validate()
+            .map_err(|e| UserError::ValidationError(format!("{}", e)))?;
+        let role = grantable(req.role)?;
+        self.require_manager(user_id, organization_id).await?;
+
+        let active: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM organization_api_keys WHERE organization_id = $1 AND revoked_at IS NULL",
+        )
+        .bind(organization_id)
+        .fetch_one(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+        if active >= self.config.organizations.max_api_keys {
+            return Err(UserError::Conflict(format!(
+                "Organizations can hold at most {} active API keys",
+                self.config.organizations.max_api_keys
+            )));
+        }
+
+        // Same format the gateway issues and accepts
+        let api_key = format!("nxs_{}", Uuid::new_v4().simple());
+        let key: OrganizationApiKey = sqlx::query_as(
+            r#"
+            INSERT INTO organization_api_keys
+                (id, organization_id, name, key_prefix, key_hash, role, created_by, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, NOW())
+            RETURNING *
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(organization_id)
+        .bind(req.name.trim())
+        .bind(&api_key[..8])
+        .bind(sha256_hex(&api_key))
+        .bind(role.as_str())
+        .bind(user_id)
+        .fetch_one(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+
+        tracing::info!("User {} created {} API key {} for organization {}", user_id, role.as_str(), key.id, organization_id);
+        Ok(CreatedOrganizationApiKey { api_key, key })
+    }
+
+    pub async fn api_keys(&self, user_id: Uuid, organization_id: Uuid) -> UserResult<Vec<OrganizationApiKey>> {
+        self.require_manager(user_id, organization_id).await?;
+
+        sqlx::query_as(
+            "SELECT * FROM organization_api_keys WHERE organization_id = $1 AND revoked_at IS NULL ORDER BY created_at DESC",
+        )
+        .bind(organization_id)
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(db_error)
+    }
+
+    pub async fn revoke_api_key(&self, user_id: Uuid, organization_id: Uuid, key_id: Uuid) -> UserResult<()> {
+        self.require_manager(user_id, organization_id).await?;
+
+        let revoked = sqlx::query(
+            "UPDATE organization_api_keys SET revoked_at = NOW() WHERE id = $1 AND organization_id = $2 AND revoked_at IS NULL",
+        )
+        .bind(key_id)
+        .bind(organization_id)
+        .execute(&self.db_pool)
+        .await
+        .map_err(db_error)?;
+        if revoked.rows_affected() == 0 {
+            return Err(UserError::NotFound);
+        }
+
+        tracing::info!("User {} revoked API key {} of organization {}", user_id, key_id, organization_id);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_key_hash_matches_gateway_format() {
+        // hex SHA-256, lowercase, as the gateway's HashUtils::sha256
+        assert_eq!(
+            sha256_hex("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_ownership_is_not_grantable() {
+        assert!(grantable(OrganizationRole::Owner).is_err());
+        assert_eq!(grantable(OrganizationRole::Billing).unwrap(), OrganizationRole::Billing);
+    }
+}
@@ -1,19 +1,27 @@
 use chrono::Utc;
 use redis::AsyncCommands;
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgPool};
 use std::sync::Arc;
 use uuid::Uuid;
 use validator::Validate;
 
+use shared::login_guard::{LoginDecision, LoginGuard, LoginGuardConfig, SiteVerifyCaptcha};
+use shared::permissions::Scopes;
+
+use crate::auth::siwe::SiweMessage;
+use crate::auth::webauthn::{self, PendingChallenge};
 use crate::auth::AuthService;
-use crate::config::Config;
+use crate::config::{Config, OAuthProviderConfig, OAuthProviderKind};
 use crate::models::*;
+use crate::services::oauth::{random_token, ExternalIdentity, OAuthClient, PendingAuthorization};
 
 pub struct UserService {
     config: Config,
     db_pool: PgPool,
     redis_conn: redis::aio::ConnectionManager,
     auth_service: Arc<AuthService>,
+    login_guard: LoginGuard,
+    oauth: OAuthClient,
 }
 
 impl UserService {
@@ -24,11 +32,26 @@ impl UserService {
     ) -> UserResult<Self> {
         let auth_service = Arc::new(AuthService::new(config.jwt.clone()));
 
+        // Failed password counters are shared with the gateway's login
+        let guard_conn = redis::Client::open(config.redis.url.clone())
+            .map_err(|e| UserError::DatabaseError(e.to_string()))?
+            .get_multiplexed_tokio_connection()
+            .await
+            .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+        let mut login_guard = LoginGuard::new(guard_conn, LoginGuardConfig::from_env());
+        if let Some(captcha) = SiteVerifyCaptcha::from_env() {
+            login_guard = login_guard.with_captcha(Arc::new(captcha));
+        }
+
+        let oauth = OAuthClient::new(config.oauth.clone())?;
+
         Ok(Self {
             config,
             db_pool,
             redis_conn,
             auth_service,
+            login_guard,
+            oauth,
         })
     }
 
@@ -56,50 +79,9 @@ impl UserService {
         // Hash password
         let password_hash = self.auth_service.hash_password(&req.password)?;
 
-        // Create user
-        let user = sqlx::query_as::<_, User>(
-            r#"
-            INSERT INTO users (id, username, email, password_hash, ethereum_address, email_verified,
-                              is_active, is_admin, two_factor_enabled, kyc_status, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, $5, false, true, false, false, 'not_submitted', NOW(), NOW())
-            RETURNING *
-            "#
-        )
-        .bind(Uuid::new_v4())
-        .bind(&req.username)
-        .bind(&req.email)
-        .bind(&password_hash)
-        .bind(&req.ethereum_address)
-        .fetch_one(&self.db_pool)
-        .await
-        .map_err(|e| UserError::DatabaseError(e.to_string()))?;
-
-        // Create default profile
-        sqlx::query(
-            r#"
-            INSERT INTO user_profiles (user_id, created_at, updated_at)
-            VALUES ($1, NOW(), NOW())
-            "#
-        )
-        .bind(user.id)
-        .execute(&self.db_pool)
-        .await
-        .map_err(|e| UserError::DatabaseError(e.to_string()))?;
-
-        // Create default settings
-        sqlx::query(
-            r#"
-            INSERT INTO user_settings (user_id, email_notifications, push_notifications,
-                                       webhook_notifications, privacy_public_profile,
-                                       privacy_show_email, privacy_show_stats,
-                                       language, timezone, updated_at)
-            VALUES ($1, true, true, false, true, false, true, 'en', 'UTC', NOW())
-            "#
-        )
-        .bind(user.id)
-        .execute(&self.db_pool)
-        .await
-        .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+        let user = self
+            .create_user(&req.username, &req.email, &password_hash, req.ethereum_address.as_deref())
+            .await?;
 
         // Generate verification token
         let verification_token = self.auth_service.generate_verification_token();
@@ -134,14 +116,13 @@ impl UserService {
             user.id,
             &user.email,
             &user.username,
-            user.is_admin,
+            self.scopes_for(&user).await?,
         )?;
 
         let refresh_token = self.auth_service.generate_refresh_token(
             user.id,
             &user.email,
             &user.username,
-            user.is_admin,
         )?;
 
         Ok(AuthResponse {
@@ -152,32 +133,93 @@ impl UserService {
         })
     }
 
+    /// Create a user with their default profile and settings
+    async fn create_user(
+        &self,
+        username: &str,
+        email: &str,
+        password_hash: &str,
+        ethereum_address: Option<&str>,
+    ) -> UserResult<User> {
+        let mut tx = self.db_pool.begin().await.map_err(|e| UserError::DatabaseError(e.to_string()))?;
+        let user = insert_user(&mut tx, username, email, password_hash, ethereum_address)
+            .await
+            .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+        tx.commit().await.map_err(|e| UserError::DatabaseError(e.to_string()))?;
+        Ok(user)
+    }
+
     /// Login user
-    pub async fn login(&self, req: LoginRequest) -> UserResult<AuthResponse> {
+    pub async fn login(&self, req: LoginRequest, ip: Option<String>) -> UserResult<AuthResponse> {
         req.validate()
             .map_err(|e| UserError::ValidationError(format!("{}", e)))?;
 
+        // Locked out accounts and IPs are turned away before any password
+        // check; Redis being down lets logins through
+        let ip = ip.as_deref();
+        match self.login_guard.check(&req.email, ip, req.captcha_token.as_deref()).await {
+            Ok(LoginDecision::Allow) => {}
+            Ok(LoginDecision::CaptchaRequired) => return Err(UserError::CaptchaRequired),
+            Ok(LoginDecision::Locked { retry_after }) => {
+                return Err(UserError::LockedOut(retry_after.as_secs().max(1)))
+            }
+            Err(e) => tracing::warn!("Login guard unavailable, allowing attempt: {}", e),
+        }
+
         // Find user by email
         let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE email = $1")
             .bind(&req.email)
             .fetch_optional(&self.db_pool)
             .await
-            .map_err(|e| UserError::DatabaseError(e.to_string()))?
-            .ok_or(UserError::AuthenticationError("Invalid credentials".to_string()))?;
+            .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+        let Some(user) = user else {
+            return Err(self.login_failed(&req.email, ip, None, "Invalid credentials").await);
+        };
 
         // Check if user is active
         if !user.is_active {
             return Err(UserError::Unauthorized("Account is suspended".to_string()));
         }
 
-        // Verify password
-        if !self.auth_service.verify_password(&req.password, &user.password_hash)? {
-            return Err(UserError::AuthenticationError("Invalid credentials".to_string()));
+        // Verify password; wallet-only accounts have none to match
+        if !user.has_password() || !self.auth_service.verify_password(&req.password, &user.password_hash)? {
+            return Err(self.login_failed(&req.email, ip, Some(&user), "Invalid credentials").await);
+        }
+
+        // Check the second factor if set up; wrong codes count as failures too
+        if !self.verify_second_factor(&user, req.two_factor_code.as_deref(), req.webauthn.as_ref()).await? {
+            return Err(self.login_failed(&req.email, ip, Some(&user), "Invalid 2FA code").await);
+        }
+
+        if let Err(e) = self.login_guard.record_success(&req.email).await {
+            tracing::warn!("Failed to clear login failures: {}", e);
+        }
+
+        self.issue_session(user, LoginMethod::Password).await
+    }
+
+    /// Check the second factor of a user who has set one up: a TOTP code or
+    /// an assertion from one of their security keys. Errs when neither is
+    /// given; `Ok(false)` when the one given is wrong.
+    async fn verify_second_factor(
+        &self,
+        user: &User,
+        code: Option<&str>,
+        assertion: Option<&WebAuthnAssertion>,
+    ) -> UserResult<bool> {
+        if let Some(assertion) = assertion {
+            return match self.verify_webauthn_assertion(assertion, Some(user.id), false).await {
+                Ok(_) => Ok(true),
+                Err(UserError::AuthenticationError(reason)) => {
+                    tracing::debug!("Rejected security key for {}: {}", user.id, reason);
+                    Ok(false)
+                }
+                Err(e) => Err(e),
+            };
         }
 
-        // Check 2FA if enabled
         if user.two_factor_enabled {
-            let code = req.two_factor_code.ok_or(
+            let code = code.ok_or(
                 UserError::AuthenticationError("2FA code required".to_string())
             )?;
 
@@ -185,11 +227,1038 @@ impl UserService {
                 UserError::DatabaseError("2FA secret not found".to_string())
             )?;
 
-            if !self.auth_service.verify_2fa_code(secret, &code)? {
-                return Err(UserError::AuthenticationError("Invalid 2FA code".to_string()));
+            return self.auth_service.verify_2fa_code(secret, code);
+        }
+
+        if !self.webauthn_credentials(user.id).await?.is_empty() {
+            return Err(UserError::AuthenticationError("Security key required".to_string()));
+        }
+        Ok(true)
+    }
+
+    /// Count a failed login and tell the owner when it locks their account
+    async fn login_failed(
+        &self,
+        email: &str,
+        ip: Option<&str>,
+        user: Option<&User>,
+        reason: &str,
+    ) -> UserError {
+        let error = UserError::AuthenticationError(reason.to_string());
+        let outcome = match self.login_guard.record_failure(email, ip).await {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                tracing::warn!("Failed to record login failure: {}", e);
+                return error;
+            }
+        };
+
+        let (Some(locked_for), Some(user)) = (outcome.account_locked_for, user) else {
+            return error;
+        };
+        tracing::warn!("Account {} locked for {:?} after failed logins", user.id, locked_for);
+
+        let event = shared::messaging::event_types::AccountLockedEvent {
+            user_id: user.id,
+            email: user.email.clone(),
+            failed_attempts: outcome.account_failures,
+            ip_address: ip.map(str::to_string),
+            locked_until: Utc::now()
+                + chrono::Duration::from_std(locked_for).unwrap_or_else(|_| chrono::Duration::zero()),
+        };
+        let published = shared::messaging::publish_event_on(
+            &mut self.redis_conn.clone(),
+            &shared::messaging::event_types::NexusEvent::AccountLocked(event),
+        )
+        .await;
+        if let Err(e) = published {
+            tracing::warn!("Failed to queue lockout notice for user {}: {}", user.id, e);
+        }
+
+        self.record_activity(user.id, "account_locked", None).await;
+        error
+    }
+
+    /// Send a single-use sign-in link to the given address.
+    ///
+    /// Always succeeds for unknown or suspended accounts so the endpoint
+    /// cannot be used to enumerate registered emails.
+    pub async fn request_magic_link(&self, req: MagicLinkRequest) -> UserResult<()> {
+        req.validate()
+            .map_err(|e| UserError::ValidationError(format!("{}", e)))?;
+
+        let email = req.email.trim().to_lowercase();
+        let mut conn = self.redis_conn.clone();
+
+        // Rate limit per address within a rolling one-hour bucket
+        let rate_key = format!("magic_link_rate:{}", email);
+        let attempts: u32 = conn.incr(&rate_key, 1)
+            .await
+            .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+        if attempts == 1 {
+            conn.expire::<_, ()>(&rate_key, 3600)
+                .await
+                .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+        }
+        if attempts > self.config.magic_link.max_requests_per_hour {
+            return Err(UserError::RateLimited(
+                "Too many sign-in link requests, try again later".to_string(),
+            ));
+        }
+
+        let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE LOWER(email) = $1")
+            .bind(&email)
+            .fetch_optional(&self.db_pool)
+            .await
+            .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+
+        let user = match user {
+            Some(user) if user.is_active => user,
+            _ => {
+                tracing::info!("Magic link requested for unknown or inactive address");
+                return Ok(());
+            }
+        };
+
+        let expiry_minutes = self.config.magic_link.expiry_minutes;
+        let (token, jti) = self.auth_service
+            .generate_magic_link_token(user.id, &user.email, expiry_minutes)?;
+
+        // The jti is deleted on first use, which makes the link single-use
+        conn.set_ex::<_, _, ()>(format!("magic_link:{}", jti), user.id.to_string(), expiry_minutes * 60)
+            .await
+            .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+
+        let event = shared::messaging::event_types::MagicLinkRequestedEvent {
+            user_id: user.id,
+            email: user.email.clone(),
+            login_url: format!("{}?token={}", self.config.magic_link.base_url, token),
+            expires_at: Utc::now() + chrono::Duration::minutes(expiry_minutes as i64),
+        };
+
+        shared::messaging::publish_event(
+            &redis::Client::open(self.config.redis.url.clone())
+                .map_err(|e| UserError::DatabaseError(e.to_string()))?,
+            &shared::messaging::event_types::NexusEvent::MagicLinkRequested(event),
+        )
+        .await
+        .map_err(|e| UserError::DatabaseError(format!("Failed to queue sign-in email: {}", e)))?;
+
+        self.record_activity(user.id, "magic_link_requested", None).await;
+
+        Ok(())
+    }
+
+    /// Exchange a magic link token for a session
+    pub async fn login_with_magic_link(
+        &self,
+        req: MagicLinkVerifyRequest,
+        ip: Option<String>,
+    ) -> UserResult<AuthResponse> {
+        let claims = self.auth_service.validate_magic_link_token(&req.token)?;
+
+        // The token is only consumed once the whole login succeeds, so a
+        // mistyped 2FA code does not burn the link
+        let link_key = format!("magic_link:{}", claims.jti);
+        let mut conn = self.redis_conn.clone();
+        let unused: bool = conn.exists(&link_key)
+            .await
+            .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+        if !unused {
+            return Err(UserError::InvalidToken);
+        }
+
+        let user_id = Uuid::parse_str(&claims.sub)
+            .map_err(|_| UserError::InvalidToken)?;
+        let user = self.get_user_by_id(user_id).await?;
+
+        if !user.is_active {
+            return Err(UserError::Unauthorized("Account is suspended".to_string()));
+        }
+
+        // The link stays valid until used, so second factor guesses against
+        // it are throttled like password logins
+        let ip = ip.as_deref();
+        match self.login_guard.check(&user.email, ip, req.captcha_token.as_deref()).await {
+            Ok(LoginDecision::Allow) => {}
+            Ok(LoginDecision::CaptchaRequired) => return Err(UserError::CaptchaRequired),
+            Ok(LoginDecision::Locked { retry_after }) => {
+                return Err(UserError::LockedOut(retry_after.as_secs().max(1)))
+            }
+            Err(e) => tracing::warn!("Login guard unavailable, allowing attempt: {}", e),
+        }
+
+        // The link replaces the password, not the second factor
+        if !self.verify_second_factor(&user, req.two_factor_code.as_deref(), req.webauthn.as_ref()).await? {
+            return Err(self.login_failed(&user.email, ip, Some(&user), "Invalid 2FA code").await);
+        }
+
+        // A concurrent exchange of the same link finds nothing to delete
+        let consumed: i64 = conn.del(&link_key)
+            .await
+            .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+        if consumed == 0 {
+            return Err(UserError::InvalidToken);
+        }
+
+        if let Err(e) = self.login_guard.record_success(&user.email).await {
+            tracing::warn!("Failed to clear login failures: {}", e);
+        }
+
+        // Clicking the link proves control of the inbox
+        if !user.email_verified {
+            sqlx::query("UPDATE users SET email_verified = true WHERE id = $1")
+                .bind(user.id)
+                .execute(&self.db_pool)
+                .await
+                .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+        }
+
+        self.issue_session(user, LoginMethod::MagicLink).await
+    }
+
+    // ============= Sign-In with Ethereum =============
+
+    /// Issue a single-use nonce for a SIWE message
+    pub async fn siwe_nonce(&self) -> UserResult<SiweNonceResponse> {
+        let nonce = self.auth_service.generate_verification_token();
+        let ttl = self.config.siwe.nonce_ttl_seconds;
+
+        let mut conn = self.redis_conn.clone();
+        conn.set_ex::<_, _, ()>(format!("siwe_nonce:{}", nonce), 1, ttl)
+            .await
+            .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+
+        Ok(SiweNonceResponse {
+            nonce,
+            domain: self.config.siwe.domain.clone(),
+            uri: self.config.siwe.uri.clone(),
+            statement: self.config.siwe.statement.clone(),
+            chain_ids: self.config.siwe.chain_ids.clone(),
+            expires_at: Utc::now() + chrono::Duration::seconds(ttl as i64),
+        })
+    }
+
+    /// Check a signed SIWE message and consume its nonce
+    async fn verify_siwe(&self, message: &str, signature: &str) -> UserResult<SiweMessage> {
+        let siwe = SiweMessage::parse(message)?;
+        siwe.validate(&self.config.siwe.domain, &self.config.siwe.chain_ids, Utc::now())?;
+
+        if !self.auth_service.verify_wallet_signature(message, signature, &siwe.address)? {
+            return Err(UserError::AuthenticationError("Invalid wallet signature".to_string()));
+        }
+
+        // Consumed only once the signature checks out, so a forged message
+        // cannot burn someone else's nonce
+        let mut conn = self.redis_conn.clone();
+        let consumed: i64 = conn.del(format!("siwe_nonce:{}", siwe.nonce))
+            .await
+            .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+        if consumed == 0 {
+            return Err(UserError::AuthenticationError("Nonce is unknown, expired or already used".to_string()));
+        }
+
+        Ok(siwe)
+    }
+
+    /// Sign in with a wallet, creating a wallet-only account the first time
+    /// an unlinked wallet signs in
+    pub async fn login_with_siwe(&self, req: SiweVerifyRequest) -> UserResult<AuthResponse> {
+        let siwe = self.verify_siwe(&req.message, &req.signature).await?;
+        let address = siwe.address.to_lowercase();
+
+        let user_id = sqlx::query_scalar::<_, Uuid>(
+            "UPDATE siwe_identities SET last_sign_in_at = NOW() WHERE address = $1 RETURNING user_id"
+        )
+        .bind(&address)
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+
+        let user = match user_id {
+            Some(user_id) => self.get_user_by_id(user_id).await?,
+            None => self.create_wallet_account(&address, siwe.chain_id).await?,
+        };
+
+        if !user.is_active {
+            return Err(UserError::Unauthorized("Account is suspended".to_string()));
+        }
+
+        // The signature replaces the password, not the second factor
+        if !self.verify_second_factor(&user, req.two_factor_code.as_deref(), req.webauthn.as_ref()).await? {
+            return Err(UserError::AuthenticationError("Invalid 2FA code".to_string()));
+        }
+
+        self.issue_session(user, LoginMethod::Siwe).await
+    }
+
+    /// Create an account for a wallet that signed in for the first time. It
+    /// has no password and a placeholder email until the user adds their own.
+    ///
+    /// The account and its identity are created together. When the same
+    /// wallet signs in twice at once, the losing attempt rolls back and
+    /// signs in to the account the other one created.
+    async fn create_wallet_account(&self, address: &str, chain_id: u64) -> UserResult<User> {
+        let email = format!("{}@{}", address, WALLET_ONLY_EMAIL_DOMAIN);
+        let mut tx = self.db_pool.begin().await.map_err(|e| UserError::DatabaseError(e.to_string()))?;
+        let created = async {
+            let user = insert_user(&mut tx, address, &email, "", Some(address)).await?;
+            sqlx::query(
+                r#"
+                INSERT INTO siwe_identities (address, user_id, chain_id, created_at, last_sign_in_at)
+                VALUES ($1, $2, $3, NOW(), NOW())
+                "#
+            )
+            .bind(address)
+            .bind(user.id)
+            .bind(chain_id as i64)
+            .execute(&mut *tx)
+            .await?;
+            Ok::<_, sqlx::Error>(user)
+        }
+        .await;
+
+        let user = match created {
+            Ok(user) => {
+                tx.commit().await.map_err(|e| UserError::DatabaseError(e.to_string()))?;
+                user
+            }
+            Err(sqlx::Error::Database(db)) if db.is_unique_violation() => {
+                drop(tx);
+                let user_id = sqlx::query_scalar::<_, Uuid>(
+                    "SELECT user_id FROM siwe_identities WHERE address = $1"
+                )
+                .bind(address)
+                .fetch_optional(&self.db_pool)
+                .await
+                .map_err(|e| UserError::DatabaseError(e.to_string()))?
+                .ok_or_else(|| UserError::Conflict(format!("An account already uses {}", address)))?;
+                return self.get_user_by_id(user_id).await;
+            }
+            Err(e) => return Err(UserError::DatabaseError(e.to_string())),
+        };
+
+        tracing::info!("Created wallet-only account {} for {}", user.id, address);
+        self.record_activity(user.id, "wallet_account_created", Some(LoginMethod::Siwe)).await;
+        Ok(user)
+    }
+
+    /// Let a signed-in user also sign in with the wallet that signed `req`
+    pub async fn link_siwe_identity(&self, user_id: Uuid, req: SiweLinkRequest) -> UserResult<SiweIdentity> {
+        let siwe = self.verify_siwe(&req.message, &req.signature).await?;
+        let address = siwe.address.to_lowercase();
+
+        let identity = sqlx::query_as::<_, SiweIdentity>(
+            r#"
+            INSERT INTO siwe_identities (address, user_id, chain_id, created_at)
+            VALUES ($1, $2, $3, NOW())
+            ON CONFLICT (address) DO UPDATE SET chain_id = EXCLUDED.chain_id
+                WHERE siwe_identities.user_id = EXCLUDED.user_id
+            RETURNING address, user_id, chain_id, created_at, last_sign_in_at
+            "#
+        )
+        .bind(&address)
+        .bind(user_id)
+        .bind(siwe.chain_id as i64)
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(|e| UserError::DatabaseError(e.to_string()))?
+        .ok_or_else(|| UserError::Conflict("This wallet is linked to another account".to_string()))?;
+
+        // The first linked wallet also becomes the account's wallet address
+        sqlx::query("UPDATE users SET ethereum_address = $1 WHERE id = $2 AND ethereum_address IS NULL")
+            .bind(&address)
+            .bind(user_id)
+            .execute(&self.db_pool)
+            .await
+            .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+
+        self.record_activity(user_id, "siwe_identity_linked", Some(LoginMethod::Siwe)).await;
+        Ok(identity)
+    }
+
+    /// Wallets the user can sign in with
+    pub async fn siwe_identities(&self, user_id: Uuid) -> UserResult<Vec<SiweIdentity>> {
+        sqlx::query_as::<_, SiweIdentity>(
+            r#"
+            SELECT address, user_id, chain_id, created_at, last_sign_in_at
+            FROM siwe_identities WHERE user_id = $1 ORDER BY created_at
+            "#
+        )
+        .bind(user_id)
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(|e| UserError::DatabaseError(e.to_string()))
+    }
+
+    /// Stop a wallet signing in to the account. An account without a
+    /// password keeps its last wallet or provider, its only way in.
+    pub async fn unlink_siwe_identity(&self, user_id: Uuid, address: &str) -> UserResult<()> {
+        let identities = self.siwe_identities(user_id).await?;
+        let address = address.to_lowercase();
+
+        if !identities.iter().any(|identity| identity.address == address) {
+            return Err(UserError::NotFound);
+        }
+        self.ensure_other_sign_in(user_id).await?;
+
+        sqlx::query("DELETE FROM siwe_identities WHERE address = $1 AND user_id = $2")
+            .bind(&address)
+            .bind(user_id)
+            .execute(&self.db_pool)
+            .await
+            .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+
+        self.record_activity(user_id, "siwe_identity_unlinked", None).await;
+        Ok(())
+    }
+
+    /// Refuse to remove a wallet or provider that is the last way into an
+    /// account without a password
+    async fn ensure_other_sign_in(&self, user_id: Uuid) -> UserResult<()> {
+        let user = self.get_user_by_id(user_id).await?;
+        if user.has_password() {
+            return Ok(());
+        }
+
+        let external = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT (SELECT COUNT(*) FROM siwe_identities WHERE user_id = $1)
+                 + (SELECT COUNT(*) FROM oauth_identities WHERE user_id = $1)
+            "#
+        )
+        .bind(user_id)
+        .fetch_one(&self.db_pool)
+        .await
+        .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+        if external <= 1 {
+            return Err(UserError::Conflict(
+                "This is the only way to sign in to an account without a password".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    // ============= OAuth / OIDC Methods =============
+
+    pub fn oauth_providers(&self) -> Vec<OAuthProviderInfo> {
+        self.oauth
+            .providers()
+            .iter()
+            .map(|provider| OAuthProviderInfo {
+                name: provider.name.clone(),
+                kind: match provider.kind {
+                    OAuthProviderKind::Github => "github",
+                    OAuthProviderKind::Oidc => "oidc",
+                }
+                .to_string(),
+            })
+            .collect()
+    }
+
+    /// Start the authorization-code flow with a provider: to sign in, or,
+    /// with `link_user_id`, to link the provider to a signed-in user
+    pub async fn start_oauth(&self, provider: &str, link_user_id: Option<Uuid>) -> UserResult<OAuthAuthorizeResponse> {
+        let provider = self.oauth.provider(provider)?;
+        let state = random_token()?;
+        let pending = PendingAuthorization {
+            provider: provider.name.clone(),
+            code_verifier: random_token()?,
+            link_user_id,
+        };
+        let authorization_url = self
+            .oauth
+            .authorization_url(provider, &state, &pending.code_verifier)
+            .await?;
+
+        let ttl = self.oauth.state_ttl_seconds();
+        let pending = serde_json::to_string(&pending)
+            .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+        let mut conn = self.redis_conn.clone();
+        conn.set_ex::<_, _, ()>(format!("oauth_state:{}", state), pending, ttl)
+            .await
+            .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+
+        Ok(OAuthAuthorizeResponse {
+            authorization_url,
+            state,
+            expires_at: Utc::now() + chrono::Duration::seconds(ttl as i64),
+        })
+    }
+
+    /// Finish the flow: sign in the user the provider account is linked to,
+    /// link it by verified email or to the user who started linking, or
+    /// provision a new account
+    pub async fn login_with_oauth(&self, provider: &str, req: OAuthCallbackRequest) -> UserResult<AuthResponse> {
+        let provider = self.oauth.provider(provider)?;
+
+        // Consume the state; a replayed callback finds nothing
+        let key = format!("oauth_state:{}", req.state);
+        let mut conn = self.redis_conn.clone();
+        let pending: Option<String> = conn.get(&key)
+            .await
+            .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+        let consumed: i64 = conn.del(&key)
+            .await
+            .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+        let pending: PendingAuthorization = pending
+            .filter(|_| consumed == 1)
+            .and_then(|pending| serde_json::from_str(&pending).ok())
+            .filter(|pending: &PendingAuthorization| pending.provider == provider.name)
+            .ok_or_else(|| UserError::AuthenticationError("OAuth state is unknown, expired or already used".to_string()))?;
+
+        let identity = self.oauth.identity(provider, &req.code, &pending.code_verifier).await?;
+
+        let linked = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            UPDATE oauth_identities SET email = $3, last_sign_in_at = NOW()
+            WHERE provider = $1 AND subject = $2
+            RETURNING user_id
+            "#
+        )
+        .bind(&provider.name)
+        .bind(&identity.subject)
+        .bind(&identity.email)
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+
+        let user = match (pending.link_user_id, linked) {
+            (Some(user_id), Some(linked)) if linked != user_id => {
+                return Err(UserError::Conflict(format!(
+                    "This {} account is linked to another user",
+                    provider.name
+                )))
+            }
+            (Some(user_id), Some(_)) => self.get_user_by_id(user_id).await?,
+            (Some(user_id), None) => {
+                self.link_oauth_identity(user_id, provider, &identity).await?;
+                self.get_user_by_id(user_id).await?
+            }
+            (None, Some(linked)) => self.get_user_by_id(linked).await?,
+            (None, None) => {
+                let by_email = match identity.email.as_deref() {
+                    Some(email) if provider.link_by_email && identity.email_verified => {
+                        sqlx::query_as::<_, User>("SELECT * FROM users WHERE LOWER(email) = LOWER($1)")
+                            .bind(email)
+                            .fetch_optional(&self.db_pool)
+                            .await
+                            .map_err(|e| UserError::DatabaseError(e.to_string()))?
+                    }
+                    _ => None,
+                };
+                match by_email {
+                    // Whoever registered an unverified address may not own it
+                    Some(user) if user.email_verified => {
+                        self.link_oauth_identity(user.id, provider, &identity).await?;
+                        user
+                    }
+                    Some(_) => {
+                        return Err(UserError::AuthenticationError(format!(
+                            "Sign in and link your {} account from your profile",
+                            provider.name
+                        )))
+                    }
+                    None if provider.jit_provisioning => self.provision_oauth_user(provider, &identity).await?,
+                    None => {
+                        return Err(UserError::AuthenticationError(format!(
+                            "No account is linked to this {} account",
+                            provider.name
+                        )))
+                    }
+                }
+            }
+        };
+
+        if !user.is_active {
+            return Err(UserError::Unauthorized("Account is suspended".to_string()));
+        }
+
+        // The provider replaces the password, not the second factor
+        if !self.verify_second_factor(&user, req.two_factor_code.as_deref(), req.webauthn.as_ref()).await? {
+            return Err(UserError::AuthenticationError("Invalid 2FA code".to_string()));
+        }
+
+        self.issue_session(user, LoginMethod::OAuth).await
+    }
+
+    async fn link_oauth_identity(
+        &self,
+        user_id: Uuid,
+        provider: &OAuthProviderConfig,
+        identity: &ExternalIdentity,
+    ) -> UserResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO oauth_identities (provider, subject, user_id, email, created_at, last_sign_in_at)
+            VALUES ($1, $2, $3, $4, NOW(), NOW())
+            "#
+        )
+        .bind(&provider.name)
+        .bind(&identity.subject)
+        .bind(user_id)
+        .bind(&identity.email)
+        .execute(&self.db_pool)
+        .await
+        .map_err(|e| match e.as_database_error().and_then(|db| db.code()).as_deref() {
+            Some("23505") => UserError::Conflict(format!(
+                "A {} account is already linked to this user",
+                provider.name
+            )),
+            _ => UserError::DatabaseError(e.to_string()),
+        })?;
+
+        self.record_activity(user_id, "oauth_identity_linked", Some(LoginMethod::OAuth)).await;
+        Ok(())
+    }
+
+    /// Create an account for someone signing in through a provider for the
+    /// first time, with the provider's default role
+    async fn provision_oauth_user(&self, provider: &OAuthProviderConfig, identity: &ExternalIdentity) -> UserResult<User> {
+        let email = identity.email.as_deref().ok_or_else(|| {
+            UserError::ValidationError(format!("{} did not share an email address", provider.name))
+        })?;
+
+        let taken = sqlx::query_scalar::<_, bool>("SELECT EXISTS (SELECT 1 FROM users WHERE LOWER(email) = LOWER($1))")
+            .bind(email)
+            .fetch_one(&self.db_pool)
+            .await
+            .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+        if taken {
+            return Err(UserError::Conflict(format!(
+                "An account with this email already exists; sign in and link {} from your account",
+                provider.name
+            )));
+        }
+
+        let username = self
+            .available_username(identity.username.as_deref().unwrap_or_else(|| email.split('@').next().unwrap_or_default()))
+            .await?;
+        let user = self.create_user(&username, email, "", None).await?;
+        let user = sqlx::query_as::<_, User>(
+            "UPDATE users SET email_verified = $2, is_admin = $3 WHERE id = $1 RETURNING *"
+        )
+        .bind(user.id)
+        .bind(identity.email_verified)
+        .bind(provider.default_role == "admin")
+        .fetch_one(&self.db_pool)
+        .await
+        .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+
+        self.link_oauth_identity(user.id, provider, identity).await?;
+
+        tracing::info!("Provisioned account {} from {}", user.id, provider.name);
+        self.record_activity(user.id, "oauth_account_provisioned", Some(LoginMethod::OAuth)).await;
+        Ok(user)
+    }
+
+    /// `preferred`, cleaned up, or with a numeric suffix if it is taken
+    async fn available_username(&self, preferred: &str) -> UserResult<String> {
+        use rand::Rng;
+
+        let mut base: String = preferred
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '-')
+            .take(40)
+            .collect::<String>()
+            .to_lowercase();
+        if base.len() < 3 {
+            base = format!("user{}", base);
+        }
+
+        let mut candidate = base.clone();
+        for _ in 0..5 {
+            let taken = sqlx::query_scalar::<_, bool>("SELECT EXISTS (SELECT 1 FROM users WHERE username = $1)")
+                .bind(&candidate)
+                .fetch_one(&self.db_pool)
+                .await
+                .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+            if !taken {
+                return Ok(candidate);
+            }
+            candidate = format!("{}-{}", base, rand::thread_rng().gen_range(1000..10000));
+        }
+        Err(UserError::Conflict("Could not find a free username".to_string()))
+    }
+
+    /// Provider accounts the user can sign in with
+    pub async fn oauth_identities(&self, user_id: Uuid) -> UserResult<Vec<OAuthIdentity>> {
+        sqlx::query_as::<_, OAuthIdentity>(
+            r#"
+            SELECT provider, subject, user_id, email, created_at, last_sign_in_at
+            FROM oauth_identities WHERE user_id = $1 ORDER BY created_at
+            "#
+        )
+        .bind(user_id)
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(|e| UserError::DatabaseError(e.to_string()))
+    }
+
+    pub async fn unlink_oauth_identity(&self, user_id: Uuid, provider: &str) -> UserResult<()> {
+        let identities = self.oauth_identities(user_id).await?;
+        if !identities.iter().any(|identity| identity.provider.eq_ignore_ascii_case(provider)) {
+            return Err(UserError::NotFound);
+        }
+        self.ensure_other_sign_in(user_id).await?;
+
+        sqlx::query("DELETE FROM oauth_identities WHERE LOWER(provider) = LOWER($1) AND user_id = $2")
+            .bind(provider)
+            .bind(user_id)
+            .execute(&self.db_pool)
+            .await
+            .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+
+        self.record_activity(user_id, "oauth_identity_unlinked", None).await;
+        Ok(())
+    }
+
+    // ============= WebAuthn Methods =============
+
+    async fn store_webauthn_challenge(&self, pending: &PendingChallenge) -> UserResult<String> {
+        let challenge = random_token()?;
+        let pending = serde_json::to_string(pending)
+            .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+
+        let mut conn = self.redis_conn.clone();
+        conn.set_ex::<_, _, ()>(
+            format!("webauthn_challenge:{}", challenge),
+            pending,
+            self.config.webauthn.challenge_ttl_seconds,
+        )
+        .await
+        .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+        Ok(challenge)
+    }
+
+    /// Consume the challenge a client data JSON answers; a replayed response
+    /// finds nothing
+    async fn take_webauthn_challenge(&self, client_data_json: &[u8]) -> UserResult<(String, PendingChallenge)> {
+        let challenge = webauthn::client_data_challenge(client_data_json)?;
+        let key = format!("webauthn_challenge:{}", challenge);
+
+        let mut conn = self.redis_conn.clone();
+        let pending: Option<String> = conn.get(&key)
+            .await
+            .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+        let consumed: i64 = conn.del(&key)
+            .await
+            .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+        let pending = pending
+            .filter(|_| consumed == 1)
+            .and_then(|pending| serde_json::from_str(&pending).ok())
+            .ok_or_else(|| UserError::AuthenticationError("WebAuthn challenge is unknown, expired or already used".to_string()))?;
+        Ok((challenge, pending))
+    }
+
+    fn credential_descriptors(credentials: &[WebAuthnCredential]) -> Vec<WebAuthnCredentialDescriptor> {
+        credentials
+            .iter()
+            .map(|credential| WebAuthnCredentialDescriptor {
+                kind: "public-key".to_string(),
+                id: credential.credential_id.clone(),
+                transports: credential.transports.clone(),
+            })
+            .collect()
+    }
+
+    /// Options for registering a security key or, with `passkey`, a passkey
+    pub async fn webauthn_registration_options(
+        &self,
+        user_id: Uuid,
+        req: WebAuthnRegistrationOptionsRequest,
+    ) -> UserResult<WebAuthnCreationOptions> {
+        let user = self.get_user_by_id(user_id).await?;
+        let existing = self.webauthn_credentials(user_id).await?;
+        if existing.len() as i64 >= self.config.webauthn.max_credentials {
+            return Err(UserError::Conflict(format!(
+                "At most {} security keys can be registered",
+                self.config.webauthn.max_credentials
+            )));
+        }
+
+        let challenge = self
+            .store_webauthn_challenge(&PendingChallenge {
+                user_id: Some(user_id),
+                registration: true,
+                passkey: req.passkey,
+            })
+            .await?;
+        let (resident_key, user_verification) = if req.passkey {
+            ("required", "required")
+        } else {
+            ("discouraged", "preferred")
+        };
+
+        Ok(WebAuthnCreationOptions {
+            challenge,
+            rp: WebAuthnRelyingParty {
+                id: self.config.webauthn.rp_id.clone(),
+                name: self.config.webauthn.rp_name.clone(),
+            },
+            user: WebAuthnUserEntity {
+                id: webauthn::encode(user.id.as_bytes()),
+                name: user.email,
+                display_name: user.username,
+            },
+            pub_key_cred_params: webauthn::SUPPORTED_ALGORITHMS
+                .iter()
+                .map(|alg| WebAuthnCredentialParameter {
+                    kind: "public-key".to_string(),
+                    alg: *alg,
+                })
+                .collect(),
+            timeout: self.config.webauthn.challenge_ttl_seconds * 1000,
+            attestation: "none".to_string(),
+            authenticator_selection: WebAuthnAuthenticatorSelection {
+                resident_key: resident_key.to_string(),
+                user_verification: user_verification.to_string(),
+            },
+            exclude_credentials: Self::credential_descriptors(&existing),
+        })
+    }
+
+    /// Store the credential from a `navigator.credentials.create()` response
+    pub async fn register_webauthn_credential(
+        &self,
+        user_id: Uuid,
+        req: WebAuthnRegisterRequest,
+    ) -> UserResult<WebAuthnCredential> {
+        let name = req.name.trim();
+        if name.is_empty() || name.chars().count() > 64 {
+            return Err(UserError::ValidationError("Name must be 1 to 64 characters".to_string()));
+        }
+        let client_data_json = webauthn::decode("client_data_json", &req.client_data_json)?;
+        let attestation_object = webauthn::decode("attestation_object", &req.attestation_object)?;
+
+        let (challenge, pending) = self.take_webauthn_challenge(&client_data_json).await?;
+        if !pending.registration || pending.user_id != Some(user_id) {
+            return Err(UserError::AuthenticationError("WebAuthn challenge was not issued for this registration".to_string()));
+        }
+        let (auth_data, credential) =
+            webauthn::verify_registration(&self.config.webauthn, &challenge, &client_data_json, &attestation_object)?;
+        if pending.passkey && !auth_data.user_verified() {
+            return Err(UserError::ValidationError("A passkey must verify the user with a PIN or biometric".to_string()));
+        }
+
+        let credential = sqlx::query_as::<_, WebAuthnCredential>(
+            r#"
+            INSERT INTO webauthn_credentials
+                (id, user_id, credential_id, name, public_key, algorithm, sign_count, aaguid,
+                 transports, passkey, backup_eligible, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, NOW())
+            RETURNING *
+            "#
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(webauthn::encode(&credential.credential_id))
+        .bind(name)
+        .bind(&credential.public_key)
+        .bind(credential.algorithm as i32)
+        .bind(auth_data.sign_count as i64)
+        .bind(credential.aaguid)
+        .bind(&req.transports)
+        .bind(pending.passkey)
+        .bind(auth_data.backup_eligible())
+        .fetch_one(&self.db_pool)
+        .await
+        .map_err(|e| match e.as_database_error().and_then(|db| db.code()).as_deref() {
+            Some("23505") => UserError::Conflict("This security key is already registered".to_string()),
+            _ => UserError::DatabaseError(e.to_string()),
+        })?;
+
+        self.record_activity(user_id, "webauthn_credential_added", None).await;
+        Ok(credential)
+    }
+
+    pub async fn webauthn_credentials(&self, user_id: Uuid) -> UserResult<Vec<WebAuthnCredential>> {
+        sqlx::query_as::<_, WebAuthnCredential>(
+            "SELECT * FROM webauthn_credentials WHERE user_id = $1 ORDER BY created_at"
+        )
+        .bind(user_id)
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(|e| UserError::DatabaseError(e.to_string()))
+    }
+
+    pub async fn remove_webauthn_credential(&self, user_id: Uuid, credential_id: Uuid) -> UserResult<()> {
+        let removed = sqlx::query("DELETE FROM webauthn_credentials WHERE id = $1 AND user_id = $2")
+            .bind(credential_id)
+            .bind(user_id)
+            .execute(&self.db_pool)
+            .await
+            .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+        if removed.rows_affected() == 0 {
+            return Err(UserError::NotFound);
+        }
+
+        self.record_activity(user_id, "webauthn_credential_removed", None).await;
+        Ok(())
+    }
+
+    /// Options for `navigator.credentials.get()`: the security keys of the
+    /// user with `email` as a second factor, or any passkey without one
+    pub async fn webauthn_assertion_options(
+        &self,
+        req: WebAuthnAssertionOptionsRequest,
+    ) -> UserResult<WebAuthnRequestOptions> {
+        let (user_id, credentials) = match req.email {
+            Some(email) => {
+                let user_id = sqlx::query_scalar::<_, Uuid>("SELECT id FROM users WHERE email = $1")
+                    .bind(&email)
+                    .fetch_optional(&self.db_pool)
+                    .await
+                    .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+                let credentials = match user_id {
+                    Some(user_id) => self.webauthn_credentials(user_id).await?,
+                    None => Vec::new(),
+                };
+                (user_id, Some(credentials))
             }
+            None => (None, None),
+        };
+
+        let challenge = self
+            .store_webauthn_challenge(&PendingChallenge {
+                user_id,
+                registration: false,
+                passkey: false,
+            })
+            .await?;
+
+        Ok(WebAuthnRequestOptions {
+            challenge,
+            rp_id: self.config.webauthn.rp_id.clone(),
+            timeout: self.config.webauthn.challenge_ttl_seconds * 1000,
+            user_verification: if credentials.is_some() { "preferred" } else { "required" }.to_string(),
+            allow_credentials: Self::credential_descriptors(&credentials.unwrap_or_default()),
+        })
+    }
+
+    /// Check an assertion, by one of `user_id`'s credentials if given, and
+    /// advance the credential's sign count. With `passkey`, the credential
+    /// must be a passkey and the user verified.
+    async fn verify_webauthn_assertion(
+        &self,
+        assertion: &WebAuthnAssertion,
+        user_id: Option<Uuid>,
+        passkey: bool,
+    ) -> UserResult<WebAuthnCredential> {
+        let client_data_json = webauthn::decode("client_data_json", &assertion.client_data_json)?;
+        let authenticator_data = webauthn::decode("authenticator_data", &assertion.authenticator_data)?;
+        let signature = webauthn::decode("signature", &assertion.signature)?;
+
+        let (challenge, pending) = self.take_webauthn_challenge(&client_data_json).await?;
+        let credential = sqlx::query_as::<_, WebAuthnCredential>(
+            "SELECT * FROM webauthn_credentials WHERE credential_id = $1"
+        )
+        .bind(assertion.credential_id.trim_end_matches('='))
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(|e| UserError::DatabaseError(e.to_string()))?
+        .ok_or_else(|| UserError::AuthenticationError("Unknown security key".to_string()))?;
+
+        let user_handle = assertion
+            .user_handle
+            .as_deref()
+            .map(|handle| webauthn::decode("user_handle", handle))
+            .transpose()?;
+        if pending.registration
+            || user_id.is_some_and(|user_id| user_id != credential.user_id)
+            || pending.user_id.is_some_and(|user_id| user_id != credential.user_id)
+            || user_handle.is_some_and(|handle| handle != credential.user_id.as_bytes())
+        {
+            return Err(UserError::AuthenticationError("Security key does not belong to this account".to_string()));
+        }
+
+        let auth_data = webauthn::verify_assertion(
+            &self.config.webauthn,
+            &challenge,
+            &credential.public_key,
+            &client_data_json,
+            &authenticator_data,
+            &signature,
+        )?;
+        if passkey && !(credential.passkey && auth_data.user_verified()) {
+            return Err(UserError::AuthenticationError("Sign-in needs a passkey that verifies the user".to_string()));
+        }
+
+        // Counters only go up; one that does not was likely cloned. Synced
+        // passkeys report zero throughout.
+        let sign_count = auth_data.sign_count as i64;
+        if (sign_count != 0 || credential.sign_count != 0) && sign_count <= credential.sign_count {
+            tracing::warn!("Sign count of credential {} went backwards", credential.id);
+            return Err(UserError::AuthenticationError("Security key may have been cloned".to_string()));
+        }
+
+        sqlx::query("UPDATE webauthn_credentials SET sign_count = $2, last_used_at = NOW() WHERE id = $1")
+            .bind(credential.id)
+            .bind(sign_count)
+            .execute(&self.db_pool)
+            .await
+            .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+        Ok(credential)
+    }
+
+    /// Sign in with a passkey alone, for high-trust users
+    pub async fn login_with_passkey(&self, assertion: WebAuthnAssertion) -> UserResult<AuthResponse> {
+        if !self.config.webauthn.passkey_login {
+            return Err(UserError::Unauthorized("Passkey sign-in is disabled".to_string()));
         }
 
+        let credential = self.verify_webauthn_assertion(&assertion, None, true).await?;
+        let user = self.get_user_by_id(credential.user_id).await?;
+
+        if !user.is_active {
+            return Err(UserError::Unauthorized("Account is suspended".to_string()));
+        }
+        if !user.is_high_trust() {
+            return Err(UserError::Unauthorized(
+                "Passkey sign-in requires a verified email and approved KYC".to_string(),
+            ));
+        }
+
+        self.issue_session(user, LoginMethod::Passkey).await
+    }
+
+    /// Permission scopes for `user`'s access tokens: those of their built-in
+    /// role, of roles granted to them and of roles granted to their
+    /// organizations
+    async fn scopes_for(&self, user: &User) -> UserResult<Vec<String>> {
+        let base_role = if user.is_admin { "admin" } else { "user" };
+        let granted: Vec<(String,)> = sqlx::query_as(
+            r#"
+            SELECT DISTINCT p.permission
+            FROM role_permissions p
+            WHERE p.role = $2
+               OR p.role IN (SELECT role FROM user_roles WHERE user_id = $1)
+               OR p.role IN (
+                   SELECT g.role
+                   FROM organization_roles g
+                   JOIN organization_members m ON m.organization_id = g.organization_id
+                   JOIN organizations o ON o.id = g.organization_id
+                   WHERE m.user_id = $1 AND o.is_active = TRUE
+               )
+            "#,
+        )
+        .bind(user.id)
+        .bind(base_role)
+        .fetch_all(&self.db_pool)
+        .await
+        .map_err(|e| UserError::DatabaseError(e.to_string()))?;
+
+        let scopes: Scopes = if granted.is_empty() {
+            // Roles not seeded yet
+            Scopes::for_role(base_role)
+        } else {
+            granted.into_iter().map(|(permission,)| permission).collect()
+        };
+        Ok(scopes.iter().map(str::to_string).collect())
+    }
+
+    /// Record a successful login and hand out a fresh token pair
+    async fn issue_session(&self, user: User, method: LoginMethod) -> UserResult<AuthResponse> {
         // Update last login
         sqlx::query("UPDATE users SET last_login = NOW() WHERE id = $1")
             .bind(user.id)
@@ -202,14 +1271,13 @@ impl UserService {
             user.id,
             &user.email,
             &user.username,
-            user.is_admin,
+            self.scopes_for(&user).await?,
         )?;
 
         let refresh_token = self.auth_service.generate_refresh_token(
             user.id,
             &user.email,
             &user.username,
-            user.is_admin,
         )?;
 
         // Store refresh token in Redis
@@ -219,6 +1287,8 @@ impl UserService {
             .await
             .map_err(|e| UserError::DatabaseError(e.to_string()))?;
 
+        self.record_activity(user.id, "login", Some(method)).await;
+
         Ok(AuthResponse {
             access_token,
             refresh_token,
@@ -227,6 +1297,27 @@ impl UserService {
         })
     }
 
+    /// Append an entry to the user activity log. Failures are logged, not
+    /// propagated, so auditing never blocks authentication.
+    async fn record_activity(&self, user_id: Uuid, action: &str, method: Option<LoginMethod>) {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO user_activity_log (id, user_id, action, method, created_at)
+            VALUES ($1, $2, $3, $4, NOW())
+            "#
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(action)
+        .bind(method.map(|m| m.as_str()))
+        .execute(&self.db_pool)
+        .await;
+
+        if let Err(e) = result {
+            tracing::error!("Failed to record activity '{}' for user {}: {}", action, user_id, e);
+        }
+    }
+
     /// Logout user
     pub async fn logout(&self, user_id: Uuid) -> UserResult<()> {
         let session_key = format!("session:{}", user_id);
@@ -276,14 +1367,13 @@ impl UserService {
             user.id,
             &user.email,
             &user.username,
-            user.is_admin,
+            self.scopes_for(&user).await?,
         )?;
 
         let new_refresh_token = self.auth_service.generate_refresh_token(
             user.id,
             &user.email,
             &user.username,
-            user.is_admin,
         )?;
 
         // Update session in Redis
@@ -488,57 +1578,6 @@ impl UserService {
         Ok(())
     }
 
-    // ============= KYC Methods =============
-
-    /// Submit KYC
-    pub async fn submit_kyc(&self, user_id: Uuid, req: SubmitKycRequest) -> UserResult<Uuid> {
-        // Parse date
-        let date_of_birth = chrono::NaiveDate::parse_from_str(&req.date_of_birth, "%Y-%m-%d")
-            .map_err(|_| UserError::ValidationError("Invalid date format, use YYYY-MM-DD".to_string()))?;
-
-        // Create KYC verification (document URLs will be added via upload endpoint)
-        let kyc_id = Uuid::new_v4();
-
-        sqlx::query(
-            r#"
-            INSERT INTO kyc_verifications
-            (id, user_id, full_name, date_of_birth, country, document_type, document_number,
-             document_front_url, selfie_url, status, submitted_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, '', '', 'pending', NOW())
-            "#
-        )
-        .bind(kyc_id)
-        .bind(user_id)
-        .bind(&req.full_name)
-        .bind(date_of_birth)
-        .bind(&req.country)
-        .bind(&req.document_type)
-        .bind(&req.document_number)
-        .execute(&self.db_pool)
-        .await
-        .map_err(|e| UserError::DatabaseError(e.to_string()))?;
-
-        // Update user KYC status
-        sqlx::query("UPDATE users SET kyc_status = 'pending' WHERE id = $1")
-            .bind(user_id)
-            .execute(&self.db_pool)
-            .await
-            .map_err(|e| UserError::DatabaseError(e.to_string()))?;
-
-        Ok(kyc_id)
-    }
-
-    /// Get KYC status
-    pub async fn get_kyc_status(&self, user_id: Uuid) -> UserResult<Option<KycVerification>> {
-        sqlx::query_as::<_, KycVerification>(
-            "SELECT * FROM kyc_verifications WHERE user_id = $1 ORDER BY submitted_at DESC LIMIT 1"
-        )
-        .bind(user_id)
-        .fetch_optional(&self.db_pool)
-        .await
-        .map_err(|e| UserError::DatabaseError(e.to_string()))
-    }
-
     // ============= Wallet Methods =============
 
     /// Link Ethereum wallet
@@ -569,3 +1608,55 @@ impl UserService {
             .ok_or(UserError::NotFound)
     }
 }
+
+/// Insert a user with their default profile and settings
+async fn insert_user(
+    conn: &mut PgConnection,
+    username: &str,
+    email: &str,
+    password_hash: &str,
+    ethereum_address: Option<&str>,
+) -> Result<User, sqlx::Error> {
+    let user = sqlx::query_as::<_, User>(
+        r#"
+        INSERT INTO users (id, username, email, password_hash, ethereum_address, email_verified,
+                          is_active, is_admin, two_factor_enabled, kyc_status, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, false, true, false, false, 'not_submitted', NOW(), NOW())
+        RETURNING *
+        "#
+    )
+    .bind(Uuid::new_v4())
+    .bind(username)
+    .bind(email)
+    .bind(password_hash)
+    .bind(ethereum_address)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    // Create default profile
+    sqlx::query(
+        r#"
+        INSERT INTO user_profiles (user_id, created_at, updated_at)
+        VALUES ($1, NOW(), NOW())
+        "#
+    )
+    .bind(user.id)
+    .execute(&mut *conn)
+    .await?;
+
+    // Create default settings
+    sqlx::query(
+        r#"
+        INSERT INTO user_settings (user_id, email_notifications, push_notifications,
+                                   webhook_notifications, privacy_public_profile,
+                                   privacy_show_email, privacy_show_stats,
+                                   language, timezone, updated_at)
+        VALUES ($1, true, true, false, true, false, true, 'en', 'UTC', NOW())
+        "#
+    )
+    .bind(user.id)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(user)
+}
@@ -0,0 +1,9 @@
+-- resumable_submissions.sql - Resumable analysis status
+
+-- Analysis-engine replicas shutting down mark interrupted submissions as
+-- 'resumable' (with the interrupted stage in metadata.resume) and re-queue them
+
+ALTER TABLE submissions DROP CONSTRAINT IF EXISTS submissions_analysis_status_check;
+ALTER TABLE submissions ADD CONSTRAINT submissions_analysis_status_check CHECK (
+    analysis_status IN ('pending', 'analyzing', 'resumable', 'completed', 'failed')
+);
@@ -0,0 +1,8 @@
+-- api_key_quotas.sql - Per-key daily quotas
+
+-- Daily limits enforced by the analysis-engine API key middleware. Usage
+-- counters live in Redis (quota:<key_id>:<YYYYMMDD>:<metric>).
+
+ALTER TABLE api_keys ADD COLUMN IF NOT EXISTS daily_submission_quota INTEGER NOT NULL DEFAULT 1000;
+ALTER TABLE api_keys ADD COLUMN IF NOT EXISTS daily_bytes_quota BIGINT NOT NULL DEFAULT 10737418240; -- 10GB
+ALTER TABLE api_keys ADD COLUMN IF NOT EXISTS daily_sandbox_minutes_quota INTEGER NOT NULL DEFAULT 600;
@@ -0,0 +1,43 @@
+-- impersonation.sql - Admin impersonation with user consent
+
+-- An admin requests to act as a user, the user approves or denies, and an
+-- approved request can be started once to issue a short-lived read-only
+-- token. Liveness of active sessions is tracked in Redis
+-- (impersonation:session:<id>) so ending a session revokes its token.
+
+CREATE TABLE IF NOT EXISTS impersonation_sessions (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    admin_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    reason TEXT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending' CHECK (
+        status IN ('pending', 'approved', 'denied', 'expired', 'active', 'ended')
+    ),
+    requested_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    consent_expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    consented_at TIMESTAMP WITH TIME ZONE,
+    started_at TIMESTAMP WITH TIME ZONE,
+    expires_at TIMESTAMP WITH TIME ZONE,
+    ended_at TIMESTAMP WITH TIME ZONE,
+    ended_by UUID REFERENCES users(id) ON DELETE SET NULL
+);
+
+-- Append-only trail of lifecycle events and of every request made with an
+-- impersonation token (event 'request', or 'blocked' for refused writes)
+CREATE TABLE IF NOT EXISTS impersonation_audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    session_id UUID NOT NULL REFERENCES impersonation_sessions(id) ON DELETE CASCADE,
+    admin_id UUID NOT NULL,
+    user_id UUID NOT NULL,
+    event VARCHAR(20) NOT NULL,
+    method VARCHAR(10),
+    path TEXT,
+    status_code SMALLINT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_impersonation_sessions_user ON impersonation_sessions(user_id, requested_at DESC);
+CREATE INDEX IF NOT EXISTS idx_impersonation_sessions_admin ON impersonation_sessions(admin_id, requested_at DESC);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_impersonation_sessions_open
+    ON impersonation_sessions(admin_id, user_id) WHERE status IN ('pending', 'approved', 'active');
+CREATE INDEX IF NOT EXISTS idx_impersonation_audit_session ON impersonation_audit_log(session_id, created_at);
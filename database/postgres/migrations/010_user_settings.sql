@@ -0,0 +1,22 @@
+-- user_settings.sql - Per-user account settings
+
+-- Written by the user-service on registration and read by the
+-- notification-service, which uses the timezone to place quiet hours.
+
+CREATE TABLE IF NOT EXISTS user_settings (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    email_notifications BOOLEAN NOT NULL DEFAULT true,
+    push_notifications BOOLEAN NOT NULL DEFAULT true,
+    webhook_notifications BOOLEAN NOT NULL DEFAULT false,
+    privacy_public_profile BOOLEAN NOT NULL DEFAULT true,
+    privacy_show_email BOOLEAN NOT NULL DEFAULT false,
+    privacy_show_stats BOOLEAN NOT NULL DEFAULT true,
+    language VARCHAR(10) NOT NULL DEFAULT 'en',
+    -- IANA zone name, e.g. Europe/Berlin
+    timezone VARCHAR(64) NOT NULL DEFAULT 'UTC',
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO user_settings (user_id)
+SELECT id FROM users
+ON CONFLICT (user_id) DO NOTHING;
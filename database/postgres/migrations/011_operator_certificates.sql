@@ -0,0 +1,22 @@
+-- operator_certificates.sql - Signed engine operator performance certificates
+
+-- Issued by the user-service from per-engine performance reported by the
+-- reputation-service. `payload` holds the exact JSON bytes that were signed,
+-- so verification never depends on re-serializing the document.
+
+CREATE TABLE IF NOT EXISTS operator_certificates (
+    id UUID PRIMARY KEY,
+    operator_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    period_start TIMESTAMP WITH TIME ZONE NOT NULL,
+    period_end TIMESTAMP WITH TIME ZONE NOT NULL,
+    payload TEXT NOT NULL,
+    -- Base64url Ed25519 signature over `payload`
+    signature TEXT NOT NULL,
+    key_id VARCHAR(32) NOT NULL,
+    issued_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    revoked_at TIMESTAMP WITH TIME ZONE,
+    CHECK (period_start < period_end)
+);
+
+CREATE INDEX IF NOT EXISTS idx_operator_certificates_operator
+    ON operator_certificates(operator_id, issued_at DESC);
@@ -0,0 +1,17 @@
+-- siwe_identities.sql - Sign-In with Ethereum
+
+-- Wallets a user can sign in with by signing an EIP-4361 message. A wallet
+-- belongs to at most one account. Accounts created by a first wallet
+-- sign-in have no password and a placeholder <address>@wallet.invalid
+-- email; a wallet can also be linked to an existing email account.
+-- Issued nonces live in Redis (siwe_nonce:<nonce>) until used or expired.
+
+CREATE TABLE IF NOT EXISTS siwe_identities (
+    address VARCHAR(42) PRIMARY KEY CHECK (address = LOWER(address)),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    chain_id BIGINT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_sign_in_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_siwe_identities_user ON siwe_identities(user_id);
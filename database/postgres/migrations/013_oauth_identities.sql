@@ -0,0 +1,20 @@
+-- oauth_identities.sql - OAuth2 / OpenID Connect sign-in
+
+-- Provider accounts (GitHub, Google, enterprise OIDC) a user can sign in
+-- with, by the provider's stable subject ID. A user links at most one
+-- account per provider. Accounts provisioned on first sign-in have no
+-- password. Pending authorization requests live in Redis
+-- (oauth_state:<state>) until their callback or expiry.
+
+CREATE TABLE IF NOT EXISTS oauth_identities (
+    provider VARCHAR(50) NOT NULL,
+    subject VARCHAR(255) NOT NULL,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    email VARCHAR(255),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_sign_in_at TIMESTAMP WITH TIME ZONE,
+    PRIMARY KEY (provider, subject),
+    UNIQUE (user_id, provider)
+);
+
+CREATE INDEX IF NOT EXISTS idx_oauth_identities_user ON oauth_identities(user_id);
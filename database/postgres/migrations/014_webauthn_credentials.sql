@@ -0,0 +1,26 @@
+-- webauthn_credentials.sql - WebAuthn security keys and passkeys
+
+-- Authenticators a user registered, one row per device. Any of them answers
+-- the second factor at sign-in instead of a TOTP code; passkeys (registered
+-- with user verification) also sign high-trust users in without a password.
+-- Challenges live in Redis (webauthn_challenge:<challenge>) until answered.
+
+CREATE TABLE IF NOT EXISTS webauthn_credentials (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    -- base64url, unpadded
+    credential_id VARCHAR(1400) NOT NULL UNIQUE,
+    name VARCHAR(64) NOT NULL,
+    -- COSE_Key
+    public_key BYTEA NOT NULL,
+    algorithm INTEGER NOT NULL,
+    sign_count BIGINT NOT NULL DEFAULT 0,
+    aaguid UUID NOT NULL,
+    transports TEXT[] NOT NULL DEFAULT '{}',
+    passkey BOOLEAN NOT NULL DEFAULT FALSE,
+    backup_eligible BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_used_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_webauthn_credentials_user ON webauthn_credentials(user_id);
@@ -0,0 +1,74 @@
+-- organizations.sql - Organizations, member roles, invitations and API keys
+
+-- The gateway's usage metering created organizations and their members
+-- first (api-gateway 004_usage_metering); the definitions below match it so
+-- the two migrations can run in either order. A user belongs to at most one
+-- organization, which usage, quotas and bounty ownership are attributed to.
+
+CREATE TABLE IF NOT EXISTS organizations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(255) NOT NULL,
+    billing_email VARCHAR(255),
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS organization_members (
+    organization_id UUID REFERENCES organizations(id) ON DELETE CASCADE NOT NULL,
+    user_id UUID REFERENCES users(id) ON DELETE CASCADE NOT NULL,
+    role VARCHAR(20) NOT NULL DEFAULT 'member',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (organization_id, user_id)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_org_members_user ON organization_members(user_id);
+
+-- Member roles: owners manage everything including other owners, admins
+-- manage members, bounties and API keys, analysts run bounties, billing
+-- members see and fund escrow. The old catch-all 'member' becomes 'analyst'.
+UPDATE organization_members SET role = 'analyst' WHERE role = 'member';
+ALTER TABLE organization_members ALTER COLUMN role SET DEFAULT 'analyst';
+ALTER TABLE organization_members DROP CONSTRAINT IF EXISTS organization_members_role_check;
+ALTER TABLE organization_members ADD CONSTRAINT organization_members_role_check
+    CHECK (role IN ('owner', 'admin', 'analyst', 'billing'));
+
+-- Invitations are emailed as a link carrying a random token; only its
+-- SHA-256 is stored. Accepting requires signing in with the invited email.
+CREATE TABLE IF NOT EXISTS organization_invitations (
+    id UUID PRIMARY KEY,
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    email VARCHAR(255) NOT NULL,
+    role VARCHAR(20) NOT NULL CHECK (role IN ('admin', 'analyst', 'billing')),
+    -- hex SHA-256 of the token
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    invited_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    accepted_at TIMESTAMP WITH TIME ZONE,
+    revoked_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- One open invitation per address and organization
+CREATE UNIQUE INDEX IF NOT EXISTS idx_org_invitations_pending
+    ON organization_invitations(organization_id, LOWER(email))
+    WHERE accepted_at IS NULL AND revoked_at IS NULL;
+
+-- Organization API keys (nxs_ followed by 32 characters) authenticate at the
+-- gateway as the organization with the key's role. The secret is shown once.
+CREATE TABLE IF NOT EXISTS organization_api_keys (
+    id UUID PRIMARY KEY,
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    name VARCHAR(64) NOT NULL,
+    -- first 8 characters, to tell keys apart in listings
+    key_prefix VARCHAR(12) NOT NULL,
+    -- hex SHA-256 of the key
+    key_hash VARCHAR(64) NOT NULL UNIQUE,
+    role VARCHAR(20) NOT NULL CHECK (role IN ('admin', 'analyst', 'billing')),
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_used_at TIMESTAMP WITH TIME ZONE,
+    revoked_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_org_api_keys_org ON organization_api_keys(organization_id);
@@ -0,0 +1,46 @@
+-- kyc_providers.sql - KYC verifications checked by an identity provider
+
+-- One row per submission; a user's latest decides users.kyc_status.
+-- 'manual' submissions carry document URLs an admin reviews. Provider
+-- submissions (sumsub, onfido) are checked as the provider's applicant,
+-- whose SDK collects the documents, so their URLs stay empty.
+
+CREATE TABLE IF NOT EXISTS kyc_verifications (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    full_name VARCHAR(255) NOT NULL,
+    date_of_birth DATE,
+    country VARCHAR(100) NOT NULL,
+    document_type VARCHAR(50) NOT NULL,
+    document_number VARCHAR(100) NOT NULL,
+    document_front_url TEXT NOT NULL DEFAULT '',
+    document_back_url TEXT,
+    selfie_url TEXT NOT NULL DEFAULT '',
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    rejection_reason TEXT,
+    verified_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    submitted_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    verified_at TIMESTAMP WITH TIME ZONE
+);
+
+ALTER TABLE kyc_verifications ADD COLUMN IF NOT EXISTS provider VARCHAR(32) NOT NULL DEFAULT 'manual';
+ALTER TABLE kyc_verifications ADD COLUMN IF NOT EXISTS provider_applicant_id VARCHAR(255);
+ALTER TABLE kyc_verifications ADD COLUMN IF NOT EXISTS provider_updated_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX IF NOT EXISTS idx_kyc_verifications_user ON kyc_verifications(user_id, submitted_at DESC);
+CREATE INDEX IF NOT EXISTS idx_kyc_verifications_applicant
+    ON kyc_verifications(provider, provider_applicant_id)
+    WHERE provider_applicant_id IS NOT NULL;
+
+-- Webhook deliveries already applied, by the digest of their payload, so a
+-- provider's retries change nothing
+CREATE TABLE IF NOT EXISTS kyc_webhook_events (
+    provider VARCHAR(32) NOT NULL,
+    payload_digest VARCHAR(64) NOT NULL,
+    applicant_id VARCHAR(255) NOT NULL,
+    event_type VARCHAR(64) NOT NULL,
+    received_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (provider, payload_digest)
+);
+
+CREATE INDEX IF NOT EXISTS idx_kyc_webhook_events_applicant ON kyc_webhook_events(provider, applicant_id);